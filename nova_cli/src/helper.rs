@@ -32,7 +32,13 @@ pub fn initialize_global_object(agent: &mut Agent, global: Object, mut gc: GcSco
         if args.is_empty() {
             println!();
         } else {
-            println!("{}", args[0].unbind().to_string(agent, gc)?.as_str(agent));
+            println!(
+                "{}",
+                args[0]
+                    .unbind()
+                    .to_string(agent, gc)?
+                    .to_string_lossy(agent)
+            );
         }
         Ok(Value::Undefined)
     }
@@ -60,7 +66,7 @@ pub fn initialize_global_object(agent: &mut Agent, global: Object, mut gc: GcSco
             ));
         };
 
-        let file = match std::fs::read_to_string(path.as_str(agent)) {
+        let file = match std::fs::read_to_string(&*path.to_string_lossy(agent)) {
             Ok(file) => file,
             Err(e) => {
                 return Err(agent.throw_exception(
@@ -96,16 +96,16 @@ impl CliHostHooks {
     fn has_promise_jobs(&self) -> bool {
         !self.promise_job_queue.borrow().is_empty()
     }
-
-    fn pop_promise_job(&self) -> Option<Job> {
-        self.promise_job_queue.borrow_mut().pop_front()
-    }
 }
 
 impl HostHooks for CliHostHooks {
     fn enqueue_promise_job(&self, job: Job) {
         self.promise_job_queue.borrow_mut().push_back(job);
     }
+
+    fn dequeue_promise_job(&self) -> Option<Job> {
+        self.promise_job_queue.borrow_mut().pop_front()
+    }
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -145,6 +145,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 Options {
                     disable_gc: nogc,
                     print_internals: verbose,
+                    ..Default::default()
                 },
                 // SAFETY: Host hooks is a valid pointer.
                 unsafe { host_hooks.as_ref() },
@@ -184,24 +185,23 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                             Ok(script) => script,
                             Err(errors) => {
                                 // Borrow the string data from the Agent
-                                let source_text = source_text.as_str(agent);
-                                exit_with_parse_errors(errors, &path, source_text)
+                                let source_text = source_text.to_string_lossy(agent);
+                                exit_with_parse_errors(errors, &path, &source_text)
                             }
                         };
                         let result = script_evaluation(agent, script.unbind(), gc.reborrow());
 
                         fn run_microtask_queue<'gc>(
                             agent: &mut Agent,
-                            host_hooks: &CliHostHooks,
                             result: JsResult<Value>,
                             mut gc: GcScope<'gc, '_>,
                         ) -> JsResult<'gc, Value<'gc>> {
                             match result.bind(gc.nogc()) {
                                 Ok(result) => {
                                     let ok_result = result.unbind().scope(agent, gc.nogc());
-                                    while let Some(job) = host_hooks.pop_promise_job() {
-                                        job.run(agent, gc.reborrow()).unbind()?.bind(gc.nogc());
-                                    }
+                                    agent
+                                        .run_microtasks(usize::MAX, gc.reborrow())
+                                        .map_err(|err| err.unbind())?;
                                     Ok(ok_result.get(agent).bind(gc.into_nogc()))
                                 }
                                 Err(_) => result.unbind(),
@@ -211,7 +211,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                         // SAFETY: Still valid.
                         let host_hooks = unsafe { host_hooks.as_ref() };
                         let result = if host_hooks.has_promise_jobs() {
-                            run_microtask_queue(agent, host_hooks, result.unbind(), gc.reborrow())
+                            run_microtask_queue(agent, result.unbind(), gc.reborrow())
                                 .unbind()
                                 .bind(gc.nogc())
                         } else {
@@ -231,7 +231,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                                         .value()
                                         .unbind()
                                         .string_repr(agent, gc.reborrow())
-                                        .as_str(agent)
+                                        .to_string_lossy(agent)
                                 );
                                 std::process::exit(1);
                             }
@@ -254,6 +254,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 Options {
                     disable_gc,
                     print_internals,
+                    ..Default::default()
                 },
                 host_hooks,
             );
@@ -311,7 +312,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                         Err(error) => {
                             eprintln!(
                                 "Uncaught exception: {}",
-                                error.value().unbind().string_repr(agent, gc).as_str(agent)
+                                error
+                                    .value()
+                                    .unbind()
+                                    .string_repr(agent, gc)
+                                    .to_string_lossy(agent)
                             );
                         }
                     }
@@ -2,6 +2,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+pub(crate) mod iterator_helpers;
 pub(crate) mod keyed_group;
 pub(crate) mod operations_on_iterator_objects;
 pub(crate) mod operations_on_objects;
@@ -0,0 +1,173 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Iterator protocol helpers for built-in functions.
+//!
+//! These wrap the [operations on iterator objects] so that built-ins which
+//! consume an iterable from Rust do not need to re-implement the details of
+//! the protocol: the iterator and its next method are rooted across calls
+//! into user code, a non-callable next method is reported once when the
+//! iterator is created, and an abrupt completion in the consumer closes the
+//! iterator while an abrupt completion from the iterator itself does not.
+//!
+//! [operations on iterator objects]: super::operations_on_iterator_objects
+
+use super::operations_on_iterator_objects::{
+    IteratorRecord, iterator_close_with_error, iterator_close_with_value,
+};
+use crate::{
+    ecmascript::{
+        abstract_operations::operations_on_iterator_objects,
+        execution::{Agent, JsResult},
+        types::{Function, Object, Value},
+    },
+    engine::{
+        Scoped,
+        context::{Bindable, GcScope, NoGcScope},
+        rootable::Scopable,
+    },
+};
+
+/// The kind of iterator requested from [`get_iterator`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum IteratorHint {
+    Sync,
+    Async,
+}
+
+/// An Iterator Record whose iterator and next method are rooted, so that it
+/// can be held across calls into user code.
+pub(crate) struct ScopedIteratorRecord<'scope> {
+    iterator: Scoped<'scope, Object<'static>>,
+    next_method: Scoped<'scope, Function<'static>>,
+    /// ### \[\[Done]]
+    done: bool,
+}
+
+// SAFETY: Trivially safe: the record holds only scoped values.
+unsafe impl<'scope> Bindable for ScopedIteratorRecord<'scope> {
+    type Of<'a> = ScopedIteratorRecord<'scope>;
+
+    #[inline(always)]
+    fn unbind(self) -> Self::Of<'static> {
+        self
+    }
+
+    #[inline(always)]
+    fn bind<'a>(self, _gc: NoGcScope<'a, '_>) -> Self::Of<'a> {
+        self
+    }
+}
+
+impl ScopedIteratorRecord<'_> {
+    /// Returns true if the iterator has finished or thrown an error.
+    pub(crate) fn is_done(&self) -> bool {
+        self.done
+    }
+
+    fn get(&self, agent: &Agent) -> IteratorRecord<'static> {
+        IteratorRecord {
+            iterator: self.iterator.get(agent),
+            next_method: self.next_method.get(agent),
+        }
+    }
+}
+
+/// ### [7.4.4 GetIterator ( obj, kind )](https://tc39.es/ecma262/#sec-getiterator)
+///
+/// Unlike [`operations_on_iterator_objects::get_iterator`], this throws a
+/// TypeError if the next method of the iterator is not callable.
+pub(crate) fn get_iterator<'a, 'scope>(
+    agent: &mut Agent,
+    obj: Value,
+    hint: IteratorHint,
+    mut gc: GcScope<'a, 'scope>,
+) -> JsResult<'a, ScopedIteratorRecord<'scope>> {
+    let Some(IteratorRecord {
+        iterator,
+        next_method,
+    }) = operations_on_iterator_objects::get_iterator(
+        agent,
+        obj,
+        hint == IteratorHint::Async,
+        gc.reborrow(),
+    )
+    .unbind()?
+    .bind(gc.nogc())
+    else {
+        return agent.type_error("Iterator next method is not callable", gc.into_nogc());
+    };
+    Ok(ScopedIteratorRecord {
+        iterator: iterator.scope(agent, gc.nogc()),
+        next_method: next_method.scope(agent, gc.nogc()),
+        done: false,
+    })
+}
+
+/// ### [7.4.10 IteratorStepValue ( iteratorRecord )](https://tc39.es/ecma262/#sec-iteratorstepvalue)
+///
+/// Returns None once the iterator is done. If the iterator throws an error,
+/// it is marked done and must not be closed.
+pub(crate) fn iterator_step_value<'a>(
+    agent: &mut Agent,
+    iterator_record: &mut ScopedIteratorRecord,
+    gc: GcScope<'a, '_>,
+) -> JsResult<'a, Option<Value<'a>>> {
+    if iterator_record.done {
+        return Ok(None);
+    }
+    let result =
+        operations_on_iterator_objects::iterator_step_value(agent, iterator_record.get(agent), gc);
+    if !matches!(result, Ok(Some(_))) {
+        iterator_record.done = true;
+    }
+    result
+}
+
+/// ### [7.4.11 IteratorClose ( iteratorRecord, completion )](https://tc39.es/ecma262/#sec-iteratorclose)
+///
+/// If the completion is a throw completion, it is returned as is even if
+/// the iterator's return method throws.
+pub(crate) fn iterator_close<'a>(
+    agent: &mut Agent,
+    iterator_record: &ScopedIteratorRecord,
+    completion: JsResult<Value>,
+    gc: GcScope<'a, '_>,
+) -> JsResult<'a, Value<'a>> {
+    let iterator = iterator_record.iterator.get(agent);
+    match completion {
+        Ok(value) => iterator_close_with_value(agent, iterator, value, gc),
+        Err(err) => Err(iterator_close_with_error(agent, iterator, err, gc)),
+    }
+}
+
+/// Call `f` with each value produced by the iterator of `obj`.
+///
+/// If `f` returns an error, the iterator is closed and the error is
+/// returned. Errors thrown by the iterator itself are returned without
+/// closing it.
+pub(crate) fn iterate<'a>(
+    agent: &mut Agent,
+    obj: Value,
+    mut gc: GcScope<'a, '_>,
+    mut f: impl for<'b> FnMut(&mut Agent, Value<'b>, GcScope<'b, '_>) -> JsResult<'b, ()>,
+) -> JsResult<'a, ()> {
+    let mut iterator_record =
+        get_iterator(agent, obj, IteratorHint::Sync, gc.reborrow()).unbind()?;
+    while let Some(value) = iterator_step_value(agent, &mut iterator_record, gc.reborrow())
+        .unbind()?
+        .bind(gc.nogc())
+    {
+        if let Err(err) = f(agent, value.unbind(), gc.reborrow()) {
+            let err = err.unbind();
+            return Err(iterator_close_with_error(
+                agent,
+                iterator_record.iterator.get(agent),
+                err,
+                gc,
+            ));
+        }
+    }
+    Ok(())
+}
@@ -14,10 +14,7 @@ use crate::{
             type_conversion::to_boolean,
         },
         builtins::{ArgumentsList, ordinary::ordinary_object_create_with_intrinsics},
-        execution::{
-            Agent, JsResult, ProtoIntrinsics,
-            agent::{ExceptionType, JsError},
-        },
+        execution::{Agent, JsResult, ProtoIntrinsics, agent::JsError},
         types::{
             BUILTIN_STRING_MEMORY, Function, IntoObject, IntoValue, Object, PropertyDescriptor,
             PropertyKey, Value,
@@ -128,11 +125,7 @@ pub(crate) fn get_iterator_from_method<'a>(
 
     // 2. If iterator is not an Object, throw a TypeError exception.
     let Ok(iterator) = Object::try_from(iterator) else {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Iterator is not an object",
-            gc.into_nogc(),
-        ));
+        return agent.type_error("Iterator is not an object", gc.into_nogc());
     };
 
     let scoped_iterator = iterator.scope(agent, gc.nogc());
@@ -196,11 +189,7 @@ pub(crate) fn get_iterator<'a>(
             .unbind()?
             .bind(gc.nogc()) else {
                 // ii. If syncMethod is undefined, throw a TypeError exception.
-                return Err(agent.throw_exception_with_static_message(
-                    ExceptionType::TypeError,
-                    "No iterator on object",
-                    gc.into_nogc(),
-                ));
+                return agent.type_error("No iterator on object", gc.into_nogc());
             };
 
             // iii. Let syncIteratorRecord be ? GetIteratorFromMethod(obj, syncMethod).
@@ -233,11 +222,7 @@ pub(crate) fn get_iterator<'a>(
 
     // 3. If method is undefined, throw a TypeError exception.
     let Some(method) = method else {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Iterator method cannot be undefined",
-            gc.into_nogc(),
-        ));
+        return agent.type_error("Iterator method cannot be undefined", gc.into_nogc());
     };
 
     // 4. Return ? GetIteratorFromMethod(obj, method).
@@ -277,11 +262,7 @@ pub(crate) fn iterator_next<'a>(
     // 4. Return result.
     result
         .try_into()
-        .or(Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "The iterator result was not an object",
-            gc,
-        )))
+        .or(agent.type_error("The iterator result was not an object", gc))
 }
 
 /// ### [7.4.7 IteratorComplete ( iterResult )](https://tc39.es/ecma262/#sec-iteratorcomplete)
@@ -488,11 +469,10 @@ pub(crate) fn iterator_close_with_value<'a>(
     let inner_result = inner_result.unbind()?.bind(gc.nogc());
     // 7. If innerResult.[[Value]] is not an Object, throw a TypeError exception.
     if !inner_result.is_object() {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
+        return agent.type_error(
             "Invalid iterator 'return' method return value",
             gc.into_nogc(),
-        ));
+        );
     }
     // 8. Return ? completion.
     Ok(completion.unbind())
@@ -20,6 +20,7 @@ use crate::{
         },
         builtins::{
             ArgumentsList, Array, BuiltinConstructorFunction, array_create,
+            error::ErrorMessage,
             keyed_collections::map_objects::map_prototype::canonicalize_keyed_collection_key,
             proxy::abstract_operations::{
                 try_validate_non_revoked_proxy, validate_non_revoked_proxy,
@@ -43,7 +44,10 @@ use crate::{
         rootable::{Rootable, Scopable},
         unwrap_try,
     },
-    heap::{Heap, ObjectEntry, WellKnownSymbolIndexes, element_array::ElementDescriptor},
+    heap::{
+        Heap, ObjectEntry, WellKnownSymbolIndexes,
+        element_array::{ElementDescriptor, ElementStorageRef},
+    },
 };
 
 /// ### [7.3.2 Get ( O, P )](https://tc39.es/ecma262/#sec-get-o-p)
@@ -242,9 +246,10 @@ pub(crate) fn throw_set_error<'a>(
     p: PropertyKey,
     gc: NoGcScope<'a, '_>,
 ) -> JsResult<'a, ()> {
-    Err(agent.throw_exception(
+    Err(agent.throw_exception_with_deferred_message(
         ExceptionType::TypeError,
-        format!("Could not set property '{}'.", p.as_display(agent)),
+        ErrorMessage::CannotSetProperty,
+        [p.into()],
         gc,
     ))
 }
@@ -355,12 +360,10 @@ pub(crate) fn try_create_data_property_or_throw<'a, 'gc>(
 ) -> TryResult<JsResult<'gc, ()>> {
     let success = try_create_data_property(agent, object, property_key, value, gc)?;
     if !success {
-        TryResult::Continue(Err(agent.throw_exception(
+        TryResult::Continue(Err(agent.throw_exception_with_deferred_message(
             ExceptionType::TypeError,
-            format!(
-                "Could not create property '{}'.",
-                property_key.as_display(agent)
-            ),
+            ErrorMessage::CannotCreateProperty,
+            [property_key.into()],
             gc,
         )))
     } else {
@@ -387,15 +390,11 @@ pub(crate) fn create_data_property_or_throw<'a, 'gc>(
     let success = create_data_property(agent, object, property_key.unbind(), value, gc.reborrow())
         .unbind()?;
     if !success {
-        Err(agent.throw_exception(
+        let property_key = scoped_property_key.get(agent).bind(gc.nogc());
+        Err(agent.throw_exception_with_deferred_message(
             ExceptionType::TypeError,
-            format!(
-                "Could not create property '{}'.",
-                scoped_property_key
-                    .get(agent)
-                    .bind(gc.nogc())
-                    .as_display(agent)
-            ),
+            ErrorMessage::CannotCreateProperty,
+            [property_key.unbind().into()],
             gc.into_nogc(),
         ))
     } else {
@@ -422,11 +421,7 @@ pub(crate) fn try_define_property_or_throw<'a, 'gc>(
     let success = object.try_define_own_property(agent, property_key, desc, gc)?;
     // 2. If success is false, throw a TypeError exception.
     if !success {
-        TryResult::Continue(Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Failed to defined property on object",
-            gc,
-        )))
+        TryResult::Continue(agent.type_error("Failed to defined property on object", gc))
     } else {
         // 3. Return UNUSED.
         TryResult::Continue(Ok(()))
@@ -456,11 +451,7 @@ pub(crate) fn define_property_or_throw<'a, 'gc>(
         .unbind()?;
     // 2. If success is false, throw a TypeError exception.
     if !success {
-        Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Failed to defined property on object",
-            gc.into_nogc(),
-        ))
+        agent.type_error("Failed to defined property on object", gc.into_nogc())
     } else {
         // 3. Return UNUSED.
         Ok(())
@@ -483,11 +474,7 @@ pub(crate) fn try_delete_property_or_throw<'a, 'gc>(
     let success = o.try_delete(agent, p, gc)?;
     // 2. If success is false, throw a TypeError exception.
     if !success {
-        TryResult::Continue(Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Failed to delete property",
-            gc,
-        )))
+        TryResult::Continue(agent.type_error("Failed to delete property", gc))
     } else {
         // 3. Return unused.
         TryResult::Continue(Ok(()))
@@ -513,11 +500,7 @@ pub(crate) fn delete_property_or_throw<'a, 'gc>(
         .unbind()?;
     // 2. If success is false, throw a TypeError exception.
     if !success {
-        Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Failed to delete property",
-            gc.into_nogc(),
-        ))
+        agent.type_error("Failed to delete property", gc.into_nogc())
     } else {
         // 3. Return unused.
         Ok(())
@@ -621,11 +604,7 @@ fn get_method_internal<'a>(
     // 3. If IsCallable(func) is false, throw a TypeError exception.
     let func = is_callable(func, gc);
     if func.is_none() {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Not a callable object",
-            gc,
-        ));
+        return agent.type_error("Not a callable object", gc);
     }
     // 4. Return func.
     Ok(func)
@@ -1038,6 +1017,20 @@ pub(crate) fn create_array_from_list<'a>(
     array
 }
 
+/// Performs CreateArrayFromList(« key, value ») for the entries produced by
+/// `entries()` methods and iterators.
+///
+/// The elements are written directly into the new Array: Setters and
+/// prototype properties named "0" or "1" are never observed.
+pub(crate) fn create_entry_pair<'a>(
+    agent: &mut Agent,
+    key: Value,
+    value: Value,
+    gc: NoGcScope<'a, '_>,
+) -> Array<'a> {
+    create_array_from_list(agent, &[key, value], gc)
+}
+
 pub(crate) fn create_array_from_scoped_list<'a>(
     agent: &mut Agent,
     elements: ScopedCollection<Vec<Value>>,
@@ -1170,11 +1163,7 @@ pub(crate) fn create_list_from_array_like<'gc>(
             Ok(list.take(agent).bind(gc))
         }
         // 2. If obj is not an Object, throw a TypeError exception.
-        _ => Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Not an object",
-            gc.into_nogc(),
-        )),
+        _ => agent.type_error("Not an object", gc.into_nogc()),
     }
 }
 
@@ -1194,11 +1183,7 @@ pub(crate) fn create_property_key_list_from_array_like<'a, 'b>(
     // 1. If validElementTypes is not present, set validElementTypes to all.
     // 2. If obj is not an Object, throw a TypeError exception.
     let Ok(object) = Object::try_from(obj) else {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Not an object",
-            gc.into_nogc(),
-        ));
+        return agent.type_error("Not an object", gc.into_nogc());
     };
     let object = object.bind(gc.nogc());
     let scoped_object = object.scope(agent, gc.nogc());
@@ -1231,11 +1216,7 @@ pub(crate) fn create_property_key_list_from_array_like<'a, 'b>(
             }
             Value::Symbol(sym) => list.push(agent, sym.into()),
             _ => {
-                return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "proxy [[OwnPropertyKeys]] must return an array with only string and symbol elements",
-                gc.into_nogc(),
-            ));
+                return agent.type_error("proxy [[OwnPropertyKeys]] must return an array with only string and symbol elements", gc.into_nogc());
             }
         }
         index += 1;
@@ -1363,11 +1344,7 @@ pub(crate) fn ordinary_has_instance<'a, 'b>(
     .bind(gc.nogc());
     // 5. If P is not an Object, throw a TypeError exception.
     let Ok(p) = Object::try_from(p) else {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Non-object prototype found",
-            gc.into_nogc(),
-        ));
+        return agent.type_error("Non-object prototype found", gc.into_nogc());
     };
     // 6. Repeat,
     is_prototype_of_loop(agent, p.unbind(), o.unbind(), gc)
@@ -1395,11 +1372,10 @@ pub(crate) fn species_constructor<'a>(
     }
     // 3. If C is not an Object, throw a TypeError exception.
     let Ok(c) = Object::try_from(c) else {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
+        return agent.type_error(
             "constructor property value is not an object",
             gc.into_nogc(),
-        ));
+        );
     };
     // 4. Let S be ? Get(C, %Symbol.species%).
     let s = get(
@@ -1419,11 +1395,7 @@ pub(crate) fn species_constructor<'a>(
         return Ok(s.unbind());
     }
     // 7. Throw a TypeError exception.
-    Err(agent.throw_exception_with_static_message(
-        ExceptionType::TypeError,
-        "constructor species is not a constructor",
-        gc.into_nogc(),
-    ))
+    agent.type_error("constructor species is not a constructor", gc.into_nogc())
 }
 
 pub(crate) fn is_prototype_of_loop<'a>(
@@ -1505,11 +1477,13 @@ pub(crate) fn scoped_enumerable_own_keys<'a, 'b>(
     mut gc: GcScope<'a, 'b>,
 ) -> JsResult<'a, ScopedCollection<'b, Vec<PropertyKey<'static>>>> {
     // Note: Only Proxy and possibly Module and EmbedderObject can run JS in
-    // [[OwnPropertyKeys]] and [[GetOwnProperty]] calls.
+    // [[OwnPropertyKeys]] and [[GetOwnProperty]] calls, and global objects
+    // with lazy globals can trigger GC.
     if !matches!(
         o.get(agent),
         Object::Proxy(_) | Object::Module(_) | Object::EmbedderObject(_)
-    ) {
+    ) && !agent.lazy_globals.has_pending(o.get(agent))
+    {
         let gc = gc.into_nogc();
         let o = o.get(agent).bind(gc);
         let keys = unwrap_try(o.try_own_property_keys(agent, gc))
@@ -1676,7 +1650,7 @@ pub(crate) fn enumerable_own_properties<'gc, Kind: EnumerablePropertiesKind>(
                 PropertyKey::String(str) => str.into(),
             };
             // ii. Let entry be CreateArrayFromList(« key, value »).
-            let entry = create_array_from_list(agent, &[key_value.into_value(), value], gc.nogc());
+            let entry = create_entry_pair(agent, key_value.into_value(), value, gc.nogc());
             // iii. Append entry to results.
             results.push(entry.into_value());
         }
@@ -1699,6 +1673,13 @@ pub(crate) fn enumerable_own_properties<'gc, Kind: EnumerablePropertiesKind>(
     }
 }
 
+/// Continues EnumerableOwnProperties with the remaining `own_keys`, calling
+/// into user code as needed.
+///
+/// Getters may add or delete properties of O while it is being enumerated.
+/// The keys come from the ownKeys list taken up front, and each is looked up
+/// again before it is read, so deleted keys are skipped and added keys are
+/// not visited.
 fn enumerable_own_properties_slow<'gc, Kind: EnumerablePropertiesKind>(
     agent: &mut Agent,
     o: Scoped<Object>,
@@ -1748,9 +1729,10 @@ fn enumerable_own_properties_slow<'gc, Kind: EnumerablePropertiesKind>(
             let key_value =
                 String::try_from(key.get(gc.nogc()).convert_to_value(agent, gc.nogc())).unwrap();
             // ii. Let entry be CreateArrayFromList(« key, value »).
-            let entry = create_array_from_list(
+            let entry = create_entry_pair(
                 agent,
-                &[key_value.into_value().unbind(), value.unbind()],
+                key_value.into_value().unbind(),
+                value.unbind(),
                 gc.nogc(),
             );
             // iii. Append entry to results.
@@ -1833,6 +1815,23 @@ fn ordinary_enumerable_own_keys<'gc>(
     agent: &mut Agent,
     o: OrdinaryObject,
     gc: NoGcScope<'gc, '_>,
+) -> Vec<PropertyKey<'gc>> {
+    let version = agent[o].keys_version;
+    if let Some(keys) = agent.heap.own_keys_cache.get_enumerable_keys(o, version) {
+        return keys.iter().map(|key| key.bind(gc)).collect();
+    }
+    let keys = compute_ordinary_enumerable_own_keys(agent, o, gc);
+    agent
+        .heap
+        .own_keys_cache
+        .set_enumerable_keys(o, version, &keys);
+    keys
+}
+
+fn compute_ordinary_enumerable_own_keys<'gc>(
+    agent: &Agent,
+    o: OrdinaryObject,
+    gc: NoGcScope<'gc, '_>,
 ) -> Vec<PropertyKey<'gc>> {
     let props = &agent[o].property_storage;
     // 1. Let keys be a new empty List.
@@ -1879,6 +1878,23 @@ fn ordinary_enumerable_own_keys<'gc>(
         }
     }
 
+    // Array index properties stored in the indexed elements.
+    let ElementStorageRef {
+        values,
+        descriptors,
+    } = agent[o].indexed_elements.get_storage(agent);
+    for (index, value) in values.iter().enumerate() {
+        let index = index as u32;
+        let descriptor = descriptors.and_then(|d| d.get(&index));
+        if value.is_none() && descriptor.is_none() {
+            // A hole.
+            continue;
+        }
+        if descriptor.is_none_or(|desc| desc.is_enumerable()) {
+            integer_keys.push(index);
+        }
+    }
+
     // 2. For each own property key P of O such that P is an array index,
     if !integer_keys.is_empty() {
         // in ascending numeric index order, do
@@ -2211,7 +2227,6 @@ pub(crate) fn copy_data_properties_into_object<'a, 'b>(
 ) -> JsResult<'a, OrdinaryObject<'a>> {
     let from = source.into_object().bind(gc.nogc());
     let scoped_from = from.scope(agent, gc.nogc());
-    let mut entries = Vec::new();
 
     // 3. Let keys be ? from.[[OwnPropertyKeys]]().
     let mut keys = from
@@ -2219,6 +2234,7 @@ pub(crate) fn copy_data_properties_into_object<'a, 'b>(
         .internal_own_property_keys(agent, gc.reborrow())
         .unbind()?
         .bind(gc.nogc());
+    let mut entries = Vec::with_capacity(keys.len());
     let from = scoped_from.get(agent).bind(gc.nogc());
     // 4. For each element nextKey of keys, do
     let mut broke = false;
@@ -2513,11 +2529,7 @@ pub(crate) fn private_set<'a>(
                 // an unwritable, unconfigurable (and unenumerable) descriptor, ie.
                 // method.
                 assert!(d.is_data_descriptor() && !d.is_writable().unwrap() && !d.is_enumerable());
-                Err(agent.throw_exception_with_static_message(
-                    ExceptionType::TypeError,
-                    "cannot assign to private method",
-                    gc.into_nogc(),
-                ))
+                agent.type_error("cannot assign to private method", gc.into_nogc())
             } else {
                 // a. Throw a TypeError exception.
                 // a. Set entry.[[Value]] to value.
@@ -2533,11 +2545,7 @@ pub(crate) fn private_set<'a>(
             // b. If entry.[[Set]] is undefined, throw a TypeError exception.
             // c. Let setter be entry.[[Set]].
             let Some(setter) = descriptor.setter_function(gc.nogc()) else {
-                return Err(agent.throw_exception_with_static_message(
-                    ExceptionType::TypeError,
-                    "setting getter-only private field",
-                    gc.into_nogc(),
-                ));
+                return agent.type_error("setting getter-only private field", gc.into_nogc());
             };
             // d. Perform ? Call(setter, O, « value »).
             call_function(
@@ -2676,11 +2684,7 @@ pub(crate) fn group_by_property<'gc>(
 
     // 2. If IsCallable(callback) is false, throw a TypeError exception.
     let Some(callback_fn) = is_callable(callback_fn, gc.nogc()) else {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Callback is not callable",
-            gc.into_nogc(),
-        ));
+        return agent.type_error("Callback is not callable", gc.into_nogc());
     };
     let callback_fn = callback_fn.scope(agent, gc.nogc());
 
@@ -2811,11 +2815,7 @@ pub(crate) fn group_by_collection<'gc>(
 
     // 2. If IsCallable(callback) is false, throw a TypeError exception.
     let Some(callback_fn) = is_callable(callback_fn, gc.nogc()) else {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Callback is not callable",
-            gc.into_nogc(),
-        ));
+        return agent.type_error("Callback is not callable", gc.into_nogc());
     };
     let callback_fn = callback_fn.scope(agent, gc.nogc());
 
@@ -8,14 +8,14 @@ use crate::ecmascript::abstract_operations::type_conversion::to_numeric_primitiv
 use crate::ecmascript::builtins::proxy::abstract_operations::{
     NonRevokedProxy, validate_non_revoked_proxy,
 };
-use crate::ecmascript::types::{InternalSlots, Numeric, Primitive, PropertyKey};
+use crate::ecmascript::types::{Numeric, Primitive, PropertyKey};
 use crate::engine::TryResult;
 use crate::engine::context::{Bindable, GcScope, NoGcScope};
 use crate::engine::rootable::Scopable;
 use crate::heap::WellKnownSymbolIndexes;
 use crate::{
     ecmascript::{
-        execution::{Agent, JsResult, agent::ExceptionType},
+        execution::{Agent, JsResult},
         types::{
             Function, InternalMethods, IntoValue, Number, Object, String, Value, bigint::BigInt,
         },
@@ -41,11 +41,7 @@ pub(crate) fn require_object_coercible<'gc>(
     gc: NoGcScope<'gc, '_>,
 ) -> JsResult<'gc, Value<'gc>> {
     if argument.is_undefined() || argument.is_null() {
-        Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Argument cannot be converted into an object",
-            gc,
-        ))
+        agent.type_error("Argument cannot be converted into an object", gc)
     } else {
         Ok(argument.bind(gc))
     }
@@ -155,20 +151,20 @@ pub(crate) fn is_reg_exp<'a>(
     gc: GcScope<'a, '_>,
 ) -> JsResult<'a, bool> {
     // 1. If argument is not an Object, return false.
-    if !argument.is_object() {
+    let Ok(object) = Object::try_from(argument) else {
         return Ok(false);
-    }
+    };
 
     // 2. Let matcher be ? Get(argument, %Symbol.match%).
     let matcher = get(
         agent,
-        Object::internal_prototype(Object::try_from(argument).unwrap(), agent).unwrap(),
+        object,
         PropertyKey::Symbol(WellKnownSymbolIndexes::Match.into()),
         gc,
     )?;
 
     // 3. If matcher is not undefined, return ToBoolean(matcher).
-    if matcher.is_undefined() {
+    if !matcher.is_undefined() {
         return Ok(to_boolean(agent, matcher));
     }
 
@@ -429,13 +425,9 @@ pub(crate) fn is_less_than<'a, const LEFT_FIRST: bool>(
         // iii. If cx < cy, return true.
         // iv. If cx > cy, return false.
         // d. If lx < ly, return true. Otherwise, return false.
-        // NOTE: For UTF-8 strings (i.e. strings with no lone surrogates), this
-        // should be equivalent to regular byte-by-byte string comparison.
-        // TODO: WTF-8 strings with lone surrogates will probably need special
-        // handling.
         let sx = String::try_from(px).unwrap();
         let sy = String::try_from(py).unwrap();
-        Ok(Some(sx.as_str(agent) < sy.as_str(agent)))
+        Ok(Some(String::cmp_code_units(agent, sx, sy).is_lt()))
     }
     // 4. Else,
     else {
@@ -15,6 +15,7 @@
 //! programmers must call BigInt explicitly to convert values from other types.
 
 use num_bigint::Sign;
+use small_string::SmallString;
 
 use crate::ecmascript::types::IntoPrimitive;
 use crate::engine::TryResult;
@@ -195,11 +196,7 @@ pub(crate) fn ordinary_to_primitive<'gc>(
         o = scoped_o.get(agent).bind(gc.nogc());
     }
     // 4. Throw a TypeError exception.
-    Err(agent.throw_exception_with_static_message(
-        ExceptionType::TypeError,
-        "Could not convert to primitive",
-        gc.into_nogc(),
-    ))
+    agent.type_error("Could not convert to primitive", gc.into_nogc())
 }
 
 /// ### [7.1.2 ToBoolean ( argument )](https://tc39.es/ecma262/#sec-toboolean)
@@ -315,21 +312,14 @@ pub(crate) fn to_number_primitive<'gc>(
         Primitive::String(str) => Ok(string_to_number(agent, str.into(), gc)),
         Primitive::SmallString(str) => Ok(string_to_number(agent, str.into(), gc)),
         // 2. If argument is either a Symbol or a BigInt, throw a TypeError exception.
-        Primitive::Symbol(_) => Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "cannot convert symbol to number",
-            gc,
-        )),
+        Primitive::Symbol(_) => agent.type_error("cannot convert symbol to number", gc),
         // 1. If argument is a Number, return argument.
         Primitive::Number(idx) => Ok(idx.unbind().bind(gc).into()),
         Primitive::Integer(idx) => Ok(idx.into()),
         Primitive::SmallF64(idx) => Ok(idx.into()),
-        Primitive::BigInt(_) | Primitive::SmallBigInt(_) => Err(agent
-            .throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "cannot convert bigint to number",
-                gc,
-            )),
+        Primitive::BigInt(_) | Primitive::SmallBigInt(_) => {
+            agent.type_error("cannot convert bigint to number", gc)
+        }
     }
 }
 
@@ -349,7 +339,10 @@ pub(crate) fn string_to_number<'gc>(
     // 1. Let literal be ParseText(str, StringNumericLiteral).
     // 2. If literal is a List of errors, return NaN.
     // 3. Return the StringNumericValue of literal.
-    let str = str.as_str(agent).trim_matches(is_trimmable_whitespace);
+    // NOTE: Lone surrogates are replaced with U+FFFD, which is not part of
+    // any StringNumericLiteral.
+    let str = str.to_string_lossy(agent);
+    let str = str.trim_matches(is_trimmable_whitespace);
     match str {
         "+Infinity" | "Infinity" => {
             return Number::pos_inf();
@@ -371,10 +364,6 @@ pub(crate) fn string_to_number<'gc>(
         (Some(b'0'), Some(b'b' | b'B')) => Some(2),
         (Some(b'0'), Some(b'o' | b'O')) => Some(8),
         (Some(b'0'), Some(b'x' | b'X')) => Some(16),
-        // Make sure that no further variants of "infinity" are parsed.
-        (Some(b'i' | b'I'), _) => {
-            return Number::nan();
-        }
         _ => None,
     };
 
@@ -385,9 +374,12 @@ pub(crate) fn string_to_number<'gc>(
             return Number::nan();
         }
 
-        // Fast path
-        if let Ok(value) = u32::from_str_radix(string, base) {
-            return value.into();
+        // Fast path: from_str_radix accepts a leading plus sign, which
+        // NonDecimalIntegerLiteral does not.
+        if !string.starts_with('+') {
+            if let Ok(value) = u32::from_str_radix(string, base) {
+                return value.into();
+            }
         }
 
         // Slow path
@@ -402,6 +394,13 @@ pub(crate) fn string_to_number<'gc>(
         return Number::from_f64(agent, value, gc);
     }
 
+    // StrDecimalLiteral is an optional sign followed by digits or a dot. The
+    // float parser also accepts variants of "inf", "infinity" and "nan".
+    let unsigned = str.strip_prefix(['+', '-']).unwrap_or(str);
+    if !unsigned.starts_with(|c: char| c.is_ascii_digit() || c == '.') {
+        return Number::nan();
+    }
+
     if let Ok(result) = fast_float::parse(str) {
         Number::from_f64(agent, result, gc)
     } else {
@@ -604,6 +603,17 @@ pub(crate) fn try_to_integer_or_infinity<'a>(
     TryResult::Continue(Ok(to_integer_or_infinity_number(agent, number)))
 }
 
+/// Computes truncate(number) modulo 2^32 for a finite number.
+///
+/// All of the ToIntN and ToUintN operations take their result modulo a
+/// divisor of 2^32, so the result can be narrowed with a wrapping cast.
+/// Casting the truncated number directly to an integer would saturate at
+/// the integer's bounds instead of wrapping.
+fn truncate_modulo_2_32(number: f64) -> u32 {
+    // Note: Both the truncation and the remainder are exact.
+    number.trunc().rem_euclid(4294967296.0) as u32
+}
+
 /// ### [7.1.6 ToInt32 ( argument )](https://tc39.es/ecma262/#sec-toint32)
 pub(crate) fn to_int32<'a>(
     agent: &mut Agent,
@@ -639,7 +649,7 @@ pub(crate) fn to_int32_number(agent: &mut Agent, number: Number) -> i32 {
     // 3. Let int be truncate(ℝ(number)).
     // 4. Let int32bit be int modulo 2^32.
     // 5. If int32bit ≥ 2^31, return 𝔽(int32bit - 2^32); otherwise return 𝔽(int32bit).
-    number.into_f64(agent).trunc() as i64 as i32
+    truncate_modulo_2_32(number.into_f64(agent)) as i32
 }
 
 /// ### [7.1.7 ToUint32 ( argument )](https://tc39.es/ecma262/#sec-touint32)
@@ -678,7 +688,7 @@ pub(crate) fn to_uint32_number(agent: &mut Agent, number: Number) -> u32 {
     // 3. Let int be truncate(ℝ(number)).
     // 4. Let int32bit be int modulo 2^32.
     // 5. Return 𝔽(int32bit).
-    number.into_f64(agent).trunc() as i64 as u32
+    truncate_modulo_2_32(number.into_f64(agent))
 }
 
 /// ### [7.1.8 ToInt16 ( argument )](https://tc39.es/ecma262/#sec-toint16)
@@ -714,7 +724,7 @@ pub(crate) fn to_int16_number(agent: &mut Agent, number: Number) -> i16 {
     // 3. Let int be truncate(ℝ(number)).
     // 4. Let int16bit be int modulo 2^16.
     // 5. If int16bit ≥ 2^15, return 𝔽(int16bit - 2^16); otherwise return 𝔽(int16bit).
-    number.into_f64(agent).trunc() as i64 as i16
+    truncate_modulo_2_32(number.into_f64(agent)) as i16
 }
 
 /// ### [7.1.9 ToUint16 ( argument )](https://tc39.es/ecma262/#sec-touint16)
@@ -750,7 +760,7 @@ pub(crate) fn to_uint16_number(agent: &mut Agent, number: Number) -> u16 {
     // 3. Let int be truncate(ℝ(number)).
     // 4. Let int16bit be int modulo 2^16.
     // Return 𝔽(int16bit).
-    number.into_f64(agent).trunc() as i64 as u16
+    truncate_modulo_2_32(number.into_f64(agent)) as u16
 }
 
 /// ### [7.1.10 ToInt8 ( argument )](https://tc39.es/ecma262/#sec-toint8)
@@ -786,7 +796,7 @@ pub(crate) fn to_int8_number(agent: &mut Agent, number: Number) -> i8 {
     // 3. Let int be truncate(ℝ(number)).
     // 4. Let int8bit be int modulo 2^8.
     // 5. If int8bit ≥ 2^7, return 𝔽(int8bit - 2^8); otherwise return 𝔽(int8bit).
-    number.into_f64(agent).trunc() as i64 as i8
+    truncate_modulo_2_32(number.into_f64(agent)) as i8
 }
 
 /// ### [7.1.11 ToUint8 ( argument )](https://tc39.es/ecma262/#sec-touint8)
@@ -822,7 +832,7 @@ pub(crate) fn to_uint8_number(agent: &mut Agent, number: Number) -> u8 {
     // 3. Let int be truncate(ℝ(number)).
     // 4. Let int8bit be int modulo 2^8.
     // 5. Return 𝔽(int8bit).
-    number.into_f64(agent).trunc() as i64 as u8
+    truncate_modulo_2_32(number.into_f64(agent)) as u8
 }
 
 /// ### [7.1.12 ToUint8Clamp ( argument )](https://tc39.es/ecma262/#sec-touint8clamp)
@@ -889,16 +899,8 @@ pub(crate) fn to_big_int_primitive<'a>(
 ) -> JsResult<'a, BigInt<'a>> {
     // 2. Return the value that prim corresponds to in Table 12.
     match prim {
-        Primitive::Undefined => Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Invalid primitive 'undefined'",
-            gc,
-        )),
-        Primitive::Null => Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Invalid primitive 'null'",
-            gc,
-        )),
+        Primitive::Undefined => agent.type_error("Invalid primitive 'undefined'", gc),
+        Primitive::Null => agent.type_error("Invalid primitive 'null'", gc),
         Primitive::Boolean(bool) => {
             if bool {
                 Ok(BigInt::from(1))
@@ -908,17 +910,10 @@ pub(crate) fn to_big_int_primitive<'a>(
         }
         Primitive::String(idx) => string_to_big_int(agent, idx.into(), gc),
         Primitive::SmallString(data) => string_to_big_int(agent, data.into(), gc),
-        Primitive::Symbol(_) => Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Cannot convert Symbol to BigInt",
-            gc,
-        )),
-        Primitive::Number(_) | Primitive::Integer(_) | Primitive::SmallF64(_) => Err(agent
-            .throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Cannot convert Number to BigInt",
-                gc,
-            )),
+        Primitive::Symbol(_) => agent.type_error("Cannot convert Symbol to BigInt", gc),
+        Primitive::Number(_) | Primitive::Integer(_) | Primitive::SmallF64(_) => {
+            agent.type_error("Cannot convert Number to BigInt", gc)
+        }
         Primitive::BigInt(idx) => Ok(BigInt::BigInt(idx).bind(gc)),
         Primitive::SmallBigInt(data) => Ok(data.into()),
     }
@@ -938,7 +933,9 @@ pub(crate) fn string_to_big_int<'a>(
     // StringIntegerLiteral is either whitespace only or a StrIntegerLiteral surrounded by
     // optional whitespace.
 
-    let literal = argument.as_str(agent); // Extra line literally just for displaying error
+    // NOTE: Lone surrogates are replaced with U+FFFD, which is not part of
+    // any StringIntegerLiteral.
+    let literal = argument.to_string_lossy(agent); // Extra line literally just for displaying error
 
     // 4. Let mv be the MV of literal.
     // 5. Assert: mv is an integer.
@@ -1118,11 +1115,7 @@ pub(crate) fn to_string_primitive<'gc>(
         Primitive::String(idx) => Ok(String::String(idx)),
         Primitive::SmallString(data) => Ok(String::SmallString(data)),
         // 2. If argument is a Symbol, throw a TypeError exception.
-        Primitive::Symbol(_) => Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Cannot turn Symbol into string",
-            gc,
-        )),
+        Primitive::Symbol(_) => agent.type_error("Cannot turn Symbol into string", gc),
         // 7. If argument is a Number, return Number::toString(argument, 10).
         Primitive::Number(_) | Primitive::Integer(_) | Primitive::SmallF64(_) => {
             Ok(Number::to_string_radix_10(agent, Number::try_from(argument).unwrap(), gc).unbind())
@@ -1147,11 +1140,9 @@ pub(crate) fn to_object<'a>(
 ) -> JsResult<'a, Object<'a>> {
     let argument = argument.bind(gc);
     match argument {
-        Value::Undefined | Value::Null => Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Argument cannot be converted into an object",
-            gc,
-        )),
+        Value::Undefined | Value::Null => {
+            agent.type_error("Argument cannot be converted into an object", gc)
+        }
         // Return a new Boolean object whose [[BooleanData]] internal slot is set to argument.
         Value::Boolean(bool) => Ok(agent
             .heap
@@ -1253,6 +1244,7 @@ pub(crate) fn to_property_key<'a, 'gc>(
 /// - Safe integer numbers
 /// - Safe integet bigints
 /// - Negative zero
+/// - Numbers whose string form fits in a SmallString
 /// - Stringified safe integers
 /// - Strings
 /// - Symbols
@@ -1272,19 +1264,19 @@ pub(crate) fn to_property_key_simple<'a, 'gc>(
     match argument {
         Value::String(_) | Value::SmallString(_) => {
             let (str, string_key) = match &argument {
-                Value::String(x) => (agent[*x].as_str(), PropertyKey::String(*x)),
-                Value::SmallString(x) => (x.as_str(), PropertyKey::SmallString(*x)),
+                Value::String(x) => (agent[*x].as_wtf8().as_str(), PropertyKey::String(*x)),
+                Value::SmallString(x) => (Some(x.as_str()), PropertyKey::SmallString(*x)),
                 _ => unreachable!(),
             };
-            if let Some(key) = parse_string_to_integer_property_key(str) {
+            if let Some(key) = str.and_then(parse_string_to_integer_property_key) {
                 TryResult::Continue(key)
             } else {
                 TryResult::Continue(string_key)
             }
         }
         Value::Integer(x) => TryResult::Continue(PropertyKey::Integer(x)),
-        Value::SmallF64(x) if x.into_f64() == -0.0 => {
-            TryResult::Continue(PropertyKey::Integer(0.into()))
+        Value::Number(_) | Value::SmallF64(_) => {
+            try_number_to_property_key(agent, Number::try_from(argument).unwrap())
         }
         Value::Symbol(x) => TryResult::Continue(PropertyKey::Symbol(x)),
         Value::SmallBigInt(x)
@@ -1326,10 +1318,12 @@ pub(crate) fn to_property_key_complex<'a, 'gc>(
     // keys here as well.
     if let TryResult::Continue(key) = to_property_key_simple(agent, key, gc) {
         Ok(key)
+    } else if let Ok(number) = Number::try_from(key) {
+        // Key was a Number whose string form does not fit in a SmallString.
+        Ok(number_to_property_key(agent, number, gc))
     } else {
-        // Key was still not simple: This mean it's a heap allocated f64,
-        // BigInt, or non-negative-zero f32: These should never be safe
-        // integers and thus will never be PropertyKey::Integer after
+        // Key was still not simple: This mean it's a BigInt outside the safe
+        // integer range: These will never be PropertyKey::Integer after
         // stringifying.
 
         // 3. Return ! ToString(key).
@@ -1337,6 +1331,44 @@ pub(crate) fn to_property_key_complex<'a, 'gc>(
     }
 }
 
+/// Convert a Number into a PropertyKey.
+///
+/// Numbers that are safe integers, including negative zero, become integer
+/// keys. All other Numbers are stringified using Number::toString(x, 10) and
+/// become SmallString keys when the result fits inline; only longer results
+/// are allocated (or found interned) on the heap. This guarantees that a key
+/// created from a Number is identical to the key created from its canonical
+/// string form.
+pub(crate) fn number_to_property_key<'gc>(
+    agent: &mut Agent,
+    number: Number,
+    gc: NoGcScope<'gc, '_>,
+) -> PropertyKey<'gc> {
+    if let TryResult::Continue(key) = try_number_to_property_key(agent, number) {
+        return key;
+    }
+    let mut buffer = ryu_js::Buffer::new();
+    String::from_str(agent, buffer.format(number.into_f64(agent)), gc).into()
+}
+
+/// Convert a Number into a PropertyKey without allocating.
+///
+/// Returns Break if the key's string form does not fit in a SmallString.
+pub(crate) fn try_number_to_property_key(
+    agent: &Agent,
+    number: Number,
+) -> TryResult<PropertyKey<'static>> {
+    let value = number.into_f64(agent);
+    if let Ok(integer) = SmallInteger::try_from(value) {
+        return TryResult::Continue(PropertyKey::Integer(integer));
+    }
+    let mut buffer = ryu_js::Buffer::new();
+    match SmallString::try_from(buffer.format(value)) {
+        Ok(string) => TryResult::Continue(PropertyKey::SmallString(string)),
+        Err(_) => TryResult::Break(()),
+    }
+}
+
 pub(crate) fn parse_string_to_integer_property_key(str: &str) -> Option<PropertyKey<'static>> {
     // i64::from_string will accept eg. 0123 as 123 but JS property keys do
     // not agree. Hence, only "0" can start with "0", all other integer
@@ -1431,11 +1463,7 @@ pub(crate) fn validate_index<'a>(
     gc: NoGcScope<'a, '_>,
 ) -> JsResult<'a, u64> {
     if !(0..=(SmallInteger::MAX)).contains(&value) {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::RangeError,
-            "Index is out of range",
-            gc,
-        ));
+        return agent.range_error("Index is out of range", gc);
     }
     Ok(value as u64)
 }
@@ -1510,3 +1538,96 @@ pub const fn is_trimmable_whitespace(c: char) -> bool {
     '\u{000A}' | '\u{000D}' | '\u{2028}' | '\u{2029}'
     )
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        ecmascript::{
+            execution::{Agent, DefaultHostHooks, agent::Options, initialize_default_realm},
+            types::{String, Value},
+        },
+        engine::context::Bindable,
+    };
+
+    /// String literals in JavaScript source form and the Number that they convert
+    /// to, also in source form.
+    const CASES: &[(&str, &str)] = &[
+        // Empty and whitespace-only strings are zero.
+        (r#""""#, "0"),
+        (r#""   ""#, "0"),
+        (r#"" \t\n\v\f\r   ﻿""#, "0"),
+        // Surrounding whitespace and line terminators are ignored.
+        (r#"" \t42\n""#, "42"),
+        (r#""﻿　-7.5 ""#, "-7.5"),
+        // Decimal literals.
+        (r#""1e3""#, "1000"),
+        (r#""1E+3""#, "1000"),
+        (r#""-1e-3""#, "-0.001"),
+        (r#""+.5""#, "0.5"),
+        (r#""5.""#, "5"),
+        (r#""00017""#, "17"),
+        (r#""-0""#, "-0"),
+        (r#""1e1000""#, "Infinity"),
+        // Infinity is only spelled one way.
+        (r#""Infinity""#, "Infinity"),
+        (r#""+Infinity""#, "Infinity"),
+        (r#""-Infinity""#, "-Infinity"),
+        (r#""infinity""#, "NaN"),
+        (r#""-inf""#, "NaN"),
+        (r#""+inf""#, "NaN"),
+        (r#""INF""#, "NaN"),
+        (r#""nan""#, "NaN"),
+        (r#""NaN""#, "NaN"),
+        // Non-decimal integer literals are unsigned.
+        (r#""0x1F""#, "31"),
+        (r#""0X1f""#, "31"),
+        (r#""0b101""#, "5"),
+        (r#""0B101""#, "5"),
+        (r#""0o17""#, "15"),
+        (r#""0O17""#, "15"),
+        (r#""0x100000000""#, "4294967296"),
+        (r#""-0x10""#, "NaN"),
+        (r#""+0x10""#, "NaN"),
+        (r#""0x+1F""#, "NaN"),
+        (r#""0x""#, "NaN"),
+        (r#""0b2""#, "NaN"),
+        (r#""0o8""#, "NaN"),
+        (r#""0x1.5""#, "NaN"),
+        // Anything else is NaN.
+        (r#""12abc""#, "NaN"),
+        (r#""1e""#, "NaN"),
+        (r#"".""#, "NaN"),
+        (r#""++1""#, "NaN"),
+        (r#""1_000""#, "NaN"),
+        (r#""1 2""#, "NaN"),
+    ];
+
+    #[test]
+    fn string_to_number_conversions() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        for (literal, expected) in CASES {
+            // Unary plus, the Number constructor and loose equality all go
+            // through StringToNumber.
+            let source = format!(
+                "Object.is(+{literal}, {expected}) &&
+                Object.is(Number({literal}), {expected}) &&
+                ({literal} == {expected}) === !Number.isNaN({expected}) &&
+                ({literal} < {expected}) === false"
+            );
+            let source = String::from_string(agent, source, gc.nogc());
+            let result = agent
+                .run_script(source.unbind(), gc.reborrow())
+                .unwrap()
+                .unbind();
+            assert_eq!(
+                result,
+                Value::Boolean(true),
+                "{literal} should convert to {expected}"
+            );
+        }
+    }
+}
@@ -16,7 +16,7 @@ use crate::{
     },
     engine::context::Bindable,
     heap::{
-        element_array::ElementDescriptor,
+        element_array::{ElementDescriptor, ElementsVector},
         indexes::{BuiltinFunctionIndex, ObjectIndex},
     },
 };
@@ -102,6 +102,25 @@ impl<'agent>
         }
     }
 
+    /// Start building a builtin function whose length, name, and behaviour
+    /// are only known at runtime, eg. a method installed by the embedder.
+    #[must_use]
+    pub(crate) fn new_dynamic(agent: &'agent mut Agent, realm: Realm<'static>) -> Self {
+        agent.heap.builtin_functions.push(None);
+        let this = BuiltinFunctionIndex::last(&agent.heap.builtin_functions).into();
+        BuiltinFunctionBuilder {
+            agent,
+            this,
+            object_index: None,
+            realm,
+            prototype: NoPrototype,
+            length: NoLength,
+            name: NoName,
+            behaviour: NoBehaviour,
+            properties: NoProperties,
+        }
+    }
+
     #[must_use]
     pub(crate) fn new_intrinsic_constructor<T: BuiltinIntrinsicConstructor>(
         agent: &'agent mut Agent,
@@ -616,6 +635,8 @@ impl
         *slot = Some(ObjectHeapData {
             prototype,
             property_storage,
+            indexed_elements: ElementsVector::default(),
+            keys_version: 0,
         });
 
         let data = BuiltinFunctionHeapData {
@@ -687,6 +708,8 @@ impl
         *slot = Some(ObjectHeapData {
             prototype: prototype.0,
             property_storage,
+            indexed_elements: ElementsVector::default(),
+            keys_version: 0,
         });
 
         let data = BuiltinFunctionHeapData {
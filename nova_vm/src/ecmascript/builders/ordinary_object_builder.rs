@@ -8,10 +8,14 @@ use crate::{
         execution::{Agent, Realm},
         types::{
             BUILTIN_STRING_MEMORY, IntoFunction, IntoObject, IntoValue, ObjectHeapData,
-            OrdinaryObject, PropertyKey, Value,
+            OrdinaryObject, PropertyKey, String, Value,
         },
     },
-    heap::{element_array::ElementDescriptor, indexes::ObjectIndex},
+    heap::{
+        WellKnownSymbolIndexes,
+        element_array::{ElementDescriptor, ElementsVector},
+        indexes::ObjectIndex,
+    },
 };
 
 use super::{
@@ -164,6 +168,9 @@ impl<P> OrdinaryObjectBuilder<'_, P, CreatorProperties> {
         }
     }
 
+    /// Adds a `constructor` property linking a prototype object back to its
+    /// constructor. The property is writable, non-enumerable, and
+    /// configurable.
     #[must_use]
     pub fn with_constructor_property(mut self, constructor: BuiltinFunction<'static>) -> Self {
         let property = PropertyBuilder::new(self.agent)
@@ -182,6 +189,9 @@ impl<P> OrdinaryObjectBuilder<'_, P, CreatorProperties> {
         }
     }
 
+    /// Adds a built-in method property. The attributes are taken from the
+    /// [`Builtin`]; by default methods are writable, non-enumerable, and
+    /// configurable.
     #[must_use]
     pub fn with_builtin_function_property<T: Builtin>(mut self) -> Self {
         let (value, key) = {
@@ -237,6 +247,8 @@ impl<P> OrdinaryObjectBuilder<'_, P, CreatorProperties> {
         }
     }
 
+    /// Adds a built-in accessor property with only a getter. The getter's
+    /// name should be `get <key>`, eg. `get [Symbol.species]`.
     #[must_use]
     pub(crate) fn with_builtin_function_getter_property<T: BuiltinGetter>(mut self) -> Self {
         let getter_function = BuiltinFunctionBuilder::new::<T>(self.agent, self.realm)
@@ -258,6 +270,20 @@ impl<P> OrdinaryObjectBuilder<'_, P, CreatorProperties> {
             properties: self.properties,
         }
     }
+
+    /// Adds a `%Symbol.toStringTag%` property. The property is non-writable,
+    /// non-enumerable, and configurable.
+    #[must_use]
+    pub(crate) fn with_to_string_tag(self, tag: String<'static>) -> Self {
+        self.with_property(|builder| {
+            builder
+                .with_key(WellKnownSymbolIndexes::ToStringTag.into())
+                .with_value_readonly(tag.into_value())
+                .with_enumerable(false)
+                .with_configurable(true)
+                .build()
+        })
+    }
 }
 
 impl OrdinaryObjectBuilder<'_, NoPrototype, NoProperties> {
@@ -278,6 +304,8 @@ impl OrdinaryObjectBuilder<'_, NoPrototype, NoProperties> {
         *slot = Some(ObjectHeapData {
             prototype: None,
             property_storage,
+            indexed_elements: ElementsVector::default(),
+            keys_version: 0,
         });
         self.this
     }
@@ -301,6 +329,8 @@ impl<T: IntoObject<'static>> OrdinaryObjectBuilder<'_, CreatorPrototype<T>, NoPr
         *slot = Some(ObjectHeapData {
             prototype: Some(self.prototype.0.into_object()),
             property_storage,
+            indexed_elements: ElementsVector::default(),
+            keys_version: 0,
         });
         self.this
     }
@@ -336,6 +366,8 @@ impl OrdinaryObjectBuilder<'_, NoPrototype, CreatorProperties> {
         *slot = Some(ObjectHeapData {
             prototype: None,
             property_storage,
+            indexed_elements: ElementsVector::default(),
+            keys_version: 0,
         });
         self.this
     }
@@ -371,6 +403,8 @@ impl<T: IntoObject<'static>> OrdinaryObjectBuilder<'_, CreatorPrototype<T>, Crea
         *slot = Some(ObjectHeapData {
             prototype: Some(self.prototype.0.into_object()),
             property_storage,
+            indexed_elements: ElementsVector::default(),
+            keys_version: 0,
         });
         self.this
     }
@@ -8,6 +8,7 @@
 //!
 //! https://tc39.es/ecma262/#sec-ordinary-and-exotic-objects-behaviours
 
+pub(crate) mod argument_coercion;
 pub(crate) mod arguments;
 mod array;
 #[cfg(feature = "array-buffer")]
@@ -30,7 +31,7 @@ pub(crate) mod indexed_collections;
 pub(crate) mod keyed_collections;
 pub(crate) mod managing_memory;
 pub(crate) mod map;
-pub(crate) mod module;
+pub mod module;
 pub(crate) mod numbers_and_dates;
 pub mod ordinary;
 pub(crate) mod primitive_objects;
@@ -69,6 +70,7 @@ pub use builtin_function::{
     ConstructorFn, RegularFn as JsFunction, RegularFn, ScopedArgumentsList,
     create_builtin_function,
 };
-pub(crate) use builtin_function::{BuiltinIntrinsic, BuiltinIntrinsicConstructor};
+pub(crate) use builtin_function::{BuiltinIntrinsic, BuiltinIntrinsicConstructor, create_closure};
 pub use control_abstraction_objects::*;
 pub(crate) use ecmascript_function::*;
+pub use promise::Promise;
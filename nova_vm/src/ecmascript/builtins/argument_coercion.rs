@@ -0,0 +1,304 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! ## Coercion of builtin function parameters
+//!
+//! Most builtin functions start by coercing their this value and their
+//! arguments into the types that their algorithm works with. Each coercion
+//! may call into user code (`valueOf`, `toString`, `Symbol.toPrimitive`), so
+//! their order is observable and each coerced value must be kept alive across
+//! the coercions that come after it.
+//!
+//! The helpers in this module perform a single coercion each, and the
+//! [`coerce_arguments!`] macro strings them together in the order they are
+//! written in, rooting the arguments and the intermediate results as needed.
+
+use crate::{
+    ecmascript::{
+        abstract_operations::type_conversion::{
+            IntegerOrInfinity, to_big_int, to_index, to_integer_or_infinity, to_number, to_object,
+            to_string,
+        },
+        builtins::ScopedArgumentsList,
+        execution::{Agent, JsResult, agent::ExceptionType},
+        types::{BigInt, Number, Object, String, Value},
+    },
+    engine::{
+        Scoped,
+        context::{Bindable, GcScope, NoGcScope},
+        rootable::{Rootable, Scopable},
+    },
+};
+
+/// Throws a TypeError if `this_value` is undefined or null, naming the method
+/// that was called on it.
+fn require_object_coercible_this<'gc>(
+    agent: &mut Agent,
+    this_value: Value,
+    method_name: &str,
+    gc: NoGcScope<'gc, '_>,
+) -> JsResult<'gc, ()> {
+    if this_value.is_undefined() || this_value.is_null() {
+        Err(agent.throw_exception(
+            ExceptionType::TypeError,
+            format!("{method_name} called on null or undefined"),
+            gc,
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Performs `? ToObject(this value)` for the method `method_name`.
+pub(crate) fn to_object_this<'gc>(
+    agent: &mut Agent,
+    this_value: Value,
+    method_name: &str,
+    gc: NoGcScope<'gc, '_>,
+) -> JsResult<'gc, Object<'gc>> {
+    require_object_coercible_this(agent, this_value, method_name, gc)?;
+    to_object(agent, this_value, gc)
+}
+
+/// Performs `? RequireObjectCoercible(this value)` followed by
+/// `? ToString(O)` for the method `method_name`.
+pub(crate) fn to_string_this<'gc>(
+    agent: &mut Agent,
+    this_value: Value,
+    method_name: &str,
+    gc: GcScope<'gc, '_>,
+) -> JsResult<'gc, String<'gc>> {
+    let this_value = this_value.bind(gc.nogc());
+    require_object_coercible_this(agent, this_value, method_name, gc.nogc())
+        .unbind()?
+        .bind(gc.nogc());
+    to_string(agent, this_value.unbind(), gc)
+}
+
+/// Performs `? ToNumber(args[index])`.
+pub(crate) fn to_number_arg<'gc>(
+    agent: &mut Agent,
+    args: &ScopedArgumentsList,
+    index: u32,
+    gc: GcScope<'gc, '_>,
+) -> JsResult<'gc, Number<'gc>> {
+    let argument = args.get(agent, index, gc.nogc());
+    to_number(agent, argument.unbind(), gc)
+}
+
+/// Performs `? ToString(args[index])`.
+pub(crate) fn to_string_arg<'gc>(
+    agent: &mut Agent,
+    args: &ScopedArgumentsList,
+    index: u32,
+    gc: GcScope<'gc, '_>,
+) -> JsResult<'gc, String<'gc>> {
+    let argument = args.get(agent, index, gc.nogc());
+    to_string(agent, argument.unbind(), gc)
+}
+
+/// Performs `? ToIntegerOrInfinity(args[index])`.
+pub(crate) fn to_integer_or_infinity_arg<'gc>(
+    agent: &mut Agent,
+    args: &ScopedArgumentsList,
+    index: u32,
+    gc: GcScope<'gc, '_>,
+) -> JsResult<'gc, IntegerOrInfinity> {
+    let argument = args.get(agent, index, gc.nogc());
+    to_integer_or_infinity(agent, argument.unbind(), gc)
+}
+
+/// Performs `? ToIntegerOrInfinity(args[index])` unless the argument is
+/// undefined, in which case None is returned.
+pub(crate) fn to_optional_integer_or_infinity_arg<'gc>(
+    agent: &mut Agent,
+    args: &ScopedArgumentsList,
+    index: u32,
+    gc: GcScope<'gc, '_>,
+) -> JsResult<'gc, Option<IntegerOrInfinity>> {
+    let argument = args.get(agent, index, gc.nogc());
+    if argument.is_undefined() {
+        return Ok(None);
+    }
+    to_integer_or_infinity(agent, argument.unbind(), gc).map(Some)
+}
+
+/// Performs `? ToIndex(args[index])`, throwing a RangeError if the integer
+/// is not in the inclusive interval from 0 to 2**53 - 1.
+pub(crate) fn to_index_arg<'gc>(
+    agent: &mut Agent,
+    args: &ScopedArgumentsList,
+    index: u32,
+    gc: GcScope<'gc, '_>,
+) -> JsResult<'gc, i64> {
+    let argument = args.get(agent, index, gc.nogc());
+    to_index(agent, argument.unbind(), gc)
+}
+
+/// Performs `? ToBigInt(args[index])`.
+pub(crate) fn to_big_int_arg<'gc>(
+    agent: &mut Agent,
+    args: &ScopedArgumentsList,
+    index: u32,
+    gc: GcScope<'gc, '_>,
+) -> JsResult<'gc, BigInt<'gc>> {
+    let argument = args.get(agent, index, gc.nogc());
+    to_big_int(agent, argument.unbind(), gc)
+}
+
+/// A coerced value that [`coerce_arguments!`] keeps alive while the remaining
+/// arguments are coerced.
+pub(crate) trait Hold: Bindable {
+    type Held<'scope>: Release<Value = Self::Of<'static>>;
+
+    fn hold<'scope>(self, agent: &mut Agent, gc: NoGcScope<'_, 'scope>) -> Self::Held<'scope>;
+}
+
+/// A held coerced value that can be taken back out once all coercions are
+/// done.
+pub(crate) trait Release {
+    type Value;
+
+    fn release(self, agent: &Agent) -> Self::Value;
+}
+
+impl<T: Rootable> Release for Scoped<'_, T> {
+    type Value = T;
+
+    #[inline(always)]
+    fn release(self, agent: &Agent) -> T {
+        self.get(agent)
+    }
+}
+
+macro_rules! held_by_scoping {
+    ($ty:ident) => {
+        impl Hold for $ty<'_> {
+            type Held<'scope> = Scoped<'scope, $ty<'static>>;
+
+            #[inline(always)]
+            fn hold<'scope>(
+                self,
+                agent: &mut Agent,
+                gc: NoGcScope<'_, 'scope>,
+            ) -> Self::Held<'scope> {
+                self.scope(agent, gc)
+            }
+        }
+    };
+}
+
+held_by_scoping!(BigInt);
+held_by_scoping!(Number);
+held_by_scoping!(Object);
+held_by_scoping!(String);
+
+macro_rules! held_by_value {
+    ($ty:ty) => {
+        impl Hold for $ty {
+            type Held<'scope> = $ty;
+
+            #[inline(always)]
+            fn hold<'scope>(self, _: &mut Agent, _: NoGcScope<'_, 'scope>) -> Self::Held<'scope> {
+                self
+            }
+        }
+
+        impl Release for $ty {
+            type Value = $ty;
+
+            #[inline(always)]
+            fn release(self, _: &Agent) -> $ty {
+                self
+            }
+        }
+    };
+}
+
+held_by_value!(i64);
+held_by_value!(IntegerOrInfinity);
+held_by_value!(Option<IntegerOrInfinity>);
+
+/// Coerces the this value and arguments of a builtin function, binding the
+/// results to the given names.
+///
+/// The coercions are performed left to right, in the order they are written
+/// in. An optional `this` coercion comes first and is given the this value;
+/// the `let` coercions are given the arguments list. Each coercion names one
+/// of the helpers in this module along with its extra parameters, and
+/// receives the Agent first and the GcScope last.
+///
+/// ```rust,ignore
+/// coerce_arguments!(agent, this_value, arguments, gc => {
+///     this s = to_string_this("String.prototype.at");
+///     let relative_index = to_integer_or_infinity_arg(0);
+/// });
+/// ```
+///
+/// The arguments list must be declared `mut`. The results are bound to
+/// `gc.nogc()`.
+macro_rules! coerce_arguments {
+    (
+        $agent:ident, $this:ident, $arguments:ident, $gc:ident => {
+            $(this $this_name:ident = $this_coercion:ident($($this_param:expr),* $(,)?);)?
+            $(let $name:ident = $coercion:ident($($param:expr),* $(,)?);)+
+        }
+    ) => {
+        #[allow(unused_variables)]
+        let this_value = $this.unbind();
+        let ($($this_name,)? $($name,)+) = $arguments
+            .with_scoped(
+                $agent,
+                |agent, args, mut gc| {
+                    use crate::ecmascript::builtins::argument_coercion::{Hold, Release};
+                    $(
+                        // The this value is coerced first, before any
+                        // garbage collection can have occurred.
+                        let $this_name = crate::ecmascript::builtins::argument_coercion::$this_coercion(
+                            agent,
+                            this_value,
+                            $($this_param,)*
+                            gc.reborrow(),
+                        )
+                        .unbind()?
+                        .bind(gc.nogc())
+                        .hold(agent, gc.nogc());
+                    )?
+                    $(
+                        let $name = crate::ecmascript::builtins::argument_coercion::$coercion(
+                            agent,
+                            &args,
+                            $($param,)*
+                            gc.reborrow(),
+                        )
+                        .unbind()?
+                        .bind(gc.nogc())
+                        .hold(agent, gc.nogc());
+                    )+
+                    crate::ecmascript::execution::JsResult::Ok((
+                        $($this_name.release(agent),)?
+                        $($name.release(agent),)+
+                    ))
+                },
+                $gc.reborrow(),
+            )
+            .map_err(|err| err.unbind())?;
+        $(let $this_name = $this_name.bind($gc.nogc());)?
+        $(let $name = $name.bind($gc.nogc());)+
+    };
+    (
+        $agent:ident, $arguments:ident, $gc:ident => {
+            $(let $name:ident = $coercion:ident($($param:expr),* $(,)?);)+
+        }
+    ) => {
+        let this_value = crate::ecmascript::types::Value::Undefined;
+        crate::ecmascript::builtins::argument_coercion::coerce_arguments!(
+            $agent, this_value, $arguments, $gc => {
+                $(let $name = $coercion($($param),*);)+
+            }
+        );
+    };
+}
+
+pub(crate) use coerce_arguments;
@@ -22,7 +22,7 @@ use crate::{
             array::abstract_operations::{array_set_length, array_try_set_length},
             ordinary::ordinary_define_own_property,
         },
-        execution::{Agent, JsResult, ProtoIntrinsics},
+        execution::{Agent, JsResult, ProtoIntrinsics, SlowPathReason},
         types::{
             BUILTIN_STRING_MEMORY, Function, InternalMethods, InternalSlots, IntoFunction,
             IntoObject, Object, OrdinaryObject, PropertyDescriptor, PropertyKey, Value,
@@ -115,6 +115,16 @@ impl<'a> Array<'a> {
         agent[self].elements.is_trivial(agent)
     }
 
+    /// Returns true if the array is dense and a
+    /// [`SlowPathReason::SparseArrayTransition`] should be reported if holes
+    /// are created in it.
+    ///
+    /// Checking the density walks the elements, so this is only done if slow
+    /// paths are reported at all.
+    pub(crate) fn reports_sparse_transition(self, agent: &Agent) -> bool {
+        agent.options.report_slow_paths.is_some() && self.is_dense(agent)
+    }
+
     /// Returns the `value` as an Array if it is one `method` is
     /// `%Array.prototype.values%`.
     pub(crate) fn is_iterable_array(
@@ -169,6 +179,36 @@ impl<'a> Array<'a> {
         }
     }
 
+    /// Returns true if ToString(array) is known to call the intrinsic
+    /// Array.prototype.join with an undefined separator, without calling any
+    /// user code on the way.
+    pub(crate) fn uses_intrinsic_join(self, agent: &mut Agent, gc: NoGcScope<'a, '_>) -> bool {
+        let object = self.into_object();
+        // ToPrimitive looks for a @@toPrimitive method first...
+        let TryResult::Continue(Ok(None)) = try_get_object_method(
+            agent,
+            object,
+            PropertyKey::Symbol(WellKnownSymbolIndexes::ToPrimitive.into()),
+            gc,
+        ) else {
+            return false;
+        };
+        // ... then calls toString, which calls join.
+        let TryResult::Continue(Ok(Some(to_string_method))) =
+            try_get_object_method(agent, object, BUILTIN_STRING_MEMORY.toString.into(), gc)
+        else {
+            return false;
+        };
+        let TryResult::Continue(Ok(Some(join_method))) =
+            try_get_object_method(agent, object, BUILTIN_STRING_MEMORY.join.into(), gc)
+        else {
+            return false;
+        };
+        let intrinsics = agent.current_realm_record().intrinsics();
+        to_string_method == intrinsics.array_prototype_to_string().into_function()
+            && join_method == intrinsics.array_prototype_join().into_function()
+    }
+
     // This method creates a "shallow clone" of the elements of a simple array (no descriptors).
     // If array is not simple, this cloned array will do some odd things (e.g. getter/setter indexes become holes)
     pub(crate) fn to_cloned(self, agent: &mut Agent) -> Self {
@@ -437,11 +477,19 @@ impl<'a> InternalMethods<'a> for Array<'a> {
             // e. Assert: length is a non-negative integral Number.
             // f. Let index be ! ToUint32(P).
             let index = index as u32;
+            let is_accessor = property_descriptor.is_accessor_descriptor();
             if index >= length {
                 // g. If index ≥ length and lengthDesc.[[Writable]] is false, return false.
                 if !length_writable {
                     return TryResult::Continue(false);
                 }
+                // Note: Growing the Array past its maximum capacity throws a
+                // RangeError.
+                if !agent.is_valid_array_element_capacity(u64::from(index) + 1) {
+                    return TryResult::Break(());
+                }
+                let sparse_transition = index > length && self.reports_sparse_transition(agent);
+                agent.heap.write_barrier(self);
                 let Heap {
                     elements,
                     arrays,
@@ -469,17 +517,27 @@ impl<'a> InternalMethods<'a> for Array<'a> {
                 // i. Set lengthDesc.[[Value]] to index + 1𝔽.
                 // This should've already been handled by the push.
                 debug_assert_eq!(agent[self].elements.len(), index + 1);
+                if sparse_transition {
+                    agent.report_slow_path(SlowPathReason::SparseArrayTransition);
+                }
+                if is_accessor {
+                    agent.report_slow_path(SlowPathReason::AccessorElement);
+                }
                 // iii. Assert: succeeded is true.
                 TryResult::Continue(true)
             } else {
                 // h. Let succeeded be ! OrdinaryDefineOwnProperty(A, P, Desc).
-                return TryResult::Continue(ordinary_define_own_property_for_array(
+                let succeeded = ordinary_define_own_property_for_array(
                     agent,
                     elements,
                     index,
                     property_descriptor,
                     gc,
-                ));
+                );
+                if succeeded && is_accessor {
+                    agent.report_slow_path(SlowPathReason::AccessorElement);
+                }
+                TryResult::Continue(succeeded)
             }
         } else {
             let backing_object = self
@@ -507,12 +565,15 @@ impl<'a> InternalMethods<'a> for Array<'a> {
         if property_key == PropertyKey::from(BUILTIN_STRING_MEMORY.length) {
             array_set_length(agent, self, property_descriptor.unbind(), gc)
         } else {
-            Ok(unwrap_try(self.try_define_own_property(
-                agent,
-                property_key.unbind(),
-                property_descriptor.unbind(),
-                gc.into_nogc(),
-            )))
+            let (property_key, property_descriptor) =
+                (property_key.unbind(), property_descriptor.unbind());
+            let gc = gc.into_nogc();
+            match self.try_define_own_property(agent, property_key, property_descriptor, gc) {
+                TryResult::Continue(result) => Ok(result),
+                // Note: Defining elements only breaks when the Array would
+                // grow past its maximum capacity.
+                TryResult::Break(_) => agent.range_error("Invalid array length", gc),
+            }
         }
     }
 
@@ -685,7 +746,8 @@ impl<'a> InternalMethods<'a> for Array<'a> {
         gc: NoGcScope,
     ) -> TryResult<bool> {
         if property_key == PropertyKey::from(BUILTIN_STRING_MEMORY.length) {
-            TryResult::Continue(true)
+            // The length property is non-configurable.
+            TryResult::Continue(false)
         } else if let PropertyKey::Integer(index) = property_key {
             let index = index.into_i64();
             if !ARRAY_INDEX_RANGE.contains(&index) {
@@ -702,6 +764,7 @@ impl<'a> InternalMethods<'a> for Array<'a> {
             if index >= elements.len() {
                 return TryResult::Continue(true);
             }
+            let sparse_transition = self.reports_sparse_transition(agent);
             let ElementStorageMut {
                 values,
                 descriptors,
@@ -717,7 +780,12 @@ impl<'a> InternalMethods<'a> for Array<'a> {
                 }
             }
             // Index has been checked to be between 0 <= idx < len; indexing should never fail.
+            // Deleting leaves a hole in its place: the length is unchanged
+            // and the array is no longer dense.
             values[index as usize] = None;
+            if sparse_transition {
+                agent.report_slow_path(SlowPathReason::SparseArrayTransition);
+            }
             TryResult::Continue(true)
         } else {
             TryResult::Continue(
@@ -741,16 +809,24 @@ impl<'a> InternalMethods<'a> for Array<'a> {
             Default::default()
         };
         let elements = &agent[self].elements;
-        let mut keys = Vec::with_capacity(elements.len() as usize + backing_keys.len());
+        let mut keys = Vec::with_capacity(elements.len() as usize + 1 + backing_keys.len());
 
-        let elements_data = &agent[elements];
+        let storage = agent.heap.elements.get_element_storage(elements);
 
-        for (index, value) in elements_data.iter().enumerate() {
-            if value.is_some() {
+        for (index, value) in storage.values.iter().enumerate() {
+            // Accessor elements have no value, only a descriptor.
+            if value.is_some()
+                || storage
+                    .descriptors
+                    .is_some_and(|descriptors| descriptors.contains_key(&(index as u32)))
+            {
                 keys.push(PropertyKey::Integer((index as u32).into()))
             }
         }
 
+        // "length" is an own property that precedes all other string keys.
+        keys.push(BUILTIN_STRING_MEMORY.length.into());
+
         keys.extend(backing_keys);
 
         TryResult::Continue(keys)
@@ -767,6 +843,7 @@ impl Index<Array<'_>> for Agent {
 
 impl IndexMut<Array<'_>> for Agent {
     fn index_mut(&mut self, index: Array) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.arrays[index]
     }
 }
@@ -2,6 +2,8 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use std::collections::hash_map::Entry;
+
 use crate::{
     ecmascript::{
         abstract_operations::{
@@ -13,7 +15,7 @@ use crate::{
             ArgumentsList,
             array::{Array, ArrayHeapData},
         },
-        execution::{Agent, JsResult, agent::ExceptionType},
+        execution::{Agent, JsResult, SlowPathReason},
         types::{BUILTIN_STRING_MEMORY, IntoObject, Number, Object, PropertyDescriptor, Value},
     },
     engine::{
@@ -21,7 +23,7 @@ use crate::{
         context::{Bindable, GcScope, NoGcScope},
         rootable::Scopable,
     },
-    heap::{CreateHeapData, Heap, WellKnownSymbolIndexes},
+    heap::{CreateHeapData, Heap, WellKnownSymbolIndexes, element_array::ElementStorageMut},
 };
 
 /// ### [10.4.2.2 ArrayCreate ( length \[ , proto \] )](https://tc39.es/ecma262/#sec-arraycreate)
@@ -39,12 +41,9 @@ pub(crate) fn array_create<'a>(
 ) -> JsResult<'a, Array<'a>> {
     // 1. If length > 2**32 - 1, throw a RangeError exception.
     if length > (2usize.pow(32) - 1) {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::RangeError,
-            "invalid array length",
-            gc,
-        ));
+        return agent.range_error("invalid array length", gc);
     }
+    agent.check_array_element_capacity(capacity as u64, gc)?;
     // 2. If proto is not present, set proto to %Array.prototype%.
     let object_index = if let Some(proto) = proto {
         if proto
@@ -160,11 +159,7 @@ pub(crate) fn array_species_create<'a>(
     }
     // 7. If IsConstructor(C) is false, throw a TypeError exception.
     let Some(c) = is_constructor(agent, c) else {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Not a constructor",
-            gc.into_nogc(),
-        ));
+        return agent.type_error("Not a constructor", gc.into_nogc());
     };
     // 8. Return ? Construct(C, « 𝔽(length) »).
     let length = Value::from_f64(agent, length as f64, gc.nogc());
@@ -191,37 +186,15 @@ pub(crate) fn array_set_length<'a>(
     // 1. If Desc does not have a [[Value]] field, then
     let Some(desc_value) = desc.value else {
         // a. Return ! OrdinaryDefineOwnProperty(A, "length", Desc).
-        if !desc.has_fields() {
-            return Ok(true);
-        }
-        if desc.configurable == Some(true) || desc.enumerable == Some(true) {
-            return Ok(false);
-        }
-        if !desc.is_generic_descriptor() && desc.is_accessor_descriptor() {
-            return Ok(false);
-        }
-        if !agent[a].elements.len_writable {
-            // Length is already frozen.
-            if desc.writable == Some(true) {
-                return Ok(false);
-            }
-        } else if desc.writable == Some(false) {
-            agent[a].elements.len_writable = false;
-        }
-        return Ok(true);
+        return Ok(set_array_length(agent, a, None, &desc));
     };
     // 2. Let newLenDesc be a copy of Desc.
-    // 13. If newLenDesc does not have a [[Writable]] field or newLenDesc.[[Writable]] is true, then
-    // a. Let newLenDesc.[[Writable]] be true
-    let new_len_writable = desc.writable.unwrap_or(true);
-    // NOTE: Setting the [[Writable]] attribute to false is deferred in case any elements cannot be deleted.
-
-    let PropertyDescriptor {
-        enumerable: desc_enumerable,
-        configurable: desc_configurable,
-        ..
-    } = desc;
-
+    let new_len_desc = PropertyDescriptor {
+        writable: desc.writable,
+        enumerable: desc.enumerable,
+        configurable: desc.configurable,
+        ..Default::default()
+    };
     // 3. Let newLen be ? ToUint32(Desc.[[Value]]).
     let a = a.scope(agent, gc.nogc());
     let scoped_desc_value = desc_value.scope(agent, gc.nogc());
@@ -237,74 +210,14 @@ pub(crate) fn array_set_length<'a>(
     .bind(gc.nogc());
     // 5. If SameValueZero(newLen, numberLen) is false, throw a RangeError exception.
     if !Number::same_value_zero(agent, number_len, new_len.into()) {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::RangeError,
-            "invalid array length",
-            gc.into_nogc(),
-        ));
+        return agent.range_error("invalid array length", gc.into_nogc());
     }
     let gc = gc.into_nogc();
-    let a = a.get(agent).bind(gc);
-    // 6. Set newLenDesc.[[Value]] to newLen.
-    // 7. Let oldLenDesc be OrdinaryGetOwnProperty(A, "length").
-    let Heap {
-        arrays, elements, ..
-    } = &mut agent.heap;
-    let array_heap_data = &mut arrays[a];
-    // 10. Let oldLen be oldLenDesc.[[Value]].
-    let (old_len, old_len_writable) = (
-        array_heap_data.elements.len(),
-        array_heap_data.elements.len_writable,
-    );
-    // 12. If oldLenDesc.[[Writable]] is false, return false.
-    if !old_len_writable {
-        return Ok(false);
+    if new_len > a.get(agent).len(agent) {
+        agent.check_array_element_capacity(new_len.into(), gc)?;
     }
-    // Optimization: check OrdinaryDefineOwnProperty conditions for failing early on.
-    if desc_configurable == Some(true) || desc_enumerable == Some(true) {
-        // 16. If succeeded is false, return false.
-        return Ok(false);
-    }
-    // 11. If newLen ≥ oldLen, then
-    if new_len >= old_len {
-        // a. Return ! OrdinaryDefineOwnProperty(A, "length", newLenDesc).
-        array_heap_data.elements.reserve(elements, new_len);
-        array_heap_data.elements.len = new_len;
-        array_heap_data.elements.len_writable = new_len_writable;
-        return Ok(true);
-    }
-    // 15. Let succeeded be ! OrdinaryDefineOwnProperty(A, "length", newLenDesc).
-    let old_elements = array_heap_data.elements;
-    array_heap_data.elements.len = new_len;
-    // 17. For each own property key P of A such that P is an array index and ! ToUint32(P) ≥ newLen, in descending numeric index order, do
-    debug_assert!(old_len > new_len);
-    for i in new_len + 1..old_len {
-        // a. Let deleteSucceeded be ! A.[[Delete]](P).
-        let elements = &mut elements[&old_elements];
-        // TODO: Handle unwritable properties and property descriptors.
-        *elements.get_mut(i as usize).unwrap() = None;
-        let delete_succeeded = true;
-        // b. If deleteSucceeded is false, then
-        if !delete_succeeded {
-            let array_heap_data = &mut arrays[a];
-            // i. Set newLenDesc.[[Value]] to ! ToUint32(P) + 1𝔽.
-            array_heap_data.elements.len = i + 1;
-            // ii. If newWritable is false, set newLenDesc.[[Writable]] to false.
-            array_heap_data.elements.len_writable &= new_len_writable;
-            // iii. Perform ! OrdinaryDefineOwnProperty(A, "length", newLenDesc).
-            // iv. Return false.
-            return Ok(false);
-        }
-    }
-    // 18. If newWritable is false, then
-    if !new_len_writable {
-        // a. Set succeeded to ! OrdinaryDefineOwnProperty(A, "length", PropertyDescriptor { [[Writable]]: false }).
-        // b. Assert: succeeded is true.
-        let array_heap_data = &mut arrays[a];
-        array_heap_data.elements.len_writable &= new_len_writable;
-    }
-    // 19. Return true.
-    Ok(true)
+    let a = a.get(agent).bind(gc);
+    Ok(set_array_length(agent, a, Some(new_len), &new_len_desc))
 }
 
 pub(crate) fn array_try_set_length(
@@ -314,43 +227,67 @@ pub(crate) fn array_try_set_length(
 ) -> TryResult<bool> {
     // 1. If Desc does not have a [[Value]] field, then
     let Some(desc_value) = desc.value else {
+        // a. Return ! OrdinaryDefineOwnProperty(A, "length", Desc).
+        return TryResult::Continue(set_array_length(agent, a, None, &desc));
+    };
+    // 3. Let newLen be ? ToUint32(Desc.[[Value]]).
+    // 4. Let numberLen be ? ToNumber(Desc.[[Value]]).
+    let Ok(number_len) = Number::try_from(desc_value) else {
+        return TryResult::Break(());
+    };
+    let new_len = to_uint32_number(agent, number_len);
+    // 5. If SameValueZero(newLen, numberLen) is false, throw a RangeError exception.
+    if !Number::same_value_zero(agent, number_len, new_len.into()) {
+        return TryResult::Break(());
+    }
+    // Note: Growing the Array past its maximum capacity throws a RangeError.
+    if new_len > a.len(agent) && !agent.is_valid_array_element_capacity(new_len.into()) {
+        return TryResult::Break(());
+    }
+    TryResult::Continue(set_array_length(agent, a, Some(new_len), &desc))
+}
+
+/// The steps of ArraySetLength that follow the conversion of Desc.[[Value]]
+/// to `new_len`, shared by [`array_set_length`] and [`array_try_set_length`].
+/// If Desc has no [[Value]] field, `new_len` is None.
+fn set_array_length(
+    agent: &mut Agent,
+    a: Array,
+    new_len: Option<u32>,
+    desc: &PropertyDescriptor,
+) -> bool {
+    let Some(new_len) = new_len else {
+        // 1. If Desc does not have a [[Value]] field, then
         // a. Return ! OrdinaryDefineOwnProperty(A, "length", Desc).
         if !desc.has_fields() {
-            return TryResult::Continue(true);
+            return true;
         }
         if desc.configurable == Some(true) || desc.enumerable == Some(true) {
-            return TryResult::Continue(false);
+            return false;
         }
         if !desc.is_generic_descriptor() && desc.is_accessor_descriptor() {
-            return TryResult::Continue(false);
+            return false;
         }
         if !agent[a].elements.len_writable {
             // Length is already frozen.
             if desc.writable == Some(true) {
-                return TryResult::Continue(false);
+                return false;
             }
         } else if desc.writable == Some(false) {
             agent[a].elements.len_writable = false;
         }
-        return TryResult::Continue(true);
+        return true;
     };
-    // 2. Let newLenDesc be a copy of Desc.
     // 13. If newLenDesc does not have a [[Writable]] field or newLenDesc.[[Writable]] is true, then
-    // a. Let newLenDesc.[[Writable]] be true
-    let new_len_writable = desc.writable.unwrap_or(true);
-    // NOTE: Setting the [[Writable]] attribute to false is deferred in case any elements cannot be deleted.
-    // 3. Let newLen be ? ToUint32(Desc.[[Value]]).
-    // 4. Let numberLen be ? ToNumber(Desc.[[Value]]).
-    let Ok(number_len) = Number::try_from(desc_value) else {
-        return TryResult::Break(());
-    };
-    let new_len = to_uint32_number(agent, number_len);
-    // 5. If SameValueZero(newLen, numberLen) is false, throw a RangeError exception.
-    if !Number::same_value_zero(agent, number_len, new_len.into()) {
-        return TryResult::Break(());
-    }
+    // a. Let newWritable be true.
+    // 14. Else,
+    // a. NOTE: Setting the [[Writable]] attribute to false is deferred in case any elements cannot be deleted.
+    // b. Let newWritable be false.
+    let new_writable = desc.writable.unwrap_or(true);
+    let sparse_transition = new_len > a.len(agent) && a.reports_sparse_transition(agent);
     // 6. Set newLenDesc.[[Value]] to newLen.
     // 7. Let oldLenDesc be OrdinaryGetOwnProperty(A, "length").
+    agent.heap.write_barrier(a);
     let Heap {
         arrays, elements, ..
     } = &mut agent.heap;
@@ -360,53 +297,68 @@ pub(crate) fn array_try_set_length(
         array_heap_data.elements.len(),
         array_heap_data.elements.len_writable,
     );
-    // 12. If oldLenDesc.[[Writable]] is false, return false.
-    if !old_len_writable {
-        return TryResult::Continue(false);
-    }
-    // Optimization: check OrdinaryDefineOwnProperty conditions for failing early on.
-    if desc.configurable == Some(true) || desc.enumerable == Some(true) {
-        // 16. If succeeded is false, return false.
-        return TryResult::Continue(false);
-    }
     // 11. If newLen ≥ oldLen, then
     if new_len >= old_len {
         // a. Return ! OrdinaryDefineOwnProperty(A, "length", newLenDesc).
+        if desc.configurable == Some(true) || desc.enumerable == Some(true) {
+            return false;
+        }
+        if !old_len_writable {
+            return new_len == old_len && desc.writable != Some(true);
+        }
         array_heap_data.elements.reserve(elements, new_len);
         array_heap_data.elements.len = new_len;
-        array_heap_data.elements.len_writable = new_len_writable;
-        return TryResult::Continue(true);
+        array_heap_data.elements.len_writable = new_writable;
+        if sparse_transition {
+            agent.report_slow_path(SlowPathReason::SparseArrayTransition);
+        }
+        return true;
+    }
+    // 12. If oldLenDesc.[[Writable]] is false, return false.
+    if !old_len_writable {
+        return false;
     }
     // 15. Let succeeded be ! OrdinaryDefineOwnProperty(A, "length", newLenDesc).
-    let old_elements = array_heap_data.elements;
-    array_heap_data.elements.len = new_len;
-    // 17. For each own property key P of A such that P is an array index and ! ToUint32(P) ≥ newLen, in descending numeric index order, do
-    debug_assert!(old_len > new_len);
-    for i in new_len + 1..old_len {
-        // a. Let deleteSucceeded be ! A.[[Delete]](P).
-        let elements = &mut elements[&old_elements];
-        // TODO: Handle unwritable properties and property descriptors.
-        *elements.get_mut(i as usize).unwrap() = None;
-        let delete_succeeded = true;
-        // b. If deleteSucceeded is false, then
-        if !delete_succeeded {
-            let array_heap_data = &mut arrays[a];
-            // i. Set newLenDesc.[[Value]] to ! ToUint32(P) + 1𝔽.
-            array_heap_data.elements.len = i + 1;
-            // ii. If newWritable is false, set newLenDesc.[[Writable]] to false.
-            array_heap_data.elements.len_writable &= new_len_writable;
-            // iii. Perform ! OrdinaryDefineOwnProperty(A, "length", newLenDesc).
-            // iv. Return false.
-            return TryResult::Continue(false);
+    // 16. If succeeded is false, return false.
+    if desc.configurable == Some(true) || desc.enumerable == Some(true) {
+        return false;
+    }
+    // 17. For each own property key P of A such that P is an array index and
+    //     ! ToUint32(P) ≥ newLen, in descending numeric index order, do
+    // a. Let deleteSucceeded be ! A.[[Delete]](P).
+    // Note: Only elements with a non-configurable descriptor fail to delete,
+    // so the deletion stops at the highest such element.
+    let ElementStorageMut {
+        values,
+        descriptors,
+    } = elements.get_element_storage_mut(&array_heap_data.elements);
+    let undeletable = match &descriptors {
+        Entry::Occupied(descriptors) => descriptors
+            .get()
+            .iter()
+            .filter(|(index, descriptor)| **index >= new_len && !descriptor.is_configurable())
+            .map(|(index, _)| *index)
+            .max(),
+        Entry::Vacant(_) => None,
+    };
+    // b. If deleteSucceeded is false, then
+    // i. Set newLenDesc.[[Value]] to ! ToUint32(P) + 1𝔽.
+    let final_len = undeletable.map_or(new_len, |index| index + 1);
+    values[final_len as usize..old_len as usize].fill(None);
+    if let Entry::Occupied(mut descriptors) = descriptors {
+        descriptors.get_mut().retain(|index, _| *index < final_len);
+        if descriptors.get().is_empty() {
+            descriptors.remove();
         }
     }
+    array_heap_data.elements.len = final_len;
+    // ii. If newWritable is false, set newLenDesc.[[Writable]] to false.
+    // iii. Perform ! OrdinaryDefineOwnProperty(A, "length", newLenDesc).
     // 18. If newWritable is false, then
-    if !new_len_writable {
-        // a. Set succeeded to ! OrdinaryDefineOwnProperty(A, "length", PropertyDescriptor { [[Writable]]: false }).
-        // b. Assert: succeeded is true.
-        let array_heap_data = &mut arrays[a];
-        array_heap_data.elements.len_writable &= new_len_writable;
-    }
+    // a. Set succeeded to ! OrdinaryDefineOwnProperty(A, "length", PropertyDescriptor { [[Writable]]: false }).
+    // b. Assert: succeeded is true.
+    array_heap_data.elements.len_writable = new_writable;
+    // iv. Return false.
     // 19. Return true.
-    TryResult::Continue(true)
+    undeletable.is_none()
 }
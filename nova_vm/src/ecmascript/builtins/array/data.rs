@@ -5,7 +5,11 @@
 use crate::{
     ecmascript::types::OrdinaryObject,
     engine::context::{Bindable, NoGcScope},
-    heap::{CompactionLists, HeapMarkAndSweep, WorkQueues, element_array::ElementsVector},
+    heap::{
+        CompactionLists, HeapMarkAndSweep, WorkQueues,
+        element_array::ElementsVector,
+        snapshot::{HeapSnapshot, SnapshotError, SnapshotReader, SnapshotWriter},
+    },
 };
 
 /// An Array is an exotic object that gives special treatment to array index
@@ -56,3 +60,21 @@ impl HeapMarkAndSweep for ArrayHeapData<'static> {
         elements.sweep_values(compactions);
     }
 }
+
+impl HeapSnapshot for ArrayHeapData<'static> {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        let Self {
+            object_index,
+            elements,
+        } = self;
+        object_index.write_snapshot(writer);
+        elements.write_snapshot(writer);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        Ok(Self {
+            object_index: HeapSnapshot::read_snapshot(reader)?,
+            elements: HeapSnapshot::read_snapshot(reader)?,
+        })
+    }
+}
@@ -215,6 +215,7 @@ impl Index<ArrayBuffer<'_>> for Agent {
 
 impl IndexMut<ArrayBuffer<'_>> for Agent {
     fn index_mut(&mut self, index: ArrayBuffer) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.array_buffers[index]
     }
 }
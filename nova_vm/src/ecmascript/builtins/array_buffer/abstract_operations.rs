@@ -11,7 +11,7 @@ use crate::{
     Heap,
     ecmascript::{
         abstract_operations::operations_on_objects::get,
-        execution::{Agent, JsResult, agent::ExceptionType},
+        execution::{Agent, JsResult},
         types::{BUILTIN_STRING_MEMORY, DataBlock, Function, IntoFunction, Number, Object, Value},
     },
 };
@@ -51,11 +51,7 @@ pub(crate) fn allocate_array_buffer<'a>(
     if allocating_resizable_buffer {
         // a. If byteLength > maxByteLength, throw a RangeError exception.
         if byte_length > max_byte_length.unwrap() {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::RangeError,
-                "Byte length is over maximumm byte length",
-                gc,
-            ));
+            return agent.range_error("Byte length is over maximumm byte length", gc);
         }
         // b. Append [[ArrayBufferMaxByteLength]] to slots.
     }
@@ -66,11 +62,7 @@ pub(crate) fn allocate_array_buffer<'a>(
     //      b. NOTE: Resizable ArrayBuffers are designed to be implementable with in-place growth. Implementations may throw if, for example, virtual memory cannot be reserved up front.
     //      c. Set obj.[[ArrayBufferMaxByteLength]] to maxByteLength.
     if byte_length > u32::MAX as u64 {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::RangeError,
-            "Byte length is too large",
-            gc,
-        ));
+        return agent.range_error("Byte length is too large", gc);
     }
     let block = DataBlock::create_byte_data_block(agent, byte_length, gc)?;
     // 6. Set obj.[[ArrayBufferData]] to block.
@@ -134,11 +126,7 @@ pub(crate) fn detach_array_buffer<'a>(
     // 2. If key is not present, set key to undefined.
     // 3. If arrayBuffer.[[ArrayBufferDetachKey]] is not key, throw a TypeError exception.
     if array_buffer.get_detach_key(agent) != key {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Mismatching array buffer detach keys",
-            gc,
-        ));
+        return agent.type_error("Mismatching array buffer detach keys", gc);
     }
 
     // 4. Set arrayBuffer.[[ArrayBufferData]] to null.
@@ -114,6 +114,26 @@ pub(crate) fn bound_function_create<'a>(
         target_function = scoped_target_function.get(agent).bind(gc.nogc());
         proto
     };
+    Ok(bound_function_create_with_prototype(
+        agent,
+        target_function.unbind(),
+        proto.unbind(),
+        bound_this,
+        bound_args,
+        gc.into_nogc(),
+    ))
+}
+
+/// BoundFunctionCreate from step 2 onwards, with proto as the result of
+/// targetFunction.\[\[GetPrototypeOf\]\]().
+pub(crate) fn bound_function_create_with_prototype<'a>(
+    agent: &mut Agent,
+    target_function: Function,
+    proto: Option<Object>,
+    bound_this: Value,
+    bound_args: &[Value],
+    gc: NoGcScope<'a, '_>,
+) -> BoundFunction<'a> {
     // 2. Let internalSlotsList be the list-concatenation of « [[Prototype]],
     //     [[Extensible]] » and the internal slots listed in Table 31.
     // 3. Let obj be MakeBasicObject(internalSlotsList).
@@ -141,10 +161,10 @@ pub(crate) fn bound_function_create<'a>(
     // 7. Set obj.[[BoundTargetFunction]] to targetFunction.
     // 8. Set obj.[[BoundThis]] to boundThis.
     // 9. Set obj.[[BoundArguments]] to boundArgs.
-    let obj = agent.heap.create(data);
-    unwrap_try(obj.try_set_prototype_of(agent, proto, gc.nogc()));
+    let obj = agent.heap.create(data).bind(gc);
+    unwrap_try(obj.try_set_prototype_of(agent, proto, gc));
     // 10. Return obj.
-    Ok(obj)
+    obj
 }
 
 impl<'a> FunctionInternalProperties<'a> for BoundFunction<'a> {
@@ -396,6 +416,7 @@ impl<'a> Index<BoundFunction<'a>> for Agent {
 
 impl<'a> IndexMut<BoundFunction<'a>> for Agent {
     fn index_mut(&mut self, index: BoundFunction<'a>) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.bound_functions[index]
     }
 }
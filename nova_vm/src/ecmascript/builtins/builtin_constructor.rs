@@ -15,7 +15,6 @@ use crate::{
     ecmascript::{
         execution::{
             Agent, Environment, ExecutionContext, JsResult, PrivateEnvironment, ProtoIntrinsics,
-            agent::ExceptionType,
         },
         scripts_and_modules::source_code::SourceCode,
         syntax_directed_operations::class_definitions::{
@@ -147,6 +146,7 @@ impl Index<BuiltinConstructorFunction<'_>> for Agent {
 
 impl IndexMut<BuiltinConstructorFunction<'_>> for Agent {
     fn index_mut(&mut self, index: BuiltinConstructorFunction) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.builtin_constructors[index]
     }
 }
@@ -322,11 +322,10 @@ impl<'a> InternalMethods<'a> for BuiltinConstructorFunction<'a> {
     ) -> JsResult<'gc, Value<'gc>> {
         // 1. Return ? BuiltinCallOrConstruct(F, thisArgument, argumentsList, undefined).
         // ii. If NewTarget is undefined, throw a TypeError exception.
-        Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
+        agent.type_error(
             "class constructors must be invoked with 'new'",
             gc.into_nogc(),
-        ))
+        )
     }
 
     /// ### [10.3.2 \[\[Construct\]\] ( argumentsList, newTarget )](https://tc39.es/ecma262/#sec-built-in-function-objects-construct-argumentslist-newtarget)
@@ -500,6 +499,7 @@ pub(crate) fn create_builtin_constructor<'a>(
             // 8. Set func.[[Realm]] to realm.
             realm,
             compiled_initializer_bytecode: args.compiled_initializer_bytecode,
+            home_object: args.prototype_property,
             is_derived: args.is_derived,
             object_index: Some(backing_object),
             environment: args.env,
@@ -583,12 +583,14 @@ impl HeapMarkAndSweep for BuiltinConstructorHeapData<'static> {
             realm,
             is_derived: _,
             compiled_initializer_bytecode,
+            home_object,
             environment,
             private_environment,
             source_text: _,
             source_code,
         } = self;
         realm.mark_values(queues);
+        home_object.mark_values(queues);
         object_index.mark_values(queues);
         environment.mark_values(queues);
         private_environment.mark_values(queues);
@@ -602,12 +604,14 @@ impl HeapMarkAndSweep for BuiltinConstructorHeapData<'static> {
             realm,
             is_derived: _,
             compiled_initializer_bytecode,
+            home_object,
             environment,
             private_environment,
             source_text: _,
             source_code,
         } = self;
         realm.sweep_values(compactions);
+        home_object.sweep_values(compactions);
         object_index.sweep_values(compactions);
         environment.sweep_values(compactions);
         private_environment.sweep_values(compactions);
@@ -10,9 +10,8 @@ use std::{hint::unreachable_unchecked, ptr::NonNull};
 
 use crate::{
     ecmascript::{
-        execution::{
-            Agent, ExecutionContext, JsResult, ProtoIntrinsics, Realm, agent::ExceptionType,
-        },
+        builtins::bound_function::{BoundFunction, bound_function_create_with_prototype},
+        execution::{Agent, ExecutionContext, JsResult, ProtoIntrinsics, Realm},
         types::{
             BUILTIN_STRING_MEMORY, BuiltinFunctionHeapData, Function, FunctionInternalProperties,
             InternalMethods, InternalSlots, IntoFunction, IntoObject, IntoValue, Object,
@@ -24,17 +23,26 @@ use crate::{
         },
     },
     engine::{
-        Scoped, TryResult,
+        ScopableCollection, Scoped, ScopedCollection, TryResult,
         context::{Bindable, GcScope, NoGcScope},
         rootable::{HeapRootCollectionData, HeapRootData, HeapRootRef, Rootable},
     },
     heap::{
         CompactionLists, CreateHeapData, Heap, HeapMarkAndSweep, HeapSweepWeakReference,
         IntrinsicConstructorIndexes, IntrinsicFunctionIndexes, ObjectEntry,
-        ObjectEntryPropertyDescriptor, WorkQueues, indexes::BuiltinFunctionIndex,
+        ObjectEntryPropertyDescriptor, WorkQueues,
+        indexes::BuiltinFunctionIndex,
+        snapshot::{HeapSnapshot, SnapshotError, SnapshotReader, SnapshotWriter},
     },
 };
 
+/// Arguments of a function call.
+///
+/// The arguments list borrows the caller's argument area, which usually lies
+/// in the calling VM's value stack. It is only valid for the duration of the
+/// call and its Values are not rooted: a builtin function that needs its
+/// arguments after a garbage collection point must scope them, for instance
+/// with [`ArgumentsList::scope`].
 #[derive(Default)]
 #[repr(transparent)]
 pub struct ArgumentsList<'slice, 'value> {
@@ -197,6 +205,16 @@ impl<'slice, 'value> ArgumentsList<'slice, 'value> {
         self.slice
     }
 
+    /// Copy the Values in an ArgumentsList into a scoped collection, keeping
+    /// them alive across garbage collection for the rest of the scope.
+    pub fn scope<'scope>(
+        &self,
+        agent: &Agent,
+        gc: NoGcScope<'_, 'scope>,
+    ) -> ScopedCollection<'scope, Vec<Value<'static>>> {
+        self.slice.to_vec().scope(agent, gc)
+    }
+
     /// Access the Values in an ArgumentsList as a mut slice.
     pub(crate) fn as_mut_slice(&mut self) -> &mut [Value<'static>] {
         self.slice
@@ -501,6 +519,7 @@ impl Index<BuiltinFunction<'_>> for Agent {
 
 impl IndexMut<BuiltinFunction<'_>> for Agent {
     fn index_mut(&mut self, index: BuiltinFunction) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.builtin_functions[index]
     }
 }
@@ -748,11 +767,7 @@ pub(crate) fn builtin_call_or_construct<'gc>(
     let result = match func {
         Behaviour::Regular(func) => {
             if new_target.is_some() {
-                Err(agent.throw_exception_with_static_message(
-                    ExceptionType::TypeError,
-                    "Not a constructor",
-                    gc.into_nogc(),
-                ))
+                agent.type_error("Not a constructor", gc.into_nogc())
             } else {
                 func(
                     agent,
@@ -889,6 +904,42 @@ pub fn create_builtin_function<'a>(
         .bind(gc)
 }
 
+/// Creates a function object that behaves like an anonymous built-in function
+/// created by CreateBuiltinFunction(closure, length, "", « ») from an Abstract
+/// Closure that captures `captures`.
+///
+/// NOTE: The function is a bound function of `behaviour` with `captures` as
+/// its bound arguments, so `behaviour` receives the captured values before
+/// the arguments of the call.
+pub(crate) fn create_closure<'a>(
+    agent: &mut Agent,
+    behaviour: RegularFn,
+    length: u8,
+    captures: &[Value],
+    gc: NoGcScope<'a, '_>,
+) -> BoundFunction<'a> {
+    let target = create_builtin_function(
+        agent,
+        Behaviour::Regular(behaviour),
+        BuiltinFunctionArgs::new(length.into(), ""),
+        gc,
+    );
+    let proto = agent
+        .current_realm_record()
+        .intrinsics()
+        .function_prototype();
+    let closure = bound_function_create_with_prototype(
+        agent,
+        target.into_function(),
+        Some(proto.into_object()),
+        Value::Undefined,
+        captures,
+        gc,
+    );
+    agent[closure].length = length;
+    closure
+}
+
 impl<'a> CreateHeapData<BuiltinFunctionHeapData<'a>, BuiltinFunction<'a>> for Heap {
     fn create(&mut self, data: BuiltinFunctionHeapData<'a>) -> BuiltinFunction<'a> {
         self.builtin_functions.push(Some(data.unbind()));
@@ -981,3 +1032,61 @@ impl HeapMarkAndSweep for BuiltinFunctionHeapData<'static> {
         object_index.sweep_values(compactions);
     }
 }
+
+impl HeapSnapshot for Behaviour {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        match *self {
+            Behaviour::Regular(behaviour) => {
+                writer.write_u8(0);
+                writer.write_function_pointer(behaviour as usize);
+            }
+            Behaviour::Constructor(behaviour) => {
+                writer.write_u8(1);
+                writer.write_function_pointer(behaviour as usize);
+            }
+        }
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        let kind = reader.read_u8()?;
+        let address = reader.read_function_pointer()?;
+        // SAFETY: Startup snapshots are only restored in the build that
+        // created them, so the address is that of a function of this type.
+        match kind {
+            0 => Ok(Behaviour::Regular(unsafe {
+                core::mem::transmute::<usize, RegularFn>(address)
+            })),
+            1 => Ok(Behaviour::Constructor(unsafe {
+                core::mem::transmute::<usize, ConstructorFn>(address)
+            })),
+            _ => Err(SnapshotError::Corrupted),
+        }
+    }
+}
+
+impl HeapSnapshot for BuiltinFunctionHeapData<'static> {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        let Self {
+            object_index,
+            length,
+            realm,
+            initial_name,
+            behaviour,
+        } = self;
+        object_index.write_snapshot(writer);
+        length.write_snapshot(writer);
+        realm.write_snapshot(writer);
+        initial_name.write_snapshot(writer);
+        behaviour.write_snapshot(writer);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        Ok(Self {
+            object_index: HeapSnapshot::read_snapshot(reader)?,
+            length: HeapSnapshot::read_snapshot(reader)?,
+            realm: HeapSnapshot::read_snapshot(reader)?,
+            initial_name: HeapSnapshot::read_snapshot(reader)?,
+            behaviour: HeapSnapshot::read_snapshot(reader)?,
+        })
+    }
+}
@@ -141,6 +141,21 @@ impl AwaitReactionIdentifier<'_> {
     }
 }
 
+// SAFETY: Property implemented as a lifetime transmute.
+unsafe impl Bindable for AwaitReactionIdentifier<'_> {
+    type Of<'a> = AwaitReactionIdentifier<'a>;
+
+    #[inline(always)]
+    fn unbind(self) -> Self::Of<'static> {
+        unsafe { core::mem::transmute::<Self, Self::Of<'static>>(self) }
+    }
+
+    #[inline(always)]
+    fn bind<'a>(self, _gc: NoGcScope<'a, '_>) -> Self::Of<'a> {
+        unsafe { core::mem::transmute::<Self, Self::Of<'a>>(self) }
+    }
+}
+
 impl Index<AwaitReactionIdentifier<'_>> for Agent {
     type Output = AwaitReaction<'static>;
 
@@ -151,6 +166,7 @@ impl Index<AwaitReactionIdentifier<'_>> for Agent {
 
 impl IndexMut<AwaitReactionIdentifier<'_>> for Agent {
     fn index_mut(&mut self, index: AwaitReactionIdentifier) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.await_reactions[index]
     }
 }
@@ -407,6 +407,7 @@ impl Index<AsyncGenerator<'_>> for Agent {
 
 impl IndexMut<AsyncGenerator<'_>> for Agent {
     fn index_mut(&mut self, index: AsyncGenerator) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.async_generators[index]
     }
 }
@@ -16,10 +16,7 @@ use crate::{
                 promise_prototype::inner_promise_then,
             },
         },
-        execution::{
-            Agent, JsResult, Realm,
-            agent::{ExceptionType, JsError},
-        },
+        execution::{Agent, JsResult, Realm, agent::JsError},
         types::{IntoValue, Value},
     },
     engine::{
@@ -88,11 +85,7 @@ pub(super) fn async_generator_validate<'a>(
     if let Value::AsyncGenerator(generator) = generator {
         Ok(generator.unbind())
     } else {
-        Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Not an async generator object",
-            gc,
-        ))
+        agent.type_error("Not an async generator object", gc)
     }
 }
 
@@ -8,15 +8,12 @@ use crate::ecmascript::builtins::promise_objects::promise_abstract_operations::p
 use crate::ecmascript::execution::agent::JsError;
 use crate::engine::context::{Bindable, GcScope};
 use crate::engine::rootable::Scopable;
-use crate::{
-    ecmascript::{
+use crate::ecmascript::{
         builders::ordinary_object_builder::OrdinaryObjectBuilder,
         builtins::{ArgumentsList, Behaviour, Builtin},
         execution::{Agent, JsResult, Realm},
         types::{IntoValue, String, Value, BUILTIN_STRING_MEMORY},
-    },
-    heap::WellKnownSymbolIndexes,
-};
+    };
 
 use super::AsyncGeneratorRequestCompletion;
 use super::async_generator_abstract_operations::{
@@ -252,14 +249,7 @@ impl AsyncGeneratorPrototype {
             .with_builtin_function_property::<AsyncGeneratorPrototypeNext>()
             .with_builtin_function_property::<AsyncGeneratorPrototypeReturn>()
             .with_builtin_function_property::<AsyncGeneratorPrototypeThrow>()
-            .with_property(|builder| {
-                builder
-                    .with_key(WellKnownSymbolIndexes::ToStringTag.into())
-                    .with_value_readonly(BUILTIN_STRING_MEMORY.AsyncGenerator.into_value())
-                    .with_enumerable(false)
-                    .with_configurable(true)
-                    .build()
-            })
+            .with_to_string_tag(BUILTIN_STRING_MEMORY.AsyncGenerator)
             .build();
     }
 }
@@ -2,13 +2,10 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use crate::{
-    ecmascript::{
-        builders::ordinary_object_builder::OrdinaryObjectBuilder,
-        execution::{Agent, Realm},
-        types::{BUILTIN_STRING_MEMORY, IntoValue},
-    },
-    heap::WellKnownSymbolIndexes,
+use crate::ecmascript::{
+    builders::ordinary_object_builder::OrdinaryObjectBuilder,
+    execution::{Agent, Realm},
+    types::{BUILTIN_STRING_MEMORY, IntoValue},
 };
 
 pub(crate) struct GeneratorFunctionPrototype;
@@ -33,14 +30,7 @@ impl GeneratorFunctionPrototype {
                     .with_configurable(true)
                     .build()
             })
-            .with_property(|builder| {
-                builder
-                    .with_key(WellKnownSymbolIndexes::ToStringTag.into())
-                    .with_value_readonly(BUILTIN_STRING_MEMORY.GeneratorFunction.into_value())
-                    .with_enumerable(false)
-                    .with_configurable(true)
-                    .build()
-            })
+            .with_to_string_tag(BUILTIN_STRING_MEMORY.GeneratorFunction)
             .build();
     }
 }
@@ -11,10 +11,7 @@ use crate::heap::HeapSweepWeakReference;
 use crate::{
     ecmascript::{
         abstract_operations::operations_on_iterator_objects::create_iter_result_object,
-        execution::{
-            Agent, ExecutionContext, JsResult, ProtoIntrinsics,
-            agent::{ExceptionType, JsError},
-        },
+        execution::{Agent, ExecutionContext, JsResult, ProtoIntrinsics, agent::JsError},
         types::{InternalMethods, InternalSlots, Object, OrdinaryObject, Value},
     },
     engine::{
@@ -62,11 +59,7 @@ impl Generator<'_> {
                 // 3. Assert: state is either suspended-start or suspended-yield.
             }
             GeneratorState::Executing => {
-                return Err(agent.throw_exception_with_static_message(
-                    ExceptionType::TypeError,
-                    "The generator is currently running",
-                    gc.into_nogc(),
-                ));
+                return agent.type_error("The generator is currently running", gc.into_nogc());
             }
             GeneratorState::Completed => {
                 // 2. If state is completed, return CreateIterResultObject(undefined, true).
@@ -206,11 +199,7 @@ impl Generator<'_> {
                 // 4. Assert: state is suspended-yield.
             }
             GeneratorState::Executing => {
-                return Err(agent.throw_exception_with_static_message(
-                    ExceptionType::TypeError,
-                    "The generator is currently running",
-                    gc.into_nogc(),
-                ));
+                return agent.type_error("The generator is currently running", gc.into_nogc());
             }
             GeneratorState::Completed => {
                 // 3. If state is completed, then
@@ -323,11 +312,7 @@ impl Generator<'_> {
                 // 4. Assert: state is suspended-yield.
             }
             GeneratorState::Executing => {
-                return Err(agent.throw_exception_with_static_message(
-                    ExceptionType::TypeError,
-                    "The generator is currently running",
-                    gc.into_nogc(),
-                ));
+                return agent.type_error("The generator is currently running", gc.into_nogc());
             }
             GeneratorState::Completed => {
                 // 3. If abruptCompletion is a return completion, then
@@ -488,6 +473,7 @@ impl Index<Generator<'_>> for Agent {
 
 impl IndexMut<Generator<'_>> for Agent {
     fn index_mut(&mut self, index: Generator) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.generators[index]
     }
 }
@@ -7,10 +7,10 @@ use crate::{
     ecmascript::{
         builders::ordinary_object_builder::OrdinaryObjectBuilder,
         builtins::{ArgumentsList, Behaviour, Builtin, BuiltinIntrinsic},
-        execution::{Agent, JsResult, Realm, agent::ExceptionType},
+        execution::{Agent, JsResult, Realm},
         types::{BUILTIN_STRING_MEMORY, IntoValue, String, Value},
     },
-    heap::{IntrinsicFunctionIndexes, WellKnownSymbolIndexes},
+    heap::IntrinsicFunctionIndexes,
 };
 
 pub(crate) struct GeneratorPrototype;
@@ -53,11 +53,7 @@ impl GeneratorPrototype {
     ) -> JsResult<'gc, Value<'gc>> {
         // GeneratorResume: 1. Let state be ? GeneratorValidate(generator, generatorBrand).
         let Value::Generator(generator) = this_value else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Generator expected",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Generator expected", gc.into_nogc());
         };
 
         // 1. Return ? GeneratorResume(this value, value, empty).
@@ -82,11 +78,7 @@ impl GeneratorPrototype {
         // ### 27.5.3.4 GeneratorResumeAbrupt
         // 1. Let state be ? GeneratorValidate(generator, generatorBrand).
         let Value::Generator(g) = g else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Generator expected",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Generator expected", gc.into_nogc());
         };
 
         g.unbind()
@@ -102,11 +94,7 @@ impl GeneratorPrototype {
     ) -> JsResult<'gc, Value<'gc>> {
         // GeneratorResumeAbrupt: 1. Let state be ? GeneratorValidate(generator, generatorBrand).
         let Value::Generator(generator) = this_value else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Generator expected",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Generator expected", gc.into_nogc());
         };
 
         // 1. Let g be the this value.
@@ -137,14 +125,7 @@ impl GeneratorPrototype {
             .with_builtin_intrinsic_function_property::<GeneratorPrototypeNext>()
             .with_builtin_function_property::<GeneratorPrototypeReturn>()
             .with_builtin_function_property::<GeneratorPrototypeThrow>()
-            .with_property(|builder| {
-                builder
-                    .with_key(WellKnownSymbolIndexes::ToStringTag.into())
-                    .with_value_readonly(BUILTIN_STRING_MEMORY.Generator.into_value())
-                    .with_enumerable(false)
-                    .with_configurable(true)
-                    .build()
-            })
+            .with_to_string_tag(BUILTIN_STRING_MEMORY.Generator)
             .build();
     }
 }
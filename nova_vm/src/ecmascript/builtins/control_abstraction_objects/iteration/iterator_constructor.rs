@@ -9,7 +9,7 @@ use crate::{
             ArgumentsList, Behaviour, Builtin, BuiltinIntrinsicConstructor,
             ordinary::ordinary_create_from_constructor,
         },
-        execution::{Agent, JsResult, ProtoIntrinsics, Realm, agent::ExceptionType},
+        execution::{Agent, JsResult, ProtoIntrinsics, Realm},
         types::{BUILTIN_STRING_MEMORY, Function, IntoObject, Object, String, Value},
     },
     engine::context::{Bindable, GcScope},
@@ -36,11 +36,7 @@ impl IteratorConstructor {
     ) -> JsResult<'gc, Value<'gc>> {
         // 1. If NewTarget is either undefined or the active function object, throw a TypeError exception.
         let Some(new_target) = new_target else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Iterator Constructor requires 'new'",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Iterator Constructor requires 'new'", gc.into_nogc());
         };
         if new_target
             == agent
@@ -49,11 +45,10 @@ impl IteratorConstructor {
                 .unwrap()
                 .into_object()
         {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
+            return agent.type_error(
                 "Iterator constructor can't be used directly",
                 gc.into_nogc(),
-            ));
+            );
         }
 
         // 2. Return ? OrdinaryCreateFromConstructor(NewTarget, "%Iterator.prototype%").
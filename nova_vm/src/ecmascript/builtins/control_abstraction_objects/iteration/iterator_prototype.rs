@@ -108,11 +108,7 @@ impl IteratorPrototype {
         // 1. Let O be the this value.
         // 2. If O is not an Object, throw a TypeError exception.
         let Ok(o) = Object::try_from(this_value) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "'this' is not an object",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("'this' is not an object", gc.into_nogc());
         };
 
         // 3. Let iterated be the Iterator Record { [[Iterator]]: O, [[NextMethod]]: undefined, [[Done]]: false }.
@@ -218,11 +214,7 @@ impl IteratorPrototype {
         // 1. Let O be the this value.
         // 2. If O is not an Object, throw a TypeError exception.
         let Ok(o) = Object::try_from(this_value) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "'this' is not an object",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("'this' is not an object", gc.into_nogc());
         };
 
         // 3. Let iterated be the Iterator Record { [[Iterator]]: O, [[NextMethod]]: undefined, [[Done]]: false }.
@@ -332,11 +324,7 @@ impl IteratorPrototype {
         // 1. Let O be the this value.
         // 2. If O is not an Object, throw a TypeError exception.
         let Ok(o) = Object::try_from(this_value) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "'this' is not an object",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("'this' is not an object", gc.into_nogc());
         };
 
         // 3. Let iterated be the Iterator Record { [[Iterator]]: O, [[NextMethod]]: undefined, [[Done]]: false }.
@@ -434,11 +422,7 @@ impl IteratorPrototype {
         // 1. Let O be the this value.
         // 2. If O is not an Object, throw a TypeError exception.
         let Ok(o) = Object::try_from(this_value) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "'this' is not an object",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("'this' is not an object", gc.into_nogc());
         };
 
         // 3. Let iterated be the Iterator Record { [[Iterator]]: O, [[NextMethod]]: undefined, [[Done]]: false }.
@@ -488,11 +472,7 @@ impl IteratorPrototype {
 
             // b. If accumulator is done, throw a TypeError exception.
             let Some(accumulator) = accumulator else {
-                return Err(agent.throw_exception_with_static_message(
-                    ExceptionType::TypeError,
-                    "'this' was done",
-                    gc.into_nogc(),
-                ));
+                return agent.type_error("'this' was done", gc.into_nogc());
             };
 
             // c. Let counter be 1.
@@ -565,11 +545,7 @@ impl IteratorPrototype {
         // 1. Let O be the this value.
         // 2. If O is not an Object, throw a TypeError exception.
         let Ok(o) = Object::try_from(this_value) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "'this' is not an object",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("'this' is not an object", gc.into_nogc());
         };
 
         // 3. Let iterated be the Iterator Record { [[Iterator]]: O, [[NextMethod]]: undefined, [[Done]]: false }.
@@ -674,11 +650,7 @@ impl IteratorPrototype {
         // 1. Let O be the this value.
         // 2. If O is not an Object, throw a TypeError exception.
         let Ok(o) = Object::try_from(this_value) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "'this' is not an object",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("'this' is not an object", gc.into_nogc());
         };
 
         // 3. Let iterated be ? GetIteratorDirect(O).
@@ -4,7 +4,11 @@
 
 //! ## [27.2.1.1 PromiseCapability Records]()
 
-use crate::ecmascript::abstract_operations::operations_on_objects::try_get;
+use crate::ecmascript::abstract_operations::operations_on_objects::{construct, try_get};
+use crate::ecmascript::abstract_operations::testing_and_comparison::is_constructor;
+use crate::ecmascript::builtins::{
+    ArgumentsList, Behaviour, BuiltinFunctionArgs, create_builtin_function,
+};
 use crate::engine::TryResult;
 use crate::engine::context::{Bindable, GcScope, NoGcScope};
 use crate::engine::rootable::Scopable;
@@ -32,9 +36,9 @@ use super::promise_jobs::new_promise_resolve_thenable_job;
 /// NOTE: In the spec, promise capability records contain an object that is
 /// usable as a promise, together with its resolve and reject functions. In our
 /// current implementation, we only ever support built-in promises, and not
-/// other promise-like objects (e.g. we don't support Promise subclasses), and
-/// for that we don't need to store resolve and reject functions, we can create
-/// them only when needed.
+/// other promise-like objects (Promise subclasses are supported as long as
+/// they construct built-in promises), and for that we don't need to store
+/// resolve and reject functions, we can create them only when needed.
 ///
 /// The `must_be_unresolved` boolean is used to map the `AlreadyResolved` state
 /// of a pair of resolve/reject functions with the promise state. If
@@ -49,12 +53,74 @@ pub struct PromiseCapability<'a> {
 
 impl<'a> PromiseCapability<'a> {
     /// [27.2.1.5 NewPromiseCapability ( C )](https://tc39.es/ecma262/#sec-newpromisecapability)
-    /// NOTE: Our implementation doesn't take C as a parameter, since we don't
-    /// yet support promise subclassing.
+    /// NOTE: This is NewPromiseCapability(%Promise%). See
+    /// [`PromiseCapability::new_from_constructor`] for other constructors.
     pub fn new(agent: &mut Agent, gc: NoGcScope<'a, '_>) -> Self {
         Self::from_promise(agent.heap.create(PromiseHeapData::default()), true).bind(gc)
     }
 
+    /// [27.2.1.5 NewPromiseCapability ( C )](https://tc39.es/ecma262/#sec-newpromisecapability)
+    ///
+    /// NOTE: We only support constructors which produce built-in promises,
+    /// such as subclasses of %Promise%. The executor passed to C ignores the
+    /// resolving functions it receives, and the constructed promise is
+    /// resolved and rejected directly instead.
+    pub(crate) fn new_from_constructor(
+        agent: &mut Agent,
+        c: Value,
+        mut gc: GcScope<'a, '_>,
+    ) -> JsResult<'a, Self> {
+        let c = c.bind(gc.nogc());
+        if c == agent
+            .current_realm_record()
+            .intrinsics()
+            .promise()
+            .into_value()
+        {
+            return Ok(Self::new(agent, gc.into_nogc()));
+        }
+        // 1. If IsConstructor(C) is false, throw a TypeError exception.
+        let Some(c) = is_constructor(agent, c) else {
+            return agent.type_error(
+                "Promise capability target is not a constructor",
+                gc.into_nogc(),
+            );
+        };
+        // 2. NOTE: C is assumed to be a constructor function that supports the parameter conventions of the Promise constructor (see 27.2.3.1).
+        // 3. Let resolvingFunctions be the Record { [[Resolve]]: undefined, [[Reject]]: undefined }.
+        // 4. Let executorClosure be a new Abstract Closure with parameters (resolve, reject) that captures resolvingFunctions and performs the following steps when called:
+        // 5. Let executor be CreateBuiltinFunction(executorClosure, 2, "", « »).
+        let executor = create_builtin_function(
+            agent,
+            Behaviour::Regular(get_capabilities_executor),
+            BuiltinFunctionArgs::new(2, ""),
+            gc.nogc(),
+        );
+        // 6. Let promise be ? Construct(C, « executor »).
+        let promise = construct(
+            agent,
+            c.unbind(),
+            Some(ArgumentsList::from_mut_slice(&mut [executor
+                .into_value()
+                .unbind()])),
+            None,
+            gc.reborrow(),
+        )
+        .unbind()?;
+        let gc = gc.into_nogc();
+        let promise = promise.bind(gc);
+        // 7. If IsCallable(resolvingFunctions.[[Resolve]]) is false, throw a TypeError exception.
+        // 8. If IsCallable(resolvingFunctions.[[Reject]]) is false, throw a TypeError exception.
+        let Object::Promise(promise) = promise else {
+            return agent.type_error(
+                "Promise capability constructor did not return a promise",
+                gc,
+            );
+        };
+        // 9. Return the PromiseCapability Record { [[Promise]]: promise, [[Resolve]]: resolvingFunctions.[[Resolve]], [[Reject]]: resolvingFunctions.[[Reject]] }.
+        Ok(Self::from_promise(promise, true))
+    }
+
     pub fn from_promise(promise: Promise<'a>, must_be_unresolved: bool) -> Self {
         Self {
             promise,
@@ -346,6 +412,17 @@ unsafe impl Bindable for PromiseCapability<'_> {
     }
 }
 
+/// Executor passed to promise subclass constructors by
+/// [`PromiseCapability::new_from_constructor`].
+fn get_capabilities_executor<'gc>(
+    _: &mut Agent,
+    _: Value,
+    _: ArgumentsList,
+    _: GcScope<'gc, '_>,
+) -> JsResult<'gc, Value<'gc>> {
+    Ok(Value::Undefined)
+}
+
 impl HeapMarkAndSweep for PromiseCapability<'static> {
     fn mark_values(&self, queues: &mut WorkQueues) {
         let Self {
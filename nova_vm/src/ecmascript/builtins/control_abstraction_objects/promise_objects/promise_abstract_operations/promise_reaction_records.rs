@@ -116,6 +116,7 @@ impl Index<PromiseReaction<'_>> for Agent {
 
 impl IndexMut<PromiseReaction<'_>> for Agent {
     fn index_mut(&mut self, index: PromiseReaction) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.promise_reaction_records[index]
     }
 }
@@ -264,6 +264,7 @@ impl Index<BuiltinPromiseResolvingFunction<'_>> for Agent {
 
 impl IndexMut<BuiltinPromiseResolvingFunction<'_>> for Agent {
     fn index_mut(&mut self, index: BuiltinPromiseResolvingFunction) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.promise_resolving_functions[index]
     }
 }
@@ -7,19 +7,15 @@ use crate::engine::rootable::Scopable;
 use crate::{
     ecmascript::{
         abstract_operations::{
-            operations_on_objects::{call, call_function},
+            operations_on_objects::{call, call_function, get},
             testing_and_comparison::is_constructor,
         },
         builders::builtin_function_builder::BuiltinFunctionBuilder,
         builtins::{
             ArgumentsList, Behaviour, Builtin, BuiltinGetter, BuiltinIntrinsicConstructor,
             ordinary::ordinary_create_from_constructor,
-            promise::{
-                Promise,
-                data::{PromiseHeapData, PromiseState},
-            },
         },
-        execution::{Agent, JsResult, ProtoIntrinsics, Realm, agent::ExceptionType},
+        execution::{Agent, JsResult, ProtoIntrinsics, Realm},
         types::{
             BUILTIN_STRING_MEMORY, Function, IntoObject, IntoValue, Object, PropertyKey, String,
             Value,
@@ -114,32 +110,14 @@ impl PromiseConstructor {
 
         // 1. If NewTarget is undefined, throw a TypeError exception.
         let Some(new_target) = new_target else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Promise Constructor requires 'new'",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Promise Constructor requires 'new'", gc.into_nogc());
         };
         let new_target = new_target.unbind().bind(gc.nogc());
 
-        // We currently don't support Promise subclassing.
-        assert_eq!(
-            new_target,
-            agent
-                .current_realm_record()
-                .intrinsics()
-                .promise()
-                .into_object()
-        );
-
         // 2. If IsCallable(executor) is false, throw a TypeError exception.
         // TODO: Callable proxies
         let Ok(executor) = Function::try_from(executor) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Not a callable value",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Not a callable value", gc.into_nogc());
         };
         let executor = executor.unbind().scope(agent, gc.nogc());
 
@@ -240,32 +218,19 @@ impl PromiseConstructor {
         agent: &mut Agent,
         this_value: Value,
         arguments: ArgumentsList,
-        gc: GcScope<'gc, '_>,
+        mut gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
-        let r = arguments.get(0).bind(gc.nogc());
-        // We currently don't support Promise subclassing.
-        assert_eq!(
-            this_value,
-            agent
-                .current_realm_record()
-                .intrinsics()
-                .promise()
-                .into_value()
-        );
-
+        let r = arguments.get(0).scope(agent, gc.nogc());
         // 1. Let C be the this value.
         // 2. Let promiseCapability be ? NewPromiseCapability(C).
+        let promise_capability =
+            PromiseCapability::new_from_constructor(agent, this_value, gc.reborrow()).unbind()?;
+        let gc = gc.into_nogc();
+        let promise_capability = promise_capability.bind(gc);
+        let promise = promise_capability.promise();
         // 3. Perform ? Call(promiseCapability.[[Reject]], undefined, « r »).
+        promise_capability.reject(agent, r.get(agent), gc);
         // 4. Return promiseCapability.[[Promise]].
-        // NOTE: Since we don't support promise subclassing, this is equivalent
-        // to creating an already-rejected promise.
-        let promise = agent.heap.create(PromiseHeapData {
-            object_index: None,
-            promise_state: PromiseState::Rejected {
-                promise_result: r.unbind(),
-                is_handled: false,
-            },
-        });
         Ok(promise.into_value())
     }
 
@@ -275,18 +240,14 @@ impl PromiseConstructor {
         arguments: ArgumentsList,
         gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
-        // We currently don't support Promise subclassing.
-        assert_eq!(
-            this_value,
-            agent
-                .current_realm_record()
-                .intrinsics()
-                .promise()
-                .into_value()
-        );
-
+        let x = arguments.get(0).bind(gc.nogc());
+        // 1. Let C be the this value.
+        // 2. If C is not an Object, throw a TypeError exception.
+        let Ok(c) = Object::try_from(this_value) else {
+            return agent.type_error("Expected the this value to be an object.", gc.into_nogc());
+        };
         // 3. Return ? PromiseResolve(C, x).
-        Ok(Promise::resolve(agent, arguments.get(0), gc).into_value())
+        promise_resolve(agent, c.unbind(), x.unbind(), gc)
     }
 
     /// ### [1 Promise.try ( callbackfn, ...args )](https://tc39.es/proposal-promise-try)
@@ -303,82 +264,61 @@ impl PromiseConstructor {
         // 1. Let C be the this value.
         // 2. If C is not an Object, throw a TypeError exception.
         if is_constructor(agent, this_value).is_none() {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
+            return agent.type_error(
                 "Expected the this value to be a constructor.",
                 gc.into_nogc(),
-            ));
+            );
         }
-        // We currently don't support Promise subclassing.
-        assert_eq!(
-            this_value,
-            agent
-                .current_realm_record()
-                .intrinsics()
-                .promise()
-                .into_value()
-        );
+        let callback_fn = callback_fn.scope(agent, gc.nogc());
+        let args = args.scope(agent, gc.nogc());
 
         // 3. Let promiseCapability be ? NewPromiseCapability(C).
+        let promise_capability =
+            PromiseCapability::new_from_constructor(agent, this_value, gc.reborrow())
+                .unbind()?
+                .bind(gc.nogc());
+        let promise = promise_capability.promise().scope(agent, gc.nogc());
         // 4. Let status be Completion(Call(callbackfn, undefined, args)).
+        let mut args = args
+            .iter(agent)
+            .map(|arg| arg.get(gc.nogc()).unbind())
+            .collect::<Vec<_>>();
         let status = call(
             agent,
-            callback_fn.unbind(),
+            callback_fn.get(agent),
             Value::Undefined,
-            Some(args.unbind()),
+            Some(ArgumentsList::from_mut_slice(&mut args)),
             gc.reborrow(),
         );
-        let promise = match status {
+        let promise_capability = PromiseCapability::from_promise(promise.get(agent), true);
+        match status {
             // 5. If status is an abrupt completion, then
             Err(err) => {
                 // a. Perform ? Call(promiseCapability.[[Reject]], undefined, « status.[[Value]] »).
-                // 7. Return promiseCapability.[[Promise]].
-                agent.heap.create(PromiseHeapData {
-                    object_index: None,
-                    promise_state: PromiseState::Rejected {
-                        promise_result: err.value(),
-                        is_handled: false,
-                    },
-                })
+                promise_capability.reject(agent, err.value().unbind(), gc.nogc());
             }
             // 6. Else,
             Ok(result) => {
                 // a. Perform ? Call(promiseCapability.[[Resolve]], undefined, « status.[[Value]] »).
-                Promise::resolve(agent, result.unbind(), gc)
+                promise_capability.resolve(agent, result.unbind(), gc.reborrow());
             }
         };
         // 7. Return promiseCapability.[[Promise]].
-        Ok(promise.into_value().unbind())
+        Ok(promise.get(agent).into_value())
     }
 
     fn with_resolvers<'gc>(
         agent: &mut Agent,
         this_value: Value,
         _arguments: ArgumentsList,
-        gc: GcScope<'gc, '_>,
+        mut gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
-        let gc = gc.into_nogc();
-        // Step 2 will throw if `this_value` is not a constructor.
-        if is_constructor(agent, this_value).is_none() {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Expected the this value to be a constructor.",
-                gc,
-            ));
-        }
-        // We currently don't support Promise subclassing.
-        assert_eq!(
-            this_value,
-            agent
-                .current_realm_record()
-                .intrinsics()
-                .promise()
-                .into_value()
-        );
-
         // 1. Let C be the this value.
         // 2. Let promiseCapability be ? NewPromiseCapability(C).
-        let promise_capability = PromiseCapability::new(agent, gc);
+        let promise_capability =
+            PromiseCapability::new_from_constructor(agent, this_value, gc.reborrow()).unbind()?;
+        let gc = gc.into_nogc();
+        let promise_capability = promise_capability.bind(gc);
         let resolve_function = agent
             .heap
             .create(PromiseResolvingFunctionHeapData {
@@ -454,3 +394,47 @@ impl PromiseConstructor {
             .build();
     }
 }
+
+/// ### [27.2.4.7.1 PromiseResolve ( C, x )](https://tc39.es/ecma262/#sec-promise-resolve)
+///
+/// The abstract operation PromiseResolve takes arguments C (an Object) and x
+/// (an ECMAScript language value) and returns either a normal completion
+/// containing an ECMAScript language value or a throw completion. It returns
+/// a new promise resolved with x.
+pub(crate) fn promise_resolve<'gc>(
+    agent: &mut Agent,
+    c: Object,
+    x: Value,
+    mut gc: GcScope<'gc, '_>,
+) -> JsResult<'gc, Value<'gc>> {
+    let c = c.scope(agent, gc.nogc());
+    let x = x.scope(agent, gc.nogc());
+    // 1. If IsPromise(x) is true, then
+    if let Value::Promise(promise) = x.get(agent) {
+        // a. Let xConstructor be ? Get(x, "constructor").
+        let x_constructor = get(
+            agent,
+            promise,
+            BUILTIN_STRING_MEMORY.constructor.into(),
+            gc.reborrow(),
+        )
+        .unbind()?
+        .bind(gc.nogc());
+        // b. If SameValue(xConstructor, C) is true, return x.
+        if x_constructor == c.get(agent).into_value() {
+            return Ok(x.get(agent));
+        }
+    }
+    // 2. Let promiseCapability be ? NewPromiseCapability(C).
+    let promise_capability =
+        PromiseCapability::new_from_constructor(agent, c.get(agent).into_value(), gc.reborrow())
+            .unbind()?
+            .bind(gc.nogc());
+    let promise = promise_capability.promise().scope(agent, gc.nogc());
+    // 3. Perform ? Call(promiseCapability.[[Resolve]], undefined, « x »).
+    promise_capability
+        .unbind()
+        .resolve(agent, x.get(agent), gc.reborrow());
+    // 4. Return promiseCapability.[[Promise]].
+    Ok(promise.get(agent).into_value())
+}
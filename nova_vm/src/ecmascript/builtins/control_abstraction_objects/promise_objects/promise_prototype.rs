@@ -3,12 +3,16 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use crate::engine::context::{Bindable, GcScope, NoGcScope};
+use crate::engine::rootable::Scopable;
 use crate::{
     ecmascript::{
-        abstract_operations::operations_on_objects::invoke,
+        abstract_operations::{
+            operations_on_objects::{call, invoke, species_constructor},
+            testing_and_comparison::is_callable,
+        },
         builders::ordinary_object_builder::OrdinaryObjectBuilder,
         builtins::{
-            ArgumentsList, Behaviour, Builtin,
+            ArgumentsList, Behaviour, Builtin, RegularFn, create_closure,
             promise::{
                 Promise,
                 data::{PromiseReactions, PromiseState},
@@ -16,19 +20,25 @@ use crate::{
         },
         execution::{
             Agent, JsResult, Realm,
-            agent::{ExceptionType, PromiseRejectionTrackerOperation},
+            agent::{JsError, PromiseRejectionTrackerOperation},
+        },
+        types::{
+            BUILTIN_STRING_MEMORY, Function, IntoFunction, IntoObject, IntoValue, Object, String,
+            Value,
         },
-        types::{BUILTIN_STRING_MEMORY, Function, IntoValue, String, Value},
     },
-    heap::{CreateHeapData, WellKnownSymbolIndexes},
+    heap::CreateHeapData,
 };
 
-use super::promise_abstract_operations::{
-    promise_capability_records::PromiseCapability,
-    promise_jobs::new_promise_reaction_job,
-    promise_reaction_records::{
-        PromiseReactionHandler, PromiseReactionRecord, PromiseReactionType,
+use super::{
+    promise_abstract_operations::{
+        promise_capability_records::PromiseCapability,
+        promise_jobs::new_promise_reaction_job,
+        promise_reaction_records::{
+            PromiseReactionHandler, PromiseReactionRecord, PromiseReactionType,
+        },
     },
+    promise_constructor::promise_resolve,
 };
 
 pub(crate) struct PromisePrototype;
@@ -76,13 +86,65 @@ impl PromisePrototype {
         )
     }
 
+    /// ### [27.2.5.3 Promise.prototype.finally ( onFinally )](https://tc39.es/ecma262/#sec-promise.prototype.finally)
     fn finally<'gc>(
         agent: &mut Agent,
-        _this_value: Value,
-        _: ArgumentsList,
-        gc: GcScope<'gc, '_>,
+        this_value: Value,
+        args: ArgumentsList,
+        mut gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
-        Err(agent.todo("Promise.prototype.finally", gc.into_nogc()))
+        let this_value = this_value.bind(gc.nogc());
+        let on_finally = args.get(0).scope(agent, gc.nogc());
+        // 1. Let promise be the this value.
+        // 2. If promise is not an Object, throw a TypeError exception.
+        let Ok(promise) = Object::try_from(this_value) else {
+            return agent.type_error("'this' is not an object", gc.into_nogc());
+        };
+        let scoped_promise = promise.scope(agent, gc.nogc());
+        // 3. Let C be ? SpeciesConstructor(promise, %Promise%).
+        let promise_constructor = agent.current_realm_record().intrinsics().promise();
+        let c = species_constructor(
+            agent,
+            promise.unbind(),
+            promise_constructor.into_function(),
+            gc.reborrow(),
+        )
+        .unbind()?
+        .bind(gc.nogc());
+        // 4. Assert: IsConstructor(C) is true.
+        let nogc = gc.nogc();
+        let on_finally = on_finally.get(agent).bind(nogc);
+        // 5. If IsCallable(onFinally) is false, then
+        let (then_finally, catch_finally) = if is_callable(on_finally, nogc).is_none() {
+            // a. Let thenFinally be onFinally.
+            // b. Let catchFinally be onFinally.
+            (on_finally, on_finally)
+        } else {
+            // 6. Else,
+            // a. Let thenFinallyClosure be a new Abstract Closure with
+            //    parameters (value) that captures onFinally and C and
+            //    performs the following steps when called:
+            // b. Let thenFinally be CreateBuiltinFunction(thenFinallyClosure, 1, "", « »).
+            let captures = [on_finally, c.into_value()];
+            let then_finally = create_closure(agent, then_finally_closure, 1, &captures, nogc);
+            // c. Let catchFinallyClosure be a new Abstract Closure with
+            //    parameters (reason) that captures onFinally and C and
+            //    performs the following steps when called:
+            // d. Let catchFinally be CreateBuiltinFunction(catchFinallyClosure, 1, "", « »).
+            let catch_finally = create_closure(agent, catch_finally_closure, 1, &captures, nogc);
+            (then_finally.into_value(), catch_finally.into_value())
+        };
+        // 7. Return ? Invoke(promise, "then", « thenFinally, catchFinally »).
+        invoke(
+            agent,
+            scoped_promise.get(agent).into_value(),
+            BUILTIN_STRING_MEMORY.then.into(),
+            Some(ArgumentsList::from_mut_slice(&mut [
+                then_finally.unbind(),
+                catch_finally.unbind(),
+            ])),
+            gc,
+        )
     }
 
     /// ### [27.2.5.4 Promise.prototype.then ( onFulfilled, onRejected )](https://tc39.es/ecma262/#sec-promise.prototype.then)
@@ -90,33 +152,42 @@ impl PromisePrototype {
         agent: &mut Agent,
         this_value: Value,
         args: ArgumentsList,
-        gc: GcScope<'gc, '_>,
+        mut gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
-        let gc = gc.into_nogc();
-        let on_fulfilled = args.get(0).bind(gc);
-        let on_rejected = args.get(1).bind(gc);
+        let this_value = this_value.bind(gc.nogc());
         // 1. Let promise be the this value.
         // 2. If IsPromise(promise) is false, throw a TypeError exception.
         let Value::Promise(promise) = this_value else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "'this' is not a promise",
-                gc,
-            ));
+            return agent.type_error("'this' is not a promise", gc.into_nogc());
         };
+        let on_fulfilled = args.get(0).scope(agent, gc.nogc());
+        let on_rejected = args.get(1).scope(agent, gc.nogc());
+        let scoped_promise = promise.scope(agent, gc.nogc());
 
         // 3. Let C be ? SpeciesConstructor(promise, %Promise%).
+        let promise_constructor = agent.current_realm_record().intrinsics().promise();
+        let c = species_constructor(
+            agent,
+            promise.into_object().unbind(),
+            promise_constructor.into_function(),
+            gc.reborrow(),
+        )
+        .unbind()?
+        .bind(gc.nogc());
         // 4. Let resultCapability be ? NewPromiseCapability(C).
-        // NOTE: We're ignoring species and subclasses.
-        let result_capability = PromiseCapability::new(agent, gc);
+        let result_capability =
+            PromiseCapability::new_from_constructor(agent, c.into_value().unbind(), gc.reborrow())
+                .unbind()?;
+        let gc = gc.into_nogc();
+        let result_capability = result_capability.bind(gc);
         let result_capability_promise = result_capability.promise();
 
         // 5. Return PerformPromiseThen(promise, onFulfilled, onRejected, resultCapability).
         perform_promise_then(
             agent,
-            promise,
-            on_fulfilled,
-            on_rejected,
+            scoped_promise.get(agent).bind(gc),
+            on_fulfilled.get(agent).bind(gc),
+            on_rejected.get(agent).bind(gc),
             Some(result_capability),
             gc,
         );
@@ -136,18 +207,101 @@ impl PromisePrototype {
             .with_constructor_property(promise_constructor)
             .with_builtin_function_property::<PromisePrototypeFinally>()
             .with_builtin_function_property::<PromisePrototypeThen>()
-            .with_property(|builder| {
-                builder
-                    .with_key(WellKnownSymbolIndexes::ToStringTag.into())
-                    .with_value_readonly(BUILTIN_STRING_MEMORY.Promise.into_value())
-                    .with_enumerable(false)
-                    .with_configurable(true)
-                    .build()
-            })
+            .with_to_string_tag(BUILTIN_STRING_MEMORY.Promise)
             .build();
     }
 }
 
+/// The steps of thenFinallyClosure in Promise.prototype.finally, called with
+/// the captured onFinally and C followed by value.
+fn then_finally_closure<'gc>(
+    agent: &mut Agent,
+    _this_value: Value,
+    args: ArgumentsList,
+    gc: GcScope<'gc, '_>,
+) -> JsResult<'gc, Value<'gc>> {
+    finally_closure(agent, args, return_value_closure, gc)
+}
+
+/// The steps of catchFinallyClosure in Promise.prototype.finally, called with
+/// the captured onFinally and C followed by reason.
+fn catch_finally_closure<'gc>(
+    agent: &mut Agent,
+    _this_value: Value,
+    args: ArgumentsList,
+    gc: GcScope<'gc, '_>,
+) -> JsResult<'gc, Value<'gc>> {
+    finally_closure(agent, args, throw_reason_closure, gc)
+}
+
+/// The shared steps of thenFinallyClosure and catchFinallyClosure, which
+/// differ only in the closure that settles the returned promise with the
+/// value or reason.
+fn finally_closure<'gc>(
+    agent: &mut Agent,
+    args: ArgumentsList,
+    settle: RegularFn,
+    mut gc: GcScope<'gc, '_>,
+) -> JsResult<'gc, Value<'gc>> {
+    let on_finally = args.get(0).bind(gc.nogc());
+    let c = args.get(1).scope(agent, gc.nogc());
+    let value = args.get(2).scope(agent, gc.nogc());
+    // i. Let result be ? Call(onFinally, undefined).
+    let result = call(
+        agent,
+        on_finally.unbind(),
+        Value::Undefined,
+        None,
+        gc.reborrow(),
+    )
+    .unbind()?
+    .bind(gc.nogc());
+    // ii. Let p be ? PromiseResolve(C, result).
+    let c = Object::try_from(c.get(agent)).unwrap();
+    let p = promise_resolve(agent, c.unbind(), result.unbind(), gc.reborrow())
+        .unbind()?
+        .bind(gc.nogc());
+    // iii. Let returnValue be a new Abstract Closure with no parameters that
+    //      captures value and performs the following steps when called:
+    //   1. Return NormalCompletion(value).
+    // iv. Let valueThunk be CreateBuiltinFunction(returnValue, 0, "", « »).
+    // NOTE: catchFinallyClosure creates thrower from throwReason, which
+    // returns ThrowCompletion(reason), instead.
+    let value_thunk = create_closure(agent, settle, 0, &[value.get(agent)], gc.nogc());
+    // v. Return ? Invoke(p, "then", « valueThunk »).
+    invoke(
+        agent,
+        p.unbind(),
+        BUILTIN_STRING_MEMORY.then.into(),
+        Some(ArgumentsList::from_mut_slice(&mut [value_thunk
+            .into_value()
+            .unbind()])),
+        gc,
+    )
+}
+
+/// The steps of returnValue in Promise.prototype.finally.
+fn return_value_closure<'gc>(
+    _: &mut Agent,
+    _this_value: Value,
+    args: ArgumentsList,
+    gc: GcScope<'gc, '_>,
+) -> JsResult<'gc, Value<'gc>> {
+    // 1. Return NormalCompletion(value).
+    Ok(args.get(0).bind(gc.into_nogc()))
+}
+
+/// The steps of throwReason in Promise.prototype.finally.
+fn throw_reason_closure<'gc>(
+    _: &mut Agent,
+    _this_value: Value,
+    args: ArgumentsList,
+    gc: GcScope<'gc, '_>,
+) -> JsResult<'gc, Value<'gc>> {
+    // 1. Return ThrowCompletion(reason).
+    Err(JsError::new(args.get(0).bind(gc.into_nogc())))
+}
+
 /// [27.2.5.4.1 PerformPromiseThen ( promise, onFulfilled, onRejected \[ , resultCapability \] )](https://tc39.es/ecma262/#sec-performpromisethen)
 pub(crate) fn perform_promise_then(
     agent: &mut Agent,
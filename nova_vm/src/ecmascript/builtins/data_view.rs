@@ -133,6 +133,7 @@ impl Index<DataView<'_>> for Agent {
 
 impl IndexMut<DataView<'_>> for Agent {
     fn index_mut(&mut self, index: DataView) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.data_views[index]
     }
 }
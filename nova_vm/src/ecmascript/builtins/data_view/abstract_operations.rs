@@ -8,7 +8,7 @@ use crate::{
             },
             structured_data::data_view_objects::data_view_prototype::require_internal_slot_data_view,
         },
-        execution::{Agent, JsResult, agent::ExceptionType},
+        execution::{Agent, JsResult},
         types::{BigInt, IntoNumeric, Number, Numeric, Value, Viewable},
     },
     engine::{
@@ -207,11 +207,7 @@ pub(crate) fn get_view_value<'gc, T: Viewable>(
     // 7. NOTE: Bounds checking is not a synchronizing operation when view's backing buffer is a growable SharedArrayBuffer.
     // 8. If IsViewOutOfBounds(viewRecord) is true, throw a TypeError exception.
     if is_view_out_of_bounds(agent, &view_record, gc) {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "DataView is out of bounds",
-            gc,
-        ));
+        return agent.type_error("DataView is out of bounds", gc);
     }
 
     // 9. Let viewSize be GetViewByteLength(viewRecord).
@@ -222,11 +218,7 @@ pub(crate) fn get_view_value<'gc, T: Viewable>(
 
     // 11. If getIndex + elementSize > viewSize, throw a RangeError exception.
     if get_index + element_size > view_size {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::RangeError,
-            "Index out of bounds",
-            gc,
-        ));
+        return agent.range_error("Index out of bounds", gc);
     }
 
     // 12. Let bufferIndex be getIndex + viewOffset.
@@ -321,11 +313,7 @@ pub(crate) fn set_view_value<'gc, T: Viewable>(
     // 9. NOTE: Bounds checking is not a synchronizing operation when view's backing buffer is a growable SharedArrayBuffer.
     // 10. If IsViewOutOfBounds(viewRecord) is true, throw a TypeError exception.
     if is_view_out_of_bounds(agent, &view_record, gc) {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "DataView is out of bounds",
-            gc,
-        ));
+        return agent.type_error("DataView is out of bounds", gc);
     }
 
     // 11. Let viewSize be GetViewByteLength(viewRecord).
@@ -335,11 +323,7 @@ pub(crate) fn set_view_value<'gc, T: Viewable>(
     let element_size = size_of::<T>();
     // 13. If getIndex + elementSize > viewSize, throw a RangeError exception.
     if get_index + element_size > view_size {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::RangeError,
-            "Index out of bounds",
-            gc,
-        ));
+        return agent.range_error("Index out of bounds", gc);
     }
 
     // 14. Let bufferIndex be getIndex + viewOffset.
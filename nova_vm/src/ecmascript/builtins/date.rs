@@ -134,6 +134,7 @@ impl Index<Date<'_>> for Agent {
 
 impl IndexMut<Date<'_>> for Agent {
     fn index_mut(&mut self, index: Date) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.dates[index]
     }
 }
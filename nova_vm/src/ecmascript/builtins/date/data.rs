@@ -2,11 +2,12 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use std::time::SystemTime;
-
 use crate::{
     SmallInteger,
-    ecmascript::types::{IntoValue, OrdinaryObject, Value},
+    ecmascript::{
+        execution::Agent,
+        types::{IntoValue, OrdinaryObject, Value},
+    },
     engine::context::{Bindable, NoGcScope},
     heap::{CompactionLists, HeapMarkAndSweep, WorkQueues},
 };
@@ -43,13 +44,10 @@ impl DateValue {
         self.get_i64().map(|v| v as f64)
     }
 
-    pub fn now() -> Self {
-        let now = SystemTime::now();
-        let now = now
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .expect("Time went backwards")
-            .as_millis();
-        Self(now as i64)
+    /// The time value identifying the current time, as read from
+    /// [`HostHooks::system_time_now`](crate::ecmascript::execution::agent::HostHooks::system_time_now).
+    pub fn now(agent: &Agent) -> Self {
+        time_clip(agent.host_hooks.system_time_now())
     }
 }
 
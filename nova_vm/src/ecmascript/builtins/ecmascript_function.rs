@@ -30,12 +30,12 @@ use crate::{
         },
         types::{
             BUILTIN_STRING_MEMORY, ECMAScriptFunctionHeapData, Function,
-            FunctionInternalProperties, InternalMethods, InternalSlots, IntoFunction, IntoObject,
-            IntoValue, Object, OrdinaryObject, PropertyDescriptor, PropertyKey, String, Value,
-            function_create_backing_object, function_internal_define_own_property,
-            function_internal_delete, function_internal_get, function_internal_get_own_property,
-            function_internal_has_property, function_internal_own_property_keys,
-            function_internal_set,
+            FunctionInternalProperties, HeapStringBuilder, InternalMethods, InternalSlots,
+            IntoFunction, IntoObject, IntoValue, Object, OrdinaryObject, PropertyDescriptor,
+            PropertyKey, String, Value, function_create_backing_object,
+            function_internal_define_own_property, function_internal_delete, function_internal_get,
+            function_internal_get_own_property, function_internal_has_property,
+            function_internal_own_property_keys, function_internal_set,
         },
     },
     engine::{Executable, rootable::Scopable},
@@ -161,7 +161,7 @@ pub enum ThisMode {
 }
 
 /// ### [10.2 ECMAScript Function Objects](https://tc39.es/ecma262/#sec-ecmascript-function-objects)
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct ECMAScriptFunctionObjectHeapData<'a> {
     /// \[\[Environment]]
     pub environment: Environment<'a>,
@@ -246,6 +246,7 @@ impl Index<ECMAScriptFunction<'_>> for Agent {
 
 impl IndexMut<ECMAScriptFunction<'_>> for Agent {
     fn index_mut(&mut self, index: ECMAScriptFunction) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.ecmascript_functions[index]
     }
 }
@@ -486,15 +487,9 @@ impl<'a> InternalMethods<'a> for ECMAScriptFunction<'a> {
         // 1. Let callerContext be the running execution context.
         let _ = agent.running_execution_context();
         // 2. Let calleeContext be PrepareForOrdinaryCall(F, undefined).
-        let callee_context = prepare_for_ordinary_call(agent, f, None, gc.nogc());
         // This is step 4. or OrdinaryCallBindThis:
         // "Let localEnv be the LexicalEnvironment of calleeContext."
-        let local_env = callee_context
-            .ecmascript_code
-            .as_ref()
-            .unwrap()
-            .lexical_environment
-            .bind(gc.nogc());
+        let local_env = prepare_for_ordinary_call(agent, f, None, gc.nogc());
         // 3. Assert: calleeContext is now the running execution context.
         // assert!(core::ptr::eq(agent.running_execution_context(), callee_context));
         // 4. If F.[[IsClassConstructor]] is true, then
@@ -515,11 +510,12 @@ impl<'a> InternalMethods<'a> for ECMAScriptFunction<'a> {
             // d. Return ThrowCompletion(error).
             return Err(error.unbind());
         }
-        let Environment::Function(local_env) = local_env else {
-            panic!("localEnv is not a Function Environment Record");
-        };
         // 5. Perform OrdinaryCallBindThis(F, calleeContext, thisArgument).
-        ordinary_call_bind_this(agent, f, local_env, this_argument, gc.nogc());
+        // NOTE: Functions that keep their bindings in frame slots never
+        // observe their this value and have no localEnv to bind it in.
+        if let Some(local_env) = local_env {
+            ordinary_call_bind_this(agent, f, local_env, this_argument, gc.nogc());
+        }
         // 6. Let result be Completion(OrdinaryCallEvaluateBody(F, argumentsList)).
         let result = ordinary_call_evaluate_body(agent, f.unbind(), arguments_list.unbind(), gc);
         // 7. Remove calleeContext from the execution context stack and restore callerContext as the running execution context.
@@ -577,31 +573,24 @@ impl<'a> InternalMethods<'a> for ECMAScriptFunction<'a> {
         };
 
         // 4. Let calleeContext be PrepareForOrdinaryCall(F, newTarget).
-        let callee_context =
-            prepare_for_ordinary_call(agent, self_fn, Some(new_target.into_object()), gc.nogc());
         // 7. Let constructorEnv be the LexicalEnvironment of calleeContext.
-        let constructor_env = callee_context
-            .ecmascript_code
-            .as_ref()
-            .unwrap()
-            .lexical_environment
-            .bind(gc.nogc());
-        let Environment::Function(constructor_env) = constructor_env else {
-            panic!("constructorEnv is not a Function Environment Record");
-        };
+        let constructor_env =
+            prepare_for_ordinary_call(agent, self_fn, Some(new_target.into_object()), gc.nogc());
         // 5. Assert: calleeContext is now the running execution context.
         // assert!(core::ptr::eq(agent.running_execution_context(), callee_context));
 
         // 6. If kind is base, then
         if is_base {
             // a. Perform OrdinaryCallBindThis(F, calleeContext, thisArgument).
-            ordinary_call_bind_this(
-                agent,
-                self,
-                constructor_env,
-                this_argument.unwrap().into_value(),
-                gc.nogc(),
-            );
+            if let Some(constructor_env) = constructor_env {
+                ordinary_call_bind_this(
+                    agent,
+                    self,
+                    constructor_env,
+                    this_argument.unwrap().into_value(),
+                    gc.nogc(),
+                );
+            }
             // b. Let initializeResult be Completion(InitializeInstanceElements(thisArgument, F)).
             // c. If initializeResult is an abrupt completion, then
             //    i. Remove calleeContext from the execution context stack and
@@ -610,7 +599,7 @@ impl<'a> InternalMethods<'a> for ECMAScriptFunction<'a> {
             // TODO: Classes.
         }
 
-        let scoped_constructor_env = constructor_env.scope(agent, gc.nogc());
+        let scoped_constructor_env = constructor_env.map(|env| env.scope(agent, gc.nogc()));
         let scoped_this_argument = this_argument.map(|f| f.scope(agent, gc.nogc()));
 
         // 8. Let result be Completion(OrdinaryCallEvaluateBody(F, argumentsList)).
@@ -643,7 +632,7 @@ impl<'a> InternalMethods<'a> for ECMAScriptFunction<'a> {
                 value
                     .unbind()
                     .string_repr(agent, gc.reborrow())
-                    .as_str(agent)
+                    .to_string_lossy(agent)
             );
             let message = String::from_string(agent, message, gc.nogc());
             Err(agent.throw_exception_with_message(
@@ -654,8 +643,10 @@ impl<'a> InternalMethods<'a> for ECMAScriptFunction<'a> {
         } else {
             // 12. Let thisBinding be ? constructorEnv.GetThisBinding().
             // 13. Assert: thisBinding is an Object.
+            // NOTE: Derived class constructors always have a constructorEnv.
             let Ok(this_binding) = Object::try_from(
                 scoped_constructor_env
+                    .unwrap()
                     .get(agent)
                     .get_this_binding(agent, gc.into_nogc())?,
             ) else {
@@ -673,12 +664,16 @@ impl<'a> InternalMethods<'a> for ECMAScriptFunction<'a> {
 /// The abstract operation PrepareForOrdinaryCall takes arguments `F` (an
 /// ECMAScript function object) and newTarget (an Object or undefined) and
 /// returns an execution context.
+///
+/// Note: The calleeContext is only used for its localEnv, which is returned
+/// instead. None is returned if F's bytecode keeps all of its bindings in Vm
+/// frame slots, as no localEnv is then created.
 pub(crate) fn prepare_for_ordinary_call<'a>(
-    agent: &'a mut Agent,
+    agent: &mut Agent,
     f: ECMAScriptFunction,
     new_target: Option<Object>,
-    gc: NoGcScope,
-) -> &'a ExecutionContext {
+    gc: NoGcScope<'a, '_>,
+) -> Option<FunctionEnvironment<'a>> {
     let f = f.bind(gc);
     let new_target = new_target.bind(gc);
     let ecmascript_function_object = &agent[f].ecmascript_function;
@@ -691,15 +686,26 @@ pub(crate) fn prepare_for_ordinary_call<'a>(
     // 4. Let calleeRealm be F.[[Realm]].
     let callee_realm = ecmascript_function_object.realm;
     // 7. Let localEnv be NewFunctionEnvironment(F, newTarget).
-    let local_env = new_function_environment(agent, f, new_target, gc);
+    // NOTE: Functions whose bytecode keeps all bindings in Vm frame slots do
+    // not need a localEnv; they execute directly in F.[[Environment]].
+    let environment = ecmascript_function_object.environment.bind(gc);
+    let local_env = if agent[f]
+        .compiled_bytecode
+        .is_some_and(|exe| !exe.needs_function_environment(agent))
+    {
+        None
+    } else {
+        Some(new_function_environment(agent, f, new_target, gc))
+    };
+    let env = local_env.map_or(environment, Environment::Function);
     // 2. Let calleeContext be a new ECMAScript code execution context.
     let callee_context = ExecutionContext {
         // 8. Set the LexicalEnvironment of calleeContext to localEnv.
         // 9. Set the VariableEnvironment of calleeContext to localEnv.
         // 10. Set the PrivateEnvironment of calleeContext to F.[[PrivateEnvironment]].
         ecmascript_code: Some(ECMAScriptCodeEvaluationState {
-            lexical_environment: Environment::Function(local_env.unbind()),
-            variable_environment: Environment::Function(local_env.unbind()),
+            lexical_environment: env.unbind(),
+            variable_environment: env.unbind(),
             private_environment: private_environment.unbind(),
             is_strict_mode,
             source_code,
@@ -716,7 +722,7 @@ pub(crate) fn prepare_for_ordinary_call<'a>(
     agent.push_execution_context(callee_context);
     // 13. NOTE: Any exception objects produced after this point are associated with calleeRealm.
     // 14. Return calleeContext.
-    agent.running_execution_context()
+    local_env
 }
 
 /// ### [10.2.1.2 OrdinaryCallBindThis ( F, calleeContext, thisArgument )](https://tc39.es/ecma262/#sec-ordinarycallbindthis)
@@ -1126,12 +1132,12 @@ pub(crate) fn set_function_name<'a>(
             symbol_data
                 .descriptor
                 .map_or(String::EMPTY_STRING, |descriptor| {
-                    let descriptor = descriptor.as_str(agent);
-                    String::from_string(
-                        agent,
-                        format!("{}[{descriptor}]", prefix_into_str(prefix)),
-                        gc,
-                    )
+                    let mut name = HeapStringBuilder::default();
+                    name.push_str(prefix_into_str(prefix));
+                    name.push_char('[');
+                    name.push_string(agent, descriptor);
+                    name.push_char(']');
+                    name.finish(agent, gc)
                 })
         }
 
@@ -1149,11 +1155,10 @@ pub(crate) fn set_function_name<'a>(
         }
         PropertyKey::String(str) => {
             if let Some(prefix) = prefix {
-                String::from_string(
-                    agent,
-                    format!("{}{}", prefix.into_str(), str.as_str(agent)),
-                    gc,
-                )
+                let mut name = HeapStringBuilder::default();
+                name.push_str(prefix.into_str());
+                name.push_string(agent, str.into());
+                name.finish(agent, gc)
             } else {
                 str.into()
             }
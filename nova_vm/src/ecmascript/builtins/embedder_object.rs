@@ -106,6 +106,7 @@ impl Index<EmbedderObject<'_>> for Agent {
 
 impl IndexMut<EmbedderObject<'_>> for Agent {
     fn index_mut(&mut self, index: EmbedderObject) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.embedder_objects[index]
     }
 }
@@ -3,10 +3,14 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 mod data;
+mod message;
+mod serialization;
 
 use core::ops::{Index, IndexMut};
 
 pub(crate) use data::ErrorHeapData;
+pub(crate) use message::{DeferredErrorMessage, ErrorMessage, MessageArgument};
+pub use serialization::{ErrorData, ErrorDataValue};
 
 use crate::engine::context::{Bindable, GcScope, NoGcScope};
 use crate::engine::rootable::{HeapRootData, HeapRootRef, Rootable};
@@ -38,6 +42,20 @@ impl Error<'_> {
     pub(crate) const fn get_index(self) -> usize {
         self.0.into_index()
     }
+
+    /// Formats a deferred message of the error into its message String.
+    ///
+    /// This must be called before the message of the error is read.
+    pub(crate) fn materialize_message(self, agent: &mut Agent) {
+        if let Some(deferred_message) = agent[self].deferred_message.take() {
+            let message = deferred_message.format(agent);
+            agent[self].message = Some(agent.heap.create(message));
+        }
+    }
+
+    fn has_message(self, agent: &Agent) -> bool {
+        agent[self].message.is_some() || agent[self].deferred_message.is_some()
+    }
 }
 
 // SAFETY: Property implemented as a lifetime transmute.
@@ -108,6 +126,7 @@ impl<'a> InternalSlots<'a> for Error<'a> {
 
     fn create_backing_object(self, agent: &mut Agent) -> OrdinaryObject<'static> {
         let prototype = self.internal_prototype(agent).unwrap();
+        self.materialize_message(agent);
         let message_entry = agent[self].message.map(|message| ObjectEntry {
             key: PropertyKey::from(BUILTIN_STRING_MEMORY.message),
             value: ObjectEntryPropertyDescriptor::Data {
@@ -182,6 +201,7 @@ impl<'a> InternalMethods<'a> for Error<'a> {
             None => {
                 let property_value =
                     if property_key == PropertyKey::from(BUILTIN_STRING_MEMORY.message) {
+                        self.materialize_message(agent);
                         agent[self].message.map(|message| message.into_value())
                     } else if property_key == PropertyKey::from(BUILTIN_STRING_MEMORY.cause) {
                         agent[self].cause
@@ -210,7 +230,7 @@ impl<'a> InternalMethods<'a> for Error<'a> {
             Some(backing_object) => backing_object.try_has_property(agent, property_key, gc),
             None => TryResult::Continue(
                 if property_key == PropertyKey::from(BUILTIN_STRING_MEMORY.message) {
-                    agent[self].message.is_some()
+                    self.has_message(agent)
                 } else if property_key == PropertyKey::from(BUILTIN_STRING_MEMORY.cause) {
                     agent[self].cause.is_some()
                 } else {
@@ -233,7 +253,7 @@ impl<'a> InternalMethods<'a> for Error<'a> {
             }
             None => Ok(
                 if property_key == PropertyKey::from(BUILTIN_STRING_MEMORY.message) {
-                    agent[self].message.is_some()
+                    self.has_message(agent)
                 } else if property_key == PropertyKey::from(BUILTIN_STRING_MEMORY.cause) {
                     agent[self].cause.is_some()
                 } else {
@@ -255,6 +275,7 @@ impl<'a> InternalMethods<'a> for Error<'a> {
             None => {
                 let property_value =
                     if property_key == PropertyKey::from(BUILTIN_STRING_MEMORY.message) {
+                        self.materialize_message(agent);
                         agent[self].message.map(|message| message.into_value())
                     } else if property_key == PropertyKey::from(BUILTIN_STRING_MEMORY.cause) {
                         agent[self].cause
@@ -288,6 +309,7 @@ impl<'a> InternalMethods<'a> for Error<'a> {
             None => {
                 let property_value =
                     if property_key == PropertyKey::from(BUILTIN_STRING_MEMORY.message) {
+                        self.materialize_message(agent);
                         agent[self].message.map(|message| message.into_value())
                     } else if property_key == PropertyKey::from(BUILTIN_STRING_MEMORY.cause) {
                         agent[self].cause
@@ -328,6 +350,7 @@ impl<'a> InternalMethods<'a> for Error<'a> {
                     && value.is_string()
                 {
                     agent[self].message = Some(String::try_from(value.unbind()).unwrap());
+                    agent[self].deferred_message = None;
                     TryResult::Continue(true)
                 } else if property_key == PropertyKey::from(BUILTIN_STRING_MEMORY.cause) {
                     agent[self].cause = Some(value.unbind());
@@ -358,6 +381,7 @@ impl<'a> InternalMethods<'a> for Error<'a> {
                     && value.is_string()
                 {
                     agent[self].message = Some(String::try_from(value.unbind()).unwrap());
+                    agent[self].deferred_message = None;
                     Ok(true)
                 } else if property_key == PropertyKey::from(BUILTIN_STRING_MEMORY.cause) {
                     agent[self].cause = Some(value.unbind());
@@ -385,6 +409,7 @@ impl<'a> InternalMethods<'a> for Error<'a> {
             None => {
                 if property_key == PropertyKey::from(BUILTIN_STRING_MEMORY.message) {
                     agent[self].message = None;
+                    agent[self].deferred_message = None;
                 } else if property_key == PropertyKey::from(BUILTIN_STRING_MEMORY.cause) {
                     agent[self].cause = None;
                 }
@@ -404,7 +429,7 @@ impl<'a> InternalMethods<'a> for Error<'a> {
             }
             None => {
                 let mut property_keys = Vec::with_capacity(2);
-                if agent[self].message.is_some() {
+                if self.has_message(agent) {
                     property_keys.push(BUILTIN_STRING_MEMORY.message.into());
                 }
                 if agent[self].cause.is_some() {
@@ -473,6 +498,7 @@ impl Index<Error<'_>> for Agent {
 
 impl IndexMut<Error<'_>> for Agent {
     fn index_mut(&mut self, index: Error) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.errors[index]
     }
 }
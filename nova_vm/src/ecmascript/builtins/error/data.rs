@@ -2,6 +2,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use super::DeferredErrorMessage;
 use crate::{
     ecmascript::{
         execution::agent::ExceptionType,
@@ -16,6 +17,9 @@ pub struct ErrorHeapData<'a> {
     pub(crate) object_index: Option<OrdinaryObject<'a>>,
     pub(crate) kind: ExceptionType,
     pub(crate) message: Option<String<'a>>,
+    /// Message that has not been formatted yet. Only one of `message` and
+    /// `deferred_message` is ever set.
+    pub(crate) deferred_message: Option<DeferredErrorMessage<'a>>,
     pub(crate) cause: Option<Value<'a>>,
     // TODO: stack? name?
 }
@@ -30,9 +34,23 @@ impl<'a> ErrorHeapData<'a> {
             object_index: None,
             kind,
             message,
+            deferred_message: None,
             cause,
         }
     }
+
+    pub(crate) fn with_deferred_message(
+        kind: ExceptionType,
+        message: DeferredErrorMessage<'a>,
+    ) -> Self {
+        Self {
+            object_index: None,
+            kind,
+            message: None,
+            deferred_message: Some(message),
+            cause: None,
+        }
+    }
 }
 
 // SAFETY: Property implemented as a lifetime transmute.
@@ -56,11 +74,13 @@ impl HeapMarkAndSweep for ErrorHeapData<'static> {
             object_index,
             kind: _,
             message,
+            deferred_message,
             cause,
         } = self;
 
         object_index.mark_values(queues);
         message.mark_values(queues);
+        deferred_message.mark_values(queues);
         cause.mark_values(queues);
     }
 
@@ -69,10 +89,12 @@ impl HeapMarkAndSweep for ErrorHeapData<'static> {
             object_index,
             kind: _,
             message,
+            deferred_message,
             cause,
         } = self;
         object_index.sweep_values(compactions);
         message.sweep_values(compactions);
+        deferred_message.sweep_values(compactions);
         cause.sweep_values(compactions);
     }
 }
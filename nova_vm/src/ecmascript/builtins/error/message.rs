@@ -0,0 +1,246 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Error messages that are only formatted once they are observed.
+//!
+//! Most exceptions thrown by the engine are caught by ECMAScript code without
+//! ever reading their `message` property. Instead of formatting the message
+//! into a heap String when the exception is thrown, the error stores one of
+//! the templates below along with its arguments, and the message String is
+//! created when the `message` property is first accessed or the error is
+//! reported to the host.
+
+use crate::{
+    ecmascript::{
+        execution::Agent,
+        types::{PropertyKey, String},
+    },
+    engine::context::{Bindable, NoGcScope},
+    heap::{CompactionLists, HeapMarkAndSweep, WorkQueues},
+};
+
+/// Templates of error messages with up to two interpolated arguments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ErrorMessage {
+    /// `Cannot access undeclared variable '{0}'.`
+    UndeclaredVariableAccess,
+    /// `Cannot assign to undeclared variable '{0}'.`
+    UndeclaredVariableAssignment,
+    /// `Cannot read property '{0}' of {1}.`
+    CannotReadProperty,
+    /// `Could not set property '{0}' of {1}.`
+    CannotSetPropertyOf,
+    /// `Could not set property '{0}'.`
+    CannotSetProperty,
+    /// `Could not create property '{0}'.`
+    CannotCreateProperty,
+    /// `Identifier '{0}' does not exist.`
+    IdentifierDoesNotExist,
+    /// `Cannot access '{0}' before initialization`
+    AccessBeforeInitialization,
+    /// `Cannot assign to immutable identifier '{0}' in strict mode.`
+    ImmutableIdentifierAssignment,
+    /// `Property '{0}' does not exist in {1}.`
+    PropertyDoesNotExistIn,
+    /// `'{0}' is not a constructor.`
+    NotAConstructor,
+    /// `right-hand side of 'in' should be an object, got {0}.`
+    InTargetNotObject,
+    /// `Redeclaration of restricted global property '{0}'.`
+    RestrictedGlobalRedeclaration,
+    /// `Redeclaration of lexical binding '{0}'.`
+    LexicalBindingRedeclaration,
+    /// `Redeclaration of lexical declaration '{0}'.`
+    LexicalDeclarationRedeclaration,
+    /// `Redeclaration of variable '{0}'.`
+    VariableRedeclaration,
+    /// `Cannot declare global function '{0}'.`
+    CannotDeclareGlobalFunction,
+    /// `Cannot declare global variable '{0}'.`
+    CannotDeclareGlobalVariable,
+}
+
+impl ErrorMessage {
+    fn format(
+        self,
+        first: &dyn core::fmt::Display,
+        second: &dyn core::fmt::Display,
+    ) -> std::string::String {
+        match self {
+            Self::UndeclaredVariableAccess => {
+                format!("Cannot access undeclared variable '{first}'.")
+            }
+            Self::UndeclaredVariableAssignment => {
+                format!("Cannot assign to undeclared variable '{first}'.")
+            }
+            Self::CannotReadProperty => format!("Cannot read property '{first}' of {second}."),
+            Self::CannotSetPropertyOf => format!("Could not set property '{first}' of {second}."),
+            Self::CannotSetProperty => format!("Could not set property '{first}'."),
+            Self::CannotCreateProperty => format!("Could not create property '{first}'."),
+            Self::IdentifierDoesNotExist => format!("Identifier '{first}' does not exist."),
+            Self::AccessBeforeInitialization => {
+                format!("Cannot access '{first}' before initialization")
+            }
+            Self::ImmutableIdentifierAssignment => {
+                format!("Cannot assign to immutable identifier '{first}' in strict mode.")
+            }
+            Self::PropertyDoesNotExistIn => {
+                format!("Property '{first}' does not exist in {second}.")
+            }
+            Self::NotAConstructor => format!("'{first}' is not a constructor."),
+            Self::InTargetNotObject => {
+                format!("right-hand side of 'in' should be an object, got {first}.")
+            }
+            Self::RestrictedGlobalRedeclaration => {
+                format!("Redeclaration of restricted global property '{first}'.")
+            }
+            Self::LexicalBindingRedeclaration => {
+                format!("Redeclaration of lexical binding '{first}'.")
+            }
+            Self::LexicalDeclarationRedeclaration => {
+                format!("Redeclaration of lexical declaration '{first}'")
+            }
+            Self::VariableRedeclaration => format!("Redeclaration of variable '{first}'"),
+            Self::CannotDeclareGlobalFunction => {
+                format!("Cannot declare global function '{first}'.")
+            }
+            Self::CannotDeclareGlobalVariable => {
+                format!("Cannot declare global variable '{first}'.")
+            }
+        }
+    }
+}
+
+/// An argument interpolated into an [`ErrorMessage`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum MessageArgument<'a> {
+    Static(&'static str),
+    String(String<'a>),
+    PropertyKey(PropertyKey<'a>),
+}
+
+impl From<&'static str> for MessageArgument<'_> {
+    fn from(value: &'static str) -> Self {
+        Self::Static(value)
+    }
+}
+
+impl<'a> From<String<'a>> for MessageArgument<'a> {
+    fn from(value: String<'a>) -> Self {
+        Self::String(value)
+    }
+}
+
+impl<'a> From<PropertyKey<'a>> for MessageArgument<'a> {
+    fn from(value: PropertyKey<'a>) -> Self {
+        Self::PropertyKey(value)
+    }
+}
+
+struct DisplayableArgument<'a, 'b> {
+    argument: MessageArgument<'a>,
+    agent: &'b Agent,
+}
+
+impl core::fmt::Display for DisplayableArgument<'_, '_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.argument {
+            MessageArgument::Static(str) => f.write_str(str),
+            MessageArgument::String(string) => f.write_str(&string.to_string_lossy(self.agent)),
+            MessageArgument::PropertyKey(key) => key.as_display(self.agent).fmt(f),
+        }
+    }
+}
+
+/// An error message template and its arguments, waiting to be formatted.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct DeferredErrorMessage<'a> {
+    template: ErrorMessage,
+    arguments: [MessageArgument<'a>; 2],
+}
+
+impl<'a> DeferredErrorMessage<'a> {
+    pub(crate) fn new<const N: usize>(
+        template: ErrorMessage,
+        arguments: [MessageArgument<'a>; N],
+    ) -> Self {
+        const { assert!(N <= 2, "error messages take at most two arguments") };
+        let mut all_arguments = [MessageArgument::Static(""); 2];
+        all_arguments[..N].copy_from_slice(&arguments);
+        Self {
+            template,
+            arguments: all_arguments,
+        }
+    }
+
+    /// Formats the message.
+    pub(crate) fn format(self, agent: &Agent) -> std::string::String {
+        let [first, second] = self.arguments;
+        self.template.format(
+            &DisplayableArgument {
+                argument: first,
+                agent,
+            },
+            &DisplayableArgument {
+                argument: second,
+                agent,
+            },
+        )
+    }
+}
+
+// SAFETY: Property implemented as a lifetime transmute.
+unsafe impl Bindable for DeferredErrorMessage<'_> {
+    type Of<'a> = DeferredErrorMessage<'a>;
+
+    #[inline(always)]
+    fn unbind(self) -> Self::Of<'static> {
+        unsafe { core::mem::transmute::<Self, Self::Of<'static>>(self) }
+    }
+
+    #[inline(always)]
+    fn bind<'a>(self, _gc: NoGcScope<'a, '_>) -> Self::Of<'a> {
+        unsafe { core::mem::transmute::<Self, Self::Of<'a>>(self) }
+    }
+}
+
+impl HeapMarkAndSweep for MessageArgument<'static> {
+    fn mark_values(&self, queues: &mut WorkQueues) {
+        match self {
+            Self::Static(_) => {}
+            Self::String(string) => string.mark_values(queues),
+            Self::PropertyKey(key) => key.mark_values(queues),
+        }
+    }
+
+    fn sweep_values(&mut self, compactions: &CompactionLists) {
+        match self {
+            Self::Static(_) => {}
+            Self::String(string) => string.sweep_values(compactions),
+            Self::PropertyKey(key) => key.sweep_values(compactions),
+        }
+    }
+}
+
+impl HeapMarkAndSweep for DeferredErrorMessage<'static> {
+    fn mark_values(&self, queues: &mut WorkQueues) {
+        let Self {
+            template: _,
+            arguments,
+        } = self;
+        for argument in arguments {
+            argument.mark_values(queues);
+        }
+    }
+
+    fn sweep_values(&mut self, compactions: &CompactionLists) {
+        let Self {
+            template: _,
+            arguments,
+        } = self;
+        for argument in arguments {
+            argument.sweep_values(compactions);
+        }
+    }
+}
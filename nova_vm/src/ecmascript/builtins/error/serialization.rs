@@ -0,0 +1,651 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use super::{Error, ErrorHeapData};
+use crate::{
+    ecmascript::{
+        abstract_operations::operations_on_objects::create_array_from_list,
+        builtins::Array,
+        execution::{Agent, agent::ExceptionType},
+        types::{
+            BUILTIN_STRING_MEMORY, InternalMethods, IntoObject, IntoValue, JsType, Object,
+            PropertyDescriptor, PropertyKey, String, Value,
+        },
+    },
+    engine::{
+        TryResult,
+        context::{Bindable, NoGcScope},
+        unwrap_try,
+    },
+    heap::{CreateHeapData, ObjectEntry, ObjectEntryPropertyDescriptor},
+};
+
+/// An owned, Agent-independent snapshot of an Error object.
+///
+/// Created from a thrown value with
+/// [`JsError::to_error_data`](crate::ecmascript::execution::agent::JsError::to_error_data)
+/// and turned back into an Error object, possibly in a different Agent, with
+/// [`ErrorData::into_value`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorData {
+    /// The error's `name`, eg. `"TypeError"`.
+    pub name: std::string::String,
+    /// The error's own `message` property.
+    pub message: Option<std::string::String>,
+    /// The error's own `stack` property, if it is a String.
+    ///
+    /// > Note: Nova does not capture stack traces itself; this is only set if
+    /// > the host or a script attached one to the error.
+    pub stack: Option<std::string::String>,
+    /// The error's own `cause` property.
+    pub cause: Option<ErrorDataValue>,
+    /// The `errors` list of an AggregateError.
+    pub errors: Option<Vec<ErrorDataValue>>,
+    /// Any other own enumerable String-keyed properties of the error.
+    pub properties: Vec<(std::string::String, ErrorDataValue)>,
+}
+
+/// A property value of an [`ErrorData`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ErrorDataValue {
+    Undefined,
+    Null,
+    Boolean(bool),
+    Number(f64),
+    String(std::string::String),
+    Array(Vec<ErrorDataValue>),
+    /// Own enumerable String-keyed data properties of an ordinary object.
+    Object(Vec<(std::string::String, ErrorDataValue)>),
+    Error(Box<ErrorData>),
+    /// Refers back to an Error that contains this value, breaking a cycle.
+    /// `0` is the innermost containing Error, `1` the one containing that,
+    /// and so on.
+    Cycle(usize),
+    /// A value that has no plain data representation, such as a function,
+    /// a Symbol, an accessor property or a circular object. Holds a short
+    /// description of what was lost and is restored as undefined.
+    Lossy(std::string::String),
+}
+
+impl ErrorData {
+    /// Creates an ErrorData from a thrown value.
+    ///
+    /// Values that are not Error objects are described as a plain `Error`
+    /// whose message is the value if it is a String, or its type otherwise.
+    pub(crate) fn from_value(agent: &mut Agent, value: Value, gc: NoGcScope) -> Self {
+        let value = value.bind(gc);
+        if let Value::Error(error) = value {
+            let mut path = Vec::new();
+            return serialize_error(agent, error, &mut path, gc);
+        }
+        let message = value
+            .as_string_lossy(agent)
+            .unwrap_or_else(|| js_type_name(value.type_of(agent)).to_owned());
+        Self {
+            name: "Error".to_owned(),
+            message: Some(message),
+            stack: None,
+            cause: None,
+            errors: None,
+            properties: vec![],
+        }
+    }
+
+    /// Creates an Error object from the data in the current Realm of the
+    /// Agent.
+    ///
+    /// Native error names are mapped onto the Realm's intrinsic error
+    /// prototypes; other names create an `Error` with an own `name` property.
+    pub fn into_value<'gc>(self, agent: &mut Agent, gc: NoGcScope<'gc, '_>) -> Value<'gc> {
+        let mut errors = Vec::new();
+        self.create_error(agent, &mut errors, gc).into_value()
+    }
+
+    fn create_error<'gc>(
+        self,
+        agent: &mut Agent,
+        errors: &mut Vec<Error<'gc>>,
+        gc: NoGcScope<'gc, '_>,
+    ) -> Error<'gc> {
+        let kind = exception_type_from_name(&self.name);
+        let message = self
+            .message
+            .map(|message| String::from_string(agent, message, gc));
+        let error = agent
+            .heap
+            .create(ErrorHeapData::new(
+                kind.unwrap_or(ExceptionType::Error),
+                message.unbind(),
+                None,
+            ))
+            .bind(gc);
+        errors.push(error);
+        // Note: The cause must be set before any other properties are
+        // defined, as those create the backing object of the error.
+        if let Some(cause) = self.cause {
+            let cause = cause.into_value(agent, errors, gc);
+            agent[error].cause = Some(cause.unbind());
+        }
+        if kind.is_none() {
+            let name = String::from_string(agent, self.name, gc);
+            define_hidden_property(
+                agent,
+                error,
+                BUILTIN_STRING_MEMORY.name.into(),
+                name.into_value(),
+                gc,
+            );
+        }
+        if let Some(list) = self.errors {
+            let list = list
+                .into_iter()
+                .map(|value| value.into_value(agent, errors, gc))
+                .collect::<Vec<_>>();
+            let list = create_array_from_list(agent, &list, gc);
+            define_hidden_property(
+                agent,
+                error,
+                BUILTIN_STRING_MEMORY.errors.into(),
+                list.into_value(),
+                gc,
+            );
+        }
+        if let Some(stack) = self.stack {
+            let key = PropertyKey::from_static_str(agent, "stack", gc);
+            let stack = String::from_string(agent, stack, gc);
+            define_hidden_property(agent, error, key, stack.into_value(), gc);
+        }
+        for (key, value) in self.properties {
+            let key = PropertyKey::from_string(agent, key, gc);
+            let value = value.into_value(agent, errors, gc);
+            unwrap_try(error.try_define_own_property(
+                agent,
+                key,
+                PropertyDescriptor::new_data_descriptor(value),
+                gc,
+            ));
+        }
+        errors.pop();
+        error
+    }
+}
+
+impl ErrorDataValue {
+    fn into_value<'gc>(
+        self,
+        agent: &mut Agent,
+        errors: &mut Vec<Error<'gc>>,
+        gc: NoGcScope<'gc, '_>,
+    ) -> Value<'gc> {
+        match self {
+            ErrorDataValue::Undefined | ErrorDataValue::Lossy(_) => Value::Undefined,
+            ErrorDataValue::Null => Value::Null,
+            ErrorDataValue::Boolean(value) => Value::Boolean(value),
+            ErrorDataValue::Number(value) => Value::from_f64(agent, value, gc),
+            ErrorDataValue::String(value) => String::from_string(agent, value, gc).into_value(),
+            ErrorDataValue::Array(elements) => {
+                let elements = elements
+                    .into_iter()
+                    .map(|value| value.into_value(agent, errors, gc))
+                    .collect::<Vec<_>>();
+                create_array_from_list(agent, &elements, gc).into_value()
+            }
+            ErrorDataValue::Object(properties) => {
+                let entries = properties
+                    .into_iter()
+                    .map(|(key, value)| ObjectEntry {
+                        key: PropertyKey::from_string(agent, key, gc),
+                        value: ObjectEntryPropertyDescriptor::Data {
+                            value: value.into_value(agent, errors, gc),
+                            writable: true,
+                            enumerable: true,
+                            configurable: true,
+                        },
+                    })
+                    .collect::<Vec<_>>();
+                let prototype = agent
+                    .current_realm_record()
+                    .intrinsics()
+                    .object_prototype()
+                    .into_object();
+                agent
+                    .heap
+                    .create_object_with_prototype(prototype, &entries)
+                    .bind(gc)
+                    .into_value()
+            }
+            ErrorDataValue::Error(data) => data.create_error(agent, errors, gc).into_value(),
+            ErrorDataValue::Cycle(depth) => errors
+                .len()
+                .checked_sub(depth + 1)
+                .map_or(Value::Undefined, |index| errors[index].into_value()),
+        }
+    }
+}
+
+fn serialize_error<'a>(
+    agent: &mut Agent,
+    error: Error<'a>,
+    path: &mut Vec<Object<'a>>,
+    gc: NoGcScope<'a, '_>,
+) -> ErrorData {
+    path.push(error.into_object());
+    let name = match error.try_get(
+        agent,
+        BUILTIN_STRING_MEMORY.name.into(),
+        error.into_value(),
+        gc,
+    ) {
+        TryResult::Continue(name) => name.as_string_lossy(agent),
+        TryResult::Break(_) => None,
+    }
+    .unwrap_or_else(|| exception_type_name(agent[error].kind).to_owned());
+    let message = own_data_property(
+        agent,
+        error.into_object(),
+        BUILTIN_STRING_MEMORY.message.into(),
+        gc,
+    )
+    .and_then(|message| message.as_string_lossy(agent));
+    let stack_key = PropertyKey::from_static_str(agent, "stack", gc);
+    let stack = own_data_property(agent, error.into_object(), stack_key, gc)
+        .and_then(|stack| stack.as_string_lossy(agent));
+    let cause =
+        unwrap_try(error.try_get_own_property(agent, BUILTIN_STRING_MEMORY.cause.into(), gc))
+            .map(|descriptor| serialize_property(agent, descriptor, path, gc));
+    let errors = match own_data_property(
+        agent,
+        error.into_object(),
+        BUILTIN_STRING_MEMORY.errors.into(),
+        gc,
+    ) {
+        Some(Value::Array(list)) => Some(serialize_array(agent, list, path, gc)),
+        _ => None,
+    };
+    let skipped_keys = [
+        BUILTIN_STRING_MEMORY.name.into(),
+        BUILTIN_STRING_MEMORY.message.into(),
+        BUILTIN_STRING_MEMORY.cause.into(),
+        BUILTIN_STRING_MEMORY.errors.into(),
+        stack_key,
+    ];
+    let properties = serialize_properties(agent, error.into_object(), &skipped_keys, path, gc);
+    path.pop();
+    ErrorData {
+        name,
+        message,
+        stack,
+        cause,
+        errors,
+        properties,
+    }
+}
+
+fn serialize_value<'a>(
+    agent: &mut Agent,
+    value: Value<'a>,
+    path: &mut Vec<Object<'a>>,
+    gc: NoGcScope<'a, '_>,
+) -> ErrorDataValue {
+    match value {
+        Value::Undefined => ErrorDataValue::Undefined,
+        Value::Null => ErrorDataValue::Null,
+        Value::Boolean(value) => ErrorDataValue::Boolean(value),
+        Value::String(_) | Value::SmallString(_) => {
+            ErrorDataValue::String(value.as_string_lossy(agent).unwrap())
+        }
+        Value::Number(_) | Value::Integer(_) | Value::SmallF64(_) => {
+            ErrorDataValue::Number(value.as_number(agent).unwrap())
+        }
+        Value::Error(error) => {
+            if let Some(index) = path.iter().position(|o| *o == error.into_object()) {
+                let depth = path[index + 1..]
+                    .iter()
+                    .filter(|o| matches!(o, Object::Error(_)))
+                    .count();
+                ErrorDataValue::Cycle(depth)
+            } else {
+                ErrorDataValue::Error(Box::new(serialize_error(agent, error, path, gc)))
+            }
+        }
+        Value::Array(array) if !path.contains(&array.into_object()) => {
+            ErrorDataValue::Array(serialize_array(agent, array, path, gc))
+        }
+        Value::Object(object) if !path.contains(&object.into_object()) => {
+            path.push(object.into_object());
+            let properties = serialize_properties(agent, object.into_object(), &[], path, gc);
+            path.pop();
+            ErrorDataValue::Object(properties)
+        }
+        Value::Array(_) | Value::Object(_) => ErrorDataValue::Lossy("circular".to_owned()),
+        _ => ErrorDataValue::Lossy(js_type_name(value.type_of(agent)).to_owned()),
+    }
+}
+
+fn serialize_array<'a>(
+    agent: &mut Agent,
+    array: Array<'a>,
+    path: &mut Vec<Object<'a>>,
+    gc: NoGcScope<'a, '_>,
+) -> Vec<ErrorDataValue> {
+    path.push(array.into_object());
+    let elements = (0..array.len(agent))
+        .map(
+            |index| match array.try_get(agent, index.into(), array.into_value(), gc) {
+                TryResult::Continue(value) => serialize_value(agent, value, path, gc),
+                TryResult::Break(_) => ErrorDataValue::Lossy("accessor".to_owned()),
+            },
+        )
+        .collect();
+    path.pop();
+    elements
+}
+
+/// Serializes the own enumerable String-keyed properties of an object.
+fn serialize_properties<'a>(
+    agent: &mut Agent,
+    object: Object<'a>,
+    skipped_keys: &[PropertyKey<'a>],
+    path: &mut Vec<Object<'a>>,
+    gc: NoGcScope<'a, '_>,
+) -> Vec<(std::string::String, ErrorDataValue)> {
+    let TryResult::Continue(keys) = object.try_own_property_keys(agent, gc) else {
+        return vec![];
+    };
+    let mut properties = Vec::new();
+    for key in keys {
+        if skipped_keys.contains(&key) {
+            continue;
+        }
+        let Some(key_string) = property_key_to_string(agent, key) else {
+            continue;
+        };
+        let TryResult::Continue(Some(descriptor)) = object.try_get_own_property(agent, key, gc)
+        else {
+            continue;
+        };
+        if descriptor.enumerable != Some(true) {
+            continue;
+        }
+        let value = serialize_property(agent, descriptor, path, gc);
+        properties.push((key_string, value));
+    }
+    properties
+}
+
+fn serialize_property<'a>(
+    agent: &mut Agent,
+    descriptor: PropertyDescriptor<'a>,
+    path: &mut Vec<Object<'a>>,
+    gc: NoGcScope<'a, '_>,
+) -> ErrorDataValue {
+    match descriptor.value {
+        Some(value) => serialize_value(agent, value, path, gc),
+        None => ErrorDataValue::Lossy("accessor".to_owned()),
+    }
+}
+
+fn own_data_property<'a>(
+    agent: &mut Agent,
+    object: Object<'a>,
+    key: PropertyKey<'a>,
+    gc: NoGcScope<'a, '_>,
+) -> Option<Value<'a>> {
+    match object.try_get_own_property(agent, key, gc) {
+        TryResult::Continue(Some(descriptor)) => descriptor.value,
+        _ => None,
+    }
+}
+
+fn define_hidden_property<'a>(
+    agent: &mut Agent,
+    error: Error<'a>,
+    key: PropertyKey<'a>,
+    value: Value<'a>,
+    gc: NoGcScope<'a, '_>,
+) {
+    unwrap_try(error.try_define_own_property(
+        agent,
+        key,
+        PropertyDescriptor {
+            value: Some(value),
+            writable: Some(true),
+            enumerable: Some(false),
+            configurable: Some(true),
+            ..Default::default()
+        },
+        gc,
+    ));
+}
+
+fn property_key_to_string(agent: &Agent, key: PropertyKey) -> Option<std::string::String> {
+    match key {
+        PropertyKey::Integer(index) => Some(index.into_i64().to_string()),
+        PropertyKey::SmallString(string) => Some(string.as_str().to_owned()),
+        PropertyKey::String(string) => String::String(string).into_value().as_string_lossy(agent),
+        PropertyKey::Symbol(_) | PropertyKey::PrivateName(_) => None,
+    }
+}
+
+fn exception_type_name(kind: ExceptionType) -> &'static str {
+    match kind {
+        ExceptionType::Error => "Error",
+        ExceptionType::AggregateError => "AggregateError",
+        ExceptionType::EvalError => "EvalError",
+        ExceptionType::RangeError => "RangeError",
+        ExceptionType::ReferenceError => "ReferenceError",
+        ExceptionType::SyntaxError => "SyntaxError",
+        ExceptionType::TypeError => "TypeError",
+        ExceptionType::UriError => "URIError",
+    }
+}
+
+fn exception_type_from_name(name: &str) -> Option<ExceptionType> {
+    match name {
+        "Error" => Some(ExceptionType::Error),
+        "AggregateError" => Some(ExceptionType::AggregateError),
+        "EvalError" => Some(ExceptionType::EvalError),
+        "RangeError" => Some(ExceptionType::RangeError),
+        "ReferenceError" => Some(ExceptionType::ReferenceError),
+        "SyntaxError" => Some(ExceptionType::SyntaxError),
+        "TypeError" => Some(ExceptionType::TypeError),
+        "URIError" => Some(ExceptionType::UriError),
+        _ => None,
+    }
+}
+
+fn js_type_name(type_of: JsType) -> &'static str {
+    match type_of {
+        JsType::Undefined => "undefined",
+        JsType::Null => "null",
+        JsType::Boolean => "boolean",
+        JsType::Number => "number",
+        JsType::BigInt => "bigint",
+        JsType::String => "string",
+        JsType::Symbol => "symbol",
+        JsType::Object => "object",
+        JsType::Function => "function",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        ecmascript::{
+            builtins::error::{ErrorData, ErrorDataValue},
+            execution::{Agent, DefaultHostHooks, agent::Options, initialize_default_realm},
+            scripts_and_modules::script::test::evaluate,
+            types::{InternalMethods, IntoValue, PropertyKey, String, Value},
+        },
+        engine::context::Bindable,
+    };
+
+    /// Runs a script that throws and returns the thrown error as ErrorData.
+    fn throw_to_error_data(source: &'static str) -> ErrorData {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        agent.with_gc(|agent, mut gc| {
+            initialize_default_realm(agent, gc.reborrow());
+            let source_text = String::from_static_str(agent, source, gc.nogc());
+            let error = agent
+                .run_script(source_text.unbind(), gc.reborrow())
+                .expect_err("script should throw")
+                .unbind();
+            error.to_error_data(agent, gc.nogc())
+        })
+    }
+
+    /// Rehydrates the ErrorData as `globalThis.received` in a fresh Agent and
+    /// evaluates the checks against it.
+    fn check_in_new_agent(data: ErrorData, checks: &'static str) -> bool {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        agent.with_gc(|agent, mut gc| {
+            initialize_default_realm(agent, gc.reborrow());
+            let received = data.into_value(agent, gc.nogc()).unbind();
+            let global = agent.current_global_object(gc.nogc()).unbind();
+            let key = PropertyKey::from_static_str(agent, "received", gc.nogc()).unbind();
+            global
+                .internal_set(agent, key, received, global.into_value(), gc.reborrow())
+                .unwrap();
+            evaluate(agent, checks, gc) == Value::Boolean(true)
+        })
+    }
+
+    #[test]
+    fn type_error_with_cause_and_properties() {
+        let data = throw_to_error_data(
+            "var error = new TypeError('outer', { cause: new RangeError('inner') });
+            error.code = 42;
+            error.tags = ['a', 1, null];
+            error.meta = { ok: true };
+            error.callback = function () {};
+            error.stack = 'TypeError: outer\\n    at <anonymous>';
+            throw error;",
+        );
+        assert_eq!(data.name, "TypeError");
+        assert_eq!(data.message.as_deref(), Some("outer"));
+        assert_eq!(
+            data.stack.as_deref(),
+            Some("TypeError: outer\n    at <anonymous>")
+        );
+        let Some(ErrorDataValue::Error(cause)) = &data.cause else {
+            panic!("cause should be an error: {:?}", data.cause);
+        };
+        assert_eq!(cause.name, "RangeError");
+        assert_eq!(cause.message.as_deref(), Some("inner"));
+        assert_eq!(
+            data.properties,
+            vec![
+                ("code".to_owned(), ErrorDataValue::Number(42.0)),
+                (
+                    "tags".to_owned(),
+                    ErrorDataValue::Array(vec![
+                        ErrorDataValue::String("a".to_owned()),
+                        ErrorDataValue::Number(1.0),
+                        ErrorDataValue::Null,
+                    ])
+                ),
+                (
+                    "meta".to_owned(),
+                    ErrorDataValue::Object(vec![("ok".to_owned(), ErrorDataValue::Boolean(true))])
+                ),
+                (
+                    "callback".to_owned(),
+                    ErrorDataValue::Lossy("function".to_owned())
+                ),
+            ]
+        );
+
+        assert!(check_in_new_agent(
+            data,
+            "received instanceof TypeError &&
+            Object.getPrototypeOf(received) === TypeError.prototype &&
+            received.name === 'TypeError' &&
+            received.message === 'outer' &&
+            received.cause instanceof RangeError &&
+            received.cause.message === 'inner' &&
+            received.code === 42 &&
+            received.tags.join() === 'a,1,' &&
+            received.meta.ok === true &&
+            'callback' in received && received.callback === undefined &&
+            received.stack === 'TypeError: outer\\n    at <anonymous>' &&
+            Object.keys(received).join() === 'code,tags,meta,callback'",
+        ));
+    }
+
+    #[test]
+    fn aggregate_error_with_mixed_children() {
+        let data = throw_to_error_data(
+            "var custom = new Error('custom');
+            custom.name = 'CustomError';
+            throw new AggregateError([new Error('a'), 'b', 3, new SyntaxError('c'), custom], 'many');",
+        );
+        assert_eq!(data.name, "AggregateError");
+        assert_eq!(data.message.as_deref(), Some("many"));
+        let errors = data.errors.as_ref().unwrap();
+        assert_eq!(errors.len(), 5);
+        assert!(matches!(&errors[0], ErrorDataValue::Error(e) if e.name == "Error"));
+        assert_eq!(errors[1], ErrorDataValue::String("b".to_owned()));
+        assert_eq!(errors[2], ErrorDataValue::Number(3.0));
+        assert!(matches!(&errors[3], ErrorDataValue::Error(e) if e.name == "SyntaxError"));
+        assert!(matches!(&errors[4], ErrorDataValue::Error(e) if e.name == "CustomError"));
+
+        assert!(check_in_new_agent(
+            data,
+            "received instanceof AggregateError &&
+            received.message === 'many' &&
+            Array.isArray(received.errors) &&
+            received.errors.length === 5 &&
+            received.errors[0] instanceof Error && received.errors[0].message === 'a' &&
+            received.errors[1] === 'b' &&
+            received.errors[2] === 3 &&
+            received.errors[3] instanceof SyntaxError && received.errors[3].message === 'c' &&
+            received.errors[4] instanceof Error &&
+            received.errors[4].name === 'CustomError' &&
+            received.errors[4].message === 'custom'",
+        ));
+    }
+
+    #[test]
+    fn deeply_nested_cause_chain_with_cycle() {
+        let data = throw_to_error_data(
+            "var first = new Error('0');
+            var error = first;
+            for (var i = 1; i < 50; i++) {
+                error = new Error(String(i), { cause: error });
+            }
+            first.cause = error;
+            throw error;",
+        );
+        let mut current = &data;
+        for i in (1..50).rev() {
+            assert_eq!(current.message, Some(i.to_string()));
+            let Some(ErrorDataValue::Error(cause)) = &current.cause else {
+                panic!("cause of error {i} should be an error");
+            };
+            current = cause;
+        }
+        assert_eq!(current.message.as_deref(), Some("0"));
+        assert_eq!(current.cause, Some(ErrorDataValue::Cycle(49)));
+
+        assert!(check_in_new_agent(
+            data,
+            "var ok = true;
+            var error = received;
+            for (var i = 49; i >= 0; i--) {
+                ok = ok && error instanceof Error && error.message === String(i);
+                error = error.cause;
+            }
+            ok && error === received",
+        ));
+    }
+
+    #[test]
+    fn thrown_non_error_values() {
+        let data = throw_to_error_data("throw 'plain string'");
+        assert_eq!(data.name, "Error");
+        assert_eq!(data.message.as_deref(), Some("plain string"));
+        let data = throw_to_error_data("throw Symbol('sym')");
+        assert_eq!(data.message.as_deref(), Some("symbol"));
+    }
+}
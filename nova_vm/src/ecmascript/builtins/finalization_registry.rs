@@ -90,6 +90,7 @@ impl Index<FinalizationRegistry<'_>> for Agent {
 
 impl IndexMut<FinalizationRegistry<'_>> for Agent {
     fn index_mut(&mut self, index: FinalizationRegistry) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.finalization_registrys[index]
     }
 }
@@ -9,7 +9,7 @@ use crate::{
             ArgumentsList, Behaviour, Builtin,
             primitive_objects::{PrimitiveObjectData, PrimitiveObjectHeapData},
         },
-        execution::{Agent, JsResult, Realm, agent::ExceptionType},
+        execution::{Agent, JsResult, Realm},
         types::{BUILTIN_STRING_MEMORY, String, Value},
     },
     engine::context::{GcScope, NoGcScope},
@@ -110,9 +110,5 @@ fn this_boolean_value<'a>(
         }
     }
     // 3. Throw a TypeError exception.
-    Err(agent.throw_exception_with_static_message(
-        ExceptionType::TypeError,
-        "Not a Boolean or Boolean object",
-        gc,
-    ))
+    agent.type_error("Not a Boolean or Boolean object", gc)
 }
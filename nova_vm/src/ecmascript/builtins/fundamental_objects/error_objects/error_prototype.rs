@@ -7,7 +7,7 @@ use crate::{
         abstract_operations::{operations_on_objects::get, type_conversion::to_string},
         builders::ordinary_object_builder::OrdinaryObjectBuilder,
         builtins::{ArgumentsList, Behaviour, Builtin},
-        execution::{Agent, JsResult, Realm, agent::ExceptionType},
+        execution::{Agent, JsResult, Realm},
         types::{BUILTIN_STRING_MEMORY, IntoValue, Object, PropertyKey, String, Value},
     },
     engine::{
@@ -40,11 +40,7 @@ impl ErrorPrototype {
         // 1. Let O be the this value.
         // 2. If O is not an Object, throw a TypeError exception.
         let Ok(o) = Object::try_from(this_value) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "'this' is not an object",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("'this' is not an object", gc.into_nogc());
         };
         let scoped_o = o.scope(agent, gc.nogc());
         // 3. Let name be ? Get(O, "name").
@@ -99,8 +95,8 @@ impl ErrorPrototype {
             Ok(name.into_value())
         } else {
             // 9. Return the string-concatenation of name, the code unit 0x003A (COLON), the code unit 0x0020 (SPACE), and msg.
-            let result = format!("{}: {}", name.as_str(agent), msg.as_str(agent));
-            Ok(String::from_string(agent, result, gc).into_value())
+            let result = String::concat(agent, [name, String::from_small_string(": "), msg], gc);
+            Ok(result.into_value())
         }
     }
 
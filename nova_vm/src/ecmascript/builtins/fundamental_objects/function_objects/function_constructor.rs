@@ -13,11 +13,11 @@ use crate::{
             OrdinaryFunctionCreateParams, make_constructor,
             ordinary::get_prototype_from_constructor, ordinary_function_create, set_function_name,
         },
-        execution::{Agent, Environment, JsResult, ProtoIntrinsics, Realm, agent::ExceptionType},
+        execution::{Agent, Environment, JsResult, ProtoIntrinsics, Realm},
         scripts_and_modules::source_code::{SourceCode, SourceCodeHeapData},
         types::{
-            BUILTIN_STRING_MEMORY, Function, IntoObject, IntoValue, Object, Primitive, String,
-            Value,
+            BUILTIN_STRING_MEMORY, Function, HeapStringBuilder, IntoObject, IntoValue, Object,
+            Primitive, String, Value,
         },
     },
     engine::{
@@ -212,22 +212,22 @@ pub(crate) fn create_dynamic_function<'a>(
             } else {
                 0
             };
-        let mut string = std::string::String::with_capacity(str_len);
+        let mut string = HeapStringBuilder::with_capacity(str_len);
         string.push_str(kind.prefix());
         string.push_str(" anonymous(");
         for (i, parameter) in parameter_strings_slice.iter().enumerate() {
             if i != 0 {
-                string.push(',');
+                string.push_char(',');
             }
-            string.push_str(parameter.as_str(agent));
+            string.push_string(agent, *parameter);
         }
         string.push_str("\n) {\n");
-        string.push_str(body_string.as_str(agent));
+        string.push_string(agent, body_string);
         string.push_str("\n}");
 
         debug_assert_eq!(string.len(), str_len);
 
-        String::from_string(agent, string, gc.nogc())
+        string.finish(agent, gc.nogc())
     };
 
     // The spec says to parse the parameters and the function body separately to
@@ -243,8 +243,9 @@ pub(crate) fn create_dynamic_function<'a>(
         // GC'd before the program is dropped. If this function returns
         // successfully, then the program's AST and the SourceCode will both be
         // kept alive in the returned function object.
-        let parsed_result =
-            unsafe { SourceCode::parse_source(agent, source_string, source_type, gc.nogc()) };
+        let parsed_result = unsafe {
+            SourceCode::parse_source(agent, source_string, source_type, false, gc.nogc())
+        };
 
         if let Ok((program, sc)) = parsed_result {
             source_code = Some(sc);
@@ -285,11 +286,7 @@ pub(crate) fn create_dynamic_function<'a>(
                     agent.heap.source_codes.len()
                 );
             }
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::SyntaxError,
-                "Invalid function source text.",
-                gc.into_nogc(),
-            ));
+            return agent.syntax_error("Invalid function source text.", gc.into_nogc());
         }
     };
 
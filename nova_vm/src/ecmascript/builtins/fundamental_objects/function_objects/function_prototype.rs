@@ -24,10 +24,10 @@ use crate::{
             ArgumentsList, Behaviour, Builtin, BuiltinFunction, BuiltinIntrinsic,
             BuiltinIntrinsicConstructor, bound_function::bound_function_create, set_function_name,
         },
-        execution::{Agent, JsResult, Realm, agent::ExceptionType},
+        execution::{Agent, JsResult, Realm},
         types::{
-            BUILTIN_STRING_MEMORY, Function, InternalSlots, IntoFunction, IntoObject, IntoValue,
-            Number, OrdinaryObject, PropertyKey, String, Value,
+            BUILTIN_STRING_MEMORY, Function, HeapStringBuilder, InternalSlots, IntoFunction,
+            IntoObject, IntoValue, Number, OrdinaryObject, PropertyKey, String, Value,
         },
     },
     heap::{
@@ -122,11 +122,7 @@ impl FunctionPrototype {
         // 1. Let func be the this value.
         let Some(func) = is_callable(this_value, gc.nogc()) else {
             // 2. If IsCallable(func) is false, throw a TypeError exception.
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Not a callable value",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Not a callable value", gc.into_nogc());
         };
         if arg_array.is_undefined() || arg_array.is_null() {
             // 3. If argArray is either undefined or null, then
@@ -177,11 +173,7 @@ impl FunctionPrototype {
         let target = this_value;
         // 2. If IsCallable(Target) is false, throw a TypeError exception.
         let Some(mut target) = is_callable(target, gc.nogc()) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Cannot bind a non-callable object",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Cannot bind a non-callable object", gc.into_nogc());
         };
         let scoped_target = target.scope(agent, gc.nogc());
         // 3. Let F be ? BoundFunctionCreate(Target, thisArg, args).
@@ -326,11 +318,7 @@ impl FunctionPrototype {
         let this_value = this_value.bind(nogc);
         let this_arg = args.get(0).bind(nogc);
         let Some(func) = is_callable(this_value, nogc) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Not a callable value",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Not a callable value", gc.into_nogc());
         };
         // TODO: PrepareForTailCall
         let args = if !args.is_empty() {
@@ -351,11 +339,7 @@ impl FunctionPrototype {
         // Let func be the this value.
         let Ok(func) = Function::try_from(this_value) else {
             // 5. Throw a TypeError exception.
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Not a callable value",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Not a callable value", gc.into_nogc());
         };
 
         match func {
@@ -379,19 +363,13 @@ impl FunctionPrototype {
             // String that would be matched by NativeFunctionAccessor_opt
             // PropertyName must be the value of func.[[InitialName]].
             Function::BuiltinFunction(idx) => {
-                let data = &agent[idx];
-                let initial_name = data.initial_name.map_or_else(
-                    || "function () {{ [ native code ] }}".into(),
-                    |initial_name| match initial_name {
-                        crate::ecmascript::types::String::String(idx) => {
-                            format!("function {}() {{ [ native code ] }}", agent[idx].as_str())
-                        }
-                        crate::ecmascript::types::String::SmallString(string) => {
-                            format!("function {}() {{ [ native code ] }}", string.as_str())
-                        }
-                    },
-                );
-                Ok(Value::from_string(agent, initial_name, gc.nogc()).unbind())
+                let mut initial_name = HeapStringBuilder::default();
+                initial_name.push_str("function ");
+                if let Some(name) = agent[idx].initial_name {
+                    initial_name.push_string(agent, name);
+                }
+                initial_name.push_str("() { [ native code ] }");
+                Ok(initial_name.finish(agent, gc.nogc()).into_value().unbind())
             }
             Function::BuiltinConstructorFunction(_) => {
                 Ok(Value::from_static_str(agent, "class { [ native code ] }", gc.nogc()).unbind())
@@ -490,7 +468,7 @@ impl ThrowTypeError {
         _: ArgumentsList,
         gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
-        Err(agent.throw_exception_with_static_message(ExceptionType::TypeError, "'caller', 'callee', and 'arguments' properties may not be accessed on strict mode functions or the arguments objects for calls to them", gc.into_nogc()))
+        agent.type_error("'caller', 'callee', and 'arguments' properties may not be accessed on strict mode functions or the arguments objects for calls to them", gc.into_nogc())
     }
 
     pub(crate) fn create_intrinsic(agent: &mut Agent, realm: Realm<'static>) {
@@ -37,7 +37,7 @@ use crate::{
         rootable::Scopable,
         unwrap_try,
     },
-    heap::{IntrinsicConstructorIndexes, ObjectEntry, WellKnownSymbolIndexes},
+    heap::{Heap, IntrinsicConstructorIndexes, ObjectEntry, WellKnownSymbolIndexes},
 };
 
 pub(crate) struct ObjectConstructor;
@@ -358,6 +358,18 @@ impl ObjectConstructor {
                 .internal_own_property_keys(agent, gc.reborrow())
                 .unbind()?
                 .scope(agent, gc.nogc());
+            // Reserve space for the copied properties if the target is an
+            // empty ordinary object: then none of the keys can already exist.
+            if let Object::Object(to) = to.get(agent) {
+                let keys_len = u32::try_from(keys.len(agent)).unwrap();
+                let Heap {
+                    elements, objects, ..
+                } = &mut agent.heap;
+                let props = &mut objects[to].property_storage;
+                if props.is_empty() {
+                    props.reserve(elements, keys_len);
+                }
+            }
             // iii. For each element nextKey of keys, do
             for next_key in keys.iter(agent) {
                 // 1. Let desc be ? from.[[GetOwnProperty]](nextKey).
@@ -418,7 +430,9 @@ impl ObjectConstructor {
         } else {
             let error_message = format!(
                 "{} is not an object or null",
-                o.unbind().string_repr(agent, gc.reborrow()).as_str(agent)
+                o.unbind()
+                    .string_repr(agent, gc.reborrow())
+                    .to_string_lossy(agent)
             );
             return Err(agent.throw_exception(
                 ExceptionType::TypeError,
@@ -456,7 +470,9 @@ impl ObjectConstructor {
         let Ok(o) = Object::try_from(o) else {
             let error_message = format!(
                 "{} is not an object",
-                o.unbind().string_repr(agent, gc.reborrow()).as_str(agent)
+                o.unbind()
+                    .string_repr(agent, gc.reborrow())
+                    .to_string_lossy(agent)
             );
             return Err(agent.throw_exception(
                 ExceptionType::TypeError,
@@ -487,7 +503,9 @@ impl ObjectConstructor {
         let Ok(o) = Object::try_from(o) else {
             let error_message = format!(
                 "{} is not an object",
-                o.unbind().string_repr(agent, gc.reborrow()).as_str(agent)
+                o.unbind()
+                    .string_repr(agent, gc.reborrow())
+                    .to_string_lossy(agent)
             );
             return Err(agent.throw_exception(
                 ExceptionType::TypeError,
@@ -573,11 +591,7 @@ impl ObjectConstructor {
         let status = set_integrity_level::<Frozen>(agent, o.unbind(), gc.reborrow()).unbind()?;
         if !status {
             // 3. If status is false, throw a TypeError exception.
-            Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Could not freeze object",
-                gc.into_nogc(),
-            ))
+            agent.type_error("Could not freeze object", gc.into_nogc())
         } else {
             // 4. Return O.
             Ok(scoped_o.get(agent).into_value())
@@ -1021,11 +1035,7 @@ impl ObjectConstructor {
             .unbind()?;
         // 3. If status is false, throw a TypeError exception.
         if !status {
-            Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Could not prevent extensions",
-                gc.into_nogc(),
-            ))
+            agent.type_error("Could not prevent extensions", gc.into_nogc())
         } else {
             // 4. Return O.
             Ok(scoped_o.get(agent).into_value())
@@ -1049,11 +1059,7 @@ impl ObjectConstructor {
         let status = set_integrity_level::<Sealed>(agent, o.unbind(), gc.reborrow()).unbind()?;
         if !status {
             // 3. If status is false, throw a TypeError exception.
-            Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Could not seal object",
-                gc.into_nogc(),
-            ))
+            agent.type_error("Could not seal object", gc.into_nogc())
         } else {
             // 4. Return O.
             Ok(scoped_o.get(agent).into_value())
@@ -1084,7 +1090,7 @@ impl ObjectConstructor {
                 proto
                     .unbind()
                     .string_repr(agent, gc.reborrow())
-                    .as_str(agent)
+                    .to_string_lossy(agent)
             );
             return Err(agent.throw_exception(
                 ExceptionType::TypeError,
@@ -1104,11 +1110,7 @@ impl ObjectConstructor {
             .unbind()?;
         // 5. If status is false, throw a TypeError exception.
         if !status {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Could not set prototype",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Could not set prototype", gc.into_nogc());
         }
         // 6. Return O.
         Ok(scoped_o.get(agent).into_value())
@@ -1378,7 +1380,7 @@ pub fn add_entries_from_iterable_from_entries<'a>(
                 "Invalid iterator next return value: {} is not an object",
                 next.unbind()
                     .string_repr(agent, gc.reborrow())
-                    .as_str(agent)
+                    .to_string_lossy(agent)
             );
             let error = agent.throw_exception(ExceptionType::TypeError, error_message, gc.nogc());
             // ii. Return ? IteratorClose(iteratorRecord, error).
@@ -4,7 +4,7 @@
 
 use crate::ecmascript::abstract_operations::operations_on_objects::is_prototype_of_loop;
 use crate::ecmascript::types::IntoValue;
-use crate::engine::context::{Bindable, GcScope};
+use crate::engine::context::{Bindable, GcScope, NoGcScope};
 use crate::{
     ecmascript::{
         abstract_operations::{
@@ -17,7 +17,10 @@ use crate::{
             primitive_objects::PrimitiveObjectData,
         },
         execution::{Agent, JsResult, Realm},
-        types::{BUILTIN_STRING_MEMORY, InternalMethods, Object, PropertyKey, String, Value},
+        types::{
+            BUILTIN_STRING_MEMORY, HeapStringBuilder, InternalMethods, Object, PropertyKey, String,
+            Value,
+        },
     },
     heap::{IntrinsicFunctionIndexes, WellKnownSymbolIndexes},
 };
@@ -220,12 +223,10 @@ impl ObjectPrototype {
                     .unbind()?
                     .bind(gc.nogc());
                     if let Ok(tag) = String::try_from(tag) {
-                        let str = format!("[object {}]", tag.as_str(agent));
-                        Ok(Value::from_string(agent, str, gc.into_nogc()))
+                        let tag = tag.unbind();
+                        Ok(object_tag_string(agent, tag, gc.into_nogc()).into_value())
                     } else {
-                        let str =
-                            format!("[object {}]", BUILTIN_STRING_MEMORY.Object.as_str(agent));
-                        Ok(Value::from_string(agent, str, gc.into_nogc()))
+                        Ok(BUILTIN_STRING_MEMORY._object_Object_.into_value())
                     }
                 }
             },
@@ -243,12 +244,11 @@ impl ObjectPrototype {
                 .unbind()?
                 .bind(gc.nogc());
                 if let Ok(tag) = String::try_from(tag) {
-                    let str = format!("[object {}]", tag.as_str(agent));
-                    Ok(Value::from_string(agent, str, gc.into_nogc()))
+                    let tag = tag.unbind();
+                    Ok(object_tag_string(agent, tag, gc.into_nogc()).into_value())
                 } else {
                     // 14. Else, let builtinTag be "Object".
-                    let str = format!("[object {}]", BUILTIN_STRING_MEMORY.Object.as_str(agent));
-                    Ok(Value::from_string(agent, str, gc.into_nogc()))
+                    Ok(BUILTIN_STRING_MEMORY._object_Object_.into_value())
                 }
             }
         }
@@ -287,3 +287,12 @@ impl ObjectPrototype {
             .build();
     }
 }
+
+/// Returns the string-concatenation of "[object ", tag, and "]".
+fn object_tag_string<'gc>(agent: &mut Agent, tag: String, gc: NoGcScope<'gc, '_>) -> String<'gc> {
+    let mut result = HeapStringBuilder::with_capacity(tag.len(agent) + 9);
+    result.push_str("[object ");
+    result.push_string(agent, tag);
+    result.push_char(']');
+    result.finish(agent, gc)
+}
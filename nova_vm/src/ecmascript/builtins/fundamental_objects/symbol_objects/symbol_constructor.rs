@@ -11,7 +11,6 @@ use crate::ecmascript::builtins::BuiltinIntrinsicConstructor;
 use crate::ecmascript::execution::Agent;
 use crate::ecmascript::execution::JsResult;
 use crate::ecmascript::execution::Realm;
-use crate::ecmascript::execution::agent::ExceptionType;
 use crate::ecmascript::types::IntoObject;
 
 use crate::ecmascript::types::BUILTIN_STRING_MEMORY;
@@ -67,11 +66,7 @@ impl SymbolConstructor {
         gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
         if new_target.is_some() {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Symbol is not a constructor",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Symbol is not a constructor", gc.into_nogc());
         }
         let description = arguments.get(0).bind(gc.nogc());
         let desc_string = if description.is_undefined() {
@@ -8,7 +8,7 @@ use crate::{
     ecmascript::{
         builders::ordinary_object_builder::OrdinaryObjectBuilder,
         builtins::{ArgumentsList, Builtin, BuiltinGetter},
-        execution::{Agent, JsResult, Realm, agent::ExceptionType},
+        execution::{Agent, JsResult, Realm},
         types::{
             BUILTIN_STRING_MEMORY, IntoValue, PropertyKey, String, Symbol, SymbolHeapData, Value,
         },
@@ -85,24 +85,32 @@ impl SymbolPrototype {
             .map_or_else(|| Ok(Value::Undefined), |desc| Ok(desc.into_value()))
     }
 
+    /// ### [20.4.3.3 Symbol.prototype.toString ( )](https://tc39.es/ecma262/#sec-symbol.prototype.tostring)
     fn to_string<'gc>(
         agent: &mut Agent,
         this_value: Value,
         _: ArgumentsList,
         gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
+        // 1. Let sym be ? ThisSymbolValue(this value).
         let symb = this_symbol_value(agent, this_value, gc.nogc())
             .unbind()?
             .bind(gc.nogc());
+        // 2. Return SymbolDescriptiveString(sym).
         Ok(symbol_descriptive_string(agent, symb.unbind(), gc.into_nogc()).into_value())
     }
 
+    /// ### [20.4.3.4 Symbol.prototype.valueOf ( )](https://tc39.es/ecma262/#sec-symbol.prototype.valueof)
+    ///
+    /// Also used as [20.4.3.5 Symbol.prototype \[ %Symbol.toPrimitive% \] ( hint )](https://tc39.es/ecma262/#sec-symbol.prototype-%symbol.toprimitive%),
+    /// which ignores its argument and performs the same step.
     fn value_of<'gc>(
         agent: &mut Agent,
         this_value: Value,
         _: ArgumentsList,
         gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
+        // 1. Return ? ThisSymbolValue(this value).
         this_symbol_value(agent, this_value, gc.into_nogc()).map(|res| res.into_value())
     }
 
@@ -165,17 +173,15 @@ impl SymbolPrototype {
             .with_builtin_function_property::<SymbolPrototypeToString>()
             .with_builtin_function_property::<SymbolPrototypeValueOf>()
             .with_builtin_function_property::<SymbolPrototypeToPrimitive>()
-            .with_property(|builder| {
-                builder
-                    .with_key(WellKnownSymbolIndexes::ToStringTag.into())
-                    .with_value_readonly(BUILTIN_STRING_MEMORY.Symbol.into_value())
-                    .with_enumerable(false)
-                    .build()
-            })
+            .with_to_string_tag(BUILTIN_STRING_MEMORY.Symbol)
             .build();
     }
 }
 
+/// ### [20.4.3.4.1 ThisSymbolValue ( value )](https://tc39.es/ecma262/#sec-thissymbolvalue)
+///
+/// Accepts Symbols and Symbol wrapper objects, and throws a TypeError for
+/// anything else.
 #[inline(always)]
 fn this_symbol_value<'a>(
     agent: &mut Agent,
@@ -188,11 +194,7 @@ fn this_symbol_value<'a>(
             let s: Symbol = agent[object].data.try_into().unwrap();
             Ok(s)
         }
-        _ => Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "this is not a symbol",
-            gc,
-        )),
+        _ => agent.type_error("this is not a symbol", gc),
     }
 }
 
@@ -211,8 +213,15 @@ fn symbol_descriptive_string<'gc>(
     if let Some(desc) = desc {
         // 3. Assert: desc is a String.
         // 4. Return the string-concatenation of "Symbol(", desc, and ")".
-        let result = format!("Symbol({})", desc.as_str(agent));
-        String::from_string(agent, result, gc)
+        String::concat(
+            agent,
+            [
+                String::from_small_string("Symbol("),
+                desc,
+                String::from_small_string(")"),
+            ],
+            gc,
+        )
     } else {
         BUILTIN_STRING_MEMORY.Symbol__
     }
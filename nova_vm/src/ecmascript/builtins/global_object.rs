@@ -8,10 +8,12 @@ use ahash::AHashSet;
 use oxc_ast::ast::{BindingIdentifier, Program, VariableDeclarationKind};
 use oxc_ecmascript::BoundNames;
 use oxc_span::SourceType;
+use wtf8::CodePoint;
 
 use crate::ecmascript::abstract_operations::type_conversion::{
     is_trimmable_whitespace, to_int32, to_int32_number, to_number_primitive, to_string,
 };
+use crate::ecmascript::builtins::error::ErrorMessage;
 use crate::ecmascript::types::Primitive;
 use crate::engine::context::{Bindable, GcScope, NoGcScope};
 use crate::engine::rootable::Scopable;
@@ -33,7 +35,7 @@ use crate::{
                 script_var_scoped_declarations,
             },
         },
-        types::{BUILTIN_STRING_MEMORY, Function, IntoValue, String, Value},
+        types::{BUILTIN_STRING_MEMORY, Function, HeapStringBuilder, IntoValue, String, Value},
     },
     engine::{Executable, Vm},
     heap::IntrinsicFunctionIndexes,
@@ -175,7 +177,7 @@ pub fn perform_eval<'gc>(
         .unbind()?;
 
     // 6. Let inFunction be false.
-    let mut _in_function = false;
+    let mut in_function = false;
     // 7. Let inMethod be false.
     let mut _in_method = false;
     // 8. Let inDerivedConstructor be false.
@@ -192,7 +194,7 @@ pub fn perform_eval<'gc>(
             // i. Let F be thisEnvRec.[[FunctionObject]].
             let f = this_env_rec.get_function_object(agent, gc.nogc());
             // ii. Set inFunction to true.
-            _in_function = true;
+            in_function = true;
             // iii. Set inMethod to thisEnvRec.HasSuperBinding().
             _in_method = this_env_rec.has_super_binding(agent);
             // iv. If F.[[ConstructorKind]] is derived, set inDerivedConstructor to true.
@@ -225,7 +227,13 @@ pub fn perform_eval<'gc>(
     // call happens.
     // The Program thus refers to a valid, live Allocator for the duration of
     // this call.
-    let parse_result = unsafe { SourceCode::parse_source(agent, x, source_type, gc.nogc()) };
+    //
+    // e. If inFunction is false and body Contains NewTarget, throw a
+    //    SyntaxError exception.
+    // NOTE: The parser performs this check for us when new.target is not
+    // allowed.
+    let parse_result =
+        unsafe { SourceCode::parse_source(agent, x, source_type, in_function, gc.nogc()) };
 
     // b. If script is a List of errors, throw a SyntaxError exception.
     let (script, source_code) = match parse_result {
@@ -246,7 +254,6 @@ pub fn perform_eval<'gc>(
 
     // TODO:
     // d. Let body be the ScriptBody of script.
-    // e. If inFunction is false and body Contains NewTarget, throw a SyntaxError exception.
     // f. If inMethod is false and body Contains SuperProperty, throw a SyntaxError exception.
     // g. If inDerivedConstructor is false and body Contains SuperCall, throw a SyntaxError exception.
     // h. If inClassFieldInitializer is true and ContainsArguments of body is true, throw a SyntaxError exception.
@@ -356,10 +363,7 @@ pub fn perform_eval<'gc>(
             // a. Set result to Completion(Evaluation of body).
             // 30. If result is a normal completion and result.[[Value]] is empty, then
             // a. Set result to NormalCompletion(undefined).
-            let result = Vm::execute(agent, exe.clone(), None, gc).into_js_result();
-            // SAFETY: No one can access the bytecode anymore.
-            unsafe { exe.take(agent).try_drop(agent) };
-            result
+            Vm::execute(agent, exe, None, gc).into_js_result()
         }
         Err(err) => Err(err.unbind().bind(gc.into_nogc())),
     };
@@ -414,12 +418,10 @@ pub fn eval_declaration_instantiation<'a>(
                 // 2. NOTE: eval will not create a global var declaration that
                 //    would be shadowed by a global lexical declaration.
                 if var_env.has_lexical_declaration(agent, name) {
-                    return Err(agent.throw_exception(
+                    return Err(agent.throw_exception_with_deferred_message(
                         ExceptionType::SyntaxError,
-                        format!(
-                            "Redeclaration of lexical declaration '{}'",
-                            name.as_str(agent)
-                        ),
+                        ErrorMessage::LexicalDeclarationRedeclaration,
+                        [name.unbind().into()],
                         gc.into_nogc(),
                     ));
                 }
@@ -452,9 +454,11 @@ pub fn eval_declaration_instantiation<'a>(
                         // i. Throw a SyntaxError exception.
                         // ii. NOTE: Annex B.3.4 defines alternate semantics
                         //     for the above step.
-                        return Err(agent.throw_exception(
+                        let name = String::from_str(agent, name.as_str(), gc.nogc());
+                        return Err(agent.throw_exception_with_deferred_message(
                             ExceptionType::SyntaxError,
-                            format!("Redeclaration of variable '{name}'"),
+                            ErrorMessage::VariableRedeclaration,
+                            [name.unbind().into()],
                             gc.into_nogc(),
                         ));
                     }
@@ -522,12 +526,11 @@ pub fn eval_declaration_instantiation<'a>(
 
                     // b. If fnDefinable is false, throw a TypeError exception.
                     if !fn_definable {
-                        return Err(agent.throw_exception(
+                        let function_name = function_name.get(agent).bind(gc.nogc());
+                        return Err(agent.throw_exception_with_deferred_message(
                             ExceptionType::TypeError,
-                            format!(
-                                "Cannot declare global function '{}'.",
-                                function_name.as_str(agent)
-                            ),
+                            ErrorMessage::CannotDeclareGlobalFunction,
+                            [function_name.unbind().into()],
                             gc.into_nogc(),
                         ));
                     }
@@ -569,9 +572,11 @@ pub fn eval_declaration_instantiation<'a>(
                             .bind(gc.nogc());
                         // ii. If vnDefinable is false, throw a TypeError exception.
                         if !vn_definable {
-                            return Err(agent.throw_exception(
+                            let vn = vn.get(agent).bind(gc.nogc());
+                            return Err(agent.throw_exception_with_deferred_message(
                                 ExceptionType::TypeError,
-                                format!("Cannot declare global variable '{}'.", vn.as_str(agent)),
+                                ErrorMessage::CannotDeclareGlobalVariable,
+                                [vn.unbind().into()],
                                 gc.into_nogc(),
                             ));
                         }
@@ -847,9 +852,10 @@ impl GlobalObject {
             .bind(gc.nogc());
 
         // 2. Let trimmedString be ! TrimString(inputString, start).
-        let trimmed_string = input_string
-            .as_str(agent)
-            .trim_start_matches(is_trimmable_whitespace);
+        // NOTE: Lone surrogates are replaced with U+FFFD, which is not part of
+        // any StrDecimalLiteral.
+        let input_string = input_string.to_string_lossy(agent);
+        let trimmed_string = input_string.trim_start_matches(is_trimmable_whitespace);
 
         // 3. Let trimmed be StringToCodePoints(trimmedString).
         // 4. Let trimmedPrefix be the longest prefix of trimmed that satisfies the syntax of a StrDecimalLiteral, which might be trimmed itself. If there is no such prefix, return NaN.
@@ -952,7 +958,10 @@ impl GlobalObject {
         };
 
         // 2. Let S be ! TrimString(inputString, start).
-        let s = s.as_str(agent).trim_start_matches(is_trimmable_whitespace);
+        // NOTE: Lone surrogates are replaced with U+FFFD, which is not a
+        // radix digit.
+        let s = s.to_string_lossy(agent);
+        let s = s.trim_start_matches(is_trimmable_whitespace);
 
         // 3. Let sign be 1.
         // 4. If S is not empty and the first code unit of S is the code unit 0x002D (HYPHEN-MINUS), set sign to -1.
@@ -1283,7 +1292,7 @@ where
     // 1. Let strLen be the length of string.
     let str_len = string.utf16_len(agent);
     // 2. Let R be the empty String.
-    let mut r = std::string::String::with_capacity(string.len(agent));
+    let mut r = HeapStringBuilder::with_capacity(string.len(agent));
     let mut octets = Vec::with_capacity(4);
 
     // 3. Let k be 0.
@@ -1292,16 +1301,16 @@ where
     loop {
         // a. If k = strLen, return R.
         if k == str_len {
-            return Ok(String::from_string(agent, r, gc));
+            return Ok(r.finish(agent, gc));
         }
 
         // b. Let C be the code unit at index k within string.
-        let c = string.utf16_char(agent, k);
+        let c = string.utf16_code_unit(agent, k);
 
         // c. If C is not the code unit 0x0025 (PERCENT SIGN), then
-        if c != '%' {
+        if c != u16::from(b'%') {
             // i. Let S be the String value containing only the code unit C.
-            r.push(c);
+            r.push_code_point(CodePoint::from_u32(c.into()).unwrap());
         } else {
             // d. Else,
             // i. Let start be k.
@@ -1309,25 +1318,25 @@ where
 
             // ii. If k + 2 ≥ strLen, throw a URIError exception.
             if k + 2 >= str_len {
-                return Err(agent.throw_exception_with_static_message(
+                return agent.throw(
                     ExceptionType::UriError,
                     "invalid escape character found",
                     gc,
-                ));
+                );
             }
 
             // iii. If the code units at index (k + 1) and (k + 2) within string do not represent
             // hexadecimal digits, throw a URIError exception.
             // iv. Let B be the 8-bit value represented by the two hexadecimal digits at index (k + 1) and (k + 2).
             let Some(b) = decode_hex_byte(
-                string.utf16_char(agent, k + 1),
-                string.utf16_char(agent, k + 2),
+                string.utf16_code_unit(agent, k + 1),
+                string.utf16_code_unit(agent, k + 2),
             ) else {
-                return Err(agent.throw_exception_with_static_message(
+                return agent.throw(
                     ExceptionType::UriError,
                     "invalid hexadecimal digit found",
                     gc,
-                ));
+                );
             };
 
             // v. Set k to k + 2.
@@ -1347,28 +1356,26 @@ where
                 } else {
                     // 3. Else,
                     // a. Let S be the substring of string from start to k + 1.
-                    let start = string.utf8_index(agent, start).unwrap();
-                    let k = string.utf8_index(agent, k).unwrap();
-                    r.push_str(&string.as_str(agent)[start..=k])
+                    r.push_substring(agent, string, start, k + 1);
                 }
             } else {
                 // viii. Else,
                 // 1. If n = 1 or n > 4, throw a URIError exception.
                 if n == 1 || n > 4 {
-                    return Err(agent.throw_exception_with_static_message(
+                    return agent.throw(
                         ExceptionType::UriError,
                         "invalid escaped character found",
                         gc,
-                    ));
+                    );
                 }
 
                 // 2. If k + (3 × (n - 1)) ≥ strLen, throw a URIError exception.
                 if k + (3 * (n - 1)) > str_len {
-                    return Err(agent.throw_exception_with_static_message(
+                    return agent.throw(
                         ExceptionType::UriError,
                         "non-terminated escape character found",
                         gc,
-                    ));
+                    );
                 }
 
                 // 3. Let Octets be « B ».
@@ -1381,25 +1388,25 @@ where
                     k += 1;
 
                     // b. If the code unit at index k within string is not the code unit 0x0025 (PERCENT SIGN), throw a URIError exception.
-                    if string.utf16_char(agent, k) != '%' {
-                        return Err(agent.throw_exception_with_static_message(
+                    if string.utf16_code_unit(agent, k) != u16::from(b'%') {
+                        return agent.throw(
                             ExceptionType::UriError,
                             "escape characters must be preceded with a % sign",
                             gc,
-                        ));
+                        );
                     }
 
                     // c. If the code units at index (k + 1) and (k + 2) within string do not represent hexadecimal digits, throw a URIError exception.
                     // d. Let B be the 8-bit value represented by the two hexadecimal digits at index (k + 1) and (k + 2).
                     let Some(b) = decode_hex_byte(
-                        string.utf16_char(agent, k + 1),
-                        string.utf16_char(agent, k + 2),
+                        string.utf16_code_unit(agent, k + 1),
+                        string.utf16_code_unit(agent, k + 2),
                     ) else {
-                        return Err(agent.throw_exception_with_static_message(
+                        return agent.throw(
                             ExceptionType::UriError,
                             "invalid hexadecimal digit found",
                             gc,
-                        ));
+                        );
                     };
 
                     // e. Set k to k + 2.
@@ -1417,11 +1424,11 @@ where
                 // 7. If Octets does not contain a valid UTF-8 encoding of a Unicode code point, throw a URIError exception.
                 match std::str::from_utf8(&octets) {
                     Err(_) => {
-                        return Err(agent.throw_exception_with_static_message(
+                        return agent.throw(
                             ExceptionType::UriError,
                             "invalid UTF-8 encoding found",
                             gc,
-                        ));
+                        );
                     }
                     Ok(v) => {
                         // 8. Let V be the code point obtained by applying the UTF-8 transformation to Octets, that is, from a List of octets into a 21-bit value.
@@ -1446,7 +1453,7 @@ where
 /// Adapted from Boa JS engine. Source https://github.com/boa-dev/boa/blob/ced222fdbabacc695f8f081c5b009afc9be6b8d0/core/engine/src/builtins/uri/mod.rs#L514
 ///
 /// Copyright (c) 2019 Jason Williams
-fn decode_hex_byte(high: char, low: char) -> Option<u8> {
+fn decode_hex_byte(high: u16, low: u16) -> Option<u8> {
     match (
         char::from_u32(u32::from(high)),
         char::from_u32(u32::from(low)),
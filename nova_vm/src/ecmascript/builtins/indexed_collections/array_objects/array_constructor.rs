@@ -132,11 +132,7 @@ impl ArrayConstructor {
                 let int_len = to_uint32_number(agent, len);
                 // ii. If SameValueZero(intLen, len) is false, throw a RangeError exception.
                 if !same_value_zero(agent, int_len, len) {
-                    return Err(agent.throw_exception_with_static_message(
-                        ExceptionType::RangeError,
-                        "Invalid array length",
-                        gc,
-                    ));
+                    return agent.range_error("Invalid array length", gc);
                 }
                 let array = array_create(
                     agent,
@@ -144,8 +140,7 @@ impl ArrayConstructor {
                     int_len as usize,
                     proto.map(|p| p.get(agent)),
                     gc,
-                )
-                .unwrap();
+                )?;
                 // e. Perform ! Set(array, "length", intLen, true).
                 debug_assert_eq!(agent[array].elements.len(), int_len);
                 array
@@ -231,11 +226,10 @@ impl ArrayConstructor {
             // 3. Else,
             // a. If IsCallable(mapfn) is false, throw a TypeError exception.
             let Some(mapfn) = is_callable(mapfn, gc.nogc()) else {
-                return Err(agent.throw_exception_with_static_message(
-                    ExceptionType::TypeError,
+                return agent.type_error(
                     "The map function of Array.from is not callable",
                     gc.into_nogc(),
-                ));
+                );
             };
 
             // b. Let mapping be true.
@@ -144,6 +144,7 @@ impl Index<ArrayIterator<'_>> for Agent {
 
 impl IndexMut<ArrayIterator<'_>> for Agent {
     fn index_mut(&mut self, index: ArrayIterator) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.array_iterators[index]
     }
 }
@@ -17,21 +17,20 @@ use crate::{
     ecmascript::{
         abstract_operations::{
             operations_on_iterator_objects::create_iter_result_object,
-            operations_on_objects::{create_array_from_list, get, length_of_array_like},
+            operations_on_objects::{create_entry_pair, get, length_of_array_like},
         },
         builders::ordinary_object_builder::OrdinaryObjectBuilder,
         builtins::{
             ArgumentsList, Behaviour, Builtin, array::ARRAY_INDEX_RANGE,
             indexed_collections::array_objects::array_iterator_objects::array_iterator::CollectionIteratorKind,
         },
-        execution::{Agent, JsResult, Realm, agent::ExceptionType},
+        execution::{Agent, JsResult, Realm},
         types::{BUILTIN_STRING_MEMORY, IntoValue, Object, String, Value},
     },
     engine::{
         context::{Bindable, GcScope},
         rootable::Scopable,
     },
-    heap::WellKnownSymbolIndexes,
 };
 
 pub(crate) struct ArrayIteratorPrototype;
@@ -56,11 +55,7 @@ impl ArrayIteratorPrototype {
         // 27.5.3.2 GeneratorValidate ( generator, generatorBrand )
         // 3. If generator.[[GeneratorBrand]] is not generatorBrand, throw a TypeError exception.
         let Value::ArrayIterator(iterator) = this_value else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "ArrayIterator expected",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("ArrayIterator expected", gc.into_nogc());
         };
         let mut iterator = iterator.bind(gc.nogc());
 
@@ -126,11 +121,7 @@ impl ArrayIteratorPrototype {
                         is_typed_array_out_of_bounds::<f64>(agent, &ta_record, nogc)
                     }
                 } {
-                    return Err(agent.throw_exception_with_static_message(
-                        ExceptionType::TypeError,
-                        "TypedArray out of bounds",
-                        gc.into_nogc(),
-                    ));
+                    return agent.type_error("TypedArray out of bounds", gc.into_nogc());
                 }
 
                 // 3. Let len be TypedArrayLength(taRecord).
@@ -267,12 +258,8 @@ impl ArrayIteratorPrototype {
                 };
                 // a. Assert: kind is key+value.
                 // b. Let result be CreateArrayFromList(« indexNumber, elementValue »).
-                create_array_from_list(
-                    agent,
-                    &[index.try_into().unwrap(), value.unbind()],
-                    gc.nogc(),
-                )
-                .into_value()
+                create_entry_pair(agent, index.try_into().unwrap(), value.unbind(), gc.nogc())
+                    .into_value()
             }
         };
 
@@ -289,14 +276,7 @@ impl ArrayIteratorPrototype {
             .with_property_capacity(2)
             .with_prototype(iterator_prototype)
             .with_builtin_function_property::<ArrayIteratorPrototypeNext>()
-            .with_property(|builder| {
-                builder
-                    .with_key(WellKnownSymbolIndexes::ToStringTag.into())
-                    .with_value_readonly(BUILTIN_STRING_MEMORY.Array_Iterator.into_value())
-                    .with_enumerable(false)
-                    .with_configurable(true)
-                    .build()
-            })
+            .with_to_string_tag(BUILTIN_STRING_MEMORY.Array_Iterator)
             .build();
     }
 }
@@ -29,16 +29,13 @@ use crate::{
         },
         builders::ordinary_object_builder::OrdinaryObjectBuilder,
         builtins::{
-            ArgumentsList, ArrayHeapData, Behaviour, Builtin, BuiltinIntrinsic, array_create,
-            array_species_create,
-        },
-        execution::{
-            Agent, JsResult, Realm,
-            agent::{ExceptionType, JsError},
+            ArgumentsList, ArrayHeapData, Behaviour, Builtin, BuiltinIntrinsic,
+            argument_coercion::to_object_this, array_create, array_species_create,
         },
+        execution::{Agent, JsResult, Realm, SlowPathReason, agent::JsError},
         types::{
-            BUILTIN_STRING_MEMORY, Function, IntoFunction, IntoObject, IntoValue, Number, Object,
-            PropertyKey, String, Value,
+            BUILTIN_STRING_MEMORY, Function, HeapStringBuilder, IntoFunction, IntoObject,
+            IntoValue, Number, Object, PropertyKey, String, Value,
         },
     },
     heap::{Heap, IntrinsicFunctionIndexes, WellKnownSymbolIndexes},
@@ -150,6 +147,9 @@ impl Builtin for ArrayPrototypeJoin {
     const LENGTH: u8 = 1;
     const BEHAVIOUR: Behaviour = Behaviour::Regular(ArrayPrototype::join);
 }
+impl BuiltinIntrinsic for ArrayPrototypeJoin {
+    const INDEX: IntrinsicFunctionIndexes = IntrinsicFunctionIndexes::ArrayPrototypeJoin;
+}
 struct ArrayPrototypeKeys;
 impl Builtin for ArrayPrototypeKeys {
     const NAME: String<'static> = BUILTIN_STRING_MEMORY.keys;
@@ -299,7 +299,7 @@ impl ArrayPrototype {
         let mut index = arguments.get(0).bind(nogc);
 
         // 1. Let O be ? ToObject(this value).
-        let mut o = to_object(agent, this_value, gc.nogc())
+        let mut o = to_object_this(agent, this_value, "Array.prototype.at", gc.nogc())
             .unbind()?
             .bind(gc.nogc());
         let mut scoped_o = None;
@@ -397,10 +397,12 @@ impl ArrayPrototype {
                     total_len = item.len(agent);
                 }
             });
-            let Heap {
-                arrays, elements, ..
-            } = &mut agent.heap;
-            arrays[a].elements.reserve(elements, total_len);
+            if agent.is_valid_array_element_capacity(total_len.into()) {
+                let Heap {
+                    arrays, elements, ..
+                } = &mut agent.heap;
+                arrays[a].elements.reserve(elements, total_len);
+            }
         }
 
         let a = a.scope(agent, gc.nogc());
@@ -429,11 +431,7 @@ impl ArrayPrototype {
                     length_of_array_like(agent, spreadable_e.unbind(), gc.reborrow()).unbind()?;
                 // ii. If n + len > 2**53 - 1, throw a TypeError exception.
                 if (n + len) > SmallInteger::MAX {
-                    return Err(agent.throw_exception_with_static_message(
-                        ExceptionType::TypeError,
-                        "Array overflow",
-                        gc.into_nogc(),
-                    ));
+                    return agent.type_error("Array overflow", gc.into_nogc());
                 }
                 // iii. Let k be 0.
                 let mut k = 0;
@@ -480,11 +478,7 @@ impl ArrayPrototype {
                 // i. NOTE: E is added as a single item rather than spread.
                 // ii. If n ≥ 2**53 - 1, throw a TypeError exception.
                 if n >= SmallInteger::MAX {
-                    return Err(agent.throw_exception_with_static_message(
-                        ExceptionType::TypeError,
-                        "Array overflow",
-                        gc.into_nogc(),
-                    ));
+                    return agent.type_error("Array overflow", gc.into_nogc());
                 }
                 // iii. Perform ? CreateDataPropertyOrThrow(A, ! ToString(𝔽(n)), E).
                 create_data_property_or_throw(
@@ -722,11 +716,7 @@ impl ArrayPrototype {
     ) -> JsResult<'gc, Value<'gc>> {
         // 1. Let O be ? ToObject(this value).
         let Ok(o) = Object::try_from(this_value) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Expected this to be an object",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Expected this to be an object", gc.into_nogc());
         };
         // 2. Return CreateArrayIterator(O, key+value).
         Ok(ArrayIterator::from_object(agent, o, CollectionIteratorKind::KeyAndValue).into_value())
@@ -787,11 +777,7 @@ impl ArrayPrototype {
         let len = length_of_array_like(agent, o.get(agent), gc.reborrow()).unbind()?;
         // 3. If IsCallable(callbackfn) is false, throw a TypeError exception.
         let Some(stack_callback_fn) = is_callable(callback_fn.get(agent), gc.nogc()) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Callback is not a function",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Callback is not a function", gc.into_nogc());
         };
         // SAFETY: callback_fn never escapes this call.
         let callback_fn = unsafe { callback_fn.replace_self(agent, stack_callback_fn.unbind()) };
@@ -899,16 +885,20 @@ impl ArrayPrototype {
                     len
                 };
 
-                let data = array.as_mut_slice(agent);
-                data[k..final_end].fill(Some(value.unbind()));
-                return Ok(value.into_value().unbind());
+                if k < final_end {
+                    let data = array.as_mut_slice(agent);
+                    data[k..final_end].fill(Some(value.unbind()));
+                }
+                return Ok(array.into_value().unbind());
+            } else {
+                agent.report_slow_path(SlowPathReason::ElementsNotDense);
             }
         };
         let value = value.scope(agent, nogc);
         let start = start.scope(agent, nogc);
         let end = end.scope(agent, nogc);
         // 1. Let O be ? ToObject(this value).
-        let o = to_object(agent, this_value, nogc)
+        let o = to_object_this(agent, this_value, "Array.prototype.fill", nogc)
             .unbind()?
             .scope(agent, nogc);
         // 2. Let len be ? LengthOfArrayLike(O).
@@ -1020,11 +1010,7 @@ impl ArrayPrototype {
         let len = length_of_array_like(agent, o.get(agent), gc.reborrow()).unbind()?;
         // 3. If IsCallable(callbackfn) is false, throw a TypeError exception.
         let Some(stack_callback_fn) = is_callable(callback_fn.get(agent), gc.nogc()) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Callback function is not callable",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Callback function is not callable", gc.into_nogc());
         };
         // SAFETY: callback_fn never escapes this call.
         let callback_fn = unsafe { callback_fn.replace_self(agent, stack_callback_fn.unbind()) };
@@ -1288,11 +1274,7 @@ impl ArrayPrototype {
             length_of_array_like(agent, o.get(agent), gc.reborrow()).unbind()? as usize;
         // 3. If IsCallable(mapperFunction) is false, throw a TypeError exception.
         let Some(stack_mapper_function) = is_callable(mapper_function.get(agent), gc.nogc()) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Mapper function is not callable",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Mapper function is not callable", gc.into_nogc());
         };
         // SAFETY: callback_fn is not shared.
         let mapper_function =
@@ -1373,11 +1355,7 @@ impl ArrayPrototype {
 
         // 3. If IsCallable(callbackfn) is false, throw a TypeError exception.
         let Some(stack_callback_fn) = is_callable(callback_fn.get(agent), gc.nogc()) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Callback function is not a function",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Callback function is not a function", gc.into_nogc());
         };
         // SAFETY: callback_fn is not shared.
         let callback_fn = unsafe { callback_fn.replace_self(agent, stack_callback_fn.unbind()) };
@@ -1503,7 +1481,7 @@ impl ArrayPrototype {
         let from_index = from_index.scope(agent, nogc);
         let search_element = search_element.scope(agent, nogc);
         // 1. Let O be ? ToObject(this value).
-        let o = to_object(agent, this_value, gc.nogc())
+        let o = to_object_this(agent, this_value, "Array.prototype.includes", gc.nogc())
             .unbind()?
             .scope(agent, gc.nogc());
         // 2. Let len be ? LengthOfArrayLike(O).
@@ -1635,7 +1613,7 @@ impl ArrayPrototype {
         let from_index = from_index.scope(agent, nogc);
         let search_element = search_element.scope(agent, nogc);
         // 1. Let O be ? ToObject(this value).
-        let o = to_object(agent, this_value, gc.nogc())
+        let o = to_object_this(agent, this_value, "Array.prototype.indexOf", gc.nogc())
             .unbind()?
             .scope(agent, gc.nogc());
         // 2. Let len be ? LengthOfArrayLike(O).
@@ -1700,6 +1678,13 @@ impl ArrayPrototype {
     /// This method converts the elements of the array to Strings, and then
     /// concatenates these Strings, separated by occurrences of the separator.
     /// If no separator is provided, a single comma is used as the separator.
+    ///
+    /// > Note
+    /// >
+    /// > Joining an object that is already being joined gives the empty
+    /// > String, so that cyclic arrays do not recurse forever. Nested Arrays
+    /// > that would be joined with this method are joined in place rather
+    /// > than recursively, so deep nesting does not exhaust the native stack.
     fn join<'gc>(
         agent: &mut Agent,
         this_value: Value,
@@ -1714,11 +1699,11 @@ impl ArrayPrototype {
         let o = to_object(agent, this_value, nogc)
             .unbind()?
             .scope(agent, nogc);
-        // 2. Let len be ? LengthOfArrayLike(O).
-        let len = length_of_array_like(agent, o.get(agent), gc.reborrow()).unbind()?;
-        if len == 0 {
+        if agent.is_being_joined(o.get(agent)) {
             return Ok(String::EMPTY_STRING.into_value());
         }
+        // 2. Let len be ? LengthOfArrayLike(O).
+        let len = length_of_array_like(agent, o.get(agent), gc.reborrow()).unbind()?;
         let len = len as usize;
         // 3. If separator is undefined, let sep be ",".
         let separator = if separator.get(agent).is_undefined() {
@@ -1732,50 +1717,37 @@ impl ArrayPrototype {
             // Note: Separator is likely a small string so this is a very cheap.
             unsafe { separator.replace_self(agent, sep.unbind()) }
         };
+        if len == 0 {
+            return Ok(String::EMPTY_STRING.into_value());
+        }
+        // Note: The separators alone may already make the result too long.
+        let separator_length = separator.get(agent).len(agent);
+        let Some(separators_length) = (len - 1).checked_mul(separator_length) else {
+            return agent.range_error("Invalid string length", gc.into_nogc());
+        };
+        agent
+            .check_string_length(separators_length, gc.nogc())
+            .unbind()?;
         // 5. Let R be the empty String.
-        let mut r = std::string::String::with_capacity(len * 10);
+        // Note: Sparse array-likes can be very long while joining into a
+        // short string, so the initial capacity is bounded.
+        let capacity = separators_length.saturating_add(len.saturating_mul(4));
+        let mut r = HeapStringBuilder::with_capacity(capacity.min(1 << 20));
+        let join_stack_depth = agent.join_stack.len();
+        agent.join_stack.push(o.get(agent).unbind());
+        let frame = JoinFrame {
+            object: o,
+            separator,
+            len,
+            k: 0,
+        };
         // 6. Let k be 0.
         // 7. Repeat, while k < len,
-        // b. Let element be ? Get(O, ! ToString(𝔽(k))).
-        {
-            let element = get(agent, o.get(agent), 0.into(), gc.reborrow())
-                .unbind()?
-                .bind(gc.nogc());
-            // c. If element is neither undefined nor null, then
-            if !element.is_undefined() && !element.is_null() {
-                // i. Let S be ? ToString(element).
-                let s = to_string(agent, element.unbind(), gc.reborrow())
-                    .unbind()?
-                    .bind(gc.nogc());
-                // ii. Set R to the string-concatenation of R and S.
-                r.push_str(s.as_str(agent));
-            }
-        }
-        for k in 1..len {
-            // a. If k > 0, set R to the string-concatenation of R and sep.
-            r.push_str(separator.get(agent).as_str(agent));
-            // b. Let element be ? Get(O, ! ToString(𝔽(k))).
-            let element = get(
-                agent,
-                o.get(agent),
-                SmallInteger::try_from(k as u64).unwrap().into(),
-                gc.reborrow(),
-            )
-            .unbind()?
-            .bind(gc.nogc());
-            // c. If element is neither undefined nor null, then
-            if !element.is_undefined() && !element.is_null() {
-                // i. Let S be ? ToString(element).
-                let s = to_string(agent, element.unbind(), gc.reborrow())
-                    .unbind()?
-                    .bind(gc.nogc());
-                // ii. Set R to the string-concatenation of R and S.
-                r.push_str(s.as_str(agent));
-            }
-            // d. Set k to k + 1.
-        }
+        let result = join_elements(agent, frame, &mut r, gc.reborrow()).unbind();
+        agent.join_stack.truncate(join_stack_depth);
+        result?;
         // 8. Return R.
-        Ok(Value::from_string(agent, r, gc.into_nogc()).into_value())
+        Ok(r.finish(agent, gc.into_nogc()).into_value())
     }
 
     fn keys<'gc>(
@@ -1786,11 +1758,7 @@ impl ArrayPrototype {
     ) -> JsResult<'gc, Value<'gc>> {
         // 1. Let O be ? ToObject(this value).
         let Ok(o) = Object::try_from(this_value) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Expected this to be an object",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Expected this to be an object", gc.into_nogc());
         };
         // 2. Return CreateArrayIterator(O, key).
         Ok(ArrayIterator::from_object(agent, o, CollectionIteratorKind::Key).into_value())
@@ -1844,8 +1812,13 @@ impl ArrayPrototype {
                 if n >= 0 {
                     (n as usize).min(last)
                 } else {
+                    // Note: A fromIndex before the start of the array
+                    // searches nothing.
                     let result = len as i64 + n;
-                    if result < 0 { 0 } else { result as usize }
+                    if result < 0 {
+                        return Ok((-1).into());
+                    }
+                    result as usize
                 }
             } else if from_index == Some(Value::Undefined) {
                 0
@@ -1874,7 +1847,7 @@ impl ArrayPrototype {
         let from_index = from_index.map(|i| i.scope(agent, nogc));
         let search_element = search_element.scope(agent, nogc);
         // 1. Let O be ? ToObject(this value).
-        let o = to_object(agent, this_value, nogc)
+        let o = to_object_this(agent, this_value, "Array.prototype.lastIndexOf", nogc)
             .unbind()?
             .scope(agent, nogc);
         // 2. Let len be ? LengthOfArrayLike(O).
@@ -1980,11 +1953,7 @@ impl ArrayPrototype {
         let len = length_of_array_like(agent, o.get(agent), gc.reborrow()).unbind()?;
         // 3. If IsCallable(callbackfn) is false, throw a TypeError exception.
         let Some(stack_callback_fn) = is_callable(callback_fn.get(agent), gc.nogc()) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Callback function is not a function",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Callback function is not a function", gc.into_nogc());
         };
         // SAFETY: callback_fn is not shared.
         let callback_fn = unsafe { callback_fn.replace_self(agent, stack_callback_fn.unbind()) };
@@ -2062,11 +2031,7 @@ impl ArrayPrototype {
                 let length_writable = agent[array].elements.len_writable;
                 if len == 0 {
                     return if !length_writable {
-                        Err(agent.throw_exception_with_static_message(
-                            ExceptionType::TypeError,
-                            "Could not set property.",
-                            gc.into_nogc(),
-                        ))
+                        agent.type_error("Could not set property.", gc.into_nogc())
                     } else {
                         Ok(Value::Undefined)
                     };
@@ -2078,11 +2043,7 @@ impl ArrayPrototype {
                     if length_writable {
                         agent[array].elements.len -= 1;
                     } else {
-                        return Err(agent.throw_exception_with_static_message(
-                            ExceptionType::TypeError,
-                            "Could not set property.",
-                            gc.into_nogc(),
-                        ));
+                        return agent.type_error("Could not set property.", gc.into_nogc());
                     }
                     return Ok(last_element);
                 }
@@ -2167,20 +2128,17 @@ impl ArrayPrototype {
         let arg_count = items.len();
         // 4. If len + argCount > 2**53 - 1, throw a TypeError exception.
         if (len + arg_count as i64) > SmallInteger::MAX {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Array length overflow",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Array length overflow", gc.into_nogc());
         }
-        if let Object::Array(array) = o.get(agent) {
+        let new_len = len as u64 + arg_count as u64;
+        if let (Object::Array(array), true) =
+            (o.get(agent), agent.is_valid_array_element_capacity(new_len))
+        {
             // Fast path: Reserve enough room in the array.
             let Heap {
                 arrays, elements, ..
             } = &mut agent.heap;
-            arrays[array]
-                .elements
-                .reserve(elements, len as u32 + arg_count as u32);
+            arrays[array].elements.reserve(elements, new_len as u32);
         }
         // 5. For each element E of items, do
         for e in items.iter() {
@@ -2273,22 +2231,17 @@ impl ArrayPrototype {
 
         // 3. If IsCallable(callbackfn) is false, throw a TypeError exception.
         let Some(stack_callback_fn) = is_callable(callback_fn.get(agent), gc.nogc()) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Callback function is not a function",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Callback function is not a function", gc.into_nogc());
         };
         // SAFETY: callback_fn is not shared.
         let callback_fn = unsafe { callback_fn.replace_self(agent, stack_callback_fn.unbind()) };
 
         // 4. If len = 0 and initialValue is not present, throw a TypeError exception.
         if len == 0 && initial_value.is_none() {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
+            return agent.type_error(
                 "Array length is 0 and no initial value provided",
                 gc.into_nogc(),
-            ));
+            );
         }
 
         // 5. Let k be 0.
@@ -2328,11 +2281,10 @@ impl ArrayPrototype {
 
             // c. If kPresent is false, throw a TypeError exception.
             if !k_present {
-                return Err(agent.throw_exception_with_static_message(
-                    ExceptionType::TypeError,
+                return agent.type_error(
                     "Array length is 0 and no initial value provided",
                     gc.into_nogc(),
-                ));
+                );
             }
         }
 
@@ -2440,22 +2392,17 @@ impl ArrayPrototype {
 
         // 3. If IsCallable(callbackfn) is false, throw a TypeError exception.
         let Some(stack_callback_fn) = is_callable(callback_fn.get(agent), gc.nogc()) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Callback function is not a function",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Callback function is not a function", gc.into_nogc());
         };
         // SAFETY: callback_fn is not shared outside this call.
         let callback_fn = unsafe { callback_fn.replace_self(agent, stack_callback_fn.unbind()) };
 
         // 4. If len = 0 and initialValue is not present, throw a TypeError exception.
         if len == 0 && initial_value.is_none() {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
+            return agent.type_error(
                 "Array length is 0 and no initial value provided",
                 gc.into_nogc(),
-            ));
+            );
         }
 
         // 5. Let k be len - 1.
@@ -2495,11 +2442,10 @@ impl ArrayPrototype {
 
             // c. If kPresent is false, throw a TypeError exception.
             if !k_present {
-                return Err(agent.throw_exception_with_static_message(
-                    ExceptionType::TypeError,
+                return agent.type_error(
                     "Array length is 0 and no initial value provided",
                     gc.into_nogc(),
-                ));
+                );
             }
         }
 
@@ -2558,6 +2504,8 @@ impl ArrayPrototype {
             if array.is_trivial(agent) && array.is_dense(agent) {
                 array.as_mut_slice(agent).reverse();
                 return Ok(array.into_value().unbind());
+            } else {
+                agent.report_slow_path(SlowPathReason::ElementsNotDense);
             }
         }
 
@@ -2736,6 +2684,8 @@ impl ArrayPrototype {
                     .unbind()?;
                     unreachable!();
                 }
+            } else {
+                agent.report_slow_path(SlowPathReason::ElementsNotDense);
             }
         }
         // 1. Let O be ? ToObject(this value).
@@ -2960,6 +2910,8 @@ impl ArrayPrototype {
                 .unbind()?;
                 // 16. Return A.
                 return Ok(a.get(agent).into_value());
+            } else {
+                agent.report_slow_path(SlowPathReason::ElementsNotDense);
             }
         }
         let start = start.scope(agent, nogc);
@@ -3108,11 +3060,7 @@ impl ArrayPrototype {
         let len = length_of_array_like(agent, o.get(agent), gc.reborrow()).unbind()?;
         // 3. If IsCallable(callbackfn) is false, throw a TypeError exception.
         let Some(stack_callback_fn) = is_callable(callback_fn.get(agent), gc.nogc()) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Callback function is not callable",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Callback function is not callable", gc.into_nogc());
         };
         // SAFETY: callback_fn is never shared before this call.
         let callback_fn = unsafe { callback_fn.replace_self(agent, stack_callback_fn.unbind()) };
@@ -3191,11 +3139,7 @@ impl ArrayPrototype {
         } else if let Some(comparator) = is_callable(comparator, gc.nogc()) {
             Some(comparator.scope(agent, gc.nogc()))
         } else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("", gc.into_nogc());
         };
         // 2. Let obj be ? ToObject(this value).
         let obj = to_object(agent, this_value, gc.nogc())
@@ -3304,11 +3248,7 @@ impl ArrayPrototype {
         };
         // 11. If len + itemCount - actualDeleteCount > 2**53 - 1, throw a TypeError exception.
         if len as usize + item_count - actual_delete_count > SmallInteger::MAX as usize {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Target index overflowed",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Target index overflowed", gc.into_nogc());
         }
         // 12. Let A be ? ArraySpeciesCreate(O, actualDeleteCount).
         let a = array_species_create(agent, o.get(agent), actual_delete_count, gc.reborrow())
@@ -3495,6 +3435,8 @@ impl ArrayPrototype {
                 let cloned_array = array.to_cloned(agent);
                 cloned_array.as_mut_slice(agent).reverse();
                 return Ok(cloned_array.into_value());
+            } else {
+                agent.report_slow_path(SlowPathReason::ElementsNotDense);
             }
         }
 
@@ -3551,11 +3493,10 @@ impl ArrayPrototype {
         } else if let Some(comparator) = is_callable(comparator, gc.nogc()) {
             Some(comparator.scope(agent, gc.nogc()))
         } else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
+            return agent.type_error(
                 "The comparison function must be either a function or undefined",
                 gc.into_nogc(),
-            ));
+            );
         };
         // 2. Let o be ? ToObject(this value).
         let o = to_object(agent, this_value, gc.nogc())
@@ -3656,11 +3597,7 @@ impl ArrayPrototype {
         // 11. Let newLen be len + insertCount - actualSkipCount.
         let new_len = len + insert_count - actual_skip_count;
         if new_len > SmallInteger::MAX as usize {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Target index overflowed",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Target index overflowed", gc.into_nogc());
         };
         // 13. Let A be ? ArrayCreate(newLen).
         let a = array_create(agent, new_len, new_len, None, gc.nogc())
@@ -3799,7 +3736,8 @@ impl ArrayPrototype {
             let len = array.len(agent);
             let arg_count = items.len();
             let final_len = u32::try_from(len as u64 + arg_count as u64);
-            if final_len.is_ok()
+            if final_len
+                .is_ok_and(|final_len| agent.is_valid_array_element_capacity(final_len.into()))
                 && array.is_trivial(agent)
                 && array.is_dense(agent)
                 && array.length_writable(agent)
@@ -3838,11 +3776,7 @@ impl ArrayPrototype {
         if arg_count > 0 {
             // a. If len + argCount > 2**53 - 1, throw a TypeError exception.
             if (len + arg_count as i64) > SmallInteger::MAX {
-                return Err(agent.throw_exception_with_static_message(
-                    ExceptionType::TypeError,
-                    "Array length overflow",
-                    gc.into_nogc(),
-                ));
+                return agent.type_error("Array length overflow", gc.into_nogc());
             }
             // b. Let k be len.
             let mut k = len;
@@ -3919,11 +3853,7 @@ impl ArrayPrototype {
     ) -> JsResult<'gc, Value<'gc>> {
         // 1. Let O be ? ToObject(this value).
         let Ok(o) = Object::try_from(this_value) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Expected this to be an object",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Expected this to be an object", gc.into_nogc());
         };
         // 2. Return CreateArrayIterator(O, value).
         Ok(ArrayIterator::from_object(agent, o, CollectionIteratorKind::Value).into_value())
@@ -3952,20 +3882,18 @@ impl ArrayPrototype {
                     len + relative_index
                 };
                 if actual_index >= len || actual_index < 0 {
-                    return Err(agent.throw_exception_with_static_message(
-                        ExceptionType::RangeError,
-                        "invalid or out-of-range index",
-                        gc.into_nogc(),
-                    ));
+                    return agent.range_error("invalid or out-of-range index", gc.into_nogc());
                 }
                 // Fast path: Set new value in cloned array.
                 let cloned_array = array.to_cloned(agent);
                 cloned_array.as_mut_slice(agent)[actual_index as usize] = Some(value.unbind());
                 return Ok(cloned_array.into_value().unbind().bind(gc.into_nogc()));
+            } else {
+                agent.report_slow_path(SlowPathReason::ElementsNotDense);
             }
         }
         // 1. Let O be ? ToObject(this value).
-        let o = to_object(agent, this_value, nogc)
+        let o = to_object_this(agent, this_value, "Array.prototype.with", nogc)
             .unbind()?
             .scope(agent, nogc);
         let index = index.scope(agent, nogc);
@@ -3985,11 +3913,7 @@ impl ArrayPrototype {
         };
         // 6. If actualIndex ≥ len or actualIndex < 0, throw a RangeError exception.
         if actual_index >= len || actual_index < 0 {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::RangeError,
-                "invalid or out-of-range index",
-                gc.into_nogc(),
-            ));
+            return agent.range_error("invalid or out-of-range index", gc.into_nogc());
         }
         // 7. Let A be ? ArrayCreate(len).
         let a = array_create(agent, len as usize, len as usize, None, gc.nogc())
@@ -4054,7 +3978,7 @@ impl ArrayPrototype {
             .with_builtin_function_property::<ArrayPrototypeForEach>()
             .with_builtin_function_property::<ArrayPrototypeIncludes>()
             .with_builtin_function_property::<ArrayPrototypeIndexOf>()
-            .with_builtin_function_property::<ArrayPrototypeJoin>()
+            .with_builtin_intrinsic_function_property::<ArrayPrototypeJoin>()
             .with_builtin_function_property::<ArrayPrototypeKeys>()
             .with_builtin_function_property::<ArrayPrototypeLastIndexOf>()
             .with_builtin_function_property::<ArrayPrototypeMap>()
@@ -4119,7 +4043,6 @@ impl ArrayPrototype {
                             .into_value()
                     })
                     .with_enumerable(false)
-                    .with_configurable(false)
                     .build()
             })
             .build();
@@ -4136,6 +4059,88 @@ impl ArrayPrototype {
     }
 }
 
+/// An object whose elements are being joined by Array.prototype.join.
+struct JoinFrame<'scope> {
+    object: Scoped<'scope, Object<'static>>,
+    separator: Scoped<'scope, String<'static>>,
+    len: usize,
+    k: usize,
+}
+
+/// Performs the element loop of Array.prototype.join, appending the joined
+/// elements of `frame` to `r`.
+///
+/// Arrays whose ToString would call the intrinsic join method are joined in
+/// place by pushing a new frame, and their object is added to the Agent's
+/// join stack. Arrays already on the join stack contribute the empty String.
+/// The caller is responsible for truncating the join stack afterwards.
+fn join_elements<'gc, 'scope>(
+    agent: &mut Agent,
+    frame: JoinFrame<'scope>,
+    r: &mut HeapStringBuilder,
+    mut gc: GcScope<'gc, 'scope>,
+) -> JsResult<'gc, ()> {
+    let mut frames = vec![frame];
+    while let Some(frame) = frames.last_mut() {
+        if frame.k == frame.len {
+            frames.pop();
+            agent.join_stack.pop();
+            continue;
+        }
+        let k = frame.k;
+        // d. Set k to k + 1.
+        frame.k += 1;
+        // a. If k > 0, set R to the string-concatenation of R and sep.
+        if k > 0 {
+            let sep = frame.separator.get(agent);
+            agent
+                .check_string_length(r.len() + sep.len(agent), gc.nogc())
+                .unbind()?;
+            r.push_string(agent, sep);
+        }
+        // b. Let element be ? Get(O, ! ToString(𝔽(k))).
+        let element = get(
+            agent,
+            frame.object.get(agent),
+            SmallInteger::try_from(k as u64).unwrap().into(),
+            gc.reborrow(),
+        )
+        .unbind()?
+        .bind(gc.nogc());
+        // c. If element is neither undefined nor null, then
+        if element.is_undefined() || element.is_null() {
+            continue;
+        }
+        if let Value::Array(array) = element {
+            if array.uses_intrinsic_join(agent, gc.nogc()) {
+                let object = array.into_object();
+                if agent.is_being_joined(object) {
+                    continue;
+                }
+                let len = array.len(agent) as usize;
+                agent.join_stack.push(object.unbind());
+                frames.push(JoinFrame {
+                    object: object.scope(agent, gc.nogc()),
+                    separator: String::from_small_string(",").scope_static(),
+                    len,
+                    k: 0,
+                });
+                continue;
+            }
+        }
+        // i. Let S be ? ToString(element).
+        let s = to_string(agent, element.unbind(), gc.reborrow())
+            .unbind()?
+            .bind(gc.nogc());
+        // ii. Set R to the string-concatenation of R and S.
+        agent
+            .check_string_length(r.len() + s.len(agent), gc.nogc())
+            .unbind()?;
+        r.push_string(agent, s);
+    }
+    Ok(())
+}
+
 /// ### [23.1.3.2.1 IsConcatSpreadable ( O )](https://tc39.es/ecma262/#sec-isconcatspreadable)
 ///
 /// The abstract operation IsConcatSpreadable takes argument O (an ECMAScript
@@ -4224,11 +4229,7 @@ pub(crate) fn find_via_predicate<'gc, T: 'static + Rootable + InternalMethods<'s
 ) -> JsResult<'gc, (i64, Value<'gc>)> {
     // 1. If IsCallable(predicate) is false, throw a TypeError exception.
     let Some(stack_predicate) = is_callable(predicate.get(agent), gc.nogc()) else {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Predicate is not a function",
-            gc.into_nogc(),
-        ));
+        return agent.type_error("Predicate is not a function", gc.into_nogc());
     };
     // SAFETY: We're only ever called in a way that gives ownership of
     // predicate to us.
@@ -4411,11 +4412,7 @@ fn flatten_into_array<'a>(
             // vi. Else,
             // 1. If targetIndex ≥ 2**53 - 1, throw a TypeError exception.
             if target_index >= SmallInteger::MAX as usize {
-                return Err(agent.throw_exception_with_static_message(
-                    ExceptionType::TypeError,
-                    "Target index overflowed",
-                    gc.into_nogc(),
-                ));
+                return agent.type_error("Target index overflowed", gc.into_nogc());
             }
             // 2. Perform ? CreateDataPropertyOrThrow(target, ! ToString(𝔽(targetIndex)), element).
             create_data_property_or_throw(
@@ -4635,6 +4632,6 @@ fn compare_array_elements<'a>(
         // 9. Let ySmaller be ! IsLessThan(yString, xString, true).
         // 10. If ySmaller is true, return 1𝔽.
         // 11. Return +0𝔽.
-        Ok(x.as_str(agent).cmp(y.as_str(agent)))
+        Ok(String::cmp_code_units(agent, x, y))
     }
 }
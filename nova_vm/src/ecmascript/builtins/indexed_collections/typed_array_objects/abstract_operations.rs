@@ -27,7 +27,7 @@ use crate::{
                 data::{TypedArrayArrayLength, TypedArrayHeapData},
             },
         },
-        execution::{Agent, JsResult, ProtoIntrinsics, agent::ExceptionType},
+        execution::{Agent, JsResult, ProtoIntrinsics},
         types::{
             BigInt, Function, InternalSlots, IntoFunction, IntoNumeric, IntoObject, IntoValue,
             Number, Numeric, Object, PropertyKey, U8Clamped, Value, Viewable,
@@ -672,11 +672,7 @@ pub(crate) fn validate_typed_array<'a>(
     let ta_record = make_typed_array_with_buffer_witness_record(agent, o, order, gc);
     // 4. If IsTypedArrayOutOfBounds(taRecord) is true, throw a TypeError exception.
     if with_typed_array_viewable!(o, is_typed_array_out_of_bounds::<T>(agent, &ta_record, gc)) {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "TypedArray out of bounds",
-            gc,
-        ));
+        return agent.type_error("TypedArray out of bounds", gc);
     }
 
     // 5. Return taRecord.
@@ -764,11 +760,7 @@ pub(crate) fn initialize_typed_array_from_typed_array<'a, O: Viewable, Src: View
 
     // 8. If IsTypedArrayOutOfBounds(srcRecord) is true, throw a TypeError exception.
     if is_typed_array_out_of_bounds::<Src>(agent, &src_record, gc) {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "TypedArray out of bounds",
-            gc,
-        ));
+        return agent.type_error("TypedArray out of bounds", gc);
     }
 
     // 9. Let elementLength be TypedArrayLength(srcRecord).
@@ -795,11 +787,7 @@ pub(crate) fn initialize_typed_array_from_typed_array<'a, O: Viewable, Src: View
 
         // b. If srcArray.[[ContentType]] is not O.[[ContentType]], throw a TypeError exception.
         if O::IS_BIGINT != Src::IS_BIGINT {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "TypedArray content type mismatch",
-                gc,
-            ));
+            return agent.type_error("TypedArray content type mismatch", gc);
         }
 
         // c. Let srcByteIndex be srcByteOffset.
@@ -896,11 +884,10 @@ pub(crate) fn initialize_typed_array_from_array_buffer<'a, T: Viewable>(
 
     // 3. If offset modulo elementSize ≠ 0, throw a RangeError exception.
     if offset % element_size != 0 {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::RangeError,
+        return agent.range_error(
             "offset is not a multiple of the element size",
             gc.into_nogc(),
-        ));
+        );
     }
 
     let buffer = scoped_buffer.get(agent).bind(gc.nogc());
@@ -923,11 +910,7 @@ pub(crate) fn initialize_typed_array_from_array_buffer<'a, T: Viewable>(
     let buffer = scoped_buffer.get(agent).bind(gc.nogc());
     // 6. If IsDetachedBuffer(buffer) is true, throw a TypeError exception.
     if is_detached_buffer(agent, buffer) {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "attempting to access detached ArrayBuffer",
-            gc.into_nogc(),
-        ));
+        return agent.type_error("attempting to access detached ArrayBuffer", gc.into_nogc());
     }
 
     // 7. Let bufferByteLength be ArrayBufferByteLength(buffer, seq-cst).
@@ -940,11 +923,7 @@ pub(crate) fn initialize_typed_array_from_array_buffer<'a, T: Viewable>(
     if new_length.is_none() && !buffer_is_fixed_length {
         // a. If offset > bufferByteLength, throw a RangeError exception.
         if offset > buffer_byte_length {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::RangeError,
-                "offset is outside the bounds of the buffer",
-                gc.into_nogc(),
-            ));
+            return agent.range_error("offset is outside the bounds of the buffer", gc.into_nogc());
         }
 
         let heap_byte_offset = offset.into();
@@ -969,11 +948,8 @@ pub(crate) fn initialize_typed_array_from_array_buffer<'a, T: Viewable>(
             let new_byte_length = new_length * element_size;
             // ii. If offset + newByteLength > bufferByteLength, throw a RangeError exception.
             if offset + new_byte_length > buffer_byte_length {
-                return Err(agent.throw_exception_with_static_message(
-                    ExceptionType::RangeError,
-                    "offset is outside the bounds of the buffer",
-                    gc.into_nogc(),
-                ));
+                return agent
+                    .range_error("offset is outside the bounds of the buffer", gc.into_nogc());
             }
 
             new_byte_length
@@ -981,11 +957,10 @@ pub(crate) fn initialize_typed_array_from_array_buffer<'a, T: Viewable>(
             // a. If length is undefined, then
             // i. If bufferByteLength modulo elementSize ≠ 0, throw a RangeError exception.
             if buffer_byte_length % element_size != 0 {
-                return Err(agent.throw_exception_with_static_message(
-                    ExceptionType::RangeError,
+                return agent.range_error(
                     "buffer length is not a multiple of the element size",
                     gc.into_nogc(),
-                ));
+                );
             }
 
             // ii. Let newByteLength be bufferByteLength - offset.
@@ -993,11 +968,7 @@ pub(crate) fn initialize_typed_array_from_array_buffer<'a, T: Viewable>(
                 new_byte_length
             } else {
                 // iii. If newByteLength < 0, throw a RangeError exception.
-                return Err(agent.throw_exception_with_static_message(
-                    ExceptionType::RangeError,
-                    "new byte length is negative",
-                    gc.into_nogc(),
-                ));
+                return agent.range_error("new byte length is negative", gc.into_nogc());
             }
         };
 
@@ -1195,22 +1166,14 @@ fn typed_array_create_from_constructor_internal<'a>(
     if let Some(first_arg) = length {
         // a. If IsTypedArrayOutOfBounds(taRecord) is true, throw a TypeError exception.
         if with_typed_array_viewable!(o, is_typed_array_out_of_bounds::<T>(agent, &ta_record, gc)) {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "TypedArray out of bounds",
-                gc,
-            ));
+            return agent.type_error("TypedArray out of bounds", gc);
         }
         // b. Let length be TypedArrayLength(taRecord).
         let len =
             with_typed_array_viewable!(o, typed_array_length::<T>(agent, &ta_record, gc)) as i64;
         // c. If length < ℝ(argumentList[0]), throw a TypeError exception.
         if len < first_arg {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "TypedArray out of bounds",
-                gc,
-            ));
+            return agent.type_error("TypedArray out of bounds", gc);
         };
     }
     // 4. Return newTypedArray.
@@ -1429,11 +1392,7 @@ pub(crate) fn typed_array_species_create_with_length<'a, T: Viewable>(
     // 5. If result.[[ContentType]] is not exemplar.[[ContentType]], throw a TypeError exception.
     let is_type_match = has_matching_content_type::<T>(result);
     if !is_type_match {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "TypedArray species did not match exemplar",
-            gc.into_nogc(),
-        ));
+        return agent.type_error("TypedArray species did not match exemplar", gc.into_nogc());
     }
     // 6. Return result.
     Ok(result.unbind())
@@ -1473,11 +1432,7 @@ pub(crate) fn typed_array_species_create_with_buffer<'a, T: Viewable>(
     // 5. If result.[[ContentType]] is not exemplar.[[ContentType]], throw a TypeError exception.
     let is_type_match = has_matching_content_type::<T>(result);
     if !is_type_match {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "can't convert BigInt to number",
-            gc.into_nogc(),
-        ));
+        return agent.type_error("can't convert BigInt to number", gc.into_nogc());
     }
     // 6. Return result.
     Ok(result.unbind())
@@ -16,7 +16,6 @@ use crate::ecmascript::builtins::indexed_collections::typed_array_objects::abstr
     initialize_typed_array_from_typed_array,
 };
 use crate::ecmascript::builtins::typed_array::TypedArray;
-use crate::ecmascript::execution::agent::ExceptionType;
 use crate::ecmascript::types::{Function, IntoValue, PropertyKey, U8Clamped, Viewable};
 use crate::engine::TryResult;
 use crate::engine::context::{Bindable, GcScope};
@@ -730,11 +729,10 @@ fn typed_array_constructor<'gc, T: Viewable>(
     };
     // 1. If NewTarget is undefined, throw a TypeError exception.
     let Some(new_target) = new_target else {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
+        return agent.type_error(
             "calling a builtin TypedArray constructor without new is forbidden",
             gc.into_nogc(),
-        ));
+        );
     };
     let mut new_target = Function::try_from(new_target).unwrap();
 
@@ -33,13 +33,10 @@ use crate::{
             },
             typed_array::TypedArray,
         },
-        execution::{
-            Agent, JsResult, Realm,
-            agent::{ExceptionType, JsError},
-        },
+        execution::{Agent, JsResult, Realm, agent::JsError},
         types::{
-            BUILTIN_STRING_MEMORY, Function, IntoNumeric, IntoObject, IntoValue, Number, Object,
-            PropertyKey, String, U8Clamped, Value, Viewable,
+            BUILTIN_STRING_MEMORY, Function, HeapStringBuilder, IntoNumeric, IntoObject, IntoValue,
+            Number, Object, PropertyKey, String, U8Clamped, Value, Viewable,
         },
     },
     engine::{
@@ -123,11 +120,7 @@ impl TypedArrayIntrinsicObject {
         let c = this_value;
         // 2. If IsConstructor(C) is false, throw a TypeError exception.
         let Some(c) = is_constructor(agent, c) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Not a constructor",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Not a constructor", gc.into_nogc());
         };
         // 3. If mapper is undefined, then
         let mapping = if mapper.is_undefined() {
@@ -137,11 +130,10 @@ impl TypedArrayIntrinsicObject {
             // 3. Else,
             //  a. If IsCallable(mapper) is false, throw a TypeError exception.
             let Some(mapper) = is_callable(mapper, gc.nogc()) else {
-                return Err(agent.throw_exception_with_static_message(
-                    ExceptionType::TypeError,
+                return agent.type_error(
                     "The map function of Array.from is not callable",
                     gc.into_nogc(),
-                ));
+                );
             };
             //  b. Let mapping be true.
             Some(mapper.scope(agent, gc.nogc()))
@@ -323,11 +315,7 @@ impl TypedArrayIntrinsicObject {
         let c = this_value;
         // 3. If IsConstructor(C) is false, throw a TypeError exception.
         let Some(c) = is_constructor(agent, c) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Not a constructor",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Not a constructor", gc.into_nogc());
         };
         // 4. Let newObj be ? TypedArrayCreateFromConstructor(C, « 𝔽(len) »).
         let len = u32::try_from(len).unwrap();
@@ -837,11 +825,7 @@ impl TypedArrayPrototype {
         let len = with_typed_array_viewable!(o, typed_array_length::<T>(agent, &ta_record, nogc));
         // 4. If IsCallable(callback) is false, throw a TypeError exception.
         let Some(callback) = is_callable(callback, nogc) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Callback is not callable",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Callback is not callable", gc.into_nogc());
         };
         let callback = callback.scope(agent, nogc);
         let this_arg = this_arg.scope(agent, nogc);
@@ -940,11 +924,7 @@ impl TypedArrayPrototype {
         let o = ta_record.object;
         // 4. If IsCallable(callback) is false, throw a TypeError exception.
         let Some(callback) = is_callable(callback, gc.nogc()) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Callback is not callable",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Callback is not callable", gc.into_nogc());
         };
         // 3. Let len be TypedArrayLength(taRecord).
         let a = with_typed_array_viewable!(
@@ -1101,11 +1081,7 @@ impl TypedArrayPrototype {
                 as i64;
         // 4. If IsCallable(callback) is false, throw a TypeError exception.
         let Some(callback) = is_callable(callback, nogc) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Callback is not callable",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Callback is not callable", gc.into_nogc());
         };
         let callback = callback.scope(agent, nogc);
         let this_arg = this_arg.scope(agent, nogc);
@@ -1373,21 +1349,8 @@ impl TypedArrayPrototype {
         if len == 0 {
             return Ok(String::EMPTY_STRING.into_value());
         }
-        let owned_sep = match &sep_string {
-            String::String(heap_string) => Some(heap_string.as_str(agent).to_owned()),
-            String::SmallString(_) => None,
-        };
-        let sep = match &owned_sep {
-            Some(str_data) => str_data.as_str(),
-            None => {
-                let String::SmallString(sep) = &sep_string else {
-                    unreachable!();
-                };
-                sep.as_str()
-            }
-        };
         // 6. Let R be the empty String.
-        let mut r = std::string::String::with_capacity(len * 3);
+        let mut r = HeapStringBuilder::with_capacity(len * 3);
         // 7. Let k be 0.
         // 8. Repeat, while k < len,
         let offset = o.byte_offset(agent);
@@ -1414,14 +1377,17 @@ impl TypedArrayPrototype {
             Some(len)
         };
         let Some(after_len) = after_len else {
-            return Ok(
-                String::from_string(agent, sep.repeat(len.saturating_sub(1)), gc).into_value(),
-            );
+            let separators = len.saturating_sub(1);
+            let byte_length = sep_string.len(agent).saturating_mul(separators);
+            agent.check_string_length(byte_length, gc)?;
+            r.push_repeated(agent, sep_string, separators);
+            return Ok(r.finish(agent, gc).into_value());
         };
         for k in 0..len {
             // a. If k > 0, set R to the string-concatenation of R and sep.
             if k > 0 {
-                r.push_str(sep);
+                agent.check_string_length(r.len() + sep_string.len(agent), gc)?;
+                r.push_string(agent, sep_string);
             }
             // c. If element is not undefined, then
             if k >= after_len {
@@ -1446,11 +1412,11 @@ impl TypedArrayPrototype {
             // i. Let S be ! ToString(element).
             let s = unwrap_try(try_to_string(agent, element, gc)).unwrap();
             // ii. Set R to the string-concatenation of R and S.
-            r.push_str(s.as_str(agent));
+            r.push_string(agent, s);
             // d. Set k to k + 1.
         }
         // 9. Return R.
-        Ok(String::from_string(agent, r, gc).into_value().unbind())
+        Ok(r.finish(agent, gc).into_value())
     }
 
     /// ### [23.2.3.19 %TypedArray%.prototype.keys ( )](https://tc39.es/ecma262/#sec-%typedarray%.prototype.keys)
@@ -1589,11 +1555,7 @@ impl TypedArrayPrototype {
 
         // 4. If IsCallable(callback) is false, throw a TypeError exception.
         let Some(callback_fn) = is_callable(callback_fn, gc.nogc()) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Callback is not callable",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Callback is not callable", gc.into_nogc());
         };
 
         let a = with_typed_array_viewable!(
@@ -1637,19 +1599,14 @@ impl TypedArrayPrototype {
                 as i64;
         // 4. If IsCallable(callback) is false, throw a TypeError exception.
         let Some(callback) = is_callable(callback, gc.nogc()) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Callback is not callable",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Callback is not callable", gc.into_nogc());
         };
         // 5. If len = 0 and initialValue is not present, throw a TypeError exception.
         if len == 0 && initial_value.is_none() {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
+            return agent.type_error(
                 "Array length is 0 and no initial value provided",
                 gc.into_nogc(),
-            ));
+            );
         };
         // 6. Let k be 0.
         let mut k = 0;
@@ -1730,19 +1687,14 @@ impl TypedArrayPrototype {
                 as i64;
         // 4. If IsCallable(callback) is false, throw a TypeError exception.
         let Some(callback) = is_callable(callback, gc.nogc()) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Callback is not callable",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Callback is not callable", gc.into_nogc());
         };
         // 5. If len = 0 and initialValue is not present, throw a TypeError exception.
         if len == 0 && initial_value.is_none() {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
+            return agent.type_error(
                 "Array length is 0 and no initial value provided",
                 gc.into_nogc(),
-            ));
+            );
         };
         // 6. Let k be len - 1.
         let mut k = len - 1;
@@ -1824,13 +1776,29 @@ impl TypedArrayPrototype {
         Err(agent.todo("TypedArray.prototype.set", gc.into_nogc()))
     }
 
+    /// ### [23.2.3.27 %TypedArray%.prototype.slice ( start, end )](https://tc39.es/ecma262/multipage/indexed-collections.html#sec-%typedarray%.prototype.slice)
+    /// The interpretation and use of the arguments of this method are the
+    /// same as for Array.prototype.slice as defined in 23.1.3.28.
     fn slice<'gc>(
         agent: &mut Agent,
-        _this_value: Value,
-        _: ArgumentsList,
+        this_value: Value,
+        arguments: ArgumentsList,
         gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
-        Err(agent.todo("TypedArray.prototype.slice", gc.into_nogc()))
+        let this_value = this_value.bind(gc.nogc());
+        let start = arguments.get(0).bind(gc.nogc());
+        let end = arguments.get(1).bind(gc.nogc());
+        // 1. Let O be the this value.
+        let o = this_value;
+        // 2. Let taRecord be ? ValidateTypedArray(O, seq-cst).
+        let ta_record = validate_typed_array(agent, o, Ordering::SeqCst, gc.nogc())
+            .unbind()?
+            .bind(gc.nogc());
+        let a = with_typed_array_viewable!(
+            ta_record.object,
+            slice_typed_array::<T>(agent, ta_record.unbind(), start.unbind(), end.unbind(), gc)
+        );
+        a.map(|a| a.into_value())
     }
 
     /// ### [23.2.3.28 get %TypedArray%.prototype.some](https://tc39.es/ecma262/multipage/indexed-collections.html#sec-%typedarray%.prototype.some)
@@ -1855,11 +1823,7 @@ impl TypedArrayPrototype {
         let len = with_typed_array_viewable!(o, typed_array_length::<T>(agent, &ta_record, nogc));
         // 4. If IsCallable(callback) is false, throw a TypeError exception.
         let Some(callback) = is_callable(callback, nogc) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Callback is not callable",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Callback is not callable", gc.into_nogc());
         };
         let callback = callback.scope(agent, nogc);
         let this_arg = this_arg.scope(agent, nogc);
@@ -1920,11 +1884,10 @@ impl TypedArrayPrototype {
         } else if let Some(comparator) = is_callable(comparator, nogc) {
             Some(comparator.scope(agent, nogc))
         } else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
+            return agent.type_error(
                 "The comparison function must be either a function or undefined",
                 gc.into_nogc(),
-            ));
+            );
         };
         // 2. Let obj be the this value.
         let obj = this_value;
@@ -2118,11 +2081,10 @@ impl TypedArrayPrototype {
         } else if let Some(comparator) = is_callable(comparator, gc.nogc()) {
             Some(comparator.scope(agent, gc.nogc()))
         } else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
+            return agent.type_error(
                 "The comparison function must be either a function or undefined",
                 gc.into_nogc(),
-            ));
+            );
         };
         // 2. Let O be the this value.
         let o = this_value;
@@ -2475,6 +2437,118 @@ fn map_typed_array<'a, T: Viewable>(
     Ok(a.get(agent).unbind())
 }
 
+fn slice_typed_array<'a, T: Viewable>(
+    agent: &mut Agent,
+    ta_record: TypedArrayWithBufferWitnessRecords,
+    start: Value,
+    end: Value,
+    mut gc: GcScope<'a, '_>,
+) -> JsResult<'a, TypedArray<'a>> {
+    let ta_record = ta_record.bind(gc.nogc());
+    let start = start.bind(gc.nogc());
+    let end = end.scope(agent, gc.nogc());
+    let o = ta_record.object.scope(agent, gc.nogc());
+    // 3. Let srcArrayLength be TypedArrayLength(taRecord).
+    let src_array_length = typed_array_length::<T>(agent, &ta_record, gc.nogc()) as i64;
+    // 4. Let relativeStart be ? ToIntegerOrInfinity(start).
+    let relative_start = to_integer_or_infinity(agent, start.unbind(), gc.reborrow())
+        .unbind()?
+        .bind(gc.nogc());
+    let start_index = if relative_start.is_neg_infinity() {
+        // 5. If relativeStart = -∞, let startIndex be 0.
+        0
+    } else if relative_start.is_negative() {
+        // 6. Else if relativeStart < 0, let startIndex be max(srcArrayLength + relativeStart, 0).
+        (src_array_length + relative_start.into_i64()).max(0)
+    } else {
+        // 7. Else, let startIndex be min(relativeStart, srcArrayLength).
+        relative_start.into_i64().min(src_array_length)
+    };
+    // 8. If end is undefined, let relativeEnd be srcArrayLength; else let relativeEnd be ? ToIntegerOrInfinity(end).
+    let end_index = if end.get(agent).is_undefined() {
+        src_array_length
+    } else {
+        let relative_end = to_integer_or_infinity(agent, end.get(agent), gc.reborrow())
+            .unbind()?
+            .bind(gc.nogc());
+        if relative_end.is_neg_infinity() {
+            // 9. If relativeEnd = -∞, let endIndex be 0.
+            0
+        } else if relative_end.is_negative() {
+            // 10. Else if relativeEnd < 0, let endIndex be max(srcArrayLength + relativeEnd, 0).
+            (src_array_length + relative_end.into_i64()).max(0)
+        } else {
+            // 11. Else, let endIndex be min(relativeEnd, srcArrayLength).
+            relative_end.into_i64().min(src_array_length)
+        }
+    };
+    // 12. Let countBytes be max(endIndex - startIndex, 0).
+    let count = (end_index - start_index).max(0);
+    // 13. Let A be ? TypedArraySpeciesCreate(O, « 𝔽(countBytes) »).
+    let a = typed_array_species_create_with_length::<T>(agent, o.get(agent), count, gc.reborrow())
+        .unbind()?;
+    let gc = gc.into_nogc();
+    let a = a.bind(gc);
+    let o = o.get(agent).bind(gc);
+    // 14. If countBytes > 0, then
+    if count > 0 {
+        // a. Set taRecord to MakeTypedArrayWithBufferWitnessRecord(O, seq-cst).
+        let ta_record = make_typed_array_with_buffer_witness_record(agent, o, Ordering::SeqCst, gc);
+        // b. If IsTypedArrayOutOfBounds(taRecord) is true, throw a TypeError exception.
+        if is_typed_array_out_of_bounds::<T>(agent, &ta_record, gc) {
+            return agent.type_error("TypedArray out of bounds", gc);
+        }
+        // c. Set endIndex to min(endIndex, TypedArrayLength(taRecord)).
+        let end_index = end_index.min(typed_array_length::<T>(agent, &ta_record, gc) as i64);
+        // d. Set countBytes to max(endIndex - startIndex, 0).
+        let start_index = start_index as usize;
+        let end_index = end_index.max(start_index as i64) as usize;
+        // e. Let srcType be TypedArrayElementType(O).
+        // f. Let targetType be TypedArrayElementType(A).
+        let same_type = with_typed_array_viewable!(
+            a,
+            core::any::TypeId::of::<T>() == core::any::TypeId::of::<V>(),
+            V
+        );
+        let same_buffer =
+            a.get_viewed_array_buffer(agent, gc) == o.get_viewed_array_buffer(agent, gc);
+        // g. If srcType is targetType, then
+        if same_type && !same_buffer {
+            // i. NOTE: The transfer must be performed in a manner that
+            //    preserves the bit-level encoding of the source data.
+            // ii-ix. Copy the bytes from O's buffer into A's buffer.
+            let (a_slice, o_slice) = split_typed_array_views::<T>(agent, a, o, gc);
+            let count = (end_index - start_index).min(a_slice.len());
+            a_slice[..count].copy_from_slice(&o_slice[start_index..start_index + count]);
+        } else {
+            // h. Else,
+            // i. Let n be 0.
+            // ii. Let k be startIndex.
+            // iii. Repeat, while k < endIndex,
+            for (n, k) in (start_index..end_index).enumerate() {
+                // 1. Let Pk be ! ToString(𝔽(k)).
+                let pk = PropertyKey::try_from(k).unwrap();
+                // 2. Let kValue be ! Get(O, Pk).
+                let k_value = unwrap_try(try_get(agent, o, pk, gc));
+                // 3. Perform ! Set(A, ! ToString(𝔽(n)), kValue, true).
+                unwrap_try(try_set(
+                    agent,
+                    a.into_object(),
+                    PropertyKey::try_from(n).unwrap(),
+                    k_value,
+                    true,
+                    gc,
+                ))
+                .unwrap();
+                // 4. Set k to k + 1.
+                // 5. Set n to n + 1.
+            }
+        }
+    }
+    // 15. Return A.
+    Ok(a)
+}
+
 fn search_typed_element<T: Viewable, const ASCENDING: bool>(
     agent: &mut Agent,
     ta: TypedArray,
@@ -2602,11 +2676,7 @@ fn copy_within_typed_array<'a, T: Viewable>(
     let end_bound = (end_index - start_index).max(0).min(len - target_index) as usize;
     let ta_record = make_typed_array_with_buffer_witness_record(agent, ta, Ordering::SeqCst, gc);
     if is_typed_array_out_of_bounds::<T>(agent, &ta_record, gc) {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Callback is not callable",
-            gc,
-        ));
+        return agent.type_error("Callback is not callable", gc);
     }
     let after_len = typed_array_length::<T>(agent, &ta_record, gc) as usize;
     let slice = viewable_slice_mut::<T>(agent, ta, gc);
@@ -2693,11 +2763,7 @@ fn fill_typed_array<'a, T: Viewable>(
     let ta_record = make_typed_array_with_buffer_witness_record(agent, ta, Ordering::SeqCst, gc);
     // 15. If IsTypedArrayOutOfBounds(taRecord) is true, throw a TypeError exception.
     if is_typed_array_out_of_bounds::<T>(agent, &ta_record, gc) {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Callback is not callable",
-            gc,
-        ));
+        return agent.type_error("Callback is not callable", gc);
     };
     // 16. Set len to TypedArrayLength(taRecord).
     let len = typed_array_length::<T>(agent, &ta_record, gc) as i64;
@@ -3062,11 +3128,7 @@ fn filter_typed_array<'a, T: Viewable>(
             .is_empty()
     };
     if !properly_aligned {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "TypedArray is not properly aligned",
-            gc.into_nogc(),
-        ));
+        return agent.type_error("TypedArray is not properly aligned", gc.into_nogc());
     }
     for k in 0..len {
         let byte_slice = array_buffer.get(agent).as_slice(agent);
@@ -3256,11 +3318,7 @@ fn with_typed_array<'a, T: Viewable>(
     };
     // 9. If IsValidIntegerIndex(O, 𝔽(actualIndex)) is false, throw a RangeError exception.
     if is_valid_integer_index::<T>(agent, scoped_o.get(agent), actual_index, gc.nogc()).is_none() {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::RangeError,
-            "Index out of bounds",
-            gc.into_nogc(),
-        ));
+        return agent.range_error("Index out of bounds", gc.into_nogc());
     }
     // 10. Let A be ? TypedArrayCreateSameType(O, « 𝔽(len) »).
     let a = typed_array_create_same_type(agent, scoped_o.get(agent), len, gc.reborrow())
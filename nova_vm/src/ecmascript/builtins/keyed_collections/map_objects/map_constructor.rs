@@ -90,11 +90,7 @@ impl MapConstructor {
 
         // If NewTarget is undefined, throw a TypeError exception.
         let Some(new_target) = new_target else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Constructor Map requires 'new'",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Constructor Map requires 'new'", gc.into_nogc());
         };
         let new_target = Function::try_from(new_target).unwrap();
         // 2. Let map be ? OrdinaryCreateFromConstructor(NewTarget, "%Map.prototype%", « [[MapData]] »).
@@ -153,11 +149,7 @@ impl MapConstructor {
         };
         // 6. If IsCallable(adder) is false, throw a TypeError exception.
         let Some(adder) = is_callable(adder, gc.nogc()) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Map.prototype.set is not callable",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Map.prototype.set is not callable", gc.into_nogc());
         };
         // 7. Return ? AddEntriesFromIterable(map, iterable, adder).
         add_entries_from_iterable_map_constructor(
@@ -199,6 +191,7 @@ impl MapConstructor {
             })
             .collect::<Vec<_>>();
 
+        agent.heap.write_barrier(map);
         let Heap {
             maps,
             bigints,
@@ -315,6 +308,7 @@ pub fn add_entries_from_iterable_map_constructor<'a>(
                     )
                 {
                     let arr_iterable = scoped_iterable.get(agent).bind(gc.nogc());
+                    agent.heap.write_barrier(target);
                     let Heap {
                         elements,
                         arrays,
@@ -126,6 +126,7 @@ impl Index<MapIterator<'_>> for Agent {
 
 impl IndexMut<MapIterator<'_>> for Agent {
     fn index_mut(&mut self, index: MapIterator) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.map_iterators[index]
     }
 }
@@ -3,23 +3,20 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use crate::ecmascript::builtins::Behaviour;
-use crate::engine::context::{Bindable, GcScope};
-use crate::{
-    ecmascript::{
-        abstract_operations::{
-            operations_on_iterator_objects::create_iter_result_object,
-            operations_on_objects::create_array_from_list,
-        },
-        builders::ordinary_object_builder::OrdinaryObjectBuilder,
-        builtins::{
-            ArgumentsList, Builtin,
-            indexed_collections::array_objects::array_iterator_objects::array_iterator::CollectionIteratorKind,
-        },
-        execution::{Agent, JsResult, Realm, agent::ExceptionType},
-        types::{BUILTIN_STRING_MEMORY, IntoValue, String, Value},
+use crate::ecmascript::{
+    abstract_operations::{
+        operations_on_iterator_objects::create_iter_result_object,
+        operations_on_objects::create_entry_pair,
+    },
+    builders::ordinary_object_builder::OrdinaryObjectBuilder,
+    builtins::{
+        ArgumentsList, Builtin,
+        indexed_collections::array_objects::array_iterator_objects::array_iterator::CollectionIteratorKind,
     },
-    heap::WellKnownSymbolIndexes,
+    execution::{Agent, JsResult, Realm},
+    types::{BUILTIN_STRING_MEMORY, IntoValue, String, Value},
 };
+use crate::engine::context::{Bindable, GcScope};
 
 pub(crate) struct MapIteratorPrototype;
 
@@ -44,11 +41,7 @@ impl MapIteratorPrototype {
         // 27.5.3.2 GeneratorValidate ( generator, generatorBrand )
         // 3. If generator.[[GeneratorBrand]] is not generatorBrand, throw a TypeError exception.
         let Value::MapIterator(iterator) = this_value else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "MapIterator expected",
-                gc,
-            ));
+            return agent.type_error("MapIterator expected", gc);
         };
 
         // 24.1.5.1 CreateMapIterator ( map, kind ), step 2
@@ -94,7 +87,7 @@ impl MapIteratorPrototype {
                         continue;
                     };
                     let value = agent[map].values(gc)[index].unwrap();
-                    create_array_from_list(agent, &[key, value], gc).into_value()
+                    create_entry_pair(agent, key, value, gc).into_value()
                 }
             };
 
@@ -118,14 +111,7 @@ impl MapIteratorPrototype {
             .with_property_capacity(2)
             .with_prototype(iterator_prototype)
             .with_builtin_function_property::<MapIteratorPrototypeNext>()
-            .with_property(|builder| {
-                builder
-                    .with_key(WellKnownSymbolIndexes::ToStringTag.into())
-                    .with_value_readonly(BUILTIN_STRING_MEMORY.Map_Iterator.into_value())
-                    .with_enumerable(false)
-                    .with_configurable(true)
-                    .build()
-            })
+            .with_to_string_tag(BUILTIN_STRING_MEMORY.Map_Iterator)
             .build();
     }
 }
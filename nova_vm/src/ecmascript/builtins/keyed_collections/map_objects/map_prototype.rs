@@ -21,7 +21,7 @@ use crate::{
             keyed_collections::map_objects::map_iterator_objects::map_iterator::MapIterator,
             map::{Map, data::MapData},
         },
-        execution::{Agent, JsResult, Realm, agent::ExceptionType},
+        execution::{Agent, JsResult, Realm},
         types::{BUILTIN_STRING_MEMORY, HeapNumber, IntoValue, PropertyKey, String, Value},
     },
     heap::{Heap, IntrinsicFunctionIndexes, PrimitiveHeap, WellKnownSymbolIndexes},
@@ -138,6 +138,7 @@ impl MapPrototype {
         let key = arguments.get(0).bind(gc);
         let m = require_map_data_internal_slot(agent, this_value, gc)?;
 
+        agent.heap.write_barrier(m);
         let Heap {
             bigints,
             numbers,
@@ -241,11 +242,10 @@ impl MapPrototype {
             .bind(nogc);
         // 3. If IsCallable(callbackfn) is false, throw a TypeError exception.
         let Some(callback_fn) = is_callable(callback_fn, nogc) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
+            return agent.type_error(
                 "Callback function parameter is not callable",
                 gc.into_nogc(),
-            ));
+            );
         };
 
         // 4. Let entries be M.[[MapData]].
@@ -358,6 +358,7 @@ impl MapPrototype {
         // 2. Perform ? RequireInternalSlot(M, [[MapData]]).
         let m = require_map_data_internal_slot(agent, this_value, gc)?;
 
+        agent.heap.write_barrier(m);
         let Heap {
             bigints,
             numbers,
@@ -420,6 +421,7 @@ impl MapPrototype {
         // 2. Perform ? RequireInternalSlot(M, [[MapData]]).
         let m = require_map_data_internal_slot(agent, this_value, gc)?;
 
+        agent.heap.write_barrier(m);
         let Heap {
             bigints,
             numbers,
@@ -534,14 +536,7 @@ impl MapPrototype {
                     .with_configurable(MapPrototypeValues::CONFIGURABLE)
                     .build()
             })
-            .with_property(|builder| {
-                builder
-                    .with_key(WellKnownSymbolIndexes::ToStringTag.into())
-                    .with_value_readonly(BUILTIN_STRING_MEMORY.Map.into_value())
-                    .with_enumerable(false)
-                    .with_configurable(true)
-                    .build()
-            })
+            .with_to_string_tag(BUILTIN_STRING_MEMORY.Map)
             .build();
     }
 }
@@ -554,11 +549,7 @@ fn require_map_data_internal_slot<'a>(
 ) -> JsResult<'a, Map<'a>> {
     match value {
         Value::Map(map) => Ok(map.bind(gc)),
-        _ => Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Object is not a Map",
-            gc,
-        )),
+        _ => agent.type_error("Object is not a Map", gc),
     }
 }
 
@@ -9,10 +9,8 @@ use ahash::AHasher;
 use crate::{
     ecmascript::{
         abstract_operations::{
-            operations_on_iterator_objects::{
-                IteratorRecord, get_iterator, if_abrupt_close_iterator, iterator_step_value,
-            },
-            operations_on_objects::{call_function, get, throw_not_callable},
+            iterator_helpers::{IteratorHint, get_iterator, iterator_close, iterator_step_value},
+            operations_on_objects::{call_function, get},
             testing_and_comparison::is_callable,
         },
         builders::builtin_function_builder::BuiltinFunctionBuilder,
@@ -22,7 +20,7 @@ use crate::{
             ordinary::ordinary_create_from_constructor,
             set::{Set, data::SetData},
         },
-        execution::{Agent, JsResult, ProtoIntrinsics, Realm, agent::ExceptionType},
+        execution::{Agent, JsResult, ProtoIntrinsics, Realm},
         types::{
             BUILTIN_STRING_MEMORY, Function, IntoObject, IntoValue, Object, PropertyKey, String,
             Value,
@@ -70,11 +68,7 @@ impl SetConstructor {
         let new_target = new_target.bind(nogc);
         // 1. If NewTarget is undefined, throw a TypeError exception.
         let Some(new_target) = new_target else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Cannot call Set as a function",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Cannot call Set as a function", gc.into_nogc());
         };
         // 2. Let set be ? OrdinaryCreateFromConstructor(NewTarget, "%Set.prototype%", « [[SetData]] »).
         let new_target = Function::try_from(new_target).unwrap();
@@ -116,11 +110,7 @@ impl SetConstructor {
         .bind(gc.nogc());
         // 6. If IsCallable(adder) is false, throw a TypeError exception.
         let Some(adder) = is_callable(adder.unbind(), gc.nogc()) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Invalid adder function",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Invalid adder function", gc.into_nogc());
         };
         let adder = adder.scope(agent, gc.nogc());
         if let Value::Array(iterable) = scoped_iterable.get(agent) {
@@ -132,6 +122,7 @@ impl SetConstructor {
                 let Value::Array(iterable) = scoped_iterable.get(agent).bind(gc.nogc()) else {
                     unreachable!()
                 };
+                agent.heap.write_barrier(set);
                 let Heap {
                     elements,
                     arrays,
@@ -187,32 +178,20 @@ impl SetConstructor {
             }
         }
         // 7. Let iteratorRecord be ? GetIterator(iterable, SYNC).
-        let Some(IteratorRecord {
-            iterator,
-            next_method,
-        }) = get_iterator(agent, scoped_iterable.get(agent), false, gc.reborrow())
-            .unbind()?
-            .bind(gc.nogc())
-        else {
-            return Err(throw_not_callable(agent, gc.into_nogc()));
-        };
-
-        let iterator = iterator.scope(agent, gc.nogc());
-        let next_method = next_method.scope(agent, gc.nogc());
+        let mut iterator_record = get_iterator(
+            agent,
+            scoped_iterable.get(agent),
+            IteratorHint::Sync,
+            gc.reborrow(),
+        )
+        .unbind()?;
 
         // 8. Repeat,
         loop {
             // a. Let next be ? IteratorStepValue(iteratorRecord).
-            let next = iterator_step_value(
-                agent,
-                IteratorRecord {
-                    iterator: iterator.get(agent),
-                    next_method: next_method.get(agent),
-                },
-                gc.reborrow(),
-            )
-            .unbind()?
-            .bind(gc.nogc());
+            let next = iterator_step_value(agent, &mut iterator_record, gc.reborrow())
+                .unbind()?
+                .bind(gc.nogc());
             // b. If next is DONE, return set.
             let Some(next) = next else {
                 return Ok(scoped_set.get(agent).into_value());
@@ -226,11 +205,10 @@ impl SetConstructor {
                 gc.reborrow(),
             );
             // d. IfAbruptCloseIterator(status, iteratorRecord).
-            let iterator_record = IteratorRecord {
-                iterator: iterator.get(agent),
-                next_method: next_method.get(agent),
-            };
-            let _ = if_abrupt_close_iterator!(agent, status, iterator_record, gc);
+            if let Err(err) = status {
+                let err = err.unbind();
+                return iterator_close(agent, &iterator_record, Err(err), gc);
+            }
         }
     }
 
@@ -126,6 +126,7 @@ impl Index<SetIterator<'_>> for Agent {
 
 impl IndexMut<SetIterator<'_>> for Agent {
     fn index_mut(&mut self, index: SetIterator) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.set_iterators[index]
     }
 }
@@ -3,23 +3,20 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use crate::ecmascript::builtins::Behaviour;
-use crate::engine::context::{Bindable, GcScope};
-use crate::{
-    ecmascript::{
-        abstract_operations::{
-            operations_on_iterator_objects::create_iter_result_object,
-            operations_on_objects::create_array_from_list,
-        },
-        builders::ordinary_object_builder::OrdinaryObjectBuilder,
-        builtins::{
-            ArgumentsList, Builtin,
-            indexed_collections::array_objects::array_iterator_objects::array_iterator::CollectionIteratorKind,
-        },
-        execution::{Agent, JsResult, Realm, agent::ExceptionType},
-        types::{BUILTIN_STRING_MEMORY, IntoValue, String, Value},
+use crate::ecmascript::{
+    abstract_operations::{
+        operations_on_iterator_objects::create_iter_result_object,
+        operations_on_objects::create_entry_pair,
+    },
+    builders::ordinary_object_builder::OrdinaryObjectBuilder,
+    builtins::{
+        ArgumentsList, Builtin,
+        indexed_collections::array_objects::array_iterator_objects::array_iterator::CollectionIteratorKind,
     },
-    heap::WellKnownSymbolIndexes,
+    execution::{Agent, JsResult, Realm},
+    types::{BUILTIN_STRING_MEMORY, IntoValue, String, Value},
 };
+use crate::engine::context::{Bindable, GcScope};
 
 pub(crate) struct SetIteratorPrototype;
 
@@ -43,11 +40,7 @@ impl SetIteratorPrototype {
         // 27.5.3.2 GeneratorValidate ( generator, generatorBrand )
         // 3. If generator.[[GeneratorBrand]] is not generatorBrand, throw a TypeError exception.
         let Value::SetIterator(iterator) = this_value else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "SetIterator expected",
-                gc,
-            ));
+            return agent.type_error("SetIterator expected", gc);
         };
         let iterator = iterator.bind(gc);
 
@@ -77,7 +70,7 @@ impl SetIteratorPrototype {
                     // 1. If kind is KEY+VALUE, then
                     //   a. Let result be CreateArrayFromList(« e, e »).
                     //   b. Perform ? GeneratorYield(CreateIteratorResultObject(result, false)).
-                    create_array_from_list(agent, &[e.unbind(), e.unbind()], gc).into_value()
+                    create_entry_pair(agent, e.unbind(), e.unbind(), gc).into_value()
                 }
                 CollectionIteratorKind::Value => {
                     // 2. Else,
@@ -106,14 +99,7 @@ impl SetIteratorPrototype {
             .with_property_capacity(2)
             .with_prototype(iterator_prototype)
             .with_builtin_function_property::<SetIteratorPrototypeNext>()
-            .with_property(|builder| {
-                builder
-                    .with_key(WellKnownSymbolIndexes::ToStringTag.into())
-                    .with_value_readonly(BUILTIN_STRING_MEMORY.Set_Iterator.into_value())
-                    .with_enumerable(false)
-                    .with_configurable(true)
-                    .build()
-            })
+            .with_to_string_tag(BUILTIN_STRING_MEMORY.Set_Iterator)
             .build();
     }
 }
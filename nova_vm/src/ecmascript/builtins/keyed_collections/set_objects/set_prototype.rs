@@ -22,7 +22,7 @@ use crate::{
             keyed_collections::set_objects::set_iterator_objects::set_iterator::SetIterator,
             set::{Set, data::SetData},
         },
-        execution::{Agent, JsResult, Realm, agent::ExceptionType},
+        execution::{Agent, JsResult, Realm},
         types::{BUILTIN_STRING_MEMORY, IntoValue, Number, PropertyKey, String, Value},
     },
     heap::{Heap, IntrinsicFunctionIndexes, PrimitiveHeap, WellKnownSymbolIndexes},
@@ -99,6 +99,7 @@ impl SetPrototype {
         // 2. Perform ? RequireInternalSlot(S, [[SetData]]).
         let s = require_set_data_internal_slot(agent, this_value, gc)?;
 
+        agent.heap.write_barrier(s);
         let Heap {
             bigints,
             numbers,
@@ -189,6 +190,7 @@ impl SetPrototype {
         // 2. Perform ? RequireInternalSlot(S, [[SetData]]).
         let s = require_set_data_internal_slot(agent, this_value, gc)?;
 
+        agent.heap.write_barrier(s);
         let Heap {
             bigints,
             numbers,
@@ -296,11 +298,7 @@ impl SetPrototype {
             .bind(nogc);
         // 3. If IsCallable(callbackfn) is false, throw a TypeError exception.
         let Some(callback_fn) = is_callable(callback_fn, nogc) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Callback function is not a function",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Callback function is not a function", gc.into_nogc());
         };
         // 4. Let entries be S.[[SetData]].
         // 5. Let numEntries be the number of elements in entries.
@@ -465,14 +463,7 @@ impl SetPrototype {
                     .with_configurable(SetPrototypeValues::CONFIGURABLE)
                     .build()
             })
-            .with_property(|builder| {
-                builder
-                    .with_key(WellKnownSymbolIndexes::ToStringTag.into())
-                    .with_value_readonly(BUILTIN_STRING_MEMORY.Set.into_value())
-                    .with_enumerable(false)
-                    .with_configurable(true)
-                    .build()
-            })
+            .with_to_string_tag(BUILTIN_STRING_MEMORY.Set)
             .build();
     }
 }
@@ -485,10 +476,6 @@ fn require_set_data_internal_slot<'a>(
 ) -> JsResult<'a, Set<'a>> {
     match value {
         Value::Set(map) => Ok(map.bind(gc)),
-        _ => Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Object is not a Set",
-            gc,
-        )),
+        _ => agent.type_error("Object is not a Set", gc),
     }
 }
@@ -2,9 +2,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use crate::ecmascript::abstract_operations::operations_on_iterator_objects::{
-    IteratorRecord, get_iterator, if_abrupt_close_iterator, iterator_step_value,
-};
+use crate::ecmascript::abstract_operations::iterator_helpers::iterate;
 use crate::ecmascript::abstract_operations::operations_on_objects::{
     call_function, get, throw_not_callable,
 };
@@ -13,7 +11,6 @@ use crate::ecmascript::builtins::Array;
 use crate::ecmascript::builtins::array::ArrayHeap;
 use crate::ecmascript::builtins::ordinary::ordinary_create_from_constructor;
 use crate::ecmascript::builtins::weak_set::WeakSet;
-use crate::ecmascript::execution::agent::ExceptionType;
 use crate::ecmascript::execution::{ProtoIntrinsics, can_be_held_weakly, throw_not_weak_key_error};
 use crate::ecmascript::types::{Function, IntoValue};
 use crate::engine::Scoped;
@@ -55,11 +52,10 @@ impl WeakSetConstructor {
         let new_target = new_target.bind(gc.nogc());
         // 1. If NewTarget is undefined, throw a TypeError exception.
         let Some(new_target) = new_target else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
+            return agent.type_error(
                 "calling a builtin WeakSet constructor without new is forbidden",
                 gc.into_nogc(),
-            ));
+            );
         };
         let new_target = Function::try_from(new_target).unwrap();
         // 2. Let set be ? OrdinaryCreateFromConstructor(NewTarget, "%WeakSet.prototype%", « [[WeakSetData]] »).
@@ -138,51 +134,28 @@ fn weak_set_constructor_slow_path<'a>(
 ) -> JsResult<'a, WeakSet<'a>> {
     let adder = adder.scope(agent, gc.nogc());
     // 7. Let iteratorRecord be ? GetIterator(iterable, sync).
-    let Some(IteratorRecord {
-        iterator,
-        next_method,
-        ..
-    }) = get_iterator(agent, scoped_iterable.get(agent), false, gc.reborrow())
-        .unbind()?
-        .bind(gc.nogc())
-    else {
-        return Err(throw_not_callable(agent, gc.into_nogc()));
-    };
-    let iterator = iterator.scope(agent, gc.nogc());
-    let next_method = next_method.scope(agent, gc.nogc());
     // 8. Repeat,
-    loop {
-        // a. Let next be ? IteratorStepValue(iteratorRecord).
-        let next = iterator_step_value(
-            agent,
-            IteratorRecord {
-                iterator: iterator.get(agent),
-                next_method: next_method.get(agent),
-            },
-            gc.reborrow(),
-        )
-        .unbind()?
-        .bind(gc.nogc());
-        // b. If next is done, return set.
-        let Some(next) = next else {
-            return Ok(scoped_set.get(agent));
-        };
-        let set = scoped_set.get(agent).bind(gc.nogc());
-        // c. Let status be Completion(Call(adder, set, « next »)).
-        let status = call_function(
-            agent,
-            adder.get(agent),
-            set.unbind().into_value(),
-            Some(ArgumentsList::from_mut_value(&mut next.unbind())),
-            gc.reborrow(),
-        );
-        let iterator_record = IteratorRecord {
-            iterator: iterator.get(agent),
-            next_method: next_method.get(agent),
-        };
-        // d. IfAbruptCloseIterator(status, iteratorRecord).
-        if_abrupt_close_iterator!(agent, status, iterator_record, gc);
-    }
+    // a. Let next be ? IteratorStepValue(iteratorRecord).
+    iterate(
+        agent,
+        scoped_iterable.get(agent),
+        gc.reborrow(),
+        |agent, next, gc| {
+            // c. Let status be Completion(Call(adder, set, « next »)).
+            // d. IfAbruptCloseIterator(status, iteratorRecord).
+            call_function(
+                agent,
+                adder.get(agent),
+                scoped_set.get(agent).into_value(),
+                Some(ArgumentsList::from_mut_value(&mut next.unbind())),
+                gc,
+            )
+            .map(|_| ())
+        },
+    )
+    .unbind()?;
+    // b. If next is done, return set.
+    Ok(scoped_set.get(agent).bind(gc.into_nogc()))
 }
 
 /// Fast path for adding elements from a trivially iterable Array (contains no
@@ -195,6 +168,7 @@ fn weak_set_add_trivially_iterable_array_elements<'a>(
     iterable: Array,
     gc: NoGcScope<'a, '_>,
 ) -> JsResult<'a, ()> {
+    agent.heap.write_barrier(set);
     let Heap {
         arrays,
         elements,
@@ -3,19 +3,15 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use crate::ecmascript::builtins::weak_set::WeakSet;
-use crate::ecmascript::execution::agent::ExceptionType;
 use crate::ecmascript::execution::{can_be_held_weakly, throw_not_weak_key_error};
 use crate::ecmascript::types::IntoValue;
-use crate::engine::context::{Bindable, GcScope, NoGcScope};
-use crate::{
-    ecmascript::{
-        builders::ordinary_object_builder::OrdinaryObjectBuilder,
-        builtins::{ArgumentsList, Behaviour, Builtin},
-        execution::{Agent, JsResult, Realm},
-        types::{BUILTIN_STRING_MEMORY, String, Value},
-    },
-    heap::WellKnownSymbolIndexes,
+use crate::ecmascript::{
+    builders::ordinary_object_builder::OrdinaryObjectBuilder,
+    builtins::{ArgumentsList, Behaviour, Builtin},
+    execution::{Agent, JsResult, Realm},
+    types::{BUILTIN_STRING_MEMORY, String, Value},
 };
+use crate::engine::context::{Bindable, GcScope, NoGcScope};
 
 pub(crate) struct WeakSetPrototype;
 
@@ -140,14 +136,7 @@ impl WeakSetPrototype {
             .with_constructor_property(weak_set_constructor)
             .with_builtin_function_property::<WeakSetPrototypeDelete>()
             .with_builtin_function_property::<WeakSetPrototypeHas>()
-            .with_property(|builder| {
-                builder
-                    .with_key(WellKnownSymbolIndexes::ToStringTag.into())
-                    .with_value_readonly(BUILTIN_STRING_MEMORY.WeakSet.into_value())
-                    .with_enumerable(false)
-                    .with_configurable(true)
-                    .build()
-            })
+            .with_to_string_tag(BUILTIN_STRING_MEMORY.WeakSet)
             .build();
     }
 }
@@ -161,10 +150,6 @@ fn require_internal_slot_weak_set<'a>(
     match o {
         // 1. Perform ? RequireInternalSlot(O, [[WeakSetData]]).
         Value::WeakSet(array_buffer) => Ok(array_buffer.unbind().bind(gc)),
-        _ => Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Expected this to be WeakSet",
-            gc,
-        )),
+        _ => agent.type_error("Expected this to be WeakSet", gc),
     }
 }
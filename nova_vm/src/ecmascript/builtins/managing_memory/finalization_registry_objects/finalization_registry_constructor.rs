@@ -30,9 +30,16 @@ impl FinalizationRegistryConstructor {
         agent: &mut Agent,
         _this_value: Value,
         _arguments: ArgumentsList,
-        _new_target: Option<Object>,
+        new_target: Option<Object>,
         gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
+        // 1. If NewTarget is undefined, throw a TypeError exception.
+        if new_target.is_none() {
+            return agent.type_error(
+                "Constructor FinalizationRegistry requires 'new'",
+                gc.into_nogc(),
+            );
+        }
         Err(agent.todo("FinalizationRegistry", gc.into_nogc()))
     }
 
@@ -2,17 +2,13 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use crate::ecmascript::types::IntoValue;
-use crate::engine::context::GcScope;
-use crate::{
-    ecmascript::{
-        builders::ordinary_object_builder::OrdinaryObjectBuilder,
-        builtins::{ArgumentsList, Behaviour, Builtin},
-        execution::{Agent, JsResult, Realm},
-        types::{BUILTIN_STRING_MEMORY, String, Value},
-    },
-    heap::WellKnownSymbolIndexes,
+use crate::ecmascript::{
+    builders::ordinary_object_builder::OrdinaryObjectBuilder,
+    builtins::{ArgumentsList, Behaviour, Builtin},
+    execution::{Agent, JsResult, Realm},
+    types::{BUILTIN_STRING_MEMORY, String, Value},
 };
+use crate::engine::context::GcScope;
 
 pub(crate) struct FinalizationRegistryPrototype;
 
@@ -60,14 +56,7 @@ impl FinalizationRegistryPrototype {
             .with_constructor_property(finalization_registry_constructor)
             .with_builtin_function_property::<FinalizationRegistryPrototypeRegister>()
             .with_builtin_function_property::<FinalizationRegistryPrototypeUnregister>()
-            .with_property(|builder| {
-                builder
-                    .with_key(WellKnownSymbolIndexes::ToStringTag.into())
-                    .with_value_readonly(BUILTIN_STRING_MEMORY.FinalizationRegistry.into_value())
-                    .with_enumerable(false)
-                    .with_configurable(true)
-                    .build()
-            })
+            .with_to_string_tag(BUILTIN_STRING_MEMORY.FinalizationRegistry)
             .build();
     }
 }
@@ -10,8 +10,8 @@ use crate::{
             ordinary::ordinary_create_from_constructor,
         },
         execution::{
-            Agent, JsResult, ProtoIntrinsics, Realm, add_to_kept_objects, agent::ExceptionType,
-            can_be_held_weakly, throw_not_weak_key_error,
+            Agent, JsResult, ProtoIntrinsics, Realm, add_to_kept_objects, can_be_held_weakly,
+            throw_not_weak_key_error,
         },
         types::{BUILTIN_STRING_MEMORY, Function, IntoObject, IntoValue, Object, String, Value},
     },
@@ -47,11 +47,10 @@ impl WeakRefConstructor {
         let new_target = new_target.bind(gc.nogc());
         // 1. If NewTarget is undefined, throw a TypeError exception.
         let Some(new_target) = new_target else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
+            return agent.type_error(
                 "calling a builtin WeakRef constructor without new is forbidden",
                 gc.into_nogc(),
-            ));
+            );
         };
         let new_target = Function::try_from(new_target).unwrap();
         // 2. If CanBeHeldWeakly(target) is false, throw a TypeError exception.
@@ -4,18 +4,14 @@
 
 use crate::ecmascript::builtins::weak_ref::WeakRef;
 use crate::ecmascript::execution::add_to_kept_objects;
-use crate::ecmascript::execution::agent::ExceptionType;
 use crate::ecmascript::types::IntoValue;
-use crate::engine::context::{Bindable, GcScope};
-use crate::{
-    ecmascript::{
-        builders::ordinary_object_builder::OrdinaryObjectBuilder,
-        builtins::{ArgumentsList, Behaviour, Builtin},
-        execution::{Agent, JsResult, Realm},
-        types::{BUILTIN_STRING_MEMORY, String, Value},
-    },
-    heap::WellKnownSymbolIndexes,
+use crate::ecmascript::{
+    builders::ordinary_object_builder::OrdinaryObjectBuilder,
+    builtins::{ArgumentsList, Behaviour, Builtin},
+    execution::{Agent, JsResult, Realm},
+    types::{BUILTIN_STRING_MEMORY, String, Value},
 };
+use crate::engine::context::{Bindable, GcScope};
 
 pub(crate) struct WeakRefPrototype;
 
@@ -38,11 +34,7 @@ impl WeakRefPrototype {
         // 1. Let weakRef be the this value.
         // 2. Perform ? RequireInternalSlot(weakRef, [[WeakRefTarget]]).
         let Value::WeakRef(weak_ref) = this_value.bind(gc) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Receiver of WeakRef.deref call is not a WeakRef",
-                gc,
-            ));
+            return agent.type_error("Receiver of WeakRef.deref call is not a WeakRef", gc);
         };
         // 3. Return WeakRefDeref(weakRef).
         Ok(weak_ref_deref(agent, weak_ref))
@@ -59,14 +51,7 @@ impl WeakRefPrototype {
             .with_prototype(object_prototype)
             .with_constructor_property(weak_ref_constructor)
             .with_builtin_function_property::<WeakRefPrototypeDeref>()
-            .with_property(|builder| {
-                builder
-                    .with_key(WellKnownSymbolIndexes::ToStringTag.into())
-                    .with_value_readonly(BUILTIN_STRING_MEMORY.WeakRef.into_value())
-                    .with_enumerable(false)
-                    .with_configurable(true)
-                    .build()
-            })
+            .with_to_string_tag(BUILTIN_STRING_MEMORY.WeakRef)
             .build();
     }
 }
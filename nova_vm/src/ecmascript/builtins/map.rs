@@ -15,7 +15,8 @@ use crate::{
         rootable::{HeapRootData, HeapRootRef, Rootable},
     },
     heap::{
-        CompactionLists, CreateHeapData, HeapMarkAndSweep, HeapSweepWeakReference, WorkQueues,
+        CompactionLists, CreateHeapData, HeapIndexable, HeapMarkAndSweep, HeapSweepWeakReference,
+        WorkQueues,
         indexes::{BaseIndex, MapIndex},
     },
 };
@@ -121,6 +122,7 @@ impl Index<Map<'_>> for Agent {
 
 impl IndexMut<Map<'_>> for Agent {
     fn index_mut(&mut self, index: Map) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.maps[index]
     }
 }
@@ -145,6 +147,23 @@ impl IndexMut<Map<'_>> for Vec<Option<MapHeapData<'static>>> {
     }
 }
 
+impl HeapIndexable for Map<'_> {
+    type Data = MapHeapData<'static>;
+    const KIND: &'static str = "Map";
+
+    fn heap_index(self) -> usize {
+        self.get_index()
+    }
+
+    fn heap_vec(heap: &Heap) -> &[Option<Self::Data>] {
+        &heap.maps
+    }
+
+    fn heap_vec_mut(heap: &mut Heap) -> &mut [Option<Self::Data>] {
+        &mut heap.maps
+    }
+}
+
 impl Rootable for Map<'_> {
     type RootRepr = HeapRootRef;
 
@@ -57,6 +57,7 @@ impl Index<Module<'_>> for Agent {
 
 impl IndexMut<Module<'_>> for Agent {
     fn index_mut(&mut self, index: Module) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.modules[index]
     }
 }
@@ -484,9 +485,9 @@ impl<'a> InternalMethods<'a> for Module<'a> {
                     TryResult::Continue(Value::Undefined)
                 } else {
                     // 4. Let m be O.[[Module]].
-                    let m = &agent[self].module;
+                    let m = self;
                     // 5. Let binding be m.ResolveExport(P).
-                    let binding = m.resolve_export(property_key);
+                    let binding = m.resolve_export(agent, key, &mut vec![]);
                     // 6. Assert: binding is a ResolvedBinding Record.
                     let Some(data::ResolveExportResult::Resolved(binding)) = binding else {
                         unreachable!();
@@ -566,9 +567,9 @@ impl<'a> InternalMethods<'a> for Module<'a> {
                     Ok(Value::Undefined)
                 } else {
                     // 4. Let m be O.[[Module]].
-                    let m = &agent[self].module;
+                    let m = self;
                     // 5. Let binding be m.ResolveExport(P).
-                    let binding = m.resolve_export(property_key);
+                    let binding = m.resolve_export(agent, key, &mut vec![]);
                     // 6. Assert: binding is a ResolvedBinding Record.
                     let Some(data::ResolveExportResult::Resolved(binding)) = binding else {
                         unreachable!();
@@ -591,7 +592,7 @@ impl<'a> InternalMethods<'a> for Module<'a> {
                     match target_env {
                         None => Err(agent.throw_exception(
                             ExceptionType::ReferenceError,
-                            format!("Could not resolve module '{}'.", key.as_str(agent)),
+                            format!("Could not resolve module '{}'.", key.to_string_lossy(agent)),
                             gc.into_nogc(),
                         )),
                         Some(_target_env) => {
@@ -658,16 +659,16 @@ impl<'a> InternalMethods<'a> for Module<'a> {
         agent: &mut Agent,
         gc: NoGcScope<'gc, '_>,
     ) -> TryResult<Vec<PropertyKey<'gc>>> {
+        // 2. Let symbolKeys be OrdinaryOwnPropertyKeys(O).
+        let symbol_keys = self.get_backing_object(agent).map_or(vec![], |object| {
+            ordinary_own_property_keys(agent, object, gc)
+        });
         // 1. Let exports be O.[[Exports]].
         let exports = agent[self]
             .exports
             .iter()
             .map(|string| PropertyKey::from(*string));
         let exports_count = exports.len();
-        // 2. Let symbolKeys be OrdinaryOwnPropertyKeys(O).
-        let symbol_keys = self.get_backing_object(agent).map_or(vec![], |object| {
-            ordinary_own_property_keys(agent, object, gc)
-        });
         let symbol_keys_count = symbol_keys.len();
         // 3. Return the list-concatenation of exports and symbolKeys.
         let mut own_property_keys = Vec::with_capacity(exports_count + symbol_keys_count);
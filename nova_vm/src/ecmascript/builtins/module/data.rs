@@ -7,7 +7,7 @@ use small_string::SmallString;
 use crate::{
     ecmascript::{
         execution::{ModuleEnvironment, Realm},
-        types::{HeapString, OrdinaryObject, PropertyKey, String},
+        types::{HeapString, OrdinaryObject, String},
     },
     engine::context::{Bindable, NoGcScope},
     heap::{CompactionLists, HeapMarkAndSweep, WorkQueues},
@@ -22,7 +22,7 @@ pub struct ModuleHeapData<'a> {
     pub(crate) exports: Box<[String<'a>]>,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub(crate) struct ModuleRecord<'a> {
     /// \[\[Realm]]
     ///
@@ -43,21 +43,96 @@ pub(crate) struct ModuleRecord<'a> {
     /// Field reserved for use by host environments that need to associate
     /// additional information with a module.
     host_defined: (),
+    /// \[\[Status]]
+    pub(crate) status: ModuleStatus,
+    /// \[\[RequestedModules]]
+    ///
+    /// A List of all the ModuleSpecifier strings used by the module to
+    /// request the importation of a module, in source text occurrence order.
+    pub(crate) requested_modules: Box<[String<'a>]>,
+    /// \[\[LoadedModules]]
+    ///
+    /// A map from the specifier strings used by the module to the resolved
+    /// Module Records.
+    pub(crate) loaded_modules: Vec<(String<'a>, Module<'a>)>,
+    /// \[\[ImportEntries]]
+    pub(crate) import_entries: Box<[ImportEntry<'a>]>,
+    /// \[\[LocalExportEntries]]
+    pub(crate) local_export_entries: Box<[LocalExportEntry<'a>]>,
+    /// \[\[IndirectExportEntries]]
+    pub(crate) indirect_export_entries: Box<[IndirectExportEntry<'a>]>,
+    /// \[\[StarExportEntries]]
+    ///
+    /// The \[\[ModuleRequest]] of each `export * from` declaration.
+    pub(crate) star_export_entries: Box<[String<'a>]>,
+}
+
+/// ### \[\[Status]]
+///
+/// Nova does not load modules itself: the host adds a module's requested
+/// modules to its \[\[LoadedModules]] before linking it. The `new` and
+/// `unlinked` states are thus merged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ModuleStatus {
+    Unlinked,
+    Linking,
+    Linked,
 }
 
+/// ### [ImportEntry Record](https://tc39.es/ecma262/#importentry-record)
 #[derive(Debug, Clone, Copy)]
+pub(crate) struct ImportEntry<'a> {
+    /// \[\[ModuleRequest]]
+    pub(crate) module_request: String<'a>,
+    /// \[\[ImportName]]
+    ///
+    /// None if the import is a namespace-object import.
+    pub(crate) import_name: Option<String<'a>>,
+    /// \[\[LocalName]]
+    pub(crate) local_name: String<'a>,
+}
+
+/// ### [ExportEntry Record](https://tc39.es/ecma262/#exportentry-record)
+///
+/// An export of a binding declared in the module itself.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct LocalExportEntry<'a> {
+    /// \[\[ExportName]]
+    pub(crate) export_name: String<'a>,
+    /// \[\[LocalName]]
+    pub(crate) local_name: String<'a>,
+}
+
+/// ### [ExportEntry Record](https://tc39.es/ecma262/#exportentry-record)
+///
+/// A re-export of an imported binding or an `export * as ns from`
+/// declaration.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct IndirectExportEntry<'a> {
+    /// \[\[ExportName]]
+    pub(crate) export_name: String<'a>,
+    /// \[\[ModuleRequest]]
+    pub(crate) module_request: String<'a>,
+    /// \[\[ImportName]]
+    ///
+    /// None for `export * as ns from` declarations, ie. if the import name
+    /// is ALL.
+    pub(crate) import_name: Option<String<'a>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum ResolvedBindingName {
     String(HeapString<'static>),
     SmallString(SmallString),
     Namespace,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) struct ResolvedBinding {
     /// \[\[Module]]
-    pub(super) module: Option<Module<'static>>,
+    pub(crate) module: Option<Module<'static>>,
     /// \[\[BindingName]]
-    pub(super) binding_name: ResolvedBindingName,
+    pub(crate) binding_name: ResolvedBindingName,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -66,21 +141,28 @@ pub(crate) enum ResolveExportResult {
     Resolved(ResolvedBinding),
 }
 
-impl ModuleRecord<'_> {
-    /// Return the binding of a name exported by this module. Bindings are
-    /// represented by a ResolvedBinding Record, of the form { \[\[Module]]:
-    /// Module Record, \[\[BindingName]]: String | NAMESPACE }. If the export
-    /// is a Module Namespace Object without a direct binding in any module,
-    /// \[\[BindingName]] will be set to NAMESPACE. Return null if the name
-    /// cannot be resolved, or AMBIGUOUS if multiple bindings were found.
-    ///
-    /// Each time this operation is called with a specific exportName,
-    /// resolveSet pair as arguments it must return the same result.
-    ///
-    /// LoadRequestedModules must have completed successfully prior to
-    /// invoking this method.
-    pub(crate) fn resolve_export(&self, _property_key: PropertyKey) -> Option<ResolveExportResult> {
-        todo!()
+impl<'a> ModuleRecord<'a> {
+    pub(crate) fn new(
+        realm: Realm<'a>,
+        requested_modules: Box<[String<'a>]>,
+        import_entries: Box<[ImportEntry<'a>]>,
+        local_export_entries: Box<[LocalExportEntry<'a>]>,
+        indirect_export_entries: Box<[IndirectExportEntry<'a>]>,
+        star_export_entries: Box<[String<'a>]>,
+    ) -> Self {
+        Self {
+            realm,
+            environment: None,
+            namespace: None,
+            host_defined: (),
+            status: ModuleStatus::Unlinked,
+            requested_modules,
+            loaded_modules: Vec::new(),
+            import_entries,
+            local_export_entries,
+            indirect_export_entries,
+            star_export_entries,
+        }
     }
 }
 
@@ -111,10 +193,37 @@ impl HeapMarkAndSweep for ModuleHeapData<'static> {
             environment: _,
             namespace,
             host_defined: _,
+            status: _,
+            requested_modules,
+            loaded_modules,
+            import_entries,
+            local_export_entries,
+            indirect_export_entries,
+            star_export_entries,
         } = module;
         for ele in exports.iter() {
             ele.mark_values(queues);
         }
+        requested_modules.mark_values(queues);
+        for (specifier, module) in loaded_modules.iter() {
+            specifier.mark_values(queues);
+            module.mark_values(queues);
+        }
+        for entry in import_entries.iter() {
+            entry.module_request.mark_values(queues);
+            entry.import_name.mark_values(queues);
+            entry.local_name.mark_values(queues);
+        }
+        for entry in local_export_entries.iter() {
+            entry.export_name.mark_values(queues);
+            entry.local_name.mark_values(queues);
+        }
+        for entry in indirect_export_entries.iter() {
+            entry.export_name.mark_values(queues);
+            entry.module_request.mark_values(queues);
+            entry.import_name.mark_values(queues);
+        }
+        star_export_entries.mark_values(queues);
         realm.mark_values(queues);
         // environment.mark_values(queues);
         namespace.mark_values(queues);
@@ -132,10 +241,37 @@ impl HeapMarkAndSweep for ModuleHeapData<'static> {
             environment: _,
             namespace,
             host_defined: _,
+            status: _,
+            requested_modules,
+            loaded_modules,
+            import_entries,
+            local_export_entries,
+            indirect_export_entries,
+            star_export_entries,
         } = module;
         for ele in exports.iter_mut() {
             ele.sweep_values(compactions);
         }
+        requested_modules.sweep_values(compactions);
+        for (specifier, module) in loaded_modules.iter_mut() {
+            specifier.sweep_values(compactions);
+            module.sweep_values(compactions);
+        }
+        for entry in import_entries.iter_mut() {
+            entry.module_request.sweep_values(compactions);
+            entry.import_name.sweep_values(compactions);
+            entry.local_name.sweep_values(compactions);
+        }
+        for entry in local_export_entries.iter_mut() {
+            entry.export_name.sweep_values(compactions);
+            entry.local_name.sweep_values(compactions);
+        }
+        for entry in indirect_export_entries.iter_mut() {
+            entry.export_name.sweep_values(compactions);
+            entry.module_request.sweep_values(compactions);
+            entry.import_name.sweep_values(compactions);
+        }
+        star_export_entries.sweep_values(compactions);
         realm.sweep_values(compactions);
         // environment.sweep_values(compactions);
         namespace.sweep_values(compactions);
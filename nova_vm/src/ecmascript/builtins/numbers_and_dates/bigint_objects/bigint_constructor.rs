@@ -3,23 +3,20 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use num_bigint::ToBigInt;
-use num_traits::Pow;
 
 use crate::ecmascript::abstract_operations::testing_and_comparison::is_integral_number;
 use crate::ecmascript::abstract_operations::type_conversion::PreferredType;
-use crate::ecmascript::abstract_operations::type_conversion::to_big_int;
 use crate::ecmascript::abstract_operations::type_conversion::to_big_int_primitive;
-use crate::ecmascript::abstract_operations::type_conversion::to_index;
 use crate::ecmascript::abstract_operations::type_conversion::to_primitive;
 use crate::ecmascript::builders::builtin_function_builder::BuiltinFunctionBuilder;
 use crate::ecmascript::builtins::ArgumentsList;
 use crate::ecmascript::builtins::Behaviour;
 use crate::ecmascript::builtins::Builtin;
 use crate::ecmascript::builtins::BuiltinIntrinsicConstructor;
+use crate::ecmascript::builtins::argument_coercion::coerce_arguments;
 use crate::ecmascript::execution::Agent;
 use crate::ecmascript::execution::JsResult;
 use crate::ecmascript::execution::Realm;
-use crate::ecmascript::execution::agent::ExceptionType;
 use crate::ecmascript::types::BUILTIN_STRING_MEMORY;
 use crate::ecmascript::types::BigInt;
 use crate::ecmascript::types::BigIntHeapData;
@@ -31,8 +28,6 @@ use crate::ecmascript::types::{String, Value};
 
 use crate::SmallInteger;
 use crate::engine::context::{Bindable, GcScope};
-use crate::engine::rootable::Scopable;
-use crate::engine::small_bigint::SmallBigInt;
 use crate::heap::CreateHeapData;
 use crate::heap::IntrinsicConstructorIndexes;
 
@@ -48,6 +43,9 @@ impl BuiltinIntrinsicConstructor for BigIntConstructor {
     const INDEX: IntrinsicConstructorIndexes = IntrinsicConstructorIndexes::BigInt;
 }
 
+/// Largest number of bits in a BigInt created by `BigInt.asUintN`.
+const MAX_BIGINT_BITS: u64 = 1 << 30;
+
 struct BigIntAsIntN;
 impl Builtin for BigIntAsIntN {
     const BEHAVIOUR: Behaviour = Behaviour::Regular(BigIntConstructor::as_int_n);
@@ -70,11 +68,7 @@ impl BigIntConstructor {
         mut gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
         if new_target.is_some() {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "BigInt is not a constructor",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("BigInt is not a constructor", gc.into_nogc());
         }
         let value = arguments.get(0).bind(gc.nogc());
         let prim = to_primitive(
@@ -87,11 +81,10 @@ impl BigIntConstructor {
         .bind(gc.nogc());
         if let Ok(prim) = Number::try_from(prim) {
             if !prim.is_integer(agent) {
-                return Err(agent.throw_exception_with_static_message(
-                    ExceptionType::RangeError,
+                return agent.range_error(
                     "Can't convert number to BigInt because it isn't an integer",
                     gc.into_nogc(),
-                ));
+                );
             }
 
             Ok(BigInt::from_i64(agent, prim.into_i64(agent)).into_value())
@@ -105,132 +98,72 @@ impl BigIntConstructor {
     fn as_int_n<'gc>(
         agent: &mut Agent,
         _this_value: Value,
-        arguments: ArgumentsList,
+        mut arguments: ArgumentsList,
         mut gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
-        let bits = arguments.get(0).bind(gc.nogc());
-        let bigint = arguments.get(1).scope(agent, gc.nogc());
-        let bits = to_index(agent, bits.unbind(), gc.reborrow())
-            .unbind()?
-            .bind(gc.nogc());
-        let Ok(bits) = u32::try_from(bits) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::RangeError,
-                "Ridiculous bits value for BigInt.asIntN",
-                gc.into_nogc(),
-            ));
+        coerce_arguments!(agent, arguments, gc => {
+            // 1. Set bits to ? ToIndex(bits).
+            let bits = to_index_arg(0);
+            // 2. Set bigint to ? ToBigInt(bigint).
+            let bigint = to_big_int_arg(1);
+        });
+        let bigint = match bigint {
+            BigInt::BigInt(bigint) => agent[bigint].data.clone(),
+            BigInt::SmallBigInt(bigint) => num_bigint::BigInt::from(bigint.into_i64()),
         };
-        let bigint = to_big_int(agent, bigint.get(agent), gc.reborrow())
-            .unbind()?
-            .bind(gc.nogc());
+        // 3. Let mod be ℝ(bigint) modulo 2**bits.
+        // 4. If mod ≥ 2**(bits - 1), return ℤ(mod - 2**bits); otherwise,
+        //    return ℤ(mod).
+        // NOTE: A BigInt whose magnitude has fewer than bits bits is its own
+        // result, so 2**bits is only computed for bits no larger than the
+        // BigInt itself.
+        let bits = bits as u64;
         if bits == 0 {
             return Ok(BigInt::zero().into_value());
         }
-
-        match 2i64.checked_pow(bits) {
-            Some(divisor) => {
-                match bigint {
-                    BigInt::BigInt(bigint) => {
-                        let modulo = &agent[bigint].data % divisor;
-                        // SAFETY: This cannot overflow since 2^bits didn't.
-                        let divisor_half = divisor >> 1;
-                        if let Ok(modulo) = i64::try_from(&modulo) {
-                            let modulo = if modulo >= divisor_half {
-                                modulo - divisor
-                            } else {
-                                modulo
-                            };
-                            Ok(BigInt::from_i64(agent, modulo).into_value())
-                        } else {
-                            Ok(BigInt::from_num_bigint(agent, modulo - divisor).into_value())
-                        }
-                    }
-                    BigInt::SmallBigInt(bigint) => {
-                        let bigint = bigint.into_i64();
-                        let modulo = bigint.rem_euclid(divisor);
-                        let modulo = if modulo >= 2i64.pow(bits - 1) {
-                            modulo - divisor
-                        } else {
-                            modulo
-                        };
-                        Ok(BigInt::from(SmallBigInt::try_from(modulo).unwrap()).into_value())
-                    }
-                }
-            }
-            _ => {
-                let divisor =
-                    num_bigint::BigInt::from_bytes_le(num_bigint::Sign::Plus, &[2]).pow(bits);
-                match bigint {
-                    BigInt::BigInt(bigint) => {
-                        let modulo = &agent[bigint].data % &divisor;
-                        let divisor_half = &divisor >> 1;
-                        if let Ok(modulo) = i64::try_from(&modulo) {
-                            // Maybe safe? Maybe not.
-                            Ok(BigInt::from_i64(agent, modulo).into_value())
-                        } else {
-                            let modulo = if modulo >= divisor_half {
-                                modulo - divisor
-                            } else {
-                                modulo
-                            };
-                            Ok(BigInt::from_num_bigint(agent, modulo).into_value())
-                        }
-                    }
-                    BigInt::SmallBigInt(_) => {
-                        // Probably safe: The divisor is bigger than i64 but
-                        // value is i54.
-                        Ok(bigint.into_value().unbind())
-                    }
-                }
-            }
+        if bigint.bits() < bits {
+            return Ok(BigInt::from_num_bigint(agent, bigint).into_value());
         }
+        let modulus = num_bigint::BigInt::from(1) << bits;
+        let modulo: num_bigint::BigInt = bigint & (&modulus - 1);
+        let result = if modulo.bit(bits - 1) {
+            modulo - modulus
+        } else {
+            modulo
+        };
+        Ok(BigInt::from_num_bigint(agent, result).into_value())
     }
 
     /// ### [21.2.2.2 BigInt.asUintN ( bits, bigint )](https://tc39.es/ecma262/#sec-bigint.asuintn)
     fn as_uint_n<'gc>(
         agent: &mut Agent,
         _this_value: Value,
-        arguments: ArgumentsList,
+        mut arguments: ArgumentsList,
         mut gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
-        let bits = arguments.get(0).bind(gc.nogc());
-        let bigint = arguments.get(1).scope(agent, gc.nogc());
-        let bits = to_index(agent, bits.unbind(), gc.reborrow())
-            .unbind()?
-            .bind(gc.nogc());
-        let Ok(bits) = u32::try_from(bits) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::RangeError,
-                "Ridiculous bits value for BigInt.asUintN",
-                gc.into_nogc(),
-            ));
+        coerce_arguments!(agent, arguments, gc => {
+            // 1. Set bits to ? ToIndex(bits).
+            let bits = to_index_arg(0);
+            // 2. Set bigint to ? ToBigInt(bigint).
+            let bigint = to_big_int_arg(1);
+        });
+        let bigint = match bigint {
+            BigInt::BigInt(bigint) => agent[bigint].data.clone(),
+            BigInt::SmallBigInt(bigint) => num_bigint::BigInt::from(bigint.into_i64()),
         };
-        let bigint = to_big_int(agent, bigint.get(agent), gc.reborrow())
-            .unbind()?
-            .bind(gc.nogc());
-        match bigint {
-            BigInt::BigInt(int) => {
-                let int = &agent[int].data;
-                let modulus = 2i64.pow(bits);
-                Ok(
-                    BigInt::from_num_bigint(agent, ((int % modulus) + modulus) % modulus)
-                        .into_value(),
-                )
-            }
-            BigInt::SmallBigInt(int) => {
-                let int = int.into_i64();
-                if let Some(modulo) = 2i64
-                    .checked_pow(bits)
-                    .and_then(|base| int.checked_rem_euclid(base))
-                {
-                    Ok(BigInt::from(SmallBigInt::try_from(modulo).unwrap()).into_value())
-                } else {
-                    let modulus = num_bigint::BigInt::from(2).pow(bits);
-                    let result = ((int % modulus.clone()) + modulus.clone()) % modulus;
-                    Ok(BigInt::from_num_bigint(agent, result).into_value())
-                }
-            }
+        // 3. Return ℤ(ℝ(bigint) modulo 2**bits).
+        // NOTE: A non-negative BigInt with at most bits bits is its own
+        // result. A negative one gives a result of bits bits, which must fit
+        // into a BigInt.
+        let bits = bits as u64;
+        if bigint.sign() != num_bigint::Sign::Minus && bigint.bits() <= bits {
+            return Ok(BigInt::from_num_bigint(agent, bigint).into_value());
+        }
+        if bits > MAX_BIGINT_BITS {
+            return agent.range_error("Maximum BigInt size exceeded", gc.into_nogc());
         }
+        let mask = (num_bigint::BigInt::from(1) << bits) - 1;
+        Ok(BigInt::from_num_bigint(agent, bigint & mask).into_value())
     }
 
     pub(crate) fn create_intrinsic(agent: &mut Agent, realm: Realm<'static>) {
@@ -253,11 +186,7 @@ fn number_to_big_int<'a>(
 ) -> JsResult<'a, BigInt<'a>> {
     let gc = gc.into_nogc();
     if !is_integral_number(agent, value) {
-        Err(agent.throw_exception_with_static_message(
-            ExceptionType::RangeError,
-            "Not an integer",
-            gc,
-        ))
+        agent.range_error("Not an integer", gc)
     } else {
         match value {
             Number::Number(idx) => {
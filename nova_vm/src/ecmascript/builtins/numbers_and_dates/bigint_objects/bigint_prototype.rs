@@ -4,17 +4,14 @@
 
 use crate::ecmascript::abstract_operations::type_conversion::to_integer_or_infinity;
 use crate::ecmascript::builtins::Behaviour;
+use crate::ecmascript::{
+    builders::ordinary_object_builder::OrdinaryObjectBuilder,
+    builtins::{ArgumentsList, Builtin, primitive_objects::PrimitiveObjectData},
+    execution::{Agent, JsResult, Realm},
+    types::{BUILTIN_STRING_MEMORY, BigInt, IntoValue, String, Value},
+};
 use crate::engine::context::{Bindable, GcScope, NoGcScope};
 use crate::engine::rootable::Scopable;
-use crate::{
-    ecmascript::{
-        builders::ordinary_object_builder::OrdinaryObjectBuilder,
-        builtins::{ArgumentsList, Builtin, primitive_objects::PrimitiveObjectData},
-        execution::{Agent, JsResult, Realm, agent::ExceptionType},
-        types::{BUILTIN_STRING_MEMORY, BigInt, IntoValue, String, Value},
-    },
-    heap::WellKnownSymbolIndexes,
-};
 
 pub(crate) struct BigIntPrototype;
 
@@ -46,13 +43,21 @@ impl Builtin for BigIntPrototypeValueOf {
 }
 
 impl BigIntPrototype {
+    /// ### [21.2.3.2 BigInt.prototype.toLocaleString ( \[ reserved1 \[ , reserved2 \] \] )](https://tc39.es/ecma262/#sec-bigint.prototype.tolocalestring)
+    ///
+    /// Without ECMA-402 support this produces the same String as
+    /// `BigInt.prototype.toString()`. The arguments are locales and options,
+    /// not a radix, so they are ignored.
     fn to_locale_string<'gc>(
         agent: &mut Agent,
         this_value: Value,
-        arguments: ArgumentsList,
+        _: ArgumentsList,
         gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
-        Self::to_string(agent, this_value, arguments, gc)
+        let x = this_big_int_value(agent, this_value, gc.nogc())
+            .unbind()?
+            .bind(gc.nogc());
+        Ok(BigInt::to_string_radix_10(agent, x.unbind(), gc.into_nogc()).into_value())
     }
 
     /// ### [21.2.3.3 BigInt.prototype.toString ( [ radix ] )](https://tc39.es/ecma262/#sec-bigint.prototype.tostring)
@@ -66,6 +71,7 @@ impl BigIntPrototype {
         arguments: ArgumentsList,
         mut gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
+        // 1. Let x be ? ThisBigIntValue(this value).
         let x = this_big_int_value(agent, this_value, gc.nogc())
             .unbind()?
             .scope(agent, gc.nogc());
@@ -83,11 +89,10 @@ impl BigIntPrototype {
             let radix = radix.bind(gc);
             // 4. If radixMV is not in the inclusive interval from 2 to 36, throw a RangeError exception.
             if !(2..=36).contains(&radix) {
-                return Err(agent.throw_exception_with_static_message(
-                    ExceptionType::RangeError,
+                return agent.range_error(
                     "radix must be an integer at least 2 and no greater than 36",
                     gc,
-                ));
+                );
             }
             let radix = radix.into_i64() as u32;
             // 5. Return BigInt::toString(x, radixMV).
@@ -95,12 +100,14 @@ impl BigIntPrototype {
         }
     }
 
+    /// ### [21.2.3.4 BigInt.prototype.valueOf ( )](https://tc39.es/ecma262/#sec-bigint.prototype.valueof)
     fn value_of<'gc>(
         agent: &mut Agent,
         this_value: Value,
         _: ArgumentsList,
         gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
+        // 1. Return ? ThisBigIntValue(this value).
         this_big_int_value(agent, this_value, gc.into_nogc()).map(|result| result.into_value())
     }
 
@@ -117,14 +124,7 @@ impl BigIntPrototype {
             .with_builtin_function_property::<BigIntPrototypeToLocaleString>()
             .with_builtin_function_property::<BigIntPrototypeToString>()
             .with_builtin_function_property::<BigIntPrototypeValueOf>()
-            .with_property(|builder| {
-                builder
-                    .with_key(WellKnownSymbolIndexes::ToStringTag.into())
-                    .with_value_readonly(BUILTIN_STRING_MEMORY.BigInt.into())
-                    .with_enumerable(false)
-                    .with_configurable(true)
-                    .build()
-            })
+            .with_to_string_tag(BUILTIN_STRING_MEMORY.BigInt)
             .build();
     }
 }
@@ -154,10 +154,6 @@ fn this_big_int_value<'a>(
             }
         }
         // 3. Throw a TypeError exception.
-        _ => Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Not a BigInt",
-            gc,
-        )),
+        _ => agent.type_error("Not a BigInt", gc),
     }
 }
@@ -2,8 +2,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use std::time::SystemTime;
-
+use crate::ecmascript::abstract_operations::type_conversion::to_primitive;
 use crate::ecmascript::builtins::Behaviour;
 use crate::ecmascript::builtins::Builtin;
 use crate::ecmascript::builtins::BuiltinIntrinsicConstructor;
@@ -21,7 +20,6 @@ use crate::ecmascript::types::BUILTIN_STRING_MEMORY;
 use crate::ecmascript::types::Function;
 use crate::ecmascript::types::IntoObject;
 use crate::ecmascript::types::IntoValue;
-use crate::ecmascript::types::Number;
 use crate::ecmascript::types::Object;
 use crate::ecmascript::types::{String, Value};
 use crate::ecmascript::{
@@ -33,7 +31,6 @@ use crate::ecmascript::{
 use crate::engine::context::Bindable;
 use crate::engine::context::GcScope;
 use crate::heap::IntrinsicConstructorIndexes;
-use crate::{SmallInteger, ecmascript::abstract_operations::type_conversion::to_primitive};
 use crate::{
     ecmascript::builders::builtin_function_builder::BuiltinFunctionBuilder,
     engine::rootable::Scopable,
@@ -89,7 +86,7 @@ impl DateConstructor {
         // 1. If NewTarget is undefined, then
         let Some(new_target) = new_target else {
             // a. Let now be the time value (UTC) identifying the current time.
-            let now = DateValue::now();
+            let now = DateValue::now(agent);
             // b. Return ToDateString(now).
             return Ok(Value::from_string(
                 agent,
@@ -103,7 +100,7 @@ impl DateConstructor {
             // 3. If numberOfArgs = 0, then
             0 => {
                 // a. Let dv be the time value (UTC) identifying the current time.
-                DateValue::now()
+                DateValue::now(agent)
             }
             // 4. Else if numberOfArgs = 1, then
             1 => {
@@ -124,7 +121,7 @@ impl DateConstructor {
                     if let Ok(v) = String::try_from(v) {
                         // 1. Assert: The next step never returns an abrupt completion because v is a String.
                         // 2. Let tv be the result of parsing v as a date, in exactly the same manner as for the parse method (21.4.3.2).
-                        parse_date::parse(agent, v.as_str(agent))
+                        parse_date_string(agent, v)
                     }
                     // iii. Else,
                     else {
@@ -216,21 +213,12 @@ impl DateConstructor {
     ///
     /// This function returns the time value designating the UTC date and time of the occurrence of the call to it.
     fn now<'gc>(
-        _agent: &mut Agent,
+        agent: &mut Agent,
         _this_value: Value,
         _arguments: ArgumentsList,
         _gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
-        let time_value = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap()
-            .as_millis();
-        assert_eq!(time_value as u64 as u128, time_value);
-        let time_value = time_value as u64;
-        Ok(
-            Number::from(SmallInteger::try_from(time_value).expect("SystemTime is beyond range"))
-                .into_value(),
-        )
+        Ok(DateValue::now(agent).into_value())
     }
 
     /// ### [21.4.3.2 Date.parse ( string )](https://tc39.es/ecma262/#sec-date.parse)
@@ -284,7 +272,7 @@ impl DateConstructor {
             .to_string(agent, gc.reborrow())
             .unbind()?
             .bind(gc.nogc());
-        let parsed = parse_date::parse(agent, input.as_str(agent));
+        let parsed = parse_date_string(agent, input);
         Ok(Value::from_f64(agent, parsed, gc.into_nogc()))
     }
 
@@ -379,6 +367,14 @@ impl DateConstructor {
     }
 }
 
+/// Parses a String as a date in the same manner as Date.parse. A String with
+/// lone surrogates is never a date.
+fn parse_date_string(agent: &Agent, date: String) -> f64 {
+    date.as_wtf8(agent)
+        .as_str()
+        .map_or(f64::NAN, |date| parse_date::parse(agent, date))
+}
+
 /// Ported from Boa JS engine. Source https://github.com/boa-dev/boa/blob/13a030a0aa452e6f78e4a7e8bbc0e11b878bbd58/core/engine/src/builtins/date/utils.rs#L745
 ///
 /// Copyright (c) 2019 Jason Williams
@@ -9,6 +9,7 @@ use crate::{
             operations_on_objects::invoke,
             type_conversion::{
                 PreferredType, ordinary_to_primitive, to_integer_or_infinity_f64, to_object,
+                to_primitive,
             },
         },
         builders::ordinary_object_builder::OrdinaryObjectBuilder,
@@ -573,7 +574,7 @@ impl DatePrototype {
             time_within_day(t),
         );
         // 8. Let u be TimeClip(UTC(newDate)).
-        let u = time_clip(utc(agent, new_date));
+        let u = time_clip(utc_from_local_or_utc_time::<UTC>(agent, new_date));
         // 9. Set dateObject.[[DateValue]] to u.
         date_object.get(agent).set_date_value(agent, u);
         // 10. Return u.
@@ -640,7 +641,7 @@ impl DatePrototype {
         // 8. Let newDate be MakeDate(MakeDay(y, m, dt), TimeWithinDay(t)).
         let new_date = make_date(make_day(y, m, dt), time_within_day(t));
         // 9. Let u be TimeClip(UTC(newDate)).
-        let u = time_clip(utc(agent, new_date));
+        let u = time_clip(utc_from_local_or_utc_time::<UTC>(agent, new_date));
         // 10. Set dateObject.[[DateValue]] to u.
         date_object.get(agent).set_date_value(agent, u);
         // 11. Return u.
@@ -724,7 +725,7 @@ impl DatePrototype {
         // 13. Let date be MakeDate(Day(t), MakeTime(h, m, s, milli)).
         let date = make_date(day(t), make_time(h, m, s, milli));
         // 14. Let u be TimeClip(UTC(date)).
-        let u = time_clip(utc(agent, date));
+        let u = time_clip(utc_from_local_or_utc_time::<UTC>(agent, date));
         // 15. Set dateObject.[[DateValue]] to u.
         date_object.get(agent).set_date_value(agent, u);
         // 16. Return u.
@@ -765,7 +766,10 @@ impl DatePrototype {
             ms,
         );
         // 8. Let u be TimeClip(UTC(MakeDate(Day(t), time))).
-        let u = time_clip(utc(agent, make_date(day(t), time)));
+        let u = time_clip(utc_from_local_or_utc_time::<UTC>(
+            agent,
+            make_date(day(t), time),
+        ));
         // 9. Set dateObject.[[DateValue]] to u.
         date_object.get(agent).set_date_value(agent, u);
         // 10. Return u.
@@ -835,7 +839,7 @@ impl DatePrototype {
         // 11. Let date be MakeDate(Day(t), MakeTime(HourFromTime(t), m, s, milli)).
         let date = make_date(day(t), make_time(hour_from_time(t) as f64, m, s, milli));
         // 12. Let u be TimeClip(UTC(date)).
-        let u = time_clip(utc(agent, date));
+        let u = time_clip(utc_from_local_or_utc_time::<UTC>(agent, date));
         // 13. Set dateObject.[[DateValue]] to u.
         date_object.get(agent).set_date_value(agent, u);
         // 14. Return u.
@@ -894,7 +898,7 @@ impl DatePrototype {
             time_within_day(t),
         );
         // 10. Let u be TimeClip(UTC(newDate)).
-        let u = time_clip(utc(agent, new_date));
+        let u = time_clip(utc_from_local_or_utc_time::<UTC>(agent, new_date));
         // 11. Set dateObject.[[DateValue]] to u.
         date_object.get(agent).set_date_value(agent, u);
         // 12. Return u.
@@ -953,7 +957,7 @@ impl DatePrototype {
             make_time(hour_from_time(t) as f64, min_from_time(t) as f64, s, milli),
         );
         // 10. Let u be TimeClip(UTC(date)).
-        let u = time_clip(utc(agent, date));
+        let u = time_clip(utc_from_local_or_utc_time::<UTC>(agent, date));
         // 11. Set dateObject.[[DateValue]] to u.
         date_object.get(agent).set_date_value(agent, u);
         // 12. Return u.
@@ -1030,11 +1034,7 @@ impl DatePrototype {
         let tv = date_object.date_value(agent);
         // 4. If tv is NaN, throw a RangeError exception.
         let Some(tv) = tv.get_f64() else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::RangeError,
-                "Invalid Date",
-                gc.into_nogc(),
-            ));
+            return agent.range_error("Invalid Date", gc.into_nogc());
         };
         // 5. Assert: tv is an integral Number.
         assert!(tv.fract() == 0.0);
@@ -1042,8 +1042,14 @@ impl DatePrototype {
         // the Date Time String Format, throw a RangeError exception.
         // 7. Return a String representation of tv in the Date Time String Format
         // on the UTC time scale, including all format elements and the UTC offset representation "Z".
-        // (The format is "YYYY-MM-DDTHH:mm:ss.sssZ")
+        // (The format is "YYYY-MM-DDTHH:mm:ss.sssZ", with the expanded year
+        // format "±YYYYYY" used for years outside of 0 to 9999.)
         let year = year_from_time(tv);
+        let year = if (0..=9999).contains(&year) {
+            format!("{year:04}")
+        } else {
+            format!("{year:+07}")
+        };
         let month = month_from_time(tv) + 1;
         let day = date_from_time(tv);
         let hour = hour_from_time(tv);
@@ -1051,7 +1057,7 @@ impl DatePrototype {
         let second = sec_from_time(tv);
         let ms = ms_from_time(tv);
         let date_string =
-            format!("{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}.{ms:03}Z");
+            format!("{year}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}.{ms:03}Z");
         Ok(Value::from_string(agent, date_string, gc.into_nogc()))
     }
 
@@ -1081,9 +1087,14 @@ impl DatePrototype {
             .unbind()?
             .scope(agent, gc.nogc());
         // 2. Let tv be ? ToPrimitive(O, number).
-        let tv = ordinary_to_primitive(agent, o.get(agent), PreferredType::Number, gc.reborrow())
-            .unbind()?
-            .bind(gc.nogc());
+        let tv = to_primitive(
+            agent,
+            o.get(agent),
+            Some(PreferredType::Number),
+            gc.reborrow(),
+        )
+        .unbind()?
+        .bind(gc.nogc());
         // 3. If tv is a Number and tv is not finite, return null.
         if let Ok(tv) = Number::try_from(tv) {
             if !tv.is_finite(agent) {
@@ -1343,7 +1354,9 @@ impl DatePrototype {
         let Ok(o) = Object::try_from(this_value) else {
             let error_message = format!(
                 "{} is not an object",
-                this_value.string_repr(agent, gc.reborrow()).as_str(agent)
+                this_value
+                    .string_repr(agent, gc.reborrow())
+                    .to_string_lossy(agent)
             );
             return Err(agent.throw_exception(
                 ExceptionType::TypeError,
@@ -1368,7 +1381,7 @@ impl DatePrototype {
                 "Expected 'hint' to be \"string\", \"default\", or \"number\", got {}",
                 hint.unbind()
                     .string_repr(agent, gc.reborrow())
-                    .as_str(agent)
+                    .to_string_lossy(agent)
             );
             return Err(agent.throw_exception(
                 ExceptionType::TypeError,
@@ -1449,11 +1462,7 @@ fn require_internal_slot_date<'a>(
 ) -> JsResult<'a, Date<'a>> {
     match this_value {
         Value::Date(date) => Ok(date.bind(gc)),
-        _ => Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "this is not a Date object.",
-            gc,
-        )),
+        _ => agent.type_error("this is not a Date object.", gc),
     }
 }
 
@@ -1912,14 +1921,18 @@ fn get_named_time_zone_offset_nanoseconds(
 /// > For example, if the host environment is a browser on a system where the
 /// > user has chosen US Eastern Time as their time zone,
 /// > SystemTimeZoneIdentifier returns "America/New_York".
-fn system_time_zone_identifier(_agent: &Agent) -> &'static str {
+fn system_time_zone_identifier(agent: &Agent) -> &'static str {
     // 1. If the implementation only supports the UTC time zone, return "UTC".
     // 2. Let systemTimeZoneString be the String representing the host environment's
     // current time zone, either a primary time zone identifier or an offset time zone identifier.
     // 3. Return systemTimeZoneString.
-
-    // TODO: implement this
-    "UTC"
+    let system_time_zone_string = agent.host_hooks.system_time_zone_identifier();
+    // NOTE: Named time zones other than UTC are not supported.
+    if is_time_zone_offset_string(system_time_zone_string) {
+        system_time_zone_string
+    } else {
+        "UTC"
+    }
 }
 
 /// ### [21.4.1.25 LocalTime ( t )](https://tc39.es/ecma262/#sec-localtime)
@@ -1977,6 +1990,13 @@ fn local_or_utc_time<const UTC: bool>(agent: &mut Agent, t: f64) -> f64 {
     if UTC { t } else { local_time(agent, t) }
 }
 
+/// Inverse of [`local_or_utc_time`]: the `setUTC*` methods compute a new time
+/// value in UTC directly, and the local time methods convert theirs with
+/// [`utc`].
+fn utc_from_local_or_utc_time<const UTC: bool>(agent: &mut Agent, t: f64) -> f64 {
+    if UTC { t } else { utc(agent, t) }
+}
+
 /// ### [21.4.1.26 UTC ( t )](https://tc39.es/ecma262/#sec-utc-t)
 ///
 /// The abstract operation UTC takes argument t (a Number) and returns a time
@@ -2240,46 +2260,118 @@ pub(super) fn make_full_year(year: f64) -> f64 {
 /// (a String) and returns a Boolean. The return value indicates whether
 /// offsetString conforms to the grammar given by UTCOffset. It performs the
 /// following steps when called:
-fn is_time_zone_offset_string(_offset_string: &str) -> bool {
+fn is_time_zone_offset_string(offset_string: &str) -> bool {
     // 1. Let parseResult be ParseText(offsetString, UTCOffset).
-    let parse_result: Result<(), ()> = Err(()); // TODO:
+    let parse_result = parse_utc_offset(offset_string);
     // 2. If parseResult is a List of errors, return false.
-    if parse_result.is_err() {
+    if parse_result.is_none() {
         return false;
     }
     // 3. Return true.
     true
 }
 
+/// Parse Nodes of a [UTCOffset](https://tc39.es/ecma262/#prod-UTCOffset).
+struct UtcOffset<'a> {
+    negative: bool,
+    hour: &'a str,
+    minute: Option<&'a str>,
+    second: Option<&'a str>,
+    fraction: Option<&'a str>,
+}
+
+/// ### [21.4.1.33 Time Zone Offset String Format](https://tc39.es/ecma262/#sec-time-zone-offset-strings)
+///
+/// Parses `offset_string` as a UTCOffset: an ASCIISign followed by two digit
+/// hours and optional minutes, seconds and fraction of a second, either all
+/// separated by colons or not separated at all.
+fn parse_utc_offset(offset_string: &str) -> Option<UtcOffset<'_>> {
+    let negative = match offset_string.as_bytes().first()? {
+        b'+' => false,
+        b'-' => true,
+        _ => return None,
+    };
+    let rest = &offset_string[1..];
+    let is_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    // Hour ::: one of 00 to 23
+    let hour = rest
+        .get(..2)
+        .filter(|hour| is_digits(hour) && *hour <= "23")?;
+    let mut rest = &rest[2..];
+    let mut result = UtcOffset {
+        negative,
+        hour,
+        minute: None,
+        second: None,
+        fraction: None,
+    };
+    if rest.is_empty() {
+        return Some(result);
+    }
+    let extended = rest.starts_with(':');
+    // MinuteSecond ::: one of 00 to 59
+    fn minute_second<'a>(rest: &mut &'a str, extended: bool) -> Option<&'a str> {
+        if extended {
+            *rest = rest.strip_prefix(':')?;
+        }
+        let value = rest
+            .get(..2)
+            .filter(|value| value.bytes().all(|b| b.is_ascii_digit()) && *value <= "59")?;
+        *rest = &rest[2..];
+        Some(value)
+    }
+    result.minute = Some(minute_second(&mut rest, extended)?);
+    if rest.is_empty() {
+        return Some(result);
+    }
+    result.second = Some(minute_second(&mut rest, extended)?);
+    if rest.is_empty() {
+        return Some(result);
+    }
+    // TemporalDecimalFraction ::: TemporalDecimalSeparator DecimalDigit{1,9}
+    let fraction = rest.strip_prefix(['.', ','])?;
+    if fraction.is_empty() || fraction.len() > 9 || !is_digits(fraction) {
+        return None;
+    }
+    result.fraction = Some(fraction);
+    Some(result)
+}
+
 /// ### [21.4.1.33.2 ParseTimeZoneOffsetString ( offsetString )](https://tc39.es/ecma262/#sec-parsetimezoneoffsetstring)
 ///
 /// The abstract operation ParseTimeZoneOffsetString takes argument
 /// offsetString (a String) and returns an integer. The return value is the UTC
 /// offset, as a number of nanoseconds, that corresponds to the String
 /// offsetString.
-fn parse_time_zone_offset_string(_offset_string: &str) -> f64 {
+fn parse_time_zone_offset_string(offset_string: &str) -> f64 {
     // 1. Let parseResult be ParseText(offsetString, UTCOffset).
     // 2. Assert: parseResult is not a List of errors.
+    let parse_result = parse_utc_offset(offset_string).unwrap();
     // 3. Assert: parseResult contains a ASCIISign Parse Node.
     // 4. Let parsedSign be the source text matched by the ASCIISign Parse Node contained within parseResult.
     // 5. If parsedSign is the single code point U+002D (HYPHEN-MINUS), then
     //    a. Let sign be -1.
     // 6. Else,
     //    a. Let sign be 1.
+    let sign = if parse_result.negative { -1.0 } else { 1.0 };
     // 7. NOTE: Applications of StringToNumber below do not lose precision, since each of the parsed values is guaranteed to be a sufficiently short string of decimal digits.
+    let to_number = |digits: &str| digits.parse::<u32>().unwrap() as f64;
     // 8. Assert: parseResult contains an Hour Parse Node.
     // 9. Let parsedHours be the source text matched by the Hour Parse Node contained within parseResult.
     // 10. Let hours be ℝ(StringToNumber(CodePointsToString(parsedHours))).
+    let hours = to_number(parse_result.hour);
     // 11. If parseResult does not contain a MinuteSecond Parse Node, then
     //     a. Let minutes be 0.
     // 12. Else,
     //     a. Let parsedMinutes be the source text matched by the first MinuteSecond Parse Node contained within parseResult.
     //     b. Let minutes be ℝ(StringToNumber(CodePointsToString(parsedMinutes))).
+    let minutes = parse_result.minute.map_or(0.0, to_number);
     // 13. If parseResult does not contain two MinuteSecond Parse Nodes, then
     //     a. Let seconds be 0.
     // 14. Else,
     //     a. Let parsedSeconds be the source text matched by the second MinuteSecond Parse Node contained within parseResult.
     //     b. Let seconds be ℝ(StringToNumber(CodePointsToString(parsedSeconds))).
+    let seconds = parse_result.second.map_or(0.0, to_number);
     // 15. If parseResult does not contain a TemporalDecimalFraction Parse Node, then
     //     a. Let nanoseconds be 0.
     // 16. Else,
@@ -2287,8 +2379,12 @@ fn parse_time_zone_offset_string(_offset_string: &str) -> f64 {
     //     b. Let fraction be the string-concatenation of CodePointsToString(parsedFraction) and "000000000".
     //     c. Let nanosecondsString be the substring of fraction from 1 to 10.
     //     d. Let nanoseconds be ℝ(StringToNumber(nanosecondsString)).
+    // NOTE: The separator is not part of the fraction here.
+    let nanoseconds = parse_result
+        .fraction
+        .map_or(0.0, |fraction| to_number(&format!("{fraction:0<9}")));
     // 17. Return sign × (((hours × 60 + minutes) × 60 + seconds) × 10**9 + nanoseconds).
-    todo!()
+    sign * (((hours * 60.0 + minutes) * 60.0 + seconds) * 1e9 + nanoseconds)
 }
 
 /// ### [21.4.4.41.1 TimeString ( tv )](https://tc39.es/ecma262/#sec-timestring)
@@ -2418,3 +2514,252 @@ pub(super) fn to_date_string(agent: &Agent, tv: DateValue) -> std::string::Strin
         time_zone_string(agent, tv)
     )
 }
+
+#[cfg(test)]
+mod test {
+    use crate::ecmascript::{
+        execution::{
+            Agent, DefaultHostHooks,
+            agent::{HostHooks, Job, Options},
+            initialize_default_realm,
+        },
+        scripts_and_modules::script::test::evaluate,
+        types::Value,
+    };
+
+    /// Host hooks with a fixed system time zone.
+    #[derive(Debug)]
+    struct TimeZoneHostHooks(&'static str);
+
+    impl HostHooks for TimeZoneHostHooks {
+        fn enqueue_promise_job(&self, _job: Job) {}
+
+        fn system_time_zone_identifier(&self) -> &str {
+            self.0
+        }
+    }
+
+    static UTC: TimeZoneHostHooks = TimeZoneHostHooks("UTC");
+
+    /// Host hooks with a clock fixed at the Unix epoch.
+    #[derive(Debug)]
+    struct EpochClockHostHooks;
+
+    impl HostHooks for EpochClockHostHooks {
+        fn enqueue_promise_job(&self, _job: Job) {}
+
+        fn system_time_now(&self) -> f64 {
+            0.0
+        }
+    }
+
+    fn run(host_hooks: &'static dyn HostHooks, source: &'static str) -> bool {
+        let mut agent = Agent::new(Options::default(), host_hooks);
+        agent.with_gc(|agent, mut gc| {
+            initialize_default_realm(agent, gc.reborrow());
+            evaluate(agent, source, gc) == Value::Boolean(true)
+        })
+    }
+
+    #[test]
+    fn setters_normalize_out_of_range_components() {
+        assert!(run(
+            &DefaultHostHooks,
+            "const d = new Date(Date.UTC(2020, 0, 31));
+            const r = d.setUTCMonth(14);
+            const results = [
+                r === d.getTime() && d.toISOString() === '2021-03-31T00:00:00.000Z',
+                new Date(Date.UTC(2020, 2, 15)).setUTCDate(0) === Date.UTC(2020, 1, 29),
+                new Date(Date.UTC(2021, 2, 15)).setUTCDate(0) === Date.UTC(2021, 1, 28),
+                new Date(Date.UTC(2020, 0, 1)).setUTCMonth(-1) === Date.UTC(2019, 11, 1),
+                new Date(Date.UTC(2020, 0, 31)).setUTCMonth(1) === Date.UTC(2020, 2, 2),
+                new Date(Date.UTC(2020, 0, 1)).setUTCHours(25) === Date.UTC(2020, 0, 2, 1),
+                new Date(Date.UTC(2020, 0, 1)).setUTCHours(-1) === Date.UTC(2019, 11, 31, 23),
+                new Date(Date.UTC(2020, 0, 1)).setUTCMinutes(0, 0, -1) === Date.UTC(2019, 11, 31, 23, 59, 59, 999),
+                new Date(Date.UTC(2020, 0, 1)).setUTCSeconds(3600) === Date.UTC(2020, 0, 1, 1),
+                new Date(Date.UTC(2020, 0, 1)).setUTCMilliseconds(86400000) === Date.UTC(2020, 0, 2),
+                new Date(Date.UTC(2020, 1, 29)).setUTCFullYear(2021) === Date.UTC(2021, 2, 1),
+                // Fractional arguments are truncated.
+                new Date(Date.UTC(2020, 0, 1)).setUTCDate(2.9) === Date.UTC(2020, 0, 2),
+            ];
+            results.every(Boolean)",
+        ));
+    }
+
+    #[test]
+    fn missing_arguments_preserve_components() {
+        assert!(run(
+            &DefaultHostHooks,
+            "const t = Date.UTC(2020, 4, 17, 10, 20, 30, 400);
+            const results = [
+                new Date(t).setUTCFullYear(2000) === Date.UTC(2000, 4, 17, 10, 20, 30, 400),
+                new Date(t).setUTCFullYear(2000, 0) === Date.UTC(2000, 0, 17, 10, 20, 30, 400),
+                new Date(t).setUTCMonth(0) === Date.UTC(2020, 0, 17, 10, 20, 30, 400),
+                new Date(t).setUTCHours(1) === Date.UTC(2020, 4, 17, 1, 20, 30, 400),
+                new Date(t).setUTCHours(1, 2) === Date.UTC(2020, 4, 17, 1, 2, 30, 400),
+                new Date(t).setUTCHours(1, 2, 3) === Date.UTC(2020, 4, 17, 1, 2, 3, 400),
+                new Date(t).setUTCMinutes(1) === Date.UTC(2020, 4, 17, 10, 1, 30, 400),
+                new Date(t).setUTCSeconds(1) === Date.UTC(2020, 4, 17, 10, 20, 1, 400),
+                // An explicit undefined is present, and is NaN.
+                Number.isNaN(new Date(t).setUTCHours(1, undefined)),
+                Number.isNaN(new Date(t).setUTCMonth(1, undefined)),
+            ];
+            results.every(Boolean)",
+        ));
+    }
+
+    #[test]
+    fn arguments_are_coerced_in_order_before_checking_for_invalid_dates() {
+        assert!(run(
+            &DefaultHostHooks,
+            "const log = [];
+            const arg = (name, value) => ({ valueOf() { log.push(name); return value; } });
+            const d = new Date(0);
+            const r1 = d.setUTCHours(arg('h', NaN), arg('m', 1), arg('s', 2), arg('ms', 3));
+            const order1 = log.join() === 'h,m,s,ms';
+            log.length = 0;
+            // The date is invalid now, but all arguments are still coerced.
+            const r2 = d.setUTCMinutes(arg('m', 1), arg('s', 2), arg('ms', 3));
+            const order2 = log.join() === 'm,s,ms';
+            log.length = 0;
+            const r3 = d.setMonth(arg('month', 1), arg('date', 2));
+            const order3 = log.join() === 'month,date';
+            log.length = 0;
+            // The time value is read before the arguments are coerced.
+            const e = new Date(0);
+            const r4 = e.setUTCSeconds(arg('s', 5), { valueOf() { e.setTime(NaN); return 6; } });
+            const f = new Date(NaN);
+            const r5 = f.setUTCDate({ valueOf() { f.setTime(0); return 2; } });
+            Number.isNaN(r1) && order1 && Number.isNaN(r2) && order2 && Number.isNaN(r3) && order3 &&
+                r4 === 5006 && e.getTime() === 5006 && Number.isNaN(r5) && f.getTime() === 0",
+        ));
+    }
+
+    #[test]
+    fn setters_on_invalid_dates() {
+        assert!(run(
+            &DefaultHostHooks,
+            "const setters = [
+                'setDate', 'setHours', 'setMilliseconds', 'setMinutes', 'setMonth', 'setSeconds',
+                'setUTCDate', 'setUTCHours', 'setUTCMilliseconds', 'setUTCMinutes', 'setUTCMonth',
+                'setUTCSeconds',
+            ];
+            const stayInvalid = setters.every((name) => {
+                const d = new Date(NaN);
+                return Number.isNaN(d[name](1)) && Number.isNaN(d.getTime());
+            });
+            // setTime replaces the time value, and setFullYear starts from +0.
+            const d = new Date(NaN);
+            const time = d.setTime(5) === 5 && d.getTime() === 5;
+            const fullYear = new Date(NaN).setUTCFullYear(2000) === Date.UTC(2000, 0, 1) &&
+                new Date(NaN).setFullYear(2000, 1, 2) === new Date(2000, 1, 2).getTime();
+            stayInvalid && time && fullYear",
+        ));
+    }
+
+    #[test]
+    fn results_are_time_clipped() {
+        assert!(run(
+            &DefaultHostHooks,
+            "const d = new Date(0);
+            const results = [
+                d.setTime(8.64e15) === 8.64e15,
+                Number.isNaN(d.setTime(8.64e15 + 1)),
+                Number.isNaN(d.getTime()),
+                d.setTime(-8.64e15) === -8.64e15,
+                Number.isNaN(new Date(8.64e15).setUTCMilliseconds(1)),
+                new Date(8.64e15).setUTCMilliseconds(0) === 8.64e15,
+                Number.isNaN(new Date(0).setUTCFullYear(275761)),
+                Number.isNaN(new Date(0).setUTCHours(Infinity)),
+                Object.is(new Date(5).setTime(-0), 0),
+                Object.is(new Date(5).setUTCMilliseconds(-0), 0),
+                Object.is(new Date(0).setTime(-0.9), 0),
+                new Date(0).setTime(1.9) === 1,
+                new Date(0).setTime('12') === 12,
+            ];
+            results.every(Boolean)",
+        ));
+    }
+
+    #[test]
+    fn local_setters_match_utc_setters_at_a_fixed_offset() {
+        static PLUS_0530: TimeZoneHostHooks = TimeZoneHostHooks("+05:30");
+        static MINUS_0800: TimeZoneHostHooks = TimeZoneHostHooks("-0800");
+        let source = "const offset = -new Date(0).getTimezoneOffset() * 60000;
+            const t = Date.UTC(2020, 11, 31, 22, 45, 10, 500);
+            const cases = [
+                ['FullYear', [2021]], ['FullYear', [2021, 1, 29]], ['Month', [14]], ['Month', [1, 0]],
+                ['Date', [0]], ['Date', [32]], ['Hours', [-1]], ['Hours', [23, 59, 59, 999]],
+                ['Minutes', [90]], ['Minutes', [1, 2, 3]], ['Seconds', [-1]], ['Seconds', [1, 2]],
+                ['Milliseconds', [86400000]],
+            ];
+            cases.every(([name, args]) => {
+                const local = new Date(t)['set' + name](...args);
+                const utc = new Date(t + offset)['setUTC' + name](...args) - offset;
+                return local === utc;
+            }) && offset !== 0";
+        assert!(run(&PLUS_0530, source));
+        assert!(run(&MINUS_0800, source));
+        assert!(run(
+            &UTC,
+            source.replace("offset !== 0", "offset === 0").leak()
+        ));
+        assert!(run(
+            &PLUS_0530,
+            "const d = new Date(Date.UTC(2020, 0, 1, 20, 0));
+            d.getTimezoneOffset() === -330 && d.getDate() === 2 && d.getHours() === 1 &&
+                d.getMinutes() === 30 && d.toString().endsWith('GMT+0530') &&
+                new Date(2020, 0, 2, 1, 30).getTime() === d.getTime() &&
+                d.setUTCHours(0) === Date.UTC(2020, 0, 1, 0) && d.getUTCHours() === 0",
+        ));
+        assert!(run(
+            &MINUS_0800,
+            "new Date(0).getTimezoneOffset() === 480 && new Date(0).toString().endsWith('GMT-0800')",
+        ));
+    }
+
+    #[test]
+    fn system_time_zone_offset_formats() {
+        static IDENTIFIERS: [(TimeZoneHostHooks, i32); 8] = [
+            (TimeZoneHostHooks("+01"), 3_600_000),
+            (TimeZoneHostHooks("-0130"), -5_400_000),
+            (TimeZoneHostHooks("+01:30:30"), 5_430_000),
+            (TimeZoneHostHooks("+013030.5"), 5_430_500),
+            (TimeZoneHostHooks("-23:59:59,999999999"), -86_399_999),
+            // Unsupported identifiers are treated as UTC.
+            (TimeZoneHostHooks("Europe/Helsinki"), 0),
+            (TimeZoneHostHooks("+24:00"), 0),
+            (TimeZoneHostHooks("+01:3000"), 0),
+        ];
+        for (host_hooks, offset_ms) in &IDENTIFIERS {
+            let source =
+                format!("Math.round(-new Date(0).getTimezoneOffset() * 60000) === {offset_ms}");
+            assert!(run(host_hooks, source.leak()), "{}", host_hooks.0);
+        }
+    }
+
+    #[test]
+    fn date_is_serialized_by_to_json() {
+        for source in [
+            "Date.now() === 0",
+            "JSON.stringify(new Date()) === '\"1970-01-01T00:00:00.000Z\"'",
+            "JSON.stringify({ date: new Date() }) === '{\"date\":\"1970-01-01T00:00:00.000Z\"}'",
+            "JSON.stringify([new Date(NaN)]) === '[null]'",
+            "JSON.stringify(new Date(-1)) === '\"1969-12-31T23:59:59.999Z\"'",
+            "JSON.stringify(new Date(8.64e15)) === '\"+275760-09-13T00:00:00.000Z\"'",
+            "JSON.stringify(new Date(-62198755200000)) === '\"-000001-01-01T00:00:00.000Z\"'",
+            "new Date(JSON.parse(JSON.stringify(new Date(1234567890123)))).getTime() === 1234567890123",
+            // toJSON calls toISOString through ToPrimitive and Invoke.
+            "Date.prototype.toJSON.call({
+                [Symbol.toPrimitive]() { return Infinity; },
+                toISOString() { return 'iso'; },
+            }) === null",
+            "Date.prototype.toJSON.call({
+                valueOf() { return 1; },
+                toISOString() { return 'iso'; },
+            }) === 'iso'",
+        ] {
+            assert!(run(&EpochClockHostHooks, source), "{source}");
+        }
+    }
+}
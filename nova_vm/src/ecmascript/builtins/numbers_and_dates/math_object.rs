@@ -8,7 +8,7 @@ use crate::{
     ecmascript::{
         abstract_operations::type_conversion::{to_number, to_number_primitive, to_uint32},
         builders::ordinary_object_builder::OrdinaryObjectBuilder,
-        builtins::{ArgumentsList, Behaviour, Builtin},
+        builtins::{ArgumentsList, Behaviour, Builtin, argument_coercion::coerce_arguments},
         execution::{Agent, JsResult, Realm},
         types::{BUILTIN_STRING_MEMORY, IntoValue, Number, Primitive, String, Value},
     },
@@ -16,7 +16,6 @@ use crate::{
         context::{Bindable, GcScope, NoGcScope},
         rootable::Scopable,
     },
-    heap::WellKnownSymbolIndexes,
 };
 
 #[cfg(feature = "proposal-math-sum")]
@@ -511,19 +510,17 @@ impl MathObject {
     fn atan2<'gc>(
         agent: &mut Agent,
         _this_value: Value,
-        arguments: ArgumentsList,
+        mut arguments: ArgumentsList,
         mut gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
-        let x = arguments.get(0).bind(gc.nogc());
-        let y = arguments.get(1).scope(agent, gc.nogc());
-        // 1. Let ny be ? ToNumber(y).
-        let ny = to_number(agent, x.unbind(), gc.reborrow())
-            .unbind()?
-            .into_f64(agent);
-        // 2. Let nx be ? ToNumber(x).
-        let nx = to_number(agent, y.get(agent), gc.reborrow())
-            .unbind()?
-            .into_f64(agent);
+        coerce_arguments!(agent, arguments, gc => {
+            // 1. Let ny be ? ToNumber(y).
+            let ny = to_number_arg(0);
+            // 2. Let nx be ? ToNumber(x).
+            let nx = to_number_arg(1);
+        });
+        let ny = ny.into_f64(agent);
+        let nx = nx.into_f64(agent);
 
         // 3. If ny is NaN or nx is NaN, return NaN.
         if ny.is_nan() || nx.is_nan() {
@@ -1348,7 +1345,7 @@ impl MathObject {
     fn pow<'gc>(
         agent: &mut Agent,
         _this_value: Value,
-        arguments: ArgumentsList,
+        mut arguments: ArgumentsList,
         mut gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
         let base = arguments.get(0).bind(gc.nogc());
@@ -1368,14 +1365,13 @@ impl MathObject {
                 .bind(gc.nogc());
             (base.bind(gc.nogc()), exponent.bind(gc.nogc()))
         } else {
-            let exponent = exponent.scope(agent, gc.nogc());
-            let base = to_number(agent, base.unbind(), gc.reborrow())
-                .unbind()?
-                .scope(agent, gc.nogc());
-            let exponent = to_number(agent, exponent.get(agent), gc.reborrow())
-                .unbind()?
-                .bind(gc.nogc());
-            (base.get(agent).bind(gc.nogc()), exponent)
+            coerce_arguments!(agent, arguments, gc => {
+                // 1. Set base to ? ToNumber(base).
+                let base = to_number_arg(0);
+                // 2. Set exponent to ? ToNumber(exponent).
+                let exponent = to_number_arg(1);
+            });
+            (base, exponent)
         };
         let gc = gc.nogc();
         if let (Number::Integer(base), Number::Integer(exponent)) = (base, exponent) {
@@ -1414,11 +1410,8 @@ impl MathObject {
         _: ArgumentsList,
         gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
-        Ok(Value::from_f64(
-            agent,
-            rand::random::<f64>(),
-            gc.into_nogc(),
-        ))
+        let random = agent.random_f64();
+        Ok(Value::from_f64(agent, random, gc.into_nogc()))
     }
 
     fn round<'gc>(
@@ -1979,14 +1972,7 @@ impl MathObject {
             .with_builtin_function_property::<MathObjectTan>()
             .with_builtin_function_property::<MathObjectTanh>()
             .with_builtin_function_property::<MathObjectTrunc>()
-            .with_property(|builder| {
-                builder
-                    .with_key(WellKnownSymbolIndexes::ToStringTag.into())
-                    .with_value_readonly(BUILTIN_STRING_MEMORY.Math.into())
-                    .with_enumerable(false)
-                    .with_configurable(true)
-                    .build()
-            });
+            .with_to_string_tag(BUILTIN_STRING_MEMORY.Math);
 
         #[cfg(feature = "proposal-float16array")]
         let builder = builder.with_builtin_function_property::<MathObjectF16round>();
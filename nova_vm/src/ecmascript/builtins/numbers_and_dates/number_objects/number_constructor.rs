@@ -77,7 +77,7 @@ impl NumberConstructor {
         let mut new_target = new_target.bind(nogc);
 
         // 1. If value is present, then
-        let n = if !value.is_undefined() {
+        let n = if !arguments.is_empty() {
             // a. Let prim be ? ToNumeric(value).
             let prim = if let Ok(prim) = Primitive::try_from(value) {
                 to_numeric_primitive(agent, prim, nogc).unbind()?.bind(nogc)
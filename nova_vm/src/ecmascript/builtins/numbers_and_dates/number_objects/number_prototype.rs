@@ -14,7 +14,7 @@ use crate::{
             ArgumentsList, Builtin,
             primitive_objects::{PrimitiveObject, PrimitiveObjectData, PrimitiveObjectHeapData},
         },
-        execution::{Agent, JsResult, Realm, agent::ExceptionType},
+        execution::{Agent, JsResult, Realm},
         types::{BUILTIN_STRING_MEMORY, IntoValue, Number, String, Value},
     },
 };
@@ -104,11 +104,7 @@ impl NumberPrototype {
         let f = f.into_i64();
         // 5. If f < 0 or f > 100, throw a RangeError exception.
         if !(0..=100).contains(&f) {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::RangeError,
-                "Fraction digits count out of range",
-                gc,
-            ));
+            return agent.range_error("Fraction digits count out of range", gc);
         }
         let f = f as usize;
 
@@ -118,7 +114,10 @@ impl NumberPrototype {
         if x == 0.0 {
             x = 0.0;
         };
-        if f == 0 {
+        // 10. If fractionDigits is undefined, then the digits are as few as
+        //     needed to represent x uniquely; a fractionDigits of 0 or NaN
+        //     gives a single digit.
+        if fraction_digits_is_undefined {
             Ok(f64_to_exponential(agent, x, gc).into_value())
         } else {
             Ok(f64_to_exponential_with_precision(agent, x, f, gc).into_value())
@@ -147,20 +146,12 @@ impl NumberPrototype {
         debug_assert!(!fraction_digits_is_undefined || f.into_i64() == 0);
         // 4. If f is not finite, throw a RangeError exception.
         if !f.is_finite() {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::RangeError,
-                "Fraction digits count out of range",
-                gc,
-            ));
+            return agent.range_error("Fraction digits count out of range", gc);
         }
         let f = f.into_i64();
         // 5. If f < 0 or f > 100, throw a RangeError exception.
         if !(0..=100).contains(&f) {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::RangeError,
-                "Fraction digits count out of range",
-                gc,
-            ));
+            return agent.range_error("Fraction digits count out of range", gc);
         }
         // 6. If x is not finite, return Number::toString(x, 10).
         if !x.is_finite(agent) {
@@ -225,11 +216,7 @@ impl NumberPrototype {
         // 5. If p < 1 or p > 100, throw a RangeError exception.
         let precision = p.into_i64();
         if !(1..=100).contains(&precision) {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::RangeError,
-                "Precision out of range",
-                gc,
-            ));
+            return agent.range_error("Precision out of range", gc);
         }
         let precision = precision as u8;
 
@@ -462,11 +449,10 @@ impl NumberPrototype {
             let gc = gc.into_nogc();
             // 4. If radixMV is not in the inclusive interval from 2 to 36, throw a RangeError exception.
             if !(2..=36).contains(&radix) {
-                return Err(agent.throw_exception_with_static_message(
-                    ExceptionType::RangeError,
+                return agent.range_error(
                     "radix must be an integer at least 2 and no greater than 36",
                     gc,
-                ));
+                );
             }
             let radix = radix.into_i64() as u32;
             // 5. Return Number::toString(x, radixMV).
@@ -562,5 +548,5 @@ fn this_number_value<'gc>(
         }
     }
     // 3. Throw a TypeError exception.
-    Err(agent.throw_exception_with_static_message(ExceptionType::TypeError, "Not a Number", gc))
+    agent.type_error("Not a Number", gc)
 }
@@ -15,7 +15,10 @@ use crate::{
         rootable::Scopable,
         unwrap_try,
     },
-    heap::{Heap, HeapSweepWeakReference, element_array::PropertyStorageMut},
+    heap::{
+        Heap, HeapSweepWeakReference,
+        element_array::{ElementStorageMut, ElementStorageRef, PropertyStorageMut},
+    },
 };
 use crate::{
     ecmascript::{
@@ -24,11 +27,14 @@ use crate::{
             testing_and_comparison::same_value,
         },
         builtins::ArgumentsList,
-        execution::{Agent, JsResult, ProtoIntrinsics, agent::ExceptionType},
+        execution::{
+            Agent, JsResult, ProtoIntrinsics, agent::ExceptionType, initialize_lazy_global,
+            try_initialize_lazy_global,
+        },
         types::{
             BUILTIN_STRING_MEMORY, Function, InternalMethods, InternalSlots, IntoFunction,
             IntoObject, Object, ObjectHeapData, OrdinaryObject, PropertyDescriptor, PropertyKey,
-            String, Symbol, Value,
+            String, Symbol, Value, array_index, get_indexed_element,
         },
     },
     heap::{CompactionLists, CreateHeapData, HeapMarkAndSweep, WellKnownSymbolIndexes, WorkQueues},
@@ -36,8 +42,6 @@ use crate::{
 
 #[cfg(feature = "date")]
 use super::date::data::DateHeapData;
-#[cfg(feature = "regexp")]
-use super::regexp::RegExpHeapData;
 #[cfg(feature = "shared-array-buffer")]
 use super::shared_array_buffer::data::SharedArrayBufferHeapData;
 #[cfg(feature = "array-buffer")]
@@ -59,6 +63,11 @@ use super::{
     keyed_collections::set_objects::set_iterator_objects::set_iterator::SetIteratorHeapData,
     set::data::SetHeapData,
 };
+#[cfg(feature = "regexp")]
+use super::{
+    regexp::RegExpHeapData,
+    text_processing::regexp_objects::regexp_string_iterator_objects::RegExpStringIteratorHeapData,
+};
 #[cfg(feature = "weak-refs")]
 use super::{
     weak_map::data::WeakMapHeapData, weak_ref::data::WeakRefHeapData,
@@ -75,6 +84,7 @@ impl Index<OrdinaryObject<'_>> for Agent {
 
 impl IndexMut<OrdinaryObject<'_>> for Agent {
     fn index_mut(&mut self, index: OrdinaryObject<'_>) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.objects[index]
     }
 }
@@ -100,7 +110,133 @@ impl IndexMut<OrdinaryObject<'_>> for Vec<Option<ObjectHeapData<'static>>> {
 }
 
 /// ### [10.1 Ordinary Object Internal Methods and Internal Slots](https://tc39.es/ecma262/#sec-ordinary-object-internal-methods-and-internal-slots)
-impl<'a> InternalMethods<'a> for OrdinaryObject<'a> {}
+///
+/// Note: The methods that need the value of a property initialize it first if
+/// it is a lazy global, see [`Agent::define_lazy_global`].
+impl<'a> InternalMethods<'a> for OrdinaryObject<'a> {
+    fn try_get_own_property<'gc>(
+        self,
+        agent: &mut Agent,
+        property_key: PropertyKey,
+        gc: NoGcScope<'gc, '_>,
+    ) -> TryResult<Option<PropertyDescriptor<'gc>>> {
+        try_initialize_lazy_global(agent, self, property_key)?;
+        // 1. Return OrdinaryGetOwnProperty(O, P).
+        TryResult::Continue(ordinary_get_own_property(agent, self, property_key).bind(gc))
+    }
+
+    fn internal_get_own_property<'gc>(
+        self,
+        agent: &mut Agent,
+        property_key: PropertyKey,
+        mut gc: GcScope<'gc, '_>,
+    ) -> JsResult<'gc, Option<PropertyDescriptor<'gc>>> {
+        if !agent.lazy_globals.is_pending(self, property_key) {
+            // 1. Return OrdinaryGetOwnProperty(O, P).
+            return Ok(ordinary_get_own_property(agent, self, property_key).bind(gc.into_nogc()));
+        }
+        let object = self.scope(agent, gc.nogc());
+        let property_key = property_key.scope(agent, gc.nogc());
+        initialize_lazy_global(agent, self, property_key.get(agent), gc.reborrow());
+        // 1. Return OrdinaryGetOwnProperty(O, P).
+        Ok(
+            ordinary_get_own_property(agent, object.get(agent), property_key.get(agent))
+                .bind(gc.into_nogc()),
+        )
+    }
+
+    fn try_define_own_property(
+        self,
+        agent: &mut Agent,
+        property_key: PropertyKey,
+        property_descriptor: PropertyDescriptor,
+        gc: NoGcScope,
+    ) -> TryResult<bool> {
+        try_initialize_lazy_global(agent, self, property_key)?;
+        // 1. Let current be ? O.[[GetOwnProperty]](P).
+        // 2. Let extensible be ? IsExtensible(O).
+        // 3. Return ValidateAndApplyPropertyDescriptor(O, P, extensible, Desc, current).
+        TryResult::Continue(ordinary_define_own_property(
+            agent,
+            self,
+            property_key,
+            property_descriptor,
+            gc,
+        ))
+    }
+
+    fn internal_define_own_property<'gc>(
+        self,
+        agent: &mut Agent,
+        property_key: PropertyKey,
+        property_descriptor: PropertyDescriptor,
+        mut gc: GcScope<'gc, '_>,
+    ) -> JsResult<'gc, bool> {
+        if !agent.lazy_globals.is_pending(self, property_key) {
+            return Ok(ordinary_define_own_property(
+                agent,
+                self,
+                property_key,
+                property_descriptor,
+                gc.into_nogc(),
+            ));
+        }
+        let object = self.scope(agent, gc.nogc());
+        let property_key = property_key.scope(agent, gc.nogc());
+        let property_descriptor = property_descriptor.scope(agent, gc.nogc());
+        initialize_lazy_global(agent, self, property_key.get(agent), gc.reborrow());
+        let gc = gc.into_nogc();
+        let property_descriptor = property_descriptor.take(agent, gc);
+        Ok(ordinary_define_own_property(
+            agent,
+            object.get(agent),
+            property_key.get(agent),
+            property_descriptor,
+            gc,
+        ))
+    }
+
+    fn try_has_property(
+        self,
+        agent: &mut Agent,
+        property_key: PropertyKey,
+        gc: NoGcScope,
+    ) -> TryResult<bool> {
+        if agent.lazy_globals.is_pending(self, property_key) {
+            // The placeholder of the lazy global is an own property.
+            return TryResult::Continue(true);
+        }
+        // 1. Return ? OrdinaryHasProperty(O, P).
+        ordinary_try_has_property(agent, self, property_key, gc)
+    }
+
+    fn internal_has_property<'gc>(
+        self,
+        agent: &mut Agent,
+        property_key: PropertyKey,
+        gc: GcScope<'gc, '_>,
+    ) -> JsResult<'gc, bool> {
+        if agent.lazy_globals.is_pending(self, property_key) {
+            // The placeholder of the lazy global is an own property.
+            return Ok(true);
+        }
+        // 1. Return ? OrdinaryHasProperty(O, P).
+        ordinary_has_property(agent, self, property_key, gc)
+    }
+
+    fn try_delete(
+        self,
+        agent: &mut Agent,
+        property_key: PropertyKey,
+        gc: NoGcScope,
+    ) -> TryResult<bool> {
+        // Deleting a lazy global that has not been initialized yet cancels
+        // its initialization.
+        let _ = agent.lazy_globals.remove(self, property_key);
+        // 1. Return ? OrdinaryDelete(O, P).
+        TryResult::Continue(ordinary_delete(agent, self, property_key, gc))
+    }
+}
 
 /// ### [10.1.1.1 OrdinaryGetPrototypeOf ( O )](https://tc39.es/ecma262/#sec-ordinarygetprototypeof)
 pub(crate) fn ordinary_get_prototype_of<'a>(
@@ -1123,7 +1259,28 @@ pub(crate) fn ordinary_delete(
 }
 
 /// ### [10.1.11.1 OrdinaryOwnPropertyKeys ( O )](https://tc39.es/ecma262/#sec-ordinaryownpropertykeys)
+///
+/// The result is cached in the heap's
+/// [`OwnKeysCache`](crate::heap::OwnKeysCache) and reused until the object's
+/// keys version changes.
 pub(crate) fn ordinary_own_property_keys<'a>(
+    agent: &mut Agent,
+    object: OrdinaryObject<'a>,
+    gc: NoGcScope<'a, '_>,
+) -> Vec<PropertyKey<'a>> {
+    let version = agent[object].keys_version;
+    if let Some(keys) = agent.heap.own_keys_cache.get_own_keys(object, version) {
+        return keys.iter().map(|key| key.bind(gc)).collect();
+    }
+    let keys = compute_ordinary_own_property_keys(agent, object, gc);
+    agent
+        .heap
+        .own_keys_cache
+        .set_own_keys(object, version, &keys);
+    keys
+}
+
+fn compute_ordinary_own_property_keys<'a>(
     agent: &Agent,
     object: OrdinaryObject<'a>,
     gc: NoGcScope<'a, '_>,
@@ -1157,6 +1314,18 @@ pub(crate) fn ordinary_own_property_keys<'a>(
         }
     }
 
+    // Array index properties stored in the indexed elements.
+    let ElementStorageRef {
+        values,
+        descriptors,
+    } = agent[object].indexed_elements.get_storage(agent);
+    for (index, value) in values.iter().enumerate() {
+        let index = index as u32;
+        if value.is_some() || descriptors.is_some_and(|d| d.contains_key(&index)) {
+            integer_keys.push(index);
+        }
+    }
+
     // 2. For each own property key P of O such that P is an array index,
     if !integer_keys.is_empty() {
         // in ascending numeric index order, do
@@ -1378,6 +1547,11 @@ pub(crate) fn ordinary_object_create_with_intrinsics<'a>(
             .heap
             .create(SetIteratorHeapData::default())
             .into_object(),
+        #[cfg(feature = "regexp")]
+        ProtoIntrinsics::RegExpStringIterator => agent
+            .heap
+            .create(RegExpStringIteratorHeapData::default())
+            .into_object(),
         #[cfg(feature = "shared-array-buffer")]
         ProtoIntrinsics::SharedArrayBuffer => agent
             .heap
@@ -1547,6 +1721,8 @@ pub(crate) fn get_prototype_from_constructor<'a>(
             ProtoIntrinsics::Set => Some(intrinsics.set().into_function()),
             #[cfg(feature = "set")]
             ProtoIntrinsics::SetIterator => None,
+            #[cfg(feature = "regexp")]
+            ProtoIntrinsics::RegExpStringIterator => None,
             #[cfg(feature = "shared-array-buffer")]
             ProtoIntrinsics::SharedArrayBuffer => {
                 Some(intrinsics.shared_array_buffer().into_function())
@@ -1681,7 +1857,15 @@ pub(crate) fn try_get_ordinary_object_value<'a>(
     binding_object: OrdinaryObject<'a>,
     name: PropertyKey<'a>,
 ) -> Result<Option<Value<'a>>, ()> {
-    let props = &agent[binding_object].property_storage;
+    let data = &agent[binding_object];
+    if let Some((value, _)) =
+        get_indexed_element(&agent.heap.elements, &data.indexed_elements, name)
+    {
+        // If value is None, it means that the slot is a getter or setter
+        // and we cannot handle those on the fast path.
+        return value.map(Some).ok_or(());
+    }
+    let props = &data.property_storage;
     let index = props
         .keys(agent)
         .iter()
@@ -1689,6 +1873,10 @@ pub(crate) fn try_get_ordinary_object_value<'a>(
         .find(|(_, k)| **k == name)
         .map(|(i, _)| i);
     if let Some(index) = index {
+        if agent.lazy_globals.is_pending(binding_object, name) {
+            // Lazy globals are initialized on the slow path.
+            return Err(());
+        }
         // If value is None, it means that the slot is a getter or setter
         // and we cannot handle those on the fast path.
         let Some(value) = props.values(agent)[index] else {
@@ -1727,9 +1915,26 @@ pub(crate) fn try_set_ordinary_object_value(
     name: PropertyKey,
     value: Value,
 ) -> Option<bool> {
+    if agent.lazy_globals.is_pending(binding_object, name) {
+        // Lazy globals are initialized on the slow path.
+        return None;
+    }
     let Heap {
         objects, elements, ..
     } = &mut agent.heap;
+    let indexed_elements = &objects[binding_object].indexed_elements;
+    if let Some(index) = array_index(name) {
+        if let Some((slot, descriptor)) = get_indexed_element(elements, indexed_elements, name) {
+            // Note: accessor properties are not handled on the fast path.
+            slot?;
+            let writable = descriptor.is_none_or(|d| d.is_writable().unwrap());
+            if writable {
+                let ElementStorageMut { values, .. } = indexed_elements.get_storage_mut(elements);
+                values[index as usize] = Some(value.unbind());
+            }
+            return Some(writable);
+        }
+    }
     let PropertyStorageMut {
         keys,
         values,
@@ -31,6 +31,7 @@ use crate::{
     heap::{
         CompactionLists, CreateHeapData, Heap, HeapMarkAndSweep, WorkQueues,
         indexes::PrimitiveObjectIndex,
+        snapshot::{HeapSnapshot, SnapshotError, SnapshotReader, SnapshotWriter},
     },
 };
 use small_string::SmallString;
@@ -93,6 +94,7 @@ impl Index<PrimitiveObject<'_>> for Agent {
 
 impl IndexMut<PrimitiveObject<'_>> for Agent {
     fn index_mut(&mut self, index: PrimitiveObject) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.primitive_objects[index]
     }
 }
@@ -725,6 +727,42 @@ impl HeapMarkAndSweep for PrimitiveObjectHeapData<'static> {
     }
 }
 
+impl HeapSnapshot for PrimitiveObjectHeapData<'static> {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        let Self { object_index, data } = self;
+        object_index.write_snapshot(writer);
+        let data = match *data {
+            PrimitiveObjectData::Boolean(data) => Value::Boolean(data),
+            PrimitiveObjectData::String(data) => Value::String(data),
+            PrimitiveObjectData::SmallString(data) => Value::SmallString(data),
+            PrimitiveObjectData::Symbol(data) => Value::Symbol(data),
+            PrimitiveObjectData::Number(data) => Value::Number(data),
+            PrimitiveObjectData::Integer(data) => Value::Integer(data),
+            PrimitiveObjectData::SmallF64(data) => Value::SmallF64(data),
+            PrimitiveObjectData::BigInt(data) => Value::BigInt(data),
+            PrimitiveObjectData::SmallBigInt(data) => Value::SmallBigInt(data),
+        };
+        data.write_snapshot(writer);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        let object_index = HeapSnapshot::read_snapshot(reader)?;
+        let data = match Value::read_snapshot(reader)? {
+            Value::Boolean(data) => PrimitiveObjectData::Boolean(data),
+            Value::String(data) => PrimitiveObjectData::String(data),
+            Value::SmallString(data) => PrimitiveObjectData::SmallString(data),
+            Value::Symbol(data) => PrimitiveObjectData::Symbol(data),
+            Value::Number(data) => PrimitiveObjectData::Number(data),
+            Value::Integer(data) => PrimitiveObjectData::Integer(data),
+            Value::SmallF64(data) => PrimitiveObjectData::SmallF64(data),
+            Value::BigInt(data) => PrimitiveObjectData::BigInt(data),
+            Value::SmallBigInt(data) => PrimitiveObjectData::SmallBigInt(data),
+            _ => return Err(SnapshotError::Corrupted),
+        };
+        Ok(Self { object_index, data })
+    }
+}
+
 impl HeapMarkAndSweep for PrimitiveObject<'static> {
     fn mark_values(&self, queues: &mut WorkQueues) {
         queues.primitive_objects.push(*self);
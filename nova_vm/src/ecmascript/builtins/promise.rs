@@ -147,6 +147,7 @@ impl Index<Promise<'_>> for Agent {
 
 impl IndexMut<Promise<'_>> for Agent {
     fn index_mut(&mut self, index: Promise) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.promises[index]
     }
 }
@@ -198,11 +198,10 @@ impl<'a> InternalMethods<'a> for Proxy<'a> {
         } else if let Ok(handler_proto) = Object::try_from(handler_proto) {
             Some(handler_proto.scope(agent, gc.nogc()))
         } else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
+            return agent.type_error(
                 "Handler prototype must be an object or null",
                 gc.into_nogc(),
-            ));
+            );
         };
 
         // 9. Let extensibleTarget be ? IsExtensible(target).
@@ -224,11 +223,10 @@ impl<'a> InternalMethods<'a> for Proxy<'a> {
 
         // 12. If SameValue(handlerProto, targetProto) is false, throw a TypeError exception.
         if handler_proto != target_proto {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
+            return agent.type_error(
                 "handlerProto and targetProto are not the same value",
                 gc.into_nogc(),
-            ));
+            );
         }
 
         // 13. Return handlerProto.
@@ -339,11 +337,7 @@ impl<'a> InternalMethods<'a> for Proxy<'a> {
             .bind(gc.nogc());
         // 12. If SameValue(V, targetProto) is false, throw a TypeError exception.
         if scoped_prototype.map(|p| p.get(agent)) != target_proto {
-            return  Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "'setPrototypeOf' on proxy: trap returned truish for setting a new prototype on the non-extensible proxy target",
-                gc.into_nogc(),
-            ));
+            return  agent.type_error("'setPrototypeOf' on proxy: trap returned truish for setting a new prototype on the non-extensible proxy target", gc.into_nogc());
         }
         // 13. Return true.
         Ok(true)
@@ -417,11 +411,10 @@ impl<'a> InternalMethods<'a> for Proxy<'a> {
 
         // 9. If booleanTrapResult is not targetResult, throw a TypeError exception.
         if boolean_trap_result != target_result {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
+            return agent.type_error(
                 "proxy must report same extensiblitity as target",
                 gc.into_nogc(),
-            ));
+            );
         };
 
         // 10. Return booleanTrapResult.
@@ -500,11 +493,10 @@ impl<'a> InternalMethods<'a> for Proxy<'a> {
 
             // b. If extensibleTarget is true, throw a TypeError exception.
             if extensible_target {
-                return Err(agent.throw_exception_with_static_message(
-                    ExceptionType::TypeError,
+                return agent.type_error(
                     "proxy can't report an extensible object as non-extensible",
                     gc.into_nogc(),
-                ));
+                );
             }
         };
 
@@ -597,11 +589,10 @@ impl<'a> InternalMethods<'a> for Proxy<'a> {
         // 8. If trapResultObj is not an Object and trapResultObj is not undefined, throw a TypeError exception.
         let trap_result_obj_is_undefined = trap_result_obj.is_undefined();
         if !trap_result_obj.is_object() && !trap_result_obj_is_undefined {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
+            return agent.type_error(
                 "proxy [[GetOwnProperty]] must return an object or undefined",
                 gc.into_nogc(),
-            ));
+            );
         };
         let trap_result_obj = trap_result_obj.unbind().scope(agent, gc.nogc());
         // 9. Let targetDesc be ? target.[[GetOwnProperty]](P).
@@ -671,11 +662,7 @@ impl<'a> InternalMethods<'a> for Proxy<'a> {
         );
         // 15. If valid is false, throw a TypeError exception.
         if !valid {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "valid",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("valid", gc.into_nogc());
         };
         // 16. If resultDesc.[[Configurable]] is false, then
         if result_desc.configurable == Some(false) {
@@ -994,11 +981,10 @@ impl<'a> InternalMethods<'a> for Proxy<'a> {
                 // ii. Let extensibleTarget be ? IsExtensible(target).
                 // iii. If extensibleTarget is false, throw a TypeError exception.
                 if !is_extensible(agent, scoped_target.get(agent), gc.reborrow()).unbind()? {
-                    return Err(agent.throw_exception_with_static_message(
-                        ExceptionType::TypeError,
+                    return agent.type_error(
                         "proxy can't report an extensible object as non-extensible",
                         gc.into_nogc(),
-                    ));
+                    );
                 }
             }
         };
@@ -1133,11 +1119,7 @@ impl<'a> InternalMethods<'a> for Proxy<'a> {
                         && target_desc.get.is_none()
                         && trap_result.is_undefined()
                 {
-                    return Err(agent.throw_exception_with_static_message(
-                        ExceptionType::TypeError,
-                        "Invalid Proxy [[Get]] method",
-                        gc.into_nogc(),
-                    ));
+                    return agent.type_error("Invalid Proxy [[Get]] method", gc.into_nogc());
                 }
             }
         }
@@ -1274,11 +1256,7 @@ impl<'a> InternalMethods<'a> for Proxy<'a> {
                     )
                     || target_desc.is_accessor_descriptor() && target_desc.set.is_none()
                 {
-                    return Err(agent.throw_exception_with_static_message(
-                        ExceptionType::TypeError,
-                        "Invalid Proxy [[Set]] method",
-                        gc.into_nogc(),
-                    ));
+                    return agent.type_error("Invalid Proxy [[Set]] method", gc.into_nogc());
                 }
             }
         }
@@ -1601,11 +1579,10 @@ impl<'a> InternalMethods<'a> for Proxy<'a> {
         }
         // 22. If uncheckedResultKeys is not empty, throw a TypeError exception.
         if !unchecked_result_keys.is_empty() {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
+            return agent.type_error(
                 "trap returned extra keys but proxy target is non-extensible",
                 gc,
-            ));
+            );
         }
         // 23. Return trapResult.
         Ok(trap_result)
@@ -1782,11 +1759,7 @@ impl<'a> InternalMethods<'a> for Proxy<'a> {
             Ok(new_obj.unbind())
         } else {
             // 10. If newObj is not an Object, throw a TypeError exception.
-            Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "proxy [[Construct]] must return an object",
-                gc.into_nogc(),
-            ))
+            agent.type_error("proxy [[Construct]] must return an object", gc.into_nogc())
         }
     }
 }
@@ -1805,19 +1778,11 @@ pub(crate) fn proxy_create<'a>(
 ) -> JsResult<'a, Proxy<'a>> {
     // 1. If target is not an Object, throw a TypeError exception.
     let Ok(target) = Object::try_from(target.unbind()) else {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Proxy target must be an object",
-            gc,
-        ));
+        return agent.type_error("Proxy target must be an object", gc);
     };
     // 2. If handler is not an Object, throw a TypeError exception.
     let Ok(handler) = Object::try_from(handler.unbind()) else {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Proxy handler must be an object",
-            gc,
-        ));
+        return agent.type_error("Proxy handler must be an object", gc);
     };
     // 3. Let P be MakeBasicObject(« [[ProxyHandler]], [[ProxyTarget]] »).
     let p = agent.heap.create(ProxyHeapData::NonRevoked {
@@ -1846,6 +1811,7 @@ impl Index<Proxy<'_>> for Agent {
 
 impl IndexMut<Proxy<'_>> for Agent {
     fn index_mut(&mut self, index: Proxy) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.proxys[index]
     }
 }
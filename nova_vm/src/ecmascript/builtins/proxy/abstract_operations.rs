@@ -4,7 +4,7 @@
 
 use crate::{
     ecmascript::{
-        execution::{Agent, JsResult, agent::ExceptionType},
+        execution::{Agent, JsResult},
         types::Object,
     },
     engine::context::{Bindable, NoGcScope},
@@ -57,11 +57,7 @@ pub(crate) fn validate_non_revoked_proxy<'a>(
     else {
         // 1. If proxy.[[ProxyTarget]] is null, throw a TypeError exception.
         // 2. Assert: proxy.[[ProxyHandler]] is not null.
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Proxy target is missing",
-            gc,
-        ));
+        return agent.type_error("Proxy target is missing", gc);
     };
 
     // 3. Return unused.
@@ -2,15 +2,19 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use crate::ecmascript::builtins::proxy::proxy_create;
-use crate::ecmascript::execution::agent::ExceptionType;
+use crate::ecmascript::abstract_operations::operations_on_objects::try_create_data_property_or_throw;
+use crate::ecmascript::builtins::ordinary::ordinary_object_create_with_intrinsics;
+use crate::ecmascript::builtins::proxy::{data::ProxyHeapData, proxy_create};
 use crate::ecmascript::types::IntoValue;
 use crate::engine::context::{Bindable, GcScope};
+use crate::engine::unwrap_try;
 use crate::{
     ecmascript::{
         builders::builtin_function_builder::BuiltinFunctionBuilder,
-        builtins::{ArgumentsList, Behaviour, Builtin, BuiltinIntrinsicConstructor},
-        execution::{Agent, JsResult, Realm},
+        builtins::{
+            ArgumentsList, Behaviour, Builtin, BuiltinIntrinsicConstructor, create_closure,
+        },
+        execution::{Agent, JsResult, ProtoIntrinsics, Realm},
         types::{BUILTIN_STRING_MEMORY, Object, String, Value},
     },
     heap::IntrinsicConstructorIndexes,
@@ -51,23 +55,61 @@ impl ProxyConstructor {
         let handler = arguments.get(1).bind(gc);
         // 1. If NewTarget is undefined, throw a TypeError exception.
         if new_target.is_none() {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
+            return agent.type_error(
                 "calling a builtin Proxy constructor without new is forbidden",
                 gc,
-            ));
+            );
         }
         // 2. Return ? ProxyCreate(target, handler).
         proxy_create(agent, target, handler, gc).map(|proxy| proxy.into_value())
     }
 
+    /// ### [28.2.2.1 Proxy.revocable ( target, handler )](https://tc39.es/ecma262/#sec-proxy.revocable)
+    ///
+    /// This function creates a revocable Proxy object.
     fn revocable<'gc>(
         agent: &mut Agent,
         _this_value: Value,
-        _arguments: ArgumentsList,
+        arguments: ArgumentsList,
         gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
-        Err(agent.todo("Proxy.revocable", gc.into_nogc()))
+        let gc = gc.into_nogc();
+        let target = arguments.get(0).bind(gc);
+        let handler = arguments.get(1).bind(gc);
+        // 1. Let proxy be ? ProxyCreate(target, handler).
+        let proxy = proxy_create(agent, target, handler, gc)?;
+        // 2. Let revokerClosure be a new Abstract Closure with no parameters
+        //    that captures nothing and performs the following steps when
+        //    called:
+        // 3. Let revoker be CreateBuiltinFunction(revokerClosure, 0, "", « [[RevocableProxy]] »).
+        // 4. Set revoker.[[RevocableProxy]] to proxy.
+        // NOTE: The revoker captures the proxy instead of holding it in a
+        // [[RevocableProxy]] slot. Revoking a revoked proxy again is not
+        // observable, so the slot is never cleared.
+        let revoker = create_closure(agent, revoker_closure, 0, &[proxy.into_value()], gc);
+        // 5. Let result be OrdinaryObjectCreate(%Object.prototype%).
+        let result =
+            ordinary_object_create_with_intrinsics(agent, Some(ProtoIntrinsics::Object), None, gc);
+        // 6. Perform ! CreateDataPropertyOrThrow(result, "proxy", proxy).
+        unwrap_try(try_create_data_property_or_throw(
+            agent,
+            result,
+            BUILTIN_STRING_MEMORY.proxy.into(),
+            proxy.into_value(),
+            gc,
+        ))
+        .unwrap();
+        // 7. Perform ! CreateDataPropertyOrThrow(result, "revoke", revoker).
+        unwrap_try(try_create_data_property_or_throw(
+            agent,
+            result,
+            BUILTIN_STRING_MEMORY.revoke.into(),
+            revoker.into_value(),
+            gc,
+        ))
+        .unwrap();
+        // 8. Return result.
+        Ok(result.into_value())
     }
 
     pub(crate) fn create_intrinsic(agent: &mut Agent, realm: Realm<'static>) {
@@ -77,3 +119,31 @@ impl ProxyConstructor {
             .build();
     }
 }
+
+/// The steps of revokerClosure in Proxy.revocable, called with the captured
+/// proxy.
+fn revoker_closure<'gc>(
+    agent: &mut Agent,
+    _this_value: Value,
+    arguments: ArgumentsList,
+    gc: GcScope<'gc, '_>,
+) -> JsResult<'gc, Value<'gc>> {
+    let gc = gc.into_nogc();
+    // a. Let F be the active function object.
+    // b. Let p be F.[[RevocableProxy]].
+    // c. If p is null, return undefined.
+    // d. Set F.[[RevocableProxy]] to null.
+    // e. Assert: p is a Proxy exotic object.
+    let Value::Proxy(p) = arguments.get(0).bind(gc) else {
+        unreachable!()
+    };
+    // f. Set p.[[ProxyTarget]] to null.
+    // g. Set p.[[ProxyHandler]] to null.
+    agent[p] = if p.is_callable(agent, gc) {
+        ProxyHeapData::RevokedCallable
+    } else {
+        ProxyHeapData::Revoked
+    };
+    // h. Return undefined.
+    Ok(Value::Undefined)
+}
@@ -13,7 +13,7 @@ use crate::{
         },
         builders::ordinary_object_builder::OrdinaryObjectBuilder,
         builtins::{ArgumentsList, Behaviour, Builtin},
-        execution::{Agent, JsResult, Realm, agent::ExceptionType},
+        execution::{Agent, JsResult, Realm},
         types::{
             BUILTIN_STRING_MEMORY, InternalMethods, IntoValue, Object, PropertyDescriptor, String,
             Value,
@@ -24,7 +24,6 @@ use crate::{
         context::{Bindable, GcScope},
         rootable::Scopable,
     },
-    heap::WellKnownSymbolIndexes,
 };
 
 pub(crate) struct ReflectObject;
@@ -151,11 +150,7 @@ impl ReflectObject {
 
         // 1. If IsCallable(target) is false, throw a TypeError exception.
         let Some(target) = is_callable(target, nogc) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Value is not callable",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Value is not callable", gc.into_nogc());
         };
         let target = target.scope(agent, nogc);
         let this_argument = this_argument.scope(agent, nogc);
@@ -187,11 +182,7 @@ impl ReflectObject {
 
         // 1. If IsConstructor(target) is false, throw a TypeError exception.
         let Some(target) = is_constructor(agent, target) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Value is not a constructor",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Value is not a constructor", gc.into_nogc());
         };
 
         // 2. If newTarget is not present, set newTarget to target.
@@ -199,11 +190,7 @@ impl ReflectObject {
         let new_target = if arguments.len() > 2 {
             let new_target = arguments.get(2).bind(nogc);
             let Some(new_target) = is_constructor(agent, new_target) else {
-                return Err(agent.throw_exception_with_static_message(
-                    ExceptionType::TypeError,
-                    "Value is not a constructor",
-                    gc.into_nogc(),
-                ));
+                return agent.type_error("Value is not a constructor", gc.into_nogc());
             };
             new_target
         } else {
@@ -241,11 +228,7 @@ impl ReflectObject {
 
         // 1. If target is not an Object, throw a TypeError exception.
         let Ok(target) = Object::try_from(target) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Value is not an object",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Value is not an object", gc.into_nogc());
         };
         let mut target = target.bind(nogc);
 
@@ -309,11 +292,7 @@ impl ReflectObject {
 
         // 1. If target is not an Object, throw a TypeError exception.
         let Ok(mut target) = Object::try_from(target) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Value is not an object",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Value is not an object", gc.into_nogc());
         };
 
         // 2. Let key be ? ToPropertyKey(propertyKey).
@@ -355,11 +334,7 @@ impl ReflectObject {
 
         // 1. If target is not an Object, throw a TypeError exception.
         let Ok(target) = Object::try_from(target) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Value is not an object",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Value is not an object", gc.into_nogc());
         };
         let mut target = target.bind(nogc);
 
@@ -399,11 +374,7 @@ impl ReflectObject {
 
         // 1. If target is not an Object, throw a TypeError exception.
         let Ok(target) = Object::try_from(target) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Value is not an object",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Value is not an object", gc.into_nogc());
         };
         let mut target = target.bind(nogc);
 
@@ -444,11 +415,7 @@ impl ReflectObject {
 
         // 1. If target is not an Object, throw a TypeError exception.
         let Ok(target) = Object::try_from(target) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Value is not an object",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Value is not an object", gc.into_nogc());
         };
 
         // 2. Return ? target.[[GetPrototypeOf]]().
@@ -471,11 +438,7 @@ impl ReflectObject {
 
         // 1. If target is not an Object, throw a TypeError exception.
         let Ok(target) = Object::try_from(target) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Value is not an object",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Value is not an object", gc.into_nogc());
         };
         let mut target = target.bind(nogc);
 
@@ -510,11 +473,7 @@ impl ReflectObject {
 
         // 1. If target is not an Object, throw a TypeError exception.
         let Ok(target) = Object::try_from(target) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Value is not an object",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Value is not an object", gc.into_nogc());
         };
 
         // 2. Return ? target.[[IsExtensible]]().
@@ -534,11 +493,7 @@ impl ReflectObject {
 
         // 1. If target is not an Object, throw a TypeError exception.
         let Ok(target) = Object::try_from(target) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Value is not an object",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Value is not an object", gc.into_nogc());
         };
 
         // 2. Let keys be ? target.[[OwnPropertyKeys]]().
@@ -565,11 +520,7 @@ impl ReflectObject {
 
         // 1. If target is not an Object, throw a TypeError exception.
         let Ok(target) = Object::try_from(target) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Value is not an object",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Value is not an object", gc.into_nogc());
         };
 
         // 2. Return ? target.[[PreventExtensions]]().
@@ -596,11 +547,7 @@ impl ReflectObject {
 
         // 1. If target is not an Object, throw a TypeError exception.
         let Ok(mut target) = Object::try_from(target) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Value is not an object",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Value is not an object", gc.into_nogc());
         };
 
         // 2. Let key be ? ToPropertyKey(propertyKey).
@@ -645,11 +592,7 @@ impl ReflectObject {
 
         // 1. If target is not an Object, throw a TypeError exception.
         let Ok(target) = Object::try_from(target) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Value is not an object",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Value is not an object", gc.into_nogc());
         };
 
         // 2. If proto is not an Object and proto is not null, throw a TypeError exception.
@@ -658,11 +601,7 @@ impl ReflectObject {
         } else if proto.is_null() {
             None
         } else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Prototype must be an object or null",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Prototype must be an object or null", gc.into_nogc());
         };
 
         // 3. Return ? target.[[SetPrototypeOf]](proto).
@@ -693,14 +632,7 @@ impl ReflectObject {
             .with_builtin_function_property::<ReflectObjectPreventExtensions>()
             .with_builtin_function_property::<ReflectObjectSet>()
             .with_builtin_function_property::<ReflectObjectSetPrototypeOf>()
-            .with_property(|builder| {
-                builder
-                    .with_key(WellKnownSymbolIndexes::ToStringTag.into())
-                    .with_value_readonly(BUILTIN_STRING_MEMORY.Reflect.into())
-                    .with_enumerable(false)
-                    .with_configurable(true)
-                    .build()
-            })
+            .with_to_string_tag(BUILTIN_STRING_MEMORY.Reflect)
             .build();
     }
 }
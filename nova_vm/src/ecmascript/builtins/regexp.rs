@@ -381,6 +381,7 @@ impl Index<RegExp<'_>> for Agent {
 
 impl IndexMut<RegExp<'_>> for Agent {
     fn index_mut(&mut self, index: RegExp) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.regexps[index]
     }
 }
@@ -425,3 +426,182 @@ impl<'a> CreateHeapData<RegExpHeapData<'a>, RegExp<'a>> for Heap {
         RegExp(RegExpIndex::last(&self.regexps))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        ecmascript::{
+            execution::{
+                Agent, DefaultHostHooks,
+                agent::{GcAgent, Options},
+                initialize_default_realm,
+            },
+            scripts_and_modules::script::test::evaluate,
+            types::Value,
+        },
+        engine::context::Bindable,
+    };
+
+    fn assert_all_true(agent: &mut GcAgent, sources: &[&'static str]) {
+        let realm = agent.create_default_realm();
+        agent.run_in_realm(&realm, |agent, mut gc| {
+            for source in sources {
+                let result = evaluate(agent, source, gc.reborrow()).unbind();
+                assert_eq!(result, Value::Boolean(true), "{source}");
+            }
+        });
+        agent.remove_realm(realm);
+    }
+
+    fn assert_script_true(source: &'static str) {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let result = evaluate(agent, source, gc);
+        assert_eq!(result, Value::Boolean(true), "{source}");
+    }
+
+    fn slots(agent: &GcAgent, name: &str) -> usize {
+        agent
+            .heap_vector_statistics()
+            .iter()
+            .find(|statistics| statistics.name == name)
+            .unwrap()
+            .slots
+    }
+
+    #[test]
+    fn each_evaluation_creates_a_new_regexp() {
+        let mut agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+        assert_all_true(
+            &mut agent,
+            &[
+                "function f() { return /ab+c/gi; }
+                var first = f();
+                var second = f();
+                first !== second",
+                "first instanceof RegExp && second instanceof RegExp &&
+                    Object.getPrototypeOf(first) === RegExp.prototype",
+                "first.custom = 1; second.custom === undefined",
+                "const seen = [];
+                for (let i = 0; i < 3; i++) seen.push(/x/);
+                seen[0] !== seen[1] && seen[1] !== seen[2]",
+            ],
+        );
+    }
+
+    #[test]
+    fn last_index_is_not_shared_between_evaluations() {
+        let mut agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+        assert_all_true(
+            &mut agent,
+            &[
+                "var regexps = [];
+                for (let i = 0; i < 5; i++) {
+                    const regexp = /a/g;
+                    if (regexp.lastIndex !== 0) throw new Error('lastIndex leaked');
+                    regexp.lastIndex = i + 1;
+                    regexps.push(regexp);
+                }
+                regexps.every((regexp, i) => regexp.lastIndex === i + 1)",
+                "Object.freeze(regexps[0]);
+                const fresh = regexps.map(() => /a/g)[0];
+                fresh.lastIndex = 7; fresh.lastIndex === 7 && regexps[0].lastIndex === 1",
+            ],
+        );
+    }
+
+    #[test]
+    fn pattern_is_created_once_per_call_site() {
+        let mut agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+        let realm = agent.create_default_realm();
+        agent.run_in_realm(&realm, |agent, mut gc| {
+            evaluate(
+                agent,
+                "var regexps = [];
+                function f() { return /a long pattern that is not a small string+/u; }",
+                gc.reborrow(),
+            );
+        });
+        // The template of the call site is kept alive by the function's bytecode.
+        agent.gc();
+        let regexps_before = slots(&agent, "regexps");
+        let strings_before = slots(&agent, "strings");
+        agent.run_in_realm(&realm, |agent, mut gc| {
+            evaluate(
+                agent,
+                "for (let i = 0; i < 1000; i++) regexps.push(f());",
+                gc.reborrow(),
+            );
+        });
+        assert!(slots(&agent, "regexps") - regexps_before >= 1000);
+        // The pattern source is shared by all the RegExp objects.
+        assert!(slots(&agent, "strings") - strings_before < 10);
+        agent.run_in_realm(&realm, |agent, mut gc| {
+            let result = evaluate(
+                agent,
+                "new Set(regexps).size === 1000 &&
+                    regexps.every((regexp) => regexp instanceof RegExp && regexp.lastIndex === 0)",
+                gc.reborrow(),
+            )
+            .unbind();
+            assert_eq!(result, Value::Boolean(true));
+        });
+        agent.remove_realm(realm);
+    }
+
+    #[test]
+    fn plain_regexps_match_with_the_builtin_matcher() {
+        assert_script_true(
+            "var m = /a(?<rest>b+)?/.exec('xxabb');
+            m.length === 2 && m[0] === 'abb' && m[1] === 'bb' && m.index === 2 &&
+            m.input === 'xxabb' && m.groups.rest === 'bb' &&
+            Object.getPrototypeOf(m.groups) === null &&
+            /a+/.exec('bcd') === null && /a+/.test('baa') && !/a+/.test('bcd') &&
+            /A/i.test('a') && /^b/m.test('a\\nb') && /a.b/s.test('a\\nb') &&
+            /\\u{1F600}/u.test('\\u{1F600}')",
+        );
+    }
+
+    #[test]
+    fn plain_regexps_update_last_index() {
+        assert_script_true(
+            "var g = /a/g;
+            g.exec('aba').index === 0 && g.lastIndex === 1 &&
+            g.exec('aba').index === 2 && g.lastIndex === 3 &&
+            g.exec('aba') === null && g.lastIndex === 0 &&
+            (() => {
+                var y = /a/y;
+                y.lastIndex = 1;
+                return y.exec('aba') === null && y.lastIndex === 0 &&
+                    y.exec('aba').index === 0 && y.lastIndex === 1;
+            })() &&
+            (() => {
+                var d = /b(c)/d.exec('abc');
+                return d.indices[0].join() === '1,3' && d.indices[1].join() === '2,3';
+            })()",
+        );
+    }
+
+    #[test]
+    fn plain_regexps_split_and_match_all() {
+        assert_script_true(
+            "var parts = /a/[Symbol.split]('bab');
+            parts.length === 2 && parts[0] === 'b' && parts[1] === 'b' &&
+            'a1b2c'.split(/\\d/).join('|') === 'a|b|c' &&
+            (() => {
+                var matches = [...'aXa'.matchAll(/a/g)];
+                return matches.length === 2 && matches[0].index === 0 && matches[1].index === 2;
+            })() &&
+            [...'aXa'.matchAll('a')].length === 2 &&
+            (() => {
+                try { 'aXa'.matchAll(/a/); return false; } catch (err) { return err instanceof TypeError; }
+            })() &&
+            (() => {
+                try { new RegExp('['); return false; } catch (err) { return err instanceof SyntaxError; }
+            })()",
+        );
+    }
+}
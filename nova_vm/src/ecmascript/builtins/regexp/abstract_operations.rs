@@ -2,15 +2,28 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use std::{ops::Range, sync::Arc};
+
 use oxc_ast::ast::RegExpFlags;
+use regress::{Flags, Match, Regex};
 
-use crate::ecmascript::abstract_operations::operations_on_objects::{set, try_set};
-use crate::ecmascript::types::IntoObject;
+use crate::ecmascript::abstract_operations::operations_on_objects::{
+    call_function, create_array_from_list, get, set, try_create_data_property_or_throw, try_set,
+};
+use crate::ecmascript::abstract_operations::testing_and_comparison::is_callable;
+use crate::ecmascript::abstract_operations::type_conversion::{to_length, to_string};
+use crate::ecmascript::builtins::ArgumentsList;
+use crate::ecmascript::builtins::ordinary::ordinary_object_create_with_intrinsics;
+use crate::ecmascript::execution::agent::ExceptionType;
+use crate::ecmascript::types::{
+    IntoFunction, IntoObject, IntoValue, Number, Object, PropertyKey, Value,
+};
 use crate::engine::context::{Bindable, GcScope, NoGcScope};
 use crate::engine::rootable::Scopable;
+use crate::engine::unwrap_try;
 use crate::{
     ecmascript::{
-        builtins::ordinary::ordinary_create_from_constructor,
+        builtins::{Array, ordinary::ordinary_create_from_constructor},
         execution::{Agent, JsResult, ProtoIntrinsics},
         types::{BUILTIN_STRING_MEMORY, Function, String},
     },
@@ -36,10 +49,14 @@ pub(crate) fn reg_exp_create_literal<'a>(
     //     1. Let obj be ! RegExpAlloc(%RegExp%).
     //     2. Return ? RegExpInitialize(obj, P, F).
     let f = f.unwrap_or(RegExpFlags::empty());
+    // Note: A literal that the matcher cannot compile throws a SyntaxError
+    // when it is first executed.
+    let reg_exp_matcher = compile_reg_exp_matcher(agent, p, f).ok().map(Arc::new);
     agent
         .heap
         .create(RegExpHeapData {
             object_index: None,
+            reg_exp_matcher,
             original_source: p.unbind(),
             original_flags: f,
             last_index: RegExpLastIndex::ZERO,
@@ -47,6 +64,55 @@ pub(crate) fn reg_exp_create_literal<'a>(
         .bind(gc)
 }
 
+/// Creates a new RegExp object from a RegExp literal template.
+///
+/// The new RegExp shares the pattern, flags and compiled matcher of the
+/// template.
+pub(crate) fn reg_exp_create_from_template<'a>(
+    agent: &mut Agent,
+    template: RegExp,
+    gc: NoGcScope<'a, '_>,
+) -> RegExp<'a> {
+    let template = &agent[template];
+    let data = RegExpHeapData {
+        object_index: None,
+        reg_exp_matcher: template.reg_exp_matcher.clone(),
+        original_source: template.original_source,
+        original_flags: template.original_flags,
+        last_index: RegExpLastIndex::ZERO,
+    };
+    agent.heap.create(data).bind(gc)
+}
+
+/// ### [22.2.3.1 RegExpCreate ( P, F )](https://tc39.es/ecma262/#sec-regexpcreate)
+///
+/// The abstract operation RegExpCreate takes arguments P (an ECMAScript
+/// language value) and F (a String or undefined) and returns either a normal
+/// completion containing an Object or a throw completion.
+pub(crate) fn reg_exp_create<'a>(
+    agent: &mut Agent,
+    p: Value,
+    f: RegExpFlags,
+    mut gc: GcScope<'a, '_>,
+) -> JsResult<'a, RegExp<'a>> {
+    let p = p.bind(gc.nogc()).scope(agent, gc.nogc());
+    // 1. Let obj be ! RegExpAlloc(%RegExp%).
+    let reg_exp_constructor = agent.current_realm_record().intrinsics().reg_exp();
+    let obj = reg_exp_alloc(agent, reg_exp_constructor.into_function(), gc.reborrow())
+        .unbind()?
+        .scope(agent, gc.nogc());
+    // 2. Return ? RegExpInitialize(obj, P, F).
+    let p = p.get(agent).bind(gc.nogc());
+    let p = if p.is_undefined() {
+        String::EMPTY_STRING
+    } else {
+        to_string(agent, p.unbind(), gc.reborrow())
+            .unbind()?
+            .bind(gc.nogc())
+    };
+    reg_exp_initialize_from_string(agent, obj.get(agent), p.unbind(), Some(f), gc)
+}
+
 /// ### [22.2.3.2 RegExpAlloc ( newTarget )]()
 ///
 /// The abstract operation RegExpAlloc takes argument newTarget (a constructor)
@@ -77,6 +143,68 @@ pub(crate) fn reg_exp_alloc<'a>(
 /// pattern (an ECMAScript language value), and flags (an ECMAScript language
 /// value) and returns either a normal completion containing an Object or a
 /// throw completion.
+pub(crate) fn reg_exp_initialize<'a>(
+    agent: &mut Agent,
+    obj: RegExp,
+    pattern: Value,
+    flags: Value,
+    mut gc: GcScope<'a, '_>,
+) -> JsResult<'a, RegExp<'a>> {
+    let obj = obj.bind(gc.nogc()).scope(agent, gc.nogc());
+    let pattern = pattern.bind(gc.nogc());
+    let flags = flags.bind(gc.nogc()).scope(agent, gc.nogc());
+    // 1. If pattern is undefined, let P be the empty String.
+    let p = if pattern.is_undefined() {
+        String::EMPTY_STRING
+    } else {
+        // 2. Else, let P be ? ToString(pattern).
+        to_string(agent, pattern.unbind(), gc.reborrow())
+            .unbind()?
+            .bind(gc.nogc())
+    }
+    .scope(agent, gc.nogc());
+    let flags = flags.get(agent).bind(gc.nogc());
+    // 3. If flags is undefined, let F be the empty String.
+    let f = if flags.is_undefined() {
+        RegExpFlags::empty()
+    } else {
+        // 4. Else, let F be ? ToString(flags).
+        let f = to_string(agent, flags.unbind(), gc.reborrow())
+            .unbind()?
+            .bind(gc.nogc());
+        // 5. If F contains any code unit other than "d", "g", "i", "m", "s",
+        //    "u", "v", or "y", or if F contains any code unit more than once,
+        //    throw a SyntaxError exception.
+        let mut parsed = RegExpFlags::empty();
+        for flag in f.as_wtf8(agent).code_points() {
+            match flag.to_char().map(RegExpFlags::try_from) {
+                Some(Ok(flag)) if !parsed.contains(flag) => parsed |= flag,
+                _ => {
+                    let message = format!(
+                        "Invalid regular expression flags '{}'",
+                        f.to_string_lossy(agent)
+                    );
+                    return Err(agent.throw_exception(
+                        ExceptionType::SyntaxError,
+                        message,
+                        gc.into_nogc(),
+                    ));
+                }
+            }
+        }
+        parsed
+    };
+    reg_exp_initialize_from_string(agent, obj.get(agent), p.get(agent), Some(f), gc)
+}
+
+/// ### [22.2.3.3 RegExpInitialize ( obj, pattern, flags )]()
+///
+/// The abstract operation RegExpInitialize takes arguments obj (an Object),
+/// pattern (an ECMAScript language value), and flags (an ECMAScript language
+/// value) and returns either a normal completion containing an Object or a
+/// throw completion.
+///
+/// This is a variant for an already converted pattern and flags.
 pub(crate) fn reg_exp_initialize_from_string<'a>(
     agent: &mut Agent,
     obj: RegExp,
@@ -88,6 +216,15 @@ pub(crate) fn reg_exp_initialize_from_string<'a>(
     let p = p.bind(gc.nogc());
     //     3. If flags is undefined, let F be the empty String.
     let f = flags.unwrap_or(RegExpFlags::empty());
+    //     13. Let parseResult be ParsePattern(patternText, u, v).
+    let reg_exp_matcher = match compile_reg_exp_matcher(agent, p, f) {
+        Ok(reg_exp_matcher) => reg_exp_matcher,
+        //     14. If parseResult is a non-empty List of SyntaxError objects,
+        //         throw a SyntaxError exception.
+        Err(message) => {
+            return Err(agent.throw_exception(ExceptionType::SyntaxError, message, gc.into_nogc()));
+        }
+    };
     //     4. Else, let F be ? ToString(flags).
     //     5. If F contains any code unit other than "d", "g", "i", "m", "s", "u", "v", or "y", or if F contains any code unit more than once, throw a SyntaxError exception.
     //     6. If F contains "i", let i be true; else let i be false.
@@ -110,6 +247,7 @@ pub(crate) fn reg_exp_initialize_from_string<'a>(
     //     19. Let rer be the RegExp Record { [[IgnoreCase]]: i, [[Multiline]]: m, [[DotAll]]: s, [[Unicode]]: u, [[UnicodeSets]]: v, [[CapturingGroupsCount]]: capturingGroupsCount }.
     //     20. Set obj.[[RegExpRecord]] to rer.
     //     21. Set obj.[[RegExpMatcher]] to CompilePattern of parseResult with argument rer.
+    agent[obj].reg_exp_matcher = Some(Arc::new(reg_exp_matcher));
     //     22. Perform ? Set(obj, "lastIndex", +0𝔽, true).
     //     23. Return obj.
     if try_set(
@@ -155,3 +293,463 @@ pub(crate) fn parse_pattern() {
     //         a. Let parseResult be ParseText(patternText, Pattern[~UnicodeMode, ~UnicodeSetsMode, +NamedCaptureGroups]).
     //     5. Return parseResult.
 }
+
+/// Parses and compiles the pattern of a RegExp into its \[\[RegExpMatcher]].
+///
+/// Returns the SyntaxError message if the pattern is not valid.
+fn compile_reg_exp_matcher(
+    agent: &Agent,
+    p: String,
+    f: RegExpFlags,
+) -> Result<Regex, std::string::String> {
+    let flags = Flags {
+        icase: f.contains(RegExpFlags::I),
+        multiline: f.contains(RegExpFlags::M),
+        dot_all: f.contains(RegExpFlags::S),
+        unicode: f.contains(RegExpFlags::U),
+        unicode_sets: f.contains(RegExpFlags::V),
+        no_opt: false,
+    };
+    let pattern_text = p.as_wtf8(agent);
+    let result = if flags.unicode && flags.unicode_sets {
+        // 1. If v is true and u is true, then
+        //     a. Let parseResult be a List containing one or more SyntaxError
+        //        objects.
+        return Err("Invalid regular expression flags 'uv'".to_string());
+    } else if flags.unicode || flags.unicode_sets {
+        // 11. If u is true or v is true, then
+        //     a. Let patternText be StringToCodePoints(P).
+        Regex::from_unicode(pattern_text.code_points().map(|cp| cp.to_u32()), flags)
+    } else {
+        // 12. Else,
+        //     a. Let patternText be the result of interpreting each of P's
+        //        16-bit elements as a Unicode BMP code point.
+        Regex::from_unicode(pattern_text.to_ill_formed_utf16().map(u32::from), flags)
+    };
+    result.map_err(|err| {
+        format!(
+            "Invalid regular expression /{}/: {}",
+            p.to_string_lossy(agent),
+            err.text
+        )
+    })
+}
+
+/// Returns the \[\[RegExpMatcher]] of a RegExp, compiling it first if it has
+/// not been compiled yet.
+fn get_reg_exp_matcher<'a>(
+    agent: &mut Agent,
+    r: RegExp,
+    gc: NoGcScope<'a, '_>,
+) -> JsResult<'a, Arc<Regex>> {
+    if let Some(reg_exp_matcher) = &agent[r].reg_exp_matcher {
+        return Ok(reg_exp_matcher.clone());
+    }
+    let RegExpHeapData {
+        original_source,
+        original_flags,
+        ..
+    } = agent[r];
+    match compile_reg_exp_matcher(agent, original_source, original_flags) {
+        Ok(reg_exp_matcher) => {
+            let reg_exp_matcher = Arc::new(reg_exp_matcher);
+            agent[r].reg_exp_matcher = Some(reg_exp_matcher.clone());
+            Ok(reg_exp_matcher)
+        }
+        Err(message) => Err(agent.throw_exception(ExceptionType::SyntaxError, message, gc)),
+    }
+}
+
+/// ### [22.2.7.1 RegExpExec ( R, S )](https://tc39.es/ecma262/#sec-regexpexec)
+///
+/// The abstract operation RegExpExec takes arguments R (an Object) and S (a
+/// String) and returns either a normal completion containing either an Object
+/// or null, or a throw completion.
+///
+/// > #### Note
+/// > If a callable "exec" property is not found this algorithm falls back to
+/// > attempting to use the built-in RegExp matching algorithm. This provides
+/// > compatible behaviour for code written for prior editions where most
+/// > built-in algorithms that use regular expressions did not perform a
+/// > dynamic property lookup of "exec".
+pub(crate) fn reg_exp_exec<'a>(
+    agent: &mut Agent,
+    r: Object,
+    s: String,
+    mut gc: GcScope<'a, '_>,
+) -> JsResult<'a, Option<Object<'a>>> {
+    let r = r.bind(gc.nogc());
+    let s = s.bind(gc.nogc());
+    let scoped_r = r.scope(agent, gc.nogc());
+    let scoped_s = s.scope(agent, gc.nogc());
+    // 1. Let exec be ? Get(R, "exec").
+    let exec = get(
+        agent,
+        r.unbind(),
+        BUILTIN_STRING_MEMORY.exec.into(),
+        gc.reborrow(),
+    )
+    .unbind()?
+    .bind(gc.nogc());
+    // 2. If IsCallable(exec) is true, then
+    if let Some(exec) = is_callable(exec, gc.nogc()) {
+        // a. Let result be ? Call(exec, R, « S »).
+        let result = call_function(
+            agent,
+            exec.unbind(),
+            scoped_r.get(agent).into_value(),
+            Some(ArgumentsList::from_mut_slice(&mut [scoped_s
+                .get(agent)
+                .into_value()])),
+            gc.reborrow(),
+        )
+        .unbind()?;
+        let gc = gc.into_nogc();
+        let result = result.bind(gc);
+        // b. If result is not an Object and result is not null, throw a
+        //    TypeError exception.
+        // c. Return result.
+        return match result {
+            Value::Null => Ok(None),
+            _ => match Object::try_from(result) {
+                Ok(result) => Ok(Some(result)),
+                Err(_) => agent.type_error(
+                    "RegExp exec method returned something other than an Object or null",
+                    gc,
+                ),
+            },
+        };
+    }
+    // 3. Perform ? RequireInternalSlot(R, [[RegExpMatcher]]).
+    let Object::RegExp(r) = scoped_r.get(agent) else {
+        return agent.type_error("value is not a RegExp", gc.into_nogc());
+    };
+    // 4. Return ? RegExpBuiltinExec(R, S).
+    reg_exp_builtin_exec(agent, r, scoped_s.get(agent), gc)
+        .map(|result| result.map(|a| a.into_object()))
+}
+
+/// ### [22.2.7.2 RegExpBuiltinExec ( R, S )](https://tc39.es/ecma262/#sec-regexpbuiltinexec)
+///
+/// The abstract operation RegExpBuiltinExec takes arguments R (an initialized
+/// RegExp instance) and S (a String) and returns either a normal completion
+/// containing either an Array exotic object or null, or a throw completion.
+pub(crate) fn reg_exp_builtin_exec<'a>(
+    agent: &mut Agent,
+    r: RegExp,
+    s: String,
+    mut gc: GcScope<'a, '_>,
+) -> JsResult<'a, Option<Array<'a>>> {
+    let r = r.bind(gc.nogc());
+    let s = s.bind(gc.nogc());
+    let scoped_r = r.scope(agent, gc.nogc());
+    let scoped_s = s.scope(agent, gc.nogc());
+    // 1. Let length be the length of S.
+    let length = s.utf16_len(agent);
+    // 2. Let lastIndex be ℝ(? ToLength(! Get(R, "lastIndex"))).
+    let last_index = get(
+        agent,
+        r.unbind(),
+        BUILTIN_STRING_MEMORY.lastIndex.into(),
+        gc.reborrow(),
+    )
+    .unbind()?
+    .bind(gc.nogc());
+    let last_index = to_length(agent, last_index.unbind(), gc.reborrow()).unbind()? as usize;
+    let r = scoped_r.get(agent).bind(gc.nogc());
+    // 3. Let flags be R.[[OriginalFlags]].
+    let flags = agent[r].original_flags;
+    // 4. If flags contains "g", let global be true; else let global be false.
+    let global = flags.contains(RegExpFlags::G);
+    // 5. If flags contains "y", let sticky be true; else let sticky be false.
+    let sticky = flags.contains(RegExpFlags::Y);
+    // 6. If flags contains "d", let hasIndices be true; else let hasIndices be false.
+    let has_indices = flags.contains(RegExpFlags::D);
+    // 7. If global is false and sticky is false, set lastIndex to 0.
+    let last_index = if !global && !sticky { 0 } else { last_index };
+    // 8. Let matcher be R.[[RegExpMatcher]].
+    let matcher = get_reg_exp_matcher(agent, r, gc.nogc()).map_err(|err| err.unbind())?;
+    // 9. If flags contains "u" or flags contains "v", let fullUnicode be
+    //    true; else let fullUnicode be false.
+    let full_unicode = flags.intersects(RegExpFlags::U | RegExpFlags::V);
+    // 10. Let matchSucceeded be false.
+    // 11. If fullUnicode is true, let input be StringToCodePoints(S).
+    //     Otherwise, let input be a List whose elements are the code units
+    //     that are the elements of S.
+    // 12. NOTE: Each element of input is considered to be a character.
+    // 13. Repeat, while matchSucceeded is false,
+    //     a. If lastIndex > length, then
+    //         i. If global is true or sticky is true, then
+    //             1. Perform ? Set(R, "lastIndex", +0𝔽, true).
+    //         ii. Return null.
+    //     b. Let inputIndex be the index into input of the character that
+    //        was obtained from element lastIndex of S.
+    //     c. Let r be matcher(input, inputIndex).
+    //     d. If r is failure, then
+    //         i. If sticky is true, then
+    //             1. Perform ? Set(R, "lastIndex", +0𝔽, true).
+    //             2. Return null.
+    //         ii. Set lastIndex to AdvanceStringIndex(S, lastIndex, fullUnicode).
+    //     e. Else,
+    //         i. Assert: r is a MatchState.
+    //         ii. Set matchSucceeded to true.
+    // NOTE: The matcher searches forward from lastIndex by itself; a sticky
+    // RegExp only accepts a match that starts at lastIndex.
+    let found = if last_index > length {
+        None
+    } else {
+        let input = scoped_s
+            .get(agent)
+            .as_wtf8(agent)
+            .to_ill_formed_utf16()
+            .collect::<Vec<u16>>();
+        if full_unicode {
+            matcher.find_from_utf16(&input, last_index).next()
+        } else {
+            matcher.find_from_ucs2(&input, last_index).next()
+        }
+    }
+    .filter(|r| !sticky || r.start() == last_index);
+    let Some(found) = found else {
+        if global || sticky {
+            set(
+                agent,
+                scoped_r.get(agent).into_object(),
+                BUILTIN_STRING_MEMORY.lastIndex.into(),
+                0.into(),
+                true,
+                gc,
+            )?;
+        }
+        return Ok(None);
+    };
+    let last_index = found.start();
+    // 14. Let e be r.[[EndIndex]].
+    // 15. If fullUnicode is true, set e to GetStringIndex(S, e).
+    let e = found.end();
+    // 16. If global is true or sticky is true, then
+    if global || sticky {
+        // a. Perform ? Set(R, "lastIndex", 𝔽(e), true).
+        set(
+            agent,
+            scoped_r.get(agent).into_object(),
+            BUILTIN_STRING_MEMORY.lastIndex.into(),
+            Number::try_from(e).unwrap().into_value(),
+            true,
+            gc.reborrow(),
+        )
+        .unbind()?;
+    }
+    let gc = gc.into_nogc();
+    let s = scoped_s.get(agent).bind(gc);
+    // 17. Let n be the number of elements in r.[[Captures]].
+    // 18. Assert: n = R.[[RegExpRecord]].[[CapturingGroupsCount]].
+    // 19. Assert: n < 2**32 - 1.
+    // 20. Let A be ! ArrayCreate(n + 1).
+    // 26. Let matchedSubstr be GetMatchString(S, match).
+    // 27. Perform ! CreateDataPropertyOrThrow(A, "0", matchedSubstr).
+    // 30. For each integer i such that 1 ≤ i ≤ n, in ascending order, do
+    //     a. Let captureI be ith element of r.[[Captures]].
+    //     b. If captureI is undefined, let capturedValue be undefined.
+    //     c. Else, let capturedValue be GetMatchString(S, captureI).
+    //     d. Perform ! CreateDataPropertyOrThrow(A, ! ToString(𝔽(i)), capturedValue).
+    let mut values = Vec::with_capacity(found.captures.len() + 1);
+    values.push(Some(found.range()));
+    values.extend(found.captures.iter().cloned());
+    let values = values
+        .into_iter()
+        .map(|range| get_match_string(agent, s, range, gc))
+        .collect::<Vec<_>>();
+    let a = create_array_from_list(agent, &values, gc);
+    // 21. Perform ! CreateDataPropertyOrThrow(A, "index", 𝔽(lastIndex)).
+    unwrap_try(try_create_data_property_or_throw(
+        agent,
+        a,
+        BUILTIN_STRING_MEMORY.index.into(),
+        Number::try_from(last_index).unwrap().into_value(),
+        gc,
+    ))
+    .unwrap();
+    // 22. Perform ! CreateDataPropertyOrThrow(A, "input", S).
+    unwrap_try(try_create_data_property_or_throw(
+        agent,
+        a,
+        BUILTIN_STRING_MEMORY.input.into(),
+        s.into_value(),
+        gc,
+    ))
+    .unwrap();
+    // 28. If R contains any GroupName, then
+    //     a. Let groups be OrdinaryObjectCreate(null).
+    //     b. Let hasGroups be true.
+    // 29. Perform ! CreateDataPropertyOrThrow(A, "groups", groups).
+    // 30. e. If the ith capture of R was defined with a GroupName, then ...
+    let groups = create_groups_object(
+        agent,
+        &found,
+        |agent, range| get_match_string(agent, s, range, gc),
+        gc,
+    );
+    unwrap_try(try_create_data_property_or_throw(
+        agent,
+        a,
+        BUILTIN_STRING_MEMORY.groups.into(),
+        groups,
+        gc,
+    ))
+    .unwrap();
+    // 31. If hasIndices is true, then
+    if has_indices {
+        // a. Let indicesArray be MakeMatchIndicesIndexPairArray(S, indices, groupNames, hasGroups).
+        let indices_array = make_match_indices_index_pair_array(agent, &found, gc);
+        // b. Perform ! CreateDataPropertyOrThrow(A, "indices", indicesArray).
+        unwrap_try(try_create_data_property_or_throw(
+            agent,
+            a,
+            BUILTIN_STRING_MEMORY.indices.into(),
+            indices_array.into_value(),
+            gc,
+        ))
+        .unwrap();
+    }
+    // 32. Return A.
+    Ok(Some(a))
+}
+
+/// ### [22.2.7.7 GetMatchString ( S, match )](https://tc39.es/ecma262/#sec-getmatchstring)
+///
+/// Returns undefined for a capture that did not participate in the match.
+fn get_match_string<'a>(
+    agent: &mut Agent,
+    s: String,
+    range: Option<Range<usize>>,
+    gc: NoGcScope<'a, '_>,
+) -> Value<'a> {
+    range.map_or(Value::Undefined, |range| {
+        s.substring(agent, range.start, range.end, gc).into_value()
+    })
+}
+
+/// Creates the "groups" object of a match result, or returns undefined if the
+/// RegExp has no named capture groups.
+///
+/// With duplicate named groups, the value of the group that participated in
+/// the match wins.
+fn create_groups_object<'a>(
+    agent: &mut Agent,
+    found: &Match,
+    mut value_of: impl FnMut(&mut Agent, Option<Range<usize>>) -> Value<'a>,
+    gc: NoGcScope<'a, '_>,
+) -> Value<'a> {
+    if found.named_groups().next().is_none() {
+        return Value::Undefined;
+    }
+    let groups = ordinary_object_create_with_intrinsics(agent, None, None, gc);
+    let mut matched_group_names = Vec::new();
+    for (name, range) in found.named_groups() {
+        if matched_group_names.contains(&name) {
+            continue;
+        }
+        if range.is_some() {
+            matched_group_names.push(name);
+        }
+        let value = value_of(agent, range);
+        let name = PropertyKey::from_str(agent, name, gc);
+        unwrap_try(try_create_data_property_or_throw(
+            agent, groups, name, value, gc,
+        ))
+        .unwrap();
+    }
+    groups.into_value()
+}
+
+/// ### [22.2.7.8 MakeMatchIndicesIndexPairArray ( S, indices, groupNames, hasGroups )](https://tc39.es/ecma262/#sec-makematchindicesindexpairarray)
+fn make_match_indices_index_pair_array<'a>(
+    agent: &mut Agent,
+    found: &Match,
+    gc: NoGcScope<'a, '_>,
+) -> Array<'a> {
+    // 6. For each integer i such that 0 ≤ i < n, in ascending order, do
+    //     a. Let matchIndices be indices[i].
+    //     b. If matchIndices is not undefined, then
+    //         i. Let matchIndexPair be GetMatchIndexPair(S, matchIndices).
+    //     c. Else,
+    //         i. Let matchIndexPair be undefined.
+    //     d. Perform ! CreateDataPropertyOrThrow(A, ! ToString(𝔽(i)), matchIndexPair).
+    let mut pairs = Vec::with_capacity(found.captures.len() + 1);
+    pairs.push(Some(found.range()));
+    pairs.extend(found.captures.iter().cloned());
+    let pairs = pairs
+        .into_iter()
+        .map(|range| get_match_index_pair(agent, range, gc))
+        .collect::<Vec<_>>();
+    // 3. Let A be ! ArrayCreate(n).
+    let a = create_array_from_list(agent, &pairs, gc);
+    // 4. If hasGroups is true, then
+    //     a. Let groups be OrdinaryObjectCreate(null).
+    // 5. Perform ! CreateDataPropertyOrThrow(A, "groups", groups).
+    //     e. If i > 0 and groupNames[i - 1] is not undefined, then
+    //         ii. Perform ! CreateDataPropertyOrThrow(groups, groupNames[i - 1], matchIndexPair).
+    let groups = create_groups_object(
+        agent,
+        found,
+        |agent, range| get_match_index_pair(agent, range, gc),
+        gc,
+    );
+    unwrap_try(try_create_data_property_or_throw(
+        agent,
+        a,
+        BUILTIN_STRING_MEMORY.groups.into(),
+        groups,
+        gc,
+    ))
+    .unwrap();
+    // 7. Return A.
+    a
+}
+
+/// ### [22.2.7.9 GetMatchIndexPair ( S, match )](https://tc39.es/ecma262/#sec-getmatchindexpair)
+///
+/// Returns undefined for a capture that did not participate in the match.
+fn get_match_index_pair<'a>(
+    agent: &mut Agent,
+    range: Option<Range<usize>>,
+    gc: NoGcScope<'a, '_>,
+) -> Value<'a> {
+    let Some(range) = range else {
+        return Value::Undefined;
+    };
+    // 3. Return CreateArrayFromList(« 𝔽(match.[[StartIndex]]), 𝔽(match.[[EndIndex]]) »).
+    create_array_from_list(
+        agent,
+        &[
+            Number::try_from(range.start).unwrap().into_value(),
+            Number::try_from(range.end).unwrap().into_value(),
+        ],
+        gc,
+    )
+    .into_value()
+}
+
+/// ### [22.2.7.3 AdvanceStringIndex ( S, index, unicode )](https://tc39.es/ecma262/#sec-advancestringindex)
+///
+/// The abstract operation AdvanceStringIndex takes arguments S (a String),
+/// index (a non-negative integer), and unicode (a Boolean) and returns an
+/// integer.
+pub(crate) fn advance_string_index(agent: &Agent, s: String, index: usize, unicode: bool) -> usize {
+    // 1. Assert: index ≤ 2**53 - 1.
+    // 2. If unicode is false, return index + 1.
+    if !unicode {
+        return index + 1;
+    }
+    // 3. Let length be the length of S.
+    let length = s.utf16_len(agent);
+    // 4. If index + 1 ≥ length, return index + 1.
+    if index + 1 >= length {
+        return index + 1;
+    }
+    // 5. Let cp be CodePointAt(S, index).
+    let (_, code_unit_count) = s.code_point_at(agent, index);
+    // 6. Return index + cp.[[CodeUnitCount]].
+    index + code_unit_count
+}
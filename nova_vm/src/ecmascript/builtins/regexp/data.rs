@@ -2,7 +2,10 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use std::sync::Arc;
+
 use oxc_ast::ast::RegExpFlags;
+use regress::Regex;
 
 use crate::{
     ecmascript::types::{OrdinaryObject, PropertyDescriptor, String, Value},
@@ -99,10 +102,14 @@ impl RegExpLastIndex {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct RegExpHeapData<'a> {
     pub(crate) object_index: Option<OrdinaryObject<'a>>,
-    // _regex: RegExp,
+    /// The compiled \[\[RegExpMatcher]] of the RegExp.
+    ///
+    /// This is shared between RegExps created from the same literal and is
+    /// compiled on demand if it is missing.
+    pub(crate) reg_exp_matcher: Option<Arc<Regex>>,
     pub(crate) original_source: String<'a>,
     pub(crate) original_flags: RegExpFlags,
     pub(crate) last_index: RegExpLastIndex,
@@ -112,6 +119,7 @@ impl Default for RegExpHeapData<'_> {
     fn default() -> Self {
         Self {
             object_index: Default::default(),
+            reg_exp_matcher: None,
             original_source: String::EMPTY_STRING,
             original_flags: RegExpFlags::empty(),
             last_index: Default::default(),
@@ -138,6 +146,7 @@ impl HeapMarkAndSweep for RegExpHeapData<'static> {
     fn mark_values(&self, queues: &mut WorkQueues) {
         let Self {
             object_index,
+            reg_exp_matcher: _,
             original_source,
             original_flags: _,
             last_index: _,
@@ -149,6 +158,7 @@ impl HeapMarkAndSweep for RegExpHeapData<'static> {
     fn sweep_values(&mut self, compactions: &CompactionLists) {
         let Self {
             object_index,
+            reg_exp_matcher: _,
             original_source,
             original_flags: _,
             last_index: _,
@@ -135,6 +135,7 @@ impl Index<Set<'_>> for Agent {
 
 impl IndexMut<Set<'_>> for Agent {
     fn index_mut(&mut self, index: Set) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.sets[index]
     }
 }
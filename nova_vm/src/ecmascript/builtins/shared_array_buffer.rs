@@ -74,6 +74,7 @@ impl Index<SharedArrayBuffer<'_>> for Agent {
 
 impl IndexMut<SharedArrayBuffer<'_>> for Agent {
     fn index_mut(&mut self, index: SharedArrayBuffer) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.shared_array_buffers[index]
     }
 }
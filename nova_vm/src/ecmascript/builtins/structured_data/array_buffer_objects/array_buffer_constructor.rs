@@ -13,7 +13,7 @@ use crate::{
             ArgumentsList, Behaviour, Builtin, BuiltinGetter, BuiltinIntrinsicConstructor,
             array_buffer::allocate_array_buffer,
         },
-        execution::{Agent, JsResult, Realm, agent::ExceptionType},
+        execution::{Agent, JsResult, Realm},
         types::{
             BUILTIN_STRING_MEMORY, Function, IntoObject, IntoValue, Object, PropertyKey, String,
             Value,
@@ -72,11 +72,7 @@ impl ArrayBufferConstructor {
         let nogc = gc.nogc();
         // 1. If NewTarget is undefined, throw a TypeError exception.
         let Some(new_target) = new_target else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Constructor ArrayBuffer requires 'new'",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Constructor ArrayBuffer requires 'new'", gc.into_nogc());
         };
         let new_target = new_target.bind(nogc);
         let length = arguments.get(0).bind(nogc);
@@ -3,27 +3,22 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use crate::ecmascript::abstract_operations::type_conversion::try_to_index;
+use crate::ecmascript::{
+    abstract_operations::{
+        operations_on_objects::construct,
+        type_conversion::{to_index, to_integer_or_infinity},
+    },
+    builders::ordinary_object_builder::OrdinaryObjectBuilder,
+    builtins::{
+        ArgumentsList, ArrayBuffer, Behaviour, Builtin, BuiltinGetter,
+        array_buffer::{is_detached_buffer, is_fixed_length_array_buffer},
+    },
+    execution::{Agent, JsResult, Realm},
+    types::{BUILTIN_STRING_MEMORY, IntoFunction, IntoValue, Object, PropertyKey, String, Value},
+};
 use crate::engine::TryResult;
 use crate::engine::context::{Bindable, GcScope, NoGcScope};
 use crate::engine::rootable::Scopable;
-use crate::{
-    ecmascript::{
-        abstract_operations::{
-            operations_on_objects::construct,
-            type_conversion::{to_index, to_integer_or_infinity},
-        },
-        builders::ordinary_object_builder::OrdinaryObjectBuilder,
-        builtins::{
-            ArgumentsList, ArrayBuffer, Behaviour, Builtin, BuiltinGetter,
-            array_buffer::{is_detached_buffer, is_fixed_length_array_buffer},
-        },
-        execution::{Agent, JsResult, Realm, agent::ExceptionType},
-        types::{
-            BUILTIN_STRING_MEMORY, IntoFunction, IntoValue, Object, PropertyKey, String, Value,
-        },
-    },
-    heap::WellKnownSymbolIndexes,
-};
 
 pub(crate) struct ArrayBufferPrototype;
 
@@ -185,11 +180,10 @@ impl ArrayBufferPrototype {
             .unbind()?
             .bind(gc.nogc());
         if !o.is_resizable(agent) {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
+            return agent.type_error(
                 "Attempted to resize fixed length ArrayBuffer",
                 gc.into_nogc(),
-            ));
+            );
         }
         // 4. Let newByteLength be ? ToIndex(newLength).
         let new_byte_length =
@@ -205,19 +199,14 @@ impl ArrayBufferPrototype {
             };
         // 5. If IsDetachedBuffer(O) is true, throw a TypeError exception.
         if is_detached_buffer(agent, o) {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Cannot resize a detached ArrayBuffer",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Cannot resize a detached ArrayBuffer", gc.into_nogc());
         }
         // 6. If newByteLength > O.[[ArrayBufferMaxByteLength]], throw a RangeError exception.
         if new_byte_length > o.max_byte_length(agent) {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::RangeError,
+            return agent.range_error(
                 "Attempted to resize beyond ArrayBuffer maxByteLength",
                 gc.into_nogc(),
-            ));
+            );
         }
         // 7. Let hostHandled be ? HostResizeArrayBuffer(O, newByteLength).
         // 8. If hostHandled is handled, return undefined.
@@ -257,11 +246,7 @@ impl ArrayBufferPrototype {
             .bind(gc.nogc());
         // 4. If IsDetachedBuffer(O) is true, throw a TypeError exception.
         if is_detached_buffer(agent, o) {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Cannot slice a detached ArrayBuffer",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Cannot slice a detached ArrayBuffer", gc.into_nogc());
         }
         // 5. Let len be O.[[ArrayBufferByteLength]].
         let len = o.byte_length(agent);
@@ -325,37 +310,24 @@ impl ArrayBufferPrototype {
         // 18. If IsSharedArrayBuffer(new) is true, throw a TypeError exception.
         // 19. If IsDetachedBuffer(new) is true, throw a TypeError exception.
         if is_detached_buffer(agent, new) {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Construction produced a detached ArrayBuffer",
-                gc,
-            ));
+            return agent.type_error("Construction produced a detached ArrayBuffer", gc);
         }
         // 20. If SameValue(new, O) is true, throw a TypeError exception.
         let o = scoped_o.get(agent).bind(gc);
         if new == o {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Construction returned the original ArrayBuffer",
-                gc,
-            ));
+            return agent.type_error("Construction returned the original ArrayBuffer", gc);
         }
         // 21. If new.[[ArrayBufferByteLength]] < newLen, throw a TypeError exception.
         if new.byte_length(agent) < new_len {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
+            return agent.type_error(
                 "Construction returned a smaller ArrayBuffer than requested",
                 gc,
-            ));
+            );
         }
         // 22. NOTE: Side-effects of the above steps may have detached or resized O.
         // 23. If IsDetachedBuffer(O) is true, throw a TypeError exception.
         if is_detached_buffer(agent, o) {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Construction detached ArrayBuffer being sliced",
-                gc,
-            ));
+            return agent.type_error("Construction detached ArrayBuffer being sliced", gc);
         }
         // 24. Let fromBuf be O.[[ArrayBufferData]].
         // 25. Let toBuf be new.[[ArrayBufferData]].
@@ -421,14 +393,7 @@ impl ArrayBufferPrototype {
             .with_builtin_function_property::<ArrayBufferPrototypeSlice>()
             .with_builtin_function_property::<ArrayBufferPrototypeTransfer>()
             .with_builtin_function_property::<ArrayBufferPrototypeTransferToFixedLength>()
-            .with_property(|builder| {
-                builder
-                    .with_key(WellKnownSymbolIndexes::ToStringTag.into())
-                    .with_value_readonly(BUILTIN_STRING_MEMORY.ArrayBuffer.into_value())
-                    .with_enumerable(false)
-                    .with_configurable(true)
-                    .build()
-            })
+            .with_to_string_tag(BUILTIN_STRING_MEMORY.ArrayBuffer)
             .build();
     }
 }
@@ -443,10 +408,6 @@ pub(crate) fn require_internal_slot_array_buffer<'a>(
         // 1. Perform ? RequireInternalSlot(O, [[ArrayBufferData]]).
         // 2. If IsSharedArrayBuffer(O) is true, throw a TypeError exception.
         Value::ArrayBuffer(array_buffer) => Ok(array_buffer.unbind()),
-        _ => Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Expected this to be ArrayBuffer",
-            gc,
-        )),
+        _ => agent.type_error("Expected this to be ArrayBuffer", gc),
     }
 }
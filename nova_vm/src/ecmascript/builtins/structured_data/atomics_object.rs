@@ -3,18 +3,13 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use crate::ecmascript::builtins::Behaviour;
-#[cfg(feature = "proposal-atomics-microwait")]
-use crate::ecmascript::execution::agent::ExceptionType;
-use crate::engine::context::GcScope;
-use crate::{
-    ecmascript::{
-        builders::ordinary_object_builder::OrdinaryObjectBuilder,
-        builtins::{ArgumentsList, Builtin},
-        execution::{Agent, JsResult, Realm},
-        types::{BUILTIN_STRING_MEMORY, String, Value},
-    },
-    heap::WellKnownSymbolIndexes,
+use crate::ecmascript::{
+    builders::ordinary_object_builder::OrdinaryObjectBuilder,
+    builtins::{ArgumentsList, Builtin},
+    execution::{Agent, JsResult, Realm},
+    types::{BUILTIN_STRING_MEMORY, String, Value},
 };
+use crate::engine::context::GcScope;
 
 pub(crate) struct AtomicsObject;
 
@@ -285,11 +280,7 @@ impl AtomicsObject {
 
         // 1. If N is neither undefined nor an integral Number, throw a TypeError exception.
         if !n.is_undefined() && !n.is_integer() {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Atomics.pause called with non-integral Number",
-                nogc,
-            ));
+            return agent.type_error("Atomics.pause called with non-integral Number", nogc);
         }
 
         // Consider this the "internal upper bound" on the maximum amount of
@@ -346,14 +337,7 @@ impl AtomicsObject {
             .with_builtin_function_property::<AtomicsObjectWaitAsync>()
             .with_builtin_function_property::<AtomicsObjectNotify>()
             .with_builtin_function_property::<AtomicsObjectXor>()
-            .with_property(|builder| {
-                builder
-                    .with_key(WellKnownSymbolIndexes::ToStringTag.into())
-                    .with_value_readonly(BUILTIN_STRING_MEMORY.Atomics.into())
-                    .with_enumerable(false)
-                    .with_configurable(true)
-                    .build()
-            });
+            .with_to_string_tag(BUILTIN_STRING_MEMORY.Atomics);
 
         #[cfg(feature = "proposal-atomics-microwait")]
         let builder = builder.with_builtin_function_property::<AtomicsObjectPause>();
@@ -21,7 +21,7 @@ use crate::{
             ordinary::ordinary_create_from_constructor,
             structured_data::array_buffer_objects::array_buffer_prototype::require_internal_slot_array_buffer,
         },
-        execution::{Agent, JsResult, ProtoIntrinsics, Realm, agent::ExceptionType},
+        execution::{Agent, JsResult, ProtoIntrinsics, Realm},
         types::{BUILTIN_STRING_MEMORY, Function, IntoObject, IntoValue, Object, String, Value},
     },
     heap::IntrinsicConstructorIndexes,
@@ -51,11 +51,10 @@ impl DataViewConstructor {
     ) -> JsResult<'gc, Value<'gc>> {
         // 1. If NewTarget is undefined, throw a TypeError exception.
         let Some(new_target) = new_target.bind(gc.nogc()) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
+            return agent.type_error(
                 "calling a builtin DataView constructor without new is forbidden",
                 gc.into_nogc(),
-            ));
+            );
         };
         let new_target = Function::try_from(new_target)
             .unwrap()
@@ -78,11 +77,7 @@ impl DataViewConstructor {
         // 4. If IsDetachedBuffer(buffer) is true, throw a TypeError exception.
         let buffer = scoped_buffer.get(agent).bind(gc.nogc());
         if is_detached_buffer(agent, buffer) {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "attempting to access detached ArrayBuffer",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("attempting to access detached ArrayBuffer", gc.into_nogc());
         }
 
         // 5. Let bufferByteLength be ArrayBufferByteLength(buffer, seq-cst).
@@ -90,11 +85,7 @@ impl DataViewConstructor {
 
         // 6. If offset > bufferByteLength, throw a RangeError exception.
         if offset > buffer_byte_length {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::RangeError,
-                "offset is outside the bounds of the buffer",
-                gc.into_nogc(),
-            ));
+            return agent.range_error("offset is outside the bounds of the buffer", gc.into_nogc());
         }
 
         // 7. Let bufferIsFixedLength be IsFixedLengthArrayBuffer(buffer).
@@ -120,11 +111,8 @@ impl DataViewConstructor {
                 .bind(gc.nogc()) as usize;
             // b. If offset + viewByteLength > bufferByteLength, throw a RangeError exception.
             if offset + view_byte_length > buffer_byte_length {
-                return Err(agent.throw_exception_with_static_message(
-                    ExceptionType::RangeError,
-                    "offset is outside the bounds of the buffer",
-                    gc.into_nogc(),
-                ));
+                return agent
+                    .range_error("offset is outside the bounds of the buffer", gc.into_nogc());
             }
             Some(view_byte_length)
         };
@@ -142,11 +130,7 @@ impl DataViewConstructor {
         let buffer = scoped_buffer.get(agent).bind(gc);
         // 11. If IsDetachedBuffer(buffer) is true, throw a TypeError exception.
         if is_detached_buffer(agent, buffer) {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "attempting to access detached ArrayBuffer",
-                gc,
-            ));
+            return agent.type_error("attempting to access detached ArrayBuffer", gc);
         }
 
         // 12. Set bufferByteLength to ArrayBufferByteLength(buffer, seq-cst).
@@ -154,22 +138,14 @@ impl DataViewConstructor {
 
         // 13. If offset > bufferByteLength, throw a RangeError exception.
         if offset > buffer_byte_length {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::RangeError,
-                "offset is outside the bounds of the buffer",
-                gc,
-            ));
+            return agent.range_error("offset is outside the bounds of the buffer", gc);
         }
 
         // 14. If byteLength is not undefined, then
         if let Some(view_byte_length) = view_byte_length {
             // a. If offset + viewByteLength > bufferByteLength, throw a RangeError exception.
             if offset + view_byte_length > buffer_byte_length {
-                return Err(agent.throw_exception_with_static_message(
-                    ExceptionType::RangeError,
-                    "offset is outside the bounds of the buffer",
-                    gc,
-                ));
+                return agent.range_error("offset is outside the bounds of the buffer", gc);
             }
         }
 
@@ -20,10 +20,9 @@ use crate::{
                 },
             },
         },
-        execution::{Agent, JsResult, Realm, agent::ExceptionType},
+        execution::{Agent, JsResult, Realm},
         types::{BUILTIN_STRING_MEMORY, IntoValue, Number, PropertyKey, String, Value},
     },
-    heap::WellKnownSymbolIndexes,
 };
 
 pub(crate) struct DataViewPrototype;
@@ -231,11 +230,7 @@ impl DataViewPrototype {
         let view_record = make_data_view_with_buffer_witness_record(agent, o, Ordering::SeqCst, gc);
         // 5. If IsViewOutOfBounds(viewRecord) is true, throw a TypeError exception.
         if is_view_out_of_bounds(agent, &view_record, gc) {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "DataView is out of bounds",
-                gc,
-            ));
+            return agent.type_error("DataView is out of bounds", gc);
         }
         // 6. Let size be GetViewByteLength(viewRecord).
         let size = get_view_byte_length(agent, &view_record, gc) as i64;
@@ -262,11 +257,7 @@ impl DataViewPrototype {
         let view_record = make_data_view_with_buffer_witness_record(agent, o, Ordering::SeqCst, gc);
         // 5. If IsViewOutOfBounds(viewRecord) is true, throw a TypeError exception.
         if is_view_out_of_bounds(agent, &view_record, gc) {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "DataView is out of bounds",
-                gc,
-            ));
+            return agent.type_error("DataView is out of bounds", gc);
         }
         // 6. Let offset be O.[[ByteOffset]].
         // 7. Return 𝔽(offset).
@@ -651,14 +642,7 @@ impl DataViewPrototype {
             .with_builtin_function_property::<DataViewPrototypeSetUint8>()
             .with_builtin_function_property::<DataViewPrototypeSetUint16>()
             .with_builtin_function_property::<DataViewPrototypeSetUint32>()
-            .with_property(|builder| {
-                builder
-                    .with_key(WellKnownSymbolIndexes::ToStringTag.into())
-                    .with_value_readonly(BUILTIN_STRING_MEMORY.DataView.into_value())
-                    .with_enumerable(false)
-                    .with_configurable(true)
-                    .build()
-            });
+            .with_to_string_tag(BUILTIN_STRING_MEMORY.DataView);
 
         #[cfg(feature = "proposal-float16array")]
         let builder = builder
@@ -678,10 +662,6 @@ pub(crate) fn require_internal_slot_data_view<'a>(
     match o {
         // 1. Perform ? RequireInternalSlot(O, [[DataView]]).
         Value::DataView(array_buffer) => Ok(array_buffer.unbind().bind(gc)),
-        _ => Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Expected this to be DataView",
-            gc,
-        )),
+        _ => agent.type_error("Expected this to be DataView", gc),
     }
 }
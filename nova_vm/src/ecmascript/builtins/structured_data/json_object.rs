@@ -5,6 +5,7 @@
 use std::fmt::Write;
 
 use sonic_rs::{JsonContainerTrait, JsonValueTrait};
+use wtf8::{Wtf8, Wtf8Buf};
 
 use crate::{
     SmallInteger,
@@ -29,8 +30,9 @@ use crate::{
         },
         execution::{Agent, JsResult, ProtoIntrinsics, Realm, agent::ExceptionType},
         types::{
-            BUILTIN_STRING_MEMORY, Function, InternalMethods, IntoObject, IntoPrimitive, IntoValue,
-            Number, Object, PropertyDescriptor, PropertyKey, ScopedPropertyKey, String, Value,
+            BUILTIN_STRING_MEMORY, Function, HeapStringBuilder, InternalMethods, IntoObject,
+            IntoPrimitive, IntoValue, Number, Object, PropertyDescriptor, PropertyKey,
+            ScopedPropertyKey, String, Value,
         },
     },
     engine::{
@@ -39,7 +41,6 @@ use crate::{
         rootable::Scopable,
         unwrap_try,
     },
-    heap::WellKnownSymbolIndexes,
 };
 
 pub(crate) struct JSONObject;
@@ -109,7 +110,10 @@ impl JSONObject {
             .bind(gc.nogc());
 
         // 2. Parse StringToCodePoints(jsonString) as a JSON text as specified in ECMA-404. Throw a SyntaxError exception if it is not a valid JSON text as defined in that specification.
-        let json_value = match sonic_rs::from_str::<sonic_rs::Value>(json_string.as_str(agent)) {
+        // NOTE: Lone surrogates are only valid inside of JSON strings, where a
+        // code unit and its escape sequence are equivalent.
+        let json_text = json_string.escape_lone_surrogates(agent);
+        let json_value = match sonic_rs::from_str::<sonic_rs::Value>(&json_text) {
             Ok(value) => value,
             Err(error) => {
                 return Err(agent.throw_exception(
@@ -366,7 +370,7 @@ impl JSONObject {
             None
         };
 
-        let gap: Box<str> = space.map_or("".into(), |space| {
+        let gap = space.map_or(Wtf8Buf::new(), |space| {
             // 7. If space is a Number, then
             if let Ok(space) = Number::try_from(space) {
                 // a. Let spaceMV be ! ToIntegerOrInfinity(space).
@@ -374,20 +378,20 @@ impl JSONObject {
                 // b. Set spaceMV to min(10, spaceMV).
                 // c. If spaceMV < 1, let gap be the empty String; otherwise let gap be the String value containing spaceMV occurrences of the code unit 0x0020 (SPACE).
                 let space_mv = space_mv.into_i64().clamp(0, 10) as usize;
-                " ".repeat(space_mv as usize).into()
+                Wtf8Buf::from_string(" ".repeat(space_mv))
             } else if let Ok(space) = String::try_from(space) {
                 // 8. Else if space is a String, then
-                let space = space.as_str(agent);
                 // a. If the length of space ≤ 10, let gap be space; otherwise let gap be the substring of space from 0 to 10.
-                if space.len() <= 10 {
-                    space.into()
-                } else {
-                    space[..10].into()
-                }
+                let code_units = space
+                    .as_wtf8(agent)
+                    .to_ill_formed_utf16()
+                    .take(10)
+                    .collect::<Vec<_>>();
+                Wtf8Buf::from_ill_formed_utf16(&code_units)
             } else {
                 // 9. Else,
                 // a. Let gap be the empty String.
-                "".into()
+                Wtf8Buf::new()
             }
         });
 
@@ -414,7 +418,7 @@ impl JSONObject {
             replacer_function,
             stack,
             // 2. Let indent be the empty String.
-            indent: Default::default(),
+            indent: Wtf8Buf::new(),
             gap,
             property_list,
         };
@@ -435,7 +439,7 @@ impl JSONObject {
             serialize_json_property_value(agent, &mut state, value_p.unbind(), gc.reborrow())
                 .unbind()?
                 .bind(gc.nogc());
-            Ok(String::from_string(agent, state.result, gc.into_nogc()).into_value())
+            Ok(state.result.finish(agent, gc.into_nogc()).into_value())
         } else {
             Ok(Value::Undefined)
         }
@@ -451,14 +455,7 @@ impl JSONObject {
             .with_prototype(object_prototype)
             .with_builtin_function_property::<JSONObjectParse>()
             .with_builtin_function_property::<JSONObjectStringify>()
-            .with_property(|builder| {
-                builder
-                    .with_key(WellKnownSymbolIndexes::ToStringTag.into())
-                    .with_value_readonly(BUILTIN_STRING_MEMORY.JSON.into())
-                    .with_enumerable(false)
-                    .with_configurable(true)
-                    .build()
-            })
+            .with_to_string_tag(BUILTIN_STRING_MEMORY.JSON)
             .build();
     }
 }
@@ -615,12 +612,21 @@ fn internalize_json_property<'a>(
     )
 }
 
+/// Returns the string-concatenation of `prefix`, `indent`, and `suffix`.
+fn surround(prefix: &str, indent: &Wtf8, suffix: &str) -> Wtf8Buf {
+    let mut result = Wtf8Buf::with_capacity(prefix.len() + indent.len() + suffix.len());
+    result.push_str(prefix);
+    result.push_wtf8(indent);
+    result.push_str(suffix);
+    result
+}
+
 struct JSONSerializationRecord<'a> {
-    result: std::string::String,
+    result: HeapStringBuilder,
     replacer_function: Option<Scoped<'a, Function<'static>>>,
     stack: ScopedCollection<'a, Vec<Value<'static>>>,
-    indent: Box<str>,
-    gap: Box<str>,
+    indent: Wtf8Buf,
+    gap: Wtf8Buf,
     property_list: Option<ScopedCollection<'a, Vec<PropertyKey<'static>>>>,
 }
 
@@ -761,11 +767,7 @@ fn get_serializable_json_property_value<'a>(
 
     if value.is_bigint() {
         // 10. If value is a BigInt, throw a TypeError exception.
-        Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Cannot serialize BigInt to JSON",
-            gc.into_nogc(),
-        ))
+        agent.type_error("Do not know how to serialize a BigInt", gc.into_nogc())
     } else if value.is_undefined() || value.is_symbol() {
         Ok(None)
     } else if is_callable(value, gc.nogc()).is_some() {
@@ -832,9 +834,10 @@ fn serialize_json_property_value<'a, 'b>(
                 // c. Return ? SerializeJSONObject(state, value).
                 serialize_json_object(agent, state, value.unbind(), gc)?;
             }
+            return Ok(());
         }
     }
-    Ok(())
+    agent.check_string_length(state.result.len(), gc.into_nogc())
 }
 
 /// ### [25.5.2.3 QuoteJSONString ( value )](https://tc39.es/ecma262/#sec-quotejsonstring)
@@ -843,14 +846,13 @@ fn serialize_json_property_value<'a, 'b>(
 /// returns a String. It wraps value in 0x0022 (QUOTATION MARK) code units and
 /// escapes certain other code units within it. This operation interprets value
 /// as a sequence of UTF-16 encoded code points, as described in 6.1.4.
-fn quote_json_string(agent: &Agent, product: &mut std::string::String, value: String) {
-    product.reserve(value.len(agent) + 2);
+fn quote_json_string(agent: &Agent, product: &mut HeapStringBuilder, value: String) {
     // 1. Let product be the String value consisting solely of the code unit
     //    0x0022 (QUOTATION MARK).
-    product.push('"');
+    product.push_char('"');
     // 2. For each code point C of StringToCodePoints(value), do
-    for c in value.as_str(agent).chars() {
-        match c {
+    for c in value.as_wtf8(agent).code_points() {
+        match c.to_char() {
             // a. If C is listed in the “Code Point” column of Table 81, then
             // i. Set product to the string-concatenation of product and the
             //    escape sequence for C as specified in the “Escape Sequence”
@@ -861,44 +863,48 @@ fn quote_json_string(agent: &Agent, product: &mut std::string::String, value: St
             // | Code Point | Unicode Character Name | Escape Sequence |
             // +------------+------------------------+-----------------+
             // | U+0008     | Backspace              | \b              |
-            '\u{0008}' => product.push_str("\\b"),
+            Some('\u{0008}') => product.push_str("\\b"),
             // | U+0009     | CHARACTER TABULATION   | \t              |
-            '\u{0009}' => product.push_str("\\t'"),
+            Some('\u{0009}') => product.push_str("\\t"),
             // | U+000A     | LINE FEED (LF)         | \n              |
-            '\u{000A}' => product.push_str("\\n'"),
+            Some('\u{000A}') => product.push_str("\\n"),
             // | U+000C     | FORM FEED (FF)         | \f              |
-            '\u{000C}' => product.push_str("\\f'"),
+            Some('\u{000C}') => product.push_str("\\f"),
             // | U+000D     | CARRIAGE RETURN (CR)   | \r              |
-            '\u{000D}' => product.push_str("\\r'"),
+            Some('\u{000D}') => product.push_str("\\r"),
             // | U+0022     | QUOTATION MARK         | \"              |
-            '\u{0022}' => product.push_str("\\\""),
+            Some('\u{0022}') => product.push_str("\\\""),
             // | U+005C     | REVERSE SOLIDUS        | \\              |
-            '\u{005C}' => product.push_str("\\\\"),
+            Some('\u{005C}') => product.push_str("\\\\"),
             // +------------+------------------------+-----------------+
             // b. Else if C has a numeric value less than 0x0020 (SPACE) or C
             //    has the same numeric value as a leading surrogate or trailing
             //    surrogate, then
-            _ if c < '\u{0020}' => {
-                // i. Let unit be the code unit whose numeric value is the
-                //    numeric value of C.
-                let unit = c as u32;
-                // ii. Set product to the string-concatenation of product and
-                //     UnicodeEscape(unit).
-                write!(product, "\\u{unit:04x}").unwrap();
-            }
+            // Note: Lone surrogates are the code points that are not chars.
+            None => write_unicode_escape(product, c.to_u32()),
+            Some(c) if c < '\u{0020}' => write_unicode_escape(product, c as u32),
             // c. Else,
             // i. Set product to the string-concatenation of product and
             //    UTF16EncodeCodePoint(C).
-            _ => product.push(c),
+            Some(c) => product.push_char(c),
         }
     }
     // 3. Set product to the string-concatenation of product and the code unit
     //    0x0022 (QUOTATION MARK).
-    product.push('"');
+    product.push_char('"');
     // 4. Return product.
 }
 
-fn quote_property_key(agent: &Agent, product: &mut std::string::String, key: PropertyKey) {
+/// Appends UnicodeEscape(unit) to `product`.
+fn write_unicode_escape(product: &mut HeapStringBuilder, unit: u32) {
+    // i. Let unit be the code unit whose numeric value is the numeric value
+    //    of C.
+    // ii. Set product to the string-concatenation of product and
+    //     UnicodeEscape(unit).
+    write!(product, "\\u{unit:04x}").unwrap();
+}
+
+fn quote_property_key(agent: &Agent, product: &mut HeapStringBuilder, key: PropertyKey) {
     if let PropertyKey::Integer(key) = key {
         let key = key.into_i64();
         write!(product, "\"{key}\"").unwrap();
@@ -932,11 +938,7 @@ fn serialize_json_object<'a, 'b>(
         // SAFETY: We only push objects into the stack.
         .any(|x| unsafe { Object::try_from(x.get(gc.nogc())).unwrap_unchecked() } == value)
     {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Cyclical structure in JSON",
-            gc.into_nogc(),
-        ));
+        return agent.type_error("Cyclical structure in JSON", gc.into_nogc());
     }
 
     // 2. Append value to state.[[Stack]].
@@ -962,14 +964,14 @@ fn serialize_json_object<'a, 'b>(
         return Ok(());
     }
 
-    let open_string: Box<str>;
-    let separator_string: Box<str>;
-    let close_string: Box<str>;
-    let step_back: Box<str>;
+    let open_string: Wtf8Buf;
+    let separator_string: Wtf8Buf;
+    let close_string: Wtf8Buf;
+    let step_back: Wtf8Buf;
 
     // a. If state.[[Gap]] is the empty String, then
-    let (open, separator, key_value_separator, close) = if state.gap.is_empty() {
-        step_back = Default::default();
+    let (open, separator, key_value_separator, close) = if state.gap.len() == 0 {
+        step_back = Wtf8Buf::new();
         // i. Let properties be the String value formed by concatenating
         //    all the element Strings of partial with each adjacent pair of
         //    Strings separated with the code unit 0x002C (COMMA). A comma
@@ -977,21 +979,25 @@ fn serialize_json_object<'a, 'b>(
         //    last String.
         // ii. Let final be the string-concatenation of "{", properties,
         //     and "}".
-        ("{", ",", ":", "}")
+        (
+            Wtf8::from_str("{"),
+            Wtf8::from_str(","),
+            ":",
+            Wtf8::from_str("}"),
+        )
     } else {
         // 3. Let stepBack be state.[[Indent]].
         // 4. Set state.[[Indent]] to the string-concatenation of state.[[Indent]] and state.[[Gap]].
-        let mut new_ident =
-            std::string::String::with_capacity(state.indent.len() + state.gap.len());
-        new_ident.push_str(&state.indent);
-        new_ident.push_str(&state.gap);
-        step_back = core::mem::replace(&mut state.indent, new_ident.into_boxed_str());
+        let mut new_ident = Wtf8Buf::with_capacity(state.indent.len() + state.gap.len());
+        new_ident.push_wtf8(&state.indent);
+        new_ident.push_wtf8(&state.gap);
+        step_back = core::mem::replace(&mut state.indent, new_ident);
 
         // b. Else,
         // i. Let separator be the string-concatenation of the code unit
         //    0x002C (COMMA), the code unit 0x000A (LINE FEED), and
         //    state.[[Indent]].
-        separator_string = format!(",\n{}", &state.indent).into_boxed_str();
+        separator_string = surround(",\n", &state.indent, "");
         // ii. Let properties be the String value formed by concatenating
         //     all the element Strings of partial with each adjacent pair
         //     of Strings separated with separator. The separator String is
@@ -1000,14 +1006,9 @@ fn serialize_json_object<'a, 'b>(
         // iii. Let final be the string-concatenation of "{", the code unit
         //      0x000A (LINE FEED), state.[[Indent]], properties, the code
         //      unit 0x000A (LINE FEED), stepBack, and "}".
-        open_string = format!("{{\n{}", &state.indent).into_boxed_str();
-        close_string = format!("\n{}}}", &step_back).into_boxed_str();
-        (
-            open_string.as_ref(),
-            separator_string.as_ref(),
-            ": ",
-            close_string.as_ref(),
-        )
+        open_string = surround("{\n", &state.indent, "");
+        close_string = surround("\n", &step_back, "}");
+        (&*open_string, &*separator_string, ": ", &*close_string)
     };
 
     let mut first_inserted = false;
@@ -1043,9 +1044,9 @@ fn serialize_json_object<'a, 'b>(
 
         if !first_inserted {
             first_inserted = true;
-            state.result.push_str(open);
+            state.result.push_wtf8(open);
         } else {
-            state.result.push_str(separator);
+            state.result.push_wtf8(separator);
         }
 
         // i. Let member be QuoteJSONString(P).
@@ -1065,7 +1066,7 @@ fn serialize_json_object<'a, 'b>(
     // 11. Remove the last element of state.[[Stack]].
     state.stack.pop(agent, gc.nogc());
 
-    if state.gap.is_empty() {
+    if state.gap.len() == 0 {
         // 12. Set state.[[Indent]] to stepBack.
         state.indent = step_back;
         // 13. Return final.
@@ -1076,7 +1077,7 @@ fn serialize_json_object<'a, 'b>(
         // a. Let final be "{}".
         state.result.push_str("{}");
     } else {
-        state.result.push_str(close);
+        state.result.push_wtf8(close);
     }
     Ok(())
 }
@@ -1101,11 +1102,7 @@ fn serialize_json_array<'a, 'b>(
         // SAFETY: We only push objects into the stack.
         .any(|x| unsafe { Object::try_from(x.get(gc.nogc())).unwrap_unchecked() } == value)
     {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Cyclical structure in JSON",
-            gc.into_nogc(),
-        ));
+        return agent.type_error("Cyclical structure in JSON", gc.into_nogc());
     }
     // 2. Append value to state.[[Stack]].
     state.stack.push(agent, value.into_value());
@@ -1126,34 +1123,37 @@ fn serialize_json_array<'a, 'b>(
         return Ok(());
     }
 
-    let open_string: Box<str>;
-    let separator_string: Box<str>;
-    let close_string: Box<str>;
-    let step_back: Box<str>;
+    let open_string: Wtf8Buf;
+    let separator_string: Wtf8Buf;
+    let close_string: Wtf8Buf;
+    let step_back: Wtf8Buf;
 
     // a. If state.[[Gap]] is the empty String, then
-    let (open, separator, close) = if state.gap.is_empty() {
-        step_back = Default::default();
+    let (open, separator, close) = if state.gap.len() == 0 {
+        step_back = Wtf8Buf::new();
         // i. Let properties be the String value formed by concatenating all
         //    the element Strings of partial with each adjacent pair of Strings
         //    separated with the code unit 0x002C (COMMA). A comma is not
         //    inserted either before the first String or after the last String.
         // ii. Let final be the string-concatenation of "[", properties, and
         //     "]".
-        ("[", ",", "]")
+        (
+            Wtf8::from_str("["),
+            Wtf8::from_str(","),
+            Wtf8::from_str("]"),
+        )
     } else {
         // 3. Let stepBack be state.[[Indent]].
         // 4. Set state.[[Indent]] to the string-concatenation of state.[[Indent]] and state.[[Gap]].
-        let mut new_ident =
-            std::string::String::with_capacity(state.indent.len() + state.gap.len());
-        new_ident.push_str(&state.indent);
-        new_ident.push_str(&state.gap);
-        step_back = core::mem::replace(&mut state.indent, new_ident.into_boxed_str());
+        let mut new_ident = Wtf8Buf::with_capacity(state.indent.len() + state.gap.len());
+        new_ident.push_wtf8(&state.indent);
+        new_ident.push_wtf8(&state.gap);
+        step_back = core::mem::replace(&mut state.indent, new_ident);
 
         // b. Else,
         // i. Let separator be the string-concatenation of the code unit 0x002C
         //    (COMMA), the code unit 0x000A (LINE FEED), and state.[[Indent]].
-        separator_string = format!(",\n{}", &state.indent).into_boxed_str();
+        separator_string = surround(",\n", &state.indent, "");
         // ii. Let properties be the String value formed by concatenating all
         //     the element Strings of partial with each adjacent pair of
         //     Strings separated with separator. The separator String is not
@@ -1162,25 +1162,28 @@ fn serialize_json_array<'a, 'b>(
         // iii. Let final be the string-concatenation of "[", the code unit
         //      0x000A (LINE FEED), state.[[Indent]], properties, the code unit
         //      0x000A (LINE FEED), stepBack, and "]".
-        open_string = format!("[\n{}", &state.indent).into_boxed_str();
-        close_string = format!("\n{}]", &step_back).into_boxed_str();
-        (
-            open_string.as_ref(),
-            separator_string.as_ref(),
-            close_string.as_ref(),
-        )
+        open_string = surround("[\n", &state.indent, "");
+        close_string = surround("\n", &step_back, "]");
+        (&*open_string, &*separator_string, &*close_string)
     };
 
+    // Note: Each element takes at least one byte, so the result length can
+    // be checked before any of the elements are serialized.
+    let min_length = (len as usize)
+        .saturating_mul(separator.len() + 1)
+        .saturating_add(state.result.len() + open.len() + close.len())
+        - separator.len();
+    agent.check_string_length(min_length, gc.nogc()).unbind()?;
     // 5. Let partial be a new empty List.
     state
         .result
         .reserve(open.len() + close.len() + (len as usize) * (separator.len() + 1));
-    state.result.push_str(open);
+    state.result.push_wtf8(open);
     // 7. Let index be 0.
     // 8. Repeat, while index < len,
     for index in 0..len {
         if index > 0 {
-            state.result.push_str(separator);
+            state.result.push_wtf8(separator);
         }
         let key = PropertyKey::try_from(index).unwrap().scope_static();
         // a. Let strP be ? SerializeJSONProperty(state, ! ToString(𝔽(index)), value).
@@ -1210,7 +1213,7 @@ fn serialize_json_array<'a, 'b>(
         }
         // d. Set index to index + 1.
     }
-    state.result.push_str(close);
+    state.result.push_wtf8(close);
     // 11. Remove the last element of state.[[Stack]].
     state.stack.pop(agent, gc.nogc());
     // 12. Set state.[[Indent]] to stepBack.
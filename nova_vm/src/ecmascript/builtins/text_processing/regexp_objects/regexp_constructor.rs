@@ -2,23 +2,29 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use crate::ecmascript::abstract_operations::operations_on_objects::get;
+use crate::ecmascript::abstract_operations::testing_and_comparison::is_reg_exp;
 use crate::ecmascript::builders::builtin_function_builder::BuiltinFunctionBuilder;
 use crate::ecmascript::builtins::ArgumentsList;
 use crate::ecmascript::builtins::Behaviour;
 use crate::ecmascript::builtins::Builtin;
 use crate::ecmascript::builtins::BuiltinGetter;
 use crate::ecmascript::builtins::BuiltinIntrinsicConstructor;
+use crate::ecmascript::builtins::regexp::{reg_exp_alloc, reg_exp_initialize};
 use crate::ecmascript::execution::Agent;
 use crate::ecmascript::execution::JsResult;
 use crate::ecmascript::execution::Realm;
 
 use crate::ecmascript::types::BUILTIN_STRING_MEMORY;
+use crate::ecmascript::types::Function;
 use crate::ecmascript::types::IntoObject;
+use crate::ecmascript::types::IntoValue;
 use crate::ecmascript::types::Object;
 use crate::ecmascript::types::PropertyKey;
 use crate::ecmascript::types::String;
 use crate::ecmascript::types::Value;
 use crate::engine::context::{Bindable, GcScope};
+use crate::engine::rootable::Scopable;
 use crate::heap::IntrinsicConstructorIndexes;
 use crate::heap::WellKnownSymbolIndexes;
 
@@ -44,14 +50,100 @@ impl Builtin for RegExpGetSpecies {
 impl BuiltinGetter for RegExpGetSpecies {}
 
 impl RegExpConstructor {
+    /// ### [22.2.4.1 RegExp ( pattern, flags )](https://tc39.es/ecma262/#sec-regexp-pattern-flags)
     fn constructor<'gc>(
         agent: &mut Agent,
         _this_value: Value,
-        _arguments: ArgumentsList,
-        _new_target: Option<Object>,
-        gc: GcScope<'gc, '_>,
+        arguments: ArgumentsList,
+        new_target: Option<Object>,
+        mut gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
-        Err(agent.todo("RegExp", gc.into_nogc()))
+        let pattern = arguments.get(0).scope(agent, gc.nogc());
+        let flags = arguments.get(1).scope(agent, gc.nogc());
+        let new_target = new_target.map(|n| n.scope(agent, gc.nogc()));
+        // 1. Let patternIsRegExp be ? IsRegExp(pattern).
+        let pattern_is_reg_exp = is_reg_exp(agent, pattern.get(agent), gc.reborrow()).unbind()?;
+        // 2. If NewTarget is undefined, then
+        let new_target = if let Some(new_target) = new_target {
+            Function::try_from(new_target.get(agent)).unwrap()
+        } else {
+            // a. Let newTarget be the active function object.
+            let new_target = agent
+                .active_function_object(gc.nogc())
+                .scope(agent, gc.nogc());
+            // b. If patternIsRegExp is true and flags is undefined, then
+            if pattern_is_reg_exp && flags.get(agent).is_undefined() {
+                // i. Let patternConstructor be ? Get(pattern, "constructor").
+                let pattern_constructor = get(
+                    agent,
+                    Object::try_from(pattern.get(agent)).unwrap(),
+                    BUILTIN_STRING_MEMORY.constructor.into(),
+                    gc.reborrow(),
+                )
+                .unbind()?
+                .bind(gc.nogc());
+                // ii. If SameValue(newTarget, patternConstructor) is true, return pattern.
+                if pattern_constructor == new_target.get(agent).into_value() {
+                    return Ok(pattern.get(agent));
+                }
+            }
+            new_target.get(agent)
+        }
+        .scope(agent, gc.nogc());
+        // 3. Else, let newTarget be NewTarget.
+        // 4. If pattern is an Object and pattern has a [[RegExpMatcher]] internal slot, then
+        let (p, f) = if let Value::RegExp(regexp) = pattern.get(agent) {
+            // a. Let P be pattern.[[OriginalSource]].
+            let p = agent[regexp].original_source.into_value();
+            // b. If flags is undefined, let F be pattern.[[OriginalFlags]].
+            // c. Else, let F be flags.
+            let f = if flags.get(agent).is_undefined() {
+                let flags = agent[regexp].original_flags.to_string();
+                Value::from_string(agent, flags, gc.nogc())
+            } else {
+                flags.get(agent)
+            };
+            (p.scope(agent, gc.nogc()), f.scope(agent, gc.nogc()))
+        } else if pattern_is_reg_exp {
+            // 5. Else if patternIsRegExp is true, then
+            // a. Let P be ? Get(pattern, "source").
+            let p = get(
+                agent,
+                Object::try_from(pattern.get(agent)).unwrap(),
+                BUILTIN_STRING_MEMORY.source.into(),
+                gc.reborrow(),
+            )
+            .unbind()?
+            .scope(agent, gc.nogc());
+            // b. If flags is undefined, then
+            let f = if flags.get(agent).is_undefined() {
+                // i. Let F be ? Get(pattern, "flags").
+                get(
+                    agent,
+                    Object::try_from(pattern.get(agent)).unwrap(),
+                    BUILTIN_STRING_MEMORY.flags.into(),
+                    gc.reborrow(),
+                )
+                .unbind()?
+                .scope(agent, gc.nogc())
+            } else {
+                // c. Else, let F be flags.
+                flags
+            };
+            (p, f)
+        } else {
+            // 6. Else,
+            // a. Let P be pattern.
+            // b. Let F be flags.
+            (pattern, flags)
+        };
+        // 7. Let O be ? RegExpAlloc(newTarget).
+        let o = reg_exp_alloc(agent, new_target.get(agent), gc.reborrow())
+            .unbind()?
+            .bind(gc.nogc());
+        // 8. Return ? RegExpInitialize(O, P, F).
+        reg_exp_initialize(agent, o.unbind(), p.get(agent), f.get(agent), gc)
+            .map(|o| o.into_value())
     }
 
     fn get_species<'gc>(
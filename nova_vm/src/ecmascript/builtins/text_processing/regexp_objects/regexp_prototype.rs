@@ -2,24 +2,37 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use oxc_ast::ast::RegExpFlags;
+
 use crate::{
     ecmascript::{
         abstract_operations::{
-            operations_on_objects::get,
-            type_conversion::{to_boolean, to_string},
+            operations_on_objects::{
+                construct, create_data_property_or_throw, get, length_of_array_like, set,
+                species_constructor,
+            },
+            type_conversion::{to_boolean, to_length, to_string, to_uint32},
         },
         builders::ordinary_object_builder::OrdinaryObjectBuilder,
-        builtins::{ArgumentsList, Behaviour, Builtin, BuiltinGetter, BuiltinIntrinsic},
+        builtins::{
+            ArgumentsList, Behaviour, Builtin, BuiltinGetter, BuiltinIntrinsic, array_create,
+            regexp::{advance_string_index, reg_exp_builtin_exec, reg_exp_exec},
+        },
         execution::{Agent, JsResult, Realm, agent::ExceptionType},
-        types::{BUILTIN_STRING_MEMORY, IntoValue, Object, PropertyKey, String, Value},
+        types::{
+            BUILTIN_STRING_MEMORY, HeapStringBuilder, IntoFunction, IntoObject, IntoValue, Object,
+            PropertyKey, String, Value,
+        },
     },
     engine::{
-        context::{Bindable, GcScope},
+        context::{Bindable, GcScope, NoGcScope},
         rootable::Scopable,
     },
     heap::{IntrinsicFunctionIndexes, WellKnownSymbolIndexes},
 };
 
+use super::regexp_string_iterator_objects::RegExpStringIterator;
+
 pub(crate) struct RegExpPrototype;
 
 struct RegExpPrototypeExec;
@@ -167,22 +180,48 @@ impl Builtin for RegExpPrototypeGetUnicodeSets {
 impl BuiltinGetter for RegExpPrototypeGetUnicodeSets {}
 
 impl RegExpPrototype {
+    /// ### [22.2.6.2 RegExp.prototype.exec ( string )](https://tc39.es/ecma262/#sec-regexp.prototype.exec)
+    ///
+    /// This method searches string for an occurrence of the regular
+    /// expression pattern and returns an Array containing the results of the
+    /// match, or null if string did not match.
     fn exec<'gc>(
         agent: &mut Agent,
-        _this_value: Value,
-        _: ArgumentsList,
-        gc: GcScope<'gc, '_>,
+        this_value: Value,
+        arguments: ArgumentsList,
+        mut gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
-        Err(agent.todo("RegExp.prototype.exec", gc.into_nogc()))
+        let this_value = this_value.bind(gc.nogc());
+        let string = arguments.get(0).bind(gc.nogc());
+        // 1. Let R be the this value.
+        // 2. Perform ? RequireInternalSlot(R, [[RegExpMatcher]]).
+        let Value::RegExp(r) = this_value else {
+            return agent.type_error("value is not a RegExp", gc.into_nogc());
+        };
+        let r = r.scope(agent, gc.nogc());
+        // 3. Let S be ? ToString(string).
+        let s = to_string(agent, string.unbind(), gc.reborrow())
+            .unbind()?
+            .bind(gc.nogc());
+        // 4. Return ? RegExpBuiltinExec(R, S).
+        let result = reg_exp_builtin_exec(agent, r.get(agent), s.unbind(), gc)?;
+        Ok(result.map_or(Value::Null, |a| a.into_value()))
     }
 
+    /// ### [22.2.6.3 get RegExp.prototype.dotAll](https://tc39.es/ecma262/#sec-get-regexp.prototype.dotall)
+    ///
+    /// RegExp.prototype.dotAll is an accessor property whose set accessor
+    /// function is undefined.
     fn get_dot_all<'gc>(
         agent: &mut Agent,
-        _this_value: Value,
+        this_value: Value,
         _: ArgumentsList,
         gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
-        Err(agent.todo("RegExp.prototype.dotAll", gc.into_nogc()))
+        // 1. Let R be the this value.
+        // 2. Let cu be the code unit 0x0073 (LATIN SMALL LETTER S).
+        // 3. Return ? RegExpHasFlag(R, cu).
+        reg_exp_has_flag(agent, this_value, RegExpFlags::S, gc.into_nogc())
     }
 
     /// ### [22.2.6.4 get RegExp.prototype.flags](https://tc39.es/ecma262/#sec-get-regexp.prototype.flags)
@@ -198,11 +237,7 @@ impl RegExpPrototype {
 
         // 2. If R is not an Object, throw a TypeError exception.
         let Ok(r) = Object::try_from(r) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "value is not object",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("value is not object", gc.into_nogc());
         };
 
         // 3. Let codeUnits be a new empty List.
@@ -275,6 +310,7 @@ impl RegExpPrototype {
         // 11. If multiline is true, append the code unit 0x006D (LATIN SMALL LETTER M) to codeUnits.
         if multiline {
             code_units[i] = b'm';
+            i += 1;
         };
 
         // 12. Let dotAll be ToBoolean(? Get(R, "dotAll")).
@@ -341,7 +377,7 @@ impl RegExpPrototype {
 
         // 19. If sticky is true, append the code unit 0x0079 (LATIN SMALL LETTER Y) to codeUnits.
         if sticky {
-            code_units[i] = b'v';
+            code_units[i] = b'y';
             i += 1;
         };
 
@@ -350,31 +386,52 @@ impl RegExpPrototype {
         Ok(Value::from_string(agent, res.to_string(), gc.nogc()).unbind())
     }
 
+    /// ### [22.2.6.6 get RegExp.prototype.global](https://tc39.es/ecma262/#sec-get-regexp.prototype.global)
+    ///
+    /// RegExp.prototype.global is an accessor property whose set accessor
+    /// function is undefined.
     fn get_global<'gc>(
         agent: &mut Agent,
-        _this_value: Value,
+        this_value: Value,
         _: ArgumentsList,
         gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
-        Err(agent.todo("RegExp.prototype.global", gc.into_nogc()))
+        // 1. Let R be the this value.
+        // 2. Let cu be the code unit 0x0067 (LATIN SMALL LETTER G).
+        // 3. Return ? RegExpHasFlag(R, cu).
+        reg_exp_has_flag(agent, this_value, RegExpFlags::G, gc.into_nogc())
     }
 
+    /// ### [22.2.6.7 get RegExp.prototype.hasIndices](https://tc39.es/ecma262/#sec-get-regexp.prototype.hasindices)
+    ///
+    /// RegExp.prototype.hasIndices is an accessor property whose set accessor
+    /// function is undefined.
     fn get_has_indices<'gc>(
         agent: &mut Agent,
-        _this_value: Value,
+        this_value: Value,
         _: ArgumentsList,
         gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
-        Err(agent.todo("RegExp.prototype.hasIndices", gc.into_nogc()))
+        // 1. Let R be the this value.
+        // 2. Let cu be the code unit 0x0064 (LATIN SMALL LETTER D).
+        // 3. Return ? RegExpHasFlag(R, cu).
+        reg_exp_has_flag(agent, this_value, RegExpFlags::D, gc.into_nogc())
     }
 
+    /// ### [22.2.6.8 get RegExp.prototype.ignoreCase](https://tc39.es/ecma262/#sec-get-regexp.prototype.ignorecase)
+    ///
+    /// RegExp.prototype.ignoreCase is an accessor property whose set accessor
+    /// function is undefined.
     fn get_ignore_case<'gc>(
         agent: &mut Agent,
-        _this_value: Value,
+        this_value: Value,
         _: ArgumentsList,
         gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
-        Err(agent.todo("RegExp.prototype.ignoreCase", gc.into_nogc()))
+        // 1. Let R be the this value.
+        // 2. Let cu be the code unit 0x0069 (LATIN SMALL LETTER I).
+        // 3. Return ? RegExpHasFlag(R, cu).
+        reg_exp_has_flag(agent, this_value, RegExpFlags::I, gc.into_nogc())
     }
 
     fn r#match<'gc>(
@@ -386,22 +443,115 @@ impl RegExpPrototype {
         Err(agent.todo("RegExp.prototype.match", gc.into_nogc()))
     }
 
+    /// ### [22.2.6.9 RegExp.prototype \[ %Symbol.matchAll% \] ( string )](https://tc39.es/ecma262/#sec-regexp-prototype-%symbol.matchall%)
     fn match_all<'gc>(
         agent: &mut Agent,
-        _this_value: Value,
-        _: ArgumentsList,
-        gc: GcScope<'gc, '_>,
+        this_value: Value,
+        arguments: ArgumentsList,
+        mut gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
-        Err(agent.todo("RegExp.prototype.matchAll", gc.into_nogc()))
+        let this_value = this_value.bind(gc.nogc());
+        let string = arguments.get(0).bind(gc.nogc());
+        // 1. Let R be the this value.
+        // 2. If R is not an Object, throw a TypeError exception.
+        let Ok(r) = Object::try_from(this_value) else {
+            return agent.type_error("value is not an object", gc.into_nogc());
+        };
+        let scoped_r = r.scope(agent, gc.nogc());
+        // 3. Let S be ? ToString(string).
+        let s = to_string(agent, string.unbind(), gc.reborrow())
+            .unbind()?
+            .scope(agent, gc.nogc());
+        // 4. Let C be ? SpeciesConstructor(R, %RegExp%).
+        let reg_exp_constructor = agent.current_realm_record().intrinsics().reg_exp();
+        let c = species_constructor(
+            agent,
+            scoped_r.get(agent),
+            reg_exp_constructor.into_function(),
+            gc.reborrow(),
+        )
+        .unbind()?
+        .scope(agent, gc.nogc());
+        // 5. Let flags be ? ToString(? Get(R, "flags")).
+        let flags = get(
+            agent,
+            scoped_r.get(agent),
+            BUILTIN_STRING_MEMORY.flags.into(),
+            gc.reborrow(),
+        )
+        .unbind()?
+        .bind(gc.nogc());
+        let flags = to_string(agent, flags.unbind(), gc.reborrow())
+            .unbind()?
+            .scope(agent, gc.nogc());
+        // 6. Let matcher be ? Construct(C, « R, flags »).
+        let matcher = construct(
+            agent,
+            c.get(agent),
+            Some(ArgumentsList::from_mut_slice(&mut [
+                scoped_r.get(agent).into_value(),
+                flags.get(agent).into_value(),
+            ])),
+            None,
+            gc.reborrow(),
+        )
+        .unbind()?
+        .scope(agent, gc.nogc());
+        // 7. Let lastIndex be ? ToLength(? Get(R, "lastIndex")).
+        let last_index = get(
+            agent,
+            scoped_r.get(agent),
+            BUILTIN_STRING_MEMORY.lastIndex.into(),
+            gc.reborrow(),
+        )
+        .unbind()?
+        .bind(gc.nogc());
+        let last_index = to_length(agent, last_index.unbind(), gc.reborrow()).unbind()?;
+        // 8. Perform ? Set(matcher, "lastIndex", lastIndex, true).
+        set(
+            agent,
+            matcher.get(agent),
+            BUILTIN_STRING_MEMORY.lastIndex.into(),
+            Value::try_from(last_index).unwrap(),
+            true,
+            gc.reborrow(),
+        )
+        .unbind()?;
+        let gc = gc.into_nogc();
+        let flags = flags.get(agent).bind(gc);
+        // 9. If flags contains "g", let global be true.
+        // 10. Else, let global be false.
+        let global = flags_contain(agent, flags, b'g');
+        // 11. If flags contains "u" or flags contains "v", let fullUnicode be
+        //     true.
+        // 12. Else, let fullUnicode be false.
+        let full_unicode = flags_contain(agent, flags, b'u') || flags_contain(agent, flags, b'v');
+        // 13. Return CreateRegExpStringIterator(matcher, S, global, fullUnicode).
+        Ok(RegExpStringIterator::create(
+            agent,
+            matcher.get(agent),
+            s.get(agent),
+            global,
+            full_unicode,
+            gc,
+        )
+        .into_value())
     }
 
+    /// ### [22.2.6.11 get RegExp.prototype.multiline](https://tc39.es/ecma262/#sec-get-regexp.prototype.multiline)
+    ///
+    /// RegExp.prototype.multiline is an accessor property whose set accessor
+    /// function is undefined.
     fn get_multiline<'gc>(
         agent: &mut Agent,
-        _this_value: Value,
+        this_value: Value,
         _: ArgumentsList,
         gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
-        Err(agent.todo("RegExp.prototype.multiline", gc.into_nogc()))
+        // 1. Let R be the this value.
+        // 2. Let cu be the code unit 0x006D (LATIN SMALL LETTER M).
+        // 3. Return ? RegExpHasFlag(R, cu).
+        reg_exp_has_flag(agent, this_value, RegExpFlags::M, gc.into_nogc())
     }
 
     fn replace<'gc>(
@@ -431,31 +581,283 @@ impl RegExpPrototype {
         Err(agent.todo("RegExp.prototype.source", gc.into_nogc()))
     }
 
+    /// ### [22.2.6.14 RegExp.prototype \[ %Symbol.split% \] ( string, limit )](https://tc39.es/ecma262/#sec-regexp.prototype-%symbol.split%)
+    ///
+    /// > #### Note 1
+    /// >
+    /// > This method returns an Array into which substrings of the result of
+    /// > converting string to a String have been stored. The substrings are
+    /// > determined by searching from left to right for matches of the this
+    /// > value regular expression; these occurrences are not part of any
+    /// > String in the returned array, but serve to divide up the String
+    /// > value.
+    ///
+    /// > #### Note 2
+    /// >
+    /// > This method ignores the value of the "global" and "sticky"
+    /// > properties of this RegExp object.
     fn split<'gc>(
         agent: &mut Agent,
-        _this_value: Value,
-        _: ArgumentsList,
-        gc: GcScope<'gc, '_>,
+        this_value: Value,
+        arguments: ArgumentsList,
+        mut gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
-        Err(agent.todo("RegExp.prototype.split", gc.into_nogc()))
+        let this_value = this_value.bind(gc.nogc());
+        let string = arguments.get(0).bind(gc.nogc());
+        let limit = arguments.get(1).scope(agent, gc.nogc());
+        // 1. Let rx be the this value.
+        // 2. If rx is not an Object, throw a TypeError exception.
+        let Ok(rx) = Object::try_from(this_value) else {
+            return agent.type_error("value is not an object", gc.into_nogc());
+        };
+        let rx = rx.scope(agent, gc.nogc());
+        // 3. Let S be ? ToString(string).
+        let s = to_string(agent, string.unbind(), gc.reborrow())
+            .unbind()?
+            .scope(agent, gc.nogc());
+        // 4. Let C be ? SpeciesConstructor(rx, %RegExp%).
+        let reg_exp_constructor = agent.current_realm_record().intrinsics().reg_exp();
+        let c = species_constructor(
+            agent,
+            rx.get(agent),
+            reg_exp_constructor.into_function(),
+            gc.reborrow(),
+        )
+        .unbind()?
+        .scope(agent, gc.nogc());
+        // 5. Let flags be ? ToString(? Get(rx, "flags")).
+        let flags = get(
+            agent,
+            rx.get(agent),
+            BUILTIN_STRING_MEMORY.flags.into(),
+            gc.reborrow(),
+        )
+        .unbind()?
+        .bind(gc.nogc());
+        let flags = to_string(agent, flags.unbind(), gc.reborrow())
+            .unbind()?
+            .bind(gc.nogc());
+        // 6. If flags contains "u" or flags contains "v", let unicodeMatching
+        //    be true.
+        // 7. Else, let unicodeMatching be false.
+        let unicode_matching =
+            flags_contain(agent, flags, b'u') || flags_contain(agent, flags, b'v');
+        // 8. If flags contains "y", let newFlags be flags.
+        let new_flags = if flags_contain(agent, flags, b'y') {
+            flags
+        } else {
+            // 9. Else, let newFlags be the string-concatenation of flags and
+            //    "y".
+            String::concat(agent, [flags, String::from_small_string("y")], gc.nogc())
+        };
+        // 10. Let splitter be ? Construct(C, « rx, newFlags »).
+        let splitter = construct(
+            agent,
+            c.get(agent),
+            Some(ArgumentsList::from_mut_slice(&mut [
+                rx.get(agent).into_value(),
+                new_flags.into_value().unbind(),
+            ])),
+            None,
+            gc.reborrow(),
+        )
+        .unbind()?
+        .scope(agent, gc.nogc());
+        // 11. Let A be ! ArrayCreate(0).
+        let a = array_create(agent, 0, 0, None, gc.nogc())
+            .unwrap()
+            .scope(agent, gc.nogc());
+        // 12. Let lengthA be 0.
+        let mut length_a: u32 = 0;
+        // 13. If limit is undefined, let lim be 2**32 - 1; else let lim be
+        //     ℝ(? ToUint32(limit)).
+        let limit = limit.get(agent);
+        let lim = if limit.is_undefined() {
+            u32::MAX
+        } else {
+            to_uint32(agent, limit, gc.reborrow()).unbind()?
+        };
+        // 14. If lim = 0, return A.
+        if lim == 0 {
+            return Ok(a.get(agent).into_value());
+        }
+        // 15. If S is the empty String, then
+        if s.get(agent).is_empty_string() {
+            // a. Let z be ? RegExpExec(splitter, S).
+            let z =
+                reg_exp_exec(agent, splitter.get(agent), s.get(agent), gc.reborrow()).unbind()?;
+            // b. If z is not null, return A.
+            if z.is_none() {
+                // c. Perform ! CreateDataPropertyOrThrow(A, "0", S).
+                create_data_property_or_throw(
+                    agent,
+                    a.get(agent),
+                    PropertyKey::Integer(0.into()),
+                    s.get(agent).into_value(),
+                    gc.reborrow(),
+                )
+                .unwrap();
+            }
+            // d. Return A.
+            return Ok(a.get(agent).into_value());
+        }
+        // 16. Let size be the length of S.
+        let size = s.get(agent).utf16_len(agent);
+        // 17. Let p be 0.
+        let mut p = 0;
+        // 18. Let q be p.
+        let mut q = p;
+        // 19. Repeat, while q < size,
+        while q < size {
+            // a. Perform ? Set(splitter, "lastIndex", 𝔽(q), true).
+            set(
+                agent,
+                splitter.get(agent),
+                BUILTIN_STRING_MEMORY.lastIndex.into(),
+                Value::try_from(q as i64).unwrap(),
+                true,
+                gc.reborrow(),
+            )
+            .unbind()?;
+            // b. Let z be ? RegExpExec(splitter, S).
+            let z = reg_exp_exec(agent, splitter.get(agent), s.get(agent), gc.reborrow())
+                .unbind()?
+                .bind(gc.nogc());
+            // c. If z is null, set q to AdvanceStringIndex(S, q, unicodeMatching).
+            let Some(z) = z else {
+                q = advance_string_index(agent, s.get(agent), q, unicode_matching);
+                continue;
+            };
+            // d. Else,
+            let z = z.scope(agent, gc.nogc());
+            // i. Let e be ℝ(? ToLength(? Get(splitter, "lastIndex"))).
+            let e = get(
+                agent,
+                splitter.get(agent),
+                BUILTIN_STRING_MEMORY.lastIndex.into(),
+                gc.reborrow(),
+            )
+            .unbind()?
+            .bind(gc.nogc());
+            let e = to_length(agent, e.unbind(), gc.reborrow()).unbind()?;
+            // ii. Set e to min(e, size).
+            let e = (e as usize).min(size);
+            // iii. If e = p, set q to AdvanceStringIndex(S, q, unicodeMatching).
+            if e == p {
+                q = advance_string_index(agent, s.get(agent), q, unicode_matching);
+                continue;
+            }
+            // iv. Else,
+            // 1. Let T be the substring of S from p to q.
+            let t = s.get(agent).substring(agent, p, q, gc.nogc());
+            // 2. Perform ! CreateDataPropertyOrThrow(A, ! ToString(𝔽(lengthA)), T).
+            create_data_property_or_throw(
+                agent,
+                a.get(agent),
+                PropertyKey::Integer(length_a.into()),
+                t.into_value().unbind(),
+                gc.reborrow(),
+            )
+            .unwrap();
+            // 3. Set lengthA to lengthA + 1.
+            length_a += 1;
+            // 4. If lengthA = lim, return A.
+            if length_a == lim {
+                return Ok(a.get(agent).into_value());
+            }
+            // 5. Set p to e.
+            p = e;
+            // 6. Let numberOfCaptures be ? LengthOfArrayLike(z).
+            let number_of_captures =
+                length_of_array_like(agent, z.get(agent), gc.reborrow()).unbind()?;
+            // 7. Set numberOfCaptures to max(numberOfCaptures - 1, 0).
+            let number_of_captures = (number_of_captures - 1).max(0);
+            // 8. Let i be 1.
+            // 9. Repeat, while i ≤ numberOfCaptures,
+            for i in 1..=number_of_captures {
+                // a. Let nextCapture be ? Get(z, ! ToString(𝔽(i))).
+                let next_capture = get(
+                    agent,
+                    z.get(agent),
+                    PropertyKey::Integer(i.try_into().unwrap()),
+                    gc.reborrow(),
+                )
+                .unbind()?
+                .bind(gc.nogc());
+                // b. Perform ! CreateDataPropertyOrThrow(A, ! ToString(𝔽(lengthA)), nextCapture).
+                create_data_property_or_throw(
+                    agent,
+                    a.get(agent),
+                    PropertyKey::Integer(length_a.into()),
+                    next_capture.unbind(),
+                    gc.reborrow(),
+                )
+                .unwrap();
+                // c. Set i to i + 1.
+                // d. Set lengthA to lengthA + 1.
+                length_a += 1;
+                // e. If lengthA = lim, return A.
+                if length_a == lim {
+                    return Ok(a.get(agent).into_value());
+                }
+            }
+            // 10. Set q to p.
+            q = p;
+        }
+        // 20. Let T be the substring of S from p to size.
+        let t = s.get(agent).substring(agent, p, size, gc.nogc());
+        // 21. Perform ! CreateDataPropertyOrThrow(A, ! ToString(𝔽(lengthA)), T).
+        create_data_property_or_throw(
+            agent,
+            a.get(agent),
+            PropertyKey::Integer(length_a.into()),
+            t.into_value().unbind(),
+            gc.reborrow(),
+        )
+        .unwrap();
+        // 22. Return A.
+        Ok(a.get(agent).into_value())
     }
 
+    /// ### [22.2.6.15 get RegExp.prototype.sticky](https://tc39.es/ecma262/#sec-get-regexp.prototype.sticky)
+    ///
+    /// RegExp.prototype.sticky is an accessor property whose set accessor
+    /// function is undefined.
     fn get_sticky<'gc>(
         agent: &mut Agent,
-        _this_value: Value,
+        this_value: Value,
         _: ArgumentsList,
         gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
-        Err(agent.todo("RegExp.prototype.sticky", gc.into_nogc()))
+        // 1. Let R be the this value.
+        // 2. Let cu be the code unit 0x0079 (LATIN SMALL LETTER Y).
+        // 3. Return ? RegExpHasFlag(R, cu).
+        reg_exp_has_flag(agent, this_value, RegExpFlags::Y, gc.into_nogc())
     }
 
+    /// ### [22.2.6.16 RegExp.prototype.test ( S )](https://tc39.es/ecma262/#sec-regexp.prototype.test)
     fn test<'gc>(
         agent: &mut Agent,
-        _this_value: Value,
-        _: ArgumentsList,
-        gc: GcScope<'gc, '_>,
+        this_value: Value,
+        arguments: ArgumentsList,
+        mut gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
-        Err(agent.todo("RegExp.prototype.test", gc.into_nogc()))
+        let this_value = this_value.bind(gc.nogc());
+        let s = arguments.get(0).bind(gc.nogc());
+        // 1. Let R be the this value.
+        // 2. If R is not an Object, throw a TypeError exception.
+        let Ok(r) = Object::try_from(this_value) else {
+            return agent.type_error("value is not an object", gc.into_nogc());
+        };
+        let r = r.scope(agent, gc.nogc());
+        // 3. Let string be ? ToString(S).
+        let string = to_string(agent, s.unbind(), gc.reborrow())
+            .unbind()?
+            .bind(gc.nogc());
+        // 4. Let match be ? RegExpExec(R, string).
+        let r#match = reg_exp_exec(agent, r.get(agent), string.unbind(), gc)?;
+        // 5. If match is not null, return true; else return false.
+        Ok(r#match.is_some().into())
     }
 
     /// ### [22.2.6.17 RegExp.prototype.toString ( )](https://tc39.es/ecma262/#sec-regexp.prototype.tostring)
@@ -481,7 +883,7 @@ impl RegExpPrototype {
                 this_value
                     .unbind()
                     .string_repr(agent, gc.reborrow())
-                    .as_str(agent)
+                    .to_string_lossy(agent)
             );
             return Err(agent.throw_exception(
                 ExceptionType::TypeError,
@@ -496,16 +898,14 @@ impl RegExpPrototype {
             let string_length = data.original_source.len(agent);
             let flags_length = data.original_flags.iter().count();
             let mut regexp_string =
-                std::string::String::with_capacity(1 + string_length + 1 + flags_length);
-            regexp_string.push('/');
-            regexp_string.push_str(data.original_source.as_str(agent));
-            regexp_string.push('/');
+                HeapStringBuilder::with_capacity(1 + string_length + 1 + flags_length);
+            regexp_string.push_char('/');
+            regexp_string.push_string(agent, data.original_source);
+            regexp_string.push_char('/');
             data.original_flags.iter_names().for_each(|(flag, _)| {
                 regexp_string.push_str(flag);
             });
-            return Ok(String::from_string(agent, regexp_string, nogc)
-                .into_value()
-                .unbind());
+            return Ok(regexp_string.finish(agent, nogc).into_value().unbind());
         }
         let scoped_r = r.scope(agent, nogc);
         // 3. Let pattern be ? ToString(? Get(R, "source")).
@@ -533,32 +933,52 @@ impl RegExpPrototype {
             .unbind()?
             .bind(gc.nogc());
         // 5. Let result be the string-concatenation of "/", pattern, "/", and flags.
-        let result = format!(
-            "/{}/{}",
-            pattern.get(agent).bind(gc.nogc()).as_str(agent),
-            flags.as_str(agent)
+        let flags = flags.unbind();
+        let gc = gc.into_nogc();
+        let result = String::concat(
+            agent,
+            [
+                String::from_small_string("/"),
+                pattern.get(agent).bind(gc),
+                String::from_small_string("/"),
+                flags.bind(gc),
+            ],
+            gc,
         );
-        let result = String::from_string(agent, result, gc.into_nogc());
         // 6. Return result.
         Ok(result.into_value())
     }
 
+    /// ### [22.2.6.18 get RegExp.prototype.unicode](https://tc39.es/ecma262/#sec-get-regexp.prototype.unicode)
+    ///
+    /// RegExp.prototype.unicode is an accessor property whose set accessor
+    /// function is undefined.
     fn get_unicode<'gc>(
         agent: &mut Agent,
-        _this_value: Value,
+        this_value: Value,
         _: ArgumentsList,
         gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
-        Err(agent.todo("RegExp.prototype.unicode", gc.into_nogc()))
+        // 1. Let R be the this value.
+        // 2. Let cu be the code unit 0x0075 (LATIN SMALL LETTER U).
+        // 3. Return ? RegExpHasFlag(R, cu).
+        reg_exp_has_flag(agent, this_value, RegExpFlags::U, gc.into_nogc())
     }
 
+    /// ### [22.2.6.19 get RegExp.prototype.unicodeSets](https://tc39.es/ecma262/#sec-get-regexp.prototype.unicodesets)
+    ///
+    /// RegExp.prototype.unicodeSets is an accessor property whose set accessor
+    /// function is undefined.
     fn get_unicode_sets<'gc>(
         agent: &mut Agent,
-        _this_value: Value,
+        this_value: Value,
         _: ArgumentsList,
         gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
-        Err(agent.todo("RegExp.prototype.unicodeSets", gc.into_nogc()))
+        // 1. Let R be the this value.
+        // 2. Let cu be the code unit 0x0076 (LATIN SMALL LETTER V).
+        // 3. Return ? RegExpHasFlag(R, cu).
+        reg_exp_has_flag(agent, this_value, RegExpFlags::V, gc.into_nogc())
     }
 
     pub(crate) fn create_intrinsic(agent: &mut Agent, realm: Realm<'static>) {
@@ -592,3 +1012,45 @@ impl RegExpPrototype {
             .build();
     }
 }
+
+/// ### [22.2.6.4.1 RegExpHasFlag ( R, codeUnit )](https://tc39.es/ecma262/#sec-regexphasflag)
+///
+/// The abstract operation RegExpHasFlag takes arguments R (an ECMAScript
+/// language value) and codeUnit (a code unit) and returns either a normal
+/// completion containing either a Boolean or undefined, or a throw
+/// completion.
+fn reg_exp_has_flag<'gc>(
+    agent: &mut Agent,
+    r: Value,
+    flag: RegExpFlags,
+    gc: NoGcScope<'gc, '_>,
+) -> JsResult<'gc, Value<'gc>> {
+    let r = r.bind(gc);
+    // 1. If R is not an Object, throw a TypeError exception.
+    let Ok(r) = Object::try_from(r) else {
+        return agent.type_error("value is not an object", gc);
+    };
+    // 2. If R does not have an [[OriginalFlags]] internal slot, then
+    let Object::RegExp(r) = r else {
+        // a. If SameValue(R, %RegExp.prototype%) is true, return undefined.
+        let reg_exp_prototype = agent
+            .current_realm_record()
+            .intrinsics()
+            .reg_exp_prototype();
+        if r == reg_exp_prototype.into_object() {
+            return Ok(Value::Undefined);
+        }
+        // b. Otherwise, throw a TypeError exception.
+        return agent.type_error("value is not a RegExp", gc);
+    };
+    // 3. Let flags be R.[[OriginalFlags]].
+    // 4. If flags contains codeUnit, return true.
+    // 5. Return false.
+    Ok(agent[r].original_flags.contains(flag).into())
+}
+
+/// Returns true if the flags String contains the given ASCII code unit.
+fn flags_contain(agent: &Agent, flags: String, code_unit: u8) -> bool {
+    // NOTE: ASCII bytes only appear in WTF-8 as themselves.
+    flags.as_wtf8_bytes(agent).contains(&code_unit)
+}
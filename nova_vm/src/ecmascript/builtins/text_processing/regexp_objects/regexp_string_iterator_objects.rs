@@ -0,0 +1,429 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use core::ops::{Index, IndexMut};
+
+use crate::{
+    ecmascript::{
+        abstract_operations::{
+            operations_on_iterator_objects::create_iter_result_object,
+            operations_on_objects::{get, set},
+            type_conversion::{to_length, to_string},
+        },
+        builders::ordinary_object_builder::OrdinaryObjectBuilder,
+        builtins::{
+            ArgumentsList, Behaviour, Builtin,
+            regexp::{advance_string_index, reg_exp_exec},
+        },
+        execution::{Agent, JsResult, ProtoIntrinsics, Realm},
+        types::{
+            BUILTIN_STRING_MEMORY, InternalMethods, InternalSlots, IntoValue, Object,
+            OrdinaryObject, String, Value,
+        },
+    },
+    engine::{
+        context::{Bindable, GcScope, NoGcScope},
+        rootable::{HeapRootData, Scopable},
+    },
+    heap::{
+        CompactionLists, CreateHeapData, Heap, HeapMarkAndSweep, HeapSweepWeakReference,
+        WorkQueues, indexes::RegExpStringIteratorIndex,
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RegExpStringIterator<'a>(RegExpStringIteratorIndex<'a>);
+
+impl<'a> RegExpStringIterator<'a> {
+    /// ### [22.2.9.1 CreateRegExpStringIterator ( R, S, global, fullUnicode )](https://tc39.es/ecma262/#sec-createregexpstringiterator)
+    ///
+    /// The abstract operation CreateRegExpStringIterator takes arguments R (an
+    /// Object), S (a String), global (a Boolean), and fullUnicode (a Boolean)
+    /// and returns an Object.
+    pub(crate) fn create(
+        agent: &mut Agent,
+        r: Object,
+        s: String,
+        global: bool,
+        full_unicode: bool,
+        gc: NoGcScope<'a, '_>,
+    ) -> Self {
+        agent
+            .heap
+            .create(RegExpStringIteratorHeapData {
+                object_index: None,
+                iterating_reg_exp: Some(r.unbind()),
+                iterated_string: s.unbind(),
+                global,
+                unicode: full_unicode,
+            })
+            .bind(gc)
+    }
+
+    /// # Do not use this
+    /// This is only for Value discriminant creation.
+    pub(crate) const fn _def() -> Self {
+        Self(RegExpStringIteratorIndex::from_u32_index(0))
+    }
+
+    pub(crate) const fn get_index(self) -> usize {
+        self.0.into_index()
+    }
+}
+
+// SAFETY: Property implemented as a lifetime transmute.
+unsafe impl Bindable for RegExpStringIterator<'_> {
+    type Of<'a> = RegExpStringIterator<'a>;
+
+    #[inline(always)]
+    fn unbind(self) -> Self::Of<'static> {
+        unsafe { core::mem::transmute::<Self, Self::Of<'static>>(self) }
+    }
+
+    #[inline(always)]
+    fn bind<'a>(self, _gc: NoGcScope<'a, '_>) -> Self::Of<'a> {
+        unsafe { core::mem::transmute::<Self, Self::Of<'a>>(self) }
+    }
+}
+
+impl<'a> From<RegExpStringIterator<'a>> for Object<'a> {
+    fn from(value: RegExpStringIterator<'a>) -> Self {
+        Self::RegExpStringIterator(value)
+    }
+}
+
+impl<'a> From<RegExpStringIterator<'a>> for Value<'a> {
+    fn from(value: RegExpStringIterator<'a>) -> Self {
+        Self::RegExpStringIterator(value)
+    }
+}
+
+impl<'a> TryFrom<Value<'a>> for RegExpStringIterator<'a> {
+    type Error = ();
+
+    fn try_from(value: Value<'a>) -> Result<Self, Self::Error> {
+        match value {
+            Value::RegExpStringIterator(data) => Ok(data),
+            _ => Err(()),
+        }
+    }
+}
+
+impl<'a> TryFrom<Object<'a>> for RegExpStringIterator<'a> {
+    type Error = ();
+
+    fn try_from(value: Object<'a>) -> Result<Self, Self::Error> {
+        match value {
+            Object::RegExpStringIterator(data) => Ok(data),
+            _ => Err(()),
+        }
+    }
+}
+
+impl<'a> InternalSlots<'a> for RegExpStringIterator<'a> {
+    const DEFAULT_PROTOTYPE: ProtoIntrinsics = ProtoIntrinsics::RegExpStringIterator;
+
+    #[inline(always)]
+    fn get_backing_object(self, agent: &Agent) -> Option<OrdinaryObject<'static>> {
+        agent[self].object_index
+    }
+
+    fn set_backing_object(self, agent: &mut Agent, backing_object: OrdinaryObject<'static>) {
+        assert!(
+            agent[self]
+                .object_index
+                .replace(backing_object.unbind())
+                .is_none()
+        );
+    }
+}
+
+impl<'a> InternalMethods<'a> for RegExpStringIterator<'a> {}
+
+impl Index<RegExpStringIterator<'_>> for Agent {
+    type Output = RegExpStringIteratorHeapData<'static>;
+
+    fn index(&self, index: RegExpStringIterator) -> &Self::Output {
+        &self.heap.reg_exp_string_iterators[index]
+    }
+}
+
+impl IndexMut<RegExpStringIterator<'_>> for Agent {
+    fn index_mut(&mut self, index: RegExpStringIterator) -> &mut Self::Output {
+        self.heap.write_barrier(index);
+        &mut self.heap.reg_exp_string_iterators[index]
+    }
+}
+
+impl Index<RegExpStringIterator<'_>> for Vec<Option<RegExpStringIteratorHeapData<'static>>> {
+    type Output = RegExpStringIteratorHeapData<'static>;
+
+    fn index(&self, index: RegExpStringIterator) -> &Self::Output {
+        self.get(index.get_index())
+            .expect("RegExpStringIterator out of bounds")
+            .as_ref()
+            .expect("RegExpStringIterator slot empty")
+    }
+}
+
+impl IndexMut<RegExpStringIterator<'_>> for Vec<Option<RegExpStringIteratorHeapData<'static>>> {
+    fn index_mut(&mut self, index: RegExpStringIterator) -> &mut Self::Output {
+        self.get_mut(index.get_index())
+            .expect("RegExpStringIterator out of bounds")
+            .as_mut()
+            .expect("RegExpStringIterator slot empty")
+    }
+}
+
+impl TryFrom<HeapRootData> for RegExpStringIterator<'_> {
+    type Error = ();
+
+    #[inline]
+    fn try_from(value: HeapRootData) -> Result<Self, Self::Error> {
+        if let HeapRootData::RegExpStringIterator(value) = value {
+            Ok(value)
+        } else {
+            Err(())
+        }
+    }
+}
+
+impl<'a> CreateHeapData<RegExpStringIteratorHeapData<'a>, RegExpStringIterator<'a>> for Heap {
+    fn create(&mut self, data: RegExpStringIteratorHeapData<'a>) -> RegExpStringIterator<'a> {
+        self.reg_exp_string_iterators.push(Some(data.unbind()));
+        self.alloc_counter += core::mem::size_of::<Option<RegExpStringIteratorHeapData<'static>>>();
+        RegExpStringIterator(RegExpStringIteratorIndex::last(
+            &self.reg_exp_string_iterators,
+        ))
+    }
+}
+
+impl HeapMarkAndSweep for RegExpStringIterator<'static> {
+    fn mark_values(&self, queues: &mut WorkQueues) {
+        queues.reg_exp_string_iterators.push(*self);
+    }
+
+    fn sweep_values(&mut self, compactions: &CompactionLists) {
+        compactions
+            .reg_exp_string_iterators
+            .shift_index(&mut self.0);
+    }
+}
+
+impl HeapSweepWeakReference for RegExpStringIterator<'static> {
+    fn sweep_weak_reference(self, compactions: &CompactionLists) -> Option<Self> {
+        compactions
+            .reg_exp_string_iterators
+            .shift_weak_index(self.0)
+            .map(Self)
+    }
+}
+
+pub(crate) struct RegExpStringIteratorPrototype;
+
+struct RegExpStringIteratorPrototypeNext;
+impl Builtin for RegExpStringIteratorPrototypeNext {
+    const NAME: String<'static> = BUILTIN_STRING_MEMORY.next;
+
+    const LENGTH: u8 = 0;
+
+    const BEHAVIOUR: Behaviour = Behaviour::Regular(RegExpStringIteratorPrototype::next);
+}
+
+impl RegExpStringIteratorPrototype {
+    /// ### [22.2.9.2.1 %RegExpStringIteratorPrototype%.next ( )](https://tc39.es/ecma262/#sec-%regexpstringiteratorprototype%.next)
+    fn next<'gc>(
+        agent: &mut Agent,
+        this_value: Value,
+        _arguments: ArgumentsList,
+        mut gc: GcScope<'gc, '_>,
+    ) -> JsResult<'gc, Value<'gc>> {
+        let this_value = this_value.bind(gc.nogc());
+        // 1. Return ? GeneratorResume(this value, empty, "%RegExpStringIteratorPrototype%").
+        let Value::RegExpStringIterator(iterator) = this_value else {
+            return agent.type_error("not a RegExp String Iterator", gc.into_nogc());
+        };
+        let scoped_iterator = iterator.scope(agent, gc.nogc());
+        let result = Self::resume(agent, iterator.unbind(), gc.reborrow()).unbind();
+        let gc = gc.into_nogc();
+        let result = result.bind(gc);
+        let iterator = scoped_iterator.get(agent).bind(gc);
+        let (value, done) = match result {
+            Ok(Some(result)) => {
+                if !agent[iterator].global {
+                    // iii. If global is false, then
+                    // 2. Return undefined.
+                    agent[iterator].iterating_reg_exp = None;
+                }
+                (result.into_value(), false)
+            }
+            Ok(None) => {
+                agent[iterator].iterating_reg_exp = None;
+                (Value::Undefined, true)
+            }
+            Err(err) => {
+                // An abrupt completion completes the generator.
+                agent[iterator].iterating_reg_exp = None;
+                return Err(err);
+            }
+        };
+        Ok(create_iter_result_object(agent, value, done, gc).into_value())
+    }
+
+    /// The closure of CreateRegExpStringIterator, resumed until its next
+    /// yield. Returns None when the closure returns.
+    fn resume<'gc>(
+        agent: &mut Agent,
+        iterator: RegExpStringIterator,
+        mut gc: GcScope<'gc, '_>,
+    ) -> JsResult<'gc, Option<Object<'gc>>> {
+        let iterator = iterator.bind(gc.nogc());
+        let RegExpStringIteratorHeapData {
+            iterating_reg_exp,
+            iterated_string,
+            global,
+            unicode: full_unicode,
+            ..
+        } = agent[iterator];
+        // The closure has already returned.
+        let Some(r) = iterating_reg_exp else {
+            return Ok(None);
+        };
+        let r = r.bind(gc.nogc());
+        let s = iterated_string.bind(gc.nogc());
+        let scoped_r = r.scope(agent, gc.nogc());
+        let scoped_s = s.scope(agent, gc.nogc());
+        // a. Repeat,
+        // i. Let match be ? RegExpExec(R, S).
+        let Some(r#match) = reg_exp_exec(agent, r.unbind(), s.unbind(), gc.reborrow())
+            .unbind()?
+            .bind(gc.nogc())
+        else {
+            // ii. If match is null, return undefined.
+            return Ok(None);
+        };
+        // iii. If global is false, then
+        if !global {
+            // 1. Perform ? GeneratorYield(CreateIteratorResultObject(match, false)).
+            return Ok(Some(r#match.unbind()));
+        }
+        let scoped_match = r#match.scope(agent, gc.nogc());
+        // iv. Let matchStr be ? ToString(? Get(match, "0")).
+        let match_str = get(agent, r#match.unbind(), 0.into(), gc.reborrow())
+            .unbind()?
+            .bind(gc.nogc());
+        let match_str = to_string(agent, match_str.unbind(), gc.reborrow())
+            .unbind()?
+            .bind(gc.nogc());
+        // v. If matchStr is the empty String, then
+        if match_str.is_empty_string() {
+            // 1. Let thisIndex be ℝ(? ToLength(? Get(R, "lastIndex"))).
+            let this_index = get(
+                agent,
+                scoped_r.get(agent),
+                BUILTIN_STRING_MEMORY.lastIndex.into(),
+                gc.reborrow(),
+            )
+            .unbind()?
+            .bind(gc.nogc());
+            let this_index =
+                to_length(agent, this_index.unbind(), gc.reborrow()).unbind()? as usize;
+            // 2. Let nextIndex be AdvanceStringIndex(S, thisIndex, fullUnicode).
+            let next_index =
+                advance_string_index(agent, scoped_s.get(agent), this_index, full_unicode);
+            // 3. Perform ? Set(R, "lastIndex", 𝔽(nextIndex), true).
+            let next_index = Value::try_from(next_index as i64).unwrap();
+            set(
+                agent,
+                scoped_r.get(agent),
+                BUILTIN_STRING_MEMORY.lastIndex.into(),
+                next_index,
+                true,
+                gc.reborrow(),
+            )
+            .unbind()?;
+        }
+        // vi. Perform ? GeneratorYield(CreateIteratorResultObject(match, false)).
+        Ok(Some(scoped_match.get(agent).bind(gc.into_nogc())))
+    }
+
+    pub(crate) fn create_intrinsic(agent: &mut Agent, realm: Realm<'static>) {
+        let intrinsics = agent.get_realm_record_by_id(realm).intrinsics();
+        let this = intrinsics.reg_exp_string_iterator_prototype();
+        let iterator_prototype = intrinsics.iterator_prototype();
+
+        OrdinaryObjectBuilder::new_intrinsic_object(agent, realm, this)
+            .with_property_capacity(2)
+            .with_prototype(iterator_prototype)
+            .with_builtin_function_property::<RegExpStringIteratorPrototypeNext>()
+            .with_to_string_tag(BUILTIN_STRING_MEMORY.RegExp_String_Iterator)
+            .build();
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RegExpStringIteratorHeapData<'a> {
+    pub(crate) object_index: Option<OrdinaryObject<'a>>,
+    /// The RegExp object being iterated, or None once the iterator has
+    /// completed.
+    pub(crate) iterating_reg_exp: Option<Object<'a>>,
+    pub(crate) iterated_string: String<'a>,
+    pub(crate) global: bool,
+    pub(crate) unicode: bool,
+}
+
+impl Default for RegExpStringIteratorHeapData<'_> {
+    fn default() -> Self {
+        Self {
+            object_index: None,
+            iterating_reg_exp: None,
+            iterated_string: String::EMPTY_STRING,
+            global: false,
+            unicode: false,
+        }
+    }
+}
+
+// SAFETY: Property implemented as a lifetime transmute.
+unsafe impl Bindable for RegExpStringIteratorHeapData<'_> {
+    type Of<'a> = RegExpStringIteratorHeapData<'a>;
+
+    #[inline(always)]
+    fn unbind(self) -> Self::Of<'static> {
+        unsafe { core::mem::transmute::<Self, Self::Of<'static>>(self) }
+    }
+
+    #[inline(always)]
+    fn bind<'a>(self, _gc: NoGcScope<'a, '_>) -> Self::Of<'a> {
+        unsafe { core::mem::transmute::<Self, Self::Of<'a>>(self) }
+    }
+}
+
+impl HeapMarkAndSweep for RegExpStringIteratorHeapData<'static> {
+    fn mark_values(&self, queues: &mut WorkQueues) {
+        let Self {
+            object_index,
+            iterating_reg_exp,
+            iterated_string,
+            global: _,
+            unicode: _,
+        } = self;
+        object_index.mark_values(queues);
+        iterating_reg_exp.mark_values(queues);
+        iterated_string.mark_values(queues);
+    }
+
+    fn sweep_values(&mut self, compactions: &CompactionLists) {
+        let Self {
+            object_index,
+            iterating_reg_exp,
+            iterated_string,
+            global: _,
+            unicode: _,
+        } = self;
+        object_index.sweep_values(compactions);
+        iterating_reg_exp.sweep_values(compactions);
+        iterated_string.sweep_values(compactions);
+    }
+}
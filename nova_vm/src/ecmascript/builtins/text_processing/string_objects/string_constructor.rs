@@ -36,6 +36,7 @@ use crate::ecmascript::types::Value;
 use crate::engine::context::{Bindable, GcScope};
 use crate::engine::rootable::Scopable;
 use crate::heap::IntrinsicConstructorIndexes;
+use wtf8::{CodePoint, Wtf8Buf};
 
 pub struct StringConstructor;
 
@@ -165,12 +166,10 @@ impl StringConstructor {
             return Ok(String::EMPTY_STRING.into_value());
         }
 
-        // fast path: only a single valid code unit
+        // fast path: only a single code unit
         if code_units.len() == 1 {
             let cu = code_units.get(0).to_uint16(agent, gc.reborrow()).unbind()?;
-            if let Some(cu) = char::from_u32(cu as u32) {
-                return Ok(SmallString::from(cu).into());
-            }
+            return Ok(String::from_code_unit(agent, cu).into());
         }
 
         let buf = if code_units.iter().all(|cu| cu.is_number()) {
@@ -193,9 +192,10 @@ impl StringConstructor {
                 .bind(gc.nogc())
         };
 
-        let result = std::string::String::from_utf16_lossy(&buf);
+        // NOTE: Lone surrogates are preserved in the WTF-8 result.
+        let result = Wtf8Buf::from_ill_formed_utf16(&buf);
 
-        Ok(String::from_string(agent, result, gc.into_nogc()).into())
+        Ok(String::from_wtf8_buf(agent, result, gc.into_nogc()).into())
     }
 
     /// ### [22.1.2.2 String.fromCodePoint ( ...`codePoints` )](https://tc39.es/ecma262/multipage/text-processing.html#sec-string.fromcodepoint)
@@ -235,7 +235,9 @@ impl StringConstructor {
             }
         };
         // 1. Let result be the empty String.
-        let mut result = std::string::String::with_capacity(code_points.len());
+        // NOTE: Surrogate code points are stored as lone surrogates in the
+        // WTF-8 result, and adjacent surrogates are joined into a pair.
+        let mut result = Wtf8Buf::with_capacity(code_points.len());
         if code_points.iter().all(|cp| cp.is_integer()) {
             // 2. For each element next of codePoints, do
             for next in code_points.iter() {
@@ -252,7 +254,7 @@ impl StringConstructor {
                     ));
                 }
                 // d. Set result to the string-concatenation of result and UTF16EncodeCodePoint(ℝ(nextCP)).
-                result.push(char::from_u32(next_cp as u32).unwrap());
+                result.push(CodePoint::from_u32(next_cp as u32).unwrap());
             }
         } else {
             let code_points = code_points
@@ -283,11 +285,11 @@ impl StringConstructor {
                     ));
                 }
                 // d. Set result to the string-concatenation of result and UTF16EncodeCodePoint(ℝ(nextCP)).
-                result.push(char::from_u32(next_cp as u32).unwrap());
+                result.push(CodePoint::from_u32(next_cp as u32).unwrap());
             }
         }
         // 4. Return result.
-        Ok(String::from_string(agent, result, gc.into_nogc()).into())
+        Ok(String::from_wtf8_buf(agent, result, gc.into_nogc()).into())
     }
 
     /// ### [22.1.2.4 String.raw ( template, ...substitutions )](https://tc39.es/ecma262/#sec-string.raw)
@@ -346,7 +348,7 @@ impl StringConstructor {
                 }
 
                 // 6. Let R be the empty String.
-                let mut r = std::string::String::with_capacity(literal_count as usize);
+                let mut r = Wtf8Buf::with_capacity(literal_count as usize);
 
                 // 7. Let nextIndex be 0.
                 // 8. Repeat,
@@ -367,7 +369,7 @@ impl StringConstructor {
                         .bind(gc.nogc());
 
                     // c. Set R to the string-concatenation of R and nextLiteral.
-                    r.push_str(next_literal.as_str(agent));
+                    r.push_wtf8(next_literal.as_wtf8(agent));
 
                     // d. If nextIndex + 1 = literalCount, return R.
                     // Note: this branch is now below the loop.
@@ -383,12 +385,12 @@ impl StringConstructor {
                             .bind(gc.nogc());
 
                         // iii. Set R to the string-concatenation of R and nextSub.
-                        r.push_str(next_sub.as_str(agent));
+                        r.push_wtf8(next_sub.as_wtf8(agent));
                     }
 
                     // f. Set nextIndex to nextIndex + 1.
                 }
-                Ok(String::from_string(agent, r, gc.into_nogc()).into())
+                Ok(String::from_wtf8_buf(agent, r, gc.into_nogc()).into())
             },
             gc,
         )
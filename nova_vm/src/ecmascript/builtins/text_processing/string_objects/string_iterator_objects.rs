@@ -5,24 +5,20 @@
 use crate::ecmascript::abstract_operations::operations_on_iterator_objects::create_iter_result_object;
 use crate::ecmascript::builtins::Behaviour;
 use crate::ecmascript::execution::ProtoIntrinsics;
-use crate::ecmascript::execution::agent::ExceptionType;
 use crate::ecmascript::types::{
     InternalMethods, InternalSlots, IntoObject, IntoValue, Object, OrdinaryObject,
 };
+use crate::ecmascript::{
+    builders::ordinary_object_builder::OrdinaryObjectBuilder,
+    builtins::{ArgumentsList, Builtin},
+    execution::{Agent, JsResult, Realm},
+    types::{BUILTIN_STRING_MEMORY, String, Value},
+};
 use crate::engine::context::{Bindable, GcScope, NoGcScope};
 use crate::heap::indexes::StringIteratorIndex;
 use crate::heap::{
     CompactionLists, CreateHeapData, Heap, HeapMarkAndSweep, HeapSweepWeakReference, WorkQueues,
 };
-use crate::{
-    ecmascript::{
-        builders::ordinary_object_builder::OrdinaryObjectBuilder,
-        builtins::{ArgumentsList, Builtin},
-        execution::{Agent, JsResult, Realm},
-        types::{BUILTIN_STRING_MEMORY, String, Value},
-    },
-    heap::WellKnownSymbolIndexes,
-};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(transparent)]
@@ -42,7 +38,7 @@ impl<'a> StringIterator<'a> {
         // c. Repeat, while position < len,
         // d. Return undefined.
         let StringIteratorHeapData { s, position, .. } = self.get_data(agent);
-        let len = s.len(agent);
+        let len = s.utf16_len(agent);
         *position >= len
     }
 
@@ -189,29 +185,21 @@ impl StringIteratorPrototype {
         // 1. Return ? GeneratorResume(this value, empty, "%StringIteratorPrototype%").
         // 1. Let state be ? GeneratorValidate(generator, generatorBrand).
         let Value::StringIterator(generator) = this_value else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "not a string iterator",
-                gc,
-            ));
+            return agent.type_error("not a string iterator", gc);
         };
         // 2. If state is completed, return CreateIteratorResultObject(undefined, true).
         if generator.is_completed(agent) {
             return Ok(create_iter_result_object(agent, Value::Undefined, true, gc).into_value());
         }
-        let StringIteratorHeapData { s, position, .. } = generator.get_data(agent);
+        let &StringIteratorHeapData { s, position, .. } = generator.get_data(agent);
+        let s = s.bind(gc);
         // 3. Assert: state is either suspended-start or suspended-yield.
         // i. Let cp be CodePointAt(s, position).
-        let u8_idx = s.utf8_index(agent, *position).unwrap();
-        let cp = s.as_str(agent)[u8_idx..]
-            .chars()
-            .next()
-            .unwrap()
-            .to_string();
+        let (_, code_unit_count) = s.code_point_at(agent, position);
         // ii. Let nextIndex be position + cp.[[CodeUnitCount]].
-        let next_index = *position + cp.len();
+        let next_index = position + code_unit_count;
         // iii. Let resultString be the substring of s from position to nextIndex.
-        let result_string = String::from_string(agent, cp, gc);
+        let result_string = s.substring(agent, position, next_index, gc);
         // iv. Set position to nextIndex.
         generator.get_data_mut(agent).position = next_index;
         // v. Perform ? GeneratorYield(CreateIteratorResultObject(resultString, false)).
@@ -228,14 +216,7 @@ impl StringIteratorPrototype {
             .with_property_capacity(2)
             .with_prototype(iterator_prototype)
             .with_builtin_function_property::<StringIteratorPrototypeNext>()
-            .with_property(|builder| {
-                builder
-                    .with_key(WellKnownSymbolIndexes::ToStringTag.into())
-                    .with_value_readonly(BUILTIN_STRING_MEMORY.String_Iterator.into_value())
-                    .with_enumerable(false)
-                    .with_configurable(true)
-                    .build()
-            })
+            .with_to_string_tag(BUILTIN_STRING_MEMORY.String_Iterator)
             .build();
     }
 }
@@ -2,18 +2,19 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use core::{cmp::max, iter::repeat_n, str::FromStr};
+use core::str::FromStr;
 use small_string::SmallString;
-use std::collections::VecDeque;
 use unicode_normalization::{
     IsNormalized, UnicodeNormalization, is_nfc_quick, is_nfd_quick, is_nfkc_quick, is_nfkd_quick,
 };
 
 use crate::ecmascript::abstract_operations::testing_and_comparison::is_reg_exp;
 use crate::ecmascript::abstract_operations::type_conversion::{
-    to_integer_or_infinity_number, to_string_primitive, try_to_integer_or_infinity, try_to_length,
-    try_to_string,
+    to_integer_or_infinity_number, to_string_primitive, try_to_length, try_to_string,
 };
+use crate::ecmascript::builtins::argument_coercion::coerce_arguments;
+#[cfg(feature = "regexp")]
+use crate::ecmascript::builtins::regexp::reg_exp_create;
 use crate::ecmascript::types::Primitive;
 use crate::engine::TryResult;
 use crate::engine::context::{Bindable, GcScope, NoGcScope};
@@ -21,11 +22,12 @@ use crate::engine::rootable::Scopable;
 use crate::{
     ecmascript::{
         abstract_operations::{
-            operations_on_objects::{call_function, create_array_from_list, get_method},
+            operations_on_objects::{
+                call_function, create_array_from_list, get, get_method, invoke,
+            },
             testing_and_comparison::{is_callable, require_object_coercible},
             type_conversion::{
-                is_trimmable_whitespace, to_integer_or_infinity, to_length, to_number, to_string,
-                to_uint32,
+                is_trimmable_whitespace, to_integer_or_infinity, to_length, to_string, to_uint32,
             },
         },
         builders::ordinary_object_builder::OrdinaryObjectBuilder,
@@ -33,8 +35,11 @@ use crate::{
             ArgumentsList, Array, Behaviour, Builtin, BuiltinIntrinsic,
             primitive_objects::{PrimitiveObjectData, PrimitiveObjectHeapData},
         },
-        execution::{Agent, JsResult, Realm, agent::ExceptionType},
-        types::{BUILTIN_STRING_MEMORY, IntoValue, Number, PropertyKey, String, Value},
+        execution::{Agent, JsResult, Realm},
+        types::{
+            BUILTIN_STRING_MEMORY, HeapStringBuilder, IntoValue, Number, Object, PropertyKey,
+            String, Value,
+        },
     },
     heap::{IntrinsicFunctionIndexes, WellKnownSymbolIndexes},
 };
@@ -393,7 +398,7 @@ impl StringPrototype {
     fn at<'gc>(
         agent: &mut Agent,
         this_value: Value,
-        args: ArgumentsList,
+        mut args: ArgumentsList,
         mut gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
         let nogc = gc.nogc();
@@ -404,20 +409,14 @@ impl StringPrototype {
         {
             (s, relative_index.into_i64())
         } else {
-            let index = index.scope(agent, nogc);
-            // 1. Let O be ? RequireObjectCoercible(this value).
-            let o = require_object_coercible(agent, this_value, nogc)
-                .unbind()?
-                .bind(nogc);
-            // 2. Let S be ? ToString(O).
-            let s = to_string(agent, o.unbind(), gc.reborrow())
-                .unbind()?
-                .scope(agent, gc.nogc());
-            // 4. Let relativeIndex be ? ToIntegerOrInfinity(pos).
-            let relative_index = to_integer_or_infinity(agent, index.get(agent), gc.reborrow())
-                .unbind()?
-                .into_i64();
-            (s.get(agent).bind(gc.nogc()), relative_index)
+            coerce_arguments!(agent, this_value, args, gc => {
+                // 1. Let O be ? RequireObjectCoercible(this value).
+                // 2. Let S be ? ToString(O).
+                this s = to_string_this("String.prototype.at");
+                // 4. Let relativeIndex be ? ToIntegerOrInfinity(pos).
+                let relative_index = to_integer_or_infinity_arg(0);
+            });
+            (s, relative_index.into_i64())
         };
         // 3. Let len be the length of S.
         let len = i64::try_from(s.utf16_len(agent)).unwrap();
@@ -435,8 +434,8 @@ impl StringPrototype {
             Ok(Value::Undefined)
         } else {
             // 8. Return the substring of S from k to k + 1.
-            let ch = s.utf16_char(agent, usize::try_from(k).unwrap());
-            Ok(SmallString::from_code_point(ch).into_value())
+            let cu = s.utf16_code_unit(agent, usize::try_from(k).unwrap());
+            Ok(String::from_code_unit(agent, cu).into_value())
         }
     }
 
@@ -444,7 +443,7 @@ impl StringPrototype {
     fn char_at<'gc>(
         agent: &mut Agent,
         this_value: Value,
-        args: ArgumentsList,
+        mut args: ArgumentsList,
         mut gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
         let nogc = gc.nogc();
@@ -454,20 +453,14 @@ impl StringPrototype {
             if let (Ok(s), Value::Integer(position)) = (String::try_from(this_value), pos) {
                 (s, position.into_i64())
             } else {
-                let pos = pos.scope(agent, nogc);
-                // 1. Let O be ? RequireObjectCoercible(this value).
-                let o = require_object_coercible(agent, this_value, nogc)
-                    .unbind()?
-                    .bind(nogc);
-                // 2. Let S be ? ToString(O).
-                let s = to_string(agent, o.unbind(), gc.reborrow())
-                    .unbind()?
-                    .scope(agent, gc.nogc());
-                // 3. Let position be ? ToIntegerOrInfinity(pos).
-                let position = to_integer_or_infinity(agent, pos.get(agent), gc.reborrow())
-                    .unbind()?
-                    .into_i64();
-                (s.get(agent).bind(gc.nogc()), position)
+                coerce_arguments!(agent, this_value, args, gc => {
+                    // 1. Let O be ? RequireObjectCoercible(this value).
+                    // 2. Let S be ? ToString(O).
+                    this s = to_string_this("String.prototype.charAt");
+                    // 3. Let position be ? ToIntegerOrInfinity(pos).
+                    let position = to_integer_or_infinity_arg(0);
+                });
+                (s, position.into_i64())
             };
         // 4. Let size be the length of S.
         let size = s.utf16_len(agent);
@@ -476,8 +469,8 @@ impl StringPrototype {
             Ok(String::EMPTY_STRING.into_value())
         } else {
             // 6. Return the substring of S from position to position + 1.
-            let ch = s.utf16_char(agent, usize::try_from(position).unwrap());
-            Ok(SmallString::from_code_point(ch).into_value())
+            let cu = s.utf16_code_unit(agent, usize::try_from(position).unwrap());
+            Ok(String::from_code_unit(agent, cu).into_value())
         }
     }
 
@@ -485,7 +478,7 @@ impl StringPrototype {
     fn char_code_at<'gc>(
         agent: &mut Agent,
         this_value: Value,
-        args: ArgumentsList,
+        mut args: ArgumentsList,
         mut gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
         let nogc = gc.nogc();
@@ -495,20 +488,14 @@ impl StringPrototype {
             if let (Ok(s), Value::Integer(position)) = (String::try_from(this_value), pos) {
                 (s, position.into_i64())
             } else {
-                let pos = pos.scope(agent, nogc);
-                // 1. Let O be ? RequireObjectCoercible(this value).
-                let o = require_object_coercible(agent, this_value, nogc)
-                    .unbind()?
-                    .bind(nogc);
-                // 2. Let S be ? ToString(O).
-                let s = to_string(agent, o.unbind(), gc.reborrow())
-                    .unbind()?
-                    .scope(agent, gc.nogc());
-                // 3. Let position be ? ToIntegerOrInfinity(pos).
-                let position = to_integer_or_infinity(agent, pos.get(agent), gc.reborrow())
-                    .unbind()?
-                    .into_i64();
-                (s.get(agent).bind(gc.nogc()), position)
+                coerce_arguments!(agent, this_value, args, gc => {
+                    // 1. Let O be ? RequireObjectCoercible(this value).
+                    // 2. Let S be ? ToString(O).
+                    this s = to_string_this("String.prototype.charCodeAt");
+                    // 3. Let position be ? ToIntegerOrInfinity(pos).
+                    let position = to_integer_or_infinity_arg(0);
+                });
+                (s, position.into_i64())
             };
         // 4. Let size be the length of S.
         let size = s.utf16_len(agent);
@@ -518,8 +505,8 @@ impl StringPrototype {
         } else {
             // 6. Return the Number value for the numeric value of the code unit at index position
             // within the String S.
-            let ch = s.utf16_char(agent, usize::try_from(position).unwrap());
-            Ok(Value::from(ch as u32))
+            let cu = s.utf16_code_unit(agent, usize::try_from(position).unwrap());
+            Ok(Value::from(cu))
         }
     }
 
@@ -527,7 +514,7 @@ impl StringPrototype {
     fn code_point_at<'gc>(
         agent: &mut Agent,
         this_value: Value,
-        args: ArgumentsList,
+        mut args: ArgumentsList,
         mut gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
         let nogc = gc.nogc();
@@ -537,20 +524,14 @@ impl StringPrototype {
             if let (Ok(s), Value::Integer(position)) = (String::try_from(this_value), pos) {
                 (s, position.into_i64())
             } else {
-                let pos = pos.scope(agent, nogc);
-                // 1. Let O be ? RequireObjectCoercible(this value).
-                let o = require_object_coercible(agent, this_value, nogc)
-                    .unbind()?
-                    .bind(nogc);
-                // 2. Let S be ? ToString(O).
-                let s = to_string(agent, o.unbind(), gc.reborrow())
-                    .unbind()?
-                    .scope(agent, gc.nogc());
-                // 3. Let position be ? ToIntegerOrInfinity(pos).
-                let position = to_integer_or_infinity(agent, pos.get(agent), gc.reborrow())
-                    .unbind()?
-                    .into_i64();
-                (s.get(agent).bind(gc.nogc()), position)
+                coerce_arguments!(agent, this_value, args, gc => {
+                    // 1. Let O be ? RequireObjectCoercible(this value).
+                    // 2. Let S be ? ToString(O).
+                    this s = to_string_this("String.prototype.codePointAt");
+                    // 3. Let position be ? ToIntegerOrInfinity(pos).
+                    let position = to_integer_or_infinity_arg(0);
+                });
+                (s, position.into_i64())
             };
         // 4. Let size be the length of S.
         let size = s.utf16_len(agent);
@@ -560,12 +541,8 @@ impl StringPrototype {
         } else {
             // 6. Let cp be CodePointAt(S, position).
             // 7. Return 𝔽(cp.[[CodePoint]]).
-            // TODO: Deal with lone surrogates.
-            let u8_idx = s
-                .utf8_index(agent, usize::try_from(position).unwrap())
-                .unwrap();
-            let ch = s.as_str(agent)[u8_idx..].chars().next().unwrap();
-            Ok(Value::from(ch as u32))
+            let (cp, _) = s.code_point_at(agent, usize::try_from(position).unwrap());
+            Ok(Value::from(cp.to_u32()))
         }
     }
 
@@ -612,7 +589,7 @@ impl StringPrototype {
                     .into_iter()
                     .map(|arg| arg.get(agent).bind(nogc))
                     .collect::<Vec<_>>();
-                return Ok(String::concat(agent, &string_args, nogc).into_value());
+                return Ok(String::concat_or_throw(agent, &string_args, nogc)?.into_value());
             }
         };
         // 3. Let R be S.
@@ -650,7 +627,7 @@ impl StringPrototype {
         //     a. Let nextString be ? ToString(next).
         //     b. Set R to the string-concatenation of R and nextString.
         // 5. Return R.
-        Ok(String::concat(agent, &strings, nogc).into_value())
+        Ok(String::concat_or_throw(agent, &strings, nogc)?.into_value())
     }
 
     fn ends_with<'gc>(
@@ -691,11 +668,7 @@ impl StringPrototype {
             // 3. Let isRegExp be ? IsRegExp(searchString).
             // 4. If isRegExp is true, throw a TypeError exception.
             if is_reg_exp(agent, search_string.get(agent), gc.reborrow()).unbind()? {
-                return Err(agent.throw_exception_with_static_message(
-                    ExceptionType::TypeError,
-                    "searchString is RegExp",
-                    gc.into_nogc(),
-                ));
+                return agent.type_error("searchString is RegExp", gc.into_nogc());
             }
 
             // 5. Let searchStr be ? ToString(searchString).
@@ -729,19 +702,7 @@ impl StringPrototype {
 
         // 6. Let len be the length of S.
         // 8. Let end be the result of clamping pos between 0 and len.
-        let haystack_str = if pos == usize::MAX {
-            s.as_str(agent)
-        } else {
-            let end = if pos != 0 {
-                // NOTE: `pos` was already clamped to 0.
-                pos.min(s.utf16_len(agent))
-            } else {
-                0
-            };
-            let u8_idx = s.utf8_index(agent, end).unwrap();
-            &s.as_str(agent)[..u8_idx]
-        };
-
+        // NOTE: `pos` was already clamped to 0.
         // 9. Let searchLength be the length of searchStr.
         // 10. If searchLength = 0, return true.
         // 11. Let start be end - searchLength.
@@ -749,9 +710,7 @@ impl StringPrototype {
         // 13. Let substring be the substring of S from start to end.
         // 14. If substring is searchStr, return true.
         // 15. Return false.
-        Ok(Value::from(
-            haystack_str.ends_with(search_str.as_str(agent)),
-        ))
+        Ok(Value::from(s.ends_with_at(agent, search_str, pos)))
     }
 
     fn includes<'gc>(
@@ -792,11 +751,7 @@ impl StringPrototype {
             // 3. Let isRegExp be ? IsRegExp(searchString).
             // 4. If isRegExp is true, throw a TypeError exception.
             if is_reg_exp(agent, search_string.get(agent), gc.reborrow()).unbind()? {
-                return Err(agent.throw_exception_with_static_message(
-                    ExceptionType::TypeError,
-                    "searchString is RegExp",
-                    gc.into_nogc(),
-                ));
+                return agent.type_error("searchString is RegExp", gc.into_nogc());
             }
 
             // 5. Let searchStr be ? ToString(searchString).
@@ -829,27 +784,20 @@ impl StringPrototype {
 
         // 8. Let len be the length of S.
         // 9. Let start be the result of clamping pos between 0 and len.
-        let haystack_str = {
-            let start = if pos != 0 {
-                // NOTE: `pos` was already clamped to 0.
-                pos.min(s.utf16_len(agent))
-            } else {
-                0
-            };
-            &s.as_str(agent)[start..]
-        };
+        // NOTE: `pos` was already clamped to 0.
+        let start = pos.min(s.utf16_len(agent));
 
         // 10. Let index be StringIndexOf(S, searchStr, start).
         // 11. If index is not-found, return false.
         // 12. Return true.
-        Ok(Value::from(haystack_str.contains(search_str.as_str(agent))))
+        Ok(Value::from(s.index_of(agent, search_str, start).is_some()))
     }
 
     /// ### [22.1.3.9 String.prototype.indexOf ( searchString \[ , position \] )](https://tc39.es/ecma262/#sec-string.prototype.indexof)
     fn index_of<'gc>(
         agent: &mut Agent,
         this_value: Value,
-        args: ArgumentsList,
+        mut args: ArgumentsList,
         mut gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
         let nogc = gc.nogc();
@@ -870,56 +818,28 @@ impl StringPrototype {
         ) {
             (s, search_str, position.into_i64().max(0) as usize)
         } else {
-            let search_string = search_string.scope(agent, nogc);
-            let position = position.scope(agent, nogc);
-            // 1. Let O be ? RequireObjectCoercible(this value).
-            let o = require_object_coercible(agent, this_value, nogc)
-                .unbind()?
-                .bind(nogc);
-            // 2. Let S be ? ToString(O).
-            let s = to_string(agent, o.unbind(), gc.reborrow())
-                .unbind()?
-                .scope(agent, gc.nogc());
-            // 3. Let searchStr be ? ToString(searchString).
-            let search_str = to_string(agent, search_string.get(agent), gc.reborrow())
-                .unbind()?
-                .bind(gc.nogc());
-            // SAFETY: search_str is not shared.
-            let search_str = unsafe { search_string.replace_self(agent, search_str.unbind()) };
-            // 4. Let pos be ? ToIntegerOrInfinity(position).
-            // 5. Assert: If position is undefined, then pos is 0.
-            let pos = to_integer_or_infinity(agent, position.get(agent), gc.reborrow())
-                .unbind()?
-                .into_i64()
-                .max(0) as usize;
-
-            let nogc = gc.nogc();
-            (
-                s.get(agent).bind(nogc),
-                search_str.get(agent).bind(nogc),
-                pos,
-            )
+            coerce_arguments!(agent, this_value, args, gc => {
+                // 1. Let O be ? RequireObjectCoercible(this value).
+                // 2. Let S be ? ToString(O).
+                this s = to_string_this("String.prototype.indexOf");
+                // 3. Let searchStr be ? ToString(searchString).
+                let search_str = to_string_arg(0);
+                // 4. Let pos be ? ToIntegerOrInfinity(position).
+                // 5. Assert: If position is undefined, then pos is 0.
+                let pos = to_integer_or_infinity_arg(1);
+            });
+            (s, search_str, pos.into_i64().max(0) as usize)
         };
 
         // 6. Let len be the length of S.
         // 7. Let start be the result of clamping pos between 0 and len.
-        let utf8_start = if pos != 0 {
-            let u16_len = s.utf16_len(agent);
-            if pos >= u16_len {
-                s.len(agent)
-            } else {
-                s.utf8_index(agent, pos).unwrap()
-            }
-        } else {
-            0
-        };
+        // NOTE: `pos` was already clamped to 0.
+        let start = pos.min(s.utf16_len(agent));
 
         // 8. Let result be StringIndexOf(S, searchStr, start).
         // 9. If result is not-found, return -1𝔽.
         // 10. Return 𝔽(result).
-        if let Some(rel_u8_pos) = s.as_str(agent)[utf8_start..].find(search_str.as_str(agent)) {
-            let u8_pos = utf8_start + rel_u8_pos;
-            let result = s.utf16_index(agent, u8_pos);
+        if let Some(result) = s.index_of(agent, search_str, start) {
             Ok(Number::try_from(result).unwrap().into_value())
         } else {
             Ok(Number::from(-1).into_value())
@@ -941,10 +861,7 @@ impl StringPrototype {
         let s = to_string(agent, o.unbind(), gc)?;
 
         // 3. Return IsStringWellFormedUnicode(S).
-        // TODO: For now, all strings are well-formed Unicode. In the future, `.as_str()` will
-        // return None for WTF-8 strings.
-        let _: &str = s.as_str(agent);
-        Ok(Value::Boolean(true))
+        Ok(Value::Boolean(s.as_wtf8(agent).as_str().is_some()))
     }
 
     /// ### [22.1.3.11 String.prototype.lastIndexOf ( searchString \[ , position \] )]()
@@ -960,7 +877,7 @@ impl StringPrototype {
     fn last_index_of<'gc>(
         agent: &mut Agent,
         this_value: Value,
-        args: ArgumentsList,
+        mut args: ArgumentsList,
         mut gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
         let nogc = gc.nogc();
@@ -981,75 +898,40 @@ impl StringPrototype {
         ) {
             (s, search_str, position.into_i64().max(0) as usize)
         } else {
-            let search_string = search_string.scope(agent, nogc);
-            let position = position.scope(agent, nogc);
-            // 1. Let O be ? RequireObjectCoercible(this value).
-            let o = require_object_coercible(agent, this_value, nogc)
-                .unbind()?
-                .bind(nogc);
-            // 2. Let S be ? ToString(O).
-            let s = to_string(agent, o.unbind(), gc.reborrow())
-                .unbind()?
-                .scope(agent, gc.nogc());
-            // 3. Let searchStr be ? ToString(searchString).
-            let mut search_str = to_string(agent, search_string.get(agent), gc.reborrow())
-                .unbind()?
-                .bind(gc.nogc());
-
-            let position = position.get(agent).bind(gc.nogc());
-            let pos = if position.is_undefined() {
+            coerce_arguments!(agent, this_value, args, gc => {
+                // 1. Let O be ? RequireObjectCoercible(this value).
+                // 2. Let S be ? ToString(O).
+                this s = to_string_this("String.prototype.lastIndexOf");
+                // 3. Let searchStr be ? ToString(searchString).
+                let search_str = to_string_arg(0);
+                // 4. Let numPos be ? ToNumber(position).
                 // 5. Assert: If position is undefined, then numPos is NaN.
+                let num_pos = to_number_arg(1);
+            });
+            let pos = if num_pos.is_nan(agent) {
                 // 6. If numPos is NaN, let pos be +∞;
                 usize::MAX
-            } else if let Value::Integer(position) = position {
-                position.into_i64().max(0) as usize
             } else {
-                // 4. Let numPos be ? ToNumber(position).
-                let scoped_search_str = search_str.scope(agent, gc.nogc());
-                let num_pos = to_number(agent, position.unbind(), gc.reborrow())
-                    .unbind()?
-                    .bind(gc.nogc());
-                search_str = scoped_search_str.get(agent).bind(gc.nogc());
-                if num_pos.is_nan(agent) {
-                    // 6. If numPos is NaN, let pos be +∞;
-                    usize::MAX
-                } else {
-                    // otherwise, let pos be! ToIntegerOrInfinity(numPos).
-                    to_integer_or_infinity_number(agent, num_pos)
-                        .into_i64()
-                        .max(0) as usize
-                }
+                // otherwise, let pos be! ToIntegerOrInfinity(numPos).
+                to_integer_or_infinity_number(agent, num_pos)
+                    .into_i64()
+                    .max(0) as usize
             };
 
-            (s.get(agent).bind(gc.nogc()), search_str, pos)
+            (s, search_str, pos)
         };
 
         // 7. Let len be the length of S.
         // 8. Let searchLen be the length of searchStr.
         // 9. Let start be the result of clamping pos between 0 and len - searchLen.
         // 10. Let result be StringLastIndexOf(S, searchStr, start).
-        // NOTE: If start >= (len - searchLen), there is a last index if and
-        // only if start.endsWith(searchLen).
-        let haystack_str = {
-            if pos == usize::MAX {
-                s.as_str(agent)
-            } else {
-                // When starting from a position, the position may mark the
-                // start of the search string, so we need to include the search
-                // string length in the haystack.
-                let utf8_pos = s.utf8_index(agent, pos).unwrap();
-                let utf8_len = s.len(agent);
-                let search_str_len = search_str.len(agent);
-                &s.as_str(agent)[..utf8_len.min(utf8_pos + search_str_len)]
-            }
-        };
-        let search_str = search_str.as_str(agent);
-        let utf8_result = haystack_str.rfind(search_str);
+        // NOTE: StringLastIndexOf clamps the start position and returns
+        // not-found if searchStr is longer than S.
+        let result = s.last_index_of(agent, search_str, pos);
 
         // 11. If result is not-found, return -1𝔽.
         // 12. Return 𝔽(result).
-        if let Some(utf8_idx) = utf8_result {
-            let result = s.utf16_index(agent, utf8_idx);
+        if let Some(result) = result {
             Ok(Number::try_from(result).unwrap().into_value())
         } else {
             Ok(Number::from(-1).into_value())
@@ -1074,13 +956,110 @@ impl StringPrototype {
         Err(agent.todo("String.prototype.match", gc.into_nogc()))
     }
 
+    /// ### [22.1.3.14 String.prototype.matchAll ( regexp )](https://tc39.es/ecma262/#sec-string.prototype.matchall)
+    ///
+    /// This method performs a regular expression match of the String
+    /// representing the this value against regexp and returns an iterator
+    /// that yields match results.
     fn match_all<'gc>(
         agent: &mut Agent,
-        _this_value: Value,
-        _: ArgumentsList,
-        gc: GcScope<'gc, '_>,
+        this_value: Value,
+        arguments: ArgumentsList,
+        mut gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
-        Err(agent.todo("String.prototype.matchAll", gc.into_nogc()))
+        let nogc = gc.nogc();
+        let this_value = this_value.bind(nogc);
+        let regexp = arguments.get(0).scope(agent, nogc);
+        // 1. Let O be ? RequireObjectCoercible(this value).
+        let o = require_object_coercible(agent, this_value, nogc)
+            .unbind()?
+            .scope(agent, nogc);
+        // 2. If regexp is neither undefined nor null, then
+        if !regexp.get(agent).is_undefined() && !regexp.get(agent).is_null() {
+            // a. Let isRegExp be ? IsRegExp(regexp).
+            // b. If isRegExp is true, then
+            if is_reg_exp(agent, regexp.get(agent), gc.reborrow()).unbind()? {
+                // i. Let flags be ? Get(regexp, "flags").
+                let flags = get(
+                    agent,
+                    Object::try_from(regexp.get(agent)).unwrap(),
+                    BUILTIN_STRING_MEMORY.flags.into(),
+                    gc.reborrow(),
+                )
+                .unbind()?
+                .bind(gc.nogc());
+                // ii. Perform ? RequireObjectCoercible(flags).
+                let flags = require_object_coercible(agent, flags, gc.nogc())
+                    .unbind()?
+                    .bind(gc.nogc());
+                // iii. If ? ToString(flags) does not contain "g", throw a
+                //      TypeError exception.
+                let flags = to_string(agent, flags.unbind(), gc.reborrow())
+                    .unbind()?
+                    .bind(gc.nogc());
+                if !flags
+                    .as_wtf8(agent)
+                    .code_points()
+                    .any(|c| c.to_u32() == u32::from(b'g'))
+                {
+                    return agent.type_error(
+                        "String.prototype.matchAll called with a non-global RegExp",
+                        gc.into_nogc(),
+                    );
+                }
+            }
+            // c. Let matcher be ? GetMethod(regexp, %Symbol.matchAll%).
+            let matcher = get_method(
+                agent,
+                regexp.get(agent),
+                WellKnownSymbolIndexes::MatchAll.into(),
+                gc.reborrow(),
+            )
+            .unbind()?
+            .bind(gc.nogc());
+            // d. If matcher is not undefined, then
+            if let Some(matcher) = matcher {
+                // i. Return ? Call(matcher, regexp, « O »).
+                return call_function(
+                    agent,
+                    matcher.unbind(),
+                    regexp.get(agent),
+                    Some(ArgumentsList::from_mut_value(&mut o.get(agent))),
+                    gc,
+                );
+            }
+        }
+        // 3. Let S be ? ToString(O).
+        let s = to_string(agent, o.get(agent), gc.reborrow())
+            .unbind()?
+            .scope(agent, gc.nogc());
+        #[cfg(feature = "regexp")]
+        {
+            // 4. Let rx be ? RegExpCreate(regexp, "g").
+            let rx = reg_exp_create(
+                agent,
+                regexp.get(agent),
+                oxc_ast::ast::RegExpFlags::G,
+                gc.reborrow(),
+            )
+            .unbind()?
+            .bind(gc.nogc());
+            // 5. Return ? Invoke(rx, %Symbol.matchAll%, « S »).
+            invoke(
+                agent,
+                rx.into_value().unbind(),
+                WellKnownSymbolIndexes::MatchAll.into(),
+                Some(ArgumentsList::from_mut_value(
+                    &mut s.get(agent).into_value(),
+                )),
+                gc,
+            )
+        }
+        #[cfg(not(feature = "regexp"))]
+        {
+            let _ = s;
+            Err(agent.todo("String.prototype.matchAll without RegExp", gc.into_nogc()))
+        }
     }
 
     /// ### [22.1.3.15 String.prototype.normalize ( \[ form \] )](https://tc39.es/ecma262/#sec-string.prototype.normalize)
@@ -1125,26 +1104,37 @@ impl StringPrototype {
                 s = scoped_s.get(agent).bind(gc.nogc());
                 f
             };
-            let form_result = NormalizeForm::from_str(f.as_str(agent));
+            let form_result = f
+                .as_wtf8(agent)
+                .as_str()
+                .ok_or(())
+                .and_then(NormalizeForm::from_str);
             match form_result {
                 Ok(form) => form,
                 // 5. If f is not one of "NFC", "NFD", "NFKC", or "NFKD", throw a RangeError exception.
                 Err(()) => {
-                    return Err(agent.throw_exception_with_static_message(
-                        ExceptionType::RangeError,
+                    return agent.range_error(
                         "The normalization form should be one of NFC, NFD, NFKC, NFKD.",
                         gc.into_nogc(),
-                    ));
+                    );
                 }
             }
         };
 
         // 6. Let ns be the String value that is the result of normalizing S into the normalization form named by f as specified in the latest Unicode Standard, Normalization Forms.
-        match unicode_normalize(s.as_str(agent), f) {
-            // 7. Return ns.
-            None => Ok(s.into_value().unbind()),
-            Some(ns) => Ok(Value::from_string(agent, ns, gc.into_nogc()).into_value()),
-        }
+        // NOTE: Lone surrogates are normalization boundaries and are kept as
+        // they are.
+        let s = s.unbind();
+        let gc = gc.into_nogc();
+        let s = s.bind(gc);
+        let ns = map_well_formed_parts(
+            agent,
+            s,
+            |part| unicode_normalize(part, f).map_or_else(|| part.to_owned(), |ns| ns),
+            gc,
+        );
+        // 7. Return ns.
+        Ok(ns.into_value())
     }
 
     /// ### [22.1.3.16 String.prototype.padEnd ( maxLength \[ , fillString \] )](https://tc39.es/ecma262/#sec-string.prototype.padend)
@@ -1205,53 +1195,26 @@ impl StringPrototype {
     fn repeat<'gc>(
         agent: &mut Agent,
         this_value: Value,
-        arguments: ArgumentsList,
+        mut arguments: ArgumentsList,
         mut gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
-        let nogc = gc.nogc();
-        let this_value = this_value.bind(nogc);
-        let count = arguments.get(0).scope(agent, nogc);
-
-        // 1. Let O be ? RequireObjectCoercible(this value).
-        let o = require_object_coercible(agent, this_value, nogc)
-            .unbind()?
-            .bind(nogc);
-
-        // 2. Let S be ? ToString(O).
-        let mut s = to_string(agent, o.unbind(), gc.reborrow())
-            .unbind()?
-            .bind(gc.nogc());
-
-        // 3. Let n be ? ToIntegerOrInfinity(count).
-        // SAFETY: count is not shared.
-        let count = unsafe { count.take(agent) };
-        let n = if let TryResult::Continue(n) = try_to_integer_or_infinity(agent, count, gc.nogc())
-        {
-            n.unbind()?
-        } else {
-            let scoped_s = s.scope(agent, gc.nogc());
-            let result = to_integer_or_infinity(agent, count, gc.reborrow()).unbind()?;
-            s = scoped_s.get(agent).bind(gc.nogc());
-            result
-        };
+        coerce_arguments!(agent, this_value, arguments, gc => {
+            // 1. Let O be ? RequireObjectCoercible(this value).
+            // 2. Let S be ? ToString(O).
+            this s = to_string_this("String.prototype.repeat");
+            // 3. Let n be ? ToIntegerOrInfinity(count).
+            let n = to_integer_or_infinity_arg(0);
+        });
 
         // 4. If n < 0 or n = +∞, throw a RangeError exception.
         if n.is_pos_infinity() {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::RangeError,
-                "count must be less than infinity",
-                gc.into_nogc(),
-            ));
+            return agent.range_error("count must be less than infinity", gc.into_nogc());
         }
 
         let n = n.into_i64();
 
         if n < 0 {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::RangeError,
-                "count must not be negative",
-                gc.into_nogc(),
-            ));
+            return agent.range_error("count must not be negative", gc.into_nogc());
         }
 
         // 5. If n = 0, return the empty String.
@@ -1263,12 +1226,15 @@ impl StringPrototype {
             return Ok(s.into_value().unbind());
         }
 
+        let Some(byte_length) = s.len(agent).checked_mul(n as usize) else {
+            return agent.range_error("Invalid string length", gc.into_nogc());
+        };
+        agent.check_string_length(byte_length, gc.nogc()).unbind()?;
+
         // 6. Return the String value that is made from n copies of S appended together.
-        Ok(Value::from_string(
-            agent,
-            s.as_str(agent).repeat(n as usize),
-            gc.into_nogc(),
-        ))
+        let mut result = HeapStringBuilder::with_capacity(byte_length);
+        result.push_repeated(agent, s, n as usize);
+        Ok(result.finish(agent, gc.into_nogc()).into_value())
     }
 
     /// ### [22.1.3.19 String.prototype.replace ( searchValue, replaceValue )](https://tc39.es/ecma262/multipage/text-processing.html#sec-string.prototype.replace)
@@ -1325,13 +1291,10 @@ impl StringPrototype {
         // 5. Let functionalReplace be IsCallable(replaceValue).
         if let Some(functional_replace) = is_callable(replace_value.get(agent), gc.nogc()) {
             // 7. Let searchLength be the length of searchString.
-            let search_length = search_string.len(agent);
+            let search_length = search_string.utf16_len(agent);
 
             // 8. Let position be StringIndexOf(s, searchString, 0).
-            let position = if let Some(position) = s.as_str(agent).find(search_string.as_str(agent))
-            {
-                position
-            } else {
+            let Some(position) = s.get(agent).index_of(agent, search_string, 0) else {
                 // 9. If position is not-found, return s.
                 return Ok(s.get(agent).into_value());
             };
@@ -1343,7 +1306,7 @@ impl StringPrototype {
                 Value::Undefined,
                 Some(ArgumentsList::from_mut_slice(&mut [
                     search_string.unbind().into_value(),
-                    Number::from(position as u32).into_value(),
+                    Number::try_from(position).unwrap().into_value(),
                     s.get(agent).into_value().unbind(),
                 ])),
                 gc.reborrow(),
@@ -1358,14 +1321,13 @@ impl StringPrototype {
             // 10. Let preceding be the substring of s from 0 to position.
             // 11. Let following be the substring of s from position + searchLength.
             // 12. If functionalReplace is true,
-            let preceding = &s.as_str(agent)[0..position].to_owned();
-            let following = &s.as_str(agent)[position + search_length..].to_owned();
-
             // 14. Return the string-concatenation of preceding, replacement, and following.
-            let concatenated_result = format!("{}{}{}", preceding, result.as_str(agent), following);
-            return Ok(
-                String::from_string(agent, concatenated_result, gc.into_nogc()).into_value(),
-            );
+            let result = result.unbind();
+            let gc = gc.into_nogc();
+            let result = result.bind(gc);
+            let s = s.get(agent).bind(gc);
+            return replace_at(agent, s, position, search_length, result, gc)
+                .map(String::into_value);
         }
 
         let search_string_root = search_string.scope(agent, gc.nogc());
@@ -1375,13 +1337,17 @@ impl StringPrototype {
             .unbind()?
             .bind(gc.nogc());
 
-        // Everything are strings: `"foo".replace("o", "a")` => use rust's replace
-        let result = s.as_str(agent).replacen(
-            search_string_root.as_str(agent),
-            replace_string.as_str(agent),
-            1,
-        );
-        Ok(String::from_string(agent, result, gc.into_nogc()).into_value())
+        // Everything are strings: `"foo".replace("o", "a")`
+        let replace_string = replace_string.unbind();
+        let gc = gc.into_nogc();
+        let replace_string = replace_string.bind(gc);
+        let s = s.get(agent).bind(gc);
+        let search_string = search_string_root.get(agent).bind(gc);
+        let Some(position) = s.index_of(agent, search_string, 0) else {
+            return Ok(s.into_value());
+        };
+        let search_length = search_string.utf16_len(agent);
+        replace_at(agent, s, position, search_length, replace_string, gc).map(String::into_value)
     }
 
     /// ### [22.1.3.20 String.prototype.replaceAll ( searchValue, replaceValue )](https://tc39.es/ecma262/multipage/text-processing.html#sec-string.prototype.replaceall)
@@ -1450,24 +1416,15 @@ impl StringPrototype {
         // 5. Let functionalReplace be IsCallable(replaceValue).
         if let Some(functional_replace) = is_callable(replace_value.get(agent), gc.nogc()) {
             // 7. Let searchLength be the length of searchString.
-            let search_length = search_string.len(agent);
+            let search_length = search_string.utf16_len(agent);
 
             // 8. Let advanceBy be max(1, searchLength).
-            let advance_by = max(1, search_length);
-
             // 9. Let matchPositions be a new empty List.
-            let mut match_positions: Vec<usize> = vec![];
-
             // 10. Let position be StringIndexOf(s, searchString, 0).
-            let search_str = search_string.as_str(agent);
-            let subject = s.as_str(agent).to_owned();
-            let mut position = 0;
-
             // 11. Repeat, while position is not not-found,
-            while let Some(pos) = subject[position..].find(search_str) {
-                match_positions.push(position + pos);
-                position += advance_by + pos;
-            }
+            //     a. Append position to matchPositions.
+            //     b. Set position to StringIndexOf(string, searchString, position + advanceBy).
+            let match_positions = match_positions(agent, s.get(agent), search_string);
 
             // If none has found, return s.
             if match_positions.is_empty() {
@@ -1478,7 +1435,7 @@ impl StringPrototype {
             let mut end_of_last_match = 0;
 
             // 13. Let result be the empty String.
-            let mut result = std::string::String::with_capacity(subject.len());
+            let mut result = HeapStringBuilder::with_capacity(s.get(agent).len(agent));
 
             // 14. For each element p of matchPositions, do
             let functional_replace = functional_replace.scope(agent, gc.nogc());
@@ -1490,7 +1447,7 @@ impl StringPrototype {
                     Value::Undefined,
                     Some(ArgumentsList::from_mut_slice(&mut [
                         search_string_root.get(agent).into_value(),
-                        Number::from(position as u32).into_value(),
+                        Number::try_from(p).unwrap().into_value(),
                         s.get(agent).into_value(),
                     ])),
                     gc.reborrow(),
@@ -1502,36 +1459,46 @@ impl StringPrototype {
                     .bind(gc.nogc());
 
                 // a. Let preserved be the substring of string from endOfLastMatch to p.
-                let preserved = &subject[end_of_last_match..p];
                 // d. Set result to the string-concatenation of result, preserved, and replacement.
-                let replacement_str = replacement.as_str(agent);
-                result.reserve(preserved.len() + replacement_str.len());
-                result.push_str(preserved);
-                result.push_str(replacement_str);
+                let s = s.get(agent).bind(gc.nogc());
+                result.push_substring(agent, s, end_of_last_match, p);
+                agent
+                    .check_string_length(result.len() + replacement.len(agent), gc.nogc())
+                    .unbind()?;
+                result.push_string(agent, replacement);
                 end_of_last_match = p + search_length;
             }
 
             // 15. If endOfLastMatch < the length of string, set result to the string-concatenation of result and the substring of string from endOfLastMatch.
-            if end_of_last_match < subject.len() {
-                let preserved = &subject[end_of_last_match..];
-                result.push_str(preserved);
-            }
+            let gc = gc.into_nogc();
+            let s = s.get(agent).bind(gc);
+            result.push_substring(agent, s, end_of_last_match, s.utf16_len(agent));
 
             // 16. Return result.
-            return Ok(String::from_string(agent, result, gc.into_nogc()).into_value());
+            return Ok(result.finish(agent, gc).into_value());
         }
 
         // 6. If functionalReplace is false, Set replaceValue to ? ToString(replaceValue).
         let replace_string = to_string(agent, replace_value.get(agent), gc.reborrow())
             .unbind()?
             .bind(gc.nogc());
-        // Everything are strings: `"foo".replaceAll("o", "a")` => use rust's replace
-        search_string = search_string_root.get(agent).bind(gc.nogc());
-        let s = s.get(agent).bind(gc.nogc());
-        let result = s
-            .as_str(agent)
-            .replace(search_string.as_str(agent), replace_string.as_str(agent));
-        Ok(String::from_string(agent, result, gc.into_nogc()).into_value())
+        // Everything are strings: `"foo".replaceAll("o", "a")`
+        let replace_string = replace_string.unbind();
+        let gc = gc.into_nogc();
+        let replace_string = replace_string.bind(gc);
+        search_string = search_string_root.get(agent).bind(gc);
+        let s = s.get(agent).bind(gc);
+        let search_length = search_string.utf16_len(agent);
+        let mut result = HeapStringBuilder::with_capacity(s.len(agent));
+        let mut end_of_last_match = 0;
+        for p in match_positions(agent, s, search_string) {
+            result.push_substring(agent, s, end_of_last_match, p);
+            agent.check_string_length(result.len() + replace_string.len(agent), gc)?;
+            result.push_string(agent, replace_string);
+            end_of_last_match = p + search_length;
+        }
+        result.push_substring(agent, s, end_of_last_match, s.utf16_len(agent));
+        Ok(result.finish(agent, gc).into_value())
     }
 
     fn search<'gc>(
@@ -1546,37 +1513,32 @@ impl StringPrototype {
     fn slice<'gc>(
         agent: &mut Agent,
         this_value: Value,
-        args: ArgumentsList,
+        mut args: ArgumentsList,
         mut gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
-        let nogc = gc.nogc();
-        let this_value = this_value.bind(nogc);
-        let start = args.get(0).scope(agent, nogc);
-        let end = args.get(1).scope(agent, nogc);
-        // 1. Let O be ? RequireObjectCoercible(this value).
-        let o = require_object_coercible(agent, this_value, nogc)
-            .unbind()?
-            .bind(nogc);
-        // 2. Let S be ? ToString(O).
-        let s = to_string(agent, o.unbind(), gc.reborrow())
-            .unbind()?
-            .scope(agent, gc.nogc());
-
-        // 3. Let len be the length of S.
-        // 4. Let intStart be ? ToIntegerOrInfinity(start).
-        let int_start = to_integer_or_infinity(agent, start.get(agent), gc.reborrow()).unbind()?;
+        coerce_arguments!(agent, this_value, args, gc => {
+            // 1. Let O be ? RequireObjectCoercible(this value).
+            // 2. Let S be ? ToString(O).
+            this s = to_string_this("String.prototype.slice");
+            // 3. Let len be the length of S.
+            // 4. Let intStart be ? ToIntegerOrInfinity(start).
+            let int_start = to_integer_or_infinity_arg(0);
+            // 8. If end is undefined, let intEnd be len; else let intEnd be
+            //    ? ToIntegerOrInfinity(end).
+            let int_end = to_optional_integer_or_infinity_arg(1);
+        });
         // 5. If intStart = -∞, let from be 0.
         // NOTE: We use `None` when `from` would be `len` in the spec.
         let from = if int_start.is_neg_infinity() {
             Some(0)
         } else if int_start.is_negative() {
             // 6. Else if intStart < 0, let from be max(len + intStart, 0).
-            let len = i64::try_from(s.get(agent).utf16_len(agent)).unwrap();
+            let len = i64::try_from(s.utf16_len(agent)).unwrap();
             let int_start = int_start.into_i64();
             Some((len + int_start).max(0) as usize)
         } else {
             // 7. Else, let from be min(intStart, len).
-            let len = s.get(agent).utf16_len(agent);
+            let len = s.utf16_len(agent);
             let int_start = int_start.into_i64() as usize;
             if int_start >= len {
                 None
@@ -1585,52 +1547,37 @@ impl StringPrototype {
             }
         };
 
-        // 8. If end is undefined, let intEnd be len; else let intEnd be ? ToIntegerOrInfinity(end).
         // NOTE: We use `None` when `to` would be `len` in the spec.
-        let end = end.get(agent).bind(gc.nogc());
-        let to = if end.is_undefined() {
-            None
-        } else {
-            let int_end = to_integer_or_infinity(agent, end.unbind(), gc.reborrow()).unbind()?;
+        let to = if let Some(int_end) = int_end {
             // 9. If intEnd = -∞, let to be 0.
             if int_end.is_neg_infinity() {
                 Some(0)
             } else if int_end.is_negative() {
                 // 10. Else if intEnd < 0, let to be max(len + intEnd, 0).
-                let len = i64::try_from(s.get(agent).utf16_len(agent)).unwrap();
+                let len = i64::try_from(s.utf16_len(agent)).unwrap();
                 let int_end = int_end.into_i64();
                 Some((len + int_end).max(0) as usize)
             } else {
                 // 11. Else, let to be min(intEnd, len).
-                let len = s.get(agent).utf16_len(agent);
+                let len = s.utf16_len(agent);
                 let int_end = int_end.into_i64() as usize;
                 if int_end >= len { None } else { Some(int_end) }
             }
+        } else {
+            None
         };
 
+        let s = s.unbind();
         let gc = gc.into_nogc();
-        let s = s.get(agent).bind(gc);
+        let s = s.bind(gc);
 
         // 12. If from ≥ to, return the empty String.
         // 13. Return the substring of S from from to to.
-        let substring = match (from, to) {
-            (None, _) => "",
-            (Some(0), None) => return Ok(s.into_value()),
-            (Some(from_idx), None) => {
-                let u8_from = s.utf8_index(agent, from_idx).unwrap();
-                &s.as_str(agent)[u8_from..]
-            }
-            (Some(from_idx), Some(to_idx)) if from_idx >= to_idx => "",
-            (Some(from_idx), Some(to_idx)) => {
-                let u8_from = s.utf8_index(agent, from_idx).unwrap();
-                let u8_to = s.utf8_index(agent, to_idx).unwrap();
-                &s.as_str(agent)[u8_from..u8_to]
-            }
+        let Some(from) = from else {
+            return Ok(String::EMPTY_STRING.into_value());
         };
-        // SAFETY: The memory for `substring` (and for the WTF-8 representation
-        // of `s`) won't be moved or deallocated before this function returns.
-        let substring: &'static str = unsafe { core::mem::transmute(substring) };
-        Ok(String::from_str(agent, substring, gc).into_value())
+        let to = to.unwrap_or_else(|| s.utf16_len(agent));
+        Ok(s.substring(agent, from, to, gc).into_value())
     }
 
     /// ### [22.1.3.23 String.prototype.split ( separator, limit )](https://tc39.es/ecma262/multipage/text-processing.html#sec-string.prototype.split)
@@ -1708,26 +1655,19 @@ impl StringPrototype {
         }
 
         // 8. Let separatorLength be the length of R.
-        let separator_length = r.len(agent);
+        let separator_length = r.utf16_len(agent);
 
-        // 9. If separatorLength = 0, then split into characters
+        // 9. If separatorLength = 0, then
         if separator_length == 0 {
-            let subject = s.as_str(agent);
-            let head = subject.split("");
-
-            let mut results: Vec<Value> = head
-                .enumerate()
-                .skip(1) // Rust's split inserts an empty string in the beginning.
-                .take_while(|(i, _)| *i <= lim as usize)
-                .map(|(_, part)| SmallString::try_from(part).unwrap().into_value())
-                .collect();
-
-            // Remove the latest empty string if it's needed
-            if results.len() < lim as usize {
-                results.pop();
-            }
-
-            let results = Array::from_slice(agent, results.as_slice(), gc);
+            // a. Let head be the substring of S from 0 to lim.
+            // b. Let codeUnits be a List consisting of the sequence of code
+            //    units that are the elements of head.
+            let head_length = s.utf16_len(agent).min(lim as usize);
+            let code_units = (0..head_length)
+                .map(|i| s.substring(agent, i, i + 1, gc).into_value())
+                .collect::<Vec<_>>();
+            // c. Return CreateArrayFromList(codeUnits).
+            let results = Array::from_slice(agent, code_units.as_slice(), gc);
             return Ok(results.into_value());
         }
 
@@ -1738,20 +1678,33 @@ impl StringPrototype {
             return Ok(create_array_from_list(agent, &list, gc).into_value());
         }
 
-        // 11-17. Normal split
-        let subject = s.as_str(agent).to_owned();
-        let separator = r.as_str(agent).to_owned();
-        let head = subject.split(&separator);
-        let mut results: Vec<Value> = Vec::new();
-
-        for (i, part) in head.enumerate() {
-            if lim as usize == i {
-                break;
+        // 11. Let substrings be a new empty List.
+        let mut substrings: Vec<Value> = Vec::new();
+        // 12. Let i be 0.
+        let mut i = 0;
+        // 13. Let j be StringIndexOf(S, R, 0).
+        let mut j = s.index_of(agent, r, 0);
+        // 14. Repeat, while j is not not-found,
+        while let Some(position) = j {
+            // a. Let T be the substring of S from i to j.
+            // b. Append T to substrings.
+            substrings.push(s.substring(agent, i, position, gc).into_value());
+            // c. If the number of elements in substrings is lim, return
+            //    CreateArrayFromList(substrings).
+            if substrings.len() == lim as usize {
+                let results = Array::from_slice(agent, substrings.as_slice(), gc);
+                return Ok(results.into_value());
             }
-            results.push(Value::from_str(agent, part, gc));
+            // d. Set i to j + separatorLength.
+            i = position + separator_length;
+            // e. Set j to StringIndexOf(S, R, i).
+            j = s.index_of(agent, r, i);
         }
-
-        let results = Array::from_slice(agent, results.as_slice(), gc);
+        // 15. Let T be the substring of S from i.
+        // 16. Append T to substrings.
+        substrings.push(s.substring(agent, i, s.utf16_len(agent), gc).into_value());
+        // 17. Return CreateArrayFromList(substrings).
+        let results = Array::from_slice(agent, substrings.as_slice(), gc);
         Ok(results.into_value())
     }
 
@@ -1797,11 +1750,7 @@ impl StringPrototype {
             // 3. Let isRegExp be ? IsRegExp(searchString).
             // 4. If isRegExp is true, throw a TypeError exception.
             if is_reg_exp(agent, search_string.get(agent), gc.reborrow()).unbind()? {
-                return Err(agent.throw_exception_with_static_message(
-                    ExceptionType::TypeError,
-                    "searchString is RegExp",
-                    gc.into_nogc(),
-                ));
+                return agent.type_error("searchString is RegExp", gc.into_nogc());
             }
 
             // 5. Let searchStr be ? ToString(searchString).
@@ -1833,90 +1782,58 @@ impl StringPrototype {
 
         // 9. Let searchLength be the length of searchStr.
         // 10. If searchLength = 0, return true.
-        if search_str.len(agent) == 0 {
-            return Ok(true.into());
-        }
         // 11. Let end be start + searchLength.
         // 12. If end > len, return false.
         // 13. Let substring be the substring of S from start to end.
         // 14. If substring is searchStr, return true.
         // 15. Return false.
-        let haystack_str = if start == 0 {
-            s.as_str(agent)
-        } else {
-            let len = s.utf16_len(agent);
-            if start >= len {
-                ""
-            } else {
-                let start = s.utf8_index(agent, start).unwrap();
-                &s.as_str(agent)[start..]
-            }
-        };
-        Ok(haystack_str.starts_with(search_str.as_str(agent)).into())
+        Ok(s.starts_with_at(agent, search_str, start).into())
     }
 
     fn substring<'gc>(
         agent: &mut Agent,
         this_value: Value,
-        args: ArgumentsList,
+        mut args: ArgumentsList,
         mut gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
         let nogc = gc.nogc();
         let this_value = this_value.bind(nogc);
-        let start = args.get(0).scope(agent, nogc);
-        let end = args.get(1).scope(agent, nogc);
-        let mut s = if let Ok(s) = String::try_from(this_value) {
-            s.bind(nogc)
-        } else {
-            // 1. Let O be ? RequireObjectCoercible(this value).
-            let o = require_object_coercible(agent, this_value, nogc)
-                .unbind()?
-                .bind(nogc);
-            // 2. Let S be ? ToString(O).
-            to_string(agent, o.unbind(), gc.reborrow())
-                .unbind()?
-                .bind(gc.nogc())
-        };
-
-        let mut scoped_s = None;
-
-        // 3. Let len be the length of S.
-        // 4. Let intStart be ? ToIntegerOrInfinity(start).
-        // SAFETY: Never shared.
-        let start = unsafe { start.take(agent) }.bind(gc.nogc());
-        let int_start = if let TryResult::Continue(int_start) =
-            try_to_integer_or_infinity(agent, start, gc.nogc())
-        {
-            int_start.unbind()?
-        } else {
-            let local_scoped_s = s.scope(agent, gc.nogc());
-            let int_start =
-                to_integer_or_infinity(agent, start.unbind(), gc.reborrow()).unbind()?;
-            s = local_scoped_s.get(agent).bind(gc.nogc());
-            scoped_s = Some(local_scoped_s);
-            int_start
+        let start = args.get(0).bind(nogc);
+        let end = args.get(1).bind(nogc);
+        let integers = match (String::try_from(this_value), start, end) {
+            (Ok(s), Value::Integer(start), Value::Undefined) => Some((s, start.into_i64(), None)),
+            (Ok(s), Value::Integer(start), Value::Integer(end)) => {
+                Some((s, start.into_i64(), Some(end.into_i64())))
+            }
+            _ => None,
         };
-        // 5. If end is undefined, let intEnd be len; else let intEnd be ? ToIntegerOrInfinity(end).
-        let end = end.get(agent).bind(gc.nogc());
-        let int_end = if end.is_undefined() {
-            None
-        } else if let TryResult::Continue(int_end) =
-            try_to_integer_or_infinity(agent, end, gc.nogc())
-        {
-            Some(int_end.unbind()?)
+        let (s, int_start, int_end) = if let Some(integers) = integers {
+            integers
         } else {
-            let local_scoped_s = scoped_s.unwrap_or_else(|| s.scope(agent, gc.nogc()));
-            let int_end = to_integer_or_infinity(agent, end.unbind(), gc.reborrow()).unbind()?;
-            s = local_scoped_s.get(agent).bind(gc.nogc());
-            Some(int_end)
+            coerce_arguments!(agent, this_value, args, gc => {
+                // 1. Let O be ? RequireObjectCoercible(this value).
+                // 2. Let S be ? ToString(O).
+                this s = to_string_this("String.prototype.substring");
+                // 3. Let len be the length of S.
+                // 4. Let intStart be ? ToIntegerOrInfinity(start).
+                let int_start = to_integer_or_infinity_arg(0);
+                // 5. If end is undefined, let intEnd be len; else let intEnd
+                //    be ? ToIntegerOrInfinity(end).
+                let int_end = to_optional_integer_or_infinity_arg(1);
+            });
+            (
+                s,
+                int_start.into_i64(),
+                int_end.map(|int_end| int_end.into_i64()),
+            )
         };
 
         // Fast path: can we return `s` without computing the UTF-16 length?
         // We can if int_start <= 0 and we know int_end must be >= len
         // (i.e. it's either None or is greater than the UTF-8 length).
-        if int_start.into_i64() <= 0
+        if int_start <= 0
             && int_end
-                .map(|int_end| int_end.into_i64() >= s.len(agent) as i64)
+                .map(|int_end| int_end >= s.len(agent) as i64)
                 .unwrap_or(true)
         {
             return Ok(s.into_value().unbind());
@@ -1924,10 +1841,10 @@ impl StringPrototype {
 
         let len = s.utf16_len(agent);
         // 6. Let finalStart be the result of clamping intStart between 0 and len.
-        let final_start = (int_start.into_i64().max(0) as usize).min(len);
+        let final_start = (int_start.max(0) as usize).min(len);
         // 7. Let finalEnd be the result of clamping intEnd between 0 and len.
         let final_end = if let Some(int_end) = int_end {
-            (int_end.into_i64().max(0) as usize).min(len)
+            (int_end.max(0) as usize).min(len)
         } else {
             len
         };
@@ -1938,21 +1855,9 @@ impl StringPrototype {
         let to = final_start.max(final_end);
 
         // 10. Return the substring of S from from to to.
-        let u8_from = if from != len {
-            s.utf8_index(agent, from).unwrap()
-        } else {
-            s.len(agent)
-        };
-        let u8_to = if to != len {
-            s.utf8_index(agent, to).unwrap()
-        } else {
-            s.len(agent)
-        };
-        let substring = &s.as_str(agent)[u8_from..u8_to];
-        // SAFETY: The memory for `substring` (and for the WTF-8 representation
-        // of `s`) won't be moved or deallocated before this function returns.
-        let substring: &'static str = unsafe { core::mem::transmute(substring) };
-        Ok(String::from_str(agent, substring, gc.into_nogc()).into_value())
+        let s = s.unbind();
+        let gc = gc.into_nogc();
+        Ok(s.substring(agent, from, to, gc).into_value())
     }
 
     /// ### [22.1.3.26 String.prototype.toLocaleLowerCase ( \[ reserved1 \[ , reserved2 \] \] )](https://tc39.es/ecma262/#sec-string.prototype.tolocalelowercase)
@@ -1975,8 +1880,10 @@ impl StringPrototype {
         // 4. Let lowerText be toLowercase(sText), according to the Unicode Default Case Conversion algorithm.
         // 5. Let L be [CodePointsToString](https://tc39.es/ecma262/#sec-codepointstostring)(lowerText).
         // 6. Return L.
-        let lower_case_string: std::string::String = s.as_str(agent).to_lowercase();
-        Ok(String::from_string(agent, lower_case_string, gc.into_nogc()).into_value())
+        let s = s.unbind();
+        let gc = gc.into_nogc();
+        let s = s.bind(gc);
+        Ok(map_well_formed_parts(agent, s, str::to_lowercase, gc).into_value())
     }
 
     /// ### [22.1.3.27 String.prototype.toLocaleUpperCase ( \[ reserved1 \[ , reserved2 \] \] )](https://tc39.es/ecma262/#sec-string.prototype.tolocaleuppercase)
@@ -1999,8 +1906,10 @@ impl StringPrototype {
         // 4. Let upperText be toUppercase(sText), according to the Unicode Default Case Conversion algorithm.
         // 5. Let L be [CodePointsToString](https://tc39.es/ecma262/#sec-codepointstostring)(upperText).
         // 6. Return L.
-        let upper_case_string = s.as_str(agent).to_uppercase();
-        Ok(String::from_string(agent, upper_case_string, gc.into_nogc()).into_value())
+        let s = s.unbind();
+        let gc = gc.into_nogc();
+        let s = s.bind(gc);
+        Ok(map_well_formed_parts(agent, s, str::to_uppercase, gc).into_value())
     }
 
     /// ### [22.1.3.28 String.prototype.toLowerCase ( )](https://tc39.es/ecma262/#sec-string.prototype.tolowercase)
@@ -2025,8 +1934,10 @@ impl StringPrototype {
         // 4. Let lowerText be toLowercase(sText), according to the Unicode Default Case Conversion algorithm.
         // 5. Let L be [CodePointsToString](https://tc39.es/ecma262/#sec-codepointstostring)(lowerText).
         // 6. Return L.
-        let lower_case_string = s.as_str(agent).to_lowercase();
-        Ok(String::from_string(agent, lower_case_string, gc.into_nogc()).into_value())
+        let s = s.unbind();
+        let gc = gc.into_nogc();
+        let s = s.bind(gc);
+        Ok(map_well_formed_parts(agent, s, str::to_lowercase, gc).into_value())
     }
 
     /// ### [22.1.3.30 String.prototype.toUpperCase ( )](https://tc39.es/ecma262/#sec-string.prototype.touppercase)
@@ -2051,8 +1962,10 @@ impl StringPrototype {
         // 4. Let upperText be toUppercase(sText), according to the Unicode Default Case Conversion algorithm.
         // 5. Let L be [CodePointsToString](https://tc39.es/ecma262/#sec-codepointstostring)(upperText).
         // 6. Return L.
-        let upper_case_string = s.as_str(agent).to_uppercase();
-        Ok(String::from_string(agent, upper_case_string, gc.into_nogc()).into_value())
+        let s = s.unbind();
+        let gc = gc.into_nogc();
+        let s = s.bind(gc);
+        Ok(map_well_formed_parts(agent, s, str::to_uppercase, gc).into_value())
     }
 
     /// ### [22.1.3.31 String.prototype.toWellFormed ( )](https://tc39.es/ecma262/#sec-string.prototype.towellformed)
@@ -2082,11 +1995,14 @@ impl StringPrototype {
         //         i. Set result to the string-concatenation of result and UTF16EncodeCodePoint(cp.[[CodePoint]]).
         //     d. Set k to k + cp.[[CodeUnitCount]].
         // 7. Return result.
-
-        // TODO: For now, all strings are well-formed Unicode. In the future, `.as_str()` will
-        // return None for WTF-8 strings.
-        let _: &str = s.as_str(agent);
-        Ok(s.into_value().unbind())
+        let s = s.unbind();
+        let gc = gc.into_nogc();
+        let s = s.bind(gc);
+        if s.as_wtf8(agent).as_str().is_some() {
+            return Ok(s.into_value());
+        }
+        let result = s.as_wtf8(agent).to_string_lossy().into_owned();
+        Ok(String::from_string(agent, result, gc).into_value())
     }
 
     /// ### [22.1.3.32 String.prototype.trim ( )](https://tc39.es/ecma262/#sec-string.prototype.trim)
@@ -2118,27 +2034,39 @@ impl StringPrototype {
         let gc = gc.into_nogc();
         let s = s.bind(gc);
 
-        let s_str = s.as_str(agent);
+        // NOTE: All white space code points are single code units, so the
+        // UTF-16 indices of the first and last non-white space code points
+        // can be found in a single pass over the code points of S.
+        let mut index = 0;
+        let mut content = None;
+        for code_point in s.as_wtf8(agent).code_points() {
+            let code_units = code_point.to_char().map_or(1, char::len_utf16);
+            if !code_point.to_char().is_some_and(is_trimmable_whitespace) {
+                let (start, _) = content.get_or_insert((index, index));
+                content = Some((*start, index + code_units));
+            }
+            index += code_units;
+        }
+        let (content_start, content_end) = content.unwrap_or((index, index));
 
-        let t = match trim_where {
+        let (start, end) = match trim_where {
             // 3. If where is start, then
             //   a. Let T be the String value that is a copy of S with leading white space removed.
-            TrimWhere::Start => s_str.trim_start_matches(is_trimmable_whitespace),
+            TrimWhere::Start => (content_start, index),
             // 4. Else if where is end, then
             //   a. Let T be the String value that is a copy of S with trailing white space removed.
-            TrimWhere::End => s_str.trim_end_matches(is_trimmable_whitespace),
+            TrimWhere::End => (0, content_end),
             // 5. Else,
             //   a. Assert: where is start+end.
             //   b. Let T be the String value that is a copy of S with both leading and trailing white space removed.
-            TrimWhere::StartAndEnd => s_str.trim_matches(is_trimmable_whitespace),
+            TrimWhere::StartAndEnd => (content_start, content_end),
         };
 
-        if t == s_str {
+        if start == 0 && end == index {
             // No need to allocate a String if the string was not trimmed
             Ok(s.into_value())
         } else {
-            let t = String::from_string(agent, t.to_string(), gc);
-            Ok(t.into_value())
+            Ok(s.substring(agent, start, end, gc).into_value())
         }
     }
 
@@ -2215,34 +2143,23 @@ impl StringPrototype {
     fn substr<'gc>(
         agent: &mut Agent,
         this_value: Value,
-        args: ArgumentsList,
+        mut args: ArgumentsList,
         mut gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
-        let nogc = gc.nogc();
-        let this_value = this_value.bind(nogc);
-        let start = args.get(0).scope(agent, nogc);
-        let length = args.get(1).scope(agent, nogc);
-
-        let s = if let Ok(s) = String::try_from(this_value) {
-            s
-        } else {
+        coerce_arguments!(agent, this_value, args, gc => {
             // 1. Let O be ? RequireObjectCoercible(this value).
-            let o = require_object_coercible(agent, this_value, nogc)
-                .unbind()?
-                .bind(nogc);
             // 2. Let S be ? ToString(O).
-            to_string(agent, o.unbind(), gc.reborrow())
-                .unbind()?
-                .bind(gc.nogc())
-        };
-        let scoped_s = s.scope(agent, gc.nogc());
+            this s = to_string_this("String.prototype.substr");
+            // 4. Let intStart be ? ToIntegerOrInfinity(start).
+            let int_start = to_integer_or_infinity_arg(0);
+            // 8. If length is undefined, let intLength be size; otherwise let
+            //    intLength be ? ToIntegerOrInfinity(length).
+            let int_length = to_optional_integer_or_infinity_arg(1);
+        });
 
         // 3. Let size be the length of S.
         let size = s.utf16_len(agent) as i64;
 
-        // 4. Let intStart be ? ToIntegerOrInfinity(start).
-        let int_start = to_integer_or_infinity(agent, start.get(agent), gc.reborrow()).unbind()?;
-
         // 5. If intStart = -∞, set intStart to 0.
         let int_start = if int_start.is_neg_infinity() {
             0
@@ -2254,14 +2171,7 @@ impl StringPrototype {
             int_start.into_i64().min(size)
         };
 
-        // 8. If length is undefined, let intLength be size; otherwise let intLength be ? ToIntegerOrInfinity(length).
-        let int_length = if length.get(agent).is_undefined() {
-            size
-        } else {
-            to_integer_or_infinity(agent, length.get(agent), gc.reborrow())
-                .unbind()?
-                .into_i64()
-        };
+        let int_length = int_length.map_or(size, |int_length| int_length.into_i64());
 
         // 9. Set intLength to the result of clamping intLength between 0 and size.
         let int_length = int_length.clamp(0, size);
@@ -2270,15 +2180,11 @@ impl StringPrototype {
         let int_end = (int_start + int_length).min(size);
 
         // 11. Return the substring of S from intStart to intEnd.
+        let s = s.unbind();
         let gc = gc.into_nogc();
-        let s = scoped_s.get(agent).bind(gc);
-        let s_str = s.as_str(agent);
-        Ok(String::from_string(
-            agent,
-            s_str[int_start as usize..int_end as usize].to_string(),
-            gc,
-        )
-        .into_value())
+        let s = s.bind(gc);
+        Ok(s.substring(agent, int_start as usize, int_end as usize, gc)
+            .into_value())
     }
 
     /// ### [B.2.2.2 String.prototype.anchor ( name )](https://tc39.es/ecma262/#sec-string.prototype.anchor)
@@ -2687,57 +2593,106 @@ fn string_pad<'gc>(
         return Ok(s.into());
     }
 
+    // Note: Each UTF-16 code unit takes at least one byte, so a result
+    // longer than the maximum string length can be rejected before the
+    // filler is built.
+    agent.check_string_length(max_len as usize, gc)?;
+
     // 4. Let fillLen be maxLength - stringLength.
-    let fill_len = max_len - string_len;
-    let fill_string_len = fill_string.utf16_len(agent) as i64;
+    let fill_len = (max_len - string_len) as usize;
+    let fill_string_len = fill_string.utf16_len(agent);
+    let fill_count = fill_len / fill_string_len;
+    // Note: The byte length of S and the repeated fillStrings is checked
+    // before anything is allocated; only the truncated part of fillString,
+    // which is no longer than fillString itself, is checked afterwards.
+    let repeated_len = fill_string.len(agent).saturating_mul(fill_count);
+    let len = s.len(agent).saturating_add(repeated_len);
+    agent.check_string_length(len, gc)?;
 
     // 5. Let truncatedStringFiller be the String value consisting of repeated concatenations of fillString truncated to length fillLen.
-    let mut strings = if fill_len == fill_string_len {
-        let mut vec = VecDeque::with_capacity(2);
-        vec.push_back(fill_string);
-        vec
-    } else if fill_len % fill_string_len == 0 {
-        let fill_count = (fill_len / fill_string_len) as usize;
-        let mut vec = VecDeque::with_capacity(fill_count + 1);
-        vec.extend(repeat_n(fill_string, fill_count));
-        vec
-    } else if fill_len < fill_string_len {
-        let mut vec = VecDeque::with_capacity(2);
-        let mut sub_string = vec![0; fill_len as usize];
-        for i in 0..fill_len {
-            fill_string
-                .utf16_char(agent, i as usize)
-                .encode_utf8(&mut sub_string[fill_string.utf8_index(agent, i as usize).unwrap()..]);
-        }
-        let sub_string = core::str::from_utf8(&sub_string).unwrap();
-        // let sub_string = &fill_string.as_str(agent)[..fill_len as usize];
-        vec.push_back(String::from_string(agent, sub_string.to_owned(), gc));
-        vec
-    } else {
-        let fill_count = (fill_len / fill_string_len) as usize;
-        let mut vec = VecDeque::with_capacity(fill_count + 2);
-        vec.extend(repeat_n(fill_string, fill_count));
-        let sub_string_len = (fill_len % fill_string_len) as usize;
-        let mut sub_string = vec![0; sub_string_len];
-        for i in 0..sub_string_len {
-            fill_string
-                .utf16_char(agent, i)
-                .encode_utf8(&mut sub_string[fill_string.utf8_index(agent, i).unwrap()..]);
-        }
-        let sub_string = core::str::from_utf8(&sub_string).unwrap();
-        vec.push_back(String::from_string(agent, sub_string.to_owned(), gc));
-        vec
-    };
-
     // 6. If placement is start, return the string-concatenation of truncatedStringFiller and S.
     // 7. Else, return the string-concatenation of S and truncatedStringFiller.
+    let mut result = HeapStringBuilder::with_capacity(len.saturating_add(fill_string.len(agent)));
+    if !placement_start {
+        result.push_string(agent, s);
+    }
+    result.push_repeated(agent, fill_string, fill_count);
+    result.push_substring(agent, fill_string, 0, fill_len % fill_string_len);
     if placement_start {
-        strings.push_back(s);
-    } else {
-        strings.push_front(s);
+        result.push_string(agent, s);
     }
+    agent.check_string_length(result.len(), gc)?;
+    Ok(result.finish(agent, gc).into_value())
+}
 
-    Ok(String::concat(agent, strings.into_iter().collect::<Vec<String>>(), gc).into_value())
+/// Returns the string-concatenation of the substring of `s` before
+/// `position`, `replacement`, and the substring of `s` from
+/// `position + search_length`, as in steps 10-14 of String.prototype.replace.
+fn replace_at<'gc>(
+    agent: &mut Agent,
+    s: String,
+    position: usize,
+    search_length: usize,
+    replacement: String,
+    gc: NoGcScope<'gc, '_>,
+) -> JsResult<'gc, String<'gc>> {
+    let byte_length = s.len(agent) + replacement.len(agent);
+    agent.check_string_length(byte_length, gc)?;
+    let mut result = HeapStringBuilder::with_capacity(byte_length);
+    result.push_substring(agent, s, 0, position);
+    result.push_string(agent, replacement);
+    result.push_substring(agent, s, position + search_length, s.utf16_len(agent));
+    Ok(result.finish(agent, gc))
+}
+
+/// Returns the positions of `search_string` in `s`, as in steps 8-11 of
+/// String.prototype.replaceAll.
+fn match_positions(agent: &Agent, s: String, search_string: String) -> Vec<usize> {
+    // 8. Let advanceBy be max(1, searchLength).
+    let advance_by = search_string.utf16_len(agent).max(1);
+    // 9. Let matchPositions be a new empty List.
+    let mut match_positions = Vec::new();
+    // 10. Let position be StringIndexOf(string, searchString, 0).
+    let mut position = s.index_of(agent, search_string, 0);
+    // 11. Repeat, while position is not not-found,
+    while let Some(p) = position {
+        // a. Append position to matchPositions.
+        match_positions.push(p);
+        // b. Set position to StringIndexOf(string, searchString, position + advanceBy).
+        position = s.index_of(agent, search_string, p + advance_by);
+    }
+    match_positions
+}
+
+/// Returns a String with each maximal well-formed part of `s` replaced by
+/// the result of `f`, keeping any lone surrogates between the parts.
+fn map_well_formed_parts<'gc>(
+    agent: &mut Agent,
+    s: String,
+    f: impl Fn(&str) -> std::string::String,
+    gc: NoGcScope<'gc, '_>,
+) -> String<'gc> {
+    if let Some(str) = s.as_wtf8(agent).as_str() {
+        let result = f(str);
+        if result == str {
+            return s.bind(gc);
+        }
+        return String::from_string(agent, result, gc);
+    }
+    let mut result = HeapStringBuilder::with_capacity(s.len(agent));
+    let mut part = std::string::String::new();
+    for code_point in s.as_wtf8(agent).code_points() {
+        match code_point.to_char() {
+            Some(char) => part.push(char),
+            None => {
+                result.push_str(&f(&part));
+                part.clear();
+                result.push_code_point(code_point);
+            }
+        }
+    }
+    result.push_str(&f(&part));
+    result.finish(agent, gc)
 }
 
 /// ### [22.1.3.17.3 ToZeroPaddedDecimalString ( n, minLength )](https://tc39.es/ecma262/#sec-tozeropaddeddecimalstring)
@@ -2782,11 +2737,7 @@ fn this_string_value<'gc>(
         }
         _ => {
             // 3. Throw a TypeError exception.
-            Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Not a string value",
-                gc,
-            ))
+            agent.type_error("Not a string value", gc)
         }
     }
 }
@@ -2857,21 +2808,45 @@ fn create_html<'gc>(
             v
         };
         // b. Let escapedV be the String value that is the same as V except that each occurrence of the code unit 0x0022 (QUOTATION MARK) in V has been replaced with the six code unit sequence "&quot;".
-        let escaped_v = v.as_str(agent).replace('"', "&quot;");
-
-        let s_str = s.as_str(agent);
-        Ok(String::from_string(
-            agent,
-            format!("<{tag} {attribute}=\"{escaped_v}\">{s_str}</{tag}>"),
-            gc.into_nogc(),
-        ))
+        let s = s.unbind();
+        let v = v.unbind();
+        let gc = gc.into_nogc();
+        let s = s.bind(gc);
+        let v = v.bind(gc);
+        let mut p = HeapStringBuilder::with_capacity(s.len(agent) + v.len(agent));
+        p.push_char('<');
+        p.push_str(tag);
+        p.push_char(' ');
+        p.push_str(attribute);
+        p.push_str("=\"");
+        for code_point in v.as_wtf8(agent).code_points() {
+            if code_point.to_char() == Some('"') {
+                p.push_str("&quot;");
+            } else {
+                p.push_code_point(code_point);
+            }
+        }
+        p.push_str("\">");
+        p.push_string(agent, s);
+        p.push_str("</");
+        p.push_str(tag);
+        p.push_char('>');
+        agent.check_string_length(p.len(), gc)?;
+        Ok(p.finish(agent, gc))
     } else {
-        let s_str = s.as_str(agent);
-        Ok(String::from_string(
-            agent,
-            format!("<{tag}>{s_str}</{tag}>"),
-            gc.into_nogc(),
-        ))
+        let s = s.unbind();
+        let gc = gc.into_nogc();
+        let s = s.bind(gc);
+        let mut p = HeapStringBuilder::with_capacity(s.len(agent));
+        p.push_char('<');
+        p.push_str(tag);
+        p.push_char('>');
+        p.push_string(agent, s);
+        p.push_str("</");
+        p.push_str(tag);
+        p.push_char('>');
+        agent.check_string_length(p.len(), gc)?;
+        Ok(p.finish(agent, gc))
     }
 }
 
@@ -2881,6 +2856,7 @@ enum TrimWhere {
     StartAndEnd,
 }
 
+#[derive(Clone, Copy)]
 enum NormalizeForm {
     Nfc,
     Nfd,
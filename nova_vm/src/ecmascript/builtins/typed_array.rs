@@ -307,6 +307,7 @@ impl Index<TypedArray<'_>> for Agent {
 
 impl IndexMut<TypedArray<'_>> for Agent {
     fn index_mut(&mut self, index: TypedArray) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.typed_arrays[index]
     }
 }
@@ -15,7 +15,8 @@ use crate::{
         rootable::HeapRootData,
     },
     heap::{
-        CompactionLists, CreateHeapData, HeapMarkAndSweep, HeapSweepWeakReference, WorkQueues,
+        CompactionLists, CreateHeapData, HeapIndexable, HeapMarkAndSweep, HeapSweepWeakReference,
+        WorkQueues,
         indexes::{BaseIndex, WeakMapIndex},
     },
 };
@@ -95,6 +96,7 @@ impl Index<WeakMap<'_>> for Agent {
 
 impl IndexMut<WeakMap<'_>> for Agent {
     fn index_mut(&mut self, index: WeakMap) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.weak_maps[index]
     }
 }
@@ -119,6 +121,23 @@ impl IndexMut<WeakMap<'_>> for Vec<Option<WeakMapHeapData<'static>>> {
     }
 }
 
+impl HeapIndexable for WeakMap<'_> {
+    type Data = WeakMapHeapData<'static>;
+    const KIND: &'static str = "WeakMap";
+
+    fn heap_index(self) -> usize {
+        self.get_index()
+    }
+
+    fn heap_vec(heap: &Heap) -> &[Option<Self::Data>] {
+        &heap.weak_maps
+    }
+
+    fn heap_vec_mut(heap: &mut Heap) -> &mut [Option<Self::Data>] {
+        &mut heap.weak_maps
+    }
+}
+
 impl TryFrom<HeapRootData> for WeakMap<'_> {
     type Error = ();
 
@@ -112,6 +112,7 @@ impl Index<WeakRef<'_>> for Agent {
 
 impl IndexMut<WeakRef<'_>> for Agent {
     fn index_mut(&mut self, index: WeakRef) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.weak_refs[index]
     }
 }
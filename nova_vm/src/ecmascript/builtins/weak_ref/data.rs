@@ -5,20 +5,20 @@
 use crate::{
     ecmascript::{execution::WeakKey, types::OrdinaryObject},
     engine::context::{Bindable, NoGcScope},
-    heap::{CompactionLists, HeapMarkAndSweep, WorkQueues},
+    heap::{CompactionLists, HeapMarkAndSweep, HeapSweepWeakReference, WorkQueues},
 };
 
 #[derive(Default, Debug, Clone)]
 pub struct WeakRefHeapData<'a> {
     pub(super) object_index: Option<OrdinaryObject<'a>>,
     /// ### \[\[WeakRefTarget]]
-    pub(super) weak_ref_target: Option<WeakKey<'a>>,
+    pub(crate) weak_ref_target: Option<WeakKey<'a>>,
     /// ### \[\[KeptAlive]]
     ///
     /// Instead of storing a list of kept-alive targets in Agents, we keep only
     /// a boolean there and clear all kept_alive booleans at the end of a job
     /// run.
-    pub(super) kept_alive: bool,
+    pub(crate) kept_alive: bool,
 }
 
 impl WeakRefHeapData<'_> {
@@ -62,6 +62,6 @@ impl HeapMarkAndSweep for WeakRefHeapData<'static> {
             kept_alive: _,
         } = self;
         object_index.sweep_values(compactions);
-        value.sweep_values(compactions);
+        *value = value.and_then(|value| value.sweep_weak_reference(compactions));
     }
 }
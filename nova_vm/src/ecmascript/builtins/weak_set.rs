@@ -121,6 +121,7 @@ impl Index<WeakSet<'_>> for Agent {
 
 impl IndexMut<WeakSet<'_>> for Agent {
     fn index_mut(&mut self, index: WeakSet) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.weak_sets[index]
     }
 }
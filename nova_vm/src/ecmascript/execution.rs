@@ -3,25 +3,40 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 pub mod agent;
+mod breakpoints;
 mod default_host_hooks;
 mod environments;
 mod execution_context;
+mod performance;
 mod realm;
+mod reentrancy;
+mod slow_paths;
 mod weak_key;
 mod weak_ref_and_finalization_registry;
 
 pub use agent::{Agent, JsResult};
+pub(crate) use breakpoints::Breakpoints;
 pub use default_host_hooks::DefaultHostHooks;
 pub(crate) use environments::{
-    DeclarativeEnvironment, Environment, Environments, FunctionEnvironment, GlobalEnvironment,
-    ModuleEnvironment, ObjectEnvironment, PrivateEnvironment, PrivateField, PrivateMethod,
-    ThisBindingStatus, get_this_environment, new_class_field_initializer_environment,
-    new_class_static_element_environment, new_declarative_environment, new_function_environment,
-    new_global_environment, new_private_environment, resolve_private_identifier,
+    DeclarativeEnvironment, Environment, Environments, FunctionEnvironment, GlobalDeclarations,
+    GlobalEnvironment, ModuleEnvironment, ObjectEnvironment, PrivateEnvironment, PrivateField,
+    PrivateMethod, ThisBindingStatus, get_this_environment,
+    new_class_field_initializer_environment, new_class_static_element_environment,
+    new_declarative_environment, new_fixed_this_environment, new_function_environment,
+    new_global_environment, new_object_environment, new_private_environment,
+    resolve_private_identifier,
 };
 pub(crate) use execution_context::*;
+pub(crate) use performance::{PerformanceTimeline, create_performance_object};
+pub use realm::{LazyGlobalInitializer, RealmCheckpoint};
 pub(crate) use realm::{
-    ProtoIntrinsics, Realm, RealmRecord, initialize_default_realm, initialize_host_defined_realm,
+    LazyGlobals, ProtoIntrinsics, Realm, RealmCheckpointRecord, RealmRecord,
+    initialize_default_realm, initialize_host_defined_realm, initialize_lazy_global,
+    try_initialize_lazy_global,
 };
+pub use reentrancy::{AgentOperation, ExecutionState, NestedGcRootError, ReentrancyError};
+pub(crate) use reentrancy::{ExecutionGuard, ReentrancyState};
+pub(crate) use slow_paths::SlowPathLog;
+pub use slow_paths::{SlowPathEvent, SlowPathReason};
 pub(crate) use weak_key::*;
 pub(crate) use weak_ref_and_finalization_registry::*;
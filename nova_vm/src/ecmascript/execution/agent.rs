@@ -8,21 +8,97 @@
 //!   Copyright (c) 2023-2024 Linus Groh
 
 use ahash::AHashMap;
+use oxc_ast::ast::Program;
+use oxc_span::Span;
 
 use super::{
-    environments::{get_identifier_reference, try_get_identifier_reference}, initialize_default_realm, initialize_host_defined_realm, Environment, ExecutionContext, GlobalEnvironment, PrivateEnvironment, RealmRecord, Realm
+    environments::{get_identifier_reference, try_get_identifier_reference}, new_declarative_environment, new_fixed_this_environment, new_object_environment, ECMAScriptCodeEvaluationState, initialize_default_realm, initialize_host_defined_realm, Environment, ExecutionContext, GlobalEnvironment, PerformanceTimeline, PrivateEnvironment, LazyGlobalInitializer, LazyGlobals, RealmCheckpoint, RealmCheckpointRecord, RealmRecord, Realm, SlowPathEvent, SlowPathLog, AgentOperation, ExecutionGuard, ExecutionState, NestedGcRootError, ReentrancyError, ReentrancyState, Breakpoints
 };
 use crate::{
     ecmascript::{
-        abstract_operations::type_conversion::to_string, builtins::{control_abstraction_objects::promise_objects::promise_abstract_operations::promise_jobs::{PromiseReactionJob, PromiseResolveThenableJob}, error::ErrorHeapData, promise::Promise}, execution::clear_kept_objects, scripts_and_modules::{script::{parse_script, script_evaluation}, source_code::SourceCode, ScriptOrModule}, types::{Function, IntoValue, Object, PrivateName, Reference, String, Symbol, Value, ValueRootRepr}
-    }, engine::{context::{Bindable, GcScope, NoGcScope}, rootable::{HeapRootCollectionData, HeapRootData, HeapRootRef, Rootable}, TryResult, Vm}, heap::{heap_gc::heap_gc, CompactionLists, CreateHeapData, HeapMarkAndSweep, PrimitiveHeapIndexable, WorkQueues}, Heap
+        builders::builtin_function_builder::BuiltinFunctionBuilder, abstract_operations::{operations_on_objects::{call_function, define_property_or_throw, get_function_realm}, type_conversion::to_string}, builtins::{create_builtin_function, BuiltinFunction, global_object::eval_declaration_instantiation, ordinary::{ordinary_define_own_property, ordinary_object_create_with_intrinsics}, ArgumentsList, Behaviour, BuiltinFunctionArgs, control_abstraction_objects::promise_objects::promise_abstract_operations::promise_jobs::{PromiseReactionJob, PromiseResolveThenableJob}, error::{DeferredErrorMessage, ErrorData, ErrorHeapData, ErrorMessage, MessageArgument}, promise::Promise}, execution::{clear_kept_objects, ProtoIntrinsics}, scripts_and_modules::{script::{parse_script, script_evaluation, Script, ScriptRecord}, source_code::SourceCode, ScriptOrModule}, types::{Function, InternalMethods, IntoFunction, IntoValue, Object, OrdinaryObject, PrivateName, PropertyDescriptor, PropertyKey, Reference, String, Symbol, Value, ValueRootRepr}
+    }, engine::{context::{Bindable, GcScope, GcToken, NoGcScope, ScopeToken}, Executable, rootable::{HeapRootCollectionData, HeapRootData, HeapRootRef, Rootable, Scopable}, unwrap_try, Global, TryResult, Vm}, heap::{heap_gc::{heap_gc, heap_gc_step}, CompactionPolicy, snapshot::{read_startup_snapshot, write_startup_snapshot}, SnapshotError, clone_realm, RealmCloneError, CompactionLists, CreateHeapData, ElementArrayStats, HeapAccessError, HeapMarkAndSweep, HeapStatistics, HeapVectorStatistics, PrimitiveHeapIndexable, WorkQueues, ALLOC_COUNTER_LIMIT, HEAP_GROWTH_CHECK_INTERVAL, HEAP_GROWTH_NOTIFICATION_STEP}, Heap
 };
-use core::{any::Any, cell::RefCell, ptr::NonNull};
+#[cfg(feature = "deferred-module-loading")]
+use crate::ecmascript::{
+    builtins::module::Module,
+    scripts_and_modules::module_loading::{DeferredModuleLoad, ModuleLoad, ModuleLoading},
+};
+use core::{any::Any, cell::RefCell, num::NonZeroU32, ptr::NonNull};
+use std::{
+    rc::Rc,
+    sync::OnceLock,
+    time::{Instant, SystemTime},
+};
+
+/// The default for [`Options::max_string_length`].
+pub const DEFAULT_MAX_STRING_LENGTH: usize = (1 << 31) - 1;
+
+/// The default for [`Options::max_array_element_capacity`]: the capacity of
+/// the largest backing store that is sized to its Array. A larger backing
+/// store reserves space for 2^32 elements, which would fail to allocate.
+pub const DEFAULT_MAX_ARRAY_ELEMENT_CAPACITY: u32 = 1 << 24;
 
 #[derive(Debug, Default)]
 pub struct Options {
     pub disable_gc: bool,
     pub print_internals: bool,
+    /// Seed for a deterministic `Math.random` sequence.
+    ///
+    /// When set, `Math.random` draws from a xorshift128+ PRNG seeded with
+    /// this value instead of calling [`HostHooks::random_f64`]. The sequence
+    /// for a given seed does not change between versions of the engine.
+    pub random_seed: Option<u64>,
+    /// Collect garbage at every bytecode instruction that follows an
+    /// allocation, instead of only after 2 MiB of allocations.
+    ///
+    /// This is very slow and intended for finding values that are not
+    /// properly rooted across garbage collection.
+    pub gc_stress: bool,
+    /// Install the `performance` global with `now`, `timeOrigin`, `mark`,
+    /// `measure` and `getEntriesByType`.
+    ///
+    /// Timestamps are read from [`HostHooks::monotonic_now`].
+    pub performance: bool,
+    /// When garbage collection compacts heap vectors by moving live entries
+    /// over dead ones.
+    pub compaction: CompactionPolicy,
+    /// Maximum byte length of source text that scripts, modules, eval and
+    /// dynamic functions are parsed from.
+    ///
+    /// Longer source text is rejected with a syntax error before parsing.
+    pub max_source_length: Option<usize>,
+    /// Maximum number of AST nodes in parsed source text.
+    ///
+    /// Source text with more nodes is rejected with a syntax error before it
+    /// is analyzed or compiled to bytecode.
+    pub max_ast_nodes: Option<u32>,
+    /// Maximum byte length of strings created by the engine.
+    ///
+    /// Operations that would create a longer string throw a RangeError
+    /// before allocating it. Defaults to [`DEFAULT_MAX_STRING_LENGTH`].
+    pub max_string_length: Option<usize>,
+    /// Maximum number of elements that the backing store of an Array can
+    /// hold.
+    ///
+    /// Creating or growing an Array past this throws a RangeError before
+    /// allocating. Defaults to [`DEFAULT_MAX_ARRAY_ELEMENT_CAPACITY`].
+    pub max_array_element_capacity: Option<u32>,
+    /// Report operations that fall off a fast path of the engine to
+    /// [`HostHooks::on_slow_path`].
+    ///
+    /// The first event at each location and every nth one after it are
+    /// reported, so that a hot location does not flood the host. Setting this
+    /// to 1 reports every event.
+    pub report_slow_paths: Option<NonZeroU32>,
+}
+
+/// Options for [`Agent::eval_in_scope`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EvalInScopeOptions {
+    /// Create the var and function declarations of sloppy mode source text
+    /// on the global object instead of the innermost scope object.
+    pub declare_in_global: bool,
 }
 
 pub type JsResult<'a, T> = core::result::Result<T, JsError<'a>>;
@@ -43,6 +119,12 @@ impl<'a> JsError<'a> {
     pub fn to_string<'gc>(self, agent: &mut Agent, gc: GcScope<'gc, '_>) -> String<'gc> {
         to_string(agent, self.0, gc).unwrap()
     }
+
+    /// Creates an owned, Agent-independent snapshot of the thrown value,
+    /// suitable for moving the error to another Agent or out of the engine.
+    pub fn to_error_data(self, agent: &mut Agent, gc: NoGcScope) -> ErrorData {
+        ErrorData::from_value(agent, self.0, gc)
+    }
 }
 
 // SAFETY: Property implemented as a recursive bind.
@@ -100,6 +182,21 @@ impl HeapMarkAndSweep for JsError<'static> {
 pub(crate) enum InnerJob {
     PromiseResolveThenable(PromiseResolveThenableJob),
     PromiseReaction(PromiseReactionJob),
+    Callback(CallbackJob),
+}
+
+/// A Job that calls a function with no arguments, such as a callback given to
+/// `queueMicrotask` by a host.
+pub(crate) struct CallbackJob {
+    callback: Global<Function<'static>>,
+}
+
+impl CallbackJob {
+    fn run<'a>(self, agent: &mut Agent, gc: GcScope<'a, '_>) -> JsResult<'a, ()> {
+        let callback = self.callback.take(agent);
+        call_function(agent, callback, Value::Undefined, None, gc)?;
+        Ok(())
+    }
 }
 
 pub struct Job {
@@ -108,11 +205,44 @@ pub struct Job {
 }
 
 impl Job {
+    /// Create a Job that calls the given function with no arguments in the
+    /// function's Realm.
+    pub fn new_callback(agent: &mut Agent, callback: Function, gc: NoGcScope) -> Self {
+        let realm = get_function_realm(agent, callback, gc).unwrap_or(agent.current_realm(gc));
+        Job {
+            realm: Some(realm.unbind()),
+            inner: InnerJob::Callback(CallbackJob {
+                callback: Global::new(agent, callback.unbind()),
+            }),
+        }
+    }
+
     fn realm(&self) -> Option<Realm<'static>> {
         self.realm
     }
 
+    /// Run the Job.
+    ///
+    /// An exception thrown out of the Job is reported to
+    /// [`HostHooks::report_uncaught_exception`] before it is returned.
+    ///
+    /// ## Panics
+    ///
+    /// If the Agent is poisoned or Jobs may not be run in its current
+    /// [`ExecutionState`].
     pub fn run<'a>(self, agent: &mut Agent, gc: GcScope<'a, '_>) -> JsResult<'a, ()> {
+        self.try_run(agent, gc)
+            .unwrap_or_else(|err| panic!("Cannot run Job: {err}"))
+    }
+
+    /// Run the Job, returning an error if the Agent is poisoned or Jobs may
+    /// not be run in its current [`ExecutionState`].
+    pub fn try_run<'a>(
+        self,
+        agent: &mut Agent,
+        mut gc: GcScope<'a, '_>,
+    ) -> Result<JsResult<'a, ()>, ReentrancyError> {
+        let _guard = agent.enter_operation(AgentOperation::RunJobs)?;
         let mut pushed_context = false;
         if let Some(realm) = self.realm {
             if agent.current_realm(gc.nogc()) != realm {
@@ -127,15 +257,78 @@ impl Job {
         };
 
         let result = match self.inner {
-            InnerJob::PromiseResolveThenable(job) => job.run(agent, gc),
-            InnerJob::PromiseReaction(job) => job.run(agent, gc),
-        };
+            InnerJob::PromiseResolveThenable(job) => job.run(agent, gc.reborrow()),
+            InnerJob::PromiseReaction(job) => job.run(agent, gc.reborrow()),
+            InnerJob::Callback(job) => job.run(agent, gc.reborrow()),
+        }
+        .unbind()
+        .bind(gc.nogc());
 
         if pushed_context {
             agent.execution_context_stack.pop();
         }
 
-        result
+        Ok(match result {
+            Ok(()) => Ok(()),
+            Err(err) => Err(agent.report_uncaught_exception(err.unbind(), None, gc)),
+        })
+    }
+}
+
+/// Location of a statement in source code, eg. one that an exception was
+/// thrown from.
+#[derive(Debug, Clone, Copy)]
+pub struct SourceLocation<'a> {
+    /// Source code that was being evaluated.
+    pub source_code: SourceCode<'a>,
+    /// Span of the statement.
+    pub span: Span,
+}
+
+/// Origin of an exception reported by
+/// [`HostHooks::report_uncaught_exception`].
+#[derive(Debug, Clone, Copy)]
+pub enum ExceptionOrigin<'a> {
+    /// The exception was thrown out of a Script evaluation.
+    Script {
+        script: Script<'a>,
+        location: Option<SourceLocation<'a>>,
+    },
+    /// The exception was thrown out of a Job.
+    Job {
+        location: Option<SourceLocation<'a>>,
+    },
+}
+
+impl ExceptionOrigin<'_> {
+    /// Location that the exception was thrown from, if known.
+    pub fn location(&self) -> Option<SourceLocation<'_>> {
+        match self {
+            Self::Script { location, .. } | Self::Job { location } => *location,
+        }
+    }
+
+    /// Render the source line that the exception was thrown from, followed by
+    /// a line with a caret pointing at the column the throwing statement
+    /// starts at.
+    ///
+    /// Returns None if the location is not known.
+    pub fn render_source_context(&self, agent: &Agent) -> Option<std::string::String> {
+        let SourceLocation { source_code, span } = self.location()?;
+        let (line_number, column) = source_code.line_column_at(agent, span.start);
+        let line = source_code.line(agent, line_number)?;
+        // Keep tabs in the indentation so that the caret lines up with the
+        // source line regardless of tab width.
+        let mut code_units = 0;
+        let indentation: std::string::String = line
+            .chars()
+            .take_while(|c| {
+                code_units += c.len_utf16();
+                code_units <= column as usize
+            })
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        Some(format!("{line}\n{indentation}^"))
     }
 }
 
@@ -164,6 +357,15 @@ pub trait HostHooks: core::fmt::Debug {
     /// ### [9.5.5 HostEnqueuePromiseJob ( job, realm )](https://tc39.es/ecma262/#sec-hostenqueuepromisejob)
     fn enqueue_promise_job(&self, job: Job);
 
+    /// Take the next Job enqueued with [`HostHooks::enqueue_promise_job`] to
+    /// run it, used by [`Agent::run_microtasks`].
+    ///
+    /// The default implementation returns None: hosts that run their Jobs
+    /// themselves do not need to implement this.
+    fn dequeue_promise_job(&self) -> Option<Job> {
+        None
+    }
+
     /// ### [27.2.1.9 HostPromiseRejectionTracker ( promise, operation )](https://tc39.es/ecma262/#sec-host-promise-rejection-tracker)
     fn promise_rejection_tracker(
         &self,
@@ -173,6 +375,121 @@ pub trait HostHooks: core::fmt::Debug {
         // The default implementation of HostPromiseRejectionTracker is to return unused.
     }
 
+    /// Produce a random number in the range `[0, 1)` for `Math.random`.
+    ///
+    /// The default implementation uses the system random number generator.
+    fn random_f64(&self) -> f64 {
+        rand::random::<f64>()
+    }
+
+    /// ### [21.4.1.24 SystemTimeZoneIdentifier ( )](https://tc39.es/ecma262/#sec-systemtimezoneidentifier)
+    ///
+    /// Returns the time zone that local time Date methods operate in. Only
+    /// `"UTC"` and fixed UTC offsets such as `"+05:30"` or `"-0800"` are
+    /// supported: any other identifier is treated as `"UTC"`. The default
+    /// implementation returns `"UTC"`.
+    fn system_time_zone_identifier(&self) -> &str {
+        "UTC"
+    }
+
+    /// Read a monotonic clock in milliseconds, used by `performance.now()`
+    /// and `performance.timeOrigin` if [`Options::performance`] is set.
+    ///
+    /// The readings must never decrease. The default implementation measures
+    /// the time elapsed since the clock was first read in this process.
+    fn monotonic_now(&self) -> f64 {
+        static START: OnceLock<Instant> = OnceLock::new();
+        START.get_or_init(Instant::now).elapsed().as_secs_f64() * 1000.0
+    }
+
+    /// Read the current time in milliseconds since the Unix epoch, used by
+    /// `Date.now()` and the `Date` constructor.
+    ///
+    /// The result is truncated to an integral time value, and values outside
+    /// of the time value range produce an invalid Date. The default
+    /// implementation reads the system clock.
+    fn system_time_now(&self) -> f64 {
+        match SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+            Ok(elapsed) => elapsed.as_millis() as f64,
+            Err(err) => -(err.duration().as_millis() as f64),
+        }
+    }
+
+    /// Called when the memory reserved by the heap has grown, with the size
+    /// in bytes at the previous notification and now.
+    ///
+    /// The heap size is checked between bytecode instructions, and the host
+    /// is notified once it has grown by at least 1 MiB since the previous
+    /// notification. This is a passive notification that cannot prevent the
+    /// growth: a host may for instance log it or schedule a garbage
+    /// collection of its own. The default implementation does nothing.
+    fn on_heap_grow(&self, _bytes_before: usize, _bytes_after: usize) {}
+
+    /// Called at the end of every garbage collection, including those started
+    /// automatically in the middle of running ECMAScript code.
+    ///
+    /// The Agent is in the [`ExecutionState::InGcCallback`] state while this
+    /// runs: it may be inspected, eg. for its heap statistics, but evaluating
+    /// scripts, calling functions, running Jobs and collecting garbage fail
+    /// with a [`ReentrancyError`]. The default implementation does nothing.
+    fn on_garbage_collection(&self, _agent: &mut Agent, _gc: GcScope) {}
+
+    /// Called when an operation falls off a fast path of the engine, if
+    /// [`Options::report_slow_paths`] is set.
+    ///
+    /// This is a diagnostic for finding code that runs slower than it could,
+    /// such as an Array that has holes. The Agent may be inspected, eg. to
+    /// read the source text of the event's location, but no ECMAScript code
+    /// can be run. The default implementation does nothing.
+    fn on_slow_path(&self, _agent: &Agent, _event: SlowPathEvent) {}
+
+    /// Called when ECMAScript code reaches a breakpoint set with
+    /// [`Agent::set_breakpoint`], before the statement at `position` runs.
+    ///
+    /// The Agent may be inspected, eg. to read the source text around the
+    /// position, and code may be evaluated in it. The default implementation
+    /// does nothing.
+    fn on_breakpoint(&self, _agent: &mut Agent, _position: SourceLocation, _gc: GcScope) {}
+
+    /// Report an exception that was thrown out of a Script evaluation or a Job
+    /// and not handled by any ECMAScript code.
+    ///
+    /// This is distinct from the promise rejection tracker: rejected promises
+    /// are not reported here. The default implementation does nothing.
+    fn report_uncaught_exception(
+        &self,
+        _agent: &mut Agent,
+        _error: Value,
+        _origin: ExceptionOrigin,
+        _gc: GcScope,
+    ) {
+    }
+
+    /// ### [16.2.1.10 HostLoadImportedModule ( referrer, moduleRequest, hostDefined, payload )](https://tc39.es/ecma262/#sec-HostLoadImportedModule)
+    ///
+    /// Load the module that `referrer` imports with `specifier`, for
+    /// [`Module::load_requested_modules`]. The host may return the module or
+    /// an error at once, or return [`ModuleLoad::Deferred`] with `load` and
+    /// complete the load later using [`Agent::complete_module_load`]. The
+    /// same referrer and specifier must always give the same module.
+    ///
+    /// The default implementation fails with a TypeError.
+    #[cfg(feature = "deferred-module-loading")]
+    fn load_imported_module<'gc>(
+        &self,
+        agent: &mut Agent,
+        _referrer: Module<'gc>,
+        _specifier: String<'gc>,
+        _load: DeferredModuleLoad,
+        gc: NoGcScope<'gc, '_>,
+    ) -> ModuleLoad<'gc> {
+        ModuleLoad::Failed(agent.create_exception_with_static_message(
+            ExceptionType::TypeError,
+            "Module loading is not supported",
+            gc,
+        ))
+    }
+
     /// Get access to the Host data, useful to share state between calls of built-in functions.
     ///
     /// Note: This will panic if not implemented manually.
@@ -181,6 +498,16 @@ pub trait HostHooks: core::fmt::Debug {
     }
 }
 
+/// Result of draining the promise Job queue with [`Agent::run_microtasks`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MicrotaskCheckpoint {
+    /// The Job queue was drained.
+    Drained,
+    /// The limit of Jobs to run was reached before the Job queue was seen to
+    /// be empty. Jobs may remain in the queue.
+    LimitReached,
+}
+
 /// Owned ECMAScript Agent that can be used to run code but also to run garbage
 /// collection on the Agent heap.
 pub struct GcAgent {
@@ -233,6 +560,15 @@ impl GcAgent {
     ///
     /// The Realm will not be removed by garbage collection until
     /// [`GcAgent::remove_realm`] is called.
+    ///
+    /// - `create_global_object` creates the global object of the Realm. If
+    ///   not given, an ordinary object is created.
+    /// - `create_global_this_value` creates the global this binding: the
+    ///   value of `this` at the top level of scripts and in sloppy mode
+    ///   functions called without a receiver, and of `globalThis`. If not
+    ///   given, the global object is used.
+    /// - `initialize_global_object` is called with the global object after
+    ///   the default global bindings have been defined on it.
     pub fn create_realm(
         &mut self,
         create_global_object: Option<
@@ -257,55 +593,325 @@ impl GcAgent {
         self.root_realm(realm)
     }
 
+    /// Clones the template Realm. See [`Agent::clone_realm`].
+    ///
+    /// The clone will not be removed by garbage collection until
+    /// [`GcAgent::remove_realm`] is called.
+    ///
+    /// ## Panics
+    ///
+    /// If the RealmRoot does not refer to a Realm of this GcAgent, or if the
+    /// template cannot be cloned.
+    pub fn clone_realm(&mut self, template: &RealmRoot) -> RealmRoot {
+        self.try_clone_realm(template)
+            .expect("Cannot clone the template Realm")
+    }
+
+    /// Clones the template Realm, returning an error if heap data that cannot
+    /// be cloned is reachable from it. See [`Agent::try_clone_realm`].
+    ///
+    /// ## Panics
+    ///
+    /// If the RealmRoot does not refer to a Realm of this GcAgent.
+    pub fn try_clone_realm(&mut self, template: &RealmRoot) -> Result<RealmRoot, RealmCloneError> {
+        let template = self
+            .realm_roots
+            .get(template.index as usize)
+            .copied()
+            .flatten()
+            .expect("Cannot clone a non-existing Realm");
+        let realm = clone_realm(&mut self.agent.heap, template)?;
+        Ok(self.root_realm(realm))
+    }
+
+    /// Captures the global bindings of the Realm. See
+    /// [`Agent::checkpoint_realm`].
+    ///
+    /// ## Panics
+    ///
+    /// If the RealmRoot does not refer to a Realm of this GcAgent, or if the
+    /// global object of the Realm is not an ordinary object.
+    pub fn checkpoint_realm(&mut self, realm: &RealmRoot) -> RealmCheckpoint {
+        let realm = self
+            .realm_roots
+            .get(realm.index as usize)
+            .copied()
+            .flatten()
+            .expect("Cannot checkpoint a non-existing Realm");
+        self.agent
+            .with_gc(|agent, gc| agent.checkpoint_realm(realm, gc.nogc()))
+    }
+
+    /// Restores the global bindings of the checkpointed Realm. See
+    /// [`Agent::rollback_realm`].
+    ///
+    /// ## Panics
+    ///
+    /// If the checkpoint does not belong to this GcAgent.
+    pub fn rollback_realm(&mut self, checkpoint: &RealmCheckpoint) {
+        self.agent
+            .with_gc(|agent, gc| agent.rollback_realm(checkpoint, gc.nogc()))
+    }
+
+    /// Releases the checkpoint. See [`Agent::remove_realm_checkpoint`].
+    pub fn remove_realm_checkpoint(&mut self, checkpoint: RealmCheckpoint) {
+        self.agent.remove_realm_checkpoint(checkpoint)
+    }
+
+    /// Defines a global property in the Realm whose value is computed by
+    /// `init` the first time it is needed. See [`Agent::define_lazy_global`].
+    ///
+    /// ## Panics
+    ///
+    /// If the RealmRoot does not refer to a Realm of this GcAgent, or if the
+    /// property cannot be defined on the global object of the Realm.
+    pub fn define_lazy_global(
+        &mut self,
+        realm: &RealmRoot,
+        name: &str,
+        init: LazyGlobalInitializer,
+    ) {
+        let realm = self
+            .realm_roots
+            .get(realm.index as usize)
+            .copied()
+            .flatten()
+            .expect("Cannot define a lazy global in a non-existing Realm");
+        self.agent
+            .with_gc(|agent, gc| agent.define_lazy_global(realm, name, init, gc.nogc()))
+    }
+
+    /// Serializes the heap into a startup snapshot. See
+    /// [`Agent::serialize_startup_snapshot`].
+    pub fn serialize_startup_snapshot(&self) -> Vec<u8> {
+        self.agent.serialize_startup_snapshot()
+    }
+
+    /// Creates a GcAgent from a startup snapshot, returning it together with
+    /// the Realm stored in the snapshot.
+    ///
+    /// This is equivalent to creating a new GcAgent and calling
+    /// [`GcAgent::create_default_realm`], but skips building the intrinsics.
+    ///
+    /// ## Safety
+    ///
+    /// The snapshot must have been created by
+    /// [`GcAgent::serialize_startup_snapshot`] in the same build of the
+    /// engine. Snapshots from other builds are rejected on a best-effort
+    /// basis only, as the snapshot contains builtin function pointers.
+    pub unsafe fn from_startup_snapshot(
+        bytes: &[u8],
+        options: Options,
+        host_hooks: &'static dyn HostHooks,
+    ) -> Result<(Self, RealmRoot), SnapshotError> {
+        // SAFETY: Passed on to the caller.
+        let (agent, realm) = unsafe { Agent::from_startup_snapshot(bytes, options, host_hooks)? };
+        let mut gc_agent = Self {
+            agent,
+            realm_roots: Vec::with_capacity(1),
+        };
+        let realm = gc_agent.root_realm(realm);
+        Ok((gc_agent, realm))
+    }
+
     /// Removes the given Realm. Resources associated with the Realm are free
     /// to be collected by the garbage collector after this call.
+    ///
+    /// ## Panics
+    ///
+    /// If the RealmRoot does not refer to a Realm of this GcAgent.
     pub fn remove_realm(&mut self, realm: RealmRoot) {
+        self.try_remove_realm(realm)
+            .expect("Cannot remove a non-existing Realm");
+    }
+
+    /// Removes the given Realm, returning an error if the RealmRoot does not
+    /// refer to a Realm of this GcAgent.
+    pub fn try_remove_realm(&mut self, realm: RealmRoot) -> Result<(), HeapAccessError> {
         let RealmRoot { index } = realm;
-        let error_message = "Cannot remove a non-existing Realm";
         // After this removal, the Realm can be collected by GC.
         let _ = self
             .realm_roots
             .get_mut(index as usize)
-            .expect(error_message)
+            .ok_or(HeapAccessError::out_of_bounds("RealmRoot", index.into()))?
             .take()
-            .expect(error_message);
+            .ok_or(HeapAccessError::slot_empty("RealmRoot", index.into()))?;
         while !self.realm_roots.is_empty() && self.realm_roots.last().unwrap().is_none() {
             let _ = self.realm_roots.pop();
         }
+        Ok(())
     }
 
+    /// ## Panics
+    ///
+    /// If the RealmRoot does not refer to a Realm of this GcAgent, or if the
+    /// Agent is poisoned.
     pub fn run_in_realm<F, R>(&mut self, realm: &RealmRoot, func: F) -> R
     where
         F: for<'agent, 'gc, 'scope> FnOnce(&'agent mut Agent, GcScope<'gc, 'scope>) -> R,
     {
+        self.try_run_in_realm(realm, func)
+            .expect("Attempted to run in non-existing Realm")
+    }
+
+    /// Runs the given function in the Realm, returning an error if the
+    /// RealmRoot does not refer to a Realm of this GcAgent.
+    ///
+    /// ## Panics
+    ///
+    /// If the Agent is poisoned: its execution context stack may have been
+    /// left half-unwound by a panic.
+    pub fn try_run_in_realm<F, R>(
+        &mut self,
+        realm: &RealmRoot,
+        func: F,
+    ) -> Result<R, HeapAccessError>
+    where
+        F: for<'agent, 'gc, 'scope> FnOnce(&'agent mut Agent, GcScope<'gc, 'scope>) -> R,
+    {
+        if self.agent.is_poisoned() {
+            panic!("Cannot run in Realm: {}", ReentrancyError::Poisoned);
+        }
         let index = realm.index;
-        let error_message = "Attempted to run in non-existing Realm";
-        let realm = *self
+        let realm = self
             .realm_roots
             .get(index as usize)
-            .expect(error_message)
+            .ok_or(HeapAccessError::out_of_bounds("RealmRoot", index.into()))?
             .as_ref()
-            .expect(error_message);
+            .copied()
+            .ok_or(HeapAccessError::slot_empty("RealmRoot", index.into()))?;
+        // Make sure the Realm itself is still alive in this Agent's heap.
+        self.agent.heap.try_get(realm)?;
         assert!(self.agent.execution_context_stack.is_empty());
         let result = self.agent.run_in_realm(realm, func);
         clear_kept_objects(&mut self.agent);
         assert!(self.agent.execution_context_stack.is_empty());
         assert!(self.agent.vm_stack.is_empty());
         self.agent.stack_refs.borrow_mut().clear();
-        result
+        Ok(result)
     }
 
+    /// ## Panics
+    ///
+    /// If the Agent is poisoned or garbage collection is not allowed in its
+    /// current [`ExecutionState`].
     pub fn gc(&mut self) {
+        self.try_gc()
+            .unwrap_or_else(|err| panic!("Cannot collect garbage: {err}"))
+    }
+
+    /// Performs garbage collection, returning an error if the Agent is
+    /// poisoned or garbage collection is not allowed in its current
+    /// [`ExecutionState`].
+    pub fn try_gc(&mut self) -> Result<(), ReentrancyError> {
         if self.agent.options.disable_gc {
             // GC is disabled; no-op
-            return;
+            return Ok(());
         }
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let gc = GcScope::new(&mut gc, &mut scope);
+        let _guard = self.agent.enter_operation(AgentOperation::Gc)?;
         let Self {
             agent, realm_roots, ..
         } = self;
-        heap_gc(agent, realm_roots, gc);
+        agent.with_gc(|agent, gc| heap_gc(agent, realm_roots, gc));
+        Ok(())
+    }
+
+    /// Performs one incremental garbage collection slice, marking at most
+    /// `budget` heap entries. Returns true if the slice finished a garbage
+    /// collection.
+    ///
+    /// An incremental collection is started by the first call and finished by
+    /// the call that finds no marking work left, at which point the heap is
+    /// swept. If more allocations are performed during marking than would
+    /// normally trigger a garbage collection, the collection is finished
+    /// synchronously by the next call regardless of the budget.
+    pub fn gc_step(&mut self, budget: usize) -> bool {
+        if self.agent.options.disable_gc {
+            // GC is disabled; no-op
+            return false;
+        }
+        let _guard = self
+            .agent
+            .enter_operation(AgentOperation::Gc)
+            .unwrap_or_else(|err| panic!("Cannot collect garbage: {err}"));
+        let Self {
+            agent, realm_roots, ..
+        } = self;
+        agent.with_gc(|agent, gc| heap_gc_step(agent, realm_roots, budget, gc))
+    }
+
+    /// Returns true if a panic has unwound out of the Agent while it was
+    /// executing. See [`Agent::is_poisoned`].
+    pub fn is_poisoned(&self) -> bool {
+        self.agent.is_poisoned()
+    }
+
+    /// Returns the current heap size and garbage collection statistics.
+    pub fn heap_statistics(&self) -> HeapStatistics {
+        self.agent.heap_statistics()
+    }
+
+    /// Returns the number of live element vectors and slots in each capacity
+    /// class of the element arrays.
+    pub fn element_array_stats(&self) -> ElementArrayStats {
+        self.agent.element_array_stats()
+    }
+
+    /// Returns the number of live entries, slots and reserved capacity of
+    /// each heap vector.
+    pub fn heap_vector_statistics(&self) -> Vec<HeapVectorStatistics> {
+        self.agent.heap_vector_statistics()
+    }
+
+    /// Performs a garbage collection that compacts every heap vector
+    /// regardless of [`Options::compaction`], and then releases unused
+    /// capacity of the heap vectors back to the allocator.
+    pub fn compact_heap(&mut self) {
+        let policy =
+            core::mem::replace(&mut self.agent.options.compaction, CompactionPolicy::Always);
+        self.gc();
+        self.agent.options.compaction = policy;
+        self.agent.heap.shrink_vectors();
+    }
+}
+
+/// The xorshift128+ PRNG used by `Math.random` if [`Options::random_seed`]
+/// is set.
+#[derive(Debug)]
+struct Xorshift128Plus {
+    state0: u64,
+    state1: u64,
+}
+
+impl Xorshift128Plus {
+    fn new(seed: u64) -> Self {
+        // MurmurHash3 finalizer, so that similar seeds give unrelated states
+        // and the state is never all zeroes.
+        fn mix(mut h: u64) -> u64 {
+            h ^= h >> 33;
+            h = h.wrapping_mul(0xff51_afd7_ed55_8ccd);
+            h ^= h >> 33;
+            h = h.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+            h ^ (h >> 33)
+        }
+        let state0 = mix(seed);
+        let state1 = mix(!state0);
+        Self { state0, state1 }
+    }
+
+    /// Returns a value in the range [0, 1) made from the top 53 bits of the
+    /// next output.
+    fn next_f64(&mut self) -> f64 {
+        let mut s1 = self.state0;
+        let s0 = self.state1;
+        self.state0 = s0;
+        s1 ^= s1 << 23;
+        s1 ^= s1 >> 17;
+        s1 ^= s0;
+        s1 ^= s0 >> 26;
+        self.state1 = s1;
+        let output = self.state0.wrapping_add(self.state1);
+        (output >> 11) as f64 / (1u64 << 53) as f64
     }
 }
 
@@ -317,7 +923,9 @@ pub struct Agent {
     pub(crate) symbol_id: usize,
     pub(crate) global_symbol_registry: AHashMap<&'static str, Symbol<'static>>,
     pub(crate) host_hooks: &'static dyn HostHooks,
-    execution_context_stack: Vec<ExecutionContext>,
+    /// Seeded PRNG used by `Math.random` if [`Options::random_seed`] is set.
+    random_state: Option<Xorshift128Plus>,
+    pub(crate) execution_context_stack: Vec<ExecutionContext>,
     /// Temporary storage for on-stack heap roots.
     ///
     /// TODO: With Realm-specific heaps we'll need a side-table to define which
@@ -327,6 +935,9 @@ pub struct Agent {
     pub(crate) stack_ref_collections: RefCell<Vec<HeapRootCollectionData>>,
     /// Temporary storage for on-stack VMs.
     pub(crate) vm_stack: Vec<NonNull<Vm>>,
+    /// Finished VMs kept for reuse, so that calls need not allocate new VM
+    /// buffers. These hold no Values.
+    pub(crate) vm_pool: Vec<Vm>,
     /// ### \[\[KeptAlive]]
     ///
     /// > Note: instead of storing objects in a list here, we only store a
@@ -334,12 +945,98 @@ pub struct Agent {
     pub(super) kept_alive: bool,
     /// Global counter for PrivateNames. This only ever grows.
     private_names_counter: u32,
+    /// Lines logged to the console while a script is run by
+    /// [`Agent::run_script_capturing_console`].
+    console_output: Option<Vec<std::string::String>>,
+    /// The most recent exception that was thrown out of ECMAScript code and
+    /// the location of its innermost throw.
+    exception_location: Option<(Value<'static>, SourceLocation<'static>)>,
+    /// Time origin and entries of the `performance` global.
+    pub(super) performance: PerformanceTimeline,
+    /// Heap size last reported to [`HostHooks::on_heap_grow`].
+    reported_heap_size: usize,
+    /// Allocation counter value at the last heap growth check.
+    heap_growth_checked_at: usize,
+    /// What the Agent is currently doing, and whether a panic has unwound
+    /// out of it.
+    reentrancy: Rc<ReentrancyState>,
+    /// True while a [`GcRoot`] exists.
+    gc_root_active: bool,
+    /// Global bindings captured by [`Agent::checkpoint_realm`].
+    realm_checkpoints: Vec<Option<RealmCheckpointRecord>>,
+    /// Initializers of the globals defined by [`Agent::define_lazy_global`]
+    /// that have not been initialized yet.
+    pub(crate) lazy_globals: LazyGlobals,
+    /// Slow path events reported by [`HostHooks::on_slow_path`].
+    pub(super) slow_paths: SlowPathLog,
+    /// Breakpoints set with [`Agent::set_breakpoint`].
+    pub(super) breakpoints: Breakpoints,
+    /// Objects whose elements are currently being joined into a string.
+    ///
+    /// Joining an object that is already being joined, as happens with
+    /// cyclic arrays, gives the empty String instead of recursing forever.
+    pub(crate) join_stack: Vec<Object<'static>>,
+    /// Module graphs being loaded by [`Module::load_requested_modules`].
+    #[cfg(feature = "deferred-module-loading")]
+    pub(crate) module_loading: ModuleLoading,
+}
+
+/// Owner of the root [`GcScope`] of an Agent, created by [`Agent::enter_gc`].
+///
+/// Scoped roots created through the GcScopes of the root are released when
+/// it is dropped. Globals are not: they stay valid until they are taken.
+#[must_use]
+pub struct GcRoot<'agent> {
+    agent: &'agent mut Agent,
+    gc: GcToken,
+    scope: ScopeToken,
+    stack_refs_len: usize,
+    stack_ref_collections_len: usize,
+    vm_stack_len: usize,
+}
+
+impl GcRoot<'_> {
+    /// Returns the Agent together with the root GcScope.
+    pub fn scope(&mut self) -> (&mut Agent, GcScope<'_, '_>) {
+        (self.agent, GcScope::new(&mut self.gc, &mut self.scope))
+    }
+}
+
+impl Drop for GcRoot<'_> {
+    fn drop(&mut self) {
+        let agent = &mut *self.agent;
+        agent.gc_root_active = false;
+        if std::thread::panicking() {
+            return;
+        }
+        // Values that were created in the root cannot outlive it, but the
+        // engine's own bookkeeping must not have released anything created
+        // before it or kept pointers into the Rust stack past it.
+        debug_assert!(
+            agent.stack_refs.borrow().len() >= self.stack_refs_len
+                && agent.stack_ref_collections.borrow().len() >= self.stack_ref_collections_len,
+            "Scoped roots created before the GcScope root were released inside it"
+        );
+        debug_assert_eq!(
+            agent.vm_stack.len(),
+            self.vm_stack_len,
+            "a VM leaked past the GcScope root"
+        );
+        agent.stack_refs.borrow_mut().truncate(self.stack_refs_len);
+        agent
+            .stack_ref_collections
+            .borrow_mut()
+            .truncate(self.stack_ref_collections_len);
+    }
 }
 
 impl Agent {
     pub(crate) fn new(options: Options, host_hooks: &'static dyn HostHooks) -> Self {
+        let heap = Heap::new();
         Self {
-            heap: Heap::new(),
+            reported_heap_size: heap.reserved_bytes(),
+            heap,
+            random_state: options.random_seed.map(Xorshift128Plus::new),
             options,
             symbol_id: 0,
             global_symbol_registry: AHashMap::default(),
@@ -348,12 +1045,96 @@ impl Agent {
             stack_refs: RefCell::new(Vec::with_capacity(64)),
             stack_ref_collections: RefCell::new(Vec::with_capacity(32)),
             vm_stack: Vec::with_capacity(16),
+            vm_pool: Vec::new(),
             kept_alive: false,
             private_names_counter: 0,
+            console_output: None,
+            exception_location: None,
+            performance: PerformanceTimeline::new(host_hooks.monotonic_now()),
+            heap_growth_checked_at: 0,
+            reentrancy: Rc::default(),
+            gc_root_active: false,
+            realm_checkpoints: Vec::new(),
+            lazy_globals: LazyGlobals::default(),
+            slow_paths: SlowPathLog::default(),
+            breakpoints: Breakpoints::default(),
+            join_stack: Vec::new(),
+            #[cfg(feature = "deferred-module-loading")]
+            module_loading: ModuleLoading::default(),
         }
     }
 
+    /// Runs the function with a newly created root GcScope, releasing the
+    /// Scoped roots created through it afterwards.
+    ///
+    /// ## Panics
+    ///
+    /// If a GcScope root already exists, eg. when called from inside another
+    /// `with_gc` call or from a built-in function.
+    pub fn with_gc<F, R>(&mut self, func: F) -> R
+    where
+        F: for<'agent, 'gc, 'scope> FnOnce(&'agent mut Agent, GcScope<'gc, 'scope>) -> R,
+    {
+        self.try_with_gc(func)
+            .unwrap_or_else(|err| panic!("Cannot create GcScope: {err}"))
+    }
+
+    /// Runs the function with a newly created root GcScope, returning an
+    /// error if a GcScope root already exists.
+    pub fn try_with_gc<F, R>(&mut self, func: F) -> Result<R, NestedGcRootError>
+    where
+        F: for<'agent, 'gc, 'scope> FnOnce(&'agent mut Agent, GcScope<'gc, 'scope>) -> R,
+    {
+        let mut root = self.try_enter_gc()?;
+        let (agent, gc) = root.scope();
+        Ok(func(agent, gc))
+    }
+
+    /// Creates the root GcScope, which lives until the returned [`GcRoot`] is
+    /// dropped. This is the non-closure form of [`Agent::with_gc`].
+    ///
+    /// ## Panics
+    ///
+    /// If a GcScope root already exists.
+    pub fn enter_gc(&mut self) -> GcRoot<'_> {
+        self.try_enter_gc()
+            .unwrap_or_else(|err| panic!("Cannot create GcScope: {err}"))
+    }
+
+    /// Creates the root GcScope, returning an error if one already exists.
+    pub fn try_enter_gc(&mut self) -> Result<GcRoot<'_>, NestedGcRootError> {
+        if self.gc_root_active {
+            return Err(NestedGcRootError);
+        }
+        self.gc_root_active = true;
+        // SAFETY: No other GcScope root exists.
+        let (gc, scope) = unsafe { GcScope::create_root() };
+        let stack_refs_len = self.stack_refs.borrow().len();
+        let stack_ref_collections_len = self.stack_ref_collections.borrow().len();
+        Ok(GcRoot {
+            stack_refs_len,
+            stack_ref_collections_len,
+            vm_stack_len: self.vm_stack.len(),
+            agent: self,
+            gc,
+            scope,
+        })
+    }
+
+    /// ## Panics
+    ///
+    /// If the Agent is poisoned or garbage collection is not allowed in its
+    /// current [`ExecutionState`].
     pub fn gc(&mut self, gc: GcScope) {
+        self.try_gc(gc)
+            .unwrap_or_else(|err| panic!("Cannot collect garbage: {err}"))
+    }
+
+    /// Performs garbage collection, returning an error if the Agent is
+    /// poisoned or garbage collection is not allowed in its current
+    /// [`ExecutionState`].
+    pub fn try_gc(&mut self, gc: GcScope) -> Result<(), ReentrancyError> {
+        let _guard = self.enter_operation(AgentOperation::Gc)?;
         let mut root_realms = self
             .heap
             .realms
@@ -362,6 +1143,95 @@ impl Agent {
             .map(|(i, _)| Some(Realm::from_index(i)))
             .collect::<Vec<_>>();
         heap_gc(self, &mut root_realms, gc);
+        Ok(())
+    }
+
+    /// Performs one incremental garbage collection slice, marking at most
+    /// `budget` heap entries. Returns true if the slice finished a garbage
+    /// collection.
+    ///
+    /// See [`GcAgent::gc_step`].
+    pub fn gc_step(&mut self, budget: usize, gc: GcScope) -> bool {
+        let _guard = self
+            .enter_operation(AgentOperation::Gc)
+            .unwrap_or_else(|err| panic!("Cannot collect garbage: {err}"));
+        let mut root_realms = self
+            .heap
+            .realms
+            .iter()
+            .enumerate()
+            .map(|(i, _)| Some(Realm::from_index(i)))
+            .collect::<Vec<_>>();
+        heap_gc_step(self, &mut root_realms, budget, gc)
+    }
+
+    /// Returns the current heap size and garbage collection statistics.
+    pub fn heap_statistics(&self) -> HeapStatistics {
+        self.heap.statistics()
+    }
+
+    /// Returns the number of live element vectors and slots in each capacity
+    /// class of the element arrays.
+    pub fn element_array_stats(&self) -> ElementArrayStats {
+        self.heap.elements.stats()
+    }
+
+    /// Returns the number of live entries, slots and reserved capacity of
+    /// each heap vector.
+    pub fn heap_vector_statistics(&self) -> Vec<HeapVectorStatistics> {
+        self.heap.vector_statistics()
+    }
+
+    /// Performs a garbage collection that compacts every heap vector
+    /// regardless of [`Options::compaction`], and then releases unused
+    /// capacity of the heap vectors back to the allocator.
+    pub fn compact_heap(&mut self, gc: GcScope) {
+        let policy = core::mem::replace(&mut self.options.compaction, CompactionPolicy::Always);
+        self.gc(gc);
+        self.options.compaction = policy;
+        self.heap.shrink_vectors();
+    }
+
+    /// Returns what the Agent is currently doing.
+    pub fn execution_state(&self) -> ExecutionState {
+        self.reentrancy.state()
+    }
+
+    /// Returns true if a panic has unwound out of the Agent while it was
+    /// executing. A poisoned Agent refuses to evaluate scripts, call
+    /// functions, run Jobs, and collect garbage.
+    pub fn is_poisoned(&self) -> bool {
+        self.reentrancy.is_poisoned()
+    }
+
+    /// Checks if `operation` may be started in the current state of the
+    /// Agent.
+    pub fn check_reentrancy(&self, operation: AgentOperation) -> Result<(), ReentrancyError> {
+        self.reentrancy.check(operation)
+    }
+
+    /// Starts a guarded operation. The previous state of the Agent is
+    /// restored when the returned guard is dropped.
+    pub(crate) fn enter_operation(
+        &self,
+        operation: AgentOperation,
+    ) -> Result<ExecutionGuard, ReentrancyError> {
+        self.reentrancy.check(operation)?;
+        let state = match operation {
+            // Garbage collection does not run ECMAScript code.
+            AgentOperation::Gc => self.reentrancy.state(),
+            AgentOperation::Evaluate | AgentOperation::Call | AgentOperation::RunJobs => {
+                ExecutionState::Executing
+            }
+        };
+        Ok(self.reentrancy.enter(state))
+    }
+
+    /// Calls [`HostHooks::on_garbage_collection`].
+    pub(crate) fn notify_garbage_collection(&mut self, gc: GcScope) {
+        let _guard = self.reentrancy.enter(ExecutionState::InGcCallback);
+        let host_hooks = self.host_hooks;
+        host_hooks.on_garbage_collection(self, gc);
     }
 
     /// Checks if garbage collection should be performed based on the number of
@@ -369,8 +1239,27 @@ impl Agent {
     pub(crate) fn check_gc(&mut self) -> bool {
         // Perform garbage collection if over 2 MiB of allocations have been
         // performed since last GC.
-        const ALLOC_COUNTER_LIMIT: usize = 1024 * 1024 * 2;
         self.heap.alloc_counter > ALLOC_COUNTER_LIMIT
+            || (self.options.gc_stress && self.heap.alloc_counter > 0)
+    }
+
+    /// Notifies the host through [`HostHooks::on_heap_grow`] if the heap has
+    /// grown enough since the last notification.
+    pub(crate) fn check_heap_growth(&mut self) {
+        // Measuring the heap walks all of its vectors: only do it after some
+        // allocations have been performed.
+        let alloc_counter = self.heap.alloc_counter;
+        if alloc_counter.abs_diff(self.heap_growth_checked_at) < HEAP_GROWTH_CHECK_INTERVAL {
+            return;
+        }
+        self.heap_growth_checked_at = alloc_counter;
+        let size = self.heap.reserved_bytes();
+        if size < self.reported_heap_size {
+            self.reported_heap_size = size;
+        } else if size - self.reported_heap_size >= HEAP_GROWTH_NOTIFICATION_STEP {
+            let before = core::mem::replace(&mut self.reported_heap_size, size);
+            self.host_hooks.on_heap_grow(before, size);
+        }
     }
 
     fn get_created_realm_root(&mut self) -> Realm<'static> {
@@ -382,7 +1271,8 @@ impl Agent {
 
     /// Creates a new Realm
     ///
-    /// This is intended for usage within BuiltinFunction calls.
+    /// This is intended for usage within BuiltinFunction calls. See
+    /// [`GcAgent::create_realm`] for the meaning of the parameters.
     pub fn create_realm<'gc>(
         &mut self,
         create_global_object: Option<
@@ -414,16 +1304,15 @@ impl Agent {
         >,
         initialize_global_object: Option<impl FnOnce(&mut Agent, Object, GcScope)>,
     ) -> Realm<'static> {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let gc = GcScope::new(&mut gc, &mut scope);
-
-        initialize_host_defined_realm(
-            self,
-            create_global_object,
-            create_global_this_value,
-            initialize_global_object,
-            gc,
-        );
+        self.with_gc(|agent, gc| {
+            initialize_host_defined_realm(
+                agent,
+                create_global_object,
+                create_global_this_value,
+                initialize_global_object,
+                gc,
+            )
+        });
         self.get_created_realm_root()
     }
 
@@ -431,13 +1320,177 @@ impl Agent {
     ///
     /// This is intended for usage within BuiltinFunction calls.
     fn create_default_realm(&mut self) -> Realm {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let gc = GcScope::new(&mut gc, &mut scope);
-
-        initialize_default_realm(self, gc);
+        self.with_gc(initialize_default_realm);
         self.get_created_realm_root()
     }
 
+    /// Clones the template Realm: the global object, the global environment,
+    /// the intrinsics and everything else reachable from the template are
+    /// copied into fresh heap data, so that mutations in the clone are
+    /// invisible to the template and to other clones. Functions of the clone
+    /// have the clone as their \[\[Realm]]. Immutable data, like strings,
+    /// Symbols and compiled scripts, is shared. See the
+    /// [`realm_clone`](crate::heap::realm_clone) module for details.
+    ///
+    /// This is much cheaper than creating a new Realm and running the same
+    /// setup scripts in it.
+    ///
+    /// ## Panics
+    ///
+    /// If heap data that cannot be cloned is reachable from the template.
+    pub fn clone_realm<'gc>(&mut self, template: Realm, gc: NoGcScope<'gc, '_>) -> Realm<'gc> {
+        self.try_clone_realm(template, gc)
+            .expect("Cannot clone the template Realm")
+    }
+
+    /// Clones the template Realm, returning an error if heap data that cannot
+    /// be cloned, like a Map or a Promise, is reachable from it. See
+    /// [`Agent::clone_realm`].
+    pub fn try_clone_realm<'gc>(
+        &mut self,
+        template: Realm,
+        gc: NoGcScope<'gc, '_>,
+    ) -> Result<Realm<'gc>, RealmCloneError> {
+        clone_realm(&mut self.heap, template).map(|realm| realm.bind(gc))
+    }
+
+    /// Captures the global bindings of the Realm: the own properties of its
+    /// global object and the declarations of its global environment.
+    /// [`Agent::rollback_realm`] restores them, undoing the global mutations
+    /// of the scripts run since. Objects reachable from the global bindings
+    /// are not captured. See the [`RealmCheckpoint`] type for details.
+    ///
+    /// The checkpoint keeps the Realm alive until it is passed to
+    /// [`Agent::remove_realm_checkpoint`].
+    ///
+    /// ## Panics
+    ///
+    /// If the global object of the Realm is not an ordinary object.
+    pub fn checkpoint_realm(&mut self, realm: Realm, gc: NoGcScope) -> RealmCheckpoint {
+        let record = RealmCheckpointRecord::new(self, realm, gc);
+        let index = if let Some(index) = self
+            .realm_checkpoints
+            .iter()
+            .position(|entry| entry.is_none())
+        {
+            self.realm_checkpoints[index] = Some(record);
+            index
+        } else {
+            self.realm_checkpoints.push(Some(record));
+            self.realm_checkpoints.len() - 1
+        };
+        RealmCheckpoint {
+            index: u32::try_from(index).expect("Too many Realm checkpoints"),
+        }
+    }
+
+    /// Restores the global bindings of the checkpointed Realm to the state
+    /// captured by [`Agent::checkpoint_realm`]. The checkpoint stays valid and
+    /// can be rolled back to again.
+    ///
+    /// ## Panics
+    ///
+    /// If the checkpoint does not belong to this Agent.
+    pub fn rollback_realm(&mut self, checkpoint: &RealmCheckpoint, gc: NoGcScope) {
+        let record = self
+            .realm_checkpoints
+            .get_mut(checkpoint.index as usize)
+            .and_then(Option::take)
+            .expect("Cannot roll back to a non-existing checkpoint");
+        record.rollback(self, gc);
+        self.realm_checkpoints[checkpoint.index as usize] = Some(record);
+    }
+
+    /// Releases the checkpoint, allowing the Realm and the captured bindings
+    /// to be garbage collected.
+    ///
+    /// ## Panics
+    ///
+    /// If the checkpoint does not belong to this Agent.
+    pub fn remove_realm_checkpoint(&mut self, checkpoint: RealmCheckpoint) {
+        self.realm_checkpoints
+            .get_mut(checkpoint.index as usize)
+            .and_then(Option::take)
+            .expect("Cannot remove a non-existing checkpoint");
+    }
+
+    /// Defines a global property in the Realm whose value is computed by
+    /// `init` the first time it is needed, eg. to avoid creating the objects
+    /// of a large host API until a script uses them.
+    ///
+    /// The property is defined on the global object as a writable,
+    /// configurable, and non-enumerable data property named `name`. It is
+    /// seen by `in` and `Object.getOwnPropertyNames` without running `init`;
+    /// reading or redefining the property, or asking for its descriptor, runs
+    /// `init` exactly once and replaces the value of the property with its
+    /// result. Deleting the property before that cancels `init`.
+    ///
+    /// ## Panics
+    ///
+    /// If the global object of the Realm is not an ordinary object, or if the
+    /// property cannot be defined on it.
+    pub fn define_lazy_global(
+        &mut self,
+        realm: Realm,
+        name: &str,
+        init: LazyGlobalInitializer,
+        gc: NoGcScope,
+    ) {
+        let realm = realm.bind(gc);
+        let Ok(global_object) = OrdinaryObject::try_from(self[realm].global_object) else {
+            panic!("Cannot define a lazy global on a global object that is not an ordinary object");
+        };
+        let global_object = global_object.bind(gc);
+        let key = PropertyKey::from_str(self, name, gc);
+        // Replace a previously defined lazy global without initializing it.
+        self.lazy_globals.remove(global_object, key);
+        let defined = ordinary_define_own_property(
+            self,
+            global_object,
+            key,
+            PropertyDescriptor {
+                value: Some(Value::Undefined),
+                writable: Some(true),
+                enumerable: Some(false),
+                configurable: Some(true),
+                ..Default::default()
+            },
+            gc,
+        );
+        assert!(defined, "Cannot define the lazy global '{name}'");
+        self.lazy_globals.insert(global_object, key, init);
+    }
+
+    /// Serializes the heap into a startup snapshot, from which
+    /// [`GcAgent::from_startup_snapshot`] can restore the Realm without
+    /// building its intrinsics again.
+    ///
+    /// ## Panics
+    ///
+    /// If the heap contains anything other than the intrinsics of a single
+    /// Realm, eg. after scripts have been run in it.
+    pub fn serialize_startup_snapshot(&self) -> Vec<u8> {
+        write_startup_snapshot(self)
+    }
+
+    /// Creates an Agent from a startup snapshot, returning it together with
+    /// the Realm stored in the snapshot.
+    ///
+    /// ## Safety
+    ///
+    /// The snapshot must have been created by
+    /// [`Agent::serialize_startup_snapshot`] in the same build of the engine.
+    unsafe fn from_startup_snapshot(
+        bytes: &[u8],
+        options: Options,
+        host_hooks: &'static dyn HostHooks,
+    ) -> Result<(Self, Realm<'static>), SnapshotError> {
+        let mut agent = Self::new(options, host_hooks);
+        // SAFETY: Passed on to the caller.
+        let realm = unsafe { read_startup_snapshot(&mut agent.heap, bytes)? };
+        Ok((agent, realm))
+    }
+
     pub fn run_in_realm<F, R>(&mut self, realm: Realm, func: F) -> R
     where
         F: for<'agent, 'gc, 'scope> FnOnce(&'agent mut Agent, GcScope<'gc, 'scope>) -> R,
@@ -449,10 +1502,7 @@ impl Agent {
             realm: realm.unbind(),
             script_or_module: None,
         });
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let gc = GcScope::new(&mut gc, &mut scope);
-
-        let result = func(self, gc);
+        let result = self.with_gc(func);
         assert_eq!(
             self.execution_context_stack.len(),
             execution_stack_depth_before_call + 1
@@ -504,37 +1554,149 @@ impl Agent {
         &mut self[id]
     }
 
-    pub fn create_exception_with_static_message<'a>(
+    /// Fallibly access a Script Record.
+    ///
+    /// Returns `None` if the Script does not point to a live Script Record in
+    /// this Agent, eg. because it has been garbage collected or belongs to a
+    /// different Agent.
+    pub fn try_get_script(&self, script: Script) -> Option<&ScriptRecord<'static>> {
+        self.heap.try_get(script).ok()
+    }
+
+    /// Fallibly access a Realm Record.
+    ///
+    /// Returns `None` if the Realm does not point to a live Realm Record in
+    /// this Agent.
+    pub fn try_get_realm(&self, realm: Realm) -> Option<&RealmRecord<'static>> {
+        self.heap.try_get(realm).ok()
+    }
+
+    pub fn create_exception_with_static_message<'a>(
+        &mut self,
+        kind: ExceptionType,
+        message: &'static str,
+        gc: NoGcScope<'a, '_>,
+    ) -> Value<'a> {
+        let message = String::from_static_str(self, message, gc).unbind();
+        self.heap
+            .create(ErrorHeapData::new(kind, Some(message), None))
+            .into_value()
+    }
+
+    pub(crate) fn todo<'a>(&mut self, feature: &'static str, gc: NoGcScope<'a, '_>) -> JsError<'a> {
+        self.throw_exception(
+            ExceptionType::Error,
+            format!("{feature} not implemented"),
+            gc,
+        )
+    }
+
+    /// ### [5.2.3.2 Throw an Exception](https://tc39.es/ecma262/#sec-throw-an-exception)
+    pub fn throw_exception_with_static_message<'a>(
+        &mut self,
+        kind: ExceptionType,
+        message: &'static str,
+        gc: NoGcScope<'a, '_>,
+    ) -> JsError<'a> {
+        JsError(
+            self.create_exception_with_static_message(kind, message, gc)
+                .unbind(),
+        )
+    }
+
+    /// ### [5.2.3.2 Throw an Exception](https://tc39.es/ecma262/#sec-throw-an-exception)
+    ///
+    /// Returns an `Err` holding a new exception of the given type, ready to be
+    /// returned from a built-in function.
+    pub fn throw<'a, T>(
+        &mut self,
+        kind: ExceptionType,
+        message: &'static str,
+        gc: NoGcScope<'a, '_>,
+    ) -> JsResult<'a, T> {
+        Err(self.throw_exception_with_static_message(kind, message, gc))
+    }
+
+    /// Returns an `Err` holding a new TypeError; see [`Agent::throw`].
+    pub fn type_error<'a, T>(
+        &mut self,
+        message: &'static str,
+        gc: NoGcScope<'a, '_>,
+    ) -> JsResult<'a, T> {
+        self.throw(ExceptionType::TypeError, message, gc)
+    }
+
+    /// Returns an `Err` holding a new RangeError; see [`Agent::throw`].
+    pub fn range_error<'a, T>(
+        &mut self,
+        message: &'static str,
+        gc: NoGcScope<'a, '_>,
+    ) -> JsResult<'a, T> {
+        self.throw(ExceptionType::RangeError, message, gc)
+    }
+
+    /// Throws a RangeError if a string of `byte_length` bytes would exceed
+    /// [`Options::max_string_length`].
+    pub(crate) fn check_string_length<'a>(
+        &mut self,
+        byte_length: usize,
+        gc: NoGcScope<'a, '_>,
+    ) -> JsResult<'a, ()> {
+        let max_string_length = self
+            .options
+            .max_string_length
+            .unwrap_or(DEFAULT_MAX_STRING_LENGTH);
+        if byte_length > max_string_length {
+            return self.range_error("Invalid string length", gc);
+        }
+        Ok(())
+    }
+
+    /// Returns true if the elements of `object` are currently being joined
+    /// into a string.
+    pub(crate) fn is_being_joined(&self, object: Object) -> bool {
+        self.join_stack.contains(&object.unbind())
+    }
+
+    /// Returns true if an Array backing store of `capacity` elements is
+    /// within [`Options::max_array_element_capacity`].
+    pub(crate) fn is_valid_array_element_capacity(&self, capacity: u64) -> bool {
+        let max_capacity = self
+            .options
+            .max_array_element_capacity
+            .unwrap_or(DEFAULT_MAX_ARRAY_ELEMENT_CAPACITY);
+        capacity <= u64::from(max_capacity)
+    }
+
+    /// Throws a RangeError if an Array backing store of `capacity` elements
+    /// would exceed [`Options::max_array_element_capacity`].
+    pub(crate) fn check_array_element_capacity<'a>(
+        &mut self,
+        capacity: u64,
+        gc: NoGcScope<'a, '_>,
+    ) -> JsResult<'a, ()> {
+        if !self.is_valid_array_element_capacity(capacity) {
+            return self.range_error("Invalid array length", gc);
+        }
+        Ok(())
+    }
+
+    /// Returns an `Err` holding a new ReferenceError; see [`Agent::throw`].
+    pub fn reference_error<'a, T>(
         &mut self,
-        kind: ExceptionType,
         message: &'static str,
         gc: NoGcScope<'a, '_>,
-    ) -> Value<'a> {
-        let message = String::from_static_str(self, message, gc).unbind();
-        self.heap
-            .create(ErrorHeapData::new(kind, Some(message), None))
-            .into_value()
-    }
-
-    pub(crate) fn todo<'a>(&mut self, feature: &'static str, gc: NoGcScope<'a, '_>) -> JsError<'a> {
-        self.throw_exception(
-            ExceptionType::Error,
-            format!("{feature} not implemented"),
-            gc,
-        )
+    ) -> JsResult<'a, T> {
+        self.throw(ExceptionType::ReferenceError, message, gc)
     }
 
-    /// ### [5.2.3.2 Throw an Exception](https://tc39.es/ecma262/#sec-throw-an-exception)
-    pub fn throw_exception_with_static_message<'a>(
+    /// Returns an `Err` holding a new SyntaxError; see [`Agent::throw`].
+    pub fn syntax_error<'a, T>(
         &mut self,
-        kind: ExceptionType,
         message: &'static str,
         gc: NoGcScope<'a, '_>,
-    ) -> JsError<'a> {
-        JsError(
-            self.create_exception_with_static_message(kind, message, gc)
-                .unbind(),
-        )
+    ) -> JsResult<'a, T> {
+        self.throw(ExceptionType::SyntaxError, message, gc)
     }
 
     pub fn throw_exception<'a>(
@@ -551,6 +1713,26 @@ impl Agent {
         )
     }
 
+    /// ### [5.2.3.2 Throw an Exception](https://tc39.es/ecma262/#sec-throw-an-exception)
+    ///
+    /// Throws an exception whose message is only formatted from the template
+    /// and arguments when it is observed.
+    pub(crate) fn throw_exception_with_deferred_message<'a, const N: usize>(
+        &mut self,
+        kind: ExceptionType,
+        template: ErrorMessage,
+        arguments: [MessageArgument; N],
+        gc: NoGcScope<'a, '_>,
+    ) -> JsError<'a> {
+        let message = DeferredErrorMessage::new(template, arguments).unbind();
+        JsError(
+            self.heap
+                .create(ErrorHeapData::with_deferred_message(kind, message))
+                .into_value()
+                .bind(gc),
+        )
+    }
+
     pub fn throw_exception_with_message<'a>(
         &mut self,
         kind: ExceptionType,
@@ -591,6 +1773,79 @@ impl Agent {
         self.execution_context_stack.pop()
     }
 
+    /// Record the location of an exception thrown out of the running
+    /// ECMAScript code.
+    ///
+    /// An exception propagating through several functions keeps the location
+    /// of its innermost throw.
+    pub(crate) fn record_exception_location(&mut self, error: JsError, span: Option<Span>) {
+        let error = error.value().unbind();
+        if matches!(self.exception_location, Some((recorded, _)) if recorded == error) {
+            return;
+        }
+        let source_code = self.running_source_code();
+        self.exception_location = source_code
+            .zip(span)
+            .map(|(source_code, span)| (error, SourceLocation { source_code, span }));
+    }
+
+    /// Source code of the running execution context, if it is evaluating
+    /// ECMAScript code.
+    pub(crate) fn running_source_code(&self) -> Option<SourceCode<'static>> {
+        self.execution_context_stack
+            .last()
+            .and_then(|context| context.ecmascript_code.as_ref())
+            .map(|code| code.source_code)
+    }
+
+    /// Forget the location of an exception that was handled.
+    pub(crate) fn clear_exception_location(&mut self) {
+        self.exception_location = None;
+    }
+
+    /// Report an exception thrown out of a Script evaluation or a Job to the
+    /// host, and return it.
+    ///
+    /// Nothing is reported if ECMAScript code is still on the execution
+    /// context stack, as that code may yet handle the exception.
+    pub(crate) fn report_uncaught_exception<'a>(
+        &mut self,
+        error: JsError,
+        script: Option<Script>,
+        mut gc: GcScope<'a, '_>,
+    ) -> JsError<'a> {
+        let error = error.bind(gc.nogc());
+        let location = self
+            .exception_location
+            .take()
+            .filter(|(recorded, _)| *recorded == error.value())
+            .map(|(_, location)| location);
+        if self
+            .execution_context_stack
+            .iter()
+            .any(|context| context.function.is_some() || context.ecmascript_code.is_some())
+        {
+            return error.unbind().bind(gc.into_nogc());
+        }
+        let origin = match script {
+            Some(script) => ExceptionOrigin::Script {
+                script: script.unbind(),
+                location,
+            },
+            None => ExceptionOrigin::Job { location },
+        };
+        if let Value::Error(error) = error.value() {
+            error.materialize_message(self);
+        }
+        let scoped_error = error.scope(self, gc.nogc());
+        let host_hooks = self.host_hooks;
+        let guard = self.reentrancy.enter(ExecutionState::InHostHook);
+        host_hooks.report_uncaught_exception(self, error.value().unbind(), origin, gc.reborrow());
+        drop(guard);
+        // SAFETY: The error is not shared.
+        unsafe { scoped_error.take(self) }.bind(gc.into_nogc())
+    }
+
     pub(crate) fn current_source_code<'a>(&self, gc: NoGcScope<'a, '_>) -> SourceCode<'a> {
         self.execution_context_stack
             .last()
@@ -703,6 +1958,15 @@ impl Agent {
             .bind(gc)
     }
 
+    /// Produce the next `Math.random` value, either from the seeded PRNG or
+    /// from the host.
+    pub(crate) fn random_f64(&mut self) -> f64 {
+        match &mut self.random_state {
+            Some(rng) => rng.next_f64(),
+            None => self.host_hooks.random_f64(),
+        }
+    }
+
     /// Get access to the Host data, useful to share state between calls of built-in functions.
     ///
     /// Note: This will panic if not implemented manually.
@@ -733,6 +1997,353 @@ impl Agent {
         };
         script_evaluation(self, script.unbind(), gc)
     }
+
+    /// Run promise Jobs taken from [`HostHooks::dequeue_promise_job`] until
+    /// the queue is empty or `limit` Jobs have been run.
+    ///
+    /// A Job that keeps enqueuing new Jobs would otherwise never let the
+    /// queue drain: the limit lets the host bail out of such a loop and
+    /// decide what to do with the remaining Jobs. An exception thrown out of
+    /// a Job stops the draining and is returned.
+    ///
+    /// ## Panics
+    ///
+    /// If the Agent is poisoned or Jobs may not be run in its current
+    /// [`ExecutionState`].
+    pub fn run_microtasks<'gc>(
+        &mut self,
+        limit: usize,
+        mut gc: GcScope<'gc, '_>,
+    ) -> JsResult<'gc, MicrotaskCheckpoint> {
+        for _ in 0..limit {
+            let Some(job) = self.host_hooks.dequeue_promise_job() else {
+                return Ok(MicrotaskCheckpoint::Drained);
+            };
+            job.run(self, gc.reborrow()).unbind()?;
+        }
+        Ok(MicrotaskCheckpoint::LimitReached)
+    }
+
+    /// Get the source text that a Script was parsed from, or None if the
+    /// Script no longer exists.
+    ///
+    /// Use [`Script::source_code`] to map offsets into the source text to
+    /// lines and columns.
+    pub fn script_source(&self, script: Script) -> Option<&str> {
+        let record = self.heap.scripts.get(script.into_index())?.as_ref()?;
+        Some(record.source_code.get_source_text(self))
+    }
+
+    /// Define a builtin method on an object, eg. to extend a builtin
+    /// prototype from host code.
+    ///
+    /// The method is created in the current Realm with the given `name` and
+    /// `length`, and is defined on `target` as a writable, configurable, and
+    /// non-enumerable property named `name`, like the methods of builtin
+    /// prototypes. Throws a TypeError if the property cannot be defined.
+    pub fn install_method<'gc>(
+        &mut self,
+        target: Object,
+        name: &str,
+        length: u8,
+        behaviour: Behaviour,
+        mut gc: GcScope<'gc, '_>,
+    ) -> JsResult<'gc, BuiltinFunction<'gc>> {
+        let target = target.bind(gc.nogc());
+        let realm = self.current_realm(gc.nogc()).unbind();
+        let function_name = String::from_str(self, name, gc.nogc()).unbind();
+        let method = BuiltinFunctionBuilder::new_dynamic(self, realm)
+            .with_length(length)
+            .with_name(function_name)
+            .with_behaviour(behaviour)
+            .build()
+            .bind(gc.nogc());
+        let key = PropertyKey::from_str(self, name, gc.nogc());
+        let scoped_method = method.scope(self, gc.nogc());
+        define_property_or_throw(
+            self,
+            target.unbind(),
+            key.unbind(),
+            PropertyDescriptor {
+                value: Some(method.into_value().unbind()),
+                writable: Some(true),
+                enumerable: Some(false),
+                configurable: Some(true),
+                ..Default::default()
+            },
+            gc.reborrow(),
+        )
+        .unbind()?;
+        Ok(scoped_method.get(self).bind(gc.into_nogc()))
+    }
+
+    /// Evaluate source text as a script inside a host-provided scope.
+    ///
+    /// The scope objects are layered on top of the current Realm's global
+    /// environment the same way as `with` statements would, the last object
+    /// being the innermost: identifiers are looked up in the scope objects
+    /// from the innermost outwards before the global environment, and each
+    /// scope object's `Symbol.unscopables` is honoured. `this` evaluates to
+    /// `this_value`.
+    ///
+    /// Var and function declarations of sloppy mode source text are created
+    /// as properties of the innermost scope object, unless
+    /// [`EvalInScopeOptions::declare_in_global`] is set or no scope objects
+    /// are given, in which case they go to the global object. The
+    /// declarations of strict mode source text stay local to the evaluation.
+    pub fn eval_in_scope<'gc>(
+        &mut self,
+        source_text: String,
+        this_value: Value,
+        scope_objects: &[Object],
+        options: EvalInScopeOptions,
+        mut gc: GcScope<'gc, '_>,
+    ) -> JsResult<'gc, Value<'gc>> {
+        let this_value = this_value.bind(gc.nogc());
+        let realm = self.current_realm(gc.nogc());
+        let script = match parse_script(self, source_text, realm, false, None, gc.nogc()) {
+            Ok(script) => script,
+            Err(err) => {
+                let message =
+                    String::from_string(self, err.first().unwrap().message.to_string(), gc.nogc());
+                return Err(self
+                    .throw_exception_with_message(
+                        ExceptionType::SyntaxError,
+                        message.unbind(),
+                        gc.into_nogc(),
+                    )
+                    .unbind());
+            }
+        };
+        let ScriptRecord {
+            ecmascript_code: program,
+            source_code,
+            ..
+        } = &self[script];
+        let source_code = *source_code;
+        // SAFETY: The Program's contents live in the SourceCode's allocator,
+        // which the Script keeps alive; the Script itself is kept alive by the
+        // execution context pushed below. See global_declaration_instantiation.
+        let program =
+            unsafe { core::mem::transmute::<&Program, &'static Program<'static>>(program) };
+        if program.is_empty() {
+            return Ok(Value::Undefined);
+        }
+        let strict = program.has_use_strict_directive();
+
+        let global_env = Environment::Global(self[realm].global_env.unwrap()).bind(gc.nogc());
+        // The this binding sits right above the global environment. Its
+        // function object is the realm's eval function: the evaluation
+        // behaves like an indirect eval called with a custom this value.
+        let eval_function = self.get_realm_record_by_id(realm).intrinsics().eval();
+        let mut scope_env = Environment::Function(new_fixed_this_environment(
+            self,
+            eval_function.into_function(),
+            this_value,
+            global_env,
+            gc.nogc(),
+        ));
+        for &object in scope_objects {
+            scope_env = Environment::Object(new_object_environment(
+                self,
+                object,
+                true,
+                Some(scope_env),
+                gc.nogc(),
+            ));
+        }
+        let lexical_environment = Environment::Declarative(new_declarative_environment(
+            self,
+            Some(scope_env),
+            gc.nogc(),
+        ));
+        let variable_environment = if strict {
+            lexical_environment
+        } else if options.declare_in_global || scope_objects.is_empty() {
+            global_env
+        } else {
+            scope_env
+        };
+
+        self.push_execution_context(ExecutionContext {
+            function: None,
+            realm: realm.unbind(),
+            script_or_module: Some(ScriptOrModule::Script(script.unbind())),
+            ecmascript_code: Some(ECMAScriptCodeEvaluationState {
+                lexical_environment: lexical_environment.unbind(),
+                variable_environment: variable_environment.unbind(),
+                private_environment: None,
+                is_strict_mode: strict,
+                source_code: source_code.unbind(),
+            }),
+        });
+
+        let result = eval_declaration_instantiation(
+            self,
+            program,
+            variable_environment.unbind(),
+            lexical_environment.unbind(),
+            None,
+            strict,
+            gc.reborrow(),
+        )
+        .unbind()
+        .bind(gc.nogc());
+        let result = match result {
+            Ok(_) => {
+                let exe =
+                    Executable::compile_eval_body(self, program, gc.nogc()).scope(self, gc.nogc());
+                Vm::execute(self, exe, None, gc).into_js_result()
+            }
+            Err(err) => Err(err.unbind().bind(gc.into_nogc())),
+        };
+
+        self.pop_execution_context();
+        result
+    }
+
+    /// Run a script in the current Realm, capturing its console output.
+    ///
+    /// For the duration of the script, the global `console` property is
+    /// replaced with an object whose `log`, `info`, `warn`, `error`, and
+    /// `debug` methods each record one line of output. The previous `console`
+    /// property is restored afterwards; a non-configurable `console` property
+    /// results in a TypeError. Returns the completion of the script and the
+    /// recorded lines in order.
+    pub fn run_script_capturing_console<'gc>(
+        &mut self,
+        source_text: String,
+        mut gc: GcScope<'gc, '_>,
+    ) -> (JsResult<'gc, Value<'gc>>, Vec<std::string::String>) {
+        let source_text = source_text.scope(self, gc.nogc());
+        let global = self.current_global_object(gc.nogc()).scope(self, gc.nogc());
+        let key = PropertyKey::from_static_str(self, "console", gc.nogc()).scope(self, gc.nogc());
+
+        let previous_console =
+            match global
+                .get(self)
+                .internal_get_own_property(self, key.get(self), gc.reborrow())
+            {
+                Ok(previous_console) => previous_console.unbind(),
+                Err(err) => return (Err(err.unbind().bind(gc.into_nogc())), vec![]),
+            };
+        let previous_console = previous_console.map(|desc| desc.scope(self, gc.nogc()));
+        let console = create_capturing_console(self, gc.nogc());
+        match global.get(self).internal_define_own_property(
+            self,
+            key.get(self),
+            PropertyDescriptor {
+                value: Some(console.into_value().unbind()),
+                writable: Some(true),
+                enumerable: Some(false),
+                configurable: Some(true),
+                ..Default::default()
+            },
+            gc.reborrow(),
+        ) {
+            Ok(true) => {}
+            Ok(false) => {
+                return (
+                    Err(self.throw_exception_with_static_message(
+                        ExceptionType::TypeError,
+                        "Cannot replace the global console property",
+                        gc.into_nogc(),
+                    )),
+                    vec![],
+                );
+            }
+            Err(err) => return (Err(err.unbind().bind(gc.into_nogc())), vec![]),
+        }
+
+        let outer_output = self.console_output.replace(vec![]);
+        let result = self
+            .run_script(source_text.get(self), gc.reborrow())
+            .unbind();
+        let output = core::mem::replace(&mut self.console_output, outer_output).unwrap();
+
+        let restored = if let Some(previous_console) = previous_console {
+            let previous_console = previous_console.take(self, gc.nogc());
+            global.get(self).internal_define_own_property(
+                self,
+                key.get(self),
+                previous_console.unbind(),
+                gc.reborrow(),
+            )
+        } else {
+            global
+                .get(self)
+                .internal_delete(self, key.get(self), gc.reborrow())
+        }
+        .unbind();
+        let gc = gc.into_nogc();
+        let result = match (result, restored) {
+            (Ok(value), Ok(_)) => Ok(value.bind(gc)),
+            (Err(err), _) | (Ok(_), Err(err)) => Err(err.unbind().bind(gc)),
+        };
+        (result, output)
+    }
+}
+
+/// Create the `console` object installed by
+/// [`Agent::run_script_capturing_console`].
+fn create_capturing_console<'a>(agent: &mut Agent, gc: NoGcScope<'a, '_>) -> Object<'a> {
+    /// Record the arguments converted to strings and separated by spaces as
+    /// one line of console output.
+    fn console_method<'gc>(
+        agent: &mut Agent,
+        _this: Value,
+        args: ArgumentsList,
+        mut gc: GcScope<'gc, '_>,
+    ) -> JsResult<'gc, Value<'gc>> {
+        let args = args
+            .iter()
+            .map(|arg| arg.scope(agent, gc.nogc()))
+            .collect::<Vec<_>>();
+        let mut line = std::string::String::new();
+        for (i, arg) in args.into_iter().enumerate() {
+            if i > 0 {
+                line.push(' ');
+            }
+            let arg = arg.get(agent).bind(gc.nogc());
+            let string = if let Value::Symbol(symbol) = arg {
+                symbol.descriptive_string(agent, gc.nogc())
+            } else {
+                to_string(agent, arg.unbind(), gc.reborrow())
+                    .unbind()?
+                    .bind(gc.nogc())
+            };
+            line.push_str(&string.to_string_lossy(agent));
+        }
+        if let Some(output) = &mut agent.console_output {
+            output.push(line);
+        }
+        Ok(Value::Undefined)
+    }
+
+    let console =
+        ordinary_object_create_with_intrinsics(agent, Some(ProtoIntrinsics::Object), None, gc);
+    for name in ["log", "info", "warn", "error", "debug"] {
+        let function = create_builtin_function(
+            agent,
+            Behaviour::Regular(console_method),
+            BuiltinFunctionArgs::new(0, name),
+            gc,
+        );
+        let key = PropertyKey::from_static_str(agent, name, gc);
+        unwrap_try(console.try_define_own_property(
+            agent,
+            key,
+            PropertyDescriptor {
+                value: Some(function.into_value()),
+                writable: Some(true),
+                enumerable: Some(true),
+                configurable: Some(true),
+                ..Default::default()
+            },
+            gc,
+        ));
+    }
+    console
 }
 
 /// ### [9.4.1 GetActiveScriptOrModule ()](https://tc39.es/ecma262/#sec-getactivescriptormodule)
@@ -863,14 +2474,46 @@ impl HeapMarkAndSweep for Agent {
             stack_refs,
             stack_ref_collections,
             vm_stack,
+            vm_pool: _,
             options: _,
             symbol_id: _,
             global_symbol_registry: _,
             host_hooks: _,
+            random_state: _,
             kept_alive: _,
             private_names_counter: _,
+            console_output: _,
+            exception_location,
+            performance,
+            reported_heap_size: _,
+            heap_growth_checked_at: _,
+            reentrancy: _,
+            gc_root_active: _,
+            realm_checkpoints,
+            lazy_globals,
+            slow_paths,
+            breakpoints,
+            join_stack,
+            #[cfg(feature = "deferred-module-loading")]
+            module_loading,
         } = self;
 
+        if let Some((error, location)) = exception_location {
+            error.mark_values(queues);
+            location.source_code.mark_values(queues);
+        }
+        performance.mark_values(queues);
+        realm_checkpoints
+            .iter()
+            .for_each(|checkpoint| checkpoint.mark_values(queues));
+        lazy_globals.mark_values(queues);
+        slow_paths.mark_values(queues);
+        breakpoints.mark_values(queues);
+        join_stack
+            .iter()
+            .for_each(|object| object.mark_values(queues));
+        #[cfg(feature = "deferred-module-loading")]
+        module_loading.mark_values(queues);
         execution_context_stack.iter().for_each(|ctx| {
             ctx.mark_values(queues);
         });
@@ -911,14 +2554,46 @@ impl HeapMarkAndSweep for Agent {
             stack_refs,
             stack_ref_collections,
             vm_stack,
+            vm_pool: _,
             options: _,
             symbol_id: _,
             global_symbol_registry: _,
             host_hooks: _,
+            random_state: _,
             kept_alive: _,
             private_names_counter: _,
+            console_output: _,
+            exception_location,
+            performance,
+            reported_heap_size: _,
+            heap_growth_checked_at: _,
+            reentrancy: _,
+            gc_root_active: _,
+            realm_checkpoints,
+            lazy_globals,
+            slow_paths,
+            breakpoints,
+            join_stack,
+            #[cfg(feature = "deferred-module-loading")]
+            module_loading,
         } = self;
 
+        if let Some((error, location)) = exception_location {
+            error.sweep_values(compactions);
+            location.source_code.sweep_values(compactions);
+        }
+        performance.sweep_values(compactions);
+        realm_checkpoints
+            .iter_mut()
+            .for_each(|checkpoint| checkpoint.sweep_values(compactions));
+        lazy_globals.sweep_values(compactions);
+        slow_paths.sweep_values(compactions);
+        breakpoints.sweep_values(compactions);
+        join_stack
+            .iter_mut()
+            .for_each(|object| object.sweep_values(compactions));
+        #[cfg(feature = "deferred-module-loading")]
+        module_loading.sweep_values(compactions);
         execution_context_stack
             .iter_mut()
             .for_each(|entry| entry.sweep_values(compactions));
@@ -935,3 +2610,695 @@ impl HeapMarkAndSweep for Agent {
             .for_each(|entry| unsafe { entry.as_mut().sweep_values(compactions) });
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::{cell::RefCell, collections::VecDeque};
+
+    use crate::{
+        ecmascript::{
+            builtins::{ArgumentsList, Array, Behaviour},
+            execution::{
+                Agent, DefaultHostHooks, JsResult,
+                agent::{EvalInScopeOptions, ExceptionOrigin, GcAgent, HostHooks, Job, Options},
+                initialize_default_realm,
+            },
+            scripts_and_modules::script::{
+                Script, parse_script, script_evaluation, test::evaluate,
+            },
+            types::{
+                Function, InternalMethods, IntoObject, IntoValue, Object, OrdinaryObject,
+                PropertyKey, String, Value,
+            },
+        },
+        engine::{
+            Global,
+            context::{Bindable, GcScope},
+        },
+        heap::HeapAccessErrorReason,
+    };
+
+    #[test]
+    fn foreign_global() {
+        let mut agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+        let realm = agent.create_default_realm();
+        let global = agent.run_in_realm(&realm, |agent, gc| {
+            let object = agent.current_global_object(gc.nogc());
+            Global::new(agent, object.unbind())
+        });
+
+        let mut other_agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+        let other_realm = other_agent.create_default_realm();
+        other_agent.run_in_realm(&other_realm, |agent, gc| {
+            let err = global.try_get(agent, gc.nogc()).unwrap_err();
+            assert_eq!(err.kind, "Global");
+            assert_eq!(err.reason, HeapAccessErrorReason::OutOfBounds);
+        });
+
+        agent.run_in_realm(&realm, |agent, gc| {
+            let object = global.try_take(agent).unwrap();
+            assert_eq!(object, agent.current_global_object(gc.nogc()));
+        });
+        agent.remove_realm(realm);
+    }
+
+    #[test]
+    fn removed_realm_root() {
+        let mut agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+        let realm = agent.create_default_realm();
+        let other_realm = agent.create_default_realm();
+        agent.remove_realm(other_realm);
+
+        let mut other_agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+        let _ = other_agent.create_default_realm();
+        let foreign_realm = other_agent.create_default_realm();
+        let err = agent
+            .try_run_in_realm(&foreign_realm, |_, _| ())
+            .unwrap_err();
+        assert_eq!(err.kind, "RealmRoot");
+        assert_eq!(err.index, 1);
+
+        agent.remove_realm(realm);
+    }
+
+    #[test]
+    fn captures_console_lines_in_order() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(
+            agent,
+            "console.log('first'); console.warn('second', 2, [3, 4]); \
+             console.error(Symbol('third'), null); 'done'",
+            gc.nogc(),
+        );
+        let (result, lines) =
+            agent.run_script_capturing_console(source_text.unbind(), gc.reborrow());
+        let result = String::try_from(result.unwrap().unbind()).unwrap();
+        assert_eq!(result.as_str(agent), "done");
+        assert_eq!(lines, ["first", "second 2 3,4", "Symbol(third) null"]);
+
+        // The console is only installed for the duration of the script.
+        let source_text = String::from_static_str(agent, "typeof console", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap()
+            .unbind();
+        let result = String::try_from(result).unwrap();
+        assert_eq!(result.as_str(agent), "undefined");
+
+        // Lines logged before an uncaught exception are still returned.
+        let source_text = String::from_static_str(
+            agent,
+            "console.log('before'); throw new Error('boom');",
+            gc.nogc(),
+        );
+        let (result, lines) =
+            agent.run_script_capturing_console(source_text.unbind(), gc.reborrow());
+        assert!(result.is_err());
+        assert_eq!(lines, ["before"]);
+
+        // A previously defined console is restored afterwards.
+        let source_text = String::from_static_str(agent, "globalThis.console = 42;", gc.nogc());
+        agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        let source_text = String::from_static_str(agent, "console.info(); 1", gc.nogc());
+        let (result, lines) =
+            agent.run_script_capturing_console(source_text.unbind(), gc.reborrow());
+        assert_eq!(result.unwrap(), Value::from(1));
+        assert_eq!(lines, [""]);
+        let source_text = String::from_static_str(agent, "console", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::from(42));
+
+        // A non-configurable console cannot be replaced.
+        let source_text = String::from_static_str(
+            agent,
+            "Object.defineProperty(globalThis, 'console', { configurable: false });",
+            gc.nogc(),
+        );
+        agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        let source_text = String::from_static_str(agent, "console.log('lost')", gc.nogc());
+        let (result, lines) =
+            agent.run_script_capturing_console(source_text.unbind(), gc.reborrow());
+        assert!(result.is_err());
+        assert!(lines.is_empty());
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Report {
+        /// Index of the evaluated script, or None for a Job.
+        script: Option<usize>,
+        error: std::string::String,
+        source_context: Option<std::string::String>,
+    }
+
+    #[derive(Default)]
+    struct ReportingHostHooks {
+        scripts: RefCell<Vec<Script<'static>>>,
+        reports: RefCell<Vec<Report>>,
+        jobs: RefCell<VecDeque<Job>>,
+    }
+
+    impl core::fmt::Debug for ReportingHostHooks {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.debug_struct("ReportingHostHooks").finish_non_exhaustive()
+        }
+    }
+
+    impl HostHooks for ReportingHostHooks {
+        fn enqueue_promise_job(&self, job: Job) {
+            self.jobs.borrow_mut().push_back(job);
+        }
+
+        fn report_uncaught_exception(
+            &self,
+            agent: &mut Agent,
+            error: Value,
+            origin: ExceptionOrigin,
+            gc: GcScope,
+        ) {
+            let script = match origin {
+                ExceptionOrigin::Script { script, .. } => Some(
+                    self.scripts
+                        .borrow()
+                        .iter()
+                        .position(|s| *s == script.unbind())
+                        .unwrap(),
+                ),
+                ExceptionOrigin::Job { .. } => None,
+            };
+            let source_context = origin.render_source_context(agent);
+            let error = error.unbind().string_repr(agent, gc);
+            self.reports.borrow_mut().push(Report {
+                script,
+                error: error.as_str(agent).to_owned(),
+                source_context,
+            });
+        }
+    }
+
+    fn run_reported_script(
+        agent: &mut Agent,
+        host_hooks: &ReportingHostHooks,
+        source: &'static str,
+        gc: GcScope,
+    ) -> bool {
+        let realm = agent.current_realm(gc.nogc());
+        let source_text = String::from_static_str(agent, source, gc.nogc());
+        let script = parse_script(agent, source_text, realm, false, None, gc.nogc()).unwrap();
+        host_hooks.scripts.borrow_mut().push(script.unbind());
+        script_evaluation(agent, script.unbind(), gc).is_ok()
+    }
+
+    #[test]
+    fn reports_uncaught_script_exceptions() {
+        let host_hooks: &'static ReportingHostHooks = Box::leak(Box::default());
+        let mut agent = Agent::new(Options::default(), host_hooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        assert!(!run_reported_script(
+            agent,
+            host_hooks,
+            "let a = 1; throw new Error(\"x\");",
+            gc.reborrow()
+        ));
+        assert_eq!(
+            host_hooks.reports.take(),
+            [Report {
+                script: Some(0),
+                error: "Error: x".to_owned(),
+                source_context: Some("let a = 1; throw new Error(\"x\");\n           ^".to_owned()),
+            }]
+        );
+
+        // The innermost statement that threw is reported, not the call that the
+        // exception escaped from.
+        assert!(!run_reported_script(
+            agent,
+            host_hooks,
+            "function f() {\n\tif (true) null.x;\n}\nf();",
+            gc.reborrow()
+        ));
+        let reports = host_hooks.reports.take();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].script, Some(1));
+        assert_eq!(
+            reports[0].source_context.as_deref(),
+            Some("\tif (true) null.x;\n\t          ^")
+        );
+
+        // Handled exceptions are not reported.
+        assert!(run_reported_script(
+            agent,
+            host_hooks,
+            "try { throw 1; } catch { }\nPromise.reject(2);",
+            gc.reborrow()
+        ));
+        assert!(host_hooks.reports.take().is_empty());
+        assert!(!run_reported_script(
+            agent,
+            host_hooks,
+            "try { throw 1; } catch { }\n  throw 3;",
+            gc.reborrow()
+        ));
+        assert_eq!(
+            host_hooks.reports.take(),
+            [Report {
+                script: Some(3),
+                error: "3".to_owned(),
+                source_context: Some("  throw 3;\n  ^".to_owned()),
+            }]
+        );
+
+        // Exceptions thrown by built-in functions are reported at the calling
+        // statement.
+        assert!(!run_reported_script(
+            agent,
+            host_hooks,
+            "Reflect.apply(null);",
+            gc.reborrow()
+        ));
+        let reports = host_hooks.reports.take();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(
+            reports[0].source_context.as_deref(),
+            Some("Reflect.apply(null);\n^")
+        );
+    }
+
+    #[test]
+    fn reports_uncaught_job_exceptions() {
+        let host_hooks: &'static ReportingHostHooks = Box::leak(Box::default());
+        let mut agent = Agent::new(Options::default(), host_hooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        assert!(run_reported_script(
+            agent,
+            host_hooks,
+            "function callback() { throw new TypeError(\"job\"); }",
+            gc.reborrow()
+        ));
+        let source_text = String::from_static_str(agent, "callback", gc.nogc());
+        let callback = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap()
+            .unbind()
+            .bind(gc.nogc());
+        let callback = Function::try_from(callback).unwrap();
+        let job = Job::new_callback(agent, callback, gc.nogc());
+        assert!(job.run(agent, gc.reborrow()).is_err());
+        assert_eq!(
+            host_hooks.reports.take(),
+            [Report {
+                script: None,
+                error: "TypeError: job".to_owned(),
+                source_context: Some(
+                    "function callback() { throw new TypeError(\"job\"); }\n                      ^"
+                        .to_owned()
+                ),
+            }]
+        );
+
+        // Promise reaction jobs reject their promise instead of throwing.
+        assert!(run_reported_script(
+            agent,
+            host_hooks,
+            "Promise.resolve().then(() => { throw 1; });",
+            gc.reborrow()
+        ));
+        let job = host_hooks.jobs.borrow_mut().pop_front().unwrap();
+        assert!(job.run(agent, gc.reborrow()).is_ok());
+        assert!(host_hooks.jobs.borrow().is_empty());
+        assert!(host_hooks.reports.take().is_empty());
+    }
+
+    /// Gives a closure the higher-ranked signature expected by `create_realm`.
+    fn realm_callback<F: for<'a> FnOnce(&mut Agent, GcScope<'a, '_>) -> Object<'a>>(f: F) -> F {
+        f
+    }
+
+    #[test]
+    fn custom_global_this_value() {
+        let mut agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+        let mut this_value: Option<Global<Object<'static>>> = None;
+        let create_global_object: Option<for<'a> fn(&mut Agent, GcScope<'a, '_>) -> Object<'a>> =
+            None;
+        let create_global_this_value = realm_callback(|agent, gc| {
+            let object = OrdinaryObject::create_empty_object(agent, gc.nogc()).into_object();
+            this_value = Some(Global::new(agent, object.unbind()));
+            object.unbind().bind(gc.into_nogc())
+        });
+        let initialize_global_object: Option<fn(&mut Agent, Object, GcScope)> = None;
+        let realm = agent.create_realm(
+            create_global_object,
+            Some(create_global_this_value),
+            initialize_global_object,
+        );
+        let this_value = this_value.unwrap();
+        agent.run_in_realm(&realm, |agent, mut gc| {
+            let expected = this_value.take(agent).into_value();
+
+            let source_text = String::from_static_str(agent, "this", gc.nogc());
+            let result = agent
+                .run_script(source_text.unbind(), gc.reborrow())
+                .unwrap();
+            assert_eq!(result, expected);
+
+            let source_text = String::from_static_str(
+                agent,
+                "(function () { return this; })() === this && globalThis === this",
+                gc.nogc(),
+            );
+            let result = agent
+                .run_script(source_text.unbind(), gc.reborrow())
+                .unwrap();
+            assert_eq!(result, Value::Boolean(true));
+
+            // Global variables and the default global bindings live on the global
+            // object, not on the global this value.
+            let source_text = String::from_static_str(
+                agent,
+                "var x = 1; typeof Array === 'function' && this.Array === undefined && this.x === undefined && x === 1",
+                gc.nogc(),
+            );
+            let result = agent
+                .run_script(source_text.unbind(), gc.reborrow())
+                .unwrap();
+            assert_eq!(result, Value::Boolean(true));
+        });
+        agent.remove_realm(realm);
+    }
+
+    fn eval_in_scope<'gc>(
+        agent: &mut Agent,
+        source: &'static str,
+        this_value: Value,
+        scope_objects: &[Object],
+        options: EvalInScopeOptions,
+        gc: GcScope<'gc, '_>,
+    ) -> Value<'gc> {
+        let source_text = String::from_static_str(agent, source, gc.nogc());
+        agent
+            .eval_in_scope(source_text.unbind(), this_value, scope_objects, options, gc)
+            .unwrap()
+    }
+
+    #[test]
+    fn variables_round_trip_through_scope_object() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let scope = evaluate(agent, "globalThis.scope = { a: 1 }", gc.reborrow()).unbind();
+        let scope = Object::try_from(scope).unwrap();
+        let result = eval_in_scope(
+            agent,
+            "a += 1; var b = a * 10; function f() { return b + 1; } typeof scope",
+            Value::Undefined,
+            &[scope],
+            EvalInScopeOptions::default(),
+            gc.reborrow(),
+        )
+        .unbind();
+        assert_eq!(result, Value::from_static_str(agent, "object", gc.nogc()));
+
+        // Declarations were created on the scope object, not on the global.
+        let result = evaluate(
+            agent,
+            "scope.a === 2 && scope.b === 20 && scope.f() === 21 && typeof b === 'undefined' && typeof f === 'undefined'",
+            gc.reborrow(),
+        )
+        .unbind();
+        assert_eq!(result, Value::Boolean(true));
+
+        // With the option set, declarations go to the global object instead.
+        let result = eval_in_scope(
+            agent,
+            "var c = a; c",
+            Value::Undefined,
+            &[scope],
+            EvalInScopeOptions {
+                declare_in_global: true,
+            },
+            gc.reborrow(),
+        )
+        .unbind();
+        assert_eq!(result, Value::Integer(2.into()));
+        let result = evaluate(agent, "c === 2 && !('c' in scope)", gc.reborrow()).unbind();
+        assert_eq!(result, Value::Boolean(true));
+    }
+
+    #[test]
+    fn scope_objects_resolve_innermost_first() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let outer = evaluate(agent, "({ x: 'outer', y: 'outer' })", gc.reborrow()).unbind();
+        let outer = Object::try_from(outer).unwrap();
+        let inner = evaluate(
+            agent,
+            "({ x: 'inner', y: 'inner', [Symbol.unscopables]: { y: true } })",
+            gc.reborrow(),
+        )
+        .unbind();
+        let inner = Object::try_from(inner).unwrap();
+        let result = eval_in_scope(
+            agent,
+            "x + ' ' + y",
+            Value::Undefined,
+            &[outer, inner],
+            EvalInScopeOptions::default(),
+            gc.reborrow(),
+        )
+        .unbind();
+        assert_eq!(
+            result,
+            Value::from_static_str(agent, "inner outer", gc.nogc())
+        );
+    }
+
+    #[test]
+    fn custom_this_value() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let this_value = evaluate(agent, "({ name: 'custom' })", gc.reborrow()).unbind();
+        let result = eval_in_scope(
+            agent,
+            "this.name + ' ' + (() => this.name)() + ' ' + eval('this.name')",
+            this_value,
+            &[],
+            EvalInScopeOptions::default(),
+            gc.reborrow(),
+        )
+        .unbind();
+        assert_eq!(
+            result,
+            Value::from_static_str(agent, "custom custom custom", gc.nogc())
+        );
+        // Functions called without a receiver still get the global this.
+        let result = eval_in_scope(
+            agent,
+            "(function () { return this; })() === globalThis",
+            this_value,
+            &[],
+            EvalInScopeOptions::default(),
+            gc.reborrow(),
+        )
+        .unbind();
+        assert_eq!(result, Value::Boolean(true));
+    }
+
+    #[test]
+    fn strict_declarations_stay_local() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let scope = evaluate(agent, "globalThis.scope = {}", gc.reborrow()).unbind();
+        let scope = Object::try_from(scope).unwrap();
+        let result = eval_in_scope(
+            agent,
+            "'use strict'; var d = 4; function g() { return d; } g()",
+            Value::Undefined,
+            &[scope],
+            EvalInScopeOptions::default(),
+            gc.reborrow(),
+        )
+        .unbind();
+        assert_eq!(result, Value::Integer(4.into()));
+        let result = evaluate(
+            agent,
+            "!('d' in scope) && !('g' in scope) && typeof d === 'undefined'",
+            gc.reborrow(),
+        )
+        .unbind();
+        assert_eq!(result, Value::Boolean(true));
+    }
+
+    /// `Array.prototype.last`: returns the last element of an Array.
+    fn array_prototype_last<'gc>(
+        agent: &mut Agent,
+        this: Value,
+        _: ArgumentsList,
+        gc: GcScope<'gc, '_>,
+    ) -> JsResult<'gc, Value<'gc>> {
+        let Ok(array) = Array::try_from(this) else {
+            return Ok(Value::Undefined);
+        };
+        let len = array.len(agent);
+        if len == 0 {
+            return Ok(Value::Undefined);
+        }
+        array.unbind().internal_get(
+            agent,
+            PropertyKey::Integer((len - 1).into()),
+            array.into_value().unbind(),
+            gc,
+        )
+    }
+
+    #[test]
+    fn install_method_on_array_prototype() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(agent, "Array.prototype", gc.nogc());
+        let array_prototype = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap()
+            .unbind();
+        let array_prototype = Object::try_from(array_prototype).unwrap();
+        agent
+            .install_method(
+                array_prototype,
+                "last",
+                0,
+                Behaviour::Regular(array_prototype_last),
+                gc.reborrow(),
+            )
+            .unwrap();
+
+        let source_text = String::from_static_str(
+            agent,
+            "const descriptor = Object.getOwnPropertyDescriptor(Array.prototype, 'last');
+            [1, 2, 3].last() === 3 &&
+            [].last() === undefined &&
+            Array.prototype.last.name === 'last' &&
+            Array.prototype.last.length === 0 &&
+            descriptor.writable && !descriptor.enumerable && descriptor.configurable &&
+            !Object.keys([1]).includes('last')",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap()
+            .unbind();
+        assert_eq!(result, Value::Boolean(true));
+    }
+
+    #[test]
+    fn install_method_on_frozen_object_throws() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(agent, "Object.freeze({})", gc.nogc());
+        let target = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap()
+            .unbind();
+        let target = Object::try_from(target).unwrap();
+        let result = agent.install_method(
+            target,
+            "last",
+            0,
+            Behaviour::Regular(array_prototype_last),
+            gc.reborrow(),
+        );
+        assert!(result.is_err());
+    }
+
+    /// `globalThis.check`: throws a TypeError unless called with a number.
+    fn check<'gc>(
+        agent: &mut Agent,
+        _: Value,
+        arguments: ArgumentsList,
+        gc: GcScope<'gc, '_>,
+    ) -> JsResult<'gc, Value<'gc>> {
+        let argument = arguments.get(0);
+        if !argument.is_number() {
+            return agent.type_error("bad arg", gc.into_nogc());
+        }
+        Ok(argument.unbind())
+    }
+
+    #[test]
+    fn installed_method_throws_type_error() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let global = agent.current_global_object(gc.nogc());
+        agent
+            .install_method(
+                global.unbind(),
+                "check",
+                1,
+                Behaviour::Regular(check),
+                gc.reborrow(),
+            )
+            .unwrap();
+
+        let source_text = String::from_static_str(agent, "check('not a number')", gc.nogc());
+        let error = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap_err()
+            .value()
+            .unbind();
+        let error = Object::try_from(error).unwrap();
+        let name_key = PropertyKey::from_static_str(agent, "name", gc.nogc()).unbind();
+        let name = error
+            .internal_get(agent, name_key, error.into_value(), gc.reborrow())
+            .unwrap()
+            .unbind();
+        assert_eq!(String::try_from(name).unwrap().as_str(agent), "TypeError");
+
+        let source_text = String::from_static_str(
+            agent,
+            "check(1) === 1 && (() => {
+                try { check(); } catch (err) {
+                    return err instanceof TypeError && err.message === 'bad arg';
+                }
+            })()",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap()
+            .unbind();
+        assert_eq!(result, Value::Boolean(true));
+    }
+}
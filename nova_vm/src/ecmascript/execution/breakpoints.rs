@@ -0,0 +1,244 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Breakpoints set with [`Agent::set_breakpoint`], reported to
+//! [`HostHooks::on_breakpoint`] when ECMAScript code reaches them.
+//!
+//! [`HostHooks::on_breakpoint`]: super::agent::HostHooks::on_breakpoint
+
+use core::ops::Range;
+
+use ahash::AHashMap;
+
+use super::{Agent, agent::SourceLocation};
+use crate::{
+    ecmascript::scripts_and_modules::source_code::SourceCode,
+    engine::{
+        Executable,
+        context::{Bindable, GcScope},
+    },
+    heap::{CompactionLists, HeapMarkAndSweep, WorkQueues, sweep_side_table_values},
+};
+
+/// Breakpoints by source code, as ranges of source text offsets from the
+/// breakpoint's position to the end of its line. The source codes are held
+/// weakly: the breakpoints of a collected source code are removed.
+#[derive(Debug, Default)]
+pub(crate) struct Breakpoints {
+    positions: AHashMap<SourceCode<'static>, Vec<Range<u32>>>,
+}
+
+impl Agent {
+    /// Set a breakpoint at the given zero-based line and column of the
+    /// source code, counting the column in UTF-16 code units.
+    ///
+    /// The breakpoint is hit whenever execution reaches the first statement
+    /// of a function or script that begins on the line at or after the
+    /// column, and reported to
+    /// [`HostHooks::on_breakpoint`](super::agent::HostHooks::on_breakpoint)
+    /// before that statement runs. Returns false if the source code has
+    /// fewer lines.
+    pub fn set_breakpoint(&mut self, source_code: SourceCode, line: u32, column: u32) -> bool {
+        let Some((start, end)) = source_code.line_offsets_from(self, line, column) else {
+            return false;
+        };
+        let positions = self
+            .breakpoints
+            .positions
+            .entry(source_code.unbind())
+            .or_default();
+        if !positions.contains(&(start..end)) {
+            positions.push(start..end);
+        }
+        true
+    }
+
+    /// Remove the breakpoint set at the given line and column of the source
+    /// code. Returns false if there was no such breakpoint.
+    pub fn remove_breakpoint(&mut self, source_code: SourceCode, line: u32, column: u32) -> bool {
+        let Some((start, end)) = source_code.line_offsets_from(self, line, column) else {
+            return false;
+        };
+        let source_code = source_code.unbind();
+        let Some(positions) = self.breakpoints.positions.get_mut(&source_code) else {
+            return false;
+        };
+        let Some(index) = positions.iter().position(|range| *range == (start..end)) else {
+            return false;
+        };
+        positions.swap_remove(index);
+        if positions.is_empty() {
+            self.breakpoints.positions.remove(&source_code);
+        }
+        true
+    }
+
+    /// Returns true if any breakpoints are set.
+    #[inline]
+    pub(crate) fn has_breakpoints(&self) -> bool {
+        !self.breakpoints.positions.is_empty()
+    }
+
+    /// Report a breakpoint if the instruction at `ip` of the running
+    /// Executable begins a statement that hits one.
+    #[cold]
+    pub(crate) fn check_breakpoint(&mut self, executable: Executable, ip: usize, gc: GcScope) {
+        let Some(span) = executable.get_statement_span_at(self, ip) else {
+            return;
+        };
+        let Some(source_code) = self.running_source_code() else {
+            return;
+        };
+        let Some(positions) = self.breakpoints.positions.get(&source_code) else {
+            return;
+        };
+        // Only the first statement after the breakpoint's position hits it:
+        // statements nested in it on the same line, such as a loop body, do
+        // not.
+        let hit = positions.iter().any(|position| {
+            position.contains(&span.start)
+                && !executable.has_statement_starting_in(self, position.start..span.start)
+        });
+        if hit {
+            let host_hooks = self.host_hooks;
+            host_hooks.on_breakpoint(self, SourceLocation { source_code, span }, gc);
+        }
+    }
+}
+
+impl HeapMarkAndSweep for Breakpoints {
+    fn mark_values(&self, _queues: &mut WorkQueues) {}
+
+    fn sweep_values(&mut self, compactions: &CompactionLists) {
+        sweep_side_table_values(&mut self.positions, compactions);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::RefCell;
+
+    use crate::{
+        ecmascript::{
+            execution::{
+                Agent,
+                agent::{HostHooks, Job, Options, SourceLocation},
+                initialize_default_realm,
+            },
+            scripts_and_modules::script::{
+                Script, parse_script, script_evaluation, test::evaluate,
+            },
+            types::{String, Value},
+        },
+        engine::{
+            context::{Bindable, GcScope, NoGcScope},
+            rootable::Scopable,
+        },
+    };
+
+    #[derive(Debug, Default)]
+    struct BreakpointHooks {
+        /// Line and source text of the statements that hit a breakpoint.
+        hits: RefCell<Vec<(u32, std::string::String)>>,
+    }
+
+    impl HostHooks for BreakpointHooks {
+        fn enqueue_promise_job(&self, _job: Job) {}
+
+        fn on_breakpoint(&self, agent: &mut Agent, position: SourceLocation, _gc: GcScope) {
+            let (line, _) = position
+                .source_code
+                .line_column_at(agent, position.span.start);
+            let statement = position.source_code.slice(agent, position.span).to_owned();
+            self.hits.borrow_mut().push((line, statement));
+        }
+    }
+
+    fn parse<'gc>(agent: &mut Agent, source: &'static str, gc: NoGcScope<'gc, '_>) -> Script<'gc> {
+        let source_text = String::from_static_str(agent, source, gc);
+        let realm = agent.current_realm(gc);
+        parse_script(agent, source_text, realm, false, None, gc).unwrap()
+    }
+
+    #[test]
+    fn breakpoint_on_second_line_fires_once() {
+        let host_hooks: &'static BreakpointHooks = Box::leak(Box::default());
+        let mut agent = Agent::new(Options::default(), host_hooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let script = parse(
+            agent,
+            "var a = 1;\nvar b = a + 1;\nvar c = b + 1;",
+            gc.nogc(),
+        );
+        assert!(agent.set_breakpoint(script.source_code(agent), 1, 0));
+        script_evaluation(agent, script.unbind(), gc.reborrow()).unwrap();
+        assert_eq!(host_hooks.hits.take(), [(1, "var b = a + 1;".to_owned())]);
+    }
+
+    #[test]
+    fn breakpoints_fire_each_time_their_statement_is_reached() {
+        let host_hooks: &'static BreakpointHooks = Box::leak(Box::default());
+        let mut agent = Agent::new(Options::default(), host_hooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let script = parse(
+            agent,
+            "function double(x) {\n  return x * 2;\n}\nlet sum = 0;\nfor (let i = 0; i < 3; i++) sum += double(i);",
+            gc.nogc(),
+        );
+        let source_code = script.source_code(agent);
+        assert!(agent.set_breakpoint(source_code, 1, 0));
+        assert!(agent.set_breakpoint(source_code, 4, 0));
+        assert!(!agent.set_breakpoint(source_code, 5, 0));
+        let source_code = source_code.scope(agent, gc.nogc());
+        script_evaluation(agent, script.unbind(), gc.reborrow()).unwrap();
+        // The loop body is on the same line as the loop, so only the loop
+        // statement hits the breakpoint on that line.
+        assert_eq!(
+            host_hooks.hits.take(),
+            [
+                (
+                    4,
+                    "for (let i = 0; i < 3; i++) sum += double(i);".to_owned()
+                ),
+                (1, "return x * 2;".to_owned()),
+                (1, "return x * 2;".to_owned()),
+                (1, "return x * 2;".to_owned()),
+            ]
+        );
+
+        assert!(agent.remove_breakpoint(source_code.get(agent), 1, 0));
+        assert!(!agent.remove_breakpoint(source_code.get(agent), 1, 0));
+        let result = evaluate(agent, "double(sum)", gc.reborrow()).unbind();
+        assert_eq!(result, Value::from(12));
+        assert_eq!(host_hooks.hits.take(), []);
+    }
+
+    #[test]
+    fn breakpoints_survive_source_code_compaction() {
+        let host_hooks: &'static BreakpointHooks = Box::leak(Box::default());
+        let mut agent = Agent::new(Options::default(), host_hooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        // The source code of this Script is collected, moving the source code
+        // parsed after it.
+        parse(agent, "'garbage';", gc.nogc());
+        let script = parse(agent, "var a = 1;\nvar b = 2;", gc.nogc());
+        let index_before = format!("{:?}", script.source_code(agent));
+        assert!(agent.set_breakpoint(script.source_code(agent), 1, 0));
+        let script = script.scope(agent, gc.nogc());
+        agent.gc(gc.reborrow());
+        let script = script.get(agent);
+        assert_ne!(format!("{:?}", script.source_code(agent)), index_before);
+        script_evaluation(agent, script.unbind(), gc.reborrow()).unwrap();
+        assert_eq!(host_hooks.hits.take(), [(1, "var b = 2;".to_owned())]);
+    }
+}
@@ -2,7 +2,14 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use super::agent::{HostHooks, Job};
+use super::{
+    Agent,
+    agent::{ExceptionOrigin, HostHooks, Job},
+};
+use crate::{
+    ecmascript::types::Value,
+    engine::context::{Bindable, GcScope},
+};
 
 /// A default implementation of host hooks, meant for applications that don't
 /// need an event loop or microtasks.
@@ -20,6 +27,9 @@ use super::agent::{HostHooks, Job};
 /// as promise jobs. This is only meant for applications expecting to run a
 /// simple synchronous script and get a result from it.
 ///
+/// Uncaught exceptions are reported to stderr, together with the source line
+/// they were thrown from if it is known.
+///
 /// Other users of Nova should use a custom implementation of [`HostHooks`] that
 /// overrides the scheduling hooks.
 #[derive(Debug)]
@@ -29,4 +39,19 @@ impl HostHooks for DefaultHostHooks {
     fn enqueue_promise_job(&self, _job: Job) {
         // No-op
     }
+
+    fn report_uncaught_exception(
+        &self,
+        agent: &mut Agent,
+        error: Value,
+        origin: ExceptionOrigin,
+        gc: GcScope,
+    ) {
+        let source_context = origin.render_source_context(agent);
+        let error = error.unbind().string_repr(agent, gc);
+        eprintln!("Uncaught exception: {}", error.to_string_lossy(agent));
+        if let Some(source_context) = source_context {
+            eprintln!("{source_context}");
+        }
+    }
 }
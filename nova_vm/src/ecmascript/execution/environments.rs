@@ -38,10 +38,12 @@ pub(crate) use declarative_environment::{
 };
 pub(crate) use function_environment::{
     FunctionEnvironmentRecord, ThisBindingStatus, new_class_field_initializer_environment,
-    new_class_static_element_environment, new_function_environment,
+    new_class_static_element_environment, new_fixed_this_environment, new_function_environment,
 };
-pub(crate) use global_environment::{GlobalEnvironmentRecord, new_global_environment};
-pub(crate) use object_environment::ObjectEnvironmentRecord;
+pub(crate) use global_environment::{
+    GlobalDeclarations, GlobalEnvironmentRecord, new_global_environment,
+};
+pub(crate) use object_environment::{ObjectEnvironmentRecord, new_object_environment};
 pub(crate) use private_environment::{
     PrivateEnvironmentRecord, PrivateField, PrivateMethod, new_private_environment,
     resolve_private_identifier,
@@ -52,7 +54,10 @@ use crate::engine::context::{Bindable, GcScope, GcToken, NoGcScope};
 use crate::engine::rootable::{HeapRootData, HeapRootRef, Rootable, Scopable};
 use crate::{
     ecmascript::types::{Base, Object, Reference, String, Value},
-    heap::{CompactionLists, HeapMarkAndSweep, WorkQueues},
+    heap::{
+        CompactionLists, HeapMarkAndSweep, WorkQueues,
+        snapshot::{HeapSnapshot, SnapshotError, SnapshotReader, SnapshotWriter},
+    },
 };
 
 use super::{Agent, JsResult};
@@ -169,6 +174,20 @@ macro_rules! create_environment_index {
             }
         }
 
+        impl HeapSnapshot for $index<'static> {
+            fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+                writer.write_u32(self.into_u32_index());
+            }
+
+            fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+                let index = reader.read_u32()?;
+                if index == u32::MAX {
+                    return Err(SnapshotError::Corrupted);
+                }
+                Ok(Self::from_u32_index(index))
+            }
+        }
+
         impl core::ops::Index<$index<'_>> for Agent {
             type Output = $record;
 
@@ -179,6 +198,7 @@ macro_rules! create_environment_index {
 
         impl core::ops::IndexMut<$index<'_>> for Agent {
             fn index_mut(&mut self, index: $index) -> &mut Self::Output {
+                self.heap.write_barrier(index);
                 &mut self.heap.environments.$entry[index]
             }
         }
@@ -825,6 +845,40 @@ impl HeapMarkAndSweep for Environment<'static> {
     }
 }
 
+impl HeapSnapshot for Environment<'static> {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        match self {
+            Environment::Declarative(idx) => {
+                writer.write_u8(1);
+                idx.write_snapshot(writer);
+            }
+            Environment::Function(idx) => {
+                writer.write_u8(2);
+                idx.write_snapshot(writer);
+            }
+            Environment::Global(idx) => {
+                writer.write_u8(3);
+                idx.write_snapshot(writer);
+            }
+            Environment::Object(idx) => {
+                writer.write_u8(4);
+                idx.write_snapshot(writer);
+            }
+        }
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        let env = match reader.read_u8()? {
+            1 => Environment::Declarative(HeapSnapshot::read_snapshot(reader)?),
+            2 => Environment::Function(HeapSnapshot::read_snapshot(reader)?),
+            3 => Environment::Global(HeapSnapshot::read_snapshot(reader)?),
+            4 => Environment::Object(HeapSnapshot::read_snapshot(reader)?),
+            _ => return Err(SnapshotError::Corrupted),
+        };
+        Ok(env)
+    }
+}
+
 #[derive(Debug)]
 pub struct Environments {
     pub(crate) declarative: Vec<Option<DeclarativeEnvironmentRecord>>,
@@ -846,6 +900,34 @@ impl Default for Environments {
     }
 }
 
+impl HeapSnapshot for Environments {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        let Self {
+            declarative,
+            function,
+            global,
+            object,
+            private,
+        } = self;
+        assert!(
+            function.is_empty() && private.is_empty(),
+            "Startup snapshots cannot contain function or private environments"
+        );
+        declarative.write_snapshot(writer);
+        global.write_snapshot(writer);
+        object.write_snapshot(writer);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        Ok(Self {
+            declarative: HeapSnapshot::read_snapshot(reader)?,
+            global: HeapSnapshot::read_snapshot(reader)?,
+            object: HeapSnapshot::read_snapshot(reader)?,
+            ..Default::default()
+        })
+    }
+}
+
 /// ### Try [9.1.2.1 GetIdentifierReference ( env, name, strict )](https://tc39.es/ecma262/#sec-getidentifierreference)
 ///
 /// The abstract operation GetIdentifierReference takes arguments env (an
@@ -1013,15 +1095,10 @@ impl Environments {
     pub(crate) fn push_object_environment<'a>(
         &mut self,
         env: ObjectEnvironmentRecord,
-        decl_env: DeclarativeEnvironmentRecord,
         _: NoGcScope<'a, '_>,
-    ) -> (ObjectEnvironment<'a>, DeclarativeEnvironment<'a>) {
+    ) -> ObjectEnvironment<'a> {
         self.object.push(Some(env));
-        self.declarative.push(Some(decl_env));
-        (
-            ObjectEnvironment::from_u32(self.object.len() as u32),
-            DeclarativeEnvironment::from_u32(self.declarative.len() as u32),
-        )
+        ObjectEnvironment::from_u32(self.object.len() as u32)
     }
 
     pub(crate) fn push_private_environment<'a>(
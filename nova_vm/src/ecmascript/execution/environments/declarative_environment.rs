@@ -5,13 +5,17 @@
 use ahash::AHashMap;
 
 use super::{DeclarativeEnvironment, Environment, OuterEnv};
+use crate::ecmascript::builtins::error::ErrorMessage;
 use crate::{
     ecmascript::{
         execution::{Agent, JsResult, agent::ExceptionType},
         types::{Object, String, Value},
     },
     engine::context::{Bindable, NoGcScope},
-    heap::{CompactionLists, HeapMarkAndSweep, WorkQueues},
+    heap::{
+        CompactionLists, HeapMarkAndSweep, WorkQueues,
+        snapshot::{HeapSnapshot, SnapshotError, SnapshotReader, SnapshotWriter},
+    },
 };
 
 /// ### [9.1.1.1 Declarative Environment Records](https://tc39.es/ecma262/#sec-declarative-environment-records)
@@ -204,6 +208,48 @@ impl HeapMarkAndSweep for DeclarativeEnvironmentRecord {
     }
 }
 
+impl HeapSnapshot for DeclarativeEnvironmentRecord {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        let Self {
+            outer_env,
+            bindings,
+        } = self;
+        outer_env.write_snapshot(writer);
+        bindings.write_snapshot(writer);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        Ok(Self {
+            outer_env: HeapSnapshot::read_snapshot(reader)?,
+            bindings: HeapSnapshot::read_snapshot(reader)?,
+        })
+    }
+}
+
+impl HeapSnapshot for Binding {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        let Self {
+            value,
+            strict,
+            mutable,
+            deletable,
+        } = self;
+        value.write_snapshot(writer);
+        strict.write_snapshot(writer);
+        mutable.write_snapshot(writer);
+        deletable.write_snapshot(writer);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        Ok(Self {
+            value: HeapSnapshot::read_snapshot(reader)?,
+            strict: HeapSnapshot::read_snapshot(reader)?,
+            mutable: HeapSnapshot::read_snapshot(reader)?,
+            deletable: HeapSnapshot::read_snapshot(reader)?,
+        })
+    }
+}
+
 impl DeclarativeEnvironment<'_> {
     pub(crate) fn get_outer_env<'a>(
         self,
@@ -290,10 +336,10 @@ impl DeclarativeEnvironment<'_> {
         let Some(binding) = env_rec.bindings.get_mut(&name.unbind()) else {
             // a. If S is true, throw a ReferenceError exception.
             if is_strict {
-                let error_message = format!("Identifier '{}' does not exist.", name.as_str(agent));
-                return Err(agent.throw_exception(
+                return Err(agent.throw_exception_with_deferred_message(
                     ExceptionType::ReferenceError,
-                    error_message,
+                    ErrorMessage::IdentifierDoesNotExist,
+                    [name.into()],
                     gc,
                 ));
             }
@@ -316,11 +362,12 @@ impl DeclarativeEnvironment<'_> {
         // 3. If the binding for N in envRec has not yet been initialized, then
         if binding.value.is_none() {
             // a. Throw a ReferenceError exception.
-            let error_message = format!(
-                "Identifier '{}' has not been initialized.",
-                name.as_str(agent)
-            );
-            return Err(agent.throw_exception(ExceptionType::ReferenceError, error_message, gc));
+            return Err(agent.throw_exception_with_deferred_message(
+                ExceptionType::ReferenceError,
+                ErrorMessage::AccessBeforeInitialization,
+                [name.into()],
+                gc,
+            ));
         }
 
         // 4. Else if the binding for N in envRec is a mutable binding, then
@@ -335,11 +382,12 @@ impl DeclarativeEnvironment<'_> {
 
             // b. If S is true, throw a TypeError exception.
             if is_strict {
-                let error_message = format!(
-                    "Cannot assign to immutable identifier '{}' in strict mode.",
-                    name.as_str(agent)
-                );
-                return Err(agent.throw_exception(ExceptionType::TypeError, error_message, gc));
+                return Err(agent.throw_exception_with_deferred_message(
+                    ExceptionType::TypeError,
+                    ErrorMessage::ImmutableIdentifierAssignment,
+                    [name.into()],
+                    gc,
+                ));
             }
         }
 
@@ -369,8 +417,12 @@ impl DeclarativeEnvironment<'_> {
             None => {
                 // 2. If the binding for N in envRec is an uninitialized binding, throw
                 // a ReferenceError exception.
-                let error_message = format!("Identifier '{}' does not exist.", name.as_str(agent));
-                Err(agent.throw_exception(ExceptionType::ReferenceError, error_message, gc))
+                Err(agent.throw_exception_with_deferred_message(
+                    ExceptionType::ReferenceError,
+                    ErrorMessage::AccessBeforeInitialization,
+                    [name.into()],
+                    gc,
+                ))
             }
         }
     }
@@ -379,6 +431,18 @@ impl DeclarativeEnvironment<'_> {
         agent[self].get_binding(name)
     }
 
+    /// Iterate over the names of the environment's bindings and their
+    /// values, which are None for uninitialized bindings.
+    pub(crate) fn bindings(
+        self,
+        agent: &Agent,
+    ) -> impl Iterator<Item = (String<'static>, Option<Value<'static>>)> + '_ {
+        agent[self]
+            .bindings
+            .iter()
+            .map(|(&name, binding)| (name, binding.value))
+    }
+
     pub(crate) fn get_binding_mut<'a>(
         self,
         agent: &'a mut Agent,
@@ -5,7 +5,7 @@
 use super::{
     DeclarativeEnvironment, DeclarativeEnvironmentRecord, Environment, FunctionEnvironment,
 };
-use crate::ecmascript::types::OrdinaryObject;
+use crate::ecmascript::builtins::error::ErrorMessage;
 use crate::engine::context::{Bindable, NoGcScope};
 use crate::engine::unwrap_try;
 use crate::{
@@ -35,7 +35,7 @@ pub(crate) enum ThisBindingStatus {
 /// ArrowFunction function and references super, its Function Environment
 /// Record also contains the state that is used to perform super method
 /// invocations from within the function.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct FunctionEnvironmentRecord {
     /// ### \[\[ThisValue\]\]
     ///
@@ -212,6 +212,36 @@ pub(crate) fn new_class_field_initializer_environment<'a>(
     )
 }
 
+/// Creates a Function Environment Record with an initialized this binding,
+/// for evaluating code with a host-provided `this` value.
+///
+/// The function object is only used to answer questions such as whether the
+/// environment has a super binding; it is never called.
+pub(crate) fn new_fixed_this_environment<'a>(
+    agent: &mut Agent,
+    function_object: Function,
+    this_value: Value,
+    outer_env: Environment,
+    gc: NoGcScope<'a, '_>,
+) -> FunctionEnvironment<'a> {
+    let declarative_environment = agent
+        .heap
+        .environments
+        .push_declarative_environment(DeclarativeEnvironmentRecord::new(Some(outer_env)), gc);
+    agent.heap.alloc_counter += core::mem::size_of::<Option<FunctionEnvironmentRecord>>()
+        + core::mem::size_of::<Option<DeclarativeEnvironmentRecord>>();
+    agent.heap.environments.push_function_environment(
+        FunctionEnvironmentRecord {
+            this_value: Some(this_value.unbind()),
+            this_binding_status: ThisBindingStatus::Initialized,
+            function_object: function_object.unbind(),
+            new_target: None,
+            declarative_environment: declarative_environment.unbind(),
+        },
+        gc,
+    )
+}
+
 impl FunctionEnvironment<'_> {
     pub(crate) fn get_function_object<'a>(
         self,
@@ -257,11 +287,9 @@ impl FunctionEnvironment<'_> {
         match env_rec.this_binding_status {
             ThisBindingStatus::Lexical => unreachable!(),
             ThisBindingStatus::Initialized => Ok(env_rec.this_value.unwrap()),
-            ThisBindingStatus::Uninitialized => Err(agent.throw_exception_with_static_message(
-                ExceptionType::ReferenceError,
-                "Uninitialized this binding",
-                gc,
-            )),
+            ThisBindingStatus::Uninitialized => {
+                agent.reference_error("Uninitialized this binding", gc)
+            }
         }
     }
 
@@ -311,10 +339,10 @@ impl FunctionEnvironment<'_> {
         if !dcl_rec.has_binding(agent, name) {
             // a. If S is true, throw a ReferenceError exception.
             if is_strict {
-                let error_message = format!("Identifier '{}' does not exist.", name.as_str(agent));
-                return Err(agent.throw_exception(
+                return Err(agent.throw_exception_with_deferred_message(
                     ExceptionType::ReferenceError,
-                    error_message,
+                    ErrorMessage::IdentifierDoesNotExist,
+                    [name.into()],
                     gc,
                 ));
             }
@@ -339,11 +367,12 @@ impl FunctionEnvironment<'_> {
         // 3. If the binding for N in envRec has not yet been initialized, then
         if binding.value.is_none() {
             // a. Throw a ReferenceError exception.
-            let error_message = format!(
-                "Identifier '{}' has not been initialized.",
-                name.as_str(agent)
-            );
-            return Err(agent.throw_exception(ExceptionType::ReferenceError, error_message, gc));
+            return Err(agent.throw_exception_with_deferred_message(
+                ExceptionType::ReferenceError,
+                ErrorMessage::AccessBeforeInitialization,
+                [name.into()],
+                gc,
+            ));
         }
 
         // 4. Else if the binding for N in envRec is a mutable binding, then
@@ -358,11 +387,12 @@ impl FunctionEnvironment<'_> {
 
             // b. If S is true, throw a TypeError exception.
             if is_strict {
-                let error_message = format!(
-                    "Cannot assign to immutable identifier '{}' in strict mode.",
-                    name.as_str(agent)
-                );
-                return Err(agent.throw_exception(ExceptionType::TypeError, error_message, gc));
+                return Err(agent.throw_exception_with_deferred_message(
+                    ExceptionType::TypeError,
+                    ErrorMessage::ImmutableIdentifierAssignment,
+                    [name.into()],
+                    gc,
+                ));
             }
         }
 
@@ -415,11 +445,7 @@ impl FunctionEnvironment<'_> {
         // 2. If envRec.[[ThisBindingStatus]] is INITIALIZED, throw a
         // ReferenceError exception.
         if env_rec.this_binding_status == ThisBindingStatus::Initialized {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::ReferenceError,
-                "[[ThisBindingStatus]] is INITIALIZED",
-                gc,
-            ));
+            return agent.reference_error("[[ThisBindingStatus]] is INITIALIZED", gc);
         }
 
         // 3. Set envRec.[[ThisValue]] to V.
@@ -460,6 +486,10 @@ impl FunctionEnvironment<'_> {
             Function::ECMAScriptFunction(func) => {
                 agent[func].ecmascript_function.home_object.is_some()
             }
+            // Note: Class field initializers of classes without an explicit
+            // constructor run with the builtin class constructor as their
+            // function object.
+            Function::BuiltinConstructorFunction(_) => true,
             _ => false,
         }
     }
@@ -480,12 +510,15 @@ impl FunctionEnvironment<'_> {
         let home = match env_rec.function_object {
             // 2. If home is undefined, return undefined.
             Function::ECMAScriptFunction(func) => agent[func].ecmascript_function.home_object?,
+            Function::BuiltinConstructorFunction(func) => agent[func].home_object,
             _ => {
                 return None;
             }
         };
         // 3. Assert: home is an ordinary object.
-        let home = OrdinaryObject::try_from(home).unwrap();
+        // NOTE: Static class methods have the class constructor as their
+        // home object, which is an ordinary object in all but its type here.
+        debug_assert!(!matches!(home, Object::Proxy(_)));
         // 4. Return ! home.[[GetPrototypeOf]]().
         Some(unwrap_try(home.try_get_prototype_of(agent, gc)))
     }
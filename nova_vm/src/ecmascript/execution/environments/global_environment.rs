@@ -28,7 +28,10 @@ use crate::{
         context::{Bindable, GcScope, NoGcScope},
         rootable::Scopable,
     },
-    heap::{CompactionLists, HeapMarkAndSweep, WorkQueues},
+    heap::{
+        CompactionLists, HeapMarkAndSweep, WorkQueues,
+        snapshot::{HeapSnapshot, SnapshotError, SnapshotReader, SnapshotWriter},
+    },
 };
 
 /// ### [9.1.1.4 Global Environment Records](https://tc39.es/ecma262/#sec-global-environment-records)
@@ -109,6 +112,67 @@ impl HeapMarkAndSweep for GlobalEnvironmentRecord {
     }
 }
 
+impl HeapSnapshot for GlobalEnvironmentRecord {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        let Self {
+            object_record,
+            global_this_value,
+            declarative_record,
+            var_names,
+        } = self;
+        object_record.write_snapshot(writer);
+        global_this_value.write_snapshot(writer);
+        declarative_record.write_snapshot(writer);
+        var_names.write_snapshot(writer);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        Ok(Self {
+            object_record: HeapSnapshot::read_snapshot(reader)?,
+            global_this_value: HeapSnapshot::read_snapshot(reader)?,
+            declarative_record: HeapSnapshot::read_snapshot(reader)?,
+            var_names: HeapSnapshot::read_snapshot(reader)?,
+        })
+    }
+}
+
+/// Copy of the global declarations of a Global Environment Record: the
+/// bindings of its \[\[DeclarativeRecord\]\] and its \[\[VarNames\]\].
+#[derive(Debug, Clone)]
+pub(crate) struct GlobalDeclarations {
+    declarative_record: DeclarativeEnvironmentRecord,
+    var_names: AHashSet<String<'static>>,
+}
+
+impl HeapMarkAndSweep for GlobalDeclarations {
+    fn mark_values(&self, queues: &mut WorkQueues) {
+        let Self {
+            declarative_record,
+            var_names,
+        } = self;
+        declarative_record.mark_values(queues);
+        for ele in var_names {
+            ele.mark_values(queues);
+        }
+    }
+
+    fn sweep_values(&mut self, compactions: &CompactionLists) {
+        let Self {
+            declarative_record,
+            var_names,
+        } = self;
+        declarative_record.sweep_values(compactions);
+        *var_names = var_names
+            .iter()
+            .map(|key| {
+                let mut new_key = *key;
+                new_key.sweep_values(compactions);
+                new_key
+            })
+            .collect();
+    }
+}
+
 /// ### [9.1.2.5 NewGlobalEnvironment ( G, thisValue )](https://tc39.es/ecma262/#sec-newglobalenvironment)
 ///
 /// The abstract operation NewGlobalEnvironment takes arguments G (an
@@ -126,10 +190,11 @@ pub(crate) fn new_global_environment<'a>(
     let dcl_rec = DeclarativeEnvironmentRecord::new(None);
     agent.heap.alloc_counter += core::mem::size_of::<Option<ObjectEnvironmentRecord>>()
         + core::mem::size_of::<Option<DeclarativeEnvironmentRecord>>();
-    let (object_record, declarative_record) = agent
+    let object_record = agent.heap.environments.push_object_environment(obj_rec, gc);
+    let declarative_record = agent
         .heap
         .environments
-        .push_object_environment(obj_rec, dcl_rec, gc);
+        .push_declarative_environment(dcl_rec, gc);
 
     // 3. Let env be a new Global Environment Record.
     agent.heap.alloc_counter += core::mem::size_of::<Option<GlobalEnvironmentRecord>>();
@@ -155,6 +220,11 @@ pub(crate) fn new_global_environment<'a>(
 }
 
 impl GlobalEnvironment<'_> {
+    /// Get the \[\[DeclarativeRecord]] of the global environment.
+    pub(crate) fn get_declarative_record(self, agent: &Agent) -> DeclarativeEnvironment<'static> {
+        agent[self].declarative_record
+    }
+
     /// ### Try [9.1.1.4.1 HasBinding ( N )](https://tc39.es/ecma262/#sec-global-environment-records-hasbinding-n)
     ///
     /// The HasBinding concrete method of a Global Environment Record envRec
@@ -227,8 +297,10 @@ impl GlobalEnvironment<'_> {
         let dcl_rec = env_rec.declarative_record;
         // 2. If ! DclRec.HasBinding(N) is true, throw a TypeError exception.
         if dcl_rec.has_binding(agent, name) {
-            let error_message =
-                format!("Redeclaration of global binding '{}'.", name.as_str(agent));
+            let error_message = format!(
+                "Redeclaration of global binding '{}'.",
+                name.to_string_lossy(agent)
+            );
             Err(agent.throw_exception(ExceptionType::TypeError, error_message, gc))
         } else {
             // 3. Return ! DclRec.CreateMutableBinding(N, D).
@@ -258,8 +330,10 @@ impl GlobalEnvironment<'_> {
         let dcl_rec = env_rec.declarative_record;
         // 2. If ! DclRec.HasBinding(N) is true, throw a TypeError exception.
         if dcl_rec.has_binding(agent, name) {
-            let error_message =
-                format!("Redeclaration of global binding '{}'.", name.as_str(agent));
+            let error_message = format!(
+                "Redeclaration of global binding '{}'.",
+                name.to_string_lossy(agent)
+            );
             Err(agent.throw_exception(ExceptionType::TypeError, error_message, gc))
         } else {
             // 3. Return ! DclRec.CreateImmutableBinding(N, S).
@@ -631,6 +705,24 @@ impl GlobalEnvironment<'_> {
         var_declared_names.contains(&name)
     }
 
+    /// Returns a copy of the global declarations of this environment.
+    pub(crate) fn declarations(self, agent: &Agent) -> GlobalDeclarations {
+        let env_rec = &agent[self];
+        GlobalDeclarations {
+            declarative_record: agent[env_rec.declarative_record].clone(),
+            var_names: env_rec.var_names.clone(),
+        }
+    }
+
+    /// Replaces the global declarations of this environment with a copy
+    /// returned by [`GlobalEnvironment::declarations`], removing all global
+    /// declarations made after the copy was taken.
+    pub(crate) fn restore_declarations(self, agent: &mut Agent, declarations: &GlobalDeclarations) {
+        let dcl_rec = agent[self].declarative_record;
+        agent[dcl_rec] = declarations.declarative_record.clone();
+        agent[self].var_names = declarations.var_names.clone();
+    }
+
     /// ### [9.1.1.4.13 HasLexicalDeclaration ( N )](https://tc39.es/ecma262/#sec-haslexicaldeclaration)
     ///
     /// The HasLexicalDeclaration concrete method of a Global Environment
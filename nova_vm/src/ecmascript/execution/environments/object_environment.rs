@@ -2,6 +2,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use crate::ecmascript::builtins::error::ErrorMessage;
 use crate::{
     ecmascript::{
         abstract_operations::{
@@ -27,7 +28,10 @@ use crate::{
         context::{Bindable, GcScope, NoGcScope},
         rootable::Scopable,
     },
-    heap::{CompactionLists, HeapMarkAndSweep, WellKnownSymbolIndexes, WorkQueues},
+    heap::{
+        CompactionLists, HeapMarkAndSweep, WellKnownSymbolIndexes, WorkQueues,
+        snapshot::{HeapSnapshot, SnapshotError, SnapshotReader, SnapshotWriter},
+    },
 };
 
 /// ### [9.1.1.2 Object Environment Records](https://tc39.es/ecma262/#sec-object-environment-records)
@@ -88,6 +92,22 @@ impl ObjectEnvironmentRecord {
     }
 }
 
+/// ### [9.1.2.3 NewObjectEnvironment ( O, W, E )](https://tc39.es/ecma262/#sec-newobjectenvironment)
+///
+/// Creates a new Object Environment Record in the heap and returns a handle
+/// to it.
+pub(crate) fn new_object_environment<'a>(
+    agent: &mut Agent,
+    binding_object: Object,
+    is_with_environment: bool,
+    outer_env: OuterEnv,
+    gc: NoGcScope<'a, '_>,
+) -> ObjectEnvironment<'a> {
+    let env = ObjectEnvironmentRecord::new(binding_object, is_with_environment, outer_env);
+    agent.heap.alloc_counter += core::mem::size_of::<Option<ObjectEnvironmentRecord>>();
+    agent.heap.environments.push_object_environment(env, gc)
+}
+
 impl HeapMarkAndSweep for ObjectEnvironmentRecord {
     fn mark_values(&self, queues: &mut WorkQueues) {
         let Self {
@@ -110,6 +130,27 @@ impl HeapMarkAndSweep for ObjectEnvironmentRecord {
     }
 }
 
+impl HeapSnapshot for ObjectEnvironmentRecord {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        let Self {
+            binding_object,
+            is_with_environment,
+            outer_env,
+        } = self;
+        binding_object.write_snapshot(writer);
+        is_with_environment.write_snapshot(writer);
+        outer_env.write_snapshot(writer);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        Ok(Self {
+            binding_object: HeapSnapshot::read_snapshot(reader)?,
+            is_with_environment: HeapSnapshot::read_snapshot(reader)?,
+            outer_env: HeapSnapshot::read_snapshot(reader)?,
+        })
+    }
+}
+
 impl ObjectEnvironment<'_> {
     pub(crate) fn get_binding_object<'a>(self, agent: &Agent, gc: NoGcScope<'a, '_>) -> Object<'a> {
         agent[self].binding_object.bind(gc)
@@ -525,12 +566,12 @@ impl ObjectEnvironment<'_> {
         n: PropertyKey,
         gc: NoGcScope<'a, '_>,
     ) -> JsError<'a> {
-        let error_message = format!(
-            "Property '{}' does not exist in {}.",
-            n.as_display(agent),
-            binding_object_repr.as_str(agent)
-        );
-        agent.throw_exception(ExceptionType::ReferenceError, error_message, gc)
+        agent.throw_exception_with_deferred_message(
+            ExceptionType::ReferenceError,
+            ErrorMessage::PropertyDoesNotExistIn,
+            [n.into(), binding_object_repr.into()],
+            gc,
+        )
     }
 
     /// ### [9.1.1.2.6 GetBindingValue ( N, S )](https://tc39.es/ecma262/#sec-object-environment-records-getbindingvalue-n-s)
@@ -638,11 +679,12 @@ impl ObjectEnvironment<'_> {
         if !s {
             Ok(Value::Undefined)
         } else {
-            let error_message = format!(
-                "Property '{}' does not exist in object.",
-                name.as_display(agent)
-            );
-            Err(agent.throw_exception(ExceptionType::ReferenceError, error_message, gc))
+            Err(agent.throw_exception_with_deferred_message(
+                ExceptionType::ReferenceError,
+                ErrorMessage::PropertyDoesNotExistIn,
+                [name.into(), "object".into()],
+                gc,
+            ))
         }
     }
 
@@ -176,7 +176,7 @@ unsafe impl Bindable for PrivateField<'_> {
 /// with a ClassDeclaration or ClassExpression. Each time such a class is
 /// evaluated, a new PrivateEnvironment Record is created to record the Private
 /// Names declared by that class.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct PrivateEnvironmentRecord {
     /// ### \[\[OuterPrivateEnvironment\]\]
     ///
@@ -312,7 +312,7 @@ impl PrivateEnvironment<'_> {
     }
 
     fn get_data_mut(self, agent: &mut Agent) -> &mut PrivateEnvironmentRecord {
-        agent.heap.environments.get_private_environment_mut(self)
+        &mut agent[self]
     }
 
     pub(crate) fn get_outer_env<'a>(
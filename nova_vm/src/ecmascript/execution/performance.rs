@@ -0,0 +1,496 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::collections::VecDeque;
+
+use super::{Agent, JsResult, ProtoIntrinsics, agent::ExceptionType};
+use crate::{
+    ecmascript::{
+        abstract_operations::{
+            operations_on_objects::create_array_from_list, type_conversion::to_string,
+        },
+        builtins::{
+            ArgumentsList, Behaviour, BuiltinFunctionArgs, create_builtin_function,
+            ordinary::ordinary_object_create_with_intrinsics,
+        },
+        types::{
+            InternalMethods, IntoObject, IntoValue, Number, Object, PropertyDescriptor,
+            PropertyKey, String, Value,
+        },
+    },
+    engine::{
+        context::{Bindable, GcScope, NoGcScope},
+        rootable::Scopable,
+        unwrap_try,
+    },
+    heap::{CompactionLists, HeapMarkAndSweep, ObjectEntry, WorkQueues},
+};
+
+/// Maximum number of marks and measures kept by an Agent. When the buffer is
+/// full, the oldest entry is dropped.
+const MAX_PERFORMANCE_ENTRIES: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PerformanceEntryType {
+    Mark,
+    Measure,
+}
+
+impl PerformanceEntryType {
+    fn as_str(self) -> &'static str {
+        match self {
+            PerformanceEntryType::Mark => "mark",
+            PerformanceEntryType::Measure => "measure",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct PerformanceEntry {
+    name: String<'static>,
+    entry_type: PerformanceEntryType,
+    start_time: f64,
+    duration: f64,
+}
+
+/// Time origin and recorded entries of the `performance` global.
+#[derive(Debug)]
+pub(crate) struct PerformanceTimeline {
+    /// Reading of [`HostHooks::monotonic_now`] when the Agent was created.
+    ///
+    /// [`HostHooks::monotonic_now`]: super::agent::HostHooks::monotonic_now
+    time_origin: f64,
+    entries: VecDeque<PerformanceEntry>,
+}
+
+impl PerformanceTimeline {
+    pub(crate) fn new(time_origin: f64) -> Self {
+        Self {
+            time_origin,
+            entries: VecDeque::new(),
+        }
+    }
+
+    fn push(&mut self, entry: PerformanceEntry) {
+        if self.entries.len() == MAX_PERFORMANCE_ENTRIES {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+}
+
+impl HeapMarkAndSweep for PerformanceTimeline {
+    fn mark_values(&self, queues: &mut WorkQueues) {
+        for entry in &self.entries {
+            entry.name.mark_values(queues);
+        }
+    }
+
+    fn sweep_values(&mut self, compactions: &CompactionLists) {
+        for entry in &mut self.entries {
+            entry.name.sweep_values(compactions);
+        }
+    }
+}
+
+impl Agent {
+    /// Milliseconds elapsed since the time origin of the Agent.
+    fn performance_now(&self) -> f64 {
+        self.host_hooks.monotonic_now() - self.performance.time_origin
+    }
+}
+
+/// Create the `performance` object installed as a global if
+/// [`Options::performance`](super::agent::Options::performance) is set.
+pub(crate) fn create_performance_object<'a>(
+    agent: &mut Agent,
+    gc: NoGcScope<'a, '_>,
+) -> Object<'a> {
+    let performance =
+        ordinary_object_create_with_intrinsics(agent, Some(ProtoIntrinsics::Object), None, gc);
+    let methods: [(&'static str, u32, _); 4] = [
+        ("now", 0, Behaviour::Regular(now)),
+        ("mark", 1, Behaviour::Regular(mark)),
+        ("measure", 1, Behaviour::Regular(measure)),
+        (
+            "getEntriesByType",
+            1,
+            Behaviour::Regular(get_entries_by_type),
+        ),
+    ];
+    for (name, length, behaviour) in methods {
+        let function =
+            create_builtin_function(agent, behaviour, BuiltinFunctionArgs::new(length, name), gc);
+        let key = PropertyKey::from_static_str(agent, name, gc);
+        unwrap_try(performance.try_define_own_property(
+            agent,
+            key,
+            PropertyDescriptor {
+                value: Some(function.into_value()),
+                writable: Some(true),
+                enumerable: Some(true),
+                configurable: Some(true),
+                ..Default::default()
+            },
+            gc,
+        ));
+    }
+    let key = PropertyKey::from_static_str(agent, "timeOrigin", gc);
+    let time_origin = Value::from_f64(agent, agent.performance.time_origin, gc);
+    unwrap_try(performance.try_define_own_property(
+        agent,
+        key,
+        PropertyDescriptor {
+            value: Some(time_origin),
+            writable: Some(false),
+            enumerable: Some(true),
+            configurable: Some(true),
+            ..Default::default()
+        },
+        gc,
+    ));
+    performance
+}
+
+/// ### performance.now ( )
+fn now<'gc>(
+    agent: &mut Agent,
+    _this: Value,
+    _args: ArgumentsList,
+    gc: GcScope<'gc, '_>,
+) -> JsResult<'gc, Value<'gc>> {
+    let now = agent.performance_now();
+    Ok(Value::from_f64(agent, now, gc.into_nogc()))
+}
+
+/// ### performance.mark ( name )
+fn mark<'gc>(
+    agent: &mut Agent,
+    _this: Value,
+    args: ArgumentsList,
+    mut gc: GcScope<'gc, '_>,
+) -> JsResult<'gc, Value<'gc>> {
+    let name = to_string(agent, args.get(0), gc.reborrow()).unbind()?;
+    let gc = gc.into_nogc();
+    let name = name.bind(gc);
+    let entry = PerformanceEntry {
+        name: name.unbind(),
+        entry_type: PerformanceEntryType::Mark,
+        start_time: agent.performance_now(),
+        duration: 0.0,
+    };
+    let object = create_entry_object(agent, &entry, gc);
+    agent.performance.push(entry);
+    Ok(object.into_value())
+}
+
+/// ### performance.measure ( name \[ , startMark \[ , endMark ] ] )
+///
+/// The start and end may be given as the name of a previously recorded mark
+/// or as a timestamp. A missing start is the time origin and a missing end
+/// is the current time.
+fn measure<'gc>(
+    agent: &mut Agent,
+    _this: Value,
+    args: ArgumentsList,
+    mut gc: GcScope<'gc, '_>,
+) -> JsResult<'gc, Value<'gc>> {
+    let start_mark = args.get(1).scope(agent, gc.nogc());
+    let end_mark = args.get(2).scope(agent, gc.nogc());
+    let name = to_string(agent, args.get(0), gc.reborrow())
+        .unbind()?
+        .scope(agent, gc.nogc());
+    let start_time =
+        resolve_timestamp(agent, start_mark.get(agent), 0.0, gc.reborrow()).unbind()?;
+    let now = agent.performance_now();
+    let end_time = resolve_timestamp(agent, end_mark.get(agent), now, gc.reborrow()).unbind()?;
+    let gc = gc.into_nogc();
+    let entry = PerformanceEntry {
+        name: name.get(agent),
+        entry_type: PerformanceEntryType::Measure,
+        start_time,
+        duration: end_time - start_time,
+    };
+    let object = create_entry_object(agent, &entry, gc);
+    agent.performance.push(entry);
+    Ok(object.into_value())
+}
+
+/// Resolve a mark name or timestamp argument of `performance.measure` into a
+/// timestamp, using the given default if the argument is undefined.
+fn resolve_timestamp<'gc>(
+    agent: &mut Agent,
+    value: Value,
+    default: f64,
+    mut gc: GcScope<'gc, '_>,
+) -> JsResult<'gc, f64> {
+    if value.is_undefined() {
+        return Ok(default);
+    }
+    if let Ok(number) = Number::try_from(value) {
+        return Ok(number.to_real(agent));
+    }
+    let name = to_string(agent, value, gc.reborrow())
+        .unbind()?
+        .bind(gc.nogc());
+    let start_time = agent
+        .performance
+        .entries
+        .iter()
+        .rev()
+        .find(|entry| {
+            entry.entry_type == PerformanceEntryType::Mark && String::eq(agent, entry.name, name)
+        })
+        .map(|entry| entry.start_time);
+    match start_time {
+        Some(start_time) => Ok(start_time),
+        None => {
+            let message = format!(
+                "The mark '{}' does not exist",
+                name.as_wtf8(agent).to_string_lossy()
+            );
+            Err(agent.throw_exception(ExceptionType::SyntaxError, message, gc.into_nogc()))
+        }
+    }
+}
+
+/// ### performance.getEntriesByType ( type )
+fn get_entries_by_type<'gc>(
+    agent: &mut Agent,
+    _this: Value,
+    args: ArgumentsList,
+    mut gc: GcScope<'gc, '_>,
+) -> JsResult<'gc, Value<'gc>> {
+    let entry_type = to_string(agent, args.get(0), gc.reborrow()).unbind()?;
+    let gc = gc.into_nogc();
+    let entry_type = entry_type.bind(gc);
+    let entry_type = match entry_type.as_wtf8(agent).as_str() {
+        Some("mark") => PerformanceEntryType::Mark,
+        Some("measure") => PerformanceEntryType::Measure,
+        _ => return Ok(create_array_from_list(agent, &[], gc).into_value()),
+    };
+    let entries = core::mem::take(&mut agent.performance.entries);
+    let objects = entries
+        .iter()
+        .filter(|entry| entry.entry_type == entry_type)
+        .map(|entry| create_entry_object(agent, entry, gc).into_value())
+        .collect::<Vec<_>>();
+    agent.performance.entries = entries;
+    Ok(create_array_from_list(agent, &objects, gc).into_value())
+}
+
+/// Create a plain object with the `name`, `entryType`, `startTime` and
+/// `duration` of a performance entry.
+fn create_entry_object<'a>(
+    agent: &mut Agent,
+    entry: &PerformanceEntry,
+    gc: NoGcScope<'a, '_>,
+) -> Object<'a> {
+    let entry_type = String::from_static_str(agent, entry.entry_type.as_str(), gc);
+    let start_time = Value::from_f64(agent, entry.start_time, gc);
+    let duration = Value::from_f64(agent, entry.duration, gc);
+    let entries = [
+        (
+            PropertyKey::from_static_str(agent, "name", gc),
+            entry.name.bind(gc).into_value(),
+        ),
+        (
+            PropertyKey::from_static_str(agent, "entryType", gc),
+            entry_type.into_value(),
+        ),
+        (
+            PropertyKey::from_static_str(agent, "startTime", gc),
+            start_time,
+        ),
+        (
+            PropertyKey::from_static_str(agent, "duration", gc),
+            duration,
+        ),
+    ]
+    .map(|(key, value)| ObjectEntry::new_data_entry(key, value));
+    let prototype = agent
+        .current_realm_record()
+        .intrinsics()
+        .object_prototype()
+        .into_object();
+    agent
+        .heap
+        .create_object_with_prototype(prototype, &entries)
+        .into_object()
+        .bind(gc)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        ecmascript::{
+            execution::{
+                Agent, DefaultHostHooks,
+                agent::{HostHooks, Job, Options},
+                initialize_default_realm,
+            },
+            types::{String, Value},
+        },
+        engine::context::{Bindable, GcScope},
+    };
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    /// Host hooks with a clock that only moves when the test sets it.
+    #[derive(Debug)]
+    struct FakeClockHostHooks(AtomicU64);
+
+    impl FakeClockHostHooks {
+        const fn new(now: f64) -> Self {
+            Self(AtomicU64::new(now.to_bits()))
+        }
+
+        fn set(&self, now: f64) {
+            self.0.store(now.to_bits(), Ordering::Relaxed);
+        }
+    }
+
+    impl HostHooks for FakeClockHostHooks {
+        fn enqueue_promise_job(&self, _job: Job) {}
+
+        fn monotonic_now(&self) -> f64 {
+            f64::from_bits(self.0.load(Ordering::Relaxed))
+        }
+    }
+
+    fn evaluate(agent: &mut Agent, source: &'static str, mut gc: GcScope) -> bool {
+        let source_text = String::from_static_str(agent, source, gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap()
+            .unbind();
+        result == Value::Boolean(true)
+    }
+
+    fn performance_options() -> Options {
+        Options {
+            performance: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn performance_global_is_optional() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        assert!(evaluate(agent, "typeof performance === 'undefined'", gc));
+    }
+
+    #[test]
+    fn now_is_monotonic_across_busy_loops() {
+        let mut agent = Agent::new(performance_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        assert!(evaluate(
+            agent,
+            "let previous = performance.now();
+            let monotonic = previous >= 0 && typeof performance.timeOrigin === 'number';
+            for (let i = 0; i < 100; i++) {
+                for (let j = 0; j < 100; j++) {}
+                const now = performance.now();
+                monotonic = monotonic && now >= previous;
+                previous = now;
+            }
+            monotonic",
+            gc,
+        ));
+    }
+
+    #[test]
+    fn fake_host_clock_makes_now_deterministic() {
+        static CLOCK: FakeClockHostHooks = FakeClockHostHooks::new(1000.0);
+        let mut agent = Agent::new(performance_options(), &CLOCK);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        assert!(evaluate(
+            agent,
+            "performance.timeOrigin === 1000 &&
+            performance.now() === 0 && performance.now() === 0",
+            gc.reborrow(),
+        ));
+        CLOCK.set(1012.5);
+        assert!(evaluate(
+            agent,
+            "performance.now() === 12.5 && performance.timeOrigin === 1000",
+            gc,
+        ));
+    }
+
+    #[test]
+    fn mark_and_measure_entries_round_trip() {
+        static CLOCK: FakeClockHostHooks = FakeClockHostHooks::new(0.0);
+        let mut agent = Agent::new(performance_options(), &CLOCK);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        CLOCK.set(5.0);
+        assert!(evaluate(
+            agent,
+            "const start = performance.mark('start');
+            start.name === 'start' && start.entryType === 'mark' &&
+            start.startTime === 5 && start.duration === 0",
+            gc.reborrow(),
+        ));
+        CLOCK.set(20.0);
+        assert!(evaluate(
+            agent,
+            "performance.mark('end');
+            const m = performance.measure('work', 'start', 'end');
+            const open = performance.measure('open', 'start');
+            const fromOrigin = performance.measure('origin');
+            const numeric = performance.measure('numeric', 2, 'end');
+            m.entryType === 'measure' && m.startTime === 5 && m.duration === 15 &&
+            open.startTime === 5 && open.duration === 15 &&
+            fromOrigin.startTime === 0 && fromOrigin.duration === 20 &&
+            numeric.startTime === 2 && numeric.duration === 18",
+            gc.reborrow(),
+        ));
+        agent.gc(gc.reborrow());
+        assert!(evaluate(
+            agent,
+            "const marks = performance.getEntriesByType('mark');
+            const measures = performance.getEntriesByType('measure');
+            let threw = false;
+            try {
+                performance.measure('missing', 'no such mark');
+            } catch (err) {
+                threw = err instanceof SyntaxError;
+            }
+            threw &&
+            marks.map(entry => entry.name).join() === 'start,end' &&
+            measures.map(entry => entry.name).join() === 'work,open,origin,numeric' &&
+            performance.getEntriesByType('resource').length === 0",
+            gc,
+        ));
+    }
+
+    #[test]
+    fn entry_buffer_is_bounded() {
+        let mut agent = Agent::new(performance_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        assert!(evaluate(
+            agent,
+            "for (let i = 0; i < 2000; i++) performance.mark('m' + i);
+            const marks = performance.getEntriesByType('mark');
+            marks.length === 1024 && marks[0].name === 'm976' &&
+            marks[1023].name === 'm1999'",
+            gc,
+        ));
+    }
+}
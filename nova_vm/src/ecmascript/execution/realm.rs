@@ -2,22 +2,34 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+mod checkpoint;
 mod intrinsics;
+mod lazy_globals;
 
 use super::new_global_environment;
-use super::{Agent, ExecutionContext, JsResult, environments::GlobalEnvironment};
+use super::{
+    Agent, ExecutionContext, JsResult, create_performance_object, environments::GlobalEnvironment,
+};
 use crate::engine::context::{Bindable, GcScope, GcToken, NoGcScope};
 use crate::engine::rootable::{HeapRootData, HeapRootRef, Rootable, Scopable};
 use crate::{
+    Heap,
     ecmascript::{
-        abstract_operations::operations_on_objects::define_property_or_throw,
+        builtins::Array,
+        scripts_and_modules::source_code::SourceCode,
         types::{
-            BUILTIN_STRING_MEMORY, IntoValue, Number, Object, OrdinaryObject, PropertyDescriptor,
-            PropertyKey, Value,
+            BUILTIN_STRING_MEMORY, IntoValue, Number, Object, OrdinaryObject,
+            PropertyDescriptorTemplate, PropertyKey, Value,
         },
     },
-    heap::{CompactionLists, HeapMarkAndSweep, WorkQueues},
+    heap::{
+        CompactionLists, HeapIndexable, HeapMarkAndSweep, WorkQueues,
+        snapshot::{HeapSnapshot, SnapshotError, SnapshotReader, SnapshotWriter},
+    },
 };
+use ahash::AHashMap;
+pub use checkpoint::RealmCheckpoint;
+pub(crate) use checkpoint::RealmCheckpointRecord;
 use core::{
     any::Any,
     marker::PhantomData,
@@ -26,6 +38,9 @@ use core::{
 };
 pub(crate) use intrinsics::Intrinsics;
 pub(crate) use intrinsics::ProtoIntrinsics;
+pub use lazy_globals::LazyGlobalInitializer;
+pub(crate) use lazy_globals::{LazyGlobals, initialize_lazy_global, try_initialize_lazy_global};
+use oxc_ast::ast;
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Realm<'a>(
@@ -89,6 +104,7 @@ impl Index<Realm<'_>> for Agent {
 
 impl IndexMut<Realm<'_>> for Agent {
     fn index_mut(&mut self, index: Realm) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.realms[index]
     }
 }
@@ -113,6 +129,23 @@ impl IndexMut<Realm<'_>> for Vec<Option<RealmRecord<'static>>> {
     }
 }
 
+impl HeapIndexable for Realm<'_> {
+    type Data = RealmRecord<'static>;
+    const KIND: &'static str = "Realm";
+
+    fn heap_index(self) -> usize {
+        self.into_index()
+    }
+
+    fn heap_vec(heap: &Heap) -> &[Option<Self::Data>] {
+        &heap.realms
+    }
+
+    fn heap_vec_mut(heap: &mut Heap) -> &mut [Option<Self::Data>] {
+        &mut heap.realms
+    }
+}
+
 // SAFETY: Property implemented as a lifetime transmute.
 unsafe impl Bindable for Realm<'_> {
     type Of<'a> = Realm<'a>;
@@ -168,7 +201,7 @@ impl HeapMarkAndSweep for Realm<'static> {
 /// ECMAScript global environment, all of the ECMAScript code that is loaded
 /// within the scope of that global environment, and other associated state and
 /// resources.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct RealmRecord<'a> {
     /// ### \[\[AgentSignifier]]
     ///
@@ -196,8 +229,12 @@ pub struct RealmRecord<'a> {
     /// Realm Record's \[\[TemplateMap]]. Each \[\[Site]] value is a Parse Node
     /// that is a TemplateLiteral. The associated \[\[Array]] value is the
     /// corresponding template object that is passed to a tag function.
-    /// NOTE: The template data is included in the AST.
-    template_map: (),
+    ///
+    /// NOTE: Sites are keyed by the address of the TemplateLiteral in the AST.
+    /// The SourceCode owning the AST is kept alive alongside the template
+    /// object so that the address cannot be reused by another site.
+    pub(crate) template_map:
+        AHashMap<*const ast::TemplateLiteral<'static>, (SourceCode<'a>, Array<'a>)>,
 
     /// ### \[\[LoadedModules]]
     ///
@@ -248,13 +285,17 @@ impl HeapMarkAndSweep for RealmRecord<'static> {
             intrinsics,
             global_object,
             global_env,
-            template_map: _,
+            template_map,
             loaded_modules: _,
             host_defined: _,
         } = self;
         intrinsics.mark_values(queues);
         global_env.mark_values(queues);
         global_object.mark_values(queues);
+        for (source_code, template) in template_map.values() {
+            source_code.mark_values(queues);
+            template.mark_values(queues);
+        }
     }
 
     fn sweep_values(&mut self, compactions: &CompactionLists) {
@@ -263,13 +304,52 @@ impl HeapMarkAndSweep for RealmRecord<'static> {
             intrinsics,
             global_object,
             global_env,
-            template_map: _,
+            template_map,
             loaded_modules: _,
             host_defined: _,
         } = self;
         intrinsics.sweep_values(compactions);
         global_env.sweep_values(compactions);
         global_object.sweep_values(compactions);
+        for (source_code, template) in template_map.values_mut() {
+            source_code.sweep_values(compactions);
+            template.sweep_values(compactions);
+        }
+    }
+}
+
+impl HeapSnapshot for RealmRecord<'static> {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        let Self {
+            agent_signifier: _,
+            intrinsics,
+            global_object,
+            global_env,
+            // Note: Template objects and the cache are rebuilt on demand after
+            // reading a snapshot.
+            template_map: _,
+            loaded_modules: _,
+            host_defined,
+        } = self;
+        assert!(
+            host_defined.is_none(),
+            "Startup snapshots cannot contain host defined Realm data"
+        );
+        intrinsics.write_snapshot(writer);
+        global_object.write_snapshot(writer);
+        global_env.write_snapshot(writer);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        Ok(Self {
+            agent_signifier: PhantomData,
+            intrinsics: HeapSnapshot::read_snapshot(reader)?,
+            global_object: HeapSnapshot::read_snapshot(reader)?,
+            global_env: HeapSnapshot::read_snapshot(reader)?,
+            template_map: AHashMap::default(),
+            loaded_modules: (),
+            host_defined: None,
+        })
     }
 }
 
@@ -293,7 +373,7 @@ pub(crate) fn create_realm<'gc>(agent: &mut Agent, gc: NoGcScope<'gc, '_>) -> Re
         global_env: None,
 
         // 6. Set realmRec.[[TemplateMap]] to a new empty List.
-        template_map: (),
+        template_map: AHashMap::default(),
 
         // NOTE: These fields are implicitly empty.
         host_defined: None,
@@ -339,6 +419,12 @@ pub(crate) fn create_intrinsics(agent: &mut Agent) -> Intrinsics {
 }
 
 /// ### [9.3.3 SetRealmGlobalObject ( realmRec, globalObj, thisValue )](https://tc39.es/ecma262/#sec-setrealmglobalobject)
+///
+/// If `this_value` is given, it becomes the realm's global this binding: the
+/// value of `this` at the top level of scripts, of `this` in sloppy mode
+/// functions called without a receiver, and of `globalThis`. Global variables
+/// are still stored on the global object. This is used by web embedders to
+/// expose a `WindowProxy` as `this` while the `Window` is the global object.
 pub(crate) fn set_realm_global_object(
     agent: &mut Agent,
     realm_id: Realm,
@@ -390,6 +476,9 @@ pub(crate) fn set_default_global_bindings<'a>(
     let global = agent[realm_id].global_object.scope(agent, gc.nogc());
 
     // 2. For each property of the Global Object specified in clause 19, do
+    // NOTE: The properties are collected here and defined in a single batch
+    // at the end.
+    let mut entries = Vec::with_capacity(64);
     macro_rules! define_property {
         (intrinsic $name:ident, $value:ident) => {
             // most of the properties have this configuration
@@ -398,7 +487,7 @@ pub(crate) fn set_default_global_bindings<'a>(
                 .intrinsics()
                 .$value()
                 .into_value();
-            define_property!($name, value, Some(true), Some(false), Some(true));
+            define_property!($name, value, true, false, true);
         };
         ($name:ident, $value:ident, $writable:expr, $enumerable:expr, $configurable:expr) => {
             // a. Let name be the String value of the property name.
@@ -409,18 +498,14 @@ pub(crate) fn set_default_global_bindings<'a>(
             //    property, containing the specified attributes for the property. For
             //    properties listed in 19.2, 19.3, or 19.4 the value of the [[Value]]
             //    attribute is the corresponding intrinsic object from realmRec.
-            let desc = PropertyDescriptor {
-                value: Some(value),
+            let desc = PropertyDescriptorTemplate::Data {
+                value,
                 writable: $writable,
                 enumerable: $enumerable,
                 configurable: $configurable,
-                ..Default::default()
             };
 
-            // c. Perform ? DefinePropertyOrThrow(global, name, desc).
-            define_property_or_throw(agent, global.get(agent), name, desc, gc.reborrow())
-                .unbind()?
-                .bind(gc.nogc());
+            entries.push((name, desc));
         };
     }
 
@@ -433,23 +518,23 @@ pub(crate) fn set_default_global_bindings<'a>(
             .get_this_binding(agent, gc.nogc())
             .into_value()
             .unbind();
-        define_property!(globalThis, value, None, None, None);
+        define_property!(globalThis, value, true, false, true);
 
         // 19.1.2 Infinity
         let value = Number::from_f64(agent, f64::INFINITY, gc.nogc())
             .into_value()
             .unbind();
-        define_property!(Infinity, value, Some(false), Some(false), Some(false));
+        define_property!(Infinity, value, false, false, false);
 
         // 19.1.3 NaN
         let value = Number::from_f64(agent, f64::NAN, gc.nogc())
             .into_value()
             .unbind();
-        define_property!(NaN, value, Some(false), Some(false), Some(false));
+        define_property!(NaN, value, false, false, false);
 
         // 19.1.4 undefined
         let value = Value::Undefined;
-        define_property!(undefined, value, Some(false), Some(false), Some(false));
+        define_property!(undefined, value, false, false, false);
     }
 
     // 19.2 Function Properties of the Global Object
@@ -650,6 +735,21 @@ pub(crate) fn set_default_global_bindings<'a>(
         define_property!(intrinsic Reflect, reflect);
     }
 
+    // Host-defined properties
+    if agent.options.performance {
+        let name = PropertyKey::from_static_str(agent, "performance", gc.nogc()).unbind();
+        let value = create_performance_object(agent, gc.nogc())
+            .into_value()
+            .unbind();
+        entries.push((name, PropertyDescriptorTemplate::builtin(value)));
+    }
+
+    // c. Perform ? DefinePropertyOrThrow(global, name, desc).
+    global
+        .get(agent)
+        .define_properties_batch(agent, &entries, gc.reborrow())
+        .unbind()?;
+
     // 3. Return global.
     Ok(global.get(agent).bind(gc.into_nogc()))
 }
@@ -689,8 +789,8 @@ pub(crate) fn initialize_host_defined_realm(
     // 7. If the host requires use of an exotic object to serve as realm's global object,
     // let global be such an object created in a host-defined manner.
     // Otherwise, let global be undefined, indicating that an ordinary object should be created as the global object.
-    let global = create_global_this_value.map(|create_global_this_value| {
-        create_global_this_value(agent, gc.reborrow())
+    let global = create_global_object.map(|create_global_object| {
+        create_global_object(agent, gc.reborrow())
             .unbind()
             .scope(agent, gc.nogc())
     });
@@ -698,8 +798,8 @@ pub(crate) fn initialize_host_defined_realm(
     // 8. If the host requires that the this binding in realm's global scope return an object other than the global object,
     // let thisValue be such an object created in a host-defined manner.
     // Otherwise, let thisValue be undefined, indicating that realm's global this binding should be the global object.
-    let this_value =
-        create_global_object.map(|create_global_object| create_global_object(agent, gc.reborrow()));
+    let this_value = create_global_this_value
+        .map(|create_global_this_value| create_global_this_value(agent, gc.reborrow()));
 
     // 9. Perform SetRealmGlobalObject(realm, global, thisValue).
     set_realm_global_object(
@@ -741,6 +841,13 @@ pub(crate) fn initialize_default_realm(agent: &mut Agent, gc: GcScope) {
 
 #[cfg(test)]
 mod test {
+    use crate::ecmascript::{
+        execution::{Agent, DefaultHostHooks, agent::Options, initialize_default_realm},
+        types::{
+            Function, InternalMethods, IntoObject, IntoValue, Object, PropertyDescriptor,
+            PropertyKey, String, Value,
+        },
+    };
     #[allow(unused_imports)]
     use crate::{
         engine::context::{Bindable, GcScope},
@@ -750,6 +857,7 @@ mod test {
             LAST_INTRINSIC_OBJECT_INDEX, LAST_WELL_KNOWN_SYMBOL_INDEX,
         },
     };
+
     fn panic_builtin_function_missing(index: usize) {
         let index = index as u32;
         let mut changed_index = index;
@@ -800,9 +908,7 @@ mod test {
         use crate::heap::indexes::ObjectIndex;
 
         let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let gc = GcScope::new(&mut gc, &mut scope);
-        initialize_default_realm(&mut agent, gc);
+        agent.with_gc(initialize_default_realm);
         assert_eq!(
             agent.current_realm_record().intrinsics().object_index_base,
             ObjectIndex::from_index(0)
@@ -883,4 +989,302 @@ mod test {
         }
         assert!(agent.heap.regexps.is_empty());
     }
+
+    /// Representative built-ins whose own properties are checked.
+    const OBJECTS: &[&str] = &[
+        "Object",
+        "Object.prototype",
+        "Function",
+        "Function.prototype",
+        "Array",
+        "Array.prototype",
+        "Error",
+        "Error.prototype",
+        "EvalError",
+        "EvalError.prototype",
+        "RangeError",
+        "RangeError.prototype",
+        "ReferenceError",
+        "ReferenceError.prototype",
+        "SyntaxError",
+        "SyntaxError.prototype",
+        "TypeError",
+        "TypeError.prototype",
+        "URIError",
+        "URIError.prototype",
+        "AggregateError",
+        "AggregateError.prototype",
+        "Math",
+        "JSON",
+        "Reflect",
+    ];
+
+    /// Properties whose attributes are specified to differ from the defaults.
+    const EXCEPTIONS: &[(&str, &str)] = &[
+        // 20.2.3.6 Function.prototype [ %Symbol.hasInstance% ] ( V )
+        ("Function.prototype[Symbol.hasInstance]", "---"),
+        // 23.1.3 Properties of the Array Prototype Object
+        ("Array.prototype.length", "w--"),
+        // 23.1.3.41 Array.prototype [ %Symbol.unscopables% ]
+        ("Array.prototype[Symbol.unscopables]", "--c"),
+        // 10.2.4.1 %ThrowTypeError% ( )
+        ("Function.prototype.caller getter.length", "---"),
+        ("Function.prototype.caller getter.name", "---"),
+        ("Function.prototype.caller setter.length", "---"),
+        ("Function.prototype.caller setter.name", "---"),
+        ("Function.prototype.arguments getter.length", "---"),
+        ("Function.prototype.arguments getter.name", "---"),
+        ("Function.prototype.arguments setter.length", "---"),
+        ("Function.prototype.arguments setter.name", "---"),
+    ];
+
+    /// Functions whose name is specified to differ from their property key.
+    const NAMES: &[(&str, &str)] = &[
+        // 23.1.3.40 Array.prototype [ %Symbol.iterator% ] ( )
+        ("Array.prototype[Symbol.iterator]", "values"),
+        // 10.2.4.1 %ThrowTypeError% ( )
+        ("Function.prototype.caller getter", ""),
+        ("Function.prototype.caller setter", ""),
+        ("Function.prototype.arguments getter", ""),
+        ("Function.prototype.arguments setter", ""),
+    ];
+
+    struct Checker<'a> {
+        agent: &'a mut Agent,
+        failures: Vec<std::string::String>,
+    }
+
+    impl Checker<'_> {
+        fn eval(&mut self, source: &'static str, mut gc: GcScope) -> Value<'static> {
+            let source_text = String::from_static_str(self.agent, source, gc.nogc());
+            self.agent
+                .run_script(source_text.unbind(), gc.reborrow())
+                .unwrap()
+                .unbind()
+        }
+
+        fn own_keys(&mut self, object: Object, gc: GcScope) -> Vec<PropertyKey<'static>> {
+            object
+                .unbind()
+                .internal_own_property_keys(self.agent, gc)
+                .unwrap()
+                .into_iter()
+                .map(|key| key.unbind())
+                .collect()
+        }
+
+        fn descriptor(
+            &mut self,
+            object: Object,
+            key: PropertyKey,
+            gc: GcScope,
+        ) -> PropertyDescriptor<'static> {
+            object
+                .unbind()
+                .internal_get_own_property(self.agent, key.unbind(), gc)
+                .unwrap()
+                .unwrap()
+                .unbind()
+        }
+
+        /// Returns the key as it appears in function names, eg. `[Symbol.species]`.
+        fn key_name(&mut self, key: PropertyKey, gc: GcScope) -> std::string::String {
+            let value = key.unbind().convert_to_value(self.agent, gc.nogc());
+            let is_symbol = matches!(key, PropertyKey::Symbol(_));
+            let repr = value.into_value().unbind().string_repr(self.agent, gc);
+            let repr = repr.as_str(self.agent);
+            if is_symbol {
+                format!("[{}]", &repr["Symbol(".len()..repr.len() - 1])
+            } else {
+                repr.to_owned()
+            }
+        }
+
+        fn path(object_path: &str, key_name: &str) -> std::string::String {
+            if key_name.starts_with('[') {
+                format!("{object_path}{key_name}")
+            } else {
+                format!("{object_path}.{key_name}")
+            }
+        }
+
+        fn expect_attributes(&mut self, path: &str, desc: &PropertyDescriptor, expected: &str) {
+            let expected = EXCEPTIONS
+                .iter()
+                .find(|(exception, _)| *exception == path)
+                .map_or(expected, |(_, attributes)| attributes);
+            let flag =
+                |attribute: Option<bool>, c: char| if attribute == Some(true) { c } else { '-' };
+            let attributes: std::string::String = [
+                flag(desc.writable, 'w'),
+                flag(desc.enumerable, 'e'),
+                flag(desc.configurable, 'c'),
+            ]
+            .into_iter()
+            .collect();
+            if attributes != expected {
+                self.failures
+                    .push(format!("{path}: expected {expected}, got {attributes}"));
+            }
+        }
+
+        fn check_function(&mut self, path: &str, function: Function, name: &str, mut gc: GcScope) {
+            let function = function.unbind().into_object();
+            let keys = self.own_keys(function, gc.reborrow());
+            let key_names = keys
+                .iter()
+                .take(2)
+                .map(|key| self.key_name(*key, gc.reborrow()))
+                .collect::<Vec<_>>();
+            if key_names != ["length", "name"] {
+                self.failures.push(format!(
+                    "{path}: expected own keys to start with length, name, got {key_names:?}"
+                ));
+            }
+            for key in keys {
+                let key_name = self.key_name(key, gc.reborrow());
+                let desc = self.descriptor(function, key, gc.reborrow());
+                let property_path = Self::path(path, &key_name);
+                match key_name.as_str() {
+                    "length" => self.expect_attributes(&property_path, &desc, "--c"),
+                    "name" => {
+                        self.expect_attributes(&property_path, &desc, "--c");
+                        let actual = desc.value.unwrap().string_repr(self.agent, gc.reborrow());
+                        let actual = actual.as_str(self.agent);
+                        let expected = NAMES
+                            .iter()
+                            .find(|(function, _)| *function == path)
+                            .map_or(name, |(_, name)| name);
+                        if actual != expected {
+                            self.failures.push(format!(
+                                "{path}: expected name {expected:?}, got {actual:?}"
+                            ));
+                        }
+                    }
+                    "prototype" => self.expect_attributes(&property_path, &desc, "---"),
+                    _ => {}
+                }
+            }
+        }
+
+        fn check_object(&mut self, path: &str, object: Object, mut gc: GcScope) {
+            let object = object.unbind();
+            for key in self.own_keys(object, gc.reborrow()) {
+                let key_name = self.key_name(key, gc.reborrow());
+                let property_path = Self::path(path, &key_name);
+                let desc = self.descriptor(object, key, gc.reborrow());
+                if desc.is_accessor_descriptor() {
+                    self.expect_attributes(&property_path, &desc, "--c");
+                    if let Some(getter) = desc.get {
+                        let name = format!("get {key_name}");
+                        self.check_function(
+                            &format!("{property_path} getter"),
+                            getter,
+                            &name,
+                            gc.reborrow(),
+                        );
+                    }
+                    if let Some(setter) = desc.set {
+                        let name = format!("set {key_name}");
+                        self.check_function(
+                            &format!("{property_path} setter"),
+                            setter,
+                            &name,
+                            gc.reborrow(),
+                        );
+                    }
+                    continue;
+                }
+                let value = desc.value.unwrap();
+                match key_name.as_str() {
+                    "[Symbol.toStringTag]" => {
+                        self.expect_attributes(&property_path, &desc, "--c");
+                        if !value.is_string() {
+                            self.failures
+                                .push(format!("{property_path}: expected a string value"));
+                        }
+                    }
+                    "constructor" => {
+                        self.expect_attributes(&property_path, &desc, "w-c");
+                        let prototype = Function::try_from(value).ok().map(|constructor| {
+                            let key =
+                                PropertyKey::from_static_str(self.agent, "prototype", gc.nogc())
+                                    .unbind();
+                            self.descriptor(constructor.into_object(), key, gc.reborrow())
+                                .value
+                        });
+                        if prototype != Some(Some(object.into_value())) {
+                            self.failures.push(format!(
+                                "{property_path}: expected a constructor whose prototype is {path}"
+                            ));
+                        }
+                    }
+                    "prototype" if value.is_object() => {
+                        self.expect_attributes(&property_path, &desc, "---");
+                    }
+                    "length" | "name" if matches!(object, Object::BuiltinFunction(_)) => {
+                        self.expect_attributes(&property_path, &desc, "--c");
+                    }
+                    _ => {
+                        if let Ok(function) = Function::try_from(value) {
+                            self.expect_attributes(&property_path, &desc, "w-c");
+                            self.check_function(&property_path, function, &key_name, gc.reborrow());
+                        } else if value.is_number() {
+                            // Value properties of namespace objects and
+                            // constructors, eg. Math.PI.
+                            self.expect_attributes(&property_path, &desc, "---");
+                        } else {
+                            self.expect_attributes(&property_path, &desc, "w-c");
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Checks the attributes and key ordering of built-in properties against
+    /// the rules in [ECMAScript Standard Built-in Objects](https://tc39.es/ecma262/#sec-ecmascript-standard-built-in-objects).
+    #[test]
+    fn builtin_properties_follow_the_standard_attributes() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let mut checker = Checker {
+            agent,
+            failures: vec![],
+        };
+
+        let global = checker.eval("globalThis", gc.reborrow());
+        let global = Object::try_from(global).unwrap();
+        for key in ["globalThis", "Object", "Array", "Math", "JSON", "Reflect"] {
+            let property_key = PropertyKey::from_static_str(checker.agent, key, gc.nogc()).unbind();
+            let desc = checker.descriptor(global, property_key, gc.reborrow());
+            checker.expect_attributes(&format!("globalThis.{key}"), &desc, "w-c");
+        }
+        for key in ["Infinity", "NaN", "undefined"] {
+            let property_key = PropertyKey::from_static_str(checker.agent, key, gc.nogc()).unbind();
+            let desc = checker.descriptor(global, property_key, gc.reborrow());
+            checker.expect_attributes(&format!("globalThis.{key}"), &desc, "---");
+        }
+
+        for path in OBJECTS {
+            let object = checker.eval(path, gc.reborrow());
+            let object = Object::try_from(object).unwrap();
+            if let Ok(function) = Function::try_from(object.into_value()) {
+                // Function.prototype is itself a function, named "".
+                let name = match path.strip_suffix(".prototype") {
+                    Some(_) => "",
+                    None => path.rsplit('.').next().unwrap(),
+                };
+                checker.check_function(path, function, name, gc.reborrow());
+            }
+            checker.check_object(path, object, gc.reborrow());
+        }
+
+        let mut failures = checker.failures;
+        failures.dedup();
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
 }
@@ -0,0 +1,295 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! ## Realm checkpoints
+//!
+//! A checkpoint captures the global bindings of a Realm: the own properties,
+//! \[\[Prototype]] and \[\[Extensible]] of the global object, and the lexical
+//! declarations and \[\[VarNames]] of the global environment. Rolling back to
+//! the checkpoint restores exactly those, so that the global mutations of a
+//! script can be undone before the next script runs in the same Realm.
+//!
+//! This is much lighter than cloning the Realm, but objects reachable from the
+//! global bindings are not captured: If a script mutates such an object, for
+//! instance `Array.prototype`, the mutation survives the rollback.
+
+use super::Realm;
+use crate::{
+    ecmascript::{
+        builtins::ordinary::ordinary_own_property_keys,
+        execution::{Agent, GlobalDeclarations},
+        types::{InternalSlots, Object, OrdinaryObject, PropertyDescriptor, PropertyKey},
+    },
+    engine::context::{Bindable, NoGcScope},
+    heap::{CompactionLists, HeapMarkAndSweep, WorkQueues},
+};
+
+/// Handle to a checkpoint of the global bindings of a Realm, created by
+/// [`Agent::checkpoint_realm`].
+///
+/// As long as this is not passed to [`Agent::remove_realm_checkpoint`], the
+/// Realm and the captured bindings won't be removed by the garbage collector.
+#[must_use]
+#[derive(Debug)]
+#[repr(transparent)]
+pub struct RealmCheckpoint {
+    /// Index in the Agent::realm_checkpoints vector that contains the
+    /// captured global bindings.
+    pub(crate) index: u32,
+}
+
+/// Global bindings of a Realm captured by a checkpoint.
+#[derive(Debug)]
+pub(crate) struct RealmCheckpointRecord {
+    realm: Realm<'static>,
+    global_object: OrdinaryObject<'static>,
+    prototype: Option<Object<'static>>,
+    extensible: bool,
+    properties: Vec<(PropertyKey<'static>, PropertyDescriptor<'static>)>,
+    declarations: GlobalDeclarations,
+}
+
+impl RealmCheckpointRecord {
+    /// Captures the global bindings of the Realm.
+    ///
+    /// ## Panics
+    ///
+    /// If the global object of the Realm is not an ordinary object.
+    pub(crate) fn new(agent: &mut Agent, realm: Realm, gc: NoGcScope) -> Self {
+        let realm = realm.unbind();
+        let realm_record = &agent[realm];
+        let global_env = realm_record.global_env.unwrap();
+        let Ok(global_object) = OrdinaryObject::try_from(realm_record.global_object) else {
+            panic!("Cannot checkpoint a Realm whose global object is not an ordinary object");
+        };
+        let global_object = global_object.unbind();
+        let declarations = global_env.declarations(agent);
+        let properties = ordinary_own_property_keys(agent, global_object, gc)
+            .into_iter()
+            .map(|key| {
+                let descriptor = global_object.property_storage().get(agent, key).unwrap();
+                (key.unbind(), descriptor.unbind())
+            })
+            .collect();
+        Self {
+            realm,
+            global_object,
+            prototype: global_object.internal_prototype(agent),
+            extensible: global_object.internal_extensible(agent),
+            properties,
+            declarations,
+        }
+    }
+
+    /// Restores the global bindings of the Realm to the captured ones.
+    pub(crate) fn rollback(&self, agent: &mut Agent, gc: NoGcScope) {
+        let Self {
+            realm,
+            global_object,
+            prototype,
+            extensible,
+            properties,
+            declarations,
+        } = self;
+        let global_object = *global_object;
+        let storage = global_object.property_storage();
+        // Remove all properties and add the captured ones back, so that the
+        // property order is restored as well. Removing the last property first
+        // avoids shifting the remaining ones.
+        let keys = ordinary_own_property_keys(agent, global_object, gc);
+        for key in keys.into_iter().rev() {
+            storage.remove(agent, key);
+        }
+        for (key, descriptor) in properties {
+            storage.set(agent, *key, descriptor.clone());
+        }
+        global_object.internal_set_prototype(agent, *prototype);
+        global_object.internal_set_extensible(agent, *extensible);
+        let global_env = agent[*realm].global_env.unwrap();
+        global_env.restore_declarations(agent, declarations);
+    }
+}
+
+impl HeapMarkAndSweep for RealmCheckpointRecord {
+    fn mark_values(&self, queues: &mut WorkQueues) {
+        let Self {
+            realm,
+            global_object,
+            prototype,
+            extensible: _,
+            properties,
+            declarations,
+        } = self;
+        realm.mark_values(queues);
+        global_object.mark_values(queues);
+        prototype.mark_values(queues);
+        for (key, descriptor) in properties {
+            key.mark_values(queues);
+            descriptor.value.mark_values(queues);
+            descriptor.get.mark_values(queues);
+            descriptor.set.mark_values(queues);
+        }
+        declarations.mark_values(queues);
+    }
+
+    fn sweep_values(&mut self, compactions: &CompactionLists) {
+        let Self {
+            realm,
+            global_object,
+            prototype,
+            extensible: _,
+            properties,
+            declarations,
+        } = self;
+        realm.sweep_values(compactions);
+        global_object.sweep_values(compactions);
+        prototype.sweep_values(compactions);
+        for (key, descriptor) in properties {
+            key.sweep_values(compactions);
+            descriptor.value.sweep_values(compactions);
+            descriptor.get.sweep_values(compactions);
+            descriptor.set.sweep_values(compactions);
+        }
+        declarations.sweep_values(compactions);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        ecmascript::{
+            execution::{
+                DefaultHostHooks,
+                agent::{GcAgent, Options, RealmRoot},
+            },
+            scripts_and_modules::script::test::evaluate,
+            types::{String, Value},
+        },
+        engine::context::Bindable,
+    };
+
+    fn assert_all_true(agent: &mut GcAgent, realm: &RealmRoot, sources: &[&'static str]) {
+        agent.run_in_realm(realm, |agent, mut gc| {
+            for source in sources {
+                let result = evaluate(agent, source, gc.reborrow()).unbind();
+                assert_eq!(result, Value::Boolean(true), "{source}");
+            }
+        });
+    }
+
+    fn global_property_names(agent: &mut GcAgent, realm: &RealmRoot) -> std::string::String {
+        agent.run_in_realm(realm, |agent, mut gc| {
+            let names = evaluate(
+                agent,
+                "Object.getOwnPropertyNames(globalThis).join()",
+                gc.reborrow(),
+            );
+            let names = String::try_from(names.unbind()).unwrap();
+            names.as_str(agent).to_owned()
+        })
+    }
+
+    fn run(agent: &mut GcAgent, realm: &RealmRoot, source: &'static str) {
+        agent.run_in_realm(realm, |agent, gc| {
+            evaluate(agent, source, gc);
+        });
+    }
+
+    #[test]
+    fn rollback_reverts_global_mutations() {
+        let mut agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+        let realm = agent.create_default_realm();
+        run(
+            &mut agent,
+            &realm,
+            "var config = { name: 'host' };
+            let counter = 0;
+            function api() { return 'api'; }
+            globalThis.shared = 1;",
+        );
+        let checkpoint = agent.checkpoint_realm(&realm);
+        let names_before = global_property_names(&mut agent, &realm);
+
+        // A misbehaving plugin.
+        run(
+            &mut agent,
+            &realm,
+            "var leaked = 'var';
+            let lexical = 'let';
+            class Leaked {}
+            function api() { return 'hijacked'; }
+            globalThis.implicit = true;
+            counter = 10;
+            config = null;
+            shared = 2;
+            delete globalThis.shared;
+            Object.defineProperty(globalThis, 'locked', { value: 1 });
+            globalThis.Math = undefined;",
+        );
+        // The GC must keep the captured values alive.
+        agent.gc();
+        agent.rollback_realm(&checkpoint);
+
+        assert_all_true(
+            &mut agent,
+            &realm,
+            &[
+                "typeof leaked === 'undefined' && !('leaked' in globalThis)",
+                "typeof lexical === 'undefined' && typeof Leaked === 'undefined'",
+                "!('implicit' in globalThis) && !('locked' in globalThis)",
+                "api() === 'api' && counter === 0 && config.name === 'host' && shared === 1",
+                "Math.max(1, 2) === 2",
+            ],
+        );
+        // The property order is restored as well.
+        assert_eq!(global_property_names(&mut agent, &realm), names_before);
+
+        // The rolled back declarations can be declared again, and the checkpoint
+        // can be rolled back to again.
+        run(
+            &mut agent,
+            &realm,
+            "let lexical = 'again'; var leaked = 'again'; counter++;",
+        );
+        assert_all_true(
+            &mut agent,
+            &realm,
+            &["lexical === 'again' && counter === 1"],
+        );
+        agent.rollback_realm(&checkpoint);
+        assert_all_true(
+            &mut agent,
+            &realm,
+            &["typeof lexical === 'undefined' && counter === 0"],
+        );
+
+        agent.remove_realm_checkpoint(checkpoint);
+        agent.remove_realm(realm);
+    }
+
+    #[test]
+    fn objects_reachable_from_globals_are_not_captured() {
+        let mut agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+        let realm = agent.create_default_realm();
+        run(&mut agent, &realm, "var config = { name: 'host' };");
+        let checkpoint = agent.checkpoint_realm(&realm);
+        run(
+            &mut agent,
+            &realm,
+            "config.name = 'plugin'; Object.setPrototypeOf(globalThis, null);
+            Object.preventExtensions(globalThis);",
+        );
+        agent.rollback_realm(&checkpoint);
+        assert_all_true(
+            &mut agent,
+            &realm,
+            &[
+                "config.name === 'plugin'",
+                "Object.getPrototypeOf(globalThis) !== null && Object.isExtensible(globalThis)",
+            ],
+        );
+        agent.remove_realm_checkpoint(checkpoint);
+        agent.remove_realm(realm);
+    }
+}
@@ -40,7 +40,7 @@ use crate::ecmascript::builtins::structured_data::shared_array_buffer_objects::{
 #[cfg(feature = "regexp")]
 use crate::ecmascript::builtins::text_processing::regexp_objects::{
     regexp_constructor::RegExpConstructor, regexp_prototype::RegExpPrototype,
-    regexp_string_iterator_prototype::RegExpStringIteratorPrototype,
+    regexp_string_iterator_objects::RegExpStringIteratorPrototype,
 };
 #[cfg(feature = "array-buffer")]
 use crate::ecmascript::builtins::{
@@ -148,6 +148,7 @@ use crate::{
         IntrinsicObjectIndexes, IntrinsicPrimitiveObjectIndexes, WorkQueues,
         indexes::{ArrayIndex, BuiltinFunctionIndex, ObjectIndex, PrimitiveObjectIndex},
         intrinsic_function_count, intrinsic_object_count, intrinsic_primitive_object_count,
+        snapshot::{HeapSnapshot, SnapshotError, SnapshotReader, SnapshotWriter},
     },
 };
 #[derive(Debug, Clone)]
@@ -214,6 +215,8 @@ pub enum ProtoIntrinsics {
     Set,
     #[cfg(feature = "set")]
     SetIterator,
+    #[cfg(feature = "regexp")]
+    RegExpStringIterator,
     #[cfg(feature = "shared-array-buffer")]
     SharedArrayBuffer,
     String,
@@ -439,6 +442,10 @@ impl Intrinsics {
             ProtoIntrinsics::Set => self.set_prototype().into(),
             #[cfg(feature = "set")]
             ProtoIntrinsics::SetIterator => self.set_iterator_prototype().into(),
+            #[cfg(feature = "regexp")]
+            ProtoIntrinsics::RegExpStringIterator => {
+                self.reg_exp_string_iterator_prototype().into()
+            }
             #[cfg(feature = "shared-array-buffer")]
             ProtoIntrinsics::SharedArrayBuffer => self.shared_array_buffer_prototype().into(),
             #[cfg(feature = "array-buffer")]
@@ -501,6 +508,13 @@ impl Intrinsics {
         IntrinsicConstructorIndexes::AggregateError.get_object_index(self.object_index_base)
     }
 
+    /// %Array.prototype.join%
+    pub(crate) fn array_prototype_join(&self) -> BuiltinFunction<'static> {
+        IntrinsicFunctionIndexes::ArrayPrototypeJoin
+            .get_builtin_function_index(self.builtin_function_index_base)
+            .into()
+    }
+
     /// %Array.prototype.sort%
     pub(crate) fn array_prototype_sort(&self) -> BuiltinFunction<'static> {
         IntrinsicFunctionIndexes::ArrayPrototypeSort
@@ -1656,10 +1670,35 @@ impl Intrinsics {
     }
 }
 
+impl HeapSnapshot for Intrinsics {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        let Self {
+            object_index_base,
+            primitive_object_index_base,
+            array_prototype,
+            builtin_function_index_base,
+        } = self;
+        object_index_base.write_snapshot(writer);
+        primitive_object_index_base.write_snapshot(writer);
+        array_prototype.write_snapshot(writer);
+        builtin_function_index_base.write_snapshot(writer);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        Ok(Self {
+            object_index_base: HeapSnapshot::read_snapshot(reader)?,
+            primitive_object_index_base: HeapSnapshot::read_snapshot(reader)?,
+            array_prototype: HeapSnapshot::read_snapshot(reader)?,
+            builtin_function_index_base: HeapSnapshot::read_snapshot(reader)?,
+        })
+    }
+}
+
 impl HeapMarkAndSweep for Intrinsics {
     fn mark_values(&self, queues: &mut WorkQueues) {
         self.aggregate_error_prototype().mark_values(queues);
         self.aggregate_error().mark_values(queues);
+        self.array_prototype_join().mark_values(queues);
         self.array_prototype_sort().mark_values(queues);
         self.array_prototype_to_string().mark_values(queues);
         self.array_prototype_values().mark_values(queues);
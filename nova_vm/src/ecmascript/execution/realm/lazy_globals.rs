@@ -0,0 +1,333 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! ## Lazy globals
+//!
+//! A lazy global is a property of a global object whose value is computed by
+//! a host-provided initializer the first time it is needed. Until then the
+//! property exists as a placeholder data property with the value undefined,
+//! so that \[\[HasProperty]] and \[\[OwnPropertyKeys]] see it without running
+//! the initializer. \[\[GetOwnProperty]] and \[\[DefineOwnProperty]] run the
+//! initializer first and replace the placeholder value with its result,
+//! after which the property is an ordinary data property. \[\[Delete]] of a
+//! placeholder cancels the initializer.
+//!
+//! The infallible internal methods cannot run the initializer as it may
+//! trigger garbage collection, so they break out of the fast path instead.
+
+use ahash::AHashMap;
+
+use crate::{
+    ecmascript::{
+        execution::Agent,
+        types::{Object, OrdinaryObject, PropertyKey, Value},
+    },
+    engine::{
+        TryResult,
+        context::{Bindable, GcScope},
+        rootable::Scopable,
+    },
+    heap::{CompactionLists, HeapMarkAndSweep, HeapSweepWeakReference, WorkQueues},
+};
+
+/// Host-provided initializer of a lazy global, see
+/// [`Agent::define_lazy_global`].
+pub type LazyGlobalInitializer =
+    Box<dyn for<'gc> FnOnce(&mut Agent, GcScope<'gc, '_>) -> Value<'gc>>;
+
+/// Initializers of the lazy globals that have not been initialized yet, by
+/// global object and property key.
+///
+/// The global objects are held weakly: the lazy globals of a removed Realm
+/// are dropped with it.
+#[derive(Default)]
+pub(crate) struct LazyGlobals(
+    AHashMap<OrdinaryObject<'static>, AHashMap<PropertyKey<'static>, LazyGlobalInitializer>>,
+);
+
+impl core::fmt::Debug for LazyGlobals {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_map()
+            .entries(
+                self.0
+                    .iter()
+                    .map(|(object, initializers)| (object, initializers.keys())),
+            )
+            .finish()
+    }
+}
+
+impl LazyGlobals {
+    pub(crate) fn insert(
+        &mut self,
+        object: OrdinaryObject,
+        property_key: PropertyKey,
+        initializer: LazyGlobalInitializer,
+    ) {
+        self.0
+            .entry(object.unbind())
+            .or_default()
+            .insert(property_key.unbind(), initializer);
+    }
+
+    pub(crate) fn remove(
+        &mut self,
+        object: OrdinaryObject,
+        property_key: PropertyKey,
+    ) -> Option<LazyGlobalInitializer> {
+        if self.0.is_empty() {
+            return None;
+        }
+        let object = object.unbind();
+        let initializers = self.0.get_mut(&object)?;
+        let initializer = initializers.remove(&property_key.unbind())?;
+        if initializers.is_empty() {
+            self.0.remove(&object);
+        }
+        Some(initializer)
+    }
+
+    /// Returns true if the property of the object is a lazy global that has
+    /// not been initialized yet.
+    #[inline]
+    pub(crate) fn is_pending(&self, object: OrdinaryObject, property_key: PropertyKey) -> bool {
+        !self.0.is_empty()
+            && self
+                .0
+                .get(&object.unbind())
+                .is_some_and(|initializers| initializers.contains_key(&property_key.unbind()))
+    }
+
+    /// Returns true if the object has lazy globals that have not been
+    /// initialized yet.
+    #[inline]
+    pub(crate) fn has_pending(&self, object: Object) -> bool {
+        match object {
+            Object::Object(object) => !self.0.is_empty() && self.0.contains_key(&object.unbind()),
+            _ => false,
+        }
+    }
+}
+
+/// Breaks if the property of the object is a lazy global that has not been
+/// initialized yet.
+#[inline]
+pub(crate) fn try_initialize_lazy_global(
+    agent: &Agent,
+    object: OrdinaryObject,
+    property_key: PropertyKey,
+) -> TryResult<()> {
+    if agent.lazy_globals.is_pending(object, property_key) {
+        TryResult::Break(())
+    } else {
+        TryResult::Continue(())
+    }
+}
+
+/// Runs the initializer of the property of the object if it is a lazy global
+/// that has not been initialized yet, and replaces the placeholder value with
+/// the result.
+pub(crate) fn initialize_lazy_global(
+    agent: &mut Agent,
+    object: OrdinaryObject,
+    property_key: PropertyKey,
+    mut gc: GcScope,
+) {
+    let Some(initializer) = agent.lazy_globals.remove(object, property_key) else {
+        return;
+    };
+    let object = object.scope(agent, gc.nogc());
+    let property_key = property_key.scope(agent, gc.nogc());
+    let value = initializer(agent, gc.reborrow()).unbind();
+    let gc = gc.into_nogc();
+    let value = value.bind(gc);
+    let object = object.get(agent).bind(gc);
+    let property_key = property_key.get(agent).bind(gc);
+    let storage = object.property_storage();
+    // The initializer may have deleted or redefined the property.
+    let Some(mut descriptor) = storage.get(agent, property_key) else {
+        return;
+    };
+    if descriptor.is_data_descriptor() {
+        descriptor.value = Some(value);
+        storage.set(agent, property_key, descriptor);
+    }
+}
+
+impl HeapMarkAndSweep for LazyGlobals {
+    fn mark_values(&self, queues: &mut WorkQueues) {
+        for initializers in self.0.values() {
+            for property_key in initializers.keys() {
+                property_key.mark_values(queues);
+            }
+        }
+    }
+
+    fn sweep_values(&mut self, compactions: &CompactionLists) {
+        self.0 = self
+            .0
+            .drain()
+            .filter_map(|(object, initializers)| {
+                let object = object.sweep_weak_reference(compactions)?;
+                let initializers = initializers
+                    .into_iter()
+                    .map(|(mut property_key, initializer)| {
+                        property_key.sweep_values(compactions);
+                        (property_key, initializer)
+                    })
+                    .collect();
+                Some((object, initializers))
+            })
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::Cell, rc::Rc};
+
+    use crate::{
+        ecmascript::{
+            execution::{
+                DefaultHostHooks, LazyGlobalInitializer,
+                agent::{GcAgent, Options, RealmRoot},
+            },
+            scripts_and_modules::script::test::evaluate,
+            types::Value,
+        },
+        engine::context::Bindable,
+    };
+
+    fn assert_all_true(agent: &mut GcAgent, realm: &RealmRoot, sources: &[&'static str]) {
+        agent.run_in_realm(realm, |agent, mut gc| {
+            for source in sources {
+                let result = evaluate(agent, source, gc.reborrow()).unbind();
+                assert_eq!(result, Value::Boolean(true), "{source}");
+            }
+        });
+    }
+
+    /// Returns an initializer that counts its runs and produces a string.
+    fn counting_initializer(runs: &Rc<Cell<u32>>, value: &'static str) -> LazyGlobalInitializer {
+        let runs = runs.clone();
+        Box::new(move |agent, gc| {
+            runs.set(runs.get() + 1);
+            Value::from_static_str(agent, value, gc.into_nogc())
+        })
+    }
+
+    #[test]
+    fn initializer_runs_once() {
+        let mut agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+        let realm = agent.create_default_realm();
+        let runs = Rc::new(Cell::new(0));
+        agent.define_lazy_global(&realm, "api", counting_initializer(&runs, "api"));
+        assert_eq!(runs.get(), 0);
+        assert_all_true(
+            &mut agent,
+            &realm,
+            &[
+                "api === 'api'",
+                "let r = ''; for (let i = 0; i < 3; i++) r += api; r === 'apiapiapi'",
+                "globalThis.api === 'api' && typeof api === 'string'",
+            ],
+        );
+        assert_eq!(runs.get(), 1);
+        // The property is an ordinary data property afterwards.
+        agent.gc();
+        assert_all_true(
+            &mut agent,
+            &realm,
+            &[
+                "const d = Object.getOwnPropertyDescriptor(globalThis, 'api');
+                d.value === 'api' && d.writable && !d.enumerable && d.configurable",
+                "api = 1; api === 1",
+            ],
+        );
+        assert_eq!(runs.get(), 1);
+        agent.remove_realm(realm);
+    }
+
+    #[test]
+    fn enumeration_does_not_initialize() {
+        let mut agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+        let realm = agent.create_default_realm();
+        let runs = Rc::new(Cell::new(0));
+        agent.define_lazy_global(&realm, "api", counting_initializer(&runs, "api"));
+        assert_all_true(
+            &mut agent,
+            &realm,
+            &[
+                "Object.getOwnPropertyNames(globalThis).includes('api')",
+                "'api' in globalThis && Reflect.has(globalThis, 'api')",
+                "!Object.keys(globalThis).includes('api')",
+                "let found = false; for (const key in globalThis) found ||= key === 'api'; !found",
+            ],
+        );
+        assert_eq!(runs.get(), 0);
+        // Asking for the descriptor needs the value.
+        assert_all_true(
+            &mut agent,
+            &realm,
+            &["Object.getOwnPropertyDescriptor(globalThis, 'api').value === 'api'"],
+        );
+        assert_eq!(runs.get(), 1);
+        agent.remove_realm(realm);
+    }
+
+    #[test]
+    fn untouched_lazy_global_never_initializes() {
+        let mut agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+        let realm = agent.create_default_realm();
+        let used_runs = Rc::new(Cell::new(0));
+        let unused_runs = Rc::new(Cell::new(0));
+        let deleted_runs = Rc::new(Cell::new(0));
+        agent.define_lazy_global(&realm, "used", counting_initializer(&used_runs, "used"));
+        agent.define_lazy_global(
+            &realm,
+            "unused",
+            counting_initializer(&unused_runs, "unused"),
+        );
+        agent.define_lazy_global(
+            &realm,
+            "deleted",
+            counting_initializer(&deleted_runs, "deleted"),
+        );
+        assert_all_true(
+            &mut agent,
+            &realm,
+            &[
+                "used === 'used' && 'unused' in globalThis",
+                "delete globalThis.deleted",
+                "!('deleted' in globalThis) && typeof deleted === 'undefined'",
+            ],
+        );
+        agent.gc();
+        assert_all_true(&mut agent, &realm, &["var deleted = 1; deleted === 1"]);
+        assert_eq!(used_runs.get(), 1);
+        assert_eq!(unused_runs.get(), 0);
+        assert_eq!(deleted_runs.get(), 0);
+        agent.remove_realm(realm);
+        agent.gc();
+        assert_eq!(unused_runs.get(), 0);
+    }
+
+    #[test]
+    fn let_declaration_shadows_lazy_global() {
+        let mut agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+        let realm = agent.create_default_realm();
+        let runs = Rc::new(Cell::new(0));
+        agent.define_lazy_global(&realm, "api", counting_initializer(&runs, "api"));
+        assert_all_true(
+            &mut agent,
+            &realm,
+            &[
+                "let api = 'script'; api === 'script'",
+                "api === 'script' && globalThis.api === 'api'",
+            ],
+        );
+        assert_eq!(runs.get(), 1);
+        agent.remove_realm(realm);
+    }
+}
@@ -0,0 +1,400 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Tracking of what an Agent is doing, so that embedders calling into it from
+//! a place where it cannot safely be used get an error instead of a corrupted
+//! execution context stack.
+//!
+//! The public entry points of an Agent are [guarded](AgentOperation): each
+//! checks the [`ExecutionState`] of the Agent against the matrix documented
+//! on [`ExecutionState::allows`] before doing anything. If a panic unwinds
+//! out of a guarded entry point, the Agent is left with a half-unwound
+//! execution context stack and is marked poisoned: all guarded entry points
+//! then fail with [`ReentrancyError::Poisoned`].
+
+use std::{cell::Cell, rc::Rc};
+
+/// What an Agent is currently doing.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionState {
+    /// No guarded operation is in progress.
+    #[default]
+    Idle,
+    /// ECMAScript code, a Job, or a function call is running.
+    Executing,
+    /// A host hook is running, eg.
+    /// [`HostHooks::report_uncaught_exception`](super::agent::HostHooks::report_uncaught_exception).
+    InHostHook,
+    /// [`HostHooks::on_garbage_collection`](super::agent::HostHooks::on_garbage_collection)
+    /// is running, possibly in the middle of executing a bytecode instruction.
+    InGcCallback,
+}
+
+/// A guarded public entry point of an Agent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgentOperation {
+    /// Evaluating a Script, eg. with
+    /// [`script_evaluation`](crate::ecmascript::scripts_and_modules::script::script_evaluation).
+    Evaluate,
+    /// Calling a function with
+    /// [`Function::call`](crate::ecmascript::types::Function::call).
+    Call,
+    /// Running a [`Job`](super::agent::Job).
+    RunJobs,
+    /// Performing garbage collection with [`Agent::gc`](super::Agent::gc) or
+    /// one of its variants.
+    Gc,
+}
+
+impl ExecutionState {
+    /// Returns true if `operation` may be started while the Agent is in this
+    /// state.
+    ///
+    /// | State            | Evaluate | Call | RunJobs | Gc  |
+    /// |------------------|----------|------|---------|-----|
+    /// | `Idle`           | yes      | yes  | yes     | yes |
+    /// | `Executing`      | yes      | yes  | no      | yes |
+    /// | `InHostHook`     | yes      | yes  | no      | yes |
+    /// | `InGcCallback`   | no       | no   | no      | no  |
+    ///
+    /// A built-in function may evaluate a script or call a function, and a
+    /// host hook may do the same. Jobs only run when no other ECMAScript code
+    /// is running. Garbage collection callbacks are invoked in the middle of
+    /// executing an instruction, so they must not touch the execution context
+    /// stack or start another collection.
+    pub const fn allows(self, operation: AgentOperation) -> bool {
+        match self {
+            ExecutionState::Idle => true,
+            ExecutionState::Executing | ExecutionState::InHostHook => {
+                !matches!(operation, AgentOperation::RunJobs)
+            }
+            ExecutionState::InGcCallback => false,
+        }
+    }
+}
+
+/// Error returned by the guarded entry points of an Agent when they are
+/// called at a time when the Agent cannot safely perform the operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReentrancyError {
+    /// A panic unwound out of the Agent while it was executing, leaving its
+    /// state inconsistent.
+    Poisoned,
+    /// The operation is not allowed in the current state of the Agent; see
+    /// [`ExecutionState::allows`].
+    IllegalNesting {
+        state: ExecutionState,
+        operation: AgentOperation,
+    },
+}
+
+impl core::fmt::Display for ReentrancyError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ReentrancyError::Poisoned => {
+                f.write_str("Agent is poisoned: a panic unwound out of it while it was executing")
+            }
+            ReentrancyError::IllegalNesting { state, operation } => {
+                write!(
+                    f,
+                    "{operation:?} is not allowed while the Agent is {state:?}"
+                )
+            }
+        }
+    }
+}
+
+impl core::error::Error for ReentrancyError {}
+
+/// Error returned by [`Agent::try_with_gc`](super::Agent::try_with_gc) and
+/// [`Agent::try_enter_gc`](super::Agent::try_enter_gc) when a GcScope root
+/// already exists: only one may exist at a time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NestedGcRootError;
+
+impl core::fmt::Display for NestedGcRootError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("a GcScope root is already active; use the GcScope that was passed in instead")
+    }
+}
+
+impl core::error::Error for NestedGcRootError {}
+
+/// Execution state of an Agent, shared with the [`ExecutionGuard`]s of the
+/// operations in progress.
+#[derive(Debug, Default)]
+pub(crate) struct ReentrancyState {
+    state: Cell<ExecutionState>,
+    poisoned: Cell<bool>,
+}
+
+impl ReentrancyState {
+    pub(crate) fn state(&self) -> ExecutionState {
+        self.state.get()
+    }
+
+    pub(crate) fn is_poisoned(&self) -> bool {
+        self.poisoned.get()
+    }
+
+    pub(crate) fn check(&self, operation: AgentOperation) -> Result<(), ReentrancyError> {
+        if self.poisoned.get() {
+            return Err(ReentrancyError::Poisoned);
+        }
+        let state = self.state.get();
+        if !state.allows(operation) {
+            return Err(ReentrancyError::IllegalNesting { state, operation });
+        }
+        Ok(())
+    }
+
+    /// Set the state of the Agent until the returned guard is dropped.
+    pub(crate) fn enter(self: &Rc<Self>, state: ExecutionState) -> ExecutionGuard {
+        let previous = self.state.replace(state);
+        ExecutionGuard {
+            shared: self.clone(),
+            previous,
+        }
+    }
+}
+
+/// Restores the previous execution state of an Agent when dropped, or marks
+/// the Agent poisoned if dropped during a panic.
+#[must_use]
+pub(crate) struct ExecutionGuard {
+    shared: Rc<ReentrancyState>,
+    previous: ExecutionState,
+}
+
+impl Drop for ExecutionGuard {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            self.shared.poisoned.set(true);
+        }
+        self.shared.state.set(self.previous);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        ecmascript::{
+            builtins::{ArgumentsList, Behaviour},
+            execution::{
+                Agent, AgentOperation, DefaultHostHooks, ExecutionState, JsResult, ReentrancyError,
+                agent::{ExceptionOrigin, GcAgent, HostHooks, Job, Options},
+            },
+            scripts_and_modules::script::{parse_script, test::evaluate, try_script_evaluation},
+            types::{String, Value},
+        },
+        engine::context::{Bindable, GcScope},
+    };
+    use std::{
+        panic::{AssertUnwindSafe, catch_unwind},
+        sync::Mutex,
+    };
+
+    /// Host hooks that try to re-enter the Agent from the garbage collection
+    /// hook and record the outcome.
+    #[derive(Debug)]
+    struct ReenteringGcHooks {
+        outcomes: Mutex<Vec<(ExecutionState, ReentrancyError, ReentrancyError)>>,
+    }
+
+    impl HostHooks for ReenteringGcHooks {
+        fn enqueue_promise_job(&self, _job: Job) {}
+
+        fn on_garbage_collection(&self, agent: &mut Agent, mut gc: GcScope) {
+            let realm = agent.current_realm(gc.nogc());
+            let source_text =
+                String::from_static_str(agent, "globalThis.evaluated = true", gc.nogc());
+            let script = parse_script(agent, source_text, realm, false, None, gc.nogc()).unwrap();
+            let evaluate = try_script_evaluation(agent, script.unbind(), gc.reborrow())
+                .map(|_| ())
+                .unwrap_err();
+            let collect = agent.try_gc(gc).unwrap_err();
+            self.outcomes
+                .lock()
+                .unwrap()
+                .push((agent.execution_state(), evaluate, collect));
+        }
+    }
+
+    #[test]
+    fn evaluation_from_gc_hook_is_rejected() {
+        static HOOKS: ReenteringGcHooks = ReenteringGcHooks {
+            outcomes: Mutex::new(Vec::new()),
+        };
+        let mut agent = GcAgent::new(
+            Options {
+                gc_stress: true,
+                ..Default::default()
+            },
+            &HOOKS,
+        );
+        let realm = agent.create_default_realm();
+        agent.run_in_realm(&realm, |agent, mut gc| {
+            // A collection requested by the host between evaluations.
+            agent.gc(gc.reborrow());
+            // Collections started automatically in the middle of an evaluation.
+            assert_eq!(
+                evaluate(
+                    agent,
+                    "globalThis.evaluated = false;
+                    const objects = [{}, {}, {}];
+                    evaluated === false && objects.length === 3",
+                    gc.reborrow()
+                ),
+                Value::Boolean(true)
+            );
+            assert_eq!(agent.execution_state(), ExecutionState::Idle);
+            assert!(!agent.is_poisoned());
+        });
+        agent.remove_realm(realm);
+
+        let outcomes = HOOKS.outcomes.lock().unwrap();
+        assert!(outcomes.len() >= 2);
+        for &(state, evaluate, collect) in outcomes.iter() {
+            assert_eq!(state, ExecutionState::InGcCallback);
+            assert_eq!(
+                evaluate,
+                ReentrancyError::IllegalNesting {
+                    state: ExecutionState::InGcCallback,
+                    operation: AgentOperation::Evaluate,
+                }
+            );
+            assert_eq!(
+                collect,
+                ReentrancyError::IllegalNesting {
+                    state: ExecutionState::InGcCallback,
+                    operation: AgentOperation::Gc,
+                }
+            );
+        }
+    }
+
+    fn explode<'gc>(
+        _agent: &mut Agent,
+        _this: Value,
+        _args: ArgumentsList,
+        _gc: GcScope<'gc, '_>,
+    ) -> JsResult<'gc, Value<'gc>> {
+        panic!("builtin exploded");
+    }
+
+    #[test]
+    fn panic_through_vm_poisons_agent() {
+        let mut agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+        let realm = agent.create_default_realm();
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            agent.run_in_realm(&realm, |agent, mut gc| {
+                let global = agent.current_global_object(gc.nogc());
+                agent
+                    .install_method(
+                        global.unbind(),
+                        "explode",
+                        0,
+                        Behaviour::Regular(explode),
+                        gc.reborrow(),
+                    )
+                    .unwrap();
+                evaluate(agent, "[1, 2, 3].map(() => explode())", gc).unbind()
+            })
+        }));
+        assert!(result.is_err());
+        assert!(agent.is_poisoned());
+        assert_eq!(agent.try_gc(), Err(ReentrancyError::Poisoned));
+        let rerun = catch_unwind(AssertUnwindSafe(|| agent.run_in_realm(&realm, |_, _| ())));
+        let message = rerun.unwrap_err();
+        assert!(
+            message
+                .downcast_ref::<std::string::String>()
+                .unwrap()
+                .contains("poisoned")
+        );
+    }
+
+    fn evaluate_argument<'gc>(
+        agent: &mut Agent,
+        _this: Value,
+        args: ArgumentsList,
+        gc: GcScope<'gc, '_>,
+    ) -> JsResult<'gc, Value<'gc>> {
+        assert_eq!(agent.execution_state(), ExecutionState::Executing);
+        assert_eq!(
+            agent.check_reentrancy(AgentOperation::RunJobs),
+            Err(ReentrancyError::IllegalNesting {
+                state: ExecutionState::Executing,
+                operation: AgentOperation::RunJobs,
+            })
+        );
+        let source_text = String::try_from(args.get(0)).unwrap();
+        agent.run_script(source_text.unbind(), gc)
+    }
+
+    /// Host hooks that evaluate a script when an uncaught exception is reported.
+    #[derive(Debug)]
+    struct EvaluatingReportHooks;
+
+    impl HostHooks for EvaluatingReportHooks {
+        fn enqueue_promise_job(&self, _job: Job) {}
+
+        fn report_uncaught_exception(
+            &self,
+            agent: &mut Agent,
+            _error: Value,
+            _origin: ExceptionOrigin,
+            gc: GcScope,
+        ) {
+            assert_eq!(agent.execution_state(), ExecutionState::InHostHook);
+            evaluate(
+                agent,
+                "globalThis.reported = (globalThis.reported ?? 0) + 1",
+                gc,
+            );
+        }
+    }
+
+    #[test]
+    fn legal_nesting_is_allowed() {
+        let mut agent = GcAgent::new(Options::default(), &EvaluatingReportHooks);
+        let realm = agent.create_default_realm();
+        agent.run_in_realm(&realm, |agent, mut gc| {
+            let global = agent.current_global_object(gc.nogc());
+            agent
+                .install_method(
+                    global.unbind(),
+                    "evaluate",
+                    1,
+                    Behaviour::Regular(evaluate_argument),
+                    gc.reborrow(),
+                )
+                .unwrap();
+            assert_eq!(agent.execution_state(), ExecutionState::Idle);
+            assert_eq!(
+                evaluate(
+                    agent,
+                    "evaluate('let nested = evaluate(\"21\") * 2; nested') === 42",
+                    gc.reborrow()
+                ),
+                Value::Boolean(true)
+            );
+
+            let source_text = String::from_static_str(agent, "throw 1", gc.nogc());
+            assert!(
+                agent
+                    .run_script(source_text.unbind(), gc.reborrow())
+                    .is_err()
+            );
+            assert_eq!(
+                evaluate(agent, "reported === 1", gc.reborrow()),
+                Value::Boolean(true)
+            );
+            assert_eq!(agent.execution_state(), ExecutionState::Idle);
+            assert!(agent.try_gc(gc).is_ok());
+        });
+        assert!(!agent.is_poisoned());
+        agent.remove_realm(realm);
+    }
+}
@@ -0,0 +1,355 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Reporting of operations that fall off an engine fast path to
+//! [`HostHooks::on_slow_path`] if [`Options::report_slow_paths`] is set.
+//!
+//! [`HostHooks::on_slow_path`]: super::agent::HostHooks::on_slow_path
+//! [`Options::report_slow_paths`]: super::agent::Options::report_slow_paths
+
+use ahash::AHashMap;
+use oxc_span::Span;
+
+use super::{Agent, agent::SourceLocation};
+use crate::{
+    ecmascript::scripts_and_modules::source_code::SourceCode,
+    heap::{CompactionLists, HeapMarkAndSweep, WorkQueues, sweep_side_table_values},
+};
+
+/// Reason that an operation took a slow path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SlowPathReason {
+    /// An Array element was written past the end of the Array, its length was
+    /// increased or one of its elements was deleted, leaving a hole in its
+    /// elements.
+    SparseArrayTransition,
+    /// An accessor property was defined on an Array element.
+    AccessorElement,
+    /// An Array method took its generic path because the Array has holes or
+    /// accessor elements.
+    ElementsNotDense,
+}
+
+/// Operation that took a slow path, reported to
+/// [`HostHooks::on_slow_path`](super::agent::HostHooks::on_slow_path).
+#[derive(Debug, Clone, Copy)]
+pub struct SlowPathEvent<'a> {
+    pub reason: SlowPathReason,
+    /// Location of the statement that performed the operation. This is None
+    /// if the operation was not performed by ECMAScript code, eg. when a host
+    /// calls a built-in function directly.
+    pub location: Option<SourceLocation<'a>>,
+    /// Number of times that the operation has taken the slow path at this
+    /// location, including this time.
+    pub count: u32,
+}
+
+/// Slow path events waiting for the location of the instruction that caused
+/// them, and the number of events seen at each location.
+#[derive(Debug, Default)]
+pub(crate) struct SlowPathLog {
+    /// Reasons of events paired with the depth of the Vm stack when they
+    /// happened.
+    pending: Vec<(SlowPathReason, usize)>,
+    /// Event counts by source code and span. The source codes are held
+    /// weakly.
+    counts: AHashMap<SourceCode<'static>, AHashMap<(SlowPathReason, Span), u32>>,
+    /// Counts of events without a location.
+    unlocated_counts: AHashMap<SlowPathReason, u32>,
+}
+
+impl Agent {
+    /// Report that an operation took a slow path for the given reason.
+    ///
+    /// This does nothing unless [`Options::report_slow_paths`] is set. Events
+    /// that happen while a Vm is running are reported once the current
+    /// instruction finishes, with its location.
+    ///
+    /// [`Options::report_slow_paths`]: super::agent::Options::report_slow_paths
+    #[inline]
+    pub(crate) fn report_slow_path(&mut self, reason: SlowPathReason) {
+        if self.options.report_slow_paths.is_some() {
+            self.record_slow_path(reason);
+        }
+    }
+
+    #[cold]
+    fn record_slow_path(&mut self, reason: SlowPathReason) {
+        let depth = self.vm_stack.len();
+        if depth == 0 {
+            self.dispatch_slow_path(reason, None);
+        } else {
+            self.slow_paths.pending.push((reason, depth));
+        }
+    }
+
+    /// Returns true if events are waiting for the location of an instruction.
+    #[inline]
+    pub(crate) fn has_pending_slow_paths(&self) -> bool {
+        !self.slow_paths.pending.is_empty()
+    }
+
+    /// Report the pending events of the Vm at the given depth of the Vm stack
+    /// and any Vms above it, attributing them to the given span of the
+    /// running source code.
+    ///
+    /// Events of Vms below the given depth stay pending.
+    #[cold]
+    pub(crate) fn flush_slow_paths(&mut self, depth: usize, span: Option<Span>) {
+        let split = self
+            .slow_paths
+            .pending
+            .iter()
+            .position(|&(_, event_depth)| event_depth >= depth)
+            .unwrap_or(self.slow_paths.pending.len());
+        if split == self.slow_paths.pending.len() {
+            return;
+        }
+        let source_code = self.running_source_code();
+        let location = source_code
+            .zip(span)
+            .map(|(source_code, span)| SourceLocation { source_code, span });
+        let events = self.slow_paths.pending.split_off(split);
+        for (reason, _) in events {
+            self.dispatch_slow_path(reason, location);
+        }
+    }
+
+    fn dispatch_slow_path(
+        &mut self,
+        reason: SlowPathReason,
+        location: Option<SourceLocation<'static>>,
+    ) {
+        let Some(interval) = self.options.report_slow_paths else {
+            return;
+        };
+        let count = match location {
+            Some(SourceLocation { source_code, span }) => self
+                .slow_paths
+                .counts
+                .entry(source_code)
+                .or_default()
+                .entry((reason, span))
+                .or_default(),
+            None => self.slow_paths.unlocated_counts.entry(reason).or_default(),
+        };
+        *count = count.saturating_add(1);
+        let count = *count;
+        // Report the first event and every nth one after it.
+        if (count - 1) % interval.get() != 0 {
+            return;
+        }
+        self.host_hooks.on_slow_path(
+            self,
+            SlowPathEvent {
+                reason,
+                location,
+                count,
+            },
+        );
+    }
+}
+
+impl HeapMarkAndSweep for SlowPathLog {
+    fn mark_values(&self, _queues: &mut WorkQueues) {}
+
+    fn sweep_values(&mut self, compactions: &CompactionLists) {
+        sweep_side_table_values(&mut self.counts, compactions);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::RefCell, num::NonZeroU32};
+
+    use crate::ecmascript::{
+        execution::{
+            Agent, SlowPathEvent, SlowPathReason,
+            agent::{HostHooks, Job, Options},
+            initialize_default_realm,
+        },
+        scripts_and_modules::script::test::evaluate,
+    };
+
+    #[derive(Debug, PartialEq)]
+    struct Report {
+        reason: SlowPathReason,
+        /// Source text of the statement that the event happened in.
+        statement: Option<std::string::String>,
+        count: u32,
+    }
+
+    #[derive(Debug, Default)]
+    struct CapturingHostHooks {
+        reports: RefCell<Vec<Report>>,
+    }
+
+    impl HostHooks for CapturingHostHooks {
+        fn enqueue_promise_job(&self, _job: Job) {}
+
+        fn on_slow_path(&self, agent: &Agent, event: SlowPathEvent) {
+            let statement = event
+                .location
+                .map(|location| location.source_code.slice(agent, location.span).to_owned());
+            self.reports.borrow_mut().push(Report {
+                reason: event.reason,
+                statement,
+                count: event.count,
+            });
+        }
+    }
+
+    fn run(interval: u32, source: &'static str) -> Vec<Report> {
+        let host_hooks: &'static CapturingHostHooks = Box::leak(Box::default());
+        let mut agent = Agent::new(
+            Options {
+                report_slow_paths: NonZeroU32::new(interval),
+                ..Default::default()
+            },
+            host_hooks,
+        );
+        agent.with_gc(|agent, mut gc| {
+            initialize_default_realm(agent, gc.reborrow());
+            evaluate(agent, source, gc);
+        });
+        host_hooks.reports.take()
+    }
+
+    #[test]
+    fn sparse_transition_is_reported_once() {
+        let reports = run(
+            1,
+            "const array = [1, 2, 3];
+            array[1] = 4;
+            array[10] = 5;
+            array[20] = 6;
+            delete array[0];",
+        );
+        assert_eq!(
+            reports,
+            [Report {
+                reason: SlowPathReason::SparseArrayTransition,
+                statement: Some("array[10] = 5;".to_owned()),
+                count: 1,
+            }]
+        );
+
+        let reports = run(
+            1,
+            "const array = [1, 2, 3];
+            array.length = 4;",
+        );
+        assert_eq!(
+            reports,
+            [Report {
+                reason: SlowPathReason::SparseArrayTransition,
+                statement: Some("array.length = 4;".to_owned()),
+                count: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn accessor_element_is_reported() {
+        let reports = run(
+            1,
+            "const array = [1, 2, 3];
+            Object.defineProperty(array, 0, { get() { return 0; } });",
+        );
+        assert_eq!(
+            reports,
+            [Report {
+                reason: SlowPathReason::AccessorElement,
+                statement: Some(
+                    "Object.defineProperty(array, 0, { get() { return 0; } });".to_owned()
+                ),
+                count: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn events_at_a_hot_location_are_throttled() {
+        let reports = run(
+            3,
+            "const array = [1, , 3];
+            for (let i = 0; i < 7; i++) {
+                array.reverse();
+            }",
+        );
+        // The array literal with a hole is reported as well.
+        assert_eq!(
+            reports[0],
+            Report {
+                reason: SlowPathReason::SparseArrayTransition,
+                statement: Some("const array = [1, , 3];".to_owned()),
+                count: 1,
+            }
+        );
+        let statement = Some("array.reverse();".to_owned());
+        assert_eq!(
+            reports[1..],
+            [1, 4, 7].map(|count| Report {
+                reason: SlowPathReason::ElementsNotDense,
+                statement: statement.clone(),
+                count,
+            })
+        );
+    }
+
+    #[test]
+    fn events_are_attributed_to_the_calling_function() {
+        let reports = run(
+            1,
+            "function f(array) {
+                return array.slice(0, 1);
+            }
+            const arrays = [[1, 2], [1, 3]];
+            delete arrays[1][1];
+            arrays.map(f);",
+        );
+        assert_eq!(
+            reports,
+            [
+                Report {
+                    reason: SlowPathReason::SparseArrayTransition,
+                    statement: Some("delete arrays[1][1];".to_owned()),
+                    count: 1,
+                },
+                Report {
+                    reason: SlowPathReason::ElementsNotDense,
+                    statement: Some("return array.slice(0, 1);".to_owned()),
+                    count: 1,
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn fast_path_script_reports_nothing() {
+        let reports = run(
+            1,
+            "const array = [1, 2, 3];
+            array.push(4);
+            array[4] = 5;
+            array.reverse();
+            array.shift();
+            array.slice(1).fill(0);
+            array.toReversed().with(0, 1);",
+        );
+        assert_eq!(reports, []);
+    }
+
+    #[test]
+    fn nothing_is_reported_by_default() {
+        let reports = run(
+            0,
+            "const array = [1, 2, 3];
+            array[10] = 4;
+            array.reverse();",
+        );
+        assert_eq!(reports, []);
+    }
+}
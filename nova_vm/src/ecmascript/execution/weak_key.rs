@@ -6,18 +6,19 @@
 
 #[cfg(feature = "date")]
 use crate::ecmascript::builtins::date::Date;
-#[cfg(feature = "regexp")]
-use crate::ecmascript::builtins::regexp::RegExp;
 #[cfg(feature = "shared-array-buffer")]
 use crate::ecmascript::builtins::shared_array_buffer::SharedArrayBuffer;
+#[cfg(feature = "regexp")]
+use crate::ecmascript::builtins::{
+    regexp::RegExp,
+    text_processing::regexp_objects::regexp_string_iterator_objects::RegExpStringIterator,
+};
 #[cfg(feature = "weak-refs")]
 use crate::ecmascript::builtins::{weak_map::WeakMap, weak_ref::WeakRef, weak_set::WeakSet};
 #[cfg(feature = "date")]
 use crate::ecmascript::types::DATE_DISCRIMINANT;
 #[cfg(feature = "proposal-float16array")]
 use crate::ecmascript::types::FLOAT_16_ARRAY_DISCRIMINANT;
-#[cfg(feature = "regexp")]
-use crate::ecmascript::types::REGEXP_DISCRIMINANT;
 #[cfg(feature = "shared-array-buffer")]
 use crate::ecmascript::types::SHARED_ARRAY_BUFFER_DISCRIMINANT;
 #[cfg(feature = "array-buffer")]
@@ -28,6 +29,8 @@ use crate::ecmascript::types::{
     UINT_8_ARRAY_DISCRIMINANT, UINT_8_CLAMPED_ARRAY_DISCRIMINANT, UINT_16_ARRAY_DISCRIMINANT,
     UINT_32_ARRAY_DISCRIMINANT,
 };
+#[cfg(feature = "regexp")]
+use crate::ecmascript::types::{REGEXP_DISCRIMINANT, REGEXP_STRING_ITERATOR_DISCRIMINANT};
 #[cfg(feature = "weak-refs")]
 use crate::ecmascript::types::{
     WEAK_MAP_DISCRIMINANT, WEAK_REF_DISCRIMINANT, WEAK_SET_DISCRIMINANT,
@@ -159,6 +162,8 @@ pub(crate) enum WeakKey<'a> {
     ArrayIterator(ArrayIterator<'a>) = ARRAY_ITERATOR_DISCRIMINANT,
     #[cfg(feature = "set")]
     SetIterator(SetIterator<'a>) = SET_ITERATOR_DISCRIMINANT,
+    #[cfg(feature = "regexp")]
+    RegExpStringIterator(RegExpStringIterator<'a>) = REGEXP_STRING_ITERATOR_DISCRIMINANT,
     MapIterator(MapIterator<'a>) = MAP_ITERATOR_DISCRIMINANT,
     StringIterator(StringIterator<'a>) = STRING_ITERATOR_DISCRIMINANT,
     Generator(Generator<'a>) = GENERATOR_DISCRIMINANT,
@@ -241,6 +246,8 @@ impl<'a> From<WeakKey<'a>> for Value<'a> {
             WeakKey::ArrayIterator(d) => Self::ArrayIterator(d),
             #[cfg(feature = "set")]
             WeakKey::SetIterator(d) => Self::SetIterator(d),
+            #[cfg(feature = "regexp")]
+            WeakKey::RegExpStringIterator(d) => Self::RegExpStringIterator(d),
             WeakKey::MapIterator(d) => Self::MapIterator(d),
             WeakKey::StringIterator(d) => Self::StringIterator(d),
             WeakKey::Generator(d) => Self::Generator(d),
@@ -317,6 +324,8 @@ impl<'a> From<Object<'a>> for WeakKey<'a> {
             Object::ArrayIterator(d) => Self::ArrayIterator(d),
             #[cfg(feature = "set")]
             Object::SetIterator(d) => Self::SetIterator(d),
+            #[cfg(feature = "regexp")]
+            Object::RegExpStringIterator(d) => Self::RegExpStringIterator(d),
             Object::MapIterator(d) => Self::MapIterator(d),
             Object::StringIterator(d) => Self::StringIterator(d),
             Object::Generator(d) => Self::Generator(d),
@@ -397,6 +406,8 @@ impl<'a> TryFrom<WeakKey<'a>> for Object<'a> {
             WeakKey::ArrayIterator(d) => Ok(Self::ArrayIterator(d)),
             #[cfg(feature = "set")]
             WeakKey::SetIterator(d) => Ok(Self::SetIterator(d)),
+            #[cfg(feature = "regexp")]
+            WeakKey::RegExpStringIterator(d) => Ok(Self::RegExpStringIterator(d)),
             WeakKey::MapIterator(d) => Ok(Self::MapIterator(d)),
             WeakKey::StringIterator(d) => Ok(Self::StringIterator(d)),
             WeakKey::Generator(d) => Ok(Self::Generator(d)),
@@ -521,6 +532,8 @@ impl HeapMarkAndSweep for WeakKey<'static> {
             Self::ArrayIterator(d) => d.mark_values(queues),
             #[cfg(feature = "set")]
             Self::SetIterator(d) => d.mark_values(queues),
+            #[cfg(feature = "regexp")]
+            Self::RegExpStringIterator(d) => d.mark_values(queues),
             Self::MapIterator(d) => d.mark_values(queues),
             Self::StringIterator(d) => d.mark_values(queues),
             Self::Generator(d) => d.mark_values(queues),
@@ -595,6 +608,8 @@ impl HeapMarkAndSweep for WeakKey<'static> {
             Self::ArrayIterator(d) => d.sweep_values(compactions),
             #[cfg(feature = "set")]
             Self::SetIterator(d) => d.sweep_values(compactions),
+            #[cfg(feature = "regexp")]
+            Self::RegExpStringIterator(d) => d.sweep_values(compactions),
             Self::MapIterator(d) => d.sweep_values(compactions),
             Self::StringIterator(d) => d.sweep_values(compactions),
             Self::Generator(d) => d.sweep_values(compactions),
@@ -712,6 +727,10 @@ impl HeapSweepWeakReference for WeakKey<'static> {
             Self::SetIterator(data) => data
                 .sweep_weak_reference(compactions)
                 .map(Self::SetIterator),
+            #[cfg(feature = "regexp")]
+            Self::RegExpStringIterator(data) => data
+                .sweep_weak_reference(compactions)
+                .map(Self::RegExpStringIterator),
             Self::MapIterator(data) => data
                 .sweep_weak_reference(compactions)
                 .map(Self::MapIterator),
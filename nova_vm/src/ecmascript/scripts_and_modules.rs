@@ -12,7 +12,11 @@ use self::script::Script;
 use super::builtins::module::Module;
 
 pub mod module;
+#[cfg(feature = "deferred-module-loading")]
+pub mod module_loading;
+pub mod module_map;
 pub mod script;
+pub mod script_analysis;
 pub mod source_code;
 
 #[derive(Clone, Copy)]
@@ -1 +1,838 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+//! ### [16.2 Modules](https://tc39.es/ecma262/#sec-modules)
+//!
+//! Nova does not yet evaluate modules. Source Text Module Records can be
+//! parsed, have their requested modules provided by the host, and be linked.
+//! Linking resolves all imports and re-exports and computes the module's
+//! export names, which hosts can introspect without evaluating the module.
+
+use oxc_diagnostics::OxcDiagnostic;
+use oxc_span::SourceType;
+use oxc_syntax::module_record::{
+    ExportExportName, ExportImportName, ExportLocalName, ImportImportName,
+};
+
+use crate::{
+    ecmascript::{
+        builtins::module::{
+            Module,
+            data::{
+                ImportEntry, IndirectExportEntry, LocalExportEntry, ModuleHeapData, ModuleRecord,
+                ModuleStatus, ResolveExportResult, ResolvedBinding, ResolvedBindingName,
+            },
+        },
+        execution::{Agent, JsResult, Realm, agent::ExceptionType},
+        scripts_and_modules::source_code::SourceCode,
+        types::{BUILTIN_STRING_MEMORY, String},
+    },
+    engine::context::{Bindable, NoGcScope},
+};
+
+pub type ModuleOrErrors<'a> = Result<Module<'a>, Vec<OxcDiagnostic>>;
+
+/// The result of resolving an export name of a linked module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportResolution<'a> {
+    /// The export resolves to a binding in `module`. If `binding_name` is
+    /// None, the export is the module namespace object of `module`.
+    Resolved {
+        module: Module<'a>,
+        binding_name: Option<String<'a>>,
+    },
+    /// The export name is provided by multiple `export * from` declarations
+    /// which resolve to different bindings.
+    Ambiguous,
+    /// The module does not export the name.
+    NotFound,
+}
+
+/// ### [16.2.1.7.1 ParseModule ( sourceText, realm, hostDefined )](https://tc39.es/ecma262/#sec-parsemodule)
+///
+/// The abstract operation ParseModule takes arguments sourceText (ECMAScript
+/// source text), realm (a Realm Record), and hostDefined (anything) and
+/// returns a Source Text Module Record or a non-empty List of SyntaxError
+/// objects. It creates a Source Text Module Record based upon the result of
+/// parsing sourceText as a Module.
+pub fn parse_module<'a>(
+    agent: &mut Agent,
+    source_text: String,
+    realm: Realm,
+    gc: NoGcScope<'a, '_>,
+) -> ModuleOrErrors<'a> {
+    let mut source_type = SourceType::default().with_module(true);
+    if cfg!(feature = "typescript") {
+        source_type = source_type.with_typescript(true);
+    }
+
+    // 1. Let body be ParseText(sourceText, Module).
+    // SAFETY: The parsed Program and module record are dropped before the
+    // SourceCode can be garbage collected.
+    // TODO: Keep the Program as [[ECMAScriptCode]] once modules can be
+    // evaluated.
+    let parse_result =
+        unsafe { SourceCode::parse_module_source(agent, source_text, source_type, false, gc) };
+    let (_, module_record, _) = match parse_result {
+        Ok(result) => result,
+        // 2. If body is a List of errors, return body.
+        Err(errors) => {
+            return Err(errors);
+        }
+    };
+
+    // 3. Let requestedModules be the ModuleRequests of body.
+    let mut requested_modules = module_record
+        .requested_modules
+        .iter()
+        .filter(|(_, requests)| requests.iter().any(|request| !request.is_type))
+        .map(|(specifier, requests)| {
+            let first = requests.iter().map(|request| request.span.start).min();
+            (first, *specifier)
+        })
+        .collect::<Vec<_>>();
+    requested_modules.sort_unstable_by_key(|(first, _)| *first);
+    let requested_modules = requested_modules
+        .into_iter()
+        .map(|(_, specifier)| String::from_str(agent, &specifier, gc))
+        .collect();
+
+    // 4. Let importEntries be the ImportEntries of body.
+    let import_entries = module_record
+        .import_entries
+        .iter()
+        .filter(|entry| !entry.is_type)
+        .map(|entry| ImportEntry {
+            module_request: String::from_str(agent, &entry.module_request.name, gc),
+            import_name: match &entry.import_name {
+                ImportImportName::Name(name) => Some(String::from_str(agent, &name.name, gc)),
+                ImportImportName::Default(_) => Some(String::from_static_str(agent, "default", gc)),
+                ImportImportName::NamespaceObject => None,
+            },
+            local_name: String::from_str(agent, &entry.local_name.name, gc),
+        })
+        .collect();
+
+    // 5. Let importedBoundNames be ImportedLocalNames(importEntries).
+    // 6. Let indirectExportEntries be a new empty List.
+    // 7. Let localExportEntries be a new empty List.
+    // 8. Let starExportEntries be a new empty List.
+    // 9. Let exportEntries be the ExportEntries of body.
+    // 10. For each ExportEntry Record ee of exportEntries, do
+    // NOTE: The parser has already sorted the export entries into the three
+    // lists.
+    let export_name = |agent: &mut Agent, name: &ExportExportName| match name {
+        ExportExportName::Name(name) => String::from_str(agent, &name.name, gc),
+        ExportExportName::Default(_) => String::from_static_str(agent, "default", gc),
+        ExportExportName::Null => unreachable!(),
+    };
+    let local_export_entries = module_record
+        .local_export_entries
+        .iter()
+        .filter(|entry| !entry.is_type)
+        .map(|entry| LocalExportEntry {
+            export_name: export_name(agent, &entry.export_name),
+            local_name: match &entry.local_name {
+                ExportLocalName::Name(name) | ExportLocalName::Default(name) => {
+                    String::from_str(agent, &name.name, gc)
+                }
+                // `export default` of an anonymous expression binds
+                // "*default*".
+                ExportLocalName::Null => String::from_static_str(agent, "*default*", gc),
+            },
+        })
+        .collect();
+    let indirect_export_entries = module_record
+        .indirect_export_entries
+        .iter()
+        .filter(|entry| !entry.is_type)
+        .map(|entry| IndirectExportEntry {
+            export_name: export_name(agent, &entry.export_name),
+            module_request: String::from_str(
+                agent,
+                &entry.module_request.as_ref().unwrap().name,
+                gc,
+            ),
+            import_name: match &entry.import_name {
+                ExportImportName::Name(name) => Some(String::from_str(agent, &name.name, gc)),
+                ExportImportName::All => None,
+                ExportImportName::AllButDefault | ExportImportName::Null => unreachable!(),
+            },
+        })
+        .collect();
+    let star_export_entries = module_record
+        .star_export_entries
+        .iter()
+        .filter(|entry| !entry.is_type)
+        .map(|entry| String::from_str(agent, &entry.module_request.as_ref().unwrap().name, gc))
+        .collect();
+
+    // 11. Let async be body Contains await.
+    // 12. Return Source Text Module Record { [[Realm]]: realm,
+    //     [[Environment]]: EMPTY, [[Namespace]]: EMPTY, [[Status]]: NEW,
+    //     [[RequestedModules]]: requestedModules, [[LoadedModules]]: « »,
+    //     [[ImportEntries]]: importEntries, [[LocalExportEntries]]:
+    //     localExportEntries, [[IndirectExportEntries]]:
+    //     indirectExportEntries, [[StarExportEntries]]: starExportEntries,
+    //     ... }.
+    let module = ModuleRecord::new(
+        realm.bind(gc),
+        requested_modules,
+        import_entries,
+        local_export_entries,
+        indirect_export_entries,
+        star_export_entries,
+    );
+    Ok(agent.heap.add_module(
+        ModuleHeapData {
+            object_index: None,
+            module,
+            exports: Box::default(),
+        },
+        gc,
+    ))
+}
+
+impl Module<'_> {
+    /// Returns the \[\[RequestedModules]] of the module: the specifier
+    /// strings of its imports and re-exports in source text order.
+    ///
+    /// The host must load each of these and add them to the module using
+    /// [`Module::add_loaded_module`] before linking the module.
+    pub fn requested_modules<'gc>(self, agent: &Agent, gc: NoGcScope<'gc, '_>) -> Vec<String<'gc>> {
+        agent[self]
+            .module
+            .requested_modules
+            .iter()
+            .map(|specifier| specifier.bind(gc))
+            .collect()
+    }
+
+    /// Adds a record { \[\[Specifier]]: `specifier`, \[\[Module]]: `module` }
+    /// to the \[\[LoadedModules]] of the module.
+    ///
+    /// This is the part of
+    /// [FinishLoadingImportedModule](https://tc39.es/ecma262/#sec-FinishLoadingImportedModule)
+    /// relevant to a successfully loaded module.
+    ///
+    /// ## Panics
+    ///
+    /// If a different module was already loaded for `specifier`.
+    pub fn add_loaded_module(self, agent: &mut Agent, specifier: String, module: Module) {
+        let loaded_modules = &mut agent[self].module.loaded_modules;
+        // 1. If result is a normal completion, then
+        // a. If referrer.[[LoadedModules]] contains a LoadedModuleRequest
+        //    Record record such that ModuleRequestsEqual(record,
+        //    moduleRequest) is true, then
+        if let Some((_, loaded)) = loaded_modules.iter().find(|(s, _)| *s == specifier) {
+            // i. Assert: record.[[Module]] and result.[[Value]] are the same
+            //    Module Record.
+            assert_eq!(*loaded, module, "a different module was already loaded");
+        } else {
+            // b. Else,
+            // i. Append the LoadedModuleRequest Record { [[Specifier]]:
+            //    moduleRequest.[[Specifier]], [[Attributes]]:
+            //    moduleRequest.[[Attributes]], [[Module]]: result.[[Value]] }
+            //    to referrer.[[LoadedModules]].
+            loaded_modules.push((specifier.unbind(), module.unbind()));
+        }
+    }
+
+    /// ### [16.2.1.6.1 Link ( )](https://tc39.es/ecma262/#sec-moduledeclarationlinking)
+    ///
+    /// Links the module and all of its dependencies, resolving all imports
+    /// and re-exports and computing the export names of each module.
+    ///
+    /// Nova does not form strongly connected components while linking: all
+    /// modules linked by one call become linked together, or are all reset
+    /// to unlinked if linking fails.
+    ///
+    /// ## Panics
+    ///
+    /// If any of the requested modules of the module or its dependencies has
+    /// not been added using [`Module::add_loaded_module`].
+    pub fn link<'gc>(self, agent: &mut Agent, gc: NoGcScope<'gc, '_>) -> JsResult<'gc, ()> {
+        // 1. Assert: module.[[Status]] is one of unlinked, linked,
+        //    evaluating-async, or evaluated.
+        // 2. Let stack be a new empty List.
+        let mut stack = vec![];
+        // 3. Let result be Completion(InnerModuleLinking(module, stack, 0)).
+        let result = inner_module_linking(agent, self.unbind(), &mut stack, gc);
+        // 4. If result is an abrupt completion, then
+        if let Err(err) = result {
+            // a. For each Cyclic Module Record m of stack, do
+            for m in stack {
+                // i. Assert: m.[[Status]] is linking.
+                debug_assert_eq!(agent[m].module.status, ModuleStatus::Linking);
+                // ii. Set m.[[Status]] to unlinked.
+                agent[m].module.status = ModuleStatus::Unlinked;
+            }
+            // c. Return ? result.
+            return Err(err);
+        }
+        // 5. Assert: module.[[Status]] is one of linked, evaluating-async, or
+        //    evaluated.
+        // 6. Assert: stack is empty.
+        for m in stack {
+            agent[m].module.status = ModuleStatus::Linked;
+        }
+        // 7. Return unused.
+        Ok(())
+    }
+
+    /// Returns the names exported by the module, excluding ambiguous star
+    /// re-exports, in code unit order.
+    ///
+    /// The names are computed when the module is linked; an unlinked module
+    /// has no export names.
+    pub fn export_names<'gc>(self, agent: &Agent, gc: NoGcScope<'gc, '_>) -> Vec<String<'gc>> {
+        agent[self]
+            .exports
+            .iter()
+            .map(|name| name.bind(gc))
+            .collect()
+    }
+
+    /// Resolves an export name of the module to the module and binding name
+    /// that ultimately provides it, following re-exports.
+    ///
+    /// ## Panics
+    ///
+    /// If the requested modules of the module or its dependencies have not
+    /// all been loaded.
+    pub fn resolve_export_public<'gc>(
+        self,
+        agent: &Agent,
+        export_name: String,
+        gc: NoGcScope<'gc, '_>,
+    ) -> ExportResolution<'gc> {
+        match self.resolve_export(agent, export_name, &mut vec![]) {
+            None => ExportResolution::NotFound,
+            Some(ResolveExportResult::Ambiguous) => ExportResolution::Ambiguous,
+            Some(ResolveExportResult::Resolved(ResolvedBinding {
+                module,
+                binding_name,
+            })) => ExportResolution::Resolved {
+                module: module.unwrap().bind(gc),
+                binding_name: match binding_name {
+                    ResolvedBindingName::String(name) => Some(String::String(name.bind(gc))),
+                    ResolvedBindingName::SmallString(name) => Some(String::SmallString(name)),
+                    ResolvedBindingName::Namespace => None,
+                },
+            },
+        }
+    }
+
+    /// ### [16.2.1.7.2.1 GetExportedNames ( \[ exportStarSet \] )](https://tc39.es/ecma262/#sec-getexportednames)
+    pub(crate) fn get_exported_names(
+        self,
+        agent: &Agent,
+        export_star_set: &mut Vec<Module<'static>>,
+    ) -> Vec<String<'static>> {
+        let module = self.unbind();
+        // 1. Assert: module.[[Status]] is not new.
+        // 2. If exportStarSet is not present, set exportStarSet to a new
+        //    empty List.
+        // 3. If exportStarSet contains module, then
+        if export_star_set.contains(&module) {
+            // a. Assert: We've reached the starting point of an export *
+            //    circularity.
+            // b. Return a new empty List.
+            return vec![];
+        }
+        // 4. Append module to exportStarSet.
+        export_star_set.push(module);
+        let record = &agent[module].module;
+        // 5. Let exportedNames be a new empty List.
+        let mut exported_names = vec![];
+        // 6. For each ExportEntry Record e of module.[[LocalExportEntries]], do
+        for e in record.local_export_entries.iter() {
+            // a. Assert: module provides the direct binding for this export.
+            // b. Assert: e.[[ExportName]] is not null.
+            // c. Append e.[[ExportName]] to exportedNames.
+            exported_names.push(e.export_name);
+        }
+        // 7. For each ExportEntry Record e of module.[[IndirectExportEntries]], do
+        for e in record.indirect_export_entries.iter() {
+            // a. Assert: module imports a specific binding for this export.
+            // b. Assert: e.[[ExportName]] is not null.
+            // c. Append e.[[ExportName]] to exportedNames.
+            exported_names.push(e.export_name);
+        }
+        // 8. For each ExportEntry Record e of module.[[StarExportEntries]], do
+        for &module_request in record.star_export_entries.iter() {
+            // a. Assert: e.[[ModuleRequest]] is not null.
+            // b. Let requestedModule be GetImportedModule(module,
+            //    e.[[ModuleRequest]]).
+            let requested_module = module.get_imported_module(agent, module_request);
+            // c. Let starNames be requestedModule.GetExportedNames(exportStarSet).
+            let star_names = requested_module.get_exported_names(agent, export_star_set);
+            // d. For each element n of starNames, do
+            for n in star_names {
+                // i. If n is not "default", then
+                // 1. If exportedNames does not contain n, then
+                if !String::eq(agent, n, BUILTIN_STRING_MEMORY.default)
+                    && !exported_names.contains(&n)
+                {
+                    // a. Append n to exportedNames.
+                    exported_names.push(n);
+                }
+            }
+        }
+        // 9. Return exportedNames.
+        exported_names
+    }
+
+    /// ### [16.2.1.7.2.2 ResolveExport ( exportName \[ , resolveSet \] )](https://tc39.es/ecma262/#sec-resolveexport)
+    ///
+    /// Return the binding of a name exported by this module. Bindings are
+    /// represented by a ResolvedBinding Record, of the form { \[\[Module]]:
+    /// Module Record, \[\[BindingName]]: String | NAMESPACE }. If the export
+    /// is a Module Namespace Object without a direct binding in any module,
+    /// \[\[BindingName]] will be set to NAMESPACE. Return null if the name
+    /// cannot be resolved, or AMBIGUOUS if multiple bindings were found.
+    ///
+    /// Each time this operation is called with a specific exportName,
+    /// resolveSet pair as arguments it must return the same result.
+    ///
+    /// LoadRequestedModules must have completed successfully prior to
+    /// invoking this method.
+    pub(crate) fn resolve_export(
+        self,
+        agent: &Agent,
+        export_name: String,
+        resolve_set: &mut Vec<(Module<'static>, String<'static>)>,
+    ) -> Option<ResolveExportResult> {
+        let module = self.unbind();
+        let export_name = export_name.unbind();
+        // 1. Assert: module.[[Status]] is not new.
+        // 2. If resolveSet is not present, set resolveSet to a new empty List.
+        // 3. For each Record { [[Module]], [[ExportName]] } r of resolveSet, do
+        // a. If module and r.[[Module]] are the same Module Record and
+        //    exportName is r.[[ExportName]], then
+        if resolve_set.contains(&(module, export_name)) {
+            // i. Assert: This is a circular import request.
+            // ii. Return null.
+            return None;
+        }
+        // 4. Append the Record { [[Module]]: module, [[ExportName]]:
+        //    exportName } to resolveSet.
+        resolve_set.push((module, export_name));
+        let record = &agent[module].module;
+        // 5. For each ExportEntry Record e of module.[[LocalExportEntries]], do
+        for e in record.local_export_entries.iter() {
+            // a. If exportName is e.[[ExportName]], then
+            if export_name == e.export_name {
+                // i. Assert: module provides the direct binding for this export.
+                // ii. Return ResolvedBinding Record { [[Module]]: module,
+                //     [[BindingName]]: e.[[LocalName]] }.
+                return Some(ResolveExportResult::Resolved(ResolvedBinding {
+                    module: Some(module),
+                    binding_name: e.local_name.unbind().into(),
+                }));
+            }
+        }
+        // 6. For each ExportEntry Record e of module.[[IndirectExportEntries]], do
+        for e in record.indirect_export_entries.iter() {
+            // a. If exportName is e.[[ExportName]], then
+            if export_name == e.export_name {
+                // i. Assert: e.[[ModuleRequest]] is not null.
+                // ii. Let importedModule be GetImportedModule(module,
+                //     e.[[ModuleRequest]]).
+                let imported_module = module.get_imported_module(agent, e.module_request);
+                // iii. If e.[[ImportName]] is ALL, then
+                let Some(import_name) = e.import_name else {
+                    // 1. Assert: module does not provide the direct binding
+                    //    for this export.
+                    // 2. Return ResolvedBinding Record { [[Module]]:
+                    //    importedModule, [[BindingName]]: NAMESPACE }.
+                    return Some(ResolveExportResult::Resolved(ResolvedBinding {
+                        module: Some(imported_module),
+                        binding_name: ResolvedBindingName::Namespace,
+                    }));
+                };
+                // iv. Else,
+                // 1. Assert: module imports a specific binding for this
+                //    export.
+                // 2. Assert: e.[[ImportName]] is a String.
+                // 3. Return importedModule.ResolveExport(e.[[ImportName]],
+                //    resolveSet).
+                return imported_module.resolve_export(agent, import_name, resolve_set);
+            }
+        }
+        // 7. If exportName is "default", then
+        if String::eq(agent, export_name, BUILTIN_STRING_MEMORY.default) {
+            // a. Assert: A default export was not explicitly defined by this
+            //    module.
+            // b. Return null.
+            // c. NOTE: A default export cannot be provided by an export *
+            //    from "mod" declaration.
+            return None;
+        }
+        // 8. Let starResolution be null.
+        let mut star_resolution: Option<ResolvedBinding> = None;
+        // 9. For each ExportEntry Record e of module.[[StarExportEntries]], do
+        for &module_request in record.star_export_entries.iter() {
+            // a. Assert: e.[[ModuleRequest]] is not null.
+            // b. Let importedModule be GetImportedModule(module,
+            //    e.[[ModuleRequest]]).
+            let imported_module = module.get_imported_module(agent, module_request);
+            // c. Let resolution be importedModule.ResolveExport(exportName,
+            //    resolveSet).
+            let resolution = imported_module.resolve_export(agent, export_name, resolve_set);
+            match resolution {
+                // d. If resolution is AMBIGUOUS, return AMBIGUOUS.
+                Some(ResolveExportResult::Ambiguous) => {
+                    return Some(ResolveExportResult::Ambiguous);
+                }
+                // e. If resolution is not null, then
+                Some(ResolveExportResult::Resolved(resolution)) => {
+                    // i. Assert: resolution is a ResolvedBinding Record.
+                    match star_resolution {
+                        // ii. If starResolution is null, then
+                        None => {
+                            // 1. Set starResolution to resolution.
+                            star_resolution = Some(resolution);
+                        }
+                        // iii. Else,
+                        // 1. Assert: There is more than one * import that
+                        //    includes the requested name.
+                        // 2. If resolution.[[Module]] and
+                        //    starResolution.[[Module]] are not the same Module
+                        //    Record, return AMBIGUOUS.
+                        // 3. If resolution.[[BindingName]] is not
+                        //    starResolution.[[BindingName]], return AMBIGUOUS.
+                        Some(star_resolution) if star_resolution != resolution => {
+                            return Some(ResolveExportResult::Ambiguous);
+                        }
+                        Some(_) => {}
+                    }
+                }
+                None => {}
+            }
+        }
+        // 10. Return starResolution.
+        star_resolution.map(ResolveExportResult::Resolved)
+    }
+
+    /// ### [16.2.1.9 GetImportedModule ( referrer, request )](https://tc39.es/ecma262/#sec-GetImportedModule)
+    fn get_imported_module(self, agent: &Agent, request: String) -> Module<'static> {
+        // 1. Let records be a List consisting of each LoadedModuleRequest
+        //    Record r of referrer.[[LoadedModules]] such that
+        //    ModuleRequestsEqual(r, request) is true.
+        // 2. Assert: records has exactly one element, since
+        //    LoadRequestedModules has completed successfully on referrer prior
+        //    to invoking this abstract operation.
+        // 3. Let record be the sole element of records.
+        // 4. Return record.[[Module]].
+        agent[self]
+            .module
+            .loaded_modules
+            .iter()
+            .find(|(specifier, _)| *specifier == request)
+            .map(|(_, module)| *module)
+            .unwrap_or_else(|| {
+                panic!(
+                    "Requested module '{}' has not been loaded",
+                    request.to_string_lossy(agent)
+                )
+            })
+    }
+}
+
+impl From<String<'static>> for ResolvedBindingName {
+    fn from(value: String<'static>) -> Self {
+        match value {
+            String::String(data) => Self::String(data),
+            String::SmallString(data) => Self::SmallString(data),
+        }
+    }
+}
+
+/// ### [16.2.1.6.1.1 InnerModuleLinking ( module, stack, index )](https://tc39.es/ecma262/#sec-InnerModuleLinking)
+fn inner_module_linking<'gc>(
+    agent: &mut Agent,
+    module: Module<'static>,
+    stack: &mut Vec<Module<'static>>,
+    gc: NoGcScope<'gc, '_>,
+) -> JsResult<'gc, ()> {
+    // 1. If module is not a Cyclic Module Record, then
+    // 2. If module.[[Status]] is one of linking, linked, evaluating-async, or
+    //    evaluated, then
+    if agent[module].module.status != ModuleStatus::Unlinked {
+        // a. Return index.
+        return Ok(());
+    }
+    // 3. Assert: module.[[Status]] is unlinked.
+    // 4. Set module.[[Status]] to linking.
+    agent[module].module.status = ModuleStatus::Linking;
+    // 8. Append module to stack.
+    stack.push(module);
+    // 9. For each ModuleRequest Record request of module.[[RequestedModules]], do
+    for i in 0..agent[module].module.requested_modules.len() {
+        let request = agent[module].module.requested_modules[i];
+        // a. Let requiredModule be GetImportedModule(module, request).
+        let required_module = module.get_imported_module(agent, request);
+        // b. Set index to ? InnerModuleLinking(requiredModule, stack, index).
+        inner_module_linking(agent, required_module, stack, gc)?;
+    }
+    // 10. Perform ? module.InitializeEnvironment().
+    initialize_environment(agent, module, gc)
+}
+
+/// ### [16.2.1.7.3.3 InitializeEnvironment ( )](https://tc39.es/ecma262/#sec-source-text-module-record-initialize-environment)
+///
+/// Nova does not yet create module environments: this only checks that all
+/// imports and re-exports resolve, and computes the module's
+/// \[\[Exports]] for its module namespace object.
+fn initialize_environment<'gc>(
+    agent: &mut Agent,
+    module: Module<'static>,
+    gc: NoGcScope<'gc, '_>,
+) -> JsResult<'gc, ()> {
+    let record = &agent[module].module;
+    // 1. For each ExportEntry Record e of module.[[IndirectExportEntries]], do
+    let unresolvable_export = record.indirect_export_entries.iter().find_map(|e| {
+        // a. Assert: e.[[ExportName]] is not null.
+        // b. Let resolution be module.ResolveExport(e.[[ExportName]]).
+        let resolution = module.resolve_export(agent, e.export_name, &mut vec![]);
+        // c. If resolution is either null or AMBIGUOUS, throw a SyntaxError
+        //    exception.
+        // d. Assert: resolution is a ResolvedBinding Record.
+        (!matches!(resolution, Some(ResolveExportResult::Resolved(_)))).then(|| {
+            unresolvable_export_message(
+                agent,
+                e.module_request,
+                e.import_name.unwrap_or(e.export_name),
+                resolution,
+            )
+        })
+    });
+    // 2. Assert: All named exports from module are resolvable.
+    // 7. For each ImportEntry Record in of module.[[ImportEntries]], do
+    let unresolvable_import = || {
+        record.import_entries.iter().find_map(|entry| {
+            // a. Let importedModule be GetImportedModule(module,
+            //    in.[[ModuleRequest]]).
+            let imported_module = module.get_imported_module(agent, entry.module_request);
+            // b. If in.[[ImportName]] is NAMESPACE-OBJECT, then
+            // i. Let namespace be GetModuleNamespace(importedModule).
+            // ii. Perform ! env.CreateImmutableBinding(in.[[LocalName]], true).
+            // iii. Perform ! env.InitializeBinding(in.[[LocalName]], namespace).
+            let import_name = entry.import_name?;
+            // c. Else,
+            // i. Let resolution be importedModule.ResolveExport(in.[[ImportName]]).
+            let resolution = imported_module.resolve_export(agent, import_name, &mut vec![]);
+            // ii. If resolution is either null or AMBIGUOUS, throw a
+            //     SyntaxError exception.
+            // TODO: Create the import bindings once module environments exist.
+            (!matches!(resolution, Some(ResolveExportResult::Resolved(_)))).then(|| {
+                unresolvable_export_message(agent, entry.module_request, import_name, resolution)
+            })
+        })
+    };
+    if let Some(message) = unresolvable_export.or_else(unresolvable_import) {
+        return Err(agent.throw_exception(ExceptionType::SyntaxError, message, gc));
+    }
+
+    // NOTE: The [[Exports]] of the module namespace object are computed here
+    // rather than in GetModuleNamespace so that hosts can list the export
+    // names of a linked module.
+    // GetModuleNamespace ( module )
+    // 3. a. Let exportedNames be module.GetExportedNames().
+    let exported_names = module.get_exported_names(agent, &mut vec![]);
+    // b. Let unambiguousNames be a new empty List.
+    // c. For each element name of exportedNames, do
+    let mut unambiguous_names = exported_names
+        .into_iter()
+        .filter(|&name| {
+            // i. Let resolution be module.ResolveExport(name).
+            // ii. If resolution is a ResolvedBinding Record, append name to
+            //     unambiguousNames.
+            matches!(
+                module.resolve_export(agent, name, &mut vec![]),
+                Some(ResolveExportResult::Resolved(_))
+            )
+        })
+        .collect::<Vec<_>>();
+    // 28.3.1 ModuleNamespaceCreate ( module, exports )
+    // 7. Let sortedExports be a List whose elements are the elements of
+    //    exports ordered as if an Array of those values had been sorted using
+    //    %Array.prototype.sort% using undefined as comparefn.
+    unambiguous_names.sort_by(|a, b| String::cmp_code_units(agent, *a, *b));
+    agent[module].exports = unambiguous_names.into_boxed_slice();
+    Ok(())
+}
+
+fn unresolvable_export_message(
+    agent: &Agent,
+    module_request: String,
+    name: String,
+    resolution: Option<ResolveExportResult>,
+) -> std::string::String {
+    if resolution.is_none() {
+        format!(
+            "The requested module '{}' does not provide an export named '{}'",
+            module_request.to_string_lossy(agent),
+            name.to_string_lossy(agent)
+        )
+    } else {
+        format!(
+            "The requested module '{}' contains conflicting star exports for name '{}'",
+            module_request.to_string_lossy(agent),
+            name.to_string_lossy(agent)
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        ecmascript::{
+            builtins::module::Module,
+            execution::{Agent, DefaultHostHooks, agent::Options, initialize_default_realm},
+            scripts_and_modules::module::{ExportResolution, parse_module},
+            types::String,
+        },
+        engine::context::NoGcScope,
+    };
+
+    fn parse<'a>(agent: &mut Agent, source: &'static str, gc: NoGcScope<'a, '_>) -> Module<'a> {
+        let realm = agent.current_realm(gc);
+        let source_text = String::from_static_str(agent, source, gc);
+        parse_module(agent, source_text, realm, gc).unwrap()
+    }
+
+    fn load(
+        agent: &mut Agent,
+        referrer: Module,
+        specifier: &'static str,
+        module: Module,
+        gc: NoGcScope,
+    ) {
+        let specifier = String::from_static_str(agent, specifier, gc);
+        referrer.add_loaded_module(agent, specifier, module);
+    }
+
+    fn names(agent: &Agent, module: Module, gc: NoGcScope) -> Vec<std::string::String> {
+        module
+            .export_names(agent, gc)
+            .into_iter()
+            .map(|name| name.as_str(agent).to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn re_export_chain() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let gc = gc.nogc();
+        let a = parse(agent, "export const x = 1; export default 2;", gc);
+        let b = parse(agent, "export { x as y } from 'a'; export * from 'a';", gc);
+        let c = parse(
+            agent,
+            "import { y } from 'b'; export { y as z } from 'b'; export * from 'b';",
+            gc,
+        );
+        let specifiers = c.requested_modules(agent, gc);
+        assert_eq!(specifiers.len(), 1);
+        assert_eq!(specifiers[0].as_str(agent), "b");
+        load(agent, b, "a", a, gc);
+        load(agent, c, "b", b, gc);
+        c.link(agent, gc).unwrap();
+
+        assert_eq!(names(agent, a, gc), ["default", "x"]);
+        assert_eq!(names(agent, b, gc), ["x", "y"]);
+        assert_eq!(names(agent, c, gc), ["x", "y", "z"]);
+
+        let z = String::from_static_str(agent, "z", gc);
+        let x = String::from_static_str(agent, "x", gc);
+        assert_eq!(
+            c.resolve_export_public(agent, z, gc),
+            ExportResolution::Resolved {
+                module: a,
+                binding_name: Some(x),
+            }
+        );
+        let default = String::from_static_str(agent, "default", gc);
+        assert_eq!(
+            c.resolve_export_public(agent, default, gc),
+            ExportResolution::NotFound
+        );
+        assert_eq!(
+            a.resolve_export_public(agent, default, gc),
+            ExportResolution::Resolved {
+                module: a,
+                binding_name: Some(String::from_static_str(agent, "*default*", gc)),
+            }
+        );
+    }
+
+    #[test]
+    fn ambiguous_star_export() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let gc = gc.nogc();
+        let first = parse(agent, "export const shared = 1; export const one = 1;", gc);
+        let second = parse(agent, "export const shared = 2;", gc);
+        let both = parse(
+            agent,
+            "export * from 'first'; export * from 'second'; export * as ns from 'first';",
+            gc,
+        );
+        load(agent, both, "first", first, gc);
+        load(agent, both, "second", second, gc);
+        both.link(agent, gc).unwrap();
+
+        assert_eq!(names(agent, both, gc), ["ns", "one"]);
+        let shared = String::from_static_str(agent, "shared", gc);
+        assert_eq!(
+            both.resolve_export_public(agent, shared, gc),
+            ExportResolution::Ambiguous
+        );
+        let ns = String::from_static_str(agent, "ns", gc);
+        assert_eq!(
+            both.resolve_export_public(agent, ns, gc),
+            ExportResolution::Resolved {
+                module: first,
+                binding_name: None,
+            }
+        );
+
+        // Importing an ambiguous name is a SyntaxError.
+        let importer = parse(agent, "import { shared } from 'both';", gc);
+        load(agent, importer, "both", both, gc);
+        assert!(importer.link(agent, gc).is_err());
+        assert!(importer.export_names(agent, gc).is_empty());
+    }
+
+    #[test]
+    fn circular_star_exports() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let gc = gc.nogc();
+        let first = parse(agent, "export * from 'second'; export const p = 1;", gc);
+        let second = parse(agent, "export * from 'first'; export const q = 1;", gc);
+        load(agent, first, "second", second, gc);
+        load(agent, second, "first", first, gc);
+        first.link(agent, gc).unwrap();
+
+        assert_eq!(names(agent, first, gc), ["p", "q"]);
+        assert_eq!(names(agent, second, gc), ["p", "q"]);
+        let missing = String::from_static_str(agent, "missing", gc);
+        assert_eq!(
+            first.resolve_export_public(agent, missing, gc),
+            ExportResolution::NotFound
+        );
+    }
+}
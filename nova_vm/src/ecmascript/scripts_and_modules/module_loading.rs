@@ -0,0 +1,680 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Loading module graphs with host loads that complete later.
+//!
+//! [`Module::load_requested_modules`] asks the host to load each module
+//! request of a graph through [`HostHooks::load_imported_module`]. The host
+//! may return the module at once, or return [`ModuleLoad::Deferred`] and
+//! fetch the source text eg. in an async runtime, then deliver it on the
+//! Agent's thread with [`Agent::complete_module_load`]. The graph's promise
+//! stays pending until every load it is waiting for has completed.
+//!
+//! [`HostHooks::load_imported_module`]: crate::ecmascript::execution::agent::HostHooks::load_imported_module
+
+use ahash::AHashMap;
+
+use crate::{
+    ecmascript::{
+        builtins::{
+            control_abstraction_objects::promise_objects::promise_abstract_operations::promise_capability_records::PromiseCapability,
+            module::{Module, data::ModuleStatus},
+            promise::Promise,
+        },
+        execution::{Agent, agent::ExceptionType},
+        types::{String, Value},
+    },
+    engine::{
+        context::{Bindable, NoGcScope},
+        unwrap_try,
+    },
+    heap::{CompactionLists, HeapMarkAndSweep, WorkQueues},
+};
+
+use super::{module::parse_module, module_map::ModuleSource};
+
+/// Identifies a module load that the host has deferred by returning
+/// [`ModuleLoad::Deferred`].
+///
+/// Each token must be completed exactly once with
+/// [`Agent::complete_module_load`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DeferredModuleLoad(u32);
+
+/// Result of [`HostHooks::load_imported_module`].
+///
+/// [`HostHooks::load_imported_module`]: crate::ecmascript::execution::agent::HostHooks::load_imported_module
+#[derive(Debug, Clone, Copy)]
+pub enum ModuleLoad<'a> {
+    /// The module was loaded.
+    Loaded(Module<'a>),
+    /// The module could not be loaded; the value is thrown.
+    Failed(Value<'a>),
+    /// The load is pending: the host completes it later with
+    /// [`Agent::complete_module_load`].
+    Deferred(DeferredModuleLoad),
+}
+
+/// ### [GraphLoadingState Record](https://tc39.es/ecma262/#graphloadingstate-record)
+#[derive(Debug)]
+struct GraphLoadingState {
+    /// \[\[PromiseCapability]]
+    promise: Promise<'static>,
+    /// \[\[IsLoading]]
+    is_loading: bool,
+    /// \[\[PendingModulesCount]]
+    pending_modules_count: usize,
+    /// \[\[Visited]]
+    visited: Vec<Module<'static>>,
+}
+
+/// A module request whose load the host has deferred.
+#[derive(Debug)]
+struct PendingLoad {
+    referrer: Module<'static>,
+    specifier: String<'static>,
+    state: usize,
+}
+
+/// Module graphs being loaded and the loads they are waiting for.
+#[derive(Debug, Default)]
+pub(crate) struct ModuleLoading {
+    states: Vec<Option<GraphLoadingState>>,
+    pending: AHashMap<DeferredModuleLoad, PendingLoad>,
+    next_load: u32,
+}
+
+impl ModuleLoading {
+    /// Free the state of a graph that has stopped loading once no deferred
+    /// loads refer to it anymore.
+    fn release(&mut self, state: usize) {
+        let finished = self.states[state]
+            .as_ref()
+            .is_some_and(|state| !state.is_loading);
+        if finished && !self.pending.values().any(|load| load.state == state) {
+            self.states[state] = None;
+        }
+    }
+}
+
+impl HeapMarkAndSweep for ModuleLoading {
+    fn mark_values(&self, queues: &mut WorkQueues) {
+        let Self {
+            states,
+            pending,
+            next_load: _,
+        } = self;
+        for state in states.iter().flatten() {
+            state.promise.mark_values(queues);
+            state
+                .visited
+                .iter()
+                .for_each(|module| module.mark_values(queues));
+        }
+        for load in pending.values() {
+            load.referrer.mark_values(queues);
+            load.specifier.mark_values(queues);
+        }
+    }
+
+    fn sweep_values(&mut self, compactions: &CompactionLists) {
+        let Self {
+            states,
+            pending,
+            next_load: _,
+        } = self;
+        for state in states.iter_mut().flatten() {
+            state.promise.sweep_values(compactions);
+            state
+                .visited
+                .iter_mut()
+                .for_each(|module| module.sweep_values(compactions));
+        }
+        for load in pending.values_mut() {
+            load.referrer.sweep_values(compactions);
+            load.specifier.sweep_values(compactions);
+        }
+    }
+}
+
+impl Module<'_> {
+    /// ### [16.2.1.5.1 LoadRequestedModules ( \[ hostDefined \] )](https://tc39.es/ecma262/#sec-LoadRequestedModules)
+    ///
+    /// Loads all modules that the module imports, directly or indirectly,
+    /// through [`HostHooks::load_imported_module`], and adds them to the
+    /// \[\[LoadedModules]] of their referrers so that the module can be
+    /// linked. The returned promise is fulfilled with undefined once the
+    /// whole graph is loaded, or rejected with the error of the first load
+    /// that fails.
+    ///
+    /// [`HostHooks::load_imported_module`]: crate::ecmascript::execution::agent::HostHooks::load_imported_module
+    pub fn load_requested_modules<'gc>(
+        self,
+        agent: &mut Agent,
+        gc: NoGcScope<'gc, '_>,
+    ) -> Promise<'gc> {
+        // 1. If hostDefined is not present, let hostDefined be empty.
+        // 2. Let pc be ! NewPromiseCapability(%Promise%).
+        let promise = PromiseCapability::new(agent, gc).promise();
+        // 3. Let state be the GraphLoadingState Record { [[IsLoading]]: true,
+        //    [[PendingModulesCount]]: 1, [[Visited]]: « »,
+        //    [[PromiseCapability]]: pc, [[HostDefined]]: hostDefined }.
+        let loading = &mut agent.module_loading;
+        let state = GraphLoadingState {
+            promise: promise.unbind(),
+            is_loading: true,
+            pending_modules_count: 1,
+            visited: Vec::new(),
+        };
+        let state = if let Some(index) = loading.states.iter().position(Option::is_none) {
+            loading.states[index] = Some(state);
+            index
+        } else {
+            loading.states.push(Some(state));
+            loading.states.len() - 1
+        };
+        // 4. Perform InnerModuleLoading(state, module).
+        inner_module_loading(agent, state, self, gc);
+        agent.module_loading.release(state);
+        // 5. Return pc.[[Promise]].
+        promise
+    }
+}
+
+impl Agent {
+    /// Complete a module load that the host deferred by returning
+    /// [`ModuleLoad::Deferred`] from [`HostHooks::load_imported_module`].
+    ///
+    /// A source text is parsed as a module in the current Realm; a source
+    /// text that fails to parse is a SyntaxError. An error value rejects the
+    /// promise of the module graph that requested the module. Loading then
+    /// continues with the requests of the new module, which may be deferred
+    /// in turn.
+    ///
+    /// Returns the module that was parsed, if any.
+    ///
+    /// ## Panics
+    ///
+    /// If the load is not pending: it was already completed, or it belongs to
+    /// a different Agent.
+    ///
+    /// [`HostHooks::load_imported_module`]: crate::ecmascript::execution::agent::HostHooks::load_imported_module
+    pub fn complete_module_load<'gc>(
+        &mut self,
+        load: DeferredModuleLoad,
+        result: Result<ModuleSource, Value>,
+        gc: NoGcScope<'gc, '_>,
+    ) -> Option<Module<'gc>> {
+        assert!(
+            self.module_loading.pending.contains_key(&load),
+            "module load is not pending"
+        );
+        let result = match result {
+            Ok(source) => {
+                let realm = self.current_realm(gc);
+                let source_text = String::from_string(self, source.source_text, gc);
+                match parse_module(self, source_text, realm, gc) {
+                    Ok(module) => ModuleLoad::Loaded(module),
+                    Err(errors) => {
+                        let message = errors.first().unwrap().message.to_string();
+                        let message = String::from_string(self, message, gc);
+                        ModuleLoad::Failed(
+                            self.throw_exception_with_message(
+                                ExceptionType::SyntaxError,
+                                message,
+                                gc,
+                            )
+                            .value(),
+                        )
+                    }
+                }
+            }
+            Err(error) => ModuleLoad::Failed(error.bind(gc)),
+        };
+        self.complete_module_load_with(load, result, gc);
+        match result {
+            ModuleLoad::Loaded(module) => Some(module),
+            _ => None,
+        }
+    }
+
+    /// Complete a deferred module load with an existing module or an error,
+    /// eg. to share one module between several referrers.
+    ///
+    /// ## Panics
+    ///
+    /// If the load is not pending, or `result` is [`ModuleLoad::Deferred`].
+    pub fn complete_module_load_with(
+        &mut self,
+        load: DeferredModuleLoad,
+        result: ModuleLoad,
+        gc: NoGcScope,
+    ) {
+        let PendingLoad {
+            referrer,
+            specifier,
+            state,
+        } = self
+            .module_loading
+            .pending
+            .remove(&load)
+            .expect("module load is not pending");
+        assert!(
+            !matches!(result, ModuleLoad::Deferred(_)),
+            "cannot complete a module load by deferring it"
+        );
+        finish_loading_imported_module(self, referrer, specifier, state, result, gc);
+        self.module_loading.release(state);
+    }
+}
+
+/// ### [16.2.1.5.1.1 InnerModuleLoading ( state, module )](https://tc39.es/ecma262/#sec-InnerModuleLoading)
+fn inner_module_loading(agent: &mut Agent, state: usize, module: Module, gc: NoGcScope) {
+    // 1. Assert: state.[[IsLoading]] is true.
+    debug_assert!(graph_state(agent, state).is_loading);
+    // 2. If module is a Cyclic Module Record, module.[[Status]] is new, and
+    //    state.[[Visited]] does not contain module, then
+    if agent[module].module.status == ModuleStatus::Unlinked
+        && !graph_state(agent, state).visited.contains(&module.unbind())
+    {
+        // a. Append module to state.[[Visited]].
+        graph_state(agent, state).visited.push(module.unbind());
+        // b. Let requestedModulesCount be the number of elements in
+        //    module.[[RequestedModules]].
+        let requested_modules = module.requested_modules(agent, gc);
+        // c. Set state.[[PendingModulesCount]] to
+        //    state.[[PendingModulesCount]] + requestedModulesCount.
+        graph_state(agent, state).pending_modules_count += requested_modules.len();
+        // d. For each ModuleRequest Record request of
+        //    module.[[RequestedModules]], do
+        for specifier in requested_modules {
+            // i. If AllImportAttributesSupported(request.[[Attributes]]) is
+            //    false, then
+            // NOTE: Import attributes are not supported.
+            // ii. Else if module.[[LoadedModules]] contains a
+            //     LoadedModuleRequest Record record such that
+            //     ModuleRequestsEqual(record, request) is true, then
+            let loaded = agent[module]
+                .module
+                .loaded_modules
+                .iter()
+                .find(|(s, _)| *s == specifier)
+                .map(|(_, loaded)| loaded.bind(gc));
+            if let Some(loaded) = loaded {
+                // 1. Perform InnerModuleLoading(state, record.[[Module]]).
+                inner_module_loading(agent, state, loaded, gc);
+            } else {
+                // iii. Else,
+                // 1. Perform HostLoadImportedModule(module, request,
+                //    state.[[HostDefined]], state).
+                host_load_imported_module(agent, state, module, specifier, gc);
+                // 2. NOTE: HostLoadImportedModule will call
+                //    FinishLoadingImportedModule, which re-enters the graph
+                //    loading process through ContinueModuleLoading.
+            }
+            // iv. If state.[[IsLoading]] is false, return unused.
+            if !graph_state(agent, state).is_loading {
+                return;
+            }
+        }
+    }
+    let graph = graph_state(agent, state);
+    // 3. Assert: state.[[PendingModulesCount]] ≥ 1.
+    debug_assert!(graph.pending_modules_count >= 1);
+    // 4. Set state.[[PendingModulesCount]] to
+    //    state.[[PendingModulesCount]] - 1.
+    graph.pending_modules_count -= 1;
+    // 5. If state.[[PendingModulesCount]] = 0, then
+    if graph.pending_modules_count == 0 {
+        // a. Set state.[[IsLoading]] to false.
+        graph.is_loading = false;
+        // b. For each Cyclic Module Record loaded of state.[[Visited]], do
+        // i. If loaded.[[Status]] is new, set loaded.[[Status]] to unlinked.
+        // NOTE: The new and unlinked states are merged.
+        // c. Perform ! Call(state.[[PromiseCapability]].[[Resolve]],
+        //    undefined, « undefined »).
+        let promise = graph.promise.bind(gc);
+        unwrap_try(PromiseCapability::from_promise(promise, true).try_resolve(
+            agent,
+            Value::Undefined,
+            gc,
+        ));
+    }
+    // 6. Return unused.
+}
+
+/// ### [16.2.1.5.1.2 ContinueModuleLoading ( state, moduleCompletion )](https://tc39.es/ecma262/#sec-ContinueModuleLoading)
+fn continue_module_loading(agent: &mut Agent, state: usize, result: ModuleLoad, gc: NoGcScope) {
+    // 1. If state.[[IsLoading]] is false, return unused.
+    if !graph_state(agent, state).is_loading {
+        return;
+    }
+    match result {
+        // 2. If moduleCompletion is a normal completion, then
+        ModuleLoad::Loaded(module) => {
+            // a. Perform InnerModuleLoading(state, moduleCompletion.[[Value]]).
+            inner_module_loading(agent, state, module, gc);
+        }
+        // 3. Else,
+        ModuleLoad::Failed(error) => {
+            let graph = graph_state(agent, state);
+            // a. Set state.[[IsLoading]] to false.
+            graph.is_loading = false;
+            // b. Perform ! Call(state.[[PromiseCapability]].[[Reject]],
+            //    undefined, « moduleCompletion.[[Value]] »).
+            let promise = graph.promise.bind(gc);
+            PromiseCapability::from_promise(promise, true).reject(agent, error, gc);
+        }
+        ModuleLoad::Deferred(_) => unreachable!(),
+    }
+    // 4. Return unused.
+}
+
+/// ### [16.2.1.10 HostLoadImportedModule ( referrer, moduleRequest, hostDefined, payload )](https://tc39.es/ecma262/#sec-HostLoadImportedModule)
+fn host_load_imported_module(
+    agent: &mut Agent,
+    state: usize,
+    referrer: Module,
+    specifier: String,
+    gc: NoGcScope,
+) {
+    let loading = &mut agent.module_loading;
+    let load = DeferredModuleLoad(loading.next_load);
+    loading.next_load = loading.next_load.wrapping_add(1);
+    loading.pending.insert(
+        load,
+        PendingLoad {
+            referrer: referrer.unbind(),
+            specifier: specifier.unbind(),
+            state,
+        },
+    );
+    let host_hooks = agent.host_hooks;
+    let result = host_hooks.load_imported_module(agent, referrer, specifier, load, gc);
+    if !matches!(result, ModuleLoad::Deferred(_)) {
+        // The host completed the load synchronously.
+        let pending = agent.module_loading.pending.remove(&load);
+        assert!(pending.is_some(), "module load was completed twice");
+        finish_loading_imported_module(agent, referrer, specifier, state, result, gc);
+    }
+}
+
+/// ### [16.2.1.11 FinishLoadingImportedModule ( referrer, moduleRequest, payload, result )](https://tc39.es/ecma262/#sec-FinishLoadingImportedModule)
+fn finish_loading_imported_module(
+    agent: &mut Agent,
+    referrer: Module,
+    specifier: String,
+    state: usize,
+    result: ModuleLoad,
+    gc: NoGcScope,
+) {
+    // 1. If result is a normal completion, then
+    if let ModuleLoad::Loaded(module) = result {
+        referrer.add_loaded_module(agent, specifier, module);
+    }
+    // 2. If payload is a GraphLoadingState Record, then
+    // a. Perform ContinueModuleLoading(payload, result).
+    continue_module_loading(agent, state, result, gc);
+    // 3. Else,
+    // a. Perform ContinueDynamicImport(payload, result).
+    // NOTE: Dynamic import() is not supported.
+    // 4. Return unused.
+}
+
+fn graph_state(agent: &mut Agent, state: usize) -> &mut GraphLoadingState {
+    agent.module_loading.states[state].as_mut().unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::RefCell, collections::VecDeque};
+
+    use super::{DeferredModuleLoad, ModuleLoad};
+    use crate::{
+        ecmascript::{
+            builtins::{Promise, module::Module},
+            execution::{
+                Agent,
+                agent::{HostHooks, Job, Options},
+                initialize_default_realm,
+            },
+            scripts_and_modules::{
+                module::parse_module, module_map::ModuleSource, script::test::evaluate,
+            },
+            types::{InternalMethods, IntoValue, PropertyDescriptor, PropertyKey, String, Value},
+        },
+        engine::{
+            context::{Bindable, GcScope, NoGcScope},
+            rootable::Scopable,
+        },
+    };
+
+    /// Defers every module load, like a host that fetches modules in an async
+    /// runtime, and queues promise Jobs for [`Agent::run_microtasks`].
+    #[derive(Default)]
+    struct DeferringHostHooks {
+        jobs: RefCell<VecDeque<Job>>,
+        loads: RefCell<Vec<(std::string::String, DeferredModuleLoad)>>,
+    }
+
+    impl DeferringHostHooks {
+        /// Take the pending load of `specifier`, as the executor would when its
+        /// fetch finishes.
+        fn take_load(&self, specifier: &str) -> DeferredModuleLoad {
+            let mut loads = self.loads.borrow_mut();
+            let index = loads
+                .iter()
+                .position(|(s, _)| s == specifier)
+                .unwrap_or_else(|| panic!("{specifier} is not being loaded"));
+            loads.remove(index).1
+        }
+
+        fn pending_loads(&self) -> Vec<std::string::String> {
+            self.loads.borrow().iter().map(|(s, _)| s.clone()).collect()
+        }
+    }
+
+    impl core::fmt::Debug for DeferringHostHooks {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.debug_struct("DeferringHostHooks").finish_non_exhaustive()
+        }
+    }
+
+    impl HostHooks for DeferringHostHooks {
+        fn enqueue_promise_job(&self, job: Job) {
+            self.jobs.borrow_mut().push_back(job);
+        }
+
+        fn dequeue_promise_job(&self) -> Option<Job> {
+            self.jobs.borrow_mut().pop_front()
+        }
+
+        fn load_imported_module<'gc>(
+            &self,
+            agent: &mut Agent,
+            _referrer: Module<'gc>,
+            specifier: String<'gc>,
+            load: DeferredModuleLoad,
+            _gc: NoGcScope<'gc, '_>,
+        ) -> ModuleLoad<'gc> {
+            self.loads
+                .borrow_mut()
+                .push((specifier.as_str(agent).to_owned(), load));
+            ModuleLoad::Deferred(load)
+        }
+    }
+
+    fn parse<'gc>(agent: &mut Agent, source: &'static str, gc: NoGcScope<'gc, '_>) -> Module<'gc> {
+        let realm = agent.current_realm(gc);
+        let source_text = String::from_static_str(agent, source, gc);
+        parse_module(agent, source_text, realm, gc).unwrap()
+    }
+
+    /// Start loading the requested modules of a new module and record the
+    /// settlement of the loading promise in the global `results` object under
+    /// `name`.
+    fn start_loading<'gc>(
+        agent: &mut Agent,
+        name: &'static str,
+        source: &'static str,
+        mut gc: GcScope<'gc, '_>,
+    ) -> Module<'gc> {
+        let module = parse(agent, source, gc.nogc()).unbind();
+        let promise = module.load_requested_modules(agent, gc.nogc());
+        watch(agent, name, promise.unbind(), gc.reborrow());
+        module.bind(gc.into_nogc())
+    }
+
+    fn watch(agent: &mut Agent, name: &'static str, promise: Promise, mut gc: GcScope) {
+        let global = agent.current_global_object(gc.nogc());
+        let key = PropertyKey::from_static_str(agent, "promise", gc.nogc());
+        global
+            .unbind()
+            .internal_define_own_property(
+                agent,
+                key.unbind(),
+                PropertyDescriptor::new_data_descriptor(promise.into_value().unbind()),
+                gc.reborrow(),
+            )
+            .unwrap();
+        let key = PropertyKey::from_static_str(agent, "name", gc.nogc());
+        let name = String::from_static_str(agent, name, gc.nogc());
+        let global = agent.current_global_object(gc.nogc());
+        global
+            .unbind()
+            .internal_define_own_property(
+                agent,
+                key.unbind(),
+                PropertyDescriptor::new_data_descriptor(name.into_value().unbind()),
+                gc.reborrow(),
+            )
+            .unwrap();
+        evaluate(
+            agent,
+            "var results = globalThis.results || {};
+            var order = globalThis.order || [];
+            results[name] = 'pending';
+            ((name) => promise.then(
+                () => { results[name] = 'loaded'; order.push(name); },
+                (error) => { results[name] = `${error}`; order.push(name); },
+            ))(name);",
+            gc,
+        );
+    }
+
+    fn result(agent: &mut Agent, name: &str, gc: GcScope) -> std::string::String {
+        let source = format!("results['{name}']");
+        let source_text = String::from_string(agent, source, gc.nogc());
+        let value = agent.run_script(source_text.unbind(), gc).unwrap();
+        String::try_from(value).unwrap().as_str(agent).to_owned()
+    }
+
+    fn source(text: &str) -> Result<ModuleSource, Value<'static>> {
+        Ok(text.into())
+    }
+
+    #[test]
+    fn load_stays_pending_across_microtask_checkpoints() {
+        let host_hooks: &DeferringHostHooks = Box::leak(Box::default());
+        let mut agent = Agent::new(Options::default(), host_hooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let main = start_loading(agent, "main", "export * from './dep.js';", gc.reborrow())
+            .unbind()
+            .scope(agent, gc.nogc());
+        assert_eq!(host_hooks.pending_loads(), ["./dep.js"]);
+        for _ in 0..3 {
+            agent.run_microtasks(100, gc.reborrow()).unwrap();
+            agent.gc(gc.reborrow());
+            assert_eq!(result(agent, "main", gc.reborrow()), "pending");
+        }
+
+        // The loaded module has requests of its own, which are deferred in
+        // turn.
+        let load = host_hooks.take_load("./dep.js");
+        agent.complete_module_load(load, source("export * from './leaf.js';"), gc.nogc());
+        assert_eq!(host_hooks.pending_loads(), ["./leaf.js"]);
+        agent.run_microtasks(100, gc.reborrow()).unwrap();
+        assert_eq!(result(agent, "main", gc.reborrow()), "pending");
+
+        let load = host_hooks.take_load("./leaf.js");
+        agent.complete_module_load(load, source("export const leaf = 1;"), gc.nogc());
+        assert_eq!(result(agent, "main", gc.reborrow()), "pending");
+        agent.run_microtasks(100, gc.reborrow()).unwrap();
+        assert_eq!(result(agent, "main", gc.reborrow()), "loaded");
+
+        let gc = gc.nogc();
+        let main = main.get(agent).bind(gc);
+        main.link(agent, gc).unwrap();
+        let names: Vec<_> = main
+            .export_names(agent, gc)
+            .into_iter()
+            .map(|name| name.as_str(agent).to_owned())
+            .collect();
+        assert_eq!(names, ["leaf"]);
+    }
+
+    #[test]
+    fn concurrent_loads_complete_out_of_order() {
+        let host_hooks: &DeferringHostHooks = Box::leak(Box::default());
+        let mut agent = Agent::new(Options::default(), host_hooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        start_loading(agent, "a", "import './a.js';", gc.reborrow());
+        start_loading(agent, "b", "import './b.js';", gc.reborrow());
+        assert_eq!(host_hooks.pending_loads(), ["./a.js", "./b.js"]);
+
+        let load = host_hooks.take_load("./b.js");
+        agent.complete_module_load(load, source(""), gc.nogc());
+        agent.run_microtasks(100, gc.reborrow()).unwrap();
+        assert_eq!(result(agent, "a", gc.reborrow()), "pending");
+        assert_eq!(result(agent, "b", gc.reborrow()), "loaded");
+
+        let load = host_hooks.take_load("./a.js");
+        agent.complete_module_load(load, source(""), gc.nogc());
+        agent.run_microtasks(100, gc.reborrow()).unwrap();
+        assert_eq!(result(agent, "a", gc.reborrow()), "loaded");
+        let order = evaluate(agent, "order.join() === 'b,a'", gc.reborrow());
+        assert_eq!(order, Value::Boolean(true));
+    }
+
+    #[test]
+    fn failed_load_rejects_its_own_graph() {
+        let host_hooks: &DeferringHostHooks = Box::leak(Box::default());
+        let mut agent = Agent::new(Options::default(), host_hooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        start_loading(agent, "missing", "import './missing.js';", gc.reborrow());
+        start_loading(agent, "present", "import './present.js';", gc.reborrow());
+        start_loading(agent, "invalid", "import './invalid.js';", gc.reborrow());
+
+        let load = host_hooks.take_load("./missing.js");
+        let error = evaluate(
+            agent,
+            "new TypeError('missing.js not found')",
+            gc.reborrow(),
+        );
+        agent.complete_module_load(load, Err(error.unbind()), gc.nogc());
+        let load = host_hooks.take_load("./invalid.js");
+        agent.complete_module_load(load, source("export export;"), gc.nogc());
+        agent.run_microtasks(100, gc.reborrow()).unwrap();
+        assert_eq!(
+            result(agent, "missing", gc.reborrow()),
+            "TypeError: missing.js not found"
+        );
+        assert!(result(agent, "invalid", gc.reborrow()).starts_with("SyntaxError: "));
+        assert_eq!(result(agent, "present", gc.reborrow()), "pending");
+
+        let load = host_hooks.take_load("./present.js");
+        agent.complete_module_load(load, source(""), gc.nogc());
+        agent.run_microtasks(100, gc.reborrow()).unwrap();
+        assert_eq!(result(agent, "present", gc.reborrow()), "loaded");
+    }
+}
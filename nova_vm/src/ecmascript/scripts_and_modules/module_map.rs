@@ -0,0 +1,619 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! An optional module loading layer for hosts.
+//!
+//! A [`ModuleMap`] resolves module specifiers to keys with a
+//! [`SpecifierResolver`], loads the source text of each key at most once
+//! using a host provided loader, and adds the loaded modules to the
+//! \[\[LoadedModules]] of their referrers. Successfully loaded modules are
+//! kept alive by the map until it is cleared, and failed loads stay failed:
+//! the same key always produces the same result.
+
+use ahash::AHashMap;
+use oxc_diagnostics::OxcDiagnostic;
+
+use crate::{
+    ecmascript::{
+        builtins::module::Module, execution::Agent, scripts_and_modules::module::parse_module,
+        types::String,
+    },
+    engine::{
+        Global,
+        context::{Bindable, NoGcScope},
+    },
+};
+
+/// A module specifier resolved to the key that identifies a module in a
+/// [`ModuleMap`], such as an absolute URL or path.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResolvedSpecifier(std::string::String);
+
+impl ResolvedSpecifier {
+    pub fn new(key: impl Into<std::string::String>) -> Self {
+        Self(key.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl core::fmt::Display for ResolvedSpecifier {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Source text of a module returned by the loader of a [`ModuleMap`].
+#[derive(Debug, Clone)]
+pub struct ModuleSource {
+    pub source_text: std::string::String,
+}
+
+impl From<std::string::String> for ModuleSource {
+    fn from(source_text: std::string::String) -> Self {
+        Self { source_text }
+    }
+}
+
+impl From<&str> for ModuleSource {
+    fn from(source_text: &str) -> Self {
+        Self {
+            source_text: source_text.to_owned(),
+        }
+    }
+}
+
+/// Resolves module specifiers to [`ModuleMap`] keys.
+///
+/// Resolution must be deterministic: the same referrer and specifier must
+/// always resolve to the same key.
+pub trait SpecifierResolver {
+    /// Resolve `specifier` imported by the module with the key `referrer`.
+    /// The referrer is None for a module loaded directly by the host.
+    ///
+    /// Returns a message describing the problem if the specifier cannot be
+    /// resolved.
+    fn resolve(
+        &self,
+        referrer: Option<&ResolvedSpecifier>,
+        specifier: &str,
+    ) -> Result<ResolvedSpecifier, std::string::String>;
+}
+
+/// Resolves specifiers as URLs such as `https://example.com/lib/main.js`.
+///
+/// Absolute URLs are used as is, and specifiers starting with `/`, `./` or
+/// `../` are resolved against the URL of the referrer, or against the base
+/// URL for modules loaded directly by the host. Bare specifiers such as
+/// `lib` cannot be resolved. The `.` and `..` segments of paths are removed.
+#[derive(Debug, Clone)]
+pub struct UrlResolver {
+    base: std::string::String,
+}
+
+impl UrlResolver {
+    /// Create a resolver with the given absolute base URL.
+    ///
+    /// ## Panics
+    ///
+    /// If the base URL has no scheme.
+    pub fn new(base: impl Into<std::string::String>) -> Self {
+        let base = base.into();
+        assert!(url_origin_len(&base).is_some(), "base URL has no scheme");
+        Self { base }
+    }
+}
+
+impl SpecifierResolver for UrlResolver {
+    fn resolve(
+        &self,
+        referrer: Option<&ResolvedSpecifier>,
+        specifier: &str,
+    ) -> Result<ResolvedSpecifier, std::string::String> {
+        if let Some(origin_len) = url_origin_len(specifier) {
+            let (origin, path) = specifier.split_at(origin_len);
+            return Ok(ResolvedSpecifier(format!(
+                "{origin}{}",
+                normalize_path(path)
+            )));
+        }
+        if !is_relative_specifier(specifier) {
+            return Err(format!(
+                "Cannot resolve bare module specifier '{specifier}'"
+            ));
+        }
+        let base = referrer.map_or(self.base.as_str(), ResolvedSpecifier::as_str);
+        let origin_len = url_origin_len(base).unwrap_or(0);
+        let (origin, base_path) = base.split_at(origin_len);
+        Ok(ResolvedSpecifier(format!(
+            "{origin}{}",
+            join_path(base_path, specifier)
+        )))
+    }
+}
+
+/// Resolves specifiers as file system like paths such as `/app/main.js`.
+///
+/// Specifiers starting with `./` or `../` are resolved against the path of
+/// the referrer; other specifiers, including bare specifiers, are used as is.
+/// The `.` and `..` segments of paths are removed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RelativePathResolver;
+
+impl SpecifierResolver for RelativePathResolver {
+    fn resolve(
+        &self,
+        referrer: Option<&ResolvedSpecifier>,
+        specifier: &str,
+    ) -> Result<ResolvedSpecifier, std::string::String> {
+        match referrer {
+            Some(referrer) if is_relative_specifier(specifier) && !specifier.starts_with('/') => {
+                Ok(ResolvedSpecifier(join_path(referrer.as_str(), specifier)))
+            }
+            _ => Ok(ResolvedSpecifier(normalize_path(specifier))),
+        }
+    }
+}
+
+/// Length of the `scheme://authority` or `scheme:` prefix of a URL, if it has
+/// a scheme.
+fn url_origin_len(url: &str) -> Option<usize> {
+    let scheme_len = url.find(':')?;
+    let scheme = &url[..scheme_len];
+    if !scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        || !scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    {
+        return None;
+    }
+    let rest = &url[scheme_len + 1..];
+    if let Some(authority) = rest.strip_prefix("//") {
+        let authority_len = authority.find('/').unwrap_or(authority.len());
+        Some(scheme_len + 3 + authority_len)
+    } else {
+        Some(scheme_len + 1)
+    }
+}
+
+fn is_relative_specifier(specifier: &str) -> bool {
+    specifier.starts_with('/') || specifier.starts_with("./") || specifier.starts_with("../")
+}
+
+/// Resolve a relative path against the directory of `base`.
+fn join_path(base: &str, path: &str) -> std::string::String {
+    if path.starts_with('/') {
+        return normalize_path(path);
+    }
+    let directory = base.rfind('/').map_or("", |index| &base[..=index]);
+    normalize_path(&format!("{directory}{path}"))
+}
+
+/// Remove the `.` and `..` segments of a path. Leading `..` segments of a
+/// relative path are kept, and those of an absolute path are dropped.
+fn normalize_path(path: &str) -> std::string::String {
+    let is_absolute = path.starts_with('/');
+    let mut segments: Vec<&str> = Vec::new();
+    let mut parts = path.split('/').peekable();
+    if is_absolute {
+        parts.next();
+    }
+    while let Some(part) = parts.next() {
+        let is_last = parts.peek().is_none();
+        match part {
+            "." => {}
+            ".." => {
+                if segments.last().is_some_and(|last| *last != "..") {
+                    segments.pop();
+                } else if !is_absolute {
+                    segments.push("..");
+                }
+            }
+            _ => {
+                segments.push(part);
+                continue;
+            }
+        }
+        // A path ending in `.` or `..` names a directory.
+        if is_last {
+            segments.push("");
+        }
+    }
+    let joined = segments.join("/");
+    if is_absolute {
+        format!("/{joined}")
+    } else {
+        joined
+    }
+}
+
+/// Error of loading a module through a [`ModuleMap`].
+#[derive(Debug, Clone)]
+pub enum ModuleLoadError<E> {
+    /// The specifier could not be resolved.
+    Resolution(std::string::String),
+    /// The loader failed to load the source text of the module.
+    Load(E),
+    /// The source text of the module could not be parsed.
+    Syntax(Vec<OxcDiagnostic>),
+}
+
+/// Modules loaded by a host, by their resolved specifier.
+///
+/// The map roots every module it has loaded, so the modules stay alive until
+/// [`ModuleMap::clear`] is called. A map must only be used with one Agent.
+#[derive(Debug)]
+pub struct ModuleMap<R: SpecifierResolver, E> {
+    resolver: R,
+    modules: AHashMap<ResolvedSpecifier, Result<Global<Module<'static>>, ModuleLoadError<E>>>,
+}
+
+impl<R: SpecifierResolver, E: Clone> ModuleMap<R, E> {
+    pub fn new(resolver: R) -> Self {
+        Self {
+            resolver,
+            modules: AHashMap::default(),
+        }
+    }
+
+    /// Returns the key of a module loaded by this map.
+    pub fn key_of(
+        &self,
+        agent: &Agent,
+        module: Module,
+        gc: NoGcScope,
+    ) -> Option<&ResolvedSpecifier> {
+        self.modules.iter().find_map(|(key, entry)| match entry {
+            Ok(global) if global.try_get(agent, gc).is_ok_and(|m| m == module) => Some(key),
+            _ => None,
+        })
+    }
+
+    /// Resolve `specifier` imported by `referrer` and return the module it
+    /// names, loading and parsing it if it has not been loaded yet.
+    ///
+    /// The loader is called at most once per resolved specifier: later calls
+    /// return the same module, or the same error if loading or parsing failed.
+    /// Modules are parsed in the current Realm. If `referrer` is given, the
+    /// module is also added to its \[\[LoadedModules]]; a referrer of None
+    /// loads a module directly for the host.
+    ///
+    /// ## Panics
+    ///
+    /// If `referrer` was not loaded by this map.
+    pub fn resolve_or_load<'gc>(
+        &mut self,
+        agent: &mut Agent,
+        referrer: Option<Module>,
+        specifier: String,
+        loader: impl FnMut(&ResolvedSpecifier) -> Result<ModuleSource, E>,
+        gc: NoGcScope<'gc, '_>,
+    ) -> Result<Module<'gc>, ModuleLoadError<E>> {
+        let referrer_key = referrer.map(|referrer| {
+            self.key_of(agent, referrer, gc)
+                .expect("referrer was not loaded by this ModuleMap")
+                .clone()
+        });
+        let key = self
+            .resolver
+            .resolve(referrer_key.as_ref(), &specifier.to_string_lossy(agent))
+            .map_err(ModuleLoadError::Resolution)?;
+        let module = self.load(agent, key, loader, gc)?;
+        if let Some(referrer) = referrer {
+            referrer.add_loaded_module(agent, specifier, module);
+        }
+        Ok(module)
+    }
+
+    /// Load all modules that `module` imports, directly or indirectly, so
+    /// that it can be linked.
+    ///
+    /// This stops at the first module that fails to load. Modules that take
+    /// part in an import cycle are reused while their own imports are still
+    /// being loaded.
+    ///
+    /// ## Panics
+    ///
+    /// If `module` was not loaded by this map.
+    pub fn load_requested_modules(
+        &mut self,
+        agent: &mut Agent,
+        module: Module,
+        mut loader: impl FnMut(&ResolvedSpecifier) -> Result<ModuleSource, E>,
+        gc: NoGcScope,
+    ) -> Result<(), ModuleLoadError<E>> {
+        let mut visited = vec![module.bind(gc)];
+        let mut queue = vec![module.bind(gc)];
+        while let Some(referrer) = queue.pop() {
+            for specifier in referrer.requested_modules(agent, gc) {
+                let module =
+                    self.resolve_or_load(agent, Some(referrer), specifier, &mut loader, gc)?;
+                if !visited.contains(&module) {
+                    visited.push(module);
+                    queue.push(module);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn load<'gc>(
+        &mut self,
+        agent: &mut Agent,
+        key: ResolvedSpecifier,
+        mut loader: impl FnMut(&ResolvedSpecifier) -> Result<ModuleSource, E>,
+        gc: NoGcScope<'gc, '_>,
+    ) -> Result<Module<'gc>, ModuleLoadError<E>> {
+        if let Some(entry) = self.modules.get(&key) {
+            return match entry {
+                Ok(global) => Ok(global.try_get(agent, gc).unwrap()),
+                Err(err) => Err(err.clone()),
+            };
+        }
+        let result = loader(&key)
+            .map_err(ModuleLoadError::Load)
+            .and_then(|source| {
+                let realm = agent.current_realm(gc);
+                let source_text = String::from_string(agent, source.source_text, gc);
+                parse_module(agent, source_text, realm, gc).map_err(ModuleLoadError::Syntax)
+            });
+        let entry = match result {
+            Ok(module) => Ok(Global::new(agent, module.unbind())),
+            Err(ref err) => Err(err.clone()),
+        };
+        self.modules.insert(key, entry);
+        result
+    }
+
+    /// Release all modules and errors held by the map.
+    pub fn clear(&mut self, agent: &Agent) {
+        for (_, entry) in self.modules.drain() {
+            if let Ok(global) = entry {
+                global.take(agent);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::{
+        ModuleLoadError, ModuleMap, ModuleSource, RelativePathResolver, ResolvedSpecifier,
+        SpecifierResolver, UrlResolver,
+    };
+    use crate::{
+        ecmascript::{
+            builtins::module::Module,
+            execution::{Agent, DefaultHostHooks, agent::Options, initialize_default_realm},
+            types::String,
+        },
+        engine::context::NoGcScope,
+    };
+
+    /// Loads module source text from a fixed set of files, recording the keys it
+    /// was called with.
+    struct Files {
+        files: HashMap<&'static str, &'static str>,
+        loaded: Vec<std::string::String>,
+    }
+
+    impl Files {
+        fn new(files: &[(&'static str, &'static str)]) -> Self {
+            Self {
+                files: files.iter().copied().collect(),
+                loaded: Vec::new(),
+            }
+        }
+
+        fn load(&mut self, key: &ResolvedSpecifier) -> Result<ModuleSource, std::string::String> {
+            self.loaded.push(key.as_str().to_owned());
+            self.files
+                .get(key.as_str())
+                .map(|&source| source.into())
+                .ok_or_else(|| format!("{key} not found"))
+        }
+    }
+
+    fn load_main<'gc, R: SpecifierResolver>(
+        agent: &mut Agent,
+        map: &mut ModuleMap<R, std::string::String>,
+        files: &mut Files,
+        specifier: &'static str,
+        gc: NoGcScope<'gc, '_>,
+    ) -> Result<Module<'gc>, ModuleLoadError<std::string::String>> {
+        let specifier = String::from_static_str(agent, specifier, gc);
+        let main = map.resolve_or_load(agent, None, specifier, |key| files.load(key), gc)?;
+        map.load_requested_modules(agent, main, |key| files.load(key), gc)?;
+        Ok(main)
+    }
+
+    fn export_names(agent: &Agent, module: Module, gc: NoGcScope) -> Vec<std::string::String> {
+        module
+            .export_names(agent, gc)
+            .into_iter()
+            .map(|name| name.as_str(agent).to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn diamond_graph_loads_each_module_once() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let mut files = Files::new(&[
+            (
+                "/main.js",
+                "export * from './left.js'; export * from './right.js';",
+            ),
+            (
+                "/left.js",
+                "export { shared as left } from './shared.js'; export * from './shared.js';",
+            ),
+            (
+                "/right.js",
+                "export { shared as right } from './shared.js';",
+            ),
+            ("/shared.js", "export const shared = 1;"),
+        ]);
+        let mut map = ModuleMap::new(RelativePathResolver);
+        let gc = gc.nogc();
+        let main = load_main(agent, &mut map, &mut files, "/main.js", gc).unwrap();
+        main.link(agent, gc).unwrap();
+        assert_eq!(export_names(agent, main, gc), ["left", "right", "shared"]);
+        // Loading the graph again reuses every module.
+        let again = load_main(agent, &mut map, &mut files, "/main.js", gc).unwrap();
+        assert_eq!(again, main);
+        map.clear(agent);
+        files.loaded.sort();
+        assert_eq!(
+            files.loaded,
+            ["/left.js", "/main.js", "/right.js", "/shared.js"]
+        );
+    }
+
+    #[test]
+    fn import_cycle_reuses_the_loading_module() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let mut files = Files::new(&[
+            ("/a.js", "export * from './b.js'; export const a = 1;"),
+            ("/b.js", "export * from './a.js'; export const b = 2;"),
+        ]);
+        let mut map = ModuleMap::new(RelativePathResolver);
+        let gc = gc.nogc();
+        let a = load_main(agent, &mut map, &mut files, "/a.js", gc).unwrap();
+        a.link(agent, gc).unwrap();
+        assert_eq!(export_names(agent, a, gc), ["a", "b"]);
+        map.clear(agent);
+        assert_eq!(files.loaded, ["/a.js", "/b.js"]);
+    }
+
+    #[test]
+    fn relative_specifiers_resolve_against_nested_referrers() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let mut files = Files::new(&[
+            ("/app/main.js", "import './lib/x.js';"),
+            (
+                "/app/lib/x.js",
+                "import '../util/y.js'; import './z.js'; import '/root.js'; import 'bare';",
+            ),
+            ("/app/util/y.js", "import '../../root.js';"),
+            ("/app/lib/z.js", ""),
+            ("/root.js", ""),
+            ("bare", ""),
+        ]);
+        let mut map = ModuleMap::new(RelativePathResolver);
+        let gc = gc.nogc();
+        let main = load_main(agent, &mut map, &mut files, "/app/main.js", gc).unwrap();
+        main.link(agent, gc).unwrap();
+        map.clear(agent);
+        files.loaded.sort();
+        assert_eq!(
+            files.loaded,
+            [
+                "/app/lib/x.js",
+                "/app/lib/z.js",
+                "/app/main.js",
+                "/app/util/y.js",
+                "/root.js",
+                "bare"
+            ]
+        );
+    }
+
+    #[test]
+    fn url_resolver() {
+        let resolver = UrlResolver::new("https://example.com/app/");
+        let resolve = |referrer: Option<&str>, specifier: &str| {
+            resolver
+                .resolve(referrer.map(ResolvedSpecifier::new).as_ref(), specifier)
+                .map(|key| key.as_str().to_owned())
+        };
+        assert_eq!(
+            resolve(None, "./main.js").unwrap(),
+            "https://example.com/app/main.js"
+        );
+        let referrer = Some("https://example.com/app/lib/x.js");
+        assert_eq!(
+            resolve(referrer, "../util/./y.js").unwrap(),
+            "https://example.com/app/util/y.js"
+        );
+        assert_eq!(
+            resolve(referrer, "/root.js").unwrap(),
+            "https://example.com/root.js"
+        );
+        assert_eq!(
+            resolve(referrer, "../../../../above.js").unwrap(),
+            "https://example.com/above.js"
+        );
+        assert_eq!(
+            resolve(referrer, "https://cdn.example.org/a/../b.js").unwrap(),
+            "https://cdn.example.org/b.js"
+        );
+        assert_eq!(
+            resolve(referrer, "lib").unwrap_err(),
+            "Cannot resolve bare module specifier 'lib'"
+        );
+    }
+
+    #[test]
+    fn failed_load_stays_failed() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let mut files = Files::new(&[
+            ("/main.js", "import './a.js'; import './b.js';"),
+            ("/a.js", "import './missing.js';"),
+            ("/b.js", "import './missing.js'; import './syntax.js';"),
+            ("/syntax.js", "export {"),
+        ]);
+        let mut map = ModuleMap::new(RelativePathResolver);
+        let gc = gc.nogc();
+        for _ in 0..2 {
+            let Err(ModuleLoadError::Load(message)) =
+                load_main(agent, &mut map, &mut files, "/main.js", gc)
+            else {
+                panic!("expected a load error");
+            };
+            assert_eq!(message, "/missing.js not found");
+        }
+        // The failure is also reported for other importers of the module.
+        let referrer = String::from_static_str(agent, "/b.js", gc);
+        let b = map
+            .resolve_or_load(agent, None, referrer, |key| files.load(key), gc)
+            .unwrap();
+        let missing = String::from_static_str(agent, "./missing.js", gc);
+        let Err(ModuleLoadError::Load(message)) =
+            map.resolve_or_load(agent, Some(b), missing, |key| files.load(key), gc)
+        else {
+            panic!("expected a load error");
+        };
+        assert_eq!(message, "/missing.js not found");
+        let syntax = String::from_static_str(agent, "./syntax.js", gc);
+        for _ in 0..2 {
+            let result = map.resolve_or_load(agent, Some(b), syntax, |key| files.load(key), gc);
+            assert!(matches!(result, Err(ModuleLoadError::Syntax(_))));
+        }
+        map.clear(agent);
+        assert_eq!(
+            files.loaded,
+            ["/main.js", "/a.js", "/b.js", "/missing.js", "/syntax.js"]
+        );
+    }
+}
@@ -2,13 +2,15 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use crate::ecmascript::builtins::error::ErrorMessage;
 use crate::engine::context::{Bindable, GcScope, GcToken, NoGcScope};
 use crate::engine::rootable::{HeapRootData, HeapRootRef, Rootable, Scopable};
 use crate::{
+    Heap,
     ecmascript::{
         execution::{
-            Agent, ECMAScriptCode, Environment, ExecutionContext, GlobalEnvironment, JsResult,
-            Realm, agent::ExceptionType,
+            Agent, AgentOperation, ECMAScriptCode, Environment, ExecutionContext,
+            GlobalEnvironment, JsResult, Realm, ReentrancyError, agent::ExceptionType,
         },
         scripts_and_modules::ScriptOrModule,
         syntax_directed_operations::{
@@ -21,8 +23,8 @@ use crate::{
         },
         types::{BUILTIN_STRING_MEMORY, IntoValue, String, Value},
     },
-    engine::{Executable, Vm},
-    heap::{CompactionLists, HeapMarkAndSweep, WorkQueues},
+    engine::{Executable, Vm, VmState},
+    heap::{CompactionLists, HeapAccessError, HeapIndexable, HeapMarkAndSweep, WorkQueues},
 };
 use ahash::AHashSet;
 use core::{
@@ -36,6 +38,7 @@ use oxc_diagnostics::OxcDiagnostic;
 use oxc_ecmascript::BoundNames;
 use oxc_span::SourceType;
 
+use super::script_analysis::{ScriptAnalysis, analyze_program};
 use super::source_code::SourceCode;
 
 pub type HostDefined = &'static mut dyn Any;
@@ -80,6 +83,22 @@ impl Script<'_> {
     pub(crate) const fn into_u32(self) -> u32 {
         self.0
     }
+
+    /// Analyzes which capabilities the script uses, such as `eval` or `with`
+    /// statements, and which global identifiers it refers to.
+    ///
+    /// The analysis is purely syntactic: nothing is evaluated, so embedders
+    /// can use it to refuse a script before running it.
+    pub fn analyze(self, agent: &Agent) -> ScriptAnalysis {
+        analyze_program(&agent[self].ecmascript_code)
+    }
+}
+
+impl<'a> Script<'a> {
+    /// Returns the SourceCode that the script was parsed from.
+    pub fn source_code(self, agent: &Agent) -> SourceCode<'a> {
+        agent[self].source_code
+    }
 }
 
 impl Index<Script<'_>> for Agent {
@@ -92,6 +111,7 @@ impl Index<Script<'_>> for Agent {
 
 impl IndexMut<Script<'_>> for Agent {
     fn index_mut(&mut self, index: Script) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.scripts[index]
     }
 }
@@ -116,6 +136,23 @@ impl IndexMut<Script<'_>> for Vec<Option<ScriptRecord<'static>>> {
     }
 }
 
+impl HeapIndexable for Script<'_> {
+    type Data = ScriptRecord<'static>;
+    const KIND: &'static str = "Script";
+
+    fn heap_index(self) -> usize {
+        self.into_index()
+    }
+
+    fn heap_vec(heap: &Heap) -> &[Option<Self::Data>] {
+        &heap.scripts
+    }
+
+    fn heap_vec_mut(heap: &mut Heap) -> &mut [Option<Self::Data>] {
+        &mut heap.scripts
+    }
+}
+
 impl HeapMarkAndSweep for Script<'static> {
     fn mark_values(&self, queues: &mut WorkQueues) {
         queues.scripts.push(*self);
@@ -281,7 +318,8 @@ pub fn parse_script<'a>(
 
     // SAFETY: Script keeps the SourceCode reference alive in the Heap, thus
     // making the Program's references point to a live Allocator.
-    let parse_result = unsafe { SourceCode::parse_source(agent, source_text, source_type, gc) };
+    let parse_result =
+        unsafe { SourceCode::parse_source(agent, source_text, source_type, false, gc) };
 
     let (program, source_code) = match parse_result {
         // 2. If script is a List of errors, return script.
@@ -308,16 +346,136 @@ pub fn parse_script<'a>(
     Ok(script)
 }
 
+/// Evaluates a Script by handle, checking that the handle is valid first.
+///
+/// Returns an error naming the stale identifier if the Script or its Realm do
+/// not point to live heap data in this Agent, eg. because the Script was
+/// garbage collected or was parsed by a different Agent.
+pub fn checked_script_evaluation<'a>(
+    agent: &mut Agent,
+    script: Script,
+    gc: GcScope<'a, '_>,
+) -> Result<JsResult<'a, Value<'a>>, HeapAccessError> {
+    let realm = agent.heap.try_get(script)?.realm;
+    agent.heap.try_get(realm)?;
+    Ok(script_evaluation(agent, script, gc))
+}
+
 /// ### [16.1.6 ScriptEvaluation ( scriptRecord )](https://tc39.es/ecma262/#sec-runtime-semantics-scriptevaluation)
 ///
 /// The abstract operation ScriptEvaluation takes argument scriptRecord (a
 /// Script Record) and returns either a normal completion containing an
 /// ECMAScript language value or an abrupt completion.
+///
+/// ## Panics
+///
+/// If the Agent is poisoned or scripts may not be evaluated in its current
+/// [`ExecutionState`](crate::ecmascript::execution::ExecutionState).
 pub fn script_evaluation<'a>(
+    agent: &mut Agent,
+    script: Script,
+    gc: GcScope<'a, '_>,
+) -> JsResult<'a, Value<'a>> {
+    try_script_evaluation(agent, script, gc)
+        .unwrap_or_else(|err| panic!("Cannot evaluate Script: {err}"))
+}
+
+/// Evaluates a Script, returning an error if the Agent is poisoned or
+/// scripts may not be evaluated in its current
+/// [`ExecutionState`](crate::ecmascript::execution::ExecutionState).
+pub fn try_script_evaluation<'a>(
+    agent: &mut Agent,
+    script: Script,
+    gc: GcScope<'a, '_>,
+) -> Result<JsResult<'a, Value<'a>>, ReentrancyError> {
+    let _guard = agent.enter_operation(AgentOperation::Evaluate)?;
+    Ok(evaluate_script(agent, script, gc))
+}
+
+fn evaluate_script<'a>(
+    agent: &mut Agent,
+    script: Script,
+    mut gc: GcScope<'a, '_>,
+) -> JsResult<'a, Value<'a>> {
+    let result = match prepare_script_evaluation(agent, script, gc.reborrow())
+        .unbind()
+        .bind(gc.nogc())
+    {
+        Ok(bytecode) => {
+            let bytecode = bytecode.scope(agent, gc.nogc());
+            // a. Set result to Completion(Evaluation of script).
+            // b. If result.[[Type]] is normal and result.[[Value]] is empty, then
+            // i. Set result to NormalCompletion(undefined).
+            // NOTE: The bytecode is reclaimed by the garbage collector once
+            // nothing refers to it anymore.
+            Vm::execute(agent, bytecode, None, gc.reborrow())
+                .into_js_result()
+                .unbind()
+                .bind(gc.nogc())
+        }
+        Err(err) => Err(err),
+    };
+    finish_script_evaluation(agent, result.unbind(), gc)
+}
+
+/// Starts evaluating a Script one instruction at a time with [`Vm::step`].
+///
+/// This performs ScriptEvaluation up to the evaluation of the script body:
+/// The script's execution context is pushed onto the execution context stack
+/// and its global declarations are instantiated. The returned Executable is
+/// the compiled script body, and the returned VmState is ready to execute its
+/// first instruction. Once a step returns or throws, the evaluation must be
+/// completed with [`finish_script_stepping`].
+///
+/// If instantiating the global declarations throws, the evaluation is
+/// completed and the error is returned.
+///
+/// ## Panics
+///
+/// If the Agent is poisoned or scripts may not be evaluated in its current
+/// [`ExecutionState`](crate::ecmascript::execution::ExecutionState).
+pub fn start_script_stepping<'a>(
     agent: &mut Agent,
     script: Script,
     mut gc: GcScope<'a, '_>,
+) -> JsResult<'a, (Executable<'a>, VmState<'a>)> {
+    agent
+        .check_reentrancy(AgentOperation::Evaluate)
+        .unwrap_or_else(|err| panic!("Cannot evaluate Script: {err}"));
+    match prepare_script_evaluation(agent, script, gc.reborrow()).unbind() {
+        Ok(bytecode) => {
+            let gc = gc.into_nogc();
+            Ok((bytecode.bind(gc), VmState::new(agent, gc)))
+        }
+        Err(err) => {
+            let Err(err) = finish_script_evaluation(agent, Err(err), gc) else {
+                unreachable!()
+            };
+            Err(err)
+        }
+    }
+}
+
+/// Completes a Script evaluation started with [`start_script_stepping`] with
+/// the Value returned or the error thrown by the last step.
+///
+/// The script's execution context is removed from the execution context
+/// stack, and an error is reported to the host as an uncaught exception.
+pub fn finish_script_stepping<'a>(
+    agent: &mut Agent,
+    result: JsResult<Value>,
+    gc: GcScope<'a, '_>,
 ) -> JsResult<'a, Value<'a>> {
+    finish_script_evaluation(agent, result, gc)
+}
+
+/// Performs the steps of ScriptEvaluation up to the evaluation of the script
+/// body, and returns the script body compiled into bytecode.
+fn prepare_script_evaluation<'a>(
+    agent: &mut Agent,
+    script: Script,
+    mut gc: GcScope<'a, '_>,
+) -> JsResult<'a, Executable<'a>> {
     let script = script.bind(gc.nogc());
     let script_record = &agent[script];
     let realm_id = script_record.realm;
@@ -370,37 +528,42 @@ pub fn script_evaluation<'a>(
         global_env.unbind(),
         gc.reborrow(),
     )
-    .unbind()
-    .bind(gc.nogc());
+    .unbind();
+    let gc = gc.into_nogc();
+    let result = result.bind(gc);
 
     let Some(ScriptOrModule::Script(script)) = agent.running_execution_context().script_or_module
     else {
         panic!("Expected Script");
     };
-    let script = script.bind(gc.nogc());
+    let script = script.bind(gc);
 
     // 13. If result.[[Type]] is normal, then
-    let result: JsResult<Value> = match result {
-        Ok(_) => {
-            let bytecode =
-                Executable::compile_script(agent, script, gc.nogc()).scope(agent, gc.nogc());
-            // a. Set result to Completion(Evaluation of script).
-            // b. If result.[[Type]] is normal and result.[[Value]] is empty, then
-            // i. Set result to NormalCompletion(undefined).
-            let result = Vm::execute(agent, bytecode.clone(), None, gc.reborrow())
-                .into_js_result()
-                .unbind()
-                .bind(gc.into_nogc());
-            // SAFETY: The bytecode is not accessible by anyone anymore and no one
-            // will try to re-run it.
-            unsafe { bytecode.take(agent).try_drop(agent) };
-            result
-        }
-        Err(err) => Err(err.unbind().bind(gc.into_nogc())),
-    };
+    // a. Set result to Completion(Evaluation of script).
+    // NOTE: The script is evaluated by the caller.
+    result?;
+    Ok(Executable::compile_script(agent, script, gc))
+}
 
+/// Performs the steps of ScriptEvaluation after the evaluation of the script
+/// body.
+fn finish_script_evaluation<'a>(
+    agent: &mut Agent,
+    result: JsResult<Value>,
+    gc: GcScope<'a, '_>,
+) -> JsResult<'a, Value<'a>> {
     // 14. Suspend scriptContext and remove it from the execution context stack.
-    _ = agent.pop_execution_context();
+    // NOTE: Script evaluation may be nested inside a builtin function call
+    // made by another script. Any contexts pushed during our evaluation must
+    // have been popped by now, so that the top of the stack is our own.
+    // NOTE: The Script may have been moved by garbage collection; the
+    // execution context is a root and holds its current index.
+    let Some(ScriptOrModule::Script(script)) = agent
+        .pop_execution_context()
+        .and_then(|context| context.script_or_module)
+    else {
+        panic!("Expected Script");
+    };
 
     // TODO: 15. Assert: The execution context stack is not empty.
     // This is not currently true as we do not push an "empty" context stack to the root before running script evaluation.
@@ -411,7 +574,10 @@ pub fn script_evaluation<'a>(
     // NOTE: This is done automatically.
 
     // 17. Return ? result.
-    result
+    match result {
+        Ok(value) => Ok(value.unbind().bind(gc.into_nogc())),
+        Err(err) => Err(agent.report_uncaught_exception(err.unbind(), Some(script), gc)),
+    }
 }
 
 /// ### [16.1.7 GlobalDeclarationInstantiation ( script, env )](https://tc39.es/ecma262/#sec-globaldeclarationinstantiation)
@@ -468,13 +634,11 @@ pub(crate) fn global_declaration_instantiation<'a>(
             // d. If hasRestrictedGlobal is true, throw a SyntaxError exception.
             || env.unbind().has_restricted_global_property(agent, name.unbind(), gc.reborrow()).unbind()?.bind(gc.nogc())
         {
-            let error_message = format!(
-                "Redeclaration of restricted global property '{}'.",
-                name_atom.as_str()
-            );
-            return Err(agent.throw_exception(
+            let name = String::from_str(agent, name_atom.as_str(), gc.nogc());
+            return Err(agent.throw_exception_with_deferred_message(
                 ExceptionType::SyntaxError,
-                error_message,
+                ErrorMessage::RestrictedGlobalRedeclaration,
+                [name.unbind().into()],
                 gc.into_nogc(),
             ));
         }
@@ -486,11 +650,10 @@ pub(crate) fn global_declaration_instantiation<'a>(
         // a. If env.HasLexicalDeclaration(name) is true, throw a SyntaxError exception.
         let name = String::from_str(agent, name.as_str(), gc.nogc());
         if env.has_lexical_declaration(agent, name) {
-            let error_message =
-                format!("Redeclaration of lexical binding '{}'.", name.as_str(agent));
-            return Err(agent.throw_exception(
+            return Err(agent.throw_exception_with_deferred_message(
                 ExceptionType::SyntaxError,
-                error_message,
+                ErrorMessage::LexicalBindingRedeclaration,
+                [name.unbind().into()],
                 gc.into_nogc(),
             ));
         }
@@ -524,13 +687,11 @@ pub(crate) fn global_declaration_instantiation<'a>(
                     .bind(gc.nogc());
                 // 2. If fnDefinable is false, throw a TypeError exception.
                 if !fn_definable {
-                    let error_message = format!(
-                        "Cannot declare of global function '{}'.",
-                        function_name.as_str()
-                    );
-                    return Err(agent.throw_exception(
+                    let fn_name = String::from_str(agent, function_name.as_str(), gc.nogc());
+                    return Err(agent.throw_exception_with_deferred_message(
                         ExceptionType::TypeError,
-                        error_message,
+                        ErrorMessage::CannotDeclareGlobalFunction,
+                        [fn_name.unbind().into()],
                         gc.into_nogc(),
                     ));
                 }
@@ -542,7 +703,8 @@ pub(crate) fn global_declaration_instantiation<'a>(
     }
 
     // 9. Let declaredVarNames be a new empty List.
-    let mut declared_var_names = AHashSet::default();
+    let mut declared_var_names_strings = AHashSet::default();
+    let mut declared_var_names = Vec::new();
     // 10. For each element d of varDeclarations, do
     for d in var_declarations {
         // a. If d is either a VariableDeclaration, a ForBinding, or a BindingIdentifier, then
@@ -552,14 +714,14 @@ pub(crate) fn global_declaration_instantiation<'a>(
             d.id.bound_names(&mut |identifier| {
                 bound_names.push(identifier.name);
             });
-            for vn in bound_names {
+            for vn_atom in bound_names {
                 // 1. If declaredFunctionNames does not contain vn, then
-                if !declared_function_names.contains(&vn) {
+                if !declared_function_names.contains(&vn_atom) {
                     // a. Let vnDefinable be ? env.CanDeclareGlobalVar(vn).
                     // TODO: This is a very problematic area for lifetimes.
                     // CanDeclareGlobalVar can trigger GC, but we also need to
                     // hash the strings to eliminate duplicates...
-                    let vn = String::from_str(agent, vn.as_str(), gc.nogc()).unbind();
+                    let vn = String::from_str(agent, vn_atom.as_str(), gc.nogc()).unbind();
                     let vn_definable = scoped_env
                         .get(agent)
                         .can_declare_global_var(agent, vn, gc.reborrow())
@@ -567,17 +729,19 @@ pub(crate) fn global_declaration_instantiation<'a>(
                         .bind(gc.nogc());
                     // b. If vnDefinable is false, throw a TypeError exception.
                     if !vn_definable {
-                        let error_message =
-                            format!("Cannot declare global variable '{}'.", vn.as_str(agent));
-                        return Err(agent.throw_exception(
+                        let vn = String::from_str(agent, vn_atom.as_str(), gc.nogc());
+                        return Err(agent.throw_exception_with_deferred_message(
                             ExceptionType::TypeError,
-                            error_message,
+                            ErrorMessage::CannotDeclareGlobalVariable,
+                            [vn.unbind().into()],
                             gc.into_nogc(),
                         ));
                     }
                     // c. If declaredVarNames does not contain vn, then
-                    // i. Append vn to declaredVarNames.
-                    declared_var_names.insert(vn);
+                    if declared_var_names_strings.insert(vn_atom) {
+                        // i. Append vn to declaredVarNames.
+                        declared_var_names.push(vn);
+                    }
                 }
             }
         }
@@ -677,47 +841,123 @@ pub(crate) fn global_declaration_instantiation<'a>(
 }
 
 #[cfg(test)]
-mod test {
-    use crate::ecmascript::builtins::{Array, BuiltinFunctionArgs, create_builtin_function};
+pub(crate) mod test {
+    use crate::ecmascript::builtins::{
+        Array, BuiltinFunctionArgs, ECMAScriptFunction, create_builtin_function,
+    };
     use crate::ecmascript::execution::JsResult;
-    use crate::ecmascript::execution::agent::ExceptionType;
+    use crate::ecmascript::execution::agent::{ExceptionType, get_active_script_or_module};
+    use crate::ecmascript::scripts_and_modules::ScriptOrModule;
     use crate::engine::context::{Bindable, GcScope};
-    use crate::engine::rootable::Scopable;
+    use crate::engine::rootable::{HeapRootData, Scopable};
     use crate::engine::unwrap_try;
+    use crate::engine::{Instruction, InstructionIter};
+    use crate::heap::{HeapAccessError, HeapAccessErrorReason};
     use crate::{
         SmallInteger,
         ecmascript::{
-            abstract_operations::operations_on_objects::create_data_property_or_throw,
+            abstract_operations::{
+                operations_on_objects::create_data_property_or_throw,
+                type_conversion::number_to_property_key,
+            },
             builtins::{ArgumentsList, Behaviour},
-            execution::{Agent, DefaultHostHooks, agent::Options, initialize_default_realm},
-            scripts_and_modules::script::{parse_script, script_evaluation},
-            types::{InternalMethods, IntoValue, Number, Object, PropertyKey, String, Value},
+            execution::{
+                Agent, DefaultHostHooks, NestedGcRootError,
+                agent::{HostHooks, Job, MicrotaskCheckpoint, Options},
+                initialize_default_realm,
+            },
+            scripts_and_modules::script::{
+                checked_script_evaluation, parse_script, script_evaluation,
+            },
+            types::{
+                BUILTIN_STRING_MEMORY, InternalMethods, IntoValue, Number, Object, PropertyKey,
+                PropertyKeySet, String, Value,
+            },
         },
     };
+    use std::{cell::RefCell, collections::VecDeque};
+
+    /// Options for the tests. Setting the `NOVA_GC_STRESS` environment
+    /// variable runs them with [`Options::gc_stress`].
+    fn test_options() -> Options {
+        Options {
+            gc_stress: std::env::var_os("NOVA_GC_STRESS").is_some(),
+            ..Default::default()
+        }
+    }
+
+    /// Evaluates `source` as a script in the current realm.
+    pub(crate) fn evaluate<'gc>(
+        agent: &mut Agent,
+        source: &'static str,
+        gc: GcScope<'gc, '_>,
+    ) -> Value<'gc> {
+        let source_text = String::from_static_str(agent, source, gc.nogc());
+        agent.run_script(source_text.unbind(), gc).unwrap()
+    }
+
+    /// Runs `source` in a new default realm and asserts that it evaluates to
+    /// true.
+    fn assert_script_true(source: &'static str) {
+        assert_all_true(&[source]);
+    }
+
+    /// Runs each of `sources` in turn in one new default realm and asserts
+    /// that they all evaluate to true.
+    fn assert_all_true(sources: &[&'static str]) {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        for source in sources {
+            let result = evaluate(agent, source, gc.reborrow()).unbind();
+            assert_eq!(result, Value::Boolean(true), "{source}");
+        }
+    }
+
+    /// Host hooks that queue promise jobs for [`Agent::run_microtasks`].
+    #[derive(Default)]
+    struct QueueingHostHooks {
+        jobs: RefCell<VecDeque<Job>>,
+    }
+
+    impl core::fmt::Debug for QueueingHostHooks {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.debug_struct("QueueingHostHooks").finish_non_exhaustive()
+        }
+    }
+
+    impl HostHooks for QueueingHostHooks {
+        fn enqueue_promise_job(&self, job: Job) {
+            self.jobs.borrow_mut().push_back(job);
+        }
+
+        fn dequeue_promise_job(&self) -> Option<Job> {
+            self.jobs.borrow_mut().pop_front()
+        }
+    }
 
     #[test]
     fn empty_script() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
-
-        let source_text = String::from_static_str(&mut agent, "", gc.nogc());
-        let result = agent
-            .run_script(source_text.unbind(), gc.reborrow())
-            .unwrap();
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let result = agent.with_gc(|agent, mut gc| {
+            initialize_default_realm(agent, gc.reborrow());
+            let source_text = String::from_static_str(agent, "", gc.nogc());
+            agent.run_script(source_text.unbind(), gc).unwrap().unbind()
+        });
 
         assert_eq!(result, Value::Undefined);
     }
 
     #[test]
     fn basic_constants() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
 
-        let source_text = String::from_static_str(&mut agent, "true", gc.nogc());
+        let source_text = String::from_static_str(agent, "true", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
@@ -727,12 +967,12 @@ mod test {
 
     #[test]
     fn unary_minus() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
 
-        let source_text = String::from_static_str(&mut agent, "-2", gc.nogc());
+        let source_text = String::from_static_str(agent, "-2", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
@@ -742,12 +982,12 @@ mod test {
 
     #[test]
     fn unary_void() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
 
-        let source_text = String::from_static_str(&mut agent, "void (2 + 2 + 6)", gc.nogc());
+        let source_text = String::from_static_str(agent, "void (2 + 2 + 6)", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
@@ -757,12 +997,12 @@ mod test {
 
     #[test]
     fn unary_plus() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
 
-        let source_text = String::from_static_str(&mut agent, "+(54)", gc.nogc());
+        let source_text = String::from_static_str(agent, "+(54)", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
@@ -772,12 +1012,12 @@ mod test {
 
     #[test]
     fn logical_not() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
 
-        let source_text = String::from_static_str(&mut agent, "!true", gc.nogc());
+        let source_text = String::from_static_str(agent, "!true", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
@@ -787,12 +1027,12 @@ mod test {
 
     #[test]
     fn bitwise_not() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
 
-        let source_text = String::from_static_str(&mut agent, "~0b1111", gc.nogc());
+        let source_text = String::from_static_str(agent, "~0b1111", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
@@ -802,102 +1042,102 @@ mod test {
 
     #[test]
     fn unary_typeof() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
         // let realm = agent.current_realm_id(gc.nogc());
 
-        let source_text = String::from_static_str(&mut agent, "typeof undefined", gc.nogc());
+        let source_text = String::from_static_str(agent, "typeof undefined", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert_eq!(
             result.unbind(),
-            Value::from_static_str(&mut agent, "undefined", gc.nogc())
+            Value::from_static_str(agent, "undefined", gc.nogc())
         );
 
-        let source_text = String::from_static_str(&mut agent, "typeof null", gc.nogc());
+        let source_text = String::from_static_str(agent, "typeof null", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert_eq!(
             result.unbind(),
-            Value::from_static_str(&mut agent, "object", gc.nogc())
+            Value::from_static_str(agent, "object", gc.nogc())
         );
 
-        let source_text = String::from_static_str(&mut agent, "typeof \"string\"", gc.nogc());
+        let source_text = String::from_static_str(agent, "typeof \"string\"", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert_eq!(
             result.unbind(),
-            Value::from_static_str(&mut agent, "string", gc.nogc())
+            Value::from_static_str(agent, "string", gc.nogc())
         );
 
-        let source_text = String::from_static_str(&mut agent, "typeof Symbol()", gc.nogc());
+        let source_text = String::from_static_str(agent, "typeof Symbol()", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert_eq!(
             result.unbind(),
-            Value::from_static_str(&mut agent, "symbol", gc.nogc())
+            Value::from_static_str(agent, "symbol", gc.nogc())
         );
 
-        let source_text = String::from_static_str(&mut agent, "typeof true", gc.nogc());
+        let source_text = String::from_static_str(agent, "typeof true", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert_eq!(
             result.unbind(),
-            Value::from_static_str(&mut agent, "boolean", gc.nogc())
+            Value::from_static_str(agent, "boolean", gc.nogc())
         );
 
-        let source_text = String::from_static_str(&mut agent, "typeof 3", gc.nogc());
+        let source_text = String::from_static_str(agent, "typeof 3", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert_eq!(
             result.unbind(),
-            Value::from_static_str(&mut agent, "number", gc.nogc())
+            Value::from_static_str(agent, "number", gc.nogc())
         );
 
-        let source_text = String::from_static_str(&mut agent, "typeof 3n", gc.nogc());
+        let source_text = String::from_static_str(agent, "typeof 3n", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert_eq!(
             result.unbind(),
-            Value::from_static_str(&mut agent, "bigint", gc.nogc())
+            Value::from_static_str(agent, "bigint", gc.nogc())
         );
 
-        let source_text = String::from_static_str(&mut agent, "typeof {}", gc.nogc());
+        let source_text = String::from_static_str(agent, "typeof {}", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert_eq!(
             result.unbind(),
-            Value::from_static_str(&mut agent, "object", gc.nogc())
+            Value::from_static_str(agent, "object", gc.nogc())
         );
 
-        let source_text = String::from_static_str(&mut agent, "typeof (function() {})", gc.nogc());
+        let source_text = String::from_static_str(agent, "typeof (function() {})", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert_eq!(
             result.unbind(),
-            Value::from_static_str(&mut agent, "function", gc.nogc())
+            Value::from_static_str(agent, "function", gc.nogc())
         );
     }
 
     #[test]
     fn binary_add() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
 
-        let source_text = String::from_static_str(&mut agent, "2 + 2 + 6", gc.nogc());
+        let source_text = String::from_static_str(agent, "2 + 2 + 6", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
@@ -907,12 +1147,12 @@ mod test {
 
     #[test]
     fn var_assign() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
 
-        let source_text = String::from_static_str(&mut agent, "var foo = 3;", gc.nogc());
+        let source_text = String::from_static_str(agent, "var foo = 3;", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
@@ -921,19 +1161,19 @@ mod test {
 
     #[test]
     fn empty_object() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
 
-        let source_text = String::from_static_str(&mut agent, "var foo = {};", gc.nogc());
+        let source_text = String::from_static_str(agent, "var foo = {};", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert!(result.is_undefined());
-        let key = PropertyKey::from_static_str(&mut agent, "foo", gc.nogc());
+        let key = PropertyKey::from_static_str(agent, "foo", gc.nogc());
         let foo = unwrap_try(agent.current_global_object(gc.nogc()).try_get_own_property(
-            &mut agent,
+            agent,
             key,
             gc.nogc(),
         ))
@@ -945,7 +1185,7 @@ mod test {
         assert!(
             result
                 .unbind()
-                .internal_own_property_keys(&mut agent, gc)
+                .internal_own_property_keys(agent, gc)
                 .unwrap()
                 .is_empty()
         );
@@ -953,19 +1193,19 @@ mod test {
 
     #[test]
     fn non_empty_object() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
 
-        let source_text = String::from_static_str(&mut agent, "var foo = { a: 3 };", gc.nogc());
+        let source_text = String::from_static_str(agent, "var foo = { a: 3 };", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert!(result.is_undefined());
-        let key = PropertyKey::from_static_str(&mut agent, "foo", gc.nogc());
+        let key = PropertyKey::from_static_str(agent, "foo", gc.nogc());
         let foo = unwrap_try(agent.current_global_object(gc.nogc()).try_get_own_property(
-            &mut agent,
+            agent,
             key,
             gc.nogc(),
         ))
@@ -974,14 +1214,10 @@ mod test {
         .unwrap();
         assert!(foo.is_object());
         let result = Object::try_from(foo).unwrap();
-        let key = PropertyKey::from_static_str(&mut agent, "a", gc.nogc());
-        assert!(unwrap_try(result.try_has_property(
-            &mut agent,
-            key,
-            gc.nogc()
-        )));
+        let key = PropertyKey::from_static_str(agent, "a", gc.nogc());
+        assert!(unwrap_try(result.try_has_property(agent, key, gc.nogc())));
         assert_eq!(
-            unwrap_try(result.try_get_own_property(&mut agent, key, gc.nogc()))
+            unwrap_try(result.try_get_own_property(agent, key, gc.nogc()))
                 .unwrap()
                 .value,
             Some(Value::from(3))
@@ -990,19 +1226,19 @@ mod test {
 
     #[test]
     fn empty_array() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
 
-        let source_text = String::from_static_str(&mut agent, "var foo = [];", gc.nogc());
+        let source_text = String::from_static_str(agent, "var foo = [];", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert!(result.is_undefined());
-        let foo_key = String::from_static_str(&mut agent, "foo", gc.nogc());
+        let foo_key = String::from_static_str(agent, "foo", gc.nogc());
         let foo = unwrap_try(agent.current_global_env(gc.nogc()).try_get_binding_value(
-            &mut agent,
+            agent,
             foo_key,
             true,
             gc.nogc(),
@@ -1010,24 +1246,27 @@ mod test {
         .unwrap();
         assert!(foo.is_object());
         let result = Object::try_from(foo).unwrap();
-        assert!(unwrap_try(result.try_own_property_keys(&mut agent, gc.nogc())).is_empty());
+        assert_eq!(
+            unwrap_try(result.try_own_property_keys(agent, gc.nogc())),
+            [PropertyKey::from(BUILTIN_STRING_MEMORY.length)]
+        );
     }
 
     #[test]
     fn non_empty_array() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
 
-        let source_text = String::from_static_str(&mut agent, "var foo = [ 'a', 3 ];", gc.nogc());
+        let source_text = String::from_static_str(agent, "var foo = [ 'a', 3 ];", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert!(result.is_undefined());
-        let foo_key = String::from_static_str(&mut agent, "foo", gc.nogc());
+        let foo_key = String::from_static_str(agent, "foo", gc.nogc());
         let foo = unwrap_try(agent.current_global_env(gc.nogc()).try_get_binding_value(
-            &mut agent,
+            agent,
             foo_key,
             true,
             gc.nogc(),
@@ -1036,58 +1275,246 @@ mod test {
         assert!(foo.is_object());
         let result = Array::try_from(foo).unwrap();
         let key = PropertyKey::Integer(0.into());
-        assert!(unwrap_try(result.try_has_property(
-            &mut agent,
-            key,
-            gc.nogc()
-        )));
+        assert!(unwrap_try(result.try_has_property(agent, key, gc.nogc())));
         assert_eq!(
-            unwrap_try(result.try_get_own_property(&mut agent, key, gc.nogc()))
+            unwrap_try(result.try_get_own_property(agent, key, gc.nogc()))
                 .unwrap()
                 .value,
-            Some(Value::from_static_str(&mut agent, "a", gc.nogc()))
+            Some(Value::from_static_str(agent, "a", gc.nogc()))
         );
         let key = PropertyKey::Integer(1.into());
         assert!(unwrap_try(result.unbind().try_has_property(
-            &mut agent,
+            agent,
             key,
             gc.nogc()
         )));
         assert_eq!(
-            unwrap_try(result.try_get_own_property(&mut agent, key, gc.nogc()))
+            unwrap_try(result.try_get_own_property(agent, key, gc.nogc()))
                 .unwrap()
                 .value,
             Some(Value::from(3))
         );
     }
 
+    #[test]
+    fn array_is_array_across_realms_and_proxies() {
+        use crate::ecmascript::{
+            abstract_operations::operations_on_objects::call_function, types::Function,
+        };
+
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(agent, "[1, 2]", gc.nogc());
+        let array = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap()
+            .unbind()
+            .scope(agent, gc.nogc());
+
+        // Evaluate the checks in a second realm.
+        initialize_default_realm(agent, gc.reborrow());
+        let source_text = String::from_static_str(
+            agent,
+            "(function (array) {
+                return Array.isArray(array) && !Array.isArray({ length: 0 }) &&
+                    !Array.isArray({ 0: 'a', length: 1 }) && !Array.isArray('a') &&
+                    Array.isArray(new Proxy(array, {})) &&
+                    Array.isArray(new Proxy(new Proxy(array, {}), {})) &&
+                    !Array.isArray(new Proxy({ length: 0 }, {}));
+            })",
+            gc.nogc(),
+        );
+        let check = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        let check = Function::try_from(check).unwrap();
+        let mut array = array.get(agent);
+        let result = call_function(
+            agent,
+            check.unbind(),
+            Value::Undefined,
+            Some(ArgumentsList::from_mut_value(&mut array)),
+            gc.reborrow(),
+        )
+        .unwrap();
+        assert_eq!(result, Value::Boolean(true));
+
+        let source_text = String::from_static_str(
+            agent,
+            "const { proxy, revoke } = Proxy.revocable([], {});
+            Array.isArray(proxy) && revoke() === undefined",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::Boolean(true));
+        let source_text =
+            String::from_static_str(agent, "Array.isArray(new Proxy(proxy, {}))", gc.nogc());
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::TypeError);
+    }
+
+    #[test]
+    fn proxy_revocable() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(
+            agent,
+            "const result = Proxy.revocable({ a: 1 }, {});
+            const { proxy, revoke } = result;
+            const callable = Proxy.revocable(function () {}, {});
+            callable.revoke();
+            Object.keys(result).join() === 'proxy,revoke' && proxy.a === 1 &&
+                revoke.length === 0 && typeof callable.proxy === 'function' &&
+                revoke() === undefined && revoke() === undefined &&
+                (() => {
+                    try { proxy.a; return false; } catch (err) { if (!(err instanceof TypeError)) return false; }
+                    try { callable.proxy(); return false; } catch (err) { if (!(err instanceof TypeError)) return false; }
+                    try { Proxy.revocable(1, {}); return false; } catch (err) { return err instanceof TypeError; }
+                })()",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::Boolean(true));
+    }
+
+    #[test]
+    fn array_at_negative_indices_on_holey_arrays() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var holey = [1, , 3, , ];
+            var inherited = (Array.prototype[3] = 'p', holey.at(-1));
+            delete Array.prototype[3];
+            holey.length === 4 && holey.at(-1) === undefined && holey.at(-2) === 3 &&
+                holey.at(-3) === undefined && holey.at(-4) === 1 &&
+                holey.at(-5) === undefined && holey.at(-0.5) === 1 &&
+                holey.at('-2') === 3 && holey.at(-Infinity) === undefined &&
+                inherited === 'p' &&
+                Array.prototype.at.call({ length: 3, 2: 'x' }, -1) === 'x'",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::Boolean(true));
+    }
+
+    #[test]
+    fn array_last_index_of_from_index() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var a = [1, 2, 1, NaN];
+            var holey = [1, , 1, , ];
+            a.lastIndexOf(1, NaN) === 0 && a.lastIndexOf(1, 'x') === 0 &&
+                a.lastIndexOf(1) === 2 && a.lastIndexOf(1, undefined) === 0 &&
+                a.lastIndexOf(1, 100) === 2 && a.lastIndexOf(1, -2) === 2 &&
+                a.lastIndexOf(1, -4) === 0 && a.lastIndexOf(1, -5) === -1 &&
+                a.lastIndexOf(1, -Infinity) === -1 && a.lastIndexOf(NaN) === -1 &&
+                holey.lastIndexOf(undefined) === -1 && holey.lastIndexOf(1, -3) === 0 &&
+                holey.lastIndexOf(1, NaN) === 0",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::Boolean(true));
+    }
+
+    #[test]
+    fn array_length_shrink_stops_at_non_configurable_element() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(
+            agent,
+            "function withFixedElement() {
+                var a = [1, 2, 3, 4, 5];
+                Object.defineProperty(a, 2, { value: 3, configurable: false });
+                return a;
+            }
+            var set = withFixedElement();
+            var setResult = Reflect.set(set, 'length', 1);
+            var proxied = withFixedElement();
+            var proxyResult = Reflect.set(new Proxy(proxied, {}), 'length', 0);
+            var defined = withFixedElement();
+            var defineResult = Reflect.defineProperty(defined, 'length', {
+                value: 0,
+                writable: false,
+            });
+            var thrown = false;
+            var assigned = withFixedElement();
+            try {
+                (function () { 'use strict'; assigned.length = 2; })();
+            } catch (e) {
+                thrown = e instanceof TypeError;
+            }
+            var shrunk = [1, 2, 3];
+            shrunk.length = 1;
+            setResult === false && set.length === 3 && set[2] === 3 && !(3 in set) &&
+                proxyResult === false && proxied.length === 3 &&
+                defineResult === false && defined.length === 3 && defined[1] === 2 &&
+                !Object.getOwnPropertyDescriptor(defined, 'length').writable &&
+                thrown && assigned.length === 3 &&
+                shrunk.length === 1 && !(1 in shrunk) && (shrunk.length = 3, !(1 in shrunk))",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::Boolean(true));
+    }
+
     #[test]
     fn empty_function() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
 
-        let source_text = String::from_static_str(&mut agent, "function foo() {}", gc.nogc());
+        let source_text = String::from_static_str(agent, "function foo() {}", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert!(result.is_undefined());
         let source_text =
-            String::from_static_str(&mut agent, "let i = 0; const a = 'foo'; i = 3;", gc.nogc());
+            String::from_static_str(agent, "let i = 0; const a = 'foo'; i = 3;", gc.nogc());
         agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
 
         let global_env = agent.current_global_env(gc.nogc());
-        let foo_key = String::from_static_str(&mut agent, "foo", gc.nogc());
+        let foo_key = String::from_static_str(agent, "foo", gc.nogc());
         assert!(unwrap_try(global_env.try_has_binding(
-            &mut agent,
+            agent,
             foo_key,
             gc.nogc()
         )));
         assert!(
-            unwrap_try(global_env.try_get_binding_value(&mut agent, foo_key, true, gc.nogc()))
+            unwrap_try(global_env.try_get_binding_value(agent, foo_key, true, gc.nogc()))
                 .unwrap()
                 .is_function(),
         );
@@ -1095,12 +1522,12 @@ mod test {
 
     #[test]
     fn empty_iife_function_call() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
 
-        let source_text = String::from_static_str(&mut agent, "(function() {})()", gc.nogc());
+        let source_text = String::from_static_str(agent, "(function() {})()", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
@@ -1109,13 +1536,12 @@ mod test {
 
     #[test]
     fn empty_named_function_call() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
 
-        let source_text =
-            String::from_static_str(&mut agent, "var f = function() {}; f();", gc.nogc());
+        let source_text = String::from_static_str(agent, "var f = function() {}; f();", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
@@ -1124,12 +1550,12 @@ mod test {
 
     #[test]
     fn empty_declared_function_call() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
 
-        let source_text = String::from_static_str(&mut agent, "function f() {}; f();", gc.nogc());
+        let source_text = String::from_static_str(agent, "function f() {}; f();", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
@@ -1138,13 +1564,12 @@ mod test {
 
     #[test]
     fn non_empty_iife_function_call() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
 
-        let source_text =
-            String::from_static_str(&mut agent, "(function() { return 3 })()", gc.nogc());
+        let source_text = String::from_static_str(agent, "(function() { return 3 })()", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
@@ -1153,10 +1578,10 @@ mod test {
 
     #[test]
     fn builtin_function_call() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
 
         let global = agent.current_global_object(gc.nogc());
 
@@ -1177,15 +1602,15 @@ mod test {
         }
 
         let func = create_builtin_function(
-            &mut agent,
+            agent,
             Behaviour::Regular(test_builtin_function),
             BuiltinFunctionArgs::new(1, "test"),
             gc.nogc(),
         );
 
-        let key = PropertyKey::from_static_str(&mut agent, "test", gc.nogc());
+        let key = PropertyKey::from_static_str(agent, "test", gc.nogc());
         create_data_property_or_throw(
-            &mut agent,
+            agent,
             global.unbind(),
             key.unbind(),
             func.into_value().unbind(),
@@ -1193,19 +1618,19 @@ mod test {
         )
         .unwrap();
 
-        let source_text = String::from_static_str(&mut agent, "test(true)", gc.nogc());
+        let source_text = String::from_static_str(agent, "test(true)", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert_eq!(result, Value::from(3));
 
-        let source_text = String::from_static_str(&mut agent, "test()", gc.nogc());
+        let source_text = String::from_static_str(agent, "test()", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert_eq!(result, Value::Null);
 
-        let source_text = String::from_static_str(&mut agent, "test({})", gc.nogc());
+        let source_text = String::from_static_str(agent, "test({})", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
@@ -1214,18 +1639,18 @@ mod test {
 
     #[test]
     fn if_statement() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
 
-        let source_text = String::from_static_str(&mut agent, "if (true) 3", gc.nogc());
+        let source_text = String::from_static_str(agent, "if (true) 3", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert_eq!(result, Number::from(3).into_value());
 
-        let source_text = String::from_static_str(&mut agent, "if (false) 3", gc.nogc());
+        let source_text = String::from_static_str(agent, "if (false) 3", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
@@ -1234,13 +1659,13 @@ mod test {
 
     #[test]
     fn if_else_statement() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
 
         let source_text = String::from_static_str(
-            &mut agent,
+            agent,
             "var foo = function() { if (true) { return 3; } else { return 5; } }; foo()",
             gc.nogc(),
         );
@@ -1250,7 +1675,7 @@ mod test {
         assert_eq!(result, Number::from(3).into_value());
 
         let source_text = String::from_static_str(
-            &mut agent,
+            agent,
             "var bar = function() { if (false) { return 3; } else { return 5; } }; bar()",
             gc.nogc(),
         );
@@ -1262,13 +1687,12 @@ mod test {
 
     #[test]
     fn static_property_access() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
 
-        let source_text =
-            String::from_static_str(&mut agent, "var foo = { a: 3 }; foo.a", gc.nogc());
+        let source_text = String::from_static_str(agent, "var foo = { a: 3 }; foo.a", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
@@ -1277,13 +1701,13 @@ mod test {
 
     #[test]
     fn deep_static_property_access() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
 
         let source_text = String::from_static_str(
-            &mut agent,
+            agent,
             "var fn = function() { return 3; }; var foo = { a: { b: fn } }; foo.a.b()",
             gc.nogc(),
         );
@@ -1295,13 +1719,13 @@ mod test {
 
     #[test]
     fn computed_property_access() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
 
         let source_text = String::from_static_str(
-            &mut agent,
+            agent,
             "var foo = { a: 3 }; var prop = 'a'; foo[prop]",
             gc.nogc(),
         );
@@ -1312,20 +1736,20 @@ mod test {
     }
     #[test]
     fn for_loop() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
 
         let source_text =
-            String::from_static_str(&mut agent, "var i = 0; for (; i < 3; i++) {}", gc.nogc());
+            String::from_static_str(agent, "var i = 0; for (; i < 3; i++) {}", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert_eq!(result, Value::Undefined);
-        let key = PropertyKey::from_static_str(&mut agent, "i", gc.nogc());
+        let key = PropertyKey::from_static_str(agent, "i", gc.nogc());
         let i: Value = unwrap_try(agent.current_global_object(gc.nogc()).try_get_own_property(
-            &mut agent,
+            agent,
             key,
             gc.nogc(),
         ))
@@ -1337,13 +1761,13 @@ mod test {
 
     #[test]
     fn lexical_declarations() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
 
         let source_text =
-            String::from_static_str(&mut agent, "let i = 0; const a = 'foo'; i = 3;", gc.nogc());
+            String::from_static_str(agent, "let i = 0; const a = 'foo'; i = 3;", gc.nogc());
         agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
@@ -1354,78 +1778,112 @@ mod test {
             .global_env
             .unwrap()
             .bind(gc.nogc());
-        let a_key = String::from_static_str(&mut agent, "a", gc.nogc());
-        let i_key = String::from_static_str(&mut agent, "i", gc.nogc());
+        let a_key = String::from_static_str(agent, "a", gc.nogc());
+        let i_key = String::from_static_str(agent, "i", gc.nogc());
         assert!(unwrap_try(global_env.try_has_binding(
-            &mut agent,
+            agent,
             a_key,
             gc.nogc()
         )));
         assert!(unwrap_try(global_env.try_has_binding(
-            &mut agent,
+            agent,
             i_key,
             gc.nogc()
         )));
         assert_eq!(
-            unwrap_try(global_env.try_get_binding_value(&mut agent, a_key, true, gc.nogc()))
-                .unwrap(),
+            unwrap_try(global_env.try_get_binding_value(agent, a_key, true, gc.nogc())).unwrap(),
             String::from_small_string("foo").into_value()
         );
         assert_eq!(
-            unwrap_try(global_env.try_get_binding_value(&mut agent, i_key, true, gc.nogc()))
-                .unwrap(),
+            unwrap_try(global_env.try_get_binding_value(agent, i_key, true, gc.nogc())).unwrap(),
             Value::from(3)
         );
     }
 
     #[test]
     fn lexical_declarations_in_block() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
 
-        let source_text = String::from_static_str(
-            &mut agent,
-            "{ let i = 0; const a = 'foo'; i = 3; }",
-            gc.nogc(),
-        );
+        let source_text =
+            String::from_static_str(agent, "{ let i = 0; const a = 'foo'; i = 3; }", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert_eq!(result, 3.into());
 
         let realm = agent.current_realm(gc.nogc());
-        let a_key = String::from_static_str(&mut agent, "a", gc.nogc());
-        let i_key = String::from_static_str(&mut agent, "i", gc.nogc());
+        let a_key = String::from_static_str(agent, "a", gc.nogc());
+        let i_key = String::from_static_str(agent, "i", gc.nogc());
         let global_env = agent
             .get_realm_record_by_id(realm)
             .global_env
             .unwrap()
             .bind(gc.nogc());
-        assert!(!global_env.has_lexical_declaration(&agent, a_key));
-        assert!(!global_env.has_lexical_declaration(&agent, i_key));
+        assert!(!global_env.has_lexical_declaration(agent, a_key));
+        assert!(!global_env.has_lexical_declaration(agent, i_key));
+    }
+
+    #[test]
+    fn lexical_declaration_in_temporal_dead_zone() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(agent, "x; let x = 1;", gc.nogc());
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::ReferenceError);
+        assert_eq!(
+            agent[error].message.unwrap().as_str(agent),
+            "Cannot access 'x' before initialization"
+        );
+    }
+
+    #[test]
+    fn const_declaration_in_block_temporal_dead_zone() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text =
+            String::from_static_str(agent, "{ const before = a; const a = 'foo'; }", gc.nogc());
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::ReferenceError);
+        assert_eq!(
+            agent[error].message.unwrap().as_str(agent),
+            "Cannot access 'a' before initialization"
+        );
     }
 
     #[test]
     fn object_property_assignment() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
 
         let source_text =
-            String::from_static_str(&mut agent, "var foo = {}; foo.a = 42; foo", gc.nogc());
+            String::from_static_str(agent, "var foo = {}; foo.a = 42; foo", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         let object = Object::try_from(result).unwrap().unbind().bind(gc.nogc());
 
-        let pk = PropertyKey::from_static_str(&mut agent, "a", gc.nogc());
+        let pk = PropertyKey::from_static_str(agent, "a", gc.nogc());
         assert_eq!(
             object
                 .unbind()
-                .internal_get(&mut agent, pk.unbind(), object.into_value().unbind(), gc)
+                .internal_get(agent, pk.unbind(), object.into_value().unbind(), gc)
                 .unwrap(),
             Value::Integer(SmallInteger::from(42))
         );
@@ -1433,13 +1891,13 @@ mod test {
 
     #[test]
     fn try_catch_not_thrown() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
 
         let source_text = String::from_static_str(
-            &mut agent,
+            agent,
             "let a = 0; try { a++; } catch { a = 500; }; a++; a",
             gc.nogc(),
         );
@@ -1451,14 +1909,14 @@ mod test {
 
     #[test]
     fn try_catch_thrown() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
         // let realm = agent.current_realm_id(gc.nogc());
 
         let source_text = String::from_static_str(
-            &mut agent,
+            agent,
             "let a = 0; try { throw null; a = 500 } catch { a++; }; a++; a",
             gc.nogc(),
         );
@@ -1470,14 +1928,14 @@ mod test {
 
     #[test]
     fn catch_binding() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
         // let realm = agent.current_realm_id(gc.nogc());
 
         let source_text = String::from_static_str(
-            &mut agent,
+            agent,
             "let err; try { throw 'thrown'; } catch(e) { err = e; }; err",
             gc.nogc(),
         );
@@ -1486,20 +1944,20 @@ mod test {
             .unwrap();
         assert_eq!(
             result.unbind(),
-            Value::from_static_str(&mut agent, "thrown", gc.nogc())
+            Value::from_static_str(agent, "thrown", gc.nogc())
         );
     }
 
     #[test]
     fn throwing_in_try_restores_lexical_environment() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
         // let realm = agent.current_realm_id(gc.nogc());
 
         let source_text = String::from_static_str(
-            &mut agent,
+            agent,
             "let a = 42; try { let a = 62; throw 'thrown'; } catch { }; a",
             gc.nogc(),
         );
@@ -1511,14 +1969,14 @@ mod test {
 
     #[test]
     fn function_argument_bindings() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
         // let realm = agent.current_realm_id(gc.nogc());
 
         let source_text = String::from_static_str(
-            &mut agent,
+            agent,
             "const foo = function (a) { return a + 10; }; foo(32)",
             gc.nogc(),
         );
@@ -1530,20 +1988,20 @@ mod test {
 
     #[test]
     fn logical_and() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
         // let realm = agent.current_realm_id(gc.nogc());
 
-        let source_text = String::from_static_str(&mut agent, "true && true", gc.nogc());
+        let source_text = String::from_static_str(agent, "true && true", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert_eq!(result, Value::Boolean(true));
 
         // let realm = agent.current_realm_id(gc.nogc());
-        let source_text = String::from_static_str(&mut agent, "true && false && true", gc.nogc());
+        let source_text = String::from_static_str(agent, "true && false && true", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
@@ -1552,20 +2010,20 @@ mod test {
 
     #[test]
     fn logical_or() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
         // let realm = agent.current_realm_id(gc.nogc());
 
-        let source_text = String::from_static_str(&mut agent, "false || false", gc.nogc());
+        let source_text = String::from_static_str(agent, "false || false", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert_eq!(result, Value::Boolean(false));
 
         // let realm = agent.current_realm_id(gc.nogc());
-        let source_text = String::from_static_str(&mut agent, "true || false || true", gc.nogc());
+        let source_text = String::from_static_str(agent, "true || false || true", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
@@ -1574,30 +2032,30 @@ mod test {
 
     #[test]
     fn nullish_coalescing() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
         // let realm = agent.current_realm_id(gc.nogc());
 
-        let source_text = String::from_static_str(&mut agent, "null ?? 42", gc.nogc());
+        let source_text = String::from_static_str(agent, "null ?? 42", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert_eq!(result, Value::Integer(SmallInteger::from(42)));
 
         // let realm = agent.current_realm_id(gc.nogc());
-        let source_text = String::from_static_str(&mut agent, "'foo' ?? 12", gc.nogc());
+        let source_text = String::from_static_str(agent, "'foo' ?? 12", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert_eq!(
             result.unbind(),
-            Value::from_static_str(&mut agent, "foo", gc.nogc())
+            Value::from_static_str(agent, "foo", gc.nogc())
         );
 
         // let realm = agent.current_realm_id(gc.nogc());
-        let source_text = String::from_static_str(&mut agent, "undefined ?? null", gc.nogc());
+        let source_text = String::from_static_str(agent, "undefined ?? null", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
@@ -1606,110 +2064,179 @@ mod test {
 
     #[test]
     fn string_concat() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
         // let realm = agent.current_realm_id(gc.nogc());
 
-        let source_text = String::from_static_str(&mut agent, "'foo' + '' + 'bar'", gc.nogc());
+        let source_text = String::from_static_str(agent, "'foo' + '' + 'bar'", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert_eq!(
             result.unbind(),
-            Value::from_static_str(&mut agent, "foobar", gc.nogc())
+            Value::from_static_str(agent, "foobar", gc.nogc())
         );
 
         // let realm = agent.current_realm_id(gc.nogc());
-        let source_text =
-            String::from_static_str(&mut agent, "'foo' + ' a heap string'", gc.nogc());
+        let source_text = String::from_static_str(agent, "'foo' + ' a heap string'", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert_eq!(
             result.unbind(),
-            Value::from_static_str(&mut agent, "foo a heap string", gc.nogc())
+            Value::from_static_str(agent, "foo a heap string", gc.nogc())
         );
 
         // let realm = agent.current_realm_id(gc.nogc());
-        let source_text = String::from_static_str(
-            &mut agent,
-            "'Concatenating ' + 'two heap strings'",
-            gc.nogc(),
-        );
+        let source_text =
+            String::from_static_str(agent, "'Concatenating ' + 'two heap strings'", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert_eq!(
             result.unbind(),
-            Value::from_static_str(&mut agent, "Concatenating two heap strings", gc.nogc())
+            Value::from_static_str(agent, "Concatenating two heap strings", gc.nogc())
         );
     }
 
     #[test]
     fn property_access_on_functions() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
 
-        let source_text =
-            String::from_static_str(&mut agent, "function foo() {}; foo.bar", gc.nogc());
+        let source_text = String::from_static_str(agent, "function foo() {}; foo.bar", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert_eq!(result, Value::Undefined);
 
-        let source_text = String::from_static_str(&mut agent, "foo.bar = 42; foo.bar", gc.nogc());
+        let source_text = String::from_static_str(agent, "foo.bar = 42; foo.bar", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert_eq!(result, Value::Integer(SmallInteger::from(42)));
 
-        let source_text = String::from_static_str(&mut agent, "foo.name", gc.nogc());
+        let source_text = String::from_static_str(agent, "foo.name", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert_eq!(
             result.unbind(),
-            Value::from_static_str(&mut agent, "foo", gc.nogc())
+            Value::from_static_str(agent, "foo", gc.nogc())
         );
 
         // let realm = agent.current_realm_id(gc.nogc());
-        let source_text = String::from_static_str(&mut agent, "foo.length", gc.nogc());
+        let source_text = String::from_static_str(agent, "foo.length", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert_eq!(result, Value::Integer(SmallInteger::zero()));
 
         // let realm = agent.current_realm_id(gc.nogc());
-        let source_text = String::from_static_str(&mut agent, "foo.prototype", gc.nogc());
+        let source_text = String::from_static_str(agent, "foo.prototype", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert!(result.is_object())
     }
 
+    #[test]
+    fn property_access_on_primitives() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let primitive_objects = agent.heap.primitive_objects.len();
+        for source in [
+            "\"abc\".length === 3 && \"abc\"[1] === \"b\" && \"abc\"[3] === undefined",
+            "(255).toString(16) === \"ff\" && (5).toFixed(2) === \"5.00\"",
+            "true.toString() === \"true\" && false.valueOf() === false",
+            "Symbol(\"s\").description === \"s\" && Symbol(\"s\").toString() === \"Symbol(s)\"",
+            "(10n).toString(2) === \"1010\"",
+            "\"abc\".constructor === String && (1).constructor === Number",
+            "String.prototype.self = function () { \"use strict\"; return this; };
+            \"abc\".self() === \"abc\"",
+        ] {
+            let source_text = String::from_static_str(agent, source, gc.nogc());
+            let result = agent
+                .run_script(source_text.unbind(), gc.reborrow())
+                .unwrap();
+            assert_eq!(result, true.into(), "{source}");
+        }
+        // The primitives are not boxed into wrapper objects for the accesses.
+        assert_eq!(agent.heap.primitive_objects.len(), primitive_objects);
+
+        // Sloppy functions receive a wrapper object as their this value.
+        let source_text = String::from_static_str(
+            agent,
+            "Number.prototype.self = function () { return this; };
+            (1).self() instanceof Number",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+    }
+
+    #[test]
+    fn integer_property_keys_at_array_index_limits() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        // Integer keys cover the whole array index range, up to 2^32 - 2, and
+        // keys beyond it keep their creation order among the string keys.
+        for source in [
+            "const o1 = {}; o1[3e9] = 1; o1['3000000000'] = 2;
+            o1[3e9] === 2 && Object.keys(o1).join() === '3000000000'",
+            "const o2 = { a: 1 }; o2[2 ** 32 - 1] = 1; o2[2 ** 32 - 2] = 1; o2[2 ** 32 - 3] = 1;
+            o2['2147483648'] = 1;
+            Object.keys(o2).join() === '2147483648,4294967293,4294967294,a,4294967295'",
+            "const o3 = { a: 1, 4294967296: 1, 4294967295: 1, b: 1, 4294967294: 1 };
+            let keys = ''; for (const key in o3) keys += key + ',';
+            keys === '4294967294,a,4294967296,4294967295,b,'",
+            "const o4 = { 4294967294: 1, 4294967295: 2 };
+            delete o4['4294967294'] && !(2 ** 32 - 2 in o4) && o4[2 ** 32 - 1] === 2",
+            // 2^32 - 1 is not an array index, so it does not affect the length.
+            "const a1 = [1]; a1[2 ** 32 - 1] = 'x'; a1[-1] = 'y';
+            a1.length === 1 && a1['4294967295'] === 'x' && Object.keys(a1).join() === '0,4294967295,-1'",
+            "const a2 = [1, 2]; a2[2 ** 32] = 1; a2.length = 1;
+            a2[2 ** 32] === 1 && Object.keys(a2).join() === '0,4294967296'",
+        ] {
+            let source_text = String::from_static_str(agent, source, gc.nogc());
+            let result = agent
+                .run_script(source_text.unbind(), gc.reborrow())
+                .unwrap();
+            assert_eq!(result, true.into(), "{source}");
+        }
+    }
+
     #[test]
     fn name_and_length_on_builtin_functions() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
         // let realm = agent.current_realm_id(gc.nogc());
 
-        let source_text = String::from_static_str(&mut agent, "TypeError.name", gc.nogc());
+        let source_text = String::from_static_str(agent, "TypeError.name", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert_eq!(
             result.unbind(),
-            Value::from_static_str(&mut agent, "TypeError", gc.nogc())
+            Value::from_static_str(agent, "TypeError", gc.nogc())
         );
 
         // let realm = agent.current_realm_id(gc.nogc());
-        let source_text = String::from_static_str(&mut agent, "TypeError.length", gc.nogc());
+        let source_text = String::from_static_str(agent, "TypeError.length", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
@@ -1718,49 +2245,44 @@ mod test {
 
     #[test]
     fn constructor() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
         // let realm = agent.current_realm_id(gc.nogc());
 
         let source_text =
-            String::from_static_str(&mut agent, "function foo() {}; foo.prototype", gc.nogc());
+            String::from_static_str(agent, "function foo() {}; foo.prototype", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         let foo_prototype = Object::try_from(result)
             .unwrap()
             .unbind()
-            .scope(&mut agent, gc.nogc());
+            .scope(agent, gc.nogc());
 
         // let realm = agent.current_realm_id(gc.nogc());
-        let source_text = String::from_static_str(&mut agent, "new foo()", gc.nogc());
+        let source_text = String::from_static_str(agent, "new foo()", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         let instance = Object::try_from(result).unwrap();
         assert_eq!(
-            unwrap_try(
-                instance
-                    .unbind()
-                    .try_get_prototype_of(&mut agent, gc.nogc())
-            )
-            .unwrap(),
-            foo_prototype.get(&agent)
+            unwrap_try(instance.unbind().try_get_prototype_of(agent, gc.nogc())).unwrap(),
+            foo_prototype.get(agent)
         );
     }
 
     #[test]
     fn this_expression() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
         // let realm = agent.current_realm_id(gc.nogc());
 
         let source_text = String::from_static_str(
-            &mut agent,
+            agent,
             "function foo() { this.bar = 42; }; new foo().bar",
             gc.nogc(),
         );
@@ -1771,7 +2293,7 @@ mod test {
 
         // let realm = agent.current_realm_id(gc.nogc());
         let source_text = String::from_static_str(
-            &mut agent,
+            agent,
             "foo.prototype.baz = function() { return this.bar + 10; }; (new foo()).baz()",
             gc.nogc(),
         );
@@ -1783,13 +2305,13 @@ mod test {
 
     #[test]
     fn symbol_stringification() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
         // let realm = agent.current_realm_id(gc.nogc());
 
-        let source_text = String::from_static_str(&mut agent, "+Symbol()", gc.nogc());
+        let source_text = String::from_static_str(agent, "+Symbol()", gc.nogc());
         let result = agent.run_script(source_text.unbind(), gc.reborrow());
         assert!(result.is_err());
         let result = result.unwrap_err().value();
@@ -1799,7 +2321,7 @@ mod test {
         assert_eq!(agent[result].kind, ExceptionType::TypeError);
 
         // let realm = agent.current_realm_id(gc.nogc());
-        let source_text = String::from_static_str(&mut agent, "+Symbol('foo')", gc.nogc());
+        let source_text = String::from_static_str(agent, "+Symbol('foo')", gc.nogc());
         let result = agent.run_script(source_text.unbind(), gc.reborrow());
         assert!(result.is_err());
         let result = result.unwrap_err().value();
@@ -1808,69 +2330,157 @@ mod test {
         };
         assert_eq!(agent[result].kind, ExceptionType::TypeError);
 
-        let source_text = String::from_static_str(&mut agent, "String(Symbol())", gc.nogc());
+        let source_text = String::from_static_str(agent, "String(Symbol())", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert_eq!(
             result.unbind(),
-            Value::from_static_str(&mut agent, "Symbol()", gc.nogc())
+            Value::from_static_str(agent, "Symbol()", gc.nogc())
         );
 
         let realm = agent.current_realm(gc.nogc());
-        let source_text = String::from_static_str(&mut agent, "String(Symbol('foo'))", gc.nogc());
-        let script = parse_script(&mut agent, source_text, realm, false, None, gc.nogc()).unwrap();
-        let value = script_evaluation(&mut agent, script.unbind(), gc.reborrow()).unwrap();
+        let source_text = String::from_static_str(agent, "String(Symbol('foo'))", gc.nogc());
+        let script = parse_script(agent, source_text, realm, false, None, gc.nogc()).unwrap();
+        let value = script_evaluation(agent, script.unbind(), gc.reborrow()).unwrap();
         assert_eq!(
             value.unbind(),
-            Value::from_static_str(&mut agent, "Symbol(foo)", gc.nogc())
+            Value::from_static_str(agent, "Symbol(foo)", gc.nogc())
         );
     }
 
+    #[test]
+    fn primitive_wrappers_called_as_functions_and_constructors() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        for source in [
+            "typeof String(1) === 'string' && String(1) === '1'",
+            "typeof Number('12') === 'number' && Number('12') === 12 && Number(12n) === 12",
+            "typeof Boolean(1) === 'boolean' && Boolean('') === false",
+            "typeof new String(1) === 'object' && new String(1).valueOf() === '1'",
+            "typeof new Number('12') === 'object' && new Number('12').valueOf() === 12",
+            "typeof new Boolean(0) === 'object' && new Boolean(0).valueOf() === false",
+            "String() === '' && Object.is(Number(), 0) && Boolean() === false",
+            "new String().valueOf() === '' && Object.is(new Number().valueOf(), 0)",
+            // An undefined argument is present, unlike a missing one.
+            "String(undefined) === 'undefined' && Number.isNaN(Number(undefined))",
+            "Number.isNaN(new Number(undefined).valueOf())",
+            "Object.getPrototypeOf(new Number(1)) === Number.prototype && new Boolean(true) instanceof Boolean",
+            "class S extends String {} new S('ab') instanceof S && new S('ab').length === 2",
+        ] {
+            let source_text = String::from_static_str(agent, source, gc.nogc());
+            let result = agent
+                .run_script(source_text.unbind(), gc.reborrow())
+                .unwrap();
+            assert_eq!(result, true.into(), "{source}");
+        }
+    }
+
+    #[test]
+    fn assignment_expression_values() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        for source in [
+            "var x, y, z; (x = 5) === 5 && x === 5",
+            "(x = y = z = 7) === 7 && x === 7 && y === 7 && z === 7",
+            "var order = ''; var target = { set a(v) { order += 'a' + v; }, set b(v) { order += 'b' + v; } };
+            (target.a = target.b = 1) === 1 && order === 'b1a1'",
+            "var object = { a: 1, b: 2 }; var a, b; ({ a } = object) === object && a === 1",
+            "var array = [3, 4]; ([a, b] = array) === array && a === 3 && b === 4",
+            "var nested = { c: [5] }; ({ c: [a] } = nested).c[0] === 5 && a === 5",
+            "({ a, b } = { a: 6, b: 7 }).b === 7 && a + b === 13",
+            "x = 5; (x += 2) === 7 && (x ||= 9) === 7 && (x &&= 0) === 0 && (x ??= 1) === 0",
+            // Short-circuiting logical assignments as arguments of a method call.
+            "x = 1; [].concat((x ||= 2), (x &&= 3), (x ??= 4)).join() === '1,3,3'",
+            "x = null; [].concat((x ??= 2), (x ||= 3), (x &&= 0)).join() === '2,2,0'",
+        ] {
+            let source_text = String::from_static_str(agent, source, gc.nogc());
+            let result = agent
+                .run_script(source_text.unbind(), gc.reborrow())
+                .unwrap();
+            assert_eq!(result, true.into(), "{source}");
+        }
+    }
+
+    #[test]
+    fn number_to_string_with_radix() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        for source in [
+            "(255).toString(16) === 'ff' && (-255).toString(16) === '-ff'",
+            "(3.5).toString(2) === '11.1' && (-0.5).toString(2) === '-0.1'",
+            "(0.1).toString(16) === '0.1999999999999a' && (35).toString(36) === 'z'",
+            "(-0).toString(2) === '0' && (2 ** 53).toString(2) === '1' + '0'.repeat(53)",
+            "(1e21).toString(36) === '5v1j4f4ds7c000'",
+            "(2 ** -30).toString(2) === '0.' + '0'.repeat(29) + '1'",
+            "NaN.toString(2) === 'NaN' && Infinity.toString(36) === 'Infinity'",
+            "(-Infinity).toString(16) === '-Infinity'",
+            "(10).toString(undefined) === '10' && (10).toString(10.5) === '10'",
+            "try { (1).toString(1); false } catch (e) { e instanceof RangeError }",
+            "try { (1).toString(37); false } catch (e) { e instanceof RangeError }",
+            "try { NaN.toString(0); false } catch (e) { e instanceof RangeError }",
+        ] {
+            let source_text = String::from_static_str(agent, source, gc.nogc());
+            let result = agent
+                .run_script(source_text.unbind(), gc.reborrow())
+                .unwrap();
+            assert_eq!(result, true.into(), "{source}");
+        }
+    }
+
     #[test]
     fn instanceof() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
         // let realm = agent.current_realm_id(gc.nogc());
 
-        let source_text = String::from_static_str(&mut agent, "3 instanceof Number", gc.nogc());
+        let source_text = String::from_static_str(agent, "3 instanceof Number", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert_eq!(result, false.into());
 
         // let realm = agent.current_realm_id(gc.nogc());
-        let source_text = String::from_static_str(&mut agent, "'foo' instanceof String", gc.nogc());
+        let source_text = String::from_static_str(agent, "'foo' instanceof String", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert_eq!(result, false.into());
 
         // let realm = agent.current_realm_id(gc.nogc());
-        let source_text = String::from_static_str(&mut agent, "({}) instanceof Object", gc.nogc());
+        let source_text = String::from_static_str(agent, "({}) instanceof Object", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert_eq!(result, true.into());
 
         // let realm = agent.current_realm_id(gc.nogc());
-        let source_text = String::from_static_str(&mut agent, "({}) instanceof Array", gc.nogc());
+        let source_text = String::from_static_str(agent, "({}) instanceof Array", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert_eq!(result, false.into());
 
         // let realm = agent.current_realm_id(gc.nogc());
-        let source_text = String::from_static_str(&mut agent, "([]) instanceof Object", gc.nogc());
+        let source_text = String::from_static_str(agent, "([]) instanceof Object", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
         assert_eq!(result, true.into());
 
         // let realm = agent.current_realm_id(gc.nogc());
-        let source_text = String::from_static_str(&mut agent, "([]) instanceof Array", gc.nogc());
+        let source_text = String::from_static_str(agent, "([]) instanceof Array", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
@@ -1879,19 +2489,19 @@ mod test {
 
     #[test]
     fn array_binding_pattern() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
 
         let source_text =
-            String::from_static_str(&mut agent, "const [a, b, , c] = [1, 2, 3, 4];", gc.nogc());
+            String::from_static_str(agent, "const [a, b, , c] = [1, 2, 3, 4];", gc.nogc());
         agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
-        let a_key = String::from_static_str(&mut agent, "a", gc.nogc());
-        let b_key = String::from_static_str(&mut agent, "b", gc.nogc());
-        let c_key = String::from_static_str(&mut agent, "c", gc.nogc());
+        let a_key = String::from_static_str(agent, "a", gc.nogc());
+        let b_key = String::from_static_str(agent, "b", gc.nogc());
+        let c_key = String::from_static_str(agent, "c", gc.nogc());
 
         let realm = agent.current_realm(gc.nogc());
         let global_env = agent
@@ -1899,71 +2509,63 @@ mod test {
             .global_env
             .unwrap()
             .bind(gc.nogc());
-        assert!(global_env.has_lexical_declaration(&agent, a_key));
-        assert!(global_env.has_lexical_declaration(&agent, b_key));
-        assert!(global_env.has_lexical_declaration(&agent, c_key));
+        assert!(global_env.has_lexical_declaration(agent, a_key));
+        assert!(global_env.has_lexical_declaration(agent, b_key));
+        assert!(global_env.has_lexical_declaration(agent, c_key));
         assert_eq!(
-            unwrap_try(global_env.try_get_binding_value(&mut agent, a_key, true, gc.nogc()))
-                .unwrap(),
+            unwrap_try(global_env.try_get_binding_value(agent, a_key, true, gc.nogc())).unwrap(),
             1.into()
         );
         assert_eq!(
-            unwrap_try(global_env.try_get_binding_value(&mut agent, b_key, true, gc.nogc()))
-                .unwrap(),
+            unwrap_try(global_env.try_get_binding_value(agent, b_key, true, gc.nogc())).unwrap(),
             2.into()
         );
         assert_eq!(
-            unwrap_try(global_env.try_get_binding_value(&mut agent, c_key, true, gc.nogc()))
-                .unwrap(),
+            unwrap_try(global_env.try_get_binding_value(agent, c_key, true, gc.nogc())).unwrap(),
             4.into()
         );
     }
 
     #[test]
     fn do_while() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
         // let realm = agent.current_realm_id(gc.nogc());
 
         let source_text =
-            String::from_static_str(&mut agent, "let i = 0; do { i++ } while(i < 10)", gc.nogc());
+            String::from_static_str(agent, "let i = 0; do { i++ } while(i < 10)", gc.nogc());
         agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
 
         let realm = agent.current_realm(gc.nogc());
-        let i_key = String::from_static_str(&mut agent, "i", gc.nogc());
+        let i_key = String::from_static_str(agent, "i", gc.nogc());
         let global_env = agent
             .get_realm_record_by_id(realm)
             .global_env
             .unwrap()
             .bind(gc.nogc());
-        assert!(global_env.has_lexical_declaration(&agent, i_key));
+        assert!(global_env.has_lexical_declaration(agent, i_key));
 
         assert_eq!(
-            unwrap_try(global_env.try_get_binding_value(
-                &mut agent,
-                i_key.unbind(),
-                true,
-                gc.nogc()
-            ))
-            .unwrap(),
+            unwrap_try(global_env.try_get_binding_value(agent, i_key.unbind(), true, gc.nogc()))
+                .unwrap(),
             10.into()
         );
     }
 
     #[test]
     fn no_implicit_return() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
         // let realm = agent.current_realm_id(gc.nogc());
 
         let source_text =
-            String::from_static_str(&mut agent, "function foo() { 42; }; foo()", gc.nogc());
+            String::from_static_str(agent, "function foo() { 42; }; foo()", gc.nogc());
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
@@ -1972,20 +2574,5100 @@ mod test {
 
     #[test]
     fn for_in_loop() {
-        let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-        let mut gc = GcScope::new(&mut gc, &mut scope);
-        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-        initialize_default_realm(&mut agent, gc.reborrow());
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
         // let realm = agent.current_realm_id(gc.nogc());
 
         let source_text = String::from_static_str(
-            &mut agent,
+            agent,
             "for (let i in { a: 1, b: 2, c: 3 }) { i; }",
             gc.nogc(),
         );
         let result = agent
             .run_script(source_text.unbind(), gc.reborrow())
             .unwrap();
-        assert_eq!(result, Value::Undefined);
+        let result = result.unbind();
+        assert_eq!(result, Value::from_static_str(agent, "c", gc.nogc()));
+    }
+
+    #[test]
+    fn math_random_seed() {
+        let mut agent = Agent::new(
+            Options {
+                random_seed: Some(42),
+                ..test_options()
+            },
+            &DefaultHostHooks,
+        );
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(agent, "Math.random()", gc.nogc());
+        let first = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap()
+            .unbind();
+        let first = Number::try_from(first).unwrap().into_f64(agent);
+        let source_text = String::from_static_str(agent, "Math.random()", gc.nogc());
+        let second = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap()
+            .unbind();
+        let second = Number::try_from(second).unwrap().into_f64(agent);
+        assert_eq!(first, 0.8064042953277509);
+        assert_eq!(second, 0.11432892948280293);
+    }
+
+    #[test]
+    fn primitive_wrapper_truthiness() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(agent, "typeof new Boolean(false)", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "object", gc.nogc())
+        );
+
+        let source_text = String::from_static_str(
+            agent,
+            "if (new Boolean(false)) { 'truthy' } else { 'falsy' }",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "truthy", gc.nogc())
+        );
+
+        let source_text = String::from_static_str(agent, "new Boolean(false).valueOf()", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, false.into());
+
+        let source_text = String::from_static_str(agent, "new Number(5) + 1", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::Integer(SmallInteger::from(6)));
+    }
+
+    #[test]
+    fn string_object_index_enumeration() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var s = new String('ab'); s.x = 1; s[5] = 2; var keys = []; for (var k in s) keys.push(k); keys.join()",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "0,1,5,x", gc.nogc())
+        );
+
+        let source_text =
+            String::from_static_str(agent, "Object.keys(new String('ab')).join()", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "0,1", gc.nogc())
+        );
+
+        let source_text = String::from_static_str(
+            agent,
+            "Object.getOwnPropertyNames(new String('ab')).join()",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "0,1,length", gc.nogc())
+        );
+
+        let source_text = String::from_static_str(
+            agent,
+            "var s = new String('ab'); s[0] = 'z'; s[0] + s.length",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "a2", gc.nogc())
+        );
+    }
+
+    #[test]
+    fn primitive_wrapper_value_of_brand_checks() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text =
+            String::from_static_str(agent, "Number.prototype.valueOf.call('1')", gc.nogc());
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::TypeError);
+
+        let source_text =
+            String::from_static_str(agent, "String.prototype.valueOf.call(1)", gc.nogc());
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::TypeError);
+
+        let source_text =
+            String::from_static_str(agent, "Boolean.prototype.toString.call({})", gc.nogc());
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::TypeError);
+
+        let source_text =
+            String::from_static_str(agent, "Symbol.prototype.valueOf.call(1)", gc.nogc());
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::TypeError);
+
+        let source_text =
+            String::from_static_str(agent, "BigInt.prototype.valueOf.call(1)", gc.nogc());
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::TypeError);
+
+        let source_text = String::from_static_str(agent, "new Symbol()", gc.nogc());
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::TypeError);
+
+        let source_text = String::from_static_str(agent, "new BigInt(1)", gc.nogc());
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::TypeError);
+
+        let source_text =
+            String::from_static_str(agent, "typeof Symbol('a') + typeof BigInt(1)", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "symbolbigint", gc.nogc())
+        );
+    }
+
+    #[test]
+    fn object_wrapping_round_trip() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var o = Object('ab'); typeof o === 'object' && o instanceof String && o.valueOf() === 'ab'",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var o = Object(1); o instanceof Number && o.valueOf() === 1",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var o = Object(true); o instanceof Boolean && o.valueOf() === true",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var s = Symbol(); var o = Object(s); typeof o === 'object' && o.valueOf() === s",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var o = Object(1n); typeof o === 'object' && o.valueOf() === 1n",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(agent, "var o = {}; Object(o) === o", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+    }
+
+    #[test]
+    fn new_target() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(
+            agent,
+            "function F() { return new.target === undefined ? 'call' : 'new'; } F() + ',' + (new F() instanceof F)",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "call,true", gc.nogc())
+        );
+
+        let source_text = String::from_static_str(
+            agent,
+            "var seen; function G() { seen = new.target; } new G(); seen === G",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var seen; class Base { constructor() { seen = new.target; } } class Derived extends Base { constructor() { super(); } } new Derived(); seen === Derived",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var seen2; class Base2 {} class Derived2 extends Base2 { constructor() { seen2 = new.target; super(); } } new Derived2(); seen2 === Derived2",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "function H() { return eval('new.target'); } H() === undefined && new H() === H",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+    }
+
+    #[test]
+    fn new_target_outside_function() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(agent, "new.target", gc.nogc());
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::SyntaxError);
+
+        let source_text = String::from_static_str(agent, "eval('new.target')", gc.nogc());
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::SyntaxError);
+
+        // Other early errors in an eval inside a function are still reported.
+        let source_text = String::from_static_str(
+            agent,
+            "function F() { return eval('new.target; let a; let a;'); } F()",
+            gc.nogc(),
+        );
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::SyntaxError);
+    }
+
+    #[test]
+    fn nested_script_evaluation() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let global = agent.current_global_object(gc.nogc()).unbind();
+
+        fn run_script<'a>(
+            agent: &mut Agent,
+            _: Value,
+            arguments: ArgumentsList,
+            mut gc: GcScope<'a, '_>,
+        ) -> JsResult<'a, Value<'a>> {
+            let source_text = arguments
+                .get(0)
+                .to_string(agent, gc.reborrow())
+                .unbind()?
+                .bind(gc.nogc());
+            agent.run_script(source_text.unbind(), gc)
+        }
+
+        fn active_script<'a>(
+            agent: &mut Agent,
+            _: Value,
+            _: ArgumentsList,
+            gc: GcScope<'a, '_>,
+        ) -> JsResult<'a, Value<'a>> {
+            match get_active_script_or_module(agent, gc.nogc()) {
+                Some(ScriptOrModule::Script(script)) => Ok(Value::from(script.into_u32())),
+                _ => Ok(Value::Null),
+            }
+        }
+
+        for (name, behaviour) in [
+            ("runScript", Behaviour::Regular(run_script)),
+            ("activeScript", Behaviour::Regular(active_script)),
+        ] {
+            let func = create_builtin_function(
+                agent,
+                behaviour,
+                BuiltinFunctionArgs::new(1, name),
+                gc.nogc(),
+            );
+            let key = PropertyKey::from_static_str(agent, name, gc.nogc());
+            create_data_property_or_throw(
+                agent,
+                global,
+                key.unbind(),
+                func.into_value().unbind(),
+                gc.reborrow(),
+            )
+            .unwrap();
+        }
+
+        let source_text = String::from_static_str(
+            agent,
+            "var before = activeScript();
+            var a = runScript('var inner = 40; inner + 2');
+            var b; try { runScript('throw new TypeError(\\'inner\\')'); } catch (e) { b = e.message; }
+            var c; try { runScript('('); } catch (e) { c = e instanceof SyntaxError; }
+            var after = activeScript();
+            [a, b, c, before === after, inner].join()",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "42,inner,true,true,40", gc.nogc())
+        );
+    }
+
+    #[test]
+    fn super_property() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(
+            agent,
+            "class A { greet() { return 'hi'; } } class B extends A { greet() { return super.greet() + '!'; } } new B().greet()",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "hi!", gc.nogc())
+        );
+
+        let source_text = String::from_static_str(
+            agent,
+            "class C { static f() { return 1; } } class D extends C { static f() { return super.f() + 1; } } D.f()",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::Integer(SmallInteger::from(2)));
+
+        let source_text = String::from_static_str(
+            agent,
+            "var p = { f() { return this.v; } }; var o = { __proto__: p, v: 3, f() { return super.f() * 2; } }; o.f()",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::Integer(SmallInteger::from(6)));
+
+        let source_text = String::from_static_str(
+            agent,
+            "class E { g() { return 2; } } class F extends E { g() { var k = 'g'; return (() => super[k]())(); } } new F().g()",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::Integer(SmallInteger::from(2)));
+
+        let source_text = String::from_static_str(
+            agent,
+            "class G {} class H extends G { set(v) { super.x = v; return this.x; } } new H().set(7)",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::Integer(SmallInteger::from(7)));
+    }
+
+    #[test]
+    fn this_before_super_call() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(
+            agent,
+            "class A {} class B extends A { constructor() { this.x = 1; super(); } } new B()",
+            gc.nogc(),
+        );
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::ReferenceError);
+
+        let source_text = String::from_static_str(
+            agent,
+            "class C {} class D extends C { constructor() { super(); super(); } } new D()",
+            gc.nogc(),
+        );
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::ReferenceError);
+
+        let source_text = String::from_static_str(
+            agent,
+            "class E { constructor(x) { this.x = x; } } class F extends E { constructor() { super(5); this.y = 1; } } var f = new F(); f.x + f.y",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::Integer(SmallInteger::from(6)));
+    }
+
+    #[test]
+    fn number_property_keys() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var a = {}; a[1.5] = 1; a['1.5'] === 1 && Object.keys(a).length === 1",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var b = {}; b['1.5'] = 2; b[1.5] === 2 && Object.keys(b).length === 1",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var c = {}; c[-0] = 3; c['0'] === 3 && Object.keys(c)[0] === '0'",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var d = { [1e21]: 4, [0.1]: 5, [-1.25]: 6 }; Object.keys(d).join()",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "1e+21,0.1,-1.25", gc.nogc())
+        );
+
+        let source_text = String::from_static_str(
+            agent,
+            "var e = {}; e[2 ** 53] = 7; e['9007199254740992']",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::Integer(SmallInteger::from(7)));
+
+        let source_text = String::from_static_str(
+            agent,
+            "var f = {}; f[NaN] = 8; f[-Infinity] = 9; f.NaN + f['-Infinity']",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::Integer(SmallInteger::from(17)));
+
+        let source_text = String::from_static_str(
+            agent,
+            "Symbol(42).description === '42' && Symbol(-0).description === '0' && Symbol(1.5).description === '1.5'",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+    }
+
+    #[test]
+    fn number_property_key_storage() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, gc) = root.scope();
+        let gc = gc.nogc();
+
+        // Short numeric keys never allocate heap strings.
+        let strings_before = agent.heap.strings.len();
+        let mut keys = vec![];
+        for value in [1.5, -0.0, 0.1, -1.25, 1e21, f64::NAN, f64::INFINITY] {
+            let number = Number::from_f64(agent, value, gc);
+            keys.push(number_to_property_key(agent, number, gc));
+        }
+        assert_eq!(agent.heap.strings.len(), strings_before);
+        assert_eq!(keys[1], PropertyKey::Integer(0.into()));
+        assert_eq!(keys[0], PropertyKey::from_static_str(agent, "1.5", gc));
+
+        // Long numeric keys are interned and equal their source text form.
+        let number = Number::from_f64(agent, 2f64.powi(60), gc);
+        let long_key = number_to_property_key(agent, number, gc);
+        assert_eq!(agent.heap.strings.len(), strings_before + 1);
+        let number = Number::from_f64(agent, 2f64.powi(60), gc);
+        assert_eq!(number_to_property_key(agent, number, gc), long_key);
+        assert_eq!(agent.heap.strings.len(), strings_before + 1);
+        assert_eq!(
+            PropertyKey::from_static_str(agent, "1152921504606847000", gc),
+            long_key
+        );
+
+        // Keys created either way hash identically in property tables.
+        let mut set = PropertyKeySet::new(gc);
+        for key in keys.iter().copied().chain([long_key]) {
+            assert!(set.insert(agent, key));
+        }
+        for str in ["1.5", "0", "0.1", "-1.25", "1e+21", "NaN", "Infinity"] {
+            let key = PropertyKey::from_static_str(agent, str, gc);
+            assert!(set.contains(agent, key));
+            assert!(!set.insert(agent, key));
+        }
+        let key = PropertyKey::from_static_str(agent, "1152921504606847000", gc);
+        assert!(!set.insert(agent, key));
+    }
+
+    #[test]
+    fn function_calls_without_environments() {
+        let mut agent = Agent::new(
+            // Collecting garbage would change the Environment counts.
+            Options::default(),
+            &DefaultHostHooks,
+        );
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        // Warm up the function so that its bytecode is compiled.
+        let source_text = String::from_static_str(
+            agent,
+            "function fib(n) { if (n < 2) return n; const a = fib(n - 1); let b = fib(n - 2); return a + b; } fib(2)",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::from(1));
+
+        let function_envs = agent.heap.environments.function.len();
+        let declarative_envs = agent.heap.environments.declarative.len();
+        let source_text = String::from_static_str(agent, "fib(25)", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::from(75025));
+        // Nearly 250 000 calls were made without allocating an Environment
+        // Record for any of them.
+        assert_eq!(agent.heap.environments.function.len(), function_envs);
+        assert_eq!(agent.heap.environments.declarative.len(), declarative_envs);
+
+        // Functions creating closures still get an environment per call.
+        let source_text = String::from_static_str(
+            agent,
+            "function counter(start) { let count = start; return () => ++count; } var c1 = counter(0), c2 = counter(10); c1(); c1(); c2(); c1() + c2()",
+            gc.nogc(),
+        );
+        let function_envs = agent.heap.environments.function.len();
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::from(15));
+        assert!(agent.heap.environments.function.len() > function_envs);
+
+        // Direct eval can observe local bindings, so those are kept in an
+        // environment as well.
+        let source_text = String::from_static_str(
+            agent,
+            "function evaluate(x) { var y = x * 2; return eval('x + y'); } evaluate(1); evaluate(2)",
+            gc.nogc(),
+        );
+        let function_envs = agent.heap.environments.function.len();
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::from(6));
+        assert_eq!(agent.heap.environments.function.len(), function_envs + 2);
+
+        // Temporal dead zone and const assignment errors match those of
+        // environment bindings.
+        let source_text = String::from_static_str(
+            agent,
+            "function tdz() { try { x = 1; } catch (err) { return err instanceof ReferenceError; } let x; } tdz() && tdz()",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+        let source_text = String::from_static_str(
+            agent,
+            "function assign() { 'use strict'; const x = 1; x = 2; } assign()",
+            gc.nogc(),
+        );
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::TypeError);
+    }
+
+    #[test]
+    fn compound_assignment_operators() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text =
+            String::from_static_str(agent, "var obj = { x: 3 }; obj.x **= 2; obj.x", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::Integer(SmallInteger::from(9)));
+
+        let source_text = String::from_static_str(agent, "var u = -1; u >>>= 0; u", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::Integer(SmallInteger::from(4294967295u32))
+        );
+
+        let source_text = String::from_static_str(
+            agent,
+            "var bits = 5; bits <<= 2; bits >>= 1; bits &= 6; bits |= 1; bits ^= 4; bits",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::Integer(SmallInteger::from(7)));
+
+        let source_text =
+            String::from_static_str(agent, "var zero = 0; zero **= 3; 1 / zero", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_f64(agent, f64::INFINITY, gc.nogc())
+        );
+
+        let source_text = String::from_static_str(
+            agent,
+            "var big = 5n; big **= 2n; big <<= 1n; big >>= 2n; big &= 15n; big |= 16n; big ^= 1n; big === 29n",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text =
+            String::from_static_str(agent, "var mixed = 1n; mixed >>>= 1n", gc.nogc());
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::TypeError);
+
+        let source_text =
+            String::from_static_str(agent, "var mixed2 = 1n; mixed2 **= 2", gc.nogc());
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::TypeError);
+
+        let source_text = String::from_static_str(
+            agent,
+            "var gets = 0, sets = 0; var acc = { get v() { gets++; return 2; }, set v(x) { sets++; this.stored = x; } }; acc.v **= 10; gets === 1 && sets === 1 && acc.stored === 1024",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var keyEvals = 0; var arr = [1, 6]; arr[(keyEvals++, 1)] >>>= 1; keyEvals === 1 && arr[1] === 3",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "function local() { let n = 3; n **= 2; n ^= 1; return n; } local() + local()",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::Integer(SmallInteger::from(16)));
+    }
+
+    #[test]
+    fn for_in_inherited_keys() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(
+            agent,
+            "function forInKeys(o) { var r = []; for (var k in o) r.push(k); return r.join(); } var proto = { a: 1, b: 2, 1: 'x' }; var inherits = Object.create(proto); inherits.c = 3; inherits[0] = 0; forInKeys(inherits)",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "0,c,1,a,b", gc.nogc())
+        );
+
+        let source_text = String::from_static_str(
+            agent,
+            "var shadowed = Object.create({ x: 1, y: 2 }); Object.defineProperty(shadowed, 'x', { value: 5, enumerable: false }); forInKeys(shadowed)",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "y", gc.nogc())
+        );
+
+        let source_text = String::from_static_str(
+            agent,
+            "var overridden = Object.create({ a: 1 }); overridden.a = 2; forInKeys(overridden)",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "a", gc.nogc())
+        );
+
+        let source_text = String::from_static_str(
+            agent,
+            "var deleting = Object.create({ d: 1, e: 2 }); deleting.a = 1; deleting.b = 2; var seen = []; for (var k in deleting) { seen.push(k); delete deleting.b; delete Object.getPrototypeOf(deleting).e; } seen.join()",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "a,d", gc.nogc())
+        );
+
+        let source_text = String::from_static_str(
+            agent,
+            "var deep = Object.create(Object.create({ deep: 1 })); deep.own = 1; deep[Symbol()] = 1; forInKeys(deep)",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "own,deep", gc.nogc())
+        );
+    }
+
+    #[test]
+    fn own_property_keys_cache() {
+        let mut agent = Agent::new(
+            // Collecting garbage clears the cache.
+            Options::default(),
+            &DefaultHostHooks,
+        );
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var config = { b: 1, 1: 2, a: 3, 0: 4 }; Object.keys(config).join()",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "0,1,b,a", gc.nogc())
+        );
+
+        // Unchanged object: the keys come from the cache.
+        let hits = agent.heap.own_keys_cache.hits();
+        let source_text = String::from_static_str(
+            agent,
+            "for (var i = 0; i < 10; i++) Object.keys(config); Object.keys(config).join()",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "0,1,b,a", gc.nogc())
+        );
+        assert_eq!(agent.heap.own_keys_cache.hits(), hits + 11);
+
+        // Adding a key invalidates the cache.
+        let hits = agent.heap.own_keys_cache.hits();
+        let source_text = String::from_static_str(
+            agent,
+            "config[2] = 5; Object.keys(config).join()",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "0,1,2,b,a", gc.nogc())
+        );
+        assert_eq!(agent.heap.own_keys_cache.hits(), hits);
+
+        // Changing a value does not.
+        let hits = agent.heap.own_keys_cache.hits();
+        let source_text =
+            String::from_static_str(agent, "config.a = 6; Object.keys(config).join()", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "0,1,2,b,a", gc.nogc())
+        );
+        assert_eq!(agent.heap.own_keys_cache.hits(), hits + 1);
+
+        // Nor does garbage collection change the result.
+        agent.gc(gc.reborrow());
+        let source_text = String::from_static_str(
+            agent,
+            "delete config.b; Object.keys(config).join()",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "0,1,2,a", gc.nogc())
+        );
+    }
+
+    #[test]
+    fn for_in_keys_cache_prototype_mutation() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(
+            agent,
+            "function forInKeys(o) { var r = []; for (var k in o) r.push(k); return r.join(); } var proto = { a: 1 }; var object = Object.create(proto); object.b = 2; var results = [forInKeys(object), forInKeys(object)]; proto.c = 3; results.push(forInKeys(object)); Object.defineProperty(proto, 'a', { enumerable: false }); results.push(forInKeys(object)); Object.setPrototypeOf(object, { d: 4 }); results.push(forInKeys(object)); Object.setPrototypeOf(object, [5]); results.push(forInKeys(object)); results.join(' | ')",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "b,a | b,a | b,a,c | b,c | b,d | b,0", gc.nogc())
+        );
+    }
+
+    #[test]
+    fn invalidate_enumeration_cache() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(
+            agent,
+            "function enumerate(o) { var r = []; for (var k in o) r.push(k); return r.join() + ' | ' + Object.keys(o).join(); } var native = { a: 1 }; var child = Object.create(native); enumerate(native); enumerate(child); native",
+            gc.nogc(),
+        );
+        let native = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        let Value::Object(native) = native.unbind() else {
+            unreachable!()
+        };
+
+        // Add a property behind the engine's back, the way a host mutating
+        // the native data of an object would.
+        let key = PropertyKey::from_static_str(agent, "b", gc.nogc());
+        let crate::Heap {
+            objects, elements, ..
+        } = &mut agent.heap;
+        objects[native]
+            .property_storage
+            .push(elements, key, Some(Value::from(2)), None);
+
+        let source_text = String::from_static_str(
+            agent,
+            "enumerate(native) + ' | ' + enumerate(child)",
+            gc.nogc(),
+        );
+        let stale = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        // Garbage collection drops the cached keys as well.
+        if !agent.options.gc_stress {
+            assert_eq!(
+                stale.unbind(),
+                Value::from_static_str(agent, "a | a | a | ", gc.nogc())
+            );
+        }
+
+        native.invalidate_enumeration_cache(agent);
+        let source_text = String::from_static_str(
+            agent,
+            "enumerate(native) + ' | ' + enumerate(child)",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "a,b | a,b | a,b | ", gc.nogc())
+        );
+    }
+
+    #[test]
+    fn source_code_line_table_is_reused() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+        let realm = agent.current_realm(gc.nogc());
+
+        let source_text = String::from_static_str(agent, "var a = 1;\nvar b = 2;\n", gc.nogc());
+        let script = parse_script(agent, source_text, realm, false, None, gc.nogc()).unwrap();
+        let source_code = script.source_code(agent);
+        assert_eq!(agent[source_code].line_table_builds(), 0);
+
+        assert_eq!(source_code.line_column_at(agent, 15), (1, 4));
+        assert_eq!(source_code.line_column_at(agent, 4), (0, 4));
+        assert_eq!(source_code.line(agent, 1), Some("var b = 2;"));
+        assert_eq!(
+            agent.script_source(script),
+            Some("var a = 1;\nvar b = 2;\n")
+        );
+        assert_eq!(agent[source_code].line_table_builds(), 1);
+    }
+
+    #[test]
+    fn symbol_to_primitive() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var hints = []; var prim = { [Symbol.toPrimitive](hint) { hints.push(hint); return hint === 'number' ? 42 : hint === 'string' ? 'str' : 'dflt'; } }; prim + 1",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "dflt1", gc.nogc())
+        );
+
+        let source_text = String::from_static_str(agent, "`${prim}` + String(prim)", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "strstr", gc.nogc())
+        );
+
+        let source_text = String::from_static_str(agent, "+prim * 2", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::Integer(SmallInteger::from(84)));
+
+        let source_text = String::from_static_str(agent, "prim == 'dflt'", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "hints.join() === 'default,string,string,number,default'",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "({ [Symbol.toPrimitive]() { return {}; } }) + 1",
+            gc.nogc(),
+        );
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::TypeError);
+
+        let source_text =
+            String::from_static_str(agent, "({ [Symbol.toPrimitive]: 1 }) + 1", gc.nogc());
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::TypeError);
+
+        let source_text = String::from_static_str(
+            agent,
+            "({ [Symbol.toPrimitive]: undefined, valueOf() { return 7; } }) + 1",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::Integer(SmallInteger::from(8)));
+
+        let source_text = String::from_static_str(
+            agent,
+            "var order = []; var fallback = { valueOf() { order.push('valueOf'); return {}; }, toString() { order.push('toString'); return 'x'; } }; fallback + `${fallback}` + order.join() === 'xxtoString,valueOf,toString'",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+    }
+
+    #[test]
+    fn object_destructuring_in_for_of_and_catch() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(
+            agent,
+            "let forOfSum = 0; for (const {a, b = 10, ['c']: c = 100} of [{a: 1}, {a: 2, b: 3, c: 4}]) { forOfSum += a + b + c; } forOfSum",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::Integer(SmallInteger::from(120)));
+
+        let source_text = String::from_static_str(
+            agent,
+            "let forOfKeys = ''; const forOfKey = 'x'; for (let {[forOfKey]: x, ...rest} of [{x: 1, y: 2}]) { forOfKeys = x + Object.keys(rest).join(); } forOfKeys",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "1y", gc.nogc())
+        );
+
+        let source_text =
+            String::from_static_str(agent, "for (const {a} of [undefined]) {}", gc.nogc());
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::TypeError);
+
+        let source_text = String::from_static_str(agent, "for (const {a} of [null]) {}", gc.nogc());
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::TypeError);
+
+        let source_text = String::from_static_str(
+            agent,
+            "try { throw new RangeError('bad'); } catch ({message, ['name']: name, code = 7}) { message + name + code === 'badRangeError7'; }",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text =
+            String::from_static_str(agent, "try { throw null; } catch ({message}) {}", gc.nogc());
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::TypeError);
+    }
+
+    #[test]
+    fn conditional_branches_match_value_semantics() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        // Every comparison in a test position must branch the same way as the
+        // boolean it produces in a value position.
+        let source_text = String::from_static_str(
+            agent,
+            r#"
+            var values = [0, -0, 1, -1, NaN, Infinity, "", "0", "1", "a", "b", null, undefined,
+                true, false, 1n, 2n, {}, [], [1], { valueOf() { return 1; } }];
+            var mismatches = 0;
+            for (var op of ["<", "<=", ">", ">=", "==", "!=", "===", "!=="]) {
+                var value = eval(`(function (a, b) { return a ${op} b; })`);
+                var tests = [
+                    eval(`(function (a, b) { if (a ${op} b) return true; return false; })`),
+                    eval(`(function (a, b) { if (!(a ${op} b)) return false; else return true; })`),
+                    eval(`(function (a, b) { return a ${op} b ? true : false; })`),
+                    eval(`(function (a, b) { while (a ${op} b) return true; return false; })`),
+                    eval(`(function (a, b) { if (false || a ${op} b) return true; return false; })`),
+                    eval(`(function (a, b) { if (true && a ${op} b) return true; return false; })`),
+                    eval(`(function (a, b) { if (!(false || a ${op} b)) return false; return true; })`),
+                ];
+                for (var a of values) for (var b of values) {
+                    var expected;
+                    try { expected = value(a, b); } catch (e) { expected = "throw"; }
+                    for (var test of tests) {
+                        var got;
+                        try { got = test(a, b); } catch (e) { got = "throw"; }
+                        if (got !== expected) mismatches++;
+                    }
+                }
+            }
+            for (var v of values) {
+                if ((v == null ? 1 : 0) !== (null == v) + 0) mismatches++;
+                if ((v != null ? 1 : 0) !== (v != null) + 0) mismatches++;
+            }
+            mismatches
+            "#,
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::from(0));
+
+        // Short-circuiting conditions evaluate the same operands as in a
+        // value position.
+        let source_text = String::from_static_str(
+            agent,
+            r#"
+            var log = "";
+            function t(name, value) { log += name; return value; }
+            var operands = [0, 1, "", "x", null, NaN];
+            var mismatches = 0;
+            for (var a of operands) for (var b of operands) for (var c of operands) for (var d of operands) {
+                log = "";
+                var value = !!((t("a", a) && t("b", b)) || (t("c", c) && !t("d", d)));
+                var valueLog = log;
+                log = "";
+                var branch = false;
+                if ((t("a", a) && t("b", b)) || (t("c", c) && !t("d", d))) branch = true;
+                if (value !== branch || valueLog !== log) mismatches++;
+                log = "";
+                value = !((t("a", a) || t("b", b)) && (t("c", c) ?? t("d", d)));
+                valueLog = log;
+                log = "";
+                branch = false;
+                while (!((t("a", a) || t("b", b)) && (t("c", c) ?? t("d", d)))) { branch = true; break; }
+                if (value !== branch || valueLog !== log) mismatches++;
+            }
+            var i = 0, iterations = 0;
+            do { iterations++; } while (i++ < 3 && i !== 10);
+            mismatches + iterations
+            "#,
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::from(4));
+    }
+
+    #[test]
+    fn conditional_branches_are_fused() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(
+            agent,
+            "function hot(n) { let count = 0; for (let i = 0; i < n; i++) { if (i % 3 !== 0 && !(i > 50) && n != null) count++; } return count; } hot(100)",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::from(34));
+
+        let source_text = String::from_static_str(agent, "hot", gc.nogc());
+        let hot = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap()
+            .unbind();
+        let hot = ECMAScriptFunction::try_from(hot).unwrap();
+        let executable = hot.get_executable(agent, gc.nogc());
+        let instructions = InstructionIter::new(&agent[executable].instructions)
+            .map(|(_, instr)| instr.kind)
+            .collect::<Vec<_>>();
+        // Each condition is a single fused compare-and-branch instruction:
+        // no comparison result, logical not, or ToBoolean jump is dispatched.
+        assert_eq!(
+            instructions
+                .iter()
+                .filter(|kind| matches!(
+                    kind,
+                    Instruction::JumpIfNotLessThan
+                        | Instruction::JumpIfStrictlyEqual
+                        | Instruction::JumpIfGreaterThan
+                        | Instruction::JumpIfNullOrUndefined
+                ))
+                .count(),
+            4
+        );
+        assert!(!instructions.iter().any(|kind| matches!(
+            kind,
+            Instruction::LessThan
+                | Instruction::GreaterThan
+                | Instruction::IsStrictlyEqual
+                | Instruction::IsLooselyEqual
+                | Instruction::IsNullOrUndefined
+                | Instruction::LogicalNot
+                | Instruction::JumpIfNot
+                | Instruction::JumpIfTrue
+        )));
+    }
+
+    #[test]
+    fn spread_calls_keep_argument_order() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(
+            agent,
+            "function f(...args) { return args.join(); } function g(a, b) { return f(b, a) + ';'; } f(1, ...[2, 3], g(...[4, 5]), ...'67', 8)",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "1,2,3,5,4;,6,7,8", gc.nogc())
+        );
+
+        let source_text = String::from_static_str(
+            agent,
+            "function sum(a, b, c) { return a + b + c; } [1, 2].map((x, i) => sum(x, ...[i, sum(...[x, x, x])])).join()",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "4,9", gc.nogc())
+        );
+
+        let source_text = String::from_static_str(
+            agent,
+            "class A { constructor(...args) { this.args = args; } } class B extends A { constructor(...args) { super(0, ...args, new A(...args).args.length); } } new B(1, 2).args.join()",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "0,1,2,2", gc.nogc())
+        );
+
+        let source_text = String::from_static_str(
+            agent,
+            "function f(a, b) { return arguments.length * 10 + (b === undefined ? 1 : 0); } f(...[]) + f(...[1]) + f(...[1, 2, 3])",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::Integer(SmallInteger::from(42)));
+    }
+
+    #[test]
+    fn object_get_own_property_descriptors() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(
+            agent,
+            "{ const d = Object.getOwnPropertyDescriptor({ a: 1 }, 'a'); d.value === 1 && d.writable && d.enumerable && d.configurable && !('get' in d) && !('set' in d) }",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "{ const o = {}; const get = () => 2; Object.defineProperty(o, 'b', { get, enumerable: true }); const d = Object.getOwnPropertyDescriptor(o, 'b'); d.get === get && d.set === undefined && 'set' in d && d.enumerable && !d.configurable && !('value' in d) && !('writable' in d) }",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "Object.getOwnPropertyDescriptor({}, 'missing')",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::Undefined);
+
+        let source_text = String::from_static_str(
+            agent,
+            "Object.getOwnPropertyDescriptor(Object.create({ a: 1 }), 'a')",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::Undefined);
+
+        let source_text = String::from_static_str(
+            agent,
+            "{ const s = Symbol(); const o = { x: 1, [s]: 2, set y(v) {} }; Object.defineProperty(o, 'z', { value: 3 }); const d = Object.getOwnPropertyDescriptors(o); Reflect.ownKeys(d).length === 4 && d[s].value === 2 && typeof d.y.set === 'function' && d.y.get === undefined && d.z.writable === false && Object.getPrototypeOf(d) === Object.prototype }",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "JSON.stringify(Object.getOwnPropertyDescriptor([1, 2], 'length'))",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(
+                agent,
+                "{\"value\":2,\"writable\":true,\"enumerable\":false,\"configurable\":false}",
+                gc.nogc()
+            )
+        );
+
+        let source_text = String::from_static_str(
+            agent,
+            "Object.getOwnPropertyDescriptor(null, 'a')",
+            gc.nogc(),
+        );
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::TypeError);
+    }
+
+    #[test]
+    fn builtin_subclass_species() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(
+            agent,
+            "class MyArr extends Array {} globalThis.MyArr = MyArr; Object.getPrototypeOf(MyArr) === Array && MyArr[Symbol.species] === MyArr",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "MyArr.of(1, 2) instanceof MyArr && MyArr.from([1]) instanceof MyArr && MyArr.of(1, 2).length === 2",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "const a = MyArr.of(1, 2, 3); a.map(x => x) instanceof MyArr && a.slice(1) instanceof MyArr && a.filter(x => x) instanceof MyArr",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "class Plain extends Array { static get [Symbol.species]() { return Array; } } const m = Plain.of(1, 2).map(x => x * 2); m.constructor === Array && m.join() === '2,4'",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "{ const n = [1, 2]; n.constructor = { [Symbol.species]: null }; const r = n.map(x => x); Object.getPrototypeOf(r) === Array.prototype && r.join() === '1,2' }",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "{ const bad = [1]; bad.constructor = { [Symbol.species]: Math.max }; bad.map(x => x) }",
+            gc.nogc(),
+        );
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::TypeError);
+
+        let source_text = String::from_static_str(
+            agent,
+            "class MyPromise extends Promise {} MyPromise.resolve(1).then() instanceof MyPromise && MyPromise[Symbol.species] === MyPromise",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "{ const p = Promise.resolve(1); p.constructor = { [Symbol.species]: 5 }; p.then() }",
+            gc.nogc(),
+        );
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::TypeError);
+    }
+
+    #[test]
+    fn typed_array_slice_species() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var ta = new Uint8Array([1, 2, 3, 4]);
+            var s = ta.slice(1, -1);
+            s instanceof Uint8Array && s.join() === '2,3' && s.buffer !== ta.buffer &&
+                ta.slice().join() === '1,2,3,4' && ta.slice(3, 1).length === 0 &&
+                ta.slice(-Infinity, Infinity).length === 4",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "class MyBytes extends Uint8Array {}
+            var mine = new MyBytes([1, 2, 3]).slice(1);
+            class Widened extends Uint8Array { static get [Symbol.species]() { return Float64Array; } }
+            var widened = new Widened([255, 1]).slice();
+            mine instanceof MyBytes && mine.join() === '2,3' &&
+                widened instanceof Float64Array && widened.join() === '255,1'",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "{ const ta = new Int8Array(2); ta.constructor = { [Symbol.species]: BigInt64Array }; ta.slice() }",
+            gc.nogc(),
+        );
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::TypeError);
+    }
+
+    #[test]
+    fn object_get_own_property_names_and_symbols() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(
+            agent,
+            "{ const o = { b: 1, 2: 'x', a: 2, 1: 'y' }; Object.defineProperty(o, 'hidden', { value: 3 }); Object.getOwnPropertyNames(o).join() + '|' + Object.keys(o).join() }",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "1,2,b,a,hidden|1,2,b,a", gc.nogc())
+        );
+
+        let source_text = String::from_static_str(
+            agent,
+            "{ const s = Symbol('s'); const o = { a: 1, [s]: 2 }; const symbols = Object.getOwnPropertySymbols(o); symbols.length === 1 && symbols[0] === s && !Object.getOwnPropertyNames(o).includes(s) }",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "Object.getOwnPropertyNames('ab').join() + '|' + Object.getOwnPropertySymbols(1).length",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "0,1,length|0", gc.nogc())
+        );
+
+        let source_text = String::from_static_str(
+            agent,
+            "{ const a = [, 2]; Object.defineProperty(a, 0, { get() { return 1; } }); a.x = 3; Object.getOwnPropertyNames(a).join() }",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "0,1,length,x", gc.nogc())
+        );
+
+        let source_text =
+            String::from_static_str(agent, "Object.getOwnPropertyNames(null)", gc.nogc());
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::TypeError);
+
+        let source_text =
+            String::from_static_str(agent, "Object.getOwnPropertySymbols(undefined)", gc.nogc());
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::TypeError);
+    }
+
+    #[test]
+    fn symbol_descriptions() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(agent, "String(Symbol())", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "Symbol()", gc.nogc())
+        );
+
+        let source_text =
+            String::from_static_str(agent, "Symbol().description === undefined", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text =
+            String::from_static_str(agent, "Symbol('').description === ''", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(agent, "String(Symbol(''))", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "Symbol()", gc.nogc())
+        );
+
+        let source_text =
+            String::from_static_str(agent, "Object(Symbol('desc')).toString()", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "Symbol(desc)", gc.nogc())
+        );
+
+        let source_text = String::from_static_str(
+            agent,
+            "var sym = Symbol('p'); Object(sym)[Symbol.toPrimitive]('number') === sym",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+    }
+
+    #[test]
+    fn primitive_value_of_brand_checks() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text =
+            String::from_static_str(agent, "Symbol.prototype.valueOf.call(1n)", gc.nogc());
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::TypeError);
+
+        let source_text =
+            String::from_static_str(agent, "BigInt.prototype.valueOf.call(Symbol())", gc.nogc());
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::TypeError);
+
+        let source_text =
+            String::from_static_str(agent, "Symbol.prototype.toString.call({})", gc.nogc());
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::TypeError);
+
+        let source_text = String::from_static_str(
+            agent,
+            "BigInt.prototype.toString.call(Object(Symbol()))",
+            gc.nogc(),
+        );
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::TypeError);
+
+        let source_text = String::from_static_str(
+            agent,
+            "Object.getOwnPropertyDescriptor(Symbol.prototype, 'description').get.call('desc')",
+            gc.nogc(),
+        );
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::TypeError);
+
+        let source_text = String::from_static_str(
+            agent,
+            "BigInt.prototype.valueOf.call(Object(5n)) === 5n",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+    }
+
+    #[test]
+    fn bigint_to_string_radix() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var x = 2n ** 256n - 12345678901234567890n; var s = x.toString(36); var y = 0n; for (var c of s) y = y * 36n + BigInt(parseInt(c, 36)); y === x && s.length === 50",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(agent, "(-255n).toString(16)", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "-ff", gc.nogc())
+        );
+
+        let source_text = String::from_static_str(agent, "(10n).toString(37)", gc.nogc());
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::RangeError);
+
+        let source_text = String::from_static_str(agent, "(1234n).toLocaleString('en')", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "1234", gc.nogc())
+        );
+    }
+
+    #[test]
+    fn computed_member_assignment_evaluation_order() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var log = []; var o = {}; function obj() { log.push('object'); return o; } function key() { log.push('key'); return { toString() { log.push('ToPropertyKey'); return 'k'; } }; } function value() { log.push('value'); return 1; } obj()[key()] = value(); log.join() + ' ' + o.k",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "object,key,ToPropertyKey,value 1", gc.nogc())
+        );
+
+        let source_text = String::from_static_str(
+            agent,
+            "var log = []; var o = { k: 1 }; function obj() { log.push('object'); return o; } function key() { log.push('key'); return { toString() { log.push('ToPropertyKey'); return 'k'; } }; } function value() { log.push('value'); return 1; } obj()[key()] += value(); log.join() + ' ' + o.k",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "object,key,ToPropertyKey,value 2", gc.nogc())
+        );
+    }
+
+    #[test]
+    fn string_prototype_concat() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(
+            agent,
+            "'a'.concat('b', 1, null, undefined, [2, 3])",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "ab1nullundefined2,3", gc.nogc())
+        );
+
+        let source_text = String::from_static_str(agent, "'a'.concat()", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "a", gc.nogc())
+        );
+
+        let source_text = String::from_static_str(
+            agent,
+            "String.prototype.concat.call(1, 2, { toString() { return 'c'; } })",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "12c", gc.nogc())
+        );
+    }
+
+    #[test]
+    fn array_prototype_concat_spreadable() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var inner = [3, 4]; JSON.stringify([1].concat([2, [inner]], 5))",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "[1,2,[[3,4]],5]", gc.nogc())
+        );
+
+        let source_text = String::from_static_str(
+            agent,
+            "var notSpread = [2, 3]; notSpread[Symbol.isConcatSpreadable] = false; var flagged = { length: 2, 0: 'x', 1: 'y', [Symbol.isConcatSpreadable]: true }; var result = [1].concat(notSpread, flagged); JSON.stringify(result) + ' ' + (result[1] === notSpread)",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "[1,[2,3],\"x\",\"y\"] true", gc.nogc())
+        );
+
+        let source_text =
+            String::from_static_str(agent, "[].concat({ length: 1, 0: 'x' }).length", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::Integer(SmallInteger::from(1)));
+    }
+
+    #[test]
+    fn string_search_at_utf16_boundaries() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(agent, "'a😀b😀'.indexOf('😀', 2)", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::Integer(SmallInteger::from(4)));
+
+        let source_text = String::from_static_str(agent, "'a😀b😀'.indexOf('', 2)", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::Integer(SmallInteger::from(2)));
+
+        let source_text = String::from_static_str(agent, "'a😀'.indexOf('', 9)", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::Integer(SmallInteger::from(3)));
+
+        let source_text = String::from_static_str(agent, "'aaab'.indexOf('aab')", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::Integer(SmallInteger::from(1)));
+
+        let source_text =
+            String::from_static_str(agent, "'a😀b😀'.lastIndexOf('😀', 2)", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::Integer(SmallInteger::from(1)));
+
+        let source_text = String::from_static_str(agent, "'a😀'.lastIndexOf('', 2)", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::Integer(SmallInteger::from(2)));
+
+        let source_text = String::from_static_str(agent, "'ab'.lastIndexOf('abc')", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::Integer(SmallInteger::from(-1)));
+
+        let source_text = String::from_static_str(agent, "'a😀b'.startsWith('b', 3)", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(agent, "'a😀b'.startsWith('b', 2)", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, false.into());
+
+        let source_text = String::from_static_str(agent, "'a😀b'.endsWith('a', 2)", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, false.into());
+
+        let source_text = String::from_static_str(agent, "'a😀b'.endsWith('😀', 3)", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(agent, "'grüße'.includes('ße', 4)", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, false.into());
+
+        let source_text =
+            String::from_static_str(agent, "'grüße grüße'.includes('ße', 5)", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text =
+            String::from_static_str(agent, "'x😀y'.replace('y', (m, p) => p)", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "x😀3", gc.nogc())
+        );
+
+        let source_text =
+            String::from_static_str(agent, "'a😀b😀'.replaceAll('😀', (m, p) => p)", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "a1b4", gc.nogc())
+        );
+
+        let source_text = String::from_static_str(agent, "'ab'.replaceAll('', '-')", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "-a-b-", gc.nogc())
+        );
+
+        let source_text =
+            String::from_static_str(agent, "'ab,c😀,d,'.split(',').join('|')", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "ab|c😀|d|", gc.nogc())
+        );
+    }
+
+    #[test]
+    fn string_prototype_starts_ends_with_and_includes_positions() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(agent, "'hello'.startsWith('lo', 3)", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(agent, "'hello'.startsWith('lo')", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, false.into());
+
+        let source_text = String::from_static_str(agent, "'hello'.startsWith('he', -5)", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(agent, "'hello'.endsWith('ell', 4)", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(agent, "'hello'.endsWith('ell')", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, false.into());
+
+        let source_text = String::from_static_str(agent, "'hello'.endsWith('', 0)", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text =
+            String::from_static_str(agent, "'hello'.endsWith('o', Infinity)", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(agent, "'hello'.includes('ll', 2)", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(agent, "'hello'.includes('ll', 3)", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, false.into());
+
+        let source_text = String::from_static_str(agent, "'hello'.includes('h', '1')", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, false.into());
+
+        let source_text = String::from_static_str(agent, "'hello'.startsWith(/h/)", gc.nogc());
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::TypeError);
+
+        let source_text = String::from_static_str(agent, "'hello'.endsWith(/o/)", gc.nogc());
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::TypeError);
+
+        let source_text = String::from_static_str(agent, "'hello'.includes(/l/)", gc.nogc());
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::TypeError);
+
+        let source_text = String::from_static_str(
+            agent,
+            "var re = /./; re[Symbol.match] = false; '/./'.startsWith(re)",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "'hello'.includes({ [Symbol.match]: true })",
+            gc.nogc(),
+        );
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::TypeError);
+    }
+
+    #[test]
+    fn reflect_own_keys_order() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var sym = Symbol('s'); var keys = Reflect.ownKeys({b:1, 2:1, a:1, 1:1, [sym]:1}); keys.length === 5 && keys[0] === '1' && keys[1] === '2' && keys[2] === 'b' && keys[3] === 'a' && keys[4] === sym",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var s = Symbol(), t = Symbol(); var keys = Reflect.ownKeys({[t]:1, z:1, 4294967294:1, 4294967295:1, '-1':1, '01':1, [s]:1, 0:1}); keys.slice(0, 6).join() === '0,4294967294,z,4294967295,-1,01' && keys[6] === t && keys[7] === s",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var o = {a:1, b:1, c:1}; delete o.a; o.a = 1; o[3] = 1; Reflect.ownKeys(o).join()",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "3,b,c,a", gc.nogc())
+        );
+
+        let source_text = String::from_static_str(
+            agent,
+            "var a = [1, 2]; a.x = 1; a[5] = 1; Reflect.ownKeys(a).join()",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "0,1,5,length,x", gc.nogc())
+        );
+
+        let source_text =
+            String::from_static_str(agent, "Reflect.ownKeys(new String('ab')).join()", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "0,1,length", gc.nogc())
+        );
+
+        let source_text = String::from_static_str(
+            agent,
+            "class C { static b() {} static 3() {} } Reflect.ownKeys(C).join()",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "3,length,name,prototype,b", gc.nogc())
+        );
+    }
+
+    #[test]
+    fn member_call_evaluates_base_once() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var count = 0; var inner = { m() { return this === inner; } }; var a = { get b() { count++; return inner; } }; a.b.m() && count === 1",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var count = 0; var inner = { m() { return this === inner; } }; var a = { get b() { count++; return inner; } }; var results = [a.b?.m(), a?.b.m(), a.b?.m?.(), a.b['m'](), (a.b).m()]; results.every(Boolean) && count === 5",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var count = 0; var keys = 0; var a = { get b() { count++; return { m() { return 1; } }; } }; var key = { toString() { keys++; return 'm'; } }; a.b[key]() === 1 && count === 1 && keys === 1",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var count = 0; var o = { get m() { count++; return function () { return this === o; }; } }; o.m() && o?.m() && o.m?.() && count === 3",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var count = 0; var p = new Proxy({ m() { return this === p; } }, { get(target, key) { count++; return target[key]; } }); p.m() && p?.m() && p?.['m']?.() && count === 3",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var count = 0; class B { get x() { count++; return function () { return this; }; } } class D extends B { t() { return super.x() === this; } } new D().t() && count === 1",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var count = 0; class C { #m() { return this; } get g() { count++; return this; } t() { return this.g.#m() === this && this.g?.#m() === this; } } new C().t() && count === 2",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var log = []; var o = { get f() { log.push('get'); return function () { log.push('call'); }; } }; o.f(log.push('argument')); log.join()",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "get,argument,call", gc.nogc())
+        );
+
+        let source_text = String::from_static_str(
+            agent,
+            "var o = { m() { return 'original'; } }; o.m(o.m = function () { return 'replaced'; })",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(
+            result.unbind(),
+            Value::from_static_str(agent, "original", gc.nogc())
+        );
+    }
+
+    #[test]
+    fn literal_storage_is_allocated_once() {
+        use crate::heap::element_array::{ElementArrayKey, ElementArrays};
+
+        /// Number of allocated key and value arrays in the two smallest tiers.
+        fn allocations(elements: &ElementArrays) -> [usize; 4] {
+            [
+                elements.k2pow4.keys.len(),
+                elements.e2pow4.values.len(),
+                elements.k2pow6.keys.len(),
+                elements.e2pow6.values.len(),
+            ]
+        }
+
+        let mut agent = Agent::new(
+            // Collecting garbage compacts the element arrays.
+            Options::default(),
+            &DefaultHostHooks,
+        );
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        // A wide object literal allocates its storage in the 2^6 tier once,
+        // instead of growing through the 2^4 tier.
+        let before = allocations(&agent.heap.elements);
+        let source_text = String::from_static_str(
+            agent,
+            "({ a: 1, b: 2, c: 3, d: 4, e: 5, f: 6, g: 7, h: 8, i: 9, j: 10, k: 11, l: 12,
+                m: 13, n: 14, o: 15, p: 16, q: 17, r: 18, s: 19, t: 20, u: 21, v: 22, w: 23,
+                x: 24, y: 25, z: 26 })",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        let Value::Object(object) = result else {
+            unreachable!()
+        };
+        let after = allocations(&agent.heap.elements);
+        assert_eq!(after, [before[0], before[1], before[2] + 1, before[3] + 1]);
+        let storage = agent.heap.objects[object].property_storage;
+        assert_eq!(storage.cap, ElementArrayKey::E6);
+        assert_eq!(storage.len(), 26);
+
+        // The array literal's elements are allocated in the tier matching
+        // its element count.
+        let source = format!(
+            "[{}]",
+            (0..1000)
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+        let source_text = String::from_string(agent, source, gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        let Value::Array(array) = result else {
+            unreachable!()
+        };
+        assert_eq!(agent[array].elements.cap, ElementArrayKey::E10);
+        assert_eq!(array.len(agent), 1000);
+
+        // Object.assign into an empty target reserves the copied properties.
+        let source_text = String::from_static_str(
+            agent,
+            "var source = Object.fromEntries(Array.from({ length: 20 }, (_, i) => ['k' + i, i]));",
+            gc.nogc(),
+        );
+        agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        let before = allocations(&agent.heap.elements);
+        let source_text = String::from_static_str(agent, "Object.assign({}, source)", gc.nogc());
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        let Value::Object(object) = result else {
+            unreachable!()
+        };
+        assert_eq!(allocations(&agent.heap.elements)[0], before[0]);
+        assert_eq!(
+            agent.heap.objects[object].property_storage.cap,
+            ElementArrayKey::E6
+        );
+
+        // Literals with spreads reserve a conservative capacity and still
+        // grow when needed.
+        let source_text = String::from_static_str(
+            agent,
+            "const wide = Object.fromEntries(Array.from({ length: 40 }, (_, i) => ['k' + i, i]));
+            const object = { a: 1, ...wide, b: 2, ...{ c: 3 }, __proto__: null };
+            const array = [0, ...Array.from({ length: 40 }, (_, i) => i + 1), , 42];
+            Object.keys(object).length === 43 && object.k39 === 39 &&
+                Object.keys(object).slice(-2).join() === 'b,c' &&
+                Object.getPrototypeOf(object) === null &&
+                array.length === 43 && array[40] === 40 && !(41 in array) && array[42] === 42",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::Boolean(true));
+    }
+
+    #[test]
+    fn delete_array_index_leaves_hole() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var a = [1, 2, 3, 4]; var deleted = delete a[2]; deleted && !(2 in a) && a.length === 4 && a[2] === undefined",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var a = [1, 2, 3, 4]; delete a[2]; var visited = []; a.forEach((v, i) => visited.push(i)); visited.join() === '0,1,3' && a.indexOf(undefined) === -1 && Object.keys(a).join() === '0,1,3'",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var a = [1, 2, 3]; delete a[10] && a.length === 3",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+    }
+
+    #[test]
+    fn delete_array_length_returns_false() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source_text = String::from_static_str(
+            agent,
+            "var a = [1, 2, 3]; (delete a.length) === false && a.length === 3",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, true.into());
+
+        let source_text = String::from_static_str(
+            agent,
+            "(function () { 'use strict'; delete [1, 2, 3].length; })()",
+            gc.nogc(),
+        );
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        let Value::Error(error) = result.unwrap_err().value() else {
+            unreachable!()
+        };
+        assert_eq!(agent[error].kind, ExceptionType::TypeError);
+    }
+
+    #[test]
+    fn nested_gc_root_is_rejected() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        agent.with_gc(|agent, mut gc| {
+            initialize_default_realm(agent, gc.reborrow());
+            let string = String::from_static_str(agent, "a heap string", gc.nogc());
+            let _scoped = string.scope(agent, gc.nogc());
+            assert!(!agent.stack_refs.borrow().is_empty());
+
+            assert_eq!(agent.try_with_gc(|_, _| ()), Err(NestedGcRootError));
+            assert!(agent.try_enter_gc().is_err());
+        });
+        // Scoped roots do not outlive the root GcScope.
+        assert!(agent.stack_refs.borrow().is_empty());
+
+        {
+            let mut root = agent.enter_gc();
+            let (agent, mut gc) = root.scope();
+            assert!(agent.try_with_gc(|_, _| ()).is_err());
+            let source_text = String::from_static_str(agent, "1 + 1", gc.nogc());
+            let result = agent
+                .run_script(source_text.unbind(), gc.reborrow())
+                .unwrap();
+            assert_eq!(result, 2.into());
+        }
+        // Dropping the GcRoot releases the root GcScope.
+        assert_eq!(agent.try_with_gc(|_, _| 42), Ok(42));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(
+        expected = "Scoped roots created before the GcScope root were released inside it"
+    )]
+    fn releasing_scoped_roots_of_enclosing_code_is_detected() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        agent.stack_refs.borrow_mut().push(HeapRootData::Empty);
+        agent.with_gc(|agent, _| agent.stack_refs.borrow_mut().clear());
+    }
+
+    #[test]
+    fn stale_script_after_gc() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let realm = agent.current_realm(gc.nogc());
+        let source_text = String::from_static_str(agent, "1 + 1", gc.nogc());
+        let script = parse_script(agent, source_text, realm, false, None, gc.nogc())
+            .unwrap()
+            .unbind();
+        assert!(agent.try_get_script(script).is_some());
+
+        // Nothing keeps the Script alive, so garbage collection sweeps it.
+        agent.gc(gc.reborrow());
+
+        assert!(agent.try_get_script(script).is_none());
+        let err = checked_script_evaluation(agent, script, gc.reborrow()).unwrap_err();
+        assert_eq!(
+            err,
+            HeapAccessError {
+                kind: "Script",
+                index: 0,
+                reason: HeapAccessErrorReason::OutOfBounds,
+            }
+        );
+        assert_eq!(err.to_string(), "Script(0) out of bounds");
+    }
+
+    /// Runs `setup`, drains the microtask queue, and asserts that each of
+    /// `sources` evaluates to true afterwards.
+    fn assert_true_after_microtasks(setup: &'static str, sources: &[&'static str]) {
+        let host_hooks: &QueueingHostHooks = Box::leak(Box::default());
+        let mut agent = Agent::new(test_options(), host_hooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        evaluate(agent, setup, gc.reborrow());
+        let checkpoint = agent.run_microtasks(1000, gc.reborrow()).unwrap();
+        assert_eq!(checkpoint, MicrotaskCheckpoint::Drained);
+        for source in sources {
+            let result = evaluate(agent, source, gc.reborrow()).unbind();
+            assert_eq!(result, Value::Boolean(true), "{source}");
+        }
+    }
+
+    #[test]
+    fn finally_passes_through_the_settlement() {
+        assert_true_after_microtasks(
+            "var log = [];
+            Promise.resolve(1)
+                .finally(() => { log.push('fulfilled'); return 2; })
+                .then(value => log.push('value ' + value));
+            Promise.reject(3)
+                .finally(() => Promise.resolve(4))
+                .catch(reason => log.push('reason ' + reason));
+            Promise.resolve(5)
+                .finally(() => { throw 6; })
+                .catch(reason => log.push('thrown ' + reason));
+            Promise.reject(7)
+                .finally(() => Promise.reject(8))
+                .catch(reason => log.push('rejected ' + reason));
+            Promise.resolve(9).finally().then(value => log.push('no callback ' + value));",
+            &[
+                "log.includes('fulfilled') && log.includes('value 1')",
+                "log.includes('reason 3')",
+                "log.includes('thrown 6')",
+                "log.includes('rejected 8')",
+                "log.includes('no callback 9')",
+            ],
+        );
+    }
+
+    #[test]
+    fn finally_invokes_then_with_closures() {
+        assert_true_after_microtasks(
+            "var promise = Promise.resolve();
+            var args;
+            promise.then = function () { args = arguments; return 'then result'; };
+            var result = promise.finally(() => {});
+            var closures = args;
+            var passed = promise.finally(42);
+            var thenable = { then(onFulfilled, onRejected) { this.args = [onFulfilled, onRejected]; } };
+            Promise.prototype.finally.call(thenable, 'not callable');",
+            &[
+                "result === 'then result'",
+                "typeof closures[0] === 'function' && typeof closures[1] === 'function'",
+                "closures[0] !== closures[1] && closures[0].length === 1 && closures[1].length === 1",
+                "closures[0].name === '' && closures[1].name === '' && !('prototype' in closures[0])",
+                "passed === 'then result' && args[0] === 42 && args[1] === 42",
+                "thenable.args[0] === 'not callable' && thenable.args[1] === 'not callable'",
+                "(() => { try { Promise.prototype.finally.call(1); } catch (err) { return err instanceof TypeError; } })()",
+            ],
+        );
+    }
+
+    #[test]
+    fn finally_uses_the_species_constructor() {
+        assert_true_after_microtasks(
+            "class MyPromise extends Promise {}
+            var constructed = 0;
+            class CountingPromise extends Promise {
+                constructor(executor) { constructed++; super(executor); }
+            }
+            var fromSubclass = MyPromise.resolve(1).finally(() => {});
+            var before = constructed;
+            var counted;
+            CountingPromise.resolve(1).finally(() => {}).then(value => { counted = value; });
+            var plain = Promise.resolve(1);
+            plain.constructor = { [Symbol.species]: MyPromise };
+            var fromSpecies = plain.finally(() => {});",
+            &[
+                "fromSubclass instanceof MyPromise",
+                "fromSpecies instanceof MyPromise",
+                "counted === 1 && constructed - before > 1",
+                "(() => { const p = Promise.resolve(); p.constructor = { [Symbol.species]: 5 }; try { p.finally(); } catch (err) { return err instanceof TypeError; } })()",
+            ],
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "regexp")]
+    fn flag_getters_read_the_original_flags() {
+        assert_script_true(
+            "var re = new RegExp('a', 'dgimsy');
+            re.hasIndices && re.global && re.ignoreCase && re.multiline && re.dotAll &&
+            re.sticky && !re.unicode && !re.unicodeSets &&
+            re.flags === 'dgimsy' &&
+            new RegExp('a', 'u').flags === 'u' && new RegExp('a', 'v').unicodeSets &&
+            new RegExp('a').flags === '' &&
+            RegExp.prototype.global === undefined && RegExp.prototype.flags === '' &&
+            (() => {
+                const get = Object.getOwnPropertyDescriptor(RegExp.prototype, 'sticky').get;
+                try { get.call({}); return false; } catch (err) { if (!(err instanceof TypeError)) return false; }
+                try { get.call(1); return false; } catch (err) { return err instanceof TypeError; }
+            })()",
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "regexp")]
+    fn split_constructs_a_sticky_splitter_from_the_species() {
+        assert_script_true(
+            "var constructed = [];
+            class Comma extends RegExp {
+                constructor(pattern, flags) { super(pattern, flags); constructed.push(flags); }
+                exec(s) {
+                    if (s[this.lastIndex] !== ',') return null;
+                    this.lastIndex += 1;
+                    return [','];
+                }
+            }
+            var re = new Comma(',', 'g');
+            var parts = re[Symbol.split]('a,b,,c');
+            parts.length === 4 && parts.join('|') === 'a|b||c' &&
+            constructed.join() === 'g,gy' &&
+            re[Symbol.split]('a,b,c', 2).join('|') === 'a|b' &&
+            re[Symbol.split]('a,b', 0).length === 0 &&
+            re[Symbol.split]('').length === 1 && re[Symbol.split]('')[0] === '' &&
+            re[Symbol.split]('abc').join('|') === 'abc'",
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "regexp")]
+    fn split_copies_captures() {
+        assert_script_true(
+            "class Capturing extends RegExp {
+                exec(s) {
+                    if (s[this.lastIndex] !== '-') return null;
+                    this.lastIndex += 1;
+                    return ['-', 'x', undefined];
+                }
+            }
+            var parts = new Capturing('-')[Symbol.split]('a-b');
+            parts.length === 4 && parts[0] === 'a' && parts[1] === 'x' &&
+            parts[2] === undefined && parts[3] === 'b' &&
+            new Capturing('-')[Symbol.split]('a-b', 2).join('|') === 'a|x'",
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "regexp")]
+    fn regexp_species_override_is_used() {
+        assert_script_true(
+            "var splitters = [];
+            class Other extends RegExp {
+                exec(s) { splitters.push(this); return null; }
+            }
+            class Base extends RegExp {
+                static get [Symbol.species]() { return Other; }
+            }
+            var re = new Base('x', 'i');
+            var parts = re[Symbol.split]('ab');
+            var matcher;
+            class Matcher extends RegExp {
+                constructor(pattern, flags) { super(pattern, flags); matcher = this; }
+                exec() { return null; }
+            }
+            var plain = /x/g;
+            plain.lastIndex = 3;
+            plain.constructor = { [Symbol.species]: Matcher };
+            var iterator = plain[Symbol.matchAll]('abc');
+            parts.length === 1 && parts[0] === 'ab' &&
+            splitters.length === 2 && splitters[0] instanceof Other &&
+            splitters[0].flags === 'iy' && splitters[0] !== re &&
+            matcher instanceof Matcher && matcher.flags === 'g' && matcher.lastIndex === 3 &&
+            iterator.next().done === true",
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "regexp")]
+    fn null_species_falls_back_to_regexp() {
+        assert_script_true(
+            "var prototypes = [];
+            RegExp.prototype.exec = function () {
+                prototypes.push(Object.getPrototypeOf(this));
+                return null;
+            };
+            class NullSpecies extends RegExp {
+                static get [Symbol.species]() { return null; }
+            }
+            var re = new NullSpecies('x');
+            var undefinedSpecies = /x/;
+            undefinedSpecies.constructor = undefined;
+            re[Symbol.split]('a')[0] === 'a' &&
+            undefinedSpecies[Symbol.split]('b')[0] === 'b' &&
+            re[Symbol.matchAll]('c').next().done &&
+            prototypes.length === 3 &&
+            prototypes.every(prototype => prototype === RegExp.prototype)",
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "regexp")]
+    fn regexp_non_constructor_species_throws() {
+        assert_script_true(
+            "function throwsTypeError(f) {
+                try { f(); } catch (err) { return err instanceof TypeError; }
+                return false;
+            }
+            var re = /x/;
+            re.constructor = { [Symbol.species]: 5 };
+            var arrow = /x/;
+            arrow.constructor = { [Symbol.species]: () => {} };
+            throwsTypeError(() => re[Symbol.split]('a')) &&
+            throwsTypeError(() => re[Symbol.matchAll]('a')) &&
+            throwsTypeError(() => arrow[Symbol.split]('a')) &&
+            throwsTypeError(() => RegExp.prototype[Symbol.split].call(1, 'a')) &&
+            throwsTypeError(() => RegExp.prototype[Symbol.matchAll].call('x', 'a'))",
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "regexp")]
+    fn match_all_iterates_exec_results() {
+        assert_script_true(
+            "class Digits extends RegExp {
+                exec(s) {
+                    while (this.lastIndex < s.length) {
+                        var i = this.lastIndex++;
+                        if (s[i] >= '0' && s[i] <= '9') return [s[i]];
+                    }
+                    return null;
+                }
+            }
+            var iterator = new Digits('[0-9]', 'g')[Symbol.matchAll]('a1b22');
+            var all = [...iterator].map(match => match[0]).join();
+            var single = [...new Digits('[0-9]')[Symbol.matchAll]('a1b22')];
+            all === '1,2,2' && iterator.next().done &&
+            single.length === 1 && single[0][0] === '1' &&
+            Object.prototype.toString.call(iterator) === '[object RegExp String Iterator]' &&
+            Object.getPrototypeOf(Object.getPrototypeOf(iterator)) ===
+                Object.getPrototypeOf(Object.getPrototypeOf([][Symbol.iterator]())) &&
+            (() => {
+                try { iterator.next.call({}); } catch (err) { return err instanceof TypeError; }
+                return false;
+            })()",
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "regexp")]
+    fn match_all_advances_past_empty_matches() {
+        assert_script_true(
+            "class Empty extends RegExp {
+                exec(s) { return this.lastIndex < s.length ? [''] : null; }
+            }
+            [...new Empty('', 'g')[Symbol.matchAll]('\u{1F600}a')].length === 3 &&
+            [...new Empty('', 'gu')[Symbol.matchAll]('\u{1F600}a')].length === 2",
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "regexp")]
+    fn match_all_completes_after_an_abrupt_completion() {
+        assert_script_true(
+            "var calls = 0;
+            class Throwing extends RegExp {
+                exec() { calls++; throw new Error('exec'); }
+            }
+            var iterator = new Throwing('x', 'g')[Symbol.matchAll]('x');
+            var threw = false;
+            try { iterator.next(); } catch (err) { threw = err.message === 'exec'; }
+            threw && iterator.next().done && calls === 1",
+        );
+    }
+
+    #[test]
+    fn deferred_error_messages_are_formatted_on_read() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let result = evaluate(
+            agent,
+            "var messages = [];
+            try { null.someProperty; } catch (err) { messages.push(err.message); }
+            try { undefined[3]; } catch (err) { messages.push(String(err)); }
+            try { notDeclaredAnywhere; } catch (err) { messages.push(err.message); }
+            try { (() => { 'use strict'; const constant = 1; constant = 2; })(); }
+            catch (err) { messages.push(err.message); }
+            try { null.someProperty; } catch (err) {
+                messages.push(Object.getOwnPropertyNames(err).join());
+                err.message = 'replaced';
+                messages.push(err.message);
+            }
+            messages.join('\\n')",
+            gc.reborrow(),
+        )
+        .unbind();
+        let result = result.to_string(agent, gc).unwrap();
+        assert_eq!(
+            result.as_str(agent).lines().collect::<Vec<_>>(),
+            [
+                "Cannot read property 'someProperty' of null.",
+                "TypeError: Cannot read property '3' of undefined.",
+                "Cannot access undeclared variable 'notDeclaredAnywhere'.",
+                "Cannot assign to immutable identifier 'constant' in strict mode.",
+                "message",
+                "replaced",
+            ]
+        );
+    }
+
+    /// Runs the script with garbage collection performed at nearly every
+    /// instruction, and returns true if it evaluated to `true`.
+    fn run_under_gc_stress(source: &'static str) -> bool {
+        let mut agent = Agent::new(
+            Options {
+                gc_stress: true,
+                ..Default::default()
+            },
+            &DefaultHostHooks,
+        );
+        agent.with_gc(|agent, mut gc| {
+            initialize_default_realm(agent, gc.reborrow());
+            evaluate(agent, source, gc) == Value::Boolean(true)
+        })
+    }
+
+    #[test]
+    fn method_call_receiver_survives_allocating_arguments() {
+        assert!(run_under_gc_stress(
+            "const garbage = () => { for (let i = 0; i < 20; i++) ({ i }); return [1, 2]; };
+            const receiver = { tag: 'receiver', get(a, b) { return this.tag + a.length + b.length; } };
+            receiver.get(garbage(), garbage()) === 'receiver22' &&
+                [receiver][0].get(garbage(), [garbage(), garbage()]) === 'receiver22'",
+        ));
+    }
+
+    #[test]
+    fn operand_stack_survives_user_code_conversions() {
+        assert!(run_under_gc_stress(
+            "const key = (name) => ({ toString() { for (let i = 0; i < 20; i++) ({ i }); return name; } });
+            const object = { foo: { bar: 'found' } };
+            class Base { get baz() { return 'super'; } }
+            class Derived extends Base { get baz() { return super[key('baz')]; } }
+            const results = [
+                object[key('foo')][key('bar')] === 'found',
+                key('foo') in object,
+                `${key('a')}-${key('b')}-${1}-${key('c')}` === 'a-b-1-c',
+                new Derived().baz === 'super',
+                ({ [key('computed')]: function () {} }).computed.name === 'computed',
+            ];
+            results.every(Boolean)",
+        ));
+    }
+
+    #[test]
+    fn iterator_and_binding_state_survives_collection() {
+        assert!(run_under_gc_stress(
+            "function* values() { for (let i = 0; i < 5; i++) yield { i }; }
+            const [first, ...rest] = values();
+            const { a, ...others } = { a: first, b: rest, c: [...values()] };
+            let sum = 0;
+            for (const { i } of values()) sum += i;
+            a.i === 0 && rest.length === 4 && rest[3].i === 4 && others.c.length === 5 &&
+                sum === 10",
+        ));
+    }
+
+    #[test]
+    fn private_environments_are_collected() {
+        assert!(run_under_gc_stress(
+            "class Counter {
+                #count = 0;
+                static #instances = 0;
+                constructor() { Counter.#instances++; }
+                #step() { return ++this.#count; }
+                get next() { return this.#step(); }
+                static get instances() { return Counter.#instances; }
+            }
+            const counters = [new Counter(), new Counter()];
+            counters[0].next;
+            class Unused { #x = 1; }
+            new Unused();
+            counters[0].next === 2 && counters[1].next === 1 && Counter.instances === 2",
+        ));
+    }
+
+    #[test]
+    fn class_constructor_bytecode_survives_collection() {
+        assert!(run_under_gc_stress(
+            "class Point {
+                x = 1;
+                y = this.x + 1;
+                constructor(z) { this.z = z; }
+            }
+            const points = [new Point(3), new Point(4)];
+            points.every((p, i) => p.x === 1 && p.y === 2 && p.z === i + 3)",
+        ));
+    }
+
+    #[test]
+    fn search_for_halves_of_surrogate_pairs() {
+        assert_all_true(&[
+            "'a\u{1F600}b'.indexOf('\\uDE00') === 2 && 'a\u{1F600}b'.indexOf('\\uD83D') === 1",
+            "'a\u{1F600}b\u{1F600}'.lastIndexOf('\\uDE00') === 5",
+            "'a\u{1F600}b\u{1F600}'.lastIndexOf('\\uD83D', 3) === 1",
+            "'a\u{1F600}b'.includes('\\uDE00b') && !'a\u{1F600}b'.includes('\\uDE00a')",
+            "'a\u{1F600}b'.startsWith('\\uDE00', 2) && !'a\u{1F600}b'.startsWith('\\uD83D', 2)",
+            "'a\u{1F600}b'.endsWith('a\\uD83D', 2) && !'a\u{1F600}b'.endsWith('\\uDE00', 2)",
+            "'a\u{1F600}b'.split('\\uDE00').join() === 'a\\uD83D,b'",
+            "'a\u{1F600}b'.replace('\\uD83D', 'x') === 'ax\\uDE00b'",
+            "'\u{1F600}\u{1F600}'.replaceAll('\\uDE00', '') === '\\uD83D\\uD83D'",
+            "'\u{1F600}'.indexOf('', 1) === 1 && '\u{1F600}'.lastIndexOf('') === 2",
+        ]);
+    }
+
+    #[test]
+    fn lone_surrogates_join_into_pairs() {
+        assert_all_true(&[
+            "String.fromCharCode(0xD83D).length === 1",
+            "String.fromCharCode(0xD83D).charCodeAt(0) === 0xD83D",
+            "(String.fromCharCode(0xD83D) + String.fromCharCode(0xDE00)).codePointAt(0) === 0x1F600",
+            "(String.fromCharCode(0xD83D) + String.fromCharCode(0xDE00)).length === 2",
+            "String.fromCharCode(0xD83D).concat(String.fromCharCode(0xDE00)) === '\u{1F600}'",
+            "String.fromCharCode(0xD83D, 0xDE00) === String.fromCodePoint(0x1F600)",
+            "'\\uD83D' === String.fromCharCode(0xD83D)",
+            "'x\\uDC00y'.length === 3 && 'x\\uDC00y'.charCodeAt(1) === 0xDC00",
+            "'\\uFFFD'.charCodeAt(0) === 0xFFFD",
+            "String.fromCodePoint(0xDC00).codePointAt(0) === 0xDC00",
+            "'\\uDE00\\uD83D'.repeat(3) === '\\uDE00\u{1F600}\u{1F600}\\uD83D'",
+            "'\\uD83D'.padEnd(4, '\\uDE00\\uD83D') === '\u{1F600}\u{1F600}'",
+        ]);
+    }
+
+    #[test]
+    fn code_unit_accessors() {
+        assert_all_true(&[
+            "'\u{1F600}'.charCodeAt(0) === 0xD83D && '\u{1F600}'.charCodeAt(1) === 0xDE00",
+            "'\u{1F600}'.codePointAt(1) === 0xDE00",
+            "'\u{1F600}'.at(-1) === String.fromCharCode(0xDE00)",
+            "'abc'.at(-1) === 'c' && 'abc'.at(3) === undefined && 'abc'.at(-4) === undefined",
+            "'abc'.charAt(3) === '' && 'abc'.charAt(-1) === ''",
+            "Number.isNaN('abc'.charCodeAt(3)) && Number.isNaN('abc'.charCodeAt(-1))",
+            "'abc'.charCodeAt(1.9) === 98 && 'abc'.charAt(1.9) === 'b'",
+            "'abc'.codePointAt(3) === undefined",
+        ]);
+    }
+
+    #[test]
+    fn string_constructor_functions() {
+        assert_all_true(&[
+            "String.fromCharCode() === '' && String.fromCharCode(0x10061) === 'a'",
+            "(() => { try { String.fromCodePoint(0x110000); } catch (err) { return err instanceof RangeError; } })()",
+            "(() => { try { String.fromCodePoint(1.5); } catch (err) { return err instanceof RangeError; } })()",
+            "String.raw({ raw: ['a', 'b', 'c'] }, 1, 2) === 'a1b2c'",
+            "String.raw({ raw: ['a', 'b'] }) === 'ab'",
+            "'a'.concat('b', 1, null) === 'ab1null'",
+        ]);
+    }
+
+    #[test]
+    fn tagged_templates() {
+        assert_all_true(&[
+            "String.raw`a${1}` === 'a1'",
+            "String.raw`\\u{1F600}\\n${'x'}\\t` === '\\\\u{1F600}\\\\nx\\\\t'",
+            "String.raw`\\unicode` === '\\\\unicode'",
+            "((strings) => strings[0] === undefined && strings.raw[0] === '\\\\unicode')`\\unicode`",
+            "(() => {
+                const tag = (strings, ...substitutions) => strings;
+                const site = () => tag`a${1}b`;
+                const strings = site();
+                return site() === strings && tag`a${1}b` !== strings &&
+                    strings.length === 2 && strings[0] === 'a' && strings[1] === 'b' &&
+                    Object.isFrozen(strings) && Object.isFrozen(strings.raw) &&
+                    !Object.getOwnPropertyDescriptor(strings, 'raw').enumerable;
+            })()",
+            "(() => {
+                const sites = [];
+                function tag(strings) { sites.push(strings); }
+                function f() { tag`x`; }
+                f();
+                f();
+                new Function('tag', 'tag`x`')(tag);
+                return sites[0] === sites[1] && sites[0] !== sites[2];
+            })()",
+            "(() => {
+                const object = { prefix: '<', tag(strings, value) { return this.prefix + strings[0] + value; } };
+                return object.tag`a${object.prefix = '>'}` === '>a>';
+            })()",
+        ]);
+    }
+
+    #[test]
+    fn search_in_strings_with_lone_surrogates() {
+        assert_all_true(&[
+            "'a\\uD800b'.indexOf('b') === 2 && 'a\\uD800b'.lastIndexOf('b') === 2",
+            "'a\\uD800b'.includes('b') && 'a\\uD800b'.includes('\\uD800')",
+            "'a\\uD800b'.startsWith('\\uD800b', 1) && 'a\\uD800b'.endsWith('a\\uD800', 2)",
+            "'a\\uD800b'.split('\\uD800').join() === 'a,b'",
+            "'a\\uD800b'.split('').length === 3 && 'a\\uD800b'.split('')[1] === '\\uD800'",
+            "'\u{1F600}'.split('').join() === '\\uD83D,\\uDE00' && 'abc'.split('', 2).join() === 'a,b'",
+            "'a\\uD800b'.replace('b', 'c') === 'a\\uD800c'",
+            "'a\\uD800b'.replace('\\uD800', (m, p) => p) === 'a1b'",
+            "'\\uD800a\\uD800'.replaceAll('\\uD800', '-') === '-a-'",
+            "'\\uD800a\\uD800'.replaceAll('a', (m, p) => p) === '\\uD8001\\uD800'",
+        ]);
+    }
+
+    #[test]
+    fn builtins_keep_lone_surrogates() {
+        assert_all_true(&[
+            "'a\\uD800'.trim() === 'a\\uD800' && ' \\uD800 '.trim() === '\\uD800'",
+            "' \\uD800 '.trimStart() === '\\uD800 ' && ' \\uD800 '.trimEnd() === ' \\uD800'",
+            "'\\uD800'.toUpperCase() === '\\uD800' && 'a\\uD800b'.toUpperCase() === 'A\\uD800B'",
+            "'A\\uD800B'.toLowerCase() === 'a\\uD800b' && 'a\\uD800'.toLocaleUpperCase() === 'A\\uD800'",
+            "'\\uD800'.normalize() === '\\uD800' && 'e\\u0301\\uD800'.normalize() === '\\u00E9\\uD800'",
+            "!'a\\uD800'.isWellFormed() && 'a\\uD800'.toWellFormed() === 'a\\uFFFD'",
+            "Symbol('\\uD800').toString() === 'Symbol(\\uD800)'",
+            "({ '\\uD800': 1 })['\\uD800'] === 1",
+            "`${'\\uD800'}x` === '\\uD800x'",
+            "String(new Error('\\uD800')) === 'Error: \\uD800'",
+            "Object.prototype.toString.call({ [Symbol.toStringTag]: '\\uD800' }) === '[object \\uD800]'",
+            "Object.getOwnPropertyDescriptor({ get ['\\uD800']() {} }, '\\uD800').get.name === 'get \\uD800'",
+            "(() => { const s = Symbol('\\uD800'); return ({ [s]() {} })[s].name === '[\\uD800]'; })()",
+            "'\\uD800'.anchor('\\uD800\"') === '<a name=\"\\uD800&quot;\">\\uD800</a>'",
+            "new Uint8Array([1, 2]).join('\\uD800') === '1\\uD8002'",
+            "eval('\"\\uD800\"') === '\\uD800' && new Function('return \"\\uD800\"')() === '\\uD800'",
+            "decodeURI('\\uD800%41') === '\\uD800A' && decodeURIComponent('\u{1F600}%41') === '\u{1F600}A'",
+            "JSON.stringify([1], null, '\\uD800') === '[\\n\\uD8001\\n]'",
+            "JSON.stringify([1], null, '\u{1F600}'.repeat(6)).length === 15",
+        ]);
+    }
+
+    #[test]
+    fn conversions_of_lone_surrogates() {
+        assert_all_true(&[
+            "'\u{10000}' < '\\uFFFF' && '\\uD800' < '\\uD801'",
+            "['\\uFFFF', '\u{10000}'].sort()[0] === '\u{10000}'",
+            "Number.isNaN(Number('1\\uD800')) && Number.isNaN(Date.parse('\\uD800'))",
+            "parseInt('12\\uD800') === 12 && parseFloat('1.5\\uD800') === 1.5",
+            "(() => { try { BigInt('1\\uD800'); } catch (err) { return err instanceof SyntaxError; } })()",
+            "(() => { try { new RegExp('a', '\\uD800'); } catch (err) { return err instanceof SyntaxError; } })()",
+            "(() => { try { JSON.parse('\\uD800'); } catch (err) { return err instanceof SyntaxError; } })()",
+            "(() => { try { eval('\\uD800'); } catch (err) { return err instanceof SyntaxError; } })()",
+        ]);
+    }
+
+    #[test]
+    fn slice_substring_and_substr() {
+        assert_all_true(&[
+            "'hello'.slice(-3) === 'llo' && 'hello'.substring(-3) === 'hello'",
+            "'hello'.substring(2) === 'llo' && 'hello'.slice(2) === 'llo'",
+            "'hello'.slice(3, 1) === '' && 'hello'.substring(3, 1) === 'el'",
+            "'hello'.slice(1, -1) === 'ell' && 'hello'.substring(1, -1) === 'h'",
+            "'hello'.substr(-3, 2) === 'll' && 'hello'.substr(1) === 'ello'",
+            "'hello'.substr(2, -1) === '' && 'hello'.substr(10) === ''",
+            "'a longer heap string'.substr(2, 6) === 'longer'",
+            "'a longer heap string'.slice(-6) === 'string'",
+        ]);
+    }
+
+    #[test]
+    fn substrings_split_surrogate_pairs() {
+        assert_all_true(&[
+            "'\u{1F600}'.slice(0, 1) === String.fromCharCode(0xD83D)",
+            "'\u{1F600}'.slice(1) === String.fromCharCode(0xDE00)",
+            "'\u{1F600}'.substring(1, 2).length === 1",
+            "'\u{1F600}'.substr(0, 1).charCodeAt(0) === 0xD83D",
+            "('a\u{1F600}b').slice(2) === String.fromCharCode(0xDE00) + 'b'",
+            "('a long prefix \u{1F600}').slice(-1).charCodeAt(0) === 0xDE00",
+            "'\u{1F600}'.slice(0, 1) + '\u{1F600}'.slice(1) === '\u{1F600}'",
+            "'x\\uD800y'.substring(1, 2).charCodeAt(0) === 0xD800",
+        ]);
+    }
+
+    #[test]
+    fn growing_array_during_iteration() {
+        assert_script_true(
+            "var array = [1, 2, 3];
+            var seen = [];
+            for (var value of array) {
+                seen.push(value);
+                if (value === 1) array.push(4);
+            }
+            seen.join() === '1,2,3,4'",
+        );
+    }
+
+    #[test]
+    fn shrinking_array_during_iteration() {
+        assert_script_true(
+            "var array = [1, 2, 3, 4];
+            var seen = [];
+            for (var value of array) {
+                seen.push(value);
+                if (value === 1) array.length = 2;
+            }
+            seen.join() === '1,2'",
+        );
+    }
+
+    #[test]
+    fn holey_array_with_polluted_prototype() {
+        assert_script_true(
+            "Array.prototype[1] = 'from prototype';
+            var seen = [];
+            for (var value of [0, , 2, , ]) seen.push(String(value));
+            delete Array.prototype[1];
+            seen.join() === '0,from prototype,2,undefined'",
+        );
+    }
+
+    #[test]
+    fn keys_values_and_entries() {
+        assert_script_true(
+            "var array = ['a', 'b'];
+            var entries = [];
+            for (var entry of array.entries()) entries.push(entry);
+            entries.length === 2 &&
+            Array.isArray(entries[0]) && entries[0][0] === 0 && entries[0][1] === 'a' &&
+            entries[1][0] === 1 && entries[1][1] === 'b' &&
+            Array.from(array.keys()).join() === '0,1' &&
+            Array.from(array.values()).join() === 'a,b' &&
+            Array.prototype[Symbol.iterator] === Array.prototype.values &&
+            Object.prototype.toString.call(array.values()) === '[object Array Iterator]'",
+        );
+    }
+
+    #[test]
+    fn exhausted_iterator_stays_done() {
+        assert_script_true(
+            "var array = [1];
+            var iterator = array.values();
+            var first = iterator.next();
+            var second = iterator.next();
+            array.push(2);
+            var third = iterator.next();
+            first.value === 1 && first.done === false &&
+            second.value === undefined && second.done === true &&
+            third.value === undefined && third.done === true",
+        );
+    }
+
+    #[test]
+    fn subclass_results_use_species() {
+        assert_script_true(
+            "class SubArray extends Array {}
+            var instance = SubArray.from([1, 2, 3]);
+            var mapped = instance.map(function (x) { return x * 2; });
+            mapped instanceof SubArray && mapped.join() === '2,4,6' &&
+            instance.filter(function (x) { return x > 1; }) instanceof SubArray &&
+            instance.slice(1) instanceof SubArray &&
+            instance.concat([4]) instanceof SubArray",
+        );
+    }
+
+    #[test]
+    fn undefined_or_null_species_creates_plain_array() {
+        assert_script_true(
+            "class UndefinedSpecies extends Array {
+                static get [Symbol.species]() { return undefined; }
+            }
+            class NullSpecies extends Array {
+                static get [Symbol.species]() { return null; }
+            }
+            var plain = [1];
+            plain.constructor = undefined;
+            Object.getPrototypeOf(UndefinedSpecies.from([1]).map(function (x) { return x; })) === Array.prototype &&
+            Object.getPrototypeOf(NullSpecies.from([1]).slice()) === Array.prototype &&
+            Object.getPrototypeOf(plain.filter(function () { return true; })) === Array.prototype",
+        );
+    }
+
+    #[test]
+    fn non_constructor_species_throws() {
+        assert_script_true(
+            "class BadSpecies extends Array {
+                static get [Symbol.species]() { return 1; }
+            }
+            var array = [1];
+            array.constructor = 5;
+            var threw = [];
+            try { BadSpecies.from([1]).map(function (x) { return x; }); } catch (err) { threw.push(err instanceof TypeError); }
+            try { array.concat([]); } catch (err) { threw.push(err instanceof TypeError); }
+            threw.join() === 'true,true'",
+        );
+    }
+
+    #[test]
+    fn body_error_wins_over_throwing_return() {
+        assert_script_true(
+            "const log = [];
+            function hostile(values) {
+                let i = 0;
+                return {
+                    [Symbol.iterator]() { return this; },
+                    next() { return { done: i >= values.length, value: values[i++] }; },
+                    return() { log.push('return'); throw new Error('from return'); },
+                };
+            }
+            const bodyError = new Error('from add');
+            class ThrowingSet extends Set {
+                add(value) { if (value === 2) throw bodyError; return super.add(value); }
+            }
+            let setError;
+            try { new ThrowingSet(hostile([1, 2, 3])); } catch (err) { setError = err; }
+            let weakSetError;
+            try { new WeakSet(hostile([{}, 1])); } catch (err) { weakSetError = err; }
+            setError === bodyError &&
+                weakSetError instanceof TypeError &&
+                log.join() === 'return,return'",
+        );
+    }
+
+    #[test]
+    fn non_callable_next_throws_type_error() {
+        assert_script_true(
+            "let returned = false;
+            const iterable = {
+                [Symbol.iterator]() {
+                    return { next: 1, return() { returned = true; return {}; } };
+                },
+            };
+            let setError, weakSetError;
+            try { new Set(iterable); } catch (err) { setError = err; }
+            try { new WeakSet(iterable); } catch (err) { weakSetError = err; }
+            setError instanceof TypeError && weakSetError instanceof TypeError && !returned",
+        );
+    }
+
+    #[test]
+    fn done_getter_exceptions_propagate_without_closing() {
+        assert_script_true(
+            "let returned = false;
+            const doneError = new Error('done');
+            const valueError = new Error('value');
+            function iterable(throwFrom) {
+                return {
+                    [Symbol.iterator]() {
+                        return {
+                            next() {
+                                return {
+                                    get done() { if (throwFrom === 'done') throw doneError; return false; },
+                                    get value() { throw valueError; },
+                                };
+                            },
+                            return() { returned = true; return {}; },
+                        };
+                    },
+                };
+            }
+            const errors = [];
+            for (const C of [Set, WeakSet]) {
+                for (const throwFrom of ['done', 'value']) {
+                    try { new C(iterable(throwFrom)); } catch (err) { errors.push(err); }
+                }
+            }
+            errors.length === 4 &&
+                errors[0] === doneError && errors[1] === valueError &&
+                errors[2] === doneError && errors[3] === valueError &&
+                !returned",
+        );
+    }
+
+    #[test]
+    fn iteration_runs_to_completion() {
+        assert_script_true(
+            "let returned = false;
+            const a = {}, b = {};
+            function* values(...items) {
+                try { for (const item of items) yield item; } finally { returned = true; }
+            }
+            const set = new Set(values(1, 2, 2, 3));
+            const finishedSet = returned;
+            returned = false;
+            const weakSet = new WeakSet(values(a, b));
+            set.size === 3 && weakSet.has(a) && weakSet.has(b) && finishedSet && returned",
+        );
+    }
+
+    #[test]
+    fn optional_catch_binding() {
+        assert_all_true(&[
+            "(() => { try { throw 1; } catch { return true; } })()",
+            "(() => { try { throw 1; } catch (e) { return e === 1; } })()",
+            // Without a binding the thrown value cannot be reached by any name.
+            "(() => { try { throw 'hidden'; } catch { return typeof e === 'undefined'; } })()",
+            "(() => { let e = 'outer'; try { throw 'hidden'; } catch { return e === 'outer'; } })()",
+            "(() => { try { throw 1; } catch { let y = 2; } return typeof y === 'undefined'; })()",
+        ]);
+    }
+
+    #[test]
+    fn rethrow_from_catch_restores_outer_environment() {
+        assert_all_true(&[
+            "(() => {
+                let e = 'outer e', x = 'outer x';
+                const log = [];
+                try {
+                    try {
+                        throw 1;
+                    } catch (e) {
+                        let x = 'inner x';
+                        log.push(e, x);
+                        throw e;
+                    }
+                } catch (err) {
+                    log.push(err, e, x);
+                }
+                return log.join() === '1,inner x,1,outer e,outer x';
+            })()",
+            "(() => {
+                let e = 'outer e';
+                try {
+                    try { throw 1; } catch (e) { throw new Error('again ' + e); }
+                } catch (err) {
+                    return err.message === 'again 1' && e === 'outer e';
+                }
+            })()",
+            "(() => {
+                let x = 'outer x';
+                try {
+                    try { throw 1; } catch { let x = 'inner x'; notDeclaredAnywhere; }
+                } catch (err) {
+                    return err instanceof ReferenceError && x === 'outer x';
+                }
+            })()",
+        ]);
+    }
+
+    #[test]
+    fn abrupt_catch_parameter_initialization_restores_outer_environment() {
+        assert_all_true(&[
+            "(() => {
+                let a = 'outer a';
+                try {
+                    try { throw undefined; } catch ({ a }) { return false; }
+                } catch (err) {
+                    return err instanceof TypeError && a === 'outer a';
+                }
+            })()",
+            "(() => {
+                let e = 'outer e';
+                try {
+                    try { throw {}; } catch ({ e = (() => { throw 'default'; })() }) { return false; }
+                } catch (err) {
+                    return err === 'default' && e === 'outer e';
+                }
+            })()",
+        ]);
+    }
+
+    #[test]
+    fn rethrow_unwinds_through_loops_and_finally() {
+        assert_all_true(&[
+            "(() => {
+                const depth = 'function';
+                try {
+                    for (let i = 0; i < 3; i++) {
+                        try { throw i; } catch (j) { if (j === 2) throw 'r' + j; }
+                    }
+                } catch (k) {
+                    return k === 'r2' && depth === 'function' &&
+                        typeof i === 'undefined' && typeof j === 'undefined';
+                }
+            })()",
+            "(() => {
+                const log = [];
+                let e = 'outer e';
+                try {
+                    try { throw 1; } catch { try { throw 2; } finally { log.push('finally'); } }
+                } catch (err) {
+                    log.push(err, e);
+                }
+                return log.join() === 'finally,2,outer e';
+            })()",
+            "(() => {
+                const closures = [];
+                let e = 'outer e';
+                for (let i = 0; i < 2; i++) {
+                    try { throw i; } catch (e) { closures.push(() => e); }
+                }
+                return closures.map(f => f()).join() === '0,1' && e === 'outer e';
+            })()",
+        ]);
+    }
+
+    #[test]
+    fn rethrow_from_generator_catch() {
+        assert_all_true(&["(() => {
+                let e = 'outer e';
+                function* generator() {
+                    try { yield 1; throw 'g'; } catch (e) { yield e; throw e + '!'; }
+                }
+                const iterator = generator();
+                iterator.next();
+                const caught = iterator.next().value;
+                try {
+                    iterator.next();
+                } catch (err) {
+                    return caught === 'g' && err === 'g!' && e === 'outer e' &&
+                        iterator.next().done;
+                }
+            })()"]);
+    }
+
+    #[test]
+    fn bigint_throws_type_error() {
+        assert_all_true(&[
+            "(() => {
+                try { JSON.stringify(1n); } catch (err) {
+                    return err instanceof TypeError &&
+                        err.message === 'Do not know how to serialize a BigInt';
+                }
+            })()",
+            "(() => {
+                try { JSON.stringify(2n ** 100n); } catch (err) { return err instanceof TypeError; }
+            })()",
+            "(() => {
+                try { JSON.stringify(Object(1n)); } catch (err) { return err instanceof TypeError; }
+            })()",
+        ]);
+    }
+
+    #[test]
+    fn nested_bigint_throws_type_error() {
+        assert_all_true(&[
+            "(() => {
+                try { JSON.stringify({ a: 1, b: { c: 1n } }); } catch (err) {
+                    return err instanceof TypeError;
+                }
+            })()",
+            "(() => {
+                try { JSON.stringify([1, [2, 3n]], null, 2); } catch (err) {
+                    return err instanceof TypeError;
+                }
+            })()",
+        ]);
+    }
+
+    #[test]
+    fn bigint_can_be_serialized_by_replacer_or_to_json() {
+        assert_all_true(&[
+            "JSON.stringify({ a: 1n }, (key, value) => typeof value === 'bigint' ? `${value}` : value) === '{\"a\":\"1\"}'",
+            "(() => {
+                BigInt.prototype.toJSON = function () { return this.toString(); };
+                try {
+                    return JSON.stringify([1n, { b: 2n }]) === '[\"1\",{\"b\":\"2\"}]';
+                } finally {
+                    delete BigInt.prototype.toJSON;
+                }
+            })()",
+        ]);
+    }
+
+    /// Evaluates each Script in a new Realm and checks its completion value.
+    fn assert_completion_values(cases: &[(&'static str, Value<'static>)]) {
+        for &(source, expected) in cases {
+            let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+            let mut root = agent.enter_gc();
+            let (agent, mut gc) = root.scope();
+            initialize_default_realm(agent, gc.reborrow());
+
+            let result = evaluate(agent, source, gc.reborrow()).unbind();
+            assert_eq!(result, expected, "{source}");
+        }
+    }
+
+    #[test]
+    fn empty_statements_keep_the_completion_value() {
+        assert_completion_values(&[
+            ("{ 1; ; }", 1.into()),
+            ("1; {}", 1.into()),
+            ("1; var x = 2;", 1.into()),
+            ("1; var x;", 1.into()),
+            ("1; let x = 2;", 1.into()),
+            ("1; const x = 2; ;", 1.into()),
+            ("1; class C {}", 1.into()),
+            ("1; function f() {}", 1.into()),
+            ("1; { function f() {} }", 1.into()),
+            ("{ let x = 1; function f() { return x; } f(); }", 1.into()),
+            ("var x = 2;", Value::Undefined),
+            ("var x = 2; x;", 2.into()),
+        ]);
+    }
+
+    #[test]
+    fn statements_update_empty_to_undefined() {
+        assert_completion_values(&[
+            ("1; if (true) {}", Value::Undefined),
+            ("1; if (false) 2;", Value::Undefined),
+            ("1; if (false) {} else {}", Value::Undefined),
+            ("1; if (true) 2;", 2.into()),
+            ("1; for (;false;) {}", Value::Undefined),
+            ("1; while (false);", Value::Undefined),
+            ("1; do ; while (false)", Value::Undefined),
+            ("1; for (const a of []) 2;", Value::Undefined),
+            ("1; for (const a in {}) 2;", Value::Undefined),
+            ("1; switch (1) {}", Value::Undefined),
+            ("1; switch (1) { case 1: }", Value::Undefined),
+            ("1; try {} catch {}", Value::Undefined),
+            ("1; try { throw 0; } catch {}", Value::Undefined),
+            ("1; try { throw 0; } catch (err) { err; }", 0.into()),
+            ("1; try {} finally { 2; }", Value::Undefined),
+            ("1; try { 2; } finally { 3; }", 2.into()),
+        ]);
+    }
+
+    #[test]
+    fn loops_keep_the_last_completion_value() {
+        assert_completion_values(&[
+            ("1; for (let i = 0; i < 3; i++) i;", 2.into()),
+            ("1; var i = 0; while (i < 3) i++;", 2.into()),
+            ("1; var i = 0; do i++; while (i < 3)", 2.into()),
+            ("1; for (const a of [1, 2]) a;", 2.into()),
+            ("1; for (const [a, b] of [[1, 2]]) a + b;", 3.into()),
+            ("1; for (const key in { a: 1 }) 2;", 2.into()),
+            (
+                "1; for (var i = 0; i < 3; i++) { if (i === 1) continue; i; }",
+                2.into(),
+            ),
+            // The completion value of an IfStatement is never EMPTY.
+            (
+                "1; for (var i = 0; i < 3; i++) { if (i === 2) continue; i; }",
+                Value::Undefined,
+            ),
+            (
+                "1; do { 2; if (true) { break; } 3; } while (false)",
+                Value::Undefined,
+            ),
+            ("1; while (true) { break; }", Value::Undefined),
+            ("1; while (true) { 2; break; }", 2.into()),
+            ("1; for (const a of [1, 2]) { 3; break; }", 3.into()),
+            (
+                "1; for (var i = 0; i < 2; i++) try { i; continue; } finally { 3; }",
+                1.into(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn labelled_statements_keep_the_completion_value() {
+        assert_completion_values(&[
+            ("1; l: { break l; }", 1.into()),
+            ("1; l: { 2; break l; 3; }", 2.into()),
+            ("1; l: 2;", 2.into()),
+            (
+                "1; l: while (true) { 2; try { 3; break l; } finally { 4; } }",
+                3.into(),
+            ),
+            (
+                "1; outer: for (const a of [1]) { for (const b of [2]) { b; break outer; } }",
+                2.into(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn switch_without_matching_case() {
+        assert_completion_values(&[
+            ("1; switch (2) { case 1: 3; }", Value::Undefined),
+            ("1; switch (2) { case 1: 3; case 4: 5; }", Value::Undefined),
+            ("var x = 1; switch (2) { case 1: x = 3; } x", 1.into()),
+            ("switch (1) {} var x = 1;", Value::Undefined),
+            ("1; switch (2) { case 1: 3; default: }", Value::Undefined),
+            ("1; switch (2) { case 2: 3; default: 4; }", 4.into()),
+        ]);
+    }
+
+    #[test]
+    fn eval_completion_value() {
+        assert_completion_values(&[
+            ("eval('1; ;')", 1.into()),
+            ("eval('1; var x = 2;')", 1.into()),
+            ("eval('var x = 2;')", Value::Undefined),
+            ("eval('1; if (false) 2;')", Value::Undefined),
+            ("eval('1; l: { break l; }')", 1.into()),
+            ("eval('1; try {} catch {}')", Value::Undefined),
+        ]);
+    }
+
+    /// Installs accessors that record their use and throw on Object.prototype and
+    /// Array.prototype for the property names of engine-created objects.
+    ///
+    /// The descriptors have a null prototype: Object.defineProperty would
+    /// otherwise, as it should, read the installed getters while converting them.
+    const POLLUTE: &str = "
+        var observed = '';
+        for (const prototype of [Object.prototype, Array.prototype]) {
+            for (const key of ['value', 'done', '0', '1', 'get', 'set', 'enumerable',
+                'configurable', 'writable']) {
+                Object.defineProperty(prototype, key, {
+                    __proto__: null,
+                    get() { observed += ` get ${key}`; throw new Error(`get ${key}`); },
+                    set(v) { observed += ` set ${key}`; throw new Error(`set ${key}`); },
+                    configurable: true,
+                });
+            }
+        }";
+
+    /// Evaluates each source in a new polluted Realm, checking that it returns
+    /// true without touching the polluted properties.
+    fn assert_all_true_when_polluted(sources: &[&'static str]) {
+        for source in sources {
+            let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+            let mut root = agent.enter_gc();
+            let (agent, mut gc) = root.scope();
+            initialize_default_realm(agent, gc.reborrow());
+
+            evaluate(agent, POLLUTE, gc.reborrow());
+            let result = evaluate(agent, source, gc.reborrow()).unbind();
+            assert_eq!(result, Value::Boolean(true), "{source}");
+            let observed = evaluate(agent, "observed === ''", gc.reborrow()).unbind();
+            assert_eq!(
+                observed,
+                Value::Boolean(true),
+                "{source} observed the pollution"
+            );
+        }
+    }
+
+    #[test]
+    fn iteration_ignores_polluted_prototypes() {
+        assert_all_true_when_polluted(&[
+            "let r = ''; for (const x of [1, 2]) r += x; r === '12'",
+            "let r = ''; for (const x of new Set([1, 2])) r += x; r === '12'",
+            "let r = ''; for (const x of 'ab') r += x; r === 'ab'",
+            "function* g() { yield 1; yield 2; } [...g()].length === 2",
+            "const it = [1].values(); const { value, done } = it.next();
+                value === 1 && done === false && it.next().done === true",
+            "Object.keys([1].keys().next()).join() === 'value,done'",
+            "Array.from(new Set([1, 2])).length === 2",
+            "async function f() { for await (const x of [1]) {} } f() instanceof Promise",
+        ]);
+    }
+
+    #[test]
+    fn property_descriptors_ignore_polluted_prototypes() {
+        assert_all_true_when_polluted(&[
+            "const d = Object.getOwnPropertyDescriptor({ x: 1 }, 'x');
+                Object.keys(d).join() === 'value,writable,enumerable,configurable' &&
+                Object.getOwnPropertyDescriptor(d, 'value').value === 1",
+            "const d = Object.getOwnPropertyDescriptors({ get x() { return 1; } }).x;
+                Object.keys(d).join() === 'get,set,enumerable,configurable'",
+            "const d = Reflect.getOwnPropertyDescriptor(new Proxy({ x: 1 }, {}), 'x');
+                Object.getOwnPropertyDescriptor(d, 'writable').value === true",
+        ]);
+    }
+
+    #[test]
+    fn entries_ignore_polluted_prototypes() {
+        assert_all_true_when_polluted(&[
+            "const entries = Object.entries({ a: 1, b: 2 });
+                entries.length === 2 && Object.keys(entries[1]).join() === '0,1'",
+            "let r = ''; for (const [k, v] of new Map([['a', 1]])) r += k + v; r === 'a1'",
+            "let r = ''; for (const [k, v] of new Set(['a']).entries()) r += k + v; r === 'aa'",
+            "let r = ''; for (const [k, v] of ['a'].entries()) r += k + v; r === '0a'",
+            "Object.fromEntries(Object.entries({ a: 1 })).a === 1",
+            "new Map(Object.entries({ a: 1 })).get('a') === 1",
+        ]);
+    }
+
+    #[test]
+    fn destructuring_ignores_polluted_prototypes() {
+        assert_all_true_when_polluted(&[
+            "const [a, b] = [1, 2]; a === 1 && b === 2",
+            "const [[k, v]] = Object.entries({ x: 1 }); k === 'x' && v === 1",
+            "const [x, ...rest] = [1, 2, 3]; rest.length === 2 && rest.indexOf(3) === 1",
+            "const { a, ...rest } = { a: 1, b: 2, c: 3 }; Object.keys(rest).join() === 'b,c'",
+            "((...args) => { const [x, y] = args; return x + y; })(1, 2) === 3",
+        ]);
+    }
+
+    fn evaluate_string<'gc>(
+        agent: &mut Agent,
+        source: std::string::String,
+        gc: GcScope<'gc, '_>,
+    ) -> Value<'gc> {
+        let source_text = String::from_string(agent, source, gc.nogc());
+        agent.run_script(source_text.unbind(), gc).unwrap()
+    }
+
+    /// Describes the result of calling `f`: "todo" for the error thrown by
+    /// features that are not implemented yet, the name of any other thrown error,
+    /// the type of a primitive, "instance" for an object inheriting from
+    /// `constructor.prototype`, or "object" for any other object.
+    const DESCRIBE: &str = "
+        function describe(f, constructor) {
+            let result;
+            try {
+                result = f();
+            } catch (error) {
+                if (error.message.endsWith(' not implemented')) {
+                    return 'todo';
+                }
+                return error.name;
+            }
+            if (typeof result !== 'object' && typeof result !== 'function') {
+                return typeof result;
+            }
+            return Object.getPrototypeOf(result) === constructor.prototype ? 'instance' : 'object';
+        }";
+
+    /// Each intrinsic constructor with representative arguments, and the result
+    /// of calling it and of constructing it. Constructors that are not
+    /// implemented yet are expected to construct "todo", so that their cells fail
+    /// once they are implemented.
+    const MATRIX: &[(&str, &str, &str, &str)] = &[
+        ("Object", "", "instance", "instance"),
+        ("Object", "undefined", "instance", "instance"),
+        ("Object", "null", "instance", "instance"),
+        ("Object", "1", "object", "object"),
+        ("Function", "'return 1'", "instance", "instance"),
+        (
+            "Object.getPrototypeOf(function* () {}).constructor",
+            "",
+            "instance",
+            "instance",
+        ),
+        (
+            "Object.getPrototypeOf(async function () {}).constructor",
+            "",
+            "instance",
+            "instance",
+        ),
+        (
+            "Object.getPrototypeOf(async function* () {}).constructor",
+            "",
+            "instance",
+            "instance",
+        ),
+        ("Boolean", "1", "boolean", "instance"),
+        ("Symbol", "'description'", "symbol", "TypeError"),
+        ("Error", "'message'", "instance", "instance"),
+        ("EvalError", "'message'", "instance", "instance"),
+        ("RangeError", "'message'", "instance", "instance"),
+        ("ReferenceError", "'message'", "instance", "instance"),
+        ("SyntaxError", "'message'", "instance", "instance"),
+        ("TypeError", "'message'", "instance", "instance"),
+        ("URIError", "'message'", "instance", "instance"),
+        ("AggregateError", "[]", "instance", "instance"),
+        ("Number", "'12'", "number", "instance"),
+        ("BigInt", "12", "bigint", "TypeError"),
+        ("Date", "", "string", "instance"),
+        ("Date", "0", "string", "instance"),
+        ("String", "12", "string", "instance"),
+        ("String", "Symbol('description')", "string", "TypeError"),
+        ("RegExp", "'a+'", "instance", "instance"),
+        ("RegExp", "/a+/g, 'i'", "instance", "instance"),
+        ("RegExp", "'a+', 'gg'", "SyntaxError", "SyntaxError"),
+        ("Array", "3", "instance", "instance"),
+        ("Array", "1, 2", "instance", "instance"),
+        ("Array", "-1", "RangeError", "RangeError"),
+        (
+            "Object.getPrototypeOf(Int8Array)",
+            "",
+            "TypeError",
+            "TypeError",
+        ),
+        ("Int8Array", "2", "TypeError", "instance"),
+        ("Uint8Array", "2", "TypeError", "instance"),
+        ("Uint8ClampedArray", "2", "TypeError", "instance"),
+        ("Int16Array", "2", "TypeError", "instance"),
+        ("Uint16Array", "2", "TypeError", "instance"),
+        ("Int32Array", "2", "TypeError", "instance"),
+        ("Uint32Array", "2", "TypeError", "instance"),
+        ("BigInt64Array", "2", "TypeError", "instance"),
+        ("BigUint64Array", "2", "TypeError", "instance"),
+        ("Float32Array", "2", "TypeError", "instance"),
+        ("Float64Array", "2", "TypeError", "instance"),
+        ("Map", "", "TypeError", "instance"),
+        ("Set", "", "TypeError", "instance"),
+        ("WeakMap", "", "TypeError", "todo"),
+        ("WeakSet", "", "TypeError", "instance"),
+        ("ArrayBuffer", "8", "TypeError", "instance"),
+        ("SharedArrayBuffer", "8", "TypeError", "todo"),
+        ("DataView", "new ArrayBuffer(8)", "TypeError", "instance"),
+        ("WeakRef", "{}", "TypeError", "instance"),
+        ("FinalizationRegistry", "() => {}", "TypeError", "todo"),
+        ("Iterator", "", "TypeError", "TypeError"),
+        ("Promise", "() => {}", "TypeError", "instance"),
+        ("Proxy", "{}, {}", "TypeError", "object"),
+        ("parseInt", "'12'", "number", "TypeError"),
+    ];
+
+    #[test]
+    fn intrinsic_constructors_called_and_constructed() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        evaluate_string(agent, DESCRIBE.to_owned(), gc.reborrow());
+        for &(constructor, arguments, call, construct) in MATRIX {
+            let source = format!("describe(() => {constructor}({arguments}), {constructor})");
+            let result = evaluate_string(agent, source.clone(), gc.reborrow()).unbind();
+            let result = String::try_from(result).unwrap();
+            assert_eq!(result.as_str(agent), call, "{source}");
+            let source = format!("describe(() => new ({constructor})({arguments}), {constructor})");
+            let result = evaluate_string(agent, source.clone(), gc.reborrow()).unbind();
+            let result = String::try_from(result).unwrap();
+            assert_eq!(result.as_str(agent), construct, "{source}");
+        }
+    }
+
+    #[test]
+    fn spread_string_yields_code_points() {
+        assert_all_true(&[
+            "[...'a\u{1F600}b'].length === 3",
+            "[...'a\u{1F600}b'][1] === '\u{1F600}' && [...'a\u{1F600}b'][1].length === 2",
+            "[...'a\u{1F600}b'].join('|') === 'a|\u{1F600}|b'",
+            "Array.from('\u{1F600}\u{1F601}').length === 2",
+            "[...'\\uD83Dx'].length === 2 && [...'\\uD83Dx'][0] === '\\uD83D'",
+            "[...new String('x\u{10000}')].length === 2",
+        ]);
+    }
+
+    /// Defines `log` and `arg(name, value)`, which creates an object that logs
+    /// its name whenever it is converted to a primitive.
+    const COERCION_PRELUDE: &str = "
+        var log = [];
+        function arg(name, value) {
+            return {
+                valueOf() { log.push(name); return value; },
+                toString() { log.push(name); return String(value); },
+            };
+        }
+        function check(name, actual, expected, order) {
+            const logged = log.join();
+            log = [];
+            if (actual !== expected || logged !== order) {
+                throw new Error(`${name}: got ${String(actual)} in order ${logged}`);
+            }
+        }
+    ";
+
+    #[test]
+    fn arguments_are_coerced_left_to_right() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        evaluate(agent, COERCION_PRELUDE, gc.reborrow());
+        let result = evaluate(
+            agent,
+            "const self = arg('this', 'hello world');
+            check('at', String.prototype.at.call(self, arg('index', -1)), 'd', 'this,index');
+            check('charAt', String.prototype.charAt.call(self, arg('pos', 1)), 'e', 'this,pos');
+            check('charCodeAt', String.prototype.charCodeAt.call(self, arg('pos', 0)), 104, 'this,pos');
+            check('codePointAt', String.prototype.codePointAt.call(self, arg('pos', 4)), 111, 'this,pos');
+            check('indexOf', String.prototype.indexOf.call(self, arg('search', 'o'), arg('position', 5)), 7, 'this,search,position');
+            check('lastIndexOf', String.prototype.lastIndexOf.call(self, arg('search', 'o'), arg('position', 5)), 4, 'this,search,position');
+            check('slice', String.prototype.slice.call(self, arg('start', 1), arg('end', -6)), 'ello', 'this,start,end');
+            check('substring', String.prototype.substring.call(self, arg('start', 5), arg('end', 1)), 'ello', 'this,start,end');
+            check('substr', String.prototype.substr.call(self, arg('start', -5), arg('length', 3)), 'wor', 'this,start,length');
+            check('repeat', String.prototype.repeat.call(arg('this', 'ab'), arg('count', 2)), 'abab', 'this,count');
+            check('pow', Math.pow(arg('base', 2), arg('exponent', 10)), 1024, 'base,exponent');
+            check('atan2', Math.atan2(arg('y', 0), arg('x', 1)), 0, 'y,x');
+            check('asIntN', BigInt.asIntN(arg('bits', 8), arg('bigint', 255n)), -1n, 'bits,bigint');
+            check('asUintN', BigInt.asUintN(arg('bits', 8), arg('bigint', -1n)), 255n, 'bits,bigint');
+            true",
+            gc,
+        );
+        assert_eq!(result, Value::Boolean(true));
+    }
+
+    #[test]
+    fn this_coercion_errors_come_before_argument_coercion() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        evaluate(agent, COERCION_PRELUDE, gc.reborrow());
+        let result = evaluate(
+            agent,
+            "function error(f) {
+                try {
+                    f();
+                } catch (err) {
+                    return `${err.name}: ${err.message}`;
+                }
+            }
+            const throwing = { toString() { throw new SyntaxError('this'); } };
+            check(
+                'null this',
+                error(() => String.prototype.at.call(null, arg('index', 0))),
+                'TypeError: String.prototype.at called on null or undefined',
+                '',
+            );
+            check(
+                'undefined this',
+                error(() => String.prototype.indexOf.call(undefined, arg('search', 'a'), arg('position', 0))),
+                'TypeError: String.prototype.indexOf called on null or undefined',
+                '',
+            );
+            check(
+                'array this',
+                error(() => Array.prototype.at.call(null, arg('index', 0))),
+                'TypeError: Array.prototype.at called on null or undefined',
+                '',
+            );
+            check(
+                'throwing this',
+                error(() => String.prototype.slice.call(throwing, arg('start', 0), arg('end', 1))),
+                'SyntaxError: this',
+                '',
+            );
+            check(
+                'throwing argument',
+                error(() => String.prototype.substring.call('abc', { valueOf() { throw new EvalError('start'); } }, arg('end', 1))),
+                'EvalError: start',
+                '',
+            );
+            check(
+                'index out of range',
+                error(() => BigInt.asIntN(-1, arg('bigint', 1n))),
+                'RangeError: Index is out of range',
+                '',
+            );
+            check(
+                'repeat count',
+                error(() => 'ab'.repeat(arg('count', -1))),
+                'RangeError: count must not be negative',
+                'count',
+            );
+            true",
+            gc,
+        );
+        assert_eq!(result, Value::Boolean(true));
+    }
+
+    #[test]
+    fn static_blocks_run_in_order_with_static_fields() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source = String::from_static_str(
+            agent,
+            "const log = [];
+            class A {
+                static #secret = 1;
+                static x = 2;
+                static {
+                    log.push('first block');
+                    this.y = this.x + A.#secret;
+                    A.#secret = 10;
+                }
+                static z = (log.push('z'), this.y * this.#secret);
+                static {
+                    log.push('second block');
+                    var v = 5;
+                    let w = 6;
+                    function add(a, b) { return a + b; }
+                    this.w = add(v, w);
+                }
+                static getSecret() { return A.#secret; }
+            }
+            JSON.stringify({
+                x: A.x,
+                y: A.y,
+                z: A.z,
+                w: A.w,
+                secret: A.getSecret(),
+                log,
+                leaked: typeof v + typeof w + typeof add,
+            })",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source.unbind(), gc.reborrow())
+            .unwrap()
+            .unbind();
+        let result = result.to_string(agent, gc.reborrow()).unwrap();
+        assert_eq!(
+            result.as_str(agent),
+            r#"{"x":2,"y":3,"z":30,"w":11,"secret":10,"log":["first block","z","second block"],"leaked":"undefinedundefinedundefined"}"#
+        );
+    }
+
+    #[test]
+    fn static_block_errors_propagate_out_of_class_definition() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source = String::from_static_str(
+            agent,
+            "let reached = false;
+            try {
+                class B {
+                    static { throw new RangeError('static'); }
+                    static after = (reached = true);
+                }
+            } catch (err) {
+                err instanceof RangeError && err.message === 'static' && !reached
+            }",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source.unbind(), gc.reborrow())
+            .unwrap()
+            .unbind();
+        assert_eq!(result, Value::Boolean(true));
+    }
+
+    /// Defines `throwsRangeError(f)`, which returns true if calling `f` throws a
+    /// RangeError.
+    const LENGTH_LIMIT_PRELUDE: &str = "
+        function throwsRangeError(f) {
+            try {
+                f();
+            } catch (err) {
+                return err instanceof RangeError;
+            }
+            return false;
+        }
+    ";
+
+    fn run_with_length_limits(options: Options, sources: &[&'static str]) {
+        let mut agent = Agent::new(options, &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        evaluate(agent, LENGTH_LIMIT_PRELUDE, gc.reborrow());
+        for source in sources {
+            let result = evaluate(agent, source, gc.reborrow()).unbind();
+            assert_eq!(result, Value::Boolean(true), "{source}");
+        }
+    }
+
+    #[test]
+    fn string_length_is_limited() {
+        run_with_length_limits(
+            Options {
+                max_string_length: Some(1000),
+                ..Default::default()
+            },
+            &[
+                "'x'.repeat(1000).length === 1000",
+                "throwsRangeError(() => 'x'.repeat(1001))",
+                "throwsRangeError(() => 'xy'.repeat(2 ** 52))",
+                "Array(10).fill('x'.repeat(100)).join('').length === 1000",
+                "throwsRangeError(() => Array(11).fill('x'.repeat(100)).join(''))",
+                "throwsRangeError(() => Array.prototype.join.call({ length: 2 ** 40 }))",
+                "'x'.padStart(1000).length === 1000",
+                "throwsRangeError(() => 'x'.padEnd(1001, 'ab'))",
+                "throwsRangeError(() => 'x'.padStart(2 ** 53 - 1))",
+                "throwsRangeError(() => 'ab'.padStart(2 ** 30))",
+                "throwsRangeError(() => 'ab'.padEnd(600, '\u{1F600}'))",
+                "'ab'.padEnd(1000, 'xyz').length === 1000 && 'ab'.padEnd(7, 'xyz') === 'abxyzxy'",
+                "'ab'.padStart(5, '\u{1F600}') === '\u{1F600}\\uD83Dab'",
+                "'xyz'.repeat(333).length === 999 && 'xyz'.repeat(333).endsWith('zxyz')",
+                "'x'.padStart(999, 'yz').startsWith('yzy') && 'x'.padStart(999, 'yz').endsWith('yzx')",
+                "throwsRangeError(() => JSON.stringify(['x'.repeat(600), 'x'.repeat(600)]))",
+                "throwsRangeError(() => JSON.stringify(Array(2 ** 20)))",
+                // The agent is still usable after the errors above.
+                "JSON.stringify(['x'.repeat(10)]) === '[\"xxxxxxxxxx\"]'",
+            ],
+        );
+    }
+
+    #[test]
+    fn string_concatenation_is_limited_before_allocation() {
+        run_with_length_limits(
+            Options {
+                max_string_length: Some(1000),
+                ..Default::default()
+            },
+            &[
+                "var half = 'x'.repeat(500); (half + half).length === 1000",
+                "throwsRangeError(() => half + half + 'x')",
+                "throwsRangeError(() => `${half}${half}${1}`)",
+                "throwsRangeError(() => half.concat(half, '!'))",
+                "throwsRangeError(() => { let s = 'x'; for (;;) s += s; })",
+                "(half + half).length === 1000",
+            ],
+        );
+    }
+
+    #[test]
+    fn array_element_capacity_is_limited() {
+        run_with_length_limits(
+            Options {
+                max_array_element_capacity: Some(1000),
+                ..Default::default()
+            },
+            &[
+                "Array(1000).fill(0).length === 1000",
+                "throwsRangeError(() => Array(1001).fill(0))",
+                "throwsRangeError(() => { const a = []; a.length = 1001; })",
+                "throwsRangeError(() => { const a = []; a[1000] = 1; })",
+                "throwsRangeError(() => { const a = Array(1000); a.push(1); })",
+                "throwsRangeError(() => { const a = []; for (;;) a.push(0); })",
+                // Setting a smaller length and properties outside of the array
+                // index range are unaffected.
+                "const a = Array(1000); a.length = 10; a[2 ** 32] = 1; a.length === 10",
+            ],
+        );
+    }
+
+    #[test]
+    fn limits_are_per_agent() {
+        const SOURCES: &[&str] = &[
+            "'x'.repeat(2000).length === 2000",
+            "Array(2000).length === 2000",
+        ];
+        run_with_length_limits(Options::default(), SOURCES);
+        run_with_length_limits(
+            Options {
+                max_string_length: Some(1000),
+                max_array_element_capacity: Some(1000),
+                ..Default::default()
+            },
+            &[
+                "throwsRangeError(() => 'x'.repeat(2000))",
+                "throwsRangeError(() => Array(2000))",
+            ],
+        );
+        run_with_length_limits(
+            Options {
+                max_string_length: Some(4000),
+                max_array_element_capacity: Some(4000),
+                ..Default::default()
+            },
+            SOURCES,
+        );
+    }
+
+    #[test]
+    fn arrow_callback_sees_method_this() {
+        assert_script_true(
+            "const scaler = {
+                factor: 3,
+                scale(arr) {
+                    return arr.map(x => this.factor * x);
+                },
+            };
+            JSON.stringify(scaler.scale([1, 2, 3])) === '[3,6,9]'",
+        );
+    }
+
+    #[test]
+    fn nested_arrows_capture_this_lexically() {
+        assert_script_true(
+            "const counter = {
+                count: 0,
+                addAll(rows) {
+                    rows.forEach(row => row.forEach(x => { this.count += x; }));
+                    return this.count;
+                },
+            };
+            const other = { count: 100 };
+            function outer() {
+                const arrow = () => () => this;
+                // call, apply and bind cannot change the this value of an arrow.
+                return arrow.call(other)() === this && arrow().bind(other)() === this;
+            }
+            counter.addAll([[1, 2], [3]]) === 6 &&
+                outer.call(counter) &&
+                (() => this)() === globalThis",
+        );
+    }
+
+    #[test]
+    fn arrows_capture_arguments_new_target_and_super() {
+        assert_script_true(
+            "function args() {
+                return (() => (() => arguments[1])())();
+            }
+            function Target() {
+                this.target = (() => () => new.target)()();
+            }
+            const plain = {};
+            class Base {
+                greet() { return 'base'; }
+            }
+            class Derived extends Base {
+                greet() {
+                    return [0].map(() => (() => super.greet())())[0];
+                }
+            }
+            class Fields extends Base {
+                greeting = () => super.greet();
+            }
+            args('a', 'b') === 'b' &&
+                new Target().target === Target &&
+                (Target.call(plain), plain.target === undefined) &&
+                new Derived().greet() === 'base' &&
+                new Fields().greeting() === 'base'",
+        );
+    }
+
+    #[test]
+    fn cyclic_arrays_join_to_empty_strings() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let result = evaluate(
+            agent,
+            "const a = [1];
+            a.push(a);
+            const b = [2, a];
+            a.push(b);
+            String(a) === '1,,2,' &&
+                a.join('-') === '1--2,' &&
+                [1, [2, [3]]].toString() === '1,2,3' &&
+                [null, undefined, 0, , false].join() === ',,0,,false'",
+            gc,
+        );
+        assert_eq!(result, Value::Boolean(true));
+    }
+
+    #[test]
+    fn deeply_nested_arrays_join_iteratively() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let result = evaluate(
+            agent,
+            "let x = ['end'];
+            for (let i = 0; i < 10000; i++) {
+                x = [i, x];
+            }
+            const s = String(x);
+            s.startsWith('9999,9998,') && s.endsWith(',1,0,end') && s.split(',').length === 10001",
+            gc,
+        );
+        assert_eq!(result, Value::Boolean(true));
+    }
+
+    #[test]
+    fn join_coerces_separator_and_elements() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let result = evaluate(
+            agent,
+            "const log = [];
+            const separator = { toString() { log.push('separator'); return '-'; } };
+            const element = { toString() { log.push('element'); return 'e'; } };
+            // A nested array with its own join is not joined in place.
+            const custom = [1, 2];
+            custom.join = function () { return 'custom'; };
+            [1, element, 3].join(separator) === '1-e-3' &&
+                [].join(separator) === '' &&
+                log.join() === 'separator,element,separator' &&
+                [1, custom].join() === '1,custom' &&
+                [[1, 2], [3]].join(' ') === '1,2 3'",
+            gc,
+        );
+        assert_eq!(result, Value::Boolean(true));
+    }
+
+    #[test]
+    fn join_and_to_string_on_array_likes() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let result = evaluate(
+            agent,
+            "const arrayLike = { length: 3, 0: 'a', 2: 'c' };
+            const cyclic = { length: 2, 0: 'x' };
+            cyclic[1] = { toString() { return Array.prototype.join.call(cyclic); } };
+            Array.prototype.join.call(arrayLike) === 'a,,c' &&
+                Array.prototype.join.call(arrayLike, '+') === 'a++c' &&
+                Array.prototype.join.call(cyclic) === 'x,' &&
+                Array.prototype.toString.call({ join() { return 'joined'; } }) === 'joined' &&
+                Array.prototype.toString.call({ join: 1 }) === '[object Object]'",
+            gc,
+        );
+        assert_eq!(result, Value::Boolean(true));
+    }
+
+    #[test]
+    fn self_enqueuing_microtask_stops_at_limit() {
+        let host_hooks: &QueueingHostHooks = Box::leak(Box::default());
+        let mut agent = Agent::new(test_options(), host_hooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        evaluate(
+            agent,
+            "var turns = 0;
+            Promise.resolve().then(function f() {
+                turns++;
+                Promise.resolve().then(f);
+            });",
+            gc.reborrow(),
+        );
+        let checkpoint = agent.run_microtasks(100, gc.reborrow()).unwrap();
+        assert_eq!(checkpoint, MicrotaskCheckpoint::LimitReached);
+        assert_eq!(host_hooks.jobs.borrow().len(), 1);
+        let turns = evaluate(agent, "turns", gc.reborrow());
+        assert_eq!(turns, Value::from(100));
+
+        // The remaining Jobs can be run later, or dropped to break the loop.
+        let checkpoint = agent.run_microtasks(10, gc.reborrow()).unwrap();
+        assert_eq!(checkpoint, MicrotaskCheckpoint::LimitReached);
+        host_hooks.jobs.borrow_mut().clear();
+        let checkpoint = agent.run_microtasks(10, gc.reborrow()).unwrap();
+        assert_eq!(checkpoint, MicrotaskCheckpoint::Drained);
+        let turns = evaluate(agent, "turns", gc);
+        assert_eq!(turns, Value::from(110));
+    }
+
+    #[test]
+    fn finite_microtasks_drain_within_limit() {
+        let host_hooks: &QueueingHostHooks = Box::leak(Box::default());
+        let mut agent = Agent::new(test_options(), host_hooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        evaluate(
+            agent,
+            "var log = [];
+            Promise.resolve(1)
+                .then((x) => { log.push(x); return x + 1; })
+                .then((x) => { log.push(x); });",
+            gc.reborrow(),
+        );
+        let checkpoint = agent.run_microtasks(100, gc.reborrow()).unwrap();
+        assert_eq!(checkpoint, MicrotaskCheckpoint::Drained);
+        let result = evaluate(agent, "log.join() === '1,2'", gc);
+        assert_eq!(result, Value::Boolean(true));
+    }
+
+    /// Defines `check(name, actual, expected)`, comparing the JSON of both, and
+    /// constructors for objects whose getter `b` mutates the object while it is
+    /// being enumerated.
+    const ENUMERATION_PRELUDE: &str = "
+        function check(name, actual, expected) {
+            actual = JSON.stringify(actual);
+            expected = JSON.stringify(expected);
+            if (actual !== expected) {
+                throw new Error(`${name}: got ${actual}, expected ${expected}`);
+            }
+        }
+        // The getter deletes a key that comes after it.
+        function deletesLater() {
+            return { a: 1, get b() { delete this.c; return 2; }, c: 3, d: 4 };
+        }
+        // The getter adds enough keys to make the object reallocate its storage.
+        function addsKeys() {
+            return {
+                a: 1,
+                get b() {
+                    for (let i = 0; i < 64; i++) this['n' + i] = i;
+                    return 2;
+                },
+                c: 3,
+            };
+        }
+        // The getter deletes a key that was already visited.
+        function deletesEarlier() {
+            return { a: 1, get b() { delete this.a; return 2; }, c: 3 };
+        }
+        // The same, on an Array with an indexed getter.
+        function arrayDeletesLater() {
+            const array = [1, , 3, 4];
+            Object.defineProperty(array, 1, {
+                get() { array.length = 2; return 2; },
+                enumerable: true,
+                configurable: true,
+            });
+            return array;
+        }
+    ";
+
+    #[test]
+    fn getters_mutating_object_during_entries_and_values() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        evaluate(agent, ENUMERATION_PRELUDE, gc.reborrow());
+        let result = evaluate(
+            agent,
+            "check('entries deletes later', Object.entries(deletesLater()), [['a', 1], ['b', 2], ['d', 4]]);
+            check('values deletes later', Object.values(deletesLater()), [1, 2, 4]);
+            check('entries adds keys', Object.entries(addsKeys()), [['a', 1], ['b', 2], ['c', 3]]);
+            check('values adds keys', Object.values(addsKeys()), [1, 2, 3]);
+            const added = addsKeys();
+            check('keys adds keys', Object.keys(added), ['a', 'b', 'c']);
+            added.b;
+            check('keys after getter', Object.keys(added).length, 67);
+            check('entries deletes earlier', Object.entries(deletesEarlier()), [['a', 1], ['b', 2], ['c', 3]]);
+            check('values deletes earlier', Object.values(deletesEarlier()), [1, 2, 3]);
+            check('entries array', Object.entries(arrayDeletesLater()), [['0', 1], ['1', 2]]);
+            check('spread deletes later', { ...deletesLater() }, { a: 1, b: 2, d: 4 });
+            check('assign adds keys', Object.keys(Object.assign({}, addsKeys())), ['a', 'b', 'c']);
+            check('stringify deletes later', JSON.parse(JSON.stringify(deletesLater())), { a: 1, b: 2, d: 4 });
+            true",
+            gc,
+        );
+        assert_eq!(result, Value::Boolean(true));
+    }
+
+    #[test]
+    fn getters_mutating_object_during_for_in() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        evaluate(agent, ENUMERATION_PRELUDE, gc.reborrow());
+        let result = evaluate(
+            agent,
+            "function visit(object) {
+                const visited = [];
+                for (const key in object) {
+                    visited.push([key, object[key]]);
+                }
+                return visited;
+            }
+            check('for-in deletes later', visit(deletesLater()), [['a', 1], ['b', 2], ['d', 4]]);
+            check('for-in adds keys', visit(addsKeys()).slice(0, 3), [['a', 1], ['b', 2], ['c', 3]]);
+            check('for-in deletes earlier', visit(deletesEarlier()), [['a', 1], ['b', 2], ['c', 3]]);
+            check('for-in array', visit(arrayDeletesLater()), [['0', 1], ['1', 2]]);
+            // Keys of the prototype chain deleted before they are visited are
+            // skipped as well.
+            const proto = { p: 1, q: 2 };
+            const child = Object.create(proto);
+            child.a = 0;
+            Object.defineProperty(child, 'b', {
+                get() { delete proto.q; return 1; },
+                enumerable: true,
+            });
+            check('for-in prototype', visit(child), [['a', 0], ['b', 1], ['p', 1]]);
+            // Deleting everything from the loop body ends the loop.
+            const object = { a: 1, b: 2, c: 3 };
+            const keys = [];
+            for (const key in object) {
+                keys.push(key);
+                for (const other in object) delete object[other];
+            }
+            check('for-in delete all', keys, ['a']);
+            true",
+            gc,
+        );
+        assert_eq!(result, Value::Boolean(true));
+    }
+
+    #[test]
+    fn global_var_declarations_are_created_in_source_order() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        evaluate(agent, ENUMERATION_PRELUDE, gc.reborrow());
+        let result = evaluate(
+            agent,
+            "var zeta, alpha = 1, mu;
+            var { omega, beta } = {};
+            var alpha, gamma;
+            const names = ['zeta', 'alpha', 'mu', 'omega', 'beta', 'gamma'];
+            const keys = Object.keys(globalThis).filter((key) => names.includes(key));
+            check('global vars', keys, names);
+            true",
+            gc,
+        );
+        assert_eq!(result, Value::Boolean(true));
+    }
+
+    #[test]
+    fn for_each_skips_holes() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let result = evaluate(
+            agent,
+            "const calls = [];
+            const array = [1, , 3, , 5];
+            const thisArg = { name: 'this' };
+            array.forEach(function (value, index, object) {
+                calls.push(`${value}@${index}:${object === array}:${this.name}`);
+            }, thisArg);
+            // Holes that are filled through the prototype are visited.
+            Array.prototype[3] = 'proto';
+            const withPrototype = [];
+            array.forEach((value) => withPrototype.push(value));
+            delete Array.prototype[3];
+            // Elements deleted and appended during the walk are skipped.
+            const mutated = [];
+            const shrinking = [1, 2, 3, 4];
+            shrinking.forEach((value, index) => {
+                if (index === 0) {
+                    delete shrinking[1];
+                    shrinking.push(5);
+                }
+                mutated.push(value);
+            });
+            // Array-likes with missing indices work the same.
+            const arrayLike = [];
+            Array.prototype.forEach.call({ length: 3, 0: 'a', 2: 'c' }, (value, index) => {
+                arrayLike.push(index);
+            });
+            calls.join() === '1@0:true:this,3@2:true:this,5@4:true:this' &&
+                withPrototype.join() === '1,3,proto,5' &&
+                mutated.join() === '1,3,4' &&
+                arrayLike.join() === '0,2'",
+            gc,
+        );
+        assert_eq!(result, Value::Boolean(true));
+    }
+
+    #[test]
+    fn map_preserves_holes() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let result = evaluate(
+            agent,
+            "let calls = 0;
+            const mapped = [1, , 3, , ].map((value) => {
+                calls++;
+                return value * 2;
+            });
+            const arrayLike = Array.prototype.map.call({ length: 3, 1: 'b' }, (value) => value + value);
+            calls === 2 &&
+                mapped.length === 4 &&
+                !(1 in mapped) &&
+                !(3 in mapped) &&
+                mapped[0] === 2 &&
+                mapped[2] === 6 &&
+                arrayLike.length === 3 &&
+                Object.keys(arrayLike).join() === '1' &&
+                arrayLike[1] === 'bb'",
+            gc,
+        );
+        assert_eq!(result, Value::Boolean(true));
+    }
+
+    #[test]
+    fn predicates_skip_holes() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let result = evaluate(
+            agent,
+            "const array = [1, , 3];
+            const isDefined = (value) => value !== undefined;
+            const thisArg = { limit: 2 };
+            // The length is read once: Appended elements are not visited.
+            const growing = [1, 2];
+            const visited = [];
+            growing.some((value) => {
+                growing.push(value);
+                visited.push(value);
+                return false;
+            });
+            array.every(isDefined) &&
+                !array.some((value) => value === undefined) &&
+                array.filter(() => true).join() === '1,3' &&
+                array.filter(function (value) { return value > this.limit; }, thisArg).join() === '3' &&
+                visited.join() === '1,2' &&
+                growing.length === 4",
+            gc,
+        );
+        assert_eq!(result, Value::Boolean(true));
+    }
+
+    #[test]
+    fn nan_is_nan() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let result = evaluate(
+            agent,
+            "const nan = 0 / 0;
+            Object.is(NaN, NaN) &&
+                Object.is(NaN, nan) &&
+                Object.is(Number('not a number'), -NaN) &&
+                // Unlike strict equality and SameValueZero users.
+                NaN !== NaN &&
+                [NaN].indexOf(NaN) === -1 &&
+                [NaN].includes(NaN) &&
+                !Object.is(NaN, 'NaN') &&
+                !Object.is(NaN, undefined) &&
+                !Object.is(NaN, Infinity)",
+            gc,
+        );
+        assert_eq!(result, Value::Boolean(true));
+    }
+
+    #[test]
+    fn signed_zeros_differ() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let result = evaluate(
+            agent,
+            "const negativeZero = -1 / Infinity;
+            !Object.is(0, -0) &&
+                !Object.is(-0, 0) &&
+                !Object.is(+0, negativeZero) &&
+                Object.is(-0, negativeZero) &&
+                Object.is(0, 0 * 1) &&
+                Object.is(Math.round(-0.25), -0) &&
+                Object.is(0, -0 + 0) &&
+                // Strict equality does not tell the zeros apart.
+                0 === -0 &&
+                !Object.is(0, 0n) &&
+                !Object.is(0, false) &&
+                !Object.is(-0, '-0')",
+            gc,
+        );
+        assert_eq!(result, Value::Boolean(true));
+    }
+
+    #[test]
+    fn objects_compare_by_reference() {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let result = evaluate(
+            agent,
+            "const object = {};
+            const symbol = Symbol('a');
+            const long = 'a string too long to be stored inline';
+            Object.is(object, object) &&
+                !Object.is(object, {}) &&
+                !Object.is([], []) &&
+                Object.is(symbol, symbol) &&
+                !Object.is(symbol, Symbol('a')) &&
+                // Primitives are compared by value, also when heap allocated.
+                Object.is(long, long.slice(0, 10) + long.slice(10)) &&
+                Object.is(2n ** 100n, 2n ** 100n) &&
+                Object.is(1e300, 1e299 * 10) &&
+                Object.is() &&
+                Object.is(undefined) &&
+                !Object.is(null, undefined) &&
+                Object.is.length === 2",
+            gc,
+        );
+        assert_eq!(result, Value::Boolean(true));
+    }
+
+    /// Defines `check(actual, expected)`, which compares a result printed with
+    /// `show` to the expected text, and `throws(f)`, which returns the name of
+    /// the error thrown by `f`. `big` converts to 2 ** 70.
+    const NUMERIC_ARGUMENT_PRELUDE: &str = "
+        function show(value) {
+            if (Array.isArray(value)) return `[${value.map(show).join()}]`;
+            if (Object.is(value, -0)) return '-0';
+            return typeof value === 'bigint' ? `${value}n` : String(value);
+        }
+        function check(actual, expected) {
+            if (show(actual) !== expected) {
+                throw new Error(`expected ${expected}, got ${show(actual)}`);
+            }
+        }
+        function throws(f) {
+            try {
+                f();
+            } catch (err) {
+                return err.name;
+            }
+            return 'no error';
+        }
+        const big = { valueOf() { return 2 ** 70; } };
+    ";
+
+    fn run_numeric_arguments(source: &'static str) {
+        let mut agent = Agent::new(test_options(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        evaluate(agent, NUMERIC_ARGUMENT_PRELUDE, gc.reborrow());
+        let result = evaluate(agent, source, gc.reborrow()).unbind();
+        assert_eq!(result, Value::Boolean(true));
+    }
+
+    #[test]
+    fn array_methods_clamp_relative_indices() {
+        run_numeric_arguments(
+            "check([1, 2, 3].fill(0, NaN, Infinity), '[0,0,0]');
+            check([1, 2, 3].fill(0, -Infinity, -(2 ** 53)), '[1,2,3]');
+            check([1, 2, 3].fill(0, 2 ** 53 + 1), '[1,2,3]');
+            check([1, 2, 3].fill(0, -0.5, 1.9), '[0,2,3]');
+            check([1, 2, 3].slice(-1e300, big), '[1,2,3]');
+            check([1, 2, 3].slice(2 ** 32 + 1), '[]');
+            check([1, 2, 3].at(-Infinity), 'undefined');
+            check([1, 2, 3].at(-0.9), '1');
+            check([1, 2, 3].indexOf(1, -Infinity), '0');
+            check([1, 2, 3].indexOf(1, -1e300), '0');
+            check([1, 2, 3].lastIndexOf(3, -4), '-1');
+            check([1, 2, 3].lastIndexOf(1, -Infinity), '-1');
+            check([1, 2, 3].lastIndexOf(3, Infinity), '2');
+            check([1, 2, 3].includes(3, -(2 ** 64)), 'true');
+            check([1, 2, 3].includes(1, 2 ** 64), 'false');
+            check([1, 2, 3].splice(-Infinity, NaN), '[]');
+            check([1, 2, 3].copyWithin(-1e300, 2 ** 53), '[1,2,3]');
+            check(throws(() => [1, 2, 3].with(-Infinity, 0)), 'RangeError');
+            check([1, 2, 3].with(-0.5, 0), '[0,2,3]');
+            true",
+        );
+    }
+
+    #[test]
+    fn string_methods_clamp_relative_indices() {
+        run_numeric_arguments(
+            "check('abc'.at(-1e300), 'undefined');
+            check('abc'.slice(2 ** 64, -(2 ** 64)), '');
+            check('abc'.substr(-Infinity, 2 ** 53), 'abc');
+            check('abc'.substring(NaN, big), 'abc');
+            check(throws(() => 'abc'.repeat(Infinity)), 'RangeError');
+            check(throws(() => 'abc'.repeat(-1.5)), 'RangeError');
+            check('abc'.repeat(-0.5), '');
+            check('abc'.padStart(-Infinity, 'x'), 'abc');
+            // The limit is converted with ToUint32.
+            check('abc'.split('', 2 ** 32 + 2), '[a,b]');
+            check('abc'.split('', -(2 ** 32) + 1), '[a]');
+            true",
+        );
+    }
+
+    #[test]
+    fn lengths_and_digits_are_range_checked() {
+        run_numeric_arguments(
+            "check(throws(() => new Array(2 ** 32)), 'RangeError');
+            check(throws(() => new Array(1.5)), 'RangeError');
+            check(throws(() => { [].length = -0.5; }), 'RangeError');
+            check(throws(() => (1).toFixed(Infinity)), 'RangeError');
+            check((1.25).toFixed(-0.9), '1');
+            check((123.456).toExponential(undefined), '1.23456e+2');
+            check((123.456).toExponential(-0.5), '1e+2');
+            check(throws(() => (1).toExponential(101)), 'RangeError');
+            check(BigInt.asIntN(2 ** 53 - 1, -5n), '-5n');
+            check(BigInt.asIntN(64, 2n ** 63n), '-9223372036854775808n');
+            check(BigInt.asUintN(0, -1n), '0n');
+            check(BigInt.asUintN(2 ** 53 - 1, 5n), '5n');
+            check(throws(() => BigInt.asUintN(2 ** 53, 0n)), 'RangeError');
+            check(throws(() => BigInt.asIntN(-1, 0n)), 'RangeError');
+            true",
+        );
+    }
+
+    #[test]
+    fn huge_array_lengths_throw_instead_of_aborting() {
+        // An Array this long cannot be backed by memory, so creating one is an
+        // implementation limit rather than a crash.
+        run_numeric_arguments(
+            "check(throws(() => new Array(2 ** 30)), 'RangeError');
+            check(throws(() => { [].length = 2 ** 32 - 1; }), 'RangeError');
+            check(throws(() => { [][2 ** 31] = 1; }), 'RangeError');
+            true",
+        );
+    }
+
+    #[test]
+    fn typed_arrays_and_buffers_clamp_indices() {
+        run_numeric_arguments(
+            "check(Array.from(new Uint8Array(3).fill(7, -Infinity, NaN)), '[0,0,0]');
+            check(new Uint8Array(4).subarray(-1e300, 2 ** 53).length, '4');
+            check(new Uint8Array([1, 2]).at(-Infinity), 'undefined');
+            check(throws(() => new ArrayBuffer(-1)), 'RangeError');
+            check(throws(() => new ArrayBuffer(2 ** 53)), 'RangeError');
+            check(new ArrayBuffer(-0.5).byteLength, '0');
+            check(throws(() => new DataView(new ArrayBuffer(2), 2 ** 53)), 'RangeError');
+            check(new DataView(new ArrayBuffer(2), NaN).byteOffset, '0');
+            true",
+        );
+    }
+
+    #[test]
+    fn integer_conversions_wrap_huge_numbers() {
+        run_numeric_arguments("check([1e300 | 0, 1e300 >>> 0, -1e300 | 0], '[0,0,0]');
+            check([(2 ** 70 + 5 * 2 ** 20) | 0, -(2 ** 63) | 0], '[5242880,0]');
+            check([-1.5 >>> 0, 4294967295.7 | 0, -(2 ** 31) - 1.5 | 0, 2 ** 53 + 2 | 0], '[4294967295,-1,2147483647,2]');
+            check(Array.from(new Int16Array([2 ** 70 + 3 * 2 ** 16, -129.9, 1e21])), '[0,-129,0]');
+            check(Array.from(new Uint8ClampedArray([300.5, -3, Infinity, NaN])), '[255,0,255,0]');
+            true");
+    }
+
+    #[test]
+    fn destructured_catch_parameters() {
+        assert_all_true(&[
+            "(() => {
+                try { throw new TypeError('bad'); } catch ({ message, code = 0, name }) {
+                    return message === 'bad' && code === 0 && name === 'TypeError';
+                }
+            })()",
+            "(() => {
+                try { throw [1, [2, 3], 4, 5]; } catch ([first, [, second], ...rest]) {
+                    return first === 1 && second === 3 && rest.join() === '4,5';
+                }
+            })()",
+            "(() => {
+                try { throw { a: { b: 'nested' } }; } catch ({ a: { b }, c: [d] = ['default'] }) {
+                    return b === 'nested' && d === 'default';
+                }
+            })()",
+            // Defaults are evaluated in order, once, and only for missing values.
+            "(() => {
+                const log = [];
+                const next = (name) => (log.push(name), name);
+                try { throw { x: 'x' }; } catch ({ x = next('x default'), y = next('y default') }) {
+                    return x === 'x' && y === 'y default' && log.join() === 'y default';
+                }
+            })()",
+            // A throw while initializing the parameter is not caught by the same
+            // catch clause.
+            "(() => {
+                const log = [];
+                try {
+                    try { throw null; } catch ({ message }) { log.push('inner'); }
+                } catch (err) {
+                    log.push(err instanceof TypeError);
+                }
+                return log.join() === 'true';
+            })()",
+            "(() => {
+                try {
+                    try { throw 1; } catch ([first]) { return false; }
+                } catch (err) {
+                    return err instanceof TypeError;
+                }
+            })()",
+            "(() => {
+                let message = 'outer';
+                try {
+                    try { throw new Error('inner'); } catch ({ message }) { throw message + '!'; }
+                } catch (err) {
+                    return err === 'inner!' && message === 'outer';
+                }
+            })()",
+            // Functions declared in the catch block close over the parameters.
+            "(() => {
+                try { throw { m: 1 }; } catch ({ m }) {
+                    let n = m + 1;
+                    function sum() { return m + n; }
+                    return sum() === 3;
+                }
+            })()",
+        ]);
     }
 }
@@ -0,0 +1,324 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Syntactic analysis of the capabilities a parsed Script uses.
+//!
+//! The analysis walks the AST of a Script once without evaluating any of it,
+//! so that embedders can audit or refuse source text before running it. It
+//! cannot see through aliasing: `const e = globalThis["ev" + "al"]` is only
+//! reported as a use of `globalThis`.
+
+use core::ops::{BitOr, BitOrAssign};
+
+use oxc_ast::ast::{
+    ArrowFunctionExpression, AwaitExpression, BreakStatement, CallExpression,
+    ComputedMemberExpression, ContinueStatement, Expression, ForOfStatement, Function,
+    IdentifierReference, ImportExpression, Program, RegExpLiteral, StaticMemberExpression,
+    WithStatement,
+};
+use oxc_ast_visit::{
+    Visit,
+    walk::{
+        walk_arrow_function_expression, walk_await_expression, walk_call_expression,
+        walk_computed_member_expression, walk_for_of_statement, walk_function,
+        walk_import_expression, walk_static_member_expression, walk_with_statement,
+    },
+};
+use oxc_semantic::{SemanticBuilder, SemanticBuilderReturn};
+use oxc_syntax::scope::ScopeFlags;
+
+/// Set of capabilities used by a Script.
+///
+/// See [`Script::analyze`](super::script::Script::analyze).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ScriptFeatures(u16);
+
+impl ScriptFeatures {
+    /// No features.
+    pub const NONE: Self = Self(0);
+    /// A direct `eval(...)` call.
+    pub const DIRECT_EVAL: Self = Self(1 << 0);
+    /// A reference to `eval` other than a direct call, eg. `(0, eval)(...)`
+    /// or `globalThis.eval`.
+    pub const INDIRECT_EVAL: Self = Self(1 << 1);
+    /// A reference to the `Function` constructor, eg. `new Function(...)`.
+    pub const FUNCTION_CONSTRUCTOR: Self = Self(1 << 2);
+    /// A `with` statement.
+    pub const WITH_STATEMENT: Self = Self(1 << 3);
+    /// A dynamic `import(...)` call.
+    pub const DYNAMIC_IMPORT: Self = Self(1 << 4);
+    /// An `await` or `for await` outside of any function.
+    pub const TOP_LEVEL_AWAIT: Self = Self(1 << 5);
+    /// A regular expression literal or a reference to the `RegExp`
+    /// constructor.
+    pub const REGULAR_EXPRESSION: Self = Self(1 << 6);
+    /// A `break` or `continue` statement with a label.
+    pub const LABELED_BREAK: Self = Self(1 << 7);
+
+    /// Returns the raw bits of the set.
+    pub const fn bits(self) -> u16 {
+        self.0
+    }
+
+    /// Returns true if no features are in the set.
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns true if all features of `other` are in the set.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Adds the features of `other` to the set.
+    pub fn insert(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+}
+
+impl BitOr for ScriptFeatures {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for ScriptFeatures {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.insert(rhs);
+    }
+}
+
+/// Result of [`Script::analyze`](super::script::Script::analyze).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ScriptAnalysis {
+    /// Capabilities used anywhere in the Script, including nested functions
+    /// and classes.
+    pub features: ScriptFeatures,
+    /// Names of the identifiers the Script refers to without declaring them,
+    /// ie. the global bindings it reads or writes, sorted and deduplicated.
+    pub free_identifiers: Vec<String>,
+}
+
+pub(crate) fn analyze_program(program: &Program) -> ScriptAnalysis {
+    let mut visitor = FeatureVisitor {
+        features: ScriptFeatures::NONE,
+        function_depth: 0,
+    };
+    visitor.visit_program(program);
+
+    let SemanticBuilderReturn { semantic, .. } = SemanticBuilder::new().build(program);
+    let mut free_identifiers = semantic
+        .scoping()
+        .root_unresolved_references()
+        .keys()
+        .map(|name| name.to_string())
+        .collect::<Vec<_>>();
+    free_identifiers.sort_unstable();
+
+    ScriptAnalysis {
+        features: visitor.features,
+        free_identifiers,
+    }
+}
+
+fn feature_of_global_name(name: &str) -> ScriptFeatures {
+    match name {
+        "eval" => ScriptFeatures::INDIRECT_EVAL,
+        "Function" => ScriptFeatures::FUNCTION_CONSTRUCTOR,
+        "RegExp" => ScriptFeatures::REGULAR_EXPRESSION,
+        _ => ScriptFeatures::NONE,
+    }
+}
+
+struct FeatureVisitor {
+    features: ScriptFeatures,
+    /// Number of functions enclosing the visited node.
+    function_depth: u32,
+}
+
+impl<'a> Visit<'a> for FeatureVisitor {
+    fn visit_function(&mut self, it: &Function<'a>, flags: ScopeFlags) {
+        self.function_depth += 1;
+        walk_function(self, it, flags);
+        self.function_depth -= 1;
+    }
+
+    fn visit_arrow_function_expression(&mut self, it: &ArrowFunctionExpression<'a>) {
+        self.function_depth += 1;
+        walk_arrow_function_expression(self, it);
+        self.function_depth -= 1;
+    }
+
+    fn visit_call_expression(&mut self, it: &CallExpression<'a>) {
+        if matches!(it.callee.get_inner_expression(), Expression::Identifier(callee) if callee.name == "eval")
+        {
+            self.features |= ScriptFeatures::DIRECT_EVAL;
+            self.visit_arguments(&it.arguments);
+            return;
+        }
+        walk_call_expression(self, it);
+    }
+
+    fn visit_identifier_reference(&mut self, it: &IdentifierReference<'a>) {
+        self.features |= feature_of_global_name(&it.name);
+    }
+
+    fn visit_static_member_expression(&mut self, it: &StaticMemberExpression<'a>) {
+        self.features |= feature_of_global_name(&it.property.name);
+        walk_static_member_expression(self, it);
+    }
+
+    fn visit_computed_member_expression(&mut self, it: &ComputedMemberExpression<'a>) {
+        if let Expression::StringLiteral(property) = it.expression.get_inner_expression() {
+            self.features |= feature_of_global_name(&property.value);
+        }
+        walk_computed_member_expression(self, it);
+    }
+
+    fn visit_with_statement(&mut self, it: &WithStatement<'a>) {
+        self.features |= ScriptFeatures::WITH_STATEMENT;
+        walk_with_statement(self, it);
+    }
+
+    fn visit_import_expression(&mut self, it: &ImportExpression<'a>) {
+        self.features |= ScriptFeatures::DYNAMIC_IMPORT;
+        walk_import_expression(self, it);
+    }
+
+    fn visit_await_expression(&mut self, it: &AwaitExpression<'a>) {
+        if self.function_depth == 0 {
+            self.features |= ScriptFeatures::TOP_LEVEL_AWAIT;
+        }
+        walk_await_expression(self, it);
+    }
+
+    fn visit_for_of_statement(&mut self, it: &ForOfStatement<'a>) {
+        if it.r#await && self.function_depth == 0 {
+            self.features |= ScriptFeatures::TOP_LEVEL_AWAIT;
+        }
+        walk_for_of_statement(self, it);
+    }
+
+    fn visit_reg_exp_literal(&mut self, _: &RegExpLiteral<'a>) {
+        self.features |= ScriptFeatures::REGULAR_EXPRESSION;
+    }
+
+    fn visit_break_statement(&mut self, it: &BreakStatement<'a>) {
+        if it.label.is_some() {
+            self.features |= ScriptFeatures::LABELED_BREAK;
+        }
+    }
+
+    fn visit_continue_statement(&mut self, it: &ContinueStatement<'a>) {
+        if it.label.is_some() {
+            self.features |= ScriptFeatures::LABELED_BREAK;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ScriptAnalysis, ScriptFeatures};
+    use crate::ecmascript::{
+        execution::{Agent, DefaultHostHooks, agent::Options, initialize_default_realm},
+        scripts_and_modules::script::parse_script,
+        types::String,
+    };
+
+    fn analyze(source: &'static str, strict: bool) -> ScriptAnalysis {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        agent.with_gc(|agent, mut gc| {
+            initialize_default_realm(agent, gc.reborrow());
+            let realm = agent.current_realm(gc.nogc());
+            let source_text = String::from_static_str(agent, source, gc.nogc());
+            let script = parse_script(agent, source_text, realm, strict, None, gc.nogc()).unwrap();
+            script.analyze(agent)
+        })
+    }
+
+    #[test]
+    fn clean_script_uses_no_features() {
+        let analysis = analyze(
+            "function add(a, b) { return a + b; } const x = add(1, 2); outer: for (;;) { break; }",
+            false,
+        );
+        assert_eq!(analysis.features, ScriptFeatures::NONE);
+        assert!(analysis.features.is_empty());
+        assert!(analysis.free_identifiers.is_empty());
+    }
+
+    #[test]
+    fn each_feature_is_reported() {
+        let cases = [
+            ("eval('1')", ScriptFeatures::DIRECT_EVAL),
+            ("(0, eval)('1')", ScriptFeatures::INDIRECT_EVAL),
+            ("globalThis['eval']('1')", ScriptFeatures::INDIRECT_EVAL),
+            (
+                "new Function('return 1')",
+                ScriptFeatures::FUNCTION_CONSTRUCTOR,
+            ),
+            ("with ({}) {}", ScriptFeatures::WITH_STATEMENT),
+            ("import('./module.js')", ScriptFeatures::DYNAMIC_IMPORT),
+            ("/a+/.test('aa')", ScriptFeatures::REGULAR_EXPRESSION),
+            ("new RegExp('a+')", ScriptFeatures::REGULAR_EXPRESSION),
+            ("a: for (;;) { break a; }", ScriptFeatures::LABELED_BREAK),
+            (
+                "a: for (;;) { for (;;) { continue a; } }",
+                ScriptFeatures::LABELED_BREAK,
+            ),
+            // Features are found inside nested functions and classes.
+            (
+                "function f() { return () => { with ({}) {} }; }",
+                ScriptFeatures::WITH_STATEMENT,
+            ),
+            (
+                "class C { static { function f() { eval('1'); } } }",
+                ScriptFeatures::DIRECT_EVAL,
+            ),
+        ];
+        for (source, feature) in cases {
+            let analysis = analyze(source, false);
+            assert_eq!(analysis.features, feature, "{source}");
+        }
+    }
+
+    #[test]
+    fn top_level_await_is_only_reported_outside_functions() {
+        let analysis = analyze("await 1;", true);
+        assert_eq!(analysis.features, ScriptFeatures::TOP_LEVEL_AWAIT);
+        let analysis = analyze("for await (const x of []) {}", true);
+        assert_eq!(analysis.features, ScriptFeatures::TOP_LEVEL_AWAIT);
+        let analysis = analyze("async function f() { await 1; }", true);
+        assert_eq!(analysis.features, ScriptFeatures::NONE);
+    }
+
+    #[test]
+    fn free_identifiers_are_listed_once() {
+        let analysis = analyze(
+            "var local = 1;
+            function f(parameter) {
+                const inner = fetch(parameter + local);
+                return process.env[inner] ?? fetch('fallback');
+            }
+            process.exit(f(local));",
+            false,
+        );
+        assert_eq!(analysis.free_identifiers, ["fetch", "process"]);
+        assert!(analysis.features.is_empty());
+
+        let analysis = analyze("eval(x); new Function(y)", false);
+        assert_eq!(
+            analysis.features,
+            ScriptFeatures::DIRECT_EVAL | ScriptFeatures::FUNCTION_CONSTRUCTOR
+        );
+        assert!(
+            analysis
+                .features
+                .contains(ScriptFeatures::FUNCTION_CONSTRUCTOR)
+        );
+        assert_eq!(analysis.free_identifiers, ["Function", "eval", "x", "y"]);
+    }
+}
@@ -7,14 +7,16 @@
 //! that the eval call defines functions. Those functions will refer to the
 //! SourceCode for their function source text.
 
-use core::{fmt::Debug, ops::Index, ptr::NonNull};
+use core::{cell::OnceCell, fmt::Debug, ops::Index, ptr::NonNull};
 
 use oxc_allocator::Allocator;
-use oxc_ast::ast::Program;
+use oxc_ast::ast::{MetaProperty, Program};
+use oxc_ast_visit::Visit;
 use oxc_diagnostics::OxcDiagnostic;
 use oxc_parser::{Parser, ParserReturn};
-use oxc_semantic::{SemanticBuilder, SemanticBuilderReturn};
-use oxc_span::SourceType;
+use oxc_semantic::{SemanticBuilder, SemanticBuilderReturn, Stats};
+use oxc_span::{SourceType, Span};
+use oxc_syntax::module_record::ModuleRecord;
 
 use crate::{
     ecmascript::{
@@ -26,13 +28,14 @@ use crate::{
         rootable::{HeapRootData, HeapRootRef, Rootable},
     },
     heap::{
-        CompactionLists, CreateHeapData, Heap, HeapMarkAndSweep, WorkQueues, indexes::BaseIndex,
+        CompactionLists, CreateHeapData, Heap, HeapMarkAndSweep, HeapSweepWeakReference,
+        WorkQueues, indexes::BaseIndex,
     },
 };
 
 type SourceCodeIndex<'a> = BaseIndex<'a, SourceCodeHeapData<'static>>;
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct SourceCode<'a>(SourceCodeIndex<'a>);
 
 impl core::fmt::Debug for SourceCode<'_> {
@@ -45,6 +48,10 @@ impl<'a> SourceCode<'a> {
     /// Parses the given source string as JavaScript code and returns the
     /// parsed result and a SourceCode heap reference.
     ///
+    /// If `allow_new_target` is true, `new.target` is allowed to appear
+    /// outside of functions. This is used by direct eval calls made inside
+    /// functions.
+    ///
     /// ### Safety
     ///
     /// The caller must keep the SourceCode from being garbage collected until
@@ -53,8 +60,49 @@ impl<'a> SourceCode<'a> {
         agent: &mut Agent,
         source: String,
         source_type: SourceType,
+        allow_new_target: bool,
         gc: NoGcScope<'a, '_>,
     ) -> Result<(Program<'static>, Self), Vec<OxcDiagnostic>> {
+        // SAFETY: Passed through from the caller.
+        unsafe { Self::parse_module_source(agent, source, source_type, allow_new_target, gc) }
+            .map(|(program, _, source_code)| (program, source_code))
+    }
+
+    /// Parses the given source string as JavaScript code and returns the
+    /// parsed result, its ImportEntry and ExportEntry records, and a
+    /// SourceCode heap reference.
+    ///
+    /// ### Safety
+    ///
+    /// The caller must keep the SourceCode from being garbage collected until
+    /// they drop the parsed code and module record.
+    pub(crate) unsafe fn parse_module_source(
+        agent: &mut Agent,
+        source: String,
+        source_type: SourceType,
+        allow_new_target: bool,
+        gc: NoGcScope<'a, '_>,
+    ) -> Result<(Program<'static>, ModuleRecord<'static>, Self), Vec<OxcDiagnostic>> {
+        if let Some(max_source_length) = agent.options.max_source_length {
+            let source_length = source.len(agent);
+            if source_length > max_source_length {
+                return Err(vec![OxcDiagnostic::error(format!(
+                    "Source text is {source_length} bytes long, which exceeds the maximum of {max_source_length} bytes"
+                ))]);
+            }
+        }
+
+        // The parser only accepts UTF-8. Lone surrogates can only appear in
+        // string, template, and regular expression literals, where they are
+        // equivalent to their escape sequences, or in comments. Anywhere else
+        // their escape sequences are a syntax error just like they are.
+        let source = if source.as_wtf8(agent).as_str().is_none() {
+            let escaped = source.escape_lone_surrogates(agent).into_owned();
+            String::from_string(agent, escaped, gc)
+        } else {
+            source.bind(gc)
+        };
+
         // If the source code is not a heap string, pad it with whitespace and
         // allocate it on the heap. This makes it safe (for some definition of
         // "safe") for the any functions created referring to this source code to
@@ -95,13 +143,28 @@ impl<'a> SourceCode<'a> {
         let parser = Parser::new(unsafe { allocator.as_mut() }, source_text, source_type);
 
         let ParserReturn {
-            errors, program, ..
+            mut errors,
+            program,
+            module_record,
+            ..
         } = parser.parse();
 
+        if errors.is_empty() {
+            if let Some(max_ast_nodes) = agent.options.max_ast_nodes {
+                let ast_nodes = Stats::count(&program).nodes;
+                if ast_nodes > max_ast_nodes {
+                    errors.push(OxcDiagnostic::error(format!(
+                        "Source text has {ast_nodes} syntax nodes, which exceeds the maximum of {max_ast_nodes} nodes"
+                    )));
+                }
+            }
+        }
+
         if !errors.is_empty() {
             // Drop program before dropping allocator.
             #[allow(clippy::drop_non_drop)]
             drop(program);
+            drop(module_record);
             // SAFETY: No references to allocator exist anymore. It is safe to
             // drop it.
             drop(unsafe { Box::from_raw(allocator.as_mut()) });
@@ -109,14 +172,23 @@ impl<'a> SourceCode<'a> {
             return Err(errors);
         }
 
-        let SemanticBuilderReturn { errors, .. } = SemanticBuilder::new()
+        let SemanticBuilderReturn { mut errors, .. } = SemanticBuilder::new()
             .with_check_syntax_error(true)
             .build(&program);
+        if allow_new_target && !errors.is_empty() {
+            // The semantic checker has no notion of the code being evaluated
+            // inside a function, so we filter out the errors it reports at
+            // new.target expressions.
+            let mut new_target_spans = NewTargetSpans::default();
+            new_target_spans.visit_program(&program);
+            errors.retain(|error| !new_target_spans.is_new_target_error(error));
+        }
 
         if !errors.is_empty() {
             // Drop program before dropping allocator.
             #[allow(clippy::drop_non_drop)]
             drop(program);
+            drop(module_record);
             // SAFETY: No references to allocator exist anymore. It is safe to
             // drop it.
             drop(unsafe { Box::from_raw(allocator.as_mut()) });
@@ -126,16 +198,101 @@ impl<'a> SourceCode<'a> {
         // SAFETY: Caller guarantees that they will drop the Program before
         // SourceCode can be garbage collected.
         let program = unsafe { core::mem::transmute::<Program, Program<'static>>(program) };
-        let source_code = agent.heap.create(SourceCodeHeapData {
-            source: source.unbind(),
+        // SAFETY: As above.
+        let module_record =
+            unsafe { core::mem::transmute::<ModuleRecord, ModuleRecord<'static>>(module_record) };
+        let source_code = agent.heap.create(SourceCodeHeapData::new(
+            source.unbind(),
+            source_text.len() as u32,
             allocator,
-        });
+        ));
+
+        Ok((program, module_record, source_code))
+    }
+
+    /// Get the source text that this SourceCode was parsed from.
+    pub fn get_source_text(self, agent: &Agent) -> &str {
+        let data = &agent[self];
+        &agent[data.source].as_str()[..data.source_len as usize]
+    }
 
-        Ok((program, source_code))
+    /// Get the source text in the given span, eg. the span of a
+    /// [`SourceLocation`](crate::ecmascript::execution::agent::SourceLocation).
+    ///
+    /// The span is clamped to the source text, and its ends are moved back to
+    /// the nearest character boundaries.
+    pub fn slice(self, agent: &Agent, span: Span) -> &str {
+        let source_text = self.get_source_text(agent);
+        let end = floor_char_boundary(source_text, span.end as usize);
+        let start = floor_char_boundary(source_text, span.start as usize).min(end);
+        &source_text[start..end]
+    }
+
+    /// Get the zero-based line and column of the given byte offset into the
+    /// source text.
+    ///
+    /// Lines are separated by the ECMAScript line terminators: LF, CR, CRLF,
+    /// U+2028 LINE SEPARATOR, and U+2029 PARAGRAPH SEPARATOR. The column is
+    /// counted in UTF-16 code units, as debuggers expect. Offsets past the end
+    /// of the source text are clamped to it, and offsets inside a character
+    /// are moved back to its start.
+    ///
+    /// The line table is built on the first call and reused afterwards.
+    pub fn line_column_at(self, agent: &Agent, offset: u32) -> (u32, u32) {
+        let source_text = self.get_source_text(agent);
+        let offset = floor_char_boundary(source_text, offset as usize);
+        let line_starts = agent[self].line_starts(source_text);
+        // The first line starts at offset 0, so there is always a line start
+        // at or before the offset.
+        let line = line_starts.partition_point(|&start| start as usize <= offset) - 1;
+        let line_start = line_starts[line] as usize;
+        let column = source_text[line_start..offset].encode_utf16().count();
+        (line as u32, column as u32)
     }
 
-    pub(crate) fn get_source_text(self, agent: &Agent) -> &str {
-        agent[agent[self].source].as_str()
+    /// Get the text of the given zero-based line without its line
+    /// terminator, or None if the source text has fewer lines.
+    pub fn line(self, agent: &Agent, line: u32) -> Option<&str> {
+        let source_text = self.get_source_text(agent);
+        let line_starts = agent[self].line_starts(source_text);
+        let start = *line_starts.get(line as usize)? as usize;
+        let end = line_starts
+            .get(line as usize + 1)
+            .map_or(source_text.len(), |&end| end as usize);
+        let line = &source_text[start..end];
+        Some(
+            line.strip_suffix("\r\n")
+                .or_else(|| line.strip_suffix(['\n', '\r', '\u{2028}', '\u{2029}']))
+                .unwrap_or(line),
+        )
+    }
+
+    /// Get the byte offsets of the given zero-based line and UTF-16 column
+    /// and of the end of that line, or None if the source text has fewer
+    /// lines. A column past the end of the line is clamped to it.
+    pub(crate) fn line_offsets_from(
+        self,
+        agent: &Agent,
+        line: u32,
+        column: u32,
+    ) -> Option<(u32, u32)> {
+        let line_start = agent[self]
+            .line_starts(self.get_source_text(agent))
+            .get(line as usize)
+            .copied()?;
+        let line_text = self.line(agent, line)?;
+        let mut units = 0;
+        let offset = line_text
+            .char_indices()
+            .find(|(_, c)| {
+                units += c.len_utf16() as u32;
+                units > column
+            })
+            .map_or(line_text.len(), |(index, _)| index);
+        Some((
+            line_start + offset as u32,
+            line_start + line_text.len() as u32,
+        ))
     }
 
     pub(crate) fn get_index(self) -> usize {
@@ -143,6 +300,34 @@ impl<'a> SourceCode<'a> {
     }
 }
 
+/// Spans of the `new.target` expressions in a Program.
+#[derive(Default)]
+struct NewTargetSpans(Vec<Span>);
+
+impl NewTargetSpans {
+    /// Returns true if the error is reported at a `new.target` expression
+    /// and nowhere else.
+    fn is_new_target_error(&self, error: &OxcDiagnostic) -> bool {
+        let Some(labels) = error.labels.as_deref() else {
+            return false;
+        };
+        !labels.is_empty()
+            && labels.iter().all(|label| {
+                let start = label.offset() as u32;
+                let span = Span::new(start, start + label.len() as u32);
+                self.0.contains(&span)
+            })
+    }
+}
+
+impl<'a> Visit<'a> for NewTargetSpans {
+    fn visit_meta_property(&mut self, it: &MetaProperty<'a>) {
+        if it.meta.name == "new" && it.property.name == "target" {
+            self.0.push(it.span);
+        }
+    }
+}
+
 pub struct SourceCodeHeapData<'a> {
     /// The source JavaScript string data the eval was called with. The string
     /// is known and required to be a HeapString because functions created
@@ -150,8 +335,73 @@ pub struct SourceCodeHeapData<'a> {
     /// string was small-string optimised and on the stack, then those
     /// references would necessarily and definitely be invalid.
     source: HeapString<'a>,
+    /// Length of the source text in bytes. The source string may be longer,
+    /// as small strings are padded to force a heap allocation.
+    source_len: u32,
     /// The arena that contains the parsed data of the eval source.
     allocator: NonNull<Allocator>,
+    /// Byte offsets of the starts of the lines of the source text, built on
+    /// first use.
+    line_starts: OnceCell<Box<[u32]>>,
+    /// Number of times that the line table has been built.
+    #[cfg(test)]
+    line_table_builds: core::cell::Cell<usize>,
+}
+
+impl<'a> SourceCodeHeapData<'a> {
+    pub(crate) fn new(
+        source: HeapString<'a>,
+        source_len: u32,
+        allocator: NonNull<Allocator>,
+    ) -> Self {
+        Self {
+            source,
+            source_len,
+            allocator,
+            line_starts: OnceCell::new(),
+            #[cfg(test)]
+            line_table_builds: Default::default(),
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn line_table_builds(&self) -> usize {
+        self.line_table_builds.get()
+    }
+
+    fn line_starts(&self, source_text: &str) -> &[u32] {
+        self.line_starts.get_or_init(|| {
+            #[cfg(test)]
+            self.line_table_builds.set(self.line_table_builds.get() + 1);
+            let bytes = source_text.as_bytes();
+            let mut line_starts = vec![0];
+            let mut index = 0;
+            while index < bytes.len() {
+                let terminator_len = match bytes[index..] {
+                    [b'\r', b'\n', ..] => 2,
+                    [b'\n' | b'\r', ..] => 1,
+                    // U+2028 LINE SEPARATOR and U+2029 PARAGRAPH SEPARATOR.
+                    [0xE2, 0x80, 0xA8 | 0xA9, ..] => 3,
+                    _ => {
+                        index += 1;
+                        continue;
+                    }
+                };
+                index += terminator_len;
+                line_starts.push(index as u32);
+            }
+            line_starts.into_boxed_slice()
+        })
+    }
+}
+
+/// Returns the largest character boundary in `text` that is at most `index`.
+fn floor_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
 }
 
 unsafe impl Send for SourceCodeHeapData<'_> {}
@@ -251,7 +501,11 @@ impl HeapMarkAndSweep for SourceCodeHeapData<'static> {
     fn mark_values(&self, queues: &mut WorkQueues) {
         let Self {
             source,
+            source_len: _,
             allocator: _,
+            line_starts: _,
+            #[cfg(test)]
+                line_table_builds: _,
         } = self;
         source.mark_values(queues);
     }
@@ -259,7 +513,11 @@ impl HeapMarkAndSweep for SourceCodeHeapData<'static> {
     fn sweep_values(&mut self, compactions: &CompactionLists) {
         let Self {
             source,
+            source_len: _,
             allocator: _,
+            line_starts: _,
+            #[cfg(test)]
+                line_table_builds: _,
         } = self;
         source.sweep_values(compactions);
     }
@@ -274,3 +532,211 @@ impl HeapMarkAndSweep for SourceCode<'static> {
         compactions.source_codes.shift_index(&mut self.0);
     }
 }
+
+impl HeapSweepWeakReference for SourceCode<'static> {
+    fn sweep_weak_reference(self, compactions: &CompactionLists) -> Option<Self> {
+        compactions.source_codes.shift_weak_index(self.0).map(Self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use oxc_span::Span;
+
+    use super::SourceCode;
+    use crate::{
+        ecmascript::{
+            execution::{Agent, DefaultHostHooks, agent::Options, initialize_default_realm},
+            scripts_and_modules::script::{Script, parse_script, test::evaluate},
+            types::{String, Value},
+        },
+        engine::context::{Bindable, NoGcScope},
+    };
+
+    /// Parses the source as a script, returning the message of the first error.
+    fn parse_error(
+        agent: &mut Agent,
+        source: &'static str,
+        gc: NoGcScope,
+    ) -> Option<std::string::String> {
+        let realm = agent.current_realm(gc);
+        let source_text = String::from_static_str(agent, source, gc);
+        parse_script(agent, source_text, realm, false, None, gc)
+            .err()
+            .map(|errors| errors[0].message.to_string())
+    }
+
+    fn parse<'gc>(agent: &mut Agent, source: &str, gc: NoGcScope<'gc, '_>) -> Script<'gc> {
+        let source_text = String::from_str(agent, source, gc);
+        let realm = agent.current_realm(gc);
+        parse_script(agent, source_text, realm, false, None, gc).unwrap()
+    }
+
+    /// Byte offset of the first occurrence of `needle` in the source text.
+    fn offset_of(agent: &Agent, source_code: SourceCode, needle: &str) -> u32 {
+        source_code.get_source_text(agent).find(needle).unwrap() as u32
+    }
+
+    #[test]
+    fn source_length_is_limited() {
+        let mut agent = Agent::new(
+            Options {
+                max_source_length: Some(100),
+                ..Default::default()
+            },
+            &DefaultHostHooks,
+        );
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        assert_eq!(parse_error(agent, "1 + 1", gc.nogc()), None);
+        let message = parse_error(
+            agent,
+            "const generated = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22];",
+            gc.nogc(),
+        )
+        .unwrap();
+        assert_eq!(
+            message,
+            "Source text is 101 bytes long, which exceeds the maximum of 100 bytes"
+        );
+
+        // The limit applies to eval and dynamic functions as well.
+        for source in [
+            "try { eval('1' + ' +1'.repeat(20)) === 21 } catch { false }",
+            "try { eval('1' + ' +1'.repeat(50)) } catch (e) { e instanceof SyntaxError }",
+            "try { Function('1' + ' +1'.repeat(50)) } catch (e) { e instanceof SyntaxError }",
+        ] {
+            let result = evaluate(agent, source, gc.reborrow()).unbind();
+            assert_eq!(result, Value::Boolean(true), "{source}");
+        }
+    }
+
+    #[test]
+    fn ast_node_count_is_limited() {
+        let mut agent = Agent::new(
+            Options {
+                max_ast_nodes: Some(32),
+                ..Default::default()
+            },
+            &DefaultHostHooks,
+        );
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        assert_eq!(parse_error(agent, "1 + 1", gc.nogc()), None);
+        let message = parse_error(agent, "[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31];", gc.nogc()).unwrap();
+        assert!(
+            message.starts_with("Source text has ")
+                && message.ends_with(" syntax nodes, which exceeds the maximum of 32 nodes"),
+            "{message}"
+        );
+        // Syntax errors are still reported as such.
+        let message = parse_error(agent, "[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31;", gc.nogc()).unwrap();
+        assert!(!message.contains("exceeds the maximum"), "{message}");
+    }
+
+    #[test]
+    fn line_column_mapping_with_mixed_line_endings() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let gc = gc.nogc();
+        let source = "var a = 1;\r\nvar b = 2;\rvar c = 3;\nvar d = 4;\u{2028}var e = 5;\u{2029}var f = 6;\n\nvar g = '\u{1F600}é', h = 7;";
+        let script = parse(agent, source, gc);
+        assert_eq!(agent.script_source(script), Some(source));
+        let source_code = script.source_code(agent);
+
+        let position = |needle: &str| {
+            let offset = offset_of(agent, source_code, needle);
+            source_code.line_column_at(agent, offset)
+        };
+        assert_eq!(position("var a"), (0, 0));
+        assert_eq!(position("1;"), (0, 8));
+        assert_eq!(position("var b"), (1, 0));
+        assert_eq!(position("var c"), (2, 0));
+        assert_eq!(position("var d"), (3, 0));
+        assert_eq!(position("var e"), (4, 0));
+        assert_eq!(position("var f"), (5, 0));
+        assert_eq!(position("var g"), (7, 0));
+        // The emoji is two UTF-16 code units, and é one, though they take
+        // four and two bytes.
+        assert_eq!(position("\u{1F600}"), (7, 9));
+        assert_eq!(position("h ="), (7, 15));
+
+        // The CRLF line terminator is a single line break, and its LF is on
+        // the line that it ends.
+        let crlf = offset_of(agent, source_code, "\r\n");
+        assert_eq!(source_code.line_column_at(agent, crlf + 1), (0, 11));
+        // Offsets inside a character are moved back to its start, and
+        // offsets past the end are clamped.
+        let emoji = offset_of(agent, source_code, "\u{1F600}");
+        assert_eq!(source_code.line_column_at(agent, emoji + 2), (7, 9));
+        assert_eq!(source_code.line_column_at(agent, u32::MAX), (7, 21));
+
+        let lines = (0..9)
+            .map(|line| source_code.line(agent, line))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            [
+                Some("var a = 1;"),
+                Some("var b = 2;"),
+                Some("var c = 3;"),
+                Some("var d = 4;"),
+                Some("var e = 5;"),
+                Some("var f = 6;"),
+                Some(""),
+                Some("var g = '\u{1F600}é', h = 7;"),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn snippet_extraction() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let gc = gc.nogc();
+        let source = "const greeting = 'héllo';\nconsole.log(greeting);";
+        let script = parse(agent, source, gc);
+        let source_code = script.source_code(agent);
+        let start = offset_of(agent, source_code, "'h");
+        assert_eq!(
+            source_code.slice(agent, Span::new(start, start + 8)),
+            "'héllo'"
+        );
+        let start = offset_of(agent, source_code, "console");
+        assert_eq!(
+            source_code.slice(agent, Span::new(start, start + 11)),
+            "console.log"
+        );
+        // Spans are clamped to the source text and character boundaries.
+        let start = offset_of(agent, source_code, "é");
+        assert_eq!(
+            source_code.slice(agent, Span::new(start + 1, start + 2)),
+            "é"
+        );
+        assert_eq!(
+            source_code.slice(agent, Span::new(start + 1, start + 1)),
+            ""
+        );
+        assert_eq!(
+            source_code.slice(agent, Span::new(start + 1, u32::MAX)),
+            &source[start as usize..]
+        );
+
+        // Short source texts are padded internally, but that is not visible.
+        let script = parse(agent, "x;", gc);
+        assert_eq!(agent.script_source(script), Some("x;"));
+        let source_code = script.source_code(agent);
+        assert_eq!(source_code.line_column_at(agent, u32::MAX), (0, 2));
+    }
+}
@@ -11,7 +11,7 @@ use crate::{
         builtins::{
             ArgumentsList, BuiltinConstructorFunction, ordinary::ordinary_create_from_constructor,
         },
-        execution::{Agent, JsResult, ProtoIntrinsics, agent::ExceptionType},
+        execution::{Agent, JsResult, ProtoIntrinsics},
         types::{Function, InternalMethods, Object},
     },
     engine::{
@@ -79,11 +79,7 @@ pub(crate) fn derived_class_default_constructor<'a>(
     let func = unwrap_try(f.try_get_prototype_of(agent, gc.nogc()));
     // 3. If IsConstructor(func) is false, throw a TypeError exception.
     let Some(func) = func.and_then(|func| is_constructor(agent, func)) else {
-        return Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "Expected callable function",
-            gc.into_nogc(),
-        ));
+        return agent.type_error("Expected callable function", gc.into_nogc());
     };
     let f = f.scope(agent, gc.nogc());
     // 4. Let result be ? Construct(func, args, NewTarget).
@@ -8,9 +8,9 @@ mod spec;
 pub(crate) use language::*;
 pub use language::{
     BigInt, Function, HeapNumber, HeapString, InternalMethods, InternalSlots, IntoFunction,
-    IntoNumeric, IntoObject, IntoPrimitive, IntoValue, Number, Numeric, Object, OrdinaryObject,
-    Primitive, PropertyKey, PropertyKeySet, String, Symbol, Value, bigint,
+    IntoNumeric, IntoObject, IntoPrimitive, IntoValue, JsType, Number, Numeric, Object,
+    OrdinaryObject, Primitive, PropertyKey, PropertyKeySet, String, Symbol, Value, bigint,
 };
 pub use spec::PrivateName;
-pub use spec::PropertyDescriptor;
 pub(crate) use spec::*;
+pub use spec::{PropertyDescriptor, PropertyDescriptorTemplate};
@@ -31,24 +31,22 @@ pub use into_primitive::IntoPrimitive;
 pub use into_value::IntoValue;
 pub use number::{HeapNumber, Number, NumberHeapData};
 pub use numeric::Numeric;
-pub(crate) use object::ScopedPropertyKey;
 pub use object::{
     InternalMethods, InternalSlots, IntoObject, Object, ObjectHeapData, OrdinaryObject,
     PropertyKey, PropertyKeySet,
 };
+pub(crate) use object::{ScopedPropertyKey, array_index, get_indexed_element};
 pub(crate) use primitive::HeapPrimitive;
 pub use primitive::Primitive;
+pub(crate) use string::HeapStringBuilder;
 pub use string::{BUILTIN_STRING_MEMORY, BUILTIN_STRINGS_LIST, HeapString, String, StringHeapData};
 pub use symbol::{Symbol, SymbolHeapData};
 #[cfg(feature = "date")]
 pub(crate) use value::DATE_DISCRIMINANT;
 #[cfg(feature = "proposal-float16array")]
 pub(crate) use value::FLOAT_16_ARRAY_DISCRIMINANT;
-#[cfg(feature = "regexp")]
-pub(crate) use value::REGEXP_DISCRIMINANT;
 #[cfg(feature = "shared-array-buffer")]
 pub(crate) use value::SHARED_ARRAY_BUFFER_DISCRIMINANT;
-pub use value::Value;
 pub(crate) use value::{
     ARGUMENTS_DISCRIMINANT, ARRAY_DISCRIMINANT, ARRAY_ITERATOR_DISCRIMINANT,
     ASYNC_FROM_SYNC_ITERATOR_DISCRIMINANT, ASYNC_GENERATOR_DISCRIMINANT, BIGINT_DISCRIMINANT,
@@ -71,6 +69,9 @@ pub(crate) use value::{
     UINT_8_ARRAY_DISCRIMINANT, UINT_8_CLAMPED_ARRAY_DISCRIMINANT, UINT_16_ARRAY_DISCRIMINANT,
     UINT_32_ARRAY_DISCRIMINANT,
 };
+pub use value::{JsType, Value};
+#[cfg(feature = "regexp")]
+pub(crate) use value::{REGEXP_DISCRIMINANT, REGEXP_STRING_ITERATOR_DISCRIMINANT};
 #[cfg(feature = "set")]
 pub(crate) use value::{SET_DISCRIMINANT, SET_ITERATOR_DISCRIMINANT};
 #[cfg(feature = "weak-refs")]
@@ -12,7 +12,7 @@ use super::{
 };
 use crate::{
     SmallInteger, bigint_bitwise_op,
-    ecmascript::execution::{Agent, JsResult, agent::ExceptionType},
+    ecmascript::execution::{Agent, JsResult},
     engine::{
         context::{Bindable, NoGcScope},
         rootable::{HeapRootData, HeapRootRef, Rootable},
@@ -276,26 +276,14 @@ impl<'a> BigInt<'a> {
             BigInt::BigInt(x) => agent[x].data < 0.into(),
             _ => false,
         } {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::RangeError,
-                "exponent must be positive",
-                gc,
-            ));
+            return agent.range_error("exponent must be positive", gc);
         }
 
         let BigInt::SmallBigInt(exponent) = exponent else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::RangeError,
-                "exponent over bounds",
-                gc,
-            ));
+            return agent.range_error("exponent over bounds", gc);
         };
         let Ok(exponent) = u32::try_from(exponent.into_i64()) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::RangeError,
-                "exponent over bounds",
-                gc,
-            ));
+            return agent.range_error("exponent over bounds", gc);
         };
 
         if exponent == 1 {
@@ -380,11 +368,7 @@ impl<'a> BigInt<'a> {
             (BigInt::SmallBigInt(x), BigInt::SmallBigInt(y)) => {
                 let y = y.into_i64();
                 match y {
-                    0 => Err(agent.throw_exception_with_static_message(
-                        ExceptionType::RangeError,
-                        "Division by zero",
-                        gc,
-                    )),
+                    0 => agent.range_error("Division by zero", gc),
                     1 => Ok(BigInt::SmallBigInt(x)),
                     y => Ok(BigInt::SmallBigInt(
                         SmallBigInt::try_from(x.into_i64() / y).unwrap(),
@@ -400,11 +384,7 @@ impl<'a> BigInt<'a> {
             (BigInt::BigInt(x), BigInt::SmallBigInt(y)) => {
                 let y = y.into_i64();
                 match y {
-                    0 => Err(agent.throw_exception_with_static_message(
-                        ExceptionType::RangeError,
-                        "Division by zero",
-                        gc,
-                    )),
+                    0 => agent.range_error("Division by zero", gc),
                     1 => Ok(BigInt::BigInt(x)),
                     y => Ok(Self::from_num_bigint(agent, &agent[x].data / y)),
                 }
@@ -432,11 +412,7 @@ impl<'a> BigInt<'a> {
         match (n, d) {
             (BigInt::SmallBigInt(n), BigInt::SmallBigInt(d)) => {
                 if d == SmallBigInt::zero() {
-                    return Err(agent.throw_exception_with_static_message(
-                        ExceptionType::RangeError,
-                        "Division by zero",
-                        gc,
-                    ));
+                    return agent.range_error("Division by zero", gc);
                 }
                 let (n, d) = (n.into_i64(), d.into_i64());
                 let result = n % d;
@@ -448,11 +424,7 @@ impl<'a> BigInt<'a> {
             }
             (BigInt::BigInt(n), BigInt::SmallBigInt(d)) => {
                 if d == SmallBigInt::zero() {
-                    return Err(agent.throw_exception_with_static_message(
-                        ExceptionType::RangeError,
-                        "Division by zero",
-                        gc,
-                    ));
+                    return agent.range_error("Division by zero", gc);
                 }
                 Ok(Self::SmallBigInt(
                     SmallBigInt::try_from(
@@ -553,11 +525,7 @@ impl<'a> BigInt<'a> {
         } {
             Ok(r)
         } else {
-            Err(agent.throw_exception_with_static_message(
-                ExceptionType::RangeError,
-                "BigInt is too large to allocate",
-                gc,
-            ))
+            agent.range_error("BigInt is too large to allocate", gc)
         }
     }
 
@@ -591,11 +559,7 @@ impl<'a> BigInt<'a> {
         } {
             Ok(r)
         } else {
-            Err(agent.throw_exception_with_static_message(
-                ExceptionType::RangeError,
-                "BigInt is too large to allocate",
-                gc,
-            ))
+            agent.range_error("BigInt is too large to allocate", gc)
         }
     }
 
@@ -609,11 +573,7 @@ impl<'a> BigInt<'a> {
         _y: Self,
         gc: NoGcScope<'a, '_>,
     ) -> JsResult<'a, Self> {
-        Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
-            "BigInts have no unsigned right shift, use >> instead",
-            gc,
-        ))
+        agent.type_error("BigInts have no unsigned right shift, use >> instead", gc)
     }
 
     /// ### [6.1.6.2.12 BigInt::lessThan ( x, y )](https://tc39.es/ecma262/#sec-numeric-types-bigint-lessThan)
@@ -851,6 +811,7 @@ impl Index<HeapBigInt<'_>> for Agent {
 
 impl IndexMut<HeapBigInt<'_>> for Agent {
     fn index_mut(&mut self, index: HeapBigInt<'_>) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.bigints[index]
     }
 }
@@ -20,7 +20,7 @@ use crate::{
         builtins::{
             bound_function::BoundFunction, control_abstraction_objects::promise_objects::promise_abstract_operations::promise_resolving_functions::BuiltinPromiseResolvingFunction, ArgumentsList, BuiltinConstructorFunction, BuiltinFunction, ECMAScriptFunction
         },
-        execution::{Agent, JsResult, ProtoIntrinsics},
+        execution::{Agent, AgentOperation, JsResult, ProtoIntrinsics, ReentrancyError},
         types::PropertyDescriptor,
     }, engine::rootable::{HeapRootData, HeapRootRef, Rootable}, heap::{CompactionLists, HeapMarkAndSweep, WorkQueues}
 };
@@ -618,6 +618,12 @@ impl HeapMarkAndSweep for Function<'static> {
 }
 
 impl Function<'_> {
+    /// Call the function from host code.
+    ///
+    /// ## Panics
+    ///
+    /// If the Agent is poisoned or functions may not be called in its current
+    /// [`ExecutionState`](crate::ecmascript::execution::ExecutionState).
     pub fn call<'gc>(
         self,
         agent: &mut Agent,
@@ -625,12 +631,27 @@ impl Function<'_> {
         args: &mut [Value],
         gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Value<'gc>> {
-        self.internal_call(
+        self.try_call(agent, this_argument, args, gc)
+            .unwrap_or_else(|err| panic!("Cannot call function: {err}"))
+    }
+
+    /// Call the function from host code, returning an error if the Agent is
+    /// poisoned or functions may not be called in its current
+    /// [`ExecutionState`](crate::ecmascript::execution::ExecutionState).
+    pub fn try_call<'gc>(
+        self,
+        agent: &mut Agent,
+        this_argument: Value,
+        args: &mut [Value],
+        gc: GcScope<'gc, '_>,
+    ) -> Result<JsResult<'gc, Value<'gc>>, ReentrancyError> {
+        let _guard = agent.enter_operation(AgentOperation::Call)?;
+        Ok(self.internal_call(
             agent,
             this_argument,
             ArgumentsList::from_mut_slice(args),
             gc,
-        )
+        ))
     }
 }
 
@@ -9,7 +9,7 @@ use crate::{
         builtins::{Behaviour, ECMAScriptFunctionObjectHeapData},
         execution::{Environment, PrivateEnvironment, Realm},
         scripts_and_modules::source_code::SourceCode,
-        types::{OrdinaryObject, String, Value},
+        types::{Object, OrdinaryObject, String, Value},
     },
     engine::Executable,
     heap::element_array::ElementsVector,
@@ -67,6 +67,12 @@ pub struct BuiltinConstructorHeapData<'a> {
     pub(crate) is_derived: bool,
     /// Stores the compiled bytecode of class field initializers.
     pub(crate) compiled_initializer_bytecode: Option<Executable<'a>>,
+    /// ### \[\[HomeObject]]
+    ///
+    /// The class field initializers are methods of the class prototype, and
+    /// they run with the constructor as their function object. This is
+    /// required for `super` property access in class field initializers.
+    pub(crate) home_object: Object<'a>,
     /// ### \[\[Environment]]
     ///
     /// This is required for class field initializers.
@@ -85,7 +91,7 @@ pub struct BuiltinConstructorHeapData<'a> {
     pub(crate) source_code: SourceCode<'a>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ECMAScriptFunctionHeapData<'a> {
     pub(crate) object_index: Option<OrdinaryObject<'a>>,
     pub(crate) length: u8,
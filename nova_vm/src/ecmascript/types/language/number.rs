@@ -31,7 +31,7 @@ use crate::{
 
 pub use data::NumberHeapData;
 use num_traits::{PrimInt, Zero};
-use radix::make_float_string_ascii_lowercase;
+use radix::f64_to_radix_string;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(transparent)]
@@ -569,9 +569,9 @@ impl<'a> Number<'a> {
 
     pub fn is_odd_integer(self, agent: &mut Agent) -> bool {
         match self {
-            Number::Number(n) => agent[n.unbind()] % 2.0 == 1.0,
-            Number::Integer(n) => Into::<i64>::into(n) % 2 == 1,
-            Number::SmallF64(n) => n.into_f64() % 2.0 == 1.0,
+            Number::Number(n) => agent[n.unbind()].rem_euclid(2.0) == 1.0,
+            Number::Integer(n) => Into::<i64>::into(n) % 2 != 0,
+            Number::SmallF64(n) => n.into_f64().rem_euclid(2.0) == 1.0,
         }
     }
 
@@ -599,7 +599,7 @@ impl<'a> Number<'a> {
     }
 
     pub fn greater_than(agent: &mut Agent, x: Self, y: Self) -> Option<bool> {
-        Number::less_than(agent, y, x).map(|x| !x)
+        Number::less_than(agent, y, x)
     }
 
     /// ### [6.1.6.1.1 Number::unaryMinus ( x )](https://tc39.es/ecma262/#sec-numeric-types-number-unaryMinus)
@@ -750,7 +750,7 @@ impl<'a> Number<'a> {
 
             // a. If abs(ℝ(base)) > 1, return +0𝔽.
             return if base > 1.0 {
-                Number::pos_inf()
+                Number::pos_zero()
             }
             // b. If abs(ℝ(base)) = 1, return NaN.
             else if base == 1.0 {
@@ -767,7 +767,7 @@ impl<'a> Number<'a> {
 
         // 12. If base < -0𝔽 and exponent is not an integral Number, return NaN.
         if Number::less_than(agent, base, Number::neg_zero()).unwrap_or(false)
-            && !exponent.is_odd_integer(agent)
+            && !exponent.is_integer(agent)
         {
             return Number::nan();
         }
@@ -1322,39 +1322,19 @@ impl<'a> Number<'a> {
         radix: u32,
         gc: NoGcScope<'gc, '_>,
     ) -> String<'gc> {
-        String::from_string(
-            agent,
-            with_radix!(
+        let string = match x {
+            Number::Integer(x) => with_radix!(
                 radix,
-                match x {
-                    Number::Integer(x) => {
-                        lexical::to_string_with_options::<_, RADIX>(
-                            x.into_i64(),
-                            &lexical::write_integer_options::STANDARD,
-                        )
-                        .to_ascii_lowercase()
-                    }
-                    Number::Number(x) => {
-                        let x = agent[x];
-                        let mut string = lexical::to_string_with_options::<_, RADIX>(
-                            x,
-                            &lexical::write_float_options::JAVASCRIPT_LITERAL,
-                        );
-                        make_float_string_ascii_lowercase(&mut string);
-                        string
-                    }
-                    Number::SmallF64(x) => {
-                        let mut string = lexical::to_string_with_options::<_, RADIX>(
-                            x.into_f64(),
-                            &lexical::write_float_options::JAVASCRIPT_LITERAL,
-                        );
-                        make_float_string_ascii_lowercase(&mut string);
-                        string
-                    }
-                }
+                lexical::to_string_with_options::<_, RADIX>(
+                    x.into_i64(),
+                    &lexical::write_integer_options::STANDARD,
+                )
+                .to_ascii_lowercase()
             ),
-            gc,
-        )
+            Number::Number(x) => f64_to_radix_string(agent[x], radix),
+            Number::SmallF64(x) => f64_to_radix_string(x.into_f64(), radix),
+        };
+        String::from_string(agent, string, gc)
     }
 
     // ### [6.1.6.1.20 Number::toString ( x, radix )](https://tc39.es/ecma262/#sec-numeric-types-number-tostring)
@@ -1366,12 +1346,13 @@ impl<'a> Number<'a> {
         match x {
             Number::Number(x) => {
                 let mut buffer = ryu_js::Buffer::new();
-                String::from_string(agent, buffer.format(agent[x]).to_string(), gc)
+                let value = agent[x];
+                String::from_str(agent, buffer.format(value), gc)
             }
             Number::Integer(x) => String::from_string(agent, x.into_i64().to_string(), gc),
             Number::SmallF64(x) => {
                 let mut buffer = ryu_js::Buffer::new();
-                String::from_string(agent, buffer.format(x.into_f64()).to_string(), gc)
+                String::from_str(agent, buffer.format(x.into_f64()), gc)
             }
         }
     }
@@ -1443,6 +1424,7 @@ impl Index<HeapNumber<'_>> for Agent {
 
 impl IndexMut<HeapNumber<'_>> for Agent {
     fn index_mut(&mut self, index: HeapNumber<'_>) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.numbers[index]
     }
 }
@@ -2,7 +2,10 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use crate::heap::{CompactionLists, HeapMarkAndSweep, WorkQueues};
+use crate::heap::{
+    CompactionLists, HeapMarkAndSweep, WorkQueues,
+    snapshot::{HeapSnapshot, SnapshotError, SnapshotReader, SnapshotWriter},
+};
 
 #[derive(Debug, Clone, Copy)]
 pub struct NumberHeapData {
@@ -31,3 +34,16 @@ impl HeapMarkAndSweep for NumberHeapData {
         let Self { data: _ } = self;
     }
 }
+
+impl HeapSnapshot for NumberHeapData {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        let Self { data } = self;
+        data.write_snapshot(writer);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        Ok(Self {
+            data: HeapSnapshot::read_snapshot(reader)?,
+        })
+    }
+}
@@ -153,11 +153,101 @@ macro_rules! with_radix {
     };
 }
 
-pub(crate) fn make_float_string_ascii_lowercase(str: &mut str) {
-    match &*str {
-        "NaN" | "Infinity" | "-Infinity" => (),
-        _ => {
-            str.make_ascii_lowercase();
+/// Formats a float in the given radix, for radixes other than 10.
+///
+/// The fractional digits are only generated up to the precision of the float,
+/// so that the result is the shortest one that reads back to the same float,
+/// and large integers have their digits below the precision of the float
+/// filled with zeros. This matches the output of other engines.
+pub(crate) fn f64_to_radix_string(value: f64, radix: u32) -> std::string::String {
+    const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    debug_assert!((2..=36).contains(&radix));
+    if value.is_nan() {
+        return "NaN".into();
+    }
+    if value == 0.0 {
+        return "0".into();
+    }
+    if value.is_infinite() {
+        return if value > 0.0 { "Infinity" } else { "-Infinity" }.into();
+    }
+    let negative = value < 0.0;
+    let value = value.abs();
+    let radix_f64 = f64::from(radix);
+
+    let mut integer = value.floor();
+    let mut fraction = value - integer;
+    // Half the distance to the next float: fractional digits smaller than this
+    // are not represented in the value.
+    let next_float = f64::from_bits(value.to_bits() + 1);
+    let mut delta = (0.5 * (next_float - value)).max(f64::from_bits(1));
+    let mut fraction_digits = Vec::new();
+    if fraction >= delta {
+        loop {
+            fraction *= radix_f64;
+            delta *= radix_f64;
+            let digit = fraction as u8;
+            fraction_digits.push(digit);
+            fraction -= f64::from(digit);
+            // Round half to even.
+            if (fraction > 0.5 || (fraction == 0.5 && digit & 1 == 1)) && fraction + delta > 1.0 {
+                // Carry over into the digits written so far, possibly all the
+                // way into the integer part.
+                loop {
+                    match fraction_digits.pop() {
+                        Some(digit) if u32::from(digit) + 1 < radix => {
+                            fraction_digits.push(digit + 1);
+                            break;
+                        }
+                        Some(_) => {}
+                        None => {
+                            integer += 1.0;
+                            break;
+                        }
+                    }
+                }
+                break;
+            }
+            if fraction < delta {
+                break;
+            }
+        }
+    }
+
+    let mut integer_digits = Vec::new();
+    // Digits below the precision of the value are zero.
+    while integer / radix_f64 >= 2f64.powi(f64::MANTISSA_DIGITS as i32) {
+        integer /= radix_f64;
+        integer_digits.push(0);
+    }
+    loop {
+        let remainder = integer % radix_f64;
+        integer_digits.push(remainder as u8);
+        integer = (integer - remainder) / radix_f64;
+        if integer <= 0.0 {
+            break;
         }
     }
+
+    let mut result = std::string::String::with_capacity(
+        usize::from(negative) + integer_digits.len() + 1 + fraction_digits.len(),
+    );
+    if negative {
+        result.push('-');
+    }
+    result.extend(
+        integer_digits
+            .iter()
+            .rev()
+            .map(|&digit| char::from(DIGITS[usize::from(digit)])),
+    );
+    if !fraction_digits.is_empty() {
+        result.push('.');
+        result.extend(
+            fraction_digits
+                .iter()
+                .map(|&digit| char::from(DIGITS[usize::from(digit)])),
+        );
+    }
+    result
 }
@@ -17,8 +17,6 @@ use core::hash::Hash;
 use super::value::DATE_DISCRIMINANT;
 #[cfg(feature = "proposal-float16array")]
 use super::value::FLOAT_16_ARRAY_DISCRIMINANT;
-#[cfg(feature = "regexp")]
-use super::value::REGEXP_DISCRIMINANT;
 #[cfg(feature = "shared-array-buffer")]
 use super::value::SHARED_ARRAY_BUFFER_DISCRIMINANT;
 #[cfg(feature = "array-buffer")]
@@ -29,6 +27,8 @@ use super::value::{
     UINT_8_ARRAY_DISCRIMINANT, UINT_8_CLAMPED_ARRAY_DISCRIMINANT, UINT_16_ARRAY_DISCRIMINANT,
     UINT_32_ARRAY_DISCRIMINANT,
 };
+#[cfg(feature = "regexp")]
+use super::value::{REGEXP_DISCRIMINANT, REGEXP_STRING_ITERATOR_DISCRIMINANT};
 #[cfg(feature = "weak-refs")]
 use super::value::{WEAK_MAP_DISCRIMINANT, WEAK_REF_DISCRIMINANT, WEAK_SET_DISCRIMINANT};
 use super::{
@@ -49,10 +49,13 @@ use super::{
 };
 #[cfg(feature = "date")]
 use crate::ecmascript::builtins::date::Date;
-#[cfg(feature = "regexp")]
-use crate::ecmascript::builtins::regexp::RegExp;
 #[cfg(feature = "shared-array-buffer")]
 use crate::ecmascript::builtins::shared_array_buffer::SharedArrayBuffer;
+#[cfg(feature = "regexp")]
+use crate::ecmascript::builtins::{
+    regexp::RegExp,
+    text_processing::regexp_objects::regexp_string_iterator_objects::RegExpStringIterator,
+};
 #[cfg(feature = "weak-refs")]
 use crate::ecmascript::builtins::{weak_map::WeakMap, weak_ref::WeakRef, weak_set::WeakSet};
 #[cfg(feature = "set")]
@@ -65,11 +68,11 @@ use crate::ecmascript::{
 #[cfg(feature = "array-buffer")]
 use crate::{
     ecmascript::builtins::{ArrayBuffer, data_view::DataView, typed_array::TypedArray},
-    engine::context::NoGcScope,
     heap::indexes::TypedArrayIndex,
 };
 use crate::{
     ecmascript::{
+        abstract_operations::operations_on_objects::define_property_or_throw,
         builtins::{
             ArgumentsList, Array, BuiltinConstructorFunction, BuiltinFunction, ECMAScriptFunction,
             async_generator_objects::AsyncGenerator,
@@ -85,23 +88,26 @@ use crate::{
             keyed_collections::map_objects::map_iterator_objects::map_iterator::MapIterator,
             map::Map,
             module::Module,
-            ordinary::ordinary_object_create_with_intrinsics,
+            ordinary::{
+                is_compatible_property_descriptor, ordinary_define_own_property,
+                ordinary_object_create_with_intrinsics,
+            },
             primitive_objects::PrimitiveObject,
             promise::Promise,
             proxy::Proxy,
             text_processing::string_objects::string_iterator_objects::StringIterator,
         },
-        execution::{Agent, JsResult, ProtoIntrinsics},
-        types::PropertyDescriptor,
+        execution::{Agent, JsResult, ProtoIntrinsics, agent::ExceptionType},
+        types::{PropertyDescriptor, PropertyDescriptorTemplate},
     },
     engine::{
         TryResult,
-        context::{Bindable, GcScope},
-        rootable::HeapRootData,
+        context::{Bindable, GcScope, NoGcScope},
+        rootable::{HeapRootData, Scopable},
     },
     heap::{
         CompactionLists, CreateHeapData, Heap, HeapMarkAndSweep, HeapSweepWeakReference,
-        WorkQueues, indexes::ObjectIndex,
+        ObjectEntry, ObjectEntryPropertyDescriptor, WorkQueues, indexes::ObjectIndex,
     },
 };
 
@@ -113,6 +119,7 @@ pub use property_key::PropertyKey;
 pub use property_key_set::PropertyKeySet;
 pub(crate) use property_key_vec::ScopedPropertyKey;
 pub use property_storage::PropertyStorage;
+pub(crate) use property_storage::{array_index, get_indexed_element};
 
 /// ### [6.1.7 The Object Type](https://tc39.es/ecma262/#sec-object-type)
 ///
@@ -186,6 +193,8 @@ pub enum Object<'a> {
     ArrayIterator(ArrayIterator<'a>) = ARRAY_ITERATOR_DISCRIMINANT,
     #[cfg(feature = "set")]
     SetIterator(SetIterator<'a>) = SET_ITERATOR_DISCRIMINANT,
+    #[cfg(feature = "regexp")]
+    RegExpStringIterator(RegExpStringIterator<'a>) = REGEXP_STRING_ITERATOR_DISCRIMINANT,
     MapIterator(MapIterator<'a>) = MAP_ITERATOR_DISCRIMINANT,
     StringIterator(StringIterator<'a>) = STRING_ITERATOR_DISCRIMINANT,
     Generator(Generator<'a>) = GENERATOR_DISCRIMINANT,
@@ -198,9 +207,116 @@ impl Object<'_> {
     pub fn is_proxy(self) -> bool {
         matches!(self, Object::Proxy(_))
     }
+
+    /// Define many properties on this object at once, eg. to install host
+    /// functions and constants on a global object.
+    ///
+    /// Like `Object.defineProperties`, all definitions are validated against
+    /// the current properties of the object before any of them is applied:
+    /// if one of them cannot be defined, a TypeError is thrown and the object
+    /// is left untouched. A later entry with the same key as an earlier one
+    /// redefines the property. The property storage of an ordinary object is
+    /// grown at most once for all of the new properties.
+    ///
+    /// Exotic objects can reject definitions that are valid for ordinary
+    /// objects, and Proxies cannot be validated without calling into
+    /// JavaScript, so their properties are defined one at a time and an error
+    /// leaves the earlier definitions applied.
+    pub fn define_properties_batch<'gc>(
+        self,
+        agent: &mut Agent,
+        entries: &[(PropertyKey, PropertyDescriptorTemplate)],
+        mut gc: GcScope<'gc, '_>,
+    ) -> JsResult<'gc, ()> {
+        let object = self.bind(gc.nogc());
+        let validated = validate_properties_batch(agent, object, entries, gc.nogc());
+        if let TryResult::Continue(Err(index)) = validated {
+            let key = entries[index].0;
+            return Err(agent.throw_exception(
+                ExceptionType::TypeError,
+                format!("Cannot define property '{}'", key.as_display(agent)),
+                gc.into_nogc(),
+            ));
+        }
+        if let (Object::Object(object), TryResult::Continue(Ok(new_properties))) =
+            (object, validated)
+        {
+            object.property_storage().reserve(agent, new_properties);
+            for &(key, template) in entries {
+                let defined =
+                    ordinary_define_own_property(agent, object, key, template.into(), gc.nogc());
+                debug_assert!(defined);
+            }
+            return Ok(());
+        }
+        let scoped_object = object.scope(agent, gc.nogc());
+        let entries = entries
+            .iter()
+            .map(|&(key, template)| {
+                (
+                    key.scope(agent, gc.nogc()),
+                    PropertyDescriptor::from(template).scope(agent, gc.nogc()),
+                )
+            })
+            .collect::<Vec<_>>();
+        for (key, descriptor) in entries {
+            define_property_or_throw(
+                agent,
+                scoped_object.get(agent),
+                key.get(agent),
+                descriptor.get(agent, gc.nogc()).unbind(),
+                gc.reborrow(),
+            )
+            .unbind()?;
+        }
+        Ok(())
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+/// Validates the definitions of a batch against the current properties of the
+/// object and the earlier definitions of the batch.
+///
+/// Returns the number of new non-index properties, or the index of the first
+/// entry that cannot be defined.
+fn validate_properties_batch(
+    agent: &mut Agent,
+    object: Object,
+    entries: &[(PropertyKey, PropertyDescriptorTemplate)],
+    gc: NoGcScope,
+) -> TryResult<Result<u32, usize>> {
+    let extensible = object.try_is_extensible(agent, gc)?;
+    let mut new_properties = 0u32;
+    for (index, &(key, template)) in entries.iter().enumerate() {
+        if let PropertyDescriptorTemplate::Accessor {
+            get: None,
+            set: None,
+            ..
+        } = template
+        {
+            return TryResult::Continue(Err(index));
+        }
+        let earlier = entries[..index]
+            .iter()
+            .rev()
+            .find(|(earlier_key, _)| *earlier_key == key);
+        let current = match earlier {
+            Some(&(_, earlier)) => Some(PropertyDescriptor::from(earlier)),
+            None => {
+                let current = object.try_get_own_property(agent, key, gc)?;
+                if current.is_none() && array_index(key).is_none() {
+                    new_properties += 1;
+                }
+                current
+            }
+        };
+        if !is_compatible_property_descriptor(agent, extensible, template.into(), current, gc) {
+            return TryResult::Continue(Err(index));
+        }
+    }
+    TryResult::Continue(Ok(new_properties))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct OrdinaryObject<'a>(pub(crate) ObjectIndex<'a>);
 
 // SAFETY: Property implemented as a lifetime transmute.
@@ -325,6 +441,47 @@ impl<'a> OrdinaryObject<'a> {
         };
         ordinary
     }
+
+    /// Creates a frozen object with %Object.prototype% as its prototype and
+    /// the given entries as its own properties.
+    ///
+    /// Every property is created as an enumerable, non-writable and
+    /// non-configurable data property, and the object is non-extensible. This
+    /// is equivalent to but cheaper than creating the object and then calling
+    /// `Object.freeze` on it. If a key appears multiple times, the last value
+    /// is used.
+    pub fn frozen_from_entries(
+        agent: &mut Agent,
+        entries: impl IntoIterator<Item = (PropertyKey<'a>, Value<'a>)>,
+        gc: NoGcScope<'a, '_>,
+    ) -> Self {
+        let entries = entries.into_iter();
+        let mut object_entries: Vec<ObjectEntry> = Vec::with_capacity(entries.size_hint().0);
+        for (key, value) in entries {
+            let value = ObjectEntryPropertyDescriptor::Data {
+                value,
+                writable: false,
+                enumerable: true,
+                configurable: false,
+            };
+            if let Some(entry) = object_entries.iter_mut().find(|entry| entry.key == key) {
+                entry.value = value;
+            } else {
+                object_entries.push(ObjectEntry { key, value });
+            }
+        }
+        let prototype = agent
+            .current_realm_record()
+            .intrinsics()
+            .object_prototype()
+            .into_object();
+        let object = agent
+            .heap
+            .create_object_with_prototype(prototype, &object_entries)
+            .bind(gc);
+        object.internal_set_extensible(agent, false);
+        object
+    }
 }
 
 impl<'a> From<ObjectIndex<'a>> for Object<'a> {
@@ -411,6 +568,8 @@ impl<'a> From<Object<'a>> for Value<'a> {
             Object::ArrayIterator(data) => Value::ArrayIterator(data.unbind()),
             #[cfg(feature = "set")]
             Object::SetIterator(data) => Value::SetIterator(data.unbind()),
+            #[cfg(feature = "regexp")]
+            Object::RegExpStringIterator(data) => Value::RegExpStringIterator(data.unbind()),
             Object::MapIterator(data) => Value::MapIterator(data.unbind()),
             Object::StringIterator(data) => Value::StringIterator(data.unbind()),
             Object::Generator(data) => Value::Generator(data.unbind()),
@@ -501,6 +660,8 @@ impl<'a> TryFrom<Value<'a>> for Object<'a> {
             Value::ArrayIterator(data) => Ok(Object::ArrayIterator(data)),
             #[cfg(feature = "set")]
             Value::SetIterator(data) => Ok(Object::SetIterator(data)),
+            #[cfg(feature = "regexp")]
+            Value::RegExpStringIterator(data) => Ok(Object::RegExpStringIterator(data)),
             Value::MapIterator(data) => Ok(Object::MapIterator(data)),
             Value::StringIterator(data) => Ok(Object::StringIterator(data)),
             Value::Generator(data) => Ok(Object::Generator(data)),
@@ -514,6 +675,19 @@ impl<'a> OrdinaryObject<'a> {
     pub fn property_storage(self) -> PropertyStorage<'a> {
         PropertyStorage::new(self)
     }
+
+    /// Drop any cached property keys of this object, so that the next
+    /// `for...in`, `Object.keys`, or \[\[OwnPropertyKeys]] enumeration of it
+    /// or of an object inheriting from it collects the keys again.
+    ///
+    /// The engine invalidates the cached keys itself whenever properties are
+    /// added, removed, or change enumerability through its own APIs. Hosts
+    /// that change the properties of the object in some other way, eg. by
+    /// mutating the native data that the object wraps, must call this
+    /// afterwards to avoid stale enumeration results.
+    pub fn invalidate_enumeration_cache(self, agent: &mut Agent) {
+        self.property_storage().invalidate_keys(agent);
+    }
 }
 
 impl Hash for Object<'_> {
@@ -584,6 +758,8 @@ impl Hash for Object<'_> {
             Object::ArrayIterator(data) => data.get_index().hash(state),
             #[cfg(feature = "set")]
             Object::SetIterator(data) => data.get_index().hash(state),
+            #[cfg(feature = "regexp")]
+            Object::RegExpStringIterator(data) => data.get_index().hash(state),
             Object::MapIterator(data) => data.get_index().hash(state),
             Object::StringIterator(data) => data.get_index().hash(state),
             Object::Generator(data) => data.get_index().hash(state),
@@ -666,6 +842,8 @@ impl<'a> InternalSlots<'a> for Object<'a> {
             Object::ArrayIterator(data) => data.get_backing_object(agent),
             #[cfg(feature = "set")]
             Object::SetIterator(data) => data.get_backing_object(agent),
+            #[cfg(feature = "regexp")]
+            Object::RegExpStringIterator(data) => data.get_backing_object(agent),
             Object::MapIterator(data) => data.get_backing_object(agent),
             Object::StringIterator(data) => data.get_backing_object(agent),
             Object::Generator(data) => data.get_backing_object(agent),
@@ -774,6 +952,8 @@ impl<'a> InternalSlots<'a> for Object<'a> {
             Object::ArrayIterator(data) => data.get_or_create_backing_object(agent),
             #[cfg(feature = "set")]
             Object::SetIterator(data) => data.get_or_create_backing_object(agent),
+            #[cfg(feature = "regexp")]
+            Object::RegExpStringIterator(data) => data.get_or_create_backing_object(agent),
             Object::MapIterator(data) => data.get_or_create_backing_object(agent),
             Object::StringIterator(data) => data.get_or_create_backing_object(agent),
             Object::Generator(data) => data.get_or_create_backing_object(agent),
@@ -854,6 +1034,8 @@ impl<'a> InternalSlots<'a> for Object<'a> {
             Object::ArrayIterator(data) => data.internal_extensible(agent),
             #[cfg(feature = "set")]
             Object::SetIterator(data) => data.internal_extensible(agent),
+            #[cfg(feature = "regexp")]
+            Object::RegExpStringIterator(data) => data.internal_extensible(agent),
             Object::MapIterator(data) => data.internal_extensible(agent),
             Object::StringIterator(data) => data.internal_extensible(agent),
             Object::Generator(data) => data.internal_extensible(agent),
@@ -954,6 +1136,8 @@ impl<'a> InternalSlots<'a> for Object<'a> {
             Object::ArrayIterator(data) => data.internal_set_extensible(agent, value),
             #[cfg(feature = "set")]
             Object::SetIterator(data) => data.internal_set_extensible(agent, value),
+            #[cfg(feature = "regexp")]
+            Object::RegExpStringIterator(data) => data.internal_set_extensible(agent, value),
             Object::MapIterator(data) => data.internal_set_extensible(agent, value),
             Object::Generator(data) => data.internal_set_extensible(agent, value),
             Object::StringIterator(data) => data.internal_set_extensible(agent, value),
@@ -1034,6 +1218,8 @@ impl<'a> InternalSlots<'a> for Object<'a> {
             Object::ArrayIterator(data) => data.internal_prototype(agent),
             #[cfg(feature = "set")]
             Object::SetIterator(data) => data.internal_prototype(agent),
+            #[cfg(feature = "regexp")]
+            Object::RegExpStringIterator(data) => data.internal_prototype(agent),
             Object::MapIterator(data) => data.internal_prototype(agent),
             Object::StringIterator(data) => data.internal_prototype(agent),
             Object::Generator(data) => data.internal_prototype(agent),
@@ -1136,6 +1322,8 @@ impl<'a> InternalSlots<'a> for Object<'a> {
             Object::ArrayIterator(data) => data.internal_set_prototype(agent, prototype),
             #[cfg(feature = "set")]
             Object::SetIterator(data) => data.internal_set_prototype(agent, prototype),
+            #[cfg(feature = "regexp")]
+            Object::RegExpStringIterator(data) => data.internal_set_prototype(agent, prototype),
             Object::MapIterator(data) => data.internal_set_prototype(agent, prototype),
             Object::StringIterator(data) => data.internal_set_prototype(agent, prototype),
             Object::Generator(data) => data.internal_set_prototype(agent, prototype),
@@ -1238,6 +1426,8 @@ impl<'a> InternalMethods<'a> for Object<'a> {
             Object::ArrayIterator(data) => data.try_get_prototype_of(agent, gc),
             #[cfg(feature = "set")]
             Object::SetIterator(data) => data.try_get_prototype_of(agent, gc),
+            #[cfg(feature = "regexp")]
+            Object::RegExpStringIterator(data) => data.try_get_prototype_of(agent, gc),
             Object::MapIterator(data) => data.try_get_prototype_of(agent, gc),
             Object::StringIterator(data) => data.try_get_prototype_of(agent, gc),
             Object::Generator(data) => data.try_get_prototype_of(agent, gc),
@@ -1342,6 +1532,8 @@ impl<'a> InternalMethods<'a> for Object<'a> {
             Object::ArrayIterator(data) => data.internal_get_prototype_of(agent, gc),
             #[cfg(feature = "set")]
             Object::SetIterator(data) => data.internal_get_prototype_of(agent, gc),
+            #[cfg(feature = "regexp")]
+            Object::RegExpStringIterator(data) => data.internal_get_prototype_of(agent, gc),
             Object::MapIterator(data) => data.internal_get_prototype_of(agent, gc),
             Object::StringIterator(data) => data.internal_get_prototype_of(agent, gc),
             Object::Generator(data) => data.internal_get_prototype_of(agent, gc),
@@ -1449,6 +1641,8 @@ impl<'a> InternalMethods<'a> for Object<'a> {
             Object::ArrayIterator(data) => data.try_set_prototype_of(agent, prototype, gc),
             #[cfg(feature = "set")]
             Object::SetIterator(data) => data.try_set_prototype_of(agent, prototype, gc),
+            #[cfg(feature = "regexp")]
+            Object::RegExpStringIterator(data) => data.try_set_prototype_of(agent, prototype, gc),
             Object::MapIterator(data) => data.try_set_prototype_of(agent, prototype, gc),
             Object::StringIterator(data) => data.try_set_prototype_of(agent, prototype, gc),
             Object::Generator(data) => data.try_set_prototype_of(agent, prototype, gc),
@@ -1560,6 +1754,10 @@ impl<'a> InternalMethods<'a> for Object<'a> {
             Object::ArrayIterator(data) => data.internal_set_prototype_of(agent, prototype, gc),
             #[cfg(feature = "set")]
             Object::SetIterator(data) => data.internal_set_prototype_of(agent, prototype, gc),
+            #[cfg(feature = "regexp")]
+            Object::RegExpStringIterator(data) => {
+                data.internal_set_prototype_of(agent, prototype, gc)
+            }
             Object::MapIterator(data) => data.internal_set_prototype_of(agent, prototype, gc),
             Object::StringIterator(data) => data.internal_set_prototype_of(agent, prototype, gc),
             Object::Generator(data) => data.internal_set_prototype_of(agent, prototype, gc),
@@ -1646,6 +1844,8 @@ impl<'a> InternalMethods<'a> for Object<'a> {
             Object::ArrayIterator(data) => data.try_is_extensible(agent, gc),
             #[cfg(feature = "set")]
             Object::SetIterator(data) => data.try_is_extensible(agent, gc),
+            #[cfg(feature = "regexp")]
+            Object::RegExpStringIterator(data) => data.try_is_extensible(agent, gc),
             Object::MapIterator(data) => data.try_is_extensible(agent, gc),
             Object::StringIterator(data) => data.try_is_extensible(agent, gc),
             Object::Generator(data) => data.try_is_extensible(agent, gc),
@@ -1748,6 +1948,8 @@ impl<'a> InternalMethods<'a> for Object<'a> {
             Object::ArrayIterator(data) => data.internal_is_extensible(agent, gc),
             #[cfg(feature = "set")]
             Object::SetIterator(data) => data.internal_is_extensible(agent, gc),
+            #[cfg(feature = "regexp")]
+            Object::RegExpStringIterator(data) => data.internal_is_extensible(agent, gc),
             Object::MapIterator(data) => data.internal_is_extensible(agent, gc),
             Object::StringIterator(data) => data.internal_is_extensible(agent, gc),
             Object::Generator(data) => data.internal_is_extensible(agent, gc),
@@ -1846,6 +2048,8 @@ impl<'a> InternalMethods<'a> for Object<'a> {
             Object::ArrayIterator(data) => data.try_prevent_extensions(agent, gc),
             #[cfg(feature = "set")]
             Object::SetIterator(data) => data.try_prevent_extensions(agent, gc),
+            #[cfg(feature = "regexp")]
+            Object::RegExpStringIterator(data) => data.try_prevent_extensions(agent, gc),
             Object::MapIterator(data) => data.try_prevent_extensions(agent, gc),
             Object::StringIterator(data) => data.try_prevent_extensions(agent, gc),
             Object::Generator(data) => data.try_prevent_extensions(agent, gc),
@@ -1950,6 +2154,8 @@ impl<'a> InternalMethods<'a> for Object<'a> {
             Object::ArrayIterator(data) => data.internal_prevent_extensions(agent, gc),
             #[cfg(feature = "set")]
             Object::SetIterator(data) => data.internal_prevent_extensions(agent, gc),
+            #[cfg(feature = "regexp")]
+            Object::RegExpStringIterator(data) => data.internal_prevent_extensions(agent, gc),
             Object::MapIterator(data) => data.internal_prevent_extensions(agent, gc),
             Object::StringIterator(data) => data.internal_prevent_extensions(agent, gc),
             Object::Generator(data) => data.internal_prevent_extensions(agent, gc),
@@ -2059,6 +2265,10 @@ impl<'a> InternalMethods<'a> for Object<'a> {
             Object::ArrayIterator(data) => data.try_get_own_property(agent, property_key, gc),
             #[cfg(feature = "set")]
             Object::SetIterator(data) => data.try_get_own_property(agent, property_key, gc),
+            #[cfg(feature = "regexp")]
+            Object::RegExpStringIterator(data) => {
+                data.try_get_own_property(agent, property_key, gc)
+            }
             Object::MapIterator(data) => data.try_get_own_property(agent, property_key, gc),
             Object::StringIterator(data) => data.try_get_own_property(agent, property_key, gc),
             Object::Generator(data) => data.try_get_own_property(agent, property_key, gc),
@@ -2175,6 +2385,10 @@ impl<'a> InternalMethods<'a> for Object<'a> {
             Object::ArrayIterator(data) => data.internal_get_own_property(agent, property_key, gc),
             #[cfg(feature = "set")]
             Object::SetIterator(data) => data.internal_get_own_property(agent, property_key, gc),
+            #[cfg(feature = "regexp")]
+            Object::RegExpStringIterator(data) => {
+                data.internal_get_own_property(agent, property_key, gc)
+            }
             Object::MapIterator(data) => data.internal_get_own_property(agent, property_key, gc),
             Object::StringIterator(data) => data.internal_get_own_property(agent, property_key, gc),
             Object::Generator(data) => data.internal_get_own_property(agent, property_key, gc),
@@ -2359,6 +2573,10 @@ impl<'a> InternalMethods<'a> for Object<'a> {
             Object::SetIterator(data) => {
                 data.try_define_own_property(agent, property_key, property_descriptor, gc)
             }
+            #[cfg(feature = "regexp")]
+            Object::RegExpStringIterator(data) => {
+                data.try_define_own_property(agent, property_key, property_descriptor, gc)
+            }
             Object::MapIterator(data) => {
                 data.try_define_own_property(agent, property_key, property_descriptor, gc)
             }
@@ -2529,6 +2747,10 @@ impl<'a> InternalMethods<'a> for Object<'a> {
             Object::SetIterator(data) => {
                 data.internal_define_own_property(agent, property_key, property_descriptor, gc)
             }
+            #[cfg(feature = "regexp")]
+            Object::RegExpStringIterator(data) => {
+                data.internal_define_own_property(agent, property_key, property_descriptor, gc)
+            }
             Object::MapIterator(data) => {
                 data.internal_define_own_property(agent, property_key, property_descriptor, gc)
             }
@@ -2646,6 +2868,8 @@ impl<'a> InternalMethods<'a> for Object<'a> {
             Object::ArrayIterator(data) => data.try_has_property(agent, property_key, gc),
             #[cfg(feature = "set")]
             Object::SetIterator(data) => data.try_has_property(agent, property_key, gc),
+            #[cfg(feature = "regexp")]
+            Object::RegExpStringIterator(data) => data.try_has_property(agent, property_key, gc),
             Object::MapIterator(data) => data.try_has_property(agent, property_key, gc),
             Object::StringIterator(data) => data.try_has_property(agent, property_key, gc),
             Object::Generator(data) => data.try_has_property(agent, property_key, gc),
@@ -2755,6 +2979,10 @@ impl<'a> InternalMethods<'a> for Object<'a> {
             Object::ArrayIterator(data) => data.internal_has_property(agent, property_key, gc),
             #[cfg(feature = "set")]
             Object::SetIterator(data) => data.internal_has_property(agent, property_key, gc),
+            #[cfg(feature = "regexp")]
+            Object::RegExpStringIterator(data) => {
+                data.internal_has_property(agent, property_key, gc)
+            }
             Object::MapIterator(data) => data.internal_has_property(agent, property_key, gc),
             Object::StringIterator(data) => data.internal_has_property(agent, property_key, gc),
             Object::Generator(data) => data.internal_has_property(agent, property_key, gc),
@@ -2863,6 +3091,8 @@ impl<'a> InternalMethods<'a> for Object<'a> {
             Object::ArrayIterator(data) => data.try_get(agent, property_key, receiver, gc),
             #[cfg(feature = "set")]
             Object::SetIterator(data) => data.try_get(agent, property_key, receiver, gc),
+            #[cfg(feature = "regexp")]
+            Object::RegExpStringIterator(data) => data.try_get(agent, property_key, receiver, gc),
             Object::MapIterator(data) => data.try_get(agent, property_key, receiver, gc),
             Object::StringIterator(data) => data.try_get(agent, property_key, receiver, gc),
             Object::Generator(data) => data.try_get(agent, property_key, receiver, gc),
@@ -2975,6 +3205,10 @@ impl<'a> InternalMethods<'a> for Object<'a> {
             Object::ArrayIterator(data) => data.internal_get(agent, property_key, receiver, gc),
             #[cfg(feature = "set")]
             Object::SetIterator(data) => data.internal_get(agent, property_key, receiver, gc),
+            #[cfg(feature = "regexp")]
+            Object::RegExpStringIterator(data) => {
+                data.internal_get(agent, property_key, receiver, gc)
+            }
             Object::MapIterator(data) => data.internal_get(agent, property_key, receiver, gc),
             Object::StringIterator(data) => data.internal_get(agent, property_key, receiver, gc),
             Object::Generator(data) => data.internal_get(agent, property_key, receiver, gc),
@@ -3094,6 +3328,10 @@ impl<'a> InternalMethods<'a> for Object<'a> {
             Object::ArrayIterator(data) => data.try_set(agent, property_key, value, receiver, gc),
             #[cfg(feature = "set")]
             Object::SetIterator(data) => data.try_set(agent, property_key, value, receiver, gc),
+            #[cfg(feature = "regexp")]
+            Object::RegExpStringIterator(data) => {
+                data.try_set(agent, property_key, value, receiver, gc)
+            }
             Object::MapIterator(data) => data.try_set(agent, property_key, value, receiver, gc),
             Object::StringIterator(data) => data.try_set(agent, property_key, value, receiver, gc),
             Object::Generator(data) => data.try_set(agent, property_key, value, receiver, gc),
@@ -3247,6 +3485,10 @@ impl<'a> InternalMethods<'a> for Object<'a> {
             Object::SetIterator(data) => {
                 data.internal_set(agent, property_key, value, receiver, gc)
             }
+            #[cfg(feature = "regexp")]
+            Object::RegExpStringIterator(data) => {
+                data.internal_set(agent, property_key, value, receiver, gc)
+            }
             Object::MapIterator(data) => {
                 data.internal_set(agent, property_key, value, receiver, gc)
             }
@@ -3358,6 +3600,8 @@ impl<'a> InternalMethods<'a> for Object<'a> {
             Object::ArrayIterator(data) => data.try_delete(agent, property_key, gc),
             #[cfg(feature = "set")]
             Object::SetIterator(data) => data.try_delete(agent, property_key, gc),
+            #[cfg(feature = "regexp")]
+            Object::RegExpStringIterator(data) => data.try_delete(agent, property_key, gc),
             Object::MapIterator(data) => data.try_delete(agent, property_key, gc),
             Object::StringIterator(data) => data.try_delete(agent, property_key, gc),
             Object::Generator(data) => data.try_delete(agent, property_key, gc),
@@ -3465,6 +3709,8 @@ impl<'a> InternalMethods<'a> for Object<'a> {
             Object::ArrayIterator(data) => data.internal_delete(agent, property_key, gc),
             #[cfg(feature = "set")]
             Object::SetIterator(data) => data.internal_delete(agent, property_key, gc),
+            #[cfg(feature = "regexp")]
+            Object::RegExpStringIterator(data) => data.internal_delete(agent, property_key, gc),
             Object::MapIterator(data) => data.internal_delete(agent, property_key, gc),
             Object::StringIterator(data) => data.internal_delete(agent, property_key, gc),
             Object::Generator(data) => data.internal_delete(agent, property_key, gc),
@@ -3565,6 +3811,8 @@ impl<'a> InternalMethods<'a> for Object<'a> {
             Object::ArrayIterator(data) => data.try_own_property_keys(agent, gc),
             #[cfg(feature = "set")]
             Object::SetIterator(data) => data.try_own_property_keys(agent, gc),
+            #[cfg(feature = "regexp")]
+            Object::RegExpStringIterator(data) => data.try_own_property_keys(agent, gc),
             Object::MapIterator(data) => data.try_own_property_keys(agent, gc),
             Object::StringIterator(data) => data.try_own_property_keys(agent, gc),
             Object::Generator(data) => data.try_own_property_keys(agent, gc),
@@ -3669,6 +3917,8 @@ impl<'a> InternalMethods<'a> for Object<'a> {
             Object::ArrayIterator(data) => data.internal_own_property_keys(agent, gc),
             #[cfg(feature = "set")]
             Object::SetIterator(data) => data.internal_own_property_keys(agent, gc),
+            #[cfg(feature = "regexp")]
+            Object::RegExpStringIterator(data) => data.internal_own_property_keys(agent, gc),
             Object::MapIterator(data) => data.internal_own_property_keys(agent, gc),
             Object::StringIterator(data) => data.internal_own_property_keys(agent, gc),
             Object::Generator(data) => data.internal_own_property_keys(agent, gc),
@@ -3784,6 +4034,8 @@ impl HeapMarkAndSweep for Object<'static> {
             Self::ArrayIterator(data) => data.mark_values(queues),
             #[cfg(feature = "set")]
             Self::SetIterator(data) => data.mark_values(queues),
+            #[cfg(feature = "regexp")]
+            Self::RegExpStringIterator(data) => data.mark_values(queues),
             Self::MapIterator(data) => data.mark_values(queues),
             Self::StringIterator(data) => data.mark_values(queues),
             Self::Generator(data) => data.mark_values(queues),
@@ -3858,6 +4110,8 @@ impl HeapMarkAndSweep for Object<'static> {
             Self::ArrayIterator(data) => data.sweep_values(compactions),
             #[cfg(feature = "set")]
             Self::SetIterator(data) => data.sweep_values(compactions),
+            #[cfg(feature = "regexp")]
+            Self::RegExpStringIterator(data) => data.sweep_values(compactions),
             Self::MapIterator(data) => data.sweep_values(compactions),
             Self::StringIterator(data) => data.sweep_values(compactions),
             Self::Generator(data) => data.sweep_values(compactions),
@@ -3974,6 +4228,10 @@ impl HeapSweepWeakReference for Object<'static> {
             Self::SetIterator(data) => data
                 .sweep_weak_reference(compactions)
                 .map(Self::SetIterator),
+            #[cfg(feature = "regexp")]
+            Self::RegExpStringIterator(data) => data
+                .sweep_weak_reference(compactions)
+                .map(Self::RegExpStringIterator),
             Self::MapIterator(data) => data
                 .sweep_weak_reference(compactions)
                 .map(Self::MapIterator),
@@ -4101,6 +4359,10 @@ impl TryFrom<HeapRootData> for Object<'_> {
             HeapRootData::ArrayIterator(array_iterator) => Ok(Self::ArrayIterator(array_iterator)),
             #[cfg(feature = "set")]
             HeapRootData::SetIterator(set_iterator) => Ok(Self::SetIterator(set_iterator)),
+            #[cfg(feature = "regexp")]
+            HeapRootData::RegExpStringIterator(reg_exp_string_iterator) => {
+                Ok(Self::RegExpStringIterator(reg_exp_string_iterator))
+            }
             HeapRootData::MapIterator(map_iterator) => Ok(Self::MapIterator(map_iterator)),
             HeapRootData::StringIterator(map_iterator) => Ok(Self::StringIterator(map_iterator)),
             HeapRootData::Generator(generator) => Ok(Self::Generator(generator)),
@@ -4122,3 +4384,338 @@ impl TryFrom<HeapRootData> for Object<'_> {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        ecmascript::{
+            execution::{Agent, DefaultHostHooks, agent::Options, initialize_default_realm},
+            scripts_and_modules::script::test::evaluate,
+            types::{
+                Function, InternalMethods, IntoValue, Object, OrdinaryObject,
+                PropertyDescriptorTemplate, PropertyKey, String, Value,
+            },
+        },
+        engine::context::{Bindable, GcScope},
+        heap::element_array::ElementArrayKey,
+    };
+
+    #[test]
+    fn frozen_config_object() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let entries = [
+            (
+                PropertyKey::from_static_str(agent, "name", gc.nogc()),
+                String::from_static_str(agent, "nova", gc.nogc()).into_value(),
+            ),
+            (
+                PropertyKey::from_static_str(agent, "retries", gc.nogc()),
+                Value::from(3),
+            ),
+            (
+                PropertyKey::from_static_str(agent, "verbose", gc.nogc()),
+                Value::Boolean(false),
+            ),
+            (
+                PropertyKey::from_static_str(agent, "retries", gc.nogc()),
+                Value::from(5),
+            ),
+        ];
+        let config = OrdinaryObject::frozen_from_entries(agent, entries, gc.nogc()).unbind();
+
+        let source_text = String::from_static_str(agent, "globalThis", gc.nogc());
+        let global = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap()
+            .unbind();
+        let global = Object::try_from(global).unwrap();
+        let key = PropertyKey::from_static_str(agent, "config", gc.nogc()).unbind();
+        global
+            .internal_set(
+                agent,
+                key,
+                config.into_value(),
+                global.into_value(),
+                gc.reborrow(),
+            )
+            .unwrap();
+
+        let source_text = String::from_static_str(
+            agent,
+            "Object.isFrozen(config) &&
+            !Object.isExtensible(config) &&
+            Object.getPrototypeOf(config) === Object.prototype &&
+            Object.keys(config).join() === 'name,retries,verbose' &&
+            config.name === 'nova' && config.retries === 5 && config.verbose === false",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap()
+            .unbind();
+        assert_eq!(result, Value::Boolean(true));
+
+        let source_text = String::from_static_str(
+            agent,
+            "var threw = false;
+            try {
+                (function () { 'use strict'; config.retries = 10; })();
+            } catch (err) {
+                threw = err instanceof TypeError;
+            }
+            threw && config.retries === 5 && delete config.name === false",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap()
+            .unbind();
+        assert_eq!(result, Value::Boolean(true));
+    }
+
+    /// Defines `object` as the global property `name`.
+    fn define_global(agent: &mut Agent, name: &'static str, object: Object, gc: GcScope) {
+        let object = object.bind(gc.nogc());
+        let global = agent.current_realm(gc.nogc()).global_object(agent).unbind();
+        let key = PropertyKey::from_static_str(agent, name, gc.nogc());
+        let entries = [(
+            key.unbind(),
+            PropertyDescriptorTemplate::data(object.into_value()).unbind(),
+        )];
+        global.define_properties_batch(agent, &entries, gc).unwrap();
+    }
+
+    #[test]
+    fn batch_of_new_properties_allocates_storage_once() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let object = OrdinaryObject::create_empty_object(agent, gc.nogc()).unbind();
+        let entries = (0..50)
+            .map(|i| {
+                let key = PropertyKey::from_string(agent, format!("p{i}"), gc.nogc());
+                (
+                    key.unbind(),
+                    PropertyDescriptorTemplate::data(Value::from(i)),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let before = agent.element_array_stats();
+        Object::from(object)
+            .unbind()
+            .define_properties_batch(agent, &entries, gc.reborrow())
+            .unwrap();
+        let after = agent.element_array_stats();
+
+        let allocated = |class: ElementArrayKey| {
+            after.class(class).total_slots - before.class(class).total_slots
+        };
+        let total = after
+            .classes
+            .iter()
+            .zip(before.classes)
+            .map(|(after, before)| after.total_slots - before.total_slots)
+            .sum::<usize>();
+        assert_eq!(total, 1);
+        assert_eq!(allocated(ElementArrayKey::E6), 1);
+
+        define_global(
+            agent,
+            "batched",
+            Object::from(object).unbind(),
+            gc.reborrow(),
+        );
+        let result = evaluate(
+            agent,
+            "const batchedKeys = Object.keys(batched);
+            batchedKeys.length === 50 &&
+            batchedKeys.every((key, i) => key === `p${i}` && batched[key] === i)",
+            gc,
+        );
+        assert_eq!(result, Value::Boolean(true));
+    }
+
+    #[test]
+    fn invalid_definition_leaves_object_untouched() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let object = evaluate(
+            agent,
+            "globalThis.target = Object.defineProperty({ existing: 1 }, 'fixed', { value: 2 });",
+            gc.reborrow(),
+        )
+        .unbind();
+        let object = Object::try_from(object).unwrap();
+        let key = |agent: &mut Agent, name: &'static str, gc: GcScope| {
+            PropertyKey::from_static_str(agent, name, gc.nogc()).unbind()
+        };
+        let a = key(agent, "a", gc.reborrow());
+        let existing = key(agent, "existing", gc.reborrow());
+        let fixed = key(agent, "fixed", gc.reborrow());
+        let b = key(agent, "b", gc.reborrow());
+
+        // Redefining a non-configurable property with another value.
+        let entries = [
+            (a, PropertyDescriptorTemplate::data(Value::from(1))),
+            (
+                existing,
+                PropertyDescriptorTemplate::constant(Value::from(3)),
+            ),
+            (fixed, PropertyDescriptorTemplate::constant(Value::from(4))),
+            (b, PropertyDescriptorTemplate::data(Value::from(5))),
+        ];
+        let error = object
+            .define_properties_batch(agent, &entries, gc.reborrow())
+            .unwrap_err()
+            .value()
+            .unbind();
+        // An accessor without a getter or a setter.
+        let entries = [
+            (a, PropertyDescriptorTemplate::data(Value::from(1))),
+            (
+                b,
+                PropertyDescriptorTemplate::Accessor {
+                    get: None,
+                    set: None,
+                    enumerable: true,
+                    configurable: true,
+                },
+            ),
+        ];
+        assert!(
+            object
+                .define_properties_batch(agent, &entries, gc.reborrow())
+                .is_err()
+        );
+        // A duplicate key that redefines a non-configurable property of the
+        // same batch.
+        let entries = [
+            (a, PropertyDescriptorTemplate::constant(Value::from(1))),
+            (a, PropertyDescriptorTemplate::constant(Value::from(2))),
+        ];
+        assert!(
+            object
+                .define_properties_batch(agent, &entries, gc.reborrow())
+                .is_err()
+        );
+
+        define_global(
+            agent,
+            "error",
+            Object::try_from(error).unwrap(),
+            gc.reborrow(),
+        );
+        let result = evaluate(
+            agent,
+            "error instanceof TypeError &&
+            error.message === \"Cannot define property 'fixed'\" &&
+            Object.getOwnPropertyNames(target).join() === 'existing,fixed' &&
+            target.existing === 1 &&
+            Object.getOwnPropertyDescriptor(target, 'existing').writable &&
+            target.fixed === 2",
+            gc.reborrow(),
+        );
+        assert_eq!(result, Value::Boolean(true));
+
+        // New properties cannot be added to a non-extensible object.
+        let object = evaluate(
+            agent,
+            "Object.preventExtensions({ existing: 1 })",
+            gc.reborrow(),
+        )
+        .unbind();
+        let object = Object::try_from(object).unwrap();
+        let entries = [
+            (existing, PropertyDescriptorTemplate::data(Value::from(2))),
+            (a, PropertyDescriptorTemplate::data(Value::from(1))),
+        ];
+        assert!(
+            object
+                .define_properties_batch(agent, &entries, gc.reborrow())
+                .is_err()
+        );
+        define_global(agent, "sealed", object, gc.reborrow());
+        let result = evaluate(
+            agent,
+            "Object.keys(sealed).join() === 'existing' && sealed.existing === 1",
+            gc,
+        );
+        assert_eq!(result, Value::Boolean(true));
+    }
+
+    #[test]
+    fn installed_properties_have_the_given_attributes() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let getter = evaluate(agent, "(function () { return 42; })", gc.reborrow()).unbind();
+        let getter = Function::try_from(getter).unwrap();
+        let global = agent.current_realm(gc.nogc()).global_object(agent).unbind();
+        let key = |agent: &mut Agent, name: &'static str, gc: GcScope| {
+            PropertyKey::from_static_str(agent, name, gc.nogc()).unbind()
+        };
+        let entries = [
+            (
+                key(agent, "hostData", gc.reborrow()),
+                PropertyDescriptorTemplate::data(Value::from(1)),
+            ),
+            (
+                key(agent, "hostBuiltin", gc.reborrow()),
+                PropertyDescriptorTemplate::builtin(Value::from(2)),
+            ),
+            (
+                key(agent, "hostConstant", gc.reborrow()),
+                PropertyDescriptorTemplate::constant(Value::from(3)),
+            ),
+            (
+                key(agent, "hostAccessor", gc.reborrow()),
+                PropertyDescriptorTemplate::Accessor {
+                    get: Some(getter),
+                    set: None,
+                    enumerable: true,
+                    configurable: false,
+                },
+            ),
+            (
+                key(agent, "hostRedefined", gc.reborrow()),
+                PropertyDescriptorTemplate::data(Value::from(4)),
+            ),
+            (
+                key(agent, "hostRedefined", gc.reborrow()),
+                PropertyDescriptorTemplate::constant(Value::from(5)),
+            ),
+        ];
+        global
+            .define_properties_batch(agent, &entries, gc.reborrow())
+            .unwrap();
+
+        let result = evaluate(
+            agent,
+            "function attributes(name) {
+                const { value, get, set, writable, enumerable, configurable } =
+                    Object.getOwnPropertyDescriptor(globalThis, name);
+                return JSON.stringify([value, typeof get, typeof set, writable, enumerable, configurable]);
+            }
+            attributes('hostData') === '[1,\"undefined\",\"undefined\",true,true,true]' &&
+            attributes('hostBuiltin') === '[2,\"undefined\",\"undefined\",true,false,true]' &&
+            attributes('hostConstant') === '[3,\"undefined\",\"undefined\",false,false,false]' &&
+            attributes('hostAccessor') === '[null,\"function\",\"undefined\",null,true,false]' &&
+            attributes('hostRedefined') === '[5,\"undefined\",\"undefined\",false,false,false]' &&
+            hostAccessor === 42",
+            gc,
+        );
+        assert_eq!(result, Value::Boolean(true));
+    }
+}
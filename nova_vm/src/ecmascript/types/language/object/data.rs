@@ -2,11 +2,15 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use super::{Object, PropertyKey};
+use super::{Object, PropertyKey, property_storage::has_indexed_element};
 use crate::{
     ecmascript::{execution::Agent, types::Value},
     engine::context::{Bindable, NoGcScope},
-    heap::{CompactionLists, HeapMarkAndSweep, WorkQueues, element_array::PropertyStorageVector},
+    heap::{
+        CompactionLists, HeapMarkAndSweep, WorkQueues,
+        element_array::{ElementsVector, PropertyStorageVector},
+        snapshot::{HeapSnapshot, SnapshotError, SnapshotReader, SnapshotWriter},
+    },
 };
 
 #[derive(Debug, Clone, Copy)]
@@ -23,6 +27,14 @@ pub struct ObjectHeapData<'a> {
     //   indexing at the cost of a lower maximum shape count.
     pub prototype: Option<Object<'a>>,
     pub property_storage: PropertyStorageVector<'a>,
+    /// Array index properties of an object that has accumulated many of
+    /// them, stored by index like the elements of an Array. Array index keys
+    /// that are too sparse to store here remain in the property storage.
+    pub(crate) indexed_elements: ElementsVector<'a>,
+    /// Bumped whenever a property key is added to or removed from the
+    /// object, or a property's enumerability changes. Used to validate the
+    /// [`OwnKeysCache`](crate::heap::OwnKeysCache).
+    pub(crate) keys_version: u32,
 }
 
 impl<'a> ObjectHeapData<'a> {
@@ -36,15 +48,18 @@ impl<'a> ObjectHeapData<'a> {
         Self {
             prototype,
             property_storage,
+            indexed_elements: ElementsVector::default(),
+            keys_version: 0,
         }
     }
 
     pub fn has(&self, agent: &Agent, key: PropertyKey) -> bool {
-        agent.heap.elements.has(&self.property_storage, key)
+        has_indexed_element(&agent.heap.elements, &self.indexed_elements, key)
+            || agent.heap.elements.has(&self.property_storage, key)
     }
 
     pub fn is_empty(&self) -> bool {
-        self.property_storage.len() == 0
+        self.property_storage.len() == 0 && self.indexed_elements.is_empty()
     }
 }
 
@@ -68,17 +83,46 @@ impl HeapMarkAndSweep for ObjectHeapData<'static> {
         let Self {
             prototype,
             property_storage,
+            indexed_elements,
+            keys_version: _,
         } = self;
         prototype.mark_values(queues);
         property_storage.mark_values(queues);
+        indexed_elements.mark_values(queues);
     }
 
     fn sweep_values(&mut self, compactions: &CompactionLists) {
         let Self {
             prototype,
             property_storage,
+            indexed_elements,
+            keys_version: _,
         } = self;
         prototype.sweep_values(compactions);
         property_storage.sweep_values(compactions);
+        indexed_elements.sweep_values(compactions);
+    }
+}
+
+impl HeapSnapshot for ObjectHeapData<'static> {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        let Self {
+            prototype,
+            property_storage,
+            indexed_elements,
+            keys_version: _,
+        } = self;
+        prototype.write_snapshot(writer);
+        property_storage.write_snapshot(writer);
+        indexed_elements.write_snapshot(writer);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        Ok(Self {
+            prototype: HeapSnapshot::read_snapshot(reader)?,
+            property_storage: HeapSnapshot::read_snapshot(reader)?,
+            indexed_elements: HeapSnapshot::read_snapshot(reader)?,
+            keys_version: 0,
+        })
     }
 }
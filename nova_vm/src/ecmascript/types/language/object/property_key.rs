@@ -165,11 +165,10 @@ impl<'a> PropertyKey<'a> {
             (PropertyKey::SmallString(s1), PropertyKey::SmallString(s2)) => {
                 s1.as_str() == s2.as_str()
             }
-            (PropertyKey::String(s), PropertyKey::Integer(n)) => {
-                let s = agent[s.unbind()].as_str();
-
-                Self::is_str_eq_num(s, n.into_i64())
-            }
+            (PropertyKey::String(s), PropertyKey::Integer(n)) => agent[s.unbind()]
+                .as_wtf8()
+                .as_str()
+                .is_some_and(|s| Self::is_str_eq_num(s, n.into_i64())),
             (PropertyKey::SmallString(s), PropertyKey::Integer(n)) => {
                 Self::is_str_eq_num(s.as_str(), n.into_i64())
             }
@@ -256,10 +255,10 @@ impl core::fmt::Display for DisplayablePropertyKey<'_, '_, '_> {
         match self.key {
             PropertyKey::Integer(data) => data.into_i64().fmt(f),
             PropertyKey::SmallString(data) => data.as_str().fmt(f),
-            PropertyKey::String(data) => data.as_str(self.agent).fmt(f),
+            PropertyKey::String(data) => self.agent[*data].as_wtf8().to_string_lossy().fmt(f),
             PropertyKey::Symbol(data) => {
                 if let Some(descriptor) = self.agent[*data].descriptor {
-                    let descriptor = descriptor.as_str(self.agent);
+                    let descriptor = descriptor.to_string_lossy(self.agent);
                     f.debug_tuple("Symbol").field(&descriptor).finish()
                 } else {
                     "Symbol()".fmt(f)
@@ -459,3 +458,115 @@ fn compare_num_str() {
     assert!(!PropertyKey::is_str_eq_num("19", 91));
     assert!(!PropertyKey::is_str_eq_num("-19", 19));
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        ecmascript::{
+            execution::{Agent, DefaultHostHooks, agent::Options, initialize_default_realm},
+            types::{InternalMethods, IntoValue, Object, PropertyKey, String, Value},
+        },
+        engine::context::Bindable,
+    };
+
+    #[test]
+    fn static_property_keys_are_identical() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let first = PropertyKey::from_static_str(agent, "prop", gc.nogc());
+        let second = PropertyKey::from_static_str(agent, "prop", gc.nogc());
+        assert_eq!(first, second);
+
+        let first = PropertyKey::from_static_str(agent, "a long property name", gc.nogc());
+        let second = PropertyKey::from_static_str(agent, "a long property name", gc.nogc());
+        assert_eq!(first, second);
+        // Keys created from other strings with the same contents are the
+        // same key as well.
+        let owned = std::string::String::from("a long property name");
+        let third = PropertyKey::from_string(agent, owned, gc.nogc());
+        assert_eq!(first, third);
+
+        let index = PropertyKey::from_static_str(agent, "12", gc.nogc());
+        assert_eq!(index, PropertyKey::from(12u32));
+    }
+
+    #[test]
+    fn static_property_keys_survive_garbage_collection() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let key = PropertyKey::from_static_str(agent, "a long property name", gc.nogc()).unbind();
+        let source_text = String::from_static_str(agent, "globalThis", gc.nogc());
+        let global = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap()
+            .unbind();
+        let global = Object::try_from(global).unwrap();
+        global
+            .internal_set(
+                agent,
+                key,
+                Value::from(42),
+                global.into_value(),
+                gc.reborrow(),
+            )
+            .unwrap();
+
+        agent.gc(gc.reborrow());
+
+        let key = PropertyKey::from_static_str(agent, "a long property name", gc.nogc()).unbind();
+        let source_text = String::from_static_str(agent, "globalThis", gc.nogc());
+        let global = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap()
+            .unbind();
+        let global = Object::try_from(global).unwrap();
+        let value = global
+            .internal_get(agent, key, global.into_value(), gc.reborrow())
+            .unwrap()
+            .unbind();
+        assert_eq!(value, Value::from(42));
+    }
+
+    #[test]
+    fn strings_are_interned_after_many_strings_are_created() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let key = PropertyKey::from_static_str(agent, "a long property name", gc.nogc()).unbind();
+        // Enough strings for the string lookup table to grow several times.
+        let source_text = String::from_static_str(
+            agent,
+            "var names = [];
+            for (let i = 0; i < 10000; i++) names.push(`a long name ${i}`);
+            globalThis['a long property name'] = names.length;
+            typeof Object.getOwnPropertyNames === 'function' &&
+                JSON.stringify({ 'a long name 9999': 1 }) === '{\"a long name 9999\":1}' &&
+                names.indexOf('a long name ' + 5000) === 5000",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap()
+            .unbind();
+        assert_eq!(result, Value::Boolean(true));
+        let source_text = String::from_static_str(agent, "globalThis", gc.nogc());
+        let global = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap()
+            .unbind();
+        let global = Object::try_from(global).unwrap();
+        let value = global
+            .internal_get(agent, key, global.into_value(), gc.reborrow())
+            .unwrap()
+            .unbind();
+        assert_eq!(value, Value::from(10000));
+    }
+}
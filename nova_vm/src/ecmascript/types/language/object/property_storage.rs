@@ -15,15 +15,17 @@ use crate::{
     },
     engine::context::{Bindable, NoGcScope},
     heap::{
+        OwnKeysCache,
         element_array::{
-            ElementArrays, ElementDescriptor, PropertyStorageMut, PropertyStorageRef,
+            ElementArrayKey, ElementArrays, ElementDescriptor, ElementStorageMut,
+            ElementStorageRef, ElementsVector, PropertyStorageMut, PropertyStorageRef,
             PropertyStorageUninit, PropertyStorageVector,
         },
         indexes::ElementIndex,
     },
 };
 
-use super::{IntoObject, Object, OrdinaryObject, PropertyKey};
+use super::{IntoObject, Object, ObjectHeapData, OrdinaryObject, PropertyKey};
 
 #[derive(Debug, Clone, Copy)]
 pub struct PropertyStorage<'a>(OrdinaryObject<'a>);
@@ -61,6 +63,7 @@ impl<'a> PropertyStorage<'a> {
     /// Adds an uninitialized PrivateName field to the object.
     pub(crate) fn add_private_field_slot(self, agent: &mut Agent, private_name: PrivateName) {
         let object = self.0;
+        agent.heap.write_barrier(object);
         let Heap {
             elements,
             objects,
@@ -90,6 +93,7 @@ impl<'a> PropertyStorage<'a> {
             .current_private_environment(gc)
             .expect("Expected PrivateEnvironment to be set");
         let object = self.0;
+        agent.heap.write_barrier(object);
         let Heap {
             environments,
             elements,
@@ -318,7 +322,13 @@ impl<'a> PropertyStorage<'a> {
 
     pub fn get(self, agent: &Agent, key: PropertyKey) -> Option<PropertyDescriptor<'a>> {
         let object = self.0;
-        let props = &agent[object].property_storage;
+        let data = &agent[object];
+        if let Some((value, descriptor)) =
+            get_indexed_element(&agent.heap.elements, &data.indexed_elements, key)
+        {
+            return Some(ElementDescriptor::to_property_descriptor(descriptor, value));
+        }
+        let props = &data.property_storage;
         let result = props
             .keys(agent)
             .iter()
@@ -332,19 +342,66 @@ impl<'a> PropertyStorage<'a> {
         })
     }
 
+    /// Invalidate the cached keys of the object.
+    pub(crate) fn invalidate_keys(self, agent: &mut Agent) {
+        let Heap {
+            objects,
+            own_keys_cache,
+            ..
+        } = &mut agent.heap;
+        keys_changed(&mut objects[self.0], own_keys_cache);
+    }
+
+    /// Reserve room for at least `additional` more non-index properties, so
+    /// that adding them does not grow the storage one step at a time.
+    pub(crate) fn reserve(self, agent: &mut Agent, additional: u32) {
+        let object = self.0;
+        agent.heap.write_barrier(object);
+        let Heap {
+            elements, objects, ..
+        } = &mut agent.heap;
+        let props = &mut objects[object].property_storage;
+        let new_len = props.len().saturating_add(additional);
+        props.reserve(elements, new_len);
+    }
+
     pub fn set(self, agent: &mut Agent, key: PropertyKey, descriptor: PropertyDescriptor) {
         let object = self.0;
+        agent.heap.write_barrier(object);
         let Heap {
             elements,
             objects,
+            own_keys_cache,
             alloc_counter,
             ..
         } = &mut agent.heap;
-        let props = &mut objects[object].property_storage;
+        let data = &mut objects[object];
 
         let value = descriptor.value;
         let element_descriptor = ElementDescriptor::from_property_descriptor(descriptor);
+        let is_enumerable = element_descriptor.is_none_or(|d| d.is_enumerable());
+
+        let array_index = array_index(key);
+        if let Some(index) = array_index {
+            if let Some((_, old_descriptor)) =
+                get_indexed_element(elements, &data.indexed_elements, key)
+            {
+                let was_enumerable = old_descriptor.is_none_or(|d| d.is_enumerable());
+                write_indexed_element(
+                    elements,
+                    &data.indexed_elements,
+                    index,
+                    value,
+                    element_descriptor,
+                );
+                if was_enumerable != is_enumerable {
+                    keys_changed(data, own_keys_cache);
+                }
+                return;
+            }
+        }
 
+        let props = &mut data.property_storage;
         let result = props
             .keys(elements)
             .iter()
@@ -352,25 +409,51 @@ impl<'a> PropertyStorage<'a> {
             .find(|(_, k)| **k == key)
             .map(|res| res.0);
         if let Some(index) = result {
+            let was_enumerable = elements
+                .get_descriptor(props, index)
+                .is_none_or(|d| d.is_enumerable());
             let key_entry = props.keys_mut(elements).get_mut(index).unwrap();
             *key_entry = Some(key.unbind());
             let value_entry = props.values_mut(elements).get_mut(index).unwrap();
             *value_entry = value.unbind();
             elements.set_descriptor(props, index, element_descriptor);
+            if was_enumerable != is_enumerable {
+                keys_changed(data, own_keys_cache);
+            }
+        } else if array_index.is_some_and(|index| {
+            push_indexed_element(elements, data, index, value, element_descriptor)
+        }) {
+            *alloc_counter += core::mem::size_of::<Option<Value>>();
+            keys_changed(data, own_keys_cache);
         } else {
             *alloc_counter += core::mem::size_of::<Option<Value>>() * 2;
-            props.push(elements, key, value, element_descriptor);
+            data.property_storage
+                .push(elements, key, value, element_descriptor);
+            keys_changed(data, own_keys_cache);
         };
     }
 
     pub fn remove(self, agent: &mut Agent, key: PropertyKey) {
         let object = self.0;
 
+        agent.heap.write_barrier(object);
         let Heap {
-            elements, objects, ..
+            elements,
+            objects,
+            own_keys_cache,
+            ..
         } = &mut agent.heap;
-        let props = &mut objects[object].property_storage;
+        let data = &mut objects[object];
+
+        if let Some(index) = array_index(key) {
+            if get_indexed_element(elements, &data.indexed_elements, key).is_some() {
+                remove_indexed_element(elements, &mut data.indexed_elements, index);
+                keys_changed(data, own_keys_cache);
+                return;
+            }
+        }
 
+        let props = &mut data.property_storage;
         let result = props
             .keys(elements)
             .iter()
@@ -379,7 +462,184 @@ impl<'a> PropertyStorage<'a> {
             .map(|res| res.0);
         if let Some(index) = result {
             props.remove(elements, index);
+            keys_changed(data, own_keys_cache);
+        }
+    }
+}
+
+/// Number of array index properties an object must accumulate in its property
+/// storage before they are moved into its indexed elements.
+const INDEXED_ELEMENTS_THRESHOLD: u32 = 8;
+
+/// Returns the array index that `key` represents, if any.
+pub(crate) fn array_index(key: PropertyKey) -> Option<u32> {
+    let PropertyKey::Integer(key) = key else {
+        return None;
+    };
+    let key = key.into_i64();
+    if (0..u32::MAX as i64).contains(&key) {
+        Some(key as u32)
+    } else {
+        None
+    }
+}
+
+/// Returns true if indexed elements of length `len` may grow to store `index`
+/// without turning mostly into holes.
+fn fits_indexed_elements(len: u32, index: u32) -> bool {
+    index < len.saturating_mul(2).max(INDEXED_ELEMENTS_THRESHOLD)
+}
+
+/// Get the value and descriptor of an array index property stored in the
+/// indexed elements of an object.
+///
+/// Returns None if the key is not an array index or if it is a hole in the
+/// indexed elements; the property may then still exist in the property
+/// storage.
+pub(crate) fn get_indexed_element(
+    elements: &ElementArrays,
+    indexed_elements: &ElementsVector,
+    key: PropertyKey,
+) -> Option<(Option<Value<'static>>, Option<ElementDescriptor<'static>>)> {
+    let index = array_index(key)?;
+    let ElementStorageRef {
+        values,
+        descriptors,
+    } = elements.get_element_storage(indexed_elements);
+    let value = *values.get(index as usize)?;
+    let descriptor = descriptors.and_then(|d| d.get(&index)).copied();
+    if value.is_none() && descriptor.is_none() {
+        // No value, no descriptor: That's a hole.
+        return None;
+    }
+    Some((value, descriptor))
+}
+
+pub(crate) fn has_indexed_element(
+    elements: &ElementArrays,
+    indexed_elements: &ElementsVector,
+    key: PropertyKey,
+) -> bool {
+    get_indexed_element(elements, indexed_elements, key).is_some()
+}
+
+fn write_indexed_element(
+    elements: &mut ElementArrays,
+    indexed_elements: &ElementsVector,
+    index: u32,
+    value: Option<Value>,
+    descriptor: Option<ElementDescriptor>,
+) {
+    let ElementStorageMut { values, .. } = indexed_elements.get_storage_mut(elements);
+    values[index as usize] = value.unbind();
+    elements.set_descriptor(indexed_elements, index as usize, descriptor);
+}
+
+/// Adds a new array index property to the indexed elements of an object.
+///
+/// Once the object has accumulated enough array index properties in its
+/// property storage, the dense ones are first moved into the indexed
+/// elements. Returns false if the property was not added because the index is
+/// too sparse to store in the indexed elements.
+fn push_indexed_element(
+    elements: &mut ElementArrays,
+    data: &mut ObjectHeapData,
+    index: u32,
+    value: Option<Value>,
+    descriptor: Option<ElementDescriptor>,
+) -> bool {
+    let ObjectHeapData {
+        property_storage: props,
+        indexed_elements,
+        ..
+    } = data;
+    if indexed_elements.cap == ElementArrayKey::Empty {
+        let count = props
+            .keys(elements)
+            .iter()
+            .filter(|k| array_index(**k).is_some())
+            .count() as u32;
+        if count + 1 < INDEXED_ELEMENTS_THRESHOLD {
+            return false;
+        }
+        let limit = (count + 1).saturating_mul(2);
+        if index >= limit {
+            return false;
+        }
+        // Move the dense array index properties over, in order of their
+        // position in the property storage.
+        let moved = props
+            .keys(elements)
+            .iter()
+            .enumerate()
+            .filter_map(|(position, k)| {
+                array_index(*k)
+                    .filter(|i| *i < limit)
+                    .map(|i| (i, position))
+            })
+            .collect::<Vec<_>>();
+        let len = moved.iter().map(|(i, _)| *i).max().unwrap_or(0).max(index) + 1;
+        indexed_elements.reserve(elements, len);
+        while indexed_elements.len() < len {
+            indexed_elements.push(elements, None, None);
+        }
+        for &(i, position) in moved.iter() {
+            let value = props.values(elements)[position].unbind();
+            let descriptor = elements.get_descriptor(props, position).unbind();
+            write_indexed_element(elements, indexed_elements, i, value, descriptor);
+        }
+        for &(_, position) in moved.iter().rev() {
+            props.remove(elements, position);
         }
+        write_indexed_element(elements, indexed_elements, index, value, descriptor);
+        return true;
+    }
+    if index < indexed_elements.len() {
+        // Filling in a hole.
+        write_indexed_element(elements, indexed_elements, index, value, descriptor);
+        return true;
+    }
+    if !fits_indexed_elements(indexed_elements.len(), index) {
+        return false;
+    }
+    indexed_elements.reserve(elements, index + 1);
+    while indexed_elements.len() < index {
+        indexed_elements.push(elements, None, None);
+    }
+    indexed_elements.push(elements, value, descriptor);
+    true
+}
+
+/// Removes an array index property from the indexed elements of an object,
+/// leaving a hole in its place. Holes at the end are dropped.
+fn remove_indexed_element(
+    elements: &mut ElementArrays,
+    indexed_elements: &mut ElementsVector,
+    index: u32,
+) {
+    write_indexed_element(elements, indexed_elements, index, None, None);
+    if index + 1 != indexed_elements.len() {
+        return;
+    }
+    let ElementStorageRef {
+        values,
+        descriptors,
+    } = elements.get_element_storage(indexed_elements);
+    indexed_elements.len = values
+        .iter()
+        .enumerate()
+        .rposition(|(i, value)| {
+            value.is_some() || descriptors.is_some_and(|d| d.contains_key(&(i as u32)))
+        })
+        .map_or(0, |i| i as u32 + 1);
+}
+
+/// Invalidates the cached property keys of an object.
+fn keys_changed(data: &mut ObjectHeapData, own_keys_cache: &mut OwnKeysCache) {
+    data.keys_version = data.keys_version.wrapping_add(1);
+    if data.keys_version == 0 {
+        // The version wrapped around; an old cache entry could match it again.
+        own_keys_cache.clear();
     }
 }
 
@@ -393,3 +653,165 @@ impl<'a> Entries<'a> {
         Self { realm }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        ecmascript::{
+            execution::{
+                DefaultHostHooks,
+                agent::{GcAgent, Options},
+            },
+            scripts_and_modules::script::test::evaluate,
+            types::Value,
+        },
+        engine::context::Bindable,
+    };
+
+    fn assert_all_true(agent: &mut GcAgent, sources: &[&'static str]) {
+        let realm = agent.create_default_realm();
+        agent.run_in_realm(&realm, |agent, mut gc| {
+            for source in sources {
+                let result = evaluate(agent, source, gc.reborrow()).unbind();
+                assert_eq!(result, Value::Boolean(true), "{source}");
+            }
+        });
+        agent.remove_realm(realm);
+    }
+
+    #[test]
+    fn many_integer_keys_are_ordered_ascending_first() {
+        let mut agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+        let realm = agent.create_default_realm();
+        agent.run_in_realm(&realm, |agent, mut gc| {
+            evaluate(
+                agent,
+                "var symbol = Symbol('s');
+                var object = { b: 'b' };
+                object[symbol] = 'symbol';
+                // Insert the integer keys in a scrambled order.
+                for (let i = 0; i < 1000; i++) {
+                    const key = (i * 7919) % 1000;
+                    object[key] = key * 2;
+                }
+                object.a = 'a';
+                object[4294967295] = 'not an array index';
+                object[-1] = 'negative';",
+                gc.reborrow(),
+            );
+        });
+        // The properties survive a garbage collection.
+        agent.gc();
+        agent.run_in_realm(&realm, |agent, mut gc| {
+            let sources = [
+                "(() => {
+                    const keys = Reflect.ownKeys(object);
+                    for (let i = 0; i < 1000; i++) {
+                        if (keys[i] !== `${i}`) return false;
+                    }
+                    return keys.slice(1000).map(String).join() ===
+                        'b,a,4294967295,-1,Symbol(s)';
+                })()",
+                "Object.keys(object).length === 1004 &&
+                    Object.keys(object).slice(995).join() === '995,996,997,998,999,b,a,4294967295,-1'",
+                "(() => {
+                    let i = 0;
+                    for (const key in object) {
+                        if (i < 1000 && key !== `${i}`) return false;
+                        i++;
+                    }
+                    return i === 1004;
+                })()",
+                "Object.values(object).slice(0, 1000).every((value, i) => value === i * 2)",
+                "JSON.stringify(object).startsWith('{\"0\":0,\"1\":2,\"2\":4,')",
+                "object[999] === 1998 && object['500'] === 1000 && object[symbol] === 'symbol'",
+                "object.hasOwnProperty(0) && 999 in object && !(1000 in object)",
+            ];
+            for source in sources {
+                let result = evaluate(agent, source, gc.reborrow()).unbind();
+                assert_eq!(result, Value::Boolean(true), "{source}");
+            }
+        });
+        agent.remove_realm(realm);
+    }
+
+    #[test]
+    fn integer_key_properties_keep_their_attributes() {
+        let mut agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+        assert_all_true(
+            &mut agent,
+            &[
+                "var object = {};
+                for (let i = 0; i < 100; i++) object[i] = i;
+                delete object[10];
+                delete object[99];
+                Object.keys(object).length === 98 && !(10 in object) && object[10] === undefined &&
+                    !(99 in object)",
+                "object[10] = 'again'; object[99] = 'again';
+                Object.keys(object)[10] === '10' && Object.keys(object).at(-1) === '99'",
+                "Object.defineProperty(object, 20, { value: 'hidden', enumerable: false });
+                object[20] === 'hidden' && !Object.keys(object).includes('20') &&
+                    Reflect.ownKeys(object)[20] === '20'",
+                "Object.defineProperty(object, 30, { get() { return 'getter'; }, configurable: true });
+                object[30] === 'getter' && Object.getOwnPropertyDescriptor(object, 30).set === undefined",
+                "object[150] = 'grown'; object[100000] = 'sparse';
+                Reflect.ownKeys(object).slice(-3).join() === '99,150,100000'",
+                "Object.freeze(object);
+                (() => { 'use strict'; try { object[5] = 'x'; } catch (err) { return err instanceof TypeError; } })() &&
+                    object[5] === 5 && Object.isFrozen(object)",
+                "const copy = { ...object };
+                Object.keys(copy).length === Object.keys(object).length && copy[150] === 'grown' &&
+                    !('20' in copy)",
+            ],
+        );
+    }
+
+    #[test]
+    fn integer_keys_are_not_stored_as_property_keys() {
+        /// Number of property keys that the property key storages of the heap
+        /// have room for.
+        fn property_key_slots(agent: &GcAgent) -> usize {
+            agent
+                .heap_vector_statistics()
+                .iter()
+                .filter_map(|statistics| {
+                    let exponent = statistics.name.strip_prefix("k2pow")?.parse::<u32>().ok()?;
+                    Some(statistics.slots << exponent)
+                })
+                .sum()
+        }
+
+        let mut agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+        let realm = agent.create_default_realm();
+        let before = property_key_slots(&agent);
+        agent.run_in_realm(&realm, |agent, mut gc| {
+            evaluate(
+                agent,
+                "var object = { name: 'array-like' };
+                for (let i = 0; i < 1000; i++) object[i] = i;
+                object.length = 1000;",
+                gc.reborrow(),
+            );
+        });
+        let after = property_key_slots(&agent);
+        // Only the string keys and the first few array index keys, before the
+        // object had accumulated many of them, need property key storage.
+        assert!(
+            after - before < 100,
+            "property key storage for {} keys was allocated",
+            after - before
+        );
+        agent.run_in_realm(&realm, |agent, mut gc| {
+            assert_eq!(
+                evaluate(
+                    agent,
+                    "Object.keys(object).length === 1002 && Object.keys(object)[999] === '999' &&
+                        Object.keys(object).slice(-2).join() === 'name,length'",
+                    gc.reborrow(),
+                ),
+                Value::Boolean(true)
+            );
+        });
+        agent.remove_realm(realm);
+    }
+}
@@ -3,12 +3,16 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 include!(concat!(env!("OUT_DIR"), "/builtin_strings.rs"));
+mod builder;
 mod data;
+mod search;
 
 use core::{
+    cmp::Ordering,
     hash::Hash,
     ops::{Index, IndexMut},
 };
+use std::{borrow::Cow, fmt::Write};
 
 use super::{
     IntoPrimitive, IntoValue, Primitive, PropertyKey, SMALL_STRING_DISCRIMINANT,
@@ -16,7 +20,10 @@ use super::{
 };
 use crate::{
     SmallInteger, SmallString,
-    ecmascript::{execution::Agent, types::PropertyDescriptor},
+    ecmascript::{
+        execution::{Agent, JsResult},
+        types::PropertyDescriptor,
+    },
     engine::{
         Scoped,
         context::{Bindable, NoGcScope},
@@ -28,8 +35,13 @@ use crate::{
     },
 };
 
+pub(crate) use builder::HeapStringBuilder;
 pub use data::StringHeapData;
-use wtf8::Wtf8Buf;
+use search::{
+    can_match_inside_surrogate_pair, find_code_units, find_substring, is_code_point_boundary,
+    rfind_code_units, rfind_substring,
+};
+use wtf8::{CodePoint, Wtf8, Wtf8Buf};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(transparent)]
@@ -94,6 +106,7 @@ impl Index<HeapString<'_>> for Agent {
 
 impl IndexMut<HeapString<'_>> for Agent {
     fn index_mut(&mut self, index: HeapString<'_>) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.strings[index]
     }
 }
@@ -269,8 +282,10 @@ impl<'a> String<'a> {
         strings: impl AsRef<[Self]>,
         gc: NoGcScope<'gc, '_>,
     ) -> String<'gc> {
-        // TODO: This function will need heavy changes once we support creating
-        // WTF-8 strings, since WTF-8 concatenation isn't byte concatenation.
+        // NOTE: Heap strings may contain lone surrogates, and WTF-8
+        // concatenation joins a trailing lead surrogate and a leading trail
+        // surrogate into a single code point. SmallStrings are always UTF-8,
+        // so concatenating them is plain byte concatenation.
 
         // We use this status enum so we can reuse one of the heap string inputs
         // if the output would be identical, and so we don't allocate at all
@@ -329,10 +344,9 @@ impl<'a> String<'a> {
                 }
                 Status::SmallString { data, len } => {
                     let string_len = string.len(agent);
-                    if *len + string_len <= 7 {
-                        let String::SmallString(smstr) = string else {
-                            unreachable!()
-                        };
+                    // NOTE: Heap strings containing lone surrogates may be
+                    // short enough to fit but cannot become SmallStrings.
+                    if let (String::SmallString(smstr), true) = (string, *len + string_len <= 7) {
                         data[*len..(*len + string_len)]
                             .copy_from_slice(&smstr.data()[..string_len]);
                         *len += string_len;
@@ -358,10 +372,24 @@ impl<'a> String<'a> {
                 let str_slice = unsafe { core::str::from_utf8_unchecked(&data[..len]) };
                 SmallString::from_str_unchecked(str_slice).into()
             }
-            Status::String(string) => agent.heap.create(string.into_string().unwrap()).bind(gc),
+            Status::String(string) => agent.heap.create(string).bind(gc),
         }
     }
 
+    /// Concatenates the strings like [`String::concat`], but throws a
+    /// RangeError instead if the result would exceed the maximum string
+    /// length of the Agent.
+    pub(crate) fn concat_or_throw<'gc>(
+        agent: &mut Agent,
+        strings: impl AsRef<[Self]>,
+        gc: NoGcScope<'gc, '_>,
+    ) -> JsResult<'gc, String<'gc>> {
+        let strings = strings.as_ref();
+        let len = strings.iter().fold(0usize, |a, s| a + s.len(agent));
+        agent.check_string_length(len, gc)?;
+        Ok(Self::concat(agent, strings, gc))
+    }
+
     /// Byte length of the string.
     pub fn len(self, agent: &impl Index<HeapString<'static>, Output = StringHeapData>) -> usize {
         match self {
@@ -393,6 +421,62 @@ impl<'a> String<'a> {
         }
     }
 
+    /// Returns the UTF-16 code unit at the given UTF-16 index. Indexing into
+    /// a surrogate pair returns the leading or trailing surrogate, and lone
+    /// surrogates are returned as-is.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `idx` is not less than the UTF-16 length.
+    pub fn utf16_code_unit(
+        self,
+        agent: &impl Index<HeapString<'static>, Output = StringHeapData>,
+        idx: usize,
+    ) -> u16 {
+        match self {
+            String::String(s) => agent[s.unbind()].utf16_code_unit(idx),
+            String::SmallString(s) => s.utf16_code_unit(idx),
+        }
+    }
+
+    /// ### [11.1.4 CodePointAt ( string, position )](https://tc39.es/ecma262/#sec-codepointat)
+    ///
+    /// Returns the code point starting at the given UTF-16 index, and the
+    /// number of code units it spans. Lone surrogates are returned as
+    /// surrogate code points.
+    pub fn code_point_at(
+        self,
+        agent: &impl Index<HeapString<'static>, Output = StringHeapData>,
+        position: usize,
+    ) -> (CodePoint, usize) {
+        // 1. Let size be the length of string.
+        let size = self.utf16_len(agent);
+        // 2. Assert: position ≥ 0 and position < size.
+        debug_assert!(position < size);
+        // 3. Let first be the code unit at index position within string.
+        let first = self.utf16_code_unit(agent, position);
+        // 4. Let cp be the code point whose numeric value is the numeric value of first.
+        let cp = CodePoint::from_u32(first as u32).unwrap();
+        // 5. If first is neither a leading surrogate nor a trailing surrogate, then
+        // a. Return the Record { [[CodePoint]]: cp, [[CodeUnitCount]]: 1, [[IsUnpairedSurrogate]]: false }.
+        // 6. If first is a trailing surrogate or position + 1 = size, then
+        // a. Return the Record { [[CodePoint]]: cp, [[CodeUnitCount]]: 1, [[IsUnpairedSurrogate]]: true }.
+        if !(0xD800..=0xDBFF).contains(&first) || position + 1 == size {
+            return (cp, 1);
+        }
+        // 7. Let second be the code unit at index position + 1 within string.
+        let second = self.utf16_code_unit(agent, position + 1);
+        // 8. If second is not a trailing surrogate, then
+        if !(0xDC00..=0xDFFF).contains(&second) {
+            // a. Return the Record { [[CodePoint]]: cp, [[CodeUnitCount]]: 1, [[IsUnpairedSurrogate]]: true }.
+            return (cp, 1);
+        }
+        // 9. Set cp to UTF16SurrogatePairToCodePoint(first, second).
+        let cp = 0x10000 + ((first as u32 - 0xD800) << 10) + (second as u32 - 0xDC00);
+        // 10. Return the Record { [[CodePoint]]: cp, [[CodeUnitCount]]: 2, [[IsUnpairedSurrogate]]: false }.
+        (CodePoint::from_u32(cp).unwrap(), 2)
+    }
+
     /// Returns the corresponding UTF-8 index for a UTF-16 index into the
     /// string, or `None` if the UTF-16 index is the second code unit in a
     /// surrogate pair.
@@ -430,6 +514,233 @@ impl<'a> String<'a> {
         }
     }
 
+    /// Returns the UTF-8 index of the first code point boundary at or after
+    /// a UTF-16 index into the string.
+    fn utf8_index_rounding_up(
+        self,
+        agent: &impl Index<HeapString<'static>, Output = StringHeapData>,
+        utf16_idx: usize,
+    ) -> usize {
+        self.utf8_index(agent, utf16_idx)
+            .unwrap_or_else(|| self.utf8_index(agent, utf16_idx + 1).unwrap())
+    }
+
+    /// Returns the UTF-8 index of the last code point boundary at or before
+    /// a UTF-16 index into the string.
+    fn utf8_index_rounding_down(
+        self,
+        agent: &impl Index<HeapString<'static>, Output = StringHeapData>,
+        utf16_idx: usize,
+    ) -> usize {
+        self.utf8_index(agent, utf16_idx)
+            .unwrap_or_else(|| self.utf8_index(agent, utf16_idx - 1).unwrap())
+    }
+
+    /// Returns the substring of the String from the UTF-16 index `from` up
+    /// to, but not including, the UTF-16 index `to`.
+    ///
+    /// Indices that fall inside a surrogate pair split the pair, leaving a
+    /// lone surrogate at the start or end of the result.
+    pub fn substring<'gc>(
+        self,
+        agent: &mut Agent,
+        from: usize,
+        to: usize,
+        gc: NoGcScope<'gc, '_>,
+    ) -> String<'gc> {
+        if from >= to {
+            return String::EMPTY_STRING;
+        }
+        if from == 0 && to == self.utf16_len(agent) {
+            return self.bind(gc);
+        }
+        let wtf8 = self.as_wtf8(agent);
+        let buf = match (self.utf8_index(agent, from), self.utf8_index(agent, to)) {
+            (Some(utf8_from), Some(utf8_to)) => {
+                let mut buf = Wtf8Buf::with_capacity(utf8_to - utf8_from);
+                buf.push_wtf8(wtf8.slice(utf8_from, utf8_to));
+                buf
+            }
+            _ => {
+                let code_units = wtf8
+                    .to_ill_formed_utf16()
+                    .skip(from)
+                    .take(to - from)
+                    .collect::<Vec<_>>();
+                Wtf8Buf::from_ill_formed_utf16(&code_units)
+            }
+        };
+        String::from_wtf8_buf(agent, buf, gc)
+    }
+
+    /// ### [6.1.4.1 StringIndexOf ( string, searchValue, fromIndex )](https://tc39.es/ecma262/#sec-stringindexof)
+    ///
+    /// Returns the UTF-16 index of the first occurrence of `search_value` at
+    /// or after the UTF-16 index `from_index`, or `None` if there is none.
+    pub(crate) fn index_of(
+        self,
+        agent: &impl Index<HeapString<'static>, Output = StringHeapData>,
+        search_value: Self,
+        from_index: usize,
+    ) -> Option<usize> {
+        // 1. Let len be the length of string.
+        let len = self.utf16_len(agent);
+        // 2. If searchValue is the empty String and fromIndex ≤ len, return
+        //    fromIndex.
+        if search_value.is_empty_string() {
+            return (from_index <= len).then_some(from_index);
+        }
+        if from_index >= len {
+            return None;
+        }
+        // 3. Let searchLen be the length of searchValue.
+        // 4. For each integer i such that fromIndex ≤ i ≤ len - searchLen, in
+        //    ascending order, do
+        // a. Let candidate be the substring of string from i to i + searchLen.
+        // b. If candidate is searchValue, return i.
+        let needle = search_value.as_wtf8_bytes(agent);
+        if can_match_inside_surrogate_pair(needle) {
+            let haystack = self.code_units(agent);
+            let needle = search_value.code_units(agent);
+            return find_code_units(&haystack[from_index..], &needle)
+                .map(|index| from_index + index);
+        }
+        // NOTE: Otherwise a match cannot start in the middle of a surrogate
+        // pair.
+        let utf8_from = self.utf8_index_rounding_up(agent, from_index);
+        let utf8_index = find_substring(&self.as_wtf8_bytes(agent)[utf8_from..], needle)?;
+        Some(self.utf16_index(agent, utf8_from + utf8_index))
+        // 5. Return not-found.
+    }
+
+    /// ### [6.1.4.2 StringLastIndexOf ( string, searchValue, fromIndex )](https://tc39.es/ecma262/#sec-stringlastindexof)
+    ///
+    /// Returns the UTF-16 index of the last occurrence of `search_value` at
+    /// or before the UTF-16 index `from_index`, or `None` if there is none.
+    /// `from_index` is clamped so that the match fits in the string.
+    pub(crate) fn last_index_of(
+        self,
+        agent: &impl Index<HeapString<'static>, Output = StringHeapData>,
+        search_value: Self,
+        from_index: usize,
+    ) -> Option<usize> {
+        // 1. Let len be the length of string.
+        let len = self.utf16_len(agent);
+        // 2. Let searchLen be the length of searchValue.
+        let search_len = search_value.utf16_len(agent);
+        // 3. Assert: fromIndex + searchLen ≤ len.
+        let from_index = from_index.min(len.checked_sub(search_len)?);
+        if search_len == 0 {
+            return Some(from_index);
+        }
+        // 4. For each integer i such that 0 ≤ i ≤ fromIndex, in descending
+        //    order, do
+        // a. Let candidate be the substring of string from i to i + searchLen.
+        // b. If candidate is searchValue, return i.
+        let needle = search_value.as_wtf8_bytes(agent);
+        if can_match_inside_surrogate_pair(needle) {
+            let haystack = self.code_units(agent);
+            let needle = search_value.code_units(agent);
+            return rfind_code_units(&haystack[..from_index + search_len], &needle);
+        }
+        // NOTE: Otherwise a match cannot start in the middle of a surrogate
+        // pair.
+        let haystack = self.as_wtf8_bytes(agent);
+        let mut utf8_end =
+            (self.utf8_index_rounding_down(agent, from_index) + needle.len()).min(haystack.len());
+        // Any match ends at a code point boundary.
+        while !is_code_point_boundary(haystack, utf8_end) {
+            utf8_end -= 1;
+        }
+        let utf8_index = rfind_substring(&haystack[..utf8_end], needle)?;
+        Some(self.utf16_index(agent, utf8_index))
+        // 5. Return not-found.
+    }
+
+    /// Returns true if `search_value` occurs in the string at the UTF-16
+    /// index `start`, clamped to the string length.
+    pub(crate) fn starts_with_at(
+        self,
+        agent: &impl Index<HeapString<'static>, Output = StringHeapData>,
+        search_value: Self,
+        start: usize,
+    ) -> bool {
+        let len = self.utf16_len(agent);
+        let start = start.min(len);
+        let search_len = search_value.utf16_len(agent);
+        if search_len == 0 {
+            return true;
+        }
+        if start + search_len > len {
+            return false;
+        }
+        let needle = search_value.as_wtf8_bytes(agent);
+        if can_match_inside_surrogate_pair(needle) {
+            return self.code_units_match_at(agent, search_value, start);
+        }
+        // A substring starting in the middle of a surrogate pair starts with a
+        // lone trailing surrogate, which the search value cannot match.
+        let Some(utf8_start) = self.utf8_index(agent, start) else {
+            return false;
+        };
+        self.as_wtf8_bytes(agent)[utf8_start..].starts_with(needle)
+    }
+
+    /// Returns true if `search_value` occurs in the string ending at the
+    /// UTF-16 index `end`, clamped to the string length.
+    pub(crate) fn ends_with_at(
+        self,
+        agent: &impl Index<HeapString<'static>, Output = StringHeapData>,
+        search_value: Self,
+        end: usize,
+    ) -> bool {
+        let end = end.min(self.utf16_len(agent));
+        let search_len = search_value.utf16_len(agent);
+        if search_len == 0 {
+            return true;
+        }
+        if search_len > end {
+            return false;
+        }
+        let needle = search_value.as_wtf8_bytes(agent);
+        if can_match_inside_surrogate_pair(needle) {
+            return self.code_units_match_at(agent, search_value, end - search_len);
+        }
+        // A substring ending in the middle of a surrogate pair ends with a
+        // lone leading surrogate, which the search value cannot match.
+        let Some(utf8_end) = self.utf8_index(agent, end) else {
+            return false;
+        };
+        self.as_wtf8_bytes(agent)[..utf8_end].ends_with(needle)
+    }
+
+    /// Returns true if the UTF-16 code units of `search_value` occur in the
+    /// string at the UTF-16 index `start`.
+    fn code_units_match_at(
+        self,
+        agent: &impl Index<HeapString<'static>, Output = StringHeapData>,
+        search_value: Self,
+        start: usize,
+    ) -> bool {
+        (0..search_value.utf16_len(agent)).all(|i| {
+            self.utf16_code_unit(agent, start + i) == search_value.utf16_code_unit(agent, i)
+        })
+    }
+
+    /// Returns the UTF-16 code units of the string.
+    fn code_units(
+        self,
+        agent: &impl Index<HeapString<'static>, Output = StringHeapData>,
+    ) -> Vec<u16> {
+        self.as_wtf8(agent).to_ill_formed_utf16().collect()
+    }
+
+    /// Returns the string as UTF-8.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the string contains lone surrogates. Use
+    /// [`String::as_wtf8`] to access such strings.
     pub fn as_str<'string, 'agent: 'string>(
         &'string self,
         agent: &'agent impl Index<HeapString<'static>, Output = StringHeapData>,
@@ -440,6 +751,80 @@ impl<'a> String<'a> {
         }
     }
 
+    /// Returns the string as WTF-8, which can represent lone surrogates.
+    pub fn as_wtf8<'string, 'agent: 'string>(
+        &'string self,
+        agent: &'agent impl Index<HeapString<'static>, Output = StringHeapData>,
+    ) -> &'string Wtf8 {
+        match self {
+            String::String(s) => agent[s.unbind()].as_wtf8(),
+            String::SmallString(s) => Wtf8::from_str(s.as_str()),
+        }
+    }
+
+    /// Returns the WTF-8 bytes of the string.
+    pub(crate) fn as_wtf8_bytes<'string, 'agent: 'string>(
+        &'string self,
+        agent: &'agent impl Index<HeapString<'static>, Output = StringHeapData>,
+    ) -> &'string [u8] {
+        match self {
+            String::String(s) => agent[s.unbind()].as_wtf8_bytes(),
+            String::SmallString(s) => s.as_bytes(),
+        }
+    }
+
+    /// Returns the string as UTF-8, with any lone surrogates replaced by
+    /// U+FFFD (REPLACEMENT CHARACTER). This is meant for messages and
+    /// diagnostics, not for ECMAScript semantics.
+    pub fn to_string_lossy<'string, 'agent: 'string>(
+        &'string self,
+        agent: &'agent impl Index<HeapString<'static>, Output = StringHeapData>,
+    ) -> Cow<'string, str> {
+        self.as_wtf8(agent).to_string_lossy()
+    }
+
+    /// Returns the string as UTF-8, with any lone surrogates replaced by
+    /// their `\uXXXX` escape sequences.
+    pub(crate) fn escape_lone_surrogates<'string, 'agent: 'string>(
+        &'string self,
+        agent: &'agent impl Index<HeapString<'static>, Output = StringHeapData>,
+    ) -> Cow<'string, str> {
+        let wtf8 = self.as_wtf8(agent);
+        if let Some(str) = wtf8.as_str() {
+            return Cow::Borrowed(str);
+        }
+        let mut escaped = std::string::String::with_capacity(wtf8.len() + 6);
+        for code_point in wtf8.code_points() {
+            match code_point.to_char() {
+                Some(char) => escaped.push(char),
+                None => {
+                    let _ = write!(escaped, "\\u{:04X}", code_point.to_u32());
+                }
+            }
+        }
+        Cow::Owned(escaped)
+    }
+
+    /// Compares x and y by the numeric values of their code units, as in
+    /// [IsLessThan](https://tc39.es/ecma262/#sec-islessthan) and
+    /// Array.prototype.sort.
+    ///
+    /// > NOTE: UTF-8 byte order differs from UTF-16 code unit order for code
+    /// > points above U+FFFF, which sort after U+E000..U+FFFF in UTF-8 but
+    /// > before them in UTF-16.
+    pub(crate) fn cmp_code_units(
+        agent: &impl Index<HeapString<'static>, Output = StringHeapData>,
+        x: Self,
+        y: Self,
+    ) -> Ordering {
+        let x = x.as_wtf8(agent);
+        let y = y.as_wtf8(agent);
+        if x.as_str().is_some_and(str::is_ascii) && y.as_str().is_some_and(str::is_ascii) {
+            return x.cmp(y);
+        }
+        x.to_ill_formed_utf16().cmp(y.to_ill_formed_utf16())
+    }
+
     /// If x and y have the same length and the same code units in the same
     /// positions, return true; otherwise, return false.
     pub fn eq(
@@ -479,9 +864,9 @@ impl<'a> String<'a> {
         } else if let PropertyKey::Integer(index) = property_key {
             let index = index.into_i64();
             if index >= 0 && (index as usize) < self.utf16_len(agent) {
-                let ch = self.utf16_char(agent, index as usize);
+                let cu = self.utf16_code_unit(agent, index as usize);
                 Some(PropertyDescriptor {
-                    value: Some(SmallString::from_code_point(ch).into_value()),
+                    value: Some(String::from_code_unit(agent, cu).into_value()),
                     writable: Some(false),
                     get: None,
                     set: None,
@@ -508,8 +893,8 @@ impl<'a> String<'a> {
         } else if let PropertyKey::Integer(index) = property_key {
             let index = index.into_i64();
             if index >= 0 && (index as usize) < self.utf16_len(agent) {
-                let ch = self.utf16_char(agent, index as usize);
-                Some(SmallString::from_code_point(ch).into_value())
+                let cu = self.utf16_code_unit(agent, index as usize);
+                Some(String::from_code_unit(agent, cu).into_value())
             } else {
                 None
             }
@@ -532,6 +917,24 @@ impl<'gc> String<'gc> {
         agent.heap.create(string).bind(gc)
     }
 
+    /// Creates a String from WTF-8 data that may contain lone surrogates.
+    /// Well-formed data that fits is stored as a SmallString.
+    pub fn from_wtf8_buf(agent: &mut Agent, buf: Wtf8Buf, gc: NoGcScope<'gc, '_>) -> Self {
+        agent.heap.create(buf).bind(gc)
+    }
+
+    /// Creates a String consisting of a single UTF-16 code unit, which may
+    /// be a lone surrogate.
+    pub fn from_code_unit(agent: &mut Agent, code_unit: u16) -> Self {
+        if let Some(ch) = char::from_u32(code_unit as u32) {
+            SmallString::from_code_point(ch).into()
+        } else {
+            let mut buf = Wtf8Buf::with_capacity(3);
+            buf.push(CodePoint::from_u32(code_unit as u32).unwrap());
+            agent.heap.create(buf)
+        }
+    }
+
     pub fn from_static_str(agent: &mut Agent, str: &'static str, _gc: NoGcScope<'gc, '_>) -> Self {
         if let Ok(value) = String::try_from(str) {
             value
@@ -578,8 +981,13 @@ impl<'a> CreateHeapData<(StringHeapData, u64), String<'a>> for Heap {
         let index = StringIndex::last(&self.strings);
         let heap_string = HeapString(index);
         self.alloc_counter += core::mem::size_of::<HeapString>();
+        let strings = &self.strings;
+        let string_hasher = &self.string_hasher;
+        // Note: Growing the table rehashes the strings already in it.
         self.string_lookup_table
-            .insert_unique(hash, heap_string, |_| hash);
+            .insert_unique(hash, heap_string, |heap_string| {
+                string_hasher.hash_one(strings[heap_string.get_index()].as_ref().unwrap().as_wtf8())
+            });
         String::String(heap_string)
     }
 }
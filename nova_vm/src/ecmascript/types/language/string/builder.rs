@@ -0,0 +1,390 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use wtf8::{CodePoint, Wtf8, Wtf8Buf};
+
+use super::{String, data::IndexMapping};
+use crate::{
+    ecmascript::execution::Agent,
+    engine::context::{Bindable, NoGcScope},
+    heap::CreateHeapData,
+};
+
+/// Byte length of the blocks of copies appended by
+/// [`HeapStringBuilder::push_repeated`].
+const REPEAT_BLOCK_LENGTH: usize = 4096;
+
+/// Builds a String out of many pieces, such as the result of JSON.stringify
+/// or Array.prototype.join, without creating intermediate Strings.
+///
+/// The pieces are copied into a single growable buffer outside of the heap,
+/// and the String is allocated once by [`HeapStringBuilder::finish`]. As
+/// appended Strings are copied immediately, the builder holds no references
+/// into the heap: it may be held across calls into ECMAScript code and
+/// garbage collection without rooting anything.
+#[derive(Debug)]
+pub(crate) struct HeapStringBuilder {
+    buffer: Buffer,
+    /// True if all the appended code points are ASCII, in which case the
+    /// finished String does not need to be scanned for its UTF-16 length.
+    is_ascii: bool,
+}
+
+#[derive(Debug)]
+enum Buffer {
+    /// The content is well-formed UTF-8.
+    Utf8(std::string::String),
+    /// The content contains, or once contained, lone surrogates.
+    Wtf8(Wtf8Buf),
+}
+
+impl Default for HeapStringBuilder {
+    fn default() -> Self {
+        Self::with_capacity(0)
+    }
+}
+
+impl HeapStringBuilder {
+    /// Create a builder with room for `capacity` bytes of UTF-8.
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Self {
+            buffer: Buffer::Utf8(std::string::String::with_capacity(capacity)),
+            is_ascii: true,
+        }
+    }
+
+    /// Byte length of the content, as checked against the maximum string
+    /// length by [`Agent::check_string_length`].
+    pub(crate) fn len(&self) -> usize {
+        match &self.buffer {
+            Buffer::Utf8(buffer) => buffer.len(),
+            Buffer::Wtf8(buffer) => buffer.len(),
+        }
+    }
+
+    /// Reserve room for at least `additional` more bytes.
+    pub(crate) fn reserve(&mut self, additional: usize) {
+        match &mut self.buffer {
+            Buffer::Utf8(buffer) => buffer.reserve(additional),
+            Buffer::Wtf8(buffer) => buffer.reserve(additional),
+        }
+    }
+
+    pub(crate) fn push_str(&mut self, str: &str) {
+        self.is_ascii &= str.is_ascii();
+        match &mut self.buffer {
+            Buffer::Utf8(buffer) => buffer.push_str(str),
+            Buffer::Wtf8(buffer) => buffer.push_str(str),
+        }
+    }
+
+    /// Append WTF-8 content, which may contain lone surrogates.
+    pub(crate) fn push_wtf8(&mut self, wtf8: &Wtf8) {
+        match wtf8.as_str() {
+            Some(str) => self.push_str(str),
+            None => {
+                self.is_ascii = false;
+                self.wtf8_buffer().push_wtf8(wtf8);
+            }
+        }
+    }
+
+    pub(crate) fn push_char(&mut self, char: char) {
+        self.is_ascii &= char.is_ascii();
+        match &mut self.buffer {
+            Buffer::Utf8(buffer) => buffer.push(char),
+            Buffer::Wtf8(buffer) => buffer.push_char(char),
+        }
+    }
+
+    /// Append a code point, which may be a lone surrogate.
+    pub(crate) fn push_code_point(&mut self, code_point: CodePoint) {
+        if let Some(char) = code_point.to_char() {
+            self.push_char(char);
+        } else {
+            self.is_ascii = false;
+            self.wtf8_buffer().push(code_point);
+        }
+    }
+
+    /// Append the contents of a String.
+    ///
+    /// A lone trailing surrogate at the start of the String joins a lone
+    /// leading surrogate at the end of the content into a code point, as
+    /// their UTF-16 code units would.
+    pub(crate) fn push_string(&mut self, agent: &Agent, string: String) {
+        match string {
+            String::SmallString(string) => self.push_str(string.as_str()),
+            String::String(string) => {
+                let data = &agent[string];
+                let wtf8 = data.as_wtf8();
+                match wtf8.as_str() {
+                    Some(str) => {
+                        self.is_ascii &= matches!(data.mapping.get(), Some(IndexMapping::Ascii))
+                            || str.is_ascii();
+                        match &mut self.buffer {
+                            Buffer::Utf8(buffer) => buffer.push_str(str),
+                            Buffer::Wtf8(buffer) => buffer.push_str(str),
+                        }
+                    }
+                    None => {
+                        self.is_ascii = false;
+                        self.wtf8_buffer().push_wtf8(wtf8);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Append the code units of a String from the UTF-16 index `from` up to,
+    /// but not including, the UTF-16 index `to`.
+    ///
+    /// Indices that fall inside a surrogate pair split the pair, appending a
+    /// lone surrogate, like [`String::substring`].
+    pub(crate) fn push_substring(
+        &mut self,
+        agent: &Agent,
+        string: String,
+        mut from: usize,
+        mut to: usize,
+    ) {
+        if from >= to {
+            return;
+        }
+        let lone_code_unit =
+            |index| CodePoint::from_u32(string.utf16_code_unit(agent, index).into());
+        if string.utf8_index(agent, from).is_none() {
+            self.push_code_point(lone_code_unit(from).unwrap());
+            from += 1;
+        }
+        let trailing = if from < to && string.utf8_index(agent, to).is_none() {
+            to -= 1;
+            lone_code_unit(to)
+        } else {
+            None
+        };
+        if from < to {
+            let utf8_from = string.utf8_index(agent, from).unwrap();
+            let utf8_to = string.utf8_index(agent, to).unwrap();
+            self.push_wtf8(string.as_wtf8(agent).slice(utf8_from, utf8_to));
+        }
+        if let Some(trailing) = trailing {
+            self.push_code_point(trailing);
+        }
+    }
+
+    /// Append the contents of a String `count` times.
+    pub(crate) fn push_repeated(&mut self, agent: &Agent, string: String, count: usize) {
+        let Some(additional) = string.len(agent).checked_mul(count) else {
+            panic!("String is too long.");
+        };
+        self.reserve(additional);
+        let str = match string.as_wtf8(agent).as_str() {
+            Some(str) if !str.is_empty() && count > 1 => str,
+            _ => {
+                for _ in 0..count {
+                    self.push_string(agent, string);
+                }
+                return;
+            }
+        };
+        // Short strings are appended a block of copies at a time. This is
+        // only done for UTF-8, as copies of WTF-8 content may join lone
+        // surrogates into code points at their seams.
+        let block_count = (REPEAT_BLOCK_LENGTH / str.len()).clamp(1, count);
+        let block = str.repeat(block_count);
+        for _ in 0..count / block_count {
+            self.push_str(&block);
+        }
+        self.push_str(&block[..str.len() * (count % block_count)]);
+    }
+
+    /// Create the String with the built content, as a SmallString if it fits
+    /// in one.
+    pub(crate) fn finish<'gc>(self, agent: &mut Agent, gc: NoGcScope<'gc, '_>) -> String<'gc> {
+        let string = match self.buffer {
+            Buffer::Utf8(buffer) => agent.heap.create(buffer),
+            Buffer::Wtf8(buffer) => agent.heap.create(buffer),
+        };
+        if let (String::String(string), true) = (string, self.is_ascii) {
+            // Note: An equal String may already exist, in which case its
+            // mapping may already be set.
+            let _ = agent[string].mapping.set(IndexMapping::Ascii);
+        }
+        string.bind(gc)
+    }
+
+    fn wtf8_buffer(&mut self) -> &mut Wtf8Buf {
+        if let Buffer::Utf8(buffer) = &mut self.buffer {
+            self.buffer = Buffer::Wtf8(Wtf8Buf::from_string(core::mem::take(buffer)));
+        }
+        let Buffer::Wtf8(buffer) = &mut self.buffer else {
+            unreachable!()
+        };
+        buffer
+    }
+}
+
+impl core::fmt::Write for HeapStringBuilder {
+    fn write_str(&mut self, str: &str) -> core::fmt::Result {
+        self.push_str(str);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        ecmascript::{
+            execution::{Agent, DefaultHostHooks, agent::Options, initialize_default_realm},
+            scripts_and_modules::script::test::evaluate,
+            types::{String, Value},
+        },
+        engine::context::{Bindable, GcScope},
+    };
+
+    fn evaluate_to_string(
+        agent: &mut Agent,
+        source: &'static str,
+        gc: GcScope,
+    ) -> std::string::String {
+        let result = evaluate(agent, source, gc);
+        let Ok(result) = String::try_from(result) else {
+            panic!("{source} did not evaluate to a String");
+        };
+        result.as_str(agent).to_owned()
+    }
+
+    #[test]
+    fn built_strings_match_previous_output() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let cases: [(&'static str, &str); 8] = [
+            (
+                r#"JSON.stringify({ a: [1, "two", null, true], b: { c: 1.5 } })"#,
+                r#"{"a":[1,"two",null,true],"b":{"c":1.5}}"#,
+            ),
+            (
+                r#"JSON.stringify({ a: [1, { b: [] }], c: {} }, null, 2)"#,
+                "{\n  \"a\": [\n    1,\n    {\n      \"b\": []\n    }\n  ],\n  \"c\": {}\n}",
+            ),
+            (
+                r#"JSON.stringify("\b\t\n\f\r\"\\\u0001\u001f")"#,
+                r#""\b\t\n\f\r\"\\\u0001\u001f""#,
+            ),
+            (r#"JSON.stringify({ "é": "😀" })"#, r#"{"é":"😀"}"#),
+            (r#"JSON.stringify({ 1: 2, [Symbol()]: 3 })"#, r#"{"1":2}"#),
+            (
+                r#"[1, "a", null, undefined, [2, [3]], 4.5].join()"#,
+                "1,a,,,2,3,4.5",
+            ),
+            (r#"["a", "b", "c"].join(" — ")"#, "a — b — c"),
+            (r#""ab".repeat(3) + "é".repeat(2)"#, "abababéé"),
+        ];
+        for (source, expected) in cases {
+            assert_eq!(
+                evaluate_to_string(agent, source, gc.reborrow()),
+                expected,
+                "{source}"
+            );
+        }
+    }
+
+    #[test]
+    fn built_strings_keep_lone_surrogates() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let cases: [&'static str; 6] = [
+            r#"JSON.stringify("\ud800x") === '"\\ud800x"'"#,
+            r#"JSON.stringify(["😀", "\udc00"]) === '["😀","\\udc00"]'"#,
+            r#"["a\ud800", "\udc00b"].join("") === "a𐀀b""#,
+            r#"["a", "b"].join("\ud83d").charCodeAt(1) === 0xd83d"#,
+            r#""\ud800".repeat(3).length === 3"#,
+            r#""𐀀" === "\ud800".concat("\udc00") && "\ud800".repeat(2) !== "𐀀""#,
+        ];
+        for source in cases {
+            let result = evaluate(agent, source, gc.reborrow()).unbind();
+            assert_eq!(result, Value::Boolean(true), "{source}");
+        }
+    }
+
+    #[test]
+    fn non_ascii_built_strings_index_by_code_unit() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let result = evaluate(
+            agent,
+            r#"
+            var joined = ["abcdefgh", "é", "😀", "ijklmnop"].join("");
+            var json = JSON.stringify(["é", "😀"]);
+            joined.length === 19 &&
+                joined.charCodeAt(8) === 0xe9 &&
+                joined.charCodeAt(9) === 0xd83d &&
+                joined.codePointAt(9) === 0x1f600 &&
+                joined[11] === "i" &&
+                joined.indexOf("ijk") === 11 &&
+                joined.slice(8, 11) === "é😀" &&
+                json.length === 10 &&
+                json[6] === "\ud83d" &&
+                json.indexOf("]") === 9
+            "#,
+            gc.reborrow(),
+        )
+        .unbind();
+        assert_eq!(result, Value::Boolean(true));
+    }
+
+    #[test]
+    fn join_allocates_one_string() {
+        let mut agent = Agent::new(
+            Options {
+                disable_gc: true,
+                ..Default::default()
+            },
+            &DefaultHostHooks,
+        );
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        evaluate(
+            agent,
+            r#"
+            var items = [];
+            for (var i = 0; i < 10000; i++) items.push("item number " + i);
+            var joined, concatenated;
+            "#,
+            gc.reborrow(),
+        );
+        let before = agent.heap_statistics().heap_entries;
+        evaluate(agent, "joined = items.join();", gc.reborrow());
+        let join_entries = agent.heap_statistics().heap_entries - before;
+
+        let before = agent.heap_statistics().heap_entries;
+        evaluate(
+            agent,
+            r#"
+            concatenated = items[0];
+            for (var i = 1; i < items.length; i++) concatenated += "," + items[i];
+            "#,
+            gc.reborrow(),
+        );
+        let concatenation_entries = agent.heap_statistics().heap_entries - before;
+
+        let equal = evaluate(agent, "joined === concatenated", gc.reborrow()).unbind();
+        assert_eq!(equal, Value::Boolean(true));
+        // Joining only allocates the result, next to the script being run.
+        assert!(join_entries < 20, "{join_entries}");
+        assert!(concatenation_entries >= 10000, "{concatenation_entries}");
+    }
+}
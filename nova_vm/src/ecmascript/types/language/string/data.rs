@@ -4,7 +4,7 @@
 
 use core::{cell::OnceCell, hash::Hash, num::NonZeroUsize};
 
-use wtf8::{Wtf8, Wtf8Buf};
+use wtf8::{CodePoint, Wtf8, Wtf8Buf};
 
 use crate::heap::{CompactionLists, HeapMarkAndSweep, WorkQueues};
 
@@ -77,9 +77,9 @@ impl StringHeapData {
 
     fn index_mapping(&self) -> &IndexMapping {
         self.mapping.get_or_init(|| {
-            let mut iter = self.as_str().char_indices();
+            let mut iter = code_point_indices(self.as_wtf8());
 
-            let Some((idx, ch)) = iter.find(|(_, ch)| !ch.is_ascii()) else {
+            let Some((idx, cp)) = iter.find(|(_, cp)| cp.to_u32() >= 0x80) else {
                 return IndexMapping::Ascii;
             };
 
@@ -87,16 +87,14 @@ impl StringHeapData {
             // indices less *or equal* than `idx` map to that same UTF-8 index
             let mut mapping: Vec<Option<NonZeroUsize>> = (0..=idx).map(NonZeroUsize::new).collect();
 
-            if ch.len_utf16() != 1 {
-                debug_assert_eq!(ch.len_utf16(), 2);
+            if code_point_utf16_len(cp) != 1 {
                 mapping.push(None);
             }
 
-            for (idx, ch) in iter {
+            for (idx, cp) in iter {
                 assert_ne!(idx, 0);
                 mapping.push(NonZeroUsize::new(idx));
-                if ch.len_utf16() != 1 {
-                    debug_assert_eq!(ch.len_utf16(), 2);
+                if code_point_utf16_len(cp) != 1 {
                     mapping.push(None);
                 }
             }
@@ -132,12 +130,50 @@ impl StringHeapData {
         } else {
             0
         };
-        let ch = self.as_str()[utf8_idx..].chars().next().unwrap();
+        let ch = self.code_point_at_utf8_index(utf8_idx).to_char().unwrap();
         // TODO: Deal with surrogates.
         assert_eq!(ch.len_utf16(), 1);
         ch
     }
 
+    /// Returns the UTF-16 code unit at the given UTF-16 index. Indexing into
+    /// a surrogate pair returns the leading or trailing surrogate, and lone
+    /// surrogates are returned as-is.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `idx` is not less than the UTF-16 length.
+    pub fn utf16_code_unit(&self, idx: usize) -> u16 {
+        let (utf8_idx, is_trailing_surrogate) = match self.index_mapping() {
+            IndexMapping::Ascii => return self.as_wtf8().ascii_byte_at(idx) as u16,
+            IndexMapping::NonAscii { mapping } => match (idx, mapping[idx]) {
+                (0, _) => (0, false),
+                (_, Some(utf8_idx)) => (utf8_idx.get(), false),
+                // The second code unit of a surrogate pair maps to the code
+                // point starting at the previous UTF-16 index.
+                (1, None) => (0, true),
+                (_, None) => (mapping[idx - 1].unwrap().get(), true),
+            },
+        };
+        let cp = self.code_point_at_utf8_index(utf8_idx).to_u32();
+        if cp < 0x10000 {
+            debug_assert!(!is_trailing_surrogate);
+            cp as u16
+        } else {
+            let mut buf = [0; 2];
+            let ch = char::from_u32(cp).unwrap();
+            ch.encode_utf16(&mut buf)[is_trailing_surrogate as usize]
+        }
+    }
+
+    fn code_point_at_utf8_index(&self, utf8_idx: usize) -> CodePoint {
+        self.as_wtf8()
+            .slice_from(utf8_idx)
+            .code_points()
+            .next()
+            .unwrap()
+    }
+
     pub fn utf8_index(&self, utf16_idx: usize) -> Option<usize> {
         if utf16_idx == 0 {
             Some(0)
@@ -220,6 +256,13 @@ impl StringHeapData {
         }
     }
 
+    /// Returns the string as UTF-8.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the string contains lone surrogates, which
+    /// cannot be represented in UTF-8. Use [`StringHeapData::as_wtf8`] to
+    /// access such strings.
     pub fn as_str(&self) -> &str {
         match &self.data {
             StringBuffer::Owned(buf) => buf.as_str().unwrap(),
@@ -234,6 +277,14 @@ impl StringHeapData {
         }
     }
 
+    /// Returns the WTF-8 bytes of the string.
+    pub(crate) fn as_wtf8_bytes(&self) -> &[u8] {
+        let wtf8 = self.as_wtf8();
+        // SAFETY: Wtf8 only wraps the slice of its bytes; the wtf8 crate
+        // itself creates a Wtf8 by transmuting such a slice.
+        unsafe { &*(wtf8 as *const Wtf8 as *const [u8]) }
+    }
+
     pub fn from_str(str: &str) -> Self {
         debug_assert!(str.len() > 7);
         assert!(str.len() <= Self::MAX_UTF8_LENGTH, "String is too long.");
@@ -260,6 +311,44 @@ impl StringHeapData {
             mapping: OnceCell::new(),
         }
     }
+
+    /// Creates string data from a WTF-8 buffer that may contain lone
+    /// surrogates.
+    pub fn from_wtf8_buf(buf: Wtf8Buf) -> Self {
+        // NOTE: SmallStrings are always UTF-8, so strings with lone surrogates
+        // are stored on the heap regardless of their length.
+        debug_assert!(buf.len() > 7 || buf.as_str().is_none());
+        assert!(buf.len() <= Self::MAX_UTF8_LENGTH, "String is too long.");
+        StringHeapData {
+            data: StringBuffer::Owned(buf),
+            mapping: OnceCell::new(),
+        }
+    }
+}
+
+/// Returns the number of bytes needed to encode the code point in WTF-8.
+fn code_point_utf8_len(cp: CodePoint) -> usize {
+    match cp.to_u32() {
+        0..0x80 => 1,
+        0x80..0x800 => 2,
+        0x800..0x10000 => 3,
+        _ => 4,
+    }
+}
+
+/// Returns the number of UTF-16 code units needed to encode the code point.
+fn code_point_utf16_len(cp: CodePoint) -> usize {
+    if cp.to_u32() < 0x10000 { 1 } else { 2 }
+}
+
+/// Iterates over the code points of a WTF-8 string along with the byte index
+/// at which each code point starts.
+fn code_point_indices(wtf8: &Wtf8) -> impl Iterator<Item = (usize, CodePoint)> {
+    wtf8.code_points().scan(0, |next_idx, cp| {
+        let idx = *next_idx;
+        *next_idx += code_point_utf8_len(cp);
+        Some((idx, cp))
+    })
 }
 
 impl HeapMarkAndSweep for StringHeapData {
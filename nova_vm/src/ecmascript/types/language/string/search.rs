@@ -0,0 +1,444 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Substring search over the WTF-8 bytes or the UTF-16 code units of
+//! strings.
+//!
+//! Searching the WTF-8 bytes reports byte indexes: callers map those to and
+//! from UTF-16 code unit indexes at the API boundary. As both the haystack
+//! and the needle are well-formed WTF-8, a match can only start and end at
+//! code point boundaries, so a needle that can match half of a surrogate pair
+//! (see [`can_match_inside_surrogate_pair`]) is searched for in the UTF-16
+//! code units instead.
+//!
+//! Single byte needles are searched for a word at a time. Longer needles use
+//! the Two-Way algorithm of Crochemore and Perrin, which runs in linear time
+//! and constant space whatever the structure of the needle: a naive scan
+//! takes quadratic time on needles with long repeated prefixes such as
+//! `"aaaa…ab"` in `"aaaa…a"`.
+
+const WORD: usize = size_of::<usize>();
+const LOW_BITS: usize = usize::from_ne_bytes([0x01; WORD]);
+const HIGH_BITS: usize = usize::from_ne_bytes([0x80; WORD]);
+
+/// Returns the index of the first occurrence of `needle` in `haystack`.
+pub(crate) fn find_substring(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    match needle {
+        [] => Some(0),
+        &[byte] => memchr(byte, haystack),
+        needle => two_way(haystack, needle),
+    }
+}
+
+/// Returns the index of the last occurrence of `needle` in `haystack`.
+pub(crate) fn rfind_substring(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    match needle {
+        [] => Some(haystack.len()),
+        &[byte] => memrchr(byte, haystack),
+        needle => two_way(Reversed(haystack), Reversed(needle))
+            .map(|index| haystack.len() - index - needle.len()),
+    }
+}
+
+/// Returns the index of the first occurrence of `needle` in the UTF-16 code
+/// units `haystack`.
+pub(crate) fn find_code_units(haystack: &[u16], needle: &[u16]) -> Option<usize> {
+    match needle {
+        [] => Some(0),
+        &[unit] => haystack.iter().position(|&u| u == unit),
+        needle => two_way(haystack, needle),
+    }
+}
+
+/// Returns the index of the last occurrence of `needle` in the UTF-16 code
+/// units `haystack`.
+pub(crate) fn rfind_code_units(haystack: &[u16], needle: &[u16]) -> Option<usize> {
+    match needle {
+        [] => Some(haystack.len()),
+        &[unit] => haystack.iter().rposition(|&u| u == unit),
+        needle => two_way(Reversed(haystack), Reversed(needle))
+            .map(|index| haystack.len() - index - needle.len()),
+    }
+}
+
+/// True if the WTF-8 `needle` starts with a lone trailing surrogate or ends
+/// with a lone leading surrogate. Such a needle can match the second or the
+/// first code unit of a surrogate pair, which its WTF-8 bytes cannot.
+pub(crate) fn can_match_inside_surrogate_pair(needle: &[u8]) -> bool {
+    // Surrogates are encoded as 0xED 0xA0..=0xBF 0x80..=0xBF, with leading
+    // surrogates below 0xB0 in the second byte.
+    let starts_with_trailing = matches!(needle, [0xED, 0xB0..=0xBF, _, ..]);
+    let ends_with_leading = matches!(needle, [.., 0xED, 0xA0..=0xAF, _]);
+    starts_with_trailing || ends_with_leading
+}
+
+/// True if the byte index `index` of the WTF-8 `bytes` is at a code point
+/// boundary.
+pub(crate) fn is_code_point_boundary(bytes: &[u8], index: usize) -> bool {
+    bytes.get(index).is_none_or(|&byte| byte & 0xC0 != 0x80)
+}
+
+/// True if any byte of `word` is zero.
+#[inline(always)]
+fn has_zero_byte(word: usize) -> bool {
+    word.wrapping_sub(LOW_BITS) & !word & HIGH_BITS != 0
+}
+
+#[inline(always)]
+fn read_word(chunk: &[u8]) -> usize {
+    usize::from_ne_bytes(chunk.try_into().unwrap())
+}
+
+/// Returns the index of the first `byte` in `haystack`.
+fn memchr(byte: u8, haystack: &[u8]) -> Option<usize> {
+    let repeated = usize::from_ne_bytes([byte; WORD]);
+    let mut start = 0;
+    for chunk in haystack.chunks_exact(WORD) {
+        if has_zero_byte(read_word(chunk) ^ repeated) {
+            break;
+        }
+        start += WORD;
+    }
+    haystack[start..]
+        .iter()
+        .position(|&b| b == byte)
+        .map(|index| start + index)
+}
+
+/// Returns the index of the last `byte` in `haystack`.
+fn memrchr(byte: u8, haystack: &[u8]) -> Option<usize> {
+    let repeated = usize::from_ne_bytes([byte; WORD]);
+    let mut end = haystack.len();
+    for chunk in haystack.rchunks_exact(WORD) {
+        if has_zero_byte(read_word(chunk) ^ repeated) {
+            break;
+        }
+        end -= WORD;
+    }
+    haystack[..end].iter().rposition(|&b| b == byte)
+}
+
+/// A sequence of bytes or code units searched by [`two_way`].
+///
+/// Searching backwards is searching forwards over reversed haystack and
+/// needle, so the same implementation serves both directions.
+trait Units: Copy {
+    type Unit: Copy + Ord;
+
+    fn len(&self) -> usize;
+
+    fn at(&self, index: usize) -> Self::Unit;
+}
+
+impl<T: Copy + Ord> Units for &[T] {
+    type Unit = T;
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        <[T]>::len(self)
+    }
+
+    #[inline(always)]
+    fn at(&self, index: usize) -> T {
+        self[index]
+    }
+}
+
+struct Reversed<'a, T>(&'a [T]);
+
+impl<T> Clone for Reversed<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Reversed<'_, T> {}
+
+impl<T: Copy + Ord> Units for Reversed<'_, T> {
+    type Unit = T;
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    #[inline(always)]
+    fn at(&self, index: usize) -> T {
+        self.0[self.0.len() - 1 - index]
+    }
+}
+
+/// Computes the maximal suffix of `needle` under the unit order, or under the
+/// reversed unit order if `reversed_order` is true. Returns the start index
+/// of the suffix and its period.
+fn maximal_suffix(needle: impl Units, reversed_order: bool) -> (usize, usize) {
+    let mut start = 0;
+    let mut candidate = 1;
+    let mut offset = 0;
+    let mut period = 1;
+    while candidate + offset < needle.len() {
+        let a = needle.at(candidate + offset);
+        let b = needle.at(start + offset);
+        if a == b {
+            // Advance through a repetition of the current period.
+            if offset + 1 == period {
+                candidate += period;
+                offset = 0;
+            } else {
+                offset += 1;
+            }
+        } else if (a < b) != reversed_order {
+            // The candidate suffix is smaller: the period is everything from
+            // the start of the suffix so far.
+            candidate += offset + 1;
+            offset = 0;
+            period = candidate - start;
+        } else {
+            // The candidate suffix is larger: start over from it.
+            start = candidate;
+            candidate += 1;
+            offset = 0;
+            period = 1;
+        }
+    }
+    (start, period)
+}
+
+/// Returns the index of the first occurrence of `needle` in `haystack`.
+/// `needle` must not be empty.
+fn two_way<T: Units>(haystack: T, needle: impl Units<Unit = T::Unit>) -> Option<usize> {
+    let needle_len = needle.len();
+    debug_assert!(needle_len > 0);
+    let last_position = haystack.len().checked_sub(needle_len)?;
+
+    // Critical factorization: the needle is split at the later of the two
+    // maximal suffixes.
+    let (start_less, period_less) = maximal_suffix(needle, false);
+    let (start_greater, period_greater) = maximal_suffix(needle, true);
+    let (critical_position, period) = if start_less > start_greater {
+        (start_less, period_less)
+    } else {
+        (start_greater, period_greater)
+    };
+
+    let mut position = 0;
+    if critical_position + period <= needle_len
+        && (0..critical_position).all(|i| needle.at(i) == needle.at(i + period))
+    {
+        // The needle is periodic: after shifting by the period, the prefix of
+        // the needle known to match the haystack already is remembered so
+        // that it is not compared again.
+        let mut memory = 0;
+        while position <= last_position {
+            let mut i = critical_position.max(memory);
+            while i < needle_len && needle.at(i) == haystack.at(position + i) {
+                i += 1;
+            }
+            if i < needle_len {
+                position += i - critical_position + 1;
+                memory = 0;
+                continue;
+            }
+            let mut j = critical_position;
+            while j > memory && needle.at(j - 1) == haystack.at(position + j - 1) {
+                j -= 1;
+            }
+            if j <= memory {
+                return Some(position);
+            }
+            position += period;
+            memory = needle_len - period;
+        }
+    } else {
+        // The halves of the needle do not overlap, so a mismatch in the left
+        // half allows shifting past the longer half entirely.
+        let shift = critical_position.max(needle_len - critical_position) + 1;
+        while position <= last_position {
+            let mut i = critical_position;
+            while i < needle_len && needle.at(i) == haystack.at(position + i) {
+                i += 1;
+            }
+            if i < needle_len {
+                position += i - critical_position + 1;
+                continue;
+            }
+            let mut j = critical_position;
+            while j > 0 && needle.at(j - 1) == haystack.at(position + j - 1) {
+                j -= 1;
+            }
+            if j == 0 {
+                return Some(position);
+            }
+            position += shift;
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use core::cell::Cell;
+
+    use super::{
+        Units, can_match_inside_surrogate_pair, find_code_units, find_substring, rfind_code_units,
+        rfind_substring, two_way,
+    };
+
+    /// Counts the bytes read from the haystack.
+    #[derive(Clone, Copy)]
+    struct Counting<'a> {
+        bytes: &'a [u8],
+        reads: &'a Cell<usize>,
+    }
+
+    impl Units for Counting<'_> {
+        type Unit = u8;
+
+        fn len(&self) -> usize {
+            self.bytes.len()
+        }
+
+        fn at(&self, index: usize) -> u8 {
+            self.reads.set(self.reads.get() + 1);
+            self.bytes[index]
+        }
+    }
+
+    fn naive_find(haystack: &[u8], needle: &[u8], reads: &Cell<usize>) -> Option<usize> {
+        (0..=haystack.len().checked_sub(needle.len())?).find(|&position| {
+            needle.iter().enumerate().all(|(i, &byte)| {
+                reads.set(reads.get() + 1);
+                haystack[position + i] == byte
+            })
+        })
+    }
+
+    #[test]
+    fn repeated_prefix_needle_is_searched_in_linear_time() {
+        let mut haystack = "a".repeat(100_000);
+        let needle = format!("{}b", "a".repeat(1_000));
+
+        let reads = Cell::new(0);
+        let result = two_way(
+            Counting {
+                bytes: haystack.as_bytes(),
+                reads: &reads,
+            },
+            needle.as_bytes(),
+        );
+        assert_eq!(result, None);
+        let two_way_reads = reads.get();
+        assert!(two_way_reads <= 2 * haystack.len());
+
+        let reads = Cell::new(0);
+        assert_eq!(
+            naive_find(haystack.as_bytes(), needle.as_bytes(), &reads),
+            None
+        );
+        assert!(reads.get() > 100 * two_way_reads);
+
+        haystack.push('b');
+        assert_eq!(
+            find_substring(haystack.as_bytes(), needle.as_bytes()),
+            Some(haystack.len() - needle.len())
+        );
+        assert_eq!(
+            rfind_substring(haystack.as_bytes(), needle.as_bytes()),
+            Some(haystack.len() - needle.len())
+        );
+    }
+
+    #[test]
+    fn find_matches_std() {
+        let haystacks = [
+            "",
+            "a",
+            "abracadabra",
+            "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaab",
+            "abababababababababababababab",
+            "The quick brown fox jumps over the lazy dog",
+            "grüße, 😀 und 😀😀!",
+        ];
+        let needles = [
+            "",
+            "a",
+            "b",
+            "r",
+            "ab",
+            "abra",
+            "aab",
+            "abab",
+            "bab",
+            "ba",
+            "aaab",
+            "o",
+            "the",
+            "dog",
+            "fox jumps",
+            "ü",
+            "😀",
+            "😀😀",
+            "😀!",
+            "zzz",
+        ];
+        for haystack in haystacks {
+            for needle in needles {
+                assert_eq!(
+                    find_substring(haystack.as_bytes(), needle.as_bytes()),
+                    haystack.find(needle),
+                    "{haystack:?}.find({needle:?})"
+                );
+                assert_eq!(
+                    rfind_substring(haystack.as_bytes(), needle.as_bytes()),
+                    haystack.rfind(needle),
+                    "{haystack:?}.rfind({needle:?})"
+                );
+                let haystack_units = haystack.encode_utf16().collect::<Vec<_>>();
+                let needle_units = needle.encode_utf16().collect::<Vec<_>>();
+                let to_utf16_index = |index: usize| haystack[..index].encode_utf16().count();
+                assert_eq!(
+                    find_code_units(&haystack_units, &needle_units),
+                    haystack.find(needle).map(to_utf16_index),
+                    "{haystack:?}.find({needle:?}) in UTF-16"
+                );
+                assert_eq!(
+                    rfind_code_units(&haystack_units, &needle_units),
+                    haystack.rfind(needle).map(to_utf16_index),
+                    "{haystack:?}.rfind({needle:?}) in UTF-16"
+                );
+            }
+        }
+        // Single byte needles at every offset around the word size.
+        for len in 0..40 {
+            let mut haystack = "x".repeat(len);
+            assert_eq!(find_substring(haystack.as_bytes(), b"y"), None);
+            assert_eq!(rfind_substring(haystack.as_bytes(), b"y"), None);
+            for index in 0..len {
+                haystack.replace_range(index..index + 1, "y");
+                assert_eq!(find_substring(haystack.as_bytes(), b"y"), Some(index));
+                assert_eq!(
+                    rfind_substring(haystack.as_bytes(), b"y"),
+                    haystack.rfind('y')
+                );
+                haystack.replace_range(index..index + 1, "x");
+            }
+        }
+    }
+
+    #[test]
+    fn needles_matching_inside_surrogate_pairs() {
+        // WTF-8 of a lone leading (U+D83D) and trailing (U+DE00) surrogate.
+        let leading = [0xED, 0xA0, 0xBD];
+        let trailing = [0xED, 0xB8, 0x80];
+        assert!(can_match_inside_surrogate_pair(&trailing));
+        assert!(can_match_inside_surrogate_pair(&leading));
+        assert!(can_match_inside_surrogate_pair(&[b'a', 0xED, 0xA0, 0xBD]));
+        assert!(can_match_inside_surrogate_pair(&[0xED, 0xB8, 0x80, b'a']));
+        assert!(!can_match_inside_surrogate_pair(&[0xED, 0xA0, 0xBD, b'a']));
+        assert!(!can_match_inside_surrogate_pair(&[b'a', 0xED, 0xB8, 0x80]));
+        assert!(!can_match_inside_surrogate_pair("😀".as_bytes()));
+        assert!(!can_match_inside_surrogate_pair("a\u{D7FF}".as_bytes()));
+        assert!(!can_match_inside_surrogate_pair(b""));
+    }
+}
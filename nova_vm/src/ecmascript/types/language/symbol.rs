@@ -68,8 +68,15 @@ impl<'a> Symbol<'a> {
         if let Some(descriptor) = agent[self].descriptor {
             // c. Else, set name to the string-concatenation of
             //    "[", description, and "]".
-            let description = descriptor.as_str(agent);
-            String::from_string(agent, format!("[{description}]"), gc)
+            String::concat(
+                agent,
+                [
+                    String::from_small_string("["),
+                    descriptor,
+                    String::from_small_string("]"),
+                ],
+                gc,
+            )
         } else {
             // b. If description is undefined, set name to the
             //    empty String.
@@ -154,6 +161,7 @@ impl Index<Symbol<'_>> for Agent {
 
 impl IndexMut<Symbol<'_>> for Agent {
     fn index_mut(&mut self, index: Symbol<'_>) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         &mut self.heap.symbols[index]
     }
 }
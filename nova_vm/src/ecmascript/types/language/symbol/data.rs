@@ -5,7 +5,10 @@
 use crate::{
     ecmascript::types::String,
     engine::context::{Bindable, NoGcScope},
-    heap::{CompactionLists, HeapMarkAndSweep, WorkQueues},
+    heap::{
+        CompactionLists, HeapMarkAndSweep, WorkQueues,
+        snapshot::{HeapSnapshot, SnapshotError, SnapshotReader, SnapshotWriter},
+    },
 };
 
 #[derive(Debug, Clone, Copy)]
@@ -39,3 +42,16 @@ impl HeapMarkAndSweep for SymbolHeapData<'static> {
         descriptor.sweep_values(compactions);
     }
 }
+
+impl HeapSnapshot for SymbolHeapData<'static> {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        let Self { descriptor } = self;
+        descriptor.write_snapshot(writer);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        Ok(Self {
+            descriptor: HeapSnapshot::read_snapshot(reader)?,
+        })
+    }
+}
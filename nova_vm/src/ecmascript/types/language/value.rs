@@ -5,24 +5,28 @@
 use wtf8::Wtf8;
 
 use super::{
-    BigInt, BigIntHeapData, IntoValue, Number, Numeric, OrdinaryObject, Primitive, String,
-    StringHeapData, Symbol, bigint::HeapBigInt, number::HeapNumber, string::HeapString,
+    BigInt, BigIntHeapData, Function, IntoValue, Number, Numeric, OrdinaryObject, Primitive,
+    String, StringHeapData, Symbol, bigint::HeapBigInt, number::HeapNumber, string::HeapString,
 };
 #[cfg(feature = "date")]
 use crate::ecmascript::builtins::date::Date;
-#[cfg(feature = "regexp")]
-use crate::ecmascript::builtins::regexp::RegExp;
 #[cfg(feature = "shared-array-buffer")]
 use crate::ecmascript::builtins::shared_array_buffer::SharedArrayBuffer;
 #[cfg(feature = "set")]
 use crate::ecmascript::builtins::{
     keyed_collections::set_objects::set_iterator_objects::set_iterator::SetIterator, set::Set,
 };
+#[cfg(feature = "regexp")]
+use crate::ecmascript::builtins::{
+    regexp::RegExp,
+    text_processing::regexp_objects::regexp_string_iterator_objects::RegExpStringIterator,
+};
 #[cfg(feature = "weak-refs")]
 use crate::ecmascript::builtins::{weak_map::WeakMap, weak_ref::WeakRef, weak_set::WeakSet};
 use crate::{
     SmallInteger, SmallString,
     ecmascript::{
+        abstract_operations::testing_and_comparison::is_array,
         abstract_operations::type_conversion::{
             to_big_int, to_int16, to_int32, to_number, to_numeric, to_string, to_uint16, to_uint32,
             try_to_string,
@@ -44,7 +48,7 @@ use crate::{
             module::Module,
             primitive_objects::PrimitiveObject,
             promise::Promise,
-            proxy::Proxy,
+            proxy::{Proxy, data::ProxyHeapData},
             text_processing::string_objects::string_iterator_objects::StringIterator,
         },
         execution::{Agent, JsResult},
@@ -72,8 +76,14 @@ use core::{
 };
 
 /// ### [6.1 ECMAScript Language Types](https://tc39.es/ecma262/#sec-ecmascript-language-types)
+///
+/// The variants of this enum are an implementation detail of the engine and
+/// new ones are added regularly. Embedders should prefer classifying values
+/// through [`Value::type_of`] and the `is_*` and `as_*` methods over matching
+/// on the variants directly.
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 #[repr(u8)]
+#[non_exhaustive]
 pub enum Value<'a> {
     /// ### [6.1.1 The Undefined Type](https://tc39.es/ecma262/#sec-ecmascript-language-types-undefined-type)
     #[default]
@@ -214,6 +224,8 @@ pub enum Value<'a> {
     ArrayIterator(ArrayIterator<'a>),
     #[cfg(feature = "set")]
     SetIterator(SetIterator<'a>),
+    #[cfg(feature = "regexp")]
+    RegExpStringIterator(RegExpStringIterator<'a>),
     MapIterator(MapIterator<'a>),
     StringIterator(StringIterator<'a>),
     Generator(Generator<'a>),
@@ -232,6 +244,22 @@ const _VALUE_SIZE_IS_WORD: () = assert!(size_of::<Value>() == size_of::<usize>()
 /// arrays do not start requiring extra bookkeeping.
 const _OPTIONAL_VALUE_SIZE_IS_WORD: () = assert!(size_of::<Option<Value>>() == size_of::<usize>());
 
+/// The type of a Value as reported by the
+/// [typeof operator](https://tc39.es/ecma262/#sec-typeof-operator), except
+/// that null is reported as its own type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JsType {
+    Undefined,
+    Null,
+    Boolean,
+    Number,
+    BigInt,
+    String,
+    Symbol,
+    Object,
+    Function,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum PreferredType {
     String,
@@ -355,6 +383,9 @@ pub(crate) const ARRAY_ITERATOR_DISCRIMINANT: u8 =
 #[cfg(feature = "set")]
 pub(crate) const SET_ITERATOR_DISCRIMINANT: u8 =
     value_discriminant(Value::SetIterator(SetIterator::_def()));
+#[cfg(feature = "regexp")]
+pub(crate) const REGEXP_STRING_ITERATOR_DISCRIMINANT: u8 =
+    value_discriminant(Value::RegExpStringIterator(RegExpStringIterator::_def()));
 pub(crate) const MAP_ITERATOR_DISCRIMINANT: u8 =
     value_discriminant(Value::MapIterator(MapIterator::_def()));
 pub(crate) const STRING_ITERATOR_DISCRIMINANT: u8 =
@@ -438,6 +469,81 @@ impl<'a> Value<'a> {
         Number::neg_zero().into_value()
     }
 
+    /// Returns the type of the Value as seen by the typeof operator, with
+    /// null reported separately from objects.
+    ///
+    /// Objects that have a \[\[Call]] internal method, including callable
+    /// Proxies, are reported as [`JsType::Function`].
+    pub fn type_of(self, agent: &Agent) -> JsType {
+        match self {
+            Value::Undefined => JsType::Undefined,
+            Value::Null => JsType::Null,
+            Value::Boolean(_) => JsType::Boolean,
+            Value::String(_) | Value::SmallString(_) => JsType::String,
+            Value::Symbol(_) => JsType::Symbol,
+            Value::Number(_) | Value::Integer(_) | Value::SmallF64(_) => JsType::Number,
+            Value::BigInt(_) | Value::SmallBigInt(_) => JsType::BigInt,
+            _ if self.is_callable(agent) => JsType::Function,
+            _ => JsType::Object,
+        }
+    }
+
+    /// ### [7.2.2 IsArray ( argument )](https://tc39.es/ecma262/#sec-isarray)
+    ///
+    /// Returns true if the Value is an Array exotic object or a Proxy whose
+    /// target is one. Throws a TypeError if a revoked Proxy is encountered.
+    pub fn is_array<'gc>(self, agent: &mut Agent, gc: NoGcScope<'gc, '_>) -> JsResult<'gc, bool> {
+        is_array(agent, self, gc)
+    }
+
+    /// ### [7.2.3 IsCallable ( argument )](https://tc39.es/ecma262/#sec-iscallable)
+    ///
+    /// Returns true if the Value has a \[\[Call]] internal method.
+    pub fn is_callable(self, agent: &Agent) -> bool {
+        match self {
+            Value::Proxy(proxy) => match &agent[proxy] {
+                ProxyHeapData::NonRevoked { proxy_target, .. } => {
+                    proxy_target.into_value().is_callable(agent)
+                }
+                ProxyHeapData::RevokedCallable => true,
+                ProxyHeapData::Revoked => false,
+            },
+            _ => Function::try_from(self).is_ok(),
+        }
+    }
+
+    /// ### [7.2.4 IsConstructor ( argument )](https://tc39.es/ecma262/#sec-isconstructor)
+    ///
+    /// Returns true if the Value has a \[\[Construct]] internal method.
+    ///
+    /// > #### Note
+    /// > Nova does not retain the target of a revoked Proxy, so revoked
+    /// > Proxies are never reported as constructors.
+    pub fn is_constructor(self, agent: &Agent) -> bool {
+        match self {
+            Value::BoundFunction(f) => f.is_constructor(agent),
+            Value::BuiltinFunction(f) => f.is_constructor(agent),
+            Value::ECMAScriptFunction(f) => f.is_constructor(agent),
+            Value::BuiltinConstructorFunction(_) => true,
+            Value::Proxy(proxy) => match &agent[proxy] {
+                ProxyHeapData::NonRevoked { proxy_target, .. } => {
+                    proxy_target.into_value().is_constructor(agent)
+                }
+                ProxyHeapData::RevokedCallable | ProxyHeapData::Revoked => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Returns true if the Value is a Number that is a valid
+    /// [integer index](https://tc39.es/ecma262/#integer-index), ie. an
+    /// integral Number in the inclusive interval from +0 to 2**53 - 1.
+    pub fn is_integer_index(self) -> bool {
+        // Integral Numbers in the safe integer range are always stored as
+        // SmallIntegers; -0 is stored as a SmallF64.
+        matches!(self, Value::Integer(i) if i.into_i64() >= 0)
+    }
+
     pub fn is_true(self) -> bool {
         matches!(self, Value::Boolean(true))
     }
@@ -542,6 +648,27 @@ impl<'a> Value<'a> {
         }
     }
 
+    /// Returns the Value as an Object if it is one, without coercion.
+    pub fn as_object(self) -> Option<super::Object<'a>> {
+        super::Object::try_from(self).ok()
+    }
+
+    /// Returns a copy of the String's contents if the Value is a String,
+    /// without coercion. Lone surrogates are replaced with U+FFFD.
+    pub fn as_string_lossy(self, agent: &Agent) -> Option<std::string::String> {
+        String::try_from(self)
+            .ok()
+            .map(|string| string.as_wtf8(agent).to_string_lossy().into_owned())
+    }
+
+    /// Returns the numeric value of the Value if it is a Number, without
+    /// coercion.
+    pub fn as_number(self, agent: &Agent) -> Option<f64> {
+        Number::try_from(self)
+            .ok()
+            .map(|number| number.into_f64(agent))
+    }
+
     pub fn to_number<'gc>(
         self,
         agent: &mut Agent,
@@ -865,6 +992,11 @@ impl<'a> Value<'a> {
                 discriminant.hash(hasher);
                 data.get_index().hash(hasher);
             }
+            #[cfg(feature = "regexp")]
+            Value::RegExpStringIterator(data) => {
+                discriminant.hash(hasher);
+                data.get_index().hash(hasher);
+            }
             Value::MapIterator(data) => {
                 discriminant.hash(hasher);
                 data.get_index().hash(hasher);
@@ -1099,6 +1231,11 @@ impl<'a> Value<'a> {
                 discriminant.hash(hasher);
                 data.get_index().hash(hasher);
             }
+            #[cfg(feature = "regexp")]
+            Value::RegExpStringIterator(data) => {
+                discriminant.hash(hasher);
+                data.get_index().hash(hasher);
+            }
             Value::MapIterator(data) => {
                 discriminant.hash(hasher);
                 data.get_index().hash(hasher);
@@ -1338,6 +1475,10 @@ impl Rootable for Value<'_> {
             Self::SetIterator(set_iterator) => {
                 Err(HeapRootData::SetIterator(set_iterator.unbind()))
             }
+            #[cfg(feature = "regexp")]
+            Self::RegExpStringIterator(reg_exp_string_iterator) => Err(
+                HeapRootData::RegExpStringIterator(reg_exp_string_iterator.unbind()),
+            ),
             Self::MapIterator(map_iterator) => {
                 Err(HeapRootData::MapIterator(map_iterator.unbind()))
             }
@@ -1466,6 +1607,10 @@ impl Rootable for Value<'_> {
             }
             #[cfg(feature = "set")]
             HeapRootData::SetIterator(set_iterator) => Some(Self::SetIterator(set_iterator)),
+            #[cfg(feature = "regexp")]
+            HeapRootData::RegExpStringIterator(reg_exp_string_iterator) => {
+                Some(Self::RegExpStringIterator(reg_exp_string_iterator))
+            }
             HeapRootData::MapIterator(map_iterator) => Some(Self::MapIterator(map_iterator)),
             HeapRootData::StringIterator(generator) => Some(Self::StringIterator(generator)),
             HeapRootData::Generator(generator) => Some(Self::Generator(generator)),
@@ -1583,6 +1728,8 @@ impl HeapMarkAndSweep for Value<'static> {
             Value::ArrayIterator(data) => data.mark_values(queues),
             #[cfg(feature = "set")]
             Value::SetIterator(data) => data.mark_values(queues),
+            #[cfg(feature = "regexp")]
+            Value::RegExpStringIterator(data) => data.mark_values(queues),
             Value::MapIterator(data) => data.mark_values(queues),
             Value::StringIterator(data) => data.mark_values(queues),
             Value::Generator(data) => data.mark_values(queues),
@@ -1670,6 +1817,8 @@ impl HeapMarkAndSweep for Value<'static> {
             Value::ArrayIterator(data) => data.sweep_values(compactions),
             #[cfg(feature = "set")]
             Value::SetIterator(data) => data.sweep_values(compactions),
+            #[cfg(feature = "regexp")]
+            Value::RegExpStringIterator(data) => data.sweep_values(compactions),
             Value::MapIterator(data) => data.sweep_values(compactions),
             Value::StringIterator(data) => data.sweep_values(compactions),
             Value::Generator(data) => data.sweep_values(compactions),
@@ -1678,3 +1827,236 @@ impl HeapMarkAndSweep for Value<'static> {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        ecmascript::{
+            execution::{
+                Agent, DefaultHostHooks,
+                agent::{GcAgent, Options},
+                initialize_default_realm,
+            },
+            scripts_and_modules::script::test::evaluate,
+            types::{JsType, Value},
+        },
+        engine::{
+            Global,
+            context::{Bindable, GcScope},
+        },
+    };
+
+    /// Expected classification of a single value in the zoo.
+    struct Expected {
+        type_of: JsType,
+        is_array: bool,
+        is_callable: bool,
+        is_constructor: bool,
+    }
+
+    const fn expect(
+        type_of: JsType,
+        is_array: bool,
+        is_callable: bool,
+        is_constructor: bool,
+    ) -> Expected {
+        Expected {
+            type_of,
+            is_array,
+            is_callable,
+            is_constructor,
+        }
+    }
+
+    const ZOO: &[(&str, Expected)] = &[
+        ("undefined", expect(JsType::Undefined, false, false, false)),
+        ("null", expect(JsType::Null, false, false, false)),
+        ("true", expect(JsType::Boolean, false, false, false)),
+        ("1", expect(JsType::Number, false, false, false)),
+        ("-0", expect(JsType::Number, false, false, false)),
+        ("1.5", expect(JsType::Number, false, false, false)),
+        ("2 ** 53", expect(JsType::Number, false, false, false)),
+        ("10n", expect(JsType::BigInt, false, false, false)),
+        ("10n ** 30n", expect(JsType::BigInt, false, false, false)),
+        ("('abc')", expect(JsType::String, false, false, false)),
+        (
+            "('a longer heap string')",
+            expect(JsType::String, false, false, false),
+        ),
+        ("Symbol('sym')", expect(JsType::Symbol, false, false, false)),
+        ("({})", expect(JsType::Object, false, false, false)),
+        ("[1, 2]", expect(JsType::Object, true, false, false)),
+        ("new Number(1)", expect(JsType::Object, false, false, false)),
+        (
+            "new String('abc')",
+            expect(JsType::Object, false, false, false),
+        ),
+        (
+            "new Error('e')",
+            expect(JsType::Object, false, false, false),
+        ),
+        ("new Map()", expect(JsType::Object, false, false, false)),
+        (
+            "(function () {})",
+            expect(JsType::Function, false, true, true),
+        ),
+        ("(() => {})", expect(JsType::Function, false, true, false)),
+        ("(class {})", expect(JsType::Function, false, true, true)),
+        (
+            "(function* () {})",
+            expect(JsType::Function, false, true, false),
+        ),
+        ("Math.max", expect(JsType::Function, false, true, false)),
+        ("Array", expect(JsType::Function, false, true, true)),
+        (
+            "(function () {}).bind(null)",
+            expect(JsType::Function, false, true, true),
+        ),
+        (
+            "(() => {}).bind(null)",
+            expect(JsType::Function, false, true, false),
+        ),
+        (
+            "new Proxy({}, {})",
+            expect(JsType::Object, false, false, false),
+        ),
+        (
+            "new Proxy([], {})",
+            expect(JsType::Object, true, false, false),
+        ),
+        (
+            "new Proxy(new Proxy([], {}), {})",
+            expect(JsType::Object, true, false, false),
+        ),
+        (
+            "new Proxy(function () {}, {})",
+            expect(JsType::Function, false, true, true),
+        ),
+        (
+            "new Proxy(() => {}, {})",
+            expect(JsType::Function, false, true, false),
+        ),
+        (
+            "new Proxy(new Proxy(class {}, {}), {})",
+            expect(JsType::Function, false, true, true),
+        ),
+    ];
+
+    fn check_classification(
+        agent: &mut Agent,
+        source: &str,
+        value: Value,
+        expected: &Expected,
+        gc: GcScope,
+    ) {
+        let gc = gc.into_nogc();
+        assert_eq!(value.type_of(agent), expected.type_of, "type_of {source}");
+        assert_eq!(
+            value.is_array(agent, gc).unwrap(),
+            expected.is_array,
+            "is_array {source}"
+        );
+        assert_eq!(
+            value.is_callable(agent),
+            expected.is_callable,
+            "is_callable {source}"
+        );
+        assert_eq!(
+            value.is_constructor(agent),
+            expected.is_constructor,
+            "is_constructor {source}"
+        );
+        let is_primitive = !matches!(expected.type_of, JsType::Object | JsType::Function);
+        assert_eq!(value.is_primitive(), is_primitive, "is_primitive {source}");
+        assert_eq!(
+            value.as_object().is_some(),
+            !is_primitive,
+            "as_object {source}"
+        );
+        assert_eq!(
+            value.as_number(agent).is_some(),
+            expected.type_of == JsType::Number,
+            "as_number {source}"
+        );
+        assert_eq!(
+            value.as_string_lossy(agent).is_some(),
+            expected.type_of == JsType::String,
+            "as_string_lossy {source}"
+        );
+    }
+
+    #[test]
+    fn classify_value_zoo() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        for (source, expected) in ZOO {
+            let value = evaluate(agent, source, gc.reborrow()).unbind();
+            check_classification(agent, source, value, expected, gc.reborrow());
+        }
+    }
+
+    #[test]
+    fn classify_values_from_another_realm() {
+        let mut agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+        let realm_a = agent.create_default_realm();
+        let realm_b = agent.create_default_realm();
+        let values = agent.run_in_realm(&realm_b, |agent, mut gc| {
+            ZOO.iter()
+                .map(|(source, _)| {
+                    let value = evaluate(agent, source, gc.reborrow()).unbind();
+                    Global::new(agent, value)
+                })
+                .collect::<Vec<_>>()
+        });
+        agent.run_in_realm(&realm_a, |agent, mut gc| {
+            for ((source, expected), value) in ZOO.iter().zip(values) {
+                let value = value.take(agent);
+                check_classification(agent, source, value, expected, gc.reborrow());
+            }
+        });
+        agent.remove_realm(realm_a);
+        agent.remove_realm(realm_b);
+    }
+
+    #[test]
+    fn classify_value_accessors() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let value = evaluate(agent, "1", gc.reborrow()).unbind();
+        assert!(value.is_integer_index());
+        assert_eq!(value.as_number(agent), Some(1.0));
+
+        let value = evaluate(agent, "2 ** 53 - 1", gc.reborrow()).unbind();
+        assert!(value.is_integer_index());
+
+        for source in ["-1", "-0", "1.5", "2 ** 53", "('1')", "1n", "NaN"] {
+            let value = evaluate(agent, source, gc.reborrow()).unbind();
+            assert!(!value.is_integer_index(), "is_integer_index {source}");
+        }
+
+        let value = evaluate(agent, "-0", gc.reborrow()).unbind();
+        let number = value.as_number(agent).unwrap();
+        assert!(number == 0.0 && number.is_sign_negative());
+
+        let value = evaluate(agent, "('a longer heap string')", gc.reborrow()).unbind();
+        assert_eq!(
+            value.as_string_lossy(agent).as_deref(),
+            Some("a longer heap string")
+        );
+        let value = evaluate(agent, "('abc')", gc.reborrow()).unbind();
+        assert_eq!(value.as_string_lossy(agent).as_deref(), Some("abc"));
+
+        // Wrapper objects are not unwrapped.
+        let value = evaluate(agent, "new String('abc')", gc.reborrow()).unbind();
+        assert_eq!(value.as_string_lossy(agent), None);
+        let value = evaluate(agent, "new Number(1)", gc.reborrow()).unbind();
+        assert_eq!(value.as_number(agent), None);
+        assert!(value.as_object().is_some());
+    }
+}
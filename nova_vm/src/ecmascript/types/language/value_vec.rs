@@ -63,6 +63,13 @@ impl ScopedCollection<'_, Vec<Value<'static>>> {
         self.with_cb_mut(agent, |value_vec| value_vec.pop().bind(gc))
     }
 
+    /// Get a Value by index from the scoped vec.
+    pub fn get<'a>(&self, agent: &Agent, index: usize, gc: NoGcScope<'a, '_>) -> Option<Value<'a>> {
+        self.with_cb(agent, |value_vec| {
+            value_vec.get(index).map(|value| value.bind(gc))
+        })
+    }
+
     pub fn last<'a>(&self, agent: &Agent, gc: NoGcScope<'a, '_>) -> Option<Value<'a>> {
         self.with_cb(agent, |value_vec| {
             value_vec.last().map(|value| value.bind(gc))
@@ -9,5 +9,5 @@ mod reference;
 #[cfg(feature = "array-buffer")]
 pub(crate) use data_block::*;
 pub use private_name::*;
-pub use property_descriptor::PropertyDescriptor;
+pub use property_descriptor::{PropertyDescriptor, PropertyDescriptorTemplate};
 pub(crate) use reference::*;
@@ -23,7 +23,7 @@ use crate::{
             to_int32_number, to_uint8_clamp_number, to_uint8_number, to_uint16_number,
             to_uint32_number,
         },
-        execution::{Agent, JsResult, agent::ExceptionType},
+        execution::{Agent, JsResult},
         types::{BigInt, IntoNumeric, Number, Numeric, Value},
     },
     engine::context::NoGcScope,
@@ -1102,11 +1102,7 @@ impl DataBlock {
     ) -> JsResult<'a, Self> {
         // 1. If size > 2**53 - 1, throw a RangeError exception.
         if size > u64::pow(2, 53) - 1 {
-            Err(agent.throw_exception_with_static_message(
-                ExceptionType::RangeError,
-                "Not a safe integer",
-                gc,
-            ))
+            agent.range_error("Not a safe integer", gc)
         } else if let Ok(size) = usize::try_from(size) {
             // 2. Let db be a new Data Block value consisting of size bytes.
             // 3. Set all of the bytes of db to 0.
@@ -1115,11 +1111,7 @@ impl DataBlock {
         } else {
             // 2. cont: If it is impossible to create such a Data Block, throw a RangeError exception.
             // TODO: throw a RangeError exception
-            Err(agent.throw_exception_with_static_message(
-                ExceptionType::RangeError,
-                "Invalid Data Block length",
-                gc,
-            ))
+            agent.range_error("Invalid Data Block length", gc)
         }
     }
 
@@ -1142,11 +1134,7 @@ impl DataBlock {
             // a. Append WriteSharedMemory { [[Order]]: INIT, [[NoTear]]: true, [[Block]]: db, [[ByteIndex]]: i, [[ElementSize]]: 1, [[Payload]]: zero } to eventsRecord.[[EventList]].
             Ok(Self::new(size))
         } else {
-            Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Invalid Shared Data Block length",
-                gc,
-            ))
+            agent.type_error("Invalid Shared Data Block length", gc)
         }
         // 6. Return db.
     }
@@ -171,6 +171,11 @@ impl<'a> PropertyDescriptor<'a> {
 
         let mut entries = Vec::with_capacity(4);
 
+        // NOTE: An undefined [[Get]] or [[Set]] field cannot be distinguished
+        // from a missing one. A fully populated accessor descriptor has both
+        // fields, so report the missing one as undefined.
+        let is_complete_accessor = desc.is_accessor_descriptor() && desc.is_fully_populated();
+
         // 4. If Desc has a [[Value]] field, then
         if let Some(value) = desc.value {
             // a. Perform ! CreateDataPropertyOrThrow(obj, "value", Desc.[[Value]]).
@@ -190,20 +195,20 @@ impl<'a> PropertyDescriptor<'a> {
         }
 
         // 6. If Desc has a [[Get]] field, then
-        if let Some(get) = desc.get {
+        if desc.get.is_some() || is_complete_accessor {
             // a. Perform ! CreateDataPropertyOrThrow(obj, "get", Desc.[[Get]]).
             entries.push(ObjectEntry::new_data_entry(
                 BUILTIN_STRING_MEMORY.get.into(),
-                get.into_value(),
+                desc.get.map_or(Value::Undefined, |get| get.into_value()),
             ));
         }
 
         // 7. If Desc has a [[Set]] field, then
-        if let Some(set) = desc.set {
+        if desc.set.is_some() || is_complete_accessor {
             // a. Perform ! CreateDataPropertyOrThrow(obj, "set", Desc.[[Set]]).
             entries.push(ObjectEntry::new_data_entry(
                 BUILTIN_STRING_MEMORY.set.into(),
-                set.into_value(),
+                desc.set.map_or(Value::Undefined, |set| set.into_value()),
             ));
         }
 
@@ -259,7 +264,7 @@ impl<'a> PropertyDescriptor<'a> {
             let obj_repr = obj.unbind().string_repr(agent, gc.reborrow());
             let error_message = format!(
                 "Property descriptor must be an object, got '{}'.",
-                obj_repr.as_str(agent)
+                obj_repr.to_string_lossy(agent)
             );
             return Err(agent.throw_exception(
                 ExceptionType::TypeError,
@@ -386,11 +391,7 @@ impl<'a> PropertyDescriptor<'a> {
             // throw a TypeError exception.
             if !getter.is_undefined() {
                 let Some(getter) = is_callable(getter, gc.nogc()) else {
-                    return Err(agent.throw_exception_with_static_message(
-                        ExceptionType::TypeError,
-                        "getter is not callable",
-                        gc.into_nogc(),
-                    ));
+                    return agent.type_error("getter is not callable", gc.into_nogc());
                 };
                 // c. Set desc.[[Get]] to getter.
                 desc.get = Some(getter.unbind());
@@ -419,11 +420,7 @@ impl<'a> PropertyDescriptor<'a> {
             // throw a TypeError exception.
             if !setter.is_undefined() {
                 let Some(setter) = is_callable(setter, gc.nogc()) else {
-                    return Err(agent.throw_exception_with_static_message(
-                        ExceptionType::TypeError,
-                        "setter is not callable",
-                        gc.into_nogc(),
-                    ));
+                    return agent.type_error("setter is not callable", gc.into_nogc());
                 };
                 // c. Set desc.[[Set]] to setter.
                 desc.set = Some(setter.unbind());
@@ -438,11 +435,7 @@ impl<'a> PropertyDescriptor<'a> {
             // a. If desc has a [[Value]] field or desc has a [[Writable]]
             // field, throw a TypeError exception.
             if desc.writable.is_some() || desc.writable.is_some() {
-                return Err(agent.throw_exception_with_static_message(
-                    ExceptionType::TypeError,
-                    "Over-defined property descriptor",
-                    gc.into_nogc(),
-                ));
+                return agent.type_error("Over-defined property descriptor", gc.into_nogc());
             }
         }
         // 16. Return desc.
@@ -456,11 +449,9 @@ impl<'a> PropertyDescriptor<'a> {
     ) -> TryResult<JsResult<'a, Self>> {
         // 1. If Obj is not an Object, throw a TypeError exception.
         let Ok(obj) = Object::try_from(obj) else {
-            return TryResult::Continue(Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Property descriptor must be an object",
-                gc,
-            )));
+            return TryResult::Continue(
+                agent.type_error("Property descriptor must be an object", gc),
+            );
         };
         // 2. Let desc be a new Property Descriptor that initially has no
         // fields.
@@ -516,11 +507,7 @@ impl<'a> PropertyDescriptor<'a> {
             // throw a TypeError exception.
             if !getter.is_undefined() {
                 let Some(getter) = is_callable(getter, gc) else {
-                    return TryResult::Continue(Err(agent.throw_exception_with_static_message(
-                        ExceptionType::TypeError,
-                        "getter is not callable",
-                        gc,
-                    )));
+                    return TryResult::Continue(agent.type_error("getter is not callable", gc));
                 };
                 // c. Set desc.[[Get]] to getter.
                 desc.get = Some(getter.unbind());
@@ -536,11 +523,7 @@ impl<'a> PropertyDescriptor<'a> {
             // throw a TypeError exception.
             if !setter.is_undefined() {
                 let Some(setter) = is_callable(setter, gc) else {
-                    return TryResult::Continue(Err(agent.throw_exception_with_static_message(
-                        ExceptionType::TypeError,
-                        "setter is not callable",
-                        gc,
-                    )));
+                    return TryResult::Continue(agent.type_error("setter is not callable", gc));
                 };
                 // c. Set desc.[[Set]] to setter.
                 desc.set = Some(setter.unbind());
@@ -551,11 +534,9 @@ impl<'a> PropertyDescriptor<'a> {
             // a. If desc has a [[Value]] field or desc has a [[Writable]]
             // field, throw a TypeError exception.
             if desc.writable.is_some() || desc.writable.is_some() {
-                return TryResult::Continue(Err(agent.throw_exception_with_static_message(
-                    ExceptionType::TypeError,
-                    "Over-defined property descriptor",
-                    gc,
-                )));
+                return TryResult::Continue(
+                    agent.type_error("Over-defined property descriptor", gc),
+                );
             }
         }
         // 16. Return desc.
@@ -641,3 +622,109 @@ unsafe impl Bindable for PropertyDescriptor<'_> {
         unsafe { core::mem::transmute::<Self, Self::Of<'a>>(self) }
     }
 }
+
+/// Fully populated Property Descriptor for defining properties from Rust
+/// without building descriptor objects or optional fields.
+///
+/// See [`Object::define_properties_batch`].
+#[derive(Debug, Clone, Copy)]
+pub enum PropertyDescriptorTemplate<'a> {
+    /// A data property with the given \[\[Value]].
+    Data {
+        value: Value<'a>,
+        writable: bool,
+        enumerable: bool,
+        configurable: bool,
+    },
+    /// An accessor property. At least one of \[\[Get]] and \[\[Set]] must be
+    /// present.
+    Accessor {
+        get: Option<Function<'a>>,
+        set: Option<Function<'a>>,
+        enumerable: bool,
+        configurable: bool,
+    },
+}
+
+impl<'a> PropertyDescriptorTemplate<'a> {
+    /// A writable, non-enumerable, and configurable data property, like the
+    /// builtin methods and the constructor properties of the global object.
+    pub fn builtin(value: Value<'a>) -> Self {
+        Self::Data {
+            value,
+            writable: true,
+            enumerable: false,
+            configurable: true,
+        }
+    }
+
+    /// A writable, enumerable, and configurable data property, like the
+    /// properties created by assignment.
+    pub fn data(value: Value<'a>) -> Self {
+        Self::Data {
+            value,
+            writable: true,
+            enumerable: true,
+            configurable: true,
+        }
+    }
+
+    /// A non-writable, non-enumerable, and non-configurable data property,
+    /// like the `NaN` and `undefined` properties of the global object.
+    pub fn constant(value: Value<'a>) -> Self {
+        Self::Data {
+            value,
+            writable: false,
+            enumerable: false,
+            configurable: false,
+        }
+    }
+}
+
+impl<'a> From<PropertyDescriptorTemplate<'a>> for PropertyDescriptor<'a> {
+    fn from(value: PropertyDescriptorTemplate<'a>) -> Self {
+        match value {
+            PropertyDescriptorTemplate::Data {
+                value,
+                writable,
+                enumerable,
+                configurable,
+            } => PropertyDescriptor {
+                value: Some(value),
+                writable: Some(writable),
+                get: None,
+                set: None,
+                enumerable: Some(enumerable),
+                configurable: Some(configurable),
+            },
+            PropertyDescriptorTemplate::Accessor {
+                get,
+                set,
+                enumerable,
+                configurable,
+            } => PropertyDescriptor {
+                value: None,
+                writable: None,
+                get,
+                set,
+                enumerable: Some(enumerable),
+                configurable: Some(configurable),
+            },
+        }
+    }
+}
+
+// SAFETY: Property implemented as a lifetime transmute.
+unsafe impl Bindable for PropertyDescriptorTemplate<'_> {
+    type Of<'a> = PropertyDescriptorTemplate<'a>;
+
+    #[inline(always)]
+    fn unbind(self) -> Self::Of<'static> {
+        unsafe { core::mem::transmute::<Self, Self::Of<'static>>(self) }
+    }
+
+    #[inline(always)]
+    fn bind<'a>(self, _gc: NoGcScope<'a, '_>) -> Self::Of<'a> {
+        unsafe { core::mem::transmute::<Self, Self::Of<'a>>(self) }
+    }
+}
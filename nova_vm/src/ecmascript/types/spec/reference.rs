@@ -5,6 +5,7 @@
 use crate::ecmascript::abstract_operations::operations_on_objects::{
     private_get, private_set, throw_no_private_name_error, try_private_get, try_set,
 };
+use crate::ecmascript::builtins::error::ErrorMessage;
 use crate::ecmascript::execution::agent::JsError;
 use crate::ecmascript::types::IntoValue;
 use crate::engine::TryResult;
@@ -85,7 +86,7 @@ pub(crate) fn is_property_reference(reference: &Reference) -> bool {
         Base::Unresolvable => false,
 
         // 2. If V.[[Base]] is an Environment Record, return false; otherwise return true.
-        Base::Environment(_) => false,
+        Base::Environment(_) | Base::FrameSlot(_) => false,
         _ => true,
     }
 }
@@ -167,13 +168,16 @@ pub(crate) fn get_value<'gc>(
             };
             Ok(env.get_binding_value(agent, referenced_name, reference.strict, gc)?)
         }
+        // Frame slots are read by the Vm directly.
+        Base::FrameSlot(_) => unreachable!(),
         Base::Unresolvable => {
             // 2. If IsUnresolvableReference(V) is true, throw a ReferenceError exception.
-            let error_message = format!(
-                "Cannot access undeclared variable '{}'.",
-                referenced_name.as_display(agent)
-            );
-            Err(agent.throw_exception(ExceptionType::ReferenceError, error_message, gc.into_nogc()))
+            Err(agent.throw_exception_with_deferred_message(
+                ExceptionType::ReferenceError,
+                ErrorMessage::UndeclaredVariableAccess,
+                [referenced_name.unbind().into()],
+                gc.into_nogc(),
+            ))
         }
     }
 }
@@ -242,16 +246,18 @@ pub(crate) fn throw_read_undefined_or_null_error<'a>(
     value: Value,
     gc: NoGcScope<'a, '_>,
 ) -> JsError<'a> {
-    let error_message = format!(
-        "Cannot read property '{}' of {}.",
-        referenced_value.try_string_repr(agent, gc).as_str(agent),
-        if value.is_undefined() {
-            "undefined"
-        } else {
-            "null"
-        }
-    );
-    agent.throw_exception(ExceptionType::TypeError, error_message, gc)
+    let referenced_value = referenced_value.try_string_repr(agent, gc);
+    let value = if value.is_undefined() {
+        "undefined"
+    } else {
+        "null"
+    };
+    agent.throw_exception_with_deferred_message(
+        ExceptionType::TypeError,
+        ErrorMessage::CannotReadProperty,
+        [referenced_value.into(), value.into()],
+        gc,
+    )
 }
 
 fn try_handle_primitive_get_value<'a>(
@@ -363,6 +369,8 @@ pub(crate) fn try_get_value<'gc>(
             };
             env.try_get_binding_value(agent, referenced_name, reference.strict, gc)
         }
+        // Frame slots are read by the Vm directly.
+        Base::FrameSlot(_) => unreachable!(),
         Base::Unresolvable => {
             // 2. If IsUnresolvableReference(V) is true, throw a ReferenceError exception.
             let error_message = format!(
@@ -395,13 +403,10 @@ pub(crate) fn put_value<'a>(
     if is_unresolvable_reference(v) {
         if v.strict {
             // a. If V.[[Strict]] is true, throw a ReferenceError exception.
-            let error_message = format!(
-                "Cannot assign to undeclared variable '{}'.",
-                v.referenced_name.as_display(agent)
-            );
-            return Err(agent.throw_exception(
+            return Err(agent.throw_exception_with_deferred_message(
                 ExceptionType::ReferenceError,
-                error_message,
+                ErrorMessage::UndeclaredVariableAssignment,
+                [v.referenced_name.into()],
                 gc.into_nogc(),
             ));
         }
@@ -424,7 +429,7 @@ pub(crate) fn put_value<'a>(
         // a. Let baseObj be ? ToObject(V.[[Base]]).
         let base = match v.base {
             Base::Value(value) => value,
-            Base::Environment(_) | Base::Unresolvable => unreachable!(),
+            Base::Environment(_) | Base::FrameSlot(_) | Base::Unresolvable => unreachable!(),
         };
         let base_obj = to_object(agent, base, gc.nogc()).unbind()?.bind(gc.nogc());
         // b. If IsPrivateReference(V) is true, then
@@ -458,14 +463,13 @@ pub(crate) fn put_value<'a>(
                 .get(agent)
                 .into_value()
                 .string_repr(agent, gc.reborrow());
-            let error_message = format!(
-                "Could not set property '{}' of {}.",
-                referenced_name.as_display(agent),
-                base_obj_repr.as_str(agent)
-            );
-            return Err(agent.throw_exception(
+            return Err(agent.throw_exception_with_deferred_message(
                 ExceptionType::TypeError,
-                error_message,
+                ErrorMessage::CannotSetPropertyOf,
+                [
+                    referenced_name.unbind().into(),
+                    base_obj_repr.unbind().into(),
+                ],
                 gc.into_nogc(),
             ));
         }
@@ -507,13 +511,10 @@ pub(crate) fn try_put_value<'a>(
     if is_unresolvable_reference(v) {
         if v.strict {
             // a. If V.[[Strict]] is true, throw a ReferenceError exception.
-            let error_message = format!(
-                "Cannot assign to undeclared variable '{}'.",
-                v.referenced_name.as_display(agent)
-            );
-            return TryResult::Continue(Err(agent.throw_exception(
+            return TryResult::Continue(Err(agent.throw_exception_with_deferred_message(
                 ExceptionType::ReferenceError,
-                error_message,
+                ErrorMessage::UndeclaredVariableAssignment,
+                [v.referenced_name.into()],
                 gc,
             )));
         }
@@ -531,7 +532,7 @@ pub(crate) fn try_put_value<'a>(
         // a. Let baseObj be ? ToObject(V.[[Base]]).
         let base = match v.base {
             Base::Value(value) => value,
-            Base::Environment(_) | Base::Unresolvable => unreachable!(),
+            Base::Environment(_) | Base::FrameSlot(_) | Base::Unresolvable => unreachable!(),
         };
         let base_obj = match to_object(agent, base, gc) {
             Ok(base_obj) => base_obj,
@@ -549,14 +550,10 @@ pub(crate) fn try_put_value<'a>(
         if !succeeded && v.strict {
             // d. If succeeded is false and V.[[Strict]] is true, throw a TypeError exception.
             let base_obj_repr = base_obj.into_value().try_string_repr(agent, gc);
-            let error_message = format!(
-                "Could not set property '{}' of {}.",
-                referenced_name.as_display(agent),
-                base_obj_repr.as_str(agent)
-            );
-            return TryResult::Continue(Err(agent.throw_exception(
+            return TryResult::Continue(Err(agent.throw_exception_with_deferred_message(
                 ExceptionType::TypeError,
-                error_message,
+                ErrorMessage::CannotSetPropertyOf,
+                [referenced_name.into(), base_obj_repr.into()],
                 gc,
             )));
         }
@@ -647,7 +644,7 @@ pub(crate) fn get_this_value<'a>(reference: &Reference<'a>) -> Value<'a> {
         .this_value
         .unwrap_or_else(|| match reference.base {
             Base::Value(value) => value,
-            Base::Environment(_) | Base::Unresolvable => unreachable!(),
+            Base::Environment(_) | Base::FrameSlot(_) | Base::Unresolvable => unreachable!(),
         })
 }
 
@@ -655,6 +652,10 @@ pub(crate) fn get_this_value<'a>(reference: &Reference<'a>) -> Value<'a> {
 pub(crate) enum Base<'a> {
     Value(Value<'a>),
     Environment(Environment<'a>),
+    /// Nova specific: The binding is kept in the Vm frame slot with this index
+    /// instead of an Environment Record. References to frame slots are only
+    /// created and dereferenced by the Vm itself.
+    FrameSlot(u32),
     Unresolvable,
 }
 
@@ -704,7 +705,7 @@ impl HeapMarkAndSweep for Base<'static> {
         match self {
             Base::Value(value) => value.mark_values(queues),
             Base::Environment(idx) => idx.mark_values(queues),
-            Base::Unresolvable => {}
+            Base::FrameSlot(_) | Base::Unresolvable => {}
         }
     }
 
@@ -712,7 +713,7 @@ impl HeapMarkAndSweep for Base<'static> {
         match self {
             Base::Value(value) => value.sweep_values(compactions),
             Base::Environment(idx) => idx.sweep_values(compactions),
-            Base::Unresolvable => {}
+            Base::FrameSlot(_) | Base::Unresolvable => {}
         }
     }
 }
@@ -12,6 +12,7 @@ pub mod small_integer;
 use core::ops::ControlFlow;
 
 pub(crate) use bytecode::*;
+pub use bytecode::{Executable, StepResult, Vm, VmState};
 pub use rootable::{Global, ScopableCollection, Scoped, ScopedCollection};
 
 /// Result of methods that are not allowed to call JavaScript or perform
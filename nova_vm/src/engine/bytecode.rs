@@ -11,8 +11,8 @@ mod vm;
 pub(crate) use bytecode_compiler::{
     CompileContext, CompileEvaluation, NamedEvaluationParameter, is_reference,
 };
-pub(crate) use executable::{
-    Executable, ExecutableHeapData, FunctionExpression, IndexType, SendableRef,
-};
+pub use executable::Executable;
+pub(crate) use executable::{ExecutableHeapData, FunctionExpression, IndexType, SendableRef};
 pub(crate) use instructions::{Instruction, InstructionIter};
-pub(crate) use vm::{ExecutionResult, SuspendedVm, Vm, instanceof_operator};
+pub(crate) use vm::{ExecutionResult, SuspendedVm, instanceof_operator};
+pub use vm::{StepResult, Vm, VmState};
@@ -31,7 +31,9 @@ use oxc_ast::ast::{
     Statement,
 };
 use oxc_ecmascript::BoundNames;
-use oxc_syntax::operator::{BinaryOperator, UnaryOperator};
+use oxc_span::GetSpan;
+use oxc_syntax::operator::{BinaryOperator, LogicalOperator, UnaryOperator};
+use wtf8::{CodePoint, Wtf8Buf};
 
 impl<'a, T: CompileEvaluation<'a>> CompileLabelledEvaluation<'a> for T {
     fn compile_labelled(
@@ -69,6 +71,124 @@ fn is_chain_expression(expression: &ast::Expression) -> bool {
     )
 }
 
+/// Compile an expression in a test position, such as the condition of an
+/// `if` statement or a loop.
+///
+/// Instead of producing the value of the expression, the compiled code jumps
+/// to the returned jump indexes if ToBoolean of the value equals `jump_if` and
+/// falls through otherwise. Comparisons are fused with the conditional jump,
+/// logical not inverts the jump, and logical `&&` and `||` branch directly
+/// without materialising their intermediate values.
+fn compile_test<'s>(
+    expression: &'s ast::Expression<'s>,
+    jump_if: bool,
+    ctx: &mut CompileContext<'_, 's, '_, '_>,
+) -> Vec<JumpIndex> {
+    match expression.get_inner_expression() {
+        ast::Expression::UnaryExpression(expr) if expr.operator == UnaryOperator::LogicalNot => {
+            return compile_test(&expr.argument, !jump_if, ctx);
+        }
+        ast::Expression::LogicalExpression(expr) if expr.operator != LogicalOperator::Coalesce => {
+            if (expr.operator == LogicalOperator::And) != jump_if {
+                // `a && b` is false if `a` is false, and `a || b` is true if
+                // `a` is true: both sides jump to the same target.
+                let mut jumps = compile_test(&expr.left, jump_if, ctx);
+                jumps.extend(compile_test(&expr.right, jump_if, ctx));
+                return jumps;
+            }
+            // Otherwise the left side only decides if the right side is
+            // evaluated.
+            let jumps_over_right = compile_test(&expr.left, !jump_if, ctx);
+            let jumps = compile_test(&expr.right, jump_if, ctx);
+            for jump in jumps_over_right {
+                ctx.set_jump_target_here(jump);
+            }
+            return jumps;
+        }
+        ast::Expression::BinaryExpression(expr) => {
+            let negated = matches!(
+                expr.operator,
+                BinaryOperator::Inequality | BinaryOperator::StrictInequality
+            );
+            let jump_if_equal = jump_if != negated;
+            if matches!(
+                expr.operator,
+                BinaryOperator::Equality | BinaryOperator::Inequality
+            ) {
+                // `x == null` is only a check of the other operand.
+                let operand = if expr.right.get_inner_expression().is_null() {
+                    Some(&expr.left)
+                } else if expr.left.get_inner_expression().is_null() {
+                    Some(&expr.right)
+                } else {
+                    None
+                };
+                if let Some(operand) = operand {
+                    operand.compile(ctx);
+                    if is_reference(operand) {
+                        ctx.add_instruction(Instruction::GetValue);
+                    }
+                    return vec![ctx.add_instruction_with_jump_slot(if jump_if_equal {
+                        Instruction::JumpIfNullOrUndefined
+                    } else {
+                        Instruction::JumpIfNotNullOrUndefined
+                    })];
+                }
+            }
+            let instruction = match expr.operator {
+                BinaryOperator::LessThan => Some(if jump_if {
+                    Instruction::JumpIfLessThan
+                } else {
+                    Instruction::JumpIfNotLessThan
+                }),
+                BinaryOperator::LessEqualThan => Some(if jump_if {
+                    Instruction::JumpIfLessThanEquals
+                } else {
+                    Instruction::JumpIfNotLessThanEquals
+                }),
+                BinaryOperator::GreaterThan => Some(if jump_if {
+                    Instruction::JumpIfGreaterThan
+                } else {
+                    Instruction::JumpIfNotGreaterThan
+                }),
+                BinaryOperator::GreaterEqualThan => Some(if jump_if {
+                    Instruction::JumpIfGreaterThanEquals
+                } else {
+                    Instruction::JumpIfNotGreaterThanEquals
+                }),
+                BinaryOperator::StrictEquality | BinaryOperator::StrictInequality => {
+                    Some(if jump_if_equal {
+                        Instruction::JumpIfStrictlyEqual
+                    } else {
+                        Instruction::JumpIfNotStrictlyEqual
+                    })
+                }
+                BinaryOperator::Equality | BinaryOperator::Inequality => Some(if jump_if_equal {
+                    Instruction::JumpIfLooselyEqual
+                } else {
+                    Instruction::JumpIfNotLooselyEqual
+                }),
+                _ => None,
+            };
+            if let Some(instruction) = instruction {
+                compile_binary_operands(expr, ctx);
+                return vec![ctx.add_instruction_with_jump_slot(instruction)];
+            }
+        }
+        _ => {}
+    }
+    expression.compile(ctx);
+    if is_reference(expression) {
+        ctx.add_instruction(Instruction::GetValue);
+    }
+    // Note: JumpIfNot and JumpIfTrue perform ToBoolean.
+    vec![ctx.add_instruction_with_jump_slot(if jump_if {
+        Instruction::JumpIfTrue
+    } else {
+        Instruction::JumpIfNot
+    })]
+}
+
 impl<'s> CompileEvaluation<'s> for ast::NumericLiteral<'s> {
     fn compile(&'s self, ctx: &mut CompileContext<'_, 's, '_, '_>) {
         let constant = ctx.create_number(self.value);
@@ -105,11 +225,34 @@ impl<'s> CompileEvaluation<'s> for ast::NullLiteral {
 
 impl<'s> CompileEvaluation<'s> for ast::StringLiteral<'s> {
     fn compile(&'s self, ctx: &mut CompileContext<'_, 's, '_, '_>) {
-        let constant = ctx.create_string(self.value.as_str());
+        let constant = if self.lone_surrogates {
+            ctx.create_string_from_wtf8(decode_lone_surrogates(self.value.as_str()))
+        } else {
+            ctx.create_string(self.value.as_str())
+        };
         ctx.add_instruction_with_constant(Instruction::StoreConstant, constant);
     }
 }
 
+/// Decodes a string literal value in which oxc has escaped lone surrogates
+/// as `\u{FFFD}XXXX`, where `XXXX` is the code unit in hex. The replacement
+/// character itself is escaped as `\u{FFFD}fffd`.
+fn decode_lone_surrogates(value: &str) -> Wtf8Buf {
+    let mut result = Wtf8Buf::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(ch) = chars.next() {
+        if ch == '\u{FFFD}' {
+            let hex = chars.as_str().get(..4).unwrap();
+            let code_unit = u32::from_str_radix(hex, 16).unwrap();
+            chars = chars.as_str()[4..].chars();
+            result.push(CodePoint::from_u32(code_unit).unwrap());
+        } else {
+            result.push_char(ch);
+        }
+    }
+    result
+}
+
 impl<'s> CompileEvaluation<'s> for ast::IdentifierReference<'s> {
     fn compile(&'s self, ctx: &mut CompileContext<'_, 's, '_, '_>) {
         let identifier = ctx.create_string(self.name.as_str());
@@ -233,24 +376,33 @@ impl<'s> CompileEvaluation<'s> for ast::UnaryExpression<'s> {
     }
 }
 
-impl<'s> CompileEvaluation<'s> for ast::BinaryExpression<'s> {
-    fn compile(&'s self, ctx: &mut CompileContext<'_, 's, '_, '_>) {
-        // 1. Let lref be ? Evaluation of leftOperand.
-        self.left.compile(ctx);
+/// Evaluate both operands of a binary expression, leaving the left value on
+/// the stack and the right value as the result value.
+fn compile_binary_operands<'s>(
+    expression: &'s ast::BinaryExpression<'s>,
+    ctx: &mut CompileContext<'_, 's, '_, '_>,
+) {
+    // 1. Let lref be ? Evaluation of leftOperand.
+    expression.left.compile(ctx);
 
-        // 2. Let lval be ? GetValue(lref).
-        if is_reference(&self.left) {
-            ctx.add_instruction(Instruction::GetValue);
-        }
-        ctx.add_instruction(Instruction::Load);
+    // 2. Let lval be ? GetValue(lref).
+    if is_reference(&expression.left) {
+        ctx.add_instruction(Instruction::GetValue);
+    }
+    ctx.add_instruction(Instruction::Load);
 
-        // 3. Let rref be ? Evaluation of rightOperand.
-        self.right.compile(ctx);
+    // 3. Let rref be ? Evaluation of rightOperand.
+    expression.right.compile(ctx);
 
-        // 4. Let rval be ? GetValue(rref).
-        if is_reference(&self.right) {
-            ctx.add_instruction(Instruction::GetValue);
-        }
+    // 4. Let rval be ? GetValue(rref).
+    if is_reference(&expression.right) {
+        ctx.add_instruction(Instruction::GetValue);
+    }
+}
+
+impl<'s> CompileEvaluation<'s> for ast::BinaryExpression<'s> {
+    fn compile(&'s self, ctx: &mut CompileContext<'_, 's, '_, '_>) {
+        compile_binary_operands(self, ctx);
 
         match self.operator {
             BinaryOperator::LessThan => {
@@ -374,31 +526,64 @@ impl<'s> CompileEvaluation<'s> for ast::Function<'s> {
     }
 }
 
+/// Extra capacity reserved for each spread entry of an object or array
+/// literal, as the number of entries it produces is only known at runtime.
+const SPREAD_CAPACITY_HINT: usize = 4;
+
+/// Returns the capacity to reserve for an object or array literal with the
+/// given numbers of plain and spread entries.
+fn literal_capacity_hint(entries: usize, spreads: usize) -> usize {
+    entries
+        .saturating_add(spreads.saturating_mul(SPREAD_CAPACITY_HINT))
+        .min(IndexType::MAX as usize)
+}
+
+/// Returns true if the property is a `__proto__: value` prototype setter
+/// instead of a property definition.
+fn is_proto_setter(prop: &ast::ObjectProperty) -> bool {
+    matches!(&prop.key, ast::PropertyKey::StaticIdentifier(id) if id.name == "__proto__")
+        && prop.kind == ast::PropertyKind::Init
+        && !prop.shorthand
+        && !prop.method
+}
+
 impl<'s> CompileEvaluation<'s> for ast::ObjectExpression<'s> {
     fn compile(&'s self, ctx: &mut CompileContext<'_, 's, '_, '_>) {
-        // TODO: Consider preparing the properties onto the stack and creating
-        // the object with a known size.
-        ctx.add_instruction(Instruction::ObjectCreate);
+        // Reserve property storage for every defined property up front so
+        // that the object doesn't grow while it is being constructed.
+        let mut properties = 0;
+        let mut spreads = 0;
+        for property in self.properties.iter() {
+            match property {
+                ast::ObjectPropertyKind::ObjectProperty(prop) => {
+                    if !is_proto_setter(prop) {
+                        properties += 1;
+                    }
+                }
+                ast::ObjectPropertyKind::SpreadProperty(_) => spreads += 1,
+            }
+        }
+        ctx.add_instruction_with_immediate(
+            Instruction::ObjectCreate,
+            literal_capacity_hint(properties, spreads),
+        );
         for property in self.properties.iter() {
             match property {
                 ast::ObjectPropertyKind::ObjectProperty(prop) => {
-                    let mut is_proto_setter = false;
+                    let is_proto_setter = is_proto_setter(prop);
                     match &prop.key {
                         // It shouldn't be possible for objects to be created
                         // with private identifiers as keys.
                         ast::PropertyKey::PrivateIdentifier(_) => unreachable!(),
+                        // If property key is "__proto__" then we should
+                        // dispatch a SetPrototype instruction.
+                        ast::PropertyKey::StaticIdentifier(_) if is_proto_setter => {}
                         ast::PropertyKey::StaticIdentifier(id) => {
                             if id.name == "__proto__" {
-                                if prop.kind == ast::PropertyKind::Init && !prop.shorthand {
-                                    // If property key is "__proto__" then we
-                                    // should dispatch a SetPrototype instruction.
-                                    is_proto_setter = true;
-                                } else {
-                                    ctx.add_instruction_with_constant(
-                                        Instruction::StoreConstant,
-                                        BUILTIN_STRING_MEMORY.__proto__,
-                                    );
-                                }
+                                ctx.add_instruction_with_constant(
+                                    Instruction::StoreConstant,
+                                    BUILTIN_STRING_MEMORY.__proto__,
+                                );
                             } else {
                                 let identifier = ctx.create_property_key(&id.name);
                                 ctx.add_instruction_with_constant(
@@ -421,6 +606,32 @@ impl<'s> CompileEvaluation<'s> for ast::ObjectExpression<'s> {
                         ctx.add_instruction(Instruction::Load);
                     }
                     match prop.kind {
+                        ast::PropertyKind::Init if prop.method => {
+                            let ast::Expression::FunctionExpression(function_expression) =
+                                &prop.value
+                            else {
+                                unreachable!()
+                            };
+                            // Note: MakeMethod is performed as part of
+                            // ObjectDefineMethod.
+                            ctx.add_instruction_with_function_expression_and_immediate(
+                                Instruction::ObjectDefineMethod,
+                                FunctionExpression {
+                                    expression: SendableRef::new(unsafe {
+                                        core::mem::transmute::<
+                                            &ast::Function<'_>,
+                                            &'static ast::Function<'static>,
+                                        >(
+                                            function_expression
+                                        )
+                                    }),
+                                    identifier: None,
+                                    compiled_bytecode: None,
+                                },
+                                // enumerable: true,
+                                true.into(),
+                            );
+                        }
                         ast::PropertyKind::Init => {
                             if !is_proto_setter && is_anonymous_function_definition(&prop.value) {
                                 ctx.name_identifier = Some(NamedEvaluationParameter::Stack);
@@ -486,8 +697,15 @@ impl<'s> CompileEvaluation<'s> for ast::ObjectExpression<'s> {
 
 impl<'s> CompileEvaluation<'s> for ast::ArrayExpression<'s> {
     fn compile(&'s self, ctx: &mut CompileContext<'_, 's, '_, '_>) {
-        let elements_min_count = self.elements.len();
-        ctx.add_instruction_with_immediate(Instruction::ArrayCreate, elements_min_count);
+        let spreads = self
+            .elements
+            .iter()
+            .filter(|element| matches!(element, ast::ArrayExpressionElement::SpreadElement(_)))
+            .count();
+        ctx.add_instruction_with_immediate(
+            Instruction::ArrayCreate,
+            literal_capacity_hint(self.elements.len() - spreads, spreads),
+        );
         if self.elements.is_empty() {
             return;
         }
@@ -770,6 +988,21 @@ fn compile_optional_base_reference<'s>(
 
 impl<'s> CompileEvaluation<'s> for ast::ComputedMemberExpression<'s> {
     fn compile(&'s self, ctx: &mut CompileContext<'_, 's, '_, '_>) {
+        if let ast::Expression::Super(_) = self.object {
+            // ### SuperProperty : super [ Expression ]
+            // 1. Let env be GetThisEnvironment().
+            // 2. Let actualThis be ? env.GetThisBinding().
+            ctx.add_instruction(Instruction::ResolveThisBinding);
+            ctx.add_instruction(Instruction::Load);
+            // 3. Let propertyNameReference be ? Evaluation of Expression.
+            self.expression.compile(ctx);
+            // 4. Let propertyNameValue be ? GetValue(propertyNameReference).
+            if is_reference(&self.expression) {
+                ctx.add_instruction(Instruction::GetValue);
+            }
+            ctx.add_instruction(Instruction::MakeSuperPropertyReferenceWithExpressionKey);
+            return;
+        }
         compile_optional_base_reference(&self.object, self.optional, ctx);
         // If we do not have optional chaining present it means that base value
         // is currently in the result slot. We need to store it on the stack.
@@ -797,6 +1030,18 @@ impl<'s> CompileEvaluation<'s> for ast::ComputedMemberExpression<'s> {
 
 impl<'s> CompileEvaluation<'s> for ast::StaticMemberExpression<'s> {
     fn compile(&'s self, ctx: &mut CompileContext<'_, 's, '_, '_>) {
+        if let ast::Expression::Super(_) = self.object {
+            // ### SuperProperty : super . IdentifierName
+            // 1. Let env be GetThisEnvironment().
+            // 2. Let actualThis be ? env.GetThisBinding().
+            ctx.add_instruction(Instruction::ResolveThisBinding);
+            let identifier = ctx.create_string(self.property.name.as_str());
+            ctx.add_instruction_with_identifier(
+                Instruction::MakeSuperPropertyReferenceWithIdentifierKey,
+                identifier,
+            );
+            return;
+        }
         compile_optional_base_reference(&self.object, self.optional, ctx);
         // If we are in an optional chain then result will be on the top of the
         // stack. We need to pop it into the register slot in that case.
@@ -913,14 +1158,9 @@ impl<'s> CompileEvaluation<'s> for ast::ConditionalExpression<'s> {
     /// ### [13.14.1 Runtime Semantics: Evaluation](https://tc39.es/ecma262/#sec-conditional-operator-runtime-semantics-evaluation)
     fn compile(&'s self, ctx: &mut CompileContext<'_, 's, '_, '_>) {
         // 1. Let lref be ? Evaluation of ShortCircuitExpression.
-        self.test.compile(ctx);
         // 2. Let lval be ToBoolean(? GetValue(lref)).
-        if is_reference(&self.test) {
-            ctx.add_instruction(Instruction::GetValue);
-        }
         // Jump over first AssignmentExpression (consequent) if test fails.
-        // Note: JumpIfNot performs ToBoolean from above step.
-        let jump_to_second = ctx.add_instruction_with_jump_slot(Instruction::JumpIfNot);
+        let jumps_to_second = compile_test(&self.test, false, ctx);
         // 3. If lval is true, then
         // a. Let trueRef be ? Evaluation of the first AssignmentExpression.
         self.consequent.compile(ctx);
@@ -931,7 +1171,9 @@ impl<'s> CompileEvaluation<'s> for ast::ConditionalExpression<'s> {
         // Jump over second AssignmentExpression (alternate).
         let jump_over_second = ctx.add_instruction_with_jump_slot(Instruction::Jump);
         // 4. Else,
-        ctx.set_jump_target_here(jump_to_second);
+        for jump_to_second in jumps_to_second {
+            ctx.set_jump_target_here(jump_to_second);
+        }
         // a. Let falseRef be ? Evaluation of the second AssignmentExpression.
         self.alternate.compile(ctx);
         // b. Return ? GetValue(falseRef).
@@ -984,8 +1226,10 @@ impl<'s> CompileEvaluation<'s> for ast::PrivateInExpression<'s> {
 impl<'s> CompileEvaluation<'s> for ast::RegExpLiteral<'s> {
     fn compile(&'s self, ctx: &mut CompileContext<'_, 's, '_, '_>) {
         let pattern = self.regex.pattern.text.as_str();
-        let regexp = ctx.create_regexp(pattern, self.regex.flags);
-        ctx.add_instruction_with_constant(Instruction::StoreConstant, regexp);
+        // The pattern is parsed once per call site into a template RegExp;
+        // every evaluation of the literal creates a new object from it.
+        let template = ctx.create_regexp(pattern, self.regex.flags);
+        ctx.add_instruction_with_constant(Instruction::CreateRegExp, template);
     }
 }
 
@@ -1027,13 +1271,63 @@ impl<'s> CompileEvaluation<'s> for ast::SequenceExpression<'s> {
 
 impl<'s> CompileEvaluation<'s> for ast::Super {
     fn compile(&'s self, _ctx: &mut CompileContext<'_, 's, '_, '_>) {
-        todo!()
+        unreachable!("super is compiled as part of SuperProperty or SuperCall")
     }
 }
 
 impl<'s> CompileEvaluation<'s> for ast::TaggedTemplateExpression<'s> {
-    fn compile(&'s self, _ctx: &mut CompileContext<'_, 's, '_, '_>) {
-        todo!()
+    /// ### [13.3.11.1 Runtime Semantics: Evaluation](https://tc39.es/ecma262/#sec-tagged-templates-runtime-semantics-evaluation)
+    ///
+    /// ```text
+    /// MemberExpression : MemberExpression TemplateLiteral
+    /// ```
+    fn compile(&'s self, ctx: &mut CompileContext<'_, 's, '_, '_>) {
+        // 1. Let tagRef be ? Evaluation of MemberExpression.
+        self.tag.compile(ctx);
+        // 2. Let tagFunc be ? GetValue(tagRef).
+        let need_pop_reference = if is_reference(&self.tag) {
+            ctx.add_instruction(Instruction::GetValueKeepReference);
+            // Evaluating the substitutions may clobber the tag's this
+            // reference.
+            if !self.quasi.expressions.is_empty() {
+                ctx.add_instruction(Instruction::PushReference);
+                true
+            } else {
+                false
+            }
+        } else {
+            false
+        };
+        ctx.add_instruction(Instruction::Load);
+        // 3. Let thisCall be this MemberExpression.
+        // 4. Let tailCall be IsInTailPosition(thisCall).
+        // 5. Return ? EvaluateCall(tagFunc, tagRef, TemplateLiteral, tailCall).
+
+        // ArgumentListEvaluation of TemplateLiteral
+        // 1. Let siteObj be GetTemplateObject(templateLiteral).
+        ctx.add_template_literal(SendableRef::new(unsafe {
+            core::mem::transmute::<
+                &ast::TemplateLiteral<'_>,
+                &'static ast::TemplateLiteral<'static>,
+            >(&self.quasi)
+        }));
+        ctx.add_instruction(Instruction::Load);
+        // 2. Let remaining be ? ArgumentListEvaluation of SubstitutionTemplate.
+        for expression in self.quasi.expressions.iter() {
+            expression.compile(ctx);
+            if is_reference(expression) {
+                ctx.add_instruction(Instruction::GetValue);
+            }
+            ctx.add_instruction(Instruction::Load);
+        }
+        // 3. Return the list-concatenation of « siteObj » and remaining.
+        if need_pop_reference {
+            ctx.add_instruction(Instruction::PopReference);
+        }
+        ctx.add_instruction_with_immediate(
+            Instruction::EvaluateCall,
+            self.quasi.expressions.len() + 1,
+        );
     }
 }
 
@@ -1231,12 +1525,11 @@ impl<'s> CompileEvaluation<'s> for ast::ReturnStatement<'s> {
 impl<'s> CompileEvaluation<'s> for ast::IfStatement<'s> {
     fn compile(&'s self, ctx: &mut CompileContext<'_, 's, '_, '_>) {
         // if (test) consequent
-        self.test.compile(ctx);
-        if is_reference(&self.test) {
-            ctx.add_instruction(Instruction::GetValue);
-        }
         // jump over consequent if test fails
-        let jump_to_else = ctx.add_instruction_with_jump_slot(Instruction::JumpIfNot);
+        let jumps_to_else = compile_test(&self.test, false, ctx);
+        // Note: The completion value of the IfStatement is
+        // UpdateEmpty(stmtCompletion, undefined).
+        ctx.store_undefined_completion_value();
         self.consequent.compile(ctx);
         let mut jump_over_else = None;
         if let Some(alternate) = &self.alternate {
@@ -1248,11 +1541,25 @@ impl<'s> CompileEvaluation<'s> for ast::IfStatement<'s> {
             }
 
             // Jump to else-branch when if test fails.
-            ctx.set_jump_target_here(jump_to_else);
+            for jump_to_else in jumps_to_else {
+                ctx.set_jump_target_here(jump_to_else);
+            }
+            ctx.store_undefined_completion_value();
             alternate.compile(ctx);
+        } else if ctx.completion_value() {
+            // 3. If exprValue is false, return undefined.
+            if !ctx.is_unreachable() {
+                jump_over_else = Some(ctx.add_instruction_with_jump_slot(Instruction::Jump));
+            }
+            for jump_to_else in jumps_to_else {
+                ctx.set_jump_target_here(jump_to_else);
+            }
+            ctx.store_undefined_completion_value();
         } else {
             // Jump over if-branch when if test fails.
-            ctx.set_jump_target_here(jump_to_else);
+            for jump_to_else in jumps_to_else {
+                ctx.set_jump_target_here(jump_to_else);
+            }
         }
 
         // Jump over else-branch at the end of if-branch if necessary.
@@ -1608,7 +1915,14 @@ fn complex_object_pattern<'s>(
             }
             ast::PropertyKey::PrivateIdentifier(_) => todo!(),
             _ => {
-                property.key.to_expression().compile(ctx);
+                // Keep the object on the stack for the following properties.
+                ctx.add_instruction(Instruction::StoreCopy);
+                ctx.add_instruction(Instruction::Load);
+                let key = property.key.to_expression();
+                key.compile(ctx);
+                if is_reference(key) {
+                    ctx.add_instruction(Instruction::GetValue);
+                }
                 ctx.add_instruction(Instruction::EvaluatePropertyAccessWithExpressionKey);
             }
         }
@@ -1629,6 +1943,7 @@ fn complex_object_pattern<'s>(
 
         // We have kept the references for all of the properties read in the reference stack, so we
         // can now use them to exclude those properties from the rest object.
+        ctx.add_instruction(Instruction::Store);
         ctx.add_instruction_with_immediate(
             Instruction::CopyDataPropertiesIntoObject,
             object_pattern.properties.len(),
@@ -1812,8 +2127,10 @@ impl<'s> CompileEvaluation<'s> for ast::VariableDeclaration<'s> {
                     // 1. Let bindingId be StringValue of BindingIdentifier.
                     // 2. Let lhs be ? ResolveBinding(bindingId).
                     let identifier_string = ctx.create_string(identifier.name.as_str());
-                    let identifier = ctx.add_identifier(identifier_string);
-                    ctx.add_instruction_with_immediate(Instruction::ResolveBinding, identifier);
+                    ctx.add_instruction_with_identifier(
+                        Instruction::ResolveBinding,
+                        identifier_string,
+                    );
                     let is_literal = init.is_literal();
                     if !is_literal {
                         ctx.add_instruction(Instruction::PushReference);
@@ -1821,6 +2138,7 @@ impl<'s> CompileEvaluation<'s> for ast::VariableDeclaration<'s> {
 
                     // 3. If IsAnonymousFunctionDefinition(Initializer) is true, then
                     if is_anonymous_function_definition(init) {
+                        let identifier = ctx.add_identifier(identifier_string);
                         ctx.add_instruction_with_immediate(Instruction::LoadConstant, identifier);
                         // a. Let value be ? NamedEvaluation of Initializer with argument StackId.
                         ctx.name_identifier = Some(NamedEvaluationParameter::Stack);
@@ -1870,8 +2188,10 @@ impl<'s> CompileEvaluation<'s> for ast::VariableDeclaration<'s> {
 
                     // 1. Let lhs be ! ResolveBinding(StringValue of BindingIdentifier).
                     let identifier_string = ctx.create_string(identifier.name.as_str());
-                    let identifier = ctx.add_identifier(identifier_string);
-                    ctx.add_instruction_with_immediate(Instruction::ResolveBinding, identifier);
+                    ctx.add_instruction_with_identifier(
+                        Instruction::ResolveBinding,
+                        identifier_string,
+                    );
 
                     let Some(init) = &decl.init else {
                         // LexicalBinding : BindingIdentifier
@@ -1897,6 +2217,7 @@ impl<'s> CompileEvaluation<'s> for ast::VariableDeclaration<'s> {
                     // 3. If IsAnonymousFunctionDefinition(Initializer) is true, then
                     if is_anonymous_function_definition(init) {
                         // a. Let value be ? NamedEvaluation of Initializer with argument bindingId.
+                        let identifier = ctx.add_identifier(identifier_string);
                         ctx.add_instruction_with_immediate(Instruction::LoadConstant, identifier);
                         ctx.name_identifier = Some(NamedEvaluationParameter::Stack);
                         init.compile(ctx);
@@ -2047,18 +2368,20 @@ impl<'s> CompileLabelledEvaluation<'s> for ast::ForStatement<'s> {
             create_per_iteration_env(ctx);
         }
 
+        // ## 14.7.4.3 ForBodyEvaluation
+        // 1. Let V be undefined.
+        ctx.store_undefined_completion_value();
         // 3. Repeat,
         ctx.enter_loop(label_set.cloned());
         let loop_jump = ctx.get_jump_index_to_here();
-        let end_jump = if let Some(test) = &self.test {
-            test.compile(ctx);
-            if is_reference(test) {
-                ctx.add_instruction(Instruction::GetValue);
-            }
-            // jump over consequent if test fails
-            Some(ctx.add_instruction_with_jump_slot(Instruction::JumpIfNot))
+        // jump over consequent if test fails
+        let end_jumps = if let Some(test) = &self.test {
+            ctx.push_completion_value();
+            let end_jumps = compile_test(test, false, ctx);
+            ctx.pop_completion_value();
+            end_jumps
         } else {
-            None
+            vec![]
         };
 
         self.body.compile(ctx);
@@ -2069,6 +2392,7 @@ impl<'s> CompileLabelledEvaluation<'s> for ast::ForStatement<'s> {
             loop_jump.clone()
         } else {
             let continue_target = ctx.get_jump_index_to_here();
+            ctx.push_completion_value();
             if let Some(create_per_iteration_env) = create_per_iteration_env {
                 create_per_iteration_env(ctx);
             }
@@ -2076,13 +2400,17 @@ impl<'s> CompileLabelledEvaluation<'s> for ast::ForStatement<'s> {
             if let Some(update) = &self.update {
                 update.compile(ctx);
             }
+            ctx.pop_completion_value();
 
             continue_target
         };
 
         ctx.add_jump_instruction_to_index(Instruction::Jump, loop_jump);
-        if let Some(end_jump) = end_jump {
-            ctx.set_jump_target_here(end_jump);
+        if !end_jumps.is_empty() {
+            for end_jump in end_jumps {
+                ctx.set_jump_target_here(end_jump);
+            }
+            ctx.pop_completion_value();
         }
 
         ctx.exit_loop(continue_target);
@@ -2145,11 +2473,16 @@ impl<'s> CompileLabelledEvaluation<'s> for ast::SwitchStatement<'s> {
             jump_indexes.push(ctx.add_instruction_with_jump_slot(Instruction::JumpIfTrue));
         }
 
-        if has_default {
+        let jump_to_no_match = if has_default {
             // 10. If foundInB is true, return V.
             // 11. Let defaultR be Completion(Evaluation of DefaultClause).
             jump_indexes.push(ctx.add_instruction_with_jump_slot(Instruction::Jump));
-        }
+            None
+        } else {
+            // No case matched and there is no default case: skip all of the
+            // case bodies.
+            Some(ctx.add_instruction_with_jump_slot(Instruction::Jump))
+        };
 
         let mut index = 0;
         for (i, case) in self.cases.iter().enumerate() {
@@ -2183,6 +2516,22 @@ impl<'s> CompileLabelledEvaluation<'s> for ast::SwitchStatement<'s> {
             }
         }
 
+        if let Some(jump_to_no_match) = jump_to_no_match {
+            let jump_over_no_match = if !ctx.is_unreachable() {
+                Some(ctx.add_instruction_with_jump_slot(Instruction::Jump))
+            } else {
+                None
+            };
+            ctx.set_jump_target_here(jump_to_no_match);
+            // Pop the switchValue from the stack.
+            ctx.add_instruction(Instruction::Store);
+            // 1. Let V be undefined.
+            ctx.add_instruction_with_constant(Instruction::StoreConstant, Value::Undefined);
+            if let Some(jump_over_no_match) = jump_over_no_match {
+                ctx.set_jump_target_here(jump_over_no_match);
+            }
+        }
+
         if did_enter_declarative_environment {
             ctx.exit_lexical_scope();
         }
@@ -2214,6 +2563,9 @@ impl<'s> CompileEvaluation<'s> for ast::TryStatement<'s> {
             None
         };
         // 1. Let B be Completion(Evaluation of Block).
+        // Note: The completion value of the TryStatement is
+        // UpdateEmpty(C, undefined).
+        ctx.store_undefined_completion_value();
         self.block.compile(ctx);
         // 2. If B is a throw completion, let C be
         //    Completion(CatchClauseEvaluation of Catch with argument B.[[Value]]).
@@ -2255,8 +2607,14 @@ impl<'s> CompileEvaluation<'s> for ast::TryStatement<'s> {
                 // 6. If status is an abrupt completion, then
                 // a. Set the running execution context's LexicalEnvironment to oldEnv.
                 // b. Return ? status.
+                // Note: Exception handlers record the LexicalEnvironment
+                // that was current when they were pushed, and the VM
+                // restores it when jumping to the handler. This covers
+                // both an abrupt status here and an exception thrown out
+                // of the catch Block below.
             }
             // 7. Let B be Completion(Evaluation of Block).
+            ctx.store_undefined_completion_value();
             catch_clause.body.compile(ctx);
             // 8. Set the running execution context's LexicalEnvironment to oldEnv.
             if catch_clause.param.is_some() {
@@ -2284,26 +2642,25 @@ impl<'s> CompileLabelledEvaluation<'s> for ast::WhileStatement<'s> {
         label_set: Option<&mut Vec<&'s LabelIdentifier<'s>>>,
         ctx: &mut CompileContext<'_, 's, '_, '_>,
     ) {
+        // 1. Let V be undefined.
+        ctx.store_undefined_completion_value();
         ctx.enter_loop(label_set.cloned());
 
-        // 1. Let V be undefined.
         // 2. Repeat
         let continue_target = ctx.get_jump_index_to_here();
 
         // a. Let exprRef be ? Evaluation of Expression.
         // OPTIMISATION: while(true) loops are pretty common, skip the test.
-        let end_jump = if !is_boolean_literal_true(&self.test) {
-            self.test.compile(ctx);
-            if is_reference(&self.test) {
-                // b. Let exprValue be ? GetValue(exprRef).
-                ctx.add_instruction(Instruction::GetValue);
-            }
-
+        let end_jumps = if !is_boolean_literal_true(&self.test) {
+            // b. Let exprValue be ? GetValue(exprRef).
             // c. If ToBoolean(exprValue) is false, return V.
             // jump over loop jump if test fails
-            Some(ctx.add_instruction_with_jump_slot(Instruction::JumpIfNot))
+            ctx.push_completion_value();
+            let end_jumps = compile_test(&self.test, false, ctx);
+            ctx.pop_completion_value();
+            end_jumps
         } else {
-            None
+            vec![]
         };
 
         // d. Let stmtResult be Completion(Evaluation of Statement).
@@ -2312,8 +2669,11 @@ impl<'s> CompileLabelledEvaluation<'s> for ast::WhileStatement<'s> {
         ctx.add_jump_instruction_to_index(Instruction::Jump, continue_target.clone());
         // e. If LoopContinues(stmtResult, labelSet) is false, return ? UpdateEmpty(stmtResult, V).
         // f. If stmtResult.[[Value]] is not EMPTY, set V to stmtResult.[[Value]].
-        if let Some(end_jump) = end_jump {
-            ctx.set_jump_target_here(end_jump);
+        if !end_jumps.is_empty() {
+            for end_jump in end_jumps {
+                ctx.set_jump_target_here(end_jump);
+            }
+            ctx.pop_completion_value();
         }
         ctx.exit_loop(continue_target);
     }
@@ -2325,6 +2685,7 @@ impl<'s> CompileLabelledEvaluation<'s> for ast::DoWhileStatement<'s> {
         label_set: Option<&mut Vec<&'s LabelIdentifier<'s>>>,
         ctx: &mut CompileContext<'_, 's, '_, '_>,
     ) {
+        ctx.store_undefined_completion_value();
         ctx.enter_loop(label_set.cloned());
         let start_jump = ctx.get_jump_index_to_here();
         self.body.compile(ctx);
@@ -2334,19 +2695,20 @@ impl<'s> CompileLabelledEvaluation<'s> for ast::DoWhileStatement<'s> {
         // jump over loop jump if test fails
         // OPTIMISATION: do {} while(true) loops are still somewhat common,
         // skip the test.
-        let end_jump = if !is_boolean_literal_true(&self.test) {
-            self.test.compile(ctx);
-            if is_reference(&self.test) {
-                ctx.add_instruction(Instruction::GetValue);
-            }
-
-            Some(ctx.add_instruction_with_jump_slot(Instruction::JumpIfNot))
+        let end_jumps = if !is_boolean_literal_true(&self.test) {
+            ctx.push_completion_value();
+            let end_jumps = compile_test(&self.test, false, ctx);
+            ctx.pop_completion_value();
+            end_jumps
         } else {
-            None
+            vec![]
         };
         ctx.add_jump_instruction_to_index(Instruction::Jump, start_jump);
-        if let Some(end_jump) = end_jump {
-            ctx.set_jump_target_here(end_jump);
+        if !end_jumps.is_empty() {
+            for end_jump in end_jumps {
+                ctx.set_jump_target_here(end_jump);
+            }
+            ctx.pop_completion_value();
         }
         ctx.exit_loop(continue_target);
     }
@@ -2371,11 +2733,21 @@ impl<'s> CompileEvaluation<'s> for ast::Statement<'s> {
             // statements cannot be executed and do not need to be compiled.
             return;
         }
+        ctx.add_span(self.span());
         match self {
             ast::Statement::ExpressionStatement(x) => x.compile(ctx),
             ast::Statement::ReturnStatement(x) => x.compile(ctx),
             ast::Statement::IfStatement(x) => x.compile(ctx),
-            ast::Statement::VariableDeclaration(x) => x.compile(ctx),
+            ast::Statement::VariableDeclaration(x) => {
+                // Note: Declarations have an EMPTY completion value.
+                if x.kind.is_lexical() || x.declarations.iter().any(|decl| decl.init.is_some()) {
+                    ctx.push_completion_value();
+                    x.compile(ctx);
+                    ctx.pop_completion_value();
+                } else {
+                    x.compile(ctx);
+                }
+            }
             ast::Statement::FunctionDeclaration(_) => {
                 // Note: Function declaration statements are always hoisted.
                 // There is no work left to do here.
@@ -2387,7 +2759,7 @@ impl<'s> CompileEvaluation<'s> for ast::Statement<'s> {
             ast::Statement::TryStatement(x) => x.compile(ctx),
             Statement::BreakStatement(statement) => statement.compile(ctx),
             Statement::ContinueStatement(statement) => statement.compile(ctx),
-            Statement::DebuggerStatement(_) => todo!(),
+            Statement::DebuggerStatement(_) => ctx.add_instruction(Instruction::Debug),
             Statement::DoWhileStatement(statement) => statement.compile_labelled(None, ctx),
             Statement::ForInStatement(statement) => statement.compile_labelled(None, ctx),
             Statement::ForOfStatement(statement) => statement.compile_labelled(None, ctx),
@@ -2395,7 +2767,11 @@ impl<'s> CompileEvaluation<'s> for ast::Statement<'s> {
             Statement::SwitchStatement(statement) => statement.compile_labelled(None, ctx),
             Statement::WhileStatement(statement) => statement.compile_labelled(None, ctx),
             Statement::WithStatement(_) => todo!(),
-            Statement::ClassDeclaration(x) => x.compile(ctx),
+            Statement::ClassDeclaration(x) => {
+                ctx.push_completion_value();
+                x.compile(ctx);
+                ctx.pop_completion_value();
+            }
             Statement::ImportDeclaration(_) => todo!(),
             Statement::ExportAllDeclaration(_) => todo!(),
             Statement::ExportDefaultDeclaration(_) => todo!(),
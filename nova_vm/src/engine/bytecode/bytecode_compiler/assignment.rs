@@ -133,9 +133,14 @@ impl<'s> CompileEvaluation<'s> for ast::AssignmentExpression<'s> {
             ctx.add_instruction(Instruction::LoadCopy);
             ctx.add_instruction(Instruction::PopReference);
             ctx.add_instruction(Instruction::PutValue);
+            let jump_over_pop_reference = ctx.add_instruction_with_jump_slot(Instruction::Jump);
 
             // 4. ... return lval.
             ctx.set_jump_target_here(jump_to_end);
+            // The reference is not needed when returning lval, but it must
+            // still be popped to keep the reference stack balanced.
+            ctx.add_instruction(Instruction::PopReference);
+            ctx.set_jump_target_here(jump_over_pop_reference);
             ctx.add_instruction(Instruction::Store);
         } else {
             // 2. let lval be ? GetValue(lref).
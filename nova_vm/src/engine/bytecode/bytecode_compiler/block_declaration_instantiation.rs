@@ -73,11 +73,16 @@ pub fn handle_block_lexically_scoped_declaration<'s>(
             // 1. Perform ! env.CreateMutableBinding(dn, false).
             // NOTE: This step is replaced in section B.3.2.6.
             ctx.add_instruction_with_identifier(Instruction::CreateMutableBinding, dn);
+            // Note: Instantiating the function object overwrites the result
+            // register, which may hold the completion value of the code
+            // preceding the block.
+            ctx.push_completion_value();
             // ii. Let fo be InstantiateFunctionObject of d with arguments env and privateEnv.
             decl.compile(ctx);
             // iii. Perform ! env.InitializeBinding(fn, fo).
             ctx.add_instruction_with_identifier(Instruction::ResolveBinding, dn);
             ctx.add_instruction(Instruction::InitializeReferencedBinding);
+            ctx.pop_completion_value();
             // NOTE: This step is replaced in section B.3.2.6.
         }
         LexicallyScopedDeclaration::Class(decl) => {
@@ -81,6 +81,22 @@ impl<'s> CompileEvaluation<'s> for ast::Class<'s> {
             }
         }
 
+        // The prototype gets a property for the constructor and for each
+        // public method and accessor.
+        let prototype_capacity = 1 + self
+            .body
+            .body
+            .iter()
+            .filter(|element| {
+                matches!(
+                    element,
+                    ast::ClassElement::MethodDefinition(method)
+                        if !method.r#static
+                            && !method.key.is_private_identifier()
+                            && method.kind != MethodDefinitionKind::Constructor
+                )
+            })
+            .count();
         let mut has_constructor_parent = false;
 
         // 7. If ClassHeritage is present, then
@@ -91,7 +107,7 @@ impl<'s> CompileEvaluation<'s> for ast::Class<'s> {
                 // Hence we do not need to set has_constructor_parent true.
                 // But we do need to remember that this is still a derived
                 // class.
-                ctx.add_instruction(Instruction::ObjectCreate);
+                ctx.add_instruction_with_immediate(Instruction::ObjectCreate, prototype_capacity);
                 ctx.add_instruction_with_constant(Instruction::StoreConstant, Value::Null);
                 ctx.add_instruction(Instruction::ObjectSetPrototype);
             } else {
@@ -119,7 +135,7 @@ impl<'s> CompileEvaluation<'s> for ast::Class<'s> {
                 // i. Let protoParent be null.
                 // Note: We already have null on the stack.
                 // 9. Let proto be OrdinaryObjectCreate(protoParent).
-                ctx.add_instruction(Instruction::ObjectCreate);
+                ctx.add_instruction_with_immediate(Instruction::ObjectCreate, prototype_capacity);
                 // Now we have proto on the stack followed be null (protoParent).
                 ctx.add_instruction(Instruction::Swap);
                 // Now we have null (protoParent) followed by proto.
@@ -185,7 +201,7 @@ impl<'s> CompileEvaluation<'s> for ast::Class<'s> {
                 // superclass is second in the stack.
 
                 // 9. Let proto be OrdinaryObjectCreate(protoParent)
-                ctx.add_instruction(Instruction::ObjectCreate);
+                ctx.add_instruction_with_immediate(Instruction::ObjectCreate, prototype_capacity);
                 ctx.add_instruction(Instruction::Swap);
                 // Now protoParent is at the top of the stack, proto is second, and
                 // superclass is third.
@@ -204,7 +220,7 @@ impl<'s> CompileEvaluation<'s> for ast::Class<'s> {
         } else {
             // a. Let protoParent be %Object.prototype%.
             // 9. Let proto be OrdinaryObjectCreate(protoParent).
-            ctx.add_instruction(Instruction::ObjectCreate);
+            ctx.add_instruction_with_immediate(Instruction::ObjectCreate, prototype_capacity);
             // b. Let constructorParent be %Function.prototype%.
             // We omit constructor parent as we statically know it is
             // uninteresting.
@@ -621,11 +637,17 @@ impl<'s> CompileEvaluation<'s> for ast::Class<'s> {
                 // b. Else,
                 // i. Assert: elementRecord is a ClassStaticBlockDefinition Record.
                 // ii. Let result be Completion(Call(elementRecord.[[BodyFunction]], F)).
+                // Note: Static field initializers are evaluated with the
+                // this value bound to F, like static blocks are.
                 PropertyInitializerField::Field((property_key, value)) => {
+                    let completion_value = ctx.enter_class_static_block();
                     compile_class_static_id_field(property_key, value, ctx);
+                    ctx.exit_class_static_block(completion_value);
                 }
                 PropertyInitializerField::Computed((key_id, value)) => {
+                    let completion_value = ctx.enter_class_static_block();
                     compile_class_computed_field(key_id, value, ctx);
+                    ctx.exit_class_static_block(completion_value);
                 }
                 PropertyInitializerField::Private((description, private_identifier, value)) => {
                     // Note: Static private fields follow third after private
@@ -633,7 +655,9 @@ impl<'s> CompileEvaluation<'s> for ast::Class<'s> {
                     let private_identifier = instance_private_field_count
                         + instance_private_method_count
                         + private_identifier;
+                    let completion_value = ctx.enter_class_static_block();
                     compile_class_private_field(description, private_identifier, value, ctx);
+                    ctx.exit_class_static_block(completion_value);
                 }
             }
             // c. If result is an abrupt completion, then
@@ -924,7 +948,7 @@ impl<'s> CompileEvaluation<'s> for ast::StaticBlock<'s> {
         let var_names = class_static_block_var_declared_names(self);
         let lex_declarations = class_static_block_lexically_scoped_declarations(self);
         // c. For each element n of varNames, do
-        ctx.enter_class_static_block();
+        let completion_value = ctx.enter_class_static_block();
         for n in var_names {
             // i. If instantiatedVarNames does not contain n, then
             if instantiated_var_names.contains(&n) {
@@ -997,7 +1021,7 @@ impl<'s> CompileEvaluation<'s> for ast::StaticBlock<'s> {
         for statement in self.body.iter() {
             statement.compile(ctx);
         }
-        ctx.exit_class_static_block();
+        ctx.exit_class_static_block(completion_value);
     }
 }
 
@@ -3,6 +3,8 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use oxc_ast::ast::{self, LabelIdentifier, RegExpFlags, Statement};
+use oxc_span::{Atom, Span};
+use wtf8::Wtf8Buf;
 
 use crate::{
     ecmascript::{
@@ -12,7 +14,11 @@ use crate::{
         types::{BigInt, Number, PropertyKey, String, Value},
     },
     engine::{
-        Executable, FunctionExpression, Instruction, bytecode::executable::ArrowFunctionExpression,
+        Executable, FunctionExpression, Instruction,
+        bytecode::executable::{
+            ArrowFunctionExpression, FrameSlot, FrameSlotKind, FunctionEnvironmentNeeds,
+            SendableRef,
+        },
         context::NoGcScope,
     },
 };
@@ -92,6 +98,10 @@ pub(crate) struct CompileContext<'agent, 'script, 'gc, 'scope> {
     pub(super) is_call_optional_chain_this: bool,
     /// Stores data needed to generate control flow graph transition points.
     control_flow_stack: Vec<ControlFlowStackEntry<'script>>,
+    /// If true, the completion values of statements are observable and must
+    /// be kept in the result register. This is the case in Script and eval
+    /// code, whose result is the completion value of the last statement.
+    completion_value: bool,
 }
 
 impl<'agent, 'script, 'gc, 'scope> CompileContext<'agent, 'script, 'gc, 'scope> {
@@ -106,6 +116,7 @@ impl<'agent, 'script, 'gc, 'scope> CompileContext<'agent, 'script, 'gc, 'scope>
             optional_chains: None,
             is_call_optional_chain_this: false,
             control_flow_stack: Vec::new(),
+            completion_value: false,
         }
     }
 
@@ -154,6 +165,12 @@ impl<'agent, 'script, 'gc, 'scope> CompileContext<'agent, 'script, 'gc, 'scope>
         self.executable.create_string_from_owned(owned)
     }
 
+    /// Create a new JavaScript String from WTF-8 data that may contain lone
+    /// surrogates.
+    pub(super) fn create_string_from_wtf8(&mut self, wtf8: Wtf8Buf) -> String<'gc> {
+        self.executable.create_string_from_wtf8(wtf8)
+    }
+
     /// Enter a labelled statement.
     pub(super) fn enter_label(&mut self, label: &'script LabelIdentifier<'script>) {
         self.control_flow_stack
@@ -224,16 +241,24 @@ impl<'agent, 'script, 'gc, 'scope> CompileContext<'agent, 'script, 'gc, 'scope>
     }
 
     /// Enter a class static initialiser.
-    pub(super) fn enter_class_static_block(&mut self) {
+    ///
+    /// Static initialisers are evaluated like method bodies, so the
+    /// completion values of their statements are not tracked. The previous
+    /// tracking state is returned and must be passed to
+    /// [`Self::exit_class_static_block`].
+    #[must_use]
+    pub(super) fn enter_class_static_block(&mut self) -> bool {
         self.add_instruction(Instruction::EnterClassStaticElementEnvironment);
         self.control_flow_stack
             .push(ControlFlowStackEntry::LexicalScope);
         self.control_flow_stack
             .push(ControlFlowStackEntry::VariableScope);
+        core::mem::replace(&mut self.completion_value, false)
     }
 
-    /// Exit a lexical scope.
-    pub(super) fn exit_class_static_block(&mut self) {
+    /// Exit a class static initialiser.
+    pub(super) fn exit_class_static_block(&mut self, completion_value: bool) {
+        self.completion_value = completion_value;
         matches!(
             self.control_flow_stack.pop(),
             Some(ControlFlowStackEntry::VariableScope)
@@ -322,7 +347,7 @@ impl<'agent, 'script, 'gc, 'scope> CompileContext<'agent, 'script, 'gc, 'scope>
             // First we have to pop off the special finally-exception target.
             self.add_instruction(Instruction::PopExceptionJumpTarget);
             // Then we compile the finally-block.
-            block.compile(self);
+            self.compile_finally_block(block);
             // And continue on our merry way!
         } else {
             // No preceding catch-block exists or the try-block's end is
@@ -335,7 +360,7 @@ impl<'agent, 'script, 'gc, 'scope> CompileContext<'agent, 'script, 'gc, 'scope>
                 // We are reachable, so let's compile the normal finally-block
                 // version here.
                 self.add_instruction(Instruction::PopExceptionJumpTarget);
-                block.compile(self);
+                self.compile_finally_block(block);
                 // We need to jump over the abrupt completion handling blocks,
                 // unless of course we're now unreachable here!
                 if !self.is_unreachable() {
@@ -357,6 +382,17 @@ impl<'agent, 'script, 'gc, 'scope> CompileContext<'agent, 'script, 'gc, 'scope>
         }
     }
 
+    /// Compile a finally-block that is entered with a normal, break, or
+    /// continue completion. A normal completion of the finally-block keeps
+    /// the completion value of the preceding try- or catch-block.
+    fn compile_finally_block(&mut self, block: &'script ast::BlockStatement<'script>) {
+        self.push_completion_value();
+        block.compile(self);
+        if !self.is_unreachable() {
+            self.pop_completion_value();
+        }
+    }
+
     fn compile_abrupt_finally_blocks(
         &mut self,
         block: &'script ast::BlockStatement<'script>,
@@ -387,7 +423,7 @@ impl<'agent, 'script, 'gc, 'scope> CompileContext<'agent, 'script, 'gc, 'scope>
                 // Exit from the finally-block's grasp.
                 self.add_instruction(Instruction::PopExceptionJumpTarget);
                 // Compile the finally-block.
-                block.compile(self);
+                self.compile_finally_block(block);
                 if !end_of_finally_block_is_unreachable {
                     // Then send the break on to its real target.
                     self.compile_break(label);
@@ -400,7 +436,7 @@ impl<'agent, 'script, 'gc, 'scope> CompileContext<'agent, 'script, 'gc, 'scope>
                 // Exit from the finally-block's grasp.
                 self.add_instruction(Instruction::PopExceptionJumpTarget);
                 // Compile the finally-block.
-                block.compile(self);
+                self.compile_finally_block(block);
                 if !end_of_finally_block_is_unreachable {
                     // Then send the continue on to its real target.
                     self.compile_continue(label);
@@ -657,6 +693,66 @@ impl<'agent, 'script, 'gc, 'scope> CompileContext<'agent, 'script, 'gc, 'scope>
         self.executable.is_unreachable()
     }
 
+    /// Keep the completion values of statements in the result register.
+    ///
+    /// The result register starts out as undefined: an EMPTY completion value
+    /// becomes undefined at the end of Script and eval code anyway, and
+    /// statements only need to tell EMPTY and undefined apart in UpdateEmpty,
+    /// where the completion value of the preceding statements is kept as is.
+    pub(crate) fn track_completion_value(&mut self) {
+        self.completion_value = true;
+        self.add_instruction_with_constant(Instruction::StoreConstant, Value::Undefined);
+    }
+
+    /// Returns true if the completion values of statements are kept in the
+    /// result register.
+    pub(super) fn completion_value(&self) -> bool {
+        self.completion_value
+    }
+
+    /// Set the completion value to undefined, as in `UpdateEmpty(C,
+    /// undefined)`.
+    pub(super) fn store_undefined_completion_value(&mut self) {
+        if self.completion_value {
+            self.add_instruction_with_constant(Instruction::StoreConstant, Value::Undefined);
+        }
+    }
+
+    /// Move the completion value onto the stack, before compiling code that
+    /// uses the result register but does not produce a completion value.
+    pub(super) fn push_completion_value(&mut self) {
+        if self.completion_value {
+            self.add_instruction(Instruction::Load);
+        }
+    }
+
+    /// Move the completion value pushed by [`Self::push_completion_value`]
+    /// back into the result register.
+    pub(super) fn pop_completion_value(&mut self) {
+        if self.completion_value {
+            self.add_instruction(Instruction::Store);
+        }
+    }
+
+    /// Keep the given bindings of the function body being compiled in Vm frame
+    /// slots instead of a function Environment Record.
+    pub(crate) fn use_frame_slots(&mut self, frame_slots: &[(Atom<'_>, FrameSlotKind)]) {
+        let frame_slots = frame_slots
+            .iter()
+            .map(|(name, kind)| FrameSlot {
+                name: self.create_string(name),
+                kind: *kind,
+            })
+            .collect();
+        self.executable.set_frame_slots(frame_slots);
+    }
+
+    /// Returns true if the function body being compiled keeps its bindings in
+    /// a function Environment Record.
+    pub(super) fn needs_function_environment(&self) -> bool {
+        self.executable.environment_needs() == FunctionEnvironmentNeeds::Heap
+    }
+
     /// Compile a function body into the current context.
     ///
     /// This is useful when the function body is part of a larger whole, namely
@@ -706,6 +802,10 @@ impl<'agent, 'script, 'gc, 'scope> CompileContext<'agent, 'script, 'gc, 'scope>
         self.executable.add_instruction(instruction);
     }
 
+    pub(super) fn add_span(&mut self, span: Span) {
+        self.executable.add_span(span);
+    }
+
     pub(super) fn add_instruction_with_jump_slot(&mut self, instruction: Instruction) -> JumpIndex {
         self.executable.add_instruction_with_jump_slot(instruction)
     }
@@ -750,6 +850,19 @@ impl<'agent, 'script, 'gc, 'scope> CompileContext<'agent, 'script, 'gc, 'scope>
         instruction: Instruction,
         identifier: String<'gc>,
     ) {
+        if instruction == Instruction::ResolveBinding {
+            if let Some(slot) = self.executable.get_frame_slot_index(identifier) {
+                // The binding lives in a Vm frame slot, not in any
+                // Environment Record.
+                self.executable
+                    .add_instruction_with_identifier_and_immediate(
+                        Instruction::ResolveLocal,
+                        identifier,
+                        slot,
+                    );
+                return;
+            }
+        }
         self.executable
             .add_instruction_with_identifier(instruction, identifier);
     }
@@ -819,6 +932,13 @@ impl<'agent, 'script, 'gc, 'scope> CompileContext<'agent, 'script, 'gc, 'scope>
             .add_arrow_function_expression(arrow_function_expression);
     }
 
+    pub(super) fn add_template_literal(
+        &mut self,
+        template_literal: SendableRef<ast::TemplateLiteral<'static>>,
+    ) {
+        self.executable.add_template_literal(template_literal);
+    }
+
     pub(super) fn set_jump_target_here(&mut self, jump: JumpIndex) {
         self.executable.set_jump_target_here(jump);
     }
@@ -3,7 +3,9 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use num_traits::Num;
-use oxc_ast::ast::RegExpFlags;
+use oxc_ast::ast::{self, RegExpFlags};
+use oxc_span::Span;
+use wtf8::Wtf8Buf;
 
 use crate::{
     ecmascript::{
@@ -13,7 +15,9 @@ use crate::{
     },
     engine::{
         Executable, ExecutableHeapData, FunctionExpression, Instruction,
-        bytecode::executable::ArrowFunctionExpression,
+        bytecode::executable::{
+            ArrowFunctionExpression, FrameSlot, FunctionEnvironmentNeeds, SendableRef,
+        },
         context::{Bindable, NoGcScope},
     },
     heap::CreateHeapData,
@@ -38,7 +42,16 @@ pub(super) struct ExecutableContext<'agent, 'gc, 'scope> {
     function_expressions: Vec<FunctionExpression<'gc>>,
     /// Arrow function expressions being built
     arrow_function_expressions: Vec<ArrowFunctionExpression>,
+    /// Tagged template literal sites being built
+    template_literals: Vec<SendableRef<ast::TemplateLiteral<'static>>>,
     class_initializer_bytecodes: Vec<(Option<Executable<'gc>>, bool)>,
+    /// Environment Record needs of the function body being built
+    environment_needs: FunctionEnvironmentNeeds,
+    /// Frame slot bindings of the function body being built
+    frame_slots: Vec<FrameSlot<'gc>>,
+    /// Source spans of the statements being built, keyed by the index of
+    /// their first instruction
+    spans: Vec<(u32, Span)>,
 }
 
 impl<'agent, 'gc, 'scope> ExecutableContext<'agent, 'gc, 'scope> {
@@ -51,7 +64,11 @@ impl<'agent, 'gc, 'scope> ExecutableContext<'agent, 'gc, 'scope> {
             constants: Vec::new(),
             function_expressions: Vec::new(),
             arrow_function_expressions: Vec::new(),
+            template_literals: Vec::new(),
             class_initializer_bytecodes: Vec::new(),
+            environment_needs: FunctionEnvironmentNeeds::Heap,
+            frame_slots: Vec::new(),
+            spans: Vec::new(),
         }
     }
 
@@ -100,6 +117,10 @@ impl<'agent, 'gc, 'scope> ExecutableContext<'agent, 'gc, 'scope> {
         String::from_string(self.agent, owned, self.gc)
     }
 
+    pub(super) fn create_string_from_wtf8(&mut self, wtf8: Wtf8Buf) -> String<'gc> {
+        String::from_wtf8_buf(self.agent, wtf8, self.gc)
+    }
+
     /// Returns true if the current instruction pointer is a unreachable.
     pub(super) fn is_unreachable(&self) -> bool {
         self.current_instruction_pointer_is_unreachable
@@ -111,14 +132,58 @@ impl<'agent, 'gc, 'scope> ExecutableContext<'agent, 'gc, 'scope> {
             constants: self.constants.unbind().into_boxed_slice(),
             function_expressions: self.function_expressions.unbind().into_boxed_slice(),
             arrow_function_expressions: self.arrow_function_expressions.into_boxed_slice(),
+            template_literals: self.template_literals.into_boxed_slice(),
             class_initializer_bytecodes: self
                 .class_initializer_bytecodes
                 .into_iter()
                 .map(|(exe, b)| (exe.unbind(), b))
                 .collect(),
+            environment_needs: self.environment_needs,
+            frame_slots: self
+                .frame_slots
+                .into_iter()
+                .map(|slot| FrameSlot {
+                    name: slot.name.unbind(),
+                    kind: slot.kind,
+                })
+                .collect(),
+            spans: self.spans.into_boxed_slice(),
         })
     }
 
+    /// Record that the instructions added from here on were compiled from
+    /// the given source span.
+    pub(super) fn add_span(&mut self, span: Span) {
+        let index = self.instructions.len() as u32;
+        match self.spans.last_mut() {
+            // An enclosing statement produced no instructions of its own.
+            Some((last_index, last_span)) if *last_index == index => *last_span = span,
+            _ => self.spans.push((index, span)),
+        }
+    }
+
+    /// Keep the given bindings in Vm frame slots instead of a function
+    /// Environment Record.
+    pub(super) fn set_frame_slots(&mut self, frame_slots: Vec<FrameSlot<'gc>>) {
+        self.environment_needs = if frame_slots.is_empty() {
+            FunctionEnvironmentNeeds::None
+        } else {
+            FunctionEnvironmentNeeds::StackOnly
+        };
+        self.frame_slots = frame_slots;
+    }
+
+    pub(super) fn environment_needs(&self) -> FunctionEnvironmentNeeds {
+        self.environment_needs
+    }
+
+    /// Get the frame slot index of a binding, if it is kept in a frame slot.
+    pub(super) fn get_frame_slot_index(&self, identifier: String<'gc>) -> Option<usize> {
+        self.frame_slots
+            .iter()
+            .position(|slot| slot.name == identifier)
+    }
+
     pub(super) fn add_instruction(&mut self, instruction: Instruction) {
         debug_assert_eq!(instruction.argument_count(), 0);
         debug_assert!(
@@ -308,6 +373,18 @@ impl<'agent, 'gc, 'scope> ExecutableContext<'agent, 'gc, 'scope> {
         self.add_index(index);
     }
 
+    pub(super) fn add_template_literal(
+        &mut self,
+        template_literal: SendableRef<ast::TemplateLiteral<'static>>,
+    ) {
+        let instruction = Instruction::GetTemplateObject;
+        debug_assert_eq!(instruction.argument_count(), 1);
+        self.push_instruction(instruction);
+        self.template_literals.push(template_literal);
+        let index = self.template_literals.len() - 1;
+        self.add_index(index);
+    }
+
     pub(super) fn set_jump_target(&mut self, source: JumpIndex, target: JumpIndex) {
         assert!(target.index < u32::MAX as usize);
         let bytes: [u8; 4] = (target.index as u32).to_ne_bytes();
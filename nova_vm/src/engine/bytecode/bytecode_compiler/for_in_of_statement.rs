@@ -148,8 +148,12 @@ fn for_in_of_body_evaluation<'s>(
         None
     };
 
+    ctx.store_undefined_completion_value();
     // 6. Repeat,
     let loop_start = ctx.get_jump_index_to_here();
+    // Note: Stepping the iterator and binding its value use the result
+    // register, keep V on the stack meanwhile.
+    ctx.push_completion_value();
     // a. Let nextResult be ? Call(iteratorRecord.[[NextMethod]], iteratorRecord.[[Iterator]]).
     // b. If iteratorKind is ASYNC, set nextResult to ? Await(nextResult).
     // c. If nextResult is not an Object, throw a TypeError exception.
@@ -332,6 +336,7 @@ fn for_in_of_body_evaluation<'s>(
     // i. If status is an abrupt completion, then ...
     // Note: we move the abrupt completion handling after the loop to improve
     // performance.
+    ctx.pop_completion_value();
 
     // j. Let result be Completion(Evaluation of stmt).
     stmt.compile(ctx);
@@ -377,6 +382,15 @@ fn for_in_of_body_evaluation<'s>(
         // continue past this line.
     }
 
+    // e. If done is true, return V.
+    let jump_to_end = if ctx.completion_value() {
+        ctx.set_jump_target_here(jump_to_end);
+        ctx.pop_completion_value();
+        ctx.add_instruction_with_jump_slot(Instruction::Jump)
+    } else {
+        jump_to_end
+    };
+
     // l. If LoopContinues(result, labelSet) is false, then
     match iteration_kind {
         // i. If iterationKind is ENUMERATE, then
@@ -31,6 +31,14 @@ pub(crate) fn instantiation<'s>(
     strict: bool,
     is_lexical: bool,
 ) {
+    if !ctx.needs_function_environment() {
+        // All parameters and local bindings live in Vm frame slots which
+        // Vm::execute initializes directly from the arguments list: no
+        // Environment Records, arguments object, or bindings need to be
+        // created.
+        return;
+    }
+
     // 5. Let parameterNames be the BoundNames of formals.
     // 6. If parameterNames has any duplicate entries, let hasDuplicates be true. Otherwise, let hasDuplicates be false.
     let mut parameter_names = AHashSet::with_capacity(formals.parameters_count());
@@ -4,7 +4,7 @@
 
 use core::{
     num::NonZeroU32,
-    ops::{Index, IndexMut},
+    ops::{Index, IndexMut, Range},
 };
 use std::marker::PhantomData;
 
@@ -23,9 +23,14 @@ use crate::{
         context::{Bindable, GcToken, NoGcScope},
         rootable::{HeapRootData, HeapRootRef, Rootable},
     },
-    heap::{CompactionLists, CreateHeapData, Heap, HeapMarkAndSweep, WorkQueues},
+    heap::{CompactionLists, CreateHeapData, Heap, HeapMarkAndSweep, HeapVectorSlot, WorkQueues},
 };
 use oxc_ast::ast::{self, Program, Statement};
+use oxc_span::Span;
+
+mod frame_slot_analysis;
+
+use frame_slot_analysis::analyze_frame_slots;
 
 #[derive(Debug)]
 /// A `Send` and `Sync` wrapper over a `&'static T` where `T` might not itself
@@ -106,7 +111,50 @@ pub(crate) struct ArrowFunctionExpression {
     pub(crate) identifier: Option<NamedEvaluationParameter>,
 }
 
+/// Classification of what an ECMAScript function's bindings require at call
+/// time.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FunctionEnvironmentNeeds {
+    /// The function declares no bindings and never observes its own
+    /// Environment Record: calls need neither an Environment Record nor frame
+    /// slots.
+    None,
+    /// All parameters and local bindings are kept in Vm frame slots: calls do
+    /// not allocate a function Environment Record.
+    StackOnly,
+    /// The function's bindings may be observed from outside the running frame
+    /// or the function otherwise relies on its Environment Record: every call
+    /// allocates a function Environment Record.
+    #[default]
+    Heap,
+}
+
+/// Kind of a binding kept in a Vm frame slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FrameSlotKind {
+    /// Formal parameter, initialized from the arguments list on entry.
+    Parameter,
+    /// `var` binding, initialized to undefined on entry.
+    Var,
+    /// `let` binding, uninitialized until its declaration is evaluated.
+    Let,
+    /// `const` binding, uninitialized until its declaration is evaluated.
+    Const,
+}
+
+/// A binding kept in a Vm frame slot instead of an Environment Record.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct FrameSlot<'a> {
+    pub(crate) name: String<'a>,
+    pub(crate) kind: FrameSlotKind,
+}
+
 /// Reference to a heap-allocated executable VM bytecode.
+///
+/// Executables are owned by the heap and reclaimed by the garbage collector
+/// once they are no longer reachable from a running Vm, a suspended generator,
+/// a function object, or another Executable. Code that holds an Executable
+/// across a garbage collection must root it, usually by scoping it.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(transparent)]
 pub struct Executable<'a>(
@@ -134,7 +182,18 @@ pub struct ExecutableHeapData<'a> {
     pub(crate) constants: Box<[Value<'a>]>,
     pub(crate) function_expressions: Box<[FunctionExpression<'a>]>,
     pub(crate) arrow_function_expressions: Box<[ArrowFunctionExpression]>,
+    /// Template literal sites of the tagged templates in the Executable.
+    pub(crate) template_literals: Box<[SendableRef<ast::TemplateLiteral<'static>>]>,
     pub(crate) class_initializer_bytecodes: Box<[(Option<Executable<'a>>, bool)]>,
+    /// Environment Record needs of the function body this Executable was
+    /// compiled from. Always Heap for scripts, modules, and eval code.
+    pub(crate) environment_needs: FunctionEnvironmentNeeds,
+    /// Bindings kept in Vm frame slots, indexed by slot. Parameters come
+    /// first, in order. Empty unless `environment_needs` is StackOnly.
+    pub(crate) frame_slots: Box<[FrameSlot<'a>]>,
+    /// Source spans of the compiled statements, keyed by the index of their
+    /// first instruction and sorted by it.
+    pub(crate) spans: Box<[(u32, Span)]>,
 }
 
 impl<'gc> Executable<'gc> {
@@ -154,6 +213,7 @@ impl<'gc> Executable<'gc> {
             unsafe { core::mem::transmute(agent[script].ecmascript_code.body.as_slice()) };
         let mut ctx = CompileContext::new(agent, gc);
 
+        ctx.track_completion_value();
         ctx.compile_statements(body);
         ctx.do_implicit_return();
         ctx.finish()
@@ -166,6 +226,10 @@ impl<'gc> Executable<'gc> {
     ) -> Self {
         let mut ctx = CompileContext::new(agent, gc);
 
+        if let Some(frame_slots) = analyze_frame_slots(data.params, data.body) {
+            ctx.use_frame_slots(&frame_slots);
+        }
+
         let is_concise = data.is_concise_body;
 
         ctx.compile_function_body(data);
@@ -188,6 +252,7 @@ impl<'gc> Executable<'gc> {
             eprintln!();
         }
         let mut ctx = CompileContext::new(agent, gc);
+        ctx.track_completion_value();
 
         // eval('"asd"') is parsed into an empty body with a single directive.
         // Multiple directives are also possible, but only the last one is
@@ -203,32 +268,37 @@ impl<'gc> Executable<'gc> {
         ctx.finish()
     }
 
-    /// Drops the Executable's heap-allocated data if possible.
-    ///
-    /// ## Safety
-    ///
-    /// Any attempt to use the Executable after this call will lead to a crash
-    /// if the drop was performed.
-    pub(crate) unsafe fn try_drop(self, agent: &mut Agent) {
-        debug_assert!(!agent.heap.executables.is_empty());
-        let index = self.get_index();
-        let last_index = agent.heap.executables.len() - 1;
-        if last_index == index {
-            // This bytecode was the last-allocated bytecode, and we can drop
-            // it from the Heap without affecting any other indexes. The caller
-            // guarantees that the Executable will not be used anymore.
-            agent.heap.alloc_counter = agent
-                .heap
-                .alloc_counter
-                .saturating_sub(core::mem::size_of::<ExecutableHeapData>());
-            let _ = agent.heap.executables.pop().unwrap();
-        }
-    }
-
     pub(crate) fn get_index(self) -> usize {
         (self.0.get() - 1) as usize
     }
 
+    /// Get the source span of the statement that the instruction preceding
+    /// the instruction pointer `ip` was compiled from.
+    pub(crate) fn get_span(self, agent: &Agent, ip: usize) -> Option<Span> {
+        let spans = &agent[self].spans;
+        let index = spans.partition_point(|(index, _)| (*index as usize) < ip);
+        index.checked_sub(1).map(|index| spans[index].1)
+    }
+
+    /// Get the source span of the statement that begins with the instruction
+    /// at `ip`, or None if the instruction does not begin a statement.
+    pub(crate) fn get_statement_span_at(self, agent: &Agent, ip: usize) -> Option<Span> {
+        let spans = &agent[self].spans;
+        spans
+            .binary_search_by_key(&ip, |(index, _)| *index as usize)
+            .ok()
+            .map(|index| spans[index].1)
+    }
+
+    /// Returns true if a statement of the Executable begins in the given
+    /// range of source text offsets.
+    pub(crate) fn has_statement_starting_in(self, agent: &Agent, range: Range<u32>) -> bool {
+        agent[self]
+            .spans
+            .iter()
+            .any(|(_, span)| range.contains(&span.start))
+    }
+
     /// SAFETY: The returned reference is valid until the Executable is garbage
     /// collected.
     #[inline]
@@ -287,6 +357,14 @@ impl<'gc> Executable<'gc> {
         &agent[self].arrow_function_expressions[index]
     }
 
+    fn fetch_template_literal(
+        self,
+        agent: &Agent,
+        index: usize,
+    ) -> &'static ast::TemplateLiteral<'static> {
+        agent[self].template_literals[index].get()
+    }
+
     fn fetch_class_initializer_bytecode(
         self,
         agent: &Agent,
@@ -295,6 +373,13 @@ impl<'gc> Executable<'gc> {
     ) -> (Option<Executable<'gc>>, bool) {
         agent[self].class_initializer_bytecodes[index]
     }
+
+    /// Returns true if calls to a function compiled into this Executable must
+    /// allocate a function Environment Record.
+    #[inline]
+    pub(crate) fn needs_function_environment(self, agent: &Agent) -> bool {
+        agent[self].environment_needs == FunctionEnvironmentNeeds::Heap
+    }
 }
 
 impl Scoped<'_, Executable<'static>> {
@@ -359,6 +444,15 @@ impl Scoped<'_, Executable<'static>> {
             .fetch_arrow_function_expression(agent, index)
     }
 
+    #[inline]
+    pub(super) fn fetch_template_literal(
+        &self,
+        agent: &Agent,
+        index: usize,
+    ) -> &'static ast::TemplateLiteral<'static> {
+        self.get(agent).fetch_template_literal(agent, index)
+    }
+
     #[inline]
     pub(super) fn fetch_class_initializer_bytecode<'gc>(
         &self,
@@ -369,6 +463,20 @@ impl Scoped<'_, Executable<'static>> {
         self.get(agent)
             .fetch_class_initializer_bytecode(agent, index, gc)
     }
+
+    #[inline]
+    pub(super) fn needs_function_environment(&self, agent: &Agent) -> bool {
+        self.get(agent).needs_function_environment(agent)
+    }
+
+    #[inline]
+    pub(super) fn get_frame_slots<'a, 'gc>(
+        &self,
+        agent: &'a Agent,
+        _: NoGcScope<'gc, '_>,
+    ) -> &'a [FrameSlot<'gc>] {
+        &agent[self.get(agent)].frame_slots[..]
+    }
 }
 
 impl Index<Executable<'_>> for Agent {
@@ -384,6 +492,7 @@ impl Index<Executable<'_>> for Agent {
 
 impl IndexMut<Executable<'_>> for Agent {
     fn index_mut(&mut self, index: Executable) -> &mut Self::Output {
+        self.heap.write_barrier(index);
         self.heap
             .executables
             .get_mut(index.get_index())
@@ -470,33 +579,71 @@ impl HeapMarkAndSweep for Executable<'static> {
     }
 }
 
+impl HeapVectorSlot for ExecutableHeapData<'static> {
+    fn is_live(&self) -> bool {
+        // Compiled bytecode always ends in a return instruction.
+        !self.instructions.is_empty()
+    }
+
+    fn clear(&mut self) {
+        self.instructions = Box::default();
+        self.constants = Box::default();
+        self.function_expressions = Box::default();
+        self.arrow_function_expressions = Box::default();
+        self.template_literals = Box::default();
+        self.class_initializer_bytecodes = Box::default();
+        self.frame_slots = Box::default();
+        self.spans = Box::default();
+    }
+}
+
 impl HeapMarkAndSweep for ExecutableHeapData<'static> {
     fn mark_values(&self, queues: &mut WorkQueues) {
         let Self {
             instructions: _,
             constants,
-            function_expressions: _,
+            function_expressions,
             arrow_function_expressions: _,
+            template_literals: _,
             class_initializer_bytecodes,
+            environment_needs: _,
+            frame_slots,
+            spans: _,
         } = self;
         constants.mark_values(queues);
+        for ele in function_expressions {
+            ele.compiled_bytecode.mark_values(queues);
+        }
         for ele in class_initializer_bytecodes {
             ele.0.mark_values(queues);
         }
+        for slot in frame_slots {
+            slot.name.mark_values(queues);
+        }
     }
 
     fn sweep_values(&mut self, compactions: &CompactionLists) {
         let Self {
             instructions: _,
             constants,
-            function_expressions: _,
+            function_expressions,
             arrow_function_expressions: _,
+            template_literals: _,
             class_initializer_bytecodes,
+            environment_needs: _,
+            frame_slots,
+            spans: _,
         } = self;
         constants.sweep_values(compactions);
+        for ele in function_expressions {
+            ele.compiled_bytecode.sweep_values(compactions);
+        }
         for ele in class_initializer_bytecodes {
             ele.0.sweep_values(compactions);
         }
+        for slot in frame_slots {
+            slot.name.sweep_values(compactions);
+        }
     }
 }
 
@@ -504,3 +651,96 @@ impl HeapMarkAndSweep for ExecutableHeapData<'static> {
 fn handle_identifier_failure() -> ! {
     panic!("Invalid identifier index: Value was not a String")
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        ecmascript::{
+            execution::{
+                DefaultHostHooks,
+                agent::{GcAgent, Options, RealmRoot},
+            },
+            scripts_and_modules::script::test::evaluate,
+            types::Value,
+        },
+        engine::context::Bindable,
+    };
+
+    fn assert_true(agent: &mut GcAgent, realm: &RealmRoot, source: &'static str) {
+        agent.run_in_realm(realm, |agent, mut gc| {
+            let result = evaluate(agent, source, gc.reborrow()).unbind();
+            assert_eq!(result, Value::Boolean(true), "{source}");
+        });
+    }
+
+    #[test]
+    fn suspended_generator_keeps_its_bytecode_alive() {
+        let mut agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+        let realm = agent.create_default_realm();
+        assert_true(
+            &mut agent,
+            &realm,
+            "var generator = (function* () {
+                const local = 'kept';
+                const received = yield 1;
+                yield local + received;
+                return 3;
+            })();
+            generator.next().value === 1",
+        );
+        // Compile and drop more bytecode so that the generator's bytecode is no
+        // longer the last one allocated, and let the GC compact the Executables.
+        for _ in 0..10 {
+            assert_true(&mut agent, &realm, "(() => true)()");
+        }
+        agent.gc();
+        assert_true(
+            &mut agent,
+            &realm,
+            "generator.next('!').value === 'kept!' &&
+                generator.next().value === 3 && generator.next().done",
+        );
+        agent.remove_realm(realm);
+    }
+
+    #[test]
+    fn closures_keep_their_bytecode_alive() {
+        let mut agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+        let realm = agent.create_default_realm();
+        assert_true(
+            &mut agent,
+            &realm,
+            "var make = function (value) { return () => value; };
+            var arrow = make(true);
+            true",
+        );
+        agent.gc();
+        assert_true(&mut agent, &realm, "arrow() && make(true)()");
+        agent.remove_realm(realm);
+    }
+
+    #[test]
+    fn script_bytecode_is_reclaimed() {
+        let mut agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+        let realm = agent.create_default_realm();
+        let run_scripts = |agent: &mut GcAgent, count: usize| {
+            for _ in 0..count {
+                assert_true(
+                    agent,
+                    &realm,
+                    "function* g() { yield 1; } (function () {
+                        return [...g()].length === 1;
+                    })()",
+                );
+            }
+            agent.gc();
+            agent.heap_statistics().bytecode_bytes
+        };
+        let after_warmup = run_scripts(&mut agent, 10);
+        let after_many = run_scripts(&mut agent, 1000);
+        // Only the last declared generator function and its bytecode are still
+        // reachable.
+        assert!(after_many <= after_warmup, "{after_many} > {after_warmup}");
+        agent.remove_realm(realm);
+    }
+}
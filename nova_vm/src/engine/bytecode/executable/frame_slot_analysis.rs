@@ -0,0 +1,215 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Compile-time analysis of whether an ECMAScript function's bindings can be
+//! kept in Vm frame slots instead of a function Environment Record.
+
+use oxc_ast::ast::{
+    ArrowFunctionExpression, AwaitExpression, BindingPatternKind, CallExpression, CatchParameter,
+    Class, Expression, ForOfStatement, FormalParameters, Function, FunctionBody,
+    IdentifierReference, MetaProperty, Super, ThisExpression, UnaryExpression, UnaryOperator,
+    VariableDeclaration, VariableDeclarationKind, WithStatement, YieldExpression,
+};
+use oxc_ast_visit::{
+    Visit,
+    walk::{
+        walk_call_expression, walk_catch_parameter, walk_for_of_statement, walk_unary_expression,
+        walk_variable_declaration,
+    },
+};
+use oxc_ecmascript::{BoundNames, IsSimpleParameterList};
+use oxc_span::Atom;
+use oxc_syntax::scope::ScopeFlags;
+
+use crate::ecmascript::syntax_directed_operations::scope_analysis::{
+    LexicallyScopedDeclaration, function_body_lexically_scoped_decarations,
+    function_body_var_declared_names,
+};
+
+use super::FrameSlotKind;
+
+/// Determines if all bindings of a function can be kept in Vm frame slots.
+///
+/// Returns the frame slot layout of the function if so, with the parameters
+/// first and in order. Returns None if every call to the function must
+/// allocate a function Environment Record. This is the case if:
+///
+/// - the function contains inner functions, arrow functions, or classes,
+/// - the function contains direct eval, `with`, or `delete` of an identifier,
+/// - the function refers to `arguments`, `this`, `super`, or `new.target`,
+/// - the function contains `await` or `yield`,
+/// - the parameter list is not simple or contains duplicates,
+/// - the function contains destructuring declarations, or
+/// - a nested block scoped binding shadows a function level binding.
+pub(super) fn analyze_frame_slots<'a>(
+    params: &'a FormalParameters<'a>,
+    body: &'a FunctionBody<'a>,
+) -> Option<Vec<(Atom<'a>, FrameSlotKind)>> {
+    if !params.is_simple_parameter_list() {
+        return None;
+    }
+
+    let mut analysis = FrameSlotAnalysis {
+        requires_environment: false,
+        block_scoped_names: Vec::new(),
+    };
+    analysis.visit_function_body(body);
+    if analysis.requires_environment {
+        return None;
+    }
+
+    let mut frame_slots: Vec<(Atom<'a>, FrameSlotKind)> = Vec::new();
+    for param in &params.items {
+        let BindingPatternKind::BindingIdentifier(identifier) = &param.pattern.kind else {
+            unreachable!()
+        };
+        if frame_slots.iter().any(|(name, _)| *name == identifier.name) {
+            // Duplicate parameter names.
+            return None;
+        }
+        frame_slots.push((identifier.name, FrameSlotKind::Parameter));
+    }
+    for name in function_body_var_declared_names(body) {
+        if !frame_slots.iter().any(|(n, _)| *n == name) {
+            frame_slots.push((name, FrameSlotKind::Var));
+        }
+    }
+    for declaration in function_body_lexically_scoped_decarations(body) {
+        let LexicallyScopedDeclaration::Variable(declarator) = declaration else {
+            // Functions and classes were already rejected by the visitor.
+            return None;
+        };
+        let kind = match declarator.kind {
+            VariableDeclarationKind::Let => FrameSlotKind::Let,
+            VariableDeclarationKind::Const => FrameSlotKind::Const,
+            _ => return None,
+        };
+        declarator.id.bound_names(&mut |identifier| {
+            frame_slots.push((identifier.name, kind));
+        });
+    }
+
+    for (name, kind) in &frame_slots {
+        if name == "arguments" {
+            // The arguments object would be bound to this name.
+            return None;
+        }
+        // Top-level lexical declarations are seen by the visitor once; any
+        // other occurrence is a nested block scoped binding shadowing a frame
+        // slot.
+        let allowed = usize::from(matches!(kind, FrameSlotKind::Let | FrameSlotKind::Const));
+        let count = analysis
+            .block_scoped_names
+            .iter()
+            .filter(|n| *n == name)
+            .count();
+        if count > allowed {
+            return None;
+        }
+    }
+
+    Some(frame_slots)
+}
+
+struct FrameSlotAnalysis<'a> {
+    requires_environment: bool,
+    /// Names bound by lexical declarations and catch parameters at any depth.
+    block_scoped_names: Vec<Atom<'a>>,
+}
+
+impl<'a> Visit<'a> for FrameSlotAnalysis<'a> {
+    fn visit_function(&mut self, _: &Function<'a>, _: ScopeFlags) {
+        self.requires_environment = true;
+    }
+
+    fn visit_arrow_function_expression(&mut self, _: &ArrowFunctionExpression<'a>) {
+        self.requires_environment = true;
+    }
+
+    fn visit_class(&mut self, _: &Class<'a>) {
+        self.requires_environment = true;
+    }
+
+    fn visit_this_expression(&mut self, _: &ThisExpression) {
+        self.requires_environment = true;
+    }
+
+    fn visit_super(&mut self, _: &Super) {
+        self.requires_environment = true;
+    }
+
+    fn visit_meta_property(&mut self, _: &MetaProperty<'a>) {
+        self.requires_environment = true;
+    }
+
+    fn visit_yield_expression(&mut self, _: &YieldExpression<'a>) {
+        self.requires_environment = true;
+    }
+
+    fn visit_await_expression(&mut self, _: &AwaitExpression<'a>) {
+        self.requires_environment = true;
+    }
+
+    fn visit_with_statement(&mut self, _: &WithStatement<'a>) {
+        self.requires_environment = true;
+    }
+
+    fn visit_identifier_reference(&mut self, it: &IdentifierReference<'a>) {
+        if it.name == "arguments" {
+            self.requires_environment = true;
+        }
+    }
+
+    fn visit_call_expression(&mut self, it: &CallExpression<'a>) {
+        if matches!(&it.callee, Expression::Identifier(callee) if callee.name == "eval") {
+            // Direct eval.
+            self.requires_environment = true;
+            return;
+        }
+        walk_call_expression(self, it);
+    }
+
+    fn visit_unary_expression(&mut self, it: &UnaryExpression<'a>) {
+        if it.operator == UnaryOperator::Delete
+            && matches!(
+                it.argument.get_inner_expression(),
+                Expression::Identifier(_)
+            )
+        {
+            self.requires_environment = true;
+            return;
+        }
+        walk_unary_expression(self, it);
+    }
+
+    fn visit_for_of_statement(&mut self, it: &ForOfStatement<'a>) {
+        if it.r#await {
+            self.requires_environment = true;
+            return;
+        }
+        walk_for_of_statement(self, it);
+    }
+
+    fn visit_variable_declaration(&mut self, it: &VariableDeclaration<'a>) {
+        if it
+            .declarations
+            .iter()
+            .any(|decl| !matches!(decl.id.kind, BindingPatternKind::BindingIdentifier(_)))
+        {
+            // Destructuring binding initialization resolves names at runtime.
+            self.requires_environment = true;
+            return;
+        }
+        if it.kind.is_lexical() {
+            it.bound_names(&mut |identifier| self.block_scoped_names.push(identifier.name));
+        }
+        walk_variable_declaration(self, it);
+    }
+
+    fn visit_catch_parameter(&mut self, it: &CatchParameter<'a>) {
+        it.pattern
+            .bound_names(&mut |identifier| self.block_scoped_names.push(identifier.name));
+        walk_catch_parameter(self, it);
+    }
+}
@@ -35,6 +35,13 @@ pub enum Instruction {
     Await,
     /// Performs steps 2-4 from the [UnaryExpression ~ Runtime Semantics](https://tc39.es/ecma262/#sec-bitwise-not-operator-runtime-semantics-evaluation).
     BitwiseNot,
+    /// Store a new RegExp object created from the RegExp literal template
+    /// constant as the result value.
+    ///
+    /// The template holds the literal's pattern and flags, and is shared by
+    /// every evaluation of the literal.
+    #[cfg(feature = "regexp")]
+    CreateRegExp,
     /// Performs CreateUnmappedArgumentsObject() on the arguments list present
     /// in the iterator stack, and stores the created arguments object as the
     /// result value.
@@ -89,6 +96,14 @@ pub enum Instruction {
     /// register and the `privateIdentifier` given as the first immediate
     /// argument, and store the result in the reference register.
     MakePrivateReference,
+    /// Perform MakeSuperPropertyReference with the `actualThis` at the top of
+    /// the stack and the `propertyNameValue` in the result register, and store
+    /// the result in the reference register.
+    MakeSuperPropertyReferenceWithExpressionKey,
+    /// Perform MakeSuperPropertyReference with the `actualThis` in the result
+    /// register and the `propertyNameString` given as the first immediate
+    /// argument, and store the result in the reference register.
+    MakeSuperPropertyReferenceWithIdentifierKey,
     /// Store [GetValue()](https://tc39.es/ecma262/#sec-getvalue) as the result
     /// value.
     ///
@@ -101,6 +116,9 @@ pub enum Instruction {
     /// property updates and function calls (where `this` comes from the
     /// reference).
     GetValueKeepReference,
+    /// Store GetTemplateObject() of the tagged template literal site as the
+    /// result value.
+    GetTemplateObject,
     /// Compare the last two values on the stack using the '>' operator rules.
     GreaterThan,
     /// Compare the last two values on the stack using the '>=' operator rules.
@@ -183,8 +201,60 @@ pub enum Instruction {
     /// if the current result is falsey.
     JumpIfNot,
     /// Jump to another intrsuction by setting the instruction pointer if the
-    /// current result is `true`.
+    /// current result is truthy.
     JumpIfTrue,
+    /// Compare the last value on the stack and the result value using the '<'
+    /// operator rules, and jump to another instruction if the comparison is
+    /// true.
+    JumpIfLessThan,
+    /// Compare the last value on the stack and the result value using the '<'
+    /// operator rules, and jump to another instruction if the comparison is
+    /// false.
+    JumpIfNotLessThan,
+    /// Compare the last value on the stack and the result value using the
+    /// '<=' operator rules, and jump to another instruction if the comparison
+    /// is true.
+    JumpIfLessThanEquals,
+    /// Compare the last value on the stack and the result value using the
+    /// '<=' operator rules, and jump to another instruction if the comparison
+    /// is false.
+    JumpIfNotLessThanEquals,
+    /// Compare the last value on the stack and the result value using the '>'
+    /// operator rules, and jump to another instruction if the comparison is
+    /// true.
+    JumpIfGreaterThan,
+    /// Compare the last value on the stack and the result value using the '>'
+    /// operator rules, and jump to another instruction if the comparison is
+    /// false.
+    JumpIfNotGreaterThan,
+    /// Compare the last value on the stack and the result value using the
+    /// '>=' operator rules, and jump to another instruction if the comparison
+    /// is true.
+    JumpIfGreaterThanEquals,
+    /// Compare the last value on the stack and the result value using the
+    /// '>=' operator rules, and jump to another instruction if the comparison
+    /// is false.
+    JumpIfNotGreaterThanEquals,
+    /// Compare the last value on the stack and the result value using
+    /// IsStrictlyEqual(), and jump to another instruction if they are equal.
+    JumpIfStrictlyEqual,
+    /// Compare the last value on the stack and the result value using
+    /// IsStrictlyEqual(), and jump to another instruction if they are not
+    /// equal.
+    JumpIfNotStrictlyEqual,
+    /// Compare the last value on the stack and the result value using
+    /// IsLooselyEqual(), and jump to another instruction if they are equal.
+    JumpIfLooselyEqual,
+    /// Compare the last value on the stack and the result value using
+    /// IsLooselyEqual(), and jump to another instruction if they are not
+    /// equal.
+    JumpIfNotLooselyEqual,
+    /// Jump to another instruction if the current result is null or
+    /// undefined.
+    JumpIfNullOrUndefined,
+    /// Jump to another instruction if the current result is neither null nor
+    /// undefined.
+    JumpIfNotNullOrUndefined,
     /// Compare the last two values on the stack using the '<' operator rules.
     LessThan,
     /// Compare the last two values on the stack using the '<=' operator rules.
@@ -203,6 +273,9 @@ pub enum Instruction {
     /// Performs steps 2-4 from the [UnaryExpression ! Runtime Semantics](https://tc39.es/ecma262/#sec-logical-not-operator-runtime-semantics-evaluation).
     LogicalNot,
     /// Store OrdinaryObjectCreate(%Object.prototype%) on the stack.
+    ///
+    /// This instruction has one immediate argument that is the number of
+    /// properties to reserve storage for.
     ObjectCreate,
     /// Call CreateDataPropertyOrThrow(object, key, value) with value being the
     /// result value, key being the top stack value and object being the second
@@ -230,6 +303,12 @@ pub enum Instruction {
     PutValue,
     /// Store ResolveBinding() as the result value.
     ResolveBinding,
+    /// Store a Reference to the frame slot given by the immediate as the
+    /// reference value. The identifier is the name of the binding.
+    ///
+    /// This is only emitted for functions whose bindings are kept in Vm frame
+    /// slots instead of a function Environment Record.
+    ResolveLocal,
     /// Store ResolveThisBinding() as the result value.
     ResolveThisBinding,
     /// Stop bytecode execution, indicating a return from the current function.
@@ -465,10 +544,25 @@ impl Instruction {
             | Self::Jump
             | Self::JumpIfNot
             | Self::JumpIfTrue
+            | Self::JumpIfLessThan
+            | Self::JumpIfNotLessThan
+            | Self::JumpIfLessThanEquals
+            | Self::JumpIfNotLessThanEquals
+            | Self::JumpIfGreaterThan
+            | Self::JumpIfNotGreaterThan
+            | Self::JumpIfGreaterThanEquals
+            | Self::JumpIfNotGreaterThanEquals
+            | Self::JumpIfStrictlyEqual
+            | Self::JumpIfNotStrictlyEqual
+            | Self::JumpIfLooselyEqual
+            | Self::JumpIfNotLooselyEqual
+            | Self::JumpIfNullOrUndefined
+            | Self::JumpIfNotNullOrUndefined
             | Self::ObjectDefineGetter
             | Self::ObjectDefineMethod
             | Self::ObjectDefineSetter
-            | Self::PushExceptionJumpTarget => 2,
+            | Self::PushExceptionJumpTarget
+            | Self::ResolveLocal => 2,
             Self::ArrayCreate
             | Self::BeginSimpleObjectBindingPattern
             | Self::BindingPatternBind
@@ -484,16 +578,21 @@ impl Instruction {
             | Self::EvaluateNew
             | Self::EvaluatePropertyAccessWithIdentifierKey
             | Self::EvaluateSuper
+            | Self::GetTemplateObject
             | Self::InstantiateArrowFunctionExpression
             | Self::InstantiateOrdinaryFunctionExpression
             | Self::LoadConstant
             | Self::MakePrivateReference
+            | Self::MakeSuperPropertyReferenceWithIdentifierKey
+            | Self::ObjectCreate
             | Self::ClassInitializePrivateValue
             | Self::ResolveBinding
             | Self::StoreConstant
             | Self::StringConcat
             | Self::ThrowError
             | Self::VerifyIsObject => 1,
+            #[cfg(feature = "regexp")]
+            Self::CreateRegExp => 1,
             _ => 0,
         }
     }
@@ -506,18 +605,34 @@ impl Instruction {
                 | Self::Jump
                 | Self::JumpIfNot
                 | Self::JumpIfTrue
+                | Self::JumpIfLessThan
+                | Self::JumpIfNotLessThan
+                | Self::JumpIfLessThanEquals
+                | Self::JumpIfNotLessThanEquals
+                | Self::JumpIfGreaterThan
+                | Self::JumpIfNotGreaterThan
+                | Self::JumpIfGreaterThanEquals
+                | Self::JumpIfNotGreaterThanEquals
+                | Self::JumpIfStrictlyEqual
+                | Self::JumpIfNotStrictlyEqual
+                | Self::JumpIfLooselyEqual
+                | Self::JumpIfNotLooselyEqual
+                | Self::JumpIfNullOrUndefined
+                | Self::JumpIfNotNullOrUndefined
                 | Self::PushExceptionJumpTarget
         )
     }
 
     pub fn has_constant_index(self) -> bool {
-        matches!(
-            self,
+        match self {
             Self::BindingPatternBindNamed
-                | Self::BindingPatternGetValueNamed
-                | Self::LoadConstant
-                | Self::StoreConstant
-        )
+            | Self::BindingPatternGetValueNamed
+            | Self::LoadConstant
+            | Self::StoreConstant => true,
+            #[cfg(feature = "regexp")]
+            Self::CreateRegExp => true,
+            _ => false,
+        }
     }
 
     pub fn has_identifier_index(self) -> bool {
@@ -532,7 +647,9 @@ impl Instruction {
                 | Self::CreateMutableBinding
                 | Self::EvaluatePropertyAccessWithIdentifierKey
                 | Self::MakePrivateReference
+                | Self::MakeSuperPropertyReferenceWithIdentifierKey
                 | Self::ResolveBinding
+                | Self::ResolveLocal
                 | Self::VerifyIsObject
         )
     }
@@ -556,6 +673,20 @@ impl Instruction {
             Self::Jump
                 | Self::JumpIfNot
                 | Self::JumpIfTrue
+                | Self::JumpIfLessThan
+                | Self::JumpIfNotLessThan
+                | Self::JumpIfLessThanEquals
+                | Self::JumpIfNotLessThanEquals
+                | Self::JumpIfGreaterThan
+                | Self::JumpIfNotGreaterThan
+                | Self::JumpIfGreaterThanEquals
+                | Self::JumpIfNotGreaterThanEquals
+                | Self::JumpIfStrictlyEqual
+                | Self::JumpIfNotStrictlyEqual
+                | Self::JumpIfLooselyEqual
+                | Self::JumpIfNotLooselyEqual
+                | Self::JumpIfNullOrUndefined
+                | Self::JumpIfNotNullOrUndefined
                 | Self::PushExceptionJumpTarget
                 | Self::IteratorStepValue
         )
@@ -885,6 +1016,10 @@ impl Instr {
             Instruction::ObjectDefineGetter => "get function() {}".to_string(),
             Instruction::ObjectDefineMethod => "function() {}".to_string(),
             Instruction::ObjectDefineSetter => "set function() {}".to_string(),
+            Instruction::ResolveLocal => {
+                let name = debug_print_identifier(agent, exe, arg0 as usize, gc);
+                format!("{name} @ slot {arg1}")
+            }
             _ => unreachable!("{kind:?}"),
         }
     }
@@ -898,12 +1033,12 @@ fn debug_print_constant(
 ) -> std::string::String {
     let constant = exe.fetch_constant(agent, index, gc);
     if let Ok(string_constant) = String::try_from(constant) {
-        format!("\"{}\"", string_constant.as_str(agent))
+        format!("\"{}\"", string_constant.to_string_lossy(agent))
     } else {
         constant
             .try_string_repr(agent, gc)
-            .as_str(agent)
-            .to_string()
+            .to_string_lossy(agent)
+            .into_owned()
     }
 }
 
@@ -914,7 +1049,7 @@ fn debug_print_identifier(
     gc: NoGcScope,
 ) -> std::string::String {
     let identifier = exe.fetch_identifier(agent, index, gc);
-    identifier.as_str(agent).to_string()
+    identifier.to_string_lossy(agent).into_owned()
 }
 
 fn debug_print_binding_pattern(b: &BindingPattern) -> std::string::String {
@@ -1073,6 +1208,8 @@ impl TryFrom<u8> for Instruction {
         const ARRAYELISION: u8 = Instruction::ArrayElision.as_u8();
         const AWAIT: u8 = Instruction::Await.as_u8();
         const BITWISENOT: u8 = Instruction::BitwiseNot.as_u8();
+        #[cfg(feature = "regexp")]
+        const CREATEREGEXP: u8 = Instruction::CreateRegExp.as_u8();
         const CREATEUNMAPPEDARGUMENTSOBJECT: u8 =
             Instruction::CreateUnmappedArgumentsObject.as_u8();
         const COPYDATAPROPERTIES: u8 = Instruction::CopyDataProperties.as_u8();
@@ -1087,8 +1224,13 @@ impl TryFrom<u8> for Instruction {
         const EVALUATEPROPERTYACCESSWITHIDENTIFIERKEY: u8 =
             Instruction::EvaluatePropertyAccessWithIdentifierKey.as_u8();
         const MAKEPRIVATEREFERENCE: u8 = Instruction::MakePrivateReference.as_u8();
+        const MAKESUPERPROPERTYREFERENCEWITHEXPRESSIONKEY: u8 =
+            Instruction::MakeSuperPropertyReferenceWithExpressionKey.as_u8();
+        const MAKESUPERPROPERTYREFERENCEWITHIDENTIFIERKEY: u8 =
+            Instruction::MakeSuperPropertyReferenceWithIdentifierKey.as_u8();
         const GETVALUE: u8 = Instruction::GetValue.as_u8();
         const GETVALUEKEEPREFERENCE: u8 = Instruction::GetValueKeepReference.as_u8();
+        const GETTEMPLATEOBJECT: u8 = Instruction::GetTemplateObject.as_u8();
         const GREATERTHAN: u8 = Instruction::GreaterThan.as_u8();
         const GREATERTHANEQUALS: u8 = Instruction::GreaterThanEquals.as_u8();
         const HASPROPERTY: u8 = Instruction::HasProperty.as_u8();
@@ -1118,6 +1260,20 @@ impl TryFrom<u8> for Instruction {
         const JUMP: u8 = Instruction::Jump.as_u8();
         const JUMPIFNOT: u8 = Instruction::JumpIfNot.as_u8();
         const JUMPIFTRUE: u8 = Instruction::JumpIfTrue.as_u8();
+        const JUMPIFLESSTHAN: u8 = Instruction::JumpIfLessThan.as_u8();
+        const JUMPIFNOTLESSTHAN: u8 = Instruction::JumpIfNotLessThan.as_u8();
+        const JUMPIFLESSTHANEQUALS: u8 = Instruction::JumpIfLessThanEquals.as_u8();
+        const JUMPIFNOTLESSTHANEQUALS: u8 = Instruction::JumpIfNotLessThanEquals.as_u8();
+        const JUMPIFGREATERTHAN: u8 = Instruction::JumpIfGreaterThan.as_u8();
+        const JUMPIFNOTGREATERTHAN: u8 = Instruction::JumpIfNotGreaterThan.as_u8();
+        const JUMPIFGREATERTHANEQUALS: u8 = Instruction::JumpIfGreaterThanEquals.as_u8();
+        const JUMPIFNOTGREATERTHANEQUALS: u8 = Instruction::JumpIfNotGreaterThanEquals.as_u8();
+        const JUMPIFSTRICTLYEQUAL: u8 = Instruction::JumpIfStrictlyEqual.as_u8();
+        const JUMPIFNOTSTRICTLYEQUAL: u8 = Instruction::JumpIfNotStrictlyEqual.as_u8();
+        const JUMPIFLOOSELYEQUAL: u8 = Instruction::JumpIfLooselyEqual.as_u8();
+        const JUMPIFNOTLOOSELYEQUAL: u8 = Instruction::JumpIfNotLooselyEqual.as_u8();
+        const JUMPIFNULLORUNDEFINED: u8 = Instruction::JumpIfNullOrUndefined.as_u8();
+        const JUMPIFNOTNULLORUNDEFINED: u8 = Instruction::JumpIfNotNullOrUndefined.as_u8();
         const LESSTHAN: u8 = Instruction::LessThan.as_u8();
         const LESSTHANEQUALS: u8 = Instruction::LessThanEquals.as_u8();
         const LOAD: u8 = Instruction::Load.as_u8();
@@ -1138,6 +1294,7 @@ impl TryFrom<u8> for Instruction {
         const PUSHREFERENCE: u8 = Instruction::PushReference.as_u8();
         const PUTVALUE: u8 = Instruction::PutValue.as_u8();
         const RESOLVEBINDING: u8 = Instruction::ResolveBinding.as_u8();
+        const RESOLVELOCAL: u8 = Instruction::ResolveLocal.as_u8();
         const RESOLVETHISBINDING: u8 = Instruction::ResolveThisBinding.as_u8();
         const RETURN: u8 = Instruction::Return.as_u8();
         const STORE: u8 = Instruction::Store.as_u8();
@@ -1261,6 +1418,8 @@ impl TryFrom<u8> for Instruction {
             ARRAYELISION => Ok(Instruction::ArrayElision),
             AWAIT => Ok(Instruction::Await),
             BITWISENOT => Ok(Instruction::BitwiseNot),
+            #[cfg(feature = "regexp")]
+            CREATEREGEXP => Ok(Instruction::CreateRegExp),
             CREATEUNMAPPEDARGUMENTSOBJECT => Ok(Instruction::CreateUnmappedArgumentsObject),
             COPYDATAPROPERTIES => Ok(Instruction::CopyDataProperties),
             COPYDATAPROPERTIESINTOOBJECT => Ok(Instruction::CopyDataPropertiesIntoObject),
@@ -1276,8 +1435,15 @@ impl TryFrom<u8> for Instruction {
                 Ok(Instruction::EvaluatePropertyAccessWithIdentifierKey)
             }
             MAKEPRIVATEREFERENCE => Ok(Instruction::MakePrivateReference),
+            MAKESUPERPROPERTYREFERENCEWITHEXPRESSIONKEY => {
+                Ok(Instruction::MakeSuperPropertyReferenceWithExpressionKey)
+            }
+            MAKESUPERPROPERTYREFERENCEWITHIDENTIFIERKEY => {
+                Ok(Instruction::MakeSuperPropertyReferenceWithIdentifierKey)
+            }
             GETVALUE => Ok(Instruction::GetValue),
             GETVALUEKEEPREFERENCE => Ok(Instruction::GetValueKeepReference),
+            GETTEMPLATEOBJECT => Ok(Instruction::GetTemplateObject),
             GREATERTHAN => Ok(Instruction::GreaterThan),
             GREATERTHANEQUALS => Ok(Instruction::GreaterThanEquals),
             HASPROPERTY => Ok(Instruction::HasProperty),
@@ -1307,6 +1473,20 @@ impl TryFrom<u8> for Instruction {
             JUMP => Ok(Instruction::Jump),
             JUMPIFNOT => Ok(Instruction::JumpIfNot),
             JUMPIFTRUE => Ok(Instruction::JumpIfTrue),
+            JUMPIFLESSTHAN => Ok(Instruction::JumpIfLessThan),
+            JUMPIFNOTLESSTHAN => Ok(Instruction::JumpIfNotLessThan),
+            JUMPIFLESSTHANEQUALS => Ok(Instruction::JumpIfLessThanEquals),
+            JUMPIFNOTLESSTHANEQUALS => Ok(Instruction::JumpIfNotLessThanEquals),
+            JUMPIFGREATERTHAN => Ok(Instruction::JumpIfGreaterThan),
+            JUMPIFNOTGREATERTHAN => Ok(Instruction::JumpIfNotGreaterThan),
+            JUMPIFGREATERTHANEQUALS => Ok(Instruction::JumpIfGreaterThanEquals),
+            JUMPIFNOTGREATERTHANEQUALS => Ok(Instruction::JumpIfNotGreaterThanEquals),
+            JUMPIFSTRICTLYEQUAL => Ok(Instruction::JumpIfStrictlyEqual),
+            JUMPIFNOTSTRICTLYEQUAL => Ok(Instruction::JumpIfNotStrictlyEqual),
+            JUMPIFLOOSELYEQUAL => Ok(Instruction::JumpIfLooselyEqual),
+            JUMPIFNOTLOOSELYEQUAL => Ok(Instruction::JumpIfNotLooselyEqual),
+            JUMPIFNULLORUNDEFINED => Ok(Instruction::JumpIfNullOrUndefined),
+            JUMPIFNOTNULLORUNDEFINED => Ok(Instruction::JumpIfNotNullOrUndefined),
             LESSTHAN => Ok(Instruction::LessThan),
             LESSTHANEQUALS => Ok(Instruction::LessThanEquals),
             LOAD => Ok(Instruction::Load),
@@ -1327,6 +1507,7 @@ impl TryFrom<u8> for Instruction {
             PUSHREFERENCE => Ok(Instruction::PushReference),
             PUTVALUE => Ok(Instruction::PutValue),
             RESOLVEBINDING => Ok(Instruction::ResolveBinding),
+            RESOLVELOCAL => Ok(Instruction::ResolveLocal),
             RESOLVETHISBINDING => Ok(Instruction::ResolveThisBinding),
             RETURN => Ok(Instruction::Return),
             STORE => Ok(Instruction::Store),
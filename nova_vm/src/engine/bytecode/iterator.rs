@@ -16,11 +16,12 @@ use crate::{
         builtins::{
             Array, ScopedArgumentsList,
             indexed_collections::array_objects::array_iterator_objects::array_iterator::ArrayIterator,
+            ordinary::ordinary_own_property_keys,
         },
-        execution::{Agent, JsResult, agent::ExceptionType},
+        execution::{Agent, JsResult},
         types::{
-            BUILTIN_STRING_MEMORY, InternalMethods, IntoObject, IntoValue, Object, PropertyKey,
-            PropertyKeySet, Value,
+            BUILTIN_STRING_MEMORY, InternalMethods, IntoObject, IntoValue, Object, OrdinaryObject,
+            PropertyKey, PropertyKeySet, Value,
         },
     },
     engine::{
@@ -28,7 +29,7 @@ use crate::{
         context::{Bindable, GcScope, NoGcScope, ScopeToken},
         rootable::Scopable,
     },
-    heap::{CompactionLists, HeapMarkAndSweep, WellKnownSymbolIndexes, WorkQueues},
+    heap::{CompactionLists, Heap, HeapMarkAndSweep, WellKnownSymbolIndexes, WorkQueues},
 };
 
 /// Marker struct for working with the active iterator of the currently active,
@@ -227,11 +228,7 @@ impl VmIteratorRecord<'_> {
         .bind(gc.nogc());
         // 3. If method is undefined, throw a TypeError exception.
         let Some(method) = method else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Iterator method cannot be undefined",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Iterator method cannot be undefined", gc.into_nogc());
         };
 
         // SAFETY: scoped_value is not shared.
@@ -327,11 +324,7 @@ impl VmIteratorRecord<'_> {
         .bind(gc.nogc());
         // ii. If syncMethod is undefined, throw a TypeError exception.
         let Some(sync_method) = sync_method else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Iterator method cannot be undefined",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Iterator method cannot be undefined", gc.into_nogc());
         };
         // SAFETY: obj is not shared.
         let obj = unsafe { obj.take(agent) }.bind(gc.nogc());
@@ -386,6 +379,9 @@ impl<'a> ObjectPropertiesIterator<'a> {
         agent: &mut Agent,
         mut gc: GcScope<'gc, '_>,
     ) -> JsResult<'gc, Option<Value<'gc>>> {
+        if self.get(agent).chain_keys.is_some() {
+            return Ok(self.next_chain_key(agent, gc.into_nogc()));
+        }
         loop {
             if !self.object_is_visited(agent) {
                 let keys = self
@@ -438,6 +434,23 @@ impl<'a> ObjectPropertiesIterator<'a> {
         }
     }
 
+    /// Steps an iterator whose keys were collected up front by
+    /// [`ordinary_for_in_keys`]. Keys that have since been deleted or made
+    /// non-enumerable are skipped.
+    fn next_chain_key<'gc>(
+        &mut self,
+        agent: &mut Agent,
+        gc: NoGcScope<'gc, '_>,
+    ) -> Option<Value<'gc>> {
+        loop {
+            let (object, next_key) = self.get_mut(agent).chain_keys.as_mut().unwrap().pop()?;
+            let desc = object.property_storage().get(agent, next_key);
+            if desc.is_some_and(|desc| desc.enumerable == Some(true)) {
+                return Some(Self::convert_result(agent, next_key, gc));
+            }
+        }
+    }
+
     fn convert_result<'gc>(
         agent: &mut Agent,
         result: PropertyKey,
@@ -520,18 +533,84 @@ pub struct ObjectPropertiesIteratorRecord<'a> {
     object: Object<'a>,
     visited_keys: PropertyKeySet<'a>,
     remaining_keys: Option<Vec<PropertyKey<'a>>>,
+    /// Enumerable keys of the whole prototype chain and their owners, in
+    /// reverse order. Only used when the chain consists of ordinary objects.
+    chain_keys: Option<Vec<(OrdinaryObject<'a>, PropertyKey<'a>)>>,
 }
 
 impl<'a> ObjectPropertiesIteratorRecord<'a> {
-    pub(super) fn new(object: Object<'a>) -> Self {
+    pub(super) fn new(agent: &mut Agent, object: Object<'a>, gc: NoGcScope<'a, '_>) -> Self {
         Self {
             object,
             visited_keys: Default::default(),
             remaining_keys: Default::default(),
+            chain_keys: ordinary_for_in_keys(agent, object, gc),
         }
     }
 }
 
+/// Collects the enumerable string keys of `object` and its prototype chain in
+/// for-in order, with shadowed keys removed, if the chain consists only of
+/// ordinary objects. The internal methods of ordinary objects are not
+/// observable, so the keys can be collected up front and reused from the
+/// heap's own keys cache for as long as no object in the chain changes.
+fn ordinary_for_in_keys<'a>(
+    agent: &mut Agent,
+    object: Object<'a>,
+    gc: NoGcScope<'a, '_>,
+) -> Option<Vec<(OrdinaryObject<'a>, PropertyKey<'a>)>> {
+    let Object::Object(object) = object else {
+        return None;
+    };
+    let Heap {
+        objects,
+        own_keys_cache,
+        ..
+    } = &mut agent.heap;
+    if let Some(keys) = own_keys_cache.get_for_in_keys(objects, object) {
+        return Some(
+            keys.iter()
+                .rev()
+                .map(|&(o, key)| (o.bind(gc), key.bind(gc)))
+                .collect(),
+        );
+    }
+    let mut chain = vec![object];
+    let mut prototype = agent[object].prototype;
+    while let Some(next) = prototype {
+        let Object::Object(next) = next else {
+            return None;
+        };
+        chain.push(next.bind(gc));
+        prototype = agent[next].prototype;
+    }
+    let mut visited_keys = PropertyKeySet::new(gc);
+    let mut keys = vec![];
+    for &o in chain.iter() {
+        for key in ordinary_own_property_keys(agent, o, gc) {
+            if let PropertyKey::Symbol(_) = key {
+                continue;
+            }
+            if !visited_keys.insert(agent, key) {
+                // Shadowed by an earlier object in the chain.
+                continue;
+            }
+            let desc = o.property_storage().get(agent, key);
+            if desc.is_some_and(|desc| desc.enumerable == Some(true)) {
+                keys.push((o, key));
+            }
+        }
+    }
+    let Heap {
+        objects,
+        own_keys_cache,
+        ..
+    } = &mut agent.heap;
+    own_keys_cache.set_for_in_keys(objects, &chain, &keys);
+    keys.reverse();
+    Some(keys)
+}
+
 struct ArrayValuesIterator<'a> {
     iter: ActiveIterator<'a>,
 }
@@ -638,11 +717,7 @@ impl<'a> GenericIterator<'a> {
         .unbind()?
         .bind(gc.nogc());
         let Ok(result) = Object::try_from(result) else {
-            return Err(agent.throw_exception_with_static_message(
-                ExceptionType::TypeError,
-                "Iterator returned a non-object result",
-                gc.into_nogc(),
-            ));
+            return agent.type_error("Iterator returned a non-object result", gc.into_nogc());
         };
         let result = result.unbind().bind(gc.nogc());
         let scoped_result = result.scope(agent, gc.nogc());
@@ -685,6 +760,7 @@ impl HeapMarkAndSweep for ObjectPropertiesIteratorRecord<'static> {
             object,
             visited_keys,
             remaining_keys,
+            chain_keys,
         } = self;
         object.mark_values(queues);
         visited_keys.mark_values(queues);
@@ -693,6 +769,12 @@ impl HeapMarkAndSweep for ObjectPropertiesIteratorRecord<'static> {
                 key.mark_values(queues);
             }
         }
+        if let Some(chain_keys) = chain_keys {
+            for (object, key) in chain_keys.iter() {
+                object.mark_values(queues);
+                key.mark_values(queues);
+            }
+        }
     }
 
     fn sweep_values(&mut self, compactions: &CompactionLists) {
@@ -700,6 +782,7 @@ impl HeapMarkAndSweep for ObjectPropertiesIteratorRecord<'static> {
             object,
             visited_keys,
             remaining_keys,
+            chain_keys,
         } = self;
         object.sweep_values(compactions);
         visited_keys.sweep_values(compactions);
@@ -708,6 +791,12 @@ impl HeapMarkAndSweep for ObjectPropertiesIteratorRecord<'static> {
                 key.sweep_values(compactions);
             }
         }
+        if let Some(chain_keys) = chain_keys {
+            for (object, key) in chain_keys.iter_mut() {
+                object.sweep_values(compactions);
+                key.sweep_values(compactions);
+            }
+        }
     }
 }
 
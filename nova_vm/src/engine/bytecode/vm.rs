@@ -3,13 +3,17 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 mod binding_methods;
+mod stepping;
 
-use std::{ptr::NonNull, sync::OnceLock};
+use std::{marker::PhantomData, ptr::NonNull, sync::OnceLock};
 
-use binding_methods::{execute_simple_array_binding, execute_simple_object_binding};
+use binding_methods::{
+    collect_rest_into_array, execute_simple_array_binding, execute_simple_object_binding,
+};
 use oxc_ast::ast;
 use oxc_span::Span;
 use oxc_syntax::operator::BinaryOperator;
+pub use stepping::{StepResult, VmState};
 
 use crate::{
     ecmascript::{
@@ -20,11 +24,12 @@ use crate::{
             },
             operations_on_objects::{
                 call, call_function, construct, copy_data_properties,
-                copy_data_properties_into_object, create_data_property_or_throw,
-                define_property_or_throw, get_method, has_property, ordinary_has_instance,
-                private_element_find, set, throw_no_proxy_private_names,
-                try_copy_data_properties_into_object, try_create_data_property,
-                try_define_property_or_throw, try_has_property,
+                copy_data_properties_into_object, create_array_from_list,
+                create_data_property_or_throw, define_property_or_throw, get_method, has_property,
+                integrity::Frozen, ordinary_has_instance, private_element_find, set,
+                set_integrity_level, throw_no_proxy_private_names,
+                try_copy_data_properties_into_object, try_define_property_or_throw,
+                try_has_property,
             },
             testing_and_comparison::{
                 is_callable, is_constructor, is_less_than, is_loosely_equal, is_strictly_equal,
@@ -36,9 +41,9 @@ use crate::{
             },
         },
         builtins::{
-            ArgumentsList, Array, BuiltinConstructorArgs, ConstructorStatus,
+            ArgumentsList, Array, BuiltinConstructorArgs, ConstructorStatus, ECMAScriptFunction,
             OrdinaryFunctionCreateParams, ScopedArgumentsList, SetFunctionNamePrefix, array_create,
-            create_builtin_constructor, create_unmapped_arguments_object,
+            create_builtin_constructor, create_unmapped_arguments_object, error::ErrorMessage,
             global_object::perform_eval, make_constructor, make_method,
             ordinary::ordinary_object_create_with_intrinsics, ordinary_function_create,
             set_function_name,
@@ -51,12 +56,12 @@ use crate::{
             resolve_this_binding,
         },
         types::{
-            BUILTIN_STRING_MEMORY, Base, BigInt, Function, InternalMethods, InternalSlots,
-            IntoFunction, IntoObject, IntoValue, Number, Numeric, Object, OrdinaryObject,
-            Primitive, PropertyDescriptor, PropertyKey, PropertyKeySet, Reference, String, Value,
-            get_this_value, get_value, initialize_referenced_binding, is_private_reference,
-            is_super_reference, put_value, throw_read_undefined_or_null_error, try_get_value,
-            try_initialize_referenced_binding,
+            BUILTIN_STRING_MEMORY, Base, BigInt, Function, HeapStringBuilder, InternalMethods,
+            InternalSlots, IntoFunction, IntoObject, IntoValue, JsType, Number, Numeric, Object,
+            OrdinaryObject, Primitive, PropertyDescriptor, PropertyKey, PropertyKeySet, Reference,
+            String, Value, get_this_value, get_value, initialize_referenced_binding,
+            is_private_reference, is_super_reference, put_value,
+            throw_read_undefined_or_null_error, try_get_value, try_initialize_referenced_binding,
         },
     },
     engine::{
@@ -64,7 +69,7 @@ use crate::{
         bytecode::{
             Executable, FunctionExpression, IndexType, Instruction, InstructionIter,
             NamedEvaluationParameter,
-            executable::ArrowFunctionExpression,
+            executable::{ArrowFunctionExpression, FrameSlotKind},
             instructions::Instr,
             iterator::{ObjectPropertiesIteratorRecord, VmIteratorRecord},
         },
@@ -75,6 +80,9 @@ use crate::{
     heap::{CompactionLists, HeapMarkAndSweep, WellKnownSymbolIndexes, WorkQueues},
 };
 
+#[cfg(feature = "regexp")]
+use crate::ecmascript::builtins::regexp::{RegExp, reg_exp_create_from_template};
+
 use super::iterator::ActiveIterator;
 
 struct EmptyParametersList(ast::FormalParameters<'static>);
@@ -166,12 +174,58 @@ enum ExceptionHandler<'a> {
     IgnoreErrorAndNextInstruction,
 }
 
+/// Arguments of a call, popped off the Vm stack but left in place in its
+/// spare capacity.
+///
+/// Nothing is pushed to the stack while a call is in progress, as the callee
+/// runs in a VM of its own. This lets ordinary calls pass their arguments to
+/// the callee without allocating.
+struct CallArgs<'a> {
+    ptr: NonNull<Value<'static>>,
+    len: usize,
+    value: PhantomData<Value<'a>>,
+}
+
+impl CallArgs<'_> {
+    fn as_mut_slice(&mut self) -> &mut [Value<'static>] {
+        // SAFETY: The arguments were popped off the stack and are not
+        // overwritten while the call is in progress.
+        unsafe { core::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<'a> core::ops::Deref for CallArgs<'a> {
+    type Target = [Value<'a>];
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: As above.
+        unsafe { core::slice::from_raw_parts(self.ptr.as_ptr().cast(), self.len) }
+    }
+}
+
+// SAFETY: Property implemented as a lifetime transmute.
+unsafe impl Bindable for CallArgs<'_> {
+    type Of<'a> = CallArgs<'a>;
+
+    #[inline(always)]
+    fn unbind(self) -> Self::Of<'static> {
+        unsafe { core::mem::transmute::<Self, Self::Of<'static>>(self) }
+    }
+
+    #[inline(always)]
+    fn bind<'a>(self, _gc: NoGcScope<'a, '_>) -> Self::Of<'a> {
+        unsafe { core::mem::transmute::<Self, Self::Of<'a>>(self) }
+    }
+}
+
+/// The bytecode virtual machine that executes an [`Executable`].
+///
 /// ## Notes
 ///
 /// - This is inspired by and/or copied from Kiesel engine:
 ///   Copyright (c) 2023-2024 Linus Groh
 #[derive(Debug)]
-pub(crate) struct Vm {
+pub struct Vm {
     /// Instruction pointer.
     ip: usize,
     stack: Vec<Value<'static>>,
@@ -180,6 +234,10 @@ pub(crate) struct Vm {
     exception_handler_stack: Vec<ExceptionHandler<'static>>,
     result: Option<Value<'static>>,
     reference: Option<Reference<'static>>,
+    /// Bindings of a function that keeps its parameters and locals in frame
+    /// slots instead of a function Environment Record. None indicates an
+    /// uninitialized binding.
+    frame_slots: Vec<Option<Value<'static>>>,
 }
 
 #[derive(Debug)]
@@ -263,9 +321,13 @@ impl SuspendedVm {
     }
 }
 
+/// Maximum number of finished VMs that the Agent keeps for reuse.
+const VM_POOL_SIZE: usize = 16;
+
 impl Vm {
-    fn new() -> Self {
-        Self {
+    /// Create a VM, reusing the buffers of a finished VM if one is available.
+    fn new(agent: &mut Agent) -> Self {
+        agent.vm_pool.pop().unwrap_or_else(|| Self {
             ip: 0,
             stack: Vec::with_capacity(32),
             reference_stack: Vec::new(),
@@ -273,10 +335,29 @@ impl Vm {
             exception_handler_stack: Vec::new(),
             result: None,
             reference: None,
+            frame_slots: Vec::new(),
+        })
+    }
+
+    /// Return a finished VM's buffers to the Agent for reuse by later calls.
+    fn release(mut self, agent: &mut Agent) {
+        if agent.vm_pool.len() < VM_POOL_SIZE {
+            self.ip = 0;
+            self.stack.clear();
+            self.reference_stack.clear();
+            self.iterator_stack.clear();
+            self.exception_handler_stack.clear();
+            self.result = None;
+            self.reference = None;
+            self.frame_slots.clear();
+            agent.vm_pool.push(self);
         }
     }
 
     fn suspend(self) -> SuspendedVm {
+        // Functions that await or yield always keep their bindings in a
+        // function Environment Record.
+        debug_assert!(self.frame_slots.is_empty());
         SuspendedVm {
             ip: self.ip,
             stack: self.stack.into_boxed_slice(),
@@ -295,6 +376,7 @@ impl Vm {
             exception_handler_stack: suspended.exception_jump_target_stack.into_vec(),
             result: None,
             reference: None,
+            frame_slots: Vec::new(),
         }
     }
 
@@ -305,9 +387,18 @@ impl Vm {
         arguments: Option<&mut [Value<'static>]>,
         gc: GcScope<'gc, '_>,
     ) -> ExecutionResult<'gc> {
-        let mut vm = Vm::new();
+        let mut vm = Vm::new(agent);
 
         if let Some(arguments) = arguments {
+            if !executable.needs_function_environment(agent) {
+                // The function keeps its parameters and locals in frame slots:
+                // bind the arguments directly.
+                vm.initialize_frame_slots(agent, &executable, arguments, gc.nogc());
+                if agent.options.print_internals {
+                    vm.print_internals(agent, executable.clone(), gc.nogc());
+                }
+                return vm.inner_execute(agent, executable, gc);
+            }
             ArgumentsList::from_mut_slice(arguments).with_scoped(
                 agent,
                 |agent, arguments, gc| {
@@ -342,6 +433,9 @@ impl Vm {
         eprintln!();
         eprintln!("=== Executing Executable ===");
         eprintln!("Constants: {:?}", executable.get_constants(agent, gc));
+        if !self.frame_slots.is_empty() {
+            self.print_frame_slots(agent, &executable, gc);
+        }
         eprintln!();
 
         eprintln!("Instructions:");
@@ -352,7 +446,119 @@ impl Vm {
         eprintln!();
     }
 
-    pub fn resume<'gc>(
+    /// Initialize the frame slots of a function that keeps its bindings in
+    /// them. Parameters are bound directly from the arguments list.
+    fn initialize_frame_slots(
+        &mut self,
+        agent: &Agent,
+        executable: &Scoped<Executable>,
+        arguments: &[Value],
+        gc: NoGcScope,
+    ) {
+        let mut arguments = arguments.iter();
+        self.frame_slots.clear();
+        self.frame_slots
+            .extend(
+                executable
+                    .get_frame_slots(agent, gc)
+                    .iter()
+                    .map(|slot| match slot.kind {
+                        FrameSlotKind::Parameter => {
+                            Some(arguments.next().map_or(Value::Undefined, |v| v.unbind()))
+                        }
+                        FrameSlotKind::Var => Some(Value::Undefined),
+                        FrameSlotKind::Let | FrameSlotKind::Const => None,
+                    }),
+            );
+    }
+
+    /// Get the bindings kept in frame slots by name, in slot order.
+    ///
+    /// This is the introspection counterpart to reading the bindings of a
+    /// function Environment Record.
+    pub(crate) fn get_frame_slot_bindings<'gc>(
+        &self,
+        agent: &Agent,
+        executable: &Scoped<Executable>,
+        gc: NoGcScope<'gc, '_>,
+    ) -> Vec<(String<'gc>, Option<Value<'gc>>)> {
+        executable
+            .get_frame_slots(agent, gc)
+            .iter()
+            .zip(self.frame_slots.iter())
+            .map(|(slot, value)| (slot.name, value.bind(gc)))
+            .collect()
+    }
+
+    fn print_frame_slots(&self, agent: &Agent, executable: &Scoped<Executable>, gc: NoGcScope) {
+        eprintln!("Frame slots:");
+        for (index, (name, value)) in self
+            .get_frame_slot_bindings(agent, executable, gc)
+            .into_iter()
+            .enumerate()
+        {
+            match value {
+                Some(value) => eprintln!("  {index}: {} = {value:?}", name.to_string_lossy(agent)),
+                None => eprintln!(
+                    "  {index}: {} = <uninitialized>",
+                    name.to_string_lossy(agent)
+                ),
+            }
+        }
+    }
+
+    /// GetBindingValue of a binding kept in a frame slot.
+    fn get_frame_slot_value<'a>(
+        &self,
+        agent: &mut Agent,
+        reference: &Reference,
+        slot: u32,
+        gc: NoGcScope<'a, '_>,
+    ) -> JsResult<'a, Value<'a>> {
+        if let Some(value) = self.frame_slots[slot as usize] {
+            Ok(value.bind(gc))
+        } else {
+            Err(agent.throw_exception_with_deferred_message(
+                ExceptionType::ReferenceError,
+                ErrorMessage::AccessBeforeInitialization,
+                [reference.referenced_name.into()],
+                gc,
+            ))
+        }
+    }
+
+    /// SetMutableBinding of a binding kept in a frame slot.
+    fn set_frame_slot_value<'a>(
+        &mut self,
+        agent: &mut Agent,
+        executable: &Scoped<Executable>,
+        reference: &Reference,
+        slot: u32,
+        value: Value,
+        gc: NoGcScope<'a, '_>,
+    ) -> JsResult<'a, ()> {
+        let slot = slot as usize;
+        if self.frame_slots[slot].is_none() {
+            return Err(agent.throw_exception_with_deferred_message(
+                ExceptionType::ReferenceError,
+                ErrorMessage::AccessBeforeInitialization,
+                [reference.referenced_name.into()],
+                gc,
+            ));
+        }
+        if executable.get_frame_slots(agent, gc)[slot].kind == FrameSlotKind::Const {
+            return Err(agent.throw_exception_with_deferred_message(
+                ExceptionType::TypeError,
+                ErrorMessage::ImmutableIdentifierAssignment,
+                [reference.referenced_name.into()],
+                gc,
+            ));
+        }
+        self.frame_slots[slot] = Some(value.unbind());
+        Ok(())
+    }
+
+    pub(crate) fn resume<'gc>(
         mut self,
         agent: &mut Agent,
         executable: Scoped<Executable>,
@@ -363,7 +569,7 @@ impl Vm {
         self.inner_execute(agent, executable, gc)
     }
 
-    pub fn resume_throw<'gc>(
+    pub(crate) fn resume_throw<'gc>(
         mut self,
         agent: &mut Agent,
         executable: Scoped<Executable>,
@@ -387,63 +593,116 @@ impl Vm {
         executable: Scoped<Executable>,
         mut gc: GcScope<'gc, '_>,
     ) -> ExecutionResult<'gc> {
+        let mut vm = VmRef::register(agent, &mut self);
         let stack_depth = agent.stack_refs.borrow().len();
+        let vm_depth = agent.vm_stack.len();
         let instructions = executable.get_instructions(agent);
-        while let Some(instr) = Instr::consume_instruction(instructions, &mut self.ip) {
-            if agent.check_gc() {
-                with_vm_gc(agent, &mut self, |agent, gc| agent.gc(gc), gc.reborrow());
-            }
-            if agent.options.print_internals {
-                eprintln!("Executing: {:?}", instr.kind);
-            }
-            match Self::execute_instruction(
+        let exit = loop {
+            match Self::execute_next_instruction(
                 agent,
-                &mut self,
-                executable.clone(),
-                &instr,
+                &mut vm,
+                &executable,
+                instructions,
+                stack_depth,
+                vm_depth,
                 gc.reborrow(),
             ) {
-                Ok(ContinuationKind::Normal) => {}
-                Ok(ContinuationKind::Return) => {
-                    if agent.options.print_internals {
-                        eprintln!("Exiting function with result\n");
-                    }
-                    let result = self.result.unwrap_or(Value::Undefined);
-                    return ExecutionResult::Return(result);
+                Some(Ok(ContinuationKind::Normal)) => {}
+                exit => break exit.map(|exit| exit.map_err(|err| err.unbind())),
+            }
+        };
+        vm.unregister(agent);
+
+        match exit {
+            None => {
+                self.release(agent);
+                ExecutionResult::Return(Value::Undefined)
+            }
+            Some(Ok(ContinuationKind::Yield)) => {
+                if agent.options.print_internals {
+                    eprintln!("Yielding value from function\n");
                 }
-                Ok(ContinuationKind::Yield) => {
-                    if agent.options.print_internals {
-                        eprintln!("Yielding value from function\n");
-                    }
-                    let yielded_value = self.result.take().unwrap();
-                    return ExecutionResult::Yield {
-                        vm: self.suspend(),
-                        yielded_value,
-                    };
+                let yielded_value = self.result.take().unwrap();
+                ExecutionResult::Yield {
+                    vm: self.suspend(),
+                    yielded_value,
                 }
-                Ok(ContinuationKind::Await) => {
-                    if agent.options.print_internals {
-                        eprintln!("Awaiting value in function\n");
-                    }
-                    let awaited_value = self.result.take().unwrap();
-                    return ExecutionResult::Await {
-                        vm: self.suspend(),
-                        awaited_value,
-                    };
+            }
+            Some(Ok(ContinuationKind::Await)) => {
+                if agent.options.print_internals {
+                    eprintln!("Awaiting value in function\n");
                 }
-                Err(err) => {
-                    if !self.handle_error(agent, err) {
-                        if agent.options.print_internals {
-                            eprintln!("Exiting function with error\n");
-                        }
-                        return ExecutionResult::Throw(err.unbind().bind(gc.into_nogc()));
-                    }
+                let awaited_value = self.result.take().unwrap();
+                ExecutionResult::Await {
+                    vm: self.suspend(),
+                    awaited_value,
+                }
+            }
+            Some(Ok(_)) => {
+                if agent.options.print_internals {
+                    eprintln!("Exiting function with result\n");
                 }
+                let result = self.result.unwrap_or(Value::Undefined);
+                self.release(agent);
+                ExecutionResult::Return(result)
+            }
+            Some(Err(err)) => {
+                if agent.options.print_internals {
+                    eprintln!("Exiting function with error\n");
+                }
+                self.release(agent);
+                ExecutionResult::Throw(err.bind(gc.into_nogc()))
             }
-            agent.stack_refs.borrow_mut().truncate(stack_depth);
         }
+    }
 
-        ExecutionResult::Return(Value::Undefined)
+    /// Execute the instruction at the instruction pointer of a registered Vm.
+    ///
+    /// Returns None if there are no instructions left, and
+    /// `Some(Ok(ContinuationKind::Normal))` if execution continues with the
+    /// next instruction, also when the instruction threw an error that was
+    /// caught.
+    #[inline(always)]
+    fn execute_next_instruction<'gc>(
+        agent: &mut Agent,
+        vm: &mut VmRef,
+        executable: &Scoped<Executable>,
+        instructions: &[u8],
+        stack_depth: usize,
+        vm_depth: usize,
+        mut gc: GcScope<'gc, '_>,
+    ) -> Option<JsResult<'gc, ContinuationKind>> {
+        if agent.has_breakpoints() {
+            agent.check_breakpoint(executable.get(agent), vm.ip, gc.reborrow());
+        }
+        let instr = Instr::consume_instruction(instructions, &mut vm.ip)?;
+        let instruction_ip = vm.ip;
+        agent.check_heap_growth();
+        if agent.check_gc() {
+            agent.gc(gc.reborrow());
+        }
+        if agent.options.print_internals {
+            eprintln!("Executing: {:?}", instr.kind);
+        }
+        let result =
+            Self::execute_instruction(agent, vm, executable.clone(), &instr, gc.reborrow());
+        if agent.has_pending_slow_paths() {
+            let span = executable.get(agent).get_span(agent, instruction_ip);
+            agent.flush_slow_paths(vm_depth, span);
+        }
+        match result {
+            Ok(ContinuationKind::Normal) => {}
+            Ok(continuation) => return Some(Ok(continuation)),
+            Err(err) => {
+                if !vm.handle_error(agent, err) {
+                    let span = executable.get(agent).get_span(agent, vm.ip);
+                    agent.record_exception_location(err, span);
+                    return Some(Err(err.unbind().bind(gc.into_nogc())));
+                }
+            }
+        }
+        agent.stack_refs.borrow_mut().truncate(stack_depth);
+        Some(Ok(ContinuationKind::Normal))
     }
 
     #[must_use]
@@ -469,15 +728,27 @@ impl Vm {
                     self.ip += 1;
                 }
             }
+            agent.clear_exception_location();
             true
         } else {
             false
         }
     }
 
+    /// Jump to the jump slot of the instruction if the condition holds.
+    fn jump_if(&mut self, agent: &Agent, instr: &Instr, condition: bool) {
+        if condition {
+            let ip = instr.get_jump_slot();
+            if agent.options.print_internals {
+                eprintln!("Condition holds, jumping to {ip}");
+            }
+            self.ip = ip;
+        }
+    }
+
     fn execute_instruction<'a>(
         agent: &mut Agent,
-        vm: &mut Vm,
+        vm: &mut VmRef,
         executable: Scoped<Executable>,
         instr: &Instr,
         mut gc: GcScope<'a, '_>,
@@ -498,12 +769,7 @@ impl Vm {
                 let key = PropertyKey::Integer(len.into());
                 let array = array.unbind();
                 let value = value.unbind();
-                with_vm_gc(
-                    agent,
-                    vm,
-                    |agent, gc| create_data_property_or_throw(agent, array, key, value, gc),
-                    gc,
-                )?;
+                create_data_property_or_throw(agent, array, key, value, gc)?;
             }
             Instruction::ArrayElision => {
                 let array = vm.stack.last().unwrap().bind(gc.nogc());
@@ -512,19 +778,12 @@ impl Vm {
                 };
                 let length = array.len(agent) + 1;
                 let array = array.into_object().unbind();
-                with_vm_gc(
+                set(
                     agent,
-                    vm,
-                    |agent, gc| {
-                        set(
-                            agent,
-                            array,
-                            BUILTIN_STRING_MEMORY.length.into(),
-                            length.into(),
-                            true,
-                            gc,
-                        )
-                    },
+                    array,
+                    BUILTIN_STRING_MEMORY.length.into(),
+                    length.into(),
+                    true,
                     gc,
                 )?;
             }
@@ -553,7 +812,10 @@ impl Vm {
             }
             Instruction::Debug => {
                 if agent.options.print_internals {
-                    eprintln!("Debug: {vm:#?}");
+                    eprintln!("Debug: {:#?}", &**vm);
+                    if !vm.frame_slots.is_empty() {
+                        vm.print_frame_slots(agent, &executable, gc.nogc());
+                    }
                 }
             }
             Instruction::ResolveBinding => {
@@ -566,16 +828,30 @@ impl Vm {
                     reference
                 } else {
                     let identifier = identifier.unbind();
-                    with_vm_gc(
-                        agent,
-                        vm,
-                        |agent, gc| resolve_binding(agent, identifier, None, gc),
-                        gc,
-                    )?
+                    resolve_binding(agent, identifier, None, gc)?
                 };
 
                 vm.reference = Some(reference.unbind());
             }
+            Instruction::ResolveLocal => {
+                let identifier =
+                    executable.fetch_identifier(agent, instr.get_first_index(), gc.nogc());
+                let slot = instr.get_second_index() as u32;
+                let strict = agent
+                    .running_execution_context()
+                    .ecmascript_code
+                    .unwrap()
+                    .is_strict_mode;
+                vm.reference = Some(
+                    Reference {
+                        base: Base::FrameSlot(slot),
+                        referenced_name: identifier.into(),
+                        strict,
+                        this_value: None,
+                    }
+                    .unbind(),
+                );
+            }
             Instruction::ResolveThisBinding => {
                 let this = resolve_this_binding(agent, gc.into_nogc())?.unbind();
                 vm.result = Some(this);
@@ -586,10 +862,12 @@ impl Vm {
                 vm.stack.push(constant.unbind());
             }
             Instruction::Load => {
-                vm.stack.push(vm.result.take().unwrap());
+                let result = vm.result.take().unwrap();
+                vm.stack.push(result);
             }
             Instruction::LoadCopy => {
-                vm.stack.push(vm.result.unwrap());
+                let result = vm.result.unwrap();
+                vm.stack.push(result);
             }
             Instruction::LoadStoreSwap => {
                 let temp = vm
@@ -632,12 +910,12 @@ impl Vm {
             }
             Instruction::ToNumber => {
                 let arg0 = vm.result.unwrap();
-                let result = with_vm_gc(agent, vm, |agent, gc| to_number(agent, arg0, gc), gc)?;
+                let result = to_number(agent, arg0, gc)?;
                 vm.result = Some(result.into_value().unbind());
             }
             Instruction::ToNumeric => {
                 let arg0 = vm.result.unwrap();
-                let result = with_vm_gc(agent, vm, |agent, gc| to_numeric(agent, arg0, gc), gc)?;
+                let result = to_numeric(agent, arg0, gc)?;
                 vm.result = Some(result.into_value().unbind());
             }
             Instruction::ToObject => {
@@ -650,30 +928,18 @@ impl Vm {
             Instruction::ApplyStringOrNumericBinaryOperator(op_text) => {
                 let lval = vm.stack.pop().unwrap();
                 let rval = vm.result.take().unwrap();
-                let result = with_vm_gc(
-                    agent,
-                    vm,
-                    |agent, gc| {
-                        if op_text == BinaryOperator::Addition {
-                            apply_string_or_numeric_addition(agent, lval, rval, gc)
-                        } else {
-                            apply_string_or_numeric_binary_operator(agent, lval, op_text, rval, gc)
-                        }
-                    },
-                    gc,
-                )?;
+                let result = if op_text == BinaryOperator::Addition {
+                    apply_string_or_numeric_addition(agent, lval, rval, gc)
+                } else {
+                    apply_string_or_numeric_binary_operator(agent, lval, op_text, rval, gc)
+                }?;
                 vm.result = Some(result.unbind());
             }
             Instruction::ObjectDefineProperty => {
                 let key = vm.stack.pop().unwrap();
-                let key = with_vm_gc(
-                    agent,
-                    vm,
-                    |agent, gc| to_property_key(agent, key, gc),
-                    gc.reborrow(),
-                )
-                .unbind()?
-                .bind(gc.nogc());
+                let key = to_property_key(agent, key, gc.reborrow())
+                    .unbind()?
+                    .bind(gc.nogc());
                 let key = key.unbind().bind(gc.nogc());
                 let value = vm.result.take().unwrap().bind(gc.nogc());
                 let object = vm.stack.last().unwrap().bind(gc.nogc());
@@ -694,14 +960,9 @@ impl Vm {
                 let enumerable = instr.get_second_bool();
                 // 1. Let propKey be ? Evaluation of ClassElementName.
                 let prop_key = vm.stack.pop().unwrap();
-                let prop_key = with_vm_gc(
-                    agent,
-                    vm,
-                    |agent, gc| to_property_key(agent, prop_key, gc),
-                    gc.reborrow(),
-                )
-                .unbind()?
-                .bind(gc.nogc());
+                let prop_key = to_property_key(agent, prop_key, gc.reborrow())
+                    .unbind()?
+                    .bind(gc.nogc());
                 let object = Object::try_from(*vm.stack.last().unwrap())
                     .unwrap()
                     .bind(gc.nogc());
@@ -776,12 +1037,7 @@ impl Vm {
 
                 let object = object.unbind();
                 let prop_key = prop_key.unbind();
-                with_vm_gc(
-                    agent,
-                    vm,
-                    |agent, gc| define_property_or_throw(agent, object, prop_key, desc, gc),
-                    gc,
-                )?;
+                define_property_or_throw(agent, object, prop_key, desc, gc)?;
                 // c. Return unused.
             }
             Instruction::ObjectDefineGetter => {
@@ -791,14 +1047,9 @@ impl Vm {
                 let enumerable = instr.get_second_bool();
                 // 1. Let propKey be ? Evaluation of ClassElementName.
                 let prop_key = vm.stack.pop().unwrap();
-                let prop_key = with_vm_gc(
-                    agent,
-                    vm,
-                    |agent, gc| to_property_key(agent, prop_key, gc),
-                    gc.reborrow(),
-                )
-                .unbind()?
-                .bind(gc.nogc());
+                let prop_key = to_property_key(agent, prop_key, gc.reborrow())
+                    .unbind()?
+                    .bind(gc.nogc());
                 // 2. Let env be the running execution context's LexicalEnvironment.
                 let env = agent.current_lexical_environment(gc.nogc());
                 // 3. Let privateEnv be the running execution context's PrivateEnvironment.
@@ -869,12 +1120,7 @@ impl Vm {
                 // b. Perform ? DefinePropertyOrThrow(object, propKey, desc).
                 let object = object.unbind();
                 let prop_key = prop_key.unbind();
-                with_vm_gc(
-                    agent,
-                    vm,
-                    |agent, gc| define_property_or_throw(agent, object, prop_key, desc, gc),
-                    gc,
-                )?;
+                define_property_or_throw(agent, object, prop_key, desc, gc)?;
                 // c. Return unused.
             }
             Instruction::ObjectDefineSetter => {
@@ -884,14 +1130,9 @@ impl Vm {
                 let enumerable = instr.get_second_bool();
                 // 1. Let propKey be ? Evaluation of ClassElementName.
                 let prop_key = vm.stack.pop().unwrap();
-                let prop_key = with_vm_gc(
-                    agent,
-                    vm,
-                    |agent, gc| to_property_key(agent, prop_key, gc),
-                    gc.reborrow(),
-                )
-                .unbind()?
-                .bind(gc.nogc());
+                let prop_key = to_property_key(agent, prop_key, gc.reborrow())
+                    .unbind()?
+                    .bind(gc.nogc());
                 // 2. Let env be the running execution context's LexicalEnvironment.
                 let env = agent.current_lexical_environment(gc.nogc());
                 // 3. Let privateEnv be the running execution context's PrivateEnvironment.
@@ -948,12 +1189,7 @@ impl Vm {
                 // b. Perform ? DefinePropertyOrThrow(object, propKey, desc).
                 let object = object.unbind();
                 let prop_key = prop_key.unbind();
-                with_vm_gc(
-                    agent,
-                    vm,
-                    |agent, gc| define_property_or_throw(agent, object, prop_key, desc, gc),
-                    gc,
-                )?;
+                define_property_or_throw(agent, object, prop_key, desc, gc)?;
                 // c. Return unused.
             }
             Instruction::ObjectSetPrototype => {
@@ -974,16 +1210,12 @@ impl Vm {
                 };
 
                 let object = object.unbind();
-                with_vm_gc(
-                    agent,
-                    vm,
-                    |agent, gc| object.internal_set_prototype_of(agent, prop_value, gc),
-                    gc,
-                )?;
+                object.internal_set_prototype_of(agent, prop_value, gc)?;
                 // b. Return unused.
             }
             Instruction::PushReference => {
-                vm.reference_stack.push(vm.reference.take().unwrap());
+                let reference = vm.reference.take().unwrap();
+                vm.reference_stack.push(reference);
             }
             Instruction::PopReference => {
                 vm.reference = Some(vm.reference_stack.pop().unwrap());
@@ -991,23 +1223,31 @@ impl Vm {
             Instruction::PutValue => {
                 let value = vm.result.take().unwrap();
                 let reference = vm.reference.take().unwrap();
-                with_vm_gc(
-                    agent,
-                    vm,
-                    |agent, gc| put_value(agent, &reference, value, gc),
-                    gc,
-                )?;
+                if let Base::FrameSlot(slot) = reference.base {
+                    vm.set_frame_slot_value(
+                        agent,
+                        &executable,
+                        &reference,
+                        slot,
+                        value,
+                        gc.into_nogc(),
+                    )?;
+                } else {
+                    put_value(agent, &reference, value, gc)?;
+                }
             }
             Instruction::GetValue => {
                 // 1. If V is not a Reference Record, return V.
                 let reference = vm.reference.take().unwrap();
 
-                let result = if let TryResult::Continue(result) =
+                let result = if let Base::FrameSlot(slot) = reference.base {
+                    vm.get_frame_slot_value(agent, &reference, slot, gc.into_nogc())?
+                } else if let TryResult::Continue(result) =
                     try_get_value(agent, &reference, gc.nogc())
                 {
                     result.unbind()?.bind(gc.into_nogc())
                 } else {
-                    with_vm_gc(agent, vm, |agent, gc| get_value(agent, &reference, gc), gc)?
+                    get_value(agent, &reference, gc)?
                 };
 
                 vm.result = Some(result.unbind());
@@ -1016,22 +1256,35 @@ impl Vm {
                 // 1. If V is not a Reference Record, return V.
                 let reference = vm.reference.as_ref().unwrap().clone();
 
-                let result = if let TryResult::Continue(result) =
+                let result = if let Base::FrameSlot(slot) = reference.base {
+                    vm.get_frame_slot_value(agent, &reference, slot, gc.into_nogc())?
+                } else if let TryResult::Continue(result) =
                     try_get_value(agent, &reference, gc.nogc())
                 {
                     result.unbind()?.bind(gc.into_nogc())
                 } else {
-                    with_vm_gc(agent, vm, |agent, gc| get_value(agent, &reference, gc), gc)?
+                    get_value(agent, &reference, gc)?
                 };
 
                 vm.result = Some(result.unbind());
             }
+            Instruction::GetTemplateObject => {
+                let template_literal =
+                    executable.fetch_template_literal(agent, instr.get_first_index());
+                let template = get_template_object(agent, template_literal, gc)?;
+                vm.result = Some(template.into_value().unbind());
+            }
             Instruction::Typeof => {
                 // 2. If val is a Reference Record, then
                 let val = if let Some(reference) = vm.reference.take() {
                     if reference.base == Base::Unresolvable {
                         // a. If IsUnresolvableReference(val) is true, return "undefined".
                         Value::Undefined
+                    } else if let Base::FrameSlot(slot) = reference.base {
+                        // 3. Set val to ? GetValue(val).
+                        vm.get_frame_slot_value(agent, &reference, slot, gc.nogc())
+                            .unbind()?
+                            .bind(gc.nogc())
                     } else {
                         // 3. Set val to ? GetValue(val).
                         if let TryResult::Continue(result) =
@@ -1039,28 +1292,36 @@ impl Vm {
                         {
                             result.unbind()?.bind(gc.nogc())
                         } else {
-                            with_vm_gc(
-                                agent,
-                                vm,
-                                |agent, gc| get_value(agent, &reference, gc),
-                                gc.reborrow(),
-                            )
-                            .unbind()?
-                            .bind(gc.nogc())
+                            get_value(agent, &reference, gc.reborrow())
+                                .unbind()?
+                                .bind(gc.nogc())
                         }
                     }
                 } else {
                     vm.result.unwrap().bind(gc.nogc())
                 };
-                vm.result = Some(typeof_operator(agent, val, gc.nogc()).into_value())
+                vm.result = Some(typeof_operator(agent, val).into_value())
             }
-            Instruction::ObjectCreate => {
-                let object = ordinary_object_create_with_intrinsics(
+            #[cfg(feature = "regexp")]
+            Instruction::CreateRegExp => {
+                let template = RegExp::try_from(executable.fetch_constant(
                     agent,
-                    Some(ProtoIntrinsics::Object),
-                    None,
+                    instr.get_first_index(),
                     gc.nogc(),
-                );
+                ))
+                .unwrap();
+                let regexp = reg_exp_create_from_template(agent, template, gc.nogc());
+                vm.result = Some(regexp.into_value().unbind());
+            }
+            Instruction::ObjectCreate => {
+                let prototype = agent
+                    .current_realm_record()
+                    .intrinsics()
+                    .object_prototype()
+                    .into_object();
+                let object = agent
+                    .heap
+                    .create_object_with_prototype_and_capacity(prototype, instr.get_first_index());
                 vm.stack.push(object.into_value().unbind())
             }
             Instruction::CopyDataProperties => {
@@ -1068,12 +1329,7 @@ impl Vm {
                 let Value::Object(target) = *vm.stack.last().unwrap() else {
                     unreachable!()
                 };
-                with_vm_gc(
-                    agent,
-                    vm,
-                    |agent, gc| copy_data_properties(agent, target, source, gc),
-                    gc,
-                )?;
+                copy_data_properties(agent, target, source, gc)?;
             }
             Instruction::CopyDataPropertiesIntoObject => {
                 let from = Object::try_from(vm.result.unwrap())
@@ -1097,14 +1353,8 @@ impl Vm {
                 } else {
                     let from = from.unbind();
                     let excluded_items = excluded_items.scope(agent, gc.nogc());
-                    let result = with_vm_gc(
-                        agent,
-                        vm,
-                        |agent, gc| {
-                            copy_data_properties_into_object(agent, from, excluded_items, gc)
-                        },
-                        gc,
-                    )?;
+                    let result =
+                        { copy_data_properties_into_object(agent, from, excluded_items, gc) }?;
                     vm.result = Some(result.into_value().unbind());
                 }
             }
@@ -1166,19 +1416,15 @@ impl Vm {
                     match pk_result {
                         Ok(pk) => pk.bind(gc.nogc()),
                         Err(pk_value) => {
-                            let scoped_function = function.scope(agent, gc.nogc());
+                            // Keep the function rooted on the stack during the call.
+                            vm.stack.push(function.into_value().unbind());
                             let pk_value = pk_value.unbind();
-                            let pk = with_vm_gc(
-                                agent,
-                                vm,
-                                |agent, gc| to_property_key_complex(agent, pk_value, gc),
-                                gc.reborrow(),
-                            )
-                            .unbind()?
-                            .bind(gc.nogc());
-                            // SAFETY: not shared.
-                            function = unsafe { scoped_function.take(agent).bind(gc.nogc()) };
-                            pk
+                            let pk =
+                                to_property_key_complex(agent, pk_value, gc.reborrow()).unbind();
+                            function = ECMAScriptFunction::try_from(vm.stack.pop().unwrap())
+                                .unwrap()
+                                .bind(gc.nogc());
+                            pk?.bind(gc.nogc())
                         }
                     }
                 } else {
@@ -1203,14 +1449,9 @@ impl Vm {
                         NamedEvaluationParameter::Result => vm.result.take().unwrap(),
                         NamedEvaluationParameter::Stack => *vm.stack.last().unwrap(),
                     };
-                    let name = with_vm_gc(
-                        agent,
-                        vm,
-                        |agent, gc| to_property_key(agent, pk, gc),
-                        gc.reborrow(),
-                    )
-                    .unbind()?
-                    .bind(gc.nogc());
+                    let name = to_property_key(agent, pk, gc.reborrow())
+                        .unbind()?
+                        .bind(gc.nogc());
                     (name, agent.current_lexical_environment(gc.nogc()), false)
                 } else if let Some(binding_identifier) = &function_expression.id {
                     let name = String::from_str(agent, &binding_identifier.name, gc.nogc());
@@ -1316,7 +1557,7 @@ impl Vm {
             }
             Instruction::ClassDefineConstructor => {
                 let name = vm.stack.pop().unwrap();
-                let class_name = set_class_name(agent, vm, name, gc.reborrow())
+                let class_name = set_class_name(agent, name, gc.reborrow())
                     .unbind()?
                     .bind(gc.nogc());
 
@@ -1386,7 +1627,7 @@ impl Vm {
             }
             Instruction::ClassDefineDefaultConstructor => {
                 let name = vm.stack.pop().unwrap();
-                let class_name = set_class_name(agent, vm, name, gc.reborrow())
+                let class_name = set_class_name(agent, name, gc.reborrow())
                     .unbind()?
                     .bind(gc.nogc());
 
@@ -1495,8 +1736,11 @@ impl Vm {
                 // 7. Perform MakeMethod(closure, object).
                 make_method(agent, closure, object.into_object());
                 // 8. Perform SetFunctionName(closure, propKey).
-                let function_name = format!("#{}", description.as_str(agent));
-                let function_name = String::from_string(agent, function_name, gc.nogc());
+                let function_name = String::concat(
+                    agent,
+                    [String::from_small_string("#"), description],
+                    gc.nogc(),
+                );
                 set_function_name(
                     agent,
                     closure,
@@ -1540,14 +1784,7 @@ impl Vm {
                     // b. Perform ? DefinePropertyOrThrow(object, propKey, desc).
                     let private_name = private_env.add_static_private_method(agent, description);
                     let object = object.unbind();
-                    with_vm_gc(
-                        agent,
-                        vm,
-                        |agent, gc| {
-                            define_property_or_throw(agent, object, private_name.into(), desc, gc)
-                        },
-                        gc,
-                    )?;
+                    { define_property_or_throw(agent, object, private_name.into(), desc, gc) }?;
                 } else {
                     // a. Return PrivateElement {
                     //      [[Key]]: propKey,
@@ -1623,21 +1860,17 @@ impl Vm {
                 vm.stack.push(b);
             }
             Instruction::DirectEvalCall => {
-                let func = with_vm_gc(
-                    agent,
-                    vm,
-                    |agent, mut gc| {
-                        let func_ref =
-                            resolve_binding(agent, BUILTIN_STRING_MEMORY.eval, None, gc.reborrow())
-                                .unbind()?
-                                .bind(gc.nogc());
-                        get_value(agent, &func_ref.unbind(), gc)
-                    },
-                    gc.reborrow(),
-                )
+                let func = {
+                    let mut gc = gc.reborrow();
+                    let func_ref =
+                        resolve_binding(agent, BUILTIN_STRING_MEMORY.eval, None, gc.reborrow())
+                            .unbind()?
+                            .bind(gc.nogc());
+                    get_value(agent, &func_ref.unbind(), gc)
+                }
                 .unbind()?
                 .bind(gc.nogc());
-                let args = vm.get_call_args(instr, gc.nogc());
+                let args = vm.pop_call_args(instr, gc.nogc());
 
                 // a. If SameValue(func, %eval%) is true, then
                 let result = if func
@@ -1664,28 +1897,16 @@ impl Vm {
                             .is_strict_mode;
                         // v. Return ? PerformEval(evalArg, strictCaller, true).
                         let eval_arg = eval_arg.unbind();
-                        with_vm_gc(
-                            agent,
-                            vm,
-                            |agent, gc| perform_eval(agent, eval_arg, true, strict_caller, gc),
-                            gc,
-                        )?
+                        perform_eval(agent, eval_arg, true, strict_caller, gc)?
                     }
                 } else {
                     let func = func.unbind();
                     let mut args = args.unbind();
-                    with_vm_gc(
+                    call(
                         agent,
-                        vm,
-                        |agent, gc| {
-                            call(
-                                agent,
-                                func,
-                                Value::Undefined,
-                                Some(ArgumentsList::from_mut_slice(args.as_mut_slice())),
-                                gc,
-                            )
-                        },
+                        func,
+                        Value::Undefined,
+                        Some(ArgumentsList::from_mut_slice(args.as_mut_slice())),
                         gc,
                     )?
                 };
@@ -1708,6 +1929,9 @@ impl Vm {
                                 .map_or(Value::Undefined, |object| object.into_value())
                                 .bind(gc.nogc())
                         }
+                        // Frame slots hold declarative bindings, for which
+                        // WithBaseObject() returns undefined.
+                        Base::FrameSlot(_) => Value::Undefined,
                         // ii. Assert: refEnv is an Environment Record.
                         Base::Unresolvable => unreachable!(),
                     }
@@ -1716,63 +1940,41 @@ impl Vm {
                     // a. Let thisValue be undefined.
                     Value::Undefined
                 };
-                let mut args = vm.get_call_args(instr, gc.nogc()).unbind();
+                let mut args = vm.pop_call_args(instr, gc.nogc()).unbind();
                 let func = vm.stack.pop().unwrap().unbind();
                 let this_value = this_value.unbind();
-                let result = with_vm_gc(
+                let result = call(
                     agent,
-                    vm,
-                    |agent, gc| {
-                        call(
-                            agent,
-                            func,
-                            this_value,
-                            Some(ArgumentsList::from_mut_slice(args.as_mut_slice())),
-                            gc,
-                        )
-                    },
+                    func,
+                    this_value,
+                    Some(ArgumentsList::from_mut_slice(args.as_mut_slice())),
                     gc,
                 )?;
                 vm.result = Some(result.unbind());
             }
             Instruction::EvaluateNew => {
-                let args = vm.get_call_args(instr, gc.nogc());
+                let args = vm.pop_call_args(instr, gc.nogc());
                 let constructor = vm.stack.pop().unwrap().bind(gc.nogc());
                 let Some(constructor) = is_constructor(agent, constructor) else {
                     let constructor_string = {
                         let constructor = constructor.unbind();
-                        with_vm_gc(
-                            agent,
-                            vm,
-                            |agent, gc| constructor.string_repr(agent, gc),
-                            gc.reborrow(),
-                        )
+                        constructor.string_repr(agent, gc.reborrow())
                     };
-                    let error_message = format!(
-                        "'{}' is not a constructor.",
-                        constructor_string.as_str(agent)
-                    );
-                    return Err(agent.throw_exception(
+                    return Err(agent.throw_exception_with_deferred_message(
                         ExceptionType::TypeError,
-                        error_message,
+                        ErrorMessage::NotAConstructor,
+                        [constructor_string.unbind().into()],
                         gc.into_nogc(),
                     ));
                 };
 
                 let constructor = constructor.unbind();
                 let mut args = args.unbind();
-                let result = with_vm_gc(
+                let result = construct(
                     agent,
-                    vm,
-                    |agent, gc| {
-                        construct(
-                            agent,
-                            constructor,
-                            Some(ArgumentsList::from_mut_slice(args.as_mut_slice())),
-                            None,
-                            gc,
-                        )
-                    },
+                    constructor,
+                    Some(ArgumentsList::from_mut_slice(args.as_mut_slice())),
+                    None,
                     gc,
                 )?;
                 vm.result = Some(result.unbind().into_value());
@@ -1795,27 +1997,15 @@ impl Vm {
                     )
                 };
                 // 4. Let argList be ? ArgumentListEvaluation of Arguments.
-                let arg_list = vm.get_call_args(instr, gc.nogc());
+                let arg_list = vm.pop_call_args(instr, gc.nogc());
                 // 5. If IsConstructor(func) is false, throw a TypeError exception.
                 let Some(func) = func.and_then(|func| is_constructor(agent, func)) else {
                     let constructor = func.map_or(Value::Null, |f| f.into_value().unbind());
-                    let error_message = with_vm_gc(
-                        agent,
-                        vm,
-                        |agent, gc| {
-                            format!(
-                                "'{}' is not a constructor.",
-                                constructor
-                                    .into_value()
-                                    .string_repr(agent, gc)
-                                    .as_str(agent)
-                            )
-                        },
-                        gc.reborrow(),
-                    );
-                    return Err(agent.throw_exception(
+                    let constructor_string = constructor.string_repr(agent, gc.reborrow());
+                    return Err(agent.throw_exception_with_deferred_message(
                         ExceptionType::TypeError,
-                        error_message,
+                        ErrorMessage::NotAConstructor,
+                        [constructor_string.unbind().into()],
                         gc.into_nogc(),
                     ));
                 };
@@ -1824,18 +2014,11 @@ impl Vm {
                     let func = func.unbind();
                     let mut arg_list = arg_list.unbind();
                     let new_target = new_target.unbind();
-                    let result = with_vm_gc(
+                    let result = construct(
                         agent,
-                        vm,
-                        |agent, gc| {
-                            construct(
-                                agent,
-                                func,
-                                Some(ArgumentsList::from_mut_slice(arg_list.as_mut_slice())),
-                                Some(new_target),
-                                gc,
-                            )
-                        },
+                        func,
+                        Some(ArgumentsList::from_mut_slice(arg_list.as_mut_slice())),
+                        Some(new_target),
                         gc.reborrow(),
                     )
                     .unbind()?
@@ -1881,28 +2064,19 @@ impl Vm {
                     .unwrap()
                     .is_strict_mode;
 
-                let property_key =
-                    if property_name_value.is_string() || property_name_value.is_integer() {
-                        unwrap_try(to_property_key_simple(
-                            agent,
-                            property_name_value,
-                            gc.nogc(),
-                        ))
-                    } else {
-                        let scoped_base_value = base_value.scope(agent, gc.nogc());
-                        let property_name_value = property_name_value.unbind();
-                        let property_key = with_vm_gc(
-                            agent,
-                            vm,
-                            |agent, gc| to_property_key(agent, property_name_value, gc),
-                            gc.reborrow(),
-                        )
-                        .unbind()?
-                        .bind(gc.nogc());
-                        // SAFETY: not shared
-                        base_value = unsafe { scoped_base_value.take(agent) }.bind(gc.nogc());
-                        property_key
-                    };
+                let property_key = if let TryResult::Continue(property_key) =
+                    to_property_key_simple(agent, property_name_value, gc.nogc())
+                {
+                    property_key
+                } else {
+                    // Keep the base value rooted on the stack during the call.
+                    vm.stack.push(base_value.unbind());
+                    let property_name_value = property_name_value.unbind();
+                    let property_key =
+                        to_property_key(agent, property_name_value, gc.reborrow()).unbind();
+                    base_value = vm.stack.pop().unwrap().bind(gc.nogc());
+                    property_key?.bind(gc.nogc())
+                };
 
                 vm.reference = Some(Reference {
                     base: Base::Value(base_value.unbind()),
@@ -1928,6 +2102,64 @@ impl Vm {
                     this_value: None,
                 });
             }
+            Instruction::MakeSuperPropertyReferenceWithExpressionKey => {
+                // ### SuperProperty : super [ Expression ]
+                let property_name_value = vm.result.take().unwrap().bind(gc.nogc());
+                // 2. Let actualThis be ? env.GetThisBinding().
+                let mut actual_this = vm.stack.pop().unwrap().bind(gc.nogc());
+                // 5. Let propertyKey be ? ToPropertyKey(propertyNameValue).
+                let property_key = if let TryResult::Continue(property_key) =
+                    to_property_key_simple(agent, property_name_value, gc.nogc())
+                {
+                    property_key
+                } else {
+                    // Keep actualThis rooted on the stack during the call.
+                    vm.stack.push(actual_this.unbind());
+                    let property_name_value = property_name_value.unbind();
+                    let property_key =
+                        to_property_key(agent, property_name_value, gc.reborrow()).unbind();
+                    actual_this = vm.stack.pop().unwrap().bind(gc.nogc());
+                    property_key?.bind(gc.nogc())
+                };
+                // 6. Let strict be IsStrict(this SuperProperty).
+                let strict = agent
+                    .running_execution_context()
+                    .ecmascript_code
+                    .unwrap()
+                    .is_strict_mode;
+                // 7. Return MakeSuperPropertyReference(actualThis, propertyKey, strict).
+                let reference = make_super_property_reference(
+                    agent,
+                    actual_this,
+                    property_key,
+                    strict,
+                    gc.nogc(),
+                );
+                vm.reference = Some(reference.unbind());
+            }
+            Instruction::MakeSuperPropertyReferenceWithIdentifierKey => {
+                // ### SuperProperty : super . IdentifierName
+                let gc = gc.into_nogc();
+                // 2. Let actualThis be ? env.GetThisBinding().
+                let actual_this = vm.result.take().unwrap().bind(gc);
+                // 3. Let propertyKey be the StringValue of IdentifierName.
+                let property_key = executable.fetch_identifier(agent, instr.get_first_index(), gc);
+                // 4. Let strict be IsStrict(this SuperProperty).
+                let strict = agent
+                    .running_execution_context()
+                    .ecmascript_code
+                    .unwrap()
+                    .is_strict_mode;
+                // 5. Return MakeSuperPropertyReference(actualThis, propertyKey, strict).
+                let reference = make_super_property_reference(
+                    agent,
+                    actual_this,
+                    property_key.into(),
+                    strict,
+                    gc,
+                );
+                vm.reference = Some(reference.unbind());
+            }
             Instruction::MakePrivateReference => {
                 let gc = gc.into_nogc();
                 let private_identifier =
@@ -1974,10 +2206,7 @@ impl Vm {
             }
             Instruction::JumpIfTrue => {
                 let result = vm.result.take().unwrap();
-                let Value::Boolean(result) = result else {
-                    unreachable!()
-                };
-                if result {
+                if to_boolean(agent, result) {
                     let ip = instr.get_jump_slot();
                     if agent.options.print_internals {
                         eprintln!("Comparison succeeded, jumping to {ip}");
@@ -1985,6 +2214,54 @@ impl Vm {
                     vm.ip = ip;
                 }
             }
+            Instruction::JumpIfLessThan | Instruction::JumpIfNotLessThan => {
+                let lval = vm.stack.pop().unwrap();
+                let rval = vm.result.take().unwrap();
+                let result = is_less_than::<true>(agent, lval, rval, gc)?;
+                let jump_if = instr.kind == Instruction::JumpIfLessThan;
+                vm.jump_if(agent, instr, (result == Some(true)) == jump_if);
+            }
+            Instruction::JumpIfLessThanEquals | Instruction::JumpIfNotLessThanEquals => {
+                let lval = vm.stack.pop().unwrap();
+                let rval = vm.result.take().unwrap();
+                let result = is_less_than::<false>(agent, rval, lval, gc)?;
+                let jump_if = instr.kind == Instruction::JumpIfLessThanEquals;
+                vm.jump_if(agent, instr, (result == Some(false)) == jump_if);
+            }
+            Instruction::JumpIfGreaterThan | Instruction::JumpIfNotGreaterThan => {
+                let lval = vm.stack.pop().unwrap();
+                let rval = vm.result.take().unwrap();
+                let result = is_less_than::<false>(agent, rval, lval, gc)?;
+                let jump_if = instr.kind == Instruction::JumpIfGreaterThan;
+                vm.jump_if(agent, instr, (result == Some(true)) == jump_if);
+            }
+            Instruction::JumpIfGreaterThanEquals | Instruction::JumpIfNotGreaterThanEquals => {
+                let lval = vm.stack.pop().unwrap();
+                let rval = vm.result.take().unwrap();
+                let result = is_less_than::<true>(agent, lval, rval, gc)?;
+                let jump_if = instr.kind == Instruction::JumpIfGreaterThanEquals;
+                vm.jump_if(agent, instr, (result == Some(false)) == jump_if);
+            }
+            Instruction::JumpIfStrictlyEqual | Instruction::JumpIfNotStrictlyEqual => {
+                let lval = vm.stack.pop().unwrap();
+                let rval = vm.result.take().unwrap();
+                let result = is_strictly_equal(agent, lval, rval);
+                let jump_if = instr.kind == Instruction::JumpIfStrictlyEqual;
+                vm.jump_if(agent, instr, result == jump_if);
+            }
+            Instruction::JumpIfLooselyEqual | Instruction::JumpIfNotLooselyEqual => {
+                let lval = vm.stack.pop().unwrap();
+                let rval = vm.result.take().unwrap();
+                let result = is_loosely_equal(agent, lval, rval, gc)?;
+                let jump_if = instr.kind == Instruction::JumpIfLooselyEqual;
+                vm.jump_if(agent, instr, result == jump_if);
+            }
+            Instruction::JumpIfNullOrUndefined | Instruction::JumpIfNotNullOrUndefined => {
+                let val = vm.result.take().unwrap();
+                let result = val.is_null() || val.is_undefined();
+                let jump_if = instr.kind == Instruction::JumpIfNullOrUndefined;
+                vm.jump_if(agent, instr, result == jump_if);
+            }
             Instruction::Increment => {
                 let lhs = vm.result.take().unwrap().bind(gc.nogc());
                 // Note: This is done by the previous instruction.
@@ -2012,48 +2289,28 @@ impl Vm {
             Instruction::LessThan => {
                 let lval = vm.stack.pop().unwrap();
                 let rval = vm.result.take().unwrap();
-                let result = with_vm_gc(
-                    agent,
-                    vm,
-                    |agent, gc| is_less_than::<true>(agent, lval, rval, gc),
-                    gc,
-                )?;
+                let result = is_less_than::<true>(agent, lval, rval, gc)?;
                 let result = result == Some(true);
                 vm.result = Some(result.into());
             }
             Instruction::LessThanEquals => {
                 let lval = vm.stack.pop().unwrap();
                 let rval = vm.result.take().unwrap();
-                let result = with_vm_gc(
-                    agent,
-                    vm,
-                    |agent, gc| is_less_than::<false>(agent, rval, lval, gc),
-                    gc,
-                )?;
+                let result = is_less_than::<false>(agent, rval, lval, gc)?;
                 let result = result == Some(false);
                 vm.result = Some(result.into());
             }
             Instruction::GreaterThan => {
                 let lval = vm.stack.pop().unwrap();
                 let rval = vm.result.take().unwrap();
-                let result = with_vm_gc(
-                    agent,
-                    vm,
-                    |agent, gc| is_less_than::<false>(agent, rval, lval, gc),
-                    gc,
-                )?;
+                let result = is_less_than::<false>(agent, rval, lval, gc)?;
                 let result = result == Some(true);
                 vm.result = Some(result.into());
             }
             Instruction::GreaterThanEquals => {
                 let lval = vm.stack.pop().unwrap();
                 let rval = vm.result.take().unwrap();
-                let result = with_vm_gc(
-                    agent,
-                    vm,
-                    |agent, gc| is_less_than::<true>(agent, lval, rval, gc),
-                    gc,
-                )?;
+                let result = is_less_than::<true>(agent, lval, rval, gc)?;
                 let result = result == Some(false);
                 vm.result = Some(result.into());
             }
@@ -2073,19 +2330,14 @@ impl Vm {
                 let property_key = if lval.is_string() || lval.is_integer() {
                     unwrap_try(to_property_key_simple(agent, lval, gc.nogc()))
                 } else {
-                    let scoped_rval = rval.scope(agent, gc.nogc());
+                    // Keep rval rooted on the stack during the call.
+                    vm.stack.push(rval.into_value().unbind());
                     let lval = lval.unbind();
-                    let property_key = with_vm_gc(
-                        agent,
-                        vm,
-                        |agent, gc| to_property_key(agent, lval, gc),
-                        gc.reborrow(),
-                    )
-                    .unbind()?
-                    .bind(gc.nogc());
-                    // SAFETY: not shared.
-                    rval = unsafe { scoped_rval.take(agent).bind(gc.nogc()) };
-                    property_key
+                    let property_key = to_property_key(agent, lval, gc.reborrow()).unbind();
+                    rval = Object::try_from(vm.stack.pop().unwrap())
+                        .unwrap()
+                        .bind(gc.nogc());
+                    property_key?.bind(gc.nogc())
                 };
                 let result = if let TryResult::Continue(result) =
                     try_has_property(agent, rval, property_key, gc.nogc())
@@ -2094,12 +2346,7 @@ impl Vm {
                 } else {
                     let rval = rval.unbind();
                     let property_key = property_key.unbind();
-                    with_vm_gc(
-                        agent,
-                        vm,
-                        |agent, gc| has_property(agent, rval, property_key, gc),
-                        gc,
-                    )?
+                    has_property(agent, rval, property_key, gc)?
                 };
                 vm.result = Some(result.into());
             }
@@ -2138,12 +2385,7 @@ impl Vm {
             Instruction::IsLooselyEqual => {
                 let lval = vm.stack.pop().unwrap();
                 let rval = vm.result.take().unwrap();
-                let result = with_vm_gc(
-                    agent,
-                    vm,
-                    |agent, gc| is_loosely_equal(agent, lval, rval, gc),
-                    gc,
-                )?;
+                let result = is_loosely_equal(agent, lval, rval, gc)?;
                 vm.result = Some(result.into());
             }
             Instruction::IsNullOrUndefined => {
@@ -2186,11 +2428,15 @@ impl Vm {
             Instruction::InitializeReferencedBinding => {
                 let v = vm.reference.take().unwrap();
                 let w = vm.result.take().unwrap();
-                // Note: https://tc39.es/ecma262/#sec-initializereferencedbinding
-                // suggests this cannot call user code, hence NoGC.
-                unwrap_try(try_initialize_referenced_binding(agent, v, w, gc.nogc()))
-                    .unbind()?
-                    .bind(gc.nogc());
+                if let Base::FrameSlot(slot) = v.base {
+                    vm.frame_slots[slot as usize] = Some(w);
+                } else {
+                    // Note: https://tc39.es/ecma262/#sec-initializereferencedbinding
+                    // suggests this cannot call user code, hence NoGC.
+                    unwrap_try(try_initialize_referenced_binding(agent, v, w, gc.nogc()))
+                        .unbind()?
+                        .bind(gc.nogc());
+                }
             }
             Instruction::InitializeVariableEnvironment => {
                 let num_variables = instr.get_first_index();
@@ -2323,12 +2569,7 @@ impl Vm {
             Instruction::InstanceofOperator => {
                 let lval = vm.stack.pop().unwrap();
                 let rval = vm.result.take().unwrap();
-                let result = with_vm_gc(
-                    agent,
-                    vm,
-                    |agent, gc| instanceof_operator(agent, lval, rval, gc),
-                    gc,
-                )?;
+                let result = instanceof_operator(agent, lval, rval, gc)?;
                 vm.result = Some(result.into());
             }
             Instruction::BeginSimpleArrayBindingPattern => {
@@ -2397,43 +2638,31 @@ impl Vm {
                     // SAFETY: String is a sub-enum of Value and we've written
                     // a String into each of the args.
                     let args = unsafe { std::mem::transmute::<&[Value<'_>], &[String<'_>]>(args) };
+                    agent.check_string_length(length, gc).unbind()?;
                     concat_string_from_slice(agent, args, length, gc)
                 } else {
-                    let mut args = vm
-                        .stack
-                        .split_off(first_arg_index)
-                        .iter_mut()
-                        .map(|v| v.scope(agent, gc.nogc()))
-                        .collect::<Vec<_>>();
-                    with_vm_gc::<JsResult<()>>(
-                        agent,
-                        vm,
-                        |agent, mut gc| {
-                            for ele in args.iter_mut() {
-                                let maybe_string = ele.get(agent).bind(gc.nogc());
-                                if maybe_string.is_string() {
-                                    continue;
-                                }
-                                let string = to_string(agent, maybe_string.unbind(), gc.reborrow())
-                                    .unbind()?
-                                    .bind(gc.nogc());
-                                length += string.len(agent);
-                                let string = string.into_value();
-                                // SAFETY: args are never shared
-                                unsafe { ele.replace(agent, string.unbind()) };
-                            }
-                            Ok(())
-                        },
-                        gc.reborrow(),
-                    )
-                    .unbind()?
-                    .bind(gc.nogc());
+                    // The arguments stay on the operand stack while they are
+                    // converted: the stack is rooted, so they survive any
+                    // garbage collection triggered by ToString.
+                    for index in first_arg_index..vm.stack.len() {
+                        let arg = vm.stack[index].bind(gc.nogc());
+                        if let Ok(string) = String::try_from(arg) {
+                            length += string.len(agent);
+                            continue;
+                        }
+                        let string = to_string(agent, arg.unbind(), gc.reborrow())
+                            .unbind()?
+                            .bind(gc.nogc());
+                        length += string.len(agent);
+                        vm.stack[index] = string.into_value().unbind();
+                    }
                     let gc = gc.nogc();
-                    let args = args
-                        .into_iter()
-                        .map(|v| String::try_from(v.get(agent)).unwrap().bind(gc))
-                        .collect::<Vec<_>>();
-                    concat_string_from_slice(agent, &args, length, gc)
+                    agent.check_string_length(length, gc).unbind()?;
+                    let args = &vm.stack[first_arg_index..];
+                    // SAFETY: String is a sub-enum of Value and we've written
+                    // a String into each of the args.
+                    let args = unsafe { std::mem::transmute::<&[Value<'_>], &[String<'_>]>(args) };
+                    concat_string_from_slice(agent, args, length, gc)
                 };
                 vm.stack.truncate(first_arg_index);
                 vm.result = Some(string.into_value().unbind());
@@ -2454,11 +2683,10 @@ impl Vm {
                         debug_assert!(!is_private_reference(&refer));
                         // b. If IsSuperReference(ref) is true, throw a ReferenceError exception.
                         if is_super_reference(&refer) {
-                            return Err(agent.throw_exception_with_static_message(
-                                ExceptionType::ReferenceError,
+                            return agent.reference_error(
                                 "Invalid delete involving 'super'.",
                                 gc.into_nogc(),
-                            ));
+                            );
                         }
                         // c. Let baseObj be ? ToObject(ref.[[Base]]).
                         let base_obj = to_object(agent, base, gc.nogc()).unbind()?.bind(gc.nogc());
@@ -2474,22 +2702,14 @@ impl Vm {
                         } else {
                             let base_obj = base_obj.unbind();
                             let referenced_name = refer.referenced_name.unbind();
-                            with_vm_gc(
-                                agent,
-                                vm,
-                                |agent, gc| base_obj.internal_delete(agent, referenced_name, gc),
-                                gc.reborrow(),
-                            )
-                            .unbind()?
-                            .bind(gc.nogc())
+                            base_obj
+                                .internal_delete(agent, referenced_name, gc.reborrow())
+                                .unbind()?
+                                .bind(gc.nogc())
                         };
                         // f. If deleteStatus is false and ref.[[Strict]] is true, throw a TypeError exception.
                         if !delete_status && strict {
-                            return Err(agent.throw_exception_with_static_message(
-                                ExceptionType::TypeError,
-                                "Cannot delete property",
-                                gc.into_nogc(),
-                            ));
+                            return agent.type_error("Cannot delete property", gc.into_nogc());
                         }
                         // g. Return deleteStatus.
                         vm.result = Some(delete_status.into());
@@ -2511,15 +2731,15 @@ impl Vm {
                         } else {
                             let referenced_name = referenced_name.unbind();
                             let base = base.unbind();
-                            with_vm_gc(
-                                agent,
-                                vm,
-                                |agent, gc| base.delete_binding(agent, referenced_name, gc),
-                                gc,
-                            )?
+                            base.delete_binding(agent, referenced_name, gc)?
                         };
                         vm.result = Some(result.into());
                     }
+                    Base::FrameSlot(_) => {
+                        // Frame slots hold declarative bindings, whose
+                        // DeleteBinding() returns false.
+                        vm.result = Some(false.into());
+                    }
                 }
 
                 // Note 1
@@ -2543,40 +2763,26 @@ impl Vm {
                 let object = to_object(agent, vm.result.take().unwrap(), gc.nogc()).unwrap();
                 vm.iterator_stack.push(
                     VmIteratorRecord::ObjectProperties(Box::new(
-                        ObjectPropertiesIteratorRecord::new(object),
+                        ObjectPropertiesIteratorRecord::new(agent, object, gc.nogc()),
                     ))
                     .unbind(),
                 )
             }
             Instruction::GetIteratorSync => {
                 let expr_value = vm.result.take().unwrap();
-                let result = with_vm_gc(
-                    agent,
-                    vm,
-                    |agent, gc| VmIteratorRecord::from_value(agent, expr_value, gc),
-                    gc,
-                )?;
+                let result = VmIteratorRecord::from_value(agent, expr_value, gc)?;
                 vm.iterator_stack.push(result);
             }
             Instruction::GetIteratorAsync => {
                 let expr_value = vm.result.take().unwrap();
-                let result = with_vm_gc(
-                    agent,
-                    vm,
-                    |agent, gc| VmIteratorRecord::async_from_value(agent, expr_value, gc),
-                    gc,
-                )?;
+                let result = VmIteratorRecord::async_from_value(agent, expr_value, gc)?;
                 vm.iterator_stack.push(result);
             }
             Instruction::IteratorStepValue => {
-                let result = with_vm_gc(
-                    agent,
-                    vm,
-                    |agent, gc| ActiveIterator::new(agent, gc.nogc()).step_value(agent, gc),
-                    gc.reborrow(),
-                )
-                .unbind()
-                .bind(gc.nogc());
+                let result = ActiveIterator::new(agent, gc.nogc())
+                    .step_value(agent, gc.reborrow())
+                    .unbind()
+                    .bind(gc.nogc());
                 match result {
                     Ok(result) => {
                         vm.result = result.unbind();
@@ -2600,12 +2806,7 @@ impl Vm {
                 }
             }
             Instruction::IteratorStepValueOrUndefined => {
-                let result = with_vm_gc(
-                    agent,
-                    vm,
-                    |agent, gc| ActiveIterator::new(agent, gc.nogc()).step_value(agent, gc),
-                    gc,
-                );
+                let result = ActiveIterator::new(agent, gc.nogc()).step_value(agent, gc);
                 if result.map_or(true, |r| r.is_none()) {
                     // We have exhausted the iterator or it threw an error;
                     // replace the top iterator with an empty slice iterator so
@@ -2615,48 +2816,13 @@ impl Vm {
                 vm.result = Some(result?.unwrap_or(Value::Undefined).unbind());
             }
             Instruction::IteratorRestIntoArray => {
-                let capacity = vm
-                    .get_active_iterator()
-                    .remaining_length_estimate(agent)
-                    .unwrap_or(0);
-                let array = array_create(agent, 0, capacity, None, gc.nogc())
-                    .unbind()?
-                    .scope(agent, gc.nogc());
-
-                let result = with_vm_gc::<JsResult<()>>(
-                    agent,
-                    vm,
-                    |agent, mut gc| {
-                        let mut idx: u32 = 0;
-                        while let Some(value) = ActiveIterator::new(agent, gc.nogc())
-                            .step_value(agent, gc.reborrow())
-                            .unbind()?
-                            .bind(gc.nogc())
-                        {
-                            let key = PropertyKey::Integer(idx.into());
-                            unwrap_try(try_create_data_property(
-                                agent,
-                                array.get(agent),
-                                key,
-                                value.unbind(),
-                                gc.nogc(),
-                            ));
-                            idx += 1;
-                        }
-                        Ok(())
-                    },
-                    gc,
-                );
+                let result = collect_rest_into_array(agent, gc);
                 // We have exhausted the iterator or it threw an error; replace
                 // the top iterator with an empty slice iterator so further
                 // instructions aren't observable.
                 *vm.get_active_iterator_mut() = VmIteratorRecord::EmptySliceIterator;
-                // Now we're ready to throw the possible error.;
-                result?;
-
-                // Store the array as the result.
-                // SAFETY: not shared.
-                vm.result = Some(unsafe { array.take(agent).into_value() });
+                // Now we're ready to throw the possible error.
+                vm.result = Some(result?.unbind());
             }
             Instruction::IteratorClose => {
                 let iter = vm.pop_iterator(gc.nogc());
@@ -2666,12 +2832,7 @@ impl Vm {
                         let iterator = iterator_record.iterator.unbind();
                         // Drop iterator so we can move gc.
                         drop(iter);
-                        with_vm_gc(
-                            agent,
-                            vm,
-                            |agent, gc| iterator_close_with_value(agent, iterator, result, gc),
-                            gc,
-                        )?
+                        iterator_close_with_value(agent, iterator, result, gc)?
                     };
                     vm.result = Some(result.unbind());
                 }
@@ -2685,12 +2846,7 @@ impl Vm {
                         let iterator = iterator_record.iterator.unbind();
                         // Drop iterator so we can move gc.
                         drop(iter);
-                        with_vm_gc(
-                            agent,
-                            vm,
-                            |agent, gc| async_iterator_close_with_value(agent, iterator, gc),
-                            gc,
-                        )?
+                        async_iterator_close_with_value(agent, iterator, gc)?
                     };
                     if let Some(result) = result {
                         // AsyncIteratorClose
@@ -2709,14 +2865,9 @@ impl Vm {
                     let iterator = iterator_record.iterator.unbind();
                     // Drop iterator so we can move gc.
                     drop(iter);
-                    return Err(with_vm_gc(
-                        agent,
-                        vm,
-                        |agent, gc| {
-                            iterator_close_with_error(agent, iterator, JsError::new(result), gc)
-                        },
-                        gc,
-                    ));
+                    return Err({
+                        iterator_close_with_error(agent, iterator, JsError::new(result), gc)
+                    });
                 }
                 return Err(JsError::new(vm.result.take().unwrap()));
             }
@@ -2729,12 +2880,7 @@ impl Vm {
                         let iterator = iterator_record.iterator.unbind();
                         // Drop iterator so we can move gc.
                         drop(iter);
-                        with_vm_gc(
-                            agent,
-                            vm,
-                            |agent, gc| async_vm_iterator_close_with_error(agent, iterator, gc),
-                            gc,
-                        )
+                        async_vm_iterator_close_with_error(agent, iterator, gc)
                     };
                     if let Some(value) = inner_result_value {
                         // ### 7.4.13 AsyncIteratorClose
@@ -2745,7 +2891,8 @@ impl Vm {
                         // We need to await the value and ignore any errors it
                         // might throw, then rethrow the error. First, we need
                         // to load the error to the stack for later throwing.
-                        vm.stack.push(vm.result.take().unwrap());
+                        let result = vm.result.take().unwrap();
+                        vm.stack.push(result);
                         // Then we can put our value as the result.
                         vm.result = Some(value.unbind());
                         // Before we await we need to make sure that any error
@@ -2816,7 +2963,12 @@ impl Vm {
         Ok(ContinuationKind::Normal)
     }
 
-    fn get_call_args<'gc>(&mut self, instr: &Instr, _gc: NoGcScope<'gc, '_>) -> Vec<Value<'gc>> {
+    /// Pop the arguments of a call off the stack.
+    ///
+    /// The arguments are not copied out: they stay in place just past the end
+    /// of the stack and are passed on to the callee from there. The stack must
+    /// not be pushed to while the returned CallArgs is in use.
+    fn pop_call_args<'gc>(&mut self, instr: &Instr, _gc: NoGcScope<'gc, '_>) -> CallArgs<'gc> {
         let instr_arg0 = instr.get_first_arg();
         let arg_count = if instr_arg0 != IndexType::MAX {
             instr_arg0 as usize
@@ -2829,7 +2981,18 @@ impl Vm {
         };
 
         assert!(self.stack.len() >= arg_count);
-        self.stack.split_off(self.stack.len() - arg_count)
+        let start = self.stack.len() - arg_count;
+        // SAFETY: The arguments are within the stack's length, so the pointer
+        // is in bounds of its allocation.
+        let ptr = unsafe { NonNull::new_unchecked(self.stack.as_mut_ptr().add(start)) };
+        // SAFETY: This only shortens the stack. Values are Copy, so the
+        // arguments left past its end stay valid until they are overwritten.
+        unsafe { self.stack.set_len(start) };
+        CallArgs {
+            ptr,
+            len: arg_count,
+            value: PhantomData,
+        }
     }
 
     /// Pop the active (top-most) iterator from the iterator stack.
@@ -2871,11 +3034,11 @@ fn concat_string_from_slice<'gc>(
     string_length: usize,
     gc: NoGcScope<'gc, '_>,
 ) -> String<'gc> {
-    let mut result_string = std::string::String::with_capacity(string_length);
+    let mut result_string = HeapStringBuilder::with_capacity(string_length);
     for string in slice.iter() {
-        result_string.push_str(string.as_str(agent));
+        result_string.push_string(agent, *string);
     }
-    String::from_string(agent, result_string, gc)
+    result_string.finish(agent, gc)
 }
 
 /// ### [13.15.3 ApplyStringOrNumericBinaryOperator ( lval, opText, rval )](https://tc39.es/ecma262/#sec-applystringornumericbinaryoperator)
@@ -2915,11 +3078,10 @@ fn apply_string_or_numeric_binary_operator<'gc>(
         number_binary_operator(agent, op_text, lnum, rnum, gc).map(|v| v.into_value())
     } else {
         // 5. If Type(lnum) is not Type(rnum), throw a TypeError exception.
-        Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
+        agent.type_error(
             "The left and right-hand sides do not have the same type.",
             gc,
-        ))
+        )
     }
 }
 
@@ -2982,21 +3144,25 @@ fn apply_string_or_numeric_addition<'gc>(
     match (String::try_from(lprim), String::try_from(rprim)) {
         (Ok(lstr), Ok(rstr)) => {
             // iii. Return the string-concatenation of lstr and rstr.
-            return Ok(String::concat(agent, [lstr, rstr], gc).into_value());
+            return Ok(String::concat_or_throw(agent, [lstr, rstr], gc)?.into_value());
         }
         (Ok(lstr), Err(_)) => {
             let lstr = lstr.scope(agent, gc);
             // ii. Let rstr be ? ToString(rprim).
             let rstr = to_string_primitive(agent, rprim, gc)?;
             // iii. Return the string-concatenation of lstr and rstr.
-            return Ok(String::concat(agent, [lstr.get(agent).bind(gc), rstr], gc).into_value());
+            return Ok(
+                String::concat_or_throw(agent, [lstr.get(agent).bind(gc), rstr], gc)?.into_value(),
+            );
         }
         (Err(_), Ok(rstr)) => {
             let rstr = rstr.scope(agent, gc);
             // i. Let lstr be ? ToString(lprim).
             let lstr = to_string_primitive(agent, lprim, gc)?;
             // iii. Return the string-concatenation of lstr and rstr.
-            return Ok(String::concat(agent, [lstr, rstr.get(agent).bind(gc)], gc).into_value());
+            return Ok(
+                String::concat_or_throw(agent, [lstr, rstr.get(agent).bind(gc)], gc)?.into_value(),
+            );
         }
         (Err(_), Err(_)) => {}
     }
@@ -3018,11 +3184,10 @@ fn apply_string_or_numeric_addition<'gc>(
             .map(|v| v.into_value())
     } else {
         // 5. If Type(lnum) is not Type(rnum), throw a TypeError exception.
-        Err(agent.throw_exception_with_static_message(
-            ExceptionType::TypeError,
+        agent.type_error(
             "The left and right-hand sides do not have the same type.",
             gc,
-        ))
+        )
     }
 }
 
@@ -3105,88 +3270,28 @@ fn number_binary_operator<'a>(
 
 /// ### [13.5.3 The typeof operator](https://tc39.es/ecma262/#sec-typeof-operator)
 #[inline]
-fn typeof_operator(agent: &Agent, val: Value, gc: NoGcScope) -> String<'static> {
-    match val {
+fn typeof_operator(agent: &Agent, val: Value) -> String<'static> {
+    match val.type_of(agent) {
         // 4. If val is undefined, return "undefined".
-        Value::Undefined => BUILTIN_STRING_MEMORY.undefined,
-        // 8. If val is a Boolean, return "boolean".
-        Value::Boolean(_) => BUILTIN_STRING_MEMORY.boolean,
+        JsType::Undefined => BUILTIN_STRING_MEMORY.undefined,
+        // 5. If val is null, return "object".
+        JsType::Null => BUILTIN_STRING_MEMORY.object,
         // 6. If val is a String, return "string".
-        Value::String(_) |
-        Value::SmallString(_) => BUILTIN_STRING_MEMORY.string,
+        JsType::String => BUILTIN_STRING_MEMORY.string,
         // 7. If val is a Symbol, return "symbol".
-        Value::Symbol(_) => BUILTIN_STRING_MEMORY.symbol,
+        JsType::Symbol => BUILTIN_STRING_MEMORY.symbol,
+        // 8. If val is a Boolean, return "boolean".
+        JsType::Boolean => BUILTIN_STRING_MEMORY.boolean,
         // 9. If val is a Number, return "number".
-        Value::Number(_) |
-        Value::Integer(_) |
-        Value::SmallF64(_) => BUILTIN_STRING_MEMORY.number,
+        JsType::Number => BUILTIN_STRING_MEMORY.number,
         // 10. If val is a BigInt, return "bigint".
-        Value::BigInt(_) |
-        Value::SmallBigInt(_) => BUILTIN_STRING_MEMORY.bigint,
-        // 5. If val is null, return "object".
-        Value::Null |
+        JsType::BigInt => BUILTIN_STRING_MEMORY.bigint,
         // 11. Assert: val is an Object.
         // 12. NOTE: This step is replaced in section B.3.6.3.
-        Value::Object(_)  |
-        Value::Array(_)  |
-        Value::Error(_)  |
-        // 14. Return "object".
-        Value::PrimitiveObject(_) |
-        Value::Arguments(_) |
-        Value::FinalizationRegistry(_) |
-        Value::Map(_) |
-        Value::Promise(_) |
-        Value::AsyncFromSyncIterator |
-        Value::AsyncGenerator(_) |
-        Value::ArrayIterator(_) |
-        Value::MapIterator(_) |
-        Value::StringIterator(_) |
-        Value::Generator(_) |
-        Value::Module(_) |
-        Value::EmbedderObject(_) => BUILTIN_STRING_MEMORY.object,
-        #[cfg(feature = "regexp")]
-        Value::RegExp(_) => BUILTIN_STRING_MEMORY.object,
-        #[cfg(feature = "weak-refs")]
-        Value::WeakMap(_) |
-        Value::WeakRef(_) |
-        Value::WeakSet(_)  => BUILTIN_STRING_MEMORY.object,
-        #[cfg(feature = "set")]
-        Value::Set(_) |
-        Value::SetIterator(_) => BUILTIN_STRING_MEMORY.object,
-        #[cfg(feature = "shared-array-buffer")]
-        Value::SharedArrayBuffer(_) => BUILTIN_STRING_MEMORY.object,
-        #[cfg(feature = "array-buffer")]
-        Value::ArrayBuffer(_) |
-        Value::Int8Array(_) |
-        Value::Uint8Array(_) |
-        Value::Uint8ClampedArray(_) |
-        Value::Int16Array(_) |
-        Value::Uint16Array(_) |
-        Value::Int32Array(_) |
-        Value::Uint32Array(_) |
-        Value::BigInt64Array(_) |
-        Value::BigUint64Array(_) |
-        Value::Float32Array(_) |
-        Value::Float64Array(_) |
-        Value::DataView(_) => BUILTIN_STRING_MEMORY.object,
-        #[cfg(feature = "proposal-float16array")]
-        Value::Float16Array(_) => BUILTIN_STRING_MEMORY.object,
-        #[cfg(feature = "date")]
-        Value::Date(_)  => BUILTIN_STRING_MEMORY.object,
         // 13. If val has a [[Call]] internal slot, return "function".
-        Value::BoundFunction(_) | Value::BuiltinFunction(_) | Value::ECMAScriptFunction(_) |
-        Value::BuiltinGeneratorFunction |
-        Value::BuiltinConstructorFunction(_) |
-        Value::BuiltinPromiseResolvingFunction(_) |
-        Value::BuiltinPromiseCollectorFunction |
-        Value::BuiltinProxyRevokerFunction => BUILTIN_STRING_MEMORY.function,
-        Value::Proxy(proxy) => {
-            if proxy.is_callable(agent, gc) {
-                BUILTIN_STRING_MEMORY.function
-            } else {
-                BUILTIN_STRING_MEMORY.object
-            }
-        },
+        JsType::Function => BUILTIN_STRING_MEMORY.function,
+        // 14. Return "object".
+        JsType::Object => BUILTIN_STRING_MEMORY.object,
     }
 }
 
@@ -3218,7 +3323,7 @@ pub(crate) fn instanceof_operator<'a, 'b>(
             target
                 .into_value()
                 .string_repr(agent, gc.reborrow())
-                .as_str(agent)
+                .to_string_lossy(agent)
         );
         return Err(agent.throw_exception(ExceptionType::TypeError, error_message, gc.into_nogc()));
     };
@@ -3254,7 +3359,7 @@ pub(crate) fn instanceof_operator<'a, 'b>(
                 target
                     .into_value()
                     .string_repr(agent, gc.reborrow())
-                    .as_str(agent)
+                    .to_string_lossy(agent)
             );
             return Err(agent.throw_exception(
                 ExceptionType::TypeError,
@@ -3267,18 +3372,51 @@ pub(crate) fn instanceof_operator<'a, 'b>(
     }
 }
 
-fn with_vm_gc<'a, 'b, R: 'a>(
-    agent: &mut Agent,
-    vm: &mut Vm,
-    work: impl FnOnce(&mut Agent, GcScope<'a, 'b>) -> R,
-    gc: GcScope<'a, 'b>,
-) -> R {
-    let vm = NonNull::from(vm);
-    agent.vm_stack.push(vm);
-    let result = work(agent, gc);
-    let return_vm = agent.vm_stack.pop().unwrap();
-    assert_eq!(vm, return_vm, "VM Stack was misused");
-    result
+/// Handle to a running [`Vm`], registered as a garbage collection root in the
+/// Agent's VM stack for as long as the handle lives.
+///
+/// The garbage collector marks and sweeps the operand stack, frame slots and
+/// other temporaries of every running Vm in place, so Values held there need
+/// no scoping of their own across calls that may collect garbage. In exchange,
+/// no Rust reference into the Vm may be held across such a call: the Vm is only
+/// accessed through borrows of the handle that end before the call.
+struct VmRef<'a> {
+    vm: NonNull<Vm>,
+    _vm: PhantomData<&'a mut Vm>,
+}
+
+impl<'a> VmRef<'a> {
+    fn register(agent: &mut Agent, vm: &'a mut Vm) -> Self {
+        let vm = NonNull::from(vm);
+        agent.vm_stack.push(vm);
+        Self {
+            vm,
+            _vm: PhantomData,
+        }
+    }
+
+    fn unregister(self, agent: &mut Agent) {
+        let vm = agent.vm_stack.pop();
+        assert_eq!(vm, Some(self.vm), "VM Stack was misused");
+    }
+}
+
+impl core::ops::Deref for VmRef<'_> {
+    type Target = Vm;
+
+    fn deref(&self) -> &Vm {
+        // SAFETY: The Vm is exclusively borrowed for the lifetime of the
+        // handle, and the garbage collector only accesses it while no borrow
+        // of the handle is live.
+        unsafe { self.vm.as_ref() }
+    }
+}
+
+impl core::ops::DerefMut for VmRef<'_> {
+    fn deref_mut(&mut self) -> &mut Vm {
+        // SAFETY: See Deref.
+        unsafe { self.vm.as_mut() }
+    }
 }
 
 impl HeapMarkAndSweep for ExceptionHandler<'static> {
@@ -3317,6 +3455,7 @@ impl HeapMarkAndSweep for Vm {
             exception_handler_stack: exception_jump_target_stack,
             result,
             reference,
+            frame_slots,
         } = self;
         stack.as_slice().mark_values(queues);
         reference_stack.as_slice().mark_values(queues);
@@ -3324,6 +3463,7 @@ impl HeapMarkAndSweep for Vm {
         exception_jump_target_stack.as_slice().mark_values(queues);
         result.mark_values(queues);
         reference.mark_values(queues);
+        frame_slots.as_slice().mark_values(queues);
     }
 
     fn sweep_values(&mut self, compactions: &CompactionLists) {
@@ -3335,6 +3475,7 @@ impl HeapMarkAndSweep for Vm {
             exception_handler_stack: exception_jump_target_stack,
             result,
             reference,
+            frame_slots,
         } = self;
         stack.as_mut_slice().sweep_values(compactions);
         reference_stack.as_mut_slice().sweep_values(compactions);
@@ -3344,6 +3485,7 @@ impl HeapMarkAndSweep for Vm {
             .sweep_values(compactions);
         result.sweep_values(compactions);
         reference.sweep_values(compactions);
+        frame_slots.as_mut_slice().sweep_values(compactions);
     }
 }
 
@@ -3381,7 +3523,6 @@ impl HeapMarkAndSweep for SuspendedVm {
 /// function name and returns it.
 fn set_class_name<'a>(
     agent: &mut Agent,
-    vm: &mut Vm,
     name: Value,
     mut gc: GcScope<'a, '_>,
 ) -> JsResult<'a, String<'a>> {
@@ -3403,18 +3544,13 @@ fn set_class_name<'a>(
         // 3. Return ? ToPropertyKey(propName).
         let prop_key = {
             let name = name.unbind();
-            with_vm_gc(
-                agent,
-                vm,
-                |agent, gc| to_property_key(agent, name, gc),
-                gc.reborrow(),
-            )
-            .unbind()?
-            .bind(gc.nogc())
+            to_property_key(agent, name, gc.reborrow())
+                .unbind()?
+                .bind(gc.nogc())
         };
 
         let name = prop_key.convert_to_value(agent, gc.nogc());
-        set_class_name(agent, vm, name.into_value().unbind(), gc)
+        set_class_name(agent, name.into_value().unbind(), gc)
     }
 }
 
@@ -3423,9 +3559,151 @@ fn throw_error_in_target_not_object<'a>(
     value: Value,
     gc: NoGcScope<'a, '_>,
 ) -> JsError<'a> {
-    let error_message = format!(
-        "right-hand side of 'in' should be an object, got {}.",
-        typeof_operator(agent, value, gc).as_str(agent)
-    );
-    agent.throw_exception(ExceptionType::TypeError, error_message, gc)
+    let type_string = typeof_operator(agent, value);
+    agent.throw_exception_with_deferred_message(
+        ExceptionType::TypeError,
+        ErrorMessage::InTargetNotObject,
+        [type_string.into()],
+        gc,
+    )
+}
+
+/// ### [13.2.8.4 GetTemplateObject ( templateLiteral )](https://tc39.es/ecma262/#sec-gettemplateobject)
+///
+/// The abstract operation GetTemplateObject takes argument templateLiteral (a
+/// Parse Node) and returns an Array.
+fn get_template_object<'a>(
+    agent: &mut Agent,
+    template_literal: &'static ast::TemplateLiteral<'static>,
+    mut gc: GcScope<'a, '_>,
+) -> JsResult<'a, Array<'a>> {
+    // 1. Let realm be the current Realm Record.
+    let realm = agent.current_realm_record();
+    // 2. Let templateRegistry be realm.[[TemplateMap]].
+    // 3. For each element e of templateRegistry, do
+    //    a. If e.[[Site]] is the same Parse Node as templateLiteral, then
+    //       i. Return e.[[Array]].
+    let site = template_literal as *const ast::TemplateLiteral<'static>;
+    if let Some((_, template)) = realm.template_map.get(&site) {
+        return Ok(template.bind(gc.into_nogc()));
+    }
+    // 4. Let rawStrings be the TemplateStrings of templateLiteral with
+    //    argument true.
+    // 5. Assert: rawStrings is a List of Strings.
+    // 6. Let cookedStrings be the TemplateStrings of templateLiteral with
+    //    argument false.
+    let mut raw_strings = Vec::with_capacity(template_literal.quasis.len());
+    let mut cooked_strings = Vec::with_capacity(template_literal.quasis.len());
+    for quasi in template_literal.quasis.iter() {
+        let raw = quasi.value.raw.as_str();
+        let raw = if raw.contains('\r') {
+            String::from_string(
+                agent,
+                raw.replace("\r\n", "\n").replace('\r', "\n"),
+                gc.nogc(),
+            )
+        } else {
+            String::from_str(agent, raw, gc.nogc())
+        };
+        raw_strings.push(raw.into_value());
+        // NOTE: The TV of a template with an invalid escape sequence is
+        // undefined.
+        cooked_strings.push(
+            quasi
+                .value
+                .cooked
+                .as_ref()
+                .map_or(Value::Undefined, |cooked| {
+                    String::from_str(agent, cooked.as_str(), gc.nogc()).into_value()
+                }),
+        );
+    }
+    // 7. Let count be the number of elements in the List rawStrings.
+    // 8. Let template be ! ArrayCreate(count).
+    // 9. Let rawObj be ! ArrayCreate(count).
+    // 10. Let index be 0.
+    // 11. Repeat, while index < count,
+    //     ...
+    //     e. Perform ! DefinePropertyOrThrow(template, prop, PropertyDescriptor { [[Value]]: cookedValue, [[Writable]]: false, [[Enumerable]]: true, [[Configurable]]: false }).
+    //     ...
+    //     g. Perform ! DefinePropertyOrThrow(rawObj, prop, PropertyDescriptor { [[Value]]: rawValue, [[Writable]]: false, [[Enumerable]]: true, [[Configurable]]: false }).
+    // NOTE: The elements are made non-writable and non-configurable when the
+    // arrays are frozen below.
+    let template =
+        create_array_from_list(agent, &cooked_strings, gc.nogc()).scope(agent, gc.nogc());
+    let raw_obj = create_array_from_list(agent, &raw_strings, gc.nogc());
+    drop(cooked_strings);
+    drop(raw_strings);
+    let scoped_raw_obj = raw_obj.scope(agent, gc.nogc());
+    // 12. Perform ! SetIntegrityLevel(rawObj, frozen).
+    set_integrity_level::<Frozen>(agent, raw_obj.into_object().unbind(), gc.reborrow()).unbind()?;
+    // 13. Perform ! DefinePropertyOrThrow(template, "raw", PropertyDescriptor { [[Value]]: rawObj, [[Writable]]: false, [[Enumerable]]: false, [[Configurable]]: false }).
+    unwrap_try(try_define_property_or_throw(
+        agent,
+        template.get(agent).into_object(),
+        BUILTIN_STRING_MEMORY.raw.into(),
+        PropertyDescriptor {
+            value: Some(scoped_raw_obj.get(agent).into_value()),
+            writable: Some(false),
+            enumerable: Some(false),
+            configurable: Some(false),
+            ..Default::default()
+        },
+        gc.nogc(),
+    ))
+    .unbind()?;
+    // 14. Perform ! SetIntegrityLevel(template, frozen).
+    set_integrity_level::<Frozen>(agent, template.get(agent).into_object(), gc.reborrow())
+        .unbind()?;
+    // 15. Append the Record { [[Site]]: templateLiteral, [[Array]]: template }
+    //     to realm.[[TemplateMap]].
+    let gc = gc.into_nogc();
+    let template = template.get(agent).bind(gc);
+    let source_code = agent.current_source_code(gc);
+    agent
+        .current_realm_record_mut()
+        .template_map
+        .insert(site, (source_code.unbind(), template.unbind()));
+    // 16. Return template.
+    Ok(template)
+}
+
+/// ### [13.3.7.3 MakeSuperPropertyReference ( actualThis, propertyKey, strict )](https://tc39.es/ecma262/#sec-makesuperpropertyreference)
+///
+/// The abstract operation MakeSuperPropertyReference takes arguments
+/// actualThis (an ECMAScript language value), propertyKey (an ECMAScript
+/// language value), and strict (a Boolean) and returns a Super Reference
+/// Record.
+fn make_super_property_reference<'a>(
+    agent: &mut Agent,
+    actual_this: Value,
+    property_key: PropertyKey,
+    strict: bool,
+    gc: NoGcScope<'a, '_>,
+) -> Reference<'a> {
+    // 1. Let env be GetThisEnvironment().
+    let Environment::Function(env) = get_this_environment(agent, gc) else {
+        unreachable!("super property reference outside of a method");
+    };
+    // 2. Assert: env.HasSuperBinding() is true.
+    debug_assert!(env.has_super_binding(agent));
+    // 3. Let baseValue be env.GetSuperBase().
+    let base_value = env
+        .get_super_base(agent, gc)
+        .map_or(Value::Undefined, |base| {
+            base.map_or(Value::Null, |base| base.into_value())
+        });
+    // 4. Return the Reference Record {
+    //    [[Base]]: baseValue,
+    //    [[ReferencedName]]: propertyKey,
+    //    [[Strict]]: strict,
+    //    [[ThisValue]]: actualThis
+    // }.
+    Reference {
+        base: Base::Value(base_value.unbind()),
+        referenced_name: property_key.unbind(),
+        strict,
+        this_value: Some(actual_this.unbind()),
+    }
+    .bind(gc)
 }
@@ -14,7 +14,7 @@ use crate::{
     engine::{
         ScopableCollection, Scoped,
         bytecode::vm::{
-            Environment, Executable, Instruction, Vm, VmIteratorRecord, array_create,
+            Environment, Executable, Instruction, VmIteratorRecord, array_create,
             copy_data_properties_into_object, initialize_referenced_binding, put_value,
             resolve_binding, to_object,
         },
@@ -25,11 +25,11 @@ use crate::{
     },
 };
 
-use super::with_vm_gc;
+use super::VmRef;
 
 pub(super) fn execute_simple_array_binding<'a>(
     agent: &mut Agent,
-    vm: &mut Vm,
+    vm: &mut VmRef,
     executable: Scoped<Executable>,
     environment: Option<Scoped<Environment>>,
     mut gc: GcScope<'a, '_>,
@@ -47,14 +47,10 @@ pub(super) fn execute_simple_array_binding<'a>(
             Instruction::BindingPatternBind
             | Instruction::BindingPatternGetValue
             | Instruction::BindingPatternSkip => {
-                let result = with_vm_gc(
-                    agent,
-                    vm,
-                    |agent, gc| ActiveIterator::new(agent, gc.nogc()).step_value(agent, gc),
-                    gc.reborrow(),
-                )
-                .unbind()
-                .bind(gc.nogc());
+                let result = ActiveIterator::new(agent, gc.nogc())
+                    .step_value(agent, gc.reborrow())
+                    .unbind()
+                    .bind(gc.nogc());
 
                 result.map(|r| {
                     iterator_is_done = r.is_none();
@@ -68,39 +64,11 @@ pub(super) fn execute_simple_array_binding<'a>(
                         .unwrap()
                         .into_value())
                 } else {
-                    with_vm_gc(
-                        agent,
-                        vm,
-                        |agent, mut gc| {
-                            let mut iterator = ActiveIterator::new(agent, gc.nogc());
-                            let capacity = iterator.remaining_length_estimate(agent).unwrap_or(0);
-                            let rest = array_create(agent, 0, capacity, None, gc.nogc())
-                                .unwrap()
-                                .scope(agent, gc.nogc());
-                            let mut idx = 0u32;
-                            while let Some(result) = iterator
-                                .step_value(agent, gc.reborrow())
-                                .unbind()?
-                                .bind(gc.nogc())
-                            {
-                                unwrap_try(try_create_data_property_or_throw(
-                                    agent,
-                                    rest.get(agent),
-                                    PropertyKey::from(idx),
-                                    result.unbind(),
-                                    gc.nogc(),
-                                ))
-                                .unwrap();
-                                idx += 1;
-                            }
-                            iterator_is_done = true;
-                            // SAFETY: rest is not shared
-                            JsResult::Ok(unsafe { rest.take(agent).into_value() })
-                        },
-                        gc.reborrow(),
-                    )
-                    .unbind()
-                    .bind(gc.nogc())
+                    let result = collect_rest_into_array(agent, gc.reborrow())
+                        .unbind()
+                        .bind(gc.nogc());
+                    iterator_is_done = result.is_ok();
+                    result
                 }
             }
             Instruction::FinishBindingPattern => break,
@@ -126,48 +94,28 @@ pub(super) fn execute_simple_array_binding<'a>(
                 continue;
             }
             Instruction::BindingPatternBind | Instruction::BindingPatternBindRest => {
-                let value = value.unbind();
-                with_vm_gc(
+                let binding_id =
+                    executable.fetch_identifier(agent, instr.get_first_index(), gc.nogc());
+                // Keep the value rooted on the stack while resolving the binding.
+                vm.stack.push(value.unbind());
+                let lhs = resolve_binding(
                     agent,
-                    vm,
-                    |agent, mut gc| {
-                        let value = value.scope(agent, gc.nogc());
-                        let binding_id =
-                            executable.fetch_identifier(agent, instr.get_first_index(), gc.nogc());
-                        let lhs = resolve_binding(
-                            agent,
-                            binding_id.unbind(),
-                            environment.as_ref().map(|v| v.get(agent)),
-                            gc.reborrow(),
-                        )
-                        .unbind()?
-                        .bind(gc.nogc());
-                        if environment.is_none() {
-                            put_value(
-                                agent,
-                                &lhs.unbind(),
-                                // SAFETY: value is not shared.
-                                unsafe { value.take(agent) },
-                                gc.reborrow(),
-                            )
-                            .unbind()?
-                            .bind(gc.nogc());
-                        } else {
-                            initialize_referenced_binding(
-                                agent,
-                                lhs.unbind(),
-                                // SAFETY: value is not shared.
-                                unsafe { value.take(agent) },
-                                gc.reborrow(),
-                            )
-                            .unbind()?
-                            .bind(gc.nogc());
-                        }
-                        JsResult::Ok(())
-                    },
+                    binding_id.unbind(),
+                    environment.as_ref().map(|v| v.get(agent)),
                     gc.reborrow(),
                 )
-                .unbind()?;
+                .unbind();
+                let value = vm.stack.pop().unwrap();
+                let lhs = lhs?.bind(gc.nogc());
+                if environment.is_none() {
+                    put_value(agent, &lhs.unbind(), value, gc.reborrow())
+                        .unbind()?
+                        .bind(gc.nogc());
+                } else {
+                    initialize_referenced_binding(agent, lhs.unbind(), value, gc.reborrow())
+                        .unbind()?
+                        .bind(gc.nogc());
+                }
             }
             Instruction::BindingPatternGetValue | Instruction::BindingPatternGetRestValue => {
                 execute_nested_simple_binding(
@@ -198,16 +146,7 @@ pub(super) fn execute_simple_array_binding<'a>(
     if !iterator_is_done {
         if let VmIteratorRecord::GenericIterator(iterator_record) = vm.get_active_iterator() {
             let iterator = iterator_record.iterator.unbind();
-            with_vm_gc(
-                agent,
-                vm,
-                |agent, gc| {
-                    iterator_close_with_value(agent, iterator, Value::Undefined, gc)?;
-                    JsResult::Ok(())
-                },
-                gc,
-            )
-            .unbind()?;
+            iterator_close_with_value(agent, iterator, Value::Undefined, gc)?;
         }
     }
 
@@ -216,7 +155,7 @@ pub(super) fn execute_simple_array_binding<'a>(
 
 pub(super) fn execute_simple_object_binding<'a>(
     agent: &mut Agent,
-    vm: &mut Vm,
+    vm: &mut VmRef,
     executable: Scoped<Executable>,
     object: Object,
     environment: Option<Scoped<Environment>>,
@@ -232,75 +171,60 @@ pub(super) fn execute_simple_object_binding<'a>(
         }
         match instr.kind {
             Instruction::BindingPatternBind | Instruction::BindingPatternBindNamed => {
-                with_vm_gc(
-                    agent,
-                    vm,
-                    |agent, mut gc| {
-                        let binding_id =
-                            executable.fetch_identifier(agent, instr.get_first_index(), gc.nogc());
-                        let property_key = if instr.kind == Instruction::BindingPatternBind {
-                            binding_id.into()
-                        } else {
-                            let key_value = executable.fetch_constant(
-                                agent,
-                                instr.get_second_index(),
-                                gc.nogc(),
-                            );
-                            // SAFETY: It should be impossible for binding pattern
-                            // names to be integer strings.
-                            unsafe { PropertyKey::from_value_unchecked(key_value) }
-                        };
+                let binding_id =
+                    executable.fetch_identifier(agent, instr.get_first_index(), gc.nogc());
+                let property_key = if instr.kind == Instruction::BindingPatternBind {
+                    binding_id.into()
+                } else {
+                    let key_value =
+                        executable.fetch_constant(agent, instr.get_second_index(), gc.nogc());
+                    // SAFETY: It should be impossible for binding pattern
+                    // names to be integer strings.
+                    unsafe { PropertyKey::from_value_unchecked(key_value) }
+                };
 
-                        excluded_names.insert(agent, property_key);
+                excluded_names.insert(agent, property_key);
 
-                        let property_key = property_key.scope(agent, gc.nogc());
-                        let lhs = resolve_binding(
-                            agent,
-                            binding_id.unbind(),
-                            environment.as_ref().map(|v| v.get(agent)),
-                            gc.reborrow(),
-                        )
-                        .unbind()?;
-                        let v = get(
-                            agent,
-                            object.get(agent),
-                            // SAFETY: property_key is not shared.
-                            unsafe { property_key.take(agent) },
-                            gc.reborrow(),
-                        )
-                        .unbind()?
-                        .bind(gc.nogc());
-                        if environment.is_none() {
-                            put_value(agent, &lhs, v.unbind(), gc.reborrow()).unbind()?;
-                        } else {
-                            initialize_referenced_binding(agent, lhs, v.unbind(), gc.reborrow())
-                                .unbind()?
-                        }
-                        JsResult::Ok(())
-                    },
+                let property_key = property_key.scope(agent, gc.nogc());
+                let lhs = resolve_binding(
+                    agent,
+                    binding_id.unbind(),
+                    environment.as_ref().map(|v| v.get(agent)),
                     gc.reborrow(),
                 )
                 .unbind()?;
+                let v = get(
+                    agent,
+                    object.get(agent),
+                    // SAFETY: property_key is not shared.
+                    unsafe { property_key.take(agent) },
+                    gc.reborrow(),
+                )
+                .unbind()?
+                .bind(gc.nogc());
+                if environment.is_none() {
+                    put_value(agent, &lhs, v.unbind(), gc.reborrow()).unbind()?;
+                } else {
+                    initialize_referenced_binding(agent, lhs, v.unbind(), gc.reborrow()).unbind()?
+                }
             }
             Instruction::BindingPatternGetValueNamed => {
-                let v = with_vm_gc(
-                    agent,
-                    vm,
-                    |agent, gc| {
-                        // SAFETY: The constant was created using PropertyKey::from_str
-                        // which checks for integer-ness, and then converted to Value
-                        // without conversion, or is a floating point number string.
-                        let property_key = unsafe {
-                            PropertyKey::from_value_unchecked(executable.fetch_constant(
-                                agent,
-                                instr.get_first_index(),
-                                gc.nogc(),
-                            ))
-                        };
+                // SAFETY: The constant was created using PropertyKey::from_str
+                // which checks for integer-ness, and then converted to Value
+                // without conversion, or is a floating point number string.
+                let property_key = unsafe {
+                    PropertyKey::from_value_unchecked(executable.fetch_constant(
+                        agent,
+                        instr.get_first_index(),
+                        gc.nogc(),
+                    ))
+                };
 
-                        excluded_names.insert(agent, property_key);
-                        get(agent, object.get(agent), property_key.unbind(), gc)
-                    },
+                excluded_names.insert(agent, property_key);
+                let v = get(
+                    agent,
+                    object.get(agent),
+                    property_key.unbind(),
                     gc.reborrow(),
                 )
                 .unbind()?
@@ -316,50 +240,36 @@ pub(super) fn execute_simple_object_binding<'a>(
                 .unbind()?;
             }
             Instruction::BindingPatternBindRest => {
-                with_vm_gc(
+                // 1. Let lhs be ? ResolveBinding(StringValue of BindingIdentifier, environment).
+                let binding_id =
+                    executable.fetch_identifier(agent, instr.get_first_index(), gc.nogc());
+                // TODO: Properly handle potential GC.
+                let lhs = resolve_binding(
                     agent,
-                    vm,
-                    |agent, mut gc| {
-                        // 1. Let lhs be ? ResolveBinding(StringValue of BindingIdentifier, environment).
-                        let binding_id =
-                            executable.fetch_identifier(agent, instr.get_first_index(), gc.nogc());
-                        // TODO: Properly handle potential GC.
-                        let lhs = resolve_binding(
-                            agent,
-                            binding_id.unbind(),
-                            environment.as_ref().map(|v| v.get(agent)),
-                            gc.reborrow(),
-                        )
-                        .unbind()?;
-                        // 2. Let restObj be OrdinaryObjectCreate(%Object.prototype%).
-                        // 3. Perform ? CopyDataProperties(restObj, value, excludedNames).
-                        let rest_obj = copy_data_properties_into_object(
-                            agent,
-                            object.get(agent),
-                            excluded_names,
-                            gc.reborrow(),
-                        )
-                        .unbind()?
-                        .bind(gc.nogc())
-                        .into_value();
-                        // 4. If environment is undefined, return ? PutValue(lhs, restObj).
-                        // 5. Return ? InitializeReferencedBinding(lhs, restObj).
-                        if environment.is_none() {
-                            put_value(agent, &lhs, rest_obj.unbind(), gc.reborrow()).unbind()?;
-                        } else {
-                            initialize_referenced_binding(
-                                agent,
-                                lhs,
-                                rest_obj.unbind(),
-                                gc.reborrow(),
-                            )
-                            .unbind()?;
-                        }
-                        JsResult::Ok(())
-                    },
+                    binding_id.unbind(),
+                    environment.as_ref().map(|v| v.get(agent)),
                     gc.reborrow(),
                 )
                 .unbind()?;
+                // 2. Let restObj be OrdinaryObjectCreate(%Object.prototype%).
+                // 3. Perform ? CopyDataProperties(restObj, value, excludedNames).
+                let rest_obj = copy_data_properties_into_object(
+                    agent,
+                    object.get(agent),
+                    excluded_names,
+                    gc.reborrow(),
+                )
+                .unbind()?
+                .bind(gc.nogc())
+                .into_value();
+                // 4. If environment is undefined, return ? PutValue(lhs, restObj).
+                // 5. Return ? InitializeReferencedBinding(lhs, restObj).
+                if environment.is_none() {
+                    put_value(agent, &lhs, rest_obj.unbind(), gc.reborrow()).unbind()?;
+                } else {
+                    initialize_referenced_binding(agent, lhs, rest_obj.unbind(), gc.reborrow())
+                        .unbind()?;
+                }
                 break;
             }
             Instruction::FinishBindingPattern => break,
@@ -371,7 +281,7 @@ pub(super) fn execute_simple_object_binding<'a>(
 
 pub(super) fn execute_nested_simple_binding<'a>(
     agent: &mut Agent,
-    vm: &mut Vm,
+    vm: &mut VmRef,
     executable: Scoped<Executable>,
     value: Value,
     environment: Option<Scoped<Environment>>,
@@ -383,14 +293,9 @@ pub(super) fn execute_nested_simple_binding<'a>(
     }
     match instr.kind {
         Instruction::BeginSimpleArrayBindingPattern => {
-            let result = with_vm_gc(
-                agent,
-                vm,
-                |agent, gc| VmIteratorRecord::from_value(agent, value, gc),
-                gc.reborrow(),
-            )
-            .unbind()?
-            .bind(gc.nogc());
+            let result = VmIteratorRecord::from_value(agent, value, gc.reborrow())
+                .unbind()?
+                .bind(gc.nogc());
             vm.iterator_stack.push(result.unbind());
             let result =
                 execute_simple_array_binding(agent, vm, executable, environment.clone(), gc);
@@ -411,3 +316,33 @@ pub(super) fn execute_nested_simple_binding<'a>(
         _ => unreachable!(),
     }
 }
+
+/// Collects the remaining values of the active iterator into a new Array.
+pub(super) fn collect_rest_into_array<'a>(
+    agent: &mut Agent,
+    mut gc: GcScope<'a, '_>,
+) -> JsResult<'a, Value<'a>> {
+    let mut iterator = ActiveIterator::new(agent, gc.nogc());
+    let capacity = iterator.remaining_length_estimate(agent).unwrap_or(0);
+    let rest = array_create(agent, 0, capacity, None, gc.nogc())
+        .unwrap()
+        .scope(agent, gc.nogc());
+    let mut idx = 0u32;
+    while let Some(result) = iterator
+        .step_value(agent, gc.reborrow())
+        .unbind()?
+        .bind(gc.nogc())
+    {
+        unwrap_try(try_create_data_property_or_throw(
+            agent,
+            rest.get(agent),
+            PropertyKey::from(idx),
+            result.unbind(),
+            gc.nogc(),
+        ))
+        .unwrap();
+        idx += 1;
+    }
+    // SAFETY: rest is not shared
+    Ok(unsafe { rest.take(agent).into_value() })
+}
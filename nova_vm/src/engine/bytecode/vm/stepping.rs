@@ -0,0 +1,351 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Execution of bytecode one instruction at a time, for debuggers.
+
+use core::marker::PhantomData;
+
+use crate::{
+    ecmascript::{
+        execution::{
+            Agent, AgentOperation,
+            agent::{JsError, SourceLocation},
+        },
+        types::Value,
+    },
+    engine::{
+        Scoped,
+        bytecode::{Executable, Vm},
+        context::{Bindable, GcScope, GcToken, NoGcScope},
+    },
+};
+
+use super::{ContinuationKind, VmRef};
+
+/// The state of a [`Vm`] between two instructions, as passed to and returned
+/// by [`Vm::step`].
+///
+/// The state holds the Values on the Vm's stacks. Like a Value, it must not
+/// be held across a garbage collection: [`Vm::step`] keeps the state alive
+/// while it executes an instruction, but the state must not be used after
+/// anything else collects garbage.
+#[derive(Debug)]
+pub struct VmState<'a> {
+    vm: Vm,
+    _gc: PhantomData<&'a GcToken>,
+}
+
+impl<'a> VmState<'a> {
+    /// Create the state of a Vm that is about to execute the first
+    /// instruction of an Executable.
+    pub(crate) fn new(agent: &mut Agent, _gc: NoGcScope<'a, '_>) -> Self {
+        Self {
+            vm: Vm::new(agent),
+            _gc: PhantomData,
+        }
+    }
+
+    /// Index of the next instruction to execute in the Executable's
+    /// bytecode.
+    pub fn instruction_pointer(&self) -> usize {
+        self.vm.ip
+    }
+
+    /// The Values on the Vm's operand stack, from the bottom up.
+    pub fn stack(&self) -> &[Value<'a>] {
+        &self.vm.stack
+    }
+
+    /// The Value of the Vm's result register, which holds the result of the
+    /// last instruction that produced a Value.
+    pub fn result(&self) -> Option<Value<'a>> {
+        self.vm.result
+    }
+}
+
+// SAFETY: Property implemented as a lifetime transmute.
+unsafe impl Bindable for VmState<'_> {
+    type Of<'a> = VmState<'a>;
+
+    #[inline(always)]
+    fn unbind(self) -> Self::Of<'static> {
+        unsafe { core::mem::transmute::<Self, Self::Of<'static>>(self) }
+    }
+
+    #[inline(always)]
+    fn bind<'a>(self, _gc: NoGcScope<'a, '_>) -> Self::Of<'a> {
+        unsafe { core::mem::transmute::<Self, Self::Of<'a>>(self) }
+    }
+}
+
+/// Result of executing a single instruction with [`Vm::step`].
+#[derive(Debug)]
+#[must_use]
+pub enum StepResult<'a> {
+    /// The instruction was executed and execution continues from `state`.
+    Continue {
+        state: VmState<'a>,
+        /// Location of the statement that the next instruction begins, if
+        /// it begins one. A debugger steps over a source line by stepping
+        /// until the next statement on another line is reached.
+        statement: Option<SourceLocation<'a>>,
+    },
+    /// The code returned a Value.
+    Return(Value<'a>),
+    /// The code threw an exception that it did not catch.
+    Throw(JsError<'a>),
+}
+
+// SAFETY: Property implemented as a lifetime transmute.
+unsafe impl Bindable for StepResult<'_> {
+    type Of<'a> = StepResult<'a>;
+
+    #[inline(always)]
+    fn unbind(self) -> Self::Of<'static> {
+        unsafe { core::mem::transmute::<Self, Self::Of<'static>>(self) }
+    }
+
+    #[inline(always)]
+    fn bind<'a>(self, _gc: NoGcScope<'a, '_>) -> Self::Of<'a> {
+        unsafe { core::mem::transmute::<Self, Self::Of<'a>>(self) }
+    }
+}
+
+impl Vm {
+    /// Execute exactly one instruction of `executable` in the running
+    /// execution context, continuing from `state`.
+    ///
+    /// An instruction that calls a function runs the whole call before the
+    /// step returns.
+    ///
+    /// ## Panics
+    ///
+    /// If the Agent is poisoned or code may not be evaluated in its current
+    /// [`ExecutionState`](crate::ecmascript::execution::ExecutionState), or
+    /// if the code awaits or yields.
+    pub fn step<'gc>(
+        agent: &mut Agent,
+        executable: Scoped<Executable>,
+        state: VmState,
+        mut gc: GcScope<'gc, '_>,
+    ) -> StepResult<'gc> {
+        let _guard = agent
+            .enter_operation(AgentOperation::Evaluate)
+            .unwrap_or_else(|err| panic!("Cannot step Vm: {err}"));
+        let mut vm = state.vm;
+        let mut vm_ref = VmRef::register(agent, &mut vm);
+        let stack_depth = agent.stack_refs.borrow().len();
+        let vm_depth = agent.vm_stack.len();
+        let instructions = executable.get_instructions(agent);
+        let exit = Self::execute_next_instruction(
+            agent,
+            &mut vm_ref,
+            &executable,
+            instructions,
+            stack_depth,
+            vm_depth,
+            gc.reborrow(),
+        )
+        .map(|exit| exit.map_err(|err| err.unbind()));
+        vm_ref.unregister(agent);
+
+        let gc = gc.into_nogc();
+        match exit {
+            Some(Ok(ContinuationKind::Normal)) => {
+                let statement = executable
+                    .get(agent)
+                    .get_statement_span_at(agent, vm.ip)
+                    .zip(agent.running_source_code())
+                    .map(|(span, source_code)| SourceLocation {
+                        source_code: source_code.bind(gc),
+                        span,
+                    });
+                StepResult::Continue {
+                    state: VmState {
+                        vm,
+                        _gc: PhantomData,
+                    },
+                    statement,
+                }
+            }
+            None => {
+                vm.release(agent);
+                StepResult::Return(Value::Undefined)
+            }
+            Some(Ok(ContinuationKind::Return)) => {
+                let result = vm.result.unwrap_or(Value::Undefined).bind(gc);
+                vm.release(agent);
+                StepResult::Return(result)
+            }
+            Some(Ok(ContinuationKind::Yield | ContinuationKind::Await)) => {
+                panic!("Cannot step code that awaits or yields")
+            }
+            Some(Err(err)) => {
+                vm.release(agent);
+                StepResult::Throw(err.bind(gc))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        ecmascript::{
+            execution::{Agent, DefaultHostHooks, agent::Options, initialize_default_realm},
+            scripts_and_modules::script::{
+                Script, finish_script_stepping, parse_script, start_script_stepping, test::evaluate,
+            },
+            types::{IntoValue, String, Value},
+        },
+        engine::{
+            StepResult, Vm,
+            context::{Bindable, NoGcScope},
+            rootable::Scopable,
+        },
+    };
+
+    fn parse<'gc>(agent: &mut Agent, source: &str, gc: NoGcScope<'gc, '_>) -> Script<'gc> {
+        let source_text = String::from_str(agent, source, gc);
+        let realm = agent.current_realm(gc);
+        parse_script(agent, source_text, realm, false, None, gc).unwrap()
+    }
+
+    #[test]
+    fn step_through_var_declaration() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let script = parse(agent, "var a = 1 + 2;", gc.nogc());
+        let (executable, state) =
+            start_script_stepping(agent, script.unbind(), gc.reborrow()).unwrap();
+        let mut state = state.unbind();
+        let executable = executable.unbind().scope(agent, gc.nogc());
+        let mut stacks: Vec<Vec<Value>> = vec![state.stack().to_vec()];
+        let mut results = vec![];
+        let result = loop {
+            match Vm::step(agent, executable.clone(), state, gc.reborrow()).unbind() {
+                StepResult::Continue {
+                    state: next_state, ..
+                } => {
+                    let stack = next_state.stack().to_vec();
+                    if stacks.last() != Some(&stack) {
+                        stacks.push(stack);
+                    }
+                    results.push(next_state.result());
+                    state = next_state;
+                }
+                StepResult::Return(value) => break Ok(value),
+                StepResult::Throw(err) => break Err(err),
+            }
+        };
+        // The completion value is pushed first, then the left operand of the
+        // addition, which is popped by the addition.
+        assert_eq!(
+            stacks,
+            [
+                vec![],
+                vec![Value::Undefined],
+                vec![Value::Undefined, Value::from(1)],
+                vec![Value::Undefined],
+                vec![],
+            ]
+        );
+        assert!(results.contains(&Some(Value::from(2))));
+        assert!(results.contains(&Some(Value::from(3))));
+
+        let result = finish_script_stepping(agent, result, gc.reborrow()).unwrap();
+        assert_eq!(result, Value::Undefined);
+        let a = evaluate(agent, "a === 3", gc);
+        assert_eq!(a, Value::Boolean(true));
+    }
+
+    #[test]
+    fn steps_report_statement_boundaries() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source = "var a = 1;\nvar b = a + 1;\nfor (var i = 0; i < 2; i++) b *= 2;\nb";
+        let script = parse(agent, source, gc.nogc());
+        let source_code = script.source_code(agent).unbind();
+        let (executable, state) =
+            start_script_stepping(agent, script.unbind(), gc.reborrow()).unwrap();
+        let mut state = state.unbind();
+        let executable = executable.unbind().scope(agent, gc.nogc());
+        let mut statements = vec![];
+        let value = loop {
+            match Vm::step(agent, executable.clone(), state, gc.reborrow()).unbind() {
+                StepResult::Continue {
+                    state: next_state,
+                    statement,
+                } => {
+                    if let Some(statement) = statement {
+                        assert_eq!(statement.source_code, source_code);
+                        let (line, _) = source_code.line_column_at(agent, statement.span.start);
+                        statements
+                            .push((line, source_code.slice(agent, statement.span).to_owned()));
+                    }
+                    state = next_state;
+                }
+                StepResult::Return(value) => break value,
+                StepResult::Throw(_) => panic!("Unexpected throw"),
+            }
+        };
+        let value = finish_script_stepping(agent, Ok(value), gc).unwrap();
+        assert_eq!(value, Value::from(8));
+
+        // The script first initializes its completion value, so each
+        // statement is reached by a step. The loop body is reached once per
+        // iteration.
+        assert_eq!(
+            statements,
+            [
+                (0, "var a = 1;".to_owned()),
+                (1, "var b = a + 1;".to_owned()),
+                (2, "for (var i = 0; i < 2; i++) b *= 2;".to_owned()),
+                (2, "b *= 2;".to_owned()),
+                (2, "b *= 2;".to_owned()),
+                (3, "b".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn uncaught_throw_ends_stepping() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let source = "try { null.x; } catch { var caught = true; }\nthrow 'done';";
+        let script = parse(agent, source, gc.nogc());
+        let (executable, state) =
+            start_script_stepping(agent, script.unbind(), gc.reborrow()).unwrap();
+        let mut state = state.unbind();
+        let executable = executable.unbind().scope(agent, gc.nogc());
+        let err = loop {
+            match Vm::step(agent, executable.clone(), state, gc.reborrow()).unbind() {
+                StepResult::Continue {
+                    state: next_state, ..
+                } => state = next_state,
+                StepResult::Return(_) => panic!("Unexpected return"),
+                StepResult::Throw(err) => break err,
+            }
+        };
+        let err = finish_script_stepping(agent, Err(err), gc.reborrow())
+            .unwrap_err()
+            .value()
+            .unbind();
+        let done = String::from_static_str(agent, "done", gc.nogc());
+        assert_eq!(err, done.into_value());
+        // The error caught by the script did not end stepping, and the
+        // script's execution context was removed.
+        let caught = evaluate(agent, "caught", gc);
+        assert_eq!(caught, Value::Boolean(true));
+    }
+}
@@ -9,20 +9,21 @@ pub(crate) use private::{HeapRootCollectionData, RootableCollectionSealed, Roota
 
 #[cfg(feature = "date")]
 use crate::ecmascript::builtins::date::Date;
-#[cfg(feature = "regexp")]
-use crate::ecmascript::builtins::regexp::RegExp;
 #[cfg(feature = "shared-array-buffer")]
 use crate::ecmascript::builtins::shared_array_buffer::SharedArrayBuffer;
 #[cfg(feature = "array-buffer")]
 use crate::ecmascript::builtins::{ArrayBuffer, data_view::DataView};
+#[cfg(feature = "regexp")]
+use crate::ecmascript::builtins::{
+    regexp::RegExp,
+    text_processing::regexp_objects::regexp_string_iterator_objects::RegExpStringIterator,
+};
 #[cfg(feature = "weak-refs")]
 use crate::ecmascript::builtins::{weak_map::WeakMap, weak_ref::WeakRef, weak_set::WeakSet};
 #[cfg(feature = "date")]
 use crate::ecmascript::types::DATE_DISCRIMINANT;
 #[cfg(feature = "proposal-float16array")]
 use crate::ecmascript::types::FLOAT_16_ARRAY_DISCRIMINANT;
-#[cfg(feature = "regexp")]
-use crate::ecmascript::types::REGEXP_DISCRIMINANT;
 #[cfg(feature = "shared-array-buffer")]
 use crate::ecmascript::types::SHARED_ARRAY_BUFFER_DISCRIMINANT;
 #[cfg(feature = "array-buffer")]
@@ -33,6 +34,8 @@ use crate::ecmascript::types::{
     UINT_8_ARRAY_DISCRIMINANT, UINT_8_CLAMPED_ARRAY_DISCRIMINANT, UINT_16_ARRAY_DISCRIMINANT,
     UINT_32_ARRAY_DISCRIMINANT,
 };
+#[cfg(feature = "regexp")]
+use crate::ecmascript::types::{REGEXP_DISCRIMINANT, REGEXP_STRING_ITERATOR_DISCRIMINANT};
 #[cfg(feature = "weak-refs")]
 use crate::ecmascript::types::{
     WEAK_MAP_DISCRIMINANT, WEAK_REF_DISCRIMINANT, WEAK_SET_DISCRIMINANT,
@@ -99,8 +102,6 @@ pub mod private {
 
     #[cfg(feature = "date")]
     use crate::ecmascript::builtins::date::Date;
-    #[cfg(feature = "regexp")]
-    use crate::ecmascript::builtins::regexp::RegExp;
     #[cfg(feature = "shared-array-buffer")]
     use crate::ecmascript::builtins::shared_array_buffer::SharedArrayBuffer;
     #[cfg(feature = "array-buffer")]
@@ -109,6 +110,11 @@ pub mod private {
     use crate::ecmascript::builtins::{
         keyed_collections::set_objects::set_iterator_objects::set_iterator::SetIterator, set::Set,
     };
+    #[cfg(feature = "regexp")]
+    use crate::ecmascript::builtins::{
+        regexp::RegExp,
+        text_processing::regexp_objects::regexp_string_iterator_objects::RegExpStringIterator,
+    };
     #[cfg(feature = "weak-refs")]
     use crate::ecmascript::builtins::{weak_map::WeakMap, weak_ref::WeakRef, weak_set::WeakSet};
     use crate::{
@@ -194,6 +200,8 @@ pub mod private {
     impl RootableSealed for Set<'_> {}
     #[cfg(feature = "set")]
     impl RootableSealed for SetIterator<'_> {}
+    #[cfg(feature = "regexp")]
+    impl RootableSealed for RegExpStringIterator<'_> {}
     #[cfg(feature = "shared-array-buffer")]
     impl RootableSealed for SharedArrayBuffer<'_> {}
     impl RootableSealed for SourceCode<'_> {}
@@ -495,6 +503,8 @@ pub enum HeapRootData {
     ArrayIterator(ArrayIterator<'static>) = ARRAY_ITERATOR_DISCRIMINANT,
     #[cfg(feature = "set")]
     SetIterator(SetIterator<'static>) = SET_ITERATOR_DISCRIMINANT,
+    #[cfg(feature = "regexp")]
+    RegExpStringIterator(RegExpStringIterator<'static>) = REGEXP_STRING_ITERATOR_DISCRIMINANT,
     MapIterator(MapIterator<'static>) = MAP_ITERATOR_DISCRIMINANT,
     Generator(Generator<'static>) = GENERATOR_DISCRIMINANT,
     StringIterator(StringIterator<'static>) = STRING_ITERATOR_DISCRIMINANT,
@@ -579,6 +589,10 @@ impl From<Object<'static>> for HeapRootData {
             Object::ArrayIterator(array_iterator) => Self::ArrayIterator(array_iterator),
             #[cfg(feature = "set")]
             Object::SetIterator(set_iterator) => Self::SetIterator(set_iterator),
+            #[cfg(feature = "regexp")]
+            Object::RegExpStringIterator(reg_exp_string_iterator) => {
+                Self::RegExpStringIterator(reg_exp_string_iterator)
+            }
             Object::MapIterator(map_iterator) => Self::MapIterator(map_iterator),
             Object::StringIterator(generator) => Self::StringIterator(generator),
             Object::Generator(generator) => Self::Generator(generator),
@@ -708,6 +722,10 @@ impl HeapMarkAndSweep for HeapRootData {
             HeapRootData::ArrayIterator(array_iterator) => array_iterator.mark_values(queues),
             #[cfg(feature = "set")]
             HeapRootData::SetIterator(set_iterator) => set_iterator.mark_values(queues),
+            #[cfg(feature = "regexp")]
+            HeapRootData::RegExpStringIterator(reg_exp_string_iterator) => {
+                reg_exp_string_iterator.mark_values(queues)
+            }
             HeapRootData::MapIterator(map_iterator) => map_iterator.mark_values(queues),
             HeapRootData::StringIterator(generator) => generator.mark_values(queues),
             HeapRootData::Generator(generator) => generator.mark_values(queues),
@@ -824,6 +842,10 @@ impl HeapMarkAndSweep for HeapRootData {
             HeapRootData::ArrayIterator(array_iterator) => array_iterator.sweep_values(compactions),
             #[cfg(feature = "set")]
             HeapRootData::SetIterator(set_iterator) => set_iterator.sweep_values(compactions),
+            #[cfg(feature = "regexp")]
+            HeapRootData::RegExpStringIterator(reg_exp_string_iterator) => {
+                reg_exp_string_iterator.sweep_values(compactions)
+            }
             HeapRootData::MapIterator(map_iterator) => map_iterator.sweep_values(compactions),
             HeapRootData::StringIterator(generator) => generator.sweep_values(compactions),
             HeapRootData::Generator(generator) => generator.sweep_values(compactions),
@@ -4,6 +4,7 @@ use crate::engine::context::NoGcScope;
 use crate::{
     ecmascript::execution::Agent,
     engine::rootable::{HeapRootRef, Rootable},
+    heap::HeapAccessError,
 };
 
 /// # Global heap root
@@ -49,51 +50,74 @@ impl<T: Rootable> Global<T> {
 
     /// Take the rooted value from inside this Global, releasing it in the
     /// process. Using the Global is not possible after this call.
+    ///
+    /// ## Panics
+    ///
+    /// If the Global does not belong to this Agent.
     pub fn take(self, agent: &Agent) -> T {
+        self.try_take(agent).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Take the rooted value from inside this Global, releasing it in the
+    /// process. Returns an error if the Global does not belong to this Agent.
+    pub fn try_take(self, agent: &Agent) -> Result<T, HeapAccessError> {
         let heap_ref = match T::from_root_repr(&self.0) {
             Ok(value) => {
                 // The value didn't need rooting
-                return value;
+                return Ok(value);
             }
             Err(heap_ref) => heap_ref,
         };
+        let index = heap_ref.to_index();
         // Leave a `None` in the index and return the value
         let heap_data = agent
             .heap
             .globals
             .borrow_mut()
-            .get_mut(heap_ref.to_index())
-            .unwrap()
+            .get_mut(index)
+            .ok_or(HeapAccessError::out_of_bounds("Global", index as u32))?
             .take()
-            .unwrap();
+            .ok_or(HeapAccessError::slot_empty("Global", index as u32))?;
         let Some(value) = T::from_heap_data(heap_data) else {
             panic!("Invalid Global returned different type than expected");
         };
-        value
+        Ok(value)
     }
 
     /// Access the rooted value from inside this Global without releasing the
     /// Global.
-    pub fn get(&self, agent: &mut Agent, _: NoGcScope) -> T {
+    ///
+    /// ## Panics
+    ///
+    /// If the Global does not belong to this Agent.
+    pub fn get(&self, agent: &mut Agent, gc: NoGcScope) -> T {
+        self.try_get(agent, gc)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Access the rooted value from inside this Global without releasing the
+    /// Global. Returns an error if the Global does not belong to this Agent.
+    pub fn try_get(&self, agent: &Agent, _: NoGcScope) -> Result<T, HeapAccessError> {
         let heap_ref = match T::from_root_repr(&self.0) {
             Ok(value) => {
                 // The value didn't need rooting
-                return value;
+                return Ok(value);
             }
             Err(heap_ref) => heap_ref,
         };
+        let index = heap_ref.to_index();
         let heap_data = *agent
             .heap
             .globals
-            .borrow_mut()
-            .get_mut(heap_ref.to_index())
-            .unwrap()
+            .borrow()
+            .get(index)
+            .ok_or(HeapAccessError::out_of_bounds("Global", index as u32))?
             .as_ref()
-            .unwrap();
+            .ok_or(HeapAccessError::slot_empty("Global", index as u32))?;
         let Some(value) = T::from_heap_data(heap_data) else {
             panic!("Invalid Global returned different type than expected");
         };
-        value
+        Ok(value)
     }
 
     /// Create a clone of this Global. Cloning a global means that both the
@@ -6,8 +6,13 @@ pub mod element_array;
 mod heap_bits;
 mod heap_constants;
 pub(crate) mod heap_gc;
+mod heap_statistics;
 pub mod indexes;
 mod object_entry;
+mod own_keys_cache;
+pub mod realm_clone;
+mod retainers;
+pub(crate) mod snapshot;
 
 use core::{cell::RefCell, ops::Index};
 
@@ -20,18 +25,24 @@ pub(crate) use self::heap_constants::{
 pub(crate) use self::heap_constants::{
     LAST_INTRINSIC_CONSTRUCTOR_INDEX, LAST_INTRINSIC_FUNCTION_INDEX, LAST_INTRINSIC_OBJECT_INDEX,
 };
+pub use self::heap_statistics::{
+    ElementArrayClassStats, ElementArrayStats, HeapStatistics, HeapVectorStatistics,
+};
 pub(crate) use self::object_entry::{ObjectEntry, ObjectEntryPropertyDescriptor};
+pub(crate) use self::own_keys_cache::OwnKeysCache;
+pub use self::realm_clone::RealmCloneError;
+pub(crate) use self::realm_clone::clone_realm;
+pub use self::retainers::RetainerPath;
+pub use self::snapshot::SnapshotError;
 use self::{
     element_array::{
         ElementArray2Pow8, ElementArray2Pow10, ElementArray2Pow12, ElementArray2Pow16,
-        ElementArray2Pow24, ElementArray2Pow32, ElementArrays,
+        ElementArray2Pow24, ElementArray2Pow32, ElementArrays, ElementsVector,
     },
     indexes::NumberIndex,
 };
 #[cfg(feature = "date")]
 use crate::ecmascript::builtins::date::data::DateHeapData;
-#[cfg(feature = "regexp")]
-use crate::ecmascript::builtins::regexp::RegExpHeapData;
 #[cfg(feature = "shared-array-buffer")]
 use crate::ecmascript::builtins::shared_array_buffer::data::SharedArrayBufferHeapData;
 #[cfg(feature = "array-buffer")]
@@ -45,6 +56,11 @@ use crate::ecmascript::builtins::{
     keyed_collections::set_objects::set_iterator_objects::set_iterator::SetIteratorHeapData,
     set::data::SetHeapData,
 };
+#[cfg(feature = "regexp")]
+use crate::ecmascript::builtins::{
+    regexp::RegExpHeapData,
+    text_processing::regexp_objects::regexp_string_iterator_objects::RegExpStringIteratorHeapData,
+};
 #[cfg(feature = "weak-refs")]
 use crate::ecmascript::builtins::{
     weak_map::data::WeakMapHeapData, weak_ref::data::WeakRefHeapData,
@@ -104,11 +120,14 @@ use element_array::{
     PropertyKeyArray2Pow16, PropertyKeyArray2Pow24, PropertyKeyArray2Pow32, PropertyStorageVector,
 };
 use hashbrown::HashTable;
+pub use heap_bits::CompactionPolicy;
 pub(crate) use heap_bits::{
-    CompactionLists, HeapMarkAndSweep, HeapSweepWeakReference, WorkQueues, sweep_side_set,
+    CompactionLists, HeapMarkAndSweep, HeapSweepWeakReference, HeapVectorSlot, WorkQueues,
+    sweep_side_set, sweep_side_table_values,
 };
+use heap_gc::IncrementalMarking;
 use indexes::TypedArrayIndex;
-use wtf8::Wtf8;
+use wtf8::{Wtf8, Wtf8Buf};
 
 #[derive(Debug)]
 pub struct Heap {
@@ -145,10 +164,14 @@ pub struct Heap {
     pub finalization_registrys: Vec<Option<FinalizationRegistryHeapData<'static>>>,
     pub generators: Vec<Option<GeneratorHeapData<'static>>>,
     pub(crate) globals: RefCell<Vec<Option<HeapRootData>>>,
+    /// State of the incremental garbage collection in progress, if any.
+    pub(crate) incremental_marking: Option<Box<IncrementalMarking>>,
     pub maps: Vec<Option<MapHeapData<'static>>>,
     pub map_iterators: Vec<Option<MapIteratorHeapData<'static>>>,
     pub numbers: Vec<Option<NumberHeapData>>,
     pub objects: Vec<Option<ObjectHeapData<'static>>>,
+    /// Caches the property keys of recently enumerated ordinary objects.
+    pub(crate) own_keys_cache: OwnKeysCache,
     pub primitive_objects: Vec<Option<PrimitiveObjectHeapData<'static>>>,
     pub promise_reaction_records: Vec<Option<PromiseReactionRecord<'static>>>,
     pub promise_resolving_functions: Vec<Option<PromiseResolvingFunctionHeapData<'static>>>,
@@ -161,6 +184,8 @@ pub struct Heap {
     pub sets: Vec<Option<SetHeapData<'static>>>,
     #[cfg(feature = "set")]
     pub set_iterators: Vec<Option<SetIteratorHeapData<'static>>>,
+    #[cfg(feature = "regexp")]
+    pub reg_exp_string_iterators: Vec<Option<RegExpStringIteratorHeapData<'static>>>,
     #[cfg(feature = "shared-array-buffer")]
     pub shared_array_buffers: Vec<Option<SharedArrayBufferHeapData<'static>>>,
     pub symbols: Vec<Option<SymbolHeapData<'static>>>,
@@ -189,10 +214,99 @@ pub struct Heap {
     pub strings: Vec<Option<StringHeapData>>,
     pub string_lookup_table: HashTable<HeapString<'static>>,
     pub string_hasher: ahash::RandomState,
+    /// Garbage collection statistics.
+    pub(crate) statistics: HeapStatistics,
     /// Counts allocations for garbage collection triggering.
     pub(crate) alloc_counter: usize,
 }
 
+/// Number of bytes that may be allocated before garbage collection is
+/// performed.
+pub(crate) const ALLOC_COUNTER_LIMIT: usize = 1024 * 1024 * 2;
+
+/// Number of bytes that may be allocated between checks of the heap size for
+/// [`HostHooks::on_heap_grow`](crate::ecmascript::execution::agent::HostHooks::on_heap_grow).
+pub(crate) const HEAP_GROWTH_CHECK_INTERVAL: usize = 1024 * 64;
+
+/// Number of bytes the heap must grow by before the host is notified through
+/// [`HostHooks::on_heap_grow`](crate::ecmascript::execution::agent::HostHooks::on_heap_grow).
+pub(crate) const HEAP_GROWTH_NOTIFICATION_STEP: usize = 1024 * 1024;
+
+/// Error returned by fallible heap lookups when an identifier does not point
+/// to live heap data in this Agent.
+///
+/// This happens when an identifier outlives the data it referred to, eg.
+/// because the data was garbage collected, or when an identifier created by
+/// one Agent is handed to another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeapAccessError {
+    /// Name of the identifier kind, eg. `"Script"` or `"Realm"`.
+    pub kind: &'static str,
+    /// Index carried by the identifier.
+    pub index: u32,
+    /// Why the lookup failed.
+    pub reason: HeapAccessErrorReason,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeapAccessErrorReason {
+    /// The index points past the end of the heap vector.
+    OutOfBounds,
+    /// The index points to a slot whose data has been released.
+    SlotEmpty,
+}
+
+impl HeapAccessError {
+    pub(crate) const fn out_of_bounds(kind: &'static str, index: u32) -> Self {
+        Self {
+            kind,
+            index,
+            reason: HeapAccessErrorReason::OutOfBounds,
+        }
+    }
+
+    pub(crate) const fn slot_empty(kind: &'static str, index: u32) -> Self {
+        Self {
+            kind,
+            index,
+            reason: HeapAccessErrorReason::SlotEmpty,
+        }
+    }
+}
+
+impl core::fmt::Display for HeapAccessError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let reason = match self.reason {
+            HeapAccessErrorReason::OutOfBounds => "out of bounds",
+            HeapAccessErrorReason::SlotEmpty => "slot empty",
+        };
+        write!(f, "{}({}) {}", self.kind, self.index, reason)
+    }
+}
+
+impl core::error::Error for HeapAccessError {}
+
+/// Helper trait for fallible heap data lookups by identifier.
+///
+/// The panicking `Index` implementations remain the primary way to access
+/// heap data inside the engine where invariants guarantee validity; this
+/// trait backs the fallible lookups used at the public embedding boundary.
+pub trait HeapIndexable: Copy {
+    /// Heap data type the identifier points to.
+    type Data;
+    /// Name of the identifier kind, used in [`HeapAccessError`].
+    const KIND: &'static str;
+
+    /// Index of the identifier in its heap vector.
+    fn heap_index(self) -> usize;
+
+    /// Access the heap vector containing data of this kind.
+    fn heap_vec(heap: &Heap) -> &[Option<Self::Data>];
+
+    /// Access the heap vector containing data of this kind mutably.
+    fn heap_vec_mut(heap: &mut Heap) -> &mut [Option<Self::Data>];
+}
+
 pub trait CreateHeapData<T, F> {
     /// Creates a [`Value`] from the given data. Allocating the data is **not**
     /// guaranteed.
@@ -221,6 +335,370 @@ impl CreateHeapData<std::string::String, String<'static>> for Heap {
     }
 }
 
+impl CreateHeapData<Wtf8Buf, String<'static>> for Heap {
+    fn create(&mut self, data: Wtf8Buf) -> String<'static> {
+        match data.into_string() {
+            Ok(data) => self.create(data),
+            // SAFETY: String contains lone surrogates.
+            Err(data) => unsafe { self.alloc_wtf8_buf(data) },
+        }
+    }
+}
+
+/// Run `$body` for each heap data vector, with `$name` bound to the name of
+/// the vector and `$vector` bound to a shared or exclusive reference to the
+/// vector, depending on how the heap is borrowed.
+macro_rules! for_each_heap_data_vector {
+    (&mut $heap:expr, |$name:ident, $vector:ident| $body:block) => {
+        for_each_heap_data_vector!(@each [mut] $heap, |$name, $vector| $body)
+    };
+    (&$heap:expr, |$name:ident, $vector:ident| $body:block) => {
+        for_each_heap_data_vector!(@each [] $heap, |$name, $vector| $body)
+    };
+    (@each [$($mut:tt)?] $heap:expr, |$name:ident, $vector:ident| $body:block) => {{
+        #[cfg(feature = "array-buffer")]
+        {
+            let $name: &'static str = "array_buffers";
+            let $vector = &$($mut)? $heap.array_buffers;
+            $body
+        }
+        {
+            let $name: &'static str = "arrays";
+            let $vector = &$($mut)? $heap.arrays;
+            $body
+        }
+        {
+            let $name: &'static str = "array_iterators";
+            let $vector = &$($mut)? $heap.array_iterators;
+            $body
+        }
+        {
+            let $name: &'static str = "async_generators";
+            let $vector = &$($mut)? $heap.async_generators;
+            $body
+        }
+        {
+            let $name: &'static str = "await_reactions";
+            let $vector = &$($mut)? $heap.await_reactions;
+            $body
+        }
+        {
+            let $name: &'static str = "bigints";
+            let $vector = &$($mut)? $heap.bigints;
+            $body
+        }
+        {
+            let $name: &'static str = "bound_functions";
+            let $vector = &$($mut)? $heap.bound_functions;
+            $body
+        }
+        {
+            let $name: &'static str = "builtin_constructors";
+            let $vector = &$($mut)? $heap.builtin_constructors;
+            $body
+        }
+        {
+            let $name: &'static str = "builtin_functions";
+            let $vector = &$($mut)? $heap.builtin_functions;
+            $body
+        }
+        #[cfg(feature = "array-buffer")]
+        {
+            let $name: &'static str = "data_views";
+            let $vector = &$($mut)? $heap.data_views;
+            $body
+        }
+        #[cfg(feature = "date")]
+        {
+            let $name: &'static str = "dates";
+            let $vector = &$($mut)? $heap.dates;
+            $body
+        }
+        {
+            let $name: &'static str = "ecmascript_functions";
+            let $vector = &$($mut)? $heap.ecmascript_functions;
+            $body
+        }
+        {
+            let $name: &'static str = "embedder_objects";
+            let $vector = &$($mut)? $heap.embedder_objects;
+            $body
+        }
+        {
+            let $name: &'static str = "errors";
+            let $vector = &$($mut)? $heap.errors;
+            $body
+        }
+        {
+            let $name: &'static str = "executables";
+            let $vector = &$($mut)? $heap.executables;
+            $body
+        }
+        {
+            let $name: &'static str = "finalization_registrys";
+            let $vector = &$($mut)? $heap.finalization_registrys;
+            $body
+        }
+        {
+            let $name: &'static str = "generators";
+            let $vector = &$($mut)? $heap.generators;
+            $body
+        }
+        {
+            let $name: &'static str = "maps";
+            let $vector = &$($mut)? $heap.maps;
+            $body
+        }
+        {
+            let $name: &'static str = "map_iterators";
+            let $vector = &$($mut)? $heap.map_iterators;
+            $body
+        }
+        {
+            let $name: &'static str = "modules";
+            let $vector = &$($mut)? $heap.modules;
+            $body
+        }
+        {
+            let $name: &'static str = "numbers";
+            let $vector = &$($mut)? $heap.numbers;
+            $body
+        }
+        {
+            let $name: &'static str = "objects";
+            let $vector = &$($mut)? $heap.objects;
+            $body
+        }
+        {
+            let $name: &'static str = "primitive_objects";
+            let $vector = &$($mut)? $heap.primitive_objects;
+            $body
+        }
+        {
+            let $name: &'static str = "promise_reaction_records";
+            let $vector = &$($mut)? $heap.promise_reaction_records;
+            $body
+        }
+        {
+            let $name: &'static str = "promise_resolving_functions";
+            let $vector = &$($mut)? $heap.promise_resolving_functions;
+            $body
+        }
+        {
+            let $name: &'static str = "promises";
+            let $vector = &$($mut)? $heap.promises;
+            $body
+        }
+        {
+            let $name: &'static str = "proxys";
+            let $vector = &$($mut)? $heap.proxys;
+            $body
+        }
+        {
+            let $name: &'static str = "realms";
+            let $vector = &$($mut)? $heap.realms;
+            $body
+        }
+        #[cfg(feature = "regexp")]
+        {
+            let $name: &'static str = "regexps";
+            let $vector = &$($mut)? $heap.regexps;
+            $body
+        }
+        {
+            let $name: &'static str = "scripts";
+            let $vector = &$($mut)? $heap.scripts;
+            $body
+        }
+        #[cfg(feature = "set")]
+        {
+            let $name: &'static str = "sets";
+            let $vector = &$($mut)? $heap.sets;
+            $body
+        }
+        #[cfg(feature = "set")]
+        {
+            let $name: &'static str = "set_iterators";
+            let $vector = &$($mut)? $heap.set_iterators;
+            $body
+        }
+        #[cfg(feature = "regexp")]
+        {
+            let $name: &'static str = "reg_exp_string_iterators";
+            let $vector = &$($mut)? $heap.reg_exp_string_iterators;
+            $body
+        }
+        #[cfg(feature = "shared-array-buffer")]
+        {
+            let $name: &'static str = "shared_array_buffers";
+            let $vector = &$($mut)? $heap.shared_array_buffers;
+            $body
+        }
+        {
+            let $name: &'static str = "source_codes";
+            let $vector = &$($mut)? $heap.source_codes;
+            $body
+        }
+        {
+            let $name: &'static str = "string_iterators";
+            let $vector = &$($mut)? $heap.string_iterators;
+            $body
+        }
+        {
+            let $name: &'static str = "strings";
+            let $vector = &$($mut)? $heap.strings;
+            $body
+        }
+        {
+            let $name: &'static str = "symbols";
+            let $vector = &$($mut)? $heap.symbols;
+            $body
+        }
+        #[cfg(feature = "array-buffer")]
+        {
+            let $name: &'static str = "typed_arrays";
+            let $vector = &$($mut)? $heap.typed_arrays;
+            $body
+        }
+        #[cfg(feature = "weak-refs")]
+        {
+            let $name: &'static str = "weak_maps";
+            let $vector = &$($mut)? $heap.weak_maps;
+            $body
+        }
+        #[cfg(feature = "weak-refs")]
+        {
+            let $name: &'static str = "weak_refs";
+            let $vector = &$($mut)? $heap.weak_refs;
+            $body
+        }
+        #[cfg(feature = "weak-refs")]
+        {
+            let $name: &'static str = "weak_sets";
+            let $vector = &$($mut)? $heap.weak_sets;
+            $body
+        }
+        {
+            let $name: &'static str = "declarative_environments";
+            let $vector = &$($mut)? $heap.environments.declarative;
+            $body
+        }
+        {
+            let $name: &'static str = "function_environments";
+            let $vector = &$($mut)? $heap.environments.function;
+            $body
+        }
+        {
+            let $name: &'static str = "global_environments";
+            let $vector = &$($mut)? $heap.environments.global;
+            $body
+        }
+        {
+            let $name: &'static str = "object_environments";
+            let $vector = &$($mut)? $heap.environments.object;
+            $body
+        }
+        {
+            let $name: &'static str = "private_environments";
+            let $vector = &$($mut)? $heap.environments.private;
+            $body
+        }
+    }};
+}
+
+/// Run `$body` for each element and property key vector of the element
+/// arrays, like [`for_each_heap_data_vector`].
+macro_rules! for_each_element_vector {
+    (&mut $elements:expr, |$name:ident, $vector:ident| $body:block) => {
+        for_each_element_vector!(@each [mut] $elements, |$name, $vector| $body)
+    };
+    (&$elements:expr, |$name:ident, $vector:ident| $body:block) => {
+        for_each_element_vector!(@each [] $elements, |$name, $vector| $body)
+    };
+    (@each [$($mut:tt)?] $elements:expr, |$name:ident, $vector:ident| $body:block) => {{
+        {
+            let $name: &'static str = "e2pow4";
+            let $vector = &$($mut)? $elements.e2pow4.values;
+            $body
+        }
+        {
+            let $name: &'static str = "e2pow6";
+            let $vector = &$($mut)? $elements.e2pow6.values;
+            $body
+        }
+        {
+            let $name: &'static str = "e2pow8";
+            let $vector = &$($mut)? $elements.e2pow8.values;
+            $body
+        }
+        {
+            let $name: &'static str = "e2pow10";
+            let $vector = &$($mut)? $elements.e2pow10.values;
+            $body
+        }
+        {
+            let $name: &'static str = "e2pow12";
+            let $vector = &$($mut)? $elements.e2pow12.values;
+            $body
+        }
+        {
+            let $name: &'static str = "e2pow16";
+            let $vector = &$($mut)? $elements.e2pow16.values;
+            $body
+        }
+        {
+            let $name: &'static str = "e2pow24";
+            let $vector = &$($mut)? $elements.e2pow24.values;
+            $body
+        }
+        {
+            let $name: &'static str = "e2pow32";
+            let $vector = &$($mut)? $elements.e2pow32.values;
+            $body
+        }
+        {
+            let $name: &'static str = "k2pow4";
+            let $vector = &$($mut)? $elements.k2pow4.keys;
+            $body
+        }
+        {
+            let $name: &'static str = "k2pow6";
+            let $vector = &$($mut)? $elements.k2pow6.keys;
+            $body
+        }
+        {
+            let $name: &'static str = "k2pow8";
+            let $vector = &$($mut)? $elements.k2pow8.keys;
+            $body
+        }
+        {
+            let $name: &'static str = "k2pow10";
+            let $vector = &$($mut)? $elements.k2pow10.keys;
+            $body
+        }
+        {
+            let $name: &'static str = "k2pow12";
+            let $vector = &$($mut)? $elements.k2pow12.keys;
+            $body
+        }
+        {
+            let $name: &'static str = "k2pow16";
+            let $vector = &$($mut)? $elements.k2pow16.keys;
+            $body
+        }
+        {
+            let $name: &'static str = "k2pow24";
+            let $vector = &$($mut)? $elements.k2pow24.keys;
+            $body
+        }
+        {
+            let $name: &'static str = "k2pow32";
+            let $vector = &$($mut)? $elements.k2pow32.keys;
+            $body
+        }
+    }};
+}
+
 impl Heap {
     pub fn new() -> Heap {
         let mut heap = Heap {
@@ -262,6 +740,7 @@ impl Heap {
                 k2pow16: PropertyKeyArray2Pow16::default(),
                 k2pow24: PropertyKeyArray2Pow24::default(),
                 k2pow32: PropertyKeyArray2Pow32::default(),
+                write_log: None,
             },
             embedder_objects: Vec::with_capacity(0),
             environments: Default::default(),
@@ -271,11 +750,13 @@ impl Heap {
             finalization_registrys: Vec::with_capacity(0),
             generators: Vec::with_capacity(1024),
             globals: RefCell::new(Vec::with_capacity(1024)),
+            incremental_marking: None,
             maps: Vec::with_capacity(128),
             map_iterators: Vec::with_capacity(128),
             modules: Vec::with_capacity(0),
             numbers: Vec::with_capacity(1024),
             objects: Vec::with_capacity(1024),
+            own_keys_cache: Default::default(),
             primitive_objects: Vec::with_capacity(0),
             promise_reaction_records: Vec::with_capacity(0),
             promise_resolving_functions: Vec::with_capacity(0),
@@ -289,6 +770,8 @@ impl Heap {
             sets: Vec::with_capacity(128),
             #[cfg(feature = "set")]
             set_iterators: Vec::with_capacity(128),
+            #[cfg(feature = "regexp")]
+            reg_exp_string_iterators: Vec::with_capacity(128),
             #[cfg(feature = "shared-array-buffer")]
             shared_array_buffers: Vec::with_capacity(0),
             strings: Vec::with_capacity(1024),
@@ -310,6 +793,7 @@ impl Heap {
             weak_refs: Vec::with_capacity(0),
             #[cfg(feature = "weak-refs")]
             weak_sets: Vec::with_capacity(0),
+            statistics: HeapStatistics::default(),
             alloc_counter: 0,
         };
 
@@ -320,6 +804,257 @@ impl Heap {
         heap
     }
 
+    /// Fallibly access the heap data of an identifier.
+    ///
+    /// Returns an error naming the identifier kind and index if the
+    /// identifier does not point to live heap data in this heap.
+    pub fn try_get<T: HeapIndexable>(&self, index: T) -> Result<&T::Data, HeapAccessError> {
+        let i = index.heap_index();
+        T::heap_vec(self)
+            .get(i)
+            .ok_or(HeapAccessError::out_of_bounds(T::KIND, i as u32))?
+            .as_ref()
+            .ok_or(HeapAccessError::slot_empty(T::KIND, i as u32))
+    }
+
+    /// Fallibly access the heap data of an identifier mutably.
+    ///
+    /// Writes made through the returned reference are not seen by incremental
+    /// garbage collection, so any incremental collection in progress is
+    /// abandoned.
+    pub fn try_get_mut<T: HeapIndexable>(
+        &mut self,
+        index: T,
+    ) -> Result<&mut T::Data, HeapAccessError> {
+        self.stop_incremental_marking();
+        let i = index.heap_index();
+        T::heap_vec_mut(self)
+            .get_mut(i)
+            .ok_or(HeapAccessError::out_of_bounds(T::KIND, i as u32))?
+            .as_mut()
+            .ok_or(HeapAccessError::slot_empty(T::KIND, i as u32))
+    }
+
+    /// Write barrier for heap data: Must be called before the heap data of
+    /// `handle` is mutated.
+    ///
+    /// While incremental garbage collection is marking, the written heap data
+    /// is recorded so that it is scanned again before the collection finishes.
+    /// Otherwise a reference stored into already scanned heap data could go
+    /// unseen. Element and property key storage has its own barrier in
+    /// [`ElementArrays`].
+    #[inline]
+    pub(crate) fn write_barrier<T: Bindable>(&mut self, handle: T)
+    where
+        T::Of<'static>: HeapMarkAndSweep,
+    {
+        if let Some(marking) = self.incremental_marking.as_deref_mut() {
+            handle.unbind().mark_values(&mut marking.written);
+        }
+    }
+
+    /// Abandons the incremental garbage collection in progress, if any.
+    pub(crate) fn stop_incremental_marking(&mut self) {
+        self.incremental_marking = None;
+        self.elements.write_log = None;
+    }
+
+    /// Returns the number of live heap data entries, not counting element and
+    /// property key storage.
+    fn entry_count(&self) -> usize {
+        let mut count = 0;
+        for_each_heap_data_vector!(&self, |_name, vector| {
+            count += vector.iter().filter(|slot| slot.is_live()).count();
+        });
+        count
+    }
+
+    /// Returns the fragmentation statistics of each heap vector.
+    pub(crate) fn vector_statistics(&self) -> Vec<HeapVectorStatistics> {
+        fn statistics<T: HeapVectorSlot>(
+            name: &'static str,
+            vector: &[T],
+            capacity: usize,
+        ) -> HeapVectorStatistics {
+            HeapVectorStatistics {
+                name,
+                live: vector.iter().filter(|slot| slot.is_live()).count(),
+                slots: vector.len(),
+                capacity,
+            }
+        }
+        let mut result = Vec::with_capacity(64);
+        for_each_heap_data_vector!(&self, |name, vector| {
+            result.push(statistics(name, vector, vector.capacity()));
+        });
+        for_each_element_vector!(&self.elements, |name, vector| {
+            result.push(statistics(name, vector, vector.capacity()));
+        });
+        result
+    }
+
+    /// Releases unused capacity of the heap vectors back to the allocator.
+    ///
+    /// Only vectors using less than half of their capacity are shrunk, and
+    /// room is left for a quarter more entries so that the next allocations
+    /// do not immediately grow them again.
+    pub(crate) fn shrink_vectors(&mut self) {
+        fn shrink<T>(vector: &mut Vec<T>) {
+            let len = vector.len();
+            if len < vector.capacity() / 2 {
+                vector.shrink_to(len + len / 4);
+            }
+        }
+        for_each_heap_data_vector!(&mut self, |_name, vector| {
+            shrink(vector);
+        });
+        for_each_element_vector!(&mut self.elements, |_name, vector| {
+            shrink(vector);
+        });
+    }
+
+    /// Returns the number of bytes reserved by the heap vectors, including
+    /// element and property key storage.
+    pub(crate) fn reserved_bytes(&self) -> usize {
+        fn bytes<T>(vector: &Vec<T>) -> usize {
+            vector.capacity() * core::mem::size_of::<T>()
+        }
+        let Self {
+            #[cfg(feature = "array-buffer")]
+            array_buffers,
+            arrays,
+            array_iterators,
+            async_generators,
+            await_reactions,
+            bigints,
+            bound_functions,
+            builtin_constructors,
+            builtin_functions,
+            #[cfg(feature = "array-buffer")]
+            data_views,
+            #[cfg(feature = "date")]
+            dates,
+            ecmascript_functions,
+            embedder_objects,
+            environments,
+            errors,
+            executables,
+            finalization_registrys,
+            generators,
+            maps,
+            map_iterators,
+            numbers,
+            objects,
+            primitive_objects,
+            promise_reaction_records,
+            promise_resolving_functions,
+            promises,
+            proxys,
+            realms,
+            #[cfg(feature = "regexp")]
+            regexps,
+            #[cfg(feature = "set")]
+            sets,
+            #[cfg(feature = "set")]
+            set_iterators,
+            #[cfg(feature = "regexp")]
+            reg_exp_string_iterators,
+            #[cfg(feature = "shared-array-buffer")]
+            shared_array_buffers,
+            symbols,
+            #[cfg(feature = "array-buffer")]
+            typed_arrays,
+            #[cfg(feature = "weak-refs")]
+            weak_maps,
+            #[cfg(feature = "weak-refs")]
+            weak_refs,
+            #[cfg(feature = "weak-refs")]
+            weak_sets,
+            modules,
+            scripts,
+            string_iterators,
+            source_codes,
+            strings,
+            elements,
+            ..
+        } = self;
+        let mut size = bytes(arrays)
+            + bytes(array_iterators)
+            + bytes(async_generators)
+            + bytes(await_reactions)
+            + bytes(bigints)
+            + bytes(bound_functions)
+            + bytes(builtin_constructors)
+            + bytes(builtin_functions)
+            + bytes(ecmascript_functions)
+            + bytes(embedder_objects)
+            + bytes(&environments.declarative)
+            + bytes(&environments.function)
+            + bytes(&environments.global)
+            + bytes(&environments.object)
+            + bytes(&environments.private)
+            + bytes(errors)
+            + bytes(executables)
+            + bytes(finalization_registrys)
+            + bytes(generators)
+            + bytes(maps)
+            + bytes(map_iterators)
+            + bytes(numbers)
+            + bytes(objects)
+            + bytes(primitive_objects)
+            + bytes(promise_reaction_records)
+            + bytes(promise_resolving_functions)
+            + bytes(promises)
+            + bytes(proxys)
+            + bytes(realms)
+            + bytes(symbols)
+            + bytes(modules)
+            + bytes(scripts)
+            + bytes(string_iterators)
+            + bytes(source_codes)
+            + bytes(strings)
+            + elements.reserved_bytes();
+        #[cfg(feature = "array-buffer")]
+        {
+            size += bytes(array_buffers) + bytes(data_views) + bytes(typed_arrays);
+        }
+        #[cfg(feature = "date")]
+        {
+            size += bytes(dates);
+        }
+        #[cfg(feature = "regexp")]
+        {
+            size += bytes(regexps) + bytes(reg_exp_string_iterators);
+        }
+        #[cfg(feature = "set")]
+        {
+            size += bytes(sets) + bytes(set_iterators);
+        }
+        #[cfg(feature = "shared-array-buffer")]
+        {
+            size += bytes(shared_array_buffers);
+        }
+        #[cfg(feature = "weak-refs")]
+        {
+            size += bytes(weak_maps) + bytes(weak_refs) + bytes(weak_sets);
+        }
+        size
+    }
+
+    /// Returns garbage collection statistics and the current heap size.
+    pub(crate) fn statistics(&self) -> HeapStatistics {
+        HeapStatistics {
+            heap_entries: self.entry_count(),
+            bytecode_bytes: self
+                .executables
+                .iter()
+                .map(|executable| executable.instructions.len())
+                .sum(),
+            marking_in_progress: self.incremental_marking.is_some(),
+            ..self.statistics
+        }
+    }
+
     pub(crate) fn add_module<'a>(
         &mut self,
         module: ModuleHeapData,
@@ -359,7 +1094,7 @@ impl Heap {
     /// comparison between heap allocated strings and SmallStrings can be
     /// guaranteed to never equal true.
     pub(crate) unsafe fn alloc_str(&mut self, message: &str) -> String<'static> {
-        let found = self.find_equal_string(message);
+        let found = self.find_equal_string(Wtf8::from_str(message));
         match found {
             Ok(string) => string,
             Err(hash) => {
@@ -382,7 +1117,7 @@ impl Heap {
     /// comparison between heap allocated strings and SmallStrings can be
     /// guaranteed to never equal true.
     unsafe fn alloc_string(&mut self, message: std::string::String) -> String<'static> {
-        let found = self.find_equal_string(message.as_str());
+        let found = self.find_equal_string(Wtf8::from_str(message.as_str()));
         match found {
             Ok(string) => string,
             Err(hash) => {
@@ -405,7 +1140,7 @@ impl Heap {
     /// comparison between heap allocated strings and SmallStrings can be
     /// guaranteed to never equal true.
     pub(crate) unsafe fn alloc_static_str(&mut self, message: &'static str) -> String<'static> {
-        let found = self.find_equal_string(message);
+        let found = self.find_equal_string(Wtf8::from_str(message));
         match found {
             Ok(string) => string,
             Err(hash) => {
@@ -415,10 +1150,33 @@ impl Heap {
         }
     }
 
+    /// Allocate a WTF-8 string containing lone surrogates onto the Agent
+    /// heap
+    ///
+    /// This method will currently iterate through all heap strings to look for
+    /// a possible matching string and if found will return its HeapString
+    /// instead of allocating a copy.
+    ///
+    /// # Safety
+    ///
+    /// The string being allocated must not be valid UTF-8. All well-formed
+    /// strings must go through the UTF-8 allocation paths so that they are
+    /// stored as SmallStrings when possible.
+    unsafe fn alloc_wtf8_buf(&mut self, message: Wtf8Buf) -> String<'static> {
+        debug_assert!(message.as_str().is_none());
+        let found = self.find_equal_string(&message);
+        match found {
+            Ok(string) => string,
+            Err(hash) => {
+                let data = StringHeapData::from_wtf8_buf(message);
+                self.create((data, hash))
+            }
+        }
+    }
+
     /// Find existing heap String or return the strings hash.
-    fn find_equal_string(&self, message: &str) -> Result<String<'static>, u64> {
-        debug_assert!(message.len() > 7);
-        let message = Wtf8::from_str(message);
+    fn find_equal_string(&self, message: &Wtf8) -> Result<String<'static>, u64> {
+        debug_assert!(message.len() > 7 || message.as_str().is_none());
         let hash = self.string_hasher.hash_one(message);
         self.string_lookup_table
             .find(hash, |heap_string| {
@@ -492,6 +1250,8 @@ impl Heap {
         let object_data = ObjectHeapData {
             prototype: None,
             property_storage,
+            indexed_elements: ElementsVector::default(),
+            keys_version: 0,
         };
         self.create(object_data)
     }
@@ -505,6 +1265,28 @@ impl Heap {
         let object_data = ObjectHeapData {
             prototype: Some(prototype.unbind()),
             property_storage,
+            indexed_elements: ElementsVector::default(),
+            keys_version: 0,
+        };
+        self.create(object_data)
+    }
+
+    /// Creates an empty object with storage reserved for at least `capacity`
+    /// properties.
+    pub(crate) fn create_object_with_prototype_and_capacity<'gc>(
+        &mut self,
+        prototype: Object<'gc>,
+        capacity: usize,
+    ) -> OrdinaryObject<'gc> {
+        self.alloc_counter += capacity * core::mem::size_of::<Option<Value>>() * 2;
+        let property_storage = self
+            .elements
+            .allocate_property_storage_with_capacity(capacity);
+        let object_data = ObjectHeapData {
+            prototype: Some(prototype.unbind()),
+            property_storage,
+            indexed_elements: ElementsVector::default(),
+            keys_version: 0,
         };
         self.create(object_data)
     }
@@ -579,3 +1361,68 @@ fn init_heap() {
     let heap = Heap::new();
     println!("{heap:#?}");
 }
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex;
+
+    use crate::{
+        ecmascript::{
+            execution::{
+                Agent,
+                agent::{HostHooks, Job, Options},
+                initialize_default_realm,
+            },
+            scripts_and_modules::script::test::evaluate,
+            types::Value,
+        },
+        engine::context::Bindable,
+    };
+
+    /// Host hooks that record every heap growth notification.
+    #[derive(Debug)]
+    struct RecordingHostHooks {
+        events: Mutex<Vec<(usize, usize)>>,
+    }
+
+    impl HostHooks for RecordingHostHooks {
+        fn enqueue_promise_job(&self, _job: Job) {}
+
+        fn on_heap_grow(&self, bytes_before: usize, bytes_after: usize) {
+            self.events
+                .lock()
+                .unwrap()
+                .push((bytes_before, bytes_after));
+        }
+    }
+
+    #[test]
+    fn heap_growth_is_reported_to_the_host() {
+        static HOOKS: RecordingHostHooks = RecordingHostHooks {
+            events: Mutex::new(Vec::new()),
+        };
+        let mut agent = Agent::new(Options::default(), &HOOKS);
+        let result = agent.with_gc(|agent, mut gc| {
+            initialize_default_realm(agent, gc.reborrow());
+            evaluate(
+                agent,
+                "const objects = [];
+                for (let i = 0; i < 20000; i++) objects.push({ i, name: 'object' + i });
+                objects.length === 20000",
+                gc,
+            )
+            .unbind()
+        });
+        assert_eq!(result, Value::Boolean(true));
+
+        let events = HOOKS.events.lock().unwrap();
+        assert!(!events.is_empty());
+        for &(before, after) in events.iter() {
+            assert!(after >= before + 1024 * 1024, "{before} -> {after}");
+        }
+        // Each notification starts from the size reported by the previous one.
+        for window in events.windows(2) {
+            assert_eq!(window[0].1, window[1].0);
+        }
+    }
+}
@@ -6,9 +6,10 @@ use ahash::AHashMap;
 use small_string::SmallString;
 
 use super::{
-    CompactionLists, HeapMarkAndSweep, WorkQueues,
+    CompactionLists, ElementArrayClassStats, ElementArrayStats, HeapMarkAndSweep, WorkQueues,
     indexes::{ElementIndex, PropertyKeyIndex},
     object_entry::{ObjectEntry, ObjectEntryPropertyDescriptor},
+    snapshot::{HeapSnapshot, SnapshotError, SnapshotReader, SnapshotWriter},
 };
 use crate::{
     SmallInteger,
@@ -402,6 +403,7 @@ impl<'gc> ElementsVector<'gc> {
                 .insert(self.len, descriptor.unbind());
         }
         self.len += 1;
+        elements.log_values_write(self);
     }
 
     pub(crate) fn remove(&mut self, elements: &mut ElementArrays, index: usize) {
@@ -437,6 +439,7 @@ impl<'gc> ElementsVector<'gc> {
         };
 
         self.len -= 1;
+        elements.log_values_write(self);
     }
 }
 
@@ -655,6 +658,7 @@ impl<'a> PropertyStorageVector<'a> {
                 .insert(self.len, descriptor.unbind());
         }
         self.len += 1;
+        elements.log_properties_write(self);
     }
 
     pub(crate) fn remove(&mut self, elements: &mut ElementArrays, index: usize) {
@@ -698,6 +702,7 @@ impl<'a> PropertyStorageVector<'a> {
         };
 
         self.len -= 1;
+        elements.log_properties_write(self);
     }
 }
 
@@ -1328,7 +1333,11 @@ impl<'a> ElementDescriptor<'a> {
     }
 
     pub(crate) fn getter_function<'gc>(&self, gc: NoGcScope<'gc, '_>) -> Option<Function<'gc>> {
-        match self {
+        self.getter().map(|get| get.bind(gc))
+    }
+
+    pub(crate) fn getter(&self) -> Option<Function<'a>> {
+        match *self {
             ElementDescriptor::ReadOnlyEnumerableConfigurableAccessor { get }
             | ElementDescriptor::ReadOnlyEnumerableUnconfigurableAccessor { get }
             | ElementDescriptor::ReadOnlyUnenumerableConfigurableAccessor { get }
@@ -1337,14 +1346,18 @@ impl<'a> ElementDescriptor<'a> {
             | ElementDescriptor::ReadWriteEnumerableUnconfigurableAccessor { get, .. }
             | ElementDescriptor::ReadWriteUnenumerableConfigurableAccessor { get, .. }
             | ElementDescriptor::ReadWriteUnenumerableUnconfigurableAccessor { get, .. } => {
-                Some(get.bind(gc))
+                Some(get)
             }
             _ => None,
         }
     }
 
     pub(crate) fn setter_function<'gc>(&self, gc: NoGcScope<'gc, '_>) -> Option<Function<'gc>> {
-        match self {
+        self.setter().map(|set| set.bind(gc))
+    }
+
+    pub(crate) fn setter(&self) -> Option<Function<'a>> {
+        match *self {
             ElementDescriptor::WriteOnlyEnumerableConfigurableAccessor { set }
             | ElementDescriptor::WriteOnlyEnumerableUnconfigurableAccessor { set }
             | ElementDescriptor::WriteOnlyUnenumerableConfigurableAccessor { set }
@@ -1353,7 +1366,7 @@ impl<'a> ElementDescriptor<'a> {
             | ElementDescriptor::ReadWriteEnumerableUnconfigurableAccessor { set, .. }
             | ElementDescriptor::ReadWriteUnenumerableConfigurableAccessor { set, .. }
             | ElementDescriptor::ReadWriteUnenumerableUnconfigurableAccessor { set, .. } => {
-                Some(set.bind(gc))
+                Some(set)
             }
             _ => None,
         }
@@ -1600,6 +1613,29 @@ impl<const N: usize> ElementArray<N> {
             }
         }
     }
+
+    /// Pushes a copy of the first `length` values and the descriptors of the
+    /// element array at `index`, with the references in them shifted by
+    /// `compactions`.
+    pub(crate) fn push_copy(
+        &mut self,
+        index: ElementIndex,
+        length: u32,
+        compactions: &CompactionLists,
+    ) -> ElementIndex<'static> {
+        let mut values: Box<[Option<Value>]> = self.values[index.into_index()]
+            .as_ref()
+            .map_or(Box::default(), |values| values[..length as usize].into());
+        values.sweep_values(compactions);
+        let descriptors = self.descriptors.get(&index.unbind()).map(|descriptors| {
+            let mut descriptors = descriptors.clone();
+            for descriptor in descriptors.values_mut() {
+                descriptor.sweep_values(compactions);
+            }
+            descriptors
+        });
+        self.push(&values, descriptors)
+    }
 }
 
 /// Element arrays of up to 16 elements
@@ -1686,6 +1722,22 @@ impl<const N: usize> PropertyKeyArray<N> {
         keys.copy_within((index + 1)..len, index);
         keys[len - 1] = None;
     }
+
+    /// Pushes a copy of the first `length` keys of the property key array at
+    /// `index`, with the references in them shifted by `compactions`.
+    pub(crate) fn push_copy(
+        &mut self,
+        index: PropertyKeyIndex,
+        length: u32,
+        compactions: &CompactionLists,
+    ) -> PropertyKeyIndex<'static> {
+        let mut keys: Box<[PropertyKey]> = self.keys[index.into_index()][..length as usize]
+            .iter()
+            .map(|key| key.unwrap())
+            .collect();
+        keys.sweep_values(compactions);
+        self.push(&keys)
+    }
 }
 
 /// Property key arrays of up to 16 elements
@@ -1731,6 +1783,12 @@ pub struct ElementArrays {
     /// up to 4294967296 elements
     pub k2pow32: PropertyKeyArray2Pow32,
     pub e2pow32: ElementArray2Pow32,
+    /// Storage written to while incremental garbage collection is marking.
+    ///
+    /// Element and property key storage is not a heap data entry of its own
+    /// and cannot go through the write barrier of its owner, so writes to it
+    /// are logged here instead.
+    pub(crate) write_log: Option<Box<WorkQueues>>,
 }
 
 impl Index<&ElementsVector<'_>> for ElementArrays {
@@ -1762,6 +1820,86 @@ impl IndexMut<&ElementsVector<'_>> for Agent {
 }
 
 impl ElementArrays {
+    /// Returns the number of bytes reserved by element and property key
+    /// storage.
+    pub(crate) fn reserved_bytes(&self) -> usize {
+        fn bytes<T>(vector: &Vec<T>) -> usize {
+            vector.capacity() * core::mem::size_of::<T>()
+        }
+        bytes(&self.e2pow4.values)
+            + bytes(&self.e2pow6.values)
+            + bytes(&self.e2pow8.values)
+            + bytes(&self.e2pow10.values)
+            + bytes(&self.e2pow12.values)
+            + bytes(&self.e2pow16.values)
+            + bytes(&self.e2pow24.values)
+            + bytes(&self.e2pow32.values)
+            + bytes(&self.k2pow4.keys)
+            + bytes(&self.k2pow6.keys)
+            + bytes(&self.k2pow8.keys)
+            + bytes(&self.k2pow10.keys)
+            + bytes(&self.k2pow12.keys)
+            + bytes(&self.k2pow16.keys)
+            + bytes(&self.k2pow24.keys)
+            + bytes(&self.k2pow32.keys)
+    }
+
+    /// Returns the number of live vectors and slots in each capacity class.
+    pub(crate) fn stats(&self) -> ElementArrayStats {
+        fn class<const N: usize>(
+            class: ElementArrayKey,
+            values: &ElementArray<N>,
+            keys: &PropertyKeyArray<N>,
+        ) -> ElementArrayClassStats {
+            ElementArrayClassStats {
+                class,
+                live_vectors: values.values.iter().filter(|slot| slot.is_some()).count(),
+                total_slots: values.values.len(),
+                property_key_vectors: keys.keys.len(),
+            }
+        }
+        ElementArrayStats {
+            classes: [
+                class(ElementArrayKey::E4, &self.e2pow4, &self.k2pow4),
+                class(ElementArrayKey::E6, &self.e2pow6, &self.k2pow6),
+                class(ElementArrayKey::E8, &self.e2pow8, &self.k2pow8),
+                class(ElementArrayKey::E10, &self.e2pow10, &self.k2pow10),
+                class(ElementArrayKey::E12, &self.e2pow12, &self.k2pow12),
+                class(ElementArrayKey::E16, &self.e2pow16, &self.k2pow16),
+                class(ElementArrayKey::E24, &self.e2pow24, &self.k2pow24),
+                class(ElementArrayKey::E32, &self.e2pow32, &self.k2pow32),
+            ],
+        }
+    }
+
+    /// Write barrier for values storage: Records the values of `vector` as
+    /// written if incremental garbage collection is marking.
+    fn log_values_write(&mut self, vector: &impl ElementsIndexable) {
+        let Some(log) = self.write_log.as_deref_mut() else {
+            return;
+        };
+        let entry = (vector.elements_index(), vector.len());
+        match vector.cap() {
+            ElementArrayKey::Empty => {}
+            ElementArrayKey::E4 => log.e_2_4.push(entry),
+            ElementArrayKey::E6 => log.e_2_6.push(entry),
+            ElementArrayKey::E8 => log.e_2_8.push(entry),
+            ElementArrayKey::E10 => log.e_2_10.push(entry),
+            ElementArrayKey::E12 => log.e_2_12.push(entry),
+            ElementArrayKey::E16 => log.e_2_16.push(entry),
+            ElementArrayKey::E24 => log.e_2_24.push(entry),
+            ElementArrayKey::E32 => log.e_2_32.push(entry),
+        }
+    }
+
+    /// Write barrier for property storage: Records the keys and values of
+    /// `props` as written if incremental garbage collection is marking.
+    fn log_properties_write(&mut self, props: &PropertyStorageVector) {
+        if let Some(log) = self.write_log.as_deref_mut() {
+            props.unbind().mark_values(log);
+        }
+    }
+
     fn push_values(
         &mut self,
         key: ElementArrayKey,
@@ -1888,6 +2026,7 @@ impl ElementArrays {
             k2pow16,
             k2pow24,
             k2pow32,
+            write_log: _,
         } = self;
         let (new_keys_index, new_values_index) = match new_key {
             ElementArrayKey::Empty => {
@@ -2127,6 +2266,7 @@ impl ElementArrays {
         props.cap = new_key;
         props.keys_index = new_keys_index;
         props.values_index = new_values_index;
+        self.log_properties_write(props);
     }
 
     fn reserve_elements(&mut self, elements_vector: &mut ElementsVector, new_len: u32) {
@@ -2307,6 +2447,7 @@ impl ElementArrays {
         };
         elements_vector.cap = new_key;
         elements_vector.elements_index = new_index;
+        self.log_values_write(elements_vector);
     }
 
     pub(crate) fn allocate_elements_with_capacity(
@@ -2322,6 +2463,20 @@ impl ElementArrays {
         }
     }
 
+    pub(crate) fn allocate_property_storage_with_capacity(
+        &mut self,
+        capacity: usize,
+    ) -> PropertyStorageVector<'static> {
+        let cap = ElementArrayKey::from(capacity);
+        PropertyStorageVector {
+            keys_index: self.push_keys(cap, &[]),
+            values_index: self.push_values(cap, &[], None),
+            cap,
+            len: 0,
+            extensible: true,
+        }
+    }
+
     fn allocate_object_property_storage(
         &mut self,
         length: usize,
@@ -2419,6 +2574,7 @@ impl ElementArrays {
         &mut self,
         props: &PropertyStorageVector,
     ) -> &mut [Option<PropertyKey<'static>>] {
+        self.log_properties_write(props);
         match props.cap {
             ElementArrayKey::Empty => &mut [],
             ElementArrayKey::E4 => self.k2pow4.get_uninit(props),
@@ -2450,6 +2606,7 @@ impl ElementArrays {
         &mut self,
         vector: &impl ElementsIndexable,
     ) -> &mut [Option<Value<'static>>] {
+        self.log_values_write(vector);
         match vector.cap() {
             ElementArrayKey::Empty => &mut [],
             ElementArrayKey::E4 => self.e2pow4.get_values_mut(vector),
@@ -2470,6 +2627,7 @@ impl ElementArrays {
         &mut self,
         vector: &impl ElementsIndexable,
     ) -> &mut [Option<Value<'static>>] {
+        self.log_values_write(vector);
         match vector.cap() {
             ElementArrayKey::Empty => &mut [],
             ElementArrayKey::E4 => self.e2pow4.get_values_uninit(vector),
@@ -2512,6 +2670,7 @@ impl ElementArrays {
         &mut self,
         vector: &impl ElementsIndexable,
     ) -> ElementStorageMut {
+        self.log_values_write(vector);
         match vector.cap() {
             ElementArrayKey::Empty => unreachable!(),
             ElementArrayKey::E4 => self.e2pow4.get_descriptors_and_values_mut(vector),
@@ -2535,6 +2694,7 @@ impl ElementArrays {
         &mut self,
         vector: &impl ElementsIndexable,
     ) -> ElementStorageUninit {
+        self.log_values_write(vector);
         match vector.cap() {
             ElementArrayKey::Empty => unreachable!(),
             ElementArrayKey::E4 => self.e2pow4.get_descriptors_and_values_uninit(vector),
@@ -2667,6 +2827,7 @@ impl ElementArrays {
         &mut self,
         props: &PropertyStorageVector<'gc>,
     ) -> Option<PropertyStorageMut<'_, 'gc>> {
+        self.log_properties_write(props);
         match props.cap {
             ElementArrayKey::Empty => None,
             ElementArrayKey::E4 => {
@@ -2773,6 +2934,7 @@ impl ElementArrays {
         &mut self,
         props: &PropertyStorageVector,
     ) -> PropertyStorageUninit {
+        self.log_properties_write(props);
         match props.cap {
             // It doesn't make sense to try access an empty storage.
             ElementArrayKey::Empty => unreachable!(),
@@ -2906,6 +3068,7 @@ impl ElementArrays {
         index: usize,
         descriptor: Option<ElementDescriptor>,
     ) {
+        self.log_values_write(vector);
         let index: u32 = index.try_into().unwrap();
         assert!(index < vector.len());
         let descriptors = match vector.cap() {
@@ -3200,3 +3363,250 @@ impl HeapMarkAndSweep for PropertyStorageVector<'static> {
         }
     }
 }
+
+impl HeapSnapshot for ElementArrayKey {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        writer.write_u8(*self as u8);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        let key = match reader.read_u8()? {
+            0 => ElementArrayKey::Empty,
+            1 => ElementArrayKey::E4,
+            2 => ElementArrayKey::E6,
+            3 => ElementArrayKey::E8,
+            4 => ElementArrayKey::E10,
+            5 => ElementArrayKey::E12,
+            6 => ElementArrayKey::E16,
+            7 => ElementArrayKey::E24,
+            8 => ElementArrayKey::E32,
+            _ => return Err(SnapshotError::Corrupted),
+        };
+        Ok(key)
+    }
+}
+
+impl HeapSnapshot for ElementsVector<'static> {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        let Self {
+            elements_index,
+            cap,
+            len,
+            len_writable,
+        } = self;
+        elements_index.write_snapshot(writer);
+        cap.write_snapshot(writer);
+        len.write_snapshot(writer);
+        len_writable.write_snapshot(writer);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        Ok(Self {
+            elements_index: HeapSnapshot::read_snapshot(reader)?,
+            cap: HeapSnapshot::read_snapshot(reader)?,
+            len: HeapSnapshot::read_snapshot(reader)?,
+            len_writable: HeapSnapshot::read_snapshot(reader)?,
+        })
+    }
+}
+
+impl HeapSnapshot for PropertyStorageVector<'static> {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        let Self {
+            keys_index,
+            values_index,
+            cap,
+            len,
+            extensible,
+        } = self;
+        keys_index.write_snapshot(writer);
+        values_index.write_snapshot(writer);
+        cap.write_snapshot(writer);
+        len.write_snapshot(writer);
+        extensible.write_snapshot(writer);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        Ok(Self {
+            keys_index: HeapSnapshot::read_snapshot(reader)?,
+            values_index: HeapSnapshot::read_snapshot(reader)?,
+            cap: HeapSnapshot::read_snapshot(reader)?,
+            len: HeapSnapshot::read_snapshot(reader)?,
+            extensible: HeapSnapshot::read_snapshot(reader)?,
+        })
+    }
+}
+
+impl HeapSnapshot for ElementDescriptor<'static> {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        self.is_enumerable().write_snapshot(writer);
+        self.is_configurable().write_snapshot(writer);
+        match self.is_writable() {
+            Some(writable) => {
+                writer.write_u8(0);
+                writable.write_snapshot(writer);
+            }
+            None => {
+                writer.write_u8(1);
+                self.getter().write_snapshot(writer);
+                self.setter().write_snapshot(writer);
+            }
+        }
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        let e = bool::read_snapshot(reader)?;
+        let c = bool::read_snapshot(reader)?;
+        let descriptor = match reader.read_u8()? {
+            0 => {
+                let w = bool::read_snapshot(reader)?;
+                ElementDescriptor::new_with_wec(w, e, c)
+                    .unwrap_or(ElementDescriptor::WritableEnumerableConfigurableData)
+            }
+            1 => match (
+                Option::read_snapshot(reader)?,
+                Option::read_snapshot(reader)?,
+            ) {
+                (Some(get), Some(set)) => ElementDescriptor::new_with_get_set_ec(get, set, e, c),
+                (Some(get), None) => ElementDescriptor::new_with_get_ec(get, e, c),
+                (None, Some(set)) => ElementDescriptor::new_with_set_ec(set, e, c),
+                (None, None) => return Err(SnapshotError::Corrupted),
+            },
+            _ => return Err(SnapshotError::Corrupted),
+        };
+        Ok(descriptor)
+    }
+}
+
+impl<const N: usize> HeapSnapshot for ElementArray<N> {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        let Self {
+            values,
+            descriptors,
+        } = self;
+        writer.write_len(values.len());
+        for (index, values) in values.iter().enumerate() {
+            let values = values
+                .as_ref()
+                .expect("Startup snapshots cannot contain released elements");
+            let len = values
+                .iter()
+                .rposition(Option::is_some)
+                .map_or(0, |i| i + 1);
+            writer.write_len(len);
+            for value in &values[..len] {
+                value.write_snapshot(writer);
+            }
+            match descriptors.get(&ElementIndex::from_index(index)) {
+                Some(descriptors) => {
+                    writer.write_u8(1);
+                    descriptors.write_snapshot(writer);
+                }
+                None => writer.write_u8(0),
+            }
+        }
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        let len = reader.read_len()?;
+        let mut result = Self::with_capacity(len);
+        for _ in 0..len {
+            let values = Vec::<Option<Value<'static>>>::read_snapshot(reader)?;
+            if values.len() > N {
+                return Err(SnapshotError::Corrupted);
+            }
+            let descriptors = Option::read_snapshot(reader)?;
+            result.push(&values, descriptors);
+        }
+        Ok(result)
+    }
+}
+
+impl<const N: usize> HeapSnapshot for PropertyKeyArray<N> {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        let Self { keys } = self;
+        writer.write_len(keys.len());
+        for keys in keys {
+            let len = keys.iter().position(Option::is_none).unwrap_or(N);
+            writer.write_len(len);
+            for key in &keys[..len] {
+                key.unwrap().write_snapshot(writer);
+            }
+        }
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        let len = reader.read_len()?;
+        let mut result = Self::with_capacity(len);
+        for _ in 0..len {
+            let keys = Vec::<PropertyKey<'static>>::read_snapshot(reader)?;
+            if keys.len() > N {
+                return Err(SnapshotError::Corrupted);
+            }
+            result.push(&keys);
+        }
+        Ok(result)
+    }
+}
+
+impl HeapSnapshot for ElementArrays {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        let Self {
+            k2pow4,
+            e2pow4,
+            k2pow6,
+            e2pow6,
+            k2pow8,
+            e2pow8,
+            k2pow10,
+            e2pow10,
+            k2pow12,
+            e2pow12,
+            k2pow16,
+            e2pow16,
+            k2pow24,
+            e2pow24,
+            k2pow32,
+            e2pow32,
+            write_log: _,
+        } = self;
+        k2pow4.write_snapshot(writer);
+        e2pow4.write_snapshot(writer);
+        k2pow6.write_snapshot(writer);
+        e2pow6.write_snapshot(writer);
+        k2pow8.write_snapshot(writer);
+        e2pow8.write_snapshot(writer);
+        k2pow10.write_snapshot(writer);
+        e2pow10.write_snapshot(writer);
+        k2pow12.write_snapshot(writer);
+        e2pow12.write_snapshot(writer);
+        k2pow16.write_snapshot(writer);
+        e2pow16.write_snapshot(writer);
+        k2pow24.write_snapshot(writer);
+        e2pow24.write_snapshot(writer);
+        k2pow32.write_snapshot(writer);
+        e2pow32.write_snapshot(writer);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        Ok(Self {
+            k2pow4: HeapSnapshot::read_snapshot(reader)?,
+            e2pow4: HeapSnapshot::read_snapshot(reader)?,
+            k2pow6: HeapSnapshot::read_snapshot(reader)?,
+            e2pow6: HeapSnapshot::read_snapshot(reader)?,
+            k2pow8: HeapSnapshot::read_snapshot(reader)?,
+            e2pow8: HeapSnapshot::read_snapshot(reader)?,
+            k2pow10: HeapSnapshot::read_snapshot(reader)?,
+            e2pow10: HeapSnapshot::read_snapshot(reader)?,
+            k2pow12: HeapSnapshot::read_snapshot(reader)?,
+            e2pow12: HeapSnapshot::read_snapshot(reader)?,
+            k2pow16: HeapSnapshot::read_snapshot(reader)?,
+            e2pow16: HeapSnapshot::read_snapshot(reader)?,
+            k2pow24: HeapSnapshot::read_snapshot(reader)?,
+            e2pow24: HeapSnapshot::read_snapshot(reader)?,
+            k2pow32: HeapSnapshot::read_snapshot(reader)?,
+            e2pow32: HeapSnapshot::read_snapshot(reader)?,
+            write_log: None,
+        })
+    }
+}
@@ -15,8 +15,6 @@ use super::{
 };
 #[cfg(feature = "date")]
 use crate::ecmascript::builtins::date::Date;
-#[cfg(feature = "regexp")]
-use crate::ecmascript::builtins::regexp::RegExp;
 #[cfg(feature = "shared-array-buffer")]
 use crate::ecmascript::builtins::shared_array_buffer::SharedArrayBuffer;
 #[cfg(feature = "array-buffer")]
@@ -25,6 +23,11 @@ use crate::ecmascript::builtins::{ArrayBuffer, data_view::DataView};
 use crate::ecmascript::builtins::{
     keyed_collections::set_objects::set_iterator_objects::set_iterator::SetIterator, set::Set,
 };
+#[cfg(feature = "regexp")]
+use crate::ecmascript::builtins::{
+    regexp::RegExp,
+    text_processing::regexp_objects::regexp_string_iterator_objects::RegExpStringIterator,
+};
 #[cfg(feature = "weak-refs")]
 use crate::ecmascript::builtins::{weak_map::WeakMap, weak_ref::WeakRef, weak_set::WeakSet};
 use crate::ecmascript::{
@@ -126,6 +129,8 @@ pub struct HeapBits {
     pub sets: Box<[bool]>,
     #[cfg(feature = "set")]
     pub set_iterators: Box<[bool]>,
+    #[cfg(feature = "regexp")]
+    pub reg_exp_string_iterators: Box<[bool]>,
     #[cfg(feature = "shared-array-buffer")]
     pub shared_array_buffers: Box<[bool]>,
     pub string_iterators: Box<[bool]>,
@@ -141,7 +146,7 @@ pub struct HeapBits {
     pub weak_sets: Box<[bool]>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub(crate) struct WorkQueues {
     #[cfg(feature = "array-buffer")]
     pub array_buffers: Vec<ArrayBuffer<'static>>,
@@ -203,6 +208,8 @@ pub(crate) struct WorkQueues {
     pub sets: Vec<Set<'static>>,
     #[cfg(feature = "set")]
     pub set_iterators: Vec<SetIterator<'static>>,
+    #[cfg(feature = "regexp")]
+    pub reg_exp_string_iterators: Vec<RegExpStringIterator<'static>>,
     #[cfg(feature = "shared-array-buffer")]
     pub shared_array_buffers: Vec<SharedArrayBuffer<'static>>,
     pub string_iterators: Vec<StringIterator<'static>>,
@@ -280,6 +287,8 @@ impl HeapBits {
         let sets = vec![false; heap.sets.len()];
         #[cfg(feature = "set")]
         let set_iterators = vec![false; heap.set_iterators.len()];
+        #[cfg(feature = "regexp")]
+        let reg_exp_string_iterators = vec![false; heap.reg_exp_string_iterators.len()];
         #[cfg(feature = "shared-array-buffer")]
         let shared_array_buffers = vec![false; heap.shared_array_buffers.len()];
         let string_iterators = vec![false; heap.string_iterators.len()];
@@ -354,6 +363,8 @@ impl HeapBits {
             sets: sets.into_boxed_slice(),
             #[cfg(feature = "set")]
             set_iterators: set_iterators.into_boxed_slice(),
+            #[cfg(feature = "regexp")]
+            reg_exp_string_iterators: reg_exp_string_iterators.into_boxed_slice(),
             #[cfg(feature = "shared-array-buffer")]
             shared_array_buffers: shared_array_buffers.into_boxed_slice(),
             string_iterators: string_iterators.into_boxed_slice(),
@@ -369,6 +380,130 @@ impl HeapBits {
             weak_sets: weak_sets.into_boxed_slice(),
         }
     }
+
+    /// Extends the mark bits to cover heap data allocated since the bits were
+    /// created. The new entries start out unmarked.
+    pub(crate) fn grow(&mut self, heap: &Heap) {
+        #[cfg(feature = "array-buffer")]
+        grow_bits(&mut self.array_buffers, heap.array_buffers.len());
+        grow_bits(&mut self.arrays, heap.arrays.len());
+        grow_bits(&mut self.array_iterators, heap.array_iterators.len());
+        grow_bits(&mut self.async_generators, heap.async_generators.len());
+        grow_bits(&mut self.await_reactions, heap.await_reactions.len());
+        grow_bits(&mut self.bigints, heap.bigints.len());
+        grow_bits(&mut self.bound_functions, heap.bound_functions.len());
+        grow_bits(
+            &mut self.builtin_constructors,
+            heap.builtin_constructors.len(),
+        );
+        grow_bits(&mut self.builtin_functions, heap.builtin_functions.len());
+        #[cfg(feature = "array-buffer")]
+        grow_bits(&mut self.data_views, heap.data_views.len());
+        #[cfg(feature = "date")]
+        grow_bits(&mut self.dates, heap.dates.len());
+        grow_bits(
+            &mut self.declarative_environments,
+            heap.environments.declarative.len(),
+        );
+        grow_bits(&mut self.e_2_10, heap.elements.e2pow10.values.len());
+        grow_bits(&mut self.e_2_12, heap.elements.e2pow12.values.len());
+        grow_bits(&mut self.e_2_16, heap.elements.e2pow16.values.len());
+        grow_bits(&mut self.e_2_24, heap.elements.e2pow24.values.len());
+        grow_bits(&mut self.e_2_32, heap.elements.e2pow32.values.len());
+        grow_bits(&mut self.e_2_4, heap.elements.e2pow4.values.len());
+        grow_bits(&mut self.e_2_6, heap.elements.e2pow6.values.len());
+        grow_bits(&mut self.e_2_8, heap.elements.e2pow8.values.len());
+        grow_bits(&mut self.k_2_10, heap.elements.k2pow10.keys.len());
+        grow_bits(&mut self.k_2_12, heap.elements.k2pow12.keys.len());
+        grow_bits(&mut self.k_2_16, heap.elements.k2pow16.keys.len());
+        grow_bits(&mut self.k_2_24, heap.elements.k2pow24.keys.len());
+        grow_bits(&mut self.k_2_32, heap.elements.k2pow32.keys.len());
+        grow_bits(&mut self.k_2_4, heap.elements.k2pow4.keys.len());
+        grow_bits(&mut self.k_2_6, heap.elements.k2pow6.keys.len());
+        grow_bits(&mut self.k_2_8, heap.elements.k2pow8.keys.len());
+        grow_bits(
+            &mut self.ecmascript_functions,
+            heap.ecmascript_functions.len(),
+        );
+        grow_bits(&mut self.embedder_objects, heap.embedder_objects.len());
+        grow_bits(&mut self.errors, heap.errors.len());
+        grow_bits(&mut self.executables, heap.executables.len());
+        grow_bits(&mut self.source_codes, heap.source_codes.len());
+        grow_bits(
+            &mut self.finalization_registrys,
+            heap.finalization_registrys.len(),
+        );
+        grow_bits(
+            &mut self.function_environments,
+            heap.environments.function.len(),
+        );
+        grow_bits(&mut self.generators, heap.generators.len());
+        grow_bits(
+            &mut self.global_environments,
+            heap.environments.global.len(),
+        );
+        grow_bits(&mut self.maps, heap.maps.len());
+        grow_bits(&mut self.map_iterators, heap.map_iterators.len());
+        grow_bits(&mut self.modules, heap.modules.len());
+        grow_bits(&mut self.numbers, heap.numbers.len());
+        grow_bits(
+            &mut self.object_environments,
+            heap.environments.object.len(),
+        );
+        grow_bits(&mut self.objects, heap.objects.len());
+        grow_bits(&mut self.primitive_objects, heap.primitive_objects.len());
+        grow_bits(
+            &mut self.promise_reaction_records,
+            heap.promise_reaction_records.len(),
+        );
+        grow_bits(
+            &mut self.promise_resolving_functions,
+            heap.promise_resolving_functions.len(),
+        );
+        grow_bits(
+            &mut self.private_environments,
+            heap.environments.private.len(),
+        );
+        grow_bits(&mut self.promises, heap.promises.len());
+        grow_bits(&mut self.proxys, heap.proxys.len());
+        grow_bits(&mut self.realms, heap.realms.len());
+        #[cfg(feature = "regexp")]
+        grow_bits(&mut self.regexps, heap.regexps.len());
+        grow_bits(&mut self.scripts, heap.scripts.len());
+        #[cfg(feature = "set")]
+        grow_bits(&mut self.sets, heap.sets.len());
+        #[cfg(feature = "set")]
+        grow_bits(&mut self.set_iterators, heap.set_iterators.len());
+        #[cfg(feature = "regexp")]
+        grow_bits(
+            &mut self.reg_exp_string_iterators,
+            heap.reg_exp_string_iterators.len(),
+        );
+        #[cfg(feature = "shared-array-buffer")]
+        grow_bits(
+            &mut self.shared_array_buffers,
+            heap.shared_array_buffers.len(),
+        );
+        grow_bits(&mut self.string_iterators, heap.string_iterators.len());
+        grow_bits(&mut self.strings, heap.strings.len());
+        grow_bits(&mut self.symbols, heap.symbols.len());
+        #[cfg(feature = "array-buffer")]
+        grow_bits(&mut self.typed_arrays, heap.typed_arrays.len());
+        #[cfg(feature = "weak-refs")]
+        grow_bits(&mut self.weak_maps, heap.weak_maps.len());
+        #[cfg(feature = "weak-refs")]
+        grow_bits(&mut self.weak_refs, heap.weak_refs.len());
+        #[cfg(feature = "weak-refs")]
+        grow_bits(&mut self.weak_sets, heap.weak_sets.len());
+    }
+}
+
+fn grow_bits<T: Clone + Default>(bits: &mut Box<[T]>, len: usize) {
+    if bits.len() < len {
+        let mut grown = core::mem::take(bits).into_vec();
+        grown.resize(len, T::default());
+        *bits = grown.into_boxed_slice();
+    }
 }
 
 impl WorkQueues {
@@ -436,6 +571,8 @@ impl WorkQueues {
             sets: Vec::with_capacity(heap.sets.len() / 4),
             #[cfg(feature = "set")]
             set_iterators: Vec::with_capacity(heap.set_iterators.len() / 4),
+            #[cfg(feature = "regexp")]
+            reg_exp_string_iterators: Vec::with_capacity(heap.reg_exp_string_iterators.len() / 4),
             #[cfg(feature = "shared-array-buffer")]
             shared_array_buffers: Vec::with_capacity(heap.shared_array_buffers.len() / 4),
             string_iterators: Vec::with_capacity(heap.string_iterators.len() / 4),
@@ -514,6 +651,8 @@ impl WorkQueues {
             sets,
             #[cfg(feature = "set")]
             set_iterators,
+            #[cfg(feature = "regexp")]
+            reg_exp_string_iterators,
             #[cfg(feature = "shared-array-buffer")]
             shared_array_buffers,
             string_iterators,
@@ -547,6 +686,8 @@ impl WorkQueues {
         let weak_sets: &[bool; 0] = &[];
         #[cfg(not(feature = "regexp"))]
         let regexps: &[bool; 0] = &[];
+        #[cfg(not(feature = "regexp"))]
+        let reg_exp_string_iterators: &[bool; 0] = &[];
         #[cfg(not(feature = "set"))]
         let sets: &[bool; 0] = &[];
         #[cfg(not(feature = "set"))]
@@ -602,6 +743,7 @@ impl WorkQueues {
             && proxys.is_empty()
             && realms.is_empty()
             && regexps.is_empty()
+            && reg_exp_string_iterators.is_empty()
             && scripts.is_empty()
             && sets.is_empty()
             && set_iterators.is_empty()
@@ -614,12 +756,376 @@ impl WorkQueues {
             && weak_refs.is_empty()
             && weak_sets.is_empty()
     }
+
+    /// Moves heap data written to since marking started from `written` into
+    /// these queues.
+    ///
+    /// Written heap data that has already been marked is unmarked so that it
+    /// is scanned again; unmarked data is left for marking to find. Element
+    /// and property key storage is always queued, as a write may have grown
+    /// its length past what was marked: queueing storage that has already
+    /// been marked rescans it.
+    pub(crate) fn requeue_written(&mut self, written: &mut WorkQueues, bits: &mut HeapBits) {
+        #[cfg(feature = "array-buffer")]
+        requeue_marked(
+            &mut written.array_buffers,
+            &mut self.array_buffers,
+            &mut bits.array_buffers,
+            |h| h.get_index(),
+        );
+        requeue_marked(
+            &mut written.arrays,
+            &mut self.arrays,
+            &mut bits.arrays,
+            |h| h.get_index(),
+        );
+        requeue_marked(
+            &mut written.array_iterators,
+            &mut self.array_iterators,
+            &mut bits.array_iterators,
+            |h| h.get_index(),
+        );
+        requeue_marked(
+            &mut written.async_generators,
+            &mut self.async_generators,
+            &mut bits.async_generators,
+            |h| h.get_index(),
+        );
+        requeue_marked(
+            &mut written.await_reactions,
+            &mut self.await_reactions,
+            &mut bits.await_reactions,
+            |h| h.into_index(),
+        );
+        requeue_marked(
+            &mut written.bigints,
+            &mut self.bigints,
+            &mut bits.bigints,
+            |h| h.get_index(),
+        );
+        requeue_marked(
+            &mut written.bound_functions,
+            &mut self.bound_functions,
+            &mut bits.bound_functions,
+            |h| h.get_index(),
+        );
+        requeue_marked(
+            &mut written.builtin_constructors,
+            &mut self.builtin_constructors,
+            &mut bits.builtin_constructors,
+            |h| h.get_index(),
+        );
+        requeue_marked(
+            &mut written.builtin_functions,
+            &mut self.builtin_functions,
+            &mut bits.builtin_functions,
+            |h| h.get_index(),
+        );
+        #[cfg(feature = "array-buffer")]
+        requeue_marked(
+            &mut written.data_views,
+            &mut self.data_views,
+            &mut bits.data_views,
+            |h| h.get_index(),
+        );
+        #[cfg(feature = "date")]
+        requeue_marked(&mut written.dates, &mut self.dates, &mut bits.dates, |h| {
+            h.get_index()
+        });
+        requeue_marked(
+            &mut written.declarative_environments,
+            &mut self.declarative_environments,
+            &mut bits.declarative_environments,
+            |h| h.into_index(),
+        );
+        self.e_2_10.append(&mut written.e_2_10);
+        self.e_2_12.append(&mut written.e_2_12);
+        self.e_2_16.append(&mut written.e_2_16);
+        self.e_2_24.append(&mut written.e_2_24);
+        self.e_2_32.append(&mut written.e_2_32);
+        self.e_2_4.append(&mut written.e_2_4);
+        self.e_2_6.append(&mut written.e_2_6);
+        self.e_2_8.append(&mut written.e_2_8);
+        self.k_2_10.append(&mut written.k_2_10);
+        self.k_2_12.append(&mut written.k_2_12);
+        self.k_2_16.append(&mut written.k_2_16);
+        self.k_2_24.append(&mut written.k_2_24);
+        self.k_2_32.append(&mut written.k_2_32);
+        self.k_2_4.append(&mut written.k_2_4);
+        self.k_2_6.append(&mut written.k_2_6);
+        self.k_2_8.append(&mut written.k_2_8);
+        requeue_marked(
+            &mut written.ecmascript_functions,
+            &mut self.ecmascript_functions,
+            &mut bits.ecmascript_functions,
+            |h| h.get_index(),
+        );
+        requeue_marked(
+            &mut written.embedder_objects,
+            &mut self.embedder_objects,
+            &mut bits.embedder_objects,
+            |h| h.get_index(),
+        );
+        requeue_marked(
+            &mut written.source_codes,
+            &mut self.source_codes,
+            &mut bits.source_codes,
+            |h| h.get_index(),
+        );
+        requeue_marked(
+            &mut written.errors,
+            &mut self.errors,
+            &mut bits.errors,
+            |h| h.get_index(),
+        );
+        requeue_marked(
+            &mut written.executables,
+            &mut self.executables,
+            &mut bits.executables,
+            |h| h.get_index(),
+        );
+        requeue_marked(
+            &mut written.finalization_registrys,
+            &mut self.finalization_registrys,
+            &mut bits.finalization_registrys,
+            |h| h.get_index(),
+        );
+        requeue_marked(
+            &mut written.function_environments,
+            &mut self.function_environments,
+            &mut bits.function_environments,
+            |h| h.into_index(),
+        );
+        requeue_marked(
+            &mut written.generators,
+            &mut self.generators,
+            &mut bits.generators,
+            |h| h.get_index(),
+        );
+        requeue_marked(
+            &mut written.global_environments,
+            &mut self.global_environments,
+            &mut bits.global_environments,
+            |h| h.into_index(),
+        );
+        requeue_marked(&mut written.maps, &mut self.maps, &mut bits.maps, |h| {
+            h.get_index()
+        });
+        requeue_marked(
+            &mut written.map_iterators,
+            &mut self.map_iterators,
+            &mut bits.map_iterators,
+            |h| h.get_index(),
+        );
+        requeue_marked(
+            &mut written.modules,
+            &mut self.modules,
+            &mut bits.modules,
+            |h| h.get_index(),
+        );
+        requeue_marked(
+            &mut written.numbers,
+            &mut self.numbers,
+            &mut bits.numbers,
+            |h| h.get_index(),
+        );
+        requeue_marked(
+            &mut written.object_environments,
+            &mut self.object_environments,
+            &mut bits.object_environments,
+            |h| h.into_index(),
+        );
+        requeue_marked(
+            &mut written.objects,
+            &mut self.objects,
+            &mut bits.objects,
+            |h| h.get_index(),
+        );
+        requeue_marked(
+            &mut written.primitive_objects,
+            &mut self.primitive_objects,
+            &mut bits.primitive_objects,
+            |h| h.get_index(),
+        );
+        requeue_marked(
+            &mut written.promises,
+            &mut self.promises,
+            &mut bits.promises,
+            |h| h.get_index(),
+        );
+        requeue_marked(
+            &mut written.promise_reaction_records,
+            &mut self.promise_reaction_records,
+            &mut bits.promise_reaction_records,
+            |h| h.get_index(),
+        );
+        requeue_marked(
+            &mut written.promise_resolving_functions,
+            &mut self.promise_resolving_functions,
+            &mut bits.promise_resolving_functions,
+            |h| h.get_index(),
+        );
+        requeue_marked(
+            &mut written.proxys,
+            &mut self.proxys,
+            &mut bits.proxys,
+            |h| h.get_index(),
+        );
+        requeue_marked(
+            &mut written.realms,
+            &mut self.realms,
+            &mut bits.realms,
+            |h| h.into_index(),
+        );
+        #[cfg(feature = "regexp")]
+        requeue_marked(
+            &mut written.regexps,
+            &mut self.regexps,
+            &mut bits.regexps,
+            |h| h.get_index(),
+        );
+        requeue_marked(
+            &mut written.scripts,
+            &mut self.scripts,
+            &mut bits.scripts,
+            |h| h.into_index(),
+        );
+        #[cfg(feature = "set")]
+        requeue_marked(&mut written.sets, &mut self.sets, &mut bits.sets, |h| {
+            h.get_index()
+        });
+        #[cfg(feature = "set")]
+        requeue_marked(
+            &mut written.set_iterators,
+            &mut self.set_iterators,
+            &mut bits.set_iterators,
+            |h| h.get_index(),
+        );
+        #[cfg(feature = "regexp")]
+        requeue_marked(
+            &mut written.reg_exp_string_iterators,
+            &mut self.reg_exp_string_iterators,
+            &mut bits.reg_exp_string_iterators,
+            |h| h.get_index(),
+        );
+        #[cfg(feature = "shared-array-buffer")]
+        requeue_marked(
+            &mut written.shared_array_buffers,
+            &mut self.shared_array_buffers,
+            &mut bits.shared_array_buffers,
+            |h| h.get_index(),
+        );
+        requeue_marked(
+            &mut written.string_iterators,
+            &mut self.string_iterators,
+            &mut bits.string_iterators,
+            |h| h.get_index(),
+        );
+        requeue_marked(
+            &mut written.strings,
+            &mut self.strings,
+            &mut bits.strings,
+            |h| h.get_index(),
+        );
+        requeue_marked(
+            &mut written.symbols,
+            &mut self.symbols,
+            &mut bits.symbols,
+            |h| h.get_index(),
+        );
+        #[cfg(feature = "array-buffer")]
+        requeue_marked(
+            &mut written.typed_arrays,
+            &mut self.typed_arrays,
+            &mut bits.typed_arrays,
+            |h| h.into_index(),
+        );
+        #[cfg(feature = "weak-refs")]
+        requeue_marked(
+            &mut written.weak_maps,
+            &mut self.weak_maps,
+            &mut bits.weak_maps,
+            |h| h.get_index(),
+        );
+        #[cfg(feature = "weak-refs")]
+        requeue_marked(
+            &mut written.weak_refs,
+            &mut self.weak_refs,
+            &mut bits.weak_refs,
+            |h| h.get_index(),
+        );
+        #[cfg(feature = "weak-refs")]
+        requeue_marked(
+            &mut written.weak_sets,
+            &mut self.weak_sets,
+            &mut bits.weak_sets,
+            |h| h.get_index(),
+        );
+        written.private_environments.clear();
+    }
 }
 
-#[derive(Debug)]
+fn requeue_marked<T>(
+    written: &mut Vec<T>,
+    queue: &mut Vec<T>,
+    bits: &mut [bool],
+    index: impl Fn(&T) -> usize,
+) {
+    for entry in written.drain(..) {
+        if let Some(marked) = bits.get_mut(index(&entry)) {
+            if *marked {
+                *marked = false;
+                queue.push(entry);
+            }
+        }
+    }
+}
+
+/// Policy for moving the live entries of a heap vector over its dead entries
+/// during garbage collection.
+///
+/// See [`Options::compaction`](crate::ecmascript::execution::agent::Options::compaction).
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum CompactionPolicy {
+    /// Compact every heap vector at every garbage collection.
+    #[default]
+    Always,
+    /// Never compact: dead entries are emptied in place and live entries keep
+    /// their indexes. Heap vectors never shrink, so this is intended for
+    /// debugging code that is sensitive to pointer stability.
+    Never,
+    /// Compact a heap vector only if at least `fragmentation_ratio` of its
+    /// slots are dead or empty.
+    Threshold { fragmentation_ratio: f64 },
+}
+
+impl CompactionPolicy {
+    /// Returns true if a heap vector with `live` live entries out of `slots`
+    /// should be compacted.
+    pub(crate) fn compacts(self, live: usize, slots: usize) -> bool {
+        match self {
+            CompactionPolicy::Always => true,
+            CompactionPolicy::Never => false,
+            CompactionPolicy::Threshold {
+                fragmentation_ratio,
+            } => slots > 0 && (slots - live) as f64 / slots as f64 >= fragmentation_ratio,
+        }
+    }
+}
+
+/// Describes where the entries of a heap vector move to. The default list
+/// leaves every entry where it is.
+#[derive(Debug, Default)]
 pub(crate) struct CompactionList {
     indexes: Box<[u32]>,
     shifts: Box<[u32]>,
+    /// Marks of the heap vector if it is not compacted. All indexes then stay
+    /// where they are, and only weak references to dead entries are cleared.
+    retained_marks: Option<Box<[bool]>>,
+    /// Entries of the heap vector that are redirected to copies of them, see
+    /// [`CompactionList::from_relocations`]. Other entries stay where they
+    /// are.
+    relocations: Option<AHashMap<u32, u32>>,
 }
 
 impl CompactionList {
@@ -627,6 +1133,12 @@ impl CompactionList {
     /// index.
     fn shift_strong_u32_index(&self, index: u32) -> u32 {
         assert!(self.indexes.len() == self.shifts.len());
+        if let Some(relocations) = &self.relocations {
+            return relocations.get(&index).copied().unwrap_or(index);
+        }
+        if self.retained_marks.is_some() {
+            return index;
+        }
         if self.indexes.is_empty() {
             // If there are no shifts, then all items stay where they are.
             return index;
@@ -651,6 +1163,14 @@ impl CompactionList {
     /// the reference target is live, otherwise returns None.
     pub(crate) fn shift_weak_u32_index(&self, index: u32) -> Option<u32> {
         assert!(self.indexes.len() == self.shifts.len());
+        if let Some(relocations) = &self.relocations {
+            return Some(relocations.get(&index).copied().unwrap_or(index));
+        }
+        if let Some(marks) = &self.retained_marks {
+            // Entries created after marking are live.
+            let live = marks.get(index as usize).copied().unwrap_or(true);
+            return live.then_some(index);
+        }
         // If there are no shift indexes, then all values are live.
         if self.indexes.is_empty() {
             return Some(index);
@@ -739,25 +1259,36 @@ impl CompactionList {
         Self {
             indexes: indexes.into_boxed_slice(),
             shifts: shifts.into_boxed_slice(),
+            retained_marks: None,
+            relocations: None,
         }
     }
 
-    pub(crate) fn from_mark_bits(marks: &[bool]) -> Self {
-        let mut builder = CompactionListBuilder::default();
-        marks.iter().for_each(|bit| {
-            if *bit {
-                builder.mark_used();
-            } else {
-                builder.mark_unused();
-            }
-        });
-        builder.done()
+    /// Creates a list that redirects references to the entries at the keys
+    /// of `relocations` to the entries at the corresponding values. Nothing
+    /// is removed from the heap vector: References to other entries, strong
+    /// or weak, are left as they are.
+    pub(crate) fn from_relocations(relocations: AHashMap<u32, u32>) -> Self {
+        Self {
+            relocations: Some(relocations),
+            ..Default::default()
+        }
     }
 
-    pub(crate) fn from_mark_u8s(marks: &[(bool, u8)]) -> Self {
+    fn from_marks(
+        marks: impl ExactSizeIterator<Item = bool> + Clone,
+        policy: CompactionPolicy,
+    ) -> Self {
+        let live = marks.clone().filter(|mark| *mark).count();
+        if !policy.compacts(live, marks.len()) {
+            return Self {
+                retained_marks: Some(marks.collect()),
+                ..Default::default()
+            };
+        }
         let mut builder = CompactionListBuilder::default();
-        marks.iter().for_each(|mark| {
-            if mark.0 {
+        marks.for_each(|mark| {
+            if mark {
                 builder.mark_used();
             } else {
                 builder.mark_unused();
@@ -766,28 +1297,20 @@ impl CompactionList {
         builder.done()
     }
 
-    pub(crate) fn from_mark_u16s(marks: &[(bool, u16)]) -> Self {
-        let mut builder = CompactionListBuilder::default();
-        marks.iter().for_each(|mark| {
-            if mark.0 {
-                builder.mark_used();
-            } else {
-                builder.mark_unused();
-            }
-        });
-        builder.done()
+    pub(crate) fn from_mark_bits(marks: &[bool], policy: CompactionPolicy) -> Self {
+        Self::from_marks(marks.iter().copied(), policy)
     }
 
-    pub(crate) fn from_mark_u32s(marks: &[(bool, u32)]) -> Self {
-        let mut builder = CompactionListBuilder::default();
-        marks.iter().for_each(|mark| {
-            if mark.0 {
-                builder.mark_used();
-            } else {
-                builder.mark_unused();
-            }
-        });
-        builder.done()
+    pub(crate) fn from_mark_u8s(marks: &[(bool, u8)], policy: CompactionPolicy) -> Self {
+        Self::from_marks(marks.iter().map(|mark| mark.0), policy)
+    }
+
+    pub(crate) fn from_mark_u16s(marks: &[(bool, u16)], policy: CompactionPolicy) -> Self {
+        Self::from_marks(marks.iter().map(|mark| mark.0), policy)
+    }
+
+    pub(crate) fn from_mark_u32s(marks: &[(bool, u32)], policy: CompactionPolicy) -> Self {
+        Self::from_marks(marks.iter().map(|mark| mark.0), policy)
     }
 }
 
@@ -869,6 +1392,7 @@ impl Default for CompactionListBuilder {
     }
 }
 
+#[derive(Default)]
 pub(crate) struct CompactionLists {
     #[cfg(feature = "array-buffer")]
     pub array_buffers: CompactionList,
@@ -930,6 +1454,8 @@ pub(crate) struct CompactionLists {
     pub sets: CompactionList,
     #[cfg(feature = "set")]
     pub set_iterators: CompactionList,
+    #[cfg(feature = "regexp")]
+    pub reg_exp_string_iterators: CompactionList,
     #[cfg(feature = "shared-array-buffer")]
     pub shared_array_buffers: CompactionList,
     pub string_iterators: CompactionList,
@@ -943,10 +1469,12 @@ pub(crate) struct CompactionLists {
     pub weak_refs: CompactionList,
     #[cfg(feature = "weak-refs")]
     pub weak_sets: CompactionList,
+    /// The policy the lists were created with.
+    pub policy: CompactionPolicy,
 }
 
 impl CompactionLists {
-    pub fn create_from_bits(bits: &HeapBits) -> Self {
+    pub fn create_from_bits(bits: &HeapBits, policy: CompactionPolicy) -> Self {
         // TODO: Instead of each list creating its own Vecs, this
         // could instead be a singular Vec segmented into slices.
         // The total number of vector items needed for compactions can
@@ -957,85 +1485,112 @@ impl CompactionLists {
         // areas can exist. We can use this mathematical bound to estimate a good
         // vector allocation.
         Self {
-            modules: CompactionList::from_mark_bits(&bits.modules),
-            scripts: CompactionList::from_mark_bits(&bits.scripts),
-            realms: CompactionList::from_mark_bits(&bits.realms),
+            modules: CompactionList::from_mark_bits(&bits.modules, policy),
+            scripts: CompactionList::from_mark_bits(&bits.scripts, policy),
+            realms: CompactionList::from_mark_bits(&bits.realms, policy),
             declarative_environments: CompactionList::from_mark_bits(
                 &bits.declarative_environments,
+                policy,
+            ),
+            function_environments: CompactionList::from_mark_bits(
+                &bits.function_environments,
+                policy,
             ),
-            function_environments: CompactionList::from_mark_bits(&bits.function_environments),
-            global_environments: CompactionList::from_mark_bits(&bits.global_environments),
-            object_environments: CompactionList::from_mark_bits(&bits.object_environments),
-            e_2_4: CompactionList::from_mark_u8s(&bits.e_2_4),
-            e_2_6: CompactionList::from_mark_u8s(&bits.e_2_6),
-            e_2_8: CompactionList::from_mark_u8s(&bits.e_2_8),
-            e_2_10: CompactionList::from_mark_u16s(&bits.e_2_10),
-            e_2_12: CompactionList::from_mark_u16s(&bits.e_2_12),
-            e_2_16: CompactionList::from_mark_u16s(&bits.e_2_16),
-            e_2_24: CompactionList::from_mark_u32s(&bits.e_2_24),
-            e_2_32: CompactionList::from_mark_u32s(&bits.e_2_32),
-            k_2_4: CompactionList::from_mark_u8s(&bits.k_2_4),
-            k_2_6: CompactionList::from_mark_u8s(&bits.k_2_6),
-            k_2_8: CompactionList::from_mark_u8s(&bits.k_2_8),
-            k_2_10: CompactionList::from_mark_u16s(&bits.k_2_10),
-            k_2_12: CompactionList::from_mark_u16s(&bits.k_2_12),
-            k_2_16: CompactionList::from_mark_u16s(&bits.k_2_16),
-            k_2_24: CompactionList::from_mark_u32s(&bits.k_2_24),
-            k_2_32: CompactionList::from_mark_u32s(&bits.k_2_32),
-            arrays: CompactionList::from_mark_bits(&bits.arrays),
+            global_environments: CompactionList::from_mark_bits(&bits.global_environments, policy),
+            object_environments: CompactionList::from_mark_bits(&bits.object_environments, policy),
+            e_2_4: CompactionList::from_mark_u8s(&bits.e_2_4, policy),
+            e_2_6: CompactionList::from_mark_u8s(&bits.e_2_6, policy),
+            e_2_8: CompactionList::from_mark_u8s(&bits.e_2_8, policy),
+            e_2_10: CompactionList::from_mark_u16s(&bits.e_2_10, policy),
+            e_2_12: CompactionList::from_mark_u16s(&bits.e_2_12, policy),
+            e_2_16: CompactionList::from_mark_u16s(&bits.e_2_16, policy),
+            e_2_24: CompactionList::from_mark_u32s(&bits.e_2_24, policy),
+            e_2_32: CompactionList::from_mark_u32s(&bits.e_2_32, policy),
+            k_2_4: CompactionList::from_mark_u8s(&bits.k_2_4, policy),
+            k_2_6: CompactionList::from_mark_u8s(&bits.k_2_6, policy),
+            k_2_8: CompactionList::from_mark_u8s(&bits.k_2_8, policy),
+            k_2_10: CompactionList::from_mark_u16s(&bits.k_2_10, policy),
+            k_2_12: CompactionList::from_mark_u16s(&bits.k_2_12, policy),
+            k_2_16: CompactionList::from_mark_u16s(&bits.k_2_16, policy),
+            k_2_24: CompactionList::from_mark_u32s(&bits.k_2_24, policy),
+            k_2_32: CompactionList::from_mark_u32s(&bits.k_2_32, policy),
+            arrays: CompactionList::from_mark_bits(&bits.arrays, policy),
             #[cfg(feature = "array-buffer")]
-            array_buffers: CompactionList::from_mark_bits(&bits.array_buffers),
-            array_iterators: CompactionList::from_mark_bits(&bits.array_iterators),
-            async_generators: CompactionList::from_mark_bits(&bits.async_generators),
-            await_reactions: CompactionList::from_mark_bits(&bits.await_reactions),
-            bigints: CompactionList::from_mark_bits(&bits.bigints),
-            bound_functions: CompactionList::from_mark_bits(&bits.bound_functions),
-            builtin_constructors: CompactionList::from_mark_bits(&bits.builtin_constructors),
-            builtin_functions: CompactionList::from_mark_bits(&bits.builtin_functions),
-            ecmascript_functions: CompactionList::from_mark_bits(&bits.ecmascript_functions),
-            embedder_objects: CompactionList::from_mark_bits(&bits.embedder_objects),
-            generators: CompactionList::from_mark_bits(&bits.generators),
-            source_codes: CompactionList::from_mark_bits(&bits.source_codes),
+            array_buffers: CompactionList::from_mark_bits(&bits.array_buffers, policy),
+            array_iterators: CompactionList::from_mark_bits(&bits.array_iterators, policy),
+            async_generators: CompactionList::from_mark_bits(&bits.async_generators, policy),
+            await_reactions: CompactionList::from_mark_bits(&bits.await_reactions, policy),
+            bigints: CompactionList::from_mark_bits(&bits.bigints, policy),
+            bound_functions: CompactionList::from_mark_bits(&bits.bound_functions, policy),
+            builtin_constructors: CompactionList::from_mark_bits(
+                &bits.builtin_constructors,
+                policy,
+            ),
+            builtin_functions: CompactionList::from_mark_bits(&bits.builtin_functions, policy),
+            ecmascript_functions: CompactionList::from_mark_bits(
+                &bits.ecmascript_functions,
+                policy,
+            ),
+            embedder_objects: CompactionList::from_mark_bits(&bits.embedder_objects, policy),
+            generators: CompactionList::from_mark_bits(&bits.generators, policy),
+            source_codes: CompactionList::from_mark_bits(&bits.source_codes, policy),
             #[cfg(feature = "date")]
-            dates: CompactionList::from_mark_bits(&bits.dates),
-            errors: CompactionList::from_mark_bits(&bits.errors),
-            executables: CompactionList::from_mark_bits(&bits.executables),
-            maps: CompactionList::from_mark_bits(&bits.maps),
-            map_iterators: CompactionList::from_mark_bits(&bits.map_iterators),
-            numbers: CompactionList::from_mark_bits(&bits.numbers),
-            objects: CompactionList::from_mark_bits(&bits.objects),
-            primitive_objects: CompactionList::from_mark_bits(&bits.primitive_objects),
-            private_environments: CompactionList::from_mark_bits(&bits.private_environments),
+            dates: CompactionList::from_mark_bits(&bits.dates, policy),
+            errors: CompactionList::from_mark_bits(&bits.errors, policy),
+            executables: CompactionList::from_mark_bits(&bits.executables, policy),
+            maps: CompactionList::from_mark_bits(&bits.maps, policy),
+            map_iterators: CompactionList::from_mark_bits(&bits.map_iterators, policy),
+            numbers: CompactionList::from_mark_bits(&bits.numbers, policy),
+            objects: CompactionList::from_mark_bits(&bits.objects, policy),
+            primitive_objects: CompactionList::from_mark_bits(&bits.primitive_objects, policy),
+            private_environments: CompactionList::from_mark_bits(
+                &bits.private_environments,
+                policy,
+            ),
             promise_reaction_records: CompactionList::from_mark_bits(
                 &bits.promise_reaction_records,
+                policy,
             ),
             promise_resolving_functions: CompactionList::from_mark_bits(
                 &bits.promise_resolving_functions,
+                policy,
             ),
-            promises: CompactionList::from_mark_bits(&bits.promises),
+            promises: CompactionList::from_mark_bits(&bits.promises, policy),
             #[cfg(feature = "regexp")]
-            regexps: CompactionList::from_mark_bits(&bits.regexps),
+            regexps: CompactionList::from_mark_bits(&bits.regexps, policy),
             #[cfg(feature = "set")]
-            sets: CompactionList::from_mark_bits(&bits.sets),
+            sets: CompactionList::from_mark_bits(&bits.sets, policy),
             #[cfg(feature = "set")]
-            set_iterators: CompactionList::from_mark_bits(&bits.set_iterators),
-            string_iterators: CompactionList::from_mark_bits(&bits.string_iterators),
-            strings: CompactionList::from_mark_bits(&bits.strings),
+            set_iterators: CompactionList::from_mark_bits(&bits.set_iterators, policy),
+            #[cfg(feature = "regexp")]
+            reg_exp_string_iterators: CompactionList::from_mark_bits(
+                &bits.reg_exp_string_iterators,
+                policy,
+            ),
+            string_iterators: CompactionList::from_mark_bits(&bits.string_iterators, policy),
+            strings: CompactionList::from_mark_bits(&bits.strings, policy),
             #[cfg(feature = "shared-array-buffer")]
-            shared_array_buffers: CompactionList::from_mark_bits(&bits.shared_array_buffers),
-            symbols: CompactionList::from_mark_bits(&bits.symbols),
+            shared_array_buffers: CompactionList::from_mark_bits(
+                &bits.shared_array_buffers,
+                policy,
+            ),
+            symbols: CompactionList::from_mark_bits(&bits.symbols, policy),
             #[cfg(feature = "array-buffer")]
-            data_views: CompactionList::from_mark_bits(&bits.data_views),
-            finalization_registrys: CompactionList::from_mark_bits(&bits.finalization_registrys),
-            proxys: CompactionList::from_mark_bits(&bits.proxys),
+            data_views: CompactionList::from_mark_bits(&bits.data_views, policy),
+            finalization_registrys: CompactionList::from_mark_bits(
+                &bits.finalization_registrys,
+                policy,
+            ),
+            proxys: CompactionList::from_mark_bits(&bits.proxys, policy),
             #[cfg(feature = "weak-refs")]
-            weak_maps: CompactionList::from_mark_bits(&bits.weak_maps),
+            weak_maps: CompactionList::from_mark_bits(&bits.weak_maps, policy),
             #[cfg(feature = "weak-refs")]
-            weak_refs: CompactionList::from_mark_bits(&bits.weak_refs),
+            weak_refs: CompactionList::from_mark_bits(&bits.weak_refs, policy),
             #[cfg(feature = "weak-refs")]
-            weak_sets: CompactionList::from_mark_bits(&bits.weak_sets),
+            weak_sets: CompactionList::from_mark_bits(&bits.weak_sets, policy),
             #[cfg(feature = "array-buffer")]
-            typed_arrays: CompactionList::from_mark_bits(&bits.typed_arrays),
+            typed_arrays: CompactionList::from_mark_bits(&bits.typed_arrays, policy),
+            policy,
         }
     }
 }
@@ -1206,21 +1761,76 @@ fn sweep_array_with_u32_length<T: HeapMarkAndSweep, const N: usize>(
         });
 }
 
-pub(crate) fn sweep_heap_vector_values<T: HeapMarkAndSweep + core::fmt::Debug>(
+/// A slot in a heap vector.
+pub(crate) trait HeapVectorSlot {
+    /// Returns false if the slot was emptied in place by a garbage collection
+    /// that did not compact its heap vector.
+    fn is_live(&self) -> bool;
+
+    /// Empty a dead slot in place, dropping its data.
+    fn clear(&mut self);
+}
+
+impl<T> HeapVectorSlot for Option<T> {
+    fn is_live(&self) -> bool {
+        self.is_some()
+    }
+
+    fn clear(&mut self) {
+        *self = None;
+    }
+}
+
+impl<const N: usize> HeapVectorSlot for [Option<PropertyKey<'static>>; N] {
+    fn is_live(&self) -> bool {
+        true
+    }
+
+    fn clear(&mut self) {
+        self.fill(None);
+    }
+}
+
+/// Sweep a heap vector: dead entries are removed if the compaction policy
+/// compacts the vector, and emptied in place otherwise.
+fn sweep_vector<T: HeapVectorSlot>(
     vec: &mut Vec<T>,
     compactions: &CompactionLists,
-    bits: &[bool],
+    marks: impl ExactSizeIterator<Item = bool> + Clone,
+    mut sweep_live: impl FnMut(&mut T, usize),
 ) {
-    assert_eq!(vec.len(), bits.len());
-    let mut iter = bits.iter();
-    vec.retain_mut(|item| {
-        let do_retain = iter.next().unwrap();
-        if *do_retain {
-            item.sweep_values(compactions);
-            true
-        } else {
-            false
+    assert_eq!(vec.len(), marks.len());
+    let live = marks.clone().filter(|mark| *mark).count();
+    let mut marks = marks;
+    let mut index = 0;
+    if compactions.policy.compacts(live, vec.len()) {
+        vec.retain_mut(|item| {
+            let do_retain = marks.next().unwrap();
+            if do_retain {
+                sweep_live(item, index);
+            }
+            index += 1;
+            do_retain
+        });
+    } else {
+        for item in vec.iter_mut() {
+            if marks.next().unwrap() {
+                sweep_live(item, index);
+            } else {
+                item.clear();
+            }
+            index += 1;
         }
+    }
+}
+
+pub(crate) fn sweep_heap_vector_values<T: HeapMarkAndSweep + HeapVectorSlot>(
+    vec: &mut Vec<T>,
+    compactions: &CompactionLists,
+    bits: &[bool],
+) {
+    sweep_vector(vec, compactions, bits.iter().copied(), |item, _| {
+        item.sweep_values(compactions)
     });
 }
 
@@ -1229,17 +1839,12 @@ pub(crate) fn sweep_heap_u8_property_key_vector<const N: usize>(
     compactions: &CompactionLists,
     u8s: &[(bool, u8)],
 ) {
-    assert_eq!(vec.len(), u8s.len());
-    let mut iter = u8s.iter();
-    vec.retain_mut(|item| {
-        let (mark, length) = iter.next().unwrap();
-        if *mark {
-            sweep_array_with_u32_length(item, compactions, *length as u32);
-            true
-        } else {
-            false
-        }
-    });
+    sweep_vector(
+        vec,
+        compactions,
+        u8s.iter().map(|mark| mark.0),
+        |item, index| sweep_array_with_u32_length(item, compactions, u8s[index].1 as u32),
+    );
 }
 
 pub(crate) fn sweep_heap_u16_property_key_vector<const N: usize>(
@@ -1247,17 +1852,12 @@ pub(crate) fn sweep_heap_u16_property_key_vector<const N: usize>(
     compactions: &CompactionLists,
     u16s: &[(bool, u16)],
 ) {
-    assert_eq!(vec.len(), u16s.len());
-    let mut iter = u16s.iter();
-    vec.retain_mut(|item| {
-        let (mark, length) = iter.next().unwrap();
-        if *mark {
-            sweep_array_with_u32_length(item, compactions, *length as u32);
-            true
-        } else {
-            false
-        }
-    });
+    sweep_vector(
+        vec,
+        compactions,
+        u16s.iter().map(|mark| mark.0),
+        |item, index| sweep_array_with_u32_length(item, compactions, u16s[index].1 as u32),
+    );
 }
 
 pub(crate) fn sweep_heap_u32_property_key_vector<const N: usize>(
@@ -1265,17 +1865,12 @@ pub(crate) fn sweep_heap_u32_property_key_vector<const N: usize>(
     compactions: &CompactionLists,
     u32s: &[(bool, u32)],
 ) {
-    assert_eq!(vec.len(), u32s.len());
-    let mut iter = u32s.iter();
-    vec.retain_mut(|item| {
-        let (mark, length) = iter.next().unwrap();
-        if *mark {
-            sweep_array_with_u32_length(item, compactions, *length);
-            true
-        } else {
-            false
-        }
-    });
+    sweep_vector(
+        vec,
+        compactions,
+        u32s.iter().map(|mark| mark.0),
+        |item, index| sweep_array_with_u32_length(item, compactions, u32s[index].1),
+    );
 }
 
 pub(crate) fn sweep_heap_u8_elements_vector_values<const N: usize>(
@@ -1283,17 +1878,12 @@ pub(crate) fn sweep_heap_u8_elements_vector_values<const N: usize>(
     compactions: &CompactionLists,
     u8s: &[(bool, u8)],
 ) {
-    assert_eq!(vec.len(), u8s.len());
-    let mut iter = u8s.iter();
-    vec.retain_mut(|item| {
-        let (mark, length) = iter.next().unwrap();
-        if *mark {
-            sweep_optional_array_with_u32_length(item, compactions, *length as u32);
-            true
-        } else {
-            false
-        }
-    });
+    sweep_vector(
+        vec,
+        compactions,
+        u8s.iter().map(|mark| mark.0),
+        |item, index| sweep_optional_array_with_u32_length(item, compactions, u8s[index].1 as u32),
+    );
 }
 
 pub(crate) fn sweep_heap_u16_elements_vector_values<const N: usize>(
@@ -1301,17 +1891,12 @@ pub(crate) fn sweep_heap_u16_elements_vector_values<const N: usize>(
     compactions: &CompactionLists,
     u16s: &[(bool, u16)],
 ) {
-    assert_eq!(vec.len(), u16s.len());
-    let mut iter = u16s.iter();
-    vec.retain_mut(|item| {
-        let (mark, length) = iter.next().unwrap();
-        if *mark {
-            sweep_optional_array_with_u32_length(item, compactions, *length as u32);
-            true
-        } else {
-            false
-        }
-    });
+    sweep_vector(
+        vec,
+        compactions,
+        u16s.iter().map(|mark| mark.0),
+        |item, index| sweep_optional_array_with_u32_length(item, compactions, u16s[index].1 as u32),
+    );
 }
 
 pub(crate) fn sweep_heap_u32_elements_vector_values<const N: usize>(
@@ -1319,17 +1904,12 @@ pub(crate) fn sweep_heap_u32_elements_vector_values<const N: usize>(
     compactions: &CompactionLists,
     u32s: &[(bool, u32)],
 ) {
-    assert_eq!(vec.len(), u32s.len());
-    let mut iter = u32s.iter();
-    vec.retain_mut(|item| {
-        let (mark, length) = iter.next().unwrap();
-        if *mark {
-            sweep_optional_array_with_u32_length(item, compactions, *length);
-            true
-        } else {
-            false
-        }
-    });
+    sweep_vector(
+        vec,
+        compactions,
+        u32s.iter().map(|mark| mark.0),
+        |item, index| sweep_optional_array_with_u32_length(item, compactions, u32s[index].1),
+    );
 }
 
 pub(crate) fn sweep_heap_elements_vector_descriptors<T>(
@@ -1412,3 +1992,161 @@ where
         }
     });
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        ecmascript::{
+            execution::{
+                DefaultHostHooks,
+                agent::{GcAgent, Options, RealmRoot},
+            },
+            types::{String, Value},
+        },
+        engine::context::Bindable,
+        heap::{CompactionPolicy, HeapVectorStatistics},
+    };
+
+    fn evaluate(agent: &mut GcAgent, realm: &RealmRoot, source: &'static str) -> bool {
+        let result = agent.run_in_realm(realm, |agent, mut gc| {
+            let source_text = String::from_static_str(agent, source, gc.nogc());
+            agent
+                .run_script(source_text.unbind(), gc.reborrow())
+                .unwrap()
+                .unbind()
+        });
+        result == Value::Boolean(true)
+    }
+
+    fn object_statistics(agent: &GcAgent) -> HeapVectorStatistics {
+        agent
+            .heap_vector_statistics()
+            .into_iter()
+            .find(|statistics| statistics.name == "objects")
+            .unwrap()
+    }
+
+    #[test]
+    fn threshold_policy_compacts_only_fragmented_vectors() {
+        let mut agent = GcAgent::new(
+            Options {
+                compaction: CompactionPolicy::Threshold {
+                    fragmentation_ratio: 0.5,
+                },
+                ..Default::default()
+            },
+            &DefaultHostHooks,
+        );
+        let realm = agent.create_default_realm();
+        assert!(evaluate(
+            &mut agent,
+            &realm,
+            "globalThis.objects = [];
+            for (let i = 0; i < 10000; i++) objects.push({ i });
+            true",
+        ));
+        agent.gc();
+        let before = object_statistics(&agent);
+
+        // Dropping a hundred objects leaves the vector barely fragmented.
+        assert!(evaluate(&mut agent, &realm, "objects.length = 9900; true"));
+        agent.gc();
+        let barely_fragmented = object_statistics(&agent);
+        assert_eq!(barely_fragmented.slots, before.slots);
+        assert_eq!(barely_fragmented.live, before.live - 100);
+
+        // Dropping most of the rest crosses the threshold.
+        assert!(evaluate(&mut agent, &realm, "objects.length = 100; true"));
+        agent.gc();
+        let heavily_fragmented = object_statistics(&agent);
+        assert_eq!(heavily_fragmented.live, before.live - 9900);
+        assert_eq!(heavily_fragmented.slots, heavily_fragmented.live);
+
+        assert!(evaluate(
+            &mut agent,
+            &realm,
+            "objects.every((object, i) => object.i === i)"
+        ));
+        agent.remove_realm(realm);
+    }
+
+    #[test]
+    fn never_policy_keeps_indexes_stable() {
+        let mut agent = GcAgent::new(
+            Options {
+                compaction: CompactionPolicy::Never,
+                ..Default::default()
+            },
+            &DefaultHostHooks,
+        );
+        let realm = agent.create_default_realm();
+        assert!(evaluate(
+            &mut agent,
+            &realm,
+            "globalThis.refs = [];
+            globalThis.survivors = [];
+            for (let i = 0; i < 1000; i++) {
+                const object = { i };
+                refs.push(new WeakRef(object));
+                if (i % 2 === 0) survivors.push(object);
+            }
+            true",
+        ));
+        let before = object_statistics(&agent);
+        agent.gc();
+        let after = object_statistics(&agent);
+        assert_eq!(after.slots, before.slots);
+        assert!(after.live <= before.live - 500);
+
+        // Surviving objects are found at their old indexes, and weak references
+        // to the collected objects are cleared rather than retargeted.
+        assert!(evaluate(
+            &mut agent,
+            &realm,
+            "survivors.every((object, i) => object.i === 2 * i) &&
+            refs.every((ref, i) => ref.deref() === (i % 2 === 0 ? survivors[i / 2] : undefined))",
+        ));
+        agent.remove_realm(realm);
+    }
+
+    #[test]
+    fn compact_heap_shrinks_capacity() {
+        let mut agent = GcAgent::new(
+            Options {
+                compaction: CompactionPolicy::Never,
+                ..Default::default()
+            },
+            &DefaultHostHooks,
+        );
+        let realm = agent.create_default_realm();
+        assert!(evaluate(
+            &mut agent,
+            &realm,
+            "globalThis.objects = [];
+            for (let i = 0; i < 20000; i++) objects.push({ i });
+            objects.length = 10;
+            true",
+        ));
+        agent.gc();
+        let before = object_statistics(&agent);
+        assert!(before.slots > before.live + 19000);
+
+        agent.compact_heap();
+        let after = object_statistics(&agent);
+        assert_eq!(after.live, before.live);
+        assert_eq!(after.slots, after.live);
+        assert!(after.capacity < before.capacity / 2);
+        assert!(after.capacity >= after.slots);
+
+        // The policy of the agent is restored after the explicit compaction.
+        assert!(evaluate(
+            &mut agent,
+            &realm,
+            "objects.push({ i: 10 }); objects.length = 10; true",
+        ));
+        agent.gc();
+        let restored = object_statistics(&agent);
+        assert_eq!(restored.slots, after.slots + 1);
+        agent.remove_realm(realm);
+    }
+}
@@ -238,6 +238,7 @@ pub(crate) enum IntrinsicFunctionIndexes {
     // +===================================================================================+
     // | Plain functions: These do not have a corresponding object index reserved for them |
     // +===================================================================================+
+    ArrayPrototypeJoin,
     ArrayPrototypeSort,
     ArrayPrototypeToString,
     ArrayPrototypeValues,
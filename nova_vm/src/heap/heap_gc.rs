@@ -2,10 +2,10 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-use std::thread;
+use std::{thread, time::Instant};
 
 use super::{
-    Heap, WellKnownSymbolIndexes,
+    ALLOC_COUNTER_LIMIT, Heap, WellKnownSymbolIndexes,
     element_array::ElementArrays,
     heap_bits::{
         CompactionLists, HeapBits, HeapMarkAndSweep, WorkQueues, mark_array_with_u32_length,
@@ -21,8 +21,6 @@ use super::{
 use super::{heap_bits::sweep_side_table_values, indexes::TypedArrayIndex};
 #[cfg(feature = "date")]
 use crate::ecmascript::builtins::date::Date;
-#[cfg(feature = "regexp")]
-use crate::ecmascript::builtins::regexp::RegExp;
 #[cfg(feature = "shared-array-buffer")]
 use crate::ecmascript::builtins::shared_array_buffer::SharedArrayBuffer;
 #[cfg(feature = "array-buffer")]
@@ -31,6 +29,11 @@ use crate::ecmascript::builtins::{ArrayBuffer, data_view::DataView};
 use crate::ecmascript::builtins::{
     keyed_collections::set_objects::set_iterator_objects::set_iterator::SetIterator, set::Set,
 };
+#[cfg(feature = "regexp")]
+use crate::ecmascript::builtins::{
+    regexp::RegExp,
+    text_processing::regexp_objects::regexp_string_iterator_objects::RegExpStringIterator,
+};
 #[cfg(feature = "weak-refs")]
 use crate::ecmascript::builtins::{weak_map::WeakMap, weak_ref::WeakRef, weak_set::WeakSet};
 use crate::{
@@ -61,7 +64,7 @@ use crate::{
         },
         execution::{
             Agent, DeclarativeEnvironment, Environments, FunctionEnvironment, GlobalEnvironment,
-            ObjectEnvironment, Realm,
+            ObjectEnvironment, PrivateEnvironment, Realm,
         },
         scripts_and_modules::{script::Script, source_code::SourceCode},
         types::{
@@ -75,10 +78,103 @@ use crate::{
     },
 };
 
-pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc: GcScope) {
+/// State of an incremental garbage collection whose marking phase is in
+/// progress, see [`heap_gc_step`].
+#[derive(Debug)]
+pub(crate) struct IncrementalMarking {
+    bits: HeapBits,
+    queues: WorkQueues,
+    /// Heap data written to since marking started, recorded by
+    /// [`Heap::write_barrier`].
+    pub(crate) written: WorkQueues,
+    /// Value of the allocation counter when marking started.
+    alloc_counter_at_start: usize,
+}
+
+pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], mut gc: GcScope) {
+    let start = Instant::now();
+    // A full collection marks everything from scratch: Any incremental
+    // marking in progress is abandoned.
+    agent.heap.stop_incremental_marking();
     let mut bits = HeapBits::new(&agent.heap);
     let mut queues = WorkQueues::new(&agent.heap);
+    mark_roots(agent, root_realms, &mut queues);
+    let work = mark(&agent.heap, &mut bits, &mut queues, usize::MAX, false);
+    sweep(agent, &bits, root_realms, gc.reborrow());
+    agent
+        .heap
+        .statistics
+        .record_collection(work, start.elapsed());
+    agent.notify_garbage_collection(gc);
+}
 
+/// Performs one slice of an incremental garbage collection, starting a new
+/// collection if none is in progress. Returns true if the collection was
+/// finished by this slice.
+///
+/// A slice processes at most `budget` items from the marking work queues.
+/// Heap data written to between slices is recorded by the write barrier and
+/// scanned again, and heap data allocated between slices starts out unmarked.
+/// Once the work queues run dry, the next slice finishes the collection: It
+/// marks from the roots again, finding everything that the roots came to
+/// reference while marking was in progress, and sweeps. If allocation outruns
+/// marking, the collection is finished synchronously instead.
+pub(crate) fn heap_gc_step(
+    agent: &mut Agent,
+    root_realms: &mut [Option<Realm<'static>>],
+    budget: usize,
+    mut gc: GcScope,
+) -> bool {
+    let start = Instant::now();
+    let mut marking = match agent.heap.incremental_marking.take() {
+        Some(marking) => marking,
+        None => {
+            let mut queues = WorkQueues::new(&agent.heap);
+            mark_roots(agent, root_realms, &mut queues);
+            agent.heap.elements.write_log = Some(Box::default());
+            Box::new(IncrementalMarking {
+                bits: HeapBits::new(&agent.heap),
+                queues,
+                written: WorkQueues::default(),
+                alloc_counter_at_start: agent.heap.alloc_counter,
+            })
+        }
+    };
+    let IncrementalMarking {
+        bits,
+        queues,
+        written,
+        alloc_counter_at_start,
+    } = &mut *marking;
+    bits.grow(&agent.heap);
+    queues.requeue_written(written, bits);
+    if let Some(element_writes) = agent.heap.elements.write_log.as_deref_mut() {
+        queues.requeue_written(element_writes, bits);
+    }
+    let allocation_outran_marking =
+        agent.heap.alloc_counter - *alloc_counter_at_start > ALLOC_COUNTER_LIMIT;
+    if !queues.is_empty() && !allocation_outran_marking {
+        let work = mark(&agent.heap, bits, queues, budget, true);
+        agent.heap.incremental_marking = Some(marking);
+        agent.heap.statistics.record_slice(work, start.elapsed());
+        return false;
+    }
+
+    mark_roots(agent, root_realms, queues);
+    let work = mark(&agent.heap, bits, queues, usize::MAX, true);
+    agent.heap.elements.write_log = None;
+    sweep(agent, bits, root_realms, gc.reborrow());
+    let statistics = &mut agent.heap.statistics;
+    statistics.record_slice(work, start.elapsed());
+    statistics.collections += 1;
+    if allocation_outran_marking {
+        statistics.synchronous_finishes += 1;
+    }
+    agent.notify_garbage_collection(gc);
+    true
+}
+
+fn mark_roots(agent: &Agent, root_realms: &[Option<Realm<'static>>], queues: &mut WorkQueues) {
     root_realms.iter().for_each(|realm| {
         if let Some(realm) = realm {
             queues.realms.push(realm.unbind());
@@ -103,9 +199,31 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
         WellKnownSymbolIndexes::ToStringTag.into(),
         WellKnownSymbolIndexes::Unscopables.into(),
     ]);
-    agent.mark_values(&mut queues);
+    agent.mark_values(queues);
+}
+
+/// Takes at most `budget` items from the end of a work queue.
+fn take_work<T>(queue: &mut Vec<T>, budget: &mut usize) -> Box<[T]> {
+    let count = queue.len().min(*budget);
+    *budget -= count;
+    queue.drain(queue.len() - count..).collect()
+}
 
-    while !queues.is_empty() {
+/// Marks heap data reachable from the work queues, processing at most
+/// `budget` queued items. Returns the number of items processed.
+///
+/// During incremental marking, element and property key storage that has
+/// already been marked can be queued again after being written to; it is
+/// then rescanned. Otherwise elements being queued twice is a bug.
+pub(crate) fn mark(
+    heap: &Heap,
+    bits: &mut HeapBits,
+    queues: &mut WorkQueues,
+    mut budget: usize,
+    incremental: bool,
+) -> usize {
+    let initial_budget = budget;
+    while !queues.is_empty() && budget > 0 {
         let Heap {
             #[cfg(feature = "array-buffer")]
             array_buffers,
@@ -137,11 +255,13 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
             finalization_registrys,
             generators,
             globals: _,
+            incremental_marking: _,
             maps,
             map_iterators,
             modules,
             numbers,
             objects,
+            own_keys_cache: _,
             primitive_objects,
             promise_reaction_records,
             promise_resolving_functions,
@@ -155,6 +275,8 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
             sets,
             #[cfg(feature = "set")]
             set_iterators,
+            #[cfg(feature = "regexp")]
+            reg_exp_string_iterators,
             #[cfg(feature = "shared-array-buffer")]
             shared_array_buffers,
             string_iterators,
@@ -176,14 +298,15 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
             weak_refs,
             #[cfg(feature = "weak-refs")]
             weak_sets,
+            statistics: _,
             alloc_counter: _,
-        } = &agent.heap;
+        } = heap;
         let Environments {
             declarative: declarative_environments,
             function: function_environments,
             global: global_environments,
             object: object_environments,
-            private: _private_environments,
+            private: private_environments,
         } = environments;
         let ElementArrays {
             e2pow4,
@@ -202,8 +325,9 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
             k2pow16,
             k2pow24,
             k2pow32,
+            write_log: _,
         } = elements;
-        let mut module_marks: Box<[Module]> = queues.modules.drain(..).collect();
+        let mut module_marks: Box<[Module]> = take_work(&mut queues.modules, &mut budget);
         module_marks.sort();
         module_marks.iter().for_each(|&idx| {
             let index = idx.get_index();
@@ -213,10 +337,10 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                modules.get(index).mark_values(&mut queues);
+                modules.get(index).mark_values(queues);
             }
         });
-        let mut script_marks: Box<[Script]> = queues.scripts.drain(..).collect();
+        let mut script_marks: Box<[Script]> = take_work(&mut queues.scripts, &mut budget);
         script_marks.sort();
         script_marks.iter().for_each(|&idx| {
             let index = idx.into_index();
@@ -226,10 +350,10 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                scripts.get(index).mark_values(&mut queues);
+                scripts.get(index).mark_values(queues);
             }
         });
-        let mut realm_marks: Box<[Realm]> = queues.realms.drain(..).collect();
+        let mut realm_marks: Box<[Realm]> = take_work(&mut queues.realms, &mut budget);
         realm_marks.sort();
         realm_marks.iter().for_each(|&idx| {
             let index = idx.into_index();
@@ -239,12 +363,12 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                realms.get(index).mark_values(&mut queues);
+                realms.get(index).mark_values(queues);
             }
         });
 
         let mut declarative_environment_marks: Box<[DeclarativeEnvironment]> =
-            queues.declarative_environments.drain(..).collect();
+            take_work(&mut queues.declarative_environments, &mut budget);
         declarative_environment_marks.sort();
         declarative_environment_marks.iter().for_each(|&idx| {
             let index = idx.into_index();
@@ -254,11 +378,11 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                declarative_environments.get(index).mark_values(&mut queues);
+                declarative_environments.get(index).mark_values(queues);
             }
         });
         let mut function_environment_marks: Box<[FunctionEnvironment]> =
-            queues.function_environments.drain(..).collect();
+            take_work(&mut queues.function_environments, &mut budget);
         function_environment_marks.sort();
         function_environment_marks.iter().for_each(|&idx| {
             let index = idx.into_index();
@@ -268,11 +392,11 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                function_environments.get(index).mark_values(&mut queues);
+                function_environments.get(index).mark_values(queues);
             }
         });
         let mut global_environment_marks: Box<[GlobalEnvironment]> =
-            queues.global_environments.drain(..).collect();
+            take_work(&mut queues.global_environments, &mut budget);
         global_environment_marks.sort();
         global_environment_marks.iter().for_each(|&idx| {
             let index = idx.into_index();
@@ -282,11 +406,11 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                global_environments.get(index).mark_values(&mut queues);
+                global_environments.get(index).mark_values(queues);
             }
         });
         let mut object_environment_marks: Box<[ObjectEnvironment]> =
-            queues.object_environments.drain(..).collect();
+            take_work(&mut queues.object_environments, &mut budget);
         object_environment_marks.sort();
         object_environment_marks.iter().for_each(|&idx| {
             let index = idx.into_index();
@@ -296,11 +420,25 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                object_environments.get(index).mark_values(&mut queues);
+                object_environments.get(index).mark_values(queues);
+            }
+        });
+        let mut private_environment_marks: Box<[PrivateEnvironment]> =
+            take_work(&mut queues.private_environments, &mut budget);
+        private_environment_marks.sort();
+        private_environment_marks.iter().for_each(|&idx| {
+            let index = idx.into_index();
+            if let Some(marked) = bits.private_environments.get_mut(index) {
+                if *marked {
+                    // Already marked, ignore
+                    return;
+                }
+                *marked = true;
+                private_environments.get(index).mark_values(queues);
             }
         });
 
-        let mut array_marks: Box<[Array]> = queues.arrays.drain(..).collect();
+        let mut array_marks: Box<[Array]> = take_work(&mut queues.arrays, &mut budget);
         array_marks.sort();
         array_marks.iter().for_each(|&idx| {
             let index = idx.get_index();
@@ -310,13 +448,13 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                arrays.get(index).mark_values(&mut queues);
+                arrays.get(index).mark_values(queues);
             }
         });
         #[cfg(feature = "array-buffer")]
         {
             let mut array_buffer_marks: Box<[ArrayBuffer]> =
-                queues.array_buffers.drain(..).collect();
+                take_work(&mut queues.array_buffers, &mut budget);
             array_buffer_marks.sort();
             array_buffer_marks.iter().for_each(|&idx| {
                 let index = idx.get_index();
@@ -326,12 +464,12 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                         return;
                     }
                     *marked = true;
-                    array_buffers.get(index).mark_values(&mut queues);
+                    array_buffers.get(index).mark_values(queues);
                 }
             });
         }
         let mut array_iterator_marks: Box<[ArrayIterator]> =
-            queues.array_iterators.drain(..).collect();
+            take_work(&mut queues.array_iterators, &mut budget);
         array_iterator_marks.sort();
         array_iterator_marks.iter().for_each(|&idx| {
             let index = idx.get_index();
@@ -341,11 +479,11 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                array_iterators.get(index).mark_values(&mut queues);
+                array_iterators.get(index).mark_values(queues);
             }
         });
         let mut async_generator_marks: Box<[AsyncGenerator]> =
-            queues.async_generators.drain(..).collect();
+            take_work(&mut queues.async_generators, &mut budget);
         async_generator_marks.sort();
         async_generator_marks.iter().for_each(|&idx| {
             let index = idx.get_index();
@@ -355,11 +493,11 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                async_generators.get(index).mark_values(&mut queues);
+                async_generators.get(index).mark_values(queues);
             }
         });
         let mut await_reaction_marks: Box<[AwaitReactionIdentifier]> =
-            queues.await_reactions.drain(..).collect();
+            take_work(&mut queues.await_reactions, &mut budget);
         await_reaction_marks.sort();
         await_reaction_marks.iter().for_each(|&idx| {
             let index = idx.into_index();
@@ -369,10 +507,10 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                await_reactions.get(index).mark_values(&mut queues);
+                await_reactions.get(index).mark_values(queues);
             }
         });
-        let mut bigint_marks: Box<[HeapBigInt]> = queues.bigints.drain(..).collect();
+        let mut bigint_marks: Box<[HeapBigInt]> = take_work(&mut queues.bigints, &mut budget);
         bigint_marks.sort();
         bigint_marks.iter().for_each(|&idx| {
             let index = idx.get_index();
@@ -382,11 +520,11 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                bigints.get(index).mark_values(&mut queues);
+                bigints.get(index).mark_values(queues);
             }
         });
         let mut bound_function_marks: Box<[BoundFunction]> =
-            queues.bound_functions.drain(..).collect();
+            take_work(&mut queues.bound_functions, &mut budget);
         bound_function_marks.sort();
         bound_function_marks.iter().for_each(|&idx| {
             let index = idx.get_index();
@@ -396,11 +534,11 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                bound_functions.get(index).mark_values(&mut queues);
+                bound_functions.get(index).mark_values(queues);
             }
         });
         let mut ecmascript_function_marks: Box<[ECMAScriptFunction]> =
-            queues.ecmascript_functions.drain(..).collect();
+            take_work(&mut queues.ecmascript_functions, &mut budget);
         ecmascript_function_marks.sort();
         ecmascript_function_marks.iter().for_each(|&idx| {
             let index = idx.get_index();
@@ -410,10 +548,10 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                ecmascript_functions.get(index).mark_values(&mut queues);
+                ecmascript_functions.get(index).mark_values(queues);
             }
         });
-        let mut error_marks: Box<[Error]> = queues.errors.drain(..).collect();
+        let mut error_marks: Box<[Error]> = take_work(&mut queues.errors, &mut budget);
         error_marks.sort();
         error_marks.iter().for_each(|&idx| {
             let index = idx.get_index();
@@ -423,10 +561,11 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                errors.get(index).mark_values(&mut queues);
+                errors.get(index).mark_values(queues);
             }
         });
-        let mut executable_marks: Box<[Executable]> = queues.executables.drain(..).collect();
+        let mut executable_marks: Box<[Executable]> =
+            take_work(&mut queues.executables, &mut budget);
         executable_marks.sort();
         executable_marks.iter().for_each(|&idx| {
             let index = idx.get_index();
@@ -436,10 +575,11 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                executables.get(index).mark_values(&mut queues);
+                executables.get(index).mark_values(queues);
             }
         });
-        let mut source_code_marks: Box<[SourceCode]> = queues.source_codes.drain(..).collect();
+        let mut source_code_marks: Box<[SourceCode]> =
+            take_work(&mut queues.source_codes, &mut budget);
         source_code_marks.sort();
         source_code_marks.iter().for_each(|&idx| {
             let index = idx.get_index();
@@ -449,11 +589,11 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                source_codes.get(index).mark_values(&mut queues);
+                source_codes.get(index).mark_values(queues);
             }
         });
         let mut builtin_constructors_marks: Box<[BuiltinConstructorFunction]> =
-            queues.builtin_constructors.drain(..).collect();
+            take_work(&mut queues.builtin_constructors, &mut budget);
         builtin_constructors_marks.sort();
         builtin_constructors_marks.iter().for_each(|&idx| {
             let index = idx.get_index();
@@ -463,11 +603,11 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                builtin_constructors.get(index).mark_values(&mut queues);
+                builtin_constructors.get(index).mark_values(queues);
             }
         });
         let mut builtin_functions_marks: Box<[BuiltinFunction]> =
-            queues.builtin_functions.drain(..).collect();
+            take_work(&mut queues.builtin_functions, &mut budget);
         builtin_functions_marks.sort();
         builtin_functions_marks.iter().for_each(|&idx| {
             let index = idx.get_index();
@@ -477,12 +617,13 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                builtin_functions.get(index).mark_values(&mut queues);
+                builtin_functions.get(index).mark_values(queues);
             }
         });
         #[cfg(feature = "array-buffer")]
         {
-            let mut data_view_marks: Box<[DataView]> = queues.data_views.drain(..).collect();
+            let mut data_view_marks: Box<[DataView]> =
+                take_work(&mut queues.data_views, &mut budget);
             data_view_marks.sort();
             data_view_marks.iter().for_each(|&idx| {
                 let index = idx.get_index();
@@ -492,13 +633,13 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                         return;
                     }
                     *marked = true;
-                    data_views.get(index).mark_values(&mut queues);
+                    data_views.get(index).mark_values(queues);
                 }
             });
         }
         #[cfg(feature = "date")]
         {
-            let mut date_marks: Box<[Date]> = queues.dates.drain(..).collect();
+            let mut date_marks: Box<[Date]> = take_work(&mut queues.dates, &mut budget);
             date_marks.sort();
             date_marks.iter().for_each(|&idx| {
                 let index = idx.get_index();
@@ -508,12 +649,12 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                         return;
                     }
                     *marked = true;
-                    dates.get(index).mark_values(&mut queues);
+                    dates.get(index).mark_values(queues);
                 }
             });
         }
         let mut embedder_object_marks: Box<[EmbedderObject]> =
-            queues.embedder_objects.drain(..).collect();
+            take_work(&mut queues.embedder_objects, &mut budget);
         embedder_object_marks.sort();
         embedder_object_marks.iter().for_each(|&idx| {
             let index = idx.get_index();
@@ -523,11 +664,11 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                embedder_objects.get(index).mark_values(&mut queues);
+                embedder_objects.get(index).mark_values(queues);
             }
         });
         let mut finalization_registry_marks: Box<[FinalizationRegistry]> =
-            queues.finalization_registrys.drain(..).collect();
+            take_work(&mut queues.finalization_registrys, &mut budget);
         finalization_registry_marks.sort();
         finalization_registry_marks.iter().for_each(|&idx| {
             let index = idx.get_index();
@@ -537,10 +678,10 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                finalization_registrys.get(index).mark_values(&mut queues);
+                finalization_registrys.get(index).mark_values(queues);
             }
         });
-        let mut generator_marks: Box<[Generator]> = queues.generators.drain(..).collect();
+        let mut generator_marks: Box<[Generator]> = take_work(&mut queues.generators, &mut budget);
         generator_marks.sort();
         generator_marks.iter().for_each(|&idx| {
             let index = idx.get_index();
@@ -550,10 +691,10 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                generators.get(index).mark_values(&mut queues);
+                generators.get(index).mark_values(queues);
             }
         });
-        let mut object_marks: Box<[OrdinaryObject]> = queues.objects.drain(..).collect();
+        let mut object_marks: Box<[OrdinaryObject]> = take_work(&mut queues.objects, &mut budget);
         object_marks.sort();
         object_marks.iter().for_each(|&idx| {
             let index = idx.get_index();
@@ -563,10 +704,10 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                objects.get(index).mark_values(&mut queues);
+                objects.get(index).mark_values(queues);
             }
         });
-        let mut promise_marks: Box<[Promise]> = queues.promises.drain(..).collect();
+        let mut promise_marks: Box<[Promise]> = take_work(&mut queues.promises, &mut budget);
         promise_marks.sort();
         promise_marks.iter().for_each(|&idx| {
             let index = idx.get_index();
@@ -576,11 +717,11 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                promises.get(index).mark_values(&mut queues);
+                promises.get(index).mark_values(queues);
             }
         });
         let mut promise_reaction_record_marks: Box<[PromiseReaction]> =
-            queues.promise_reaction_records.drain(..).collect();
+            take_work(&mut queues.promise_reaction_records, &mut budget);
         promise_reaction_record_marks.sort();
         promise_reaction_record_marks.iter().for_each(|&idx| {
             let index = idx.get_index();
@@ -590,11 +731,11 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                promise_reaction_records.get(index).mark_values(&mut queues);
+                promise_reaction_records.get(index).mark_values(queues);
             }
         });
         let mut promise_resolving_function_marks: Box<[BuiltinPromiseResolvingFunction]> =
-            queues.promise_resolving_functions.drain(..).collect();
+            take_work(&mut queues.promise_resolving_functions, &mut budget);
         promise_resolving_function_marks.sort();
         promise_resolving_function_marks.iter().for_each(|&idx| {
             let index = idx.get_index();
@@ -604,12 +745,10 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                promise_resolving_functions
-                    .get(index)
-                    .mark_values(&mut queues);
+                promise_resolving_functions.get(index).mark_values(queues);
             }
         });
-        let mut proxy_marks: Box<[Proxy]> = queues.proxys.drain(..).collect();
+        let mut proxy_marks: Box<[Proxy]> = take_work(&mut queues.proxys, &mut budget);
         proxy_marks.sort();
         proxy_marks.iter().for_each(|&idx| {
             let index = idx.get_index();
@@ -619,10 +758,10 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                proxys.get(index).mark_values(&mut queues);
+                proxys.get(index).mark_values(queues);
             }
         });
-        let mut map_marks: Box<[Map]> = queues.maps.drain(..).collect();
+        let mut map_marks: Box<[Map]> = take_work(&mut queues.maps, &mut budget);
         map_marks.sort();
         map_marks.iter().for_each(|&idx| {
             let index = idx.get_index();
@@ -632,10 +771,11 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                maps.get(index).mark_values(&mut queues);
+                maps.get(index).mark_values(queues);
             }
         });
-        let mut map_iterator_marks: Box<[MapIterator]> = queues.map_iterators.drain(..).collect();
+        let mut map_iterator_marks: Box<[MapIterator]> =
+            take_work(&mut queues.map_iterators, &mut budget);
         map_iterator_marks.sort();
         map_iterator_marks.iter().for_each(|&idx| {
             let index = idx.get_index();
@@ -645,10 +785,10 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                map_iterators.get(index).mark_values(&mut queues);
+                map_iterators.get(index).mark_values(queues);
             }
         });
-        let mut number_marks: Box<[HeapNumber]> = queues.numbers.drain(..).collect();
+        let mut number_marks: Box<[HeapNumber]> = take_work(&mut queues.numbers, &mut budget);
         number_marks.sort();
         number_marks.iter().for_each(|&idx| {
             let index = idx.get_index();
@@ -658,11 +798,11 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                numbers.get(index).mark_values(&mut queues);
+                numbers.get(index).mark_values(queues);
             }
         });
         let mut primitive_object_marks: Box<[PrimitiveObject]> =
-            queues.primitive_objects.drain(..).collect();
+            take_work(&mut queues.primitive_objects, &mut budget);
         primitive_object_marks.sort();
         primitive_object_marks.iter().for_each(|&idx| {
             let index = idx.get_index();
@@ -672,12 +812,12 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                primitive_objects.get(index).mark_values(&mut queues);
+                primitive_objects.get(index).mark_values(queues);
             }
         });
         #[cfg(feature = "regexp")]
         {
-            let mut regexp_marks: Box<[RegExp]> = queues.regexps.drain(..).collect();
+            let mut regexp_marks: Box<[RegExp]> = take_work(&mut queues.regexps, &mut budget);
             regexp_marks.sort();
             regexp_marks.iter().for_each(|&idx| {
                 let index = idx.get_index();
@@ -687,13 +827,28 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                         return;
                     }
                     *marked = true;
-                    regexps.get(index).mark_values(&mut queues);
+                    regexps.get(index).mark_values(queues);
+                }
+            });
+
+            let mut reg_exp_string_iterator_marks: Box<[RegExpStringIterator]> =
+                take_work(&mut queues.reg_exp_string_iterators, &mut budget);
+            reg_exp_string_iterator_marks.sort();
+            reg_exp_string_iterator_marks.iter().for_each(|&idx| {
+                let index = idx.get_index();
+                if let Some(marked) = bits.reg_exp_string_iterators.get_mut(index) {
+                    if *marked {
+                        // Already marked, ignore
+                        return;
+                    }
+                    *marked = true;
+                    reg_exp_string_iterators.get(index).mark_values(queues);
                 }
             });
         }
         #[cfg(feature = "set")]
         {
-            let mut set_marks: Box<[Set]> = queues.sets.drain(..).collect();
+            let mut set_marks: Box<[Set]> = take_work(&mut queues.sets, &mut budget);
             set_marks.sort();
             set_marks.iter().for_each(|&idx| {
                 let index = idx.get_index();
@@ -703,12 +858,12 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                         return;
                     }
                     *marked = true;
-                    sets.get(index).mark_values(&mut queues);
+                    sets.get(index).mark_values(queues);
                 }
             });
 
             let mut set_iterator_marks: Box<[SetIterator]> =
-                queues.set_iterators.drain(..).collect();
+                take_work(&mut queues.set_iterators, &mut budget);
             set_iterator_marks.sort();
             set_iterator_marks.iter().for_each(|&idx| {
                 let index = idx.get_index();
@@ -718,14 +873,14 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                         return;
                     }
                     *marked = true;
-                    set_iterators.get(index).mark_values(&mut queues);
+                    set_iterators.get(index).mark_values(queues);
                 }
             });
         }
         #[cfg(feature = "shared-array-buffer")]
         {
             let mut shared_array_buffer_marks: Box<[SharedArrayBuffer]> =
-                queues.shared_array_buffers.drain(..).collect();
+                take_work(&mut queues.shared_array_buffers, &mut budget);
             shared_array_buffer_marks.sort();
             shared_array_buffer_marks.iter().for_each(|&idx| {
                 let index = idx.get_index();
@@ -735,12 +890,12 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                         return;
                     }
                     *marked = true;
-                    shared_array_buffers.get(index).mark_values(&mut queues);
+                    shared_array_buffers.get(index).mark_values(queues);
                 }
             });
         }
         let mut string_generator_marks: Box<[StringIterator]> =
-            queues.string_iterators.drain(..).collect();
+            take_work(&mut queues.string_iterators, &mut budget);
         string_generator_marks.sort();
         string_generator_marks.iter().for_each(|&idx| {
             let index = idx.get_index();
@@ -750,10 +905,10 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                string_iterators.get(index).mark_values(&mut queues);
+                string_iterators.get(index).mark_values(queues);
             }
         });
-        let mut string_marks: Box<[HeapString]> = queues.strings.drain(..).collect();
+        let mut string_marks: Box<[HeapString]> = take_work(&mut queues.strings, &mut budget);
         string_marks.sort();
         string_marks.iter().for_each(|&idx| {
             let index = idx.get_index();
@@ -763,10 +918,10 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                strings.get(index).mark_values(&mut queues);
+                strings.get(index).mark_values(queues);
             }
         });
-        let mut symbol_marks: Box<[Symbol]> = queues.symbols.drain(..).collect();
+        let mut symbol_marks: Box<[Symbol]> = take_work(&mut queues.symbols, &mut budget);
         symbol_marks.sort();
         symbol_marks.iter().for_each(|&idx| {
             let index = idx.get_index();
@@ -776,13 +931,13 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                     return;
                 }
                 *marked = true;
-                symbols.get(index).mark_values(&mut queues);
+                symbols.get(index).mark_values(queues);
             }
         });
         #[cfg(feature = "array-buffer")]
         {
             let mut typed_arrays_marks: Box<[TypedArrayIndex]> =
-                queues.typed_arrays.drain(..).collect();
+                take_work(&mut queues.typed_arrays, &mut budget);
             typed_arrays_marks.sort();
             typed_arrays_marks.iter().for_each(|&idx| {
                 let index = idx.into_index();
@@ -792,13 +947,13 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                         return;
                     }
                     *marked = true;
-                    typed_arrays.get(index).mark_values(&mut queues);
+                    typed_arrays.get(index).mark_values(queues);
                 }
             });
         }
         #[cfg(feature = "weak-refs")]
         {
-            let mut weak_map_marks: Box<[WeakMap]> = queues.weak_maps.drain(..).collect();
+            let mut weak_map_marks: Box<[WeakMap]> = take_work(&mut queues.weak_maps, &mut budget);
             weak_map_marks.sort();
             weak_map_marks.iter().for_each(|&idx| {
                 let index = idx.get_index();
@@ -808,10 +963,10 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                         return;
                     }
                     *marked = true;
-                    weak_maps.get(index).mark_values(&mut queues);
+                    weak_maps.get(index).mark_values(queues);
                 }
             });
-            let mut weak_ref_marks: Box<[WeakRef]> = queues.weak_refs.drain(..).collect();
+            let mut weak_ref_marks: Box<[WeakRef]> = take_work(&mut queues.weak_refs, &mut budget);
             weak_ref_marks.sort();
             weak_ref_marks.iter().for_each(|&idx| {
                 let index = idx.get_index();
@@ -821,10 +976,10 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                         return;
                     }
                     *marked = true;
-                    weak_refs.get(index).mark_values(&mut queues);
+                    weak_refs.get(index).mark_values(queues);
                 }
             });
-            let mut weak_set_marks: Box<[WeakSet]> = queues.weak_sets.drain(..).collect();
+            let mut weak_set_marks: Box<[WeakSet]> = take_work(&mut queues.weak_sets, &mut budget);
             weak_set_marks.sort();
             weak_set_marks.iter().for_each(|&idx| {
                 let index = idx.get_index();
@@ -834,327 +989,419 @@ pub fn heap_gc(agent: &mut Agent, root_realms: &mut [Option<Realm<'static>>], gc
                         return;
                     }
                     *marked = true;
-                    weak_sets.get(index).mark_values(&mut queues);
+                    weak_sets.get(index).mark_values(queues);
                 }
             });
         }
 
-        let mut e_2_4_marks: Box<[(ElementIndex, u32)]> = queues.e_2_4.drain(..).collect();
+        let mut e_2_4_marks: Box<[(ElementIndex, u32)]> = take_work(&mut queues.e_2_4, &mut budget);
         e_2_4_marks.sort();
         e_2_4_marks.iter().for_each(|&(idx, len)| {
             let index = idx.into_index();
             if let Some((marked, length)) = bits.e_2_4.get_mut(index) {
-                if *marked {
+                if !*marked {
+                    *marked = true;
+                    *length = len as u8;
+                } else if incremental {
+                    // Already marked but written to since: Rescan up to the
+                    // longest length seen.
+                    *length = (*length).max(len as u8);
+                } else {
                     // Already marked, panic: Elements are uniquely owned
                     // and any other reference existing to this entry is a sign of
                     // a GC algorithm bug.
                     panic!("ElementsVector was not unique");
                 }
-                *marked = true;
-                *length = len as u8;
                 if let Some(descriptors) = e2pow4.descriptors.get(&idx) {
-                    mark_descriptors(descriptors, &mut queues);
+                    mark_descriptors(descriptors, queues);
                 }
                 if let Some(array) = e2pow4.values.get(index) {
-                    mark_optional_array_with_u32_length(array, &mut queues, len);
+                    mark_optional_array_with_u32_length(array, queues, len);
                 }
             }
         });
-        let mut e_2_6_marks: Box<[(ElementIndex, u32)]> = queues.e_2_6.drain(..).collect();
+        let mut e_2_6_marks: Box<[(ElementIndex, u32)]> = take_work(&mut queues.e_2_6, &mut budget);
         e_2_6_marks.sort();
         e_2_6_marks.iter().for_each(|&(idx, len)| {
             let index = idx.into_index();
             if let Some((marked, length)) = bits.e_2_6.get_mut(index) {
-                if *marked {
+                if !*marked {
+                    *marked = true;
+                    *length = len as u8;
+                } else if incremental {
+                    // Already marked but written to since: Rescan up to the
+                    // longest length seen.
+                    *length = (*length).max(len as u8);
+                } else {
                     // Already marked, panic: Elements are uniquely owned
                     // and any other reference existing to this entry is a sign of
                     // a GC algorithm bug.
                     panic!("ElementsVector was not unique");
                 }
-                *marked = true;
-                *length = len as u8;
                 if let Some(descriptors) = e2pow6.descriptors.get(&idx) {
-                    mark_descriptors(descriptors, &mut queues);
+                    mark_descriptors(descriptors, queues);
                 }
                 if let Some(array) = e2pow6.values.get(index) {
-                    mark_optional_array_with_u32_length(array, &mut queues, len);
+                    mark_optional_array_with_u32_length(array, queues, len);
                 }
             }
         });
-        let mut e_2_8_marks: Box<[(ElementIndex, u32)]> = queues.e_2_8.drain(..).collect();
+        let mut e_2_8_marks: Box<[(ElementIndex, u32)]> = take_work(&mut queues.e_2_8, &mut budget);
         e_2_8_marks.sort();
         e_2_8_marks.iter().for_each(|&(idx, len)| {
             let index = idx.into_index();
             if let Some((marked, length)) = bits.e_2_8.get_mut(index) {
-                if *marked {
+                if !*marked {
+                    *marked = true;
+                    *length = len as u8;
+                } else if incremental {
+                    // Already marked but written to since: Rescan up to the
+                    // longest length seen.
+                    *length = (*length).max(len as u8);
+                } else {
                     // Already marked, panic: Elements are uniquely owned
                     // and any other reference existing to this entry is a sign of
                     // a GC algorithm bug.
                     panic!("ElementsVector was not unique");
                 }
-                *marked = true;
-                *length = len as u8;
                 if let Some(descriptors) = e2pow8.descriptors.get(&idx) {
-                    mark_descriptors(descriptors, &mut queues);
+                    mark_descriptors(descriptors, queues);
                 }
                 if let Some(array) = e2pow8.values.get(index) {
-                    mark_optional_array_with_u32_length(array, &mut queues, len);
+                    mark_optional_array_with_u32_length(array, queues, len);
                 }
             }
         });
-        let mut e_2_10_marks: Box<[(ElementIndex, u32)]> = queues.e_2_10.drain(..).collect();
+        let mut e_2_10_marks: Box<[(ElementIndex, u32)]> =
+            take_work(&mut queues.e_2_10, &mut budget);
         e_2_10_marks.sort();
         e_2_10_marks.iter().for_each(|&(idx, len)| {
             let index = idx.into_index();
             if let Some((marked, length)) = bits.e_2_10.get_mut(index) {
-                if *marked {
+                if !*marked {
+                    *marked = true;
+                    *length = len as u16;
+                } else if incremental {
+                    // Already marked but written to since: Rescan up to the
+                    // longest length seen.
+                    *length = (*length).max(len as u16);
+                } else {
                     // Already marked, panic: Elements are uniquely owned
                     // and any other reference existing to this entry is a sign of
                     // a GC algorithm bug.
                     panic!("ElementsVector was not unique");
                 }
-                *marked = true;
-                *length = len as u16;
                 if let Some(descriptors) = e2pow10.descriptors.get(&idx) {
-                    mark_descriptors(descriptors, &mut queues);
+                    mark_descriptors(descriptors, queues);
                 }
                 if let Some(array) = e2pow10.values.get(index) {
-                    mark_optional_array_with_u32_length(array, &mut queues, len);
+                    mark_optional_array_with_u32_length(array, queues, len);
                 }
             }
         });
-        let mut e_2_12_marks: Box<[(ElementIndex, u32)]> = queues.e_2_12.drain(..).collect();
+        let mut e_2_12_marks: Box<[(ElementIndex, u32)]> =
+            take_work(&mut queues.e_2_12, &mut budget);
         e_2_12_marks.sort();
         e_2_12_marks.iter().for_each(|&(idx, len)| {
             let index = idx.into_index();
             if let Some((marked, length)) = bits.e_2_12.get_mut(index) {
-                if *marked {
+                if !*marked {
+                    *marked = true;
+                    *length = len as u16;
+                } else if incremental {
+                    // Already marked but written to since: Rescan up to the
+                    // longest length seen.
+                    *length = (*length).max(len as u16);
+                } else {
                     // Already marked, panic: Elements are uniquely owned
                     // and any other reference existing to this entry is a sign of
                     // a GC algorithm bug.
                     panic!("ElementsVector was not unique");
                 }
-                *marked = true;
-                *length = len as u16;
                 if let Some(descriptors) = e2pow12.descriptors.get(&idx) {
-                    mark_descriptors(descriptors, &mut queues);
+                    mark_descriptors(descriptors, queues);
                 }
                 if let Some(array) = e2pow12.values.get(index) {
-                    mark_optional_array_with_u32_length(array, &mut queues, len);
+                    mark_optional_array_with_u32_length(array, queues, len);
                 }
             }
         });
-        let mut e_2_16_marks: Box<[(ElementIndex, u32)]> = queues.e_2_16.drain(..).collect();
+        let mut e_2_16_marks: Box<[(ElementIndex, u32)]> =
+            take_work(&mut queues.e_2_16, &mut budget);
         e_2_16_marks.sort();
         e_2_16_marks.iter().for_each(|&(idx, len)| {
             let index = idx.into_index();
             if let Some((marked, length)) = bits.e_2_16.get_mut(index) {
-                if *marked {
+                if !*marked {
+                    *marked = true;
+                    *length = len as u16;
+                } else if incremental {
+                    // Already marked but written to since: Rescan up to the
+                    // longest length seen.
+                    *length = (*length).max(len as u16);
+                } else {
                     // Already marked, panic: Elements are uniquely owned
                     // and any other reference existing to this entry is a sign of
                     // a GC algorithm bug.
                     panic!("ElementsVector was not unique");
                 }
-                *marked = true;
-                *length = len as u16;
                 if let Some(descriptors) = e2pow16.descriptors.get(&idx) {
-                    mark_descriptors(descriptors, &mut queues);
+                    mark_descriptors(descriptors, queues);
                 }
                 if let Some(array) = e2pow16.values.get(index) {
-                    mark_optional_array_with_u32_length(array, &mut queues, len);
+                    mark_optional_array_with_u32_length(array, queues, len);
                 }
             }
         });
-        let mut e_2_24_marks: Box<[(ElementIndex, u32)]> = queues.e_2_24.drain(..).collect();
+        let mut e_2_24_marks: Box<[(ElementIndex, u32)]> =
+            take_work(&mut queues.e_2_24, &mut budget);
         e_2_24_marks.sort();
         e_2_24_marks.iter().for_each(|&(idx, len)| {
             let index = idx.into_index();
             if let Some((marked, length)) = bits.e_2_24.get_mut(index) {
-                if *marked {
+                if !*marked {
+                    *marked = true;
+                    *length = len;
+                } else if incremental {
+                    // Already marked but written to since: Rescan up to the
+                    // longest length seen.
+                    *length = (*length).max(len);
+                } else {
                     // Already marked, panic: Elements are uniquely owned
                     // and any other reference existing to this entry is a sign of
                     // a GC algorithm bug.
                     panic!("ElementsVector was not unique");
                 }
-                *marked = true;
-                *length = len;
                 if let Some(descriptors) = e2pow24.descriptors.get(&idx) {
-                    mark_descriptors(descriptors, &mut queues);
+                    mark_descriptors(descriptors, queues);
                 }
                 if let Some(array) = e2pow24.values.get(index) {
-                    mark_optional_array_with_u32_length(array, &mut queues, len);
+                    mark_optional_array_with_u32_length(array, queues, len);
                 }
             }
         });
-        let mut e_2_32_marks: Box<[(ElementIndex, u32)]> = queues.e_2_32.drain(..).collect();
+        let mut e_2_32_marks: Box<[(ElementIndex, u32)]> =
+            take_work(&mut queues.e_2_32, &mut budget);
         e_2_32_marks.sort();
         e_2_32_marks.iter().for_each(|&(idx, len)| {
             let index = idx.into_index();
             if let Some((marked, length)) = bits.e_2_32.get_mut(index) {
-                if *marked {
+                if !*marked {
+                    *marked = true;
+                    *length = len;
+                } else if incremental {
+                    // Already marked but written to since: Rescan up to the
+                    // longest length seen.
+                    *length = (*length).max(len);
+                } else {
                     // Already marked, panic: Elements are uniquely owned
                     // and any other reference existing to this entry is a sign of
                     // a GC algorithm bug.
                     panic!("ElementsVector was not unique");
                 }
-                *marked = true;
-                *length = len;
                 if let Some(descriptors) = e2pow32.descriptors.get(&idx) {
-                    mark_descriptors(descriptors, &mut queues);
+                    mark_descriptors(descriptors, queues);
                 }
                 if let Some(array) = e2pow32.values.get(index) {
-                    mark_optional_array_with_u32_length(array, &mut queues, len);
+                    mark_optional_array_with_u32_length(array, queues, len);
                 }
             }
         });
 
-        let mut k_2_4_marks: Box<[(PropertyKeyIndex, u32)]> = queues.k_2_4.drain(..).collect();
+        let mut k_2_4_marks: Box<[(PropertyKeyIndex, u32)]> =
+            take_work(&mut queues.k_2_4, &mut budget);
         k_2_4_marks.sort();
         k_2_4_marks.iter().for_each(|&(idx, len)| {
             let index = idx.into_index();
             if let Some((marked, length)) = bits.k_2_4.get_mut(index) {
-                if *marked {
+                if !*marked {
+                    *marked = true;
+                    *length = len as u8;
+                } else if incremental {
+                    // Already marked but written to since: Rescan up to the
+                    // longest length seen.
+                    *length = (*length).max(len as u8);
+                } else {
                     // Already marked, panic: Elements are uniquely owned
                     // and any other reference existing to this entry is a sign of
                     // a GC algorithm bug.
                     panic!("ElementsVector was not unique");
                 }
-                *marked = true;
-                *length = len as u8;
                 if let Some(array) = k2pow4.keys.get(index) {
-                    mark_array_with_u32_length(array, &mut queues, len);
+                    mark_array_with_u32_length(array, queues, len);
                 }
             }
         });
-        let mut k_2_6_marks: Box<[(PropertyKeyIndex, u32)]> = queues.k_2_6.drain(..).collect();
+        let mut k_2_6_marks: Box<[(PropertyKeyIndex, u32)]> =
+            take_work(&mut queues.k_2_6, &mut budget);
         k_2_6_marks.sort();
         k_2_6_marks.iter().for_each(|&(idx, len)| {
             let index = idx.into_index();
             if let Some((marked, length)) = bits.k_2_6.get_mut(index) {
-                if *marked {
+                if !*marked {
+                    *marked = true;
+                    *length = len as u8;
+                } else if incremental {
+                    // Already marked but written to since: Rescan up to the
+                    // longest length seen.
+                    *length = (*length).max(len as u8);
+                } else {
                     // Already marked, panic: Elements are uniquely owned
                     // and any other reference existing to this entry is a sign of
                     // a GC algorithm bug.
                     panic!("ElementsVector was not unique");
                 }
-                *marked = true;
-                *length = len as u8;
                 if let Some(array) = k2pow6.keys.get(index) {
-                    mark_array_with_u32_length(array, &mut queues, len);
+                    mark_array_with_u32_length(array, queues, len);
                 }
             }
         });
-        let mut k_2_8_marks: Box<[(PropertyKeyIndex, u32)]> = queues.k_2_8.drain(..).collect();
+        let mut k_2_8_marks: Box<[(PropertyKeyIndex, u32)]> =
+            take_work(&mut queues.k_2_8, &mut budget);
         k_2_8_marks.sort();
         k_2_8_marks.iter().for_each(|&(idx, len)| {
             let index = idx.into_index();
             if let Some((marked, length)) = bits.k_2_8.get_mut(index) {
-                if *marked {
+                if !*marked {
+                    *marked = true;
+                    *length = len as u8;
+                } else if incremental {
+                    // Already marked but written to since: Rescan up to the
+                    // longest length seen.
+                    *length = (*length).max(len as u8);
+                } else {
                     // Already marked, panic: Elements are uniquely owned
                     // and any other reference existing to this entry is a sign of
                     // a GC algorithm bug.
                     panic!("ElementsVector was not unique");
                 }
-                *marked = true;
-                *length = len as u8;
                 if let Some(array) = k2pow8.keys.get(index) {
-                    mark_array_with_u32_length(array, &mut queues, len);
+                    mark_array_with_u32_length(array, queues, len);
                 }
             }
         });
-        let mut k_2_10_marks: Box<[(PropertyKeyIndex, u32)]> = queues.k_2_10.drain(..).collect();
+        let mut k_2_10_marks: Box<[(PropertyKeyIndex, u32)]> =
+            take_work(&mut queues.k_2_10, &mut budget);
         k_2_10_marks.sort();
         k_2_10_marks.iter().for_each(|&(idx, len)| {
             let index = idx.into_index();
             if let Some((marked, length)) = bits.k_2_10.get_mut(index) {
-                if *marked {
+                if !*marked {
+                    *marked = true;
+                    *length = len as u16;
+                } else if incremental {
+                    // Already marked but written to since: Rescan up to the
+                    // longest length seen.
+                    *length = (*length).max(len as u16);
+                } else {
                     // Already marked, panic: Elements are uniquely owned
                     // and any other reference existing to this entry is a sign of
                     // a GC algorithm bug.
                     panic!("ElementsVector was not unique");
                 }
-                *marked = true;
-                *length = len as u16;
                 if let Some(array) = k2pow10.keys.get(index) {
-                    mark_array_with_u32_length(array, &mut queues, len);
+                    mark_array_with_u32_length(array, queues, len);
                 }
             }
         });
-        let mut k_2_12_marks: Box<[(PropertyKeyIndex, u32)]> = queues.k_2_12.drain(..).collect();
+        let mut k_2_12_marks: Box<[(PropertyKeyIndex, u32)]> =
+            take_work(&mut queues.k_2_12, &mut budget);
         k_2_12_marks.sort();
         k_2_12_marks.iter().for_each(|&(idx, len)| {
             let index = idx.into_index();
             if let Some((marked, length)) = bits.k_2_12.get_mut(index) {
-                if *marked {
+                if !*marked {
+                    *marked = true;
+                    *length = len as u16;
+                } else if incremental {
+                    // Already marked but written to since: Rescan up to the
+                    // longest length seen.
+                    *length = (*length).max(len as u16);
+                } else {
                     // Already marked, panic: Elements are uniquely owned
                     // and any other reference existing to this entry is a sign of
                     // a GC algorithm bug.
                     panic!("ElementsVector was not unique");
                 }
-                *marked = true;
-                *length = len as u16;
                 if let Some(array) = k2pow12.keys.get(index) {
-                    mark_array_with_u32_length(array, &mut queues, len);
+                    mark_array_with_u32_length(array, queues, len);
                 }
             }
         });
-        let mut k_2_16_marks: Box<[(PropertyKeyIndex, u32)]> = queues.k_2_16.drain(..).collect();
+        let mut k_2_16_marks: Box<[(PropertyKeyIndex, u32)]> =
+            take_work(&mut queues.k_2_16, &mut budget);
         k_2_16_marks.sort();
         k_2_16_marks.iter().for_each(|&(idx, len)| {
             let index = idx.into_index();
             if let Some((marked, length)) = bits.k_2_16.get_mut(index) {
-                if *marked {
+                if !*marked {
+                    *marked = true;
+                    *length = len as u16;
+                } else if incremental {
+                    // Already marked but written to since: Rescan up to the
+                    // longest length seen.
+                    *length = (*length).max(len as u16);
+                } else {
                     // Already marked, panic: Elements are uniquely owned
                     // and any other reference existing to this entry is a sign of
                     // a GC algorithm bug.
                     panic!("ElementsVector was not unique");
                 }
-                *marked = true;
-                *length = len as u16;
                 if let Some(array) = k2pow16.keys.get(index) {
-                    mark_array_with_u32_length(array, &mut queues, len);
+                    mark_array_with_u32_length(array, queues, len);
                 }
             }
         });
-        let mut k_2_24_marks: Box<[(PropertyKeyIndex, u32)]> = queues.k_2_24.drain(..).collect();
+        let mut k_2_24_marks: Box<[(PropertyKeyIndex, u32)]> =
+            take_work(&mut queues.k_2_24, &mut budget);
         k_2_24_marks.sort();
         k_2_24_marks.iter().for_each(|&(idx, len)| {
             let index = idx.into_index();
             if let Some((marked, length)) = bits.k_2_24.get_mut(index) {
-                if *marked {
+                if !*marked {
+                    *marked = true;
+                    *length = len;
+                } else if incremental {
+                    // Already marked but written to since: Rescan up to the
+                    // longest length seen.
+                    *length = (*length).max(len);
+                } else {
                     // Already marked, panic: Elements are uniquely owned
                     // and any other reference existing to this entry is a sign of
                     // a GC algorithm bug.
                     panic!("ElementsVector was not unique");
                 }
-                *marked = true;
-                *length = len;
                 if let Some(array) = k2pow24.keys.get(index) {
-                    mark_array_with_u32_length(array, &mut queues, len);
+                    mark_array_with_u32_length(array, queues, len);
                 }
             }
         });
-        let mut k_2_32_marks: Box<[(PropertyKeyIndex, u32)]> = queues.k_2_32.drain(..).collect();
+        let mut k_2_32_marks: Box<[(PropertyKeyIndex, u32)]> =
+            take_work(&mut queues.k_2_32, &mut budget);
         k_2_32_marks.sort();
         k_2_32_marks.iter().for_each(|&(idx, len)| {
             let index = idx.into_index();
             if let Some((marked, length)) = bits.k_2_32.get_mut(index) {
-                if *marked {
+                if !*marked {
+                    *marked = true;
+                    *length = len;
+                } else if incremental {
+                    // Already marked but written to since: Rescan up to the
+                    // longest length seen.
+                    *length = (*length).max(len);
+                } else {
                     // Already marked, panic: Elements are uniquely owned
                     // and any other reference existing to this entry is a sign of
                     // a GC algorithm bug.
                     panic!("ElementsVector was not unique");
                 }
-                *marked = true;
-                *length = len;
                 if let Some(array) = k2pow32.keys.get(index) {
-                    mark_array_with_u32_length(array, &mut queues, len);
+                    mark_array_with_u32_length(array, queues, len);
                 }
             }
         });
     }
-
-    sweep(agent, &bits, root_realms, gc);
+    initial_budget - budget
 }
 
 fn sweep(
@@ -1163,7 +1410,9 @@ fn sweep(
     root_realms: &mut [Option<Realm<'static>>],
     _: GcScope,
 ) {
-    let compactions = CompactionLists::create_from_bits(bits);
+    // The own keys cache does not keep its objects or keys alive.
+    agent.heap.own_keys_cache.clear();
+    let compactions = CompactionLists::create_from_bits(bits, agent.options.compaction);
 
     for realm in root_realms {
         realm.sweep_values(&compactions);
@@ -1202,11 +1451,13 @@ fn sweep(
         finalization_registrys,
         generators,
         globals,
+        incremental_marking: _,
         maps,
         map_iterators,
         modules,
         numbers,
         objects,
+        own_keys_cache: _,
         primitive_objects,
         promise_reaction_records,
         promise_resolving_functions,
@@ -1220,6 +1471,8 @@ fn sweep(
         sets,
         #[cfg(feature = "set")]
         set_iterators,
+        #[cfg(feature = "regexp")]
+        reg_exp_string_iterators,
         #[cfg(feature = "shared-array-buffer")]
         shared_array_buffers,
         string_iterators,
@@ -1241,6 +1494,7 @@ fn sweep(
         weak_refs,
         #[cfg(feature = "weak-refs")]
         weak_sets,
+        statistics: _,
         alloc_counter,
     } = &mut agent.heap;
     // Reset the allocation counter.
@@ -1250,7 +1504,7 @@ fn sweep(
         function,
         global,
         object,
-        private: _private_environments,
+        private,
     } = environments;
     let ElementArrays {
         e2pow4,
@@ -1269,6 +1523,7 @@ fn sweep(
         k2pow16,
         k2pow24,
         k2pow32,
+        write_log: _,
     } = elements;
 
     let mut globals = globals.borrow_mut();
@@ -1575,6 +1830,11 @@ fn sweep(
                 sweep_heap_vector_values(objects, &compactions, &bits.objects);
             });
         }
+        if !private.is_empty() {
+            s.spawn(|| {
+                sweep_heap_vector_values(private, &compactions, &bits.private_environments);
+            });
+        }
         if !primitive_objects.is_empty() {
             s.spawn(|| {
                 sweep_heap_vector_values(primitive_objects, &compactions, &bits.primitive_objects);
@@ -1636,6 +1896,16 @@ fn sweep(
                 sweep_heap_vector_values(set_iterators, &compactions, &bits.set_iterators);
             });
         }
+        #[cfg(feature = "regexp")]
+        if !reg_exp_string_iterators.is_empty() {
+            s.spawn(|| {
+                sweep_heap_vector_values(
+                    reg_exp_string_iterators,
+                    &compactions,
+                    &bits.reg_exp_string_iterators,
+                );
+            });
+        }
         #[cfg(feature = "shared-array-buffer")]
         if !shared_array_buffers.is_empty() {
             s.spawn(|| {
@@ -1699,21 +1969,17 @@ fn sweep(
 
 #[test]
 fn test_heap_gc() {
-    use crate::engine::context::GcScope;
     use crate::{
         ecmascript::execution::{DefaultHostHooks, agent::Options},
         engine::rootable::HeapRootData,
     };
 
     let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
-
-    let (mut gc, mut scope) = unsafe { GcScope::create_root() };
-    let mut gc = GcScope::new(&mut gc, &mut scope);
     assert!(agent.heap.objects.is_empty());
     let obj = HeapRootData::Object(agent.heap.create_null_object(&[]));
     println!("Object: {obj:#?}",);
     agent.heap.globals.borrow_mut().push(Some(obj));
-    heap_gc(&mut agent, &mut [], gc.reborrow());
+    agent.with_gc(|agent, gc| heap_gc(agent, &mut [], gc));
     println!("Objects: {:#?}", agent.heap.objects);
     assert_eq!(agent.heap.objects.len(), 1);
     assert_eq!(agent.heap.elements.e2pow4.values.len(), 0);
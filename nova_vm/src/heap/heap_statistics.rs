@@ -0,0 +1,183 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use core::time::Duration;
+
+use super::element_array::ElementArrayKey;
+
+/// Heap size and garbage collection statistics of an Agent.
+///
+/// See [`Agent::heap_statistics`](crate::ecmascript::execution::Agent::heap_statistics).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct HeapStatistics {
+    /// Number of heap data entries (objects, functions, strings, environments,
+    /// ...) currently allocated, not counting element and property key
+    /// storage. Right after a garbage collection this is the number of live
+    /// entries.
+    pub heap_entries: usize,
+    /// Number of bytes of bytecode instructions in the allocated Executables.
+    /// Right after a garbage collection this only counts reachable bytecode.
+    pub bytecode_bytes: usize,
+    /// Number of finished garbage collections, full and incremental.
+    pub collections: u64,
+    /// Number of incremental garbage collection slices performed, including
+    /// the slices that finished a collection.
+    pub incremental_slices: u64,
+    /// Number of incremental garbage collections that were finished
+    /// synchronously because allocation outran marking.
+    pub synchronous_finishes: u64,
+    /// Number of marking work queue items processed by the most recent
+    /// garbage collection slice or full collection.
+    pub last_slice_work: usize,
+    /// Duration of the most recent garbage collection pause.
+    pub last_pause: Duration,
+    /// Duration of the longest garbage collection pause.
+    pub max_pause: Duration,
+    /// Total duration of all garbage collection pauses.
+    pub total_pause: Duration,
+    /// True if an incremental garbage collection is in progress.
+    pub marking_in_progress: bool,
+}
+
+/// Fragmentation statistics of one heap vector.
+///
+/// See [`Agent::heap_vector_statistics`](crate::ecmascript::execution::Agent::heap_vector_statistics).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeapVectorStatistics {
+    /// Name of the heap vector, such as `"objects"` or `"e2pow4"` for the
+    /// element storage of up to 16 elements.
+    pub name: &'static str,
+    /// Number of live entries. Property key storage does not record whether
+    /// it is live, so all of its slots are counted.
+    pub live: usize,
+    /// Number of slots, including slots emptied in place by garbage
+    /// collections that did not compact the vector.
+    pub slots: usize,
+    /// Number of slots the vector has reserved memory for.
+    pub capacity: usize,
+}
+
+impl HeapStatistics {
+    /// Records a full garbage collection.
+    pub(crate) fn record_collection(&mut self, work: usize, pause: Duration) {
+        self.collections += 1;
+        self.last_slice_work = work;
+        self.record_pause(pause);
+    }
+
+    /// Records an incremental garbage collection slice.
+    pub(crate) fn record_slice(&mut self, work: usize, pause: Duration) {
+        self.incremental_slices += 1;
+        self.last_slice_work = work;
+        self.record_pause(pause);
+    }
+
+    fn record_pause(&mut self, pause: Duration) {
+        self.last_pause = pause;
+        self.max_pause = self.max_pause.max(pause);
+        self.total_pause += pause;
+    }
+}
+
+/// Element storage statistics of one capacity class of the element arrays.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ElementArrayClassStats {
+    /// The capacity class: every vector in this class has room for up to
+    /// [`ElementArrayKey::cap`] elements.
+    pub class: ElementArrayKey,
+    /// Number of element vectors currently allocated in this class.
+    pub live_vectors: usize,
+    /// Number of vector slots in the backing storage of this class,
+    /// including slots left empty by vectors that have since been freed.
+    pub total_slots: usize,
+    /// Number of property key vectors currently allocated in this class.
+    pub property_key_vectors: usize,
+}
+
+/// Per capacity class statistics of the element and property key storage
+/// backing arrays and object properties.
+///
+/// See [`Agent::element_array_stats`](crate::ecmascript::execution::Agent::element_array_stats).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ElementArrayStats {
+    /// Statistics of each capacity class, from the smallest to the largest.
+    pub classes: [ElementArrayClassStats; 8],
+}
+
+impl ElementArrayStats {
+    /// Returns the statistics of the given capacity class.
+    ///
+    /// The [`ElementArrayKey::Empty`] class has no storage, and reports
+    /// zeroes.
+    pub fn class(&self, class: ElementArrayKey) -> ElementArrayClassStats {
+        self.classes
+            .iter()
+            .find(|stats| stats.class == class)
+            .copied()
+            .unwrap_or(ElementArrayClassStats {
+                class,
+                ..Default::default()
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        ecmascript::{
+            execution::{
+                DefaultHostHooks,
+                agent::{GcAgent, Options},
+            },
+            types::String,
+        },
+        engine::context::Bindable,
+        heap::{ElementArrayStats, element_array::ElementArrayKey},
+    };
+
+    fn run_and_collect(agent: &mut GcAgent, source: &'static str) -> ElementArrayStats {
+        let realm = agent.create_default_realm();
+        agent.run_in_realm(&realm, |agent, mut gc| {
+            let source_text = String::from_static_str(agent, source, gc.nogc());
+            agent
+                .run_script(source_text.unbind(), gc.reborrow())
+                .unwrap();
+        });
+        agent.gc();
+        let stats = agent.element_array_stats();
+        agent.remove_realm(realm);
+        stats
+    }
+
+    #[test]
+    fn element_array_stats_per_capacity_class() {
+        let mut agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+        let baseline = run_and_collect(&mut agent, "globalThis.arrays = [];");
+        let stats = run_and_collect(
+            &mut agent,
+            "globalThis.arrays = [];
+            for (let i = 0; i < 100; i++) arrays.push(new Array(10).fill(i));
+            for (let i = 0; i < 20; i++) arrays.push(new Array(50).fill(i));
+            for (let i = 0; i < 5; i++) arrays.push(new Array(200).fill(i));
+            for (let i = 0; i < 2; i++) arrays.push(new Array(3000).fill(i));",
+        );
+
+        let grown = |class: ElementArrayKey| {
+            stats.class(class).live_vectors - baseline.class(class).live_vectors
+        };
+        assert!(grown(ElementArrayKey::E4) >= 100);
+        assert!(grown(ElementArrayKey::E6) >= 20);
+        assert!(grown(ElementArrayKey::E8) >= 5);
+        // The outer array of 127 arrays is in the 256 element class.
+        assert!(grown(ElementArrayKey::E8) <= 7);
+        assert!(grown(ElementArrayKey::E12) >= 2);
+        assert_eq!(stats.class(ElementArrayKey::E32).live_vectors, 0);
+        assert_eq!(stats.class(ElementArrayKey::Empty).live_vectors, 0);
+
+        // Garbage collection compacts the storage: no empty slots remain.
+        for class in stats.classes {
+            assert_eq!(class.live_vectors, class.total_slots, "{class:?}");
+        }
+    }
+}
@@ -4,8 +4,6 @@
 
 #[cfg(feature = "date")]
 use crate::ecmascript::builtins::date::data::DateHeapData;
-#[cfg(feature = "regexp")]
-use crate::ecmascript::builtins::regexp::RegExpHeapData;
 #[cfg(feature = "shared-array-buffer")]
 use crate::ecmascript::builtins::shared_array_buffer::data::SharedArrayBufferHeapData;
 #[cfg(feature = "array-buffer")]
@@ -17,6 +15,11 @@ use crate::ecmascript::builtins::{
     keyed_collections::set_objects::set_iterator_objects::set_iterator::SetIteratorHeapData,
     set::data::SetHeapData,
 };
+#[cfg(feature = "regexp")]
+use crate::ecmascript::builtins::{
+    regexp::RegExpHeapData,
+    text_processing::regexp_objects::regexp_string_iterator_objects::RegExpStringIteratorHeapData,
+};
 #[cfg(feature = "weak-refs")]
 use crate::ecmascript::builtins::{
     weak_map::data::WeakMapHeapData, weak_ref::data::WeakRefHeapData,
@@ -216,6 +219,8 @@ pub type RegExpIndex<'a> = BaseIndex<'a, RegExpHeapData<'static>>;
 pub type SetIndex<'a> = BaseIndex<'a, SetHeapData<'static>>;
 #[cfg(feature = "set")]
 pub type SetIteratorIndex<'a> = BaseIndex<'a, SetIteratorHeapData<'static>>;
+#[cfg(feature = "regexp")]
+pub type RegExpStringIteratorIndex<'a> = BaseIndex<'a, RegExpStringIteratorHeapData<'static>>;
 #[cfg(feature = "shared-array-buffer")]
 pub type SharedArrayBufferIndex<'a> = BaseIndex<'a, SharedArrayBufferHeapData<'static>>;
 pub type StringIndex<'a> = BaseIndex<'a, StringHeapData>;
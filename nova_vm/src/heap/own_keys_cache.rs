@@ -0,0 +1,205 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Cache of ordinary objects' \[\[OwnPropertyKeys]] results.
+//!
+//! Every ordinary object carries a keys version in its heap data, bumped
+//! whenever a property key is added or removed, or when a property's
+//! enumerability changes. The cache remembers the last computed own keys and
+//! enumerable own keys of a small number of objects together with the version
+//! they were computed at, and the enumerable string keys of for-in prototype
+//! chains together with the version of every object in the chain.
+//!
+//! The cache holds object indexes and property keys without marking them, so
+//! it is emptied at the start of every garbage collection.
+
+use crate::{
+    ecmascript::types::{Object, ObjectHeapData, OrdinaryObject, PropertyKey},
+    engine::context::Bindable,
+};
+
+/// Number of slots in each of the direct-mapped caches.
+const CACHE_SIZE: usize = 64;
+
+#[derive(Debug)]
+struct OwnKeysEntry {
+    object: OrdinaryObject<'static>,
+    version: u32,
+    keys: Box<[PropertyKey<'static>]>,
+}
+
+#[derive(Debug)]
+struct ForInEntry {
+    /// Objects of the prototype chain, starting with the enumerated object,
+    /// and their keys versions at the time the keys were collected.
+    chain: Box<[(OrdinaryObject<'static>, u32)]>,
+    /// Enumerable string keys of the chain in enumeration order, paired with
+    /// the object that owns them. Shadowed keys have been removed.
+    keys: Box<[(OrdinaryObject<'static>, PropertyKey<'static>)]>,
+}
+
+fn object_data<'a>(
+    objects: &'a [Option<ObjectHeapData<'static>>],
+    object: OrdinaryObject,
+) -> &'a ObjectHeapData<'static> {
+    objects[object.get_index()]
+        .as_ref()
+        .expect("Object slot empty")
+}
+
+#[derive(Debug)]
+struct KeysTable([Option<OwnKeysEntry>; CACHE_SIZE]);
+
+impl KeysTable {
+    fn get(&self, object: OrdinaryObject, version: u32) -> Option<&[PropertyKey<'static>]> {
+        let entry = self.0[object.get_index() % CACHE_SIZE].as_ref()?;
+        if entry.object != object.unbind() || entry.version != version {
+            return None;
+        }
+        Some(&entry.keys)
+    }
+
+    fn set(&mut self, object: OrdinaryObject, version: u32, keys: &[PropertyKey]) {
+        self.0[object.get_index() % CACHE_SIZE] = Some(OwnKeysEntry {
+            object: object.unbind(),
+            version,
+            keys: keys.iter().map(|key| key.unbind()).collect(),
+        });
+    }
+
+    fn clear(&mut self) {
+        self.0.iter_mut().for_each(|entry| *entry = None);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct OwnKeysCache {
+    own_keys: KeysTable,
+    enumerable_keys: KeysTable,
+    for_in: [Option<ForInEntry>; CACHE_SIZE],
+    #[cfg(test)]
+    hits: usize,
+}
+
+impl Default for OwnKeysCache {
+    fn default() -> Self {
+        Self {
+            own_keys: KeysTable([const { None }; CACHE_SIZE]),
+            enumerable_keys: KeysTable([const { None }; CACHE_SIZE]),
+            for_in: [const { None }; CACHE_SIZE],
+            #[cfg(test)]
+            hits: 0,
+        }
+    }
+}
+
+impl OwnKeysCache {
+    /// Returns the cached own property keys of `object` if they are still
+    /// valid for `version`.
+    pub(crate) fn get_own_keys(
+        &mut self,
+        object: OrdinaryObject,
+        version: u32,
+    ) -> Option<&[PropertyKey<'static>]> {
+        let keys = self.own_keys.get(object, version)?;
+        #[cfg(test)]
+        {
+            self.hits += 1;
+        }
+        Some(keys)
+    }
+
+    pub(crate) fn set_own_keys(
+        &mut self,
+        object: OrdinaryObject,
+        version: u32,
+        keys: &[PropertyKey],
+    ) {
+        self.own_keys.set(object, version, keys);
+    }
+
+    /// Returns the cached enumerable own string keys of `object` if they are
+    /// still valid for `version`.
+    pub(crate) fn get_enumerable_keys(
+        &mut self,
+        object: OrdinaryObject,
+        version: u32,
+    ) -> Option<&[PropertyKey<'static>]> {
+        let keys = self.enumerable_keys.get(object, version)?;
+        #[cfg(test)]
+        {
+            self.hits += 1;
+        }
+        Some(keys)
+    }
+
+    pub(crate) fn set_enumerable_keys(
+        &mut self,
+        object: OrdinaryObject,
+        version: u32,
+        keys: &[PropertyKey],
+    ) {
+        self.enumerable_keys.set(object, version, keys);
+    }
+
+    /// Returns the cached for-in keys of `object` if no object in its
+    /// prototype chain has been changed since they were collected, and the
+    /// prototype chain itself has not been changed.
+    pub(crate) fn get_for_in_keys(
+        &mut self,
+        objects: &[Option<ObjectHeapData<'static>>],
+        object: OrdinaryObject,
+    ) -> Option<&[(OrdinaryObject<'static>, PropertyKey<'static>)]> {
+        let entry = self.for_in[object.get_index() % CACHE_SIZE].as_ref()?;
+        let mut expected = Some(Object::Object(object.unbind()));
+        for &(chain_object, version) in entry.chain.iter() {
+            if expected != Some(Object::Object(chain_object))
+                || object_data(objects, chain_object).keys_version != version
+            {
+                return None;
+            }
+            expected = object_data(objects, chain_object).prototype;
+        }
+        if expected.is_some() {
+            return None;
+        }
+        #[cfg(test)]
+        {
+            self.hits += 1;
+        }
+        Some(&entry.keys)
+    }
+
+    pub(crate) fn set_for_in_keys(
+        &mut self,
+        objects: &[Option<ObjectHeapData<'static>>],
+        chain: &[OrdinaryObject],
+        keys: &[(OrdinaryObject, PropertyKey)],
+    ) {
+        let object = chain[0];
+        self.for_in[object.get_index() % CACHE_SIZE] = Some(ForInEntry {
+            chain: chain
+                .iter()
+                .map(|&o| (o.unbind(), object_data(objects, o).keys_version))
+                .collect(),
+            keys: keys
+                .iter()
+                .map(|&(o, key)| (o.unbind(), key.unbind()))
+                .collect(),
+        });
+    }
+
+    /// Drops all cached keys.
+    pub(crate) fn clear(&mut self) {
+        self.own_keys.clear();
+        self.enumerable_keys.clear();
+        self.for_in.iter_mut().for_each(|entry| *entry = None);
+    }
+
+    /// Number of lookups that were answered from the cache.
+    #[cfg(test)]
+    pub(crate) fn hits(&self) -> usize {
+        self.hits
+    }
+}
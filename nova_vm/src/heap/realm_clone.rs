@@ -0,0 +1,754 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! ## Realm cloning
+//!
+//! A Realm can be cloned from a template Realm, eg. one that has the default
+//! intrinsics and has run some host setup scripts. This is much cheaper than
+//! initializing a new Realm and running the setup scripts again.
+//!
+//! Cloning marks everything reachable from the template Realm, like a garbage
+//! collection would, and appends a copy of each reachable mutable heap data
+//! entry to the end of its heap vector. References inside the copies are then
+//! redirected to the copies, so mutations in a clone are invisible to the
+//! template and to other clones.
+//!
+//! The following heap data is deep-copied:
+//!
+//! - ordinary objects, Arrays, Errors, primitive objects (eg. `Object(1)`),
+//!   Dates and RegExps,
+//! - built-in, bound, and ECMAScript functions. Their \[\[Realm]] and
+//!   \[\[Environment]] are the copies,
+//! - declarative, function, global, object and private environments,
+//! - element and property key storage, and
+//! - the Realm Record itself, including its intrinsics.
+//!
+//! The following immutable heap data is shared between the template and its
+//! clones:
+//!
+//! - strings, heap numbers and BigInts,
+//! - Symbols: a Symbol created by a setup script is the same Symbol in every
+//!   clone,
+//! - compiled bytecode, source code and Script Records. Scripts keep the
+//!   template as their \[\[Realm]], and
+//! - the \[\[HostDefined]] field of the Realm Record.
+//!
+//! Heap data with internal state that cannot be copied, like Maps, Promises,
+//! Proxies, ArrayBuffers, generators and embedder objects, cannot be
+//! reachable from the template: cloning fails with
+//! [`RealmCloneError::UnsupportedHeapData`]. Nothing is copy-on-write: all
+//! copies are made eagerly.
+
+use ahash::AHashMap;
+
+use super::{
+    Heap, HeapMarkAndSweep,
+    element_array::{ElementArray, PropertyKeyArray},
+    heap_bits::{CompactionList, CompactionLists, HeapBits, WorkQueues},
+    heap_gc::mark,
+    indexes::{
+        BuiltinFunctionIndex, ElementIndex, ObjectIndex, PrimitiveObjectIndex, PropertyKeyIndex,
+    },
+    intrinsic_function_count, intrinsic_object_count, intrinsic_primitive_object_count,
+};
+use crate::{
+    ecmascript::{
+        builtins::{BuiltinFunction, primitive_objects::PrimitiveObject},
+        execution::Realm,
+        types::{OrdinaryObject, PropertyKey, Value},
+    },
+    engine::context::Bindable,
+};
+
+/// Error returned when a Realm cannot be cloned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RealmCloneError {
+    /// Heap data of the named kind that cannot be cloned is reachable from
+    /// the template Realm.
+    UnsupportedHeapData(&'static str),
+    /// Another Realm, or a function or object of it, is reachable from the
+    /// template Realm.
+    ForeignRealm,
+}
+
+impl core::fmt::Display for RealmCloneError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            RealmCloneError::UnsupportedHeapData(name) => {
+                write!(
+                    f,
+                    "{name} reachable from the template Realm cannot be cloned"
+                )
+            }
+            RealmCloneError::ForeignRealm => {
+                f.write_str("another Realm is reachable from the template Realm")
+            }
+        }
+    }
+}
+
+impl core::error::Error for RealmCloneError {}
+
+/// Returns the indexes of the marked entries of a heap vector.
+fn marked_indexes(marks: &[bool]) -> Vec<u32> {
+    marks
+        .iter()
+        .enumerate()
+        .filter(|(_, marked)| **marked)
+        .map(|(index, _)| index as u32)
+        .collect()
+}
+
+/// Returns the indexes and marked lengths of the marked entries of an
+/// element or property key storage vector.
+fn marked_storage<L: Copy + Into<u32>>(marks: &[(bool, L)]) -> Vec<(u32, u32)> {
+    marks
+        .iter()
+        .enumerate()
+        .filter(|(_, (marked, _))| *marked)
+        .map(|(index, (_, length))| (index as u32, (*length).into()))
+        .collect()
+}
+
+/// Returns the indexes of marked element or property key storage.
+fn storage(marked: &[(u32, u32)]) -> impl Iterator<Item = u32> + '_ {
+    marked.iter().map(|(index, _)| *index)
+}
+
+/// Creates a list that redirects references to the entries at `indexes` to
+/// their copies, which are appended in order to a heap vector of length
+/// `len`.
+fn relocate(indexes: impl Iterator<Item = u32>, len: usize) -> CompactionList {
+    let first_copy = u32::try_from(len).expect("Heap vector is too large");
+    CompactionList::from_relocations(
+        indexes
+            .enumerate()
+            .map(|(offset, index)| (index, first_copy + offset as u32))
+            .collect::<AHashMap<_, _>>(),
+    )
+}
+
+/// Appends copies of the entries at `indexes` to the heap vector, with the
+/// references in them shifted by `compactions`.
+fn copy_entries<T: Clone + HeapMarkAndSweep>(
+    vector: &mut Vec<Option<T>>,
+    indexes: &[u32],
+    compactions: &CompactionLists,
+    alloc_counter: &mut usize,
+) {
+    vector.reserve(indexes.len());
+    for &index in indexes {
+        let mut data = vector[index as usize].clone();
+        data.sweep_values(compactions);
+        vector.push(data);
+    }
+    *alloc_counter += core::mem::size_of::<Option<T>>() * indexes.len();
+}
+
+fn copy_elements<const N: usize>(
+    array: &mut ElementArray<N>,
+    marked: &[(u32, u32)],
+    compactions: &CompactionLists,
+    alloc_counter: &mut usize,
+) {
+    for &(index, length) in marked {
+        array.push_copy(ElementIndex::from_u32_index(index), length, compactions);
+        *alloc_counter += core::mem::size_of::<[Option<Value>; N]>();
+    }
+}
+
+fn copy_property_keys<const N: usize>(
+    array: &mut PropertyKeyArray<N>,
+    marked: &[(u32, u32)],
+    compactions: &CompactionLists,
+    alloc_counter: &mut usize,
+) {
+    for &(index, length) in marked {
+        array.push_copy(PropertyKeyIndex::from_u32_index(index), length, compactions);
+        *alloc_counter += core::mem::size_of::<[Option<PropertyKey>; N]>();
+    }
+}
+
+/// Checks that no heap data that cannot be cloned was marked.
+fn check_supported(bits: &HeapBits, template: Realm) -> Result<(), RealmCloneError> {
+    let HeapBits {
+        #[cfg(feature = "array-buffer")]
+        array_buffers,
+        arrays: _,
+        array_iterators,
+        async_generators,
+        await_reactions,
+        bigints: _,
+        bound_functions: _,
+        builtin_constructors: _,
+        builtin_functions: _,
+        #[cfg(feature = "array-buffer")]
+        data_views,
+        #[cfg(feature = "date")]
+            dates: _,
+        declarative_environments: _,
+        e_2_10: _,
+        e_2_12: _,
+        e_2_16: _,
+        e_2_24: _,
+        e_2_32: _,
+        e_2_4: _,
+        e_2_6: _,
+        e_2_8: _,
+        k_2_10: _,
+        k_2_12: _,
+        k_2_16: _,
+        k_2_24: _,
+        k_2_32: _,
+        k_2_4: _,
+        k_2_6: _,
+        k_2_8: _,
+        ecmascript_functions: _,
+        embedder_objects,
+        errors: _,
+        executables: _,
+        source_codes: _,
+        finalization_registrys,
+        function_environments: _,
+        generators,
+        global_environments: _,
+        maps,
+        map_iterators,
+        modules,
+        numbers: _,
+        object_environments: _,
+        objects: _,
+        primitive_objects: _,
+        private_environments: _,
+        promise_reaction_records,
+        promise_resolving_functions,
+        promises,
+        proxys,
+        realms,
+        #[cfg(feature = "regexp")]
+            regexps: _,
+        scripts: _,
+        #[cfg(feature = "set")]
+        sets,
+        #[cfg(feature = "set")]
+        set_iterators,
+        #[cfg(feature = "regexp")]
+        reg_exp_string_iterators,
+        #[cfg(feature = "shared-array-buffer")]
+        shared_array_buffers,
+        string_iterators,
+        strings: _,
+        symbols: _,
+        #[cfg(feature = "array-buffer")]
+        typed_arrays,
+        #[cfg(feature = "weak-refs")]
+        weak_maps,
+        #[cfg(feature = "weak-refs")]
+        weak_refs,
+        #[cfg(feature = "weak-refs")]
+        weak_sets,
+    } = bits;
+    let unsupported: &[(&'static str, &[bool])] = &[
+        #[cfg(feature = "array-buffer")]
+        ("ArrayBuffers", array_buffers),
+        ("Array Iterators", array_iterators),
+        ("AsyncGenerators", async_generators),
+        ("await reactions", await_reactions),
+        #[cfg(feature = "array-buffer")]
+        ("DataViews", data_views),
+        ("embedder objects", embedder_objects),
+        ("FinalizationRegistries", finalization_registrys),
+        ("Generators", generators),
+        ("Maps", maps),
+        ("Map Iterators", map_iterators),
+        ("Modules", modules),
+        ("promise reactions", promise_reaction_records),
+        ("promise resolving functions", promise_resolving_functions),
+        ("Promises", promises),
+        ("Proxies", proxys),
+        #[cfg(feature = "set")]
+        ("Sets", sets),
+        #[cfg(feature = "set")]
+        ("Set Iterators", set_iterators),
+        #[cfg(feature = "regexp")]
+        ("RegExp String Iterators", reg_exp_string_iterators),
+        #[cfg(feature = "shared-array-buffer")]
+        ("SharedArrayBuffers", shared_array_buffers),
+        ("String Iterators", string_iterators),
+        #[cfg(feature = "array-buffer")]
+        ("TypedArrays", typed_arrays),
+        #[cfg(feature = "weak-refs")]
+        ("WeakMaps", weak_maps),
+        #[cfg(feature = "weak-refs")]
+        ("WeakRefs", weak_refs),
+        #[cfg(feature = "weak-refs")]
+        ("WeakSets", weak_sets),
+    ];
+    for (name, marks) in unsupported {
+        if marks.contains(&true) {
+            return Err(RealmCloneError::UnsupportedHeapData(name));
+        }
+    }
+    if marked_indexes(realms) != [template.into_u32_index()] {
+        return Err(RealmCloneError::ForeignRealm);
+    }
+    Ok(())
+}
+
+/// Clones the template Realm and everything reachable from it. See the
+/// [module documentation](self) for what is copied and what is shared.
+pub(crate) fn clone_realm(
+    heap: &mut Heap,
+    template: Realm,
+) -> Result<Realm<'static>, RealmCloneError> {
+    // References from the copies to old heap data would not be seen by the
+    // marking in progress.
+    heap.stop_incremental_marking();
+
+    let mut bits = HeapBits::new(heap);
+    let mut queues = WorkQueues::new(heap);
+    queues.realms.push(template.unbind());
+    // The intrinsics of a Realm are stored in contiguous ranges, which their
+    // copies must be as well: all entries of the ranges are copied, in order.
+    let intrinsics = heap.realms[template].intrinsics();
+    let object_index_base = intrinsics.object_index_base.into_index();
+    queues.objects.extend(
+        (object_index_base..object_index_base + intrinsic_object_count())
+            .map(|index| OrdinaryObject::from(ObjectIndex::from_index(index))),
+    );
+    let primitive_object_index_base = intrinsics.primitive_object_index_base.into_index();
+    queues.primitive_objects.extend(
+        (primitive_object_index_base
+            ..primitive_object_index_base + intrinsic_primitive_object_count())
+            .map(|index| PrimitiveObject::from(PrimitiveObjectIndex::from_index(index))),
+    );
+    let builtin_function_index_base = intrinsics.builtin_function_index_base.into_index();
+    queues.builtin_functions.extend(
+        (builtin_function_index_base..builtin_function_index_base + intrinsic_function_count())
+            .map(|index| BuiltinFunction::from(BuiltinFunctionIndex::from_index(index))),
+    );
+    mark(heap, &mut bits, &mut queues, usize::MAX, false);
+    check_supported(&bits, template)?;
+
+    let arrays = marked_indexes(&bits.arrays);
+    let bound_functions = marked_indexes(&bits.bound_functions);
+    let builtin_constructors = marked_indexes(&bits.builtin_constructors);
+    let builtin_functions = marked_indexes(&bits.builtin_functions);
+    #[cfg(feature = "date")]
+    let dates = marked_indexes(&bits.dates);
+    let declarative_environments = marked_indexes(&bits.declarative_environments);
+    let ecmascript_functions = marked_indexes(&bits.ecmascript_functions);
+    let errors = marked_indexes(&bits.errors);
+    let function_environments = marked_indexes(&bits.function_environments);
+    let global_environments = marked_indexes(&bits.global_environments);
+    let object_environments = marked_indexes(&bits.object_environments);
+    let objects = marked_indexes(&bits.objects);
+    let primitive_objects = marked_indexes(&bits.primitive_objects);
+    let private_environments = marked_indexes(&bits.private_environments);
+    let realms = marked_indexes(&bits.realms);
+    #[cfg(feature = "regexp")]
+    let regexps = marked_indexes(&bits.regexps);
+    let e_2_4 = marked_storage(&bits.e_2_4);
+    let e_2_6 = marked_storage(&bits.e_2_6);
+    let e_2_8 = marked_storage(&bits.e_2_8);
+    let e_2_10 = marked_storage(&bits.e_2_10);
+    let e_2_12 = marked_storage(&bits.e_2_12);
+    let e_2_16 = marked_storage(&bits.e_2_16);
+    let e_2_24 = marked_storage(&bits.e_2_24);
+    let e_2_32 = marked_storage(&bits.e_2_32);
+    let k_2_4 = marked_storage(&bits.k_2_4);
+    let k_2_6 = marked_storage(&bits.k_2_6);
+    let k_2_8 = marked_storage(&bits.k_2_8);
+    let k_2_10 = marked_storage(&bits.k_2_10);
+    let k_2_12 = marked_storage(&bits.k_2_12);
+    let k_2_16 = marked_storage(&bits.k_2_16);
+    let k_2_24 = marked_storage(&bits.k_2_24);
+    let k_2_32 = marked_storage(&bits.k_2_32);
+
+    let environments = &heap.environments;
+    let elements = &heap.elements;
+    let compactions = CompactionLists {
+        arrays: relocate(arrays.iter().copied(), heap.arrays.len()),
+        bound_functions: relocate(bound_functions.iter().copied(), heap.bound_functions.len()),
+        builtin_constructors: relocate(
+            builtin_constructors.iter().copied(),
+            heap.builtin_constructors.len(),
+        ),
+        builtin_functions: relocate(
+            builtin_functions.iter().copied(),
+            heap.builtin_functions.len(),
+        ),
+        #[cfg(feature = "date")]
+        dates: relocate(dates.iter().copied(), heap.dates.len()),
+        declarative_environments: relocate(
+            declarative_environments.iter().copied(),
+            environments.declarative.len(),
+        ),
+        e_2_4: relocate(storage(&e_2_4), elements.e2pow4.values.len()),
+        e_2_6: relocate(storage(&e_2_6), elements.e2pow6.values.len()),
+        e_2_8: relocate(storage(&e_2_8), elements.e2pow8.values.len()),
+        e_2_10: relocate(storage(&e_2_10), elements.e2pow10.values.len()),
+        e_2_12: relocate(storage(&e_2_12), elements.e2pow12.values.len()),
+        e_2_16: relocate(storage(&e_2_16), elements.e2pow16.values.len()),
+        e_2_24: relocate(storage(&e_2_24), elements.e2pow24.values.len()),
+        e_2_32: relocate(storage(&e_2_32), elements.e2pow32.values.len()),
+        k_2_4: relocate(storage(&k_2_4), elements.k2pow4.keys.len()),
+        k_2_6: relocate(storage(&k_2_6), elements.k2pow6.keys.len()),
+        k_2_8: relocate(storage(&k_2_8), elements.k2pow8.keys.len()),
+        k_2_10: relocate(storage(&k_2_10), elements.k2pow10.keys.len()),
+        k_2_12: relocate(storage(&k_2_12), elements.k2pow12.keys.len()),
+        k_2_16: relocate(storage(&k_2_16), elements.k2pow16.keys.len()),
+        k_2_24: relocate(storage(&k_2_24), elements.k2pow24.keys.len()),
+        k_2_32: relocate(storage(&k_2_32), elements.k2pow32.keys.len()),
+        ecmascript_functions: relocate(
+            ecmascript_functions.iter().copied(),
+            heap.ecmascript_functions.len(),
+        ),
+        errors: relocate(errors.iter().copied(), heap.errors.len()),
+        function_environments: relocate(
+            function_environments.iter().copied(),
+            environments.function.len(),
+        ),
+        global_environments: relocate(
+            global_environments.iter().copied(),
+            environments.global.len(),
+        ),
+        object_environments: relocate(
+            object_environments.iter().copied(),
+            environments.object.len(),
+        ),
+        objects: relocate(objects.iter().copied(), heap.objects.len()),
+        primitive_objects: relocate(
+            primitive_objects.iter().copied(),
+            heap.primitive_objects.len(),
+        ),
+        private_environments: relocate(
+            private_environments.iter().copied(),
+            environments.private.len(),
+        ),
+        realms: relocate(realms.iter().copied(), heap.realms.len()),
+        #[cfg(feature = "regexp")]
+        regexps: relocate(regexps.iter().copied(), heap.regexps.len()),
+        ..Default::default()
+    };
+    let clone = Realm::from_index(heap.realms.len());
+
+    let alloc_counter = &mut heap.alloc_counter;
+    copy_entries(&mut heap.arrays, &arrays, &compactions, alloc_counter);
+    copy_entries(
+        &mut heap.bound_functions,
+        &bound_functions,
+        &compactions,
+        alloc_counter,
+    );
+    copy_entries(
+        &mut heap.builtin_constructors,
+        &builtin_constructors,
+        &compactions,
+        alloc_counter,
+    );
+    copy_entries(
+        &mut heap.builtin_functions,
+        &builtin_functions,
+        &compactions,
+        alloc_counter,
+    );
+    #[cfg(feature = "date")]
+    copy_entries(&mut heap.dates, &dates, &compactions, alloc_counter);
+    let environments = &mut heap.environments;
+    copy_entries(
+        &mut environments.declarative,
+        &declarative_environments,
+        &compactions,
+        alloc_counter,
+    );
+    copy_entries(
+        &mut environments.function,
+        &function_environments,
+        &compactions,
+        alloc_counter,
+    );
+    copy_entries(
+        &mut environments.global,
+        &global_environments,
+        &compactions,
+        alloc_counter,
+    );
+    copy_entries(
+        &mut environments.object,
+        &object_environments,
+        &compactions,
+        alloc_counter,
+    );
+    copy_entries(
+        &mut environments.private,
+        &private_environments,
+        &compactions,
+        alloc_counter,
+    );
+    let elements = &mut heap.elements;
+    copy_elements(&mut elements.e2pow4, &e_2_4, &compactions, alloc_counter);
+    copy_elements(&mut elements.e2pow6, &e_2_6, &compactions, alloc_counter);
+    copy_elements(&mut elements.e2pow8, &e_2_8, &compactions, alloc_counter);
+    copy_elements(&mut elements.e2pow10, &e_2_10, &compactions, alloc_counter);
+    copy_elements(&mut elements.e2pow12, &e_2_12, &compactions, alloc_counter);
+    copy_elements(&mut elements.e2pow16, &e_2_16, &compactions, alloc_counter);
+    copy_elements(&mut elements.e2pow24, &e_2_24, &compactions, alloc_counter);
+    copy_elements(&mut elements.e2pow32, &e_2_32, &compactions, alloc_counter);
+    copy_property_keys(&mut elements.k2pow4, &k_2_4, &compactions, alloc_counter);
+    copy_property_keys(&mut elements.k2pow6, &k_2_6, &compactions, alloc_counter);
+    copy_property_keys(&mut elements.k2pow8, &k_2_8, &compactions, alloc_counter);
+    copy_property_keys(&mut elements.k2pow10, &k_2_10, &compactions, alloc_counter);
+    copy_property_keys(&mut elements.k2pow12, &k_2_12, &compactions, alloc_counter);
+    copy_property_keys(&mut elements.k2pow16, &k_2_16, &compactions, alloc_counter);
+    copy_property_keys(&mut elements.k2pow24, &k_2_24, &compactions, alloc_counter);
+    copy_property_keys(&mut elements.k2pow32, &k_2_32, &compactions, alloc_counter);
+    copy_entries(
+        &mut heap.ecmascript_functions,
+        &ecmascript_functions,
+        &compactions,
+        alloc_counter,
+    );
+    copy_entries(&mut heap.errors, &errors, &compactions, alloc_counter);
+    copy_entries(&mut heap.objects, &objects, &compactions, alloc_counter);
+    copy_entries(
+        &mut heap.primitive_objects,
+        &primitive_objects,
+        &compactions,
+        alloc_counter,
+    );
+    copy_entries(&mut heap.realms, &realms, &compactions, alloc_counter);
+    #[cfg(feature = "regexp")]
+    copy_entries(&mut heap.regexps, &regexps, &compactions, alloc_counter);
+    Ok(clone)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        ecmascript::{
+            execution::{
+                DefaultHostHooks,
+                agent::{GcAgent, Options, RealmRoot},
+            },
+            scripts_and_modules::script::test::evaluate,
+            types::Value,
+        },
+        engine::context::Bindable,
+        heap::RealmCloneError,
+    };
+
+    const SETUP_SCRIPT: &str = "
+        var counter = 0;
+        function bump() { return ++counter; }
+        const makeCounter = () => { let count = 0; return () => ++count; };
+        var next = makeCounter();
+        function WithoutPrototype() {}
+        WithoutPrototype.prototype = null;
+        class Point {
+            #x;
+            constructor(x) { this.#x = x; }
+            get x() { return this.#x; }
+        }
+        var origin = new Point(0);
+        var config = {
+            name: 'template',
+            limits: [1, 2, 3],
+            created: new Date(0),
+            pattern: /a+b/g,
+            error: new TypeError('template'),
+            boxed: Object('boxed'),
+        };
+        let lexical = 'template';
+        var table = [];
+        for (let i = 0; i < 2000; i++) {
+            const entry = { key: `key${i}`, value: i * 2 };
+            if (i % 100 === 0) table.push(entry);
+        }";
+
+    fn assert_all_true(agent: &mut GcAgent, realm: &RealmRoot, sources: &[&'static str]) {
+        agent.run_in_realm(realm, |agent, mut gc| {
+            for source in sources {
+                let result = evaluate(agent, source, gc.reborrow()).unbind();
+                assert_eq!(result, Value::Boolean(true), "{source}");
+            }
+        });
+    }
+
+    fn create_template(agent: &mut GcAgent) -> RealmRoot {
+        let realm = agent.create_default_realm();
+        agent.run_in_realm(&realm, |agent, gc| {
+            evaluate(agent, SETUP_SCRIPT, gc);
+        });
+        realm
+    }
+
+    #[test]
+    fn clones_have_independent_globals() {
+        let mut agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+        let template = create_template(&mut agent);
+        let first = agent.clone_realm(&template);
+        let second = agent.clone_realm(&template);
+
+        assert_all_true(
+            &mut agent,
+            &first,
+            &[
+                "counter = 10; lexical = 'first'; globalThis.added = 'first';
+                config.name = 'first'; config.limits.push(4); config.created.setTime(1000);
+                config.error.message = 'first';
+                Array.prototype.extra = 'first'; delete Object.prototype.toString;
+                bump() === 11",
+                "config.pattern.lastIndex = 3; config.pattern.lastIndex === 3",
+            ],
+        );
+        agent.gc();
+        for realm in [&second, &template] {
+            assert_all_true(
+                &mut agent,
+                realm,
+                &[
+                    "counter === 0 && lexical === 'template' && !('added' in globalThis)",
+                    "config.name === 'template' && config.limits.length === 3 &&
+                        config.created.getTime() === 0 && config.error.message === 'template'",
+                    "config.boxed instanceof String && config.boxed.valueOf() === 'boxed'",
+                    "[].extra === undefined && typeof Object.prototype.toString === 'function' &&
+                        String({}) === '[object Object]'",
+                    "config.pattern.lastIndex === 0",
+                    "table.length === 20 && table[19].key === 'key1900'",
+                ],
+            );
+        }
+        // The clones do not depend on the template.
+        agent.remove_realm(template);
+        agent.gc();
+        assert_all_true(
+            &mut agent,
+            &first,
+            &["counter === 11 && lexical === 'first' && added === 'first' && [].extra === 'first'"],
+        );
+        assert_all_true(&mut agent, &second, &["bump() === 1 && counter === 1"]);
+        agent.remove_realm(first);
+        agent.remove_realm(second);
+    }
+
+    #[test]
+    fn cloned_functions_belong_to_the_clone() {
+        let mut agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+        let template = create_template(&mut agent);
+        let first = agent.clone_realm(&template);
+        let second = agent.clone_realm(&template);
+
+        assert_all_true(
+            &mut agent,
+            &first,
+            &[
+                "next() === 1 && next() === 2 && next() === 3",
+                "origin.x === 0 && new Point(5).x === 5",
+                "Object.getPrototypeOf(bump) === Function.prototype && next instanceof Function",
+                // Objects constructed by a function whose prototype is not an
+                // object inherit from the Object.prototype of its [[Realm]].
+                "Object.getPrototypeOf(new WithoutPrototype()) === Object.prototype",
+            ],
+        );
+        assert_all_true(
+            &mut agent,
+            &second,
+            &[
+                "next() === 1",
+                "const again = makeCounter(); again() === 1 && next() === 2",
+                "Object.getPrototypeOf(new WithoutPrototype()) === Object.prototype",
+                "(() => { try { Point.prototype.x; } catch (err) { return err instanceof TypeError; } })()",
+            ],
+        );
+        assert_all_true(&mut agent, &template, &["next() === 1"]);
+        agent.remove_realm(template);
+        agent.remove_realm(first);
+        agent.remove_realm(second);
+    }
+
+    #[test]
+    fn unsupported_heap_data_is_rejected() {
+        let mut agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+        let template = agent.create_default_realm();
+        agent.run_in_realm(&template, |agent, gc| {
+            evaluate(agent, "var cache = new Map([[1, 2]]);", gc);
+        });
+        assert_eq!(
+            agent.try_clone_realm(&template).err(),
+            Some(RealmCloneError::UnsupportedHeapData("Maps"))
+        );
+        agent.run_in_realm(&template, |agent, gc| {
+            evaluate(agent, "cache = undefined;", gc);
+        });
+        let clone = agent.clone_realm(&template);
+        assert_all_true(&mut agent, &clone, &["new Map([[1, 2]]).get(1) === 2"]);
+        agent.remove_realm(template);
+        agent.remove_realm(clone);
+    }
+
+    /// Creates a Realm, returning it together with the number of heap entries
+    /// allocated for it.
+    fn count_allocations(
+        agent: &mut GcAgent,
+        create: impl FnOnce(&mut GcAgent) -> RealmRoot,
+    ) -> (RealmRoot, usize) {
+        agent.gc();
+        let before = agent.heap_statistics();
+        let realm = create(agent);
+        let after = agent.heap_statistics();
+        assert_eq!(
+            before.collections, after.collections,
+            "garbage was collected while creating the Realm"
+        );
+        (realm, after.heap_entries - before.heap_entries)
+    }
+
+    #[test]
+    fn cloning_is_cheaper_than_initializing() {
+        const REALMS: usize = 8;
+
+        fn executables(agent: &GcAgent) -> usize {
+            agent
+                .heap_vector_statistics()
+                .iter()
+                .find(|statistics| statistics.name == "executables")
+                .unwrap()
+                .slots
+        }
+
+        let mut agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+        let template = create_template(&mut agent);
+
+        let (initialized, initialization_entries) = count_allocations(&mut agent, create_template);
+        let (cloned, clone_entries) =
+            count_allocations(&mut agent, |agent| agent.clone_realm(&template));
+        assert!(
+            clone_entries < initialization_entries,
+            "cloning allocated {clone_entries} heap entries, initializing {initialization_entries}"
+        );
+
+        let executables_before = executables(&agent);
+        let mut realms = vec![initialized];
+        for _ in 0..REALMS {
+            realms.push(create_template(&mut agent));
+        }
+        assert!(executables(&agent) > executables_before);
+
+        let executables_before = executables(&agent);
+        let mut clones = vec![cloned];
+        for _ in 0..REALMS {
+            clones.push(agent.clone_realm(&template));
+        }
+        // Compiled bytecode is shared.
+        assert_eq!(executables(&agent), executables_before);
+        for realm in &clones {
+            assert_all_true(&mut agent, realm, &["bump() === 1 && next() === 1"]);
+        }
+        for realm in realms.into_iter().chain(clones) {
+            agent.remove_realm(realm);
+        }
+        agent.remove_realm(template);
+    }
+}
@@ -0,0 +1,829 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! ## Retaining paths
+//!
+//! [`Agent::find_retainers`] answers the question "why is this value alive"
+//! by searching the heap breadth-first from the garbage collection roots for
+//! references to the value.
+//!
+//! The references held by a piece of heap data are found by marking it with
+//! the garbage collector's marking machinery, so the search sees exactly the
+//! edges that the garbage collector follows. Edges that can be named, such as
+//! properties, array elements and environment bindings, are labelled with
+//! their name; all other edges are labelled "internal".
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+#[cfg(feature = "array-buffer")]
+use super::indexes::TypedArrayIndex;
+use super::{HeapMarkAndSweep, WorkQueues, heap_bits::HeapBits, heap_gc::mark};
+#[cfg(feature = "date")]
+use crate::ecmascript::builtins::date::Date;
+#[cfg(feature = "shared-array-buffer")]
+use crate::ecmascript::builtins::shared_array_buffer::SharedArrayBuffer;
+#[cfg(feature = "array-buffer")]
+use crate::ecmascript::builtins::{ArrayBuffer, data_view::DataView};
+#[cfg(feature = "set")]
+use crate::ecmascript::builtins::{
+    keyed_collections::set_objects::set_iterator_objects::set_iterator::SetIterator, set::Set,
+};
+#[cfg(feature = "regexp")]
+use crate::ecmascript::builtins::{
+    regexp::RegExp,
+    text_processing::regexp_objects::regexp_string_iterator_objects::RegExpStringIterator,
+};
+#[cfg(feature = "weak-refs")]
+use crate::ecmascript::builtins::{weak_map::WeakMap, weak_ref::WeakRef, weak_set::WeakSet};
+use crate::{
+    ecmascript::{
+        builtins::{
+            Array, BuiltinConstructorFunction, BuiltinFunction, ECMAScriptFunction,
+            async_generator_objects::AsyncGenerator,
+            bound_function::BoundFunction,
+            control_abstraction_objects::{
+                async_function_objects::await_reaction::AwaitReactionIdentifier,
+                generator_objects::Generator,
+                promise_objects::promise_abstract_operations::{
+                    promise_reaction_records::PromiseReaction,
+                    promise_resolving_functions::BuiltinPromiseResolvingFunction,
+                },
+            },
+            embedder_object::EmbedderObject,
+            error::Error,
+            finalization_registry::FinalizationRegistry,
+            indexed_collections::array_objects::array_iterator_objects::array_iterator::ArrayIterator,
+            keyed_collections::map_objects::map_iterator_objects::map_iterator::MapIterator,
+            map::Map,
+            module::Module,
+            primitive_objects::PrimitiveObject,
+            promise::Promise,
+            proxy::Proxy,
+            text_processing::string_objects::string_iterator_objects::StringIterator,
+        },
+        execution::{
+            Agent, DeclarativeEnvironment, FunctionEnvironment, GlobalEnvironment,
+            ObjectEnvironment, PrivateEnvironment, Realm,
+        },
+        scripts_and_modules::{script::Script, source_code::SourceCode},
+        types::{
+            HeapNumber, HeapString, InternalSlots, IntoObject, IntoValue, Object, OrdinaryObject,
+            Symbol, Value, bigint::HeapBigInt,
+        },
+    },
+    engine::{Executable, context::Bindable},
+    heap::element_array::{ElementDescriptor, ElementStorageRef},
+};
+
+/// A path of references from a garbage collection root to a value.
+///
+/// See [`Agent::find_retainers`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetainerPath {
+    /// The nodes of the path, each paired with the label of the edge to the
+    /// next node.
+    ///
+    /// The first node is the root, named for example "global environment
+    /// binding 'foo'", "execution context stack", "Global handle #3" or
+    /// "kept-alive set". The last node is the target value, and its edge
+    /// label is empty. Property edges are labelled by the property key,
+    /// element edges by the index.
+    pub steps: Vec<(String, String)>,
+    /// True if the path goes through a weak reference, such as a WeakMap key
+    /// or a WeakRef target. Weak paths are only returned if the target has
+    /// no strong retaining paths.
+    pub weak: bool,
+}
+
+macro_rules! heap_nodes {
+    (@object object $node:ident) => {
+        Some($node.into_object())
+    };
+    (@object internal $node:ident) => {
+        None
+    };
+    ($($(#[$attr:meta])* $field:ident: $variant:ident($ty:ty), $label:literal, $kind:ident;)*) => {
+        /// A piece of heap data, identified by the work queue that the
+        /// garbage collector marks it from.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+        enum HeapNode {
+            $($(#[$attr])* $variant($ty),)*
+        }
+
+        impl HeapNode {
+            fn label(self) -> &'static str {
+                match self {
+                    $($(#[$attr])* Self::$variant(_) => $label,)*
+                }
+            }
+
+            fn as_object(self) -> Option<Object<'static>> {
+                match self {
+                    $($(#[$attr])* Self::$variant(_node) => heap_nodes!(@object $kind _node),)*
+                }
+            }
+
+            fn queue(self, queues: &mut WorkQueues) {
+                match self {
+                    $($(#[$attr])* Self::$variant(node) => queues.$field.push(node),)*
+                }
+            }
+        }
+
+        /// Moves all heap data out of the work queues, leaving element and
+        /// property key storage in place.
+        fn drain_nodes(queues: &mut WorkQueues, nodes: &mut Vec<HeapNode>) {
+            $(
+                $(#[$attr])*
+                nodes.extend(queues.$field.drain(..).map(HeapNode::$variant));
+            )*
+        }
+    };
+}
+
+heap_nodes! {
+    #[cfg(feature = "array-buffer")]
+    array_buffers: ArrayBuffer(ArrayBuffer<'static>), "ArrayBuffer", object;
+    arrays: Array(Array<'static>), "Array", object;
+    array_iterators: ArrayIterator(ArrayIterator<'static>), "ArrayIterator", object;
+    async_generators: AsyncGenerator(AsyncGenerator<'static>), "AsyncGenerator", object;
+    await_reactions: AwaitReaction(AwaitReactionIdentifier<'static>), "AwaitReaction", internal;
+    bigints: BigInt(HeapBigInt<'static>), "BigInt", internal;
+    bound_functions: BoundFunction(BoundFunction<'static>), "BoundFunction", object;
+    builtin_constructors: BuiltinConstructor(BuiltinConstructorFunction<'static>), "BuiltinConstructorFunction", object;
+    builtin_functions: BuiltinFunction(BuiltinFunction<'static>), "BuiltinFunction", object;
+    #[cfg(feature = "array-buffer")]
+    data_views: DataView(DataView<'static>), "DataView", object;
+    #[cfg(feature = "date")]
+    dates: Date(Date<'static>), "Date", object;
+    declarative_environments: DeclarativeEnvironment(DeclarativeEnvironment<'static>), "DeclarativeEnvironment", internal;
+    ecmascript_functions: ECMAScriptFunction(ECMAScriptFunction<'static>), "ECMAScriptFunction", object;
+    embedder_objects: EmbedderObject(EmbedderObject<'static>), "EmbedderObject", object;
+    errors: Error(Error<'static>), "Error", object;
+    executables: Executable(Executable<'static>), "Executable", internal;
+    finalization_registrys: FinalizationRegistry(FinalizationRegistry<'static>), "FinalizationRegistry", object;
+    function_environments: FunctionEnvironment(FunctionEnvironment<'static>), "FunctionEnvironment", internal;
+    generators: Generator(Generator<'static>), "Generator", object;
+    global_environments: GlobalEnvironment(GlobalEnvironment<'static>), "GlobalEnvironment", internal;
+    maps: Map(Map<'static>), "Map", object;
+    map_iterators: MapIterator(MapIterator<'static>), "MapIterator", object;
+    modules: Module(Module<'static>), "Module", object;
+    numbers: Number(HeapNumber<'static>), "Number", internal;
+    object_environments: ObjectEnvironment(ObjectEnvironment<'static>), "ObjectEnvironment", internal;
+    objects: Object(OrdinaryObject<'static>), "Object", object;
+    primitive_objects: PrimitiveObject(PrimitiveObject<'static>), "PrimitiveObject", object;
+    private_environments: PrivateEnvironment(PrivateEnvironment<'static>), "PrivateEnvironment", internal;
+    promises: Promise(Promise<'static>), "Promise", object;
+    promise_reaction_records: PromiseReaction(PromiseReaction<'static>), "PromiseReaction", internal;
+    promise_resolving_functions: PromiseResolvingFunction(BuiltinPromiseResolvingFunction<'static>), "PromiseResolvingFunction", object;
+    proxys: Proxy(Proxy<'static>), "Proxy", object;
+    realms: Realm(Realm<'static>), "Realm", internal;
+    #[cfg(feature = "regexp")]
+    regexps: RegExp(RegExp<'static>), "RegExp", object;
+    scripts: Script(Script<'static>), "Script", internal;
+    #[cfg(feature = "set")]
+    sets: Set(Set<'static>), "Set", object;
+    #[cfg(feature = "set")]
+    set_iterators: SetIterator(SetIterator<'static>), "SetIterator", object;
+    #[cfg(feature = "regexp")]
+    reg_exp_string_iterators: RegExpStringIterator(RegExpStringIterator<'static>), "RegExpStringIterator", object;
+    #[cfg(feature = "shared-array-buffer")]
+    shared_array_buffers: SharedArrayBuffer(SharedArrayBuffer<'static>), "SharedArrayBuffer", object;
+    source_codes: SourceCode(SourceCode<'static>), "SourceCode", internal;
+    string_iterators: StringIterator(StringIterator<'static>), "StringIterator", object;
+    strings: String(HeapString<'static>), "String", internal;
+    symbols: Symbol(Symbol<'static>), "Symbol", internal;
+    #[cfg(feature = "array-buffer")]
+    typed_arrays: TypedArray(TypedArrayIndex<'static>), "TypedArray", internal;
+    #[cfg(feature = "weak-refs")]
+    weak_maps: WeakMap(WeakMap<'static>), "WeakMap", object;
+    #[cfg(feature = "weak-refs")]
+    weak_refs: WeakRef(WeakRef<'static>), "WeakRef", object;
+    #[cfg(feature = "weak-refs")]
+    weak_sets: WeakSet(WeakSet<'static>), "WeakSet", object;
+}
+
+/// Number of element and property key storage entries in the work queues.
+fn storage_len(queues: &WorkQueues) -> usize {
+    [
+        queues.e_2_4.len(),
+        queues.e_2_6.len(),
+        queues.e_2_8.len(),
+        queues.e_2_10.len(),
+        queues.e_2_12.len(),
+        queues.e_2_16.len(),
+        queues.e_2_24.len(),
+        queues.e_2_32.len(),
+        queues.k_2_4.len(),
+        queues.k_2_6.len(),
+        queues.k_2_8.len(),
+        queues.k_2_10.len(),
+        queues.k_2_12.len(),
+        queues.k_2_16.len(),
+        queues.k_2_24.len(),
+        queues.k_2_32.len(),
+    ]
+    .iter()
+    .sum()
+}
+
+/// Returns the heap data that a value refers to, or None if the value is
+/// stored inline.
+fn value_node(value: Value) -> Option<HeapNode> {
+    let mut queues = WorkQueues::default();
+    value.unbind().mark_values(&mut queues);
+    let mut nodes = Vec::with_capacity(1);
+    drain_nodes(&mut queues, &mut nodes);
+    nodes.pop()
+}
+
+/// A reference from one node to another.
+struct Edge {
+    node: HeapNode,
+    label: String,
+    weak: bool,
+}
+
+/// A garbage collection root and the heap data that it references.
+struct Root {
+    name: String,
+    edges: Vec<(HeapNode, String)>,
+}
+
+/// The node or root that a node was first reached from, the label of the
+/// edge it was reached through, and whether the path to it is weak.
+struct Predecessor {
+    from: Result<HeapNode, usize>,
+    label: String,
+    weak: bool,
+}
+
+struct RetainerSearch<'a> {
+    agent: &'a Agent,
+    /// Heap data that has already been expanded. Marking a node's bit makes
+    /// the garbage collector's marking ignore it afterwards.
+    bits: HeapBits,
+    queues: WorkQueues,
+}
+
+impl RetainerSearch<'_> {
+    /// Marks the queued heap data, without following the references of the
+    /// found heap data, and returns the found heap data. Element and property
+    /// key storage is marked through, so that the values stored in it are
+    /// found.
+    fn collect(&mut self) -> Vec<HeapNode> {
+        let mut nodes = Vec::new();
+        loop {
+            drain_nodes(&mut self.queues, &mut nodes);
+            let storage = storage_len(&self.queues);
+            if storage == 0 {
+                break;
+            }
+            mark(
+                &self.agent.heap,
+                &mut self.bits,
+                &mut self.queues,
+                storage,
+                true,
+            );
+        }
+        nodes
+    }
+
+    /// Returns the heap data referenced by a root.
+    fn children(&mut self, root: &impl HeapMarkAndSweep) -> Vec<HeapNode> {
+        root.mark_values(&mut self.queues);
+        self.collect()
+    }
+
+    /// Returns the heap data referenced by a node.
+    fn expand(&mut self, node: HeapNode) -> Vec<HeapNode> {
+        node.queue(&mut self.queues);
+        mark(&self.agent.heap, &mut self.bits, &mut self.queues, 1, true);
+        self.collect()
+    }
+
+    /// Returns the labelled references held by a node.
+    fn edges(&mut self, node: HeapNode) -> Vec<Edge> {
+        let agent = self.agent;
+        let mut children = self.expand(node);
+        let mut edges = Vec::new();
+        if let Some(object) = node.as_object() {
+            match object {
+                Object::Object(object) => ordinary_edges(agent, object, &mut edges),
+                Object::Array(array) => {
+                    let storage = agent[array].elements.get_storage(agent);
+                    element_edges(storage, &mut edges);
+                }
+                _ => {}
+            }
+            // The backing object of an exotic object is a part of it.
+            if !matches!(object, Object::Object(_)) {
+                if let Some(backing_object) = object.get_backing_object(agent) {
+                    let backing_object = HeapNode::Object(backing_object);
+                    children.retain(|&child| child != backing_object);
+                    children.extend(self.expand(backing_object));
+                    if let HeapNode::Object(backing_object) = backing_object {
+                        ordinary_edges(agent, backing_object, &mut edges);
+                    }
+                }
+            }
+        }
+        match node {
+            HeapNode::DeclarativeEnvironment(env) => {
+                for (name, value) in env.bindings(agent) {
+                    if let Some(child) = value.and_then(value_node) {
+                        edges.push(Edge {
+                            node: child,
+                            label: name.as_wtf8(agent).to_string_lossy().into_owned(),
+                            weak: false,
+                        });
+                    }
+                }
+            }
+            #[cfg(feature = "weak-refs")]
+            HeapNode::WeakMap(weak_map) => {
+                let data = &agent[weak_map];
+                for (&key, &value) in data.keys.iter().zip(data.values.iter()) {
+                    if let Some(key) = value_node(key) {
+                        edges.push(Edge {
+                            node: key,
+                            label: "key".to_string(),
+                            weak: true,
+                        });
+                    }
+                    if let Some(value) = value_node(value) {
+                        edges.push(Edge {
+                            node: value,
+                            label: "value".to_string(),
+                            weak: false,
+                        });
+                    }
+                }
+            }
+            #[cfg(feature = "weak-refs")]
+            HeapNode::WeakRef(weak_ref) => {
+                if let Some(target) = agent[weak_ref].weak_ref_target {
+                    if let Some(target) = value_node(target.into()) {
+                        edges.push(Edge {
+                            node: target,
+                            label: "[[WeakRefTarget]]".to_string(),
+                            weak: true,
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+        // Anything that could not be named is an internal reference.
+        for child in children {
+            if !edges.iter().any(|edge| edge.node == child) {
+                edges.push(Edge {
+                    node: child,
+                    label: "internal".to_string(),
+                    weak: false,
+                });
+            }
+        }
+        edges
+    }
+
+    /// Returns the garbage collection roots in the order they are searched.
+    fn roots(&mut self) -> Vec<Root> {
+        let agent = self.agent;
+        let mut roots = Vec::new();
+        let binding = |roots: &mut Vec<Root>, name: &str, value: Value| {
+            if let Some(node) = value_node(value) {
+                roots.push(Root {
+                    name: format!("global environment binding '{name}'"),
+                    edges: vec![(node, "value".to_string())],
+                });
+            }
+        };
+        for realm in agent.heap.realms.iter().flatten() {
+            if let Object::Object(global_object) = realm.global_object {
+                let storage = agent[global_object]
+                    .property_storage
+                    .get_storage(&agent.heap.elements);
+                for (key, value) in storage.keys.iter().zip(storage.values) {
+                    if let Some(value) = value {
+                        binding(&mut roots, &key.as_display(agent).to_string(), *value);
+                    }
+                }
+            }
+            if let Some(global_env) = realm.global_env {
+                for (name, value) in global_env.get_declarative_record(agent).bindings(agent) {
+                    if let Some(value) = value {
+                        binding(&mut roots, &name.as_wtf8(agent).to_string_lossy(), value);
+                    }
+                }
+            }
+        }
+
+        let mut execution_context_stack = Vec::new();
+        for (i, ctx) in agent.execution_context_stack.iter().enumerate() {
+            let label = format!("execution context #{i}");
+            for child in self.children(ctx) {
+                execution_context_stack.push((child, label.clone()));
+            }
+        }
+        for vm in agent.vm_stack.iter() {
+            // SAFETY: The Vms on the stack are alive while they are running.
+            let vm = unsafe { vm.as_ref() };
+            for child in self.children(vm) {
+                execution_context_stack.push((child, "running code".to_string()));
+            }
+        }
+        roots.push(Root {
+            name: "execution context stack".to_string(),
+            edges: execution_context_stack,
+        });
+
+        for (i, global) in agent.heap.globals.borrow().iter().enumerate() {
+            if let Some(global) = global {
+                let edges = self
+                    .children(global)
+                    .into_iter()
+                    .map(|child| (child, "value".to_string()))
+                    .collect();
+                roots.push(Root {
+                    name: format!("Global handle #{i}"),
+                    edges,
+                });
+            }
+        }
+        for (i, value) in agent.stack_refs.borrow().iter().enumerate() {
+            let edges = self
+                .children(value)
+                .into_iter()
+                .map(|child| (child, "value".to_string()))
+                .collect();
+            roots.push(Root {
+                name: format!("scoped value #{i}"),
+                edges,
+            });
+        }
+        for (i, collection) in agent.stack_ref_collections.borrow().iter().enumerate() {
+            let edges = self
+                .children(collection)
+                .into_iter()
+                .map(|child| (child, "value".to_string()))
+                .collect();
+            roots.push(Root {
+                name: format!("scoped collection #{i}"),
+                edges,
+            });
+        }
+
+        #[cfg(feature = "weak-refs")]
+        {
+            let kept_alive = agent
+                .heap
+                .weak_refs
+                .iter()
+                .flatten()
+                .filter(|weak_ref| weak_ref.kept_alive)
+                .filter_map(|weak_ref| weak_ref.weak_ref_target)
+                .filter_map(|target| value_node(target.into()))
+                .map(|node| (node, "WeakRef target".to_string()))
+                .collect();
+            roots.push(Root {
+                name: "kept-alive set".to_string(),
+                edges: kept_alive,
+            });
+        }
+
+        // Everything else that the Agent holds on to.
+        let edges = self
+            .children(agent)
+            .into_iter()
+            .map(|child| (child, "internal".to_string()))
+            .collect();
+        roots.push(Root {
+            name: "agent".to_string(),
+            edges,
+        });
+
+        for (i, realm) in agent.heap.realms.iter().enumerate() {
+            if realm.is_some() {
+                roots.push(Root {
+                    name: format!("realm #{i}"),
+                    edges: vec![(HeapNode::Realm(Realm::from_index(i)), "value".to_string())],
+                });
+            }
+        }
+        roots
+    }
+
+    /// Searches breadth-first from the roots for at most `max_paths`
+    /// references to `target`, following weak references if `follow_weak`
+    /// is true.
+    fn search(
+        &mut self,
+        target: HeapNode,
+        max_paths: usize,
+        follow_weak: bool,
+    ) -> Vec<RetainerPath> {
+        let roots = self.roots();
+        // The properties of the global objects are searched as global
+        // environment bindings, so the global objects themselves are not.
+        let global_objects = self
+            .agent
+            .heap
+            .realms
+            .iter()
+            .flatten()
+            .filter_map(|realm| value_node(realm.global_object.into_value()))
+            .collect::<BTreeSet<_>>();
+        let mut reached: BTreeMap<HeapNode, Predecessor> = BTreeMap::new();
+        let mut found: Vec<Predecessor> = Vec::new();
+        let mut frontier = VecDeque::new();
+        'roots: for (i, root) in roots.iter().enumerate() {
+            for (node, label) in &root.edges {
+                let predecessor = Predecessor {
+                    from: Err(i),
+                    label: label.clone(),
+                    weak: false,
+                };
+                if *node == target {
+                    found.push(predecessor);
+                    if found.len() >= max_paths {
+                        break 'roots;
+                    }
+                } else if !reached.contains_key(node) && !global_objects.contains(node) {
+                    reached.insert(*node, predecessor);
+                    frontier.push_back(*node);
+                }
+            }
+        }
+        'search: while found.len() < max_paths {
+            let Some(node) = frontier.pop_front() else {
+                break;
+            };
+            let weak = reached[&node].weak;
+            for edge in self.edges(node) {
+                if edge.weak && !follow_weak {
+                    continue;
+                }
+                let predecessor = Predecessor {
+                    from: Ok(node),
+                    label: edge.label,
+                    weak: weak || edge.weak,
+                };
+                if edge.node == target {
+                    found.push(predecessor);
+                    if found.len() >= max_paths {
+                        break 'search;
+                    }
+                } else if !reached.contains_key(&edge.node) && !global_objects.contains(&edge.node)
+                {
+                    reached.insert(edge.node, predecessor);
+                    frontier.push_back(edge.node);
+                }
+            }
+        }
+
+        found
+            .into_iter()
+            .map(|predecessor| {
+                let weak = predecessor.weak;
+                let mut steps = vec![(target.label().to_string(), String::new())];
+                let mut predecessor = &predecessor;
+                loop {
+                    match predecessor.from {
+                        Ok(node) => {
+                            steps.push((node.label().to_string(), predecessor.label.clone()));
+                            predecessor = &reached[&node];
+                        }
+                        Err(root) => {
+                            steps.push((roots[root].name.clone(), predecessor.label.clone()));
+                            break;
+                        }
+                    }
+                }
+                steps.reverse();
+                RetainerPath { steps, weak }
+            })
+            .collect()
+    }
+}
+
+/// Pushes the edges of an ordinary object's prototype and properties.
+fn ordinary_edges(agent: &Agent, object: OrdinaryObject, edges: &mut Vec<Edge>) {
+    let data = &agent[object];
+    if let Some(prototype) = data.prototype {
+        if let Some(node) = value_node(prototype.into_value()) {
+            edges.push(Edge {
+                node,
+                label: "[[Prototype]]".to_string(),
+                weak: false,
+            });
+        }
+    }
+    let storage = data.indexed_elements.get_storage(agent);
+    element_edges(storage, edges);
+    let storage = data.property_storage.get_storage(agent);
+    for (i, (key, value)) in storage.keys.iter().zip(storage.values).enumerate() {
+        let key = key.as_display(agent).to_string();
+        let descriptor = storage
+            .descriptors
+            .and_then(|descriptors| descriptors.get(&(i as u32)));
+        property_edges(key, *value, descriptor, edges);
+    }
+}
+
+/// Pushes the edges of the elements of an element storage, labelled by
+/// their index.
+fn element_edges(storage: ElementStorageRef, edges: &mut Vec<Edge>) {
+    for (i, value) in storage.values.iter().enumerate() {
+        let index = i as u32;
+        let descriptor = storage
+            .descriptors
+            .and_then(|descriptors| descriptors.get(&index));
+        property_edges(index.to_string(), *value, descriptor, edges);
+    }
+}
+
+/// Pushes the edges of a property's value or accessor functions.
+fn property_edges(
+    key: String,
+    value: Option<Value>,
+    descriptor: Option<&ElementDescriptor>,
+    edges: &mut Vec<Edge>,
+) {
+    if let Some(node) = value.and_then(value_node) {
+        edges.push(Edge {
+            node,
+            label: key.clone(),
+            weak: false,
+        });
+    }
+    let Some(descriptor) = descriptor else {
+        return;
+    };
+    for (prefix, function) in [("get", descriptor.getter()), ("set", descriptor.setter())] {
+        if let Some(node) = function.and_then(|function| value_node(function.into_value())) {
+            edges.push(Edge {
+                node,
+                label: format!("{prefix} {key}"),
+                weak: false,
+            });
+        }
+    }
+}
+
+impl Agent {
+    /// Finds at most `max_paths` shortest paths of references from the
+    /// garbage collection roots to `target`.
+    ///
+    /// Weak references, such as WeakMap keys and WeakRef targets, do not
+    /// retain the target and are not followed. If no strong paths are found,
+    /// the search is repeated following weak references and the paths found
+    /// are marked [`weak`](RetainerPath::weak): The target is then not
+    /// strongly retained and will be collected by the next garbage
+    /// collection. An empty result means that the target is not reachable at
+    /// all, or that it is not heap data.
+    pub fn find_retainers(&self, target: Value, max_paths: usize) -> Vec<RetainerPath> {
+        let Some(target) = value_node(target) else {
+            return Vec::new();
+        };
+        if max_paths == 0 {
+            return Vec::new();
+        }
+        for follow_weak in [false, true] {
+            let mut search = RetainerSearch {
+                agent: self,
+                bits: HeapBits::new(&self.heap),
+                queues: WorkQueues::default(),
+            };
+            let paths = search.search(target, max_paths, follow_weak);
+            if !paths.is_empty() {
+                return paths;
+            }
+        }
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::RetainerPath;
+    use crate::{
+        ecmascript::{
+            execution::{
+                Agent, DefaultHostHooks,
+                agent::{GcAgent, Options},
+                initialize_default_realm,
+            },
+            scripts_and_modules::script::test::evaluate,
+        },
+        engine::context::Bindable,
+    };
+
+    fn steps(steps: &[(&str, &str)]) -> Vec<(std::string::String, std::string::String)> {
+        steps
+            .iter()
+            .map(|&(node, edge)| (node.to_string(), edge.to_string()))
+            .collect()
+    }
+
+    fn holder_path() -> RetainerPath {
+        RetainerPath {
+            steps: steps(&[
+                ("global environment binding 'holder'", "value"),
+                ("Object", "child"),
+                ("Object", ""),
+            ]),
+            weak: false,
+        }
+    }
+
+    #[test]
+    fn object_retained_by_global_property() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let target = evaluate(
+            agent,
+            "var holder = { child: {} }; holder.child",
+            gc.reborrow(),
+        );
+        assert_eq!(agent.find_retainers(target.unbind(), 10), [holder_path()]);
+    }
+
+    #[test]
+    fn weak_references_do_not_retain() {
+        let mut agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+        let realm = agent.create_default_realm();
+        agent.run_in_realm(&realm, |agent, mut gc| {
+            let target = evaluate(
+                agent,
+                "var holder = { child: {} }; var ref = new WeakRef(holder.child); holder.child",
+                gc.reborrow(),
+            );
+            // Creating a WeakRef keeps its target alive until the end of the job.
+            assert_eq!(
+                agent.find_retainers(target.unbind(), 10),
+                [
+                    RetainerPath {
+                        steps: steps(&[("kept-alive set", "WeakRef target"), ("Object", "")]),
+                        weak: false,
+                    },
+                    holder_path(),
+                ]
+            );
+        });
+        agent.run_in_realm(&realm, |agent, mut gc| {
+            // The target is not rooted: Nothing in between collects garbage.
+            let target = evaluate(agent, "holder.child", gc.reborrow()).unbind();
+            assert_eq!(agent.find_retainers(target, 10), [holder_path()]);
+
+            evaluate(agent, "delete holder.child", gc.reborrow());
+            assert_eq!(
+                agent.find_retainers(target, 10),
+                [RetainerPath {
+                    steps: steps(&[
+                        ("global environment binding 'ref'", "value"),
+                        ("WeakRef", "[[WeakRefTarget]]"),
+                        ("Object", ""),
+                    ]),
+                    weak: true,
+                }]
+            );
+        });
+        agent.remove_realm(realm);
+    }
+
+    #[test]
+    fn retainer_paths_are_truncated() {
+        let mut agent = Agent::new(Options::default(), &DefaultHostHooks);
+        let mut root = agent.enter_gc();
+        let (agent, mut gc) = root.scope();
+        initialize_default_realm(agent, gc.reborrow());
+
+        let target = evaluate(
+            agent,
+            "var t = {}; var a = [t, t, t]; var b = { t }; t",
+            gc.reborrow(),
+        );
+        let paths = agent.find_retainers(target.unbind(), 3);
+        assert_eq!(paths.len(), 3);
+        assert!(paths.iter().all(|path| !path.weak));
+        assert_eq!(
+            paths[0].steps,
+            steps(&[("global environment binding 't'", "value"), ("Object", "")])
+        );
+        assert_eq!(
+            paths[1].steps,
+            steps(&[
+                ("global environment binding 'a'", "value"),
+                ("Array", "0"),
+                ("Object", "")
+            ])
+        );
+        assert_eq!(agent.find_retainers(target.unbind(), 10).len(), 5);
+    }
+}
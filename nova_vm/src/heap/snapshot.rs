@@ -0,0 +1,839 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! ## Startup snapshots
+//!
+//! A startup snapshot is a byte serialization of the heap of an Agent that
+//! holds nothing but the intrinsics of a single Realm. Restoring a snapshot
+//! skips building the intrinsics graph from scratch.
+//!
+//! Heap data is written field by field in heap vector order, so identifiers
+//! stay valid as-is. Builtin function behaviours are function pointers; these
+//! are written as offsets from a known function, which makes a snapshot valid
+//! only for the exact build of the engine that created it.
+
+use core::hash::Hash;
+
+use ahash::{AHashMap, AHashSet};
+
+use super::{
+    Heap,
+    indexes::{
+        ArrayIndex, BaseIndex, BuiltinFunctionIndex, NumberIndex, ObjectIndex,
+        PrimitiveObjectIndex, StringIndex, SymbolIndex,
+    },
+};
+use crate::{
+    SmallInteger,
+    ecmascript::{
+        builtins::{Array, BuiltinFunction, primitive_objects::PrimitiveObject},
+        execution::{Agent, Realm, initialize_default_realm},
+        types::{
+            BUILTIN_STRINGS_LIST, Function, HeapNumber, HeapString, IntoValue, Object,
+            OrdinaryObject, PropertyKey, String, Symbol, Value,
+        },
+    },
+    engine::{small_bigint::SmallBigInt, small_f64::SmallF64},
+};
+use small_string::SmallString;
+use wtf8::Wtf8Buf;
+
+/// Identifies the start of a startup snapshot.
+const MAGIC: &[u8; 8] = b"NOVASNAP";
+
+/// Error returned when restoring an Agent from a startup snapshot fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotError {
+    /// The bytes are not a startup snapshot.
+    InvalidHeader,
+    /// The snapshot was created by a different build of the engine.
+    BuildMismatch,
+    /// The snapshot is truncated or contains invalid data.
+    Corrupted,
+}
+
+impl core::fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SnapshotError::InvalidHeader => f.write_str("not a startup snapshot"),
+            SnapshotError::BuildMismatch => {
+                f.write_str("startup snapshot was created by a different build")
+            }
+            SnapshotError::Corrupted => f.write_str("startup snapshot is corrupted"),
+        }
+    }
+}
+
+impl core::error::Error for SnapshotError {}
+
+/// Address that function pointers are stored relative to.
+#[inline(never)]
+fn function_anchor() -> usize {
+    function_anchor as usize
+}
+
+/// Offsets of a few engine functions from the anchor. These differ between
+/// builds with overwhelming probability, so comparing them catches snapshots
+/// created by another build before any of their function pointers are used.
+fn build_fingerprint() -> [u64; 2] {
+    let anchor = function_anchor();
+    [
+        (initialize_default_realm as usize).wrapping_sub(anchor) as u64,
+        (Heap::new as usize).wrapping_sub(anchor) as u64,
+    ]
+}
+
+pub(crate) struct SnapshotWriter {
+    bytes: Vec<u8>,
+    anchor: usize,
+}
+
+impl SnapshotWriter {
+    pub(crate) fn write_u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    pub(crate) fn write_u32(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn write_u64(&mut self, value: u64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub(crate) fn write_len(&mut self, len: usize) {
+        self.write_u32(u32::try_from(len).expect("Snapshot data is too large"));
+    }
+
+    pub(crate) fn write_bytes(&mut self, bytes: &[u8]) {
+        self.write_len(bytes.len());
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    pub(crate) fn write_function_pointer(&mut self, address: usize) {
+        self.write_u64(address.wrapping_sub(self.anchor) as u64);
+    }
+}
+
+pub(crate) struct SnapshotReader<'a> {
+    bytes: &'a [u8],
+    anchor: usize,
+}
+
+impl<'a> SnapshotReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], SnapshotError> {
+        if self.bytes.len() < len {
+            return Err(SnapshotError::Corrupted);
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    pub(crate) fn read_u8(&mut self) -> Result<u8, SnapshotError> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub(crate) fn read_u32(&mut self) -> Result<u32, SnapshotError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    pub(crate) fn read_u64(&mut self) -> Result<u64, SnapshotError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    pub(crate) fn read_len(&mut self) -> Result<usize, SnapshotError> {
+        let len = self.read_u32()? as usize;
+        // Every item takes at least one byte, so a length past the end of the
+        // data is corrupt; checking this avoids huge allocations.
+        if len > self.bytes.len() {
+            return Err(SnapshotError::Corrupted);
+        }
+        Ok(len)
+    }
+
+    pub(crate) fn read_bytes(&mut self) -> Result<&'a [u8], SnapshotError> {
+        let len = self.read_len()?;
+        self.take(len)
+    }
+
+    pub(crate) fn read_function_pointer(&mut self) -> Result<usize, SnapshotError> {
+        Ok(self.anchor.wrapping_add(self.read_u64()? as usize))
+    }
+}
+
+/// Heap data that can be stored in a startup snapshot.
+///
+/// Like [`HeapMarkAndSweep`](super::HeapMarkAndSweep), this is implemented
+/// next to each heap data type by destructuring all of its fields.
+pub(crate) trait HeapSnapshot: Sized {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter);
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError>;
+}
+
+impl HeapSnapshot for bool {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        writer.write_u8(*self as u8);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        match reader.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(SnapshotError::Corrupted),
+        }
+    }
+}
+
+impl HeapSnapshot for u8 {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        writer.write_u8(*self);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        reader.read_u8()
+    }
+}
+
+impl HeapSnapshot for u32 {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        writer.write_u32(*self);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        reader.read_u32()
+    }
+}
+
+impl HeapSnapshot for f64 {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        writer.write_u64(self.to_bits());
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        Ok(f64::from_bits(reader.read_u64()?))
+    }
+}
+
+impl<T: HeapSnapshot> HeapSnapshot for Option<T> {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        match self {
+            Some(value) => {
+                writer.write_u8(1);
+                value.write_snapshot(writer);
+            }
+            None => writer.write_u8(0),
+        }
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        match reader.read_u8()? {
+            0 => Ok(None),
+            1 => Ok(Some(T::read_snapshot(reader)?)),
+            _ => Err(SnapshotError::Corrupted),
+        }
+    }
+}
+
+impl<T: HeapSnapshot> HeapSnapshot for Vec<T> {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        writer.write_len(self.len());
+        for item in self {
+            item.write_snapshot(writer);
+        }
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        let len = reader.read_len()?;
+        let mut result = Vec::with_capacity(len);
+        for _ in 0..len {
+            result.push(T::read_snapshot(reader)?);
+        }
+        Ok(result)
+    }
+}
+
+impl<K: HeapSnapshot + Hash + Eq, V: HeapSnapshot> HeapSnapshot for AHashMap<K, V> {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        writer.write_len(self.len());
+        for (key, value) in self {
+            key.write_snapshot(writer);
+            value.write_snapshot(writer);
+        }
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        let len = reader.read_len()?;
+        let mut result = AHashMap::with_capacity(len);
+        for _ in 0..len {
+            let key = K::read_snapshot(reader)?;
+            let value = V::read_snapshot(reader)?;
+            result.insert(key, value);
+        }
+        Ok(result)
+    }
+}
+
+impl<T: HeapSnapshot + Hash + Eq> HeapSnapshot for AHashSet<T> {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        writer.write_len(self.len());
+        for item in self {
+            item.write_snapshot(writer);
+        }
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        let len = reader.read_len()?;
+        let mut result = AHashSet::with_capacity(len);
+        for _ in 0..len {
+            result.insert(T::read_snapshot(reader)?);
+        }
+        Ok(result)
+    }
+}
+
+impl<T: ?Sized> HeapSnapshot for BaseIndex<'static, T> {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        writer.write_u32(self.into_u32_index());
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        let index = reader.read_u32()?;
+        if index == u32::MAX {
+            return Err(SnapshotError::Corrupted);
+        }
+        Ok(Self::from_u32_index(index))
+    }
+}
+
+impl HeapSnapshot for Realm<'static> {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        writer.write_u32(self.into_u32_index());
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        let index = reader.read_u32()?;
+        if index == u32::MAX {
+            return Err(SnapshotError::Corrupted);
+        }
+        Ok(Realm::from_u32(index))
+    }
+}
+
+impl HeapSnapshot for Value<'static> {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        match *self {
+            Value::Undefined => writer.write_u8(0),
+            Value::Null => writer.write_u8(1),
+            Value::Boolean(value) => {
+                writer.write_u8(2);
+                value.write_snapshot(writer);
+            }
+            Value::String(string) => {
+                writer.write_u8(3);
+                string.0.write_snapshot(writer);
+            }
+            Value::SmallString(string) => {
+                writer.write_u8(4);
+                writer.write_bytes(string.as_bytes());
+            }
+            Value::Symbol(symbol) => {
+                writer.write_u8(5);
+                symbol.0.write_snapshot(writer);
+            }
+            Value::Number(number) => {
+                writer.write_u8(6);
+                number.0.write_snapshot(writer);
+            }
+            Value::Integer(integer) => {
+                writer.write_u8(7);
+                writer.write_u64(integer.into_i64() as u64);
+            }
+            Value::SmallF64(float) => {
+                writer.write_u8(8);
+                float.into_f64().write_snapshot(writer);
+            }
+            Value::SmallBigInt(bigint) => {
+                writer.write_u8(9);
+                writer.write_u64(bigint.into_i64() as u64);
+            }
+            Value::Object(object) => {
+                writer.write_u8(10);
+                object.0.write_snapshot(writer);
+            }
+            Value::BuiltinFunction(function) => {
+                writer.write_u8(11);
+                function.0.write_snapshot(writer);
+            }
+            Value::Array(array) => {
+                writer.write_u8(12);
+                writer.write_u32(array.get_index() as u32);
+            }
+            Value::PrimitiveObject(object) => {
+                writer.write_u8(13);
+                writer.write_u32(object.get_index() as u32);
+            }
+            _ => panic!("{self:?} cannot be stored in a startup snapshot"),
+        }
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        let value = match reader.read_u8()? {
+            0 => Value::Undefined,
+            1 => Value::Null,
+            2 => Value::Boolean(bool::read_snapshot(reader)?),
+            3 => Value::String(HeapString(StringIndex::read_snapshot(reader)?)),
+            4 => {
+                let string = core::str::from_utf8(reader.read_bytes()?)
+                    .map_err(|_| SnapshotError::Corrupted)?;
+                Value::SmallString(
+                    SmallString::try_from(string).map_err(|_| SnapshotError::Corrupted)?,
+                )
+            }
+            5 => Value::Symbol(Symbol(SymbolIndex::read_snapshot(reader)?)),
+            6 => Value::Number(HeapNumber(NumberIndex::read_snapshot(reader)?)),
+            7 => Value::Integer(
+                SmallInteger::try_from(reader.read_u64()? as i64)
+                    .map_err(|_| SnapshotError::Corrupted)?,
+            ),
+            8 => Value::SmallF64(
+                SmallF64::try_from(f64::read_snapshot(reader)?)
+                    .map_err(|_| SnapshotError::Corrupted)?,
+            ),
+            9 => Value::SmallBigInt(
+                SmallBigInt::try_from(reader.read_u64()? as i64)
+                    .map_err(|_| SnapshotError::Corrupted)?,
+            ),
+            10 => Value::Object(OrdinaryObject(ObjectIndex::read_snapshot(reader)?)),
+            11 => Value::BuiltinFunction(BuiltinFunction(BuiltinFunctionIndex::read_snapshot(
+                reader,
+            )?)),
+            12 => Value::Array(Array::from(ArrayIndex::read_snapshot(reader)?)),
+            13 => Value::PrimitiveObject(PrimitiveObject::from(
+                PrimitiveObjectIndex::read_snapshot(reader)?,
+            )),
+            _ => return Err(SnapshotError::Corrupted),
+        };
+        Ok(value)
+    }
+}
+
+impl HeapSnapshot for String<'static> {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        self.into_value().write_snapshot(writer);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        String::try_from(Value::read_snapshot(reader)?).map_err(|_| SnapshotError::Corrupted)
+    }
+}
+
+impl HeapSnapshot for Object<'static> {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        self.into_value().write_snapshot(writer);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        Object::try_from(Value::read_snapshot(reader)?).map_err(|_| SnapshotError::Corrupted)
+    }
+}
+
+impl HeapSnapshot for OrdinaryObject<'static> {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        self.0.write_snapshot(writer);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        Ok(OrdinaryObject(ObjectIndex::read_snapshot(reader)?))
+    }
+}
+
+impl HeapSnapshot for Array<'static> {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        writer.write_u32(self.get_index() as u32);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        Ok(Array::from(ArrayIndex::read_snapshot(reader)?))
+    }
+}
+
+impl HeapSnapshot for Function<'static> {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        self.into_value().write_snapshot(writer);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        Function::try_from(Value::read_snapshot(reader)?).map_err(|_| SnapshotError::Corrupted)
+    }
+}
+
+impl HeapSnapshot for PropertyKey<'static> {
+    fn write_snapshot(&self, writer: &mut SnapshotWriter) {
+        let value = match *self {
+            PropertyKey::Integer(integer) => Value::Integer(integer),
+            PropertyKey::SmallString(string) => Value::SmallString(string),
+            PropertyKey::String(string) => Value::String(string),
+            PropertyKey::Symbol(symbol) => Value::Symbol(symbol),
+            PropertyKey::PrivateName(_) => {
+                panic!("{self:?} cannot be stored in a startup snapshot")
+            }
+        };
+        value.write_snapshot(writer);
+    }
+
+    fn read_snapshot(reader: &mut SnapshotReader) -> Result<Self, SnapshotError> {
+        match Value::read_snapshot(reader)? {
+            Value::Integer(integer) => Ok(PropertyKey::Integer(integer)),
+            Value::SmallString(string) => Ok(PropertyKey::SmallString(string)),
+            Value::String(string) => Ok(PropertyKey::String(string)),
+            Value::Symbol(symbol) => Ok(PropertyKey::Symbol(symbol)),
+            _ => Err(SnapshotError::Corrupted),
+        }
+    }
+}
+
+fn assert_empty(name: &str, is_empty: bool) {
+    assert!(
+        is_empty,
+        "Startup snapshots can only contain intrinsics, but the heap contains {name}"
+    );
+}
+
+/// Serializes the heap of an Agent that contains only the intrinsics of a
+/// single Realm.
+pub(crate) fn write_startup_snapshot(agent: &Agent) -> Vec<u8> {
+    let mut writer = SnapshotWriter {
+        bytes: Vec::with_capacity(64 * 1024),
+        anchor: function_anchor(),
+    };
+    writer.bytes.extend_from_slice(MAGIC);
+    writer.write_bytes(env!("CARGO_PKG_VERSION").as_bytes());
+    for offset in build_fingerprint() {
+        writer.write_u64(offset);
+    }
+
+    assert_empty(
+        "symbols in the global registry",
+        agent.global_symbol_registry.is_empty(),
+    );
+    let Heap {
+        #[cfg(feature = "array-buffer")]
+        array_buffers,
+        #[cfg(feature = "array-buffer")]
+            array_buffer_detach_keys: _,
+        arrays,
+        array_iterators,
+        async_generators,
+        await_reactions,
+        bigints,
+        bound_functions,
+        builtin_constructors,
+        builtin_functions,
+        #[cfg(feature = "array-buffer")]
+        data_views,
+        #[cfg(feature = "array-buffer")]
+            data_view_byte_lengths: _,
+        #[cfg(feature = "array-buffer")]
+            data_view_byte_offsets: _,
+        #[cfg(feature = "date")]
+        dates,
+        ecmascript_functions,
+        elements,
+        embedder_objects,
+        environments,
+        errors,
+        executables,
+        finalization_registrys,
+        generators,
+        globals,
+        maps,
+        map_iterators,
+        numbers,
+        objects,
+        own_keys_cache: _,
+        primitive_objects,
+        promise_reaction_records,
+        promise_resolving_functions,
+        promises,
+        proxys,
+        realms,
+        #[cfg(feature = "regexp")]
+        regexps,
+        #[cfg(feature = "set")]
+        sets,
+        #[cfg(feature = "set")]
+        set_iterators,
+        #[cfg(feature = "regexp")]
+        reg_exp_string_iterators,
+        #[cfg(feature = "shared-array-buffer")]
+        shared_array_buffers,
+        symbols,
+        #[cfg(feature = "array-buffer")]
+        typed_arrays,
+        #[cfg(feature = "array-buffer")]
+            typed_array_byte_lengths: _,
+        #[cfg(feature = "array-buffer")]
+            typed_array_byte_offsets: _,
+        #[cfg(feature = "array-buffer")]
+            typed_array_array_lengths: _,
+        #[cfg(feature = "weak-refs")]
+        weak_maps,
+        #[cfg(feature = "weak-refs")]
+        weak_refs,
+        #[cfg(feature = "weak-refs")]
+        weak_sets,
+        modules,
+        scripts,
+        string_iterators,
+        source_codes,
+        strings,
+        string_lookup_table: _,
+        string_hasher: _,
+        incremental_marking: _,
+        statistics: _,
+        alloc_counter: _,
+    } = &agent.heap;
+    #[cfg(feature = "array-buffer")]
+    {
+        assert_empty("ArrayBuffers", array_buffers.is_empty());
+        assert_empty("DataViews", data_views.is_empty());
+        assert_empty("TypedArrays", typed_arrays.is_empty());
+    }
+    assert_empty("Array Iterators", array_iterators.is_empty());
+    assert_empty("AsyncGenerators", async_generators.is_empty());
+    assert_empty("await reactions", await_reactions.is_empty());
+    assert_empty("BigInts", bigints.is_empty());
+    assert_empty("bound functions", bound_functions.is_empty());
+    assert_empty("class constructors", builtin_constructors.is_empty());
+    #[cfg(feature = "date")]
+    assert_empty("Dates", dates.is_empty());
+    assert_empty("ECMAScript functions", ecmascript_functions.is_empty());
+    assert_empty("embedder objects", embedder_objects.is_empty());
+    assert_empty("Errors", errors.is_empty());
+    assert_empty("executables", executables.is_empty());
+    assert_empty("FinalizationRegistries", finalization_registrys.is_empty());
+    assert_empty("Generators", generators.is_empty());
+    assert_empty("globals", globals.borrow().iter().all(Option::is_none));
+    assert_empty("Maps", maps.is_empty());
+    assert_empty("Map Iterators", map_iterators.is_empty());
+    assert_empty("promise reactions", promise_reaction_records.is_empty());
+    assert_empty(
+        "promise resolving functions",
+        promise_resolving_functions.is_empty(),
+    );
+    assert_empty("Promises", promises.is_empty());
+    assert_empty("Proxies", proxys.is_empty());
+    #[cfg(feature = "regexp")]
+    {
+        assert_empty("RegExps", regexps.is_empty());
+        assert_empty(
+            "RegExp String Iterators",
+            reg_exp_string_iterators.is_empty(),
+        );
+    }
+    #[cfg(feature = "set")]
+    {
+        assert_empty("Sets", sets.is_empty());
+        assert_empty("Set Iterators", set_iterators.is_empty());
+    }
+    #[cfg(feature = "shared-array-buffer")]
+    assert_empty("SharedArrayBuffers", shared_array_buffers.is_empty());
+    #[cfg(feature = "weak-refs")]
+    {
+        assert_empty("WeakMaps", weak_maps.is_empty());
+        assert_empty("WeakRefs", weak_refs.is_empty());
+        assert_empty("WeakSets", weak_sets.is_empty());
+    }
+    assert_empty("modules", modules.is_empty());
+    assert_empty("scripts", scripts.is_empty());
+    assert_empty("String Iterators", string_iterators.is_empty());
+    assert_empty("source code", source_codes.is_empty());
+    assert!(
+        realms.len() == 1 && realms[0].is_some(),
+        "Startup snapshots must contain exactly one Realm"
+    );
+
+    // Builtin strings are allocated by Heap::new; only strings created
+    // during Realm initialization are written.
+    let added_strings = &strings[BUILTIN_STRINGS_LIST.len()..];
+    writer.write_len(added_strings.len());
+    for string in added_strings {
+        let string = string.as_ref().unwrap().as_wtf8();
+        if let Some(string) = string.as_str() {
+            writer.write_u8(0);
+            writer.write_bytes(string.as_bytes());
+        } else {
+            // Strings with lone surrogates are written as UTF-16 code units.
+            let code_units = string
+                .to_ill_formed_utf16()
+                .flat_map(u16::to_le_bytes)
+                .collect::<Vec<_>>();
+            writer.write_u8(1);
+            writer.write_bytes(&code_units);
+        }
+    }
+    symbols.write_snapshot(&mut writer);
+    numbers.write_snapshot(&mut writer);
+    objects.write_snapshot(&mut writer);
+    primitive_objects.write_snapshot(&mut writer);
+    arrays.write_snapshot(&mut writer);
+    builtin_functions.write_snapshot(&mut writer);
+    elements.write_snapshot(&mut writer);
+    environments.write_snapshot(&mut writer);
+    realms.write_snapshot(&mut writer);
+    writer.bytes
+}
+
+/// Restores a startup snapshot into the freshly created heap of an Agent,
+/// returning the Realm it contains.
+///
+/// ## Safety
+///
+/// The bytes must have been created by [`write_startup_snapshot`] in the same
+/// build of the engine. Builtin function pointers in the snapshot are trusted
+/// as-is.
+pub(crate) unsafe fn read_startup_snapshot(
+    heap: &mut Heap,
+    bytes: &[u8],
+) -> Result<Realm<'static>, SnapshotError> {
+    let Some(bytes) = bytes.strip_prefix(MAGIC) else {
+        return Err(SnapshotError::InvalidHeader);
+    };
+    let mut reader = SnapshotReader {
+        bytes,
+        anchor: function_anchor(),
+    };
+    if reader.read_bytes()? != env!("CARGO_PKG_VERSION").as_bytes() {
+        return Err(SnapshotError::BuildMismatch);
+    }
+    for offset in build_fingerprint() {
+        if reader.read_u64()? != offset {
+            return Err(SnapshotError::BuildMismatch);
+        }
+    }
+
+    debug_assert_eq!(heap.strings.len(), BUILTIN_STRINGS_LIST.len());
+    let added_strings = reader.read_len()?;
+    for _ in 0..added_strings {
+        let expected_index = heap.strings.len();
+        let allocated = match reader.read_u8()? {
+            0 => {
+                let string = core::str::from_utf8(reader.read_bytes()?)
+                    .map_err(|_| SnapshotError::Corrupted)?;
+                // SAFETY: Only heap strings are written into the snapshot, and
+                // these cannot be represented as SmallStrings.
+                unsafe { heap.alloc_str(string) }
+            }
+            1 => {
+                let bytes = reader.read_bytes()?;
+                if bytes.len() % 2 != 0 {
+                    return Err(SnapshotError::Corrupted);
+                }
+                let code_units = bytes
+                    .chunks_exact(2)
+                    .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+                    .collect::<Vec<_>>();
+                let string = Wtf8Buf::from_ill_formed_utf16(&code_units);
+                if string.as_str().is_some() {
+                    return Err(SnapshotError::Corrupted);
+                }
+                // SAFETY: The string is not valid UTF-8.
+                unsafe { heap.alloc_wtf8_buf(string) }
+            }
+            _ => return Err(SnapshotError::Corrupted),
+        };
+        if !matches!(allocated, String::String(string) if string.get_index() == expected_index) {
+            return Err(SnapshotError::Corrupted);
+        }
+    }
+    heap.symbols = HeapSnapshot::read_snapshot(&mut reader)?;
+    heap.numbers = HeapSnapshot::read_snapshot(&mut reader)?;
+    heap.objects = HeapSnapshot::read_snapshot(&mut reader)?;
+    heap.primitive_objects = HeapSnapshot::read_snapshot(&mut reader)?;
+    heap.arrays = HeapSnapshot::read_snapshot(&mut reader)?;
+    heap.builtin_functions = HeapSnapshot::read_snapshot(&mut reader)?;
+    heap.elements = HeapSnapshot::read_snapshot(&mut reader)?;
+    heap.environments = HeapSnapshot::read_snapshot(&mut reader)?;
+    heap.realms = HeapSnapshot::read_snapshot(&mut reader)?;
+    if !reader.bytes.is_empty() || heap.realms.len() != 1 || heap.realms[0].is_none() {
+        return Err(SnapshotError::Corrupted);
+    }
+    Ok(Realm::from_index(0))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        ecmascript::{
+            execution::{
+                DefaultHostHooks,
+                agent::{GcAgent, Options},
+            },
+            types::{String, Value},
+        },
+        engine::context::Bindable,
+        heap::SnapshotError,
+    };
+
+    fn startup_snapshot() -> Vec<u8> {
+        let mut agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+        let realm = agent.create_default_realm();
+        let snapshot = agent.serialize_startup_snapshot();
+        agent.remove_realm(realm);
+        snapshot
+    }
+
+    #[test]
+    fn restored_realm_runs_scripts() {
+        let snapshot = startup_snapshot();
+        // SAFETY: The snapshot was created by this build.
+        let (mut agent, realm) = unsafe {
+            GcAgent::from_startup_snapshot(&snapshot, Options::default(), &DefaultHostHooks)
+        }
+        .unwrap();
+        agent.run_in_realm(&realm, |agent, mut gc| {
+            let source_text = String::from_static_str(agent, "Math.max(1, 2)", gc.nogc());
+            let result = agent
+                .run_script(source_text.unbind(), gc.reborrow())
+                .unwrap();
+            assert_eq!(result, Value::from(2));
+
+            // The restored heap survives garbage collection.
+            agent.gc(gc.reborrow());
+            let source_text = String::from_static_str(agent, "[3, 1, 2].sort().join()", gc.nogc());
+            let result = agent
+                .run_script(source_text.unbind(), gc.reborrow())
+                .unwrap()
+                .unbind();
+            assert_eq!(result.to_string(agent, gc).unwrap().as_str(agent), "1,2,3");
+        });
+        agent.remove_realm(realm);
+    }
+
+    #[test]
+    fn invalid_snapshots_are_rejected() {
+        let snapshot = startup_snapshot();
+        // SAFETY: Invalid snapshots are rejected before any data is read.
+        let err = unsafe {
+            GcAgent::from_startup_snapshot(b"not a snapshot", Options::default(), &DefaultHostHooks)
+        }
+        .err()
+        .unwrap();
+        assert_eq!(err, SnapshotError::InvalidHeader);
+
+        // SAFETY: Truncated snapshots are rejected when the data runs out.
+        let err = unsafe {
+            GcAgent::from_startup_snapshot(
+                &snapshot[..snapshot.len() / 2],
+                Options::default(),
+                &DefaultHostHooks,
+            )
+        }
+        .err()
+        .unwrap();
+        assert_eq!(err, SnapshotError::Corrupted);
+    }
+}
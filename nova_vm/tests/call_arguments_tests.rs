@@ -0,0 +1,153 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+use nova_vm::{
+    ecmascript::{
+        builtins::{ArgumentsList, Array, Behaviour, BuiltinFunctionArgs, create_builtin_function},
+        execution::{
+            Agent, DefaultHostHooks, JsResult,
+            agent::{GcAgent, Options},
+        },
+        types::{InternalMethods, IntoValue, PropertyDescriptor, PropertyKey, String, Value},
+    },
+    engine::context::{Bindable, GcScope},
+};
+
+/// Counts allocations made by the current thread while counting is enabled.
+struct CountingAllocator;
+
+thread_local! {
+    static COUNTING: Cell<bool> = const { Cell::new(false) };
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if COUNTING.get() {
+            ALLOCATIONS.set(ALLOCATIONS.get() + 1);
+        }
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if COUNTING.get() {
+            ALLOCATIONS.set(ALLOCATIONS.get() + 1);
+        }
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Runs the script and returns its numeric result along with the number of
+/// allocations made while running it.
+fn count_allocations(source: std::string::String) -> (usize, f64) {
+    let mut agent = GcAgent::new(
+        Options {
+            disable_gc: true,
+            ..Default::default()
+        },
+        &DefaultHostHooks,
+    );
+    let realm = agent.create_default_realm();
+    let result = agent.run_in_realm(&realm, |agent, mut gc| {
+        let source_text = String::from_string(agent, source, gc.nogc());
+        ALLOCATIONS.set(0);
+        COUNTING.set(true);
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        COUNTING.set(false);
+        let result = result.unwrap().unbind();
+        let allocations = ALLOCATIONS.get();
+        (
+            allocations,
+            result.to_number(agent, gc).unwrap().into_f64(agent),
+        )
+    });
+    agent.remove_realm(realm);
+    result
+}
+
+#[test]
+fn calls_do_not_allocate() {
+    let call_loop = |iterations: u32| {
+        count_allocations(format!(
+            "function add(a, b) {{ return a + b; }}
+            var sum = 0;
+            for (var i = 0; i < {iterations}; i++) {{
+                sum = add(sum, Math.min(i, 1));
+            }}
+            sum"
+        ))
+    };
+    // Both scripts have the same length, so that they parse identically.
+    let (baseline, result) = call_loop(10_000);
+    assert_eq!(result, 9_999.0);
+    let (allocations, result) = call_loop(99_999);
+    assert_eq!(result, 99_998.0);
+    // Calls to both ECMAScript and builtin functions reuse the argument area
+    // on the VM stack and the buffers of finished VMs.
+    assert_eq!(allocations, baseline);
+}
+
+#[test]
+fn builtin_retains_arguments_across_gc() {
+    fn retain<'gc>(
+        agent: &mut Agent,
+        _this: Value,
+        arguments: ArgumentsList,
+        mut gc: GcScope<'gc, '_>,
+    ) -> JsResult<'gc, Value<'gc>> {
+        let arguments = arguments.scope(agent, gc.nogc());
+        agent.gc(gc.reborrow());
+        let gc = gc.into_nogc();
+        let values = (0..arguments.len(agent))
+            .map(|index| arguments.get(agent, index, gc).unwrap())
+            .collect::<Vec<_>>();
+        Ok(Array::from_slice(agent, &values, gc).into_value())
+    }
+
+    let mut agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+    let realm = agent.create_default_realm();
+    agent.run_in_realm(&realm, |agent, mut gc| {
+        let function = create_builtin_function(
+            agent,
+            Behaviour::Regular(retain),
+            BuiltinFunctionArgs::new(0, "retain"),
+            gc.nogc(),
+        );
+        let global = agent.current_global_object(gc.nogc());
+        let key = PropertyKey::from_static_str(agent, "retain", gc.nogc());
+        global
+            .unbind()
+            .internal_define_own_property(
+                agent,
+                key.unbind(),
+                PropertyDescriptor::new_data_descriptor(function.into_value().unbind()),
+                gc.reborrow(),
+            )
+            .unwrap();
+
+        // The garbage created before the call is collected and compacted away
+        // during the call, moving the argument objects.
+        let source_text = String::from_static_str(
+            agent,
+            "let garbage = Array.from({ length: 100 }, (_, i) => ({ i }));
+            garbage = null;
+            const [a, b, c] = retain({ v: 1 }, ...[{ v: 2 }, 'x'.repeat(3)]);
+            a.v === 1 && b.v === 2 && c === 'xxx'",
+            gc.nogc(),
+        );
+        let result = agent
+            .run_script(source_text.unbind(), gc.reborrow())
+            .unwrap();
+        assert_eq!(result, Value::Boolean(true));
+    });
+    agent.remove_realm(realm);
+}
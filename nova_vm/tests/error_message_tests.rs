@@ -0,0 +1,98 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+use nova_vm::{
+    ecmascript::{
+        execution::{
+            DefaultHostHooks,
+            agent::{GcAgent, Options},
+        },
+        types::String,
+    },
+    engine::context::Bindable,
+};
+
+/// Counts allocations made by the current thread while counting is enabled.
+struct CountingAllocator;
+
+thread_local! {
+    static COUNTING: Cell<bool> = const { Cell::new(false) };
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if COUNTING.get() {
+            ALLOCATIONS.set(ALLOCATIONS.get() + 1);
+        }
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if COUNTING.get() {
+            ALLOCATIONS.set(ALLOCATIONS.get() + 1);
+        }
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Runs the script and returns its string result along with the number of
+/// allocations made while running it.
+fn count_allocations(source: std::string::String) -> (usize, std::string::String) {
+    let mut agent = GcAgent::new(
+        Options {
+            disable_gc: true,
+            ..Default::default()
+        },
+        &DefaultHostHooks,
+    );
+    let realm = agent.create_default_realm();
+    let result = agent.run_in_realm(&realm, |agent, mut gc| {
+        let source_text = String::from_string(agent, source, gc.nogc());
+        ALLOCATIONS.set(0);
+        COUNTING.set(true);
+        let result = agent.run_script(source_text.unbind(), gc.reborrow());
+        COUNTING.set(false);
+        let result = result.unwrap().unbind();
+        let allocations = ALLOCATIONS.get();
+        let result = result.to_string(agent, gc).unwrap();
+        (allocations, result.as_str(agent).to_owned())
+    });
+    agent.remove_realm(realm);
+    result
+}
+
+#[test]
+fn caught_errors_do_not_format_messages() {
+    let throw_loop = |iterations: u32| {
+        count_allocations(format!(
+            "var caught = 0;
+            var object = null;
+            for (var i = 0; i < {iterations}; i++) {{
+                try {{ object.someProperty; }} catch {{ caught++; }}
+            }}
+            caught"
+        ))
+    };
+    // Both scripts have the same length, so that they parse identically.
+    let (baseline, result) = throw_loop(10_000);
+    assert_eq!(result, "10000");
+    let (allocations, result) = throw_loop(100_000);
+    assert_eq!(result, "100000");
+    // No message is formatted for errors whose message is never read, so
+    // throwing does not allocate once per error. The catch clause has no
+    // binding as each binding allocates a new environment.
+    assert!(
+        allocations - baseline < 90_000 / 10,
+        "{allocations} allocations for 100k throws, {baseline} for 10k"
+    );
+}
@@ -4,7 +4,7 @@ use nova_vm::{
     ecmascript::{
         execution::{
             Agent, DefaultHostHooks,
-            agent::{GcAgent, Options},
+            agent::{GcAgent, Options, RealmRoot},
         },
         scripts_and_modules::script::{parse_script, script_evaluation},
         types::{Object, String, Value},
@@ -12,7 +12,7 @@ use nova_vm::{
     engine::context::{Bindable, GcScope},
 };
 
-fn initialize_global_object(agent: &mut Agent, global: Object, gc: GcScope) {
+fn initialize_global_object(agent: &mut Agent, global: Object, mut gc: GcScope) {
     use nova_vm::ecmascript::{
         builtins::{ArgumentsList, Behaviour, BuiltinFunctionArgs, create_builtin_function},
         execution::JsResult,
@@ -40,6 +40,35 @@ fn initialize_global_object(agent: &mut Agent, global: Object, gc: GcScope) {
         gc.nogc(),
     );
     let property_key = PropertyKey::from_static_str(agent, "print", gc.nogc());
+    global
+        .internal_define_own_property(
+            agent,
+            property_key.unbind(),
+            PropertyDescriptor {
+                value: Some(function.into_value().unbind()),
+                ..Default::default()
+            },
+            gc.reborrow(),
+        )
+        .unwrap();
+
+    // `gcStep` function: runs one incremental garbage collection slice with
+    // an unlimited budget.
+    fn gc_step<'gc>(
+        agent: &mut Agent,
+        _this: Value,
+        _args: ArgumentsList,
+        gc: GcScope<'gc, '_>,
+    ) -> JsResult<'gc, Value<'gc>> {
+        Ok(agent.gc_step(usize::MAX, gc).into())
+    }
+    let function = create_builtin_function(
+        agent,
+        Behaviour::Regular(gc_step),
+        BuiltinFunctionArgs::new(0, "gcStep"),
+        gc.nogc(),
+    );
+    let property_key = PropertyKey::from_static_str(agent, "gcStep", gc.nogc());
     global
         .internal_define_own_property(
             agent,
@@ -117,3 +146,184 @@ fn garbage_collection_tests() {
         agent.gc();
     }
 }
+
+fn create_realm(agent: &mut GcAgent) -> RealmRoot {
+    let create_global_object: Option<for<'a> fn(&mut Agent, GcScope<'a, '_>) -> Object<'a>> = None;
+    let create_global_this_value: Option<for<'a> fn(&mut Agent, GcScope<'a, '_>) -> Object<'a>> =
+        None;
+    agent.create_realm(
+        create_global_object,
+        create_global_this_value,
+        Some(initialize_global_object),
+    )
+}
+
+fn run(agent: &mut GcAgent, realm: &RealmRoot, source: &'static str) -> std::string::String {
+    agent.run_in_realm(realm, |agent, mut gc| {
+        let realm = agent.current_realm(gc.nogc());
+        let source_text = String::from_static_str(agent, source, gc.nogc());
+        let script = parse_script(agent, source_text, realm, false, None, gc.nogc()).unwrap();
+        let result = script_evaluation(agent, script.unbind(), gc.reborrow())
+            .unbind()
+            .unwrap();
+        result.string_repr(agent, gc).as_str(agent).to_string()
+    })
+}
+
+/// Runs incremental garbage collection slices until a collection finishes.
+/// Returns the number of slices taken.
+fn finish_incremental_gc(agent: &mut GcAgent, budget: usize) -> u64 {
+    let slices_before = agent.heap_statistics().incremental_slices;
+    while !agent.gc_step(budget) {}
+    agent.heap_statistics().incremental_slices - slices_before
+}
+
+const GARBAGE_SOURCE: &str = "
+var kept = [];
+for (let i = 0; i < 200; i++) {
+    const object = { index: i, name: 'object' + i, nested: [i, { i }] };
+    if (i % 4 === 0) {
+        kept.push(object);
+    }
+}
+kept.length";
+
+#[test]
+fn incremental_gc_frees_the_same_as_full_gc() {
+    let mut full = GcAgent::new(Options::default(), &DefaultHostHooks);
+    let full_realm = create_realm(&mut full);
+    assert_eq!(run(&mut full, &full_realm, GARBAGE_SOURCE), "50");
+    full.gc();
+
+    let mut incremental = GcAgent::new(Options::default(), &DefaultHostHooks);
+    let incremental_realm = create_realm(&mut incremental);
+    assert_eq!(
+        run(&mut incremental, &incremental_realm, GARBAGE_SOURCE),
+        "50"
+    );
+    let slices = finish_incremental_gc(&mut incremental, 16);
+    assert!(slices > 1);
+
+    let full_statistics = full.heap_statistics();
+    let incremental_statistics = incremental.heap_statistics();
+    assert_eq!(
+        incremental_statistics.heap_entries,
+        full_statistics.heap_entries
+    );
+    assert_eq!(incremental_statistics.collections, 1);
+    assert!(!incremental_statistics.marking_in_progress);
+    assert_eq!(
+        run(&mut incremental, &incremental_realm, "kept[49].nested[1].i"),
+        "196"
+    );
+}
+
+#[test]
+fn incremental_gc_slices_respect_budget() {
+    let mut agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+    let realm = create_realm(&mut agent);
+    run(&mut agent, &realm, GARBAGE_SOURCE);
+    assert!(!agent.gc_step(8));
+    let statistics = agent.heap_statistics();
+    assert!(statistics.marking_in_progress);
+    assert_eq!(statistics.incremental_slices, 1);
+    assert_eq!(statistics.last_slice_work, 8);
+    assert!(statistics.max_pause >= statistics.last_pause);
+}
+
+#[test]
+fn incremental_gc_sees_writes_made_between_slices() {
+    let mut agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+    let realm = create_realm(&mut agent);
+    run(
+        &mut agent,
+        &realm,
+        "var holder = { list: [] }; var plain = {}; var map = new Map(); var round = 0;",
+    );
+    // Write newly allocated objects into existing ones between slices, so
+    // that writes also go into objects that have already been marked.
+    let mut rounds = 0;
+    while rounds < 40 {
+        assert!(!agent.gc_step(64));
+        run(
+            &mut agent,
+            &realm,
+            "
+            holder.list.push({ value: 'pushed' + round });
+            holder['late' + round] = ['late' + round];
+            plain.fresh = { value: 'fresh' + round };
+            map.set(round, { value: 'mapped' + round });
+            round++",
+        );
+        rounds += 1;
+    }
+    finish_incremental_gc(&mut agent, 64);
+    assert_eq!(
+        run(
+            &mut agent,
+            &realm,
+            "
+            var values = [];
+            for (let i = 0; i < round; i++) {
+                values.push(holder.list[i].value, holder['late' + i][0], map.get(i).value);
+            }
+            values.push(plain.fresh.value);
+            values.join()"
+        ),
+        (0..rounds)
+            .flat_map(|i| [
+                format!("pushed{i}"),
+                format!("late{i}"),
+                format!("mapped{i}")
+            ])
+            .chain([format!("fresh{}", rounds - 1)])
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+}
+
+#[test]
+fn full_gc_abandons_incremental_marking() {
+    let mut agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+    let realm = create_realm(&mut agent);
+    run(&mut agent, &realm, GARBAGE_SOURCE);
+    assert!(!agent.gc_step(8));
+    agent.gc();
+    let statistics = agent.heap_statistics();
+    assert!(!statistics.marking_in_progress);
+    assert_eq!(statistics.collections, 1);
+    assert_eq!(run(&mut agent, &realm, "kept.length"), "50");
+}
+
+#[test]
+fn incremental_gc_keeps_private_methods_added_during_marking() {
+    let mut agent = GcAgent::new(Options::default(), &DefaultHostHooks);
+    let realm = create_realm(&mut agent);
+    // The first gcStep call marks everything reachable, then the class and
+    // its PrivateEnvironment are created and filled while marking is in
+    // progress. The computed key finishes the collection before the class
+    // definition completes.
+    assert_eq!(
+        run(
+            &mut agent,
+            &realm,
+            "
+            gcStep();
+            class C {
+                #a() { return 'a'; }
+                get #b() { return 'b'; }
+                #c = 'c';
+                static #s() { return 's'; }
+                static get #t() { return 't'; }
+                static [(() => { while (!gcStep()); return 'm'; })()]() {}
+                read() { return this.#a() + this.#b + this.#c + C.#s() + C.#t; }
+            }
+            var garbage = [];
+            for (let i = 0; i < 100; i++) garbage.push(function () { return i; });
+            new C().read()"
+        ),
+        "abcst"
+    );
+    assert_eq!(agent.heap_statistics().collections, 1);
+    assert!(!agent.heap_statistics().marking_in_progress);
+}
@@ -57,6 +57,21 @@ impl SmallString {
         panic!("Index out of bounds");
     }
 
+    /// Returns the UTF-16 code unit at the given UTF-16 index. Indexing into
+    /// a surrogate pair returns the leading or trailing surrogate.
+    pub fn utf16_code_unit(&self, idx: usize) -> u16 {
+        let mut u16_i = 0;
+        for ch in self.as_str().chars() {
+            let len = ch.len_utf16();
+            if idx < u16_i + len {
+                let mut buf = [0; 2];
+                return ch.encode_utf16(&mut buf)[idx - u16_i];
+            }
+            u16_i += len;
+        }
+        panic!("Index out of bounds");
+    }
+
     pub fn utf8_index(&self, utf16_idx: usize) -> Option<usize> {
         let mut current_utf16_index = 0;
         for (idx, ch) in self.as_str().char_indices() {
@@ -68,8 +83,12 @@ impl SmallString {
                 }
             }
         }
-        assert_eq!(utf16_idx, current_utf16_index);
-        Some(self.len())
+        match current_utf16_index.cmp(&utf16_idx) {
+            core::cmp::Ordering::Equal => Some(self.len()),
+            // The index points into the middle of the last code point.
+            core::cmp::Ordering::Greater => None,
+            core::cmp::Ordering::Less => panic!("Index out of bounds"),
+        }
     }
 
     pub fn utf16_index(&self, utf8_idx: usize) -> usize {
@@ -241,3 +260,18 @@ fn valid_stack_strings() {
 fn not_valid_stack_strings() {
     assert!(SmallString::try_from("asd asd r 547 gdfg").is_err());
 }
+
+#[test]
+fn utf16_code_units() {
+    let string = SmallString::try_from("a💩b").unwrap();
+    assert_eq!(string.utf16_len(), 4);
+    assert_eq!(string.utf16_code_unit(0), 'a' as u16);
+    assert_eq!(string.utf16_code_unit(1), 0xD83D);
+    assert_eq!(string.utf16_code_unit(2), 0xDCA9);
+    assert_eq!(string.utf16_code_unit(3), 'b' as u16);
+    assert_eq!(string.utf8_index(2), None);
+    assert_eq!(string.utf8_index(3), Some(5));
+    let string = SmallString::try_from("💩").unwrap();
+    assert_eq!(string.utf8_index(1), None);
+    assert_eq!(string.utf8_index(2), Some(4));
+}